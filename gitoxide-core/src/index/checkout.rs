@@ -83,6 +83,8 @@ pub fn checkout_exclusive(
         collisions,
         files_updated,
         bytes_written,
+        files_cloned: _,
+        files_skipped: _,
         delayed_paths_unknown,
         delayed_paths_unprocessed,
     } = match repo {
@@ -58,6 +58,7 @@ pub struct Context<W> {
     /// determinism as the order of objects during expansion changes with multiple threads unless no expansion is performed.
     /// In the latter case, this flag has no effect.
     /// If `None`, counting will only use one thread and thus yield the same sequence of objects in any case.
+    /// Either way, objects are ordered so that the resulting pack is the same for the same input.
     pub nondeterministic_thread_count: Option<usize>,
     /// If true, delta objects may refer to their base as reference, allowing it not to be included in the created back.
     /// Otherwise these have to be recompressed in order to make the pack self-contained.
@@ -233,6 +234,9 @@ where
                 allow_thin_pack: thin,
                 chunk_size,
                 version: Default::default(),
+                compression_level: None,
+                stable_order: nondeterministic_thread_count.is_some(),
+                chunks_in_flight: None,
             },
         ))
     };
@@ -8,6 +8,10 @@ pub struct Options {
     pub files: Vec<(String, String)>,
     pub prefix: Option<String>,
     pub add_paths: Vec<PathBuf>,
+    /// Files to add to the root of the archive, which don't have to be in the worktree.
+    pub add_files: Vec<PathBuf>,
+    /// If `true`, add the worktree of submodules at the commit recorded in the tree, recursively.
+    pub recurse_submodules: bool,
 }
 
 pub fn stream(
@@ -19,15 +23,21 @@ pub fn stream(
         format,
         prefix,
         add_paths,
+        add_files,
         files,
+        recurse_submodules,
     }: Options,
 ) -> anyhow::Result<()> {
     let format = format.map_or_else(|| format_from_ext(destination_path), Ok)?;
     let object = repo.rev_parse_single(rev_spec.unwrap_or("HEAD"))?.object()?;
-    let (modification_date, tree) = fetch_rev_info(object)?;
+    let (modification_date, tree_or_commit) = fetch_rev_info(object)?;
 
     let start = std::time::Instant::now();
-    let (mut stream, index) = repo.worktree_stream(tree)?;
+    let (mut stream, index) = if recurse_submodules {
+        repo.worktree_stream_with_submodules(tree_or_commit)?
+    } else {
+        repo.worktree_stream(tree_or_commit)?
+    };
     if !add_paths.is_empty() {
         let root = gix::path::realpath(
             repo.work_dir()
@@ -37,6 +47,12 @@ pub fn stream(
             stream.add_entry_from_path(&root, &gix::path::realpath(&path)?)?;
         }
     }
+    for path in add_files {
+        if path.file_name().is_none() {
+            bail!("Cannot add '{}' as it has no file name", path.display());
+        }
+        stream.add_entry_from_path(path.parent().expect("paths with file name have a parent"), &path)?;
+    }
     for (path, content) in files {
         stream.add_entry(gix::worktree::stream::AdditionalEntry {
             id: gix::hash::Kind::Sha1.null(),
@@ -84,7 +100,7 @@ fn fetch_rev_info(
     Ok(match object.kind {
         gix::object::Kind::Commit => {
             let commit = object.into_commit();
-            (Some(commit.committer()?.time.seconds), commit.tree_id()?.detach())
+            (Some(commit.committer()?.time.seconds), commit.id)
         }
         gix::object::Kind::Tree => (None, object.id),
         gix::object::Kind::Tag => fetch_rev_info(object.peel_to_kind(gix::object::Kind::Commit)?)?,
@@ -99,6 +115,12 @@ fn format_from_ext(path: &Path) -> anyhow::Result<archive::Format> {
         Some("gz") => archive::Format::TarGz {
             compression_level: None,
         },
+        Some("xz") => archive::Format::TarXz {
+            compression_level: None,
+        },
+        Some("zst") => archive::Format::TarZst {
+            compression_level: None,
+        },
         Some("zip") => archive::Format::Zip {
             compression_level: None,
         },
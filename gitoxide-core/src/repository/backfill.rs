@@ -0,0 +1,34 @@
+use gix::bstr::BString;
+
+pub struct Options {
+    /// The tree-ish whose missing blobs to fetch, or `HEAD` if unset.
+    pub treeish: Option<String>,
+    /// Only fetch blobs whose path matches one of these pathspecs, or all of them if empty.
+    pub pathspecs: Vec<BString>,
+}
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
+
+pub(crate) mod function {
+    use super::Options;
+
+    pub fn backfill<P>(
+        repo: gix::Repository,
+        progress: P,
+        mut out: impl std::io::Write,
+        Options { treeish, pathspecs }: Options,
+    ) -> anyhow::Result<()>
+    where
+        P: gix::NestedProgress,
+        P::SubProgress: 'static,
+    {
+        let tree_id = repo
+            .rev_parse_single(treeish.as_deref().unwrap_or("HEAD"))?
+            .object()?
+            .peel_to_tree()?
+            .id;
+        let num_blobs = repo.backfill_missing_blobs(tree_id, pathspecs, progress, &gix::interrupt::IS_INTERRUPTED)?;
+        writeln!(out, "Fetched {num_blobs} missing blob(s)")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,333 @@
+use std::{collections::HashMap, io};
+
+use anyhow::{bail, Context};
+use gix::{
+    bstr::{BStr, BString, ByteSlice},
+    prelude::ObjectIdExt,
+    ObjectId,
+};
+
+use crate::OutputFormat;
+
+/// The way blamed lines are presented, matching the output formats of `git blame`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// One line per line of the file, prefixed with an abbreviated commit id, the author, date and line number.
+    #[default]
+    Human,
+    /// Like `git blame --porcelain`, with commit information only shown the first time a commit is mentioned.
+    Porcelain,
+    /// Like `git blame --line-porcelain`, with commit information shown for each line.
+    LinePorcelain,
+    /// Like `git blame --incremental`, one record per range of lines without their content.
+    Incremental,
+}
+
+pub struct Options {
+    pub format: OutputFormat,
+    pub blame_format: Format,
+    pub statistics: bool,
+    /// Ignore whitespace when comparing lines, like `git blame -w`.
+    pub ignore_whitespace: bool,
+    /// Detect lines moved within the file with the given minimum score, like `git blame -M`.
+    pub detect_moves: Option<u32>,
+    /// Detect lines copied from other files changed in the same commit with the given minimum score, like `git blame -C`.
+    pub detect_copies: Option<u32>,
+}
+
+pub fn blame_file(
+    repo: gix::Repository,
+    file: &BStr,
+    mut out: impl io::Write,
+    mut err: impl io::Write,
+    Options {
+        format,
+        blame_format,
+        statistics,
+        ignore_whitespace,
+        detect_moves,
+        detect_copies,
+    }: Options,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("Only human format is supported right now");
+    }
+    let head_id = repo.head_id()?.detach();
+    let options = gix::blame::file::Options {
+        ignore_whitespace,
+        detect_moves,
+        detect_copies,
+        ..repo.blame_options()?
+    };
+    let mailmap = repo.open_mailmap();
+    if blame_format == Format::Incremental {
+        let outcome = write_incremental(&repo, file, head_id, options, &mailmap, &mut out)?;
+        if statistics {
+            writeln!(err, "{:#?}", outcome.statistics)?;
+        }
+        return Ok(());
+    }
+
+    let outcome = repo
+        .blame_file(file, head_id, options)
+        .with_context(|| format!("Could not blame '{file}'"))?;
+
+    let mut commits = HashMap::new();
+    for entry in &outcome.entries {
+        if let std::collections::hash_map::Entry::Vacant(slot) = commits.entry(entry.commit_id) {
+            let source_file = entry.source_file_name.as_ref().map_or(file, |name| name.as_ref());
+            slot.insert(CommitInfo::new(&repo, entry.commit_id, source_file, &mailmap)?);
+        }
+    }
+
+    match blame_format {
+        Format::Human => write_human(&repo, &outcome, &commits, &mut out)?,
+        Format::Porcelain | Format::LinePorcelain => write_porcelain(
+            &outcome,
+            &commits,
+            file,
+            blame_format == Format::LinePorcelain,
+            &mut out,
+        )?,
+        Format::Incremental => unreachable!("handled above as entries are written as they are found"),
+    }
+
+    if statistics {
+        writeln!(err, "{:#?}", outcome.statistics)?;
+    }
+    Ok(())
+}
+
+struct CommitInfo {
+    author: gix::actor::Signature,
+    committer: gix::actor::Signature,
+    summary: BString,
+    /// `true` if this is a root commit, which is shown as boundary.
+    is_boundary: bool,
+    /// The first parent that contains the blamed file, if there is one.
+    previous: Option<ObjectId>,
+}
+
+impl CommitInfo {
+    fn new(
+        repo: &gix::Repository,
+        id: ObjectId,
+        file: &BStr,
+        mailmap: &gix::mailmap::Snapshot,
+    ) -> anyhow::Result<Self> {
+        let commit = repo.find_object(id)?.try_into_commit()?;
+        let commit = commit.decode()?;
+        let mut previous = None;
+        for parent_id in commit.parents() {
+            let tree = repo.find_object(parent_id)?.peel_to_tree()?;
+            if tree
+                .lookup_entry_by_path(gix::path::from_bstr(file), &mut Vec::new())?
+                .map_or(false, |entry| entry.mode().is_blob_or_symlink())
+            {
+                previous = Some(parent_id);
+                break;
+            }
+        }
+        Ok(CommitInfo {
+            author: mailmap.resolve(commit.author),
+            committer: mailmap.resolve(commit.committer),
+            summary: commit.message.lines().next().unwrap_or_default().into(),
+            is_boundary: commit.parents.is_empty(),
+            previous,
+        })
+    }
+}
+
+fn write_human(
+    repo: &gix::Repository,
+    outcome: &gix::blame::Outcome,
+    commits: &HashMap<ObjectId, CommitInfo>,
+    out: &mut impl io::Write,
+) -> anyhow::Result<()> {
+    // One more character than needed is shown for all commits to make room for the `^` marker of boundary commits.
+    let hex_len = 1 + commits
+        .keys()
+        .map(|id| id.attach(repo).shorten_or_id().hex_len())
+        .max()
+        .unwrap_or_default();
+    let author_width = commits
+        .values()
+        .map(|info| info.author.name.chars().count())
+        .max()
+        .unwrap_or_default();
+    let line_number_width = outcome.blob.lines_with_terminator().count().to_string().len();
+
+    for (entry, lines) in outcome.entries_with_lines() {
+        let info = &commits[&entry.commit_id];
+        let hex = entry.commit_id.to_hex().to_string();
+        let date = info.author.time.format(gix::date::time::format::ISO8601);
+        for (offset, line) in lines.into_iter().enumerate() {
+            if info.is_boundary {
+                write!(out, "^{}", &hex[..hex_len - 1])?;
+            } else {
+                write!(out, "{}", &hex[..hex_len])?;
+            }
+            let padding = author_width - info.author.name.chars().count();
+            write!(
+                out,
+                " ({}{:padding$} {date} {:line_number_width$}) ",
+                info.author.name,
+                "",
+                entry.start_in_blamed_file as usize + offset + 1
+            )?;
+            write_line(line, out)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_porcelain(
+    outcome: &gix::blame::Outcome,
+    commits: &HashMap<ObjectId, CommitInfo>,
+    file: &BStr,
+    repeat_details: bool,
+    out: &mut impl io::Write,
+) -> io::Result<()> {
+    let mut shown = gix::hashtable::HashSet::default();
+    for (entry, lines) in outcome.entries_with_lines() {
+        let info = &commits[&entry.commit_id];
+        for (offset, line) in lines.into_iter().enumerate() {
+            let offset = offset as u32;
+            write!(
+                out,
+                "{} {} {}",
+                entry.commit_id,
+                entry.start_in_source_file + offset + 1,
+                entry.start_in_blamed_file + offset + 1
+            )?;
+            if offset == 0 {
+                write!(out, " {}", entry.len)?;
+            }
+            writeln!(out)?;
+            if repeat_details || shown.insert(entry.commit_id) {
+                write_details(info, out)?;
+                write_filename(info, entry, file, out)?;
+            }
+            out.write_all(b"\t")?;
+            write_line(line, out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write each entry as soon as it is found, which is from the newest to the oldest commit.
+fn write_incremental(
+    repo: &gix::Repository,
+    file: &BStr,
+    head_id: ObjectId,
+    options: gix::blame::file::Options,
+    mailmap: &gix::mailmap::Snapshot,
+    out: &mut impl io::Write,
+) -> anyhow::Result<gix::blame::incremental::Outcome> {
+    let mut commits = HashMap::new();
+    let mut write_entry = |entry: gix::blame::BlameEntry| -> anyhow::Result<()> {
+        writeln!(
+            out,
+            "{} {} {} {}",
+            entry.commit_id,
+            entry.start_in_source_file + 1,
+            entry.start_in_blamed_file + 1,
+            entry.len
+        )?;
+        let info = match commits.entry(entry.commit_id) {
+            std::collections::hash_map::Entry::Occupied(info) => info.into_mut(),
+            std::collections::hash_map::Entry::Vacant(slot) => {
+                let source_file = entry.source_file_name.as_ref().map_or(file, |name| name.as_ref());
+                let info = slot.insert(CommitInfo::new(repo, entry.commit_id, source_file, mailmap)?);
+                write_details(info, out)?;
+                info
+            }
+        };
+        write_filename(info, &entry, file, out)?;
+        out.flush()?;
+        Ok(())
+    };
+
+    let mut error = None;
+    let outcome = repo
+        .blame_file_incremental(file, head_id, options, &mut |entry| match write_entry(entry) {
+            Ok(()) => gix::blame::incremental::Action::Continue,
+            Err(err) => {
+                error = Some(err);
+                gix::blame::incremental::Action::Cancel
+            }
+        })
+        .with_context(|| format!("Could not blame '{file}'"))?;
+    match error {
+        Some(err) => Err(err),
+        None => Ok(outcome),
+    }
+}
+
+fn write_details(info: &CommitInfo, out: &mut impl io::Write) -> io::Result<()> {
+    for (role, signature) in [("author", &info.author), ("committer", &info.committer)] {
+        writeln!(out, "{role} {}", signature.name)?;
+        writeln!(out, "{role}-mail <{}>", signature.email)?;
+        writeln!(out, "{role}-time {}", signature.time.seconds)?;
+        writeln!(out, "{role}-tz {}", format_offset(signature.time.offset))?;
+    }
+    writeln!(out, "summary {}", info.summary)?;
+    if info.is_boundary {
+        writeln!(out, "boundary")?;
+    }
+    Ok(())
+}
+
+fn write_filename(
+    info: &CommitInfo,
+    entry: &gix::blame::BlameEntry,
+    file: &BStr,
+    out: &mut impl io::Write,
+) -> io::Result<()> {
+    let file = entry.source_file_name.as_ref().map_or(file, |name| name.as_ref());
+    if let Some(previous) = info.previous {
+        writeln!(out, "previous {previous} {}", quote_path(file))?;
+    }
+    writeln!(out, "filename {}", quote_path(file))
+}
+
+/// Write `line`, and add a newline if it doesn't have one, which can happen for the last line of a file.
+fn write_line(line: &BStr, out: &mut impl io::Write) -> io::Result<()> {
+    out.write_all(line)?;
+    if !line.ends_with(b"\n") {
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Format `offset` in seconds as `+HHMM`, like git does in its object headers.
+fn format_offset(offset: i32) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let minutes = offset.unsigned_abs() / 60;
+    format!("{sign}{:02}{:02}", minutes / 60, minutes % 60)
+}
+
+/// Quote `path` like git does by default, putting it into double-quotes and escaping special and non-ASCII characters if needed.
+fn quote_path(path: &BStr) -> BString {
+    let needs_quoting = |b: u8| !(0x20..0x7f).contains(&b) || b == b'"' || b == b'\\';
+    if !path.iter().copied().any(needs_quoting) {
+        return path.to_owned();
+    }
+    let mut out = BString::from("\"");
+    for &b in path.iter() {
+        match b {
+            b'\x07' => out.extend_from_slice(b"\\a"),
+            b'\x08' => out.extend_from_slice(b"\\b"),
+            b'\t' => out.extend_from_slice(b"\\t"),
+            b'\n' => out.extend_from_slice(b"\\n"),
+            b'\x0b' => out.extend_from_slice(b"\\v"),
+            b'\x0c' => out.extend_from_slice(b"\\f"),
+            b'\r' => out.extend_from_slice(b"\\r"),
+            b'"' | b'\\' => out.extend_from_slice(&[b'\\', b]),
+            b if needs_quoting(b) => out.extend_from_slice(format!("\\{b:03o}").as_bytes()),
+            b => out.push(b),
+        }
+    }
+    out.push(b'"');
+    out
+}
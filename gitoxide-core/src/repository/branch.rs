@@ -0,0 +1,249 @@
+pub enum Kind {
+    /// Only list local branches.
+    Local,
+    /// Only list remote tracking branches.
+    Remote,
+    /// List local and remote tracking branches.
+    All,
+}
+
+pub enum Action {
+    /// List branches of the given `kind`, showing their tip and its summary if `verbose` is 1 or more, and the upstream
+    /// along with the amount of commits the branch is ahead and behind of it if `verbose` is 2 or more.
+    List { kind: Kind, verbose: u8 },
+    /// Create a branch called `name` at `start_point`, or `HEAD` if unset, overwriting an existing branch if `force` is set.
+    Create {
+        name: String,
+        start_point: Option<String>,
+        force: bool,
+    },
+    /// Delete all branches with `names`, even if they are not merged into their upstream or `HEAD` if `force` is set.
+    Delete { names: Vec<String>, force: bool },
+}
+
+pub(crate) mod function {
+    use anyhow::{anyhow, bail, Context};
+    use gix::{bstr::ByteSlice, prelude::ObjectIdExt, refs::transaction::PreviousValue, remote::Direction, ObjectId};
+
+    use super::{Action, Kind};
+
+    pub fn branch(
+        repo: gix::Repository,
+        mut out: impl std::io::Write,
+        mut err: impl std::io::Write,
+        action: Action,
+    ) -> anyhow::Result<()> {
+        match action {
+            Action::List { kind, verbose } => list(&repo, &mut out, kind, verbose),
+            Action::Create {
+                name,
+                start_point,
+                force,
+            } => {
+                let start_point = start_point.unwrap_or_else(|| "HEAD".into());
+                let id = repo
+                    .rev_parse_single(start_point.as_str())?
+                    .object()?
+                    .peel_to_kind(gix::object::Kind::Commit)?
+                    .id;
+                repo.reference(
+                    format!("refs/heads/{name}"),
+                    id,
+                    if force {
+                        PreviousValue::Any
+                    } else {
+                        PreviousValue::MustNotExist
+                    },
+                    format!("branch: Created from {start_point}"),
+                )
+                .with_context(|| format!("Could not create branch '{name}'"))?;
+                Ok(())
+            }
+            Action::Delete { names, force } => {
+                let head_name = repo.head_name()?;
+                for name in names {
+                    let Some(branch) = gix::refs::FullName::try_from(format!("refs/heads/{name}"))
+                        .ok()
+                        .map(|name| repo.try_find_reference(name.as_ref()))
+                        .transpose()?
+                        .flatten()
+                    else {
+                        bail!("branch '{name}' not found");
+                    };
+                    if head_name.as_ref() == Some(&branch.inner.name) {
+                        bail!("Cannot delete branch '{name}' as it is checked out");
+                    }
+                    let id = branch.target().try_id().map(ToOwned::to_owned);
+                    if let (Some(id), false) = (id, force) {
+                        // Like `git`, consider the upstream if there is one, as the branch may have been merged there.
+                        let merge_target = match branch.remote_tracking_ref_name(Direction::Fetch).transpose()? {
+                            Some(upstream) => repo.try_find_reference(upstream.as_ref())?,
+                            None => None,
+                        };
+                        let merge_target = match merge_target {
+                            Some(mut upstream) => Some(upstream.peel_to_id_in_place()?.detach()),
+                            None => repo.head_id().ok().map(gix::Id::detach),
+                        };
+                        if !merge_target.map_or(Ok(false), |tip| is_ancestor(&repo, id, tip))? {
+                            bail!("The branch '{name}' is not fully merged - use --force to delete it anyway");
+                        }
+                    }
+                    branch.delete()?;
+                    match id {
+                        Some(id) => writeln!(err, "Deleted branch {name} (was {}).", id.attach(&repo).shorten_or_id())?,
+                        None => writeln!(err, "Deleted branch {name}.")?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    struct Branch {
+        display_name: String,
+        is_head: bool,
+        id: Option<ObjectId>,
+        full_name: gix::refs::FullName,
+    }
+
+    fn list(repo: &gix::Repository, mut out: impl std::io::Write, kind: Kind, verbose: u8) -> anyhow::Result<()> {
+        let head = repo.head()?;
+        let head_name = head.referent_name().map(ToOwned::to_owned);
+        let platform = repo.references()?;
+        let mut branches = Vec::new();
+        if head.is_detached() {
+            if let (Some(id), Kind::Local | Kind::All) = (head.id(), &kind) {
+                branches.push(Branch {
+                    display_name: format!("(HEAD detached at {})", id.shorten_or_id()),
+                    is_head: true,
+                    id: Some(id.detach()),
+                    full_name: "HEAD".try_into().expect("valid"),
+                });
+            }
+        }
+        let iters = match kind {
+            Kind::Local => vec![platform.local_branches()?],
+            Kind::Remote => vec![platform.remote_branches()?],
+            Kind::All => vec![platform.local_branches()?, platform.remote_branches()?],
+        };
+        for reference in iters.into_iter().flatten() {
+            let mut reference = reference.map_err(|err| anyhow!(err))?;
+            let full_name = reference.name().to_owned();
+            let short_name = |name: &gix::refs::FullNameRef| match (&kind, name.category_and_short_name()) {
+                (Kind::All, Some((gix::refs::Category::RemoteBranch, short))) => format!("remotes/{short}"),
+                (_, Some((_, short))) => short.to_string(),
+                (_, None) => name.as_bstr().to_string(),
+            };
+            // Symbolic references like `origin/HEAD` are shown with their target instead of a commit.
+            let (display_name, id) = match reference.target().try_name() {
+                Some(target) => (
+                    format!("{} -> {}", short_name(full_name.as_ref()), target.shorten()),
+                    None,
+                ),
+                None => (
+                    short_name(full_name.as_ref()),
+                    reference.peel_to_id_in_place().ok().map(gix::Id::detach),
+                ),
+            };
+            branches.push(Branch {
+                display_name,
+                is_head: head_name.as_ref() == Some(&full_name),
+                id,
+                full_name,
+            });
+        }
+
+        let width = branches
+            .iter()
+            .filter(|b| b.id.is_some())
+            .map(|b| b.display_name.chars().count())
+            .max()
+            .unwrap_or(0);
+        for branch in branches {
+            let marker = if branch.is_head { '*' } else { ' ' };
+            if verbose == 0 {
+                writeln!(out, "{marker} {}", branch.display_name)?;
+                continue;
+            }
+            let Some(id) = branch.id else {
+                writeln!(out, "{marker} {}", branch.display_name)?;
+                continue;
+            };
+            let commit = id.attach(repo).object()?.into_commit();
+            let summary = commit.message()?.summary();
+            let tracking = tracking_info(repo, &branch.full_name, id, verbose > 1)?;
+            writeln!(
+                out,
+                "{marker} {:width$} {} {}{}",
+                branch.display_name,
+                commit.id().shorten_or_id(),
+                tracking.map(|info| format!("[{info}] ")).unwrap_or_default(),
+                summary.as_bstr()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Describe how the local branch `name` at `id` relates to its upstream, if it has one, similar to what
+    /// `git branch -v` shows, and include the name of the upstream if `with_upstream_name` is set.
+    fn tracking_info(
+        repo: &gix::Repository,
+        name: &gix::refs::FullName,
+        id: ObjectId,
+        with_upstream_name: bool,
+    ) -> anyhow::Result<Option<String>> {
+        if name.category() != Some(gix::refs::Category::LocalBranch) {
+            return Ok(None);
+        }
+        let Some(upstream_name) = repo
+            .branch_remote_tracking_ref_name(name.as_ref(), Direction::Fetch)
+            .transpose()?
+        else {
+            return Ok(None);
+        };
+        let upstream_display = upstream_name.shorten().to_string();
+        let counts = match repo.try_find_reference(upstream_name.as_ref())? {
+            Some(mut upstream) => {
+                let upstream_id = upstream.peel_to_id_in_place()?.detach();
+                let (ahead, behind) = ahead_behind(repo, id, upstream_id)?;
+                let mut counts = Vec::new();
+                if ahead > 0 {
+                    counts.push(format!("ahead {ahead}"));
+                }
+                if behind > 0 {
+                    counts.push(format!("behind {behind}"));
+                }
+                counts.join(", ")
+            }
+            None => "gone".into(),
+        };
+        Ok(match (with_upstream_name, counts.is_empty()) {
+            (true, true) => Some(upstream_display),
+            (true, false) => Some(format!("{upstream_display}: {counts}")),
+            (false, true) => None,
+            (false, false) => Some(counts),
+        })
+    }
+
+    /// Return the amount of commits reachable from `tip` but not from `upstream`, and vice versa.
+    fn ahead_behind(repo: &gix::Repository, tip: ObjectId, upstream: ObjectId) -> anyhow::Result<(usize, usize)> {
+        if tip == upstream {
+            return Ok((0, 0));
+        }
+        let ours = ancestors(repo, tip)?;
+        let theirs = ancestors(repo, upstream)?;
+        Ok((ours.difference(&theirs).count(), theirs.difference(&ours).count()))
+    }
+
+    fn is_ancestor(repo: &gix::Repository, id: ObjectId, tip: ObjectId) -> anyhow::Result<bool> {
+        Ok(id == tip || ancestors(repo, tip)?.contains(&id))
+    }
+
+    fn ancestors(repo: &gix::Repository, tip: ObjectId) -> anyhow::Result<gix::hashtable::HashSet<ObjectId>> {
+        Ok(repo
+            .rev_walk([tip])
+            .all()?
+            .map(|info| info.map(|info| info.id))
+            .collect::<Result<_, _>>()?)
+    }
+}
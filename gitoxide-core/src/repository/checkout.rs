@@ -0,0 +1,124 @@
+use gix::bstr::BString;
+
+pub struct Options {
+    /// The revision to check out, or the start-point of a new branch, or the source of `paths`.
+    pub revision: Option<String>,
+    /// Create a new local branch with this name at `revision` and switch to it.
+    pub create_branch: Option<String>,
+    /// Detach `HEAD` at `revision` even if it names a local branch.
+    pub detach: bool,
+    /// If `true`, revisions that don't name a local branch detach `HEAD`, like `git checkout` does.
+    /// Otherwise, like `git switch`, `detach` must be set for that.
+    pub implicit_detach: bool,
+    /// Discard local changes and overwrite untracked files that are in the way.
+    pub force: bool,
+    /// If not empty, restore these paths from `revision` or the index instead, without changing `HEAD`.
+    pub paths: Vec<BString>,
+}
+
+pub(crate) mod function {
+    use anyhow::{bail, Context};
+    use gix::{bstr::ByteSlice, refs::transaction::PreviousValue, Progress};
+
+    use super::Options;
+
+    pub fn checkout(
+        repo: gix::Repository,
+        progress: impl Progress,
+        mut err: impl std::io::Write,
+        Options {
+            revision,
+            create_branch,
+            detach,
+            implicit_detach,
+            force,
+            paths,
+        }: Options,
+    ) -> anyhow::Result<()> {
+        let options = gix::checkout::Options { force };
+        if !paths.is_empty() {
+            if create_branch.is_some() || detach {
+                bail!("Cannot create branches or detach HEAD while restoring paths");
+            }
+            let source = match revision.as_deref() {
+                Some(rev) => Some(repo.rev_parse_single(rev)?.detach()),
+                None => None,
+            };
+            progress.info(format!(
+                "Restoring {} pathspec(s) from {}",
+                paths.len(),
+                revision.as_deref().unwrap_or("the index")
+            ));
+            repo.restore(
+                &paths,
+                gix::checkout::RestoreOptions {
+                    source,
+                    staged: source.is_some(),
+                    worktree: true,
+                },
+            )?;
+            return Ok(());
+        }
+
+        if let Some(name) = create_branch {
+            let revision = revision.unwrap_or_else(|| "HEAD".into());
+            if detach {
+                bail!("Cannot detach HEAD when creating a branch");
+            }
+            let start_point = repo
+                .rev_parse_single(revision.as_str())?
+                .object()?
+                .peel_to_kind(gix::object::Kind::Commit)?
+                .id;
+            let branch = repo
+                .reference(
+                    format!("refs/heads/{name}"),
+                    start_point,
+                    PreviousValue::MustNotExist,
+                    format!("branch: Created from {revision}"),
+                )
+                .with_context(|| format!("Could not create branch '{name}'"))?;
+            progress.info(format!("Switching to new branch '{name}'"));
+            repo.switch(branch.name(), options)?;
+            writeln!(err, "Switched to a new branch '{name}'")?;
+            return Ok(());
+        }
+
+        let Some(revision) = revision else {
+            bail!("A branch or revision to check out is required");
+        };
+        if !detach {
+            let branch = gix::refs::FullName::try_from(format!("refs/heads/{revision}"))
+                .ok()
+                .map(|name| repo.try_find_reference(name.as_ref()))
+                .transpose()?
+                .flatten();
+            if let Some(branch) = branch {
+                let name = branch.name().shorten().to_owned();
+                progress.info(format!("Switching to branch '{name}'"));
+                repo.switch(branch.name(), options)?;
+                writeln!(err, "Switched to branch '{name}'")?;
+                return Ok(());
+            }
+            if !implicit_detach {
+                bail!("A branch is expected, got '{revision}' - use --detach to check out a commit");
+            }
+        }
+
+        let commit = repo
+            .rev_parse_single(revision.as_str())?
+            .object()?
+            .peel_to_kind(gix::object::Kind::Commit)?
+            .into_commit();
+        progress.info(format!("Detaching HEAD at {}", commit.id));
+        repo.checkout_detached(commit.id, options)?;
+        let message = commit.message()?;
+        writeln!(
+            err,
+            "HEAD is now at {} {}",
+            commit.id().shorten_or_id(),
+            message.summary().as_bstr()
+        )?;
+        Ok(())
+    }
+}
@@ -1,11 +1,14 @@
 use crate::OutputFormat;
 
+use gix::bstr::BString;
+
 pub struct Options {
     pub format: OutputFormat,
     pub bare: bool,
     pub handshake_info: bool,
     pub no_tags: bool,
     pub shallow: gix::remote::fetch::Shallow,
+    pub filter: Option<BString>,
 }
 
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
@@ -17,7 +20,10 @@ pub(crate) mod function {
     use gix::{bstr::BString, remote::fetch::Status, NestedProgress};
 
     use super::Options;
-    use crate::{repository::fetch::function::print_updates, OutputFormat};
+    use crate::{
+        repository::fetch::function::{bundle_path, print_bundle_updates, print_updates},
+        OutputFormat,
+    };
 
     pub fn clone<P>(
         url: impl AsRef<OsStr>,
@@ -32,6 +38,7 @@ pub(crate) mod function {
             bare,
             no_tags,
             shallow,
+            filter,
         }: Options,
     ) -> anyhow::Result<()>
     where
@@ -43,10 +50,13 @@ pub(crate) mod function {
         }
 
         let url: gix::Url = url.as_ref().try_into()?;
+        let bundle_path = bundle_path(&url);
         let directory = directory.map_or_else(
             || {
                 let path = gix::path::from_bstr(Cow::Borrowed(url.path.as_ref()));
-                if !bare && path.extension() == Some(OsStr::new("git")) {
+                if (!bare && path.extension() == Some(OsStr::new("git")))
+                    || path.extension() == Some(OsStr::new("bundle"))
+                {
                     path.file_stem().map(Into::into)
                 } else {
                     path.file_name().map(Into::into)
@@ -73,6 +83,22 @@ pub(crate) mod function {
         if no_tags {
             prepare = prepare.configure_remote(|r| Ok(r.with_fetch_tags(gix::remote::fetch::Tags::None)));
         }
+        if let Some(filter) = filter {
+            prepare = prepare.with_filter(filter);
+        }
+        if let Some(bundle_path) = bundle_path {
+            let (mut checkout, unbundle_outcome) =
+                prepare.unbundle_then_checkout(&mut progress, &gix::interrupt::IS_INTERRUPTED)?;
+            let outcome = if bare {
+                checkout.persist();
+                None
+            } else {
+                Some(checkout.main_worktree(progress, &gix::interrupt::IS_INTERRUPTED)?.1)
+            };
+            writeln!(out, "Cloned from bundle at \"{}\"", bundle_path.display())?;
+            print_bundle_updates(&unbundle_outcome, &mut out)?;
+            return report_checkout_outcome(outcome, &mut err);
+        }
         let (mut checkout, fetch_outcome) = prepare
             .with_shallow(shallow)
             .fetch_then_checkout(&mut progress, &gix::interrupt::IS_INTERRUPTED)?;
@@ -113,6 +139,13 @@ pub(crate) mod function {
             }
         };
 
+        report_checkout_outcome(outcome, &mut err)
+    }
+
+    fn report_checkout_outcome(
+        outcome: Option<gix::worktree::state::checkout::Outcome>,
+        mut err: impl std::io::Write,
+    ) -> anyhow::Result<()> {
         if let Some(gix::worktree::state::checkout::Outcome { collisions, errors, .. }) = outcome {
             if !(collisions.is_empty() && errors.is_empty()) {
                 let mut messages = Vec::new();
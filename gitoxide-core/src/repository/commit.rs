@@ -1,4 +1,9 @@
-use std::{io::Write, process::Stdio};
+use std::{
+    ffi::OsStr,
+    io::{Read, Write},
+    path::Path,
+    process::Stdio,
+};
 
 use anyhow::{anyhow, bail, Context, Result};
 
@@ -87,6 +92,309 @@ pub fn describe(
     Ok(())
 }
 
+/// Create a new commit from the index like `git commit`, or replace the commit at `HEAD` if `options.amend` is set.
+///
+/// The `pre-commit`, `prepare-commit-msg`, `commit-msg` and `post-commit` hooks are run just like `git` would, and if no
+/// message is given, `core.editor` is launched to edit a message template with the changes to be committed.
+pub fn create(
+    repo: gix::Repository,
+    progress: impl gix::Progress,
+    mut err: impl std::io::Write,
+    create::Options {
+        messages,
+        file,
+        amend,
+        allow_empty,
+        signoff,
+        no_verify,
+        no_edit,
+    }: create::Options,
+) -> Result<()> {
+    use gix::{
+        bstr::{BString, ByteSlice, ByteVec},
+        prelude::ObjectIdExt,
+    };
+
+    if !no_verify && !run_hook(&repo, "pre-commit", &[])? {
+        bail!("The pre-commit hook failed");
+    }
+    // Hooks may have changed the index, so read it afterwards.
+    let index = repo.index_or_empty()?;
+    if index.entries().iter().any(|e| e.stage() != 0) {
+        bail!("Committing is not possible because you have unmerged files");
+    }
+    let tree = index.write_tree(|tree| repo.write_object(tree).map(gix::Id::detach))?;
+
+    let head = repo.head()?;
+    let head_id = head.id().map(gix::Id::detach);
+    let amended = match (amend, head_id) {
+        (true, Some(id)) => Some(repo.find_object(id)?.into_commit()),
+        (true, None) => bail!("You have nothing to amend"),
+        (false, _) => None,
+    };
+    let parents: Vec<gix::ObjectId> = match &amended {
+        Some(commit) => commit.parent_ids().map(gix::Id::detach).collect(),
+        None => head_id.into_iter().collect(),
+    };
+    if amended.is_none() && !allow_empty {
+        let parent_tree = match parents.first() {
+            Some(parent) => repo.find_object(*parent)?.peel_to_tree()?.id,
+            None => gix::ObjectId::empty_tree(repo.object_hash()),
+        };
+        if parent_tree == tree {
+            bail!("Nothing to commit, use --allow-empty to create a commit without changes");
+        }
+    }
+
+    let (mut message, source): (BString, Option<(&str, Option<String>)>) = if !messages.is_empty() {
+        (messages.join("\n\n").into(), Some(("message", None)))
+    } else if let Some(file) = file {
+        let mut buf = Vec::new();
+        if file == Path::new("-") {
+            std::io::stdin().read_to_end(&mut buf)?;
+        } else {
+            buf = std::fs::read(&file).with_context(|| format!("Could not read message from '{}'", file.display()))?;
+        }
+        (buf.into(), Some(("message", None)))
+    } else if let Some(commit) = &amended {
+        (
+            commit.message_raw()?.to_owned(),
+            Some(("commit", Some(commit.id.to_string()))),
+        )
+    } else {
+        (BString::default(), None)
+    };
+    let edit = source.as_ref().map_or(true, |(kind, _)| *kind == "commit" && !no_edit);
+
+    if signoff {
+        let committer = repo.committer().context("A committer is required to sign off")??;
+        let trailer = format!("Signed-off-by: {} <{}>", committer.name, committer.email);
+        message = append_signoff(message, &trailer);
+    }
+
+    let message_path = repo.git_dir().join("COMMIT_EDITMSG");
+    {
+        let mut buf = message.clone();
+        if edit {
+            if !buf.is_empty() && !buf.ends_with(b"\n") {
+                buf.push_byte(b'\n');
+            }
+            buf.push_str(commit_template(&repo, &index, &parents)?);
+        }
+        std::fs::write(&message_path, buf)?;
+    }
+
+    let mut hook_args = vec![message_path.as_os_str()];
+    if let Some((kind, id)) = &source {
+        hook_args.push(OsStr::new(kind));
+        if let Some(id) = id {
+            hook_args.push(OsStr::new(id));
+        }
+    }
+    if !run_hook(&repo, "prepare-commit-msg", &hook_args)? {
+        bail!("The prepare-commit-msg hook failed");
+    }
+    if edit {
+        progress.info("Waiting for the editor to close the commit message file".into());
+        launch_editor(&repo, &message_path)?;
+    }
+    if !no_verify && !run_hook(&repo, "commit-msg", &[message_path.as_os_str()])? {
+        bail!("The commit-msg hook failed");
+    }
+
+    let message = std::fs::read(&message_path)?;
+    let message = if edit {
+        strip_comments(message.as_bstr())
+    } else {
+        message.into()
+    };
+    let message = gix::objs::commit::message::normalize(message.as_bstr());
+    if message.is_empty() {
+        bail!("Aborting commit due to empty commit message");
+    }
+    let message = message.to_str().context("Commit messages must be valid UTF-8")?;
+
+    let id = match amended {
+        Some(amended) => {
+            use gix::refs::{
+                transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+                Target,
+            };
+            let committer = repo.committer().context("A committer is required")??;
+            let commit = gix::objs::Commit {
+                tree,
+                parents: parents.into(),
+                author: amended.author()?.into(),
+                committer: committer.into(),
+                encoding: None,
+                message: message.into(),
+                extra_headers: Default::default(),
+            };
+            let id = repo.write_object(&commit)?.detach();
+            repo.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: gix::reference::log::message("commit (amend)", message.into(), commit.parents.len()),
+                    },
+                    expected: PreviousValue::MustExistAndMatch(Target::Peeled(amended.id)),
+                    new: Target::Peeled(id),
+                },
+                name: "HEAD".try_into().expect("valid"),
+                deref: true,
+            })?;
+            id
+        }
+        None => repo.commit("HEAD", message, tree, parents)?.detach(),
+    };
+    run_hook(&repo, "post-commit", &[])?;
+
+    let branch = match repo.head_name()? {
+        Some(name) => name.shorten().to_string(),
+        None => "detached HEAD".into(),
+    };
+    let summary = gix::objs::commit::MessageRef::from_bytes(message.as_bytes()).summary();
+    writeln!(err, "[{branch} {}] {summary}", id.attach(&repo).shorten_or_id())?;
+    Ok(())
+}
+
+/// Append the `Signed-off-by` `trailer` to `message` unless it's already the last trailer, keeping it in the trailer block if
+/// there is one.
+fn append_signoff(message: gix::bstr::BString, trailer: &str) -> gix::bstr::BString {
+    use gix::bstr::{ByteSlice, ByteVec};
+    let trimmed = message.trim_end();
+    if trimmed.lines().next_back() == Some(trailer.as_bytes()) {
+        return message;
+    }
+    let last_paragraph = trimmed.rfind(b"\n\n").map_or(trimmed, |pos| &trimmed[pos + 2..]);
+    let ends_with_trailers = trimmed.lines().count() > 1
+        && !last_paragraph.is_empty()
+        && last_paragraph.lines().all(|line| {
+            line.find(b": ")
+                .map_or(false, |pos| pos > 0 && !line[..pos].contains(&b' '))
+        });
+    let mut out: gix::bstr::BString = trimmed.into();
+    if !out.is_empty() {
+        out.push_str(if ends_with_trailers { "\n" } else { "\n\n" });
+    }
+    out.push_str(trailer);
+    out.push_byte(b'\n');
+    out
+}
+
+/// Remove all lines starting with `#` from `message`.
+fn strip_comments(message: &gix::bstr::BStr) -> gix::bstr::BString {
+    use gix::bstr::{ByteSlice, ByteVec};
+    let mut out = gix::bstr::BString::default();
+    for line in message.lines_with_terminator().filter(|line| !line.starts_with(b"#")) {
+        out.push_str(line);
+    }
+    out
+}
+
+/// Produce the commented template `git` presents in the editor, listing the changes between the first of `parents` and `index`.
+fn commit_template(repo: &gix::Repository, index: &gix::index::State, parents: &[gix::ObjectId]) -> Result<String> {
+    use std::fmt::Write;
+
+    use gix::status::tree_index::{Action, Change};
+
+    let mut out = String::from(
+        "\n# Please enter the commit message for your changes. Lines starting\n\
+         # with '#' will be ignored, and an empty message aborts the commit.\n#\n",
+    );
+    match repo.head_name()? {
+        Some(name) => writeln!(out, "# On branch {}", name.shorten())?,
+        None => writeln!(out, "# HEAD detached")?,
+    }
+    let tree = match parents.first() {
+        Some(parent) => repo.find_object(*parent)?.peel_to_tree()?.id,
+        None => {
+            writeln!(out, "#\n# Initial commit")?;
+            gix::ObjectId::empty_tree(repo.object_hash())
+        }
+    };
+    let mut changes = Vec::new();
+    repo.tree_index_status(&tree, index, None, |change| -> Result<_, std::convert::Infallible> {
+        let (kind, source) = match &change {
+            Change::Addition { .. } => ("new file", None),
+            Change::Deletion { .. } => ("deleted", None),
+            Change::Modification { .. } => ("modified", None),
+            Change::Rename { from, .. } => ("renamed", Some(from.to_string())),
+            Change::Copy { from, .. } => ("copied", Some(from.to_string())),
+        };
+        let path = change.location().to_string();
+        changes.push(match source {
+            Some(source) => format!("{kind}:   {source} -> {path}"),
+            None => format!("{kind}:   {path}"),
+        });
+        Ok(Action::Continue)
+    })?;
+    changes.sort();
+    if !changes.is_empty() {
+        writeln!(out, "#\n# Changes to be committed:")?;
+        for change in changes {
+            writeln!(out, "#\t{change}")?;
+        }
+    }
+    writeln!(out, "#")?;
+    Ok(out)
+}
+
+/// Run the hook called `name` with `args` if it exists and is executable, and return `false` if it failed.
+fn run_hook(repo: &gix::Repository, name: &str, args: &[&OsStr]) -> Result<bool> {
+    let Some(hook) = repo.hook(name)? else {
+        return Ok(true);
+    };
+    let status = hook.args(args).env("GIT_INDEX_FILE", repo.index_path()).status()?;
+    Ok(status.success())
+}
+
+/// Launch the editor configured like `git` would find it to edit the file at `path`.
+fn launch_editor(repo: &gix::Repository, path: &Path) -> Result<()> {
+    let editor = gix::path::from_bstring(repo.editor()?).into_os_string();
+    if editor == ":" {
+        return Ok(());
+    }
+    let status = std::process::Command::from(
+        gix::command::prepare(editor.clone())
+            .with_shell()
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .arg(path),
+    )
+    .current_dir(repo.work_dir().unwrap_or(repo.git_dir()))
+    .spawn()
+    .and_then(|mut child| child.wait())
+    .with_context(|| format!("Could not launch editor {editor:?}"))?;
+    if !status.success() {
+        bail!("There was a problem with the editor {editor:?}");
+    }
+    Ok(())
+}
+
+pub mod create {
+    use std::path::PathBuf;
+
+    #[derive(Debug, Clone)]
+    pub struct Options {
+        /// Paragraphs of the commit message.
+        pub messages: Vec<String>,
+        /// Read the commit message from this file, or from stdin if it's `-`.
+        pub file: Option<PathBuf>,
+        /// Replace the commit at `HEAD` instead of creating a new one on top of it.
+        pub amend: bool,
+        /// Allow creating a commit with the same tree as its parent.
+        pub allow_empty: bool,
+        /// Add a `Signed-off-by` trailer for the committer.
+        pub signoff: bool,
+        /// Don't run the `pre-commit` and `commit-msg` hooks.
+        pub no_verify: bool,
+        /// When amending, reuse the message of the amended commit without launching an editor.
+        pub no_edit: bool,
+    }
+}
+
 pub mod describe {
     #[derive(Debug, Clone)]
     pub struct Options {
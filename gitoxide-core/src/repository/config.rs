@@ -1,13 +1,25 @@
 use anyhow::{bail, Result};
-use gix::bstr::{BStr, BString};
+use gix::bstr::{BStr, BString, ByteSlice};
 
 use crate::OutputFormat;
 
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Options {
+    /// Print each value on its own line along with the file or other origin it was read from, like `git config --show-origin`.
+    pub show_origin: bool,
+    /// Print each value on its own line along with the scope it was read from, like `git config --show-scope`.
+    pub show_scope: bool,
+}
+
 pub fn list(
     repo: gix::Repository,
     filters: Vec<BString>,
     overrides: Vec<BString>,
     format: OutputFormat,
+    Options {
+        show_origin,
+        show_scope,
+    }: Options,
     mut out: impl std::io::Write,
 ) -> Result<()> {
     if format != OutputFormat::Human {
@@ -18,12 +30,35 @@ pub fn list(
         repo.open_options().clone().lossy_config(false).cli_overrides(overrides),
     )?;
     let config = repo.config_snapshot();
+    let filters: Vec<_> = filters.into_iter().map(Filter::new).collect();
+    if show_origin || show_scope {
+        for section in config.sections() {
+            if !filters.is_empty() && !filters.iter().any(|filter| filter.matches_section(section)) {
+                continue;
+            }
+            let meta = section.meta();
+            let header = section.header();
+            for (key, value) in section.body().clone() {
+                if show_scope {
+                    write!(out, "{}\t", meta.source.scope())?;
+                }
+                if show_origin {
+                    write!(out, "{}\t", origin(meta))?;
+                }
+                write!(out, "{}.", header.name().to_ascii_lowercase().as_bstr())?;
+                if let Some(subsection) = header.subsection_name() {
+                    write!(out, "{subsection}.")?;
+                }
+                writeln!(out, "{}={value}", key.to_ascii_lowercase().as_bstr())?;
+            }
+        }
+        return Ok(());
+    }
     if let Some(frontmatter) = config.frontmatter() {
         for event in frontmatter {
             event.write_to(&mut out)?;
         }
     }
-    let filters: Vec<_> = filters.into_iter().map(Filter::new).collect();
     let mut last_meta = None;
     let mut it = config.sections_and_postmatter().peekable();
     while let Some((section, matter)) = it.next() {
@@ -50,6 +85,74 @@ pub fn list(
     Ok(())
 }
 
+/// Changes to make to a single configuration file.
+#[derive(Debug, Default, Clone)]
+pub struct Edits {
+    /// Assignments of the form `section.subsection.key=value` to set.
+    pub set: Vec<BString>,
+    /// Keys of the form `section.subsection.key` whose values should all be removed.
+    pub unset: Vec<BString>,
+    /// Sections of the form `section.subsection` to remove entirely.
+    pub remove_sections: Vec<BString>,
+}
+
+/// Apply `edits` to the configuration file of `source`, preserving everything else in the file.
+pub fn edit(
+    repo: gix::Repository,
+    source: gix::config::Source,
+    Edits {
+        set,
+        unset,
+        remove_sections,
+    }: Edits,
+    mut out: impl std::io::Write,
+) -> Result<()> {
+    let mut file = repo.config_file_mut(source)?;
+    for assignment in set {
+        let Some((key, value)) = assignment.split_once_str("=") else {
+            bail!("Assignments must be of the form 'section.key=value', got '{assignment}'")
+        };
+        let key = parse_key(key.as_bstr())?;
+        file.set_raw_value(
+            key.section_name,
+            key.subsection_name,
+            key.value_name.to_owned(),
+            value.as_bstr(),
+        )?;
+    }
+    for key in unset {
+        let key = parse_key(key.as_ref())?;
+        let ids: Vec<_> = file
+            .sections_and_ids_by_name(key.section_name)
+            .into_iter()
+            .flatten()
+            .filter(|(section, _)| section.header().subsection_name() == key.subsection_name)
+            .map(|(_, id)| id)
+            .collect();
+        for id in ids {
+            let mut section = file.section_mut_by_id(id).expect("id is valid");
+            while section.remove(key.value_name).is_some() {}
+        }
+    }
+    for section in remove_sections {
+        let (name, subsection) = match section.split_once_str(".") {
+            Some((name, subsection)) => (name.as_bstr(), Some(subsection.as_bstr())),
+            None => (section.as_ref(), None),
+        };
+        if file.remove_sections(name.to_str_lossy(), subsection).is_empty() {
+            bail!("No section named '{section}' found in '{}'", file.path().display());
+        }
+    }
+    let path = file.path().to_owned();
+    file.commit()?;
+    writeln!(out, "Wrote '{}'", path.display())?;
+    Ok(())
+}
+
+fn parse_key(key: &BStr) -> Result<gix::config::parse::Key<'_>> {
+    gix::config::parse::key(key).ok_or_else(|| anyhow::anyhow!("Keys must be of the form 'section.key', got '{key}'"))
+}
+
 struct Filter {
     name: String,
     subsection: Option<BString>,
@@ -88,6 +191,17 @@ impl Filter {
     }
 }
 
+/// Describe where the configuration described by `meta` came from, like `git config --show-origin` does.
+fn origin(meta: &gix::config::file::Metadata) -> String {
+    use gix::config::Source;
+    match (meta.path.as_deref(), meta.source) {
+        (Some(path), _) => format!("file:{}", path.display()),
+        (None, Source::Env | Source::Cli) => "command line:".into(),
+        (None, Source::EnvOverride) => "environment:".into(),
+        (None, _) => "memory:".into(),
+    }
+}
+
 fn write_meta(meta: &gix::config::file::Metadata, out: &mut impl std::io::Write) -> std::io::Result<()> {
     writeln!(
         out,
@@ -1,5 +1,11 @@
 use std::convert::TryInto;
 
+use gix::bstr::BString;
+
+/// The environment variable that is set while credential helpers are run on behalf of `gix credential` in helper mode,
+/// to prevent it from calling itself if it's configured as one of the helpers.
+const HELPER_ACTIVE_ENV: &str = "GIX_CREDENTIAL_HELPER_ACTIVE";
+
 #[derive(Debug, thiserror::Error)]
 enum Error {
     #[error(transparent)]
@@ -10,28 +16,108 @@ enum Error {
     Protocol(#[from] gix::credentials::protocol::Error),
 }
 
-pub fn function(repo: gix::Repository, action: gix::credentials::program::main::Action) -> anyhow::Result<()> {
+pub struct Options {
+    /// If not empty, the definitions of the helpers to use in order instead of the ones configured in `credential.helper`.
+    ///
+    /// Just like in the configuration, an empty definition removes all previous ones.
+    pub helpers: Vec<BString>,
+    /// If `true`, act like a credential helper, which never prompts and doesn't fail if no credentials could be obtained.
+    /// Otherwise, act like `git credential`.
+    pub as_helper: bool,
+}
+
+pub fn function(
+    repo: gix::Repository,
+    action: gix::credentials::program::main::Action,
+    Options { helpers, as_helper }: Options,
+) -> anyhow::Result<()> {
     use gix::credentials::program::main::Action::*;
-    gix::credentials::program::main(
+    if as_helper {
+        if std::env::var_os(HELPER_ACTIVE_ENV).is_some() {
+            // We were called by ourselves, so there is nothing to contribute.
+            std::io::copy(&mut std::io::stdin(), &mut std::io::sink())?;
+            return Ok(());
+        }
+        std::env::set_var(HELPER_ACTIVE_ENV, "1");
+    }
+    let res = gix::credentials::program::main(
         Some(action.as_str().into()),
         std::io::stdin(),
         std::io::stdout(),
         |action, context| -> Result<_, Error> {
-            let (mut cascade, _action, prompt_options) = repo.config_snapshot().credential_helpers(gix::url::parse(
-                context.url.as_ref().expect("framework assures URL is present").as_ref(),
-            )?)?;
-            cascade
-                .invoke(
-                    match action {
-                        Get => gix::credentials::helper::Action::Get(context),
-                        Erase => gix::credentials::helper::Action::Erase(context.to_bstring()),
-                        Store => gix::credentials::helper::Action::Store(context.to_bstring()),
-                    },
-                    prompt_options,
-                )
-                .map(|outcome| outcome.and_then(|outcome| (&outcome.next).try_into().ok()))
-                .map_err(Into::into)
+            let (mut cascade, _action, mut prompt_options) = repo.config_snapshot().credential_helpers(
+                gix::url::parse(context.url.as_ref().expect("framework assures URL is present").as_ref())?,
+            )?;
+            if !helpers.is_empty() {
+                cascade.programs = helpers.iter().fold(Vec::new(), |mut programs, helper| {
+                    if helper.is_empty() {
+                        programs.clear();
+                    } else {
+                        programs.push(gix::credentials::Program::from_custom_definition(helper.clone()));
+                    }
+                    programs
+                });
+            }
+            if as_helper {
+                prompt_options.mode = gix::prompt::Mode::Disable;
+            }
+            let res = cascade.invoke(
+                match action {
+                    Get => gix::credentials::helper::Action::Get(context),
+                    Erase => gix::credentials::helper::Action::Erase(context.to_bstring()),
+                    Store => gix::credentials::helper::Action::Store(context.to_bstring()),
+                },
+                prompt_options,
+            );
+            match res {
+                Ok(outcome) => Ok(outcome.and_then(|outcome| (&outcome.next).try_into().ok())),
+                Err(gix::credentials::protocol::Error::Quit) if as_helper => {
+                    Ok(Some(gix::credentials::protocol::Context {
+                        quit: Some(true),
+                        ..Default::default()
+                    }))
+                }
+                Err(gix::credentials::protocol::Error::IdentityMissing { .. }) if as_helper => Ok(None),
+                Err(err) => Err(err.into()),
+            }
         },
-    )
-    .map_err(Into::into)
+    );
+    match res {
+        Err(gix::credentials::program::main::Error::CredentialsMissing { .. }) if as_helper => Ok(()),
+        res => res.map_err(Into::into),
+    }
+}
+
+/// Perform `action` with the credential cache daemon described by `options`, reading the credential context from stdin and
+/// writing the obtained credentials to stdout, like `git credential-cache` does.
+#[cfg(unix)]
+pub fn cache(action: &str, options: &gix::credentials::cache::Options) -> anyhow::Result<()> {
+    use std::io::Read;
+
+    use gix::credentials::{cache, program::main::Action};
+    let action = match action {
+        "exit" => return Ok(cache::exit(&options.socket)?),
+        "get" => Action::Get,
+        "store" => Action::Store,
+        "erase" => Action::Erase,
+        // Unknown actions are ignored, just like `git` does, to allow for future extensions.
+        _ => return Ok(()),
+    };
+    let mut buf = Vec::new();
+    std::io::stdin().read_to_end(&mut buf)?;
+    let ctx = gix::credentials::protocol::Context::from_bytes(&buf)?;
+    if let Some(ctx) = cache::credentials(action, ctx, options)? {
+        ctx.write_to(std::io::stdout())?;
+    }
+    Ok(())
+}
+
+/// Run the daemon of [`cache()`] on `socket` until it stops by itself.
+#[cfg(unix)]
+pub fn cache_daemon(socket: &std::path::Path) -> anyhow::Result<()> {
+    use std::io::Write;
+    Ok(gix::credentials::cache::daemon::serve(socket, || {
+        let mut out = std::io::stdout();
+        out.write_all(b"ok\n").and_then(|_| out.flush()).ok();
+    })?)
 }
@@ -9,6 +9,8 @@ pub struct Options {
     /// If non-empty, override all ref-specs otherwise configured in the remote
     pub ref_specs: Vec<BString>,
     pub shallow: gix::remote::fetch::Shallow,
+    /// Fetch all objects of the wanted refs again, as if we had none of them.
+    pub refetch: bool,
     pub handshake_info: bool,
     pub negotiation_info: bool,
     pub open_negotiation_graph: Option<std::path::PathBuf>,
@@ -45,6 +47,7 @@ pub(crate) mod function {
             negotiation_info,
             open_negotiation_graph,
             shallow,
+            refetch,
             ref_specs,
         }: Options,
     ) -> anyhow::Result<()>
@@ -61,11 +64,33 @@ pub(crate) mod function {
             remote.replace_refspecs(ref_specs.iter(), gix::remote::Direction::Fetch)?;
             remote = remote.with_fetch_tags(gix::remote::fetch::Tags::None);
         }
+        if let Some(path) = remote.url(gix::remote::Direction::Fetch).and_then(bundle_path) {
+            if dry_run {
+                bail!("Dry-runs aren't supported when fetching from bundles");
+            }
+            if refetch {
+                bail!("Refetching isn't supported when fetching from bundles");
+            }
+            let mut refspecs = remote.refspecs(gix::remote::Direction::Fetch).to_vec();
+            refspecs.extend(remote.fetch_tags().to_refspec().map(|spec| spec.to_owned()));
+            let outcome = repo.unbundle(
+                path,
+                &mut progress,
+                &gix::interrupt::IS_INTERRUPTED,
+                gix::bundle::unbundle::Options {
+                    refspecs,
+                    reflog_message: None,
+                },
+            )?;
+            print_bundle_updates(&outcome, &mut out)?;
+            return Ok(());
+        }
         let res: gix::remote::fetch::Outcome = remote
             .connect(gix::remote::Direction::Fetch)?
             .prepare_fetch(&mut progress, Default::default())?
             .with_dry_run(dry_run)
             .with_shallow(shallow)
+            .with_refetch(refetch)
             .receive(&mut progress, &gix::interrupt::IS_INTERRUPTED)?;
 
         if handshake_info {
@@ -127,6 +152,38 @@ pub(crate) mod function {
         Ok(())
     }
 
+    /// Return the path to the bundle `url` points to, or `None` if it doesn't point to a bundle file.
+    pub(crate) fn bundle_path(url: &gix::Url) -> Option<std::path::PathBuf> {
+        use std::io::Read;
+        if url.scheme != gix::url::Scheme::File {
+            return None;
+        }
+        let path = gix::path::from_bstr(url.path.as_ref()).into_owned();
+        let mut signature = [0; gix::bundle::SIGNATURE_V2.len()];
+        std::fs::File::open(&path).ok()?.read_exact(&mut signature).ok()?;
+        gix::bundle::is_bundle(&signature).then_some(path)
+    }
+
+    pub(crate) fn print_bundle_updates(
+        outcome: &gix::bundle::unbundle::Outcome,
+        mut out: impl std::io::Write,
+    ) -> anyhow::Result<()> {
+        for update in &outcome.updates {
+            writeln!(
+                out,
+                "\t{} {} -> {} [{}]",
+                update.new, update.source, update.name, update.mode
+            )?;
+        }
+        if let Some(data_path) = &outcome.pack.data_path {
+            writeln!(out, "pack  file: \"{}\"", data_path.display()).ok();
+        }
+        if let Some(index_path) = &outcome.pack.index_path {
+            writeln!(out, "index file: \"{}\"", index_path.display()).ok();
+        }
+        Ok(())
+    }
+
     fn render_graph(
         repo: &gix::Repository,
         graph: &gix::negotiate::IdMap,
@@ -1,9 +1,61 @@
+use std::sync::atomic::AtomicBool;
+
 use anyhow::Context;
 use gix::{objs::Kind, ObjectId};
 
-pub fn function(mut repo: gix::Repository, spec: Option<String>, mut out: impl std::io::Write) -> anyhow::Result<()> {
-    let spec = spec.unwrap_or("HEAD".into());
+pub struct Options {
+    /// If set, only check that all objects reachable from the commit this revspec resolves to exist.
+    pub spec: Option<String>,
+    /// Report all unreachable objects, not only dangling ones.
+    pub unreachable: bool,
+    /// Don't recompute the hash of each object.
+    pub skip_hash_verification: bool,
+}
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=2;
+
+pub fn function(
+    mut repo: gix::Repository,
+    mut out: impl std::io::Write,
+    mut progress: impl gix::NestedProgress + 'static,
+    should_interrupt: &AtomicBool,
+    Options {
+        spec,
+        unreachable,
+        skip_hash_verification,
+    }: Options,
+) -> anyhow::Result<()> {
+    if let Some(spec) = spec {
+        return connectivity(repo, spec, out);
+    }
+
+    repo.object_cache_size_if_unset(4 * 1024 * 1024);
+    let outcome = repo.fsck(
+        &mut progress,
+        should_interrupt,
+        gix::fsck::Options {
+            verify_hashes: !skip_hash_verification,
+            check_connectivity: true,
+            report_unreachable: unreachable,
+        },
+    )?;
+    let mut num_errors = 0;
+    for finding in &outcome.findings {
+        if finding.is_error() {
+            num_errors += 1;
+        }
+        writeln!(out, "{finding}")?;
+    }
+    if num_errors != 0 {
+        anyhow::bail!(
+            "Found {num_errors} problem(s) in {} objects and their references",
+            outcome.num_objects
+        );
+    }
+    Ok(())
+}
 
+fn connectivity(mut repo: gix::Repository, spec: String, mut out: impl std::io::Write) -> anyhow::Result<()> {
     repo.object_cache_size_if_unset(4 * 1024 * 1024);
     // We expect to be finding a bunch of non-existent objects here - never refresh the ODB
     repo.objects.refresh_never();
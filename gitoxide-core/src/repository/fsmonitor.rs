@@ -0,0 +1,55 @@
+use std::sync::atomic::AtomicBool;
+
+use anyhow::{bail, Context};
+use gix::bstr::{BString, ByteSlice};
+
+use crate::OutputFormat;
+
+/// Watch the worktree of `repo` and answer queries for changes until interrupted or asked to quit.
+pub fn run(repo: gix::Repository, mut err: impl std::io::Write, should_interrupt: &AtomicBool) -> anyhow::Result<()> {
+    let worktree = repo
+        .work_dir()
+        .context("A filesystem monitor needs a worktree to watch")?;
+    writeln!(
+        err,
+        "Listening at '{}'",
+        gix_fsmonitor::ipc::socket_path(repo.git_dir()).display()
+    )?;
+    gix_fsmonitor::daemon::run(worktree, repo.git_dir(), should_interrupt, Default::default())?;
+    Ok(())
+}
+
+/// Ask the daemon of `repo` for all changes since `token`, or since the token stored in the index if `None`.
+pub fn query(
+    repo: gix::Repository,
+    token: Option<BString>,
+    mut out: impl std::io::Write,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+    let token = match token {
+        Some(token) => token,
+        None => {
+            let index = repo.index_or_empty()?;
+            match index.fs_monitor().map(gix::index::extension::FsMonitor::token) {
+                Some(gix::index::extension::fs_monitor::Token::V2 { token }) => token.clone(),
+                _ => BString::default(),
+            }
+        }
+    };
+    let response = gix_fsmonitor::ipc::send(repo.git_dir(), &token)?;
+    let mut items = response.split_str(b"\0").filter(|item| !item.is_empty());
+    writeln!(out, "token: {}", items.next().unwrap_or_default().as_bstr())?;
+    for path in items {
+        writeln!(out, "{}", path.as_bstr())?;
+    }
+    Ok(())
+}
+
+/// Ask the daemon of `repo` to stop.
+pub fn stop(repo: gix::Repository) -> anyhow::Result<()> {
+    gix_fsmonitor::ipc::send(repo.git_dir(), b"quit")?;
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use std::{io, ops::Range, str::FromStr};
+
+use anyhow::{bail, Context};
+use gix::{
+    bstr::{BStr, BString, ByteSlice},
+    prelude::ObjectIdExt,
+};
+
+use crate::OutputFormat;
+
+/// A range of lines in a file as passed to `-L<start>,<end>:<file>`, with one-based and inclusive line numbers.
+///
+/// `<end>` can also be given as `+<count>`, and if `<start>` or `<end>` are omitted, they default to the first and the
+/// last line of the file respectively.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineRange {
+    /// The first line of the range.
+    pub start: Option<u32>,
+    /// The last line of the range.
+    pub end: Option<LineRangeEnd>,
+    /// The path to the file, relative to the root of the repository.
+    pub file: BString,
+}
+
+/// The way the end of a [`LineRange`] is specified.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LineRangeEnd {
+    /// The last line of the range.
+    Line(u32),
+    /// The amount of lines in the range.
+    Count(u32),
+}
+
+impl FromStr for LineRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (spec, file) = s
+            .split_once(':')
+            .ok_or_else(|| format!("'{s}' must be of the form <start>,<end>:<file>"))?;
+        if file.is_empty() {
+            return Err(format!("'{s}' lacks the file to follow"));
+        }
+        let (start, end) = spec.split_once(',').unwrap_or((spec, ""));
+        let parse_line = |line: &str| -> Result<u32, String> {
+            match line.parse() {
+                Ok(0) | Err(_) => Err(format!("'{line}' is not a valid line number in '{s}'")),
+                Ok(line) => Ok(line),
+            }
+        };
+        Ok(LineRange {
+            start: (!start.is_empty()).then(|| parse_line(start)).transpose()?,
+            end: match end.strip_prefix('+') {
+                Some(count) => Some(LineRangeEnd::Count(parse_line(count)?)),
+                None if end.is_empty() => None,
+                None => Some(LineRangeEnd::Line(parse_line(end)?)),
+            },
+            file: file.into(),
+        })
+    }
+}
+
+impl LineRange {
+    /// Return the zero-based, end-exclusive range of lines in a file with `num_lines` lines.
+    ///
+    /// Like `git`, the end of the range is limited to the end of the file.
+    fn resolve(&self, num_lines: u32) -> Range<u32> {
+        let start = self.start.unwrap_or(1);
+        let end = match self.end {
+            Some(LineRangeEnd::Line(end)) => end,
+            Some(LineRangeEnd::Count(count)) => start + count - 1,
+            None => num_lines,
+        };
+        let (start, end) = if end < start { (end, start) } else { (start, end) };
+        start - 1..end.min(num_lines).max(start)
+    }
+}
+
+pub struct Options {
+    pub format: OutputFormat,
+    pub statistics: bool,
+}
+
+/// Show all commits reachable from `HEAD` that changed the lines in `ranges`, along with the changes to these lines,
+/// similar to `git log -L`.
+pub fn line_ranges(
+    repo: gix::Repository,
+    ranges: &[LineRange],
+    mut out: impl io::Write,
+    mut err: impl io::Write,
+    Options { format, statistics }: Options,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("Only human format is supported right now");
+    }
+    let Some(file) = ranges.first().map(|range| range.file.as_bstr()) else {
+        bail!("At least one line range is required");
+    };
+    if ranges.iter().any(|range| range.file != file) {
+        bail!("All line ranges must refer to the same file");
+    }
+
+    let head = repo.head_commit()?;
+    let num_lines = head
+        .tree()?
+        .lookup_entry_by_path(gix::path::from_bstr(file), &mut Vec::new())?
+        .with_context(|| format!("The file '{file}' does not exist at HEAD"))?
+        .object()?
+        .data
+        .lines_with_terminator()
+        .count() as u32;
+    if ranges
+        .iter()
+        .any(|range| range.start.map_or(false, |start| start > num_lines))
+    {
+        bail!("The file '{file}' has only {num_lines} line(s)");
+    }
+    let ranges: Vec<_> = ranges.iter().map(|range| range.resolve(num_lines)).collect();
+    let outcome = repo
+        .line_history(file, &ranges, head.id, Default::default())
+        .with_context(|| format!("Could not follow the history of lines in '{file}'"))?;
+
+    let mailmap = repo.open_mailmap();
+    for (index, entry) in outcome.entries.iter().enumerate() {
+        if index != 0 {
+            writeln!(out)?;
+        }
+        let commit = repo.find_object(entry.commit_id)?.try_into_commit()?;
+        let commit = commit.decode()?;
+        writeln!(out, "commit {}", entry.commit_id)?;
+        if commit.parents.len() > 1 {
+            write!(out, "Merge:")?;
+            for parent_id in commit.parents() {
+                write!(out, " {}", parent_id.attach(&repo).shorten_or_id())?;
+            }
+            writeln!(out)?;
+        }
+        let author = mailmap.resolve(commit.author);
+        writeln!(out, "Author: {} <{}>", author.name, author.email)?;
+        writeln!(out, "Date:   {}", author.time.format(gix::date::time::format::DEFAULT))?;
+        writeln!(out)?;
+        for line in commit.message.trim_end().lines() {
+            writeln!(out, "    {}", line.as_bstr())?;
+        }
+        writeln!(out)?;
+        if commit.parents.len() <= 1 {
+            write_diff(&repo, entry, file, &mut out)?;
+        }
+    }
+
+    if statistics {
+        writeln!(err, "{:#?}", outcome.statistics)?;
+    }
+    Ok(())
+}
+
+/// Write the changes of `entry` to the lines it tracks, showing each tracked range that was changed in full, like `git` does.
+fn write_diff(
+    repo: &gix::Repository,
+    entry: &gix::blame::history::Entry,
+    file: &BStr,
+    out: &mut impl io::Write,
+) -> anyhow::Result<()> {
+    let data = repo.find_object(entry.blob_id)?.detach().data;
+    let lines: Vec<_> = data.lines_with_terminator().collect();
+    let parent_data = match entry.parent {
+        Some((_, parent_blob_id)) => repo.find_object(parent_blob_id)?.detach().data,
+        None => Vec::new(),
+    };
+    let parent_lines: Vec<_> = parent_data.lines_with_terminator().collect();
+
+    writeln!(out, "diff --git a/{file} b/{file}")?;
+    if entry.parent.is_some() {
+        writeln!(out, "--- a/{file}")?;
+    } else {
+        writeln!(out, "--- /dev/null")?;
+    }
+    writeln!(out, "+++ b/{file}")?;
+
+    let hunks = &entry.hunks;
+    let mut next = 0;
+    for range in &entry.ranges {
+        while next < hunks.len() && hunks[next].after.end < range.start {
+            next += 1;
+        }
+        if next == hunks.len() || hunks[next].after.start > range.end {
+            continue;
+        }
+        let mut last = next;
+        while last < hunks.len() && hunks[last].after.start < range.end {
+            last += 1;
+        }
+        last = last.saturating_sub(1).max(next);
+
+        // The changes tell the line numbers in the parent, and all lines around them are unchanged.
+        let (first_hunk, last_hunk) = (&hunks[next], &hunks[last]);
+        let parent_start = first_hunk.before.start as i64 - (first_hunk.after.start as i64 - range.start as i64).max(0);
+        let parent_end = last_hunk.before.end as i64 + (range.end as i64 - last_hunk.after.end as i64).max(0);
+        let (parent_start, parent_end) = if parent_start == 0 && parent_end == 0 {
+            (-1, -1)
+        } else {
+            (parent_start, parent_end)
+        };
+        writeln!(
+            out,
+            "@@ -{},{} +{},{} @@",
+            parent_start + 1,
+            parent_end - parent_start,
+            range.start + 1,
+            range.len()
+        )?;
+
+        let mut line = range.start;
+        while next < hunks.len() && hunks[next].after.start < range.end {
+            let hunk = &hunks[next];
+            for line in line..hunk.after.start {
+                write_line(b' ', lines[line as usize], out)?;
+            }
+            for parent_line in hunk.before.clone() {
+                write_line(b'-', parent_lines[parent_line as usize], out)?;
+            }
+            line = line.max(hunk.after.start);
+            while line < hunk.after.end && line < range.end {
+                write_line(b'+', lines[line as usize], out)?;
+                line += 1;
+            }
+            next += 1;
+        }
+        for line in line..range.end {
+            write_line(b' ', lines[line as usize], out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write `line` with `prefix`, and note if it doesn't end with a newline like `git` does.
+fn write_line(prefix: u8, line: &[u8], out: &mut impl io::Write) -> io::Result<()> {
+    out.write_all(&[prefix])?;
+    out.write_all(line)?;
+    if !line.ends_with(b"\n") {
+        out.write_all(b"\n\\ No newline at end of file\n")?;
+    }
+    Ok(())
+}
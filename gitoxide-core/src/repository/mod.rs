@@ -19,11 +19,20 @@ pub enum PathsOrPatterns {
 
 #[cfg(feature = "archive")]
 pub mod archive;
+pub mod blame;
+pub mod branch;
+pub use branch::function::branch;
+pub mod checkout;
+pub use checkout::function::checkout;
 pub mod commit;
 pub mod config;
-mod credential;
+pub mod credential;
 pub use credential::function as credential;
 pub mod attributes;
+#[cfg(feature = "blocking-client")]
+pub mod backfill;
+#[cfg(feature = "blocking-client")]
+pub use backfill::function::backfill;
 #[cfg(feature = "clean")]
 pub mod clean;
 #[cfg(feature = "clean")]
@@ -37,16 +46,42 @@ pub mod fetch;
 pub use clone::function::clone;
 #[cfg(feature = "blocking-client")]
 pub use fetch::function::fetch;
+#[cfg(feature = "blocking-client")]
+pub mod push;
+#[cfg(feature = "blocking-client")]
+pub use push::function::push;
+#[cfg(feature = "blocking-client")]
+pub mod receive_pack;
+#[cfg(feature = "blocking-client")]
+pub use receive_pack::function::receive_pack;
+#[cfg(feature = "blocking-client")]
+pub mod serve;
+#[cfg(feature = "blocking-client")]
+pub use serve::function::{daemon, serve};
+#[cfg(feature = "blocking-client")]
+pub mod upload_pack;
+#[cfg(feature = "blocking-client")]
+pub use upload_pack::function::upload_pack;
 
 pub mod commitgraph;
-mod fsck;
+pub mod fsck;
 pub use fsck::function as fsck;
+#[cfg(feature = "fsmonitor")]
+pub mod fsmonitor;
 pub mod index;
+pub mod log;
 pub mod mailmap;
 pub mod odb;
 pub mod remote;
 pub mod revision;
+pub mod shortlog;
+pub mod stash;
+pub use stash::function::stash;
 pub mod status;
 pub mod submodule;
+pub mod tag;
+pub use tag::function::tag;
 pub mod tree;
+pub mod var;
+pub use var::function::var;
 pub mod verify;
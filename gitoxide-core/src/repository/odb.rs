@@ -1,7 +1,5 @@
 use std::io;
 
-use anyhow::bail;
-
 use crate::OutputFormat;
 
 #[cfg_attr(not(feature = "serde"), allow(unused_variables))]
@@ -202,13 +200,23 @@ pub fn statistics(
 }
 
 pub fn entries(repo: gix::Repository, format: OutputFormat, mut out: impl io::Write) -> anyhow::Result<()> {
-    if format != OutputFormat::Human {
-        bail!("Only human output format is supported at the moment");
-    }
-
-    for object in repo.objects.iter()? {
-        let object = object?;
-        writeln!(out, "{object}")?;
+    match format {
+        OutputFormat::Human => {
+            for object in repo.objects.iter()? {
+                let object = object?;
+                writeln!(out, "{object}")?;
+            }
+        }
+        #[cfg(feature = "serde")]
+        OutputFormat::Json => {
+            // Stream the array to avoid holding all object ids in memory.
+            write!(out, "[")?;
+            for (idx, object) in repo.objects.iter()?.enumerate() {
+                let object = object?;
+                write!(out, "{}\n  \"{object}\"", if idx == 0 { "" } else { "," })?;
+            }
+            writeln!(out, "\n]")?;
+        }
     }
 
     Ok(())
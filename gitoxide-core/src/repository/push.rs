@@ -0,0 +1,887 @@
+use gix::bstr::BString;
+
+pub struct Options {
+    /// The name or url of the remote to push to, or the configured push remote if unset.
+    pub remote: Option<String>,
+    /// If non-empty, override all ref-specs otherwise configured in the remote or implied by `push.default`.
+    pub ref_specs: Vec<BString>,
+    /// Allow updates that aren't fast-forwards for all ref-specs.
+    pub force: bool,
+    /// `<refname>[:<expect>]` values of `--force-with-lease`, with an empty value applying to all pushed refs.
+    pub force_with_lease: Vec<String>,
+    /// Ask the remote to update all refs or none of them.
+    pub atomic: bool,
+    /// Strings to transmit to the remote for use by its hooks.
+    pub push_options: Vec<String>,
+    pub dry_run: bool,
+    /// Also list refs that are up to date.
+    pub verbose: bool,
+}
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
+
+pub(crate) mod function {
+    use std::{
+        borrow::Cow,
+        io::{Read, Write},
+        sync::atomic::Ordering,
+    };
+
+    use anyhow::{anyhow, bail, Context};
+    use gix::{
+        bstr::{BStr, BString, ByteSlice},
+        interrupt::IS_INTERRUPTED,
+        objs::{CommitRefIter, TagRefIter, TreeRefIter},
+        odb::pack::data::output,
+        protocol::transport::{
+            client::{MessageKind, TransportWithoutIO, WriteMode},
+            packetline::{read::ProgressAction, PacketLineRef, StreamingPeekableIter},
+            Protocol, Service,
+        },
+        refs::{
+            transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+            Category, FullName, Target,
+        },
+        refspec::{instruction, parse::Operation, Instruction, RefSpec},
+        remote::Direction,
+        Count, ObjectId, Progress,
+    };
+
+    use super::Options;
+
+    pub fn push<P>(
+        repo: gix::Repository,
+        mut progress: P,
+        mut err: impl std::io::Write,
+        Options {
+            remote,
+            ref_specs,
+            force,
+            force_with_lease,
+            atomic,
+            push_options,
+            dry_run,
+            verbose,
+        }: Options,
+    ) -> anyhow::Result<()>
+    where
+        P: gix::NestedProgress,
+        P::SubProgress: 'static,
+    {
+        let mut remote = match remote.as_deref() {
+            Some(name_or_url) => repo.find_fetch_remote(Some(name_or_url.into()))?,
+            None => repo
+                .find_default_remote(Direction::Push)
+                .context("No configured push destination - specify the remote to push to")??,
+        };
+        if !ref_specs.is_empty() {
+            remote.replace_refspecs(ref_specs.iter(), Direction::Push)?;
+        }
+        let specs = match remote.refspecs(Direction::Push) {
+            [] => vec![default_refspec(&repo)?],
+            specs => specs.to_vec(),
+        };
+        let leases = force_with_lease
+            .iter()
+            .map(|lease| Lease::parse(&repo, lease))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let url = remote
+            .url(Direction::Push)
+            .context("The remote has no url to push to")?
+            .to_owned();
+
+        let mut connection = remote.connect(Direction::Push)?;
+        let mut authenticate = connection.configured_credentials(url.clone())?;
+        if let Some(config) = repo.transport_options(
+            url.to_bstring().as_bstr(),
+            remote.name().map(gix::remote::Name::as_bstr),
+        )? {
+            connection
+                .transport_mut()
+                .configure(&*config)
+                .map_err(|err| anyhow!(err))?;
+        }
+        let transport = connection.transport_mut();
+        let handshake = gix::protocol::handshake(
+            &mut *transport,
+            Service::ReceivePack,
+            &mut authenticate,
+            Vec::new(),
+            &mut progress,
+        )?;
+        let advertised = handshake.refs.context("The remote didn't advertise its references")?;
+        let mut remote_refs = Vec::new();
+        let mut haves = Vec::new();
+        for reference in &advertised {
+            let (name, target, peeled) = reference.unpack();
+            haves.extend(target.into_iter().chain(peeled).map(ToOwned::to_owned));
+            if let (Ok(name), Some(target)) = (FullName::try_from(name.to_owned()), target) {
+                remote_refs.push((name, target.to_owned()));
+            }
+        }
+        haves.retain(|id| repo.has_object(id));
+
+        let (mut commands, mut failed) = resolve(&repo, &specs, &remote_refs, force, &mut err)?;
+        for cmd in &mut commands {
+            let lease = leases
+                .iter()
+                .rev()
+                .find(|lease| {
+                    lease
+                        .name
+                        .as_ref()
+                        .is_some_and(|name| matches_name(cmd.dst.as_ref(), name.as_ref()))
+                })
+                .or_else(|| leases.iter().rev().find(|lease| lease.name.is_none()));
+            let expected = match lease {
+                Some(Lease {
+                    expect: Some(expect), ..
+                }) => Some(*expect),
+                Some(Lease { expect: None, .. }) => Some(
+                    match tracking_ref(&repo, &remote, cmd.dst.as_ref())
+                        .map(|name| repo.try_find_reference(name.as_ref()))
+                        .transpose()?
+                        .flatten()
+                    {
+                        Some(mut tracking) => Some(tracking.peel_to_id_in_place()?.detach()),
+                        None => None,
+                    },
+                ),
+                None => None,
+            };
+            cmd.status = classify(&repo, cmd, expected)?;
+        }
+        if atomic && commands.iter().any(|cmd| matches!(cmd.status, Status::Rejected(_))) {
+            for cmd in &mut commands {
+                if matches!(cmd.status, Status::Pending) {
+                    cmd.status = Status::Rejected("atomic push failed");
+                }
+            }
+        }
+
+        let caps = &handshake.capabilities;
+        if atomic && !caps.contains("atomic") {
+            bail!("the receiving end does not support --atomic push");
+        }
+        if !push_options.is_empty() && !caps.contains("push-options") {
+            bail!("the receiving end does not support push options");
+        }
+        let to_send: Vec<_> = commands
+            .iter()
+            .filter(|cmd| matches!(cmd.status, Status::Pending))
+            .collect();
+        if dry_run || to_send.is_empty() {
+            gix::protocol::indicate_end_of_interaction(&mut *transport, false)?;
+        } else {
+            let use_sideband = caps.contains("side-band-64k");
+            let mut capabilities = vec!["report-status"];
+            if use_sideband {
+                capabilities.push("side-band-64k");
+            }
+            if atomic {
+                capabilities.push("atomic");
+            }
+            if !push_options.is_empty() {
+                capabilities.push("push-options");
+            }
+            if caps.contains("ofs-delta") {
+                capabilities.push("ofs-delta");
+            }
+
+            let null = ObjectId::null(repo.object_hash());
+            let mut request = transport.request(WriteMode::Binary, MessageKind::Flush, false)?;
+            for (idx, cmd) in to_send.iter().enumerate() {
+                let mut line = BString::from(format!(
+                    "{} {} {}",
+                    cmd.old.unwrap_or(null),
+                    cmd.new.unwrap_or(null),
+                    cmd.dst.as_bstr()
+                ));
+                if idx == 0 {
+                    line.push(0);
+                    line.extend_from_slice(capabilities.join(" ").as_bytes());
+                }
+                request.write_all(&line)?;
+            }
+            request.write_message(MessageKind::Flush)?;
+            if !push_options.is_empty() {
+                for option in &push_options {
+                    request.write_all(option.as_bytes())?;
+                }
+                request.write_message(MessageKind::Flush)?;
+            }
+
+            let (mut writer, mut reader) = request.into_parts();
+            if to_send.iter().any(|cmd| cmd.new.is_some()) {
+                let tips: Vec<_> = to_send.iter().filter_map(|cmd| cmd.new).collect();
+                write_pack(&repo, &tips, &haves, &mut progress, &mut writer)?;
+            }
+            writer.flush()?;
+            drop(writer);
+
+            reader.reset(Protocol::V1);
+            if use_sideband {
+                let mut remote_progress = progress.add_child("remote");
+                reader.set_progress_handler(Some(Box::new(move |is_err: bool, data: &[u8]| {
+                    gix::protocol::RemoteProgress::translate_to_progress(is_err, data, &mut remote_progress);
+                    if IS_INTERRUPTED.load(Ordering::Relaxed) {
+                        ProgressAction::Interrupt
+                    } else {
+                        ProgressAction::Continue
+                    }
+                })));
+            }
+            let mut report = Vec::new();
+            if use_sideband {
+                let mut buf = Vec::new();
+                reader.read_to_end(&mut buf)?;
+                let mut lines = StreamingPeekableIter::new(&buf[..], &[PacketLineRef::Flush], false);
+                while let Some(line) = lines.read_line() {
+                    if let Some(data) = line??.as_slice() {
+                        report.push(data.trim_end().as_bstr().to_owned());
+                    }
+                }
+            } else {
+                while let Some(line) = reader.readline() {
+                    if let Some(data) = line??.as_slice() {
+                        report.push(data.trim_end().as_bstr().to_owned());
+                    }
+                }
+            }
+            drop(reader);
+            apply_report(&report, &mut commands, &mut err)?;
+        }
+
+        let changed = commands.iter().any(|cmd| !matches!(cmd.status, Status::UpToDate));
+        if changed || verbose {
+            writeln!(err, "To {}", url.to_bstring())?;
+        }
+        for cmd in commands
+            .iter()
+            .filter(|cmd| verbose || !matches!(cmd.status, Status::UpToDate))
+        {
+            print_command(cmd, &mut err)?;
+        }
+        if !changed && !failed {
+            writeln!(err, "Everything up-to-date")?;
+        }
+
+        failed |= commands
+            .iter()
+            .any(|cmd| matches!(cmd.status, Status::Rejected(_) | Status::RemoteRejected(_)));
+        if !dry_run {
+            update_tracking_refs(&repo, &remote, &commands)?;
+        }
+        if failed {
+            bail!("failed to push some refs to '{}'", url.to_bstring());
+        }
+        Ok(())
+    }
+
+    /// A single reference update to request from the remote.
+    struct Command {
+        /// The local reference or revision that provides the new value, or `None` for deletions.
+        src: Option<BString>,
+        /// The full name of the reference to update on the remote.
+        dst: FullName,
+        /// The value of `dst` on the remote, or `None` if it doesn't exist.
+        old: Option<ObjectId>,
+        /// The value to set `dst` to, or `None` to delete it.
+        new: Option<ObjectId>,
+        /// If `true`, the update may discard commits on the remote.
+        force: bool,
+        /// If `true`, the update discards commits on the remote.
+        is_forced: bool,
+        status: Status,
+    }
+
+    enum Status {
+        /// The remote already has the desired value.
+        UpToDate,
+        /// The update is acceptable and was sent to the remote, or would have been in a dry-run.
+        Pending,
+        /// We decided not to send the update, for the given reason.
+        Rejected(&'static str),
+        /// The remote refused the update, for the given reason.
+        RemoteRejected(String),
+    }
+
+    /// A `--force-with-lease` value.
+    struct Lease {
+        /// The name of the reference the lease applies to, or `None` for all of them.
+        name: Option<BString>,
+        /// The value the remote reference must have, or `None` to use the value of its remote tracking branch.
+        /// An inner `None` means the reference must not exist.
+        expect: Option<Option<ObjectId>>,
+    }
+
+    impl Lease {
+        fn parse(repo: &gix::Repository, value: &str) -> anyhow::Result<Self> {
+            if value.is_empty() {
+                return Ok(Lease {
+                    name: None,
+                    expect: None,
+                });
+            }
+            Ok(match value.split_once(':') {
+                Some((name, expect)) => Lease {
+                    name: Some(name.into()),
+                    expect: Some(if expect.is_empty() {
+                        None
+                    } else {
+                        Some(
+                            repo.rev_parse_single(expect)
+                                .with_context(|| format!("cannot parse expected object name '{expect}'"))?
+                                .detach(),
+                        )
+                    }),
+                },
+                None => Lease {
+                    name: Some(value.into()),
+                    expect: None,
+                },
+            })
+        }
+    }
+
+    /// Produce the refspec to use if neither the command-line nor the remote configuration provide one, based on `push.default`.
+    fn default_refspec(repo: &gix::Repository) -> anyhow::Result<RefSpec> {
+        use gix::config::tree::Push;
+        let push_default = repo
+            .config_snapshot()
+            .string("push.default")
+            .map(|value| Push::DEFAULT.try_into_default(value))
+            .transpose()?
+            .unwrap_or_default();
+        let spec: BString = match push_default {
+            gix::push::Default::Nothing => {
+                bail!("You didn't specify any refspecs to push, and push.default is \"nothing\".")
+            }
+            gix::push::Default::Matching => ":".into(),
+            _ => {
+                let head = repo
+                    .head_name()?
+                    .context("You are not currently on a branch - specify what to push")?;
+                let Some(dst) = repo
+                    .branch_remote_ref_name(head.as_ref(), Direction::Push)
+                    .transpose()?
+                else {
+                    bail!(
+                        "The current branch {} has no upstream branch - specify the remote branch to push to",
+                        head.shorten()
+                    );
+                };
+                format!("{}:{}", head.as_bstr(), dst.as_bstr()).into()
+            }
+        };
+        Ok(gix::refspec::parse(spec.as_ref(), Operation::Push)?.to_owned())
+    }
+
+    /// Turn `specs` into commands, with deletions of refs that don't exist on the remote being reported to `err`
+    /// and signalled as failure.
+    fn resolve(
+        repo: &gix::Repository,
+        specs: &[RefSpec],
+        remote_refs: &[(FullName, ObjectId)],
+        force: bool,
+        mut err: impl std::io::Write,
+    ) -> anyhow::Result<(Vec<Command>, bool)> {
+        let mut commands = Vec::<Command>::new();
+        let mut failed = false;
+        let remote_value = |name: &FullName| remote_refs.iter().find(|(n, _)| n == name).map(|(_, id)| *id);
+        let mut push = |src: Option<BString>, dst: FullName, new: Option<ObjectId>, force: bool| {
+            if commands.iter().any(|cmd| cmd.dst == dst) {
+                return;
+            }
+            commands.push(Command {
+                src,
+                old: remote_value(&dst),
+                dst,
+                new,
+                force,
+                is_forced: false,
+                status: Status::Pending,
+            });
+        };
+        for spec in specs {
+            match spec.to_ref().instruction() {
+                Instruction::Push(instruction::Push::AllMatchingBranches { allow_non_fast_forward }) => {
+                    for reference in repo.references()?.local_branches()? {
+                        let mut reference = reference.map_err(|err| anyhow!(err))?;
+                        let name = reference.name().to_owned();
+                        if remote_value(&name).is_some() {
+                            let id = reference.peel_to_id_in_place()?.detach();
+                            push(
+                                Some(name.as_bstr().to_owned()),
+                                name,
+                                Some(id),
+                                force || allow_non_fast_forward,
+                            );
+                        }
+                    }
+                }
+                Instruction::Push(instruction::Push::Delete { ref_or_pattern }) => {
+                    if ref_or_pattern.contains(&b'*') {
+                        for (name, _) in remote_refs {
+                            if expand_pattern(ref_or_pattern, ref_or_pattern, name.as_bstr()).is_some() {
+                                push(None, name.clone(), None, true);
+                            }
+                        }
+                        continue;
+                    }
+                    let Some(dst) = expand_dst(ref_or_pattern, None, remote_refs)
+                        .ok()
+                        .filter(|dst| remote_value(dst).is_some())
+                    else {
+                        writeln!(
+                            err,
+                            "error: unable to delete '{ref_or_pattern}': remote ref does not exist"
+                        )?;
+                        failed = true;
+                        continue;
+                    };
+                    push(None, dst, None, true);
+                }
+                Instruction::Push(instruction::Push::Matching {
+                    src,
+                    dst,
+                    allow_non_fast_forward,
+                }) => {
+                    let force = force || allow_non_fast_forward;
+                    if src.contains(&b'*') {
+                        for reference in repo.references()?.all()? {
+                            let mut reference = reference.map_err(|err| anyhow!(err))?;
+                            let Some(dst) = expand_pattern(src, dst, reference.name().as_bstr()) else {
+                                continue;
+                            };
+                            let name = reference.name().as_bstr().to_owned();
+                            let id = reference.peel_to_id_in_place()?.detach();
+                            push(Some(name), dst.try_into()?, Some(id), force);
+                        }
+                        continue;
+                    }
+                    let (src_name, id) = resolve_src(repo, src)?;
+                    let dst = match (&src_name, src == dst) {
+                        (Some(name), true) => name.clone(),
+                        _ => expand_dst(dst, src_name.as_ref(), remote_refs)?,
+                    };
+                    push(
+                        Some(src_name.map_or_else(|| src.to_owned(), |name| name.as_bstr().to_owned())),
+                        dst,
+                        Some(id),
+                        force,
+                    );
+                }
+                Instruction::Fetch(_) => unreachable!("BUG: only push specs are used"),
+            }
+        }
+        Ok((commands, failed))
+    }
+
+    /// Resolve `src` to the reference it names, following symbolic references like `HEAD`, and the object it points to,
+    /// or to an object without name if it's a revision.
+    fn resolve_src(repo: &gix::Repository, src: &BStr) -> anyhow::Result<(Option<FullName>, ObjectId)> {
+        let reference = match src.to_str().ok().map(|src| repo.try_find_reference(src)) {
+            Some(res) => res?,
+            None => None,
+        };
+        if let Some(mut reference) = reference {
+            while let Some(next) = reference.follow() {
+                reference = next?;
+            }
+            let id = reference
+                .try_id()
+                .with_context(|| format!("src refspec {src} does not point to an object"))?
+                .detach();
+            return Ok((Some(reference.name().to_owned()), id));
+        }
+        let id = repo
+            .rev_parse_single(src)
+            .with_context(|| format!("src refspec {src} does not match any"))?;
+        Ok((None, id.detach()))
+    }
+
+    /// Turn the possibly partial `dst` into a full reference name, based on the references on the remote or
+    /// the category of the local reference named `src`.
+    fn expand_dst(
+        dst: &BStr,
+        src: Option<&FullName>,
+        remote_refs: &[(FullName, ObjectId)],
+    ) -> anyhow::Result<FullName> {
+        if dst.starts_with(b"refs/") {
+            return Ok(dst.try_into()?);
+        }
+        let mut candidates = remote_refs
+            .iter()
+            .filter(|(name, _)| matches_name(name.as_ref(), dst))
+            .map(|(name, _)| name);
+        match (candidates.next(), candidates.next()) {
+            (Some(name), None) => return Ok(name.clone()),
+            (Some(_), Some(_)) => bail!("dst refspec {dst} matches more than one"),
+            (None, _) => {}
+        }
+        let prefix = match src.and_then(FullName::category) {
+            Some(Category::LocalBranch) => "refs/heads/",
+            Some(Category::Tag) => "refs/tags/",
+            _ => bail!(
+                "The destination you provided is not a full refname (i.e., starting with \"refs/\") and couldn't be inferred: {dst}"
+            ),
+        };
+        Ok(format!("{prefix}{dst}").try_into()?)
+    }
+
+    /// Return `true` if the full reference `name` is what the possibly partial `partial` refers to.
+    fn matches_name(name: &gix::refs::FullNameRef, partial: &BStr) -> bool {
+        name.as_bstr() == partial
+            || ["refs/", "refs/tags/", "refs/heads/", "refs/remotes/"]
+                .iter()
+                .any(|prefix| name.as_bstr().strip_prefix(prefix.as_bytes()) == Some(partial.as_bytes()))
+    }
+
+    /// If `name` matches `src_pattern` with its single `*`, return `dst_pattern` with the matched portion substituted.
+    fn expand_pattern(src_pattern: &BStr, dst_pattern: &BStr, name: &BStr) -> Option<BString> {
+        let (prefix, suffix) = src_pattern.split_once_str("*")?;
+        let middle = name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+        Some(dst_pattern.replace("*", middle).into())
+    }
+
+    /// Decide if `cmd` may be sent, with `expected` being the value `cmd.old` must have according to a lease.
+    fn classify(
+        repo: &gix::Repository,
+        cmd: &mut Command,
+        expected: Option<Option<ObjectId>>,
+    ) -> anyhow::Result<Status> {
+        if cmd.old == cmd.new {
+            return Ok(Status::UpToDate);
+        }
+        let (Some(old), Some(new)) = (cmd.old, cmd.new) else {
+            return Ok(match expected {
+                Some(expected) if expected != cmd.old => Status::Rejected("stale info"),
+                _ => Status::Pending,
+            });
+        };
+        if let Some(expected) = expected {
+            if expected != Some(old) {
+                return Ok(Status::Rejected("stale info"));
+            }
+            cmd.is_forced = !is_fast_forward(repo, old, new)?;
+            return Ok(Status::Pending);
+        }
+        if cmd.dst.category() == Some(Category::Tag) && !cmd.force {
+            return Ok(Status::Rejected("already exists"));
+        }
+        if !repo.has_object(old) {
+            return Ok(if cmd.force {
+                cmd.is_forced = true;
+                Status::Pending
+            } else {
+                Status::Rejected("fetch first")
+            });
+        }
+        cmd.is_forced = !is_fast_forward(repo, old, new)?;
+        Ok(if cmd.is_forced && !cmd.force {
+            Status::Rejected("non-fast-forward")
+        } else {
+            Status::Pending
+        })
+    }
+
+    fn is_fast_forward(repo: &gix::Repository, old: ObjectId, new: ObjectId) -> anyhow::Result<bool> {
+        let is_commit = |id: ObjectId| -> anyhow::Result<bool> {
+            Ok(repo
+                .try_find_object(id)?
+                .is_some_and(|obj| obj.kind == gix::object::Kind::Commit))
+        };
+        if !is_commit(old)? || !is_commit(new)? {
+            return Ok(false);
+        }
+        for info in repo.rev_walk([new]).all()? {
+            if info?.id == old {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Return the name of the remote tracking reference that corresponds to `name` on the remote, if there is one.
+    fn tracking_ref(
+        repo: &gix::Repository,
+        remote: &gix::Remote<'_>,
+        name: &gix::refs::FullNameRef,
+    ) -> Option<FullName> {
+        let null = ObjectId::null(repo.object_hash());
+        gix::refspec::MatchGroup::from_fetch_specs(remote.refspecs(Direction::Fetch).iter().map(RefSpec::to_ref))
+            .match_remotes(std::iter::once(gix::refspec::match_group::Item {
+                full_ref_name: name.as_bstr(),
+                target: &null,
+                object: None,
+            }))
+            .mappings
+            .into_iter()
+            .find_map(|mapping| mapping.rhs)
+            .and_then(|name| FullName::try_from(name.into_owned()).ok())
+    }
+
+    /// Write a pack with all objects reachable from `tips` that aren't reachable from `haves` to `out`.
+    pub(crate) fn write_pack<P>(
+        repo: &gix::Repository,
+        tips: &[ObjectId],
+        haves: &[ObjectId],
+        progress: &mut P,
+        mut out: impl std::io::Write,
+    ) -> anyhow::Result<()>
+    where
+        P: gix::NestedProgress,
+        P::SubProgress: 'static,
+    {
+        let ids = {
+            let mut progress = progress.add_child("finding objects");
+            progress.init(None, gix::progress::count("objects"));
+            objects_to_send(repo, tips, haves, &progress)?
+        };
+        let mut objects = repo.objects.clone().into_arc()?;
+        objects.prevent_pack_unload();
+        let counts = {
+            let mut progress = progress.add_child("counting");
+            progress.init(None, gix::progress::count("objects"));
+            let (counts, _) = output::count::objects_unthreaded(
+                &objects,
+                &mut ids.into_iter().map(Ok),
+                &progress,
+                &IS_INTERRUPTED,
+                output::count::objects::ObjectExpansion::AsIs,
+            )?;
+            counts
+        };
+        let num_objects = counts.len();
+        let mut entries = gix::parallel::InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            objects,
+            Box::new(progress.add_child("creating entries")),
+            output::entry::iter_from_counts::Options {
+                thread_limit: None,
+                mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                allow_thin_pack: false,
+                chunk_size: 1000,
+                version: Default::default(),
+                compression_level: None,
+                stable_order: false,
+                // The remote is usually slower than compression, so don't let large chunks pile up in memory.
+                chunks_in_flight: Some(1),
+            },
+        ));
+        let mut write_progress = progress.add_child("writing");
+        write_progress.init(None, gix::progress::bytes());
+        for written in output::bytes::FromEntriesIter::new(
+            entries.by_ref(),
+            &mut out,
+            num_objects as u32,
+            gix::odb::pack::data::Version::default(),
+            repo.object_hash(),
+        ) {
+            if IS_INTERRUPTED.load(Ordering::Relaxed) {
+                bail!("Cancelled by user");
+            }
+            write_progress.inc_by(written? as usize);
+        }
+        Ok(())
+    }
+
+    /// Collect all objects reachable from `tips`, excluding those reachable from `haves` which the remote already has,
+    /// along with the trees of their commits.
+    fn objects_to_send(
+        repo: &gix::Repository,
+        tips: &[ObjectId],
+        haves: &[ObjectId],
+        progress: &dyn gix::progress::Count,
+    ) -> anyhow::Result<Vec<ObjectId>> {
+        let mut seen = gix::hashtable::HashSet::default();
+        let mut next = haves.to_vec();
+        let mut trees = Vec::new();
+        while let Some(id) = next.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            let Some(object) = repo.try_find_object(id)? else {
+                continue;
+            };
+            match object.kind {
+                gix::object::Kind::Commit => {
+                    let mut commit = CommitRefIter::from_bytes(&object.data);
+                    if haves.contains(&id) {
+                        trees.push(commit.tree_id()?);
+                    }
+                    next.extend(commit.parent_ids());
+                }
+                gix::object::Kind::Tag => next.push(TagRefIter::from_bytes(&object.data).target_id()?),
+                gix::object::Kind::Tree | gix::object::Kind::Blob => {}
+            }
+        }
+        while let Some(id) = trees.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            let Some(tree) = repo.try_find_object(id)? else {
+                continue;
+            };
+            for entry in TreeRefIter::from_bytes(&tree.data) {
+                let entry = entry?;
+                if entry.mode.is_tree() {
+                    trees.push(entry.oid.to_owned());
+                } else if !entry.mode.is_commit() {
+                    seen.insert(entry.oid.to_owned());
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        next.extend_from_slice(tips);
+        while let Some(id) = next.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            if IS_INTERRUPTED.load(Ordering::Relaxed) {
+                bail!("Cancelled by user");
+            }
+            let Some(object) = repo.try_find_object(id)? else {
+                continue;
+            };
+            out.push(id);
+            progress.inc();
+            match object.kind {
+                gix::object::Kind::Commit => {
+                    let mut commit = CommitRefIter::from_bytes(&object.data);
+                    next.push(commit.tree_id()?);
+                    next.extend(commit.parent_ids());
+                }
+                gix::object::Kind::Tag => next.push(TagRefIter::from_bytes(&object.data).target_id()?),
+                gix::object::Kind::Tree => {
+                    for entry in TreeRefIter::from_bytes(&object.data) {
+                        let entry = entry?;
+                        if entry.mode.is_tree() {
+                            next.push(entry.oid.to_owned());
+                        } else if !entry.mode.is_commit() && seen.insert(entry.oid.to_owned()) {
+                            out.push(entry.oid.to_owned());
+                            progress.inc();
+                        }
+                    }
+                }
+                gix::object::Kind::Blob => {}
+            }
+        }
+        Ok(out)
+    }
+
+    /// Apply the `report` lines of the `report-status` capability to `commands`.
+    fn apply_report(report: &[BString], commands: &mut [Command], mut err: impl std::io::Write) -> anyhow::Result<()> {
+        let mut lines = report.iter();
+        match lines.next().and_then(|line| line.strip_prefix(b"unpack ")) {
+            Some(b"ok") => {}
+            Some(reason) => {
+                writeln!(err, "error: remote unpack failed: {}", reason.as_bstr())?;
+                for cmd in commands.iter_mut().filter(|cmd| matches!(cmd.status, Status::Pending)) {
+                    cmd.status = Status::RemoteRejected("unpacker error".into());
+                }
+                return Ok(());
+            }
+            None => bail!("The remote didn't report the status of the push"),
+        }
+        for line in lines {
+            let (ok, rest) = if let Some(rest) = line.strip_prefix(b"ok ") {
+                (true, rest)
+            } else if let Some(rest) = line.strip_prefix(b"ng ") {
+                (false, rest)
+            } else {
+                continue;
+            };
+            let (name, reason) = rest.split_once_str(" ").unwrap_or((rest, b"failed"));
+            if let Some(cmd) = commands.iter_mut().find(|cmd| cmd.dst.as_bstr() == name) {
+                if !ok {
+                    cmd.status = Status::RemoteRejected(reason.to_str_lossy().into_owned());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Point the remote tracking references of all successfully updated references to their new value.
+    fn update_tracking_refs(
+        repo: &gix::Repository,
+        remote: &gix::Remote<'_>,
+        commands: &[Command],
+    ) -> anyhow::Result<()> {
+        let mut edits = Vec::new();
+        for cmd in commands.iter().filter(|cmd| matches!(cmd.status, Status::Pending)) {
+            let Some(name) = tracking_ref(repo, remote, cmd.dst.as_ref()) else {
+                continue;
+            };
+            let change = match cmd.new {
+                Some(id) => Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: "update by push".into(),
+                    },
+                    expected: PreviousValue::Any,
+                    new: Target::Peeled(id),
+                },
+                None if repo.try_find_reference(name.as_ref())?.is_some() => Change::Delete {
+                    expected: PreviousValue::Any,
+                    log: RefLog::AndReference,
+                },
+                None => continue,
+            };
+            edits.push(RefEdit {
+                change,
+                name,
+                deref: false,
+            });
+        }
+        if !edits.is_empty() {
+            repo.edit_references(edits)?;
+        }
+        Ok(())
+    }
+
+    fn print_command(cmd: &Command, mut err: impl std::io::Write) -> std::io::Result<()> {
+        let short = |id: ObjectId| id.to_hex_with_len(7).to_string();
+        let (flag, summary, reason): (_, Cow<'_, str>, _) = match &cmd.status {
+            Status::UpToDate => ('=', "[up to date]".into(), None),
+            Status::Pending => match (cmd.old, cmd.new) {
+                (_, None) => ('-', "[deleted]".into(), None),
+                (None, Some(_)) => (
+                    '*',
+                    match cmd.dst.category() {
+                        Some(Category::LocalBranch) => "[new branch]",
+                        Some(Category::Tag) => "[new tag]",
+                        _ => "[new reference]",
+                    }
+                    .into(),
+                    None,
+                ),
+                (Some(old), Some(new)) if cmd.is_forced => (
+                    '+',
+                    format!("{}...{}", short(old), short(new)).into(),
+                    Some("forced update"),
+                ),
+                (Some(old), Some(new)) => (' ', format!("{}..{}", short(old), short(new)).into(), None),
+            },
+            Status::Rejected(reason) => ('!', "[rejected]".into(), Some(*reason)),
+            Status::RemoteRejected(reason) => ('!', "[remote rejected]".into(), Some(reason.as_str())),
+        };
+        let dst = cmd.dst.shorten();
+        let refs = match (&cmd.src, cmd.new) {
+            (Some(src), Some(_)) => {
+                let src =
+                    FullName::try_from(src.clone()).map_or_else(|_| src.clone(), |name| name.shorten().to_owned());
+                format!("{src} -> {dst}")
+            }
+            _ => dst.to_string(),
+        };
+        writeln!(
+            err,
+            " {flag} {summary:<17} {refs}{}",
+            reason.map(|reason| format!(" ({reason})")).unwrap_or_default()
+        )
+    }
+}
@@ -0,0 +1,517 @@
+pub struct Options {
+    /// Only advertise the references of the repository and stop, as used by smart HTTP servers.
+    pub advertise_refs: bool,
+    /// Don't advertise references but expect commands right away, as used by smart HTTP servers.
+    pub stateless_rpc: bool,
+}
+
+pub(crate) mod function {
+    use std::{
+        ffi::OsString,
+        io::{BufReader, Read, Write},
+        path::{Path, PathBuf},
+        process::Stdio,
+        sync::atomic::AtomicBool,
+    };
+
+    use anyhow::{bail, Context};
+    use gix::{
+        bstr::{BString, ByteSlice},
+        config::tree::{Core, Key},
+        protocol::transport::packetline::{encode, Channel, PacketLineRef, StreamingPeekableIter},
+        refs::{
+            transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+            FullName, Target,
+        },
+        ObjectId,
+    };
+
+    use super::Options;
+
+    /// Receive references and the objects they need from a client like `git push` via `input`, and talk back to it
+    /// via `out`, just like `git receive-pack` does.
+    ///
+    /// Objects are kept in a quarantine directory until the `pre-receive` hook accepted the push, and the `update` hook
+    /// is run for each reference before it's changed. Finally the `post-receive` hook is informed about all changed references.
+    pub fn receive_pack(
+        repo: gix::Repository,
+        input: impl Read,
+        mut out: impl Write,
+        mut err: impl Write,
+        Options {
+            advertise_refs,
+            stateless_rpc,
+        }: Options,
+    ) -> anyhow::Result<()> {
+        if !stateless_rpc {
+            if std::env::var("GIT_PROTOCOL").is_ok_and(|value| value.split(':').any(|v| v == "version=1")) {
+                encode::text_to_write(b"version 1", &mut out)?;
+            }
+            advertise(&repo, &mut out)?;
+            out.flush()?;
+        }
+        if advertise_refs {
+            return Ok(());
+        }
+
+        let mut lines = StreamingPeekableIter::new(input, &[PacketLineRef::Flush], false);
+        let mut capabilities = Vec::new();
+        let mut commands = Vec::new();
+        while let Some(line) = lines.read_line() {
+            let line = match line {
+                // The client may hang up without sending commands, e.g. if it has nothing to push.
+                Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof && commands.is_empty() => return Ok(()),
+                line => line??,
+            };
+            let Some(data) = line.as_slice() else { continue };
+            let data = data.trim_end();
+            let data = match data.find_byte(0) {
+                Some(pos) => {
+                    capabilities.extend(data[pos + 1..].split_str(" ").map(|cap| cap.as_bstr().to_owned()));
+                    &data[..pos]
+                }
+                None => data,
+            };
+            commands.push(Command::parse(&repo, data.as_bstr())?);
+        }
+        if commands.is_empty() {
+            return Ok(());
+        }
+        let has = |name: &str| capabilities.iter().any(|cap| cap == name);
+        let mut push_options = Vec::new();
+        if has("push-options") {
+            lines.reset();
+            while let Some(line) = lines.read_line() {
+                if let Some(data) = line??.as_slice() {
+                    push_options.push(data.trim_end().as_bstr().to_owned());
+                }
+            }
+        }
+        let use_sideband = has("side-band-64k");
+        let mut messages = Messages {
+            use_sideband,
+            err: &mut err,
+            out: &mut out,
+        };
+
+        let objects_dir = repo.objects.store_ref().path().to_owned();
+        let quarantine = Quarantine::new(&objects_dir)?;
+        let unpack_result = if commands.iter().any(|cmd| !cmd.new.is_null()) {
+            receive_objects(&repo, lines.into_inner(), &quarantine)
+        } else {
+            Ok(())
+        };
+        let mut checks = repo.clone();
+        checks.objects = gix::odb::at(&quarantine.dir)?;
+
+        match &unpack_result {
+            Ok(()) => {
+                for cmd in &mut commands {
+                    cmd.status = check(&checks, cmd)?;
+                }
+            }
+            Err(_) => {
+                for cmd in &mut commands {
+                    cmd.status = Err("unpacker error".into());
+                }
+            }
+        }
+        if has("atomic") && commands.iter().any(|cmd| cmd.status.is_err()) {
+            for cmd in commands.iter_mut().filter(|cmd| cmd.status.is_ok()) {
+                cmd.status = Err("atomic transaction failed".into());
+            }
+        }
+
+        let mut env = vec![("GIT_PUSH_OPTION_COUNT".into(), push_options.len().to_string().into())];
+        for (idx, option) in push_options.iter().enumerate() {
+            env.push((
+                format!("GIT_PUSH_OPTION_{idx}").into(),
+                gix::path::from_bstr(option.as_bstr()).into_owned().into(),
+            ));
+        }
+        if commands.iter().any(|cmd| cmd.status.is_ok()) {
+            let mut quarantine_env = env.clone();
+            quarantine_env.extend([
+                ("GIT_QUARANTINE_PATH".into(), quarantine.dir.clone().into()),
+                ("GIT_OBJECT_DIRECTORY".into(), quarantine.dir.clone().into()),
+                ("GIT_ALTERNATE_OBJECT_DIRECTORIES".into(), objects_dir.clone().into()),
+            ]);
+            let stdin = status_lines(commands.iter().filter(|cmd| cmd.status.is_ok()));
+            if !run_hook(&repo, "pre-receive", &[], &stdin, &quarantine_env, &mut messages)? {
+                for cmd in commands.iter_mut().filter(|cmd| cmd.status.is_ok()) {
+                    cmd.status = Err("pre-receive hook declined".into());
+                }
+            }
+        }
+        if commands.iter().any(|cmd| cmd.status.is_ok()) {
+            quarantine.migrate(&objects_dir)?;
+        }
+        drop(quarantine);
+
+        for cmd in commands.iter_mut().filter(|cmd| cmd.status.is_ok()) {
+            let args = [
+                OsString::from(gix::path::from_bstr(cmd.name.as_bstr()).into_owned()),
+                cmd.old.to_string().into(),
+                cmd.new.to_string().into(),
+            ];
+            if !run_hook(&repo, "update", &args, &[], &env, &mut messages)? {
+                cmd.status = Err("hook declined".into());
+            }
+        }
+        if has("atomic") && commands.iter().any(|cmd| cmd.status.is_err()) {
+            for cmd in commands.iter_mut().filter(|cmd| cmd.status.is_ok()) {
+                cmd.status = Err("atomic transaction failed".into());
+            }
+        }
+        update_refs(&repo, &mut commands, has("atomic"));
+
+        let mut report = Vec::new();
+        match &unpack_result {
+            Ok(()) => encode::text_to_write(b"unpack ok", &mut report)?,
+            Err(err) => encode::text_to_write(format!("unpack {err}").as_bytes(), &mut report)?,
+        };
+        for cmd in &commands {
+            let line = match &cmd.status {
+                Ok(()) => format!("ok {}", cmd.name.as_bstr()),
+                Err(reason) => format!("ng {} {reason}", cmd.name.as_bstr()),
+            };
+            encode::text_to_write(line.as_bytes(), &mut report)?;
+        }
+        encode::flush_to_write(&mut report)?;
+        if has("report-status") || has("report-status-v2") {
+            if use_sideband {
+                for chunk in report.chunks(65515) {
+                    encode::band_to_write(Channel::Data, chunk, &mut *messages.out)?;
+                }
+            } else {
+                messages.out.write_all(&report)?;
+            }
+        }
+
+        let updated: Vec<_> = commands.iter().filter(|cmd| cmd.status.is_ok()).collect();
+        if !updated.is_empty() {
+            run_hook(&repo, "post-receive", &[], &status_lines(updated), &env, &mut messages)?;
+        }
+        if use_sideband {
+            encode::flush_to_write(&mut *messages.out)?;
+        }
+        messages.out.flush()?;
+        Ok(())
+    }
+
+    /// A reference update requested by the client.
+    struct Command {
+        /// The value the client expects the reference to have, or the null id if it shouldn't exist.
+        old: ObjectId,
+        /// The value to set the reference to, or the null id to delete it.
+        new: ObjectId,
+        name: BString,
+        /// `Ok(())` if the command can be or was applied, or the reason for not applying it.
+        status: Result<(), String>,
+    }
+
+    impl Command {
+        fn parse(repo: &gix::Repository, line: &gix::bstr::BStr) -> anyhow::Result<Self> {
+            let mut tokens = line.splitn_str(3, " ");
+            let (Some(old), Some(new), Some(name)) = (tokens.next(), tokens.next(), tokens.next()) else {
+                bail!("protocol error: expected old/new/ref, got '{line}'");
+            };
+            let parse_id = |hex: &[u8]| -> anyhow::Result<ObjectId> {
+                let id = ObjectId::from_hex(hex)
+                    .with_context(|| format!("protocol error: expected old/new/ref, got '{line}'"))?;
+                if id.kind() != repo.object_hash() {
+                    bail!("protocol error: object ids must be of kind {}", repo.object_hash());
+                }
+                Ok(id)
+            };
+            Ok(Command {
+                old: parse_id(old)?,
+                new: parse_id(new)?,
+                name: name.into(),
+                status: Ok(()),
+            })
+        }
+    }
+
+    /// Print all references along with our capabilities to `out`.
+    fn advertise(repo: &gix::Repository, mut out: impl Write) -> anyhow::Result<()> {
+        let capabilities = format!(
+            "report-status report-status-v2 delete-refs side-band-64k quiet atomic ofs-delta push-options object-format={} agent={}",
+            repo.object_hash().to_string().to_ascii_lowercase(),
+            gix::protocol::agent(gix::env::agent())
+        );
+        let mut is_first = true;
+        for reference in repo.references()?.all()? {
+            let reference = reference.map_err(|err| anyhow::anyhow!(err))?;
+            let Target::Peeled(id) = reference.inner.target else {
+                continue;
+            };
+            let mut line = BString::from(format!("{id} {}", reference.inner.name.as_bstr()));
+            if std::mem::take(&mut is_first) {
+                line.push(0);
+                line.extend_from_slice(capabilities.as_bytes());
+            }
+            encode::text_to_write(&line, &mut out)?;
+        }
+        if is_first {
+            let line = format!(
+                "{} capabilities^{{}}\0{capabilities}",
+                ObjectId::null(repo.object_hash())
+            );
+            encode::text_to_write(line.as_bytes(), &mut out)?;
+        }
+        encode::flush_to_write(&mut out)?;
+        Ok(())
+    }
+
+    /// A directory to receive objects in before the push was accepted, so they don't enter the repository otherwise.
+    /// It's deleted when dropped.
+    struct Quarantine {
+        dir: PathBuf,
+    }
+
+    impl Quarantine {
+        fn new(objects_dir: &Path) -> anyhow::Result<Self> {
+            let nanos = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |time| time.subsec_nanos());
+            let dir = objects_dir.join(format!("incoming-{}-{nanos}", std::process::id()));
+            std::fs::create_dir_all(dir.join("pack"))?;
+            std::fs::create_dir_all(dir.join("info"))?;
+            let quarantine = Quarantine { dir };
+            std::fs::write(
+                quarantine.dir.join("info").join("alternates"),
+                gix::path::into_bstr(gix::path::realpath(objects_dir)?).as_ref(),
+            )?;
+            Ok(quarantine)
+        }
+
+        /// Move all received packs into the pack directory of `objects_dir`, making their objects available.
+        fn migrate(&self, objects_dir: &Path) -> anyhow::Result<()> {
+            let pack_dir = objects_dir.join("pack");
+            std::fs::create_dir_all(&pack_dir)?;
+            let mut entries = std::fs::read_dir(self.dir.join("pack"))?.collect::<Result<Vec<_>, _>>()?;
+            // Move the data before its index as the index makes the pack visible.
+            entries.sort_by_key(|entry| entry.path().extension().is_some_and(|ext| ext == "idx"));
+            for entry in entries {
+                std::fs::rename(entry.path(), pack_dir.join(entry.file_name()))?;
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for Quarantine {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.dir).ok();
+        }
+    }
+
+    /// Read a pack from `input` into the `quarantine` directory, looking up bases of thin packs in `repo`.
+    fn receive_objects(repo: &gix::Repository, input: impl Read, quarantine: &Quarantine) -> anyhow::Result<()> {
+        let mut input = BufReader::new(input);
+        let outcome = gix::odb::pack::Bundle::write_to_directory(
+            &mut input,
+            Some(&quarantine.dir.join("pack")),
+            &mut gix::progress::Discard,
+            &AtomicBool::new(false),
+            Some(repo.objects.clone()),
+            gix::odb::pack::bundle::write::Options {
+                object_hash: repo.object_hash(),
+                ..Default::default()
+            },
+        )?;
+        if let Some(keep_path) = outcome.keep_path {
+            std::fs::remove_file(keep_path)?;
+        }
+        Ok(())
+    }
+
+    /// Return `Ok(())` if `cmd` may be applied to `repo`, whose objects include the ones that were received.
+    fn check(repo: &gix::Repository, cmd: &Command) -> anyhow::Result<Result<(), String>> {
+        let name = match FullName::try_from(cmd.name.clone()) {
+            Ok(name) if name.as_bstr().starts_with(b"refs/") => name,
+            _ => return Ok(Err("funny refname".into())),
+        };
+        let current = match repo.try_find_reference(name.as_ref())? {
+            Some(mut reference) => Some(reference.peel_to_id_in_place()?.detach()),
+            None => None,
+        };
+        if current.unwrap_or_else(|| ObjectId::null(repo.object_hash())) != cmd.old {
+            return Ok(Err("failed to lock".into()));
+        }
+        let config = repo.config_snapshot();
+        let is_current_branch =
+            repo.work_dir().is_some() && repo.head_name()?.is_some_and(|head| head.as_bstr() == name.as_bstr());
+        if cmd.new.is_null() {
+            if config.boolean("receive.denyDeletes").unwrap_or(false) {
+                return Ok(Err("deletion prohibited".into()));
+            }
+            if is_current_branch && denies(config.string("receive.denyDeleteCurrent").as_deref()) {
+                return Ok(Err("deletion of the current branch prohibited".into()));
+            }
+            return Ok(Ok(()));
+        }
+        if !repo.has_object(cmd.new) {
+            return Ok(Err("missing necessary objects".into()));
+        }
+        if is_current_branch && denies(config.string("receive.denyCurrentBranch").as_deref()) {
+            return Ok(Err("branch is currently checked out".into()));
+        }
+        if !cmd.old.is_null()
+            && config.boolean("receive.denyNonFastForwards").unwrap_or(false)
+            && !is_fast_forward(repo, cmd.old, cmd.new)?
+        {
+            return Ok(Err("non-fast-forward".into()));
+        }
+        Ok(Ok(()))
+    }
+
+    /// Return `true` if the value of a `receive.deny*` key that defaults to `refuse` refuses an update.
+    fn denies(value: Option<&gix::bstr::BStr>) -> bool {
+        !matches!(
+            value.map(|value| value.to_ascii_lowercase()).as_deref(),
+            Some(b"ignore" | b"warn" | b"false" | b"no" | b"off" | b"0")
+        )
+    }
+
+    fn is_fast_forward(repo: &gix::Repository, old: ObjectId, new: ObjectId) -> anyhow::Result<bool> {
+        let is_commit = |id: ObjectId| -> anyhow::Result<bool> {
+            Ok(repo
+                .try_find_object(id)?
+                .is_some_and(|obj| obj.kind == gix::object::Kind::Commit))
+        };
+        if !is_commit(old)? || !is_commit(new)? {
+            return Ok(false);
+        }
+        for info in repo.rev_walk([new]).all()? {
+            if info?.id == old {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Apply all acceptable `commands` to the references of `repo`, in a single transaction if `atomic` is `true`.
+    fn update_refs(repo: &gix::Repository, commands: &mut [Command], atomic: bool) {
+        let edit = |cmd: &Command| RefEdit {
+            change: if cmd.new.is_null() {
+                Change::Delete {
+                    expected: PreviousValue::MustExistAndMatch(Target::Peeled(cmd.old)),
+                    log: RefLog::AndReference,
+                }
+            } else {
+                Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: "push".into(),
+                    },
+                    expected: if cmd.old.is_null() {
+                        PreviousValue::MustNotExist
+                    } else {
+                        PreviousValue::MustExistAndMatch(Target::Peeled(cmd.old))
+                    },
+                    new: Target::Peeled(cmd.new),
+                }
+            },
+            name: FullName::try_from(cmd.name.clone()).expect("validated before"),
+            deref: false,
+        };
+        if atomic {
+            let edits: Vec<_> = commands.iter().filter(|cmd| cmd.status.is_ok()).map(edit).collect();
+            if !edits.is_empty() && repo.edit_references(edits).is_err() {
+                for cmd in commands.iter_mut().filter(|cmd| cmd.status.is_ok()) {
+                    cmd.status = Err("failed to update ref".into());
+                }
+            }
+        } else {
+            for cmd in commands.iter_mut().filter(|cmd| cmd.status.is_ok()) {
+                if repo.edit_reference(edit(cmd)).is_err() {
+                    cmd.status = Err("failed to update ref".into());
+                }
+            }
+        }
+    }
+
+    /// Produce the `<old> <new> <ref>` lines hooks receive on stdin.
+    fn status_lines<'a>(commands: impl IntoIterator<Item = &'a Command>) -> Vec<u8> {
+        let mut out = Vec::new();
+        for cmd in commands {
+            out.extend_from_slice(format!("{} {} {}\n", cmd.old, cmd.new, cmd.name).as_bytes());
+        }
+        out
+    }
+
+    /// Where to send messages for the user, like the output of hooks.
+    struct Messages<'a, O, E> {
+        /// If `true`, messages are sent to the client through the progress band of `out`.
+        use_sideband: bool,
+        out: &'a mut O,
+        err: &'a mut E,
+    }
+
+    impl<O: Write, E: Write> Messages<'_, O, E> {
+        fn send(&mut self, message: &[u8]) -> std::io::Result<()> {
+            if message.is_empty() {
+                return Ok(());
+            }
+            if self.use_sideband {
+                for chunk in message.chunks(65515) {
+                    encode::band_to_write(Channel::Progress, chunk, &mut *self.out)?;
+                }
+                self.out.flush()
+            } else {
+                self.err.write_all(message)
+            }
+        }
+    }
+
+    /// Run the hook called `name` with `args`, `stdin` and `env` in the `.git` directory if it exists and is executable,
+    /// send all of its output to the user, and return `false` if it failed.
+    fn run_hook<O: Write, E: Write>(
+        repo: &gix::Repository,
+        name: &str,
+        args: &[OsString],
+        stdin: &[u8],
+        env: &[(OsString, OsString)],
+        messages: &mut Messages<'_, O, E>,
+    ) -> anyhow::Result<bool> {
+        let hooks_dir = match repo
+            .config_snapshot()
+            .trusted_path(Core::HOOKS_PATH.logical_name().as_str())
+            .transpose()?
+        {
+            Some(path) => repo.work_dir().unwrap_or(repo.git_dir()).join(path),
+            None => repo.common_dir().join("hooks"),
+        };
+        let hook = hooks_dir.join(name);
+        let is_executable = match hook.metadata() {
+            Ok(metadata) => metadata.is_file() && (cfg!(windows) || gix::fs::is_executable(&metadata)),
+            Err(_) => false,
+        };
+        if !is_executable {
+            return Ok(true);
+        }
+        let mut cmd = std::process::Command::from(
+            gix::command::prepare(&hook)
+                .args(args.iter().cloned())
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped()),
+        );
+        cmd.current_dir(repo.git_dir())
+            .env("GIT_DIR", ".")
+            .envs(env.iter().cloned());
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Could not run hook at '{}'", hook.display()))?;
+        let mut child_stdin = child.stdin.take().expect("configured");
+        // Hooks don't have to read their input, so failing to write it is fine.
+        child_stdin.write_all(stdin).ok();
+        drop(child_stdin);
+        let output = child.wait_with_output()?;
+        messages.send(&output.stdout)?;
+        messages.send(&output.stderr)?;
+        Ok(output.status.success())
+    }
+}
@@ -2,6 +2,7 @@
 mod refs_impl {
     use anyhow::bail;
     use gix::{
+        bstr::BStr,
         protocol::handshake,
         refspec::{match_group::validate::Fix, RefSpec},
         remote::fetch::Source,
@@ -29,6 +30,7 @@ mod refs_impl {
             pub format: OutputFormat,
             pub name_or_url: Option<String>,
             pub handshake_info: bool,
+            pub cache_ttl: Option<std::time::Duration>,
         }
 
         pub(crate) use super::{print, print_ref};
@@ -45,6 +47,7 @@ mod refs_impl {
             format,
             name_or_url,
             handshake_info,
+            cache_ttl,
         }: refs::Options,
     ) -> anyhow::Result<()> {
         use anyhow::Context;
@@ -54,9 +57,6 @@ mod refs_impl {
             show_unmapped_remote_refs,
         } = &kind
         {
-            if format != OutputFormat::Human {
-                bail!("JSON output isn't yet supported for listing ref-mappings.");
-            }
             if !ref_specs.is_empty() {
                 remote.replace_refspecs(ref_specs.iter(), gix::remote::Direction::Fetch)?;
                 remote = remote.with_fetch_tags(gix::remote::fetch::Tags::None);
@@ -79,6 +79,7 @@ mod refs_impl {
                 &mut progress,
                 gix::remote::ref_map::Options {
                     prefix_from_spec_as_filter_on_remote: !matches!(kind, refs::Kind::Remote),
+                    cache_ttl,
                     ..Default::default()
                 },
             )
@@ -89,14 +90,27 @@ mod refs_impl {
             writeln!(out, "\t{:?}", map.handshake)?;
         }
         match kind {
-            refs::Kind::Tracking { .. } => print_refmap(
-                &repo,
-                remote.refspecs(gix::remote::Direction::Fetch),
-                map,
-                show_unmapped,
-                out,
-                err,
-            ),
+            refs::Kind::Tracking { .. } => match format {
+                OutputFormat::Human => print_refmap(
+                    &repo,
+                    remote.refspecs(gix::remote::Direction::Fetch),
+                    map,
+                    show_unmapped,
+                    out,
+                    err,
+                ),
+                #[cfg(feature = "serde")]
+                OutputFormat::Json => {
+                    let refspecs = remote.refspecs(gix::remote::Direction::Fetch);
+                    let mappings = map
+                        .mappings
+                        .iter()
+                        .map(|mapping| JsonMapping::new(&repo, refspecs, &map, mapping))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    serde_json::to_writer_pretty(out, &mappings)?;
+                    Ok(())
+                }
+            },
             refs::Kind::Remote => {
                 match format {
                     OutputFormat::Human => drop(print(out, &map.remote_refs)),
@@ -153,25 +167,11 @@ mod refs_impl {
                 gix::remote::fetch::Source::Ref(r) => print_ref(&mut out, r)?,
             };
             match &mapping.local {
-                Some(local) => {
-                    write!(out, " -> {local} ")?;
-                    match repo.try_find_reference(local)? {
-                        Some(tracking) => {
-                            let msg = match tracking.try_id() {
-                                Some(id) => {
-                                    if id.as_ref() == target_id {
-                                        "[up-to-date]"
-                                    } else {
-                                        "[changed]"
-                                    }
-                                }
-                                None => "[skipped]",
-                            };
-                            writeln!(out, "{msg}")
-                        }
-                        None => writeln!(out, "[new]"),
-                    }
-                }
+                Some(local) => writeln!(
+                    out,
+                    " -> {local} [{}]",
+                    tracking_status(repo, local.as_ref(), target_id)?
+                ),
                 None => writeln!(out, " (fetch only)"),
             }?;
         }
@@ -227,6 +227,78 @@ mod refs_impl {
         Ok(())
     }
 
+    /// Return how the local tracking branch named `local` relates to `target_id` on the remote.
+    fn tracking_status(
+        repo: &gix::Repository,
+        local: &BStr,
+        target_id: &gix::hash::oid,
+    ) -> anyhow::Result<&'static str> {
+        Ok(match repo.try_find_reference(local)? {
+            Some(tracking) => match tracking.try_id() {
+                Some(id) => {
+                    if id.as_ref() == target_id {
+                        "up-to-date"
+                    } else {
+                        "changed"
+                    }
+                }
+                None => "skipped",
+            },
+            None => "new",
+        })
+    }
+
+    /// The JSON representation of a mapping of a remote reference to a local tracking branch.
+    #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+    pub struct JsonMapping {
+        /// The refspec that produced the mapping.
+        pub spec: String,
+        /// Whether the refspec was added implicitly, instead of being configured or passed explicitly.
+        pub implicit: bool,
+        /// The remote reference, or `None` if an object id was requested.
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub remote: Option<JsonRef>,
+        /// The object id the remote side points to.
+        pub object: String,
+        /// The name of the local tracking branch, if the mapping isn't fetch-only.
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub local: Option<String>,
+        /// One of `new`, `up-to-date`, `changed` or `skipped`, if there is a local tracking branch.
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub status: Option<&'static str>,
+    }
+
+    impl JsonMapping {
+        #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+        fn new(
+            repo: &gix::Repository,
+            refspecs: &[RefSpec],
+            map: &gix::remote::fetch::RefMap,
+            mapping: &gix::remote::fetch::Mapping,
+        ) -> anyhow::Result<Self> {
+            let spec = mapping
+                .spec_index
+                .get(refspecs, &map.extra_refspecs)
+                .expect("refspecs here are the ones used for mapping");
+            let (remote, target_id) = match &mapping.remote {
+                Source::ObjectId(id) => (None, id.as_ref()),
+                Source::Ref(r) => (Some(JsonRef::from(r.clone())), print_ref(std::io::sink(), r)?),
+            };
+            Ok(JsonMapping {
+                spec: spec.to_ref().to_bstring().to_string(),
+                implicit: mapping.spec_index.implicit_index().is_some(),
+                remote,
+                object: target_id.to_string(),
+                local: mapping.local.as_ref().map(ToString::to_string),
+                status: mapping
+                    .local
+                    .as_ref()
+                    .map(|local| tracking_status(repo, local.as_ref(), target_id))
+                    .transpose()?,
+            })
+        }
+    }
+
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum JsonRef {
         Peeled {
@@ -327,7 +399,7 @@ mod refs_impl {
     }
 }
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
-pub use refs_impl::{refs, refs_fn as refs, JsonRef};
+pub use refs_impl::{refs, refs_fn as refs, JsonMapping, JsonRef};
 
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub(crate) fn by_name_or_url<'repo>(
@@ -0,0 +1,260 @@
+use std::path::PathBuf;
+
+pub struct Options {
+    /// If set, repository paths requested by clients are relative to this directory, like `git daemon --base-path`.
+    pub base_path: Option<PathBuf>,
+    /// If `true`, serve all repositories, not only those that contain the `git-daemon-export-ok` file.
+    pub export_all: bool,
+    /// If `true`, allow pushing to repositories via `receive-pack` unless `daemon.receivePack` is set to `false`
+    /// in a repository.
+    pub enable_receive_pack: bool,
+    /// If not empty, only serve repositories in these directories.
+    pub directories: Vec<PathBuf>,
+    /// The maximum amount of connections to serve at the same time when running as daemon, or `None` for no limit.
+    ///
+    /// Connections beyond the limit are closed right away.
+    pub max_connections: Option<usize>,
+}
+
+pub(crate) mod function {
+    use std::{
+        io::{Read, Write},
+        net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+        path::{Component, Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        time::Duration,
+    };
+
+    use anyhow::{bail, Context};
+    use gix::{
+        bstr::{BStr, ByteSlice},
+        protocol::transport::packetline::encode,
+    };
+
+    use super::Options;
+    use crate::repository::{receive_pack, upload_pack};
+
+    /// Listen for connections via the `git://` protocol on `address` and serve each of them on its own thread,
+    /// like `git daemon` does, until `should_interrupt` is set.
+    ///
+    /// Errors of individual connections are written to `err`, and at most [`Options::max_connections`] are served at once.
+    pub fn daemon(
+        address: impl ToSocketAddrs,
+        options: Options,
+        err: impl Write + Send + 'static,
+        should_interrupt: &AtomicBool,
+    ) -> anyhow::Result<()> {
+        let listener = TcpListener::bind(address)?;
+        let local_address = listener.local_addr()?;
+        let options = Arc::new(options);
+        let err = Arc::new(Mutex::new(err));
+        let num_connections = Arc::new(AtomicUsize::new(0));
+        let is_done = AtomicBool::new(false);
+        std::thread::scope(|scope| {
+            scope.spawn(|| wake_on_interrupt(local_address, should_interrupt, &is_done));
+            let res = (|| -> anyhow::Result<()> {
+                loop {
+                    let (stream, peer) = listener.accept()?;
+                    if should_interrupt.load(Ordering::Relaxed) {
+                        return Ok(());
+                    }
+                    if let Some(max) = options.max_connections {
+                        if num_connections.load(Ordering::SeqCst) >= max {
+                            if let Ok(mut err) = err.lock() {
+                                writeln!(
+                                    err,
+                                    "{peer}: dropping connection as {max} connections are served already"
+                                )
+                                .ok();
+                            }
+                            continue;
+                        }
+                    }
+                    let connection = Connection::new(Arc::clone(&num_connections));
+                    let options = Arc::clone(&options);
+                    let err = Arc::clone(&err);
+                    std::thread::spawn(move || {
+                        let _connection = connection;
+                        let res = stream
+                            .try_clone()
+                            .map_err(anyhow::Error::from)
+                            .and_then(|input| serve(input, &stream, &options));
+                        if let Err(e) = res {
+                            if let Ok(mut err) = err.lock() {
+                                writeln!(err, "{peer}: {e:#}").ok();
+                            }
+                        }
+                        stream.shutdown(std::net::Shutdown::Both).ok();
+                    });
+                }
+            })();
+            is_done.store(true, Ordering::Relaxed);
+            res
+        })
+    }
+
+    /// Connect to the listener at `address` once `should_interrupt` is set to make it return from `accept()`,
+    /// or stop once `is_done` is set.
+    ///
+    /// This is needed as interrupts merely set a flag, which can't be waited for.
+    fn wake_on_interrupt(address: SocketAddr, should_interrupt: &AtomicBool, is_done: &AtomicBool) {
+        while !is_done.load(Ordering::Relaxed) {
+            if should_interrupt.load(Ordering::Relaxed) {
+                let address = match address {
+                    SocketAddr::V4(addr) if addr.ip().is_unspecified() => {
+                        SocketAddr::new(std::net::Ipv4Addr::LOCALHOST.into(), addr.port())
+                    }
+                    SocketAddr::V6(addr) if addr.ip().is_unspecified() => {
+                        SocketAddr::new(std::net::Ipv6Addr::LOCALHOST.into(), addr.port())
+                    }
+                    addr => addr,
+                };
+                TcpStream::connect(address).ok();
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    /// Keeps track of the amount of connections that are currently served, for as long as it's alive.
+    struct Connection(Arc<AtomicUsize>);
+
+    impl Connection {
+        fn new(num_connections: Arc<AtomicUsize>) -> Self {
+            num_connections.fetch_add(1, Ordering::SeqCst);
+            Connection(num_connections)
+        }
+    }
+
+    impl Drop for Connection {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Serve a single request of a client via the `git://` protocol, reading it from `input` and answering via `out`,
+    /// like `git daemon --inetd` does.
+    ///
+    /// The request names a service, `upload-pack` or `receive-pack`, along with the path of the repository to run it in.
+    /// Only repositories that may be exported to the client are served, and others are denied with an error message
+    /// that doesn't reveal the reason.
+    pub fn serve(mut input: impl Read, mut out: impl Write, options: &Options) -> anyhow::Result<()> {
+        let request = read_request(&mut input)?;
+        let mut tokens = request.split(|b| *b == 0);
+        let command = tokens.next().unwrap_or_default();
+        let Some((service, path)) = command.split_once_str(" ") else {
+            bail!("Invalid request '{}'", command.as_bstr());
+        };
+        let repo = match open_exported(path.as_bstr(), service.as_bstr(), options) {
+            Ok(repo) => repo,
+            Err(err) => {
+                encode::error_to_write(
+                    format!("access denied or repository not exported: {}", path.as_bstr()).as_bytes(),
+                    &mut out,
+                )?;
+                out.flush()?;
+                return Err(err);
+            }
+        };
+        match service {
+            b"git-upload-pack" => upload_pack(
+                repo,
+                input,
+                out,
+                upload_pack::Options {
+                    advertise_refs: false,
+                    stateless_rpc: false,
+                },
+            ),
+            b"git-receive-pack" => receive_pack(
+                repo,
+                input,
+                out,
+                std::io::sink(),
+                receive_pack::Options {
+                    advertise_refs: false,
+                    stateless_rpc: false,
+                },
+            ),
+            _ => unreachable!("service was validated when opening the repository"),
+        }
+    }
+
+    /// Read the packet line with the initial request of a client.
+    fn read_request(mut input: impl Read) -> anyhow::Result<Vec<u8>> {
+        let mut hex_len = [0u8; 4];
+        input.read_exact(&mut hex_len)?;
+        let len = std::str::from_utf8(&hex_len)
+            .ok()
+            .and_then(|hex| usize::from_str_radix(hex, 16).ok())
+            .filter(|len| *len > 4)
+            .context("Invalid packet line in request")?;
+        let mut request = vec![0; len - 4];
+        input.read_exact(&mut request)?;
+        if request.last() == Some(&b'\n') {
+            request.pop();
+        }
+        Ok(request)
+    }
+
+    /// Open the repository at `path` as requested by a client if it may be exported and `service` is enabled for it.
+    fn open_exported(path: &BStr, service: &BStr, options: &Options) -> anyhow::Result<gix::Repository> {
+        let path = gix::path::try_from_bstr(path)?;
+        if !path.is_absolute() && !path.starts_with("/") {
+            bail!("'{}' isn't an absolute path", path.display());
+        }
+        if path.components().any(|c| matches!(c, Component::ParentDir)) {
+            bail!("'{}' must not contain '..'", path.display());
+        }
+        let path = match &options.base_path {
+            Some(base) => base.join(path.strip_prefix("/").unwrap_or(&path)),
+            None => path.into_owned(),
+        };
+        let repo = candidates(&path)
+            .find_map(|candidate| gix::open(candidate).ok())
+            .with_context(|| format!("'{}' isn't a repository", path.display()))?;
+
+        let git_dir = gix::path::realpath(repo.git_dir())?;
+        if !options.directories.is_empty()
+            && !options
+                .directories
+                .iter()
+                .filter_map(|dir| gix::path::realpath(dir).ok())
+                .any(|dir| git_dir.starts_with(dir))
+        {
+            bail!("'{}' isn't in any of the served directories", git_dir.display());
+        }
+        if !options.export_all && !git_dir.join("git-daemon-export-ok").is_file() {
+            bail!("'{}' isn't exported", git_dir.display());
+        }
+        let (key, enabled) = match service.as_bytes() {
+            b"git-upload-pack" => ("daemon.uploadPack", true),
+            b"git-receive-pack" => ("daemon.receivePack", options.enable_receive_pack),
+            _ => bail!("Service '{service}' isn't supported"),
+        };
+        if !repo.config_snapshot().boolean(key).unwrap_or(enabled) {
+            bail!("Service '{service}' isn't enabled for '{}'", git_dir.display());
+        }
+        Ok(repo)
+    }
+
+    /// Return the paths to try when opening the repository at `path`, like `git daemon` does.
+    fn candidates(path: &Path) -> impl Iterator<Item = PathBuf> + '_ {
+        let with_suffix = |suffix: &str| {
+            let mut path = path.as_os_str().to_owned();
+            path.push(suffix);
+            PathBuf::from(path)
+        };
+        [
+            path.join(".git"),
+            path.to_owned(),
+            with_suffix(".git").join(".git"),
+            with_suffix(".git"),
+        ]
+        .into_iter()
+        .filter(|path| path.is_dir())
+    }
+}
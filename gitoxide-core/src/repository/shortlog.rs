@@ -0,0 +1,59 @@
+use std::io;
+
+use anyhow::bail;
+use gix::bstr::BStr;
+
+use crate::OutputFormat;
+
+pub struct Options {
+    pub format: OutputFormat,
+    pub summary: bool,
+    pub numbered: bool,
+    pub email: bool,
+    pub committer: bool,
+}
+
+/// Show the commits selected by `spec`, or `HEAD` if unset, grouped by author or committer, similar to `git shortlog`.
+pub fn shortlog(
+    repo: gix::Repository,
+    spec: Option<&BStr>,
+    mut out: impl io::Write,
+    Options {
+        format,
+        summary,
+        numbered,
+        email,
+        committer,
+    }: Options,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("Only human format is supported right now");
+    }
+    let spec = repo.rev_parse(spec.unwrap_or("HEAD".into()))?.detach();
+    let outcome = repo.shortlog(
+        spec,
+        gix::shortlog::Options {
+            committer,
+            email,
+            summaries: !summary,
+            sort_by_count: numbered,
+        },
+    )?;
+
+    for group in outcome.groups {
+        let name = match &group.email {
+            Some(email) => format!("{} <{email}>", group.name),
+            None => group.name.to_string(),
+        };
+        if summary {
+            writeln!(out, "{:6}\t{name}", group.count)?;
+        } else {
+            writeln!(out, "{name} ({}):", group.count)?;
+            for summary in group.summaries {
+                writeln!(out, "      {summary}")?;
+            }
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,93 @@
+pub enum Action {
+    /// Save all local changes as new stash entry described by `message`, including untracked files if `include_untracked`
+    /// is set, and reset the worktree to `HEAD`.
+    Push {
+        message: Option<String>,
+        include_untracked: bool,
+    },
+    /// List all stash entries, with the most recent one first.
+    List,
+    /// Apply the stash entry at `index` to the worktree, and restore the stashed index as well if `reinstate_index` is set.
+    Apply { index: usize, reinstate_index: bool },
+    /// Like [`Action::Apply`], but drop the entry if it could be applied without conflicts.
+    Pop { index: usize, reinstate_index: bool },
+    /// Remove the stash entry at `index`.
+    Drop { index: usize },
+}
+
+pub(crate) mod function {
+    use anyhow::bail;
+    use gix::{prelude::ObjectIdExt, stash::ApplyOptions};
+
+    use super::Action;
+
+    pub fn stash(
+        repo: gix::Repository,
+        mut out: impl std::io::Write,
+        mut err: impl std::io::Write,
+        action: Action,
+    ) -> anyhow::Result<()> {
+        match action {
+            Action::Push {
+                message,
+                include_untracked,
+            } => {
+                let id = repo.stash_save(gix::stash::SaveOptions {
+                    message: message.map(Into::into),
+                    include_untracked,
+                })?;
+                match id {
+                    Some(id) => {
+                        let entry = repo.stash_list()?.into_iter().next().filter(|entry| entry.id == id);
+                        match entry {
+                            Some(entry) => writeln!(err, "Saved working directory and index state {}", entry.message)?,
+                            None => writeln!(err, "Saved working directory and index state as {id}")?,
+                        }
+                    }
+                    None => writeln!(err, "No local changes to save")?,
+                }
+            }
+            Action::List => {
+                for (index, entry) in repo.stash_list()?.into_iter().enumerate() {
+                    writeln!(out, "stash@{{{index}}}: {}", entry.message)?;
+                }
+            }
+            Action::Apply { index, reinstate_index } | Action::Pop { index, reinstate_index } => {
+                let is_pop = matches!(action, Action::Pop { .. });
+                let options = ApplyOptions { reinstate_index };
+                let outcome = if is_pop {
+                    let id = stash_id(&repo, index)?;
+                    let outcome = repo.stash_pop(index, options)?;
+                    if outcome.conflicts.is_empty() {
+                        writeln!(err, "Dropped stash@{{{index}}} ({})", id.attach(&repo).shorten_or_id())?;
+                    }
+                    outcome
+                } else {
+                    repo.stash_apply(index, options)?
+                };
+                if !outcome.conflicts.is_empty() {
+                    for conflict in &outcome.conflicts {
+                        writeln!(err, "CONFLICT: Merge conflict in {}", conflict.path)?;
+                    }
+                    if is_pop {
+                        writeln!(err, "The stash entry is kept in case you need it again.")?;
+                    }
+                    bail!("Stash entry could not be applied without conflicts");
+                }
+            }
+            Action::Drop { index } => {
+                let id = repo.stash_drop(index)?;
+                writeln!(err, "Dropped stash@{{{index}}} ({})", id.attach(&repo).shorten_or_id())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn stash_id(repo: &gix::Repository, index: usize) -> anyhow::Result<gix::ObjectId> {
+        Ok(repo
+            .stash_list()?
+            .get(index)
+            .ok_or(gix::stash::Error::NoSuchEntry { index })?
+            .id)
+    }
+}
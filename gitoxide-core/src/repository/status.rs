@@ -1,4 +1,4 @@
-use anyhow::{bail, Context};
+use anyhow::Context;
 use gix::{
     bstr::{BStr, BString},
     index::Entry,
@@ -17,40 +17,93 @@ pub enum Submodules {
     Modifications,
 }
 
+pub enum Renames {
+    /// Track renames as configured by `status.renames`, or `diff.renames`, which is the default.
+    FromConfiguration,
+    /// Do not track renames.
+    Disabled,
+    /// Track renames, and possibly copies, with the given settings.
+    Enabled(gix::diff::Rewrites),
+}
+
 pub struct Options {
     pub format: OutputFormat,
+    pub renames: Renames,
     pub submodules: Submodules,
     pub thread_limit: Option<usize>,
     pub statistics: bool,
-    pub allow_write: bool,
+    /// Control if and how the index is written back after refreshing stat information of its entries.
+    pub index_write_back: gix::index::write::WriteBack,
+    /// If `true`, list files excluded from the worktree by sparse checkout.
+    pub sparse: bool,
+}
+
+/// A single change along with its short status code, as `git status --short` would display it.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct Item {
+    status: &'static str,
+    path: String,
+    /// The path of the source of a rename or copy.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    source: Option<String>,
+}
+
+/// The JSON representation of the status.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct Status {
+    /// Changes between `HEAD^{tree}` and the index.
+    staged: Vec<Item>,
+    /// Changes between the index and the worktree.
+    worktree: Vec<Item>,
 }
 
 pub fn show(
     repo: gix::Repository,
     pathspecs: Vec<BString>,
-    out: impl std::io::Write,
+    mut out: impl std::io::Write,
     mut err: impl std::io::Write,
     mut progress: impl gix::NestedProgress,
     Options {
         format,
+        renames,
         // TODO: implement this
         submodules: _,
         thread_limit,
-        allow_write,
+        index_write_back,
         statistics,
+        sparse,
     }: Options,
 ) -> anyhow::Result<()> {
-    if format != OutputFormat::Human {
-        bail!("Only human format is supported right now");
-    }
     let mut index = repo.index_or_empty()?;
     let index = gix::threading::make_mut(&mut index);
-    let pathspec = repo.pathspec(
+    let mut pathspec = repo.pathspec(
         pathspecs,
         true,
         index,
         gix::worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
     )?;
+    let (staged, degradation) = staged(&repo, index, &mut pathspec, renames)?;
+    if let Some(degradation) = degradation {
+        writeln!(
+            err,
+            "warning: inexact rename detection was skipped due to too many files."
+        )?;
+        writeln!(
+            err,
+            "warning: you may want to set your status.renameLimit variable to at least {} and retry the command.",
+            degradation.required_limit
+        )?;
+    }
+    if format == OutputFormat::Human {
+        for item in &staged {
+            match &item.source {
+                Some(source) => writeln!(out, "{: <3} {source} -> {}", item.status, item.path)?,
+                None => writeln!(out, "{: <3} {}", item.status, item.path)?,
+            }
+        }
+    }
+
     let mut progress = progress.add_child("traverse index");
     let start = std::time::Instant::now();
     let options = gix_status::index_as_worktree::Options {
@@ -69,9 +122,14 @@ pub fn show(
             // TODO: this should be nicer by creating attributes directly, but it's a private API
             _ => unreachable!("state must be attributes stack only"),
         },
+        emit_sparse_excluded: sparse,
+        resume_from: None,
+        time_budget: None,
     };
     let mut printer = Printer {
-        out,
+        out: &mut out,
+        format,
+        items: Vec::new(),
         changes: Vec::new(),
     };
     let outcome = gix_status::index_as_worktree(
@@ -92,7 +150,7 @@ pub fn show(
         options,
     )?;
 
-    if outcome.entries_to_update != 0 && allow_write {
+    if outcome.entries_to_update != 0 && index_write_back != gix::index::write::WriteBack::Never {
         {
             let entries = index.entries_mut();
             for (entry_index, change) in printer.changes {
@@ -107,21 +165,114 @@ pub fn show(
                 }
             }
         }
-        index.write(gix::index::write::Options {
-            extensions: Default::default(),
-            skip_hash: false, // TODO: make this based on configuration
-        })?;
+        repo.smudge_racily_clean_entries(index)?;
+        let write_outcome = index.write_back(
+            gix::index::write::Options {
+                extensions: Default::default(),
+                skip_hash: false, // TODO: make this based on configuration
+            },
+            index_write_back,
+        )?;
+        if statistics {
+            writeln!(err, "index write-back: {write_outcome:?}").ok();
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    if format == OutputFormat::Json {
+        let mut worktree = printer.items;
+        worktree.sort_by(|a, b| a.path.cmp(&b.path));
+        serde_json::to_writer_pretty(&mut out, &Status { staged, worktree })?;
+        writeln!(out)?;
     }
 
     if statistics {
         writeln!(err, "{outcome:#?}").ok();
     }
 
-    writeln!(err, "\nhead -> index and untracked files aren't implemented yet")?;
+    let num_sparse_excluded = index
+        .entries()
+        .iter()
+        .filter(|entry| entry.flags.contains(gix::index::entry::Flags::SKIP_WORKTREE))
+        .count();
+    if num_sparse_excluded != 0 && format == OutputFormat::Human {
+        let num_entries = index.entries().len();
+        writeln!(
+            err,
+            "You are in a sparse checkout with {}% of tracked files present.",
+            (num_entries - num_sparse_excluded) * 100 / num_entries
+        )?;
+    }
+
+    writeln!(err, "\nuntracked files aren't implemented yet")?;
     progress.show_throughput(start);
     Ok(())
 }
 
+/// Return the changes between `HEAD^{tree}` and `index` that are included by `pathspec`, i.e. the changes to be committed,
+/// sorted by path, along with information about rename tracking that was skipped due to its limit.
+fn staged(
+    repo: &gix::Repository,
+    index: &gix::index::State,
+    pathspec: &mut gix::Pathspec<'_>,
+    renames: Renames,
+) -> anyhow::Result<(Vec<Item>, Option<gix::diff::rewrites::Degradation>)> {
+    use gix::status::tree_index::{Action, Change, Options};
+
+    let tree_id = match repo.head_tree_id() {
+        Ok(id) => id.detach(),
+        Err(_) if repo.head()?.is_unborn() => gix::ObjectId::empty_tree(repo.object_hash()),
+        Err(err) => return Err(err.into()),
+    };
+    let options = match renames {
+        Renames::FromConfiguration => None,
+        Renames::Disabled => Some(Options { rewrites: None }),
+        Renames::Enabled(rewrites) => Some(Options {
+            rewrites: Some(rewrites),
+        }),
+    };
+    let mut items = Vec::new();
+    let outcome = repo.tree_index_status(
+        &tree_id,
+        index,
+        options,
+        |change| -> Result<_, std::convert::Infallible> {
+            if !pathspec.is_included(change.location(), Some(false)) {
+                return Ok(Action::Continue);
+            }
+            let (status, source) = match change {
+                Change::Addition { .. } => ("A", None),
+                Change::Deletion { .. } => ("D", None),
+                Change::Modification {
+                    previous_entry_mode,
+                    entry_mode,
+                    ..
+                } => {
+                    let status = if previous_entry_mode.is_blob() == entry_mode.is_blob()
+                        && previous_entry_mode.is_link() == entry_mode.is_link()
+                    {
+                        "M"
+                    } else {
+                        "T"
+                    };
+                    (status, None)
+                }
+                Change::Rename { from, .. } => ("R", Some(from.to_string())),
+                Change::Copy { from, .. } => ("C", Some(from.to_string())),
+            };
+            items.push(Item {
+                status,
+                path: change.location().to_string(),
+                source,
+            });
+            Ok(Action::Continue)
+        },
+    )?;
+    // Rewrites are emitted last, but we want to show everything sorted by path like `git` does.
+    items.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok((items, outcome.rewrites.and_then(|rewrites| rewrites.degradation())))
+}
+
 #[derive(Clone)]
 struct Submodule;
 
@@ -136,6 +287,10 @@ impl gix_status::index_as_worktree::traits::SubmoduleStatus for Submodule {
 
 struct Printer<W> {
     out: W,
+    format: OutputFormat,
+    /// Changes to output once all of them are known, if they aren't printed right away.
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    items: Vec<Item>,
     changes: Vec<(usize, ApplyChange)>,
 }
 
@@ -165,7 +320,6 @@ where
 
 impl<W: std::io::Write> Printer<W> {
     fn visit_inner(&mut self, entry_index: usize, rela_path: &BStr, status: EntryStatus<()>) -> std::io::Result<()> {
-        let char_storage;
         let status = match status {
             EntryStatus::Conflict(conflict) => as_str(conflict),
             EntryStatus::Change(change) => {
@@ -178,17 +332,28 @@ impl<W: std::io::Write> Printer<W> {
                 ) {
                     self.changes.push((entry_index, ApplyChange::SetSizeToZero))
                 }
-                char_storage = change_to_char(&change);
-                std::str::from_utf8(std::slice::from_ref(&char_storage)).expect("valid ASCII")
+                change_to_str(&change)
             }
             EntryStatus::NeedsUpdate(stat) => {
                 self.changes.push((entry_index, ApplyChange::NewStat(stat)));
                 return Ok(());
             }
             EntryStatus::IntentToAdd => "A",
+            EntryStatus::SparseExcluded => "S",
         };
 
-        writeln!(&mut self.out, "{status: >3} {rela_path}")
+        match self.format {
+            OutputFormat::Human => writeln!(&mut self.out, "{status: >3} {rela_path}"),
+            #[cfg(feature = "serde")]
+            OutputFormat::Json => {
+                self.items.push(Item {
+                    status,
+                    path: rela_path.to_string(),
+                    source: None,
+                });
+                Ok(())
+            }
+        }
     }
 }
 
@@ -204,19 +369,19 @@ fn as_str(c: Conflict) -> &'static str {
     }
 }
 
-fn change_to_char(change: &Change<()>) -> u8 {
+fn change_to_str(change: &Change<()>) -> &'static str {
     // Known status letters: https://github.com/git/git/blob/6807fcfedab84bc8cd0fbf721bc13c4e68cda9ae/diff.h#L613
     match change {
-        Change::Removed => b'D',
-        Change::Type => b'T',
-        Change::SubmoduleModification(_) => b'M',
+        Change::Removed => "D",
+        Change::Type => "T",
+        Change::SubmoduleModification(_) => "M",
         Change::Modification {
             executable_bit_changed, ..
         } => {
             if *executable_bit_changed {
-                b'X'
+                "X"
             } else {
-                b'M'
+                "M"
             }
         }
     }
@@ -0,0 +1,94 @@
+pub enum Action {
+    /// List all tags whose name matches `pattern`, or all tags if unset.
+    List { pattern: Option<String> },
+    /// Create a tag called `name` pointing to `target`, or `HEAD` if unset, which is annotated if there is a `message`,
+    /// overwriting an existing tag if `force` is set.
+    Create {
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        force: bool,
+    },
+    /// Delete all tags with `names`.
+    Delete { names: Vec<String> },
+}
+
+pub(crate) mod function {
+    use anyhow::{anyhow, bail, Context};
+    use gix::{prelude::ObjectIdExt, refs::transaction::PreviousValue};
+
+    use super::Action;
+
+    pub fn tag(
+        repo: gix::Repository,
+        mut out: impl std::io::Write,
+        mut err: impl std::io::Write,
+        action: Action,
+    ) -> anyhow::Result<()> {
+        match action {
+            Action::List { pattern } => {
+                for reference in repo.references()?.tags()? {
+                    let reference = reference.map_err(|err| anyhow!(err))?;
+                    let name = reference.name().shorten();
+                    if pattern.as_ref().map_or(true, |pattern| {
+                        gix::glob::wildmatch(pattern.as_str().into(), name, gix::glob::wildmatch::Mode::empty())
+                    }) {
+                        writeln!(out, "{name}")?;
+                    }
+                }
+            }
+            Action::Create {
+                name,
+                target,
+                message,
+                force,
+            } => {
+                let target = target.unwrap_or_else(|| "HEAD".into());
+                let object = repo.rev_parse_single(target.as_str())?.object()?;
+                let constraint = if force {
+                    PreviousValue::Any
+                } else {
+                    PreviousValue::MustNotExist
+                };
+                let res = match message {
+                    Some(mut message) => {
+                        let tagger = repo.committer().context("A tagger is required for annotated tags")??;
+                        if !message.ends_with('\n') {
+                            message.push('\n');
+                        }
+                        repo.tag(&name, object.id, object.kind, Some(tagger), message, constraint)
+                            .map(drop)
+                            .map_err(anyhow::Error::from)
+                    }
+                    None => repo
+                        .tag_reference(&name, object.id, constraint)
+                        .map(drop)
+                        .map_err(anyhow::Error::from),
+                };
+                res.with_context(|| format!("Could not create tag '{name}'"))?;
+            }
+            Action::Delete { names } => {
+                for name in names {
+                    let Some(tag) = gix::refs::FullName::try_from(format!("refs/tags/{name}"))
+                        .ok()
+                        .map(|name| repo.try_find_reference(name.as_ref()))
+                        .transpose()?
+                        .flatten()
+                    else {
+                        bail!("tag '{name}' not found");
+                    };
+                    tag.delete()?;
+                    match tag.target().try_id() {
+                        Some(id) => writeln!(
+                            err,
+                            "Deleted tag '{name}' (was {})",
+                            id.to_owned().attach(&repo).shorten_or_id()
+                        )?,
+                        None => writeln!(err, "Deleted tag '{name}'")?,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
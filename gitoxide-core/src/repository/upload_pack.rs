@@ -0,0 +1,214 @@
+pub struct Options {
+    /// Only advertise the references of the repository and stop, as used by smart HTTP servers.
+    pub advertise_refs: bool,
+    /// Don't advertise references but expect the wants of the client right away, and stop after answering the haves
+    /// of a single round of negotiation, as used by smart HTTP servers.
+    pub stateless_rpc: bool,
+}
+
+pub(crate) mod function {
+    use std::io::{BufWriter, Read, Write};
+
+    use anyhow::bail;
+    use gix::{
+        bstr::{BString, ByteSlice},
+        protocol::transport::packetline::{encode, Channel, PacketLineRef, StreamingPeekableIter},
+        refs::Target,
+        ObjectId,
+    };
+
+    use super::Options;
+
+    /// Send the objects a client like `git fetch` or `git clone` wants via `out`, after negotiating with it via `input`
+    /// which objects it already has, just like `git upload-pack` does with protocol V1.
+    ///
+    /// Clients can only want objects at the tips of advertised references, and packs are never thin.
+    pub fn upload_pack(
+        repo: gix::Repository,
+        input: impl Read,
+        mut out: impl Write,
+        Options {
+            advertise_refs,
+            stateless_rpc,
+        }: Options,
+    ) -> anyhow::Result<()> {
+        let tips = tips(&repo)?;
+        if !stateless_rpc {
+            if std::env::var("GIT_PROTOCOL").is_ok_and(|value| value.split(':').any(|v| v == "version=1")) {
+                encode::text_to_write(b"version 1", &mut out)?;
+            }
+            advertise(&repo, &tips, &mut out)?;
+            out.flush()?;
+        }
+        if advertise_refs {
+            return Ok(());
+        }
+
+        let mut lines = StreamingPeekableIter::new(input, &[PacketLineRef::Flush], false);
+        let mut capabilities = Vec::new();
+        let mut wants = Vec::new();
+        while let Some(line) = lines.read_line() {
+            let line = match line {
+                // The client may hang up without wanting anything, e.g. if it's up to date or only lists references.
+                Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof && wants.is_empty() => return Ok(()),
+                line => line??,
+            };
+            let Some(data) = line.as_slice() else { continue };
+            let Some(want) = data.trim_end().strip_prefix(b"want ") else {
+                bail!("protocol error: expected want, got '{}'", data.as_bstr());
+            };
+            let mut tokens = want.split_str(" ");
+            let hex = tokens.next().unwrap_or_default();
+            if wants.is_empty() {
+                capabilities.extend(tokens.map(|cap| cap.as_bstr().to_owned()));
+            }
+            let id = ObjectId::from_hex(hex)?;
+            if !tips.iter().any(|(tip, _)| *tip == id) {
+                encode::error_to_write(format!("upload-pack: not our ref {id}").as_bytes(), &mut out)?;
+                bail!("upload-pack: not our ref {id}");
+            }
+            wants.push(id);
+        }
+        if wants.is_empty() {
+            return Ok(());
+        }
+
+        let mut common = Vec::new();
+        loop {
+            lines.reset();
+            let mut is_done = false;
+            while let Some(line) = lines.read_line() {
+                let Some(data) = line??.as_slice() else { continue };
+                let data = data.trim_end();
+                if data == b"done" {
+                    is_done = true;
+                    break;
+                }
+                let Some(hex) = data.strip_prefix(b"have ") else {
+                    bail!("git upload-pack: expected SHA1 list, got '{}'", data.as_bstr());
+                };
+                let id = ObjectId::from_hex(hex)?;
+                if repo.has_object(id) {
+                    common.push(id);
+                    if common.len() == 1 {
+                        encode::text_to_write(format!("ACK {id}").as_bytes(), &mut out)?;
+                    }
+                }
+            }
+            if is_done {
+                if common.is_empty() {
+                    encode::text_to_write(b"NAK", &mut out)?;
+                }
+                break;
+            }
+            if common.is_empty() {
+                encode::text_to_write(b"NAK", &mut out)?;
+            }
+            out.flush()?;
+            if stateless_rpc {
+                return Ok(());
+            }
+        }
+
+        let has = |name: &str| capabilities.iter().any(|cap| cap == name);
+        let max_data_len = if has("side-band-64k") {
+            Some(65515)
+        } else if has("side-band") {
+            Some(995)
+        } else {
+            None
+        };
+        let mut progress = gix::progress::Discard;
+        match max_data_len {
+            Some(max_data_len) => {
+                let mut data = BufWriter::with_capacity(
+                    max_data_len,
+                    Band {
+                        out: &mut out,
+                        max_data_len,
+                    },
+                );
+                super::super::push::function::write_pack(&repo, &wants, &common, &mut progress, &mut data)?;
+                data.flush()?;
+                drop(data);
+                encode::flush_to_write(&mut out)?;
+            }
+            None => super::super::push::function::write_pack(&repo, &wants, &common, &mut progress, &mut out)?,
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Return the ids of all references along with their names, with annotated tags followed by the object they point to.
+    fn tips(repo: &gix::Repository) -> anyhow::Result<Vec<(ObjectId, BString)>> {
+        let mut out = Vec::new();
+        if let Ok(id) = repo.head_id() {
+            out.push((id.detach(), "HEAD".into()));
+        }
+        for reference in repo.references()?.all()? {
+            let mut reference = reference.map_err(|err| anyhow::anyhow!(err))?;
+            let Target::Peeled(id) = reference.inner.target else {
+                continue;
+            };
+            let name = reference.inner.name.as_bstr().to_owned();
+            out.push((id, name.clone()));
+            let peeled = reference.peel_to_id_in_place()?.detach();
+            if peeled != id {
+                let mut name = name;
+                name.extend_from_slice(b"^{}");
+                out.push((peeled, name));
+            }
+        }
+        Ok(out)
+    }
+
+    /// Print all `tips` along with our capabilities to `out`.
+    fn advertise(repo: &gix::Repository, tips: &[(ObjectId, BString)], mut out: impl Write) -> anyhow::Result<()> {
+        let mut capabilities = format!(
+            "side-band-64k side-band ofs-delta no-progress object-format={} agent={}",
+            repo.object_hash().to_string().to_ascii_lowercase(),
+            gix::protocol::agent(gix::env::agent())
+        );
+        if let Some(head) = repo.head_name()? {
+            capabilities.push_str(&format!(" symref=HEAD:{}", head.as_bstr()));
+        }
+        let mut is_first = true;
+        for (id, name) in tips {
+            let mut line = BString::from(format!("{id} {name}"));
+            if std::mem::take(&mut is_first) {
+                line.push(0);
+                line.extend_from_slice(capabilities.as_bytes());
+            }
+            encode::text_to_write(&line, &mut out)?;
+        }
+        if is_first {
+            let line = format!(
+                "{} capabilities^{{}}\0{capabilities}",
+                ObjectId::null(repo.object_hash())
+            );
+            encode::text_to_write(line.as_bytes(), &mut out)?;
+        }
+        encode::flush_to_write(&mut out)?;
+        Ok(())
+    }
+
+    /// Write all data as packet lines into the data band of `out`.
+    struct Band<W> {
+        out: W,
+        max_data_len: usize,
+    }
+
+    impl<W: Write> Write for Band<W> {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(self.max_data_len);
+            if len != 0 {
+                encode::band_to_write(Channel::Data, &buf[..len], &mut self.out)?;
+            }
+            Ok(len)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.out.flush()
+        }
+    }
+}
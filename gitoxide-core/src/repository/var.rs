@@ -0,0 +1,27 @@
+pub(crate) mod function {
+    use anyhow::bail;
+    use gix::repository::var::Var;
+
+    /// Print the value of the logical variable called `name`, or all variables as `NAME=value` if it is `None`,
+    /// like `git var` and `git var -l` do.
+    ///
+    /// Variables that can't be resolved are skipped when listing them all.
+    pub fn var(repo: gix::Repository, name: Option<&str>, mut out: impl std::io::Write) -> anyhow::Result<()> {
+        match name {
+            Some(name) => {
+                let Some(var) = Var::from_name(name) else {
+                    bail!("'{name}' is not a known variable");
+                };
+                writeln!(out, "{}", repo.var(var)?)?;
+            }
+            None => {
+                for var in Var::ALL {
+                    if let Ok(value) = repo.var(*var) {
+                        writeln!(out, "{}={value}", var.name())?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -62,3 +62,79 @@ pub fn integrity(
     }
     Ok(())
 }
+
+/// Verify the object databases of all `repos` concurrently, followed by their indices, and report the outcome of each
+/// repository without stopping at the first failure.
+pub fn integrity_of_many(
+    repos: Vec<gix::Repository>,
+    mut out: impl std::io::Write,
+    mut progress: impl gix::NestedProgress + 'static,
+    should_interrupt: &AtomicBool,
+    Context {
+        output_statistics,
+        thread_limit,
+        verify_mode,
+        algorithm,
+    }: Context,
+) -> anyhow::Result<()> {
+    let stores: Vec<_> = repos.iter().map(|repo| repo.objects.store_ref()).collect();
+    let outcomes = gix::odb::Store::verify_integrity_of_many(
+        &stores,
+        &mut progress,
+        should_interrupt,
+        gix::odb::pack::index::verify::integrity::Options {
+            verify_mode,
+            traversal: algorithm.into(),
+            thread_limit,
+            make_pack_lookup_cache: || gix::odb::pack::cache::Never,
+        },
+    );
+
+    let mut failed = 0;
+    #[cfg(feature = "serde")]
+    let mut statistics = Vec::new();
+    for (repo, outcome) in repos.iter().zip(outcomes) {
+        let res = outcome.map_err(anyhow::Error::from).and_then(|outcome| {
+            if let Some(index) = repo.worktree().map(|wt| wt.index()).transpose()? {
+                index.verify_integrity()?;
+                index.verify_entries()?;
+                index.verify_extensions(true, &repo.objects)?;
+            }
+            Ok(outcome)
+        });
+        match res {
+            #[cfg_attr(not(feature = "serde"), allow(unused))]
+            Ok(outcome) => {
+                progress.info(format!("Repository at '{}' OK", repo.git_dir().display()));
+                #[cfg(feature = "serde")]
+                statistics.push(serde_json::json!({
+                    "path": repo.git_dir(),
+                    "index_statistics" : outcome.index_statistics,
+                    "loose_object-stores" : outcome.loose_object_stores
+                }));
+            }
+            Err(err) => {
+                failed += 1;
+                progress.fail(format!(
+                    "Repository at '{}' failed verification: {err:#}",
+                    repo.git_dir().display()
+                ));
+                #[cfg(feature = "serde")]
+                statistics.push(serde_json::json!({
+                    "path": repo.git_dir(),
+                    "error": format!("{err:#}"),
+                }));
+            }
+        }
+    }
+    match output_statistics {
+        Some(OutputFormat::Human) => writeln!(out, "Human output is currently unsupported, use JSON instead")?,
+        #[cfg(feature = "serde")]
+        Some(OutputFormat::Json) => serde_json::to_writer_pretty(out, &statistics)?,
+        None => {}
+    }
+    if failed != 0 {
+        anyhow::bail!("{failed} of {} repositories failed verification", repos.len());
+    }
+    Ok(())
+}
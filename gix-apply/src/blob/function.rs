@@ -0,0 +1,252 @@
+use bstr::ByteSlice;
+
+use crate::{
+    blob::{whitespace, Error, Options, Whitespace},
+    patch::{BinaryHunk, Content, File, Hunk, LineKind},
+};
+
+/// Apply the changes of `file` to `preimage`, the current content of the file, and write the result into `out`, which is
+/// cleared beforehand.
+///
+/// Hunks are searched for at the position they are expected to be at first, and at increasing distances from it after that,
+/// so they still apply if lines were added or removed elsewhere. Hunks that start at the first line must match at the
+/// beginning, and hunks without trailing context must match at the end of `preimage`, just like `git apply` does it.
+///
+/// For new files, `preimage` is expected to be empty, and for deleted files, `out` is empty on success.
+pub fn blob(out: &mut Vec<u8>, preimage: &[u8], file: &File, options: Options) -> Result<(), Error> {
+    out.clear();
+    match &file.content {
+        Content::Text { hunks } => text(out, preimage, hunks, options),
+        Content::Binary { forward, .. } => binary(out, preimage, file, forward),
+        Content::BinaryWithoutData => Err(Error::BinaryWithoutData),
+    }
+}
+
+/// Apply the changes of `file` to `base`, the content the patch was created for, and merge the result with `current`,
+/// the content of the file that should be patched, writing the result into `out` which is cleared beforehand.
+///
+/// This is the fallback of `git apply --3way` for patches that don't apply to `current` directly, with `base` being
+/// looked up by the id recorded in the `index` line of the patch. Conflicts are marked using `labels`.
+pub fn three_way(
+    out: &mut Vec<u8>,
+    base: &[u8],
+    current: &[u8],
+    file: &File,
+    labels: gix_merge::blob::Labels<'_>,
+    options: Options,
+    merge_options: gix_merge::blob::Options,
+) -> Result<gix_merge::blob::Resolution, Error> {
+    let mut theirs = Vec::new();
+    blob(&mut theirs, base, file, options)?;
+    Ok(gix_merge::blob(out, base, current, &theirs, labels, merge_options))
+}
+
+fn text(out: &mut Vec<u8>, preimage: &[u8], hunks: &[Hunk], options: Options) -> Result<(), Error> {
+    let target: Vec<&[u8]> = preimage.lines_with_terminator().collect();
+    let mut cursor = 0;
+    let mut offset = 0_i64;
+    for (hunk_idx, hunk) in hunks.iter().enumerate() {
+        let hunk_no = hunk_idx + 1;
+        let old: Vec<&[u8]> = hunk
+            .lines
+            .iter()
+            .filter(|l| l.kind != LineKind::Addition)
+            .map(|l| l.content.as_slice())
+            .collect();
+        let trailing_context = hunk
+            .lines
+            .iter()
+            .rev()
+            .take_while(|l| l.kind == LineKind::Context)
+            .count();
+        let match_beginning = hunk.old_start <= 1;
+        let match_end = trailing_context == 0;
+
+        // An empty range refers to the line before it.
+        let start = if hunk.old_len == 0 {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        } as i64;
+        let expected = (start + offset).clamp(cursor as i64, target.len() as i64) as usize;
+        let pos =
+            find_position(&target, cursor, expected, &old, match_beginning, match_end, options).ok_or(Error::Hunk {
+                hunk: hunk_no,
+                line: hunk.old_start,
+            })?;
+        offset = pos as i64 - start;
+
+        let is_at_end = pos + old.len() == target.len();
+        let drop_additions_from = if options.whitespace == Whitespace::Fix && is_at_end {
+            hunk.lines.len()
+                - hunk
+                    .lines
+                    .iter()
+                    .rev()
+                    .take_while(|l| l.kind == LineKind::Addition && whitespace::is_blank(&l.content))
+                    .count()
+        } else {
+            hunk.lines.len()
+        };
+
+        out.extend(target[cursor..pos].iter().copied().flatten());
+        let mut target_line = pos;
+        let mut new_line = hunk.new_start;
+        for (line_idx, line) in hunk.lines.iter().enumerate() {
+            match line.kind {
+                LineKind::Context => {
+                    out.extend_from_slice(target[target_line]);
+                    target_line += 1;
+                    new_line += 1;
+                }
+                LineKind::Removal => target_line += 1,
+                LineKind::Addition => {
+                    match options.whitespace {
+                        Whitespace::Ignore => out.extend_from_slice(&line.content),
+                        Whitespace::Fix => {
+                            if line_idx < drop_additions_from {
+                                out.extend_from_slice(&whitespace::fix(&line.content));
+                            }
+                        }
+                        Whitespace::Error => {
+                            if whitespace::has_errors(&line.content) {
+                                return Err(Error::Whitespace {
+                                    hunk: hunk_no,
+                                    line: new_line,
+                                });
+                            }
+                            out.extend_from_slice(&line.content);
+                        }
+                    }
+                    new_line += 1;
+                }
+            }
+        }
+        cursor = pos + old.len();
+    }
+    out.extend(target[cursor..].iter().copied().flatten());
+    Ok(())
+}
+
+/// Find the line at which the lines of `old` appear in `target`, starting the search at `expected` and not going before `min`.
+fn find_position(
+    target: &[&[u8]],
+    min: usize,
+    expected: usize,
+    old: &[&[u8]],
+    match_beginning: bool,
+    match_end: bool,
+    options: Options,
+) -> Option<usize> {
+    let max = target.len().checked_sub(old.len())?;
+    if max < min {
+        return None;
+    }
+    let matches_at = |pos: usize| {
+        (!match_beginning || pos == 0)
+            && (!match_end || pos == max)
+            && old
+                .iter()
+                .zip(&target[pos..])
+                .all(|(old, current)| lines_match(old, current, options))
+    };
+    if match_beginning || match_end {
+        let pos = if match_beginning { 0 } else { max };
+        return (pos >= min && matches_at(pos)).then_some(pos);
+    }
+
+    let expected = expected.clamp(min, max);
+    for distance in 0..=(max - min) {
+        let forward = expected + distance;
+        if forward <= max && matches_at(forward) {
+            return Some(forward);
+        }
+        if let Some(backward) = expected
+            .checked_sub(distance)
+            .filter(|pos| *pos >= min && distance != 0)
+        {
+            if matches_at(backward) {
+                return Some(backward);
+            }
+        }
+    }
+    None
+}
+
+fn lines_match(old: &[u8], current: &[u8], options: Options) -> bool {
+    old == current
+        || (options.ignore_whitespace_changes && whitespace::eq_ignoring_changes(old, current))
+        || (options.whitespace == Whitespace::Fix && whitespace::fix(old) == whitespace::fix(current))
+}
+
+fn binary(out: &mut Vec<u8>, preimage: &[u8], file: &File, hunk: &BinaryHunk) -> Result<(), Error> {
+    if let Some(expected) = file.old_id.filter(|_| !file.is_addition()) {
+        let actual = gix_object::compute_hash(expected.as_oid().kind(), gix_object::Kind::Blob, preimage);
+        if expected.cmp_oid(&actual).is_ne() {
+            return Err(Error::BinaryPreimage { expected, actual });
+        }
+    }
+    match hunk {
+        BinaryHunk::Literal(data) => out.extend_from_slice(data),
+        BinaryHunk::Delta(delta) => apply_delta(preimage, delta, out).ok_or(Error::Delta)?,
+    }
+    if let Some(expected) = file.new_id.filter(|_| !file.is_deletion()) {
+        let actual = gix_object::compute_hash(expected.as_oid().kind(), gix_object::Kind::Blob, out);
+        if expected.cmp_oid(&actual).is_ne() {
+            return Err(Error::BinaryPostimage { expected, actual });
+        }
+    }
+    Ok(())
+}
+
+/// Apply `delta` in the format used in packs to `base` and append the result to `out`, or return `None` if the delta is invalid.
+fn apply_delta(base: &[u8], delta: &[u8], out: &mut Vec<u8>) -> Option<()> {
+    fn size(data: &mut &[u8]) -> Option<usize> {
+        let mut size = 0_usize;
+        for shift in (0..64).step_by(7) {
+            let (byte, rest) = data.split_first()?;
+            *data = rest;
+            size |= ((byte & 0x7f) as usize).checked_shl(shift)?;
+            if byte & 0x80 == 0 {
+                return Some(size);
+            }
+        }
+        None
+    }
+    fn take(data: &mut &[u8], cmd: u8, flags: u8, count: u32) -> Option<usize> {
+        let mut value = 0_usize;
+        for idx in 0..count {
+            if cmd & (flags << idx) != 0 {
+                let (byte, rest) = data.split_first()?;
+                *data = rest;
+                value |= (*byte as usize) << (8 * idx);
+            }
+        }
+        Some(value)
+    }
+
+    let mut data = delta;
+    if size(&mut data)? != base.len() {
+        return None;
+    }
+    let result_size = size(&mut data)?;
+    let start = out.len();
+    while let Some((&cmd, rest)) = data.split_first() {
+        data = rest;
+        if cmd & 0x80 != 0 {
+            let offset = take(&mut data, cmd, 0x01, 4)?;
+            let size = match take(&mut data, cmd, 0x10, 3)? {
+                0 => 0x10000,
+                size => size,
+            };
+            out.extend_from_slice(base.get(offset..offset.checked_add(size)?)?);
+        } else if cmd != 0 {
+            let (insert, rest) = (data.get(..cmd as usize)?, &data[cmd as usize..]);
+            out.extend_from_slice(insert);
+            data = rest;
+        } else {
+            return None;
+        }
+    }
+    (out.len() - start == result_size).then_some(())
+}
@@ -0,0 +1,72 @@
+use bstr::{BStr, ByteSlice};
+
+/// Determine what to do with whitespace errors in added lines, like `--whitespace=<action>` of `git apply`.
+///
+/// Whitespace errors are trailing whitespace, including carriage returns, and spaces before tabs in the indentation of a line.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Whitespace {
+    /// Apply added lines as they are, which is the default.
+    #[default]
+    Ignore,
+    /// Remove trailing whitespace and spaces before tabs in the indentation of added lines, and don't add empty lines
+    /// at the end of the file.
+    ///
+    /// When locating hunks, context lines also match if they only differ by whitespace errors.
+    Fix,
+    /// Fail to apply the patch if an added line has whitespace errors.
+    Error,
+}
+
+impl Whitespace {
+    /// Return the action with the given `name` as used by `git` in `apply.whitespace`, like `fix`, or `None` if it is unknown.
+    ///
+    /// As warnings aren't emitted, `warn` and `nowarn` both map to [`Whitespace::Ignore`].
+    pub fn from_name(name: &BStr) -> Option<Self> {
+        Some(match name.as_bytes() {
+            b"nowarn" | b"warn" => Whitespace::Ignore,
+            b"fix" | b"strip" => Whitespace::Fix,
+            b"error" | b"error-all" => Whitespace::Error,
+            _ => return None,
+        })
+    }
+}
+
+/// Options for use in [`blob()`](crate::blob()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// What to do with whitespace errors in added lines.
+    pub whitespace: Whitespace,
+    /// If `true`, context and removed lines also match lines that differ in the amount of whitespace, like
+    /// `--ignore-whitespace` of `git apply`.
+    ///
+    /// Context lines are taken from the content that is patched, so their whitespace is preserved.
+    pub ignore_whitespace_changes: bool,
+}
+
+/// The error returned by [`blob()`](crate::blob()) and [`three_way()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Hunk #{hunk} for line {line} doesn't apply")]
+    Hunk { hunk: usize, line: u32 },
+    #[error("Hunk #{hunk} adds line {line} with whitespace errors")]
+    Whitespace { hunk: usize, line: u32 },
+    #[error("The patch doesn't contain the data to apply the change to the binary file")]
+    BinaryWithoutData,
+    #[error("The binary patch applies to {expected}, but the content to patch is {actual}")]
+    BinaryPreimage {
+        expected: gix_hash::Prefix,
+        actual: gix_hash::ObjectId,
+    },
+    #[error("The binary patch should have yielded {expected}, but yielded {actual}")]
+    BinaryPostimage {
+        expected: gix_hash::Prefix,
+        actual: gix_hash::ObjectId,
+    },
+    #[error("The delta of the binary patch is corrupt or doesn't apply to the content to patch")]
+    Delta,
+}
+
+pub(super) mod function;
+pub use function::three_way;
+mod whitespace;
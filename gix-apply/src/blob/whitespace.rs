@@ -0,0 +1,86 @@
+use bstr::BString;
+
+/// The width of a tab when converting spaces in the indentation to tabs, the default of `core.whitespace`.
+const TAB_WIDTH: usize = 8;
+
+/// Return `true` if `line` has trailing whitespace or spaces before tabs in its indentation.
+pub(super) fn has_errors(line: &[u8]) -> bool {
+    let (body, _) = split_newline(line);
+    let indent = indentation(body);
+    body.last().map_or(false, u8::is_ascii_whitespace)
+        || indent
+            .iter()
+            .rposition(|b| *b == b'\t')
+            .map_or(false, |last_tab| indent[..last_tab].contains(&b' '))
+}
+
+/// Return `line` without trailing whitespace, and with spaces before tabs in its indentation removed, turning each run of
+/// spaces as wide as a tab into a tab.
+pub(super) fn fix(line: &[u8]) -> BString {
+    let (body, newline) = split_newline(line);
+    let body = &body[..body.len() - body.iter().rev().take_while(|b| b.is_ascii_whitespace()).count()];
+    let indent = indentation(body);
+
+    let mut out = BString::default();
+    let rest = match indent.iter().rposition(|b| *b == b'\t') {
+        Some(last_tab) if indent[..last_tab].contains(&b' ') => {
+            let mut spaces = 0;
+            for byte in &indent[..=last_tab] {
+                if *byte == b' ' {
+                    spaces += 1;
+                    if spaces == TAB_WIDTH {
+                        out.push(b'\t');
+                        spaces = 0;
+                    }
+                } else {
+                    out.push(*byte);
+                    spaces = 0;
+                }
+            }
+            &body[last_tab + 1..]
+        }
+        _ => body,
+    };
+    out.extend_from_slice(rest);
+    out.extend_from_slice(newline);
+    out
+}
+
+/// Return `true` if `line` consists of whitespace only.
+pub(super) fn is_blank(line: &[u8]) -> bool {
+    line.iter().all(u8::is_ascii_whitespace)
+}
+
+/// Return `true` if `a` and `b` are the same when ignoring line endings and treating runs of whitespace as equal.
+pub(super) fn eq_ignoring_changes(a: &[u8], b: &[u8]) -> bool {
+    fn trim_eol(line: &[u8]) -> &[u8] {
+        &line[..line.len() - line.iter().rev().take_while(|b| matches!(b, b'\r' | b'\n')).count()]
+    }
+    let (mut a, mut b) = (trim_eol(a), trim_eol(b));
+    while let (Some(ca), Some(cb)) = (a.first(), b.first()) {
+        if ca.is_ascii_whitespace() {
+            if !cb.is_ascii_whitespace() {
+                return false;
+            }
+            a = &a[a.iter().take_while(|b| b.is_ascii_whitespace()).count()..];
+            b = &b[b.iter().take_while(|b| b.is_ascii_whitespace()).count()..];
+        } else if ca != cb {
+            return false;
+        } else {
+            a = &a[1..];
+            b = &b[1..];
+        }
+    }
+    a.is_empty() && b.is_empty()
+}
+
+fn split_newline(line: &[u8]) -> (&[u8], &[u8]) {
+    match line.strip_suffix(b"\n") {
+        Some(body) => (body, b"\n"),
+        None => (line, b""),
+    }
+}
+
+fn indentation(line: &[u8]) -> &[u8] {
+    &line[..line.iter().take_while(|b| matches!(b, b' ' | b'\t')).count()]
+}
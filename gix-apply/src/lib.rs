@@ -0,0 +1,19 @@
+//! Parse patches in the unified format, including the extended headers and binary patches produced by `git`, and apply them
+//! to blobs, the building block for higher-level operations like `apply` and `am`.
+//!
+//! Patches are applied in memory only, and it's up to the caller to obtain the content to patch and to persist the results,
+//! be it by writing them into the worktree, or by writing blobs into the object database to update the index or trees.
+#![deny(missing_docs, rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+///
+pub mod patch;
+pub use patch::Patch;
+
+///
+pub mod blob;
+pub use blob::function::blob;
+
+///
+pub mod mail;
+pub use mail::Mail;
@@ -0,0 +1,322 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// A mail with a patch, as produced by `git format-patch`, with the information needed to commit the patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mail {
+    /// The name of the author of the patch, taken from the `From` header.
+    pub author_name: BString,
+    /// The email address of the author of the patch, taken from the `From` header.
+    pub author_email: BString,
+    /// The time at which the patch was authored, taken from the `Date` header, if present.
+    pub date: Option<gix_date::Time>,
+    /// The subject without prefixes like `[PATCH 1/2]` or `Re:`, which is the first line of the commit message.
+    pub subject: BString,
+    /// The complete commit message, made of the subject and the body of the mail up to the patch.
+    pub message: BString,
+    /// Everything after the commit message, which is the diffstat and the patch itself, ready to be
+    /// [parsed](crate::Patch::from_bytes()).
+    pub patch: BString,
+}
+
+/// The error returned by [`Mail::from_bytes()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The mail doesn't have a 'From' header with a valid author")]
+    MissingAuthor,
+    #[error("The date '{date}' of the mail could not be parsed")]
+    Date {
+        date: BString,
+        source: gix_date::parse::Error,
+    },
+}
+
+impl Mail {
+    /// Parse a single mail from `data`, with the headers of `From`, `Subject` and `Date` used to identify the author and the
+    /// commit message, which may be overridden by the same headers at the beginning of the body.
+    ///
+    /// The body may be encoded as `quoted-printable` or `base64`, and headers may contain encoded words as described in RFC 2047.
+    /// Multi-part mails aren't supported.
+    pub fn from_bytes(data: &[u8]) -> Result<Mail, Error> {
+        let (headers, body) = match data.find(b"\n\n") {
+            Some(pos) => (&data[..pos + 1], &data[pos + 2..]),
+            None => (data, &[][..]),
+        };
+        let (mut from, mut subject, mut date, mut encoding) = (None, None, None, None);
+        for (name, value) in unfold(headers) {
+            match name.to_ascii_lowercase().as_slice() {
+                b"from" => from = Some(decode_words(&value)),
+                b"subject" => subject = Some(decode_words(&value)),
+                b"date" => date = Some(value),
+                b"content-transfer-encoding" => encoding = Some(value.to_ascii_lowercase()),
+                _ => {}
+            }
+        }
+
+        let body = match encoding.as_deref() {
+            Some(b"quoted-printable") => decode_quoted_printable(body, false),
+            Some(b"base64") => decode_base64(body).unwrap_or_else(|| body.into()),
+            _ => body.into(),
+        };
+        let mut body = body.as_slice();
+
+        // In-body headers take precedence, and are commonly used when sending patches of other authors.
+        body = &body[body.iter().take_while(|b| b.is_ascii_whitespace()).count()..];
+        let mut has_inbody_headers = false;
+        while let Some((name, value)) = body.lines().next().and_then(|line| line.split_once_str(":")) {
+            let value = value.trim().as_bstr();
+            match name.to_ascii_lowercase().as_slice() {
+                b"from" => from = Some(decode_words(value)),
+                b"subject" => subject = Some(decode_words(value)),
+                b"date" => date = Some(value.to_owned()),
+                _ => break,
+            }
+            has_inbody_headers = true;
+            body = &body[body.find_byte(b'\n').map_or(body.len(), |pos| pos + 1)..];
+        }
+        if has_inbody_headers {
+            body = &body[body.iter().take_while(|b| b.is_ascii_whitespace()).count()..];
+        }
+
+        let (author_name, author_email) = from.and_then(|from| parse_address(&from)).ok_or(Error::MissingAuthor)?;
+        let date = date
+            .map(|date| {
+                gix_date::parse(date.to_str_lossy().as_ref(), None).map_err(|err| Error::Date {
+                    date: date.clone(),
+                    source: err,
+                })
+            })
+            .transpose()?;
+        let subject = cleanup_subject(subject.as_ref().map_or(b"".as_bstr(), |s| s.as_ref()));
+
+        let patch_start = patch_start(body);
+        let patch = body[patch_start..].into();
+        let body = body[..patch_start].trim_end();
+        let mut message = subject.clone();
+        message.push_byte(b'\n');
+        if !body.is_empty() {
+            message.push_byte(b'\n');
+            message.extend_from_slice(body);
+            message.push_byte(b'\n');
+        }
+        Ok(Mail {
+            author_name,
+            author_email,
+            date,
+            subject,
+            message,
+            patch,
+        })
+    }
+}
+
+/// Split `data` in the `mbox` format into the individual mails it contains, without the `From ` lines that separate them.
+///
+/// If `data` doesn't start with such a line, it's assumed to be a single mail.
+pub fn split_mbox(data: &[u8]) -> Vec<&[u8]> {
+    if !is_from_line(data.lines().next().unwrap_or_default()) {
+        return vec![data];
+    }
+    let mut mails = Vec::new();
+    let mut start = None;
+    let mut pos = 0;
+    for line in data.lines_with_terminator() {
+        if is_from_line(line) {
+            if let Some(start) = start {
+                mails.push(&data[start..pos]);
+            }
+            start = Some(pos + line.len());
+        }
+        pos += line.len();
+    }
+    if let Some(start) = start {
+        mails.push(&data[start..]);
+    }
+    mails
+}
+
+/// Return `true` if `line` separates mails, like `From 0123abc Mon Sep 17 00:00:00 2001`, which must end with a year and
+/// contain a time to not be confused with lines in mail bodies.
+fn is_from_line(line: &[u8]) -> bool {
+    let Some(rest) = line.strip_prefix(b"From ") else {
+        return false;
+    };
+    let rest = rest.trim_end();
+    let year = &rest[rest.len().saturating_sub(4)..];
+    rest.contains(&b':') && year.len() == 4 && year.iter().all(u8::is_ascii_digit)
+}
+
+/// Return the index at which the patch starts in `body`, which is at a `---` line or at the beginning of a diff.
+fn patch_start(body: &[u8]) -> usize {
+    let mut pos = 0;
+    for line in body.lines_with_terminator() {
+        let trimmed = line.trim_end();
+        if trimmed == b"---" || line.starts_with(b"diff -") || line.starts_with(b"Index: ") {
+            return pos;
+        }
+        pos += line.len();
+    }
+    body.len()
+}
+
+/// Return all headers in `headers` as name and value, with continuation lines joined.
+fn unfold(headers: &[u8]) -> Vec<(BString, BString)> {
+    let mut out: Vec<(BString, BString)> = Vec::new();
+    for line in headers.lines() {
+        if line.first().map_or(false, |b| *b == b' ' || *b == b'\t') {
+            if let Some((_, value)) = out.last_mut() {
+                value.push_byte(b' ');
+                value.extend_from_slice(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once_str(":") {
+            out.push((name.trim().into(), value.trim().into()));
+        }
+    }
+    out
+}
+
+/// Parse `Name <email>`, `"Name" <email>`, `email (Name)` or a plain email address.
+fn parse_address(from: &[u8]) -> Option<(BString, BString)> {
+    let from = from.trim();
+    let (name, email) = if let Some(start) = from.rfind_byte(b'<') {
+        let end = start + from[start..].find_byte(b'>')?;
+        (from[..start].trim(), from[start + 1..end].trim())
+    } else if let Some(start) = from.find_byte(b'(') {
+        let end = start + from[start..].find_byte(b')')?;
+        (from[start + 1..end].trim(), from[..start].trim())
+    } else {
+        (&[][..], from)
+    };
+    if email.is_empty() {
+        return None;
+    }
+    let name: BString = match name.strip_prefix(b"\"").and_then(|n| n.strip_suffix(b"\"")) {
+        Some(quoted) => quoted.replace("\\\"", "\"").replace("\\\\", "\\").into(),
+        None => name.into(),
+    };
+    let name = if name.is_empty() { email.into() } else { name };
+    Some((name, email.into()))
+}
+
+/// Remove prefixes like `Re:` and `[PATCH v2 1/3]` from `subject`.
+fn cleanup_subject(subject: &BStr) -> BString {
+    let mut subject = subject.trim();
+    loop {
+        subject = subject.trim_start();
+        if subject.len() >= 3 && subject[..3].eq_ignore_ascii_case(b"re:") {
+            subject = &subject[3..];
+        } else if subject.starts_with(b"[") {
+            match subject.find_byte(b']') {
+                Some(end) => subject = &subject[end + 1..],
+                None => break,
+            }
+        } else {
+            break;
+        }
+    }
+    subject.trim().into()
+}
+
+/// Decode encoded words like `=?UTF-8?q?J=C3=B6rg?=` in header `value`, assuming that all charsets are compatible with UTF-8
+/// except for `ISO-8859-1` which is converted.
+fn decode_words(value: &[u8]) -> BString {
+    let mut out = BString::default();
+    let mut rest = value;
+    let mut previous_was_encoded = false;
+    while let Some(start) = rest.find(b"=?") {
+        let Some((charset, encoding, text, consumed)) = encoded_word(&rest[start..]) else {
+            out.extend_from_slice(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            previous_was_encoded = false;
+            continue;
+        };
+        let between = &rest[..start];
+        // Whitespace between encoded words is ignored.
+        if !(previous_was_encoded && between.iter().all(u8::is_ascii_whitespace)) {
+            out.extend_from_slice(between);
+        }
+        let decoded = match encoding {
+            b'q' | b'Q' => Some(decode_quoted_printable(text, true)),
+            _ => decode_base64(text),
+        };
+        match decoded {
+            Some(decoded) if charset.eq_ignore_ascii_case(b"iso-8859-1") || charset.eq_ignore_ascii_case(b"latin1") => {
+                out.extend(decoded.iter().map(|b| char::from(*b)).collect::<String>().as_bytes())
+            }
+            Some(decoded) => out.extend_from_slice(&decoded),
+            None => out.extend_from_slice(&rest[start..start + consumed]),
+        }
+        rest = &rest[start + consumed..];
+        previous_was_encoded = true;
+    }
+    out.extend_from_slice(rest);
+    out
+}
+
+/// Parse `=?<charset>?<encoding>?<text>?=` at the beginning of `input`, returning the amount of consumed bytes as well.
+fn encoded_word(input: &[u8]) -> Option<(&[u8], u8, &[u8], usize)> {
+    let rest = input.strip_prefix(b"=?")?;
+    let charset_end = rest.find_byte(b'?')?;
+    let charset = &rest[..charset_end];
+    let rest = &rest[charset_end + 1..];
+    let (&encoding, rest) = rest.split_first()?;
+    if !matches!(encoding, b'q' | b'Q' | b'b' | b'B') {
+        return None;
+    }
+    let rest = rest.strip_prefix(b"?")?;
+    let text_end = rest.find(b"?=")?;
+    let consumed = 2 + charset_end + 1 + 2 + text_end + 2;
+    Some((charset, encoding, &rest[..text_end], consumed))
+}
+
+/// Decode `quoted-printable` `data`, with `in_header` enabling the variant used in encoded words where `_` is a space.
+fn decode_quoted_printable(data: &[u8], in_header: bool) -> BString {
+    let mut out = BString::default();
+    let mut idx = 0;
+    while let Some(&byte) = data.get(idx) {
+        idx += 1;
+        match byte {
+            b'=' => {
+                let hex = data.get(idx..idx + 2);
+                if let Some(value) = hex.and_then(|hex| u8::from_str_radix(hex.to_str().ok()?, 16).ok()) {
+                    out.push(value);
+                    idx += 2;
+                } else if data[idx..].starts_with(b"\r\n") {
+                    idx += 2;
+                } else if data[idx..].starts_with(b"\n") {
+                    idx += 1;
+                } else {
+                    out.push(byte);
+                }
+            }
+            b'_' if in_header => out.push(b' '),
+            _ => out.push(byte),
+        }
+    }
+    out
+}
+
+/// Decode `base64` `data` while ignoring whitespace, or return `None` if it is invalid.
+fn decode_base64(data: &[u8]) -> Option<BString> {
+    let mut out = BString::default();
+    let (mut value, mut bits) = (0_u32, 0);
+    for &byte in data.iter().filter(|b| !b.is_ascii_whitespace()) {
+        let digit = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ => return None,
+        };
+        value = (value << 6) | u32::from(digit);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((value >> bits) as u8);
+            value &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
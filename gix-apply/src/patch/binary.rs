@@ -0,0 +1,46 @@
+/// Decode a line of base85 data as written by `git`, prefixed with a character that encodes the amount of decoded bytes, and append
+/// the decoded bytes to `out`.
+pub(crate) fn decode_line(line: &[u8], out: &mut Vec<u8>) -> Result<(), &'static str> {
+    let (len, data) = line.split_first().ok_or("empty data line")?;
+    let len = match len {
+        b'A'..=b'Z' => len - b'A' + 1,
+        b'a'..=b'z' => len - b'a' + 27,
+        _ => return Err("invalid line length"),
+    } as usize;
+    if data.len() != (len + 3) / 4 * 5 {
+        return Err("line length doesn't match its data");
+    }
+    let start = out.len();
+    for chunk in data.chunks(5) {
+        let mut value = 0_u64;
+        for byte in chunk {
+            value = value * 85 + u64::from(decode_85(*byte).ok_or("invalid base85 character")?);
+        }
+        let value = u32::try_from(value).map_err(|_| "base85 value out of range")?;
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+    out.truncate(start + len);
+    Ok(())
+}
+
+/// Decompress zlib-compressed `data` which is expected to yield exactly `size` bytes.
+pub(crate) fn inflate(data: &[u8], size: usize) -> Result<Vec<u8>, &'static str> {
+    // One more byte than needed to be sure the stream ends where we expect it.
+    let mut out = vec![0; size + 1];
+    let mut inflate = gix_features::zlib::Inflate::default();
+    let (status, consumed, written) = inflate.once(data, &mut out).map_err(|_| "could not decompress data")?;
+    if status != gix_features::zlib::Status::StreamEnd || consumed != data.len() || written != size {
+        return Err("decompressed data doesn't match the expected size");
+    }
+    out.truncate(size);
+    Ok(out)
+}
+
+fn decode_85(byte: u8) -> Option<u8> {
+    Some(match byte {
+        b'0'..=b'9' => byte - b'0',
+        b'A'..=b'Z' => byte - b'A' + 10,
+        b'a'..=b'z' => byte - b'a' + 36,
+        _ => 62 + b"!#$%&()*+-;<=>?@^_`{|}~".iter().position(|b| *b == byte)? as u8,
+    })
+}
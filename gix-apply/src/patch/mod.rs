@@ -0,0 +1,127 @@
+use bstr::{BStr, BString};
+use gix_object::tree::EntryMode;
+
+/// A patch with changes to any amount of files, as parsed from the output of `git diff`, `git format-patch` or `diff -u`.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+    /// The changes to each file, in the order they appear in the patch.
+    pub files: Vec<File>,
+}
+
+/// The changes to a single file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct File {
+    /// The repository-relative path of the file before the change, or `None` if the file is added.
+    pub old_path: Option<BString>,
+    /// The repository-relative path of the file after the change, or `None` if the file is deleted.
+    pub new_path: Option<BString>,
+    /// The mode of the file before the change, if it is known.
+    pub old_mode: Option<EntryMode>,
+    /// The mode of the file after the change, if it is known.
+    ///
+    /// It's `None` if the mode doesn't change and isn't mentioned in the patch.
+    pub new_mode: Option<EntryMode>,
+    /// The possibly abbreviated id of the blob before the change, as taken from the `index` line of `git` patches.
+    pub old_id: Option<gix_hash::Prefix>,
+    /// The possibly abbreviated id of the blob after the change, as taken from the `index` line of `git` patches.
+    pub new_id: Option<gix_hash::Prefix>,
+    /// If `true`, the file at `old_path` is kept and `new_path` is created as copy of it.
+    pub is_copy: bool,
+    /// The changes to the content of the file.
+    pub content: Content,
+}
+
+impl File {
+    /// Return the path of the file after the change, or the path before the change if it is deleted.
+    pub fn path(&self) -> &BStr {
+        self.new_path
+            .as_ref()
+            .or(self.old_path.as_ref())
+            .expect("parser assures at least one path is set")
+            .as_ref()
+    }
+
+    /// Return `true` if the file is created by this change.
+    pub fn is_addition(&self) -> bool {
+        self.old_path.is_none()
+    }
+
+    /// Return `true` if the file is removed by this change.
+    pub fn is_deletion(&self) -> bool {
+        self.new_path.is_none()
+    }
+
+    /// Return `true` if the file changes its location, either by renaming or by copying it.
+    pub fn is_rename_or_copy(&self) -> bool {
+        matches!((&self.old_path, &self.new_path), (Some(old), Some(new)) if old != new)
+    }
+}
+
+/// The changes to the content of a [`File`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Content {
+    /// Changes to text, with hunks in the order of the lines they affect.
+    ///
+    /// There may be no hunks at all if only the mode or the location of the file changes, or if an empty file is added or deleted.
+    Text {
+        /// The hunks to apply.
+        hunks: Vec<Hunk>,
+    },
+    /// A binary patch as produced by `git diff --binary`.
+    Binary {
+        /// The instructions to produce the new content from the old one.
+        forward: BinaryHunk,
+        /// The instructions to produce the old content from the new one, which `git` always writes.
+        reverse: Option<BinaryHunk>,
+    },
+    /// The binary file changed, but the patch doesn't contain the data to apply that change.
+    BinaryWithoutData,
+}
+
+/// A hunk of a binary patch, with its data already decompressed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinaryHunk {
+    /// The data is the new content of the file.
+    Literal(Vec<u8>),
+    /// The data is a delta to apply to the old content of the file to obtain the new content, in the format used by packs.
+    Delta(Vec<u8>),
+}
+
+/// A hunk of changes to lines of text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// The one-based line number at which the hunk starts in the old file, or 0 if the old file is empty.
+    pub old_start: u32,
+    /// The amount of lines in the old file that are covered by the hunk.
+    pub old_len: u32,
+    /// The one-based line number at which the hunk starts in the new file, or 0 if the new file is empty.
+    pub new_start: u32,
+    /// The amount of lines in the new file that are covered by the hunk.
+    pub new_len: u32,
+    /// All lines of the hunk, in order.
+    pub lines: Vec<Line>,
+}
+
+/// A line in a [`Hunk`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// What happens with the line.
+    pub kind: LineKind,
+    /// The content of the line, including the line terminator unless the line is the last one of a file that doesn't end with a newline.
+    pub content: BString,
+}
+
+/// Identify what happens with a [`Line`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LineKind {
+    /// The line is unchanged and only serves to locate the hunk.
+    Context,
+    /// The line is removed from the old file.
+    Removal,
+    /// The line is added to the new file.
+    Addition,
+}
+
+mod binary;
+///
+pub mod parse;
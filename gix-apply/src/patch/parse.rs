@@ -0,0 +1,423 @@
+use bstr::{BStr, BString, ByteSlice};
+use gix_object::tree::EntryMode;
+
+use crate::{
+    patch::{binary, BinaryHunk, Content, File, Hunk, Line, LineKind},
+    Patch,
+};
+
+/// The error returned by [`Patch::from_bytes()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("No valid patches found in the input")]
+    NoPatch,
+    #[error("Malformed hunk header at line {line}: '{header}'")]
+    HunkHeader { line: usize, header: BString },
+    #[error("The hunk starting at line {line} is truncated or contains an invalid line")]
+    TruncatedHunk { line: usize },
+    #[error("Could not determine the path of the file changed by the patch at line {line}")]
+    MissingPath { line: usize },
+    #[error("Invalid mode '{mode}' at line {line}")]
+    Mode { line: usize, mode: BString },
+    #[error("Invalid object id '{id}' at line {line}")]
+    ObjectId { line: usize, id: BString },
+    #[error("Could not unquote path at line {line}")]
+    Unquote {
+        line: usize,
+        source: gix_quote::ansi_c::undo::Error,
+    },
+    #[error("The binary patch at line {line} is corrupt: {message}")]
+    Binary { line: usize, message: &'static str },
+}
+
+/// Options for use in [`Patch::from_bytes_with_options()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The amount of leading path components to remove from paths in the patch, like `-p<n>` of `git apply`.
+    ///
+    /// The default of 1 removes the `a/` and `b/` prefixes that `git` uses.
+    pub strip: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { strip: 1 }
+    }
+}
+
+impl Patch {
+    /// Parse all changes from `data`, which may be the output of `git diff`, `git format-patch` or `diff -u`.
+    ///
+    /// Text that isn't part of a patch, like the commit message and the diffstat of mails, is skipped.
+    pub fn from_bytes(data: &[u8]) -> Result<Patch, Error> {
+        Self::from_bytes_with_options(data, Options::default())
+    }
+
+    /// Like [`from_bytes()`](Self::from_bytes()), but with `options` to control how paths are interpreted.
+    pub fn from_bytes_with_options(data: &[u8], options: Options) -> Result<Patch, Error> {
+        let lines: Vec<&[u8]> = data.lines_with_terminator().collect();
+        let mut files = Vec::new();
+        let mut idx = 0;
+        while let Some(line) = lines.get(idx) {
+            if let Some(header) = line.strip_prefix(b"diff --git ") {
+                let (file, next) = git_file(&lines, idx, trim_eol(header), options.strip)?;
+                files.push(file);
+                idx = next;
+            } else if line.starts_with(b"--- ")
+                && lines.get(idx + 1).map_or(false, |l| l.starts_with(b"+++ "))
+                && lines.get(idx + 2).map_or(false, |l| l.starts_with(b"@@ -"))
+            {
+                let (file, next) = traditional_file(&lines, idx, options.strip)?;
+                files.push(file);
+                idx = next;
+            } else {
+                idx += 1;
+            }
+        }
+        if files.is_empty() {
+            return Err(Error::NoPatch);
+        }
+        Ok(Patch { files })
+    }
+}
+
+/// Parse the file whose `diff --git <header>` line is at `start`, and return it along with the index of the first line after it.
+fn git_file(lines: &[&[u8]], start: usize, header: &[u8], strip: usize) -> Result<(File, usize), Error> {
+    let default_path = git_header_path(header, strip, start + 1)?;
+    let mut file = File {
+        old_path: default_path.clone(),
+        new_path: default_path,
+        old_mode: None,
+        new_mode: None,
+        old_id: None,
+        new_id: None,
+        is_copy: false,
+        content: Content::Text { hunks: Vec::new() },
+    };
+    let (mut is_addition, mut is_deletion) = (false, false);
+    let mut idx = start + 1;
+    while let Some(line) = lines.get(idx).map(|l| trim_eol(l)) {
+        let line_no = idx + 1;
+        if let Some(mode) = line.strip_prefix(b"old mode ") {
+            file.old_mode = Some(parse_mode(mode, line_no)?);
+        } else if let Some(mode) = line.strip_prefix(b"new mode ") {
+            file.new_mode = Some(parse_mode(mode, line_no)?);
+        } else if let Some(mode) = line.strip_prefix(b"deleted file mode ") {
+            file.old_mode = Some(parse_mode(mode, line_no)?);
+            is_deletion = true;
+        } else if let Some(mode) = line.strip_prefix(b"new file mode ") {
+            file.new_mode = Some(parse_mode(mode, line_no)?);
+            is_addition = true;
+        } else if let Some(path) = strip_any_prefix(line, &[b"rename from ", b"rename old ", b"copy from "]) {
+            file.old_path = Some(header_path(path, strip, line_no)?);
+        } else if let Some(path) = strip_any_prefix(line, &[b"rename to ", b"rename new ", b"copy to "]) {
+            file.new_path = Some(header_path(path, strip, line_no)?);
+            file.is_copy = line.starts_with(b"copy");
+        } else if strip_any_prefix(line, &[b"similarity index ", b"dissimilarity index "]).is_some() {
+        } else if let Some(index) = line.strip_prefix(b"index ") {
+            let (ids, mode) = match index.find_byte(b' ') {
+                Some(pos) => (&index[..pos], Some(parse_mode(&index[pos + 1..], line_no)?)),
+                None => (index, None),
+            };
+            let (old, new) = ids.split_once_str("..").ok_or_else(|| Error::ObjectId {
+                line: line_no,
+                id: ids.into(),
+            })?;
+            file.old_id = Some(parse_id(old, line_no)?);
+            file.new_id = Some(parse_id(new, line_no)?);
+            if let Some(mode) = mode {
+                file.old_mode = file.old_mode.or(Some(mode));
+                file.new_mode = file.new_mode.or(Some(mode));
+            }
+        } else if let Some(path) = line.strip_prefix(b"--- ") {
+            let next = lines.get(idx + 1).map(|l| trim_eol(l));
+            let Some(new_path) = next.and_then(|l| l.strip_prefix(b"+++ ")) else {
+                break;
+            };
+            if !is_addition && file.old_path.is_none() {
+                file.old_path = patch_path(path, strip, line_no)?;
+            }
+            if !is_deletion && file.new_path.is_none() {
+                file.new_path = patch_path(new_path, strip, line_no + 1)?;
+            }
+            let (hunks, next) = hunks(lines, idx + 2)?;
+            file.content = Content::Text { hunks };
+            idx = next;
+            break;
+        } else if line == b"GIT binary patch" {
+            let (forward, next) = binary_hunk(lines, idx + 1)?;
+            let (reverse, next) = if lines
+                .get(next)
+                .map_or(false, |l| l.starts_with(b"literal ") || l.starts_with(b"delta "))
+            {
+                let (reverse, next) = binary_hunk(lines, next)?;
+                (Some(reverse), next)
+            } else {
+                (None, next)
+            };
+            file.content = Content::Binary { forward, reverse };
+            idx = next;
+            break;
+        } else if line.starts_with(b"Binary files ") && line.ends_with(b" differ") {
+            file.content = Content::BinaryWithoutData;
+            idx += 1;
+            break;
+        } else {
+            break;
+        }
+        idx += 1;
+    }
+
+    if is_addition {
+        file.old_path = None;
+    }
+    if is_deletion {
+        file.new_path = None;
+    }
+    if file.old_path.is_none() && file.new_path.is_none() {
+        return Err(Error::MissingPath { line: start + 1 });
+    }
+    Ok((file, idx))
+}
+
+/// Parse the file of a patch without the extended headers of `git`, whose `---` line is at `start`.
+fn traditional_file(lines: &[&[u8]], start: usize, strip: usize) -> Result<(File, usize), Error> {
+    let old_path = patch_path(&trim_eol(lines[start])[4..], strip, start + 1)?;
+    let new_path = patch_path(&trim_eol(lines[start + 1])[4..], strip, start + 2)?;
+    let (old_path, new_path) = match (old_path, new_path) {
+        (None, None) => return Err(Error::MissingPath { line: start + 1 }),
+        (None, Some(new)) => (None, Some(new)),
+        (Some(old), None) => (Some(old), None),
+        // Without extended headers, renames can't be expressed, and the old path is often just a backup file.
+        (Some(_), Some(new)) => (Some(new.clone()), Some(new)),
+    };
+    let (hunks, next) = hunks(lines, start + 2)?;
+    Ok((
+        File {
+            old_path,
+            new_path,
+            old_mode: None,
+            new_mode: None,
+            old_id: None,
+            new_id: None,
+            is_copy: false,
+            content: Content::Text { hunks },
+        },
+        next,
+    ))
+}
+
+/// Parse all consecutive hunks starting at `idx`.
+fn hunks(lines: &[&[u8]], mut idx: usize) -> Result<(Vec<Hunk>, usize), Error> {
+    let mut out = Vec::new();
+    while let Some(header) = lines.get(idx).filter(|l| l.starts_with(b"@@ -")) {
+        let start = idx + 1;
+        let (old_start, old_len, new_start, new_len) =
+            parse_hunk_header(trim_eol(header)).ok_or_else(|| Error::HunkHeader {
+                line: start,
+                header: trim_eol(header).into(),
+            })?;
+        idx += 1;
+
+        let mut hunk_lines: Vec<Line> = Vec::new();
+        let (mut old_left, mut new_left) = (old_len, new_len);
+        while old_left > 0 || new_left > 0 {
+            let line = lines.get(idx).ok_or(Error::TruncatedHunk { line: start })?;
+            idx += 1;
+            let (kind, content) = match line.first() {
+                Some(b' ') => (LineKind::Context, &line[1..]),
+                // Some mailers strip the trailing whitespace of empty context lines.
+                Some(b'\n') => (LineKind::Context, *line),
+                Some(b'\r') if *line == b"\r\n" => (LineKind::Context, *line),
+                Some(b'-') => (LineKind::Removal, &line[1..]),
+                Some(b'+') => (LineKind::Addition, &line[1..]),
+                Some(b'\\') => {
+                    strip_newline(&mut hunk_lines);
+                    continue;
+                }
+                _ => return Err(Error::TruncatedHunk { line: start }),
+            };
+            let (old, new) = match kind {
+                LineKind::Context => (1, 1),
+                LineKind::Removal => (1, 0),
+                LineKind::Addition => (0, 1),
+            };
+            old_left = old_left.checked_sub(old).ok_or(Error::TruncatedHunk { line: start })?;
+            new_left = new_left.checked_sub(new).ok_or(Error::TruncatedHunk { line: start })?;
+            hunk_lines.push(Line {
+                kind,
+                content: content.into(),
+            });
+        }
+        while lines.get(idx).map_or(false, |l| l.starts_with(b"\\")) {
+            strip_newline(&mut hunk_lines);
+            idx += 1;
+        }
+        out.push(Hunk {
+            old_start,
+            old_len,
+            new_start,
+            new_len,
+            lines: hunk_lines,
+        });
+    }
+    Ok((out, idx))
+}
+
+/// Parse a `literal <size>` or `delta <size>` hunk at `idx` along with its data lines and the empty line that ends it.
+fn binary_hunk(lines: &[&[u8]], mut idx: usize) -> Result<(BinaryHunk, usize), Error> {
+    let line_no = idx + 1;
+    let err = |message| Error::Binary { line: line_no, message };
+    let header = trim_eol(lines.get(idx).ok_or_else(|| err("missing hunk"))?);
+    let (is_literal, size) = if let Some(size) = header.strip_prefix(b"literal ") {
+        (true, size)
+    } else if let Some(size) = header.strip_prefix(b"delta ") {
+        (false, size)
+    } else {
+        return Err(err("expected 'literal' or 'delta'"));
+    };
+    let size: usize = size
+        .to_str()
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| err("invalid size"))?;
+    idx += 1;
+
+    let mut compressed = Vec::new();
+    loop {
+        let line = trim_eol(lines.get(idx).ok_or_else(|| err("missing empty line after data"))?);
+        idx += 1;
+        if line.is_empty() {
+            break;
+        }
+        binary::decode_line(line, &mut compressed).map_err(err)?;
+    }
+    let data = binary::inflate(&compressed, size).map_err(err)?;
+    Ok((
+        if is_literal {
+            BinaryHunk::Literal(data)
+        } else {
+            BinaryHunk::Delta(data)
+        },
+        idx,
+    ))
+}
+
+/// Parse `@@ -<old_start>[,<old_len>] +<new_start>[,<new_len>] @@`.
+fn parse_hunk_header(line: &[u8]) -> Option<(u32, u32, u32, u32)> {
+    fn range(range: &[u8]) -> Option<(u32, u32)> {
+        let number = |n: &[u8]| n.to_str().ok()?.parse().ok();
+        match range.split_once_str(",") {
+            Some((start, len)) => Some((number(start)?, number(len)?)),
+            None => Some((number(range)?, 1)),
+        }
+    }
+    let rest = line.strip_prefix(b"@@ -")?;
+    let (old, rest) = rest.split_once_str(" +")?;
+    let (new, rest) = rest.split_once_str(" ")?;
+    if !rest.starts_with(b"@@") {
+        return None;
+    }
+    let (old_start, old_len) = range(old)?;
+    let (new_start, new_len) = range(new)?;
+    Some((old_start, old_len, new_start, new_len))
+}
+
+/// Obtain the path from a `diff --git a/<path> b/<path>` header, which is only possible if both paths are the same.
+fn git_header_path(header: &[u8], strip: usize, line: usize) -> Result<Option<BString>, Error> {
+    if header.starts_with(b"\"") {
+        let (first, consumed) =
+            gix_quote::ansi_c::undo(header.as_bstr()).map_err(|err| Error::Unquote { line, source: err })?;
+        let second = unquote(header[consumed..].trim_start(), line)?;
+        let first = strip_components(first.as_ref(), strip);
+        return Ok(first
+            .filter(|first| Some(*first) == strip_components(second.as_ref(), strip))
+            .map(ToOwned::to_owned));
+    }
+    for pos in header.find_iter(" ") {
+        let second = unquote(&header[pos + 1..], line)?;
+        let first = strip_components(header[..pos].as_bstr(), strip);
+        if let Some(first) =
+            first.filter(|first| !first.is_empty() && Some(*first) == strip_components(second.as_ref(), strip))
+        {
+            return Ok(Some(first.to_owned()));
+        }
+    }
+    Ok(None)
+}
+
+/// Obtain the path from a `---` or `+++` line, with `/dev/null` meaning there is no path.
+fn patch_path(path: &[u8], strip: usize, line: usize) -> Result<Option<BString>, Error> {
+    let path = if path.starts_with(b"\"") {
+        unquote(path, line)?
+    } else {
+        // Remove timestamps of traditional diffs, or the tab `git` adds to paths containing spaces.
+        path.split_str("\t").next().unwrap_or_default().trim_end().into()
+    };
+    if path == "/dev/null" {
+        return Ok(None);
+    }
+    strip_components(path.as_ref(), strip)
+        .map(|path| Some(path.to_owned()))
+        .ok_or(Error::MissingPath { line })
+}
+
+/// Obtain the path from `rename` and `copy` lines, which don't have a prefix and thus have one component less to strip.
+fn header_path(path: &[u8], strip: usize, line: usize) -> Result<BString, Error> {
+    let path = unquote(path, line)?;
+    strip_components(path.as_ref(), strip.saturating_sub(1))
+        .map(ToOwned::to_owned)
+        .ok_or(Error::MissingPath { line })
+}
+
+/// Remove `count` leading components from `path`, or return `None` if there are not enough of them.
+fn strip_components(mut path: &BStr, count: usize) -> Option<&BStr> {
+    for _ in 0..count {
+        let pos = path.find_byte(b'/')?;
+        path = path[pos + 1..].as_bstr();
+    }
+    Some(path)
+}
+
+fn unquote(path: &[u8], line: usize) -> Result<BString, Error> {
+    gix_quote::ansi_c::undo(path.as_bstr())
+        .map(|(path, _)| path.into_owned())
+        .map_err(|err| Error::Unquote { line, source: err })
+}
+
+fn parse_mode(mode: &[u8], line: usize) -> Result<EntryMode, Error> {
+    mode.to_str()
+        .ok()
+        .and_then(|mode| u16::from_str_radix(mode.trim(), 8).ok())
+        .map(EntryMode)
+        .ok_or_else(|| Error::Mode {
+            line,
+            mode: mode.into(),
+        })
+}
+
+fn parse_id(id: &[u8], line: usize) -> Result<gix_hash::Prefix, Error> {
+    id.to_str()
+        .ok()
+        .and_then(|id| gix_hash::Prefix::from_hex(id).ok())
+        .ok_or_else(|| Error::ObjectId { line, id: id.into() })
+}
+
+fn strip_any_prefix<'a>(line: &'a [u8], prefixes: &[&[u8]]) -> Option<&'a [u8]> {
+    prefixes.iter().find_map(|prefix| line.strip_prefix(*prefix))
+}
+
+/// Handle a `\ No newline at end of file` marker, which refers to the previous line.
+fn strip_newline(lines: &mut [Line]) {
+    if let Some(line) = lines.last_mut() {
+        if line.content.ends_with(b"\n") {
+            line.content.pop();
+        }
+    }
+}
+
+fn trim_eol(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
@@ -0,0 +1,346 @@
+use gix_testtools::Result;
+
+fn fixture() -> Result<std::path::PathBuf> {
+    gix_testtools::scripted_fixture_read_only("make_patches.sh")
+}
+
+fn read(path: impl AsRef<std::path::Path>) -> Result<Vec<u8>> {
+    Ok(std::fs::read(fixture()?.join(path))?)
+}
+
+mod patch {
+    use gix_apply::{
+        patch::{BinaryHunk, Content, LineKind},
+        Patch,
+    };
+    use gix_object::tree::EntryKind;
+
+    use crate::read;
+
+    #[test]
+    fn text_with_multiple_hunks() -> crate::Result {
+        let patch = Patch::from_bytes(&read("offset/patch")?)?;
+        assert_eq!(patch.files.len(), 1);
+        let file = &patch.files[0];
+        assert_eq!(file.old_path.as_ref().expect("set"), "file");
+        assert_eq!(file.new_path.as_ref().expect("set"), "file");
+        assert_eq!(file.old_mode.map(|m| m.kind()), Some(EntryKind::Blob));
+        assert_eq!(file.new_mode, file.old_mode);
+        assert!(file.old_id.is_some() && file.new_id.is_some());
+        let Content::Text { hunks } = &file.content else {
+            unreachable!("text patch")
+        };
+        assert_eq!(hunks.len(), 2);
+        assert_eq!((hunks[0].old_start, hunks[0].old_len), (7, 7));
+        assert_eq!((hunks[1].new_start, hunks[1].new_len), (17, 7));
+        assert_eq!(
+            hunks[0].lines.iter().map(|l| l.kind).collect::<Vec<_>>(),
+            [
+                [LineKind::Context; 3].as_slice(),
+                &[LineKind::Removal, LineKind::Addition],
+                &[LineKind::Context; 3]
+            ]
+            .concat()
+        );
+        assert_eq!(hunks[0].lines[4].content, "changed 10\n");
+        Ok(())
+    }
+
+    #[test]
+    fn missing_newline_at_end_of_file() -> crate::Result {
+        let patch = Patch::from_bytes(&read("no-newline/patch")?)?;
+        let Content::Text { hunks } = &patch.files[0].content else {
+            unreachable!("text patch")
+        };
+        let removed = hunks[0]
+            .lines
+            .iter()
+            .find(|l| l.kind == LineKind::Removal)
+            .expect("present");
+        assert_eq!(
+            removed.content, "no newline",
+            "the marker removes the newline of the previous line"
+        );
+        assert_eq!(hunks[0].lines.last().expect("present").content, "with newline\n");
+        Ok(())
+    }
+
+    #[test]
+    fn rename_with_mode_change_in_mail() -> crate::Result {
+        let patch = Patch::from_bytes(&read("rename.patch")?)?;
+        assert_eq!(patch.files.len(), 1, "the mail headers and the diffstat are skipped");
+        let file = &patch.files[0];
+        assert_eq!(file.old_path.as_ref().expect("set"), "renamed");
+        assert_eq!(file.new_path.as_ref().expect("set"), "renamed-to");
+        assert!(file.is_rename_or_copy() && !file.is_copy);
+        assert_eq!(file.old_mode.map(|m| m.kind()), Some(EntryKind::BlobExecutable));
+        assert_eq!(file.new_mode.map(|m| m.kind()), Some(EntryKind::Blob));
+        Ok(())
+    }
+
+    #[test]
+    fn addition_in_mail() -> crate::Result {
+        let patch = Patch::from_bytes(&read("mail")?)?;
+        let file = &patch.files[0];
+        assert!(file.is_addition());
+        assert_eq!(file.path(), "mail-file");
+        assert_eq!(file.new_mode.map(|m| m.kind()), Some(EntryKind::Blob));
+        Ok(())
+    }
+
+    #[test]
+    fn binary_literal_and_delta() -> crate::Result {
+        let patch = Patch::from_bytes(&read("binary-delta.patch")?)?;
+        assert!(matches!(
+            patch.files[0].content,
+            Content::Binary {
+                forward: BinaryHunk::Delta(_),
+                reverse: Some(_)
+            }
+        ));
+        let patch = Patch::from_bytes(&read("binary-literal.patch")?)?;
+        assert!(matches!(
+            &patch.files[0].content,
+            Content::Binary {
+                forward: BinaryHunk::Literal(data),
+                reverse: Some(_)
+            } if data.len() == 100
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn traditional_diff_with_timestamps() -> crate::Result {
+        let patch = Patch::from_bytes(
+            b"--- dir/file.orig\t2024-01-01 00:00:00\n+++ dir/file\t2024-01-01 00:00:00\n@@ -1 +1 @@\n-a\n+b\n",
+        )?;
+        let file = &patch.files[0];
+        assert_eq!(file.old_path.as_ref().expect("set"), "file");
+        assert_eq!(file.new_path.as_ref().expect("set"), "file");
+        assert_eq!(file.old_mode, None);
+        Ok(())
+    }
+
+    #[test]
+    fn strip_and_quoted_paths() -> crate::Result {
+        let patch = Patch::from_bytes_with_options(
+            b"diff --git \"a/d/t\\303\\244st\" \"b/d/t\\303\\244st\"\nnew file mode 100644\nindex 0000000..d95f3ad\n",
+            gix_apply::patch::parse::Options { strip: 2 },
+        )?;
+        assert_eq!(patch.files[0].new_path.as_ref().expect("set"), "täst");
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            Patch::from_bytes(b"no patch here\n"),
+            Err(gix_apply::patch::parse::Error::NoPatch)
+        ));
+        assert!(matches!(
+            Patch::from_bytes(b"--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n-a\n+b\n"),
+            Err(gix_apply::patch::parse::Error::TruncatedHunk { line: 3 })
+        ));
+    }
+}
+
+mod blob {
+    use gix_apply::{
+        blob::{Error, Options, Whitespace},
+        Patch,
+    };
+    use gix_testtools::bstr::ByteSlice;
+
+    use crate::read;
+
+    fn apply(scenario: &str, options: Options) -> crate::Result<Vec<u8>> {
+        let patch = Patch::from_bytes(&read(format!("{scenario}/patch"))?)?;
+        let mut out = Vec::new();
+        gix_apply::blob(&mut out, &read(format!("{scenario}/target"))?, &patch.files[0], options)?;
+        Ok(out)
+    }
+
+    #[test]
+    fn like_git_apply() -> crate::Result {
+        for (scenario, options) in [
+            ("offset", Options::default()),
+            ("no-newline", Options::default()),
+            (
+                "whitespace-fix",
+                Options {
+                    whitespace: Whitespace::Fix,
+                    ..Default::default()
+                },
+            ),
+            (
+                "ignore-whitespace",
+                Options {
+                    ignore_whitespace_changes: true,
+                    ..Default::default()
+                },
+            ),
+        ] {
+            assert_eq!(
+                apply(scenario, options)?.as_bstr(),
+                read(format!("{scenario}/after"))?.as_bstr(),
+                "{scenario}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn patches_apply_to_their_preimage() -> crate::Result {
+        let patch = Patch::from_bytes(&read("offset/patch")?)?;
+        let mut out = Vec::new();
+        gix_apply::blob(&mut out, &read("offset/before")?, &patch.files[0], Default::default())?;
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines[9], b"changed 10");
+        assert_eq!(lines[19], b"inserted");
+        Ok(())
+    }
+
+    #[test]
+    fn whitespace_errors() -> crate::Result {
+        assert!(matches!(
+            apply(
+                "whitespace-fix",
+                Options {
+                    whitespace: Whitespace::Error,
+                    ..Default::default()
+                }
+            ),
+            Err(err) if matches!(err.downcast_ref::<Error>(), Some(Error::Whitespace { hunk: 1, line: 4 }))
+        ));
+        assert_eq!(
+            Whitespace::from_name("strip".into()),
+            Some(Whitespace::Fix),
+            "git knows aliases"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn mismatching_context_fails() -> crate::Result {
+        let err = apply("ignore-whitespace", Options::default()).expect_err("whitespace differs");
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::Hunk { hunk: 1, line: 2 })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn binary() -> crate::Result {
+        for kind in ["delta", "literal"] {
+            let patch = Patch::from_bytes(&read(format!("binary-{kind}.patch"))?)?;
+            let mut out = Vec::new();
+            gix_apply::blob(&mut out, &read("binary.before")?, &patch.files[0], Default::default())?;
+            assert_eq!(out, read(format!("binary-{kind}.after"))?, "{kind}");
+
+            assert!(
+                matches!(
+                    gix_apply::blob(&mut out, b"other", &patch.files[0], Default::default()),
+                    Err(Error::BinaryPreimage { .. })
+                ),
+                "the preimage is verified"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn three_way() -> crate::Result {
+        let patch = Patch::from_bytes(&read("three-way.patch")?)?;
+        let current = read("three-way.current")?;
+        let mut out = Vec::new();
+        assert!(
+            gix_apply::blob(&mut out, &current, &patch.files[0], Default::default()).is_err(),
+            "the context doesn't match anymore"
+        );
+        let resolution = gix_apply::blob::three_way(
+            &mut out,
+            &read("three-way.base")?,
+            &current,
+            &patch.files[0],
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )?;
+        assert_eq!(resolution, gix_merge::blob::Resolution::Complete);
+        assert_eq!(out.as_bstr(), read("three-way.expected")?.as_bstr());
+
+        let resolution = gix_apply::blob::three_way(
+            &mut out,
+            &read("three-way.base")?,
+            b"line uno\n",
+            &patch.files[0],
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )?;
+        assert_eq!(resolution, gix_merge::blob::Resolution::Conflict);
+        assert!(out.contains_str("<<<<<<<"));
+        Ok(())
+    }
+}
+
+mod mail {
+    use gix_apply::{mail::split_mbox, Mail, Patch};
+    use gix_testtools::bstr::ByteSlice;
+
+    use crate::read;
+
+    #[test]
+    fn format_patch_output_like_git_mailinfo() -> crate::Result {
+        let mail = Mail::from_bytes(&read("mail")?)?;
+        assert_eq!(mail.author_name, "Jörg Müller");
+        assert_eq!(mail.author_email, "joerg@example.com");
+        assert_eq!(
+            mail.date.expect("set"),
+            gix_date::parse("2023-01-02 03:04:05 +0200", None)?
+        );
+        let info = read("mail.info")?;
+        let subject = info
+            .lines()
+            .find_map(|l| l.strip_prefix(b"Subject: "))
+            .expect("present");
+        assert_eq!(mail.subject, subject.as_bstr(), "folded subjects are joined");
+        assert_eq!(mail.message, read("mail.message")?.as_bstr());
+        assert_eq!(mail.patch, read("mail.patch")?.as_bstr());
+        Ok(())
+    }
+
+    #[test]
+    fn in_body_headers_and_encodings() -> crate::Result {
+        let mail = Mail::from_bytes(
+            b"From: Sender <sender@example.com>\nSubject: Re: [PATCH 1/2] =?ISO-8859-1?Q?caf=E9?=\n =?UTF-8?B?IMOk?=\nContent-Transfer-Encoding: quoted-printable\n\nFrom: \"Author, The\" <author@example.com>\n\nbody=20with=\n soft break\n---\ndiff --git a/f b/f\n",
+        )?;
+        assert_eq!(mail.author_name, "Author, The");
+        assert_eq!(mail.author_email, "author@example.com");
+        assert_eq!(mail.date, None);
+        assert_eq!(mail.subject, "café ä", "whitespace between encoded words is dropped");
+        assert_eq!(mail.message, "café ä\n\nbody with soft break\n");
+        assert_eq!(mail.patch, "---\ndiff --git a/f b/f\n");
+        assert!(matches!(
+            Mail::from_bytes(b"Subject: no author\n\nbody\n"),
+            Err(gix_apply::mail::Error::MissingAuthor)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn mbox() -> crate::Result {
+        let data = read("mbox")?;
+        let mails = split_mbox(&data);
+        assert_eq!(mails.len(), 2);
+        let mails = mails.into_iter().map(Mail::from_bytes).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(mails[1].subject, "second patch");
+        assert_eq!(Patch::from_bytes(&mails[1].patch)?.files[0].path(), "mail-file");
+
+        let single = read("mail")?;
+        assert_eq!(split_mbox(b"Subject: x\n\nbody\n").len(), 1, "not a mailbox");
+        assert_eq!(split_mbox(&single).len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config core.abbrev 7
+
+function lines() {
+  for i in $(seq "$1" "$2"); do
+    echo "line $i"
+  done
+}
+
+# Each directory contains the `before` file the patch was created for, the `patch`, the `target` to apply it to,
+# and the `after` file as produced by `git apply` with the given options.
+function scenario() {
+  local name=${1:?first argument is the scenario name}
+  shift
+  mkdir "$name"
+  mv before patch target "$name/"
+  cp "$name/target" "$name/file"
+  git apply "$@" --directory="$name" "$name/patch" 2>/dev/null
+  mv "$name/file" "$name/after"
+}
+
+lines 1 30 >file
+git add file && git commit -q -m "base"
+cp file before
+{ lines 1 9; echo "changed 10"; lines 11 19; echo "inserted"; lines 20 30; } >file
+git diff >patch
+git checkout -q file
+{ echo "prefix 1"; echo "prefix 2"; lines 1 25; lines 27 30; } >target
+scenario offset
+
+lines 1 5 >file && printf "no newline" >>file
+git add file && git commit -q -m "no newline"
+cp file before
+{ lines 1 5; echo "no newline"; echo "with newline"; } >file
+git diff >patch
+git checkout -q file
+cp before target
+scenario no-newline
+
+lines 1 6 >file
+git add file && git commit -q -m "whitespace"
+cp file before
+{ lines 1 3; echo "trailing  "; printf ' \tspace before tab\n'; lines 4 6; echo ""; echo "  "; } >file
+git diff >patch
+git checkout -q file
+cp before target
+scenario whitespace-fix --whitespace=fix
+
+{ echo "a  b"; lines 1 6; } >file
+git add file && git commit -q -m "ignore whitespace"
+cp file before
+{ echo "a  b"; lines 1 3; echo "new"; lines 4 6; } >file
+git diff >patch
+git checkout -q file
+{ echo "a b"; echo "line  1"; lines 2 6; } >target
+scenario ignore-whitespace --ignore-whitespace
+
+printf 'binary\0%.0s' $(seq 1 300) >bin
+git add bin && git commit -q -m "binary"
+printf 'binary\0%.0s' $(seq 1 300) >before
+{ printf 'changed\0'; printf 'binary\0%.0s' $(seq 1 299); } >bin
+git diff --binary >binary-delta.patch
+cp bin binary-delta.after
+head -c 100 /dev/urandom >bin
+git diff --binary >binary-literal.patch
+cp bin binary-literal.after
+mv before binary.before
+git checkout -q bin
+
+lines 1 10 >renamed
+chmod +x renamed
+git add renamed && git commit -q -m "to be renamed"
+git mv renamed renamed-to
+chmod -x renamed-to
+sed -i 's/line 5/line five/' renamed-to
+git commit -q -am "rename with mode change"
+git format-patch -1 --stdout >rename.patch
+
+lines 1 10 >three-way
+git add three-way && git commit -q -m "three-way base"
+sed -i 's/line 9/line nine/; s/line 1$/line one/' three-way
+git diff >three-way.patch
+git checkout -q three-way
+git show HEAD:three-way >three-way.base
+sed -i 's/line 3/line three/' three-way
+git commit -q -am "ours"
+cp three-way three-way.current
+git apply --3way three-way.patch 2>/dev/null
+cp three-way three-way.expected
+git reset -q --hard
+
+echo "content" >mail-file
+git add mail-file
+GIT_AUTHOR_NAME="Jörg Müller" GIT_AUTHOR_EMAIL="joerg@example.com" GIT_AUTHOR_DATE="2023-01-02 03:04:05 +0200" \
+  git commit -q -F - <<'EOF'
+A subject that is long enough to be folded by format-patch into more than one line in the mail
+
+The body of the message,
+with more than one line.
+
+Signed-off-by: Someone <someone@example.com>
+EOF
+git format-patch -1 -v2 --stdout >mail
+git mailinfo mail.msg mail.patch <mail >mail.info
+git cat-file commit HEAD | sed '1,/^$/d' >mail.message
+
+echo "more" >>mail-file
+git commit -q -am "second patch"
+git format-patch -2 --stdout >mbox
@@ -57,11 +57,30 @@ pub enum Format {
         /// ranges from 0-9 for the deflate algorithm.
         compression_level: Option<u8>,
     },
+    /// A convenience format that will compress the `tar` stream with `xz`.
+    ///
+    /// Requires the `tar_xz` feature toggle to have an effect.
+    TarXz {
+        /// If `None`, use the default compression level. Otherwise use the given one which
+        /// ranges from 0-9 for the `xz` algorithm.
+        compression_level: Option<u8>,
+    },
+    /// A convenience format that will compress the `tar` stream with `zstd`.
+    ///
+    /// Requires the `tar_zst` feature toggle to have an effect.
+    TarZst {
+        /// If `None`, use the default compression level. Otherwise use the given one which
+        /// ranges from 1-22 for the `zstd` algorithm.
+        compression_level: Option<u8>,
+    },
     /// A standard `zip` archive. Note that this format silently converts illformed UTF-8 to UTF-8, which will
     /// equal a change of path.
     ///
     /// Requires the `zip` feature toggle to have an effect.
     ///
+    /// Entries whose size isn't known upfront or exceeds 4GB are written with `zip64` extensions, which are also used
+    /// automatically if the archive itself grows beyond 4GB or contains more than 65535 entries.
+    ///
     /// ### Shortcoming
     ///
     /// Even though symlinks are stored as such, for some reason at least on MacOS those aren't restored. That works,
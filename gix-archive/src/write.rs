@@ -23,16 +23,21 @@ where
     if opts.format == Format::InternalTransientNonPersistable {
         return Err(Error::InternalFormatMustNotPersist);
     }
-    #[cfg(any(feature = "tar", feature = "tar_gz"))]
+    #[cfg(any(feature = "tar", feature = "tar_gz", feature = "tar_xz", feature = "tar_zst"))]
     {
         enum State<W: std::io::Write> {
             #[cfg(feature = "tar")]
             Tar((tar::Builder<W>, Vec<u8>)),
             #[cfg(feature = "tar_gz")]
             TarGz((tar::Builder<flate2::write::GzEncoder<W>>, Vec<u8>)),
+            #[cfg(feature = "tar_xz")]
+            TarXz((tar::Builder<xz2::write::XzEncoder<W>>, Vec<u8>)),
+            #[cfg(feature = "tar_zst")]
+            TarZst((tar::Builder<zstd::Encoder<'static, W>>, Vec<u8>)),
         }
 
         impl<W: std::io::Write> State<W> {
+            #[cfg_attr(not(feature = "tar_gz"), allow(unused_variables))]
             pub fn new(format: Format, mtime: gix_date::SecondsSinceUnixEpoch, out: W) -> Result<Self, Error> {
                 Ok(match format {
                     Format::InternalTransientNonPersistable => unreachable!("handled earlier"),
@@ -52,7 +57,7 @@ where
                         }
                         #[cfg(not(feature = "tar"))]
                         {
-                            Err(Error::SupportNotCompiledIn { wanted: Format::Tar })
+                            return Err(Error::SupportNotCompiledIn { wanted: Format::Tar });
                         }
                     }
                     Format::TarGz { compression_level } => {
@@ -76,7 +81,50 @@ where
                         }
                         #[cfg(not(feature = "tar_gz"))]
                         {
-                            Err(Error::SupportNotCompiledIn { wanted: Format::TarGz })
+                            return Err(Error::SupportNotCompiledIn {
+                                wanted: Format::TarGz { compression_level },
+                            });
+                        }
+                    }
+                    Format::TarXz { compression_level } => {
+                        #[cfg(feature = "tar_xz")]
+                        {
+                            State::TarXz((
+                                {
+                                    let xz = xz2::write::XzEncoder::new(out, compression_level.unwrap_or(6) as u32);
+                                    let mut ar = tar::Builder::new(xz);
+                                    ar.mode(tar::HeaderMode::Deterministic);
+                                    ar
+                                },
+                                Vec::with_capacity(64 * 1024),
+                            ))
+                        }
+                        #[cfg(not(feature = "tar_xz"))]
+                        {
+                            return Err(Error::SupportNotCompiledIn {
+                                wanted: Format::TarXz { compression_level },
+                            });
+                        }
+                    }
+                    Format::TarZst { compression_level } => {
+                        #[cfg(feature = "tar_zst")]
+                        {
+                            State::TarZst((
+                                {
+                                    // A level of 0 selects the default compression level.
+                                    let zst = zstd::Encoder::new(out, compression_level.map_or(0, i32::from))?;
+                                    let mut ar = tar::Builder::new(zst);
+                                    ar.mode(tar::HeaderMode::Deterministic);
+                                    ar
+                                },
+                                Vec::with_capacity(64 * 1024),
+                            ))
+                        }
+                        #[cfg(not(feature = "tar_zst"))]
+                        {
+                            return Err(Error::SupportNotCompiledIn {
+                                wanted: Format::TarZst { compression_level },
+                            });
                         }
                     }
                 })
@@ -94,6 +142,14 @@ where
                 State::TarGz((ar, buf)) => {
                     append_tar_entry(ar, buf, entry, opts.modification_time, &opts)?;
                 }
+                #[cfg(feature = "tar_xz")]
+                State::TarXz((ar, buf)) => {
+                    append_tar_entry(ar, buf, entry, opts.modification_time, &opts)?;
+                }
+                #[cfg(feature = "tar_zst")]
+                State::TarZst((ar, buf)) => {
+                    append_tar_entry(ar, buf, entry, opts.modification_time, &opts)?;
+                }
             }
         }
 
@@ -106,6 +162,14 @@ where
             State::TarGz((ar, _)) => {
                 ar.into_inner()?.finish()?;
             }
+            #[cfg(feature = "tar_xz")]
+            State::TarXz((ar, _)) => {
+                ar.into_inner()?.finish()?;
+            }
+            #[cfg(feature = "tar_zst")]
+            State::TarZst((ar, _)) => {
+                ar.into_inner()?.finish()?;
+            }
         }
     }
     Ok(())
@@ -191,7 +255,7 @@ fn append_zip_entry<W: std::io::Write + std::io::Seek>(
     Ok(())
 }
 
-#[cfg(any(feature = "tar", feature = "tar_gz"))]
+#[cfg(any(feature = "tar", feature = "tar_gz", feature = "tar_xz", feature = "tar_zst"))]
 fn append_tar_entry<W: std::io::Write>(
     ar: &mut tar::Builder<W>,
     buf: &mut Vec<u8>,
@@ -221,7 +285,7 @@ fn append_tar_entry<W: std::io::Write>(
     Ok(())
 }
 
-#[cfg(any(feature = "tar", feature = "tar_gz"))]
+#[cfg(any(feature = "tar", feature = "tar_gz", feature = "tar_xz", feature = "tar_zst"))]
 fn tar_entry_type(mode: gix_object::tree::EntryMode) -> tar::EntryType {
     use gix_object::tree::EntryKind;
     use tar::EntryType;
@@ -233,7 +297,13 @@ fn tar_entry_type(mode: gix_object::tree::EntryMode) -> tar::EntryType {
     }
 }
 
-#[cfg(any(feature = "tar", feature = "tar_gz"))]
+#[cfg(any(
+    feature = "tar",
+    feature = "tar_gz",
+    feature = "tar_xz",
+    feature = "tar_zst",
+    feature = "zip"
+))]
 fn add_prefix<'a>(relative_path: &'a bstr::BStr, prefix: Option<&bstr::BString>) -> std::borrow::Cow<'a, bstr::BStr> {
     use std::borrow::Cow;
     match prefix {
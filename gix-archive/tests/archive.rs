@@ -174,6 +174,72 @@ mod from_tree {
         )
     }
 
+    #[test]
+    #[cfg(feature = "tar_xz")]
+    fn basic_usage_tar_xz() -> gix_testtools::Result {
+        basic_usage(
+            gix_archive::Format::TarXz {
+                compression_level: Some(9),
+            },
+            |buf| {
+                let mut decompressed = Vec::new();
+                xz2::read::XzDecoder::new(buf.as_slice()).read_to_end(&mut decompressed)?;
+                assert!(
+                    buf.len() < decompressed.len(),
+                    "smaller than uncompressed: {} < {}",
+                    buf.len(),
+                    decompressed.len()
+                );
+                assert_eq!(tar_paths(&decompressed)?, EXPECTED_TAR_PATHS);
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[cfg(feature = "tar_zst")]
+    fn basic_usage_tar_zst() -> gix_testtools::Result {
+        basic_usage(
+            gix_archive::Format::TarZst {
+                compression_level: Some(19),
+            },
+            |buf| {
+                let decompressed = zstd::decode_all(buf.as_slice())?;
+                assert!(
+                    buf.len() < decompressed.len(),
+                    "smaller than uncompressed: {} < {}",
+                    buf.len(),
+                    decompressed.len()
+                );
+                assert_eq!(tar_paths(&decompressed)?, EXPECTED_TAR_PATHS);
+                Ok(())
+            },
+        )
+    }
+
+    #[cfg(any(feature = "tar_xz", feature = "tar_zst"))]
+    const EXPECTED_TAR_PATHS: &[&str] = &[
+        "prefix/.gitattributes",
+        "prefix/a",
+        "prefix/symlink-to-a",
+        "prefix/dir/b",
+        "prefix/dir/subdir/exe",
+        "prefix/extra-file",
+        "prefix/extra-exe",
+        "prefix/extra-dir-empty",
+        "prefix/extra-dir/symlink-to-extra",
+    ];
+
+    #[cfg(any(feature = "tar_xz", feature = "tar_zst"))]
+    fn tar_paths(tar: &[u8]) -> std::io::Result<Vec<bstr::BString>> {
+        let mut ar = tar::Archive::new(tar);
+        let mut out = Vec::new();
+        for entry in ar.entries()? {
+            out.push(entry?.path_bytes().as_bstr().to_owned());
+        }
+        Ok(out)
+    }
+
     #[test]
     #[cfg(feature = "zip")]
     fn basic_usage_zip() -> gix_testtools::Result {
@@ -51,6 +51,65 @@ pub fn decode(data: &[u8]) -> Result<(Vec, &[u8]), decode::Error> {
     ))
 }
 
+///
+pub mod encode {
+    use super::Vec;
+
+    impl Vec {
+        /// Compress `words`, uncompressed bits in 64 bit words where the least significant bit of the first word is the
+        /// bit with index 0, into a new instance, with `num_bits` being the amount of bits that are actually in use.
+        pub fn from_words(words: &[u64], num_bits: u32) -> Self {
+            let mut bits = std::vec::Vec::with_capacity(1);
+            let mut rlw = 0;
+            let mut words = words;
+            while !words.is_empty() || bits.is_empty() {
+                rlw = bits.len();
+                bits.push(0);
+
+                let run_bit = words.first() == Some(&u64::MAX);
+                let clean_word = if run_bit { u64::MAX } else { 0 };
+                let running_len = words
+                    .iter()
+                    .take(RLW_LARGEST_RUNNING_COUNT as usize)
+                    .take_while(|word| **word == clean_word)
+                    .count();
+                words = &words[running_len..];
+
+                let literal_words = words
+                    .iter()
+                    .take(RLW_LARGEST_LITERAL_COUNT as usize)
+                    .take_while(|word| **word != 0 && **word != u64::MAX)
+                    .count();
+                bits.extend_from_slice(&words[..literal_words]);
+                words = &words[literal_words..];
+
+                bits[rlw] = u64::from(run_bit)
+                    | ((running_len as u64) << 1)
+                    | ((literal_words as u64) << (1 + RLW_RUNNING_BITS));
+            }
+            Vec {
+                num_bits,
+                bits,
+                rlw: rlw as u64,
+            }
+        }
+
+        /// Write this bitmap to `out` in the format understood by [`decode()`](super::decode()).
+        pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+            out.write_all(&self.num_bits.to_be_bytes())?;
+            out.write_all(&(self.bits.len() as u32).to_be_bytes())?;
+            for word in &self.bits {
+                out.write_all(&word.to_be_bytes())?;
+            }
+            out.write_all(&(self.rlw as u32).to_be_bytes())
+        }
+    }
+
+    const RLW_RUNNING_BITS: u64 = 4 * 8;
+    const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    const RLW_LARGEST_LITERAL_COUNT: u64 = (1 << (64 - 1 - RLW_RUNNING_BITS)) - 1;
+}
+
 mod access {
     use std::convert::{TryFrom, TryInto};
 
@@ -0,0 +1,629 @@
+use std::{
+    borrow::Cow,
+    collections::{hash_map, BinaryHeap, HashMap},
+    num::NonZeroU32,
+    ops::Range,
+};
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_diff::blob::intern::{InternedInput, Token};
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+
+use crate::{
+    file::{Algorithm, Error, Options},
+    incremental::{Action, Delegate, Outcome as IncrementalOutcome},
+    BlameEntry, Outcome, Statistics,
+};
+
+/// Find the commit that introduced each line of the file at `file_path` as it is in the commit `suspect`, with all objects
+/// being looked up in `odb`.
+///
+/// Commits are visited from newest to oldest by their commit time. Lines that are unchanged compared to a parent are
+/// passed on to it, trying each parent in order, and lines that remain are attributed to the commit itself.
+/// If the file is identical to its version in one of the parents, all lines are passed on to that parent only.
+/// Root commits and commits that add the file are blamed for all lines that reach them.
+///
+/// Depending on `options`, the file is followed through renames, and lines that changed only in whitespace or that were
+/// moved within the file or copied from other files keep their original attribution.
+///
+/// Note that the file is compared as it is stored, without applying any filters.
+pub fn file(
+    odb: impl gix_object::Find,
+    suspect: ObjectId,
+    file_path: &BStr,
+    options: Options,
+) -> Result<Outcome, Error> {
+    let mut entries = Vec::new();
+    let IncrementalOutcome { blob, statistics, .. } = incremental(odb, suspect, file_path, options, &mut |entry| {
+        entries.push(entry);
+        Action::Continue
+    })?;
+    Ok(Outcome {
+        entries: coalesce(entries),
+        blob,
+        statistics,
+    })
+}
+
+/// Like [`file()`], but pass each entry to `delegate` as soon as it is final instead of collecting them, similar to
+/// `git blame --incremental`.
+///
+/// This allows to show the attribution of lines of large files while the rest of the history is still being traversed.
+/// Entries are not sorted or merged, and if `delegate` cancels, the remaining lines stay unattributed.
+pub fn incremental(
+    odb: impl gix_object::Find,
+    suspect: ObjectId,
+    file_path: &BStr,
+    options: Options,
+    delegate: &mut dyn Delegate,
+) -> Result<IncrementalOutcome, Error> {
+    let mut statistics = Statistics::default();
+    let mut buf = Vec::new();
+    let mut buf2 = Vec::new();
+
+    let (commit_time, entry) = commit_time_and_entry(&odb, &suspect, file_path, &mut buf, &mut statistics)?;
+    let (blob_id, mode) = entry.ok_or_else(|| Error::FileNotFound {
+        file_path: file_path.to_owned(),
+        commit: suspect,
+    })?;
+    if !mode.is_blob_or_symlink() {
+        return Err(Error::NotAFile {
+            file_path: file_path.to_owned(),
+            commit: suspect,
+        });
+    }
+    let blob = odb.find_blob(&blob_id, &mut buf)?.data.to_vec();
+    let num_lines = blob.lines_with_terminator().count() as u32;
+
+    let mut suspects: HashMap<ObjectId, Vec<Suspect>> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    if num_lines != 0 {
+        suspects.insert(
+            suspect,
+            vec![Suspect {
+                path: file_path.to_owned(),
+                blob_id,
+                hunks: vec![UnblamedHunk {
+                    range_in_blamed_file: 0..num_lines,
+                    range_in_suspect: 0..num_lines,
+                }],
+            }],
+        );
+        queue.push((commit_time, suspect));
+    }
+
+    while let Some((_, commit_id)) = queue.pop() {
+        let Some(commit_suspects) = suspects.remove(&commit_id) else {
+            continue;
+        };
+        statistics.commits_traversed += 1;
+
+        let parent_ids: Vec<_> = odb.find_commit_iter(&commit_id, &mut buf)?.parent_ids().collect();
+        let mut files = TreeFiles::default();
+        for Suspect { path, blob_id, hunks } in commit_suspects {
+            let mut data = None;
+            let mut parents = Vec::with_capacity(parent_ids.len());
+            for parent_id in &parent_ids {
+                let (time, entry) = commit_time_and_entry(&odb, parent_id, path.as_ref(), &mut buf, &mut statistics)?;
+                let mut source = entry
+                    .filter(|(_, mode)| mode.is_blob_or_symlink())
+                    .map(|(id, _)| (path.clone(), id));
+                if source.is_none() && options.follow_renames {
+                    source = find_rename(
+                        &odb,
+                        &mut files,
+                        (commit_id, *parent_id),
+                        (blob_id, &mut data),
+                        options.algorithm,
+                        (&mut buf, &mut buf2),
+                        &mut statistics,
+                    )?;
+                }
+                parents.push(Parent {
+                    id: *parent_id,
+                    time,
+                    source,
+                });
+            }
+
+            if let Some(parent) = parents
+                .iter()
+                .find(|parent| parent.source.as_ref().map(|(_, id)| id) == Some(&blob_id))
+            {
+                let (parent_path, parent_blob_id) = parent.source.clone().expect("just checked");
+                pass_to_parent(
+                    &mut suspects,
+                    &mut queue,
+                    (parent.id, parent.time),
+                    parent_path,
+                    parent_blob_id,
+                    hunks,
+                );
+                continue;
+            }
+
+            let mut remaining = hunks;
+            if parents.iter().any(|parent| parent.source.is_some()) || options.detect_copies.is_some() {
+                let data = load_blob(&mut data, &odb, &blob_id, &mut buf)?;
+                for parent in &parents {
+                    let Some((parent_path, parent_blob_id)) = &parent.source else {
+                        continue;
+                    };
+                    if remaining.is_empty() {
+                        break;
+                    }
+                    let parent_data = odb.find_blob(parent_blob_id, &mut buf2)?.data;
+                    statistics.blobs_diffed += 1;
+                    let input = interned_lines(parent_data, data, options.ignore_whitespace);
+                    let unchanged = unchanged_segments(options.algorithm, &input);
+                    let (passed, kept) = split_hunks(remaining, &unchanged);
+                    remaining = kept;
+                    if let Some(min_score) = options.detect_moves.filter(|_| !remaining.is_empty()) {
+                        let (moved, kept) = split_moved_lines(remaining, &input, data, min_score);
+                        remaining = kept;
+                        pass_to_parent(
+                            &mut suspects,
+                            &mut queue,
+                            (parent.id, parent.time),
+                            parent_path.clone(),
+                            *parent_blob_id,
+                            moved,
+                        );
+                    }
+                    pass_to_parent(
+                        &mut suspects,
+                        &mut queue,
+                        (parent.id, parent.time),
+                        parent_path.clone(),
+                        *parent_blob_id,
+                        passed,
+                    );
+                }
+
+                if let (Some(min_score), Some(parent)) = (options.detect_copies, parents.first()) {
+                    if !remaining.is_empty() {
+                        for (source_path, source_blob_id) in files.changed(
+                            &odb,
+                            (commit_id, parent.id),
+                            [Some(&path), parent.source.as_ref().map(|(path, _)| path)],
+                            &mut buf,
+                            &mut statistics,
+                        )? {
+                            if remaining.is_empty() {
+                                break;
+                            }
+                            let source_data = odb.find_blob(&source_blob_id, &mut buf2)?.data;
+                            statistics.blobs_diffed += 1;
+                            let input = interned_lines(source_data, data, options.ignore_whitespace);
+                            let (copied, kept) = split_moved_lines(remaining, &input, data, min_score);
+                            remaining = kept;
+                            pass_to_parent(
+                                &mut suspects,
+                                &mut queue,
+                                (parent.id, parent.time),
+                                source_path,
+                                source_blob_id,
+                                copied,
+                            );
+                        }
+                    }
+                }
+            }
+            let source_file_name = (path != file_path).then_some(path);
+            for hunk in remaining {
+                let entry = BlameEntry {
+                    start_in_blamed_file: hunk.range_in_blamed_file.start,
+                    start_in_source_file: hunk.range_in_suspect.start,
+                    len: NonZeroU32::new(hunk.range_in_blamed_file.len() as u32).expect("hunks are never empty"),
+                    commit_id,
+                    source_file_name: source_file_name.clone(),
+                };
+                if delegate.entry(entry) == Action::Cancel {
+                    return Ok(IncrementalOutcome {
+                        blob,
+                        statistics,
+                        cancelled: true,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(IncrementalOutcome {
+        blob,
+        statistics,
+        cancelled: false,
+    })
+}
+
+/// A parent of a suspect commit along with the path and id of the blamed file in it, if it has it.
+struct Parent {
+    id: ObjectId,
+    time: gix_date::SecondsSinceUnixEpoch,
+    source: Option<(BString, ObjectId)>,
+}
+
+/// A file in a commit along with the lines it is suspected to have introduced.
+struct Suspect {
+    /// The path of the blamed file in the suspect commit, which differs from the blamed path if it was renamed or copied.
+    path: BString,
+    /// The id of the blamed file in the suspect commit.
+    blob_id: ObjectId,
+    hunks: Vec<UnblamedHunk>,
+}
+
+/// A range of lines in the blamed file along with the range of the same lines in the file as it is in the suspect commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UnblamedHunk {
+    range_in_blamed_file: Range<u32>,
+    range_in_suspect: Range<u32>,
+}
+
+impl UnblamedHunk {
+    /// Return the part of this hunk that covers `range_in_suspect`, which must be contained in our own range.
+    fn sub_hunk(&self, range_in_suspect: Range<u32>) -> UnblamedHunk {
+        let start = self.range_in_blamed_file.start + (range_in_suspect.start - self.range_in_suspect.start);
+        UnblamedHunk {
+            range_in_blamed_file: start..start + range_in_suspect.len() as u32,
+            range_in_suspect,
+        }
+    }
+}
+
+/// A range of lines that is the same in the file of a suspect and its version in a parent.
+struct UnchangedSegment {
+    start_in_suspect: u32,
+    start_in_parent: u32,
+    len: u32,
+}
+
+fn pass_to_parent(
+    suspects: &mut HashMap<ObjectId, Vec<Suspect>>,
+    queue: &mut BinaryHeap<(gix_date::SecondsSinceUnixEpoch, ObjectId)>,
+    (parent_id, time): (ObjectId, gix_date::SecondsSinceUnixEpoch),
+    path: BString,
+    blob_id: ObjectId,
+    hunks: Vec<UnblamedHunk>,
+) {
+    if hunks.is_empty() {
+        return;
+    }
+    match suspects.entry(parent_id) {
+        hash_map::Entry::Occupied(mut entry) => {
+            let suspects = entry.get_mut();
+            match suspects.iter_mut().find(|suspect| suspect.path == path) {
+                Some(suspect) => suspect.hunks.extend(hunks),
+                None => suspects.push(Suspect { path, blob_id, hunks }),
+            }
+        }
+        hash_map::Entry::Vacant(entry) => {
+            entry.insert(vec![Suspect { path, blob_id, hunks }]);
+            queue.push((time, parent_id));
+        }
+    }
+}
+
+/// Return the data of the blob with `id`, loading it into `data` if it wasn't loaded yet.
+fn load_blob<'a>(
+    data: &'a mut Option<Vec<u8>>,
+    odb: &impl gix_object::Find,
+    id: &gix_hash::oid,
+    buf: &mut Vec<u8>,
+) -> Result<&'a [u8], Error> {
+    if data.is_none() {
+        *data = Some(odb.find_blob(id, buf)?.data.to_vec());
+    }
+    Ok(data.as_deref().expect("just set"))
+}
+
+/// All files in the trees of commits by their path, loaded on demand.
+#[derive(Default)]
+struct TreeFiles {
+    by_commit: HashMap<ObjectId, HashMap<BString, ObjectId>>,
+}
+
+impl TreeFiles {
+    fn load(
+        &mut self,
+        odb: &impl gix_object::Find,
+        commit_id: ObjectId,
+        buf: &mut Vec<u8>,
+        statistics: &mut Statistics,
+    ) -> Result<&HashMap<BString, ObjectId>, Error> {
+        if let hash_map::Entry::Vacant(entry) = self.by_commit.entry(commit_id) {
+            let mut files = HashMap::new();
+            let mut trees = vec![(BString::default(), odb.find_commit_iter(&commit_id, buf)?.tree_id()?)];
+            while let Some((prefix, tree_id)) = trees.pop() {
+                statistics.trees_decoded += 1;
+                for tree_entry in odb.find_tree_iter(&tree_id, buf)?.filter_map(Result::ok) {
+                    let mut path = prefix.clone();
+                    if !path.is_empty() {
+                        path.push(b'/');
+                    }
+                    path.extend_from_slice(tree_entry.filename);
+                    if tree_entry.mode.is_tree() {
+                        trees.push((path, tree_entry.oid.to_owned()));
+                    } else if tree_entry.mode.is_blob_or_symlink() {
+                        files.insert(path, tree_entry.oid.to_owned());
+                    }
+                }
+            }
+            entry.insert(files);
+        }
+        Ok(&self.by_commit[&commit_id])
+    }
+
+    /// Return the files in `parent_id` which were deleted in `commit_id`, sorted by path.
+    fn deleted(
+        &mut self,
+        odb: &impl gix_object::Find,
+        (commit_id, parent_id): (ObjectId, ObjectId),
+        buf: &mut Vec<u8>,
+        statistics: &mut Statistics,
+    ) -> Result<Vec<(BString, ObjectId)>, Error> {
+        self.load(odb, commit_id, buf, statistics)?;
+        self.load(odb, parent_id, buf, statistics)?;
+        let files = &self.by_commit[&commit_id];
+        let mut out: Vec<_> = self.by_commit[&parent_id]
+            .iter()
+            .filter(|(path, _)| !files.contains_key(*path))
+            .map(|(path, id)| (path.clone(), *id))
+            .collect();
+        out.sort();
+        Ok(out)
+    }
+
+    /// Return the files in `parent_id` which were changed or deleted in `commit_id`, sorted by path and without
+    /// any of the `excluded` paths.
+    fn changed(
+        &mut self,
+        odb: &impl gix_object::Find,
+        (commit_id, parent_id): (ObjectId, ObjectId),
+        excluded: [Option<&BString>; 2],
+        buf: &mut Vec<u8>,
+        statistics: &mut Statistics,
+    ) -> Result<Vec<(BString, ObjectId)>, Error> {
+        self.load(odb, commit_id, buf, statistics)?;
+        self.load(odb, parent_id, buf, statistics)?;
+        let files = &self.by_commit[&commit_id];
+        let mut out: Vec<_> = self.by_commit[&parent_id]
+            .iter()
+            .filter(|(path, id)| files.get(*path) != Some(*id) && !excluded.contains(&Some(*path)))
+            .map(|(path, id)| (path.clone(), *id))
+            .collect();
+        out.sort();
+        Ok(out)
+    }
+}
+
+/// Find the file in `parent_id` that was renamed to the file with `blob_id` in `commit_id`, among all files that were
+/// deleted in `commit_id`, and return its path and id.
+///
+/// A file with identical content is preferred, otherwise the most similar file is chosen if it has at least half of its lines
+/// in common. The content of `blob_id` is loaded into `data` if needed.
+fn find_rename(
+    odb: &impl gix_object::Find,
+    files: &mut TreeFiles,
+    commit_and_parent: (ObjectId, ObjectId),
+    (blob_id, data): (ObjectId, &mut Option<Vec<u8>>),
+    algorithm: Algorithm,
+    (buf, buf2): (&mut Vec<u8>, &mut Vec<u8>),
+    statistics: &mut Statistics,
+) -> Result<Option<(BString, ObjectId)>, Error> {
+    let deleted = files.deleted(odb, commit_and_parent, buf, statistics)?;
+    if let Some(identical) = deleted.iter().find(|(_, id)| *id == blob_id) {
+        return Ok(Some(identical.clone()));
+    }
+    if deleted.is_empty() {
+        return Ok(None);
+    }
+
+    let data = load_blob(data, odb, &blob_id, buf)?;
+    let mut best = None;
+    for (path, id) in deleted {
+        let candidate = odb.find_blob(&id, buf2)?.data;
+        statistics.blobs_diffed += 1;
+        let input = interned_lines(candidate, data, false);
+        let common: u32 = unchanged_segments(algorithm, &input)
+            .iter()
+            .map(|segment| segment.len)
+            .sum();
+        let num_lines = input.before.len().max(input.after.len()) as u32;
+        if common * 2 >= num_lines && best.as_ref().map_or(true, |(best_common, _)| common > *best_common) {
+            best = Some((common, (path, id)));
+        }
+    }
+    Ok(best.map(|(_, source)| source))
+}
+
+/// Return the commit time of `commit_id` along with the id and mode of the entry at `file_path` in its tree, if present.
+pub(crate) fn commit_time_and_entry(
+    odb: &impl gix_object::Find,
+    commit_id: &gix_hash::oid,
+    file_path: &BStr,
+    buf: &mut Vec<u8>,
+    statistics: &mut Statistics,
+) -> Result<
+    (
+        gix_date::SecondsSinceUnixEpoch,
+        Option<(ObjectId, gix_object::tree::EntryMode)>,
+    ),
+    Error,
+> {
+    let mut commit = odb.find_commit_iter(commit_id, buf)?;
+    let time = commit.committer()?.time.seconds;
+    let mut tree_id = commit.tree_id()?;
+
+    let mut components = file_path.split(|b| *b == b'/').peekable();
+    while let Some(component) = components.next() {
+        statistics.trees_decoded += 1;
+        let Some(entry) = odb
+            .find_tree_iter(&tree_id, buf)?
+            .filter_map(Result::ok)
+            .find(|entry| entry.filename.as_bytes() == component)
+        else {
+            break;
+        };
+        if components.peek().is_none() {
+            return Ok((time, Some((entry.oid.to_owned(), entry.mode))));
+        }
+        if !entry.mode.is_tree() {
+            break;
+        }
+        tree_id = entry.oid.to_owned();
+    }
+    Ok((time, None))
+}
+
+/// Intern the lines of `before` and `after`, possibly ignoring all whitespace in them.
+fn interned_lines<'a>(before: &'a [u8], after: &'a [u8], ignore_whitespace: bool) -> InternedInput<Cow<'a, [u8]>> {
+    let lines = |data: &'a [u8]| {
+        data.lines_with_terminator().map(move |line| {
+            if ignore_whitespace {
+                Cow::Owned(line.iter().copied().filter(|b| !b.is_ascii_whitespace()).collect())
+            } else {
+                Cow::Borrowed(line)
+            }
+        })
+    };
+    let mut input = InternedInput::default();
+    input.update_before(lines(before));
+    input.update_after(lines(after));
+    input
+}
+
+/// Return all ranges of lines that are unchanged between the `before` (parent) and `after` (suspect) lines in `input`, in order.
+fn unchanged_segments(algorithm: Algorithm, input: &InternedInput<Cow<'_, [u8]>>) -> Vec<UnchangedSegment> {
+    let mut segments = Vec::new();
+    let (mut pos_in_parent, mut pos_in_suspect) = (0, 0);
+    gix_diff::blob::diff(algorithm, input, |before: Range<u32>, after: Range<u32>| {
+        if after.start > pos_in_suspect {
+            segments.push(UnchangedSegment {
+                start_in_suspect: pos_in_suspect,
+                start_in_parent: pos_in_parent,
+                len: after.start - pos_in_suspect,
+            });
+        }
+        pos_in_parent = before.end;
+        pos_in_suspect = after.end;
+    });
+    let num_lines = input.after.len() as u32;
+    if num_lines > pos_in_suspect {
+        segments.push(UnchangedSegment {
+            start_in_suspect: pos_in_suspect,
+            start_in_parent: pos_in_parent,
+            len: num_lines - pos_in_suspect,
+        });
+    }
+    segments
+}
+
+/// Split `hunks` into the parts that can be found anywhere in the `before` lines of `input`, translated into the line numbers
+/// of `before`, and the parts that remain with the suspect, whose `data` corresponds to the `after` lines.
+/// Only blocks of lines with at least `min_score` alphanumeric characters are considered found, similar to what `git` does.
+fn split_moved_lines(
+    hunks: Vec<UnblamedHunk>,
+    input: &InternedInput<Cow<'_, [u8]>>,
+    data: &[u8],
+    min_score: u32,
+) -> (Vec<UnblamedHunk>, Vec<UnblamedHunk>) {
+    let mut positions: HashMap<Token, Vec<u32>> = HashMap::new();
+    for (pos, token) in input.before.iter().enumerate() {
+        positions.entry(*token).or_default().push(pos as u32);
+    }
+    let lines: Vec<_> = data.lines_with_terminator().collect();
+    let score = |range: Range<u32>| -> u32 {
+        lines[range.start as usize..range.end as usize]
+            .iter()
+            .map(|line| line.iter().filter(|b| b.is_ascii_alphanumeric()).count() as u32)
+            .sum()
+    };
+
+    let mut passed = Vec::new();
+    let mut kept = Vec::new();
+    for hunk in hunks {
+        let mut segments = Vec::new();
+        let Range { mut start, end } = hunk.range_in_suspect.clone();
+        while start < end {
+            let best = positions
+                .get(&input.after[start as usize])
+                .into_iter()
+                .flatten()
+                .map(|&start_in_parent| {
+                    let len = (0..end - start)
+                        .take_while(|offset| {
+                            input.before.get((start_in_parent + offset) as usize)
+                                == Some(&input.after[(start + offset) as usize])
+                        })
+                        .count() as u32;
+                    (start_in_parent, len)
+                })
+                .max_by_key(|(start_in_parent, len)| (*len, std::cmp::Reverse(*start_in_parent)));
+            match best {
+                Some((start_in_parent, len)) if score(start..start + len) >= min_score => {
+                    segments.push(UnchangedSegment {
+                        start_in_suspect: start,
+                        start_in_parent,
+                        len,
+                    });
+                    start += len;
+                }
+                _ => start += 1,
+            }
+        }
+        let (moved, remaining) = split_hunks(vec![hunk], &segments);
+        passed.extend(moved);
+        kept.extend(remaining);
+    }
+    (passed, kept)
+}
+
+/// Split `hunks` into the parts that are covered by `unchanged` segments, translated into the line numbers of the parent,
+/// and the parts that remain with the suspect.
+fn split_hunks(hunks: Vec<UnblamedHunk>, unchanged: &[UnchangedSegment]) -> (Vec<UnblamedHunk>, Vec<UnblamedHunk>) {
+    let mut passed = Vec::new();
+    let mut kept = Vec::new();
+    for hunk in hunks {
+        let Range { mut start, end } = hunk.range_in_suspect.clone();
+        for segment in unchanged {
+            let overlap_start = segment.start_in_suspect.max(start);
+            let overlap_end = (segment.start_in_suspect + segment.len).min(end);
+            if overlap_start >= overlap_end {
+                continue;
+            }
+            if overlap_start > start {
+                kept.push(hunk.sub_hunk(start..overlap_start));
+            }
+            let mut sub_hunk = hunk.sub_hunk(overlap_start..overlap_end);
+            let start_in_parent = overlap_start - segment.start_in_suspect + segment.start_in_parent;
+            sub_hunk.range_in_suspect = start_in_parent..start_in_parent + (overlap_end - overlap_start);
+            passed.push(sub_hunk);
+            start = overlap_end;
+        }
+        if start < end {
+            kept.push(hunk.sub_hunk(start..end));
+        }
+    }
+    (passed, kept)
+}
+
+/// Sort `entries` by their position in the blamed file and merge adjacent ones that are also adjacent in their source.
+fn coalesce(mut entries: Vec<BlameEntry>) -> Vec<BlameEntry> {
+    entries.sort_by_key(|entry| entry.start_in_blamed_file);
+    let mut out: Vec<BlameEntry> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        match out.last_mut() {
+            Some(previous)
+                if previous.commit_id == entry.commit_id
+                    && previous.source_file_name == entry.source_file_name
+                    && previous.range_in_blamed_file().end == entry.start_in_blamed_file
+                    && previous.range_in_source_file().end == entry.start_in_source_file =>
+            {
+                previous.len = previous.len.saturating_add(entry.len.get());
+            }
+            _ => out.push(entry),
+        }
+    }
+    out
+}
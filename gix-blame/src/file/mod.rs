@@ -0,0 +1,64 @@
+use gix_hash::ObjectId;
+
+pub use gix_diff::blob::Algorithm;
+
+pub(crate) mod function;
+
+/// The minimum score of a block of moved lines as used by `git blame -M`, see [`Options::detect_moves`].
+pub const DEFAULT_MOVE_SCORE: u32 = 20;
+/// The minimum score of a block of copied lines as used by `git blame -C`, see [`Options::detect_copies`].
+pub const DEFAULT_COPY_SCORE: u32 = 40;
+
+/// Options for use in [`file()`](crate::file()).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The algorithm to use when diffing the file against its version in parent commits.
+    pub algorithm: Algorithm,
+    /// If `true`, whitespace is ignored when comparing lines, so lines that only changed their indentation
+    /// keep their original attribution, similar to `git blame -w`.
+    pub ignore_whitespace: bool,
+    /// If `true`, the file is followed into parents in which it had a different name, similar to what `git blame` does by default.
+    ///
+    /// The previous name is found among the files that were deleted by the commit that added the file, preferring one with the
+    /// same content, and otherwise picking the most similar one that has at least half of its lines in common.
+    pub follow_renames: bool,
+    /// If set, lines that were moved within the file are attributed to the commit that introduced them, similar to `git blame -M`.
+    ///
+    /// The value is the minimum amount of alphanumeric characters a block of moved lines must have to be detected as moved,
+    /// with [`DEFAULT_MOVE_SCORE`] being what `git` uses.
+    pub detect_moves: Option<u32>,
+    /// If set, lines that were moved or copied from other files that were changed in the same commit are attributed to the commit
+    /// that introduced them in the other file, similar to `git blame -C`.
+    ///
+    /// The value is the minimum amount of alphanumeric characters a block of copied lines must have to be detected as copy,
+    /// with [`DEFAULT_COPY_SCORE`] being what `git` uses.
+    pub detect_copies: Option<u32>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            algorithm: Algorithm::Myers,
+            ignore_whitespace: false,
+            follow_renames: true,
+            detect_moves: None,
+            detect_copies: None,
+        }
+    }
+}
+
+/// The error returned by [`file()`](crate::file()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The file '{file_path}' does not exist in commit {commit}")]
+    FileNotFound { file_path: bstr::BString, commit: ObjectId },
+    #[error("The entry at '{file_path}' in commit {commit} is not a file")]
+    NotAFile { file_path: bstr::BString, commit: ObjectId },
+    #[error(transparent)]
+    FindObject(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    FindObjectIter(#[from] gix_object::find::existing_iter::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+}
@@ -0,0 +1,247 @@
+use std::{
+    collections::{hash_map, BinaryHeap, HashMap},
+    ops::Range,
+};
+
+use bstr::{BStr, ByteSlice};
+use gix_diff::blob::intern::InternedInput;
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+
+use crate::{
+    file,
+    file::{function::commit_time_and_entry, Algorithm},
+    history::{Entry, Error, Hunk, Options, Outcome},
+    Statistics,
+};
+
+/// Follow the zero-based, end-exclusive line `ranges` of the file at `file_path` as it is in `commit` back through history,
+/// and return all commits that changed any of these lines, with all objects being looked up in `odb`.
+///
+/// Commits are visited from newest to oldest by their commit time, and the ranges are adjusted to the version of the file
+/// in each parent, similar to `git log -L`. Lines that were changed by a commit are tracked further as the lines they replaced,
+/// and lines that were added by it stop being tracked.
+/// If the tracked lines are the same as in one of the parents of a merge, they are only followed into that parent, and the
+/// merge isn't listed. Otherwise, the changes of a merge are computed against its first parent that has the file.
+///
+/// Note that renames are not followed, and that the file is compared as it is stored, without applying any filters.
+pub fn history(
+    odb: impl gix_object::Find,
+    commit: ObjectId,
+    file_path: &BStr,
+    ranges: &[Range<u32>],
+    options: Options,
+) -> Result<Outcome, Error> {
+    let mut statistics = Statistics::default();
+    let mut buf = Vec::new();
+    let mut buf2 = Vec::new();
+
+    let (commit_time, entry) = commit_time_and_entry(&odb, &commit, file_path, &mut buf, &mut statistics)?;
+    let (blob_id, mode) = entry.ok_or_else(|| file::Error::FileNotFound {
+        file_path: file_path.to_owned(),
+        commit,
+    })?;
+    if !mode.is_blob_or_symlink() {
+        return Err(file::Error::NotAFile {
+            file_path: file_path.to_owned(),
+            commit,
+        }
+        .into());
+    }
+    let num_lines = odb.find_blob(&blob_id, &mut buf)?.data.lines_with_terminator().count() as u32;
+    if let Some(range) = ranges
+        .iter()
+        .find(|range| range.start >= range.end || range.end > num_lines)
+    {
+        return Err(Error::InvalidRange {
+            range: range.clone(),
+            num_lines,
+        });
+    }
+
+    let mut entries = Vec::new();
+    let mut tracked = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    if !ranges.is_empty() {
+        tracked.insert(
+            commit,
+            Tracked {
+                blob_id,
+                ranges: normalize(ranges.to_vec()),
+            },
+        );
+        queue.push((commit_time, commit));
+    }
+
+    while let Some((_, commit_id)) = queue.pop() {
+        let Some(Tracked { blob_id, ranges }) = tracked.remove(&commit_id) else {
+            continue;
+        };
+        statistics.commits_traversed += 1;
+
+        let parent_ids: Vec<_> = odb.find_commit_iter(&commit_id, &mut buf)?.parent_ids().collect();
+        let mut parents = Vec::with_capacity(parent_ids.len());
+        for parent_id in parent_ids {
+            let (time, entry) = commit_time_and_entry(&odb, &parent_id, file_path, &mut buf, &mut statistics)?;
+            if let Some((parent_blob_id, _)) = entry.filter(|(_, mode)| mode.is_blob_or_symlink()) {
+                parents.push((parent_id, time, parent_blob_id));
+            }
+        }
+
+        if let Some((parent_id, time, parent_blob_id)) = parents.iter().find(|(_, _, id)| *id == blob_id) {
+            track(&mut tracked, &mut queue, *parent_id, *time, *parent_blob_id, ranges);
+            continue;
+        }
+
+        let data = odb.find_blob(&blob_id, &mut buf)?.data.to_vec();
+        if parents.is_empty() {
+            entries.push(Entry {
+                commit_id,
+                blob_id,
+                parent: None,
+                ranges,
+                hunks: vec![Hunk {
+                    before: 0..0,
+                    after: 0..data.lines_with_terminator().count() as u32,
+                }],
+            });
+            continue;
+        }
+
+        let mut diffs = Vec::with_capacity(parents.len());
+        for (parent_id, time, parent_blob_id) in parents {
+            let parent_data = odb.find_blob(&parent_blob_id, &mut buf2)?.data;
+            statistics.blobs_diffed += 1;
+            let hunks = changes(options.algorithm, parent_data, &data);
+            let parent_ranges = map_to_parent(&ranges, &hunks);
+            let touched: Vec<_> = hunks
+                .into_iter()
+                .filter(|hunk| ranges.iter().any(|range| overlaps(&hunk.after, range)))
+                .collect();
+            diffs.push((parent_id, time, parent_blob_id, touched, parent_ranges));
+        }
+
+        if let Some(position) = diffs.iter().position(|(_, _, _, touched, _)| touched.is_empty()) {
+            let (parent_id, time, parent_blob_id, _, parent_ranges) = diffs.swap_remove(position);
+            track(&mut tracked, &mut queue, parent_id, time, parent_blob_id, parent_ranges);
+            continue;
+        }
+
+        let (first_parent_id, _, first_parent_blob_id, first_touched, _) = &diffs[0];
+        entries.push(Entry {
+            commit_id,
+            blob_id,
+            parent: Some((*first_parent_id, *first_parent_blob_id)),
+            ranges,
+            hunks: first_touched.clone(),
+        });
+        for (parent_id, time, parent_blob_id, _, parent_ranges) in diffs {
+            track(&mut tracked, &mut queue, parent_id, time, parent_blob_id, parent_ranges);
+        }
+    }
+
+    Ok(Outcome { entries, statistics })
+}
+
+/// A commit along with the lines that are tracked in its version of the file.
+struct Tracked {
+    /// The id of the file in the commit.
+    blob_id: ObjectId,
+    ranges: Vec<Range<u32>>,
+}
+
+fn track(
+    tracked: &mut HashMap<ObjectId, Tracked>,
+    queue: &mut BinaryHeap<(gix_date::SecondsSinceUnixEpoch, ObjectId)>,
+    commit_id: ObjectId,
+    time: gix_date::SecondsSinceUnixEpoch,
+    blob_id: ObjectId,
+    ranges: Vec<Range<u32>>,
+) {
+    if ranges.is_empty() {
+        return;
+    }
+    match tracked.entry(commit_id) {
+        hash_map::Entry::Occupied(mut entry) => {
+            let existing = &mut entry.get_mut().ranges;
+            existing.extend(ranges);
+            *existing = normalize(std::mem::take(existing));
+        }
+        hash_map::Entry::Vacant(entry) => {
+            entry.insert(Tracked { blob_id, ranges });
+            queue.push((time, commit_id));
+        }
+    }
+}
+
+/// Return all changes between `parent` and `data`, in order.
+fn changes(algorithm: Algorithm, parent: &[u8], data: &[u8]) -> Vec<Hunk> {
+    let input = InternedInput::new(
+        gix_diff::blob::sources::byte_lines_with_terminator(parent),
+        gix_diff::blob::sources::byte_lines_with_terminator(data),
+    );
+    let mut hunks = Vec::new();
+    gix_diff::blob::diff(algorithm, &input, |before: Range<u32>, after: Range<u32>| {
+        hunks.push(Hunk { before, after });
+    });
+    hunks
+}
+
+/// Return `true` if the lines changed in `after` overlap with `range`.
+///
+/// Lines that were only removed count if they were removed from within `range`, but not if they were removed at its boundaries.
+fn overlaps(after: &Range<u32>, range: &Range<u32>) -> bool {
+    !(after.end <= range.start || range.end <= after.start)
+}
+
+/// Translate `ranges` into the line numbers of the parent with the given `hunks` of changes.
+///
+/// Unchanged lines are shifted to their position in the parent, and changes that touch one of `ranges` contribute the
+/// lines they replaced.
+fn map_to_parent(ranges: &[Range<u32>], hunks: &[Hunk]) -> Vec<Range<u32>> {
+    let mut out = Vec::new();
+    for range in ranges {
+        let mut start = range.start;
+        // The amount of lines to subtract from a line in the commit to get its position in the parent.
+        let mut shift = 0i64;
+        for hunk in hunks {
+            if hunk.after.start >= range.end {
+                break;
+            }
+            let delta = hunk.after.len() as i64 - hunk.before.len() as i64;
+            if !overlaps(&hunk.after, range) {
+                shift += delta;
+                continue;
+            }
+            if hunk.after.start > start {
+                out.push(shifted(start..hunk.after.start, shift));
+            }
+            if !hunk.before.is_empty() {
+                out.push(hunk.before.clone());
+            }
+            shift += delta;
+            start = hunk.after.end.max(start);
+        }
+        if start < range.end {
+            out.push(shifted(start..range.end, shift));
+        }
+    }
+    normalize(out)
+}
+
+fn shifted(range: Range<u32>, shift: i64) -> Range<u32> {
+    (range.start as i64 - shift) as u32..(range.end as i64 - shift) as u32
+}
+
+/// Sort `ranges` and merge those that overlap or are adjacent.
+fn normalize(mut ranges: Vec<Range<u32>>) -> Vec<Range<u32>> {
+    ranges.sort_by_key(|range| range.start);
+    let mut out: Vec<Range<u32>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match out.last_mut() {
+            Some(previous) if range.start <= previous.end => previous.end = previous.end.max(range.end),
+            _ => out.push(range),
+        }
+    }
+    out
+}
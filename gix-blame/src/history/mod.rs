@@ -0,0 +1,70 @@
+use std::ops::Range;
+
+use gix_hash::ObjectId;
+
+use crate::file::Algorithm;
+
+pub(crate) mod function;
+
+/// Options for use in [`history()`](crate::history()).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The algorithm to use when diffing the file against its version in parent commits.
+    pub algorithm: Algorithm,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            algorithm: Algorithm::Myers,
+        }
+    }
+}
+
+/// The error returned by [`history()`](crate::history()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The line range {}..{} is invalid as the file has only {num_lines} line(s)", range.start + 1, range.end)]
+    InvalidRange { range: Range<u32>, num_lines: u32 },
+    #[error(transparent)]
+    File(#[from] crate::file::Error),
+    #[error(transparent)]
+    FindObject(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    FindObjectIter(#[from] gix_object::find::existing_iter::Error),
+}
+
+/// A change of lines between the version of a file in a parent commit and the version in the commit itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hunk {
+    /// The zero-based range of lines in the file as it is in the parent, which is empty if lines were only added.
+    pub before: Range<u32>,
+    /// The zero-based range of lines in the file as it is in the commit, which is empty if lines were only removed.
+    pub after: Range<u32>,
+}
+
+/// A commit that changed lines in one of the tracked ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The commit that changed the lines.
+    pub commit_id: ObjectId,
+    /// The id of the file in [`commit_id`](Self::commit_id).
+    pub blob_id: ObjectId,
+    /// The parent the changes were computed against along with the id of the file in it, or `None` if the file was
+    /// added in [`commit_id`](Self::commit_id).
+    pub parent: Option<(ObjectId, ObjectId)>,
+    /// The zero-based, sorted and non-overlapping ranges of tracked lines in the file as it is in [`commit_id`](Self::commit_id).
+    pub ranges: Vec<Range<u32>>,
+    /// All changes compared to the parent that touch one of [`ranges`](Self::ranges), in order.
+    pub hunks: Vec<Hunk>,
+}
+
+/// The outcome of [`history()`](crate::history()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// All commits that changed the tracked lines, from the newest to the oldest by commit time.
+    pub entries: Vec<Entry>,
+    /// Additional information about the work that was performed.
+    pub statistics: crate::Statistics,
+}
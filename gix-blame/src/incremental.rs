@@ -0,0 +1,39 @@
+use crate::{BlameEntry, Statistics};
+
+/// Tell [`incremental()`](crate::incremental()) what to do after an entry was received.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Continue blaming the remaining lines.
+    Continue,
+    /// Stop blaming, leaving the remaining lines unattributed.
+    Cancel,
+}
+
+/// A way to receive blame entries as soon as they are final, for use in [`incremental()`](crate::incremental()).
+pub trait Delegate {
+    /// Receive `entry`, which won't change anymore, and decide whether to continue.
+    ///
+    /// Entries are received in the order their commits are attributed, i.e. roughly from newest to oldest, and adjacent
+    /// entries of the same commit aren't merged.
+    fn entry(&mut self, entry: BlameEntry) -> Action;
+}
+
+impl<F> Delegate for F
+where
+    F: FnMut(BlameEntry) -> Action,
+{
+    fn entry(&mut self, entry: BlameEntry) -> Action {
+        self(entry)
+    }
+}
+
+/// The outcome of [`incremental()`](crate::incremental()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The content of the blamed file.
+    pub blob: Vec<u8>,
+    /// Additional information about the work that was performed.
+    pub statistics: Statistics,
+    /// If `true`, the delegate cancelled the operation and not all lines of the file were attributed.
+    pub cancelled: bool,
+}
@@ -0,0 +1,91 @@
+//! Assign each line of a file to the commit that introduced it, similar to `git blame`.
+//!
+//! Starting at a given commit, lines are passed on to the parents of each commit for as long as they remain unchanged,
+//! and are attributed to the first commit in which they were changed. Merge commits pass lines on to all of their
+//! parents, in order, so that a line is attributed to the side of the merge that introduced it.
+//!
+//! With [`incremental()`], entries are received as soon as they are final, similar to `git blame --incremental`, which is useful
+//! to show results for large files early.
+//!
+//! With [`history()`], ranges of lines can also be followed back through history to find all commits that changed them,
+//! similar to `git log -L`.
+#![deny(missing_docs, rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+use std::num::NonZeroU32;
+
+use gix_hash::ObjectId;
+
+///
+pub mod file;
+pub use file::function::file;
+///
+pub mod incremental;
+pub use file::function::incremental;
+///
+pub mod history;
+pub use history::function::history;
+
+/// A range of lines in the blamed file that were introduced by the same commit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlameEntry {
+    /// The zero-based index of the first line of this entry in the file that was blamed.
+    pub start_in_blamed_file: u32,
+    /// The zero-based index of the first line of this entry in the file as it was in `commit_id`.
+    pub start_in_source_file: u32,
+    /// The amount of lines this entry spans.
+    pub len: NonZeroU32,
+    /// The commit that introduced the lines of this entry.
+    pub commit_id: ObjectId,
+    /// The path of the file the lines are in as of [`commit_id`](Self::commit_id), or `None` if it's the blamed file.
+    ///
+    /// It's only set if lines were followed through a rename, or were detected as moved or copied from another file.
+    pub source_file_name: Option<bstr::BString>,
+}
+
+impl BlameEntry {
+    /// Return the range of lines in the blamed file covered by this entry.
+    pub fn range_in_blamed_file(&self) -> std::ops::Range<u32> {
+        self.start_in_blamed_file..self.start_in_blamed_file + self.len.get()
+    }
+
+    /// Return the range of lines in the file as it was in [`commit_id`](Self::commit_id) covered by this entry.
+    pub fn range_in_source_file(&self) -> std::ops::Range<u32> {
+        self.start_in_source_file..self.start_in_source_file + self.len.get()
+    }
+}
+
+/// The outcome of [`file()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// One entry per range of lines introduced by the same commit, sorted by their position in the blamed file.
+    ///
+    /// All lines of the blamed file are covered, without overlap.
+    pub entries: Vec<BlameEntry>,
+    /// The content of the blamed file.
+    pub blob: Vec<u8>,
+    /// Additional information about the work that was performed.
+    pub statistics: Statistics,
+}
+
+impl Outcome {
+    /// Return an iterator over each entry along with the lines of the blamed file it covers, including their line terminators.
+    pub fn entries_with_lines(&self) -> impl Iterator<Item = (&BlameEntry, Vec<&bstr::BStr>)> + '_ {
+        use bstr::ByteSlice;
+        let mut lines = self.blob.lines_with_terminator().map(ByteSlice::as_bstr);
+        self.entries
+            .iter()
+            .map(move |entry| (entry, lines.by_ref().take(entry.len.get() as usize).collect()))
+    }
+}
+
+/// Statistics gathered while blaming a file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Statistics {
+    /// The amount of commits that were looked at.
+    pub commits_traversed: usize,
+    /// The amount of trees that were decoded to find the blamed file.
+    pub trees_decoded: usize,
+    /// The amount of times the file was diffed against its version in a parent commit.
+    pub blobs_diffed: usize,
+}
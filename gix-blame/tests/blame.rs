@@ -0,0 +1,383 @@
+use std::ops::Range;
+
+use gix_blame::BlameEntry;
+use gix_hash::ObjectId;
+use gix_object::{bstr::ByteSlice, FindExt};
+use gix_testtools::Result;
+
+struct Fixture {
+    odb: gix_odb::Handle,
+    dir: std::path::PathBuf,
+}
+
+impl Fixture {
+    fn new() -> Result<Self> {
+        Self::from_script("make_blame_repo.sh")
+    }
+
+    fn moves() -> Result<Self> {
+        Self::from_script("make_blame_moves_repo.sh")
+    }
+
+    fn from_script(name: &str) -> Result<Self> {
+        let dir = gix_testtools::scripted_fixture_read_only(name)?;
+        let odb = gix_odb::at(dir.join(".git").join("objects"))?;
+        Ok(Fixture { odb, dir })
+    }
+
+    fn head_id(&self) -> Result<ObjectId> {
+        let hex = std::fs::read(self.dir.join(".git").join("refs").join("heads").join("main"))?;
+        Ok(ObjectId::from_hex(hex.trim_end())?)
+    }
+
+    fn blame(&self, path: &str) -> Result<gix_blame::Outcome> {
+        self.blame_with_options(path, Default::default())
+    }
+
+    fn blame_with_options(&self, path: &str, options: gix_blame::file::Options) -> Result<gix_blame::Outcome> {
+        Ok(gix_blame::file(&self.odb, self.head_id()?, path.into(), options)?)
+    }
+
+    /// Return `(commit, ranges, hunks as (before, after))` for each entry of the history of `ranges` in the file at `path`.
+    #[allow(clippy::type_complexity)]
+    fn history(
+        &self,
+        path: &str,
+        ranges: &[Range<u32>],
+    ) -> Result<Vec<(String, Vec<Range<u32>>, Vec<(Range<u32>, Range<u32>)>)>> {
+        let outcome = gix_blame::history(&self.odb, self.head_id()?, path.into(), ranges, Default::default())?;
+        outcome
+            .entries
+            .into_iter()
+            .map(|e| {
+                Ok((
+                    self.subject_of(e.commit_id)?,
+                    e.ranges,
+                    e.hunks.into_iter().map(|h| (h.before, h.after)).collect(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Return `(commit, start_in_blamed_file, start_in_source_file, len)` for each entry.
+    fn simplified(&self, entries: &[BlameEntry]) -> Result<Vec<(String, u32, u32, u32)>> {
+        entries
+            .iter()
+            .map(|e| {
+                Ok((
+                    self.subject_of(e.commit_id)?,
+                    e.start_in_blamed_file,
+                    e.start_in_source_file,
+                    e.len.get(),
+                ))
+            })
+            .collect()
+    }
+
+    fn subject_of(&self, id: ObjectId) -> Result<String> {
+        let mut buf = Vec::new();
+        let commit = self.odb.find_commit(&id, &mut buf)?;
+        Ok(commit.message_summary().to_string())
+    }
+}
+
+#[test]
+fn lines_are_attributed_through_merges() -> Result {
+    let fixture = Fixture::new()?;
+    let outcome = fixture.blame("file")?;
+    assert_eq!(
+        fixture.simplified(&outcome.entries)?,
+        [
+            ("c4".into(), 0, 0, 1),
+            ("c1".into(), 1, 0, 1),
+            ("c2".into(), 2, 1, 1),
+            ("c1".into(), 3, 2, 1),
+            ("c3-side".into(), 4, 3, 1),
+        ]
+    );
+    assert_eq!(outcome.blob, b"0\na\nB\nc\nd\n");
+    let lines: Vec<_> = outcome.entries_with_lines().map(|(_, lines)| lines.len()).collect();
+    assert_eq!(lines, [1, 1, 1, 1, 1]);
+    assert_eq!(
+        outcome.statistics.commits_traversed, 6,
+        "the last commit doesn't touch the file and thus isn't blamed, but it's still traversed"
+    );
+    Ok(())
+}
+
+#[test]
+fn nested_files_are_found() -> Result {
+    let fixture = Fixture::new()?;
+    let outcome = fixture.blame("dir/nested")?;
+    assert_eq!(
+        fixture.simplified(&outcome.entries)?,
+        [("c1".into(), 0, 0, 1), ("c4".into(), 1, 1, 1)]
+    );
+    Ok(())
+}
+
+#[test]
+fn files_added_in_a_commit_are_blamed_on_it_entirely() -> Result {
+    let fixture = Fixture::new()?;
+    let outcome = fixture.blame("added")?;
+    assert_eq!(fixture.simplified(&outcome.entries)?, [("c6".into(), 0, 0, 1)]);
+    Ok(())
+}
+
+#[test]
+fn empty_files_have_no_entries() -> Result {
+    let fixture = Fixture::new()?;
+    let outcome = fixture.blame("empty")?;
+    assert!(outcome.entries.is_empty());
+    assert!(outcome.blob.is_empty());
+    Ok(())
+}
+
+#[test]
+fn missing_files_and_directories_are_an_error() -> Result {
+    let fixture = Fixture::new()?;
+    assert!(matches!(
+        fixture.blame("does-not-exist"),
+        Err(err) if err.to_string().starts_with("The file 'does-not-exist' does not exist in commit")
+    ));
+    assert!(matches!(
+        fixture.blame("dir"),
+        Err(err) if err.to_string().starts_with("The entry at 'dir' in commit")
+    ));
+    Ok(())
+}
+
+mod incremental {
+    use gix_blame::incremental::Action;
+
+    use crate::Fixture;
+
+    #[test]
+    fn entries_are_received_from_the_newest_to_the_oldest_commit() -> crate::Result {
+        let fixture = Fixture::new()?;
+        let mut entries = Vec::new();
+        let outcome = gix_blame::incremental(
+            &fixture.odb,
+            fixture.head_id()?,
+            "file".into(),
+            Default::default(),
+            &mut |entry| {
+                entries.push(entry);
+                Action::Continue
+            },
+        )?;
+        assert!(!outcome.cancelled);
+        assert_eq!(outcome.blob, b"0\na\nB\nc\nd\n");
+        assert_eq!(
+            fixture.simplified(&entries)?,
+            [
+                ("c4".into(), 0, 0, 1),
+                ("c3-side".into(), 4, 3, 1),
+                ("c2".into(), 2, 1, 1),
+                ("c1".into(), 1, 0, 1),
+                ("c1".into(), 3, 2, 1),
+            ]
+        );
+
+        entries.sort_by_key(|entry| entry.start_in_blamed_file);
+        assert_eq!(
+            entries,
+            fixture.blame("file")?.entries,
+            "the entries are the same as the ones of a full blame, which merges only adjacent ones"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn the_delegate_can_cancel() -> crate::Result {
+        let fixture = Fixture::new()?;
+        let mut entries = Vec::new();
+        let outcome = gix_blame::incremental(
+            &fixture.odb,
+            fixture.head_id()?,
+            "file".into(),
+            Default::default(),
+            &mut |entry| {
+                entries.push(entry);
+                Action::Cancel
+            },
+        )?;
+        assert!(outcome.cancelled);
+        assert_eq!(fixture.simplified(&entries)?, [("c4".into(), 0, 0, 1)]);
+        Ok(())
+    }
+}
+
+mod moves_and_renames {
+    use gix_blame::file::{Options, DEFAULT_COPY_SCORE, DEFAULT_MOVE_SCORE};
+
+    use crate::Fixture;
+
+    #[test]
+    fn whitespace_changes_can_be_ignored() -> crate::Result {
+        let fixture = Fixture::moves()?;
+        let outcome = fixture.blame_with_options(
+            "code",
+            Options {
+                ignore_whitespace: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            fixture.simplified(&outcome.entries)?,
+            [
+                ("c1".into(), 0, 3, 2),
+                ("c3-move".into(), 2, 2, 3),
+                ("c1".into(), 5, 5, 1),
+                ("c4-copy".into(), 6, 6, 2),
+            ],
+            "the re-indented line is attributed to the commit that moved it, but not to the one that re-indented it"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn moved_lines_keep_their_attribution() -> crate::Result {
+        let fixture = Fixture::moves()?;
+        let outcome = fixture.blame_with_options(
+            "code",
+            Options {
+                detect_moves: Some(DEFAULT_MOVE_SCORE),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            fixture.simplified(&outcome.entries)?,
+            [
+                ("c1".into(), 0, 3, 2),
+                ("c3-move".into(), 2, 2, 1),
+                ("c1".into(), 3, 0, 1),
+                ("c2-reindent".into(), 4, 1, 1),
+                ("c1".into(), 5, 5, 1),
+                ("c4-copy".into(), 6, 6, 2),
+            ],
+            "a lone closing brace doesn't have enough alphanumeric characters to be detected as moved"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn copied_lines_are_attributed_to_their_source_file() -> crate::Result {
+        let fixture = Fixture::moves()?;
+        let outcome = fixture.blame_with_options(
+            "code",
+            Options {
+                ignore_whitespace: true,
+                detect_moves: Some(DEFAULT_MOVE_SCORE),
+                detect_copies: Some(DEFAULT_COPY_SCORE),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            fixture.simplified(&outcome.entries)?,
+            [
+                ("c1".into(), 0, 3, 2),
+                ("c3-move".into(), 2, 2, 1),
+                ("c1".into(), 3, 0, 2),
+                ("c1".into(), 5, 5, 1),
+                ("c1".into(), 6, 0, 2),
+            ]
+        );
+        let source_file_names: Vec<_> = outcome
+            .entries
+            .iter()
+            .map(|e| e.source_file_name.as_ref().map(ToString::to_string))
+            .collect();
+        assert_eq!(source_file_names, [None, None, None, None, Some("source".into())]);
+        Ok(())
+    }
+
+    #[test]
+    fn renames_are_followed_unless_disabled() -> crate::Result {
+        let fixture = Fixture::moves()?;
+        let outcome = fixture.blame("newer-name")?;
+        assert_eq!(
+            fixture.simplified(&outcome.entries)?,
+            [("c1".into(), 0, 0, 2), ("c6-rename-and-change".into(), 2, 2, 1)],
+            "the file is followed through a rename with changes, and an exact rename"
+        );
+        assert_eq!(outcome.entries[0].source_file_name.as_ref().unwrap(), "old-name");
+        assert_eq!(outcome.entries[1].source_file_name, None);
+
+        let outcome = fixture.blame_with_options(
+            "newer-name",
+            Options {
+                follow_renames: false,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            fixture.simplified(&outcome.entries)?,
+            [("c6-rename-and-change".into(), 0, 0, 3)]
+        );
+        Ok(())
+    }
+}
+
+mod history {
+    use std::ops::Range;
+
+    use crate::Fixture;
+
+    /// Put a single `range` into a `Vec`, which `clippy` would otherwise suspect to be meant as `(start..end).collect()`.
+    fn lines(range: Range<u32>) -> Vec<Range<u32>> {
+        vec![range]
+    }
+
+    #[test]
+    fn changed_lines_are_followed_to_the_root_commit() -> crate::Result {
+        let fixture = Fixture::new()?;
+        assert_eq!(
+            fixture.history("file", &lines(2..3))?,
+            [
+                ("c2".into(), lines(1..2), vec![(1..2, 1..2)]),
+                ("c1".into(), lines(1..2), vec![(0..0, 0..3)]),
+            ],
+            "the merge and c4 don't change the line, but c4 shifts it by one"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn added_lines_stop_being_tracked_and_merges_follow_the_parent_without_changes() -> crate::Result {
+        let fixture = Fixture::new()?;
+        assert_eq!(
+            fixture.history("file", &lines(4..5))?,
+            [("c3-side".into(), lines(3..4), vec![(3..3, 3..4)])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn merges_that_change_lines_compared_to_all_parents_are_listed() -> crate::Result {
+        let fixture = Fixture::new()?;
+        assert_eq!(
+            fixture.history("file", &[0..2, 2..5])?,
+            [
+                ("c5-merge".into(), lines(0..5), vec![(4..4, 4..5)]),
+                ("c4".into(), lines(0..4), vec![(0..0, 0..1)]),
+                ("c3-side".into(), lines(0..4), vec![(3..3, 3..4)]),
+                ("c2".into(), lines(0..3), vec![(1..2, 1..2)]),
+                ("c1".into(), lines(0..3), vec![(0..0, 0..3)]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ranges_must_be_within_the_file() -> crate::Result {
+        let fixture = Fixture::new()?;
+        for range in [0..6, 2..2] {
+            assert!(matches!(
+                fixture.history("file", &[range]),
+                Err(err) if err.to_string().contains("is invalid as the file has only 5 line(s)")
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+function commit() {
+  local date="$1" message="$2"
+  git add -A
+  GIT_AUTHOR_DATE="$date +0000" GIT_COMMITTER_DATE="$date +0000" git commit -q -m "$message"
+}
+
+printf 'fn first() {\n    alpha_beta_gamma_delta();\n}\nfn second() {\n    epsilon_zeta_eta_theta();\n}\n' >code
+printf 'some_long_identifier_one\nsome_long_identifier_two\n' >source
+printf 'renamed_content_line_one\nrenamed_content_line_two\n' >old-name
+commit "2000-01-01 00:00:00" c1
+
+printf 'fn first() {\n        alpha_beta_gamma_delta();\n}\nfn second() {\n    epsilon_zeta_eta_theta();\n}\n' >code
+commit "2000-01-02 00:00:00" c2-reindent
+
+printf 'fn second() {\n    epsilon_zeta_eta_theta();\n}\nfn first() {\n        alpha_beta_gamma_delta();\n}\n' >code
+commit "2000-01-03 00:00:00" c3-move
+
+printf 'fn second() {\n    epsilon_zeta_eta_theta();\n}\nfn first() {\n        alpha_beta_gamma_delta();\n}\nsome_long_identifier_one\nsome_long_identifier_two\n' >code
+printf 'some_long_identifier_one\nsome_long_identifier_two\nchanged\n' >source
+commit "2000-01-04 00:00:00" c4-copy
+
+git mv old-name new-name
+commit "2000-01-05 00:00:00" c5-rename
+
+printf 'renamed_content_line_one\nrenamed_content_line_two\nextra\n' >new-name
+git mv new-name newer-name
+commit "2000-01-06 00:00:00" c6-rename-and-change
@@ -0,0 +1,35 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config merge.ff false
+git checkout -q -b main
+
+function commit() {
+  local date="$1" message="$2"
+  git add -A
+  GIT_AUTHOR_DATE="$date +0000" GIT_COMMITTER_DATE="$date +0000" git commit -q -m "$message"
+}
+
+mkdir dir
+printf 'a\nb\nc\n' >file
+printf 'x\n' >dir/nested
+commit "2000-01-01 00:00:00" c1
+
+printf 'a\nB\nc\n' >file
+commit "2000-01-02 00:00:00" c2
+
+git checkout -q -b side
+printf 'a\nB\nc\nd\n' >file
+commit "2000-01-03 00:00:00" c3-side
+
+git checkout -q main
+printf '0\na\nB\nc\n' >file
+printf 'x\ny\n' >dir/nested
+commit "2000-01-04 00:00:00" c4
+
+GIT_AUTHOR_DATE="2000-01-05 00:00:00 +0000" GIT_COMMITTER_DATE="2000-01-05 00:00:00 +0000" git merge -q side -m c5-merge
+
+printf 'new\n' >added
+: >empty
+commit "2000-01-06 00:00:00" c6
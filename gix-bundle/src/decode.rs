@@ -0,0 +1,111 @@
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::{Capability, Header, Prerequisite, Ref, Version};
+
+/// The error returned by [`Header::from_read()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read the bundle header")]
+    Io(#[from] std::io::Error),
+    #[error("The bundle signature {signature:?} is not supported")]
+    UnsupportedSignature { signature: BString },
+    #[error("The bundle header ended before the empty line separating it from the pack")]
+    UnexpectedEof,
+    #[error("Capabilities like {name:?} are only allowed at the beginning of a version 3 bundle")]
+    UnexpectedCapability { name: BString },
+    #[error("The object format {name:?} is not supported")]
+    UnsupportedObjectFormat { name: BString },
+    #[error("Could not parse the header line {line:?}")]
+    InvalidLine { line: BString },
+    #[error("Could not decode the object id in header line {line:?}")]
+    Id {
+        line: BString,
+        source: gix_hash::decode::Error,
+    },
+}
+
+impl Header {
+    /// Read a bundle header from `read`, leaving it positioned at the beginning of the pack data.
+    pub fn from_read(read: &mut impl std::io::BufRead) -> Result<Self, Error> {
+        let mut buf = Vec::new();
+        read.read_until(b'\n', &mut buf)?;
+        let version = if buf == crate::SIGNATURE_V2 {
+            Version::V2
+        } else if buf == crate::SIGNATURE_V3 {
+            Version::V3
+        } else {
+            return Err(Error::UnsupportedSignature {
+                signature: buf.strip_suffix(b"\n").unwrap_or(&buf).into(),
+            });
+        };
+        let mut header = Header::new(version, gix_hash::Kind::Sha1);
+        let mut may_have_capabilities = version == Version::V3;
+        loop {
+            buf.clear();
+            if read.read_until(b'\n', &mut buf)? == 0 || buf.last() != Some(&b'\n') {
+                return Err(Error::UnexpectedEof);
+            }
+            let line = &buf[..buf.len() - 1];
+            if line.is_empty() {
+                break;
+            }
+            if let Some(capability) = line.strip_prefix(b"@") {
+                let (name, value) = match capability.find_byte(b'=') {
+                    Some(pos) => (&capability[..pos], Some(&capability[pos + 1..])),
+                    None => (capability, None),
+                };
+                if !may_have_capabilities {
+                    return Err(Error::UnexpectedCapability { name: name.into() });
+                }
+                if name == b"object-format" {
+                    header.object_hash = match value {
+                        Some(b"sha1") => gix_hash::Kind::Sha1,
+                        _ => {
+                            return Err(Error::UnsupportedObjectFormat {
+                                name: value.unwrap_or_default().into(),
+                            })
+                        }
+                    };
+                } else {
+                    header.capabilities.push(Capability {
+                        name: name.into(),
+                        value: value.map(Into::into),
+                    });
+                }
+                continue;
+            }
+            may_have_capabilities = false;
+            if let Some(prerequisite) = line.strip_prefix(b"-") {
+                let (id, comment) = split_id(prerequisite, header.object_hash, line)?;
+                header.prerequisites.push(Prerequisite {
+                    id,
+                    comment: comment.filter(|comment| !comment.is_empty()).map(Into::into),
+                });
+            } else {
+                let (id, name) = split_id(line, header.object_hash, line)?;
+                let name = name
+                    .filter(|name| !name.is_empty())
+                    .ok_or_else(|| Error::InvalidLine { line: line.into() })?;
+                header.refs.push(Ref { id, name: name.into() });
+            }
+        }
+        Ok(header)
+    }
+}
+
+/// Split `input` into an id of `kind` and the optional remainder after the separating space.
+fn split_id<'a>(input: &'a [u8], kind: gix_hash::Kind, line: &[u8]) -> Result<(ObjectId, Option<&'a [u8]>), Error> {
+    let hex_len = kind.len_in_hex();
+    let (hex, rest) = match input.get(hex_len) {
+        None if input.len() == hex_len => (input, None),
+        Some(b' ') => (&input[..hex_len], Some(&input[hex_len + 1..])),
+        _ => return Err(Error::InvalidLine { line: line.into() }),
+    };
+    let id = ObjectId::from_hex(hex).map_err(|source| Error::Id {
+        line: line.into(),
+        source,
+    })?;
+    Ok((id, rest))
+}
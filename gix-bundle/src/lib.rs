@@ -0,0 +1,95 @@
+//! Read and write the header of [git bundles](https://git-scm.com/docs/gitformat-bundle), files which contain a pack
+//! along with the references it provides and the objects it requires to exist in the receiving repository.
+//!
+//! The header is followed by the pack data, which is left to the caller to read or write, typically with `gix-pack`.
+#![deny(missing_docs, rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+use bstr::BString;
+use gix_hash::ObjectId;
+
+///
+pub mod decode;
+///
+pub mod list;
+pub use list::List;
+mod write;
+
+/// The signature of a version 2 bundle, along with its trailing newline.
+pub const SIGNATURE_V2: &[u8] = b"# v2 git bundle\n";
+/// The signature of a version 3 bundle, along with its trailing newline.
+pub const SIGNATURE_V3: &[u8] = b"# v3 git bundle\n";
+
+/// Return `true` if `data`, typically the first bytes of a file, starts with the signature of a bundle we can read.
+pub fn is_bundle(data: &[u8]) -> bool {
+    data.starts_with(SIGNATURE_V2) || data.starts_with(SIGNATURE_V3)
+}
+
+/// The version of the bundle format.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Version {
+    /// The original format, which only supports SHA1 and no capabilities.
+    #[default]
+    V2,
+    /// The format which adds capabilities, like `object-format` and `filter`.
+    V3,
+}
+
+/// A capability of a version 3 bundle, as in `@name` or `@name=value`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Capability {
+    /// The name of the capability.
+    pub name: BString,
+    /// The value of the capability, if one was given.
+    pub value: Option<BString>,
+}
+
+/// An object that must exist in the receiving repository, along with all objects reachable from it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Prerequisite {
+    /// The id of the commit that is required.
+    pub id: ObjectId,
+    /// The optional comment following the id, typically the subject of the commit.
+    pub comment: Option<BString>,
+}
+
+/// A reference contained in the bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Ref {
+    /// The id the reference points to, which is contained in the pack or its prerequisites.
+    pub id: ObjectId,
+    /// The full name of the reference, like `refs/heads/main` or `HEAD`.
+    pub name: BString,
+}
+
+/// The header of a bundle, which precedes the pack data.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Header {
+    /// The version of the bundle.
+    pub version: Version,
+    /// The kind of hash used by all objects in the bundle.
+    ///
+    /// It's written as `object-format` capability if it isn't `SHA1`, which requires [`Version::V3`].
+    pub object_hash: gix_hash::Kind,
+    /// All capabilities other than `object-format`, which is represented by [`object_hash`](Self::object_hash).
+    ///
+    /// Only [`Version::V3`] supports capabilities.
+    pub capabilities: Vec<Capability>,
+    /// Objects that must exist in the receiving repository as the pack refers to them without containing them.
+    pub prerequisites: Vec<Prerequisite>,
+    /// The references provided by the bundle.
+    pub refs: Vec<Ref>,
+}
+
+impl Header {
+    /// Create an empty header of the given `version` for objects with `object_hash`.
+    pub fn new(version: Version, object_hash: gix_hash::Kind) -> Self {
+        Header {
+            version,
+            object_hash,
+            capabilities: Vec::new(),
+            prerequisites: Vec::new(),
+            refs: Vec::new(),
+        }
+    }
+}
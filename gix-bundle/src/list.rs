@@ -0,0 +1,138 @@
+use bstr::{BStr, BString, ByteSlice};
+
+/// The error returned by [`List::from_lines()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Expected a 'key=value' line in the bundle list, got {line:?}")]
+    InvalidLine { line: BString },
+    #[error("The value {value:?} of bundle list key {key:?} is invalid")]
+    InvalidValue { key: BString, value: BString },
+    #[error("The bundle list version {version:?} is not supported")]
+    UnsupportedVersion { version: BString },
+    #[error("The bundle list doesn't specify its mode")]
+    MissingMode,
+    #[error("The bundle {id:?} doesn't have an URI")]
+    MissingUri { id: BString },
+}
+
+/// Whether all bundles of a [`List`] are needed, or if any of them suffices.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Mode {
+    /// All bundles are needed to obtain the complete set of objects the list provides.
+    All,
+    /// Each bundle provides the complete set of objects, so any of them can be chosen, for instance to use the closest mirror.
+    Any,
+}
+
+/// A bundle in a [`List`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct Bundle {
+    /// The identifier of the bundle within the list, like `main` in `bundle.main.uri`.
+    pub id: BString,
+    /// The location to download the bundle from, which may be relative to the location of the list.
+    pub uri: BString,
+    /// The filter that was used to create the bundle, if it is a partial bundle.
+    pub filter: Option<BString>,
+    /// A number that increases with the time the bundle was created, if the list uses the `creationToken` heuristic.
+    pub creation_token: Option<u64>,
+}
+
+/// A list of bundles to download, which together or individually provide objects and references to seed a repository with.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct List {
+    /// How the bundles relate to each other.
+    pub mode: Mode,
+    /// The heuristic to use when downloading bundles, like `creationToken`, if set.
+    pub heuristic: Option<BString>,
+    /// All bundles in the order in which they were first mentioned.
+    pub bundles: Vec<Bundle>,
+}
+
+impl List {
+    /// Parse a list from `lines` of the form `key=value`, as sent by servers in response to the `bundle-uri` protocol command.
+    ///
+    /// Keys are compared case-insensitively, except for bundle identifiers, and unknown keys are ignored.
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a BStr>) -> Result<Self, Error> {
+        let mut mode = None;
+        let mut heuristic = None;
+        let mut bundles = Vec::<Bundle>::new();
+        for line in lines {
+            let line = line.strip_suffix(b"\n").unwrap_or(line);
+            let (key, value) = line
+                .split_once_str(b"=")
+                .ok_or_else(|| Error::InvalidLine { line: line.into() })?;
+            let invalid_value = || Error::InvalidValue {
+                key: key.into(),
+                value: value.into(),
+            };
+            let Some(key_rest) = key
+                .get(..7)
+                .filter(|prefix| prefix.eq_ignore_ascii_case(b"bundle."))
+                .map(|_| &key[7..])
+            else {
+                continue;
+            };
+            match key_rest.rfind_byte(b'.') {
+                None => {
+                    if key_rest.eq_ignore_ascii_case(b"version") {
+                        if value != b"1" {
+                            return Err(Error::UnsupportedVersion { version: value.into() });
+                        }
+                    } else if key_rest.eq_ignore_ascii_case(b"mode") {
+                        mode = Some(match value {
+                            b"all" => Mode::All,
+                            b"any" => Mode::Any,
+                            _ => return Err(invalid_value()),
+                        });
+                    } else if key_rest.eq_ignore_ascii_case(b"heuristic") {
+                        heuristic = Some(value.into());
+                    }
+                }
+                Some(pos) => {
+                    let (id, name) = (&key_rest[..pos], &key_rest[pos + 1..]);
+                    let bundle = match bundles.iter_mut().position(|b| b.id == id) {
+                        Some(idx) => &mut bundles[idx],
+                        None => {
+                            bundles.push(Bundle {
+                                id: id.into(),
+                                uri: BString::default(),
+                                filter: None,
+                                creation_token: None,
+                            });
+                            bundles.last_mut().expect("just pushed")
+                        }
+                    };
+                    if name.eq_ignore_ascii_case(b"uri") {
+                        bundle.uri = value.into();
+                    } else if name.eq_ignore_ascii_case(b"filter") {
+                        bundle.filter = Some(value.into());
+                    } else if name.eq_ignore_ascii_case(b"creationToken") {
+                        bundle.creation_token = Some(
+                            value
+                                .to_str()
+                                .ok()
+                                .and_then(|v| v.parse().ok())
+                                .ok_or_else(invalid_value)?,
+                        );
+                    }
+                }
+            }
+        }
+        if let Some(bundle) = bundles.iter().find(|b| b.uri.is_empty()) {
+            return Err(Error::MissingUri { id: bundle.id.clone() });
+        }
+        Ok(List {
+            mode: mode.ok_or(Error::MissingMode)?,
+            heuristic,
+            bundles,
+        })
+    }
+
+    /// Return `true` if the bundles are meant to be applied in the order of their creation token.
+    pub fn uses_creation_token_heuristic(&self) -> bool {
+        self.heuristic
+            .as_ref()
+            .map_or(false, |h| h.eq_ignore_ascii_case(b"creationToken"))
+    }
+}
@@ -0,0 +1,52 @@
+use std::io;
+
+use crate::{Header, Version};
+
+impl Header {
+    /// Write this header to `out`, including the empty line after which the pack data is expected to follow.
+    ///
+    /// Like `git`, version 3 bundles always declare their `object-format`.
+    /// Fails with [`io::ErrorKind::InvalidInput`] if capabilities are set for a [version 2](Version::V2) bundle, or if
+    /// the object hash requires a capability that version 2 can't express.
+    pub fn write_to(&self, out: &mut dyn io::Write) -> io::Result<()> {
+        let needs_object_format = self.object_hash != gix_hash::Kind::Sha1;
+        match self.version {
+            Version::V2 => {
+                if !self.capabilities.is_empty() || needs_object_format {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "Version 2 bundles can't have capabilities or use an object format other than SHA1",
+                    ));
+                }
+                out.write_all(crate::SIGNATURE_V2)?;
+            }
+            Version::V3 => {
+                out.write_all(crate::SIGNATURE_V3)?;
+                writeln!(out, "@object-format={}", self.object_hash.to_string().to_lowercase())?;
+                for capability in &self.capabilities {
+                    out.write_all(b"@")?;
+                    out.write_all(&capability.name)?;
+                    if let Some(value) = &capability.value {
+                        out.write_all(b"=")?;
+                        out.write_all(value)?;
+                    }
+                    out.write_all(b"\n")?;
+                }
+            }
+        }
+        for prerequisite in &self.prerequisites {
+            write!(out, "-{}", prerequisite.id)?;
+            if let Some(comment) = &prerequisite.comment {
+                out.write_all(b" ")?;
+                out.write_all(comment)?;
+            }
+            out.write_all(b"\n")?;
+        }
+        for reference in &self.refs {
+            write!(out, "{} ", reference.id)?;
+            out.write_all(&reference.name)?;
+            out.write_all(b"\n")?;
+        }
+        out.write_all(b"\n")
+    }
+}
@@ -0,0 +1,243 @@
+use gix_bundle::{Capability, Header, Prerequisite, Ref, Version};
+use gix_hash::ObjectId;
+use gix_testtools::Result;
+
+fn fixture() -> Result<std::path::PathBuf> {
+    gix_testtools::scripted_fixture_read_only("make_bundles.sh")
+}
+
+/// Return the bundle at `name` and the ids of `main`, `main~1` and `v1`.
+fn bundle(name: &str) -> Result<(Vec<u8>, [ObjectId; 3])> {
+    let dir = fixture()?;
+    let ids: Vec<_> = std::fs::read_to_string(dir.join("ids"))?
+        .lines()
+        .map(|hex| ObjectId::from_hex(hex.as_bytes()))
+        .collect::<std::result::Result<_, _>>()?;
+    Ok((std::fs::read(dir.join(name))?, [ids[0], ids[1], ids[2]]))
+}
+
+/// Parse the header of `data`, assert it's followed by a pack, and assert it's written back exactly as it was read.
+fn parse_and_roundtrip(data: &[u8]) -> Result<Header> {
+    let mut read = data;
+    let header = Header::from_read(&mut read)?;
+    assert!(read.starts_with(b"PACK"), "the pack follows the header");
+
+    let mut out = Vec::new();
+    header.write_to(&mut out)?;
+    assert_eq!(
+        out.as_slice(),
+        &data[..data.len() - read.len()],
+        "round-trips losslessly"
+    );
+    Ok(header)
+}
+
+fn reference(id: ObjectId, name: &str) -> Ref {
+    Ref { id, name: name.into() }
+}
+
+#[test]
+fn v2_with_all_refs() -> Result {
+    let (data, [main, _, tag]) = bundle("all.bundle")?;
+    assert!(gix_bundle::is_bundle(&data));
+    let header = parse_and_roundtrip(&data)?;
+    assert_eq!(header.version, Version::V2);
+    assert_eq!(header.object_hash, gix_hash::Kind::Sha1);
+    assert!(header.capabilities.is_empty());
+    assert!(header.prerequisites.is_empty());
+    assert_eq!(
+        header.refs,
+        [
+            reference(main, "refs/heads/main"),
+            reference(tag, "refs/tags/v1"),
+            reference(main, "HEAD")
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn v2_with_prerequisites() -> Result {
+    let (data, [main, parent, _]) = bundle("incremental.bundle")?;
+    let header = parse_and_roundtrip(&data)?;
+    assert_eq!(
+        header.prerequisites,
+        [Prerequisite {
+            id: parent,
+            comment: Some("second".into())
+        }]
+    );
+    assert_eq!(header.refs, [reference(main, "refs/heads/main")]);
+    Ok(())
+}
+
+#[test]
+fn v3_with_object_format() -> Result {
+    let (data, [main, _, _]) = bundle("v3.bundle")?;
+    assert!(gix_bundle::is_bundle(&data));
+    let header = parse_and_roundtrip(&data)?;
+    assert_eq!(header.version, Version::V3);
+    assert!(
+        header.capabilities.is_empty(),
+        "the object format isn't kept as capability"
+    );
+    assert_eq!(header.refs, [reference(main, "refs/heads/main")]);
+    Ok(())
+}
+
+#[test]
+fn v3_capabilities_are_written_after_the_object_format() -> Result {
+    let mut header = Header::new(Version::V3, gix_hash::Kind::Sha1);
+    header.capabilities.push(Capability {
+        name: "filter".into(),
+        value: Some("blob:none".into()),
+    });
+    header
+        .refs
+        .push(reference(gix_hash::Kind::Sha1.null(), "refs/heads/main"));
+    let mut out = Vec::new();
+    header.write_to(&mut out)?;
+    assert_eq!(
+        out,
+        b"# v3 git bundle\n@object-format=sha1\n@filter=blob:none\n0000000000000000000000000000000000000000 refs/heads/main\n\n"
+    );
+    assert_eq!(Header::from_read(&mut out.as_slice())?, header);
+    Ok(())
+}
+
+mod invalid {
+    use gix_bundle::{decode, Capability, Header, Version};
+
+    fn decode_error(input: &str) -> decode::Error {
+        Header::from_read(&mut input.as_bytes()).expect_err("invalid input")
+    }
+
+    #[test]
+    fn signature() {
+        assert!(matches!(
+            decode_error("# v4 git bundle\n\n"),
+            decode::Error::UnsupportedSignature { signature } if signature == "# v4 git bundle"
+        ));
+        assert!(!gix_bundle::is_bundle(b"PACK"));
+    }
+
+    #[test]
+    fn capabilities_in_v2() {
+        assert!(matches!(
+            decode_error("# v2 git bundle\n@object-format=sha1\n\n"),
+            decode::Error::UnexpectedCapability { .. }
+        ));
+        let mut header = Header::new(Version::V2, gix_hash::Kind::Sha1);
+        header.capabilities.push(Capability {
+            name: "filter".into(),
+            value: None,
+        });
+        let err = header.write_to(&mut Vec::new()).expect_err("v2 has no capabilities");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn capabilities_after_refs() {
+        assert!(matches!(
+            decode_error("# v3 git bundle\n0000000000000000000000000000000000000000 HEAD\n@filter\n\n"),
+            decode::Error::UnexpectedCapability { .. }
+        ));
+    }
+
+    #[test]
+    fn object_format() {
+        assert!(matches!(
+            decode_error("# v3 git bundle\n@object-format=md5\n\n"),
+            decode::Error::UnsupportedObjectFormat { name } if name == "md5"
+        ));
+    }
+
+    #[test]
+    fn lines() {
+        for line in ["0000 HEAD", "0000000000000000000000000000000000000000", "-00000000"] {
+            assert!(
+                matches!(
+                    decode_error(&format!("# v2 git bundle\n{line}\n\n")),
+                    decode::Error::InvalidLine { .. }
+                ),
+                "{line}"
+            );
+        }
+        assert!(matches!(
+            decode_error("# v2 git bundle\nx000000000000000000000000000000000000000 HEAD\n\n"),
+            decode::Error::Id { .. }
+        ));
+    }
+
+    #[test]
+    fn missing_end_of_header() {
+        assert!(matches!(
+            decode_error("# v2 git bundle\n0000000000000000000000000000000000000000 HEAD\n"),
+            decode::Error::UnexpectedEof
+        ));
+    }
+}
+
+mod list {
+    use bstr::BStr;
+    use gix_bundle::list::{Bundle, Error, List, Mode};
+
+    fn parse(lines: &[&str]) -> Result<List, Error> {
+        List::from_lines(lines.iter().map(BStr::new))
+    }
+
+    #[test]
+    fn with_creation_tokens() -> gix_testtools::Result {
+        let list = parse(&[
+            "bundle.version=1",
+            "bundle.mode=any",
+            "bundle.heuristic=creationToken",
+            "bundle.mirror.one.uri=https://example.com/one.bundle",
+            "bundle.mirror.one.creationtoken=42",
+            "bundle.mirror.one.filter=blob:none",
+            "bundle.Two.URI=two.bundle",
+            "bundle.Two.unknown=ignored",
+            "other.key=ignored",
+        ])?;
+        assert_eq!(list.mode, Mode::Any);
+        assert!(list.uses_creation_token_heuristic());
+        assert_eq!(
+            list.bundles,
+            vec![
+                Bundle {
+                    id: "mirror.one".into(),
+                    uri: "https://example.com/one.bundle".into(),
+                    filter: Some("blob:none".into()),
+                    creation_token: Some(42),
+                },
+                Bundle {
+                    id: "Two".into(),
+                    uri: "two.bundle".into(),
+                    filter: None,
+                    creation_token: None,
+                }
+            ],
+            "identifiers may contain dots, and keys are case-insensitive"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(
+            parse(&["bundle.version=2", "bundle.mode=all"]),
+            Err(Error::UnsupportedVersion { .. })
+        ));
+        assert!(matches!(parse(&["bundle.version=1"]), Err(Error::MissingMode)));
+        assert!(matches!(parse(&["bundle.mode=some"]), Err(Error::InvalidValue { .. })));
+        assert!(matches!(
+            parse(&["bundle.mode=all", "bundle.a.creationToken=x", "bundle.a.uri=a"]),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse(&["bundle.mode=all", "bundle.a.creationToken=1"]),
+            Err(Error::MissingUri { .. })
+        ));
+        assert!(matches!(parse(&["no-equals-sign"]), Err(Error::InvalidLine { .. })));
+    }
+}
@@ -0,0 +1,16 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git checkout -q -b main
+  echo a > a && git add a && git commit -q -m first
+  echo b > b && git add b && git commit -q -m second
+  git tag -a -m "annotated" v1
+  echo c > c && git add c && git commit -q -m third
+
+  git bundle create -q ../all.bundle --all
+  git bundle create -q ../incremental.bundle main~1..main
+  git bundle create -q --version=3 ../v3.bundle main
+  git rev-parse main main~1 v1 > ../ids
+)
@@ -1,3 +1,5 @@
+use bstr::BStr;
+
 use crate::{file, file::Commit, File, Graph, Position};
 
 /// Access
@@ -41,10 +43,29 @@ impl Graph {
         Some(self.lookup_by_id(id.as_ref())?.graph_pos)
     }
 
+    /// Return `Some(false)` if the commit with `id` definitely didn't change `path` compared to its first parent according to its
+    /// changed-path bloom filter, or `Some(true)` if it may have changed it.
+    /// Return `None` if the commit isn't part of the graph, or if there is no usable filter for it, which can happen if the graph
+    /// was written without `--changed-paths`.
+    ///
+    /// Bloom filters have no false negatives, but false positives, with about one in a hundred queries answered with `Some(true)`
+    /// even though `path` didn't change using the settings of `git`. Thus, `Some(true)` means that a tree diff is needed to know for sure.
+    /// `path` is a slash-separated path relative to the root of the repository, like `dir/file`, and it's considered changed
+    /// if it's a directory containing a changed file.
+    pub fn may_have_changed(&self, id: impl AsRef<gix_hash::oid>, path: &BStr) -> Option<bool> {
+        let r = self.lookup_by_id(id.as_ref())?;
+        r.file.may_have_changed(r.file_pos, path)
+    }
+
     /// Returns the number of commits stored in this file.
     pub fn num_commits(&self) -> u32 {
         self.files.iter().map(File::num_commits).sum()
     }
+
+    /// Returns all files of this graph, starting with the one that doesn't depend on any other.
+    pub fn files(&self) -> &[File] {
+        &self.files
+    }
 }
 
 /// Access fundamentals
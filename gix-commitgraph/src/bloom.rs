@@ -0,0 +1,186 @@
+//! Bloom filters for changed paths, which allow to quickly determine that a commit didn't change a path.
+//!
+//! Each commit has a filter which contains all paths that changed compared to its first parent, along with all of their
+//! leading directories. If a path isn't contained in the filter, the commit definitely didn't change it, which allows
+//! path-limited traversals like `git log -- path` to skip computing tree diffs for most commits.
+use std::collections::HashSet;
+
+use bstr::{BStr, ByteSlice};
+
+/// The seed of the first hash function used to produce the hashes of a [`Key`].
+const SEED0: u32 = 0x293a_e76f;
+/// The seed of the second hash function used to produce the hashes of a [`Key`].
+const SEED1: u32 = 0x7e64_6e2c;
+/// The amount of bits in each byte of a filter.
+const BITS_PER_WORD: u64 = 8;
+
+/// The parameters for creating and querying changed-path bloom filters.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Settings {
+    /// The version of the hash function used to compute the keys, which is `1` for the original `murmur3` implementation of `git`,
+    /// and `2` for the fixed one which treats bytes of non-ASCII paths as unsigned.
+    pub hash_version: u32,
+    /// The amount of hashes to compute for each path, each setting a bit in the filter.
+    pub num_hashes: u32,
+    /// The amount of bits each path takes in the filter.
+    pub bits_per_entry: u32,
+    /// The maximum amount of changed paths, including their leading directories, that are stored in a filter.
+    ///
+    /// Commits changing more paths get a filter that contains every path.
+    pub max_changed_paths: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            hash_version: 1,
+            num_hashes: 7,
+            bits_per_entry: 10,
+            max_changed_paths: 512,
+        }
+    }
+}
+
+/// The hashes of a path, which are used to set and test bits in a bloom filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Key {
+    hashes: Vec<u32>,
+}
+
+impl Key {
+    /// Compute the key of `path` according to `settings`.
+    pub fn new(path: &BStr, settings: &Settings) -> Self {
+        let bytes_are_signed = settings.hash_version == 1;
+        let hash0 = murmur3_seeded(SEED0, path, bytes_are_signed);
+        let hash1 = murmur3_seeded(SEED1, path, bytes_are_signed);
+        Key {
+            hashes: (0..settings.num_hashes)
+                .map(|i| hash0.wrapping_add(i.wrapping_mul(hash1)))
+                .collect(),
+        }
+    }
+
+    /// Return the hashes of the key, with one hash per bit to set or test.
+    pub fn hashes(&self) -> &[u32] {
+        &self.hashes
+    }
+
+    /// Return an iterator over the bits to set in a filter of `filter_len` bytes, as tuple of the byte position and
+    /// the mask for the bit within it.
+    fn bits(&self, filter_len: usize) -> impl Iterator<Item = (usize, u8)> + '_ {
+        let num_bits = filter_len as u64 * BITS_PER_WORD;
+        self.hashes.iter().map(move |hash| {
+            let bit = u64::from(*hash) % num_bits;
+            ((bit / BITS_PER_WORD) as usize, 1 << (bit % BITS_PER_WORD))
+        })
+    }
+}
+
+/// Return `true` if `filter` may contain `key`, or `false` if it definitely doesn't.
+///
+/// Empty filters can't be used for queries, and are assumed to contain everything.
+pub fn contains(filter: &[u8], key: &Key) -> bool {
+    filter.is_empty() || key.bits(filter.len()).all(|(pos, mask)| filter[pos] & mask != 0)
+}
+
+/// Return `true` if `filter`, created with `settings`, may contain `path` along with all of its leading directories, or `false`
+/// if at least one of them definitely isn't contained.
+///
+/// This is how `git` uses filters, as checking the leading directories as well reduces the chance of false positives.
+/// Trailing slashes of `path` are ignored, and empty paths are assumed to be contained.
+pub fn contains_path(filter: &[u8], path: &BStr, settings: &Settings) -> bool {
+    let mut path = path.trim_end_with(|c| c == '/').as_bstr();
+    if filter.is_empty() || path.is_empty() {
+        return true;
+    }
+    loop {
+        if !contains(filter, &Key::new(path, settings)) {
+            return false;
+        }
+        match path.rfind_byte(b'/') {
+            Some(pos) => path = path[..pos].as_bstr(),
+            None => return true,
+        }
+    }
+}
+
+/// Create the filter for a commit which changed `paths` compared to its first parent, or `None` if the changes aren't known,
+/// according to `settings`.
+///
+/// All leading directories of `paths` are added to the filter as well.
+/// If there are too many paths, or if they aren't known, the filter will claim to contain every path.
+pub fn filter_from_changed_paths<'a>(
+    paths: Option<impl IntoIterator<Item = &'a BStr>>,
+    settings: &Settings,
+) -> Vec<u8> {
+    let too_large = vec![0xff];
+    let Some(paths) = paths else { return too_large };
+
+    let mut all_paths = HashSet::<&BStr>::new();
+    for path in paths {
+        let mut path = path;
+        while all_paths.insert(path) {
+            match path.rfind_byte(b'/') {
+                Some(pos) => path = path[..pos].as_bstr(),
+                None => break,
+            }
+        }
+        if all_paths.len() > settings.max_changed_paths {
+            return too_large;
+        }
+    }
+
+    let len =
+        ((all_paths.len() as u64 * u64::from(settings.bits_per_entry) + BITS_PER_WORD - 1) / BITS_PER_WORD) as usize;
+    let mut filter = vec![0; len.max(1)];
+    for path in all_paths {
+        for (pos, mask) in Key::new(path, settings).bits(filter.len()) {
+            filter[pos] |= mask;
+        }
+    }
+    filter
+}
+
+/// The `murmur3` hash function as implemented by `git`, which interprets bytes as signed on most platforms in version 1,
+/// which is what `bytes_are_signed` is for.
+fn murmur3_seeded(mut seed: u32, data: &[u8], bytes_are_signed: bool) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    const R1: u32 = 15;
+    const R2: u32 = 13;
+    const M: u32 = 5;
+    const N: u32 = 0xe654_6b64;
+
+    let byte = |b: u8| if bytes_are_signed { b as i8 as u32 } else { u32::from(b) };
+
+    let chunks = data.chunks_exact(4);
+    let tail = chunks.remainder();
+    for chunk in chunks {
+        let mut k = byte(chunk[0]) | (byte(chunk[1]) << 8) | (byte(chunk[2]) << 16) | (byte(chunk[3]) << 24);
+        k = k.wrapping_mul(C1);
+        k = k.rotate_left(R1);
+        k = k.wrapping_mul(C2);
+
+        seed ^= k;
+        seed = seed.rotate_left(R2).wrapping_mul(M).wrapping_add(N);
+    }
+
+    if !tail.is_empty() {
+        let mut k1 = 0u32;
+        for (shift, b) in tail.iter().enumerate().rev() {
+            k1 ^= byte(*b) << (shift * 8);
+        }
+        k1 = k1.wrapping_mul(C1);
+        k1 = k1.rotate_left(R1);
+        k1 = k1.wrapping_mul(C2);
+        seed ^= k1;
+    }
+
+    seed ^= data.len() as u32;
+    seed ^= seed >> 16;
+    seed = seed.wrapping_mul(0x85eb_ca6b);
+    seed ^= seed >> 13;
+    seed = seed.wrapping_mul(0xc2b2_ae35);
+    seed ^= seed >> 16;
+    seed
+}
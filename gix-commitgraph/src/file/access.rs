@@ -4,7 +4,10 @@ use std::{
     path::Path,
 };
 
+use bstr::BStr;
+
 use crate::{
+    bloom,
     file::{self, commit::Commit, COMMIT_DATA_ENTRY_SIZE_SANS_HASH},
     File,
 };
@@ -96,6 +99,57 @@ impl File {
         None
     }
 
+    /// Return the changed-path bloom filter of the commit at `pos`, or `None` if this file has no usable filters or if the
+    /// filter of the commit is missing or malformed.
+    ///
+    /// The filter can be queried with [`bloom::contains_path()`] along with the [settings](Self::bloom_settings()) of this file.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of bounds.
+    pub fn bloom_filter(&self, pos: file::Position) -> Option<&[u8]> {
+        assert!(
+            pos.0 < self.num_commits(),
+            "expected lexicographical position less than {}, got {}",
+            self.num_commits(),
+            pos.0
+        );
+        let index_offset = self.bloom_filter_index_offset?;
+        let data = &self.data[self.bloom_filter_data_range.clone()?];
+        let end_of = |pos: u32| {
+            let start = index_offset + pos as usize * 4;
+            u32::from_be_bytes(self.data[start..][..4].try_into().expect("4 bytes")) as usize
+        };
+        let start = pos.0.checked_sub(1).map_or(0, end_of);
+        data.get(start..end_of(pos.0))
+    }
+
+    /// Return the settings with which the changed-path bloom filters of this file were created, or `None` if there are no usable filters.
+    ///
+    /// Note that [`max_changed_paths`](bloom::Settings::max_changed_paths) isn't stored in the file and always has its default value.
+    pub fn bloom_settings(&self) -> Option<&bloom::Settings> {
+        self.bloom_settings.as_ref()
+    }
+
+    /// Return `Some(false)` if the commit at `pos` definitely didn't change `path` compared to its first parent,
+    /// or `Some(true)` if it may have changed it, or `None` if there is no usable changed-path bloom filter for it.
+    ///
+    /// Note that bloom filters have false positives, so `Some(true)` only indicates that a tree diff is needed to know for sure,
+    /// while there are no false negatives.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of bounds.
+    pub fn may_have_changed(&self, pos: file::Position, path: &BStr) -> Option<bool> {
+        let filter = self.bloom_filter(pos)?;
+        Some(bloom::contains_path(filter, path, self.bloom_settings.as_ref()?))
+    }
+
+    /// Returns `true` if this file contains the corrected commit dates of its commits.
+    pub fn has_generation_data(&self) -> bool {
+        self.generation_data_offset.is_some()
+    }
+
     /// Returns the number of commits in this graph file.
     ///
     /// The maximum valid `file::Position` that can be used with this file is one less than
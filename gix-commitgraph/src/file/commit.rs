@@ -6,7 +6,7 @@ use std::{
 };
 
 use crate::{
-    file::{self, EXTENDED_EDGES_MASK, LAST_EXTENDED_EDGE_MASK, NO_PARENT},
+    file::{self, CORRECTED_DATE_OFFSET_OVERFLOW, EXTENDED_EDGES_MASK, LAST_EXTENDED_EDGE_MASK, NO_PARENT},
     File, Position,
 };
 
@@ -75,6 +75,24 @@ impl<'a> Commit<'a> {
         self.generation
     }
 
+    /// Returns the corrected commit date of this commit, also known as generation number v2, or `None` if the
+    /// commit-graph file doesn't contain generation data.
+    ///
+    /// It's the larger of the committer timestamp and the largest corrected commit date of the parents plus one,
+    /// making it a generation number that is usually very close to the committer timestamp.
+    pub fn corrected_commit_date(&self) -> Option<u64> {
+        let generation_data = &self.file.data[self.file.generation_data_offset?..];
+        let raw_offset = read_u32(&generation_data[self.pos.0 as usize * 4..][..4]);
+        let offset = if raw_offset & CORRECTED_DATE_OFFSET_OVERFLOW != 0 {
+            let overflow = &self.file.data[self.file.generation_data_overflow_range.clone()?];
+            let index = (raw_offset ^ CORRECTED_DATE_OFFSET_OVERFLOW) as usize;
+            u64::from_be_bytes(overflow.get(index * 8..)?.get(..8)?.try_into().unwrap())
+        } else {
+            u64::from(raw_offset)
+        };
+        Some(self.commit_timestamp + offset)
+    }
+
     /// Returns an iterator over the parent positions for lookup in the owning [Graph][crate::Graph].
     pub fn iter_parents(self) -> Parents<'a> {
         // I didn't find a combinator approach that a) was as strict as ParentIterator, b) supported
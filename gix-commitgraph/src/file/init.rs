@@ -8,9 +8,12 @@ use bstr::ByteSlice;
 use memmap2::Mmap;
 
 use crate::{
+    bloom,
     file::{
-        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
-        EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, GENERATION_DATA_CHUNK_ID, GENERATION_DATA_OVERFLOW_CHUNK_ID, HEADER_LEN,
+        OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
     },
     File,
 };
@@ -189,6 +192,73 @@ impl File {
 
         let extra_edges_list_range = chunks.usize_offset_by_id(EXTENDED_EDGES_LIST_CHUNK_ID).ok();
 
+        let generation_data_offset = chunks
+            .validated_usize_offset_by_id(GENERATION_DATA_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                let expected_size = 4 * oid_lookup_count as usize;
+                if chunk_size != expected_size {
+                    return Err(Error::InvalidChunkSize {
+                        id: GENERATION_DATA_CHUNK_ID,
+                        msg: format!("expected chunk length {expected_size}, got {chunk_size}"),
+                    });
+                }
+                Ok(chunk_range.start)
+            })
+            .ok()
+            .transpose()?;
+        let generation_data_overflow_range = chunks.usize_offset_by_id(GENERATION_DATA_OVERFLOW_CHUNK_ID).ok();
+
+        let bloom_filter_index_offset = chunks
+            .validated_usize_offset_by_id(BLOOM_FILTER_INDEX_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                let expected_size = 4 * oid_lookup_count as usize;
+                if chunk_size != expected_size {
+                    return Err(Error::InvalidChunkSize {
+                        id: BLOOM_FILTER_INDEX_CHUNK_ID,
+                        msg: format!("expected chunk length {expected_size}, got {chunk_size}"),
+                    });
+                }
+                Ok(chunk_range.start)
+            })
+            .ok()
+            .transpose()?;
+        let bloom_filter_data = chunks
+            .validated_usize_offset_by_id(BLOOM_FILTER_DATA_CHUNK_ID, |chunk_range| {
+                if chunk_range.len() < BLOOM_FILTER_DATA_HEADER_LEN {
+                    return Err(Error::InvalidChunkSize {
+                        id: BLOOM_FILTER_DATA_CHUNK_ID,
+                        msg: format!(
+                            "expected at least {BLOOM_FILTER_DATA_HEADER_LEN} bytes, got {}",
+                            chunk_range.len()
+                        ),
+                    });
+                }
+                let header = &data[chunk_range.start..][..BLOOM_FILTER_DATA_HEADER_LEN];
+                let value = |index: usize| u32::from_be_bytes(header[index * 4..][..4].try_into().expect("4 bytes"));
+                let settings = bloom::Settings {
+                    hash_version: value(0),
+                    num_hashes: value(1),
+                    bits_per_entry: value(2),
+                    ..Default::default()
+                };
+                Ok((
+                    chunk_range.start + BLOOM_FILTER_DATA_HEADER_LEN..chunk_range.end,
+                    settings,
+                ))
+            })
+            .ok()
+            .transpose()?;
+        // Like `git`, ignore filters that are incomplete or that we can't query.
+        let (bloom_filter_index_offset, bloom_filter_data_range, bloom_settings) =
+            match (bloom_filter_index_offset, bloom_filter_data) {
+                (Some(index_offset), Some((data_range, settings)))
+                    if matches!(settings.hash_version, 1 | 2) && settings.num_hashes > 0 =>
+                {
+                    (Some(index_offset), Some(data_range), Some(settings))
+                }
+                _ => (None, None, None),
+            };
+
         let trailer = &data[chunks.highest_offset() as usize..];
         if trailer.len() != object_hash.len_in_bytes() {
             return Err(Error::Trailer(format!(
@@ -225,10 +295,15 @@ impl File {
         Ok(File {
             base_graph_count,
             base_graphs_list_offset,
+            bloom_filter_index_offset,
+            bloom_filter_data_range,
+            bloom_settings,
             commit_data_offset,
             data,
             extra_edges_list_range,
             fan,
+            generation_data_offset,
+            generation_data_overflow_range,
             oid_lookup_offset,
             path,
             hash_len: object_hash.len_in_bytes(),
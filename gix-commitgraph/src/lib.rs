@@ -24,10 +24,17 @@ use std::path::Path;
 pub struct File {
     base_graph_count: u8,
     base_graphs_list_offset: Option<usize>,
+    /// The offset of the changed-path bloom filter index, set only if the filters can be used.
+    bloom_filter_index_offset: Option<usize>,
+    /// The range of all changed-path bloom filters, excluding the header, set only if the filters can be used.
+    bloom_filter_data_range: Option<std::ops::Range<usize>>,
+    bloom_settings: Option<bloom::Settings>,
     commit_data_offset: usize,
     data: memmap2::Mmap,
     extra_edges_list_range: Option<std::ops::Range<usize>>,
     fan: [u32; file::FAN_LEN],
+    generation_data_offset: Option<usize>,
+    generation_data_overflow_range: Option<std::ops::Range<usize>>,
     oid_lookup_offset: usize,
     path: std::path::PathBuf,
     hash_len: usize,
@@ -49,10 +56,12 @@ pub fn at(path: impl AsRef<Path>) -> Result<Graph, init::Error> {
 }
 
 mod access;
+pub mod bloom;
 pub mod file;
 ///
 pub mod init;
 pub mod verify;
+pub mod write;
 
 /// The number of generations that are considered 'infinite' commit history.
 pub const GENERATION_NUMBER_INFINITY: u32 = 0xffff_ffff;
@@ -0,0 +1,389 @@
+//! Write commit-graph files, either as standalone file or as new layer on top of an existing [`Graph`].
+use std::{convert::TryInto, io::Write};
+
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::{
+    bloom,
+    file::{
+        BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        CORRECTED_DATE_OFFSET_OVERFLOW, EXTENDED_EDGES_LIST_CHUNK_ID, EXTENDED_EDGES_MASK, FAN_LEN,
+        GENERATION_DATA_CHUNK_ID, GENERATION_DATA_OVERFLOW_CHUNK_ID, HEADER_LEN, LAST_EXTENDED_EDGE_MASK, NO_PARENT,
+        OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    },
+    Graph, GENERATION_NUMBER_MAX, MAX_COMMITS,
+};
+
+/// The largest committer timestamp that can be stored, as only 34 bits are available.
+const MAX_COMMIT_TIMESTAMP: u64 = (1 << 34) - 1;
+/// The largest corrected commit date offset that can be stored without using the overflow chunk.
+const MAX_CORRECTED_DATE_OFFSET: u64 = (1 << 31) - 1;
+
+/// The error returned by [`write_to()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("The parent {parent} of commit {id} is neither among the commits to write nor in the base graph")]
+    MissingParent { id: ObjectId, parent: ObjectId },
+    #[error(
+        "Commit-graph files would contain {0} commits altogether, but only {} commits are allowed",
+        MAX_COMMITS
+    )]
+    TooManyCommits(u64),
+    #[error("The base graph uses hash {actual:?}, but {expected:?} was requested")]
+    ObjectHashMismatch {
+        expected: gix_hash::Kind,
+        actual: gix_hash::Kind,
+    },
+}
+
+/// A commit to write into a commit-graph file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// The id of the tree the commit points to.
+    pub tree: ObjectId,
+    /// The ids of all parents of the commit, in order.
+    pub parents: Vec<ObjectId>,
+    /// The time at which the commit was committed, in seconds since the unix epoch.
+    pub committer_timestamp: u64,
+    /// All paths, with slashes as separator, that changed compared to the first parent, or to the empty tree if there
+    /// is no parent, or `None` if they aren't known.
+    ///
+    /// These are only used if [changed-path bloom filters](Options::changed_paths) are written, and leading directories
+    /// don't need to be included.
+    pub changed_paths: Option<Vec<BString>>,
+}
+
+/// Options for use in [`write_to()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// The kind of hash used by all commits.
+    pub object_hash: gix_hash::Kind,
+    /// If `true`, write the corrected commit dates of all commits, also known as generation numbers v2, which are better
+    /// suited to limit traversals than the topological levels that are written in any case.
+    ///
+    /// Note that these are only written if all files of the base graph contain them as well.
+    pub generation_data: bool,
+    /// If set, write changed-path bloom filters with the given settings to speed up path-limited traversals.
+    pub changed_paths: Option<bloom::Settings>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            object_hash: gix_hash::Kind::Sha1,
+            generation_data: true,
+            changed_paths: None,
+        }
+    }
+}
+
+/// The outcome of [`write_to()`].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The checksum of the written file, which is also its name when it's part of a split commit-graph.
+    pub checksum: ObjectId,
+    /// The amount of commits in the written file.
+    pub num_commits: u32,
+    /// If `true`, corrected commit dates were written.
+    pub generation_data: bool,
+}
+
+/// Where to find a parent of a commit.
+#[derive(Clone, Copy)]
+enum Parent {
+    /// The parent is among the commits to write, at the given index.
+    New(usize),
+    /// The parent is in the base graph with the given topological level and corrected commit date.
+    Base {
+        pos: crate::Position,
+        level: u32,
+        corrected_date: u64,
+    },
+}
+
+/// Write a commit-graph file containing `commits` into `out`, on top of the `base` graph if it is set.
+///
+/// `commits` can be provided in any order, and commits that are contained in `base` are skipped. The parents of
+/// all `commits` must either be contained in `commits` as well, or in `base`.
+/// If `base` is set, the written file is a new layer of a split commit-graph, which contains only `commits` while
+/// referring to their parents in `base`.
+pub fn write_to(
+    mut commits: Vec<Commit>,
+    base: Option<&Graph>,
+    out: &mut dyn Write,
+    options: Options,
+) -> Result<Outcome, Error> {
+    let object_hash = options.object_hash;
+    if let Some(actual) = base
+        .and_then(|base| base.files().first())
+        .map(crate::File::object_hash)
+        .filter(|actual| *actual != object_hash)
+    {
+        return Err(Error::ObjectHashMismatch {
+            expected: object_hash,
+            actual,
+        });
+    }
+    commits.sort_by_key(|c| c.id);
+    commits.dedup_by(|a, b| a.id == b.id);
+    if let Some(base) = base {
+        commits.retain(|c| base.lookup(c.id).is_none());
+    }
+    let num_base_commits = base.map_or(0, Graph::num_commits);
+    let num_commits = u64::from(num_base_commits) + commits.len() as u64;
+    if num_commits > u64::from(MAX_COMMITS) {
+        return Err(Error::TooManyCommits(num_commits));
+    }
+    let generation_data =
+        options.generation_data && base.map_or(true, |base| base.files().iter().all(crate::File::has_generation_data));
+
+    let parents = commits
+        .iter()
+        .map(|commit| {
+            commit
+                .parents
+                .iter()
+                .map(|parent| {
+                    if let Ok(idx) = commits.binary_search_by(|c| c.id.cmp(parent)) {
+                        return Ok(Parent::New(idx));
+                    }
+                    let base_commit = base.and_then(|base| Some((base.lookup(parent)?, base.commit_by_id(parent)?)));
+                    match base_commit {
+                        Some((pos, base_commit)) => Ok(Parent::Base {
+                            pos,
+                            level: base_commit.generation(),
+                            corrected_date: base_commit
+                                .corrected_commit_date()
+                                .unwrap_or(base_commit.committer_timestamp()),
+                        }),
+                        None => Err(Error::MissingParent {
+                            id: commit.id,
+                            parent: *parent,
+                        }),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let (levels, corrected_dates) = compute_generations(&commits, &parents);
+
+    let position = |parent: &Parent| match parent {
+        Parent::New(idx) => num_base_commits + *idx as u32,
+        Parent::Base { pos, .. } => pos.0,
+    };
+    let mut extra_edges = Vec::new();
+    let mut generation_overflows = Vec::new();
+    let mut bloom_filters = Vec::new();
+    if let Some(settings) = &options.changed_paths {
+        bloom_filters = commits
+            .iter()
+            .map(|c| {
+                bloom::filter_from_changed_paths(
+                    c.changed_paths.as_ref().map(|paths| paths.iter().map(|p| p.as_bstr())),
+                    settings,
+                )
+            })
+            .collect();
+    }
+    for (idx, commit) in commits.iter().enumerate() {
+        if parents[idx].len() > 2 {
+            let (last, edges) = parents[idx][1..].split_last().expect("more than two parents");
+            extra_edges.extend(edges.iter().map(position));
+            extra_edges.push(position(last) | LAST_EXTENDED_EDGE_MASK);
+        }
+        let offset = corrected_dates[idx] - commit.committer_timestamp.min(MAX_COMMIT_TIMESTAMP);
+        if generation_data && offset > MAX_CORRECTED_DATE_OFFSET {
+            generation_overflows.push(offset);
+        }
+    }
+
+    let mut cf = gix_chunk::file::Index::for_writing();
+    let hash_len = object_hash.len_in_bytes();
+    cf.plan_chunk(OID_FAN_CHUNK_ID, (FAN_LEN * 4) as u64);
+    cf.plan_chunk(OID_LOOKUP_CHUNK_ID, (commits.len() * hash_len) as u64);
+    cf.plan_chunk(
+        COMMIT_DATA_CHUNK_ID,
+        (commits.len() * (hash_len + COMMIT_DATA_ENTRY_SIZE_SANS_HASH)) as u64,
+    );
+    if generation_data {
+        cf.plan_chunk(GENERATION_DATA_CHUNK_ID, (commits.len() * 4) as u64);
+        if !generation_overflows.is_empty() {
+            cf.plan_chunk(
+                GENERATION_DATA_OVERFLOW_CHUNK_ID,
+                (generation_overflows.len() * 8) as u64,
+            );
+        }
+    }
+    if !extra_edges.is_empty() {
+        cf.plan_chunk(EXTENDED_EDGES_LIST_CHUNK_ID, (extra_edges.len() * 4) as u64);
+    }
+    if options.changed_paths.is_some() {
+        cf.plan_chunk(BLOOM_FILTER_INDEX_CHUNK_ID, (commits.len() * 4) as u64);
+        cf.plan_chunk(
+            BLOOM_FILTER_DATA_CHUNK_ID,
+            (BLOOM_FILTER_DATA_HEADER_LEN + bloom_filters.iter().map(Vec::len).sum::<usize>()) as u64,
+        );
+    }
+    let base_files = base.map_or(&[][..], Graph::files);
+    if !base_files.is_empty() {
+        cf.plan_chunk(BASE_GRAPHS_LIST_CHUNK_ID, (base_files.len() * hash_len) as u64);
+    }
+
+    let mut out = gix_features::hash::Write::new(out, object_hash);
+    out.write_all(SIGNATURE)?;
+    out.write_all(&[1 /* version */, object_hash as u8])?;
+    out.write_all(&[
+        cf.num_chunks().try_into().expect("BUG: wrote more than 256 chunks"),
+        base_files
+            .len()
+            .try_into()
+            .expect("git doesn't allow more than 255 base graphs"),
+    ])?;
+
+    let mut chunk_write = cf.into_write(&mut out, HEADER_LEN)?;
+    while let Some(chunk_to_write) = chunk_write.next_chunk() {
+        match chunk_to_write {
+            OID_FAN_CHUNK_ID => {
+                let mut fan = [0u32; FAN_LEN];
+                for commit in &commits {
+                    fan[usize::from(commit.id.first_byte())] += 1;
+                }
+                let mut count = 0;
+                for value in fan {
+                    count += value;
+                    chunk_write.write_all(&count.to_be_bytes())?;
+                }
+            }
+            OID_LOOKUP_CHUNK_ID => {
+                for commit in &commits {
+                    chunk_write.write_all(commit.id.as_slice())?;
+                }
+            }
+            COMMIT_DATA_CHUNK_ID => {
+                let mut extra_edges_index = 0;
+                for (idx, commit) in commits.iter().enumerate() {
+                    chunk_write.write_all(commit.tree.as_slice())?;
+                    let parents = &parents[idx];
+                    let parent1 = parents.first().map_or(NO_PARENT, position);
+                    let parent2 = match parents.len() {
+                        0 | 1 => NO_PARENT,
+                        2 => position(&parents[1]),
+                        num_parents => {
+                            let value = EXTENDED_EDGES_MASK | extra_edges_index;
+                            extra_edges_index += num_parents as u32 - 1;
+                            value
+                        }
+                    };
+                    let timestamp = commit.committer_timestamp.min(MAX_COMMIT_TIMESTAMP);
+                    let level_and_time = (u64::from(levels[idx]) << 34) | timestamp;
+                    chunk_write.write_all(&parent1.to_be_bytes())?;
+                    chunk_write.write_all(&parent2.to_be_bytes())?;
+                    chunk_write.write_all(&level_and_time.to_be_bytes())?;
+                }
+            }
+            GENERATION_DATA_CHUNK_ID => {
+                let mut num_overflows = 0;
+                for (idx, commit) in commits.iter().enumerate() {
+                    let offset = corrected_dates[idx] - commit.committer_timestamp.min(MAX_COMMIT_TIMESTAMP);
+                    let value = if offset > MAX_CORRECTED_DATE_OFFSET {
+                        num_overflows += 1;
+                        CORRECTED_DATE_OFFSET_OVERFLOW | (num_overflows - 1)
+                    } else {
+                        offset as u32
+                    };
+                    chunk_write.write_all(&value.to_be_bytes())?;
+                }
+            }
+            GENERATION_DATA_OVERFLOW_CHUNK_ID => {
+                for offset in &generation_overflows {
+                    chunk_write.write_all(&offset.to_be_bytes())?;
+                }
+            }
+            EXTENDED_EDGES_LIST_CHUNK_ID => {
+                for edge in &extra_edges {
+                    chunk_write.write_all(&edge.to_be_bytes())?;
+                }
+            }
+            BLOOM_FILTER_INDEX_CHUNK_ID => {
+                let mut end = 0u32;
+                for filter in &bloom_filters {
+                    end += filter.len() as u32;
+                    chunk_write.write_all(&end.to_be_bytes())?;
+                }
+            }
+            BLOOM_FILTER_DATA_CHUNK_ID => {
+                let settings = options.changed_paths.expect("set if planned");
+                for value in [settings.hash_version, settings.num_hashes, settings.bits_per_entry] {
+                    chunk_write.write_all(&value.to_be_bytes())?;
+                }
+                for filter in &bloom_filters {
+                    chunk_write.write_all(filter)?;
+                }
+            }
+            BASE_GRAPHS_LIST_CHUNK_ID => {
+                for file in base_files {
+                    chunk_write.write_all(file.checksum().as_bytes())?;
+                }
+            }
+            unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
+        }
+    }
+
+    let checksum: ObjectId = out.hash.digest().into();
+    out.inner.write_all(checksum.as_slice())?;
+    Ok(Outcome {
+        checksum,
+        num_commits: commits.len() as u32,
+        generation_data,
+    })
+}
+
+/// Compute the topological level and the corrected commit date of each of `commits`, whose parents are described by `parents`.
+fn compute_generations(commits: &[Commit], parents: &[Vec<Parent>]) -> (Vec<u32>, Vec<u64>) {
+    let mut levels = vec![0u32; commits.len()];
+    let mut corrected_dates = vec![0u64; commits.len()];
+    let mut stack = Vec::new();
+    for start in 0..commits.len() {
+        if levels[start] != 0 {
+            continue;
+        }
+        stack.push(start);
+        while let Some(&idx) = stack.last() {
+            if levels[idx] != 0 {
+                stack.pop();
+                continue;
+            }
+            let mut all_parents_done = true;
+            let (mut level, mut corrected_date) = (0, 0);
+            for parent in &parents[idx] {
+                let (parent_level, parent_corrected_date) = match *parent {
+                    Parent::New(parent_idx) if levels[parent_idx] == 0 => {
+                        all_parents_done = false;
+                        stack.push(parent_idx);
+                        continue;
+                    }
+                    Parent::New(parent_idx) => (levels[parent_idx], corrected_dates[parent_idx]),
+                    Parent::Base {
+                        level, corrected_date, ..
+                    } => (level, corrected_date),
+                };
+                level = level.max(parent_level);
+                corrected_date = corrected_date.max(parent_corrected_date);
+            }
+            if !all_parents_done {
+                continue;
+            }
+            stack.pop();
+            levels[idx] = (level + 1).min(GENERATION_NUMBER_MAX);
+            corrected_dates[idx] = (corrected_date + 1).max(commits[idx].committer_timestamp.min(MAX_COMMIT_TIMESTAMP));
+        }
+    }
+    (levels, corrected_dates)
+}
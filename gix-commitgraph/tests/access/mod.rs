@@ -111,3 +111,54 @@ fn two_parents() {
     assert_eq!(cg.commit_at(refs["parent2"].pos()).generation(), 1);
     assert_eq!(cg.commit_at(refs["child"].pos()).generation(), 2);
 }
+
+#[test]
+fn changed_path_bloom_filters() {
+    let (cg, refs) = graph_and_expected("changed_paths.sh", &["initial", "sub-directory", "non-ascii", "empty"]);
+    check_common(&cg, &refs);
+
+    let may_have_changed = |name: &str, path: &str| cg.may_have_changed(refs[name].id(), path.into());
+    for path in ["dir", "dir/sub", "dir/sub/file", "dir/sub/", "ä", "ä/ö", "top"] {
+        assert_eq!(may_have_changed("initial", path), Some(true), "{path}");
+    }
+    for path in ["dir", "dir/sub", "dir/sub/file"] {
+        assert_eq!(may_have_changed("sub-directory", path), Some(true), "{path}");
+        assert_eq!(may_have_changed("non-ascii", path), Some(false), "{path}");
+    }
+    for path in ["ä", "ä/ö"] {
+        assert_eq!(may_have_changed("sub-directory", path), Some(false), "{path}");
+        assert_eq!(
+            may_have_changed("non-ascii", path),
+            Some(true),
+            "{path}: paths are hashed like git does"
+        );
+    }
+    for path in ["top", "dir/sub/other", "missing"] {
+        assert_eq!(may_have_changed("sub-directory", path), Some(false), "{path}");
+    }
+    assert_eq!(
+        may_have_changed("empty", "dir/sub/file"),
+        Some(false),
+        "nothing changed, nothing is contained"
+    );
+    assert_eq!(
+        may_have_changed("empty", ""),
+        Some(true),
+        "empty paths are always contained"
+    );
+
+    let file = &cg.files()[0];
+    assert_eq!(
+        file.bloom_settings()
+            .map(|s| (s.hash_version, s.num_hashes, s.bits_per_entry)),
+        Some((1, 7, 10)),
+        "the defaults of git"
+    );
+
+    let (cg, refs) = graph_and_expected("single_parent.sh", &["parent", "child"]);
+    assert_eq!(
+        cg.may_have_changed(refs["child"].id(), "file".into()),
+        None,
+        "the graph was written without changed-path filters"
+    );
+}
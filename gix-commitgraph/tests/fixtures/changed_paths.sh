@@ -0,0 +1,21 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir -p dir/sub ä
+echo 1 > dir/sub/file
+echo 1 > ä/ö
+echo 1 > top
+git add . && git commit -q -m initial && git tag initial
+
+echo 2 > dir/sub/file
+git commit -q -am "change file in sub-directory" && git tag sub-directory
+
+echo 2 > ä/ö
+git commit -q -am "change non-ascii path" && git tag non-ascii
+
+git commit -q --allow-empty -m "change nothing" && git tag empty
+
+git commit-graph write --no-progress --reachable --changed-paths
+git repack -adq
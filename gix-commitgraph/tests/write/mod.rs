@@ -0,0 +1,286 @@
+use std::path::Path;
+
+use gix_commitgraph::{bloom, write, Graph};
+
+use crate::{check_common, graph_and_expected};
+
+/// Turn all commits of `graph` back into commits to write.
+fn commits_of(graph: &Graph) -> Vec<write::Commit> {
+    graph
+        .iter_commits()
+        .map(|commit| write::Commit {
+            id: commit.id().to_owned(),
+            tree: commit.root_tree_id().to_owned(),
+            parents: commit
+                .iter_parents()
+                .map(|parent| graph.id_at(parent.expect("valid parent")).to_owned())
+                .collect(),
+            committer_timestamp: commit.committer_timestamp(),
+            changed_paths: None,
+        })
+        .collect()
+}
+
+fn write_file(
+    dir: &Path,
+    commits: Vec<write::Commit>,
+    base: Option<&Graph>,
+    options: write::Options,
+) -> (gix_commitgraph::File, write::Outcome) {
+    let mut buf = Vec::new();
+    let outcome = write::write_to(commits, base, &mut buf, options).expect("writing succeeds");
+    let path = dir.join(format!("graph-{}.graph", outcome.checksum));
+    std::fs::write(&path, buf).expect("can write file");
+    (
+        gix_commitgraph::File::at(&path).expect("written file is valid"),
+        outcome,
+    )
+}
+
+#[test]
+fn written_files_are_equivalent_to_the_ones_written_by_git() -> gix_testtools::Result {
+    for (script, refs) in [
+        ("two_parents.sh", &["parent1", "parent2", "child"][..]),
+        (
+            "octopus_merges.sh",
+            &[
+                "root",
+                "parent1",
+                "parent2",
+                "parent3",
+                "parent4",
+                "three_parents",
+                "four_parents",
+            ][..],
+        ),
+    ] {
+        let (graph, expected) = graph_and_expected(script, refs);
+        let dir = gix_testtools::tempfile::tempdir()?;
+        let (file, outcome) = write_file(dir.path(), commits_of(&graph), None, Default::default());
+        assert_eq!(outcome.num_commits, graph.num_commits());
+        assert!(outcome.generation_data);
+        assert!(file.has_generation_data());
+
+        let original = &graph.files()[0];
+        if original.has_generation_data() {
+            assert_eq!(
+                std::fs::read(file.path())?,
+                std::fs::read(original.path())?,
+                "files are byte-for-byte identical if git wrote the same chunks"
+            );
+        }
+        let written = Graph::new(vec![file])?;
+        check_common(&written, &expected);
+        for commit in graph.iter_commits() {
+            let actual = written.commit_by_id(commit.id()).expect("all commits are present");
+            assert_eq!(actual.generation(), commit.generation());
+            if let Some(expected) = commit.corrected_commit_date() {
+                assert_eq!(actual.corrected_commit_date(), Some(expected));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn corrected_commit_dates_are_larger_than_those_of_all_parents() -> gix_testtools::Result {
+    let id = |byte: u8| gix_hash::ObjectId::from([byte; 20]);
+    let commit = |byte: u8, parents: &[u8], committer_timestamp: u64| write::Commit {
+        id: id(byte),
+        tree: gix_hash::ObjectId::empty_tree(gix_hash::Kind::Sha1),
+        parents: parents.iter().copied().map(id).collect(),
+        committer_timestamp,
+        changed_paths: None,
+    };
+    let dir = gix_testtools::tempfile::tempdir()?;
+    // The child was committed long before its parent, and the offset doesn't fit into 31 bits.
+    let (file, _) = write_file(
+        dir.path(),
+        vec![commit(2, &[1], 10), commit(1, &[], 10 + (1 << 32)), commit(3, &[2], 20)],
+        None,
+        Default::default(),
+    );
+    let graph = Graph::new(vec![file])?;
+    graph.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+    let dates: Vec<_> = (1..=3)
+        .map(|byte| {
+            let commit = graph.commit_by_id(id(byte)).expect("present");
+            (commit.generation(), commit.corrected_commit_date())
+        })
+        .collect();
+    assert_eq!(
+        dates,
+        [
+            (1, Some(10 + (1 << 32))),
+            (2, Some(11 + (1 << 32))),
+            (3, Some(12 + (1 << 32)))
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn layers_only_contain_new_commits_and_refer_to_their_base() -> gix_testtools::Result {
+    let (graph, expected) = graph_and_expected(
+        "octopus_merges.sh",
+        &[
+            "root",
+            "parent1",
+            "parent2",
+            "parent3",
+            "parent4",
+            "three_parents",
+            "four_parents",
+        ],
+    );
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let all = commits_of(&graph);
+    let merges = [&expected["three_parents"], &expected["four_parents"]];
+    let base_commits = all
+        .iter()
+        .filter(|c| merges.iter().all(|info| info.id() != c.id))
+        .cloned()
+        .collect();
+    let (base_file, base_outcome) = write_file(dir.path(), base_commits, None, Default::default());
+    let base = Graph::new(vec![base_file])?;
+
+    let (layer, outcome) = write_file(dir.path(), all, Some(&base), Default::default());
+    assert_eq!(
+        outcome.num_commits, 2,
+        "only commits that aren't in the base are written"
+    );
+    assert_eq!(layer.base_graph_count(), 1);
+    assert_eq!(
+        layer.iter_base_graph_ids().collect::<Vec<_>>(),
+        [base_outcome.checksum.as_ref()]
+    );
+
+    let base_file = base.files()[0].path().to_owned();
+    let chain = Graph::new(vec![gix_commitgraph::File::at(base_file)?, layer])?;
+    chain.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+    assert_eq!(chain.num_commits(), graph.num_commits());
+    for commit in graph.iter_commits() {
+        let actual = chain.commit_by_id(commit.id()).expect("present in chain");
+        assert_eq!(actual.generation(), commit.generation());
+        assert_eq!(actual.corrected_commit_date(), commit.corrected_commit_date());
+        let parent_ids = |graph: &Graph, commit: gix_commitgraph::file::Commit<'_>| {
+            commit
+                .iter_parents()
+                .map(|parent| graph.id_at(parent.expect("valid")).to_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(parent_ids(&chain, actual), parent_ids(&graph, commit));
+    }
+    Ok(())
+}
+
+#[test]
+fn missing_parents_are_an_error() {
+    let mut buf = Vec::new();
+    let err = write::write_to(
+        vec![write::Commit {
+            id: gix_hash::ObjectId::from([1; 20]),
+            tree: gix_hash::ObjectId::empty_tree(gix_hash::Kind::Sha1),
+            parents: vec![gix_hash::ObjectId::from([2; 20])],
+            committer_timestamp: 0,
+            changed_paths: None,
+        }],
+        None,
+        &mut buf,
+        Default::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, write::Error::MissingParent { .. }));
+}
+
+mod bloom_filters {
+    use gix_commitgraph::bloom::{self, Key, Settings};
+
+    #[test]
+    fn keys_match_the_ones_computed_by_git() {
+        // Taken from `t0095-bloom.sh` in the `git` test suite.
+        assert_eq!(
+            Key::new("".into(), &Settings::default()).hashes(),
+            [0x5615800c, 0x5b966560, 0x61174ab4, 0x66983008, 0x6c19155c, 0x7199fab0, 0x771ae004]
+        );
+        assert_eq!(
+            Key::new("Hello world!".into(), &Settings::default()).hashes(),
+            [0xb270de9b, 0x1bb6f26e, 0x84fd0641, 0xee431a14, 0x57892de7, 0xc0cf41ba, 0x2a15558d]
+        );
+    }
+
+    #[test]
+    fn hash_version_2_only_differs_for_non_ascii_paths() {
+        let v1 = Settings::default();
+        let v2 = Settings {
+            hash_version: 2,
+            ..Default::default()
+        };
+        assert_eq!(Key::new("dir/file".into(), &v1), Key::new("dir/file".into(), &v2));
+        assert_ne!(Key::new("ä/ö".into(), &v1), Key::new("ä/ö".into(), &v2));
+    }
+
+    #[test]
+    fn filters_contain_changed_paths_and_their_leading_directories() {
+        let settings = Settings::default();
+        let filter = bloom::filter_from_changed_paths(Some(["dir/sub/file".into(), "other".into()]), &settings);
+        assert_eq!(filter.len(), 5, "4 paths with 10 bits each");
+        for path in ["dir", "dir/sub", "dir/sub/file", "other"] {
+            assert!(bloom::contains(&filter, &Key::new(path.into(), &settings)), "{path}");
+        }
+    }
+
+    #[test]
+    fn filters_without_changes_contain_nothing() {
+        let settings = Settings::default();
+        let filter = bloom::filter_from_changed_paths(Some(None::<&bstr::BStr>), &settings);
+        assert_eq!(filter, [0]);
+        assert!(!bloom::contains(&filter, &Key::new("file".into(), &settings)));
+    }
+
+    #[test]
+    fn filters_with_too_many_or_unknown_changes_contain_everything() {
+        let settings = Settings {
+            max_changed_paths: 2,
+            ..Default::default()
+        };
+        let key = Key::new("anything".into(), &settings);
+        let filter = bloom::filter_from_changed_paths(Some(["a/b/c".into()]), &settings);
+        assert_eq!(filter, [0xff]);
+        assert!(bloom::contains(&filter, &key));
+
+        let filter = bloom::filter_from_changed_paths(None::<Vec<&bstr::BStr>>, &settings);
+        assert_eq!(filter, [0xff]);
+        assert!(bloom::contains(&filter, &key));
+    }
+}
+
+#[test]
+fn changed_path_filters_are_written_for_all_commits() -> gix_testtools::Result {
+    let (graph, _) = graph_and_expected("single_parent.sh", &["parent", "child"]);
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let commits = commits_of(&graph)
+        .into_iter()
+        .map(|mut c| {
+            c.changed_paths = Some(vec!["a/file".into()]);
+            c
+        })
+        .collect();
+    let (file, _) = write_file(
+        dir.path(),
+        commits,
+        None,
+        write::Options {
+            changed_paths: Some(bloom::Settings::default()),
+            ..Default::default()
+        },
+    );
+    let graph = Graph::new(vec![file])?;
+    graph.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+    for id in graph.iter_ids() {
+        assert_eq!(graph.may_have_changed(id, "a/file".into()), Some(true));
+        assert_eq!(graph.may_have_changed(id, "a".into()), Some(true));
+        assert_eq!(graph.may_have_changed(id, "b/file".into()), Some(false));
+    }
+    Ok(())
+}
@@ -248,6 +248,39 @@ impl<'event> File<'event> {
         self.remove_section_by_id(id)
     }
 
+    /// Remove all sections with `name` and `subsection_name` and return them in the order in which they appeared,
+    /// similar to `git config --remove-section`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::convert::TryFrom;
+    /// let mut git_config = gix_config::File::try_from(
+    /// r#"[hello "world"]
+    ///     some-value = 4
+    /// [core]
+    ///     a = b
+    /// [hello "world"]
+    ///     some-value = 5
+    /// "#)?;
+    ///
+    /// let sections = git_config.remove_sections("hello", Some("world".into()));
+    /// assert_eq!(sections.len(), 2);
+    /// assert_eq!(git_config.to_string(), "[core]\n    a = b\n");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn remove_sections<'a>(
+        &mut self,
+        name: impl AsRef<str>,
+        subsection_name: impl Into<Option<&'a BStr>>,
+    ) -> Vec<file::Section<'event>> {
+        let ids: Vec<_> = match self.section_ids_by_name_and_subname(name.as_ref(), subsection_name.into()) {
+            Ok(ids) => ids.collect(),
+            Err(_) => return Vec::new(),
+        };
+        ids.into_iter().filter_map(|id| self.remove_section_by_id(id)).collect()
+    }
+
     /// Remove the section identified by `id` if it exists and return it, or return `None` if no such section was present.
     ///
     /// Note that section ids are unambiguous even in the face of removals and additions of sections.
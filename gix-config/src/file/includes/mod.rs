@@ -38,7 +38,53 @@ impl File<'static> {
 }
 
 pub(crate) fn resolve(config: &mut File<'static>, buf: &mut Vec<u8>, options: init::Options<'_>) -> Result<(), Error> {
-    resolve_includes_recursive(config, 0, buf, options)
+    let has_includes = ["include", "includeIf"]
+        .iter()
+        .any(|name| config.sections_by_name(name).is_some());
+    let mut remote_urls = if has_includes {
+        RemoteUrls::Uncollected(Box::new(config.clone()))
+    } else {
+        RemoteUrls::Collected(Vec::new())
+    };
+    resolve_includes_recursive(config, 0, buf, options, &mut remote_urls)
+}
+
+/// The remote URLs to match `hasconfig:remote.*.url:` conditions against.
+///
+/// Like `git`, they are collected from the entire configuration including all of its includes, no matter where they are defined,
+/// but only once the first such condition is encountered.
+enum RemoteUrls {
+    /// The configuration to collect remote URLs from, before any of its includes were resolved.
+    Uncollected(Box<File<'static>>),
+    /// We are collecting remote URLs right now, so `hasconfig:remote.*.url:` conditions never match.
+    Collecting,
+    /// All remote URLs of the configuration.
+    Collected(Vec<BString>),
+}
+
+impl RemoteUrls {
+    fn get(&mut self, buf: &mut Vec<u8>, options: init::Options<'_>) -> Result<&[BString], Error> {
+        if let RemoteUrls::Uncollected(config) = std::mem::replace(self, RemoteUrls::Collecting) {
+            let mut config = *config;
+            resolve_includes_recursive(&mut config, 0, buf, options, self)?;
+            *self = RemoteUrls::Collected(remote_urls_of(&config).collect());
+        }
+        Ok(match self {
+            RemoteUrls::Collected(urls) => urls,
+            RemoteUrls::Collecting => &[],
+            RemoteUrls::Uncollected(_) => unreachable!("collected above"),
+        })
+    }
+}
+
+fn remote_urls_of<'a>(config: &'a File<'static>) -> impl Iterator<Item = BString> + 'a {
+    config
+        .sections_by_name("remote")
+        .into_iter()
+        .flatten()
+        .filter(|section| section.header().subsection_name().is_some())
+        .flat_map(|section| section.values("url"))
+        .map(Cow::into_owned)
 }
 
 fn resolve_includes_recursive(
@@ -46,6 +92,7 @@ fn resolve_includes_recursive(
     depth: u8,
     buf: &mut Vec<u8>,
     options: init::Options<'_>,
+    remote_urls: &mut RemoteUrls,
 ) -> Result<(), Error> {
     if depth == options.includes.max_depth {
         return if options.includes.err_on_max_depth_exceeded {
@@ -66,28 +113,47 @@ fn resolve_includes_recursive(
         let header = &section.header;
         let header_name = header.name.as_ref();
         if header_name == "include" && header.subsection_name.is_none() {
-            detach_include_paths(&mut section_ids_and_include_paths, section, id)
+            detach_include_paths(&mut section_ids_and_include_paths, section, id, Condition::Other)
         } else if header_name == "includeIf" {
             if let Some(condition) = &header.subsection_name {
                 let target_config_path = section.meta.path.as_deref();
-                if include_condition_match(condition.as_ref(), target_config_path, options.includes)? {
-                    detach_include_paths(&mut section_ids_and_include_paths, section, id)
+                if let Some(condition) =
+                    include_condition_match(condition.as_ref(), target_config_path, options, buf, remote_urls)?
+                {
+                    detach_include_paths(&mut section_ids_and_include_paths, section, id, condition)
                 }
             }
         }
     }
 
-    append_followed_includes_recursively(section_ids_and_include_paths, target_config, depth, options, buf)
+    append_followed_includes_recursively(
+        section_ids_and_include_paths,
+        target_config,
+        depth,
+        options,
+        buf,
+        remote_urls,
+    )
+}
+
+/// The kind of condition that caused a file to be included.
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Condition {
+    /// A `hasconfig:remote.*.url:` condition, whose included files must not define remote URLs.
+    RemoteUrl,
+    /// Any other condition, or no condition at all.
+    Other,
 }
 
 fn append_followed_includes_recursively(
-    section_ids_and_include_paths: Vec<(SectionId, crate::Path<'_>)>,
+    section_ids_and_include_paths: Vec<(SectionId, crate::Path<'_>, Condition)>,
     target_config: &mut File<'static>,
     depth: u8,
     options: init::Options<'_>,
     buf: &mut Vec<u8>,
+    remote_urls: &mut RemoteUrls,
 ) -> Result<(), Error> {
-    for (section_id, config_path) in section_ids_and_include_paths {
+    for (section_id, config_path, condition) in section_ids_and_include_paths {
         let meta = OwnShared::clone(&target_config.sections[&section_id].meta);
         let target_config_path = meta.path.as_deref();
         let config_path = match resolve_path(config_path, target_config_path, options.includes)? {
@@ -117,7 +183,16 @@ fn append_followed_includes_recursively(
                 init::Error::Interpolate(err) => Error::Interpolate(err),
                 init::Error::Includes(_) => unreachable!("BUG: {:?} not possible due to no-follow options", err),
             })?;
-        resolve_includes_recursive(&mut include_config, depth + 1, buf, options)?;
+        resolve_includes_recursive(&mut include_config, depth + 1, buf, options, remote_urls)?;
+        if condition == Condition::RemoteUrl && remote_urls_of(&include_config).next().is_some() {
+            return Err(Error::RemoteUrlInRemoteUrlCondition {
+                path: include_config
+                    .meta()
+                    .path
+                    .clone()
+                    .expect("included configuration has a path"),
+            });
+        }
 
         target_config.append_or_insert(include_config, Some(section_id));
     }
@@ -125,46 +200,64 @@ fn append_followed_includes_recursively(
 }
 
 fn detach_include_paths(
-    include_paths: &mut Vec<(SectionId, crate::Path<'static>)>,
+    include_paths: &mut Vec<(SectionId, crate::Path<'static>, Condition)>,
     section: &file::Section<'_>,
     id: SectionId,
+    condition: Condition,
 ) {
     include_paths.extend(
         section
             .body
             .values("path")
             .into_iter()
-            .map(|path| (id, crate::Path::from(Cow::Owned(path.into_owned())))),
+            .map(|path| (id, crate::Path::from(Cow::Owned(path.into_owned())), condition)),
     )
 }
 
+/// Return the kind of `condition` if it matches.
 fn include_condition_match(
     condition: &BStr,
     target_config_path: Option<&Path>,
-    options: Options<'_>,
-) -> Result<bool, Error> {
+    options: init::Options<'_>,
+    buf: &mut Vec<u8>,
+    remote_urls: &mut RemoteUrls,
+) -> Result<Option<Condition>, Error> {
     let mut tokens = condition.splitn(2, |b| *b == b':');
     let (prefix, condition) = match (tokens.next(), tokens.next()) {
         (Some(a), Some(b)) => (a, b),
-        _ => return Ok(false),
+        _ => return Ok(None),
     };
     let condition = condition.as_bstr();
-    match prefix {
+    let is_match = match prefix {
         b"gitdir" => gitdir_matches(
             condition,
             target_config_path,
-            options,
+            options.includes,
             gix_glob::wildmatch::Mode::empty(),
-        ),
+        )?,
         b"gitdir/i" => gitdir_matches(
             condition,
             target_config_path,
-            options,
+            options.includes,
             gix_glob::wildmatch::Mode::IGNORE_CASE,
-        ),
-        b"onbranch" => Ok(onbranch_matches(condition, options.conditional).is_some()),
-        _ => Ok(false),
-    }
+        )?,
+        b"onbranch" => onbranch_matches(condition, options.includes.conditional).is_some(),
+        b"hasconfig" => {
+            let Some(pattern) = condition.strip_prefix(b"remote.*.url:") else {
+                return Ok(None);
+            };
+            let is_match = remote_urls.get(buf, options)?.iter().any(|url| {
+                gix_glob::wildmatch(
+                    pattern.as_bstr(),
+                    url.as_bstr(),
+                    gix_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+                )
+            });
+            return Ok(is_match.then_some(Condition::RemoteUrl));
+        }
+        _ => false,
+    };
+    Ok(is_match.then_some(Condition::Other))
 }
 
 fn onbranch_matches(
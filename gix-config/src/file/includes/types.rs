@@ -18,6 +18,8 @@ pub enum Error {
     MissingGitDir,
     #[error(transparent)]
     Realpath(#[from] gix_path::realpath::Error),
+    #[error("Remote URLs must not be configured in '{}' as it's included by `includeIf.hasconfig:remote.*.url`", path.display())]
+    RemoteUrlInRemoteUrlCondition { path: std::path::PathBuf },
 }
 
 /// Options to handle includes, like `include.path` or `includeIf.<condition>.path`,
@@ -60,6 +62,7 @@ impl<'a> Options<'a> {
     }
     /// Provide options to follow includes like git does, provided the required `conditional` and `interpolate` contexts
     /// to support `gitdir` and `onbranch` based `includeIf` directives as well as standard `include.path` resolution.
+    /// `hasconfig:remote.*.url` based `includeIf` directives are supported without additional context.
     /// Note that the follow-mode is `git`-style, following at most 10 indirections while
     /// producing an error if the depth is exceeded.
     pub fn follow(interpolate: interpolate::Context<'a>, conditional: conditional::Context<'a>) -> Self {
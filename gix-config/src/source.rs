@@ -52,6 +52,22 @@ impl Source {
         }
     }
 
+    /// Return the name of the scope of this source as shown by `git config --show-scope`, which is one of
+    /// `system`, `global`, `local`, `worktree` or `command`.
+    ///
+    /// Configuration that ships with the git installation is considered to be in the `system` scope, and all overrides,
+    /// including those set programmatically or by environment variables, are in the `command` scope.
+    pub const fn scope(self) -> &'static str {
+        use Source::*;
+        match self {
+            GitInstallation | System => "system",
+            Git | User => "global",
+            Local => "local",
+            Worktree => "worktree",
+            Env | Cli | Api | EnvOverride => "command",
+        }
+    }
+
     /// Returns the location at which a file of this type would be stored, or `None` if
     /// there is no notion of persistent storage for this source, with `env_var` to obtain environment variables.
     /// Note that the location can be relative for repository-local sources like `Local` and `Worktree`,
@@ -45,6 +45,26 @@ mod remove_section {
         file.section_mut_or_create_new("core", Some("name".into()))
             .expect("creation succeeds");
     }
+
+    #[test]
+    fn removal_of_all_sections_with_the_same_name_keeps_everything_else() {
+        let input = "# head\n[core]\n\ta = b\n[remote \"origin\"]\n\turl = x ; comment\n[core]\n\tc = d\n[core \"name\"]\n\te = f\n";
+        let mut file = gix_config::File::try_from(input).unwrap();
+        let removed = file.remove_sections("core", None);
+        assert_eq!(removed.len(), 2);
+        assert_eq!(
+            removed[0].value("a").expect("present").as_ref(),
+            "b",
+            "in order of appearance"
+        );
+        assert_eq!(
+            file.to_string(),
+            "# head\n[remote \"origin\"]\n\turl = x ; comment\n[core \"name\"]\n\te = f\n"
+        );
+        assert!(file.remove_sections("core", None).is_empty(), "nothing left to remove");
+        assert!(file.remove_sections("unknown", None).is_empty());
+        file.section_mut_or_create_new("core", None).expect("creation succeeds");
+    }
 }
 mod rename_section {
     use std::{borrow::Cow, convert::TryFrom};
@@ -0,0 +1,121 @@
+use std::fs;
+
+use gix_config::{file::includes, File};
+use gix_testtools::tempfile::tempdir;
+
+use crate::file::{cow_str, init::from_paths::escape_backslashes};
+
+fn config_with_root(
+    root: &str,
+    included: &str,
+) -> crate::Result<Result<File<'static>, gix_config::file::init::from_paths::Error>> {
+    let dir = tempdir()?;
+    let included_path = dir.path().join("included");
+    fs::write(&included_path, included)?;
+    let root_path = dir.path().join("root");
+    fs::write(
+        &root_path,
+        root.replace("INCLUDED", &escape_backslashes(&included_path)),
+    )?;
+    let options = gix_config::file::init::Options {
+        includes: includes::Options::follow(Default::default(), Default::default()),
+        ..Default::default()
+    };
+    let meta = gix_config::file::Metadata::try_from_path(&root_path, gix_config::Source::Local)?;
+    Ok(File::from_paths_metadata(Some(meta), options).map(|config| config.expect("non-empty")))
+}
+
+#[test]
+fn remote_urls_defined_later_are_used_for_matching() -> crate::Result {
+    let config = config_with_root(
+        r#"
+[section]
+    value = base
+[includeIf "hasconfig:remote.*.url:https://example.com/**"]
+    path = INCLUDED
+[remote "origin"]
+    url = https://example.com/org/repo"#,
+        "[section]\n  value = included",
+    )??;
+    assert_eq!(config.string("section", None, "value"), Some(cow_str("included")));
+    Ok(())
+}
+
+#[test]
+fn remote_urls_of_included_files_are_used_for_matching() -> crate::Result {
+    let dir = tempdir()?;
+    let remote_path = dir.path().join("remote");
+    fs::write(&remote_path, "[remote \"origin\"]\n  url = git@example.com:org/repo")?;
+    let config = config_with_root(
+        &format!(
+            r#"
+[section]
+    value = base
+[includeIf "hasconfig:remote.*.url:git@example.com:*/**"]
+    path = INCLUDED
+[include]
+    path = {}"#,
+            escape_backslashes(&remote_path)
+        ),
+        "[section]\n  value = included",
+    )??;
+    assert_eq!(config.string("section", None, "value"), Some(cow_str("included")));
+    Ok(())
+}
+
+#[test]
+fn non_matching_urls_do_not_include() -> crate::Result {
+    for pattern in [
+        "https://example.com/*",
+        "https://other.com/**",
+        "https://example.com/**",
+    ] {
+        let config = config_with_root(
+            &format!(
+                r#"
+[section]
+    value = base
+[includeIf "hasconfig:remote.*.url:{pattern}"]
+    path = INCLUDED
+[remote "origin"]
+    pushUrl = https://example.com/org/repo
+[remote]
+    url = https://example.com/org/repo
+[other "origin"]
+    url = https://example.com/org/repo
+[remote "upstream"]
+    url = https://example.com/org/repo/"#
+            ),
+            "[section]\n  value = included",
+        )??;
+        let expected = if pattern == "https://example.com/**" {
+            "included"
+        } else {
+            "base"
+        };
+        assert_eq!(
+            config.string("section", None, "value"),
+            Some(cow_str(expected)),
+            "{pattern}: wildcards don't match slashes, and only `remote.<name>.url` counts"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn included_files_must_not_define_remote_urls() -> crate::Result {
+    let err = config_with_root(
+        r#"
+[includeIf "hasconfig:remote.*.url:https://example.com/**"]
+    path = INCLUDED
+[remote "origin"]
+    url = https://example.com/org/repo"#,
+        "[remote \"other\"]\n  url = https://example.com/other",
+    )?
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("must not be configured"),
+        "it's an error just like in git: {err}"
+    );
+    Ok(())
+}
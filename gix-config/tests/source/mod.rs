@@ -83,3 +83,21 @@ fn git_config_global() {
         );
     }
 }
+
+#[test]
+fn scope() {
+    for (source, expected) in [
+        (Source::GitInstallation, "system"),
+        (Source::System, "system"),
+        (Source::Git, "global"),
+        (Source::User, "global"),
+        (Source::Local, "local"),
+        (Source::Worktree, "worktree"),
+        (Source::Env, "command"),
+        (Source::Cli, "command"),
+        (Source::Api, "command"),
+        (Source::EnvOverride, "command"),
+    ] {
+        assert_eq!(source.scope(), expected, "{source:?}");
+    }
+}
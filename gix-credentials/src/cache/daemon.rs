@@ -0,0 +1,177 @@
+use std::{
+    io::{Read, Write},
+    os::unix::{
+        fs::{DirBuilderExt, PermissionsExt},
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use bstr::ByteSlice;
+
+use crate::protocol::Context;
+
+/// The time to wait for the first credential to store before stopping, the same as in `git credential-cache--daemon`.
+/// Once credentials were stored, the daemon stops as soon as all of them expired.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct Entry {
+    ctx: Context,
+    expires_at: Instant,
+}
+
+/// Remove the socket file once the daemon stops.
+struct RemoveOnDrop<'a>(&'a Path);
+
+impl Drop for RemoveOnDrop<'_> {
+    fn drop(&mut self) {
+        std::fs::remove_file(self.0).ok();
+    }
+}
+
+/// Listen for requests on `socket`, calling `on_ready` once requests are accepted, and keep stored credentials in memory until
+/// they expire. Return once an `exit` request is received, or once there are no credentials left to keep.
+///
+/// The directory containing `socket` is created if needed and only made accessible to the current user, and it's an error
+/// if it exists and is accessible by others.
+/// Programs serving as daemon for [`credentials()`](super::credentials()) should print `ok\n` in `on_ready`.
+pub fn serve(socket: &Path, on_ready: impl FnOnce()) -> std::io::Result<()> {
+    if let Some(dir) = socket.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        init_socket_directory(dir)?;
+    }
+    std::fs::remove_file(socket).ok();
+    let listener = UnixListener::bind(socket)?;
+    let _remove_socket = RemoveOnDrop(socket);
+    on_ready();
+
+    let wake_up = spawn_wake_up_thread(socket.to_owned());
+    let mut idle_until = Some(Instant::now() + IDLE_TIMEOUT);
+    wake_up.send(idle_until.expect("just set")).ok();
+    let mut entries = Vec::<Entry>::new();
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        if let Ok(Exit::Yes) = handle(stream, &mut entries) {
+            break;
+        }
+        if !entries.is_empty() {
+            idle_until = None;
+        }
+        let now = Instant::now();
+        entries.retain(|e| e.expires_at > now);
+        let next_wake_up = match (entries.iter().map(|e| e.expires_at).min(), idle_until) {
+            (Some(next_expiry), _) => next_expiry,
+            (None, Some(idle_until)) if idle_until > now => idle_until,
+            (None, _) => break,
+        };
+        wake_up.send(next_wake_up).ok();
+    }
+    Ok(())
+}
+
+enum Exit {
+    Yes,
+    No,
+}
+
+fn handle(mut stream: UnixStream, entries: &mut Vec<Entry>) -> std::io::Result<Exit> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut buf = Vec::new();
+    stream.read_to_end(&mut buf)?;
+
+    let mut lines = buf.lines();
+    let (Some(action), Some(timeout)) = (
+        lines.next().and_then(|line| line.strip_prefix(b"action=")),
+        lines
+            .next()
+            .and_then(|line| line.strip_prefix(b"timeout="))
+            .and_then(|timeout| timeout.to_str().ok()?.parse::<u64>().ok()),
+    ) else {
+        // Malformed requests, including the empty ones used to wake us up, are ignored.
+        return Ok(Exit::No);
+    };
+    let header_len = buf.lines_with_terminator().take(2).map(<[u8]>::len).sum::<usize>();
+    let Ok(ctx) = Context::from_bytes(&buf[header_len..]) else {
+        return Ok(Exit::No);
+    };
+    let now = Instant::now();
+    match action {
+        b"get" => {
+            if let Some(entry) = entries
+                .iter()
+                .find(|e| e.expires_at > now && matches(&ctx, &e.ctx, false))
+            {
+                let found = Context {
+                    username: entry.ctx.username.clone(),
+                    password: entry.ctx.password.clone(),
+                    ..Default::default()
+                };
+                let mut out = Vec::new();
+                found.write_to(&mut out)?;
+                stream.write_all(&out)?;
+            }
+        }
+        b"store" if ctx.username.is_some() && ctx.password.is_some() => {
+            entries.retain(|e| !matches(&ctx, &e.ctx, false));
+            entries.push(Entry {
+                ctx,
+                expires_at: now + Duration::from_secs(timeout),
+            });
+        }
+        b"erase" => entries.retain(|e| !matches(&ctx, &e.ctx, true)),
+        b"exit" => return Ok(Exit::Yes),
+        _ => {}
+    }
+    Ok(Exit::No)
+}
+
+/// Return `true` if all fields in `want` are the same in `have`, like `git` does.
+fn matches(want: &Context, have: &Context, match_password: bool) -> bool {
+    fn field<T: PartialEq>(want: &Option<T>, have: &Option<T>) -> bool {
+        want.is_none() || want == have
+    }
+    field(&want.protocol, &have.protocol)
+        && field(&want.host, &have.host)
+        && field(&want.path, &have.path)
+        && field(&want.username, &have.username)
+        && (!match_password || field(&want.password, &have.password))
+}
+
+/// Connect to `socket` once the latest instant received over the returned channel is reached, to let the daemon
+/// expire credentials even if there are no requests. The thread stops once the channel is closed.
+fn spawn_wake_up_thread(socket: PathBuf) -> mpsc::Sender<Instant> {
+    let (tx, rx) = mpsc::channel::<Instant>();
+    std::thread::spawn(move || {
+        let mut wake_up_at: Option<Instant> = None;
+        loop {
+            let next = match wake_up_at {
+                Some(at) => rx.recv_timeout(at.saturating_duration_since(Instant::now())),
+                None => rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+            };
+            match next {
+                Ok(at) => wake_up_at = Some(at),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    wake_up_at = None;
+                    UnixStream::connect(&socket).ok();
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+    tx
+}
+
+fn init_socket_directory(dir: &Path) -> std::io::Result<()> {
+    match std::fs::metadata(dir) {
+        Ok(meta) if meta.permissions().mode() & 0o077 != 0 => Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!(
+                "The permissions on the socket directory '{}' are too loose, as other users may be able to read cached credentials",
+                dir.display()
+            ),
+        )),
+        Ok(_) => Ok(()),
+        Err(_) => std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir),
+    }
+}
@@ -0,0 +1,185 @@
+//! Cache credentials in memory of a daemon for a limited time, compatible with `git credential-cache` and
+//! `git credential-cache--daemon`, so either side can be used with the other.
+//!
+//! The client side is [`credentials()`], which connects to the daemon through a unix socket, and starts it when credentials
+//! are stored and it isn't running yet. The daemon side is [`daemon::serve()`], which keeps credentials until they expire,
+//! and stops once there are no more credentials to keep.
+use std::{
+    ffi::OsString,
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{program::main::Action, protocol::Context};
+
+///
+pub mod daemon;
+
+/// The time credentials are kept by default, the same as in `git credential-cache`.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(900);
+
+/// The error returned by [`credentials()`] and [`exit()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not communicate with the credential cache daemon at '{}'", socket.display())]
+    Io { socket: PathBuf, source: std::io::Error },
+    #[error("No program was set to start the credential cache daemon with")]
+    MissingDaemonProgram,
+    #[error("Could not start the credential cache daemon {program:?}")]
+    SpawnDaemon { program: OsString, source: std::io::Error },
+    #[error("The credential cache daemon did not start: {output:?}")]
+    DaemonStart { output: String },
+    #[error("The response of the credential cache daemon could not be decoded")]
+    Decode(#[from] crate::protocol::context::decode::Error),
+}
+
+/// Options for use in [`credentials()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The path to the unix socket of the daemon.
+    pub socket: PathBuf,
+    /// The time for which stored credentials are kept.
+    pub timeout: Duration,
+    /// The program along with its arguments to start the daemon with if it isn't running yet, with the socket path
+    /// passed as last argument. It must print `ok\n` once it's ready to serve requests, like [`daemon::serve()`] allows.
+    ///
+    /// It's `git credential-cache--daemon` by default. If empty, storing credentials fails unless the daemon is running.
+    pub daemon: Vec<OsString>,
+}
+
+impl Options {
+    /// Create a new instance to talk to the daemon at `socket`, with all other values set to their defaults.
+    pub fn new(socket: impl Into<PathBuf>) -> Self {
+        Options {
+            socket: socket.into(),
+            timeout: DEFAULT_TIMEOUT,
+            daemon: vec!["git".into(), "credential-cache--daemon".into()],
+        }
+    }
+}
+
+/// Return the path of the socket `git credential-cache` uses by default, which is `~/.git-credential-cache/socket` if its
+/// directory exists, or `$XDG_CACHE_HOME/git/credential/socket` otherwise, with `~/.cache` being the default for
+/// `$XDG_CACHE_HOME`.
+pub fn socket_path() -> Option<PathBuf> {
+    let home = gix_path::env::home_dir();
+    if let Some(dir) = home
+        .as_ref()
+        .map(|home| home.join(".git-credential-cache"))
+        .filter(|dir| dir.is_dir())
+    {
+        return Some(dir.join("socket"));
+    }
+    gix_path::env::var("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.map(|home| home.join(".cache")))
+        .map(|dir| dir.join("git").join("credential").join("socket"))
+}
+
+/// Perform `action` with the daemon described by `options`, with `ctx` identifying the credentials.
+///
+/// If the daemon isn't running, nothing is returned or erased, and it is started to store credentials.
+/// This function has the signature expected by [`program::main()`](crate::program::main()) to make it usable
+/// as standalone credential helper program as well.
+pub fn credentials(action: Action, mut ctx: Context, options: &Options) -> Result<Option<Context>, Error> {
+    if ctx.host.is_none() && ctx.url.is_some() {
+        ctx.destructure_url_in_place(false).ok();
+    }
+    let ctx = Context {
+        url: None,
+        quit: None,
+        ..ctx
+    };
+    let response = match request(&options.socket, action.as_str(), options.timeout, Some(&ctx)) {
+        Ok(response) => response,
+        Err(err) if is_not_running(&err) => match action {
+            Action::Get | Action::Erase => return Ok(None),
+            Action::Store => {
+                spawn_daemon(options)?;
+                request(&options.socket, action.as_str(), options.timeout, Some(&ctx)).map_err(|source| Error::Io {
+                    socket: options.socket.clone(),
+                    source,
+                })?
+            }
+        },
+        Err(source) => {
+            return Err(Error::Io {
+                socket: options.socket.clone(),
+                source,
+            })
+        }
+    };
+    match action {
+        Action::Get => {
+            let ctx = Context::from_bytes(&response)?;
+            Ok((ctx.username.is_some() || ctx.password.is_some()).then_some(ctx))
+        }
+        Action::Store | Action::Erase => Ok(None),
+    }
+}
+
+/// Ask the daemon listening on `socket` to stop, which forgets all credentials. Nothing happens if it isn't running.
+pub fn exit(socket: &Path) -> Result<(), Error> {
+    match request(socket, "exit", Duration::ZERO, None) {
+        Err(err) if !is_not_running(&err) => Err(Error::Io {
+            socket: socket.to_owned(),
+            source: err,
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn is_not_running(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused
+    )
+}
+
+/// Send a request for `action` and return the response, which is only non-empty for `get`.
+fn request(socket: &Path, action: &str, timeout: Duration, ctx: Option<&Context>) -> std::io::Result<Vec<u8>> {
+    let mut stream = UnixStream::connect(socket)?;
+    let mut buf = format!("action={action}\ntimeout={}\n", timeout.as_secs()).into_bytes();
+    if let Some(ctx) = ctx {
+        ctx.write_to(&mut buf)?;
+    }
+    stream.write_all(&buf)?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+    buf.clear();
+    stream.read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+fn spawn_daemon(options: &Options) -> Result<(), Error> {
+    let (program, args) = options.daemon.split_first().ok_or(Error::MissingDaemonProgram)?;
+    let spawn_err = |source| Error::SpawnDaemon {
+        program: program.clone(),
+        source,
+    };
+    let mut child = std::process::Command::new(program)
+        .args(args)
+        .arg(&options.socket)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(spawn_err)?;
+    let mut output = Vec::new();
+    child
+        .stdout
+        .take()
+        .expect("configured")
+        .take(3)
+        .read_to_end(&mut output)
+        .map_err(spawn_err)?;
+    if output != b"ok\n" {
+        return Err(Error::DaemonStart {
+            output: String::from_utf8_lossy(&output).into_owned(),
+        });
+    }
+    Ok(())
+}
@@ -7,6 +7,8 @@ impl Default for Cascade {
             stderr: true,
             use_http_path: false,
             query_user_only: false,
+            #[cfg(feature = "oauth")]
+            oauth: None,
         }
     }
 }
@@ -59,6 +61,13 @@ impl Cascade {
         self.query_user_only = toggle;
         self
     }
+
+    /// Run [OAuth](crate::program::Kind::OAuth) helpers with `options`, as they are skipped otherwise.
+    #[cfg(feature = "oauth")]
+    pub fn oauth(mut self, options: crate::oauth::Options) -> Self {
+        self.oauth = Some(options);
+        self
+    }
 }
 
 /// Finalize
@@ -85,7 +94,14 @@ impl Cascade {
 
         for program in &mut self.programs {
             program.stderr = self.stderr;
-            match helper::invoke::raw(program, &action) {
+            let res = match &program.kind {
+                #[cfg(feature = "oauth")]
+                crate::program::Kind::OAuth { client_id } => {
+                    crate::oauth::invoke(&action, client_id, self.oauth.as_mut())
+                }
+                _ => helper::invoke::raw(program, &action),
+            };
+            match res {
                 Ok(None) => {}
                 Ok(Some(stdout)) => {
                     let ctx = Context::from_bytes(&stdout)?;
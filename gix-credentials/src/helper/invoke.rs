@@ -40,6 +40,14 @@ pub fn invoke(helper: &mut crate::Program, action: &Action) -> Result {
 }
 
 pub(crate) fn raw(helper: &mut crate::Program, action: &Action) -> std::result::Result<Option<Vec<u8>>, Error> {
+    #[cfg(feature = "native")]
+    if helper.kind == crate::program::Kind::Native {
+        return crate::native::invoke(action);
+    }
+    #[cfg(feature = "oauth")]
+    if let crate::program::Kind::OAuth { client_id } = &helper.kind {
+        return crate::oauth::invoke(action, client_id, None);
+    }
     let (mut stdin, stdout) = helper.start(action)?;
     if let (Action::Get(_), None) = (&action, &stdout) {
         panic!("BUG: `Helper` impls must return an output handle to read output from if Action::Get is provided")
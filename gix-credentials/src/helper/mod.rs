@@ -18,6 +18,9 @@ pub struct Cascade {
     /// If true, default false, when getting credentials, we will set a bogus password to only obtain the user name.
     /// Storage and cancellation work the same, but without a password set.
     pub query_user_only: bool,
+    /// The means to run [OAuth](crate::program::Kind::OAuth) helpers with, which are skipped if `None`, the default.
+    #[cfg(feature = "oauth")]
+    pub oauth: Option<crate::oauth::Options>,
 }
 
 /// The outcome of the credentials helper [invocation][crate::helper::invoke()].
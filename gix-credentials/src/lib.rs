@@ -29,6 +29,15 @@ pub mod program;
 ///
 pub mod protocol;
 
+#[cfg(unix)]
+pub mod cache;
+
+#[cfg(feature = "native")]
+pub mod native;
+
+#[cfg(feature = "oauth")]
+pub mod oauth;
+
 /// Call the `git credential` helper program performing the given `action`, which reads all context from the git configuration
 /// and does everything `git` typically does. The `action` should have been created with [`helper::Action::get_for_url()`] to
 /// contain only the URL to kick off the process, or should be created by [`helper::NextAction`].
@@ -0,0 +1,31 @@
+use security_framework::passwords::{delete_generic_password, get_generic_password, set_generic_password};
+
+use super::Error;
+
+/// The account of all generic passwords we store, as the username is part of the stored secret.
+const ACCOUNT: &str = "gitoxide";
+/// `errSecItemNotFound`
+const ITEM_NOT_FOUND: i32 = -25300;
+
+fn to_error(err: security_framework::base::Error) -> Error {
+    Error::Store(err.to_string())
+}
+
+pub(crate) fn read(target: &str) -> Result<Option<Vec<u8>>, Error> {
+    match get_generic_password(target, ACCOUNT) {
+        Ok(secret) => Ok(Some(secret)),
+        Err(err) if err.code() == ITEM_NOT_FOUND => Ok(None),
+        Err(err) => Err(to_error(err)),
+    }
+}
+
+pub(crate) fn write(target: &str, _label: &str, secret: &[u8]) -> Result<(), Error> {
+    set_generic_password(target, ACCOUNT, secret).map_err(to_error)
+}
+
+pub(crate) fn delete(target: &str) -> Result<(), Error> {
+    match delete_generic_password(target, ACCOUNT) {
+        Err(err) if err.code() != ITEM_NOT_FOUND => Err(to_error(err)),
+        _ => Ok(()),
+    }
+}
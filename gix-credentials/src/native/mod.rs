@@ -0,0 +1,137 @@
+//! A credential helper that stores credentials in the credential store of the operating system, without the need
+//! for external `git-credential-*` programs.
+//!
+//! It's selected with `credential.helper = gix-native` and runs in-process, see [`Kind::Native`](crate::program::Kind::Native).
+//! The store is the Keychain on MacOS, the Credential Manager on Windows and the Secret Service via the `secret-tool`
+//! program on all other unix systems.
+//!
+//! Credentials are stored per protocol, host and path, so there is only one username and password for each of them.
+use bstr::{BString, ByteSlice};
+
+use crate::{helper, program::main::Action, protocol::Context};
+
+#[cfg(target_os = "macos")]
+#[path = "macos.rs"]
+pub(crate) mod store;
+#[cfg(windows)]
+#[path = "windows.rs"]
+pub(crate) mod store;
+#[cfg(all(unix, not(target_os = "macos")))]
+#[path = "secret_service.rs"]
+pub(crate) mod store;
+#[cfg(not(any(unix, windows)))]
+pub(crate) mod store {
+    pub(crate) fn read(_target: &str) -> Result<Option<Vec<u8>>, super::Error> {
+        Err(super::Error::Unavailable(
+            "there is no supported credential store on this platform".into(),
+        ))
+    }
+    pub(crate) fn write(_target: &str, _label: &str, _secret: &[u8]) -> Result<(), super::Error> {
+        read("").map(|_| ())
+    }
+    pub(crate) fn delete(_target: &str) -> Result<(), super::Error> {
+        read("").map(|_| ())
+    }
+}
+
+/// The name of the helper as used in `credential.helper`.
+pub const NAME: &str = "gix-native";
+
+/// The error returned by [`credentials()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The native credential store is unavailable: {0}")]
+    Unavailable(String),
+    #[error("The native credential store failed: {0}")]
+    Store(String),
+    #[error("The credentials in the native credential store could not be decoded")]
+    Decode(#[from] crate::protocol::context::decode::Error),
+}
+
+/// Perform `action` with the credential store of the operating system, with `ctx` identifying the credentials.
+///
+/// This function has the signature expected by [`program::main()`](crate::program::main()) to make it usable
+/// as standalone credential helper program as well.
+pub fn credentials(action: Action, ctx: Context) -> Result<Option<Context>, Error> {
+    let Some(target) = target(&ctx) else {
+        return Ok(None);
+    };
+    match action {
+        Action::Get => {
+            let Some(stored) = stored(&target)? else {
+                return Ok(None);
+            };
+            if ctx.username.is_some() && ctx.username != stored.username {
+                return Ok(None);
+            }
+            Ok(Some(Context {
+                username: stored.username,
+                password: stored.password,
+                ..Default::default()
+            }))
+        }
+        Action::Store => {
+            let (Some(username), Some(password)) = (ctx.username, ctx.password) else {
+                return Ok(None);
+            };
+            let mut secret = Vec::new();
+            Context {
+                username: Some(username),
+                password: Some(password),
+                ..Default::default()
+            }
+            .write_to(&mut secret)
+            .expect("no IO for in-memory writes");
+            store::write(&target, &format!("Git: {target}"), &secret)?;
+            Ok(None)
+        }
+        Action::Erase => {
+            let Some(stored) = stored(&target)? else {
+                return Ok(None);
+            };
+            let matches = |field: &Option<String>, stored: &Option<String>| field.is_none() || field == stored;
+            if matches(&ctx.username, &stored.username) && matches(&ctx.password, &stored.password) {
+                store::delete(&target)?;
+            }
+            Ok(None)
+        }
+    }
+}
+
+/// Run `action` in-process, and return what a credential helper program would have written to its standard output.
+pub(crate) fn invoke(action: &helper::Action) -> Result<Option<Vec<u8>>, helper::Error> {
+    let (main_action, ctx) = match action {
+        helper::Action::Get(ctx) => (Action::Get, ctx.clone()),
+        helper::Action::Store(payload) => (Action::Store, Context::from_bytes(payload)?),
+        helper::Action::Erase(payload) => (Action::Erase, Context::from_bytes(payload)?),
+    };
+    let failed = |err: Error| helper::Error::CredentialsHelperFailed {
+        source: std::io::Error::new(std::io::ErrorKind::Other, err),
+    };
+    let outcome = credentials(main_action, ctx).map_err(failed)?;
+    Ok(action.expects_output().then(|| {
+        let mut out = Vec::new();
+        if let Some(ctx) = outcome {
+            ctx.write_to(&mut out).expect("no IO for in-memory writes");
+        }
+        out
+    }))
+}
+
+/// Return the name under which credentials for `ctx` are stored, or `None` if `ctx` doesn't contain enough information.
+fn target(ctx: &Context) -> Option<String> {
+    let mut target = BString::from(format!("git:{}://{}", ctx.protocol.as_ref()?, ctx.host.as_ref()?));
+    if let Some(path) = ctx.path.as_ref().filter(|path| !path.is_empty()) {
+        target.push(b'/');
+        target.extend_from_slice(path.trim_start_with(|c| c == '/'));
+    }
+    Some(target.to_str_lossy().into_owned())
+}
+
+fn stored(target: &str) -> Result<Option<Context>, Error> {
+    store::read(target)?
+        .map(|secret| Context::from_bytes(&secret))
+        .transpose()
+        .map_err(Into::into)
+}
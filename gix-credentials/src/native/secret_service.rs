@@ -0,0 +1,67 @@
+//! Access the Secret Service with the `secret-tool` program that comes with `libsecret`, so neither has to be present
+//! at build time.
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+use super::Error;
+
+/// The program to run, which is the reference client of `libsecret`.
+const PROGRAM: &str = "secret-tool";
+/// The attributes that identify our items besides the target, which is the schema name `libsecret` would use for them.
+const SCHEMA: [&str; 2] = ["xdg:schema", "org.gitoxide.Credential"];
+
+fn run(args: &[&str], target: &str, stdin: Option<&[u8]>) -> Result<Output, Error> {
+    let mut child = Command::new(PROGRAM)
+        .args(args)
+        .args(SCHEMA)
+        .args(["target", target])
+        .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => Error::Unavailable(format!("the '{PROGRAM}' program could not be found")),
+            _ => Error::Store(format!("could not run '{PROGRAM}': {err}")),
+        })?;
+    if let Some(input) = stdin {
+        let mut stdin = child.stdin.take().expect("configured");
+        stdin
+            .write_all(input)
+            .map_err(|err| Error::Store(format!("could not pass the secret to '{PROGRAM}': {err}")))?;
+    }
+    child
+        .wait_with_output()
+        .map_err(|err| Error::Store(format!("could not run '{PROGRAM}': {err}")))
+}
+
+/// Turn a failed `out` into an error, unless it just didn't find anything, as indicated by an empty error message.
+fn check(out: &Output) -> Result<bool, Error> {
+    if out.status.success() {
+        return Ok(true);
+    }
+    let message = String::from_utf8_lossy(&out.stderr);
+    let message = message.trim();
+    if message.is_empty() {
+        Ok(false)
+    } else {
+        Err(Error::Store(format!("'{PROGRAM}' failed: {message}")))
+    }
+}
+
+pub(crate) fn read(target: &str) -> Result<Option<Vec<u8>>, Error> {
+    let out = run(&["lookup"], target, None)?;
+    Ok(check(&out)?.then_some(out.stdout))
+}
+
+pub(crate) fn write(target: &str, label: &str, secret: &[u8]) -> Result<(), Error> {
+    let out = run(&["store", &format!("--label={label}")], target, Some(secret))?;
+    check(&out)?
+        .then_some(())
+        .ok_or_else(|| Error::Store(format!("'{PROGRAM}' failed to store the secret")))
+}
+
+pub(crate) fn delete(target: &str) -> Result<(), Error> {
+    check(&run(&["clear"], target, None)?).map(|_| ())
+}
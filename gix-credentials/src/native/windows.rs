@@ -0,0 +1,34 @@
+use super::Error;
+
+/// The user of all entries we store, as the username is part of the stored secret.
+const USER: &str = "gitoxide";
+
+fn entry(target: &str) -> Result<keyring::Entry, Error> {
+    keyring::Entry::new_with_target(target, target, USER).map_err(to_error)
+}
+
+fn to_error(err: keyring::Error) -> Error {
+    match err {
+        keyring::Error::NoStorageAccess(_) | keyring::Error::PlatformFailure(_) => Error::Unavailable(err.to_string()),
+        err => Error::Store(err.to_string()),
+    }
+}
+
+pub(crate) fn read(target: &str) -> Result<Option<Vec<u8>>, Error> {
+    match entry(target)?.get_secret() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(to_error(err)),
+    }
+}
+
+pub(crate) fn write(target: &str, _label: &str, secret: &[u8]) -> Result<(), Error> {
+    entry(target)?.set_secret(secret).map_err(to_error)
+}
+
+pub(crate) fn delete(target: &str) -> Result<(), Error> {
+    match entry(target)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(err) => Err(to_error(err)),
+    }
+}
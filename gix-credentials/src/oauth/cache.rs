@@ -0,0 +1,51 @@
+//! Cache tokens in the credential store of the operating system, ignoring all failures as they only cause another authorization.
+use bstr::ByteSlice;
+
+use super::{Provider, Token};
+use crate::native::store;
+
+fn target(provider: &Provider) -> String {
+    format!("{}:https://{}", super::NAME, provider.host)
+}
+
+pub(crate) fn read(provider: &Provider) -> Option<Token> {
+    let secret = store::read(&target(provider))
+        .map_err(|_err| gix_trace::debug!("could not read cached token: {}", _err))
+        .ok()??;
+    let mut token = Token {
+        access_token: String::new(),
+        refresh_token: None,
+        expires_at: None,
+    };
+    for line in secret.lines() {
+        let (key, value) = line.split_once_str("=")?;
+        let value = value.to_str().ok()?;
+        match key {
+            b"access_token" => token.access_token = value.into(),
+            b"refresh_token" => token.refresh_token = Some(value.into()),
+            b"expires_at" => token.expires_at = Some(value.parse().ok()?),
+            _ => {}
+        }
+    }
+    (!token.access_token.is_empty()).then_some(token)
+}
+
+pub(crate) fn write(provider: &Provider, token: &Token) {
+    let mut secret = format!("access_token={}\n", token.access_token);
+    if let Some(refresh_token) = &token.refresh_token {
+        secret.push_str(&format!("refresh_token={refresh_token}\n"));
+    }
+    if let Some(expires_at) = token.expires_at {
+        secret.push_str(&format!("expires_at={expires_at}\n"));
+    }
+    let target = target(provider);
+    if let Err(_err) = store::write(&target, &format!("Git: {target}"), secret.as_bytes()) {
+        gix_trace::debug!("could not cache token: {}", _err);
+    }
+}
+
+pub(crate) fn delete(provider: &Provider) {
+    if let Err(_err) = store::delete(&target(provider)) {
+        gix_trace::debug!("could not delete cached token: {}", _err);
+    }
+}
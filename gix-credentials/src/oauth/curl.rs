@@ -0,0 +1,74 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use super::Client;
+
+/// A [`Client`] that performs requests with the `curl` program, passing the form through `stdin` so secrets don't appear
+/// in the list of processes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Curl {
+    /// The program to run, `curl` by default.
+    pub program: std::path::PathBuf,
+}
+
+impl Default for Curl {
+    fn default() -> Self {
+        Curl { program: "curl".into() }
+    }
+}
+
+impl Client for Curl {
+    fn post_form(
+        &mut self,
+        url: &str,
+        form: &[(&str, &str)],
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let mut child = Command::new(&self.program)
+            .args(["--silent", "--show-error", "--header", "Accept: application/json"])
+            .args(["--data-binary", "@-", url])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        child
+            .stdin
+            .take()
+            .expect("configured")
+            .write_all(encode(form).as_bytes())?;
+        let out = child.wait_with_output()?;
+        if !out.status.success() {
+            return Err(format!(
+                "{:?} failed with {}: {}",
+                self.program,
+                out.status,
+                String::from_utf8_lossy(&out.stderr).trim()
+            )
+            .into());
+        }
+        Ok(out.stdout)
+    }
+}
+
+/// Encode `form` as `application/x-www-form-urlencoded`.
+fn encode(form: &[(&str, &str)]) -> String {
+    let mut out = String::new();
+    for (key, value) in form {
+        if !out.is_empty() {
+            out.push('&');
+        }
+        for (idx, part) in [key, value].into_iter().enumerate() {
+            if idx == 1 {
+                out.push('=');
+            }
+            for byte in part.bytes() {
+                match byte {
+                    b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => out.push(byte as char),
+                    _ => out.push_str(&format!("%{byte:02X}")),
+                }
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,109 @@
+use std::time::{Duration, Instant};
+
+use super::{now, Client, DeviceCode, Error, Provider, Token};
+
+/// The response of the token endpoint, which is either a token or an error.
+#[derive(serde::Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+impl TokenResponse {
+    fn into_token(self, url: &str) -> Result<Token, Error> {
+        match self.access_token {
+            Some(access_token) => Ok(Token {
+                access_token,
+                refresh_token: self.refresh_token,
+                expires_at: self.expires_in.map(|seconds| now() + seconds),
+            }),
+            None => Err(Error::Server {
+                url: url.into(),
+                error: self.error.unwrap_or_else(|| "missing access_token".into()),
+                description: self.error_description,
+            }),
+        }
+    }
+}
+
+fn post<T: serde::de::DeserializeOwned>(
+    client: &mut (impl Client + ?Sized),
+    url: &str,
+    form: &[(&str, &str)],
+) -> Result<T, Error> {
+    let body = client.post_form(url, form).map_err(|source| Error::Request {
+        url: url.into(),
+        source,
+    })?;
+    serde_json::from_slice(&body).map_err(|source| Error::Parse {
+        url: url.into(),
+        source,
+    })
+}
+
+/// Obtain a new token from `provider` using the device authorization flow, calling `on_code` with the code the user has
+/// to enter to grant the authorization, and block until it was granted, denied or expired.
+pub fn authorize(
+    provider: &Provider,
+    client: &mut (impl Client + ?Sized),
+    on_code: impl FnOnce(&DeviceCode),
+) -> Result<Token, Error> {
+    let code: DeviceCode = post(
+        client,
+        &provider.device_authorization_url,
+        &[("client_id", &provider.client_id), ("scope", &provider.scopes)],
+    )?;
+    on_code(&code);
+
+    let deadline = Instant::now() + Duration::from_secs(code.expires_in);
+    let mut interval = code.interval;
+    loop {
+        std::thread::sleep(Duration::from_secs(interval));
+        if Instant::now() >= deadline {
+            return Err(Error::Expired);
+        }
+        let res = post::<TokenResponse>(
+            client,
+            &provider.token_url,
+            &[
+                ("client_id", &provider.client_id),
+                ("device_code", &code.device_code),
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+            ],
+        );
+        let res = match res {
+            Ok(res) => res,
+            Err(_err) => {
+                gix_trace::debug!("token request failed, retrying: {}", _err);
+                continue;
+            }
+        };
+        match res.error.as_deref() {
+            None => return res.into_token(&provider.token_url),
+            Some("authorization_pending") => {}
+            Some("slow_down") => interval += 5,
+            Some("access_denied") => return Err(Error::Denied),
+            Some("expired_token") => return Err(Error::Expired),
+            Some(_) => return res.into_token(&provider.token_url),
+        }
+    }
+}
+
+/// Obtain a new token from `provider` using `refresh_token`, which is kept in the returned token if no new one is provided.
+pub fn refresh(provider: &Provider, client: &mut (impl Client + ?Sized), refresh_token: &str) -> Result<Token, Error> {
+    let mut token = post::<TokenResponse>(
+        client,
+        &provider.token_url,
+        &[
+            ("client_id", &provider.client_id),
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ],
+    )?
+    .into_token(&provider.token_url)?;
+    token.refresh_token.get_or_insert_with(|| refresh_token.into());
+    Ok(token)
+}
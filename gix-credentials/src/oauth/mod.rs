@@ -0,0 +1,270 @@
+//! A credential helper that obtains tokens for well-known hosts with the OAuth 2.0
+//! [device authorization flow](https://datatracker.ietf.org/doc/html/rfc8628), refreshes them once they expire
+//! and caches them in the credential store of the operating system.
+//!
+//! It's selected with `credential.helper = "gix-oauth <client-id>"` and runs in-process, see [`Kind::OAuth`](crate::program::Kind::OAuth),
+//! with `<client-id>` being the id of the OAuth application registered for the host to obtain tokens for.
+//! When no valid token is cached, the code to enter along with the URL to enter it at is passed to the application to present
+//! it to the user, and the helper waits until the authorization was granted in the browser, so it's only useful in
+//! interactive sessions.
+//! As the application has to choose how to perform requests and present codes with [`Options`], the helper does nothing
+//! if these aren't provided.
+//!
+//! Only `https` URLs of the hosts known to [`Provider::well_known()`] are handled, all others are left to the next helper.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{helper, program::main::Action, protocol::Context};
+
+mod cache;
+mod curl;
+pub use curl::Curl;
+mod flow;
+pub use flow::{authorize, refresh};
+
+/// The name of the helper as used in `credential.helper`.
+pub const NAME: &str = "gix-oauth";
+
+/// The error returned by [`credentials()`], [`authorize()`] and [`refresh()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The request to '{url}' failed")]
+    Request {
+        url: String,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("The response of '{url}' could not be parsed")]
+    Parse { url: String, source: serde_json::Error },
+    #[error("'{url}' responded with error '{error}': {}", description.as_deref().unwrap_or("no description"))]
+    Server {
+        url: String,
+        error: String,
+        description: Option<String>,
+    },
+    #[error("The authorization request was denied")]
+    Denied,
+    #[error("The device code expired before the authorization request was granted")]
+    Expired,
+}
+
+/// What's needed to run the [`gix-oauth`](NAME) helper in-process, as provided by the application.
+pub struct Options {
+    /// The client to perform requests with, like [`Curl`].
+    pub client: Box<dyn Client + Send>,
+    /// Called with the code to present to the user if a new authorization is needed.
+    pub on_code: Box<dyn FnMut(&DeviceCode) + Send>,
+}
+
+impl std::fmt::Debug for Options {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Options").finish_non_exhaustive()
+    }
+}
+
+/// Perform `POST` requests on behalf of the device authorization flow.
+pub trait Client {
+    /// Post `form` as `application/x-www-form-urlencoded` body to `url` while accepting `application/json`, and return the
+    /// response body.
+    ///
+    /// Implementations should return the body for error statuses like `400` as well, as that's how servers communicate
+    /// that the authorization is still pending. Failed requests while waiting for the authorization are retried until
+    /// the device code expires, so implementations that can't provide these bodies still work, but slower.
+    fn post_form(
+        &mut self,
+        url: &str,
+        form: &[(&str, &str)],
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync + 'static>>;
+}
+
+/// An OAuth application along with the endpoints of the host it authorizes access to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Provider {
+    /// The host whose repositories the tokens provide access to, like `github.com`.
+    pub host: String,
+    /// The id of the OAuth application to request tokens for.
+    pub client_id: String,
+    /// The endpoint to obtain a device code from.
+    pub device_authorization_url: String,
+    /// The endpoint to obtain tokens from.
+    pub token_url: String,
+    /// The space-separated scopes to request.
+    pub scopes: String,
+    /// The username to pass along with the token, which is used as password.
+    pub username: String,
+}
+
+impl Provider {
+    /// Return the provider for `host` if it's one of `github.com`, `gitlab.com`, `dev.azure.com` or `*.visualstudio.com`,
+    /// to request tokens for the OAuth application with `client_id`.
+    pub fn well_known(host: &str, client_id: &str) -> Option<Self> {
+        let (device_authorization_url, token_url, scopes) = match host {
+            "github.com" => (
+                "https://github.com/login/device/code",
+                "https://github.com/login/oauth/access_token",
+                "repo gist workflow",
+            ),
+            "gitlab.com" => (
+                "https://gitlab.com/oauth/authorize_device",
+                "https://gitlab.com/oauth/token",
+                "read_repository write_repository",
+            ),
+            "dev.azure.com" => Self::AZURE,
+            _ if host.ends_with(".visualstudio.com") => Self::AZURE,
+            _ => return None,
+        };
+        Some(Provider {
+            host: host.into(),
+            client_id: client_id.into(),
+            device_authorization_url: device_authorization_url.into(),
+            token_url: token_url.into(),
+            scopes: scopes.into(),
+            username: "oauth2".into(),
+        })
+    }
+
+    const AZURE: (&'static str, &'static str, &'static str) = (
+        "https://login.microsoftonline.com/organizations/oauth2/v2.0/devicecode",
+        "https://login.microsoftonline.com/organizations/oauth2/v2.0/token",
+        "499b84ac-1321-427f-aa17-267ca6975798/.default offline_access",
+    );
+}
+
+/// The code the user has to enter at [`verification_uri`](DeviceCode::verification_uri) to grant the authorization
+/// request, as obtained in the first step of [`authorize()`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct DeviceCode {
+    /// The code identifying the device, used to obtain the token once the authorization was granted.
+    pub device_code: String,
+    /// The code to show to the user.
+    pub user_code: String,
+    /// The URL at which the user should enter the [`user_code`](DeviceCode::user_code).
+    #[serde(alias = "verification_url")]
+    pub verification_uri: String,
+    /// The amount of seconds after which the codes expire.
+    pub expires_in: u64,
+    /// The amount of seconds to wait between requests for the token.
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+}
+
+fn default_interval() -> u64 {
+    5
+}
+
+/// An access token along with the means to refresh it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// The token to use as password.
+    pub access_token: String,
+    /// The token to obtain a new access token with once it expired, if the provider supports it.
+    pub refresh_token: Option<String>,
+    /// The time in seconds since the unix epoch at which the access token expires, or `None` if it doesn't.
+    pub expires_at: Option<u64>,
+}
+
+impl Token {
+    /// Return `true` if the access token expired at `now` in seconds since the unix epoch, or will do so within a minute.
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|at| now.saturating_add(60) >= at)
+    }
+}
+
+/// Perform `action` for the well-known host identified by `ctx` with the OAuth application identified by `client_id`,
+/// using `client` for requests and calling `on_code` with the code to show to the user if a new authorization is needed.
+///
+/// Cached tokens are returned if they are still valid, or refreshed if possible. `Store` does nothing as tokens are
+/// cached as soon as they are obtained, and `Erase` removes the cached token if it's the one in `ctx`, so the next `Get`
+/// obtains a new one.
+pub fn credentials(
+    action: Action,
+    ctx: Context,
+    client_id: &str,
+    client: &mut (impl Client + ?Sized),
+    on_code: impl FnOnce(&DeviceCode),
+) -> Result<Option<Context>, Error> {
+    let Some(provider) = ctx
+        .host
+        .as_deref()
+        .filter(|_| ctx.protocol.as_deref() == Some("https"))
+        .and_then(|host| Provider::well_known(host, client_id))
+    else {
+        return Ok(None);
+    };
+    match action {
+        Action::Get => {
+            let token = match cache::read(&provider) {
+                Some(token) if !token.is_expired(now()) => token,
+                cached => {
+                    let refreshed =
+                        cached.and_then(|token| token.refresh_token).and_then(|refresh_token| {
+                            match refresh(&provider, client, &refresh_token) {
+                                Ok(token) => Some(token),
+                                Err(_err) => {
+                                    gix_trace::debug!("could not refresh token, authorizing anew: {}", _err);
+                                    None
+                                }
+                            }
+                        });
+                    let token = match refreshed {
+                        Some(token) => token,
+                        None => authorize(&provider, client, on_code)?,
+                    };
+                    cache::write(&provider, &token);
+                    token
+                }
+            };
+            Ok(Some(Context {
+                username: Some(provider.username),
+                password: Some(token.access_token),
+                ..Default::default()
+            }))
+        }
+        Action::Store => Ok(None),
+        Action::Erase => {
+            if let Some(token) = cache::read(&provider) {
+                if ctx.password.map_or(true, |password| password == token.access_token) {
+                    cache::delete(&provider);
+                }
+            }
+            Ok(None)
+        }
+    }
+}
+
+/// Run `action` in-process for the OAuth application with `client_id`, and return what a credential helper program
+/// would have written to its standard output. Nothing is done without `options`.
+pub(crate) fn invoke(
+    action: &helper::Action,
+    client_id: &str,
+    options: Option<&mut Options>,
+) -> Result<Option<Vec<u8>>, helper::Error> {
+    let Some(options) = options else {
+        gix_trace::debug!("skipping {} as no options were provided to run it with", NAME);
+        return Ok(None);
+    };
+    let (main_action, ctx) = match action {
+        helper::Action::Get(ctx) => (Action::Get, ctx.clone()),
+        helper::Action::Store(payload) => (Action::Store, Context::from_bytes(payload)?),
+        helper::Action::Erase(payload) => (Action::Erase, Context::from_bytes(payload)?),
+    };
+    let failed = |err: Error| helper::Error::CredentialsHelperFailed {
+        source: std::io::Error::new(std::io::ErrorKind::Other, err),
+    };
+    let outcome = credentials(main_action, ctx, client_id, options.client.as_mut(), |code| {
+        (options.on_code)(code)
+    })
+    .map_err(failed)?;
+    Ok(action.expects_output().then(|| {
+        let mut out = Vec::new();
+        if let Some(ctx) = outcome {
+            ctx.write_to(&mut out).expect("no IO for in-memory writes");
+        }
+        out
+    }))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
@@ -71,14 +71,15 @@ pub(crate) mod function {
     /// action to perform (as opposed to the program name).
     /// Then read context information from `stdin` and if the action is `Action::Get`, then write the result to `stdout`.
     /// `credentials` is the API version of such call, where`Ok(Some(context))` returns credentials, and `Ok(None)` indicates
-    /// no credentials could be found for `url`, which is always set when called.
+    /// no credentials could be found for `url`, which is always set when called. If the returned context has `quit` set,
+    /// it is written as well to tell the caller to stop trying other helpers.
     ///
     /// Call this function from a programs `main`, passing `std::env::args_os()`, `stdin()` and `stdout` accordingly, along with
     /// your own helper implementation.
     pub fn main<CredentialsFn, E>(
         args: impl IntoIterator<Item = OsString>,
         mut stdin: impl std::io::Read,
-        stdout: impl std::io::Write,
+        mut stdout: impl std::io::Write,
         credentials: CredentialsFn,
     ) -> Result<(), Error>
     where
@@ -99,7 +100,13 @@ pub(crate) mod function {
                     url: Context::from_bytes(&buf)?.url.expect("present and checked above"),
                 })
             }
-            (Action::Get, Some(ctx)) => ctx.write_to(stdout)?,
+            (Action::Get, Some(ctx)) => {
+                ctx.write_to(&mut stdout)?;
+                // Only helper output may contain `quit`, which is why it's never written along with the context itself.
+                if ctx.quit == Some(true) {
+                    stdout.write_all(b"quit=1\n")?;
+                }
+            }
             (Action::Erase | Action::Store, None) => {}
             (Action::Erase | Action::Store, Some(_)) => {
                 panic!("BUG: credentials helper must not return context for erase or store actions")
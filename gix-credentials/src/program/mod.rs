@@ -24,6 +24,17 @@ pub enum Kind {
     },
     /// A script to execute with `sh`.
     ExternalShellScript(BString),
+    /// The credential store of the operating system, accessed in-process without launching a program,
+    /// as selected by the [`gix-native`](crate::native::NAME) helper.
+    #[cfg(feature = "native")]
+    Native,
+    /// Tokens obtained with the OAuth device authorization flow for well-known hosts, cached in the credential store of
+    /// the operating system, as selected by the [`gix-oauth`](crate::oauth::NAME) helper.
+    #[cfg(feature = "oauth")]
+    OAuth {
+        /// The id of the OAuth application to request tokens for, as passed to the helper.
+        client_id: String,
+    },
 }
 
 /// Initialization
@@ -39,8 +50,27 @@ impl Program {
 
     /// Parse the given input as per the custom helper definition, supporting `!<script>`, `name` and `/absolute/name`, the latter two
     /// also support arguments which are ignored here.
+    ///
+    /// With the `native` feature, `gix-native` selects the [native](Kind::Native) credential store, and with the `oauth`
+    /// feature, `gix-oauth <client-id>` selects the [OAuth](Kind::OAuth) helper.
     pub fn from_custom_definition(input: impl Into<BString>) -> Self {
         fn from_custom_definition_inner(mut input: BString) -> Program {
+            #[cfg(feature = "native")]
+            if input == crate::native::NAME {
+                return Program::from_kind(Kind::Native);
+            }
+            #[cfg(feature = "oauth")]
+            if let Some(client_id) = input
+                .strip_prefix(crate::oauth::NAME.as_bytes())
+                .and_then(|rest| rest.strip_prefix(b" "))
+                .map(|client_id| client_id.trim())
+                .filter(|client_id| !client_id.is_empty() && !client_id.contains(&b' '))
+                .and_then(|client_id| client_id.to_str().ok())
+            {
+                return Program::from_kind(Kind::OAuth {
+                    client_id: client_id.into(),
+                });
+            }
             let kind = if input.starts_with(b"!") {
                 input.remove(0);
                 Kind::ExternalShellScript(input)
@@ -67,6 +97,8 @@ impl Program {
     }
 
     /// Convert the program into the respective command, suitable to invoke `action`.
+    ///
+    /// As [native](Kind::Native) and [OAuth](Kind::OAuth) helpers run in-process, their command is what `git` would run for the same configuration.
     pub fn to_command(&self, action: &helper::Action) -> std::process::Command {
         let git_program = cfg!(windows).then(|| "git.exe").unwrap_or("git");
         let mut cmd = match &self.kind {
@@ -75,6 +107,16 @@ impl Program {
                 cmd.arg("credential").arg(action.as_arg(false));
                 cmd
             }
+            #[cfg(feature = "native")]
+            Kind::Native => Program::from_kind(Kind::ExternalName {
+                name_and_args: crate::native::NAME.into(),
+            })
+            .to_command(action),
+            #[cfg(feature = "oauth")]
+            Kind::OAuth { client_id } => Program::from_kind(Kind::ExternalName {
+                name_and_args: format!("{} {client_id}", crate::oauth::NAME).into(),
+            })
+            .to_command(action),
             Kind::ExternalName { name_and_args } => {
                 let mut args = name_and_args.clone();
                 args.insert_str(0, "credential-");
@@ -0,0 +1,192 @@
+use std::{
+    io::Write,
+    os::unix::fs::PermissionsExt,
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use gix_credentials::{
+    cache::{self, Options},
+    program::main::Action,
+    protocol::Context,
+};
+
+fn ctx(username: Option<&str>, password: Option<&str>) -> Context {
+    Context {
+        protocol: Some("https".into()),
+        host: Some("example.com".into()),
+        username: username.map(Into::into),
+        password: password.map(Into::into),
+        ..Default::default()
+    }
+}
+
+fn get(options: &Options, username: Option<&str>) -> Result<Option<Context>, cache::Error> {
+    cache::credentials(Action::Get, ctx(username, None), options)
+}
+
+fn store_get_and_erase(options: &Options) -> crate::Result {
+    cache::credentials(Action::Store, ctx(Some("user"), Some("pass")), options)?;
+    cache::credentials(Action::Store, ctx(Some("other"), Some("secret")), options)?;
+    assert_eq!(get(options, None)?, Some(ctx_without_host("user", "pass")));
+    assert_eq!(get(options, Some("other"))?, Some(ctx_without_host("other", "secret")));
+
+    cache::credentials(Action::Erase, ctx(Some("user"), None), options)?;
+    assert_eq!(get(options, Some("user"))?, None);
+    assert_eq!(
+        cache::credentials(
+            Action::Get,
+            Context {
+                host: Some("other.example.com".into()),
+                ..ctx(None, None)
+            },
+            options
+        )?,
+        None,
+        "only matching hosts are returned"
+    );
+    Ok(())
+}
+
+fn ctx_without_host(username: &str, password: &str) -> Context {
+    Context {
+        username: Some(username.into()),
+        password: Some(password.into()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn daemon_serves_until_exit() -> crate::Result {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let socket = tmp.path().join("dir/socket");
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+    let daemon = std::thread::spawn({
+        let socket = socket.clone();
+        move || cache::daemon::serve(&socket, || ready_tx.send(()).expect("receiver alive"))
+    });
+    ready_rx.recv()?;
+
+    let options = Options::new(&socket);
+    store_get_and_erase(&options)?;
+
+    cache::credentials(Action::Store, ctx(Some("user"), Some("pass")), &options)?;
+    cache::credentials(Action::Erase, ctx(Some("user"), Some("wrong")), &options)?;
+    assert_eq!(
+        get(&options, Some("user"))?,
+        Some(ctx_without_host("user", "pass")),
+        "the password has to match as well, like in newer versions of git"
+    );
+    cache::exit(&socket)?;
+    daemon.join().expect("no panic")?;
+    assert!(!socket.exists(), "the socket is removed on exit");
+
+    std::fs::set_permissions(tmp.path().join("dir"), std::fs::Permissions::from_mode(0o755))?;
+    let err = cache::daemon::serve(&socket, || unreachable!("it doesn't start")).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied, "just like in git");
+    Ok(())
+}
+
+#[test]
+fn credentials_expire() -> crate::Result {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let socket = tmp.path().join("dir/socket");
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+    let daemon = std::thread::spawn({
+        let socket = socket.clone();
+        move || cache::daemon::serve(&socket, || ready_tx.send(()).expect("receiver alive"))
+    });
+    ready_rx.recv()?;
+
+    let mut options = Options::new(&socket);
+    options.timeout = Duration::from_secs(1);
+    cache::credentials(Action::Store, ctx(Some("user"), Some("pass")), &options)?;
+    assert!(get(&options, None)?.is_some());
+    daemon.join().expect("no panic")?;
+    assert!(
+        !socket.exists(),
+        "the daemon stops by itself once there is nothing left to keep"
+    );
+    assert_eq!(get(&options, None)?, None, "nothing is returned if it isn't running");
+    Ok(())
+}
+
+#[test]
+fn nothing_is_returned_or_erased_if_the_daemon_is_not_running() -> crate::Result {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let socket = tmp.path().join("dir/socket");
+    let options = Options::new(&socket);
+    assert_eq!(get(&options, None)?, None);
+    cache::credentials(Action::Erase, ctx(None, None), &options)?;
+    cache::exit(&socket)?;
+    assert!(!socket.exists());
+    Ok(())
+}
+
+#[test]
+fn storing_fails_without_daemon_program_if_the_daemon_is_not_running() -> crate::Result {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let mut options = Options::new(tmp.path().join("socket"));
+    options.daemon.clear();
+    assert!(matches!(
+        cache::credentials(Action::Store, ctx(Some("user"), Some("pass")), &options),
+        Err(cache::Error::MissingDaemonProgram)
+    ));
+    Ok(())
+}
+
+#[test]
+fn interoperates_with_git_daemon() -> crate::Result {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let socket = tmp.path().join("dir/socket");
+    let options = Options::new(&socket);
+    store_get_and_erase(&options)?;
+    cache::exit(&socket)?;
+    Ok(())
+}
+
+#[test]
+fn interoperates_with_git_client() -> crate::Result {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let socket = tmp.path().join("dir/socket");
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+    let daemon = std::thread::spawn({
+        let socket = socket.clone();
+        move || cache::daemon::serve(&socket, || ready_tx.send(()).expect("receiver alive"))
+    });
+    ready_rx.recv()?;
+
+    let git = |action: &str, input: &str| -> crate::Result<String> {
+        let mut child = Command::new("git")
+            .args(["credential-cache", "--socket"])
+            .arg(&socket)
+            .arg(action)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        child.stdin.take().expect("configured").write_all(input.as_bytes())?;
+        let out = child.wait_with_output()?;
+        assert!(out.status.success());
+        Ok(String::from_utf8(out.stdout)?)
+    };
+    git(
+        "store",
+        "protocol=https\nhost=example.com\nusername=user\npassword=pass\n",
+    )?;
+    assert_eq!(
+        get(&Options::new(&socket), None)?,
+        Some(ctx_without_host("user", "pass"))
+    );
+    cache::credentials(
+        Action::Store,
+        ctx(Some("other"), Some("secret")),
+        &Options::new(&socket),
+    )?;
+    assert_eq!(
+        git("get", "protocol=https\nhost=example.com\nusername=other\n")?,
+        "username=other\npassword=secret\n"
+    );
+    git("exit", "")?;
+    daemon.join().expect("no panic")?;
+    Ok(())
+}
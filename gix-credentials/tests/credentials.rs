@@ -1,5 +1,9 @@
 pub use gix_testtools::Result;
 
+#[cfg(unix)]
+mod cache;
 mod helper;
+#[cfg(feature = "oauth")]
+mod oauth;
 mod program;
 mod protocol;
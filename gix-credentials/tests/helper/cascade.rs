@@ -150,6 +150,55 @@ mod invoke {
     }
 
     #[allow(clippy::result_large_err)]
+    #[test]
+    #[cfg(feature = "native")]
+    fn native_helpers_are_run_in_process_and_skipped_if_they_fail() {
+        let mut programs = vec![Program::from_kind(program::Kind::Native)];
+        programs.extend(fixtures(["custom-helper"]));
+        let actual = Cascade::default()
+            .extend(programs)
+            .invoke(
+                Action::get_for_url("https://gitoxide.invalid/repo"),
+                gix_prompt::Options {
+                    mode: gix_prompt::Mode::Disable,
+                    askpass: None,
+                },
+            )
+            .unwrap()
+            .expect("credentials");
+        assert_eq!(
+            actual.identity,
+            identity("user-script", "pass-script"),
+            "the native store has nothing for this host, or isn't available at all"
+        );
+    }
+
+    #[allow(clippy::result_large_err)]
+    #[test]
+    #[cfg(feature = "oauth")]
+    fn oauth_helpers_are_skipped_without_options() {
+        let mut programs = vec![Program::from_kind(program::Kind::OAuth {
+            client_id: "client-id".into(),
+        })];
+        programs.extend(fixtures(["custom-helper"]));
+        let actual = Cascade::default()
+            .extend(programs)
+            .invoke(
+                Action::get_for_url("https://github.com/repo"),
+                gix_prompt::Options {
+                    mode: gix_prompt::Mode::Disable,
+                    askpass: None,
+                },
+            )
+            .unwrap()
+            .expect("credentials");
+        assert_eq!(
+            actual.identity,
+            identity("user-script", "pass-script"),
+            "the application has to choose how to perform requests and present codes"
+        );
+    }
+
     fn invoke_cascade<'a>(names: impl IntoIterator<Item = &'a str>, action: Action) -> protocol::Result {
         Cascade::default().use_http_path(true).extend(fixtures(names)).invoke(
             action,
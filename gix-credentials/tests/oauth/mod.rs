@@ -0,0 +1,131 @@
+use gix_credentials::{
+    oauth::{authorize, refresh, Client, Error, Provider},
+    program::main::Action,
+    protocol::Context,
+};
+
+/// A client that returns `responses` in order and records all requests.
+#[derive(Default)]
+struct Canned {
+    responses: Vec<&'static str>,
+    requests: Vec<(String, Vec<(String, String)>)>,
+}
+
+impl Canned {
+    fn new(responses: &[&'static str]) -> Self {
+        Canned {
+            responses: responses.iter().rev().copied().collect(),
+            requests: Vec::new(),
+        }
+    }
+
+    fn form_value(&self, request: usize, key: &str) -> Option<&str> {
+        self.requests[request]
+            .1
+            .iter()
+            .find_map(|(k, v)| (k == key).then_some(v.as_str()))
+    }
+}
+
+impl Client for Canned {
+    fn post_form(
+        &mut self,
+        url: &str,
+        form: &[(&str, &str)],
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        self.requests.push((
+            url.into(),
+            form.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        ));
+        Ok(self.responses.pop().expect("enough responses").into())
+    }
+}
+
+fn github() -> Provider {
+    Provider::well_known("github.com", "client-id").expect("known")
+}
+
+const DEVICE_CODE: &str = r#"{"device_code":"dc","user_code":"ABCD-1234","verification_uri":"https://github.com/login/device","expires_in":900,"interval":0}"#;
+
+#[test]
+fn well_known_providers() {
+    assert_eq!(github().token_url, "https://github.com/login/oauth/access_token");
+    assert_eq!(github().client_id, "client-id", "the application is always configured");
+    assert!(Provider::well_known("gitlab.com", "client-id").is_some());
+    assert_eq!(
+        Provider::well_known("org.visualstudio.com", "client-id").map(|p| p.token_url),
+        Provider::well_known("dev.azure.com", "client-id").map(|p| p.token_url),
+        "both Azure DevOps hosts use the same endpoints"
+    );
+    assert_eq!(Provider::well_known("example.com", "client-id"), None);
+}
+
+#[test]
+fn authorize_polls_until_the_token_is_available() -> crate::Result {
+    let mut client = Canned::new(&[
+        DEVICE_CODE,
+        r#"{"error":"authorization_pending"}"#,
+        r#"{"access_token":"at","refresh_token":"rt","expires_in":3600,"token_type":"bearer"}"#,
+    ]);
+    let mut user_code = None;
+    let token = authorize(&github(), &mut client, |code| user_code = Some(code.user_code.clone()))?;
+    assert_eq!(user_code.as_deref(), Some("ABCD-1234"));
+    assert_eq!(token.access_token, "at");
+    assert_eq!(token.refresh_token.as_deref(), Some("rt"));
+    assert!(!token.is_expired(0));
+    assert!(token.is_expired(token.expires_at.expect("set")));
+
+    assert_eq!(client.requests.len(), 3);
+    assert_eq!(client.requests[0].0, github().device_authorization_url);
+    assert_eq!(client.form_value(0, "scope"), Some("repo gist workflow"));
+    assert_eq!(client.form_value(0, "client_id"), Some("client-id"));
+    assert_eq!(client.requests[2].0, github().token_url);
+    assert_eq!(client.form_value(2, "device_code"), Some("dc"));
+    assert_eq!(
+        client.form_value(2, "grant_type"),
+        Some("urn:ietf:params:oauth:grant-type:device_code")
+    );
+    Ok(())
+}
+
+#[test]
+fn authorize_fails_if_denied() {
+    let mut client = Canned::new(&[DEVICE_CODE, r#"{"error":"access_denied"}"#]);
+    assert!(matches!(authorize(&github(), &mut client, |_| {}), Err(Error::Denied)));
+}
+
+#[test]
+fn refresh_keeps_the_refresh_token_if_there_is_no_new_one() -> crate::Result {
+    let mut client = Canned::new(&[r#"{"access_token":"new"}"#]);
+    let token = refresh(&github(), &mut client, "rt")?;
+    assert_eq!(token.access_token, "new");
+    assert_eq!(token.refresh_token.as_deref(), Some("rt"));
+    assert_eq!(token.expires_at, None, "tokens may not expire");
+    assert_eq!(client.form_value(0, "grant_type"), Some("refresh_token"));
+
+    let mut client = Canned::new(&[r#"{"error":"invalid_grant","error_description":"bad token"}"#]);
+    let err = refresh(&github(), &mut client, "rt").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "'https://github.com/login/oauth/access_token' responded with error 'invalid_grant': bad token"
+    );
+    Ok(())
+}
+
+#[test]
+fn credentials_ignores_unknown_hosts_and_insecure_protocols() -> crate::Result {
+    let mut client = Canned::default();
+    for (protocol, host) in [("https", "example.com"), ("http", "github.com")] {
+        let ctx = Context {
+            protocol: Some(protocol.into()),
+            host: Some(host.into()),
+            ..Default::default()
+        };
+        let res = gix_credentials::oauth::credentials(Action::Get, ctx, "client-id", &mut client, |_| {
+            unreachable!("not called")
+        })?;
+        assert_eq!(res, None);
+    }
+    assert!(client.requests.is_empty());
+    Ok(())
+}
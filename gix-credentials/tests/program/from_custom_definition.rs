@@ -108,3 +108,48 @@ fn path_with_simple_args() {
         "a shell is used as there are arguments, and it's generally more flexible, but on windows we split ourselves"
     );
 }
+
+#[test]
+#[cfg(feature = "native")]
+fn native() {
+    let prog = Program::from_custom_definition("gix-native");
+    assert_eq!(prog.kind, Kind::Native);
+    assert_eq!(
+        format!("{:?}", prog.to_command(&helper::Action::Store("egal".into()))),
+        format!(r#""{GIT}" "credential-gix-native" "store""#),
+        "it's run in-process, but would be the same as a `git-credential-gix-native` program"
+    );
+    assert!(
+        matches!(
+            Program::from_custom_definition("gix-native --arg").kind,
+            Kind::ExternalName { .. }
+        ),
+        "the native helper doesn't take arguments"
+    );
+}
+
+#[test]
+#[cfg(feature = "oauth")]
+fn oauth() {
+    let prog = Program::from_custom_definition("gix-oauth client-id");
+    assert_eq!(
+        prog.kind,
+        Kind::OAuth {
+            client_id: "client-id".into()
+        }
+    );
+    assert_eq!(
+        format!("{:?}", prog.to_command(&helper::Action::Store("egal".into()))),
+        format!(r#""{GIT}" "credential-gix-oauth" "client-id" "store""#),
+        "it's run in-process, but would be the same as a `git-credential-gix-oauth` program"
+    );
+    for definition in ["gix-oauth", "gix-oauth ", "gix-oauth client-id --arg"] {
+        assert!(
+            matches!(
+                Program::from_custom_definition(definition).kind,
+                Kind::ExternalName { .. }
+            ),
+            "{definition:?}: the OAuth helper needs exactly one client id"
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use gix_credentials::{program::main::Action, protocol::Context};
+
+fn get(output: Context) -> String {
+    let mut out = Vec::new();
+    gix_credentials::program::main(
+        Some("get".into()),
+        &b"url=https://example.com\n"[..],
+        &mut out,
+        |action, _ctx| -> Result<_, std::io::Error> {
+            assert!(matches!(action, Action::Get));
+            Ok(Some(output))
+        },
+    )
+    .expect("no failure");
+    String::from_utf8(out).expect("valid UTF-8")
+}
+
+#[test]
+fn quit_is_written_if_set() {
+    assert_eq!(
+        get(Context {
+            quit: Some(true),
+            ..Default::default()
+        }),
+        "quit=1\n"
+    );
+    assert_eq!(
+        get(Context {
+            username: Some("user".into()),
+            quit: Some(false),
+            ..Default::default()
+        }),
+        "username=user\n",
+        "`quit=0` has no effect, so it isn't written"
+    );
+}
@@ -1 +1,2 @@
 mod from_custom_definition;
+mod main_function;
@@ -48,8 +48,14 @@ pub(crate) mod function {
         } else if let Ok(val) = SecondsSinceUnixEpoch::from_str(input) {
             // Format::Unix
             Time::new(val, 0)
+        } else if let Some(val) = input
+            .strip_prefix('@')
+            .and_then(|seconds| SecondsSinceUnixEpoch::from_str(seconds).ok())
+        {
+            // Format::Unix with the `@` prefix git accepts
+            Time::new(val, 0)
         } else if let Some(val) = parse_raw(input) {
-            // Format::Raw
+            // Format::Raw, optionally with the `@` prefix git uses internally
             val
         } else if let Some(time) = relative::parse(input, now).transpose()? {
             Time::new(time.unix_timestamp(), time.offset().whole_seconds())
@@ -60,7 +66,8 @@ pub(crate) mod function {
 
     fn parse_raw(input: &str) -> Option<Time> {
         let mut split = input.split_whitespace();
-        let seconds: SecondsSinceUnixEpoch = split.next()?.parse().ok()?;
+        let seconds = split.next()?;
+        let seconds: SecondsSinceUnixEpoch = seconds.strip_prefix('@').unwrap_or(seconds).parse().ok()?;
         let offset = split.next()?;
         if offset.len() != 5 || split.next().is_some() {
             return None;
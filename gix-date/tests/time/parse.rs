@@ -84,6 +84,31 @@ fn raw() {
     }
 }
 
+#[test]
+fn raw_with_at_prefix() {
+    assert_eq!(
+        gix_date::parse("@1660874655 +0800", None).unwrap(),
+        Time {
+            seconds: 1660874655,
+            offset: 28800,
+            sign: Sign::Plus,
+        },
+        "this is the format git uses internally, for instance in `author-script` files"
+    );
+    assert_eq!(
+        gix_date::parse("@1660874655", None).unwrap(),
+        Time {
+            seconds: 1660874655,
+            offset: 0,
+            sign: Sign::Plus,
+        },
+        "the offset is optional"
+    );
+    for bad_date_str in ["@", "@ +0800", "@@1660874655 +0800", "@1660874655 0800"] {
+        assert!(gix_date::parse(bad_date_str, None).is_err(), "{bad_date_str}");
+    }
+}
+
 #[test]
 fn bad_raw() {
     for bad_date_str in [
@@ -3,6 +3,7 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use bstr::BString;
+use imara_diff::intern::{InternedInput, Token};
 pub use imara_diff::*;
 
 ///
@@ -11,6 +12,71 @@ pub mod pipeline;
 ///
 pub mod platform;
 
+mod patience;
+
+/// The algorithm to use for computing an edit-script with [`diff()`].
+///
+/// It extends the algorithms provided by [`imara_diff::Algorithm`] with those only implemented here.
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Algorithm {
+    /// The histogram diff algorithm, see [`imara_diff::Algorithm::Histogram`].
+    #[default]
+    Histogram,
+    /// Myers algorithm with heuristics for reasonable runtime, see [`imara_diff::Algorithm::Myers`].
+    Myers,
+    /// Myers algorithm without heuristics to guarantee a minimal edit-script, see [`imara_diff::Algorithm::MyersMinimal`].
+    MyersMinimal,
+    /// The patience diff algorithm as [described by Bram Cohen](https://bramcohen.livejournal.com/73318.html),
+    /// which anchors the diff at lines that occur exactly once on both sides, and uses Myers algorithm for sections
+    /// without such lines. It's what `git diff --patience` uses.
+    Patience,
+}
+
+impl From<imara_diff::Algorithm> for Algorithm {
+    fn from(algorithm: imara_diff::Algorithm) -> Self {
+        match algorithm {
+            imara_diff::Algorithm::Histogram => Algorithm::Histogram,
+            imara_diff::Algorithm::Myers => Algorithm::Myers,
+            imara_diff::Algorithm::MyersMinimal => Algorithm::MyersMinimal,
+        }
+    }
+}
+
+/// Compute an edit-script that transforms `input.before` into `input.after` using `algorithm`, and pass it to `sink`
+/// while it is produced.
+///
+/// This is like [`imara_diff::diff()`], but supports all of our [algorithms](Algorithm).
+pub fn diff<S: Sink, T: Eq + std::hash::Hash>(algorithm: Algorithm, input: &InternedInput<T>, sink: S) -> S::Out {
+    diff_with_tokens(
+        algorithm,
+        &input.before,
+        &input.after,
+        input.interner.num_tokens(),
+        sink,
+    )
+}
+
+/// Compute an edit-script that transforms the tokens in `before` into the tokens in `after` using `algorithm`,
+/// and pass it to `sink` while it is produced. `num_tokens` is the amount of distinct tokens in the interner that produced
+/// `before` and `after`.
+///
+/// This is like [`imara_diff::diff_with_tokens()`], but supports all of our [algorithms](Algorithm).
+pub fn diff_with_tokens<S: Sink>(
+    algorithm: Algorithm,
+    before: &[Token],
+    after: &[Token],
+    num_tokens: u32,
+    sink: S,
+) -> S::Out {
+    let algorithm = match algorithm {
+        Algorithm::Histogram => imara_diff::Algorithm::Histogram,
+        Algorithm::Myers => imara_diff::Algorithm::Myers,
+        Algorithm::MyersMinimal => imara_diff::Algorithm::MyersMinimal,
+        Algorithm::Patience => return patience::diff(before, after, num_tokens, sink),
+    };
+    imara_diff::diff_with_tokens(algorithm, before, after, num_tokens, sink)
+}
+
 /// Information about the diff performed to detect similarity.
 #[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
 pub struct DiffLineStats {
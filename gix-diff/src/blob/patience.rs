@@ -0,0 +1,152 @@
+use std::{collections::HashMap, ops::Range};
+
+use imara_diff::{intern::Token, Sink};
+
+/// Compute the patience diff between `before` and `after`.
+///
+/// Lines that occur exactly once in both sides are matched up along their longest common subsequence, and the sections
+/// between these anchors are diffed recursively. Sections without unique lines are diffed with Myers algorithm, just like `git` does.
+pub(super) fn diff<S: Sink>(before: &[Token], after: &[Token], num_tokens: u32, mut sink: S) -> S::Out {
+    recurse(before, after, 0, 0, num_tokens, &mut sink);
+    sink.finish()
+}
+
+fn recurse<S: Sink>(
+    before: &[Token],
+    after: &[Token],
+    before_start: u32,
+    after_start: u32,
+    num_tokens: u32,
+    sink: &mut S,
+) {
+    let prefix = before.iter().zip(after).take_while(|(a, b)| a == b).count();
+    let (before, after) = (&before[prefix..], &after[prefix..]);
+    let suffix = before
+        .iter()
+        .rev()
+        .zip(after.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (before, after) = (&before[..before.len() - suffix], &after[..after.len() - suffix]);
+    let (before_start, after_start) = (before_start + prefix as u32, after_start + prefix as u32);
+
+    if before.is_empty() || after.is_empty() {
+        if !(before.is_empty() && after.is_empty()) {
+            sink.process_change(
+                before_start..before_start + before.len() as u32,
+                after_start..after_start + after.len() as u32,
+            );
+        }
+        return;
+    }
+
+    let anchors = unique_common_subsequence(before, after);
+    if anchors.is_empty() {
+        imara_diff::diff_with_tokens(
+            imara_diff::Algorithm::Myers,
+            before,
+            after,
+            num_tokens,
+            Offset {
+                sink,
+                before: before_start,
+                after: after_start,
+            },
+        );
+        return;
+    }
+
+    let (mut before_pos, mut after_pos) = (0, 0);
+    for (before_anchor, after_anchor) in anchors {
+        recurse(
+            &before[before_pos..before_anchor],
+            &after[after_pos..after_anchor],
+            before_start + before_pos as u32,
+            after_start + after_pos as u32,
+            num_tokens,
+            sink,
+        );
+        (before_pos, after_pos) = (before_anchor + 1, after_anchor + 1);
+    }
+    recurse(
+        &before[before_pos..],
+        &after[after_pos..],
+        before_start + before_pos as u32,
+        after_start + after_pos as u32,
+        num_tokens,
+        sink,
+    );
+}
+
+/// Return the positions of tokens that occur exactly once in both `before` and `after`, along their longest
+/// common subsequence, ordered by position.
+fn unique_common_subsequence(before: &[Token], after: &[Token]) -> Vec<(usize, usize)> {
+    #[derive(Default)]
+    struct Occurrences {
+        in_before: u32,
+        before_pos: usize,
+        in_after: u32,
+        after_pos: usize,
+    }
+    let mut occurrences = HashMap::<Token, Occurrences>::new();
+    for (pos, token) in before.iter().enumerate() {
+        let occurrence = occurrences.entry(*token).or_default();
+        occurrence.in_before += 1;
+        occurrence.before_pos = pos;
+    }
+    for (pos, token) in after.iter().enumerate() {
+        if let Some(occurrence) = occurrences.get_mut(token) {
+            occurrence.in_after += 1;
+            occurrence.after_pos = pos;
+        }
+    }
+    let mut candidates: Vec<_> = occurrences
+        .into_values()
+        .filter(|o| o.in_before == 1 && o.in_after == 1)
+        .map(|o| (o.before_pos, o.after_pos))
+        .collect();
+    candidates.sort_unstable();
+
+    // Patience sorting: each pile is represented by the candidate on top of it, and each candidate remembers
+    // the top of the previous pile at the time it was placed to be able to reconstruct the subsequence.
+    let mut piles = Vec::<usize>::new();
+    let mut predecessors = Vec::with_capacity(candidates.len());
+    for (idx, (_, after_pos)) in candidates.iter().enumerate() {
+        let pile = piles.partition_point(|top| candidates[*top].1 < *after_pos);
+        predecessors.push(pile.checked_sub(1).map(|previous_pile| piles[previous_pile]));
+        if pile == piles.len() {
+            piles.push(idx);
+        } else {
+            piles[pile] = idx;
+        }
+    }
+
+    let mut subsequence = Vec::with_capacity(piles.len());
+    let mut next = piles.last().copied();
+    while let Some(idx) = next {
+        subsequence.push(candidates[idx]);
+        next = predecessors[idx];
+    }
+    subsequence.reverse();
+    subsequence
+}
+
+/// A sink to pass changes of a subsection of the input to the actual sink, with ranges adjusted to the whole input.
+struct Offset<'a, S> {
+    sink: &'a mut S,
+    before: u32,
+    after: u32,
+}
+
+impl<S: Sink> Sink for Offset<'_, S> {
+    type Out = ();
+
+    fn process_change(&mut self, before: Range<u32>, after: Range<u32>) {
+        self.sink.process_change(
+            before.start + self.before..before.end + self.before,
+            after.start + self.after..after.end + self.after,
+        );
+    }
+
+    fn finish(self) -> Self::Out {}
+}
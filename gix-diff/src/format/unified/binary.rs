@@ -0,0 +1,168 @@
+use std::{collections::HashMap, io, io::Write};
+
+/// The amount of bytes to look at for detecting binary files, just like `git` does it.
+const BINARY_DETECTION_LEN: usize = 8000;
+/// The amount of bytes encoded per line of a binary patch.
+const BYTES_PER_LINE: usize = 52;
+/// The zlib compression level used by `git` by default.
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// Return `true` if `data` looks binary as it contains a null-byte in its first 8000 bytes.
+pub(super) fn is_binary(data: &[u8]) -> bool {
+    data[..data.len().min(BINARY_DETECTION_LEN)].contains(&0)
+}
+
+/// Write the part of a binary patch that turns `from` into `to`, either as delta or as literal, whichever is smaller.
+pub(super) fn write_body(out: &mut dyn io::Write, from: &[u8], to: &[u8]) -> io::Result<()> {
+    let literal = deflate(to)?;
+    let delta = if !from.is_empty() && !to.is_empty() {
+        create_delta(from, to)
+            .map(|delta| deflate(&delta).map(|deflated| (delta.len(), deflated)))
+            .transpose()?
+    } else {
+        None
+    };
+    let data = match delta {
+        Some((size, deflated)) if deflated.len() < literal.len() => {
+            writeln!(out, "delta {size}")?;
+            deflated
+        }
+        _ => {
+            writeln!(out, "literal {}", to.len())?;
+            literal
+        }
+    };
+    for chunk in data.chunks(BYTES_PER_LINE) {
+        let len = chunk.len() as u8;
+        out.write_all(&[if len <= 26 { b'A' + len - 1 } else { b'a' + len - 27 }])?;
+        out.write_all(&encode_85(chunk))?;
+        out.write_all(b"\n")?;
+    }
+    out.write_all(b"\n")
+}
+
+fn deflate(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut out = gix_features::zlib::stream::deflate::Write::with_compression_level(
+        Vec::with_capacity(data.len() / 2),
+        Some(COMPRESSION_LEVEL),
+    );
+    out.write_all(data)?;
+    out.flush()?;
+    Ok(out.into_inner())
+}
+
+/// Encode `data` in base-85 like `git` does, in groups of 4 bytes that each turn into 5 characters.
+fn encode_85(data: &[u8]) -> Vec<u8> {
+    const ALPHABET: &[u8; 85] =
+        b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+    let mut out = Vec::with_capacity((data.len() + 3) / 4 * 5);
+    for group in data.chunks(4) {
+        let mut bytes = [0; 4];
+        bytes[..group.len()].copy_from_slice(group);
+        let mut value = u32::from_be_bytes(bytes);
+        let mut encoded = [0; 5];
+        for c in encoded.iter_mut().rev() {
+            *c = ALPHABET[(value % 85) as usize];
+            value /= 85;
+        }
+        out.extend_from_slice(&encoded);
+    }
+    out
+}
+
+/// Create a delta in the format used in packs to reconstruct `target` from `base`, or `None` if `base` is too large
+/// for the delta format.
+///
+/// Matches are found by indexing `base` in blocks, which are then extended as far as possible in both directions.
+fn create_delta(base: &[u8], target: &[u8]) -> Option<Vec<u8>> {
+    const BLOCK_LEN: usize = 16;
+    /// The largest amount of bytes that a single copy instruction can copy, for compatibility with version 2 packs.
+    const MAX_COPY_LEN: usize = 0x10000;
+    /// The largest amount of bytes that a single insert instruction can insert.
+    const MAX_INSERT_LEN: usize = 0x7f;
+    if base.len() > u32::MAX as usize {
+        return None;
+    }
+
+    let mut blocks = HashMap::<&[u8], usize>::new();
+    for (idx, block) in base.chunks_exact(BLOCK_LEN).enumerate() {
+        blocks.entry(block).or_insert(idx * BLOCK_LEN);
+    }
+
+    let mut out = Vec::new();
+    write_size(&mut out, base.len());
+    write_size(&mut out, target.len());
+    let insert = |out: &mut Vec<u8>, data: &[u8]| {
+        for chunk in data.chunks(MAX_INSERT_LEN) {
+            out.push(chunk.len() as u8);
+            out.extend_from_slice(chunk);
+        }
+    };
+
+    let (mut pos, mut insert_start) = (0, 0);
+    while pos + BLOCK_LEN <= target.len() {
+        let Some(&base_pos) = blocks.get(&target[pos..pos + BLOCK_LEN]) else {
+            pos += 1;
+            continue;
+        };
+        let forward = base[base_pos..]
+            .iter()
+            .zip(&target[pos..])
+            .take_while(|(a, b)| a == b)
+            .count();
+        let backward = base[..base_pos]
+            .iter()
+            .rev()
+            .zip(target[insert_start..pos].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        insert(&mut out, &target[insert_start..pos - backward]);
+        let (mut offset, mut len) = (base_pos - backward, forward + backward);
+        while len != 0 {
+            let copy_len = len.min(MAX_COPY_LEN);
+            write_copy(&mut out, offset as u32, copy_len as u32);
+            offset += copy_len;
+            len -= copy_len;
+        }
+        pos += forward;
+        insert_start = pos;
+    }
+    insert(&mut out, &target[insert_start..]);
+    Some(out)
+}
+
+/// Write `size` as variable-length integer with 7 bits per byte, least significant bits first.
+fn write_size(out: &mut Vec<u8>, mut size: usize) {
+    loop {
+        let byte = (size & 0x7f) as u8;
+        size >>= 7;
+        if size == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Write an instruction to copy `len` bytes from `offset` in the base, where only non-zero bytes of both are stored.
+/// Note that a `len` of `0x10000` is stored as zero.
+fn write_copy(out: &mut Vec<u8>, offset: u32, len: u32) {
+    let instruction_pos = out.len();
+    let mut instruction = 0x80;
+    for (idx, byte) in offset.to_le_bytes().into_iter().enumerate() {
+        if byte != 0 {
+            instruction |= 1 << idx;
+        }
+    }
+    let len = if len == 0x10000 { 0 } else { len };
+    for (idx, byte) in len.to_le_bytes().into_iter().take(3).enumerate() {
+        if byte != 0 {
+            instruction |= 0x10 << idx;
+        }
+    }
+    out.push(instruction);
+    out.extend(offset.to_le_bytes().into_iter().filter(|b| *b != 0));
+    out.extend(len.to_le_bytes().into_iter().take(3).filter(|b| *b != 0));
+    debug_assert_eq!(out[instruction_pos], instruction);
+}
@@ -0,0 +1,201 @@
+use std::io;
+
+use bstr::{BStr, BString};
+
+use crate::blob::Algorithm;
+
+mod binary;
+mod text;
+
+/// One side of a [`Change`] to render as patch.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Resource<'a> {
+    /// The path of the resource, relative to the root of the repository.
+    pub path: &'a BStr,
+    /// The id of the resource's content.
+    pub id: &'a gix_hash::oid,
+    /// The mode of the resource.
+    pub mode: gix_object::tree::EntryMode,
+    /// The content of the resource, which is diffed as is.
+    pub data: &'a [u8],
+}
+
+/// A change to render as patch with [`write()`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Change<'a> {
+    /// The resource `new` was added.
+    Addition {
+        /// The added resource.
+        new: Resource<'a>,
+    },
+    /// The resource `old` was deleted.
+    Deletion {
+        /// The deleted resource.
+        old: Resource<'a>,
+    },
+    /// The resource `old` was modified to become `new`, which is at the same path.
+    Modification {
+        /// The resource before the change.
+        old: Resource<'a>,
+        /// The resource after the change.
+        new: Resource<'a>,
+    },
+    /// The resource `old` was renamed or copied to `new`, possibly with modifications.
+    Rewrite {
+        /// The source of the rename or copy.
+        old: Resource<'a>,
+        /// The destination of the rename or copy.
+        new: Resource<'a>,
+        /// If `true`, `new` is a copy of `old`, which still exists.
+        copy: bool,
+        /// The similarity of `old` and `new` from 0.0 to 1.0, with 1.0 meaning the content is identical.
+        similarity: f32,
+    },
+}
+
+impl<'a> Change<'a> {
+    fn resources(&self) -> (Option<Resource<'a>>, Option<Resource<'a>>) {
+        match *self {
+            Change::Addition { new } => (None, Some(new)),
+            Change::Deletion { old } => (Some(old), None),
+            Change::Modification { old, new } | Change::Rewrite { old, new, .. } => (Some(old), Some(new)),
+        }
+    }
+}
+
+/// Options for use in [`write()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The algorithm to use for diffing text.
+    pub algorithm: Algorithm,
+    /// The amount of unchanged lines to show around each change, similar to `git diff -U<n>`.
+    pub context_lines: u32,
+    /// If `true`, changes to binary files are written as binary patch that `git apply` can apply, similar to `git diff --binary`.
+    /// Otherwise, only a note that the binary files differ is written.
+    pub binary: bool,
+    /// The prefix to use for paths of the old version of a resource, like `a/`.
+    pub old_prefix: BString,
+    /// The prefix to use for paths of the new version of a resource, like `b/`.
+    pub new_prefix: BString,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            algorithm: Algorithm::Myers,
+            context_lines: 3,
+            binary: false,
+            old_prefix: "a/".into(),
+            new_prefix: "b/".into(),
+        }
+    }
+}
+
+/// The amount of hex characters of abbreviated object ids in `index` lines.
+///
+/// Note that `git` assures these are unique, which we don't do as it would require access to the object database.
+const ABBREV_LEN: usize = 7;
+
+/// Write `change` as patch in the unified format to `out`, including extended headers for mode changes, additions, deletions,
+/// renames and copies, just like `git diff` does it.
+///
+/// Resources are considered binary if they contain a null-byte in their first 8000 bytes, and are written according to
+/// [`Options::binary`]. Text is diffed line by line as configured in `options`, and each hunk header shows the closest
+/// preceding line that starts with a letter, `_` or `$`, like `git` does without a diff driver.
+///
+/// ### Limitations
+///
+/// * Paths with special characters are written verbatim and not quoted.
+/// * Object ids are abbreviated without assuring they are unique.
+pub fn write(out: &mut dyn io::Write, change: Change<'_>, options: &Options) -> io::Result<()> {
+    let (old, new) = change.resources();
+    let any = old.or(new).expect("at least one resource is always set");
+    let old_path = old.unwrap_or(any).path;
+    let new_path = new.unwrap_or(any).path;
+    out.write_all(b"diff --git ")?;
+    write_path(out, &options.old_prefix, old_path)?;
+    out.write_all(b" ")?;
+    write_path(out, &options.new_prefix, new_path)?;
+    out.write_all(b"\n")?;
+
+    match change {
+        Change::Addition { new } => writeln!(out, "new file mode {:06o}", new.mode.0)?,
+        Change::Deletion { old } => writeln!(out, "deleted file mode {:06o}", old.mode.0)?,
+        Change::Modification { old, new } | Change::Rewrite { old, new, .. } => {
+            if old.mode != new.mode {
+                writeln!(out, "old mode {:06o}", old.mode.0)?;
+                writeln!(out, "new mode {:06o}", new.mode.0)?;
+            }
+        }
+    }
+    if let Change::Rewrite {
+        old,
+        new,
+        copy,
+        similarity,
+    } = change
+    {
+        let kind = if copy { "copy" } else { "rename" };
+        writeln!(
+            out,
+            "similarity index {}%",
+            (similarity.clamp(0.0, 1.0) * 100.0).round() as u32
+        )?;
+        write!(out, "{kind} from ")?;
+        write_path(out, b"", old.path)?;
+        write!(out, "\n{kind} to ")?;
+        write_path(out, b"", new.path)?;
+        out.write_all(b"\n")?;
+    }
+
+    let null = any.id.kind().null();
+    let (old_id, new_id) = (old.map_or(null.as_ref(), |r| r.id), new.map_or(null.as_ref(), |r| r.id));
+    if old_id == new_id {
+        return Ok(());
+    }
+    let (old_data, new_data) = (old.map_or(&[][..], |r| r.data), new.map_or(&[][..], |r| r.data));
+    let is_binary = binary::is_binary(old_data) || binary::is_binary(new_data);
+    let abbrev = if is_binary && options.binary {
+        any.id.kind().len_in_hex()
+    } else {
+        ABBREV_LEN
+    };
+    write!(
+        out,
+        "index {}..{}",
+        old_id.to_hex_with_len(abbrev),
+        new_id.to_hex_with_len(abbrev)
+    )?;
+    match (old, new) {
+        (Some(old), Some(new)) if old.mode == new.mode => writeln!(out, " {:06o}", old.mode.0)?,
+        _ => out.write_all(b"\n")?,
+    }
+
+    if is_binary {
+        if options.binary {
+            out.write_all(b"GIT binary patch\n")?;
+            binary::write_body(out, old_data, new_data)?;
+            binary::write_body(out, new_data, old_data)
+        } else {
+            out.write_all(b"Binary files ")?;
+            write_path_or_null(out, &options.old_prefix, old)?;
+            out.write_all(b" and ")?;
+            write_path_or_null(out, &options.new_prefix, new)?;
+            out.write_all(b" differ\n")
+        }
+    } else {
+        text::write_hunks(out, old, new, options)
+    }
+}
+
+fn write_path(out: &mut dyn io::Write, prefix: &[u8], path: &BStr) -> io::Result<()> {
+    out.write_all(prefix)?;
+    out.write_all(path)
+}
+
+fn write_path_or_null(out: &mut dyn io::Write, prefix: &[u8], resource: Option<Resource<'_>>) -> io::Result<()> {
+    match resource {
+        Some(resource) => write_path(out, prefix, resource.path),
+        None => out.write_all(b"/dev/null"),
+    }
+}
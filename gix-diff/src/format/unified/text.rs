@@ -0,0 +1,144 @@
+use std::{io, ops::Range};
+
+use crate::{
+    blob::{intern::InternedInput, sources::byte_lines_with_terminator},
+    format::unified::{write_path_or_null, Options, Resource},
+};
+
+/// The maximum length of the function name shown in hunk headers, just like in `git`.
+const MAX_FUNCTION_NAME_LEN: usize = 80;
+
+/// Write the `---` and `+++` lines followed by all hunks of changes between `old` and `new`, or nothing if there are no changes.
+pub(super) fn write_hunks(
+    out: &mut dyn io::Write,
+    old: Option<Resource<'_>>,
+    new: Option<Resource<'_>>,
+    options: &Options,
+) -> io::Result<()> {
+    let input = InternedInput::new(
+        byte_lines_with_terminator(old.map_or(&[][..], |r| r.data)),
+        byte_lines_with_terminator(new.map_or(&[][..], |r| r.data)),
+    );
+    let mut changes = Vec::<(Range<u32>, Range<u32>)>::new();
+    crate::blob::diff(options.algorithm, &input, |before: Range<u32>, after: Range<u32>| {
+        changes.push((before, after));
+    });
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    out.write_all(b"--- ")?;
+    write_path_or_null(out, &options.old_prefix, old)?;
+    out.write_all(b"\n+++ ")?;
+    write_path_or_null(out, &options.new_prefix, new)?;
+    out.write_all(b"\n")?;
+
+    let context = options.context_lines;
+    let num_before = input.before.len() as u32;
+    let mut function_name = FunctionName::default();
+    let mut rest = &changes[..];
+    while let Some(first) = rest.first() {
+        let num_changes = 1 + rest
+            .windows(2)
+            .take_while(|pair| pair[1].0.start - pair[0].0.end <= 2 * context)
+            .count();
+        let (hunk, remaining) = rest.split_at(num_changes);
+        rest = remaining;
+        let last = hunk.last().expect("at least one change per hunk");
+
+        let leading = first.0.start.min(context);
+        let trailing = (num_before - last.0.end).min(context);
+        let before = first.0.start - leading..last.0.end + trailing;
+        let after = first.1.start - leading..last.1.end + trailing;
+
+        write!(out, "@@ -{} +{} @@", HunkRange(&before), HunkRange(&after))?;
+        if let Some(name) = function_name.find(&input, before.start) {
+            out.write_all(b" ")?;
+            out.write_all(name)?;
+        }
+        out.write_all(b"\n")?;
+
+        let mut pos = before.start;
+        for (removed, added) in hunk {
+            write_lines(out, b' ', &input, &input.before[pos as usize..removed.start as usize])?;
+            write_lines(
+                out,
+                b'-',
+                &input,
+                &input.before[removed.start as usize..removed.end as usize],
+            )?;
+            write_lines(
+                out,
+                b'+',
+                &input,
+                &input.after[added.start as usize..added.end as usize],
+            )?;
+            pos = removed.end;
+        }
+        write_lines(out, b' ', &input, &input.before[pos as usize..before.end as usize])?;
+    }
+    Ok(())
+}
+
+fn write_lines(
+    out: &mut dyn io::Write,
+    prefix: u8,
+    input: &InternedInput<&[u8]>,
+    tokens: &[crate::blob::intern::Token],
+) -> io::Result<()> {
+    for token in tokens {
+        let line = input.interner[*token];
+        out.write_all(&[prefix])?;
+        out.write_all(line)?;
+        if !line.ends_with(b"\n") {
+            out.write_all(b"\n\\ No newline at end of file\n")?;
+        }
+    }
+    Ok(())
+}
+
+/// A line range formatted like `git` does in hunk headers.
+struct HunkRange<'a>(&'a Range<u32>);
+
+impl std::fmt::Display for HunkRange<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let len = self.0.len();
+        // Empty ranges refer to the line after which lines are added or removed.
+        let start = if len == 0 { self.0.start } else { self.0.start + 1 };
+        if len == 1 {
+            write!(f, "{start}")
+        } else {
+            write!(f, "{start},{len}")
+        }
+    }
+}
+
+/// Keep track of the function name shown in hunk headers, to avoid searching the same lines more than once.
+#[derive(Default)]
+struct FunctionName<'a> {
+    /// The line before which all lines were searched.
+    searched_until: u32,
+    name: Option<&'a [u8]>,
+}
+
+impl<'a> FunctionName<'a> {
+    /// Return the last line before the line at `end` that starts with a letter, `_` or `$`, like the default function name
+    /// detection of `git`.
+    fn find(&mut self, input: &InternedInput<&'a [u8]>, end: u32) -> Option<&'a [u8]> {
+        if let Some(name) = input.before[self.searched_until as usize..end as usize]
+            .iter()
+            .rev()
+            .map(|token| input.interner[*token])
+            .find(|line| {
+                line.first()
+                    .map_or(false, |b| b.is_ascii_alphabetic() || *b == b'_' || *b == b'$')
+            })
+        {
+            let name = &name[..name.len().min(MAX_FUNCTION_NAME_LEN)];
+            let len = name.len() - name.iter().rev().take_while(|b| b.is_ascii_whitespace()).count();
+            self.name = Some(&name[..len]);
+        }
+        self.searched_until = end;
+        self.name
+    }
+}
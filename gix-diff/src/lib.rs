@@ -49,3 +49,7 @@ pub mod tree;
 ///
 #[cfg(feature = "blob")]
 pub mod blob;
+
+/// Render diffs in formats suitable for humans and tools, like unified patches.
+#[cfg(feature = "blob")]
+pub mod format;
@@ -59,6 +59,35 @@ pub struct Outcome {
     pub num_similarity_checks_skipped_for_copy_tracking_due_to_limit: usize,
 }
 
+/// Signals that similarity-based rewrite tracking was skipped as it would have exceeded [the limit](Rewrites::limit),
+/// so only exact renames and copies were found.
+///
+/// It's the equivalent of the warning `git` emits when inexact rename detection was skipped, and allows callers
+/// to track the same changes again with a higher limit.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Degradation {
+    /// If `true`, similarity-based rename tracking was skipped.
+    pub renames: bool,
+    /// If `true`, similarity-based copy tracking was skipped.
+    pub copies: bool,
+    /// The smallest [limit](Rewrites::limit) that would have allowed all similarity checks to be performed.
+    pub required_limit: usize,
+}
+
+impl Outcome {
+    /// Return information about how rewrite tracking was degraded due to its limit, or `None` if all
+    /// similarity checks were performed.
+    pub fn degradation(&self) -> Option<Degradation> {
+        let renames = self.num_similarity_checks_skipped_for_rename_tracking_due_to_limit;
+        let copies = self.num_similarity_checks_skipped_for_copy_tracking_due_to_limit;
+        (renames != 0 || copies != 0).then_some(Degradation {
+            renames: renames != 0,
+            copies: copies != 0,
+            required_limit: renames.max(copies),
+        })
+    }
+}
+
 /// The default settings for rewrites according to the git configuration defaults.
 impl Default for Rewrites {
     fn default() -> Self {
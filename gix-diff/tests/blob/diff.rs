@@ -0,0 +1,94 @@
+use std::ops::Range;
+
+use gix_diff::blob::{intern::InternedInput, sources::byte_lines_with_terminator, Algorithm};
+use gix_object::bstr::ByteSlice;
+
+type Hunk = (Range<u32>, Range<u32>);
+
+const ALL: [Algorithm; 4] = [
+    Algorithm::Histogram,
+    Algorithm::Myers,
+    Algorithm::MyersMinimal,
+    Algorithm::Patience,
+];
+
+#[test]
+fn patience_and_myers_match_git() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only_standalone("make_diff_algorithms.sh")?;
+    let before = std::fs::read(root.join("before"))?;
+    let after = std::fs::read(root.join("after"))?;
+    for (algorithm, name) in [(Algorithm::Patience, "patience.diff"), (Algorithm::Myers, "myers.diff")] {
+        let expected = git_hunks(&std::fs::read(root.join(name))?);
+        assert_eq!(hunks(algorithm, &before, &after), expected, "{algorithm:?}");
+    }
+    assert_ne!(
+        hunks(Algorithm::Patience, &before, &after),
+        hunks(Algorithm::Myers, &before, &after),
+        "the example is chosen to show the difference"
+    );
+    Ok(())
+}
+
+#[test]
+fn all_algorithms_transform_before_into_after() {
+    let inputs: &[(&str, &str)] = &[
+        ("", ""),
+        ("", "a\nb\n"),
+        ("a\nb\n", ""),
+        ("a\nb\nc\n", "a\nb\nc\n"),
+        ("a\nb\nc\n", "c\nb\na\n"),
+        ("a\na\nb\nb\n", "b\nb\na\na\n"),
+        ("x\n{\n1\n}\n{\n2\n}\ny\n", "y\n{\n2\n}\nz\n{\n1\n}\nx\n"),
+    ];
+    for (before, after) in inputs {
+        for algorithm in ALL {
+            let input = InternedInput::new(
+                byte_lines_with_terminator(before.as_bytes()),
+                byte_lines_with_terminator(after.as_bytes()),
+            );
+            let mut result = Vec::new();
+            let mut pos = 0;
+            for (removed, added) in hunks(algorithm, before.as_bytes(), after.as_bytes()) {
+                assert!(
+                    removed.start >= pos,
+                    "{algorithm:?}: hunks are ordered and don't overlap"
+                );
+                result.extend(input.before[pos as usize..removed.start as usize].iter().copied());
+                result.extend(input.after[added.start as usize..added.end as usize].iter().copied());
+                pos = removed.end;
+            }
+            result.extend(input.before[pos as usize..].iter().copied());
+            assert_eq!(result, input.after, "{algorithm:?}: {before:?} -> {after:?}");
+        }
+    }
+}
+
+fn hunks(algorithm: Algorithm, before: &[u8], after: &[u8]) -> Vec<Hunk> {
+    let input = InternedInput::new(byte_lines_with_terminator(before), byte_lines_with_terminator(after));
+    let mut hunks = Vec::new();
+    gix_diff::blob::diff(algorithm, &input, |before: Range<u32>, after: Range<u32>| {
+        hunks.push((before, after));
+    });
+    hunks
+}
+
+/// Parse the hunk headers of a diff produced with `-U0`.
+fn git_hunks(diff: &[u8]) -> Vec<Hunk> {
+    fn range(spec: &str) -> Range<u32> {
+        let (start, len) = spec.split_once(',').unwrap_or((spec, "1"));
+        let (start, len): (u32, u32) = (start.parse().unwrap(), len.parse().unwrap());
+        // Empty ranges refer to the line after which lines are added or removed.
+        let start = if len == 0 { start } else { start - 1 };
+        start..start + len
+    }
+    diff.lines()
+        .filter_map(|line| line.strip_prefix(b"@@ -"))
+        .map(|header| {
+            let header = header.to_str().expect("valid UTF-8");
+            let mut ranges = header.split(" @@").next().expect("header").split(" +");
+            let before = range(ranges.next().expect("before"));
+            let after = range(ranges.next().expect("after"));
+            (before, after)
+        })
+        .collect()
+}
@@ -1,2 +1,3 @@
+mod diff;
 pub(crate) mod pipeline;
 mod platform;
@@ -0,0 +1,64 @@
+#!/bin/bash
+set -eu -o pipefail
+
+# The example used to illustrate the advantages of the patience diff algorithm.
+cat <<'END' >before
+#include <stdio.h>
+
+// Frobs foo heartily
+int frobnitz(int foo)
+{
+    int i;
+    for(i = 0; i < 10; i++)
+    {
+        printf("Your answer is: ");
+        printf("%d\n", foo);
+    }
+}
+
+int fact(int n)
+{
+    if(n > 1)
+    {
+        return fact(n-1) * n;
+    }
+    return 1;
+}
+
+int main(int argc, char **argv)
+{
+    frobnitz(fact(10));
+}
+END
+
+cat <<'END' >after
+#include <stdio.h>
+
+int fib(int n)
+{
+    if(n > 2)
+    {
+        return fib(n-1) + fib(n-2);
+    }
+    return 1;
+}
+
+// Frobs foo heartily
+int frobnitz(int foo)
+{
+    int i;
+    for(i = 0; i < 10; i++)
+    {
+        printf("%d\n", foo);
+    }
+}
+
+int main(int argc, char **argv)
+{
+    frobnitz(fib(10));
+}
+END
+
+for algorithm in patience myers; do
+  git -c diff.indentHeuristic=false diff --no-index --diff-algorithm=$algorithm -U0 before after >$algorithm.diff || test $? = 1
+done
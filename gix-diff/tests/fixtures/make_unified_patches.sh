@@ -0,0 +1,70 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config core.abbrev 7
+
+function lines() {
+  for i in $(seq "$1" "$2"); do
+    echo "    line $i"
+  done
+}
+
+{
+  echo "int first(void)"
+  echo "{"
+  lines 1 20
+  echo "}"
+  echo ""
+  echo "int second(void)"
+  echo "{"
+  lines 21 40
+  echo "}"
+  printf "no newline"
+} >modified
+lines 1 30 >deleted
+echo "unchanged" >exe
+echo "unchanged" >exe-modified
+lines 1 20 >renamed-from
+lines 1 20 >copy-source
+printf 'binary\0%.0s' $(seq 1 500) >binary
+git add . && git commit -q -m "base"
+
+{
+  echo "int first(void)"
+  echo "{"
+  lines 1 2
+  echo "inserted"
+  lines 3 12
+  lines 14 20
+  echo "}"
+  echo ""
+  echo "int second(void)"
+  echo "{"
+  lines 21 38
+  echo "changed"
+  lines 40 40
+  echo "}"
+  echo "newline"
+} >modified
+rm deleted
+lines 1 10 >added
+touch empty-added
+chmod +x exe exe-modified
+echo "changed" >>exe-modified
+git mv renamed-from renamed-to
+echo "line 21" >>renamed-to
+{ lines 1 20; echo "copied"; } >copy-destination
+printf 'binary\0%.0s' $(seq 1 250) >binary
+printf 'changed\0' >>binary
+printf 'binary\0%.0s' $(seq 1 250) >>binary
+git add . && git commit -q -m "changes"
+git rev-parse HEAD~1^{tree} HEAD^{tree} >trees
+
+for path in modified deleted added empty-added exe exe-modified binary; do
+  git diff HEAD~1 HEAD -- $path >$path.patch
+done
+git diff -U1 HEAD~1 HEAD -- modified >modified-U1.patch
+git diff --binary HEAD~1 HEAD -- binary >binary-binary.patch
+git diff -M HEAD~1 HEAD -- renamed-from renamed-to >renamed.patch
+git diff --find-copies-harder HEAD~1 HEAD -- copy-source copy-destination >copied.patch
@@ -0,0 +1,231 @@
+use std::path::PathBuf;
+
+use gix_diff::format::unified::{self, Change, Options, Resource};
+use gix_hash::ObjectId;
+use gix_object::{
+    bstr::{BStr, ByteSlice},
+    tree::EntryMode,
+    FindExt,
+};
+
+#[test]
+fn modifications_with_context_and_function_names() -> crate::Result {
+    let fixture = Fixture::new()?;
+    let (old, new) = (fixture.before("modified")?, fixture.after("modified")?);
+    for (context_lines, expected) in [(3, "modified.patch"), (1, "modified-U1.patch")] {
+        let actual = fixture.patch(
+            Change::Modification {
+                old: old.resource(),
+                new: new.resource(),
+            },
+            &Options {
+                context_lines,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(actual.as_bstr(), fixture.expected(expected)?.as_bstr());
+    }
+    Ok(())
+}
+
+#[test]
+fn additions_and_deletions() -> crate::Result {
+    let fixture = Fixture::new()?;
+    for path in ["added", "empty-added"] {
+        let new = fixture.after(path)?;
+        let actual = fixture.patch(Change::Addition { new: new.resource() }, &Default::default())?;
+        assert_eq!(actual.as_bstr(), fixture.expected(&format!("{path}.patch"))?.as_bstr());
+    }
+    let old = fixture.before("deleted")?;
+    let actual = fixture.patch(Change::Deletion { old: old.resource() }, &Default::default())?;
+    assert_eq!(actual.as_bstr(), fixture.expected("deleted.patch")?.as_bstr());
+    Ok(())
+}
+
+#[test]
+fn mode_changes_with_and_without_modification() -> crate::Result {
+    let fixture = Fixture::new()?;
+    for path in ["exe", "exe-modified"] {
+        let (old, new) = (fixture.before(path)?, fixture.after(path)?);
+        let actual = fixture.patch(
+            Change::Modification {
+                old: old.resource(),
+                new: new.resource(),
+            },
+            &Default::default(),
+        )?;
+        assert_eq!(actual.as_bstr(), fixture.expected(&format!("{path}.patch"))?.as_bstr());
+    }
+    Ok(())
+}
+
+#[test]
+fn renames_and_copies() -> crate::Result {
+    let fixture = Fixture::new()?;
+    for (source, destination, copy, similarity, expected) in [
+        ("renamed-from", "renamed-to", false, 0.96, "renamed.patch"),
+        ("copy-source", "copy-destination", true, 0.97, "copied.patch"),
+    ] {
+        let (old, new) = (fixture.before(source)?, fixture.after(destination)?);
+        let actual = fixture.patch(
+            Change::Rewrite {
+                old: old.resource(),
+                new: new.resource(),
+                copy,
+                similarity,
+            },
+            &Default::default(),
+        )?;
+        assert_eq!(actual.as_bstr(), fixture.expected(expected)?.as_bstr());
+    }
+    Ok(())
+}
+
+#[test]
+fn binary_files_are_noted_or_written_as_binary_patch_that_git_can_apply() -> crate::Result {
+    let fixture = Fixture::new()?;
+    let (old, new) = (fixture.before("binary")?, fixture.after("binary")?);
+    let change = Change::Modification {
+        old: old.resource(),
+        new: new.resource(),
+    };
+    let actual = fixture.patch(change, &Default::default())?;
+    assert_eq!(actual.as_bstr(), fixture.expected("binary.patch")?.as_bstr());
+
+    let actual = fixture.patch(
+        change,
+        &Options {
+            binary: true,
+            ..Default::default()
+        },
+    )?;
+    let expected = fixture.expected("binary-binary.patch")?;
+    let header_len = expected.find("GIT binary patch\n").expect("binary patch") + "GIT binary patch\n".len();
+    assert_eq!(
+        actual[..header_len].as_bstr(),
+        expected[..header_len].as_bstr(),
+        "the compressed data may differ depending on the zlib implementation, but not the header"
+    );
+    assert!(
+        actual[header_len..].starts_with(b"delta "),
+        "a delta is used as it's smaller than the literal"
+    );
+
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    std::fs::write(tmp.path().join("binary"), &old.data)?;
+    std::fs::write(tmp.path().join("patch"), &actual)?;
+    assert!(gix_testtools::run_git(tmp.path(), &["apply", "patch"])?.success());
+    assert_eq!(std::fs::read(tmp.path().join("binary"))?.as_bstr(), new.data.as_bstr());
+    assert!(gix_testtools::run_git(tmp.path(), &["apply", "-R", "patch"])?.success());
+    assert_eq!(
+        std::fs::read(tmp.path().join("binary"))?.as_bstr(),
+        old.data.as_bstr(),
+        "the reverse section works as well"
+    );
+    Ok(())
+}
+
+#[test]
+fn binary_additions_and_deletions_can_be_applied_by_git() -> crate::Result {
+    let fixture = Fixture::new()?;
+    let binary = fixture.after("binary")?;
+    let options = Options {
+        binary: true,
+        ..Default::default()
+    };
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    std::fs::write(
+        tmp.path().join("patch"),
+        fixture.patch(Change::Addition { new: binary.resource() }, &options)?,
+    )?;
+    assert!(gix_testtools::run_git(tmp.path(), &["apply", "patch"])?.success());
+    assert_eq!(
+        std::fs::read(tmp.path().join("binary"))?.as_bstr(),
+        binary.data.as_bstr()
+    );
+
+    std::fs::write(
+        tmp.path().join("patch"),
+        fixture.patch(Change::Deletion { old: binary.resource() }, &options)?,
+    )?;
+    assert!(gix_testtools::run_git(tmp.path(), &["apply", "patch"])?.success());
+    assert!(!tmp.path().join("binary").exists());
+    Ok(())
+}
+
+struct Side {
+    path: &'static str,
+    id: ObjectId,
+    mode: EntryMode,
+    data: Vec<u8>,
+}
+
+impl Side {
+    fn resource(&self) -> Resource<'_> {
+        Resource {
+            path: self.path.into(),
+            id: &self.id,
+            mode: self.mode,
+            data: &self.data,
+        }
+    }
+}
+
+struct Fixture {
+    root: PathBuf,
+    db: gix_odb::Handle,
+    old_tree: ObjectId,
+    new_tree: ObjectId,
+}
+
+impl Fixture {
+    fn new() -> crate::Result<Self> {
+        let root = gix_testtools::scripted_fixture_read_only_standalone("make_unified_patches.sh")?;
+        let db = gix_odb::at(root.join(".git").join("objects"))?;
+        let trees = std::fs::read_to_string(root.join("trees"))?;
+        let mut trees = trees.lines().map(|hex| ObjectId::from_hex(hex.as_bytes()));
+        Ok(Fixture {
+            old_tree: trees.next().expect("old tree")?,
+            new_tree: trees.next().expect("new tree")?,
+            root,
+            db,
+        })
+    }
+
+    fn before(&self, path: &'static str) -> crate::Result<Side> {
+        self.side(self.old_tree, path)
+    }
+
+    fn after(&self, path: &'static str) -> crate::Result<Side> {
+        self.side(self.new_tree, path)
+    }
+
+    fn side(&self, tree: ObjectId, path: &'static str) -> crate::Result<Side> {
+        let mut buf = Vec::new();
+        let entry = self
+            .db
+            .find_tree(&tree, &mut buf)?
+            .entries
+            .into_iter()
+            .find(|entry| entry.filename == <&BStr>::from(path))
+            .map(|entry| (entry.oid.to_owned(), entry.mode))
+            .ok_or_else(|| format!("'{path}' not found in {tree}"))?;
+        let data = self.db.find_blob(&entry.0, &mut buf)?.data.to_vec();
+        Ok(Side {
+            path,
+            id: entry.0,
+            mode: entry.1,
+            data,
+        })
+    }
+
+    fn patch(&self, change: Change<'_>, options: &Options) -> crate::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        unified::write(&mut out, change, options)?;
+        Ok(out)
+    }
+
+    fn expected(&self, name: &str) -> std::io::Result<Vec<u8>> {
+        std::fs::read(self.root.join(name))
+    }
+}
@@ -116,6 +116,15 @@ fn copy_by_similarity_reports_limit_if_encountered() -> crate::Result {
         },
         "no similarity check was performed at all - all or nothing"
     );
+    assert_eq!(
+        out.degradation(),
+        Some(rewrites::Degradation {
+            renames: false,
+            copies: true,
+            required_limit: 4,
+        }),
+        "callers can learn that copies were only found by identity, and which limit would avoid that"
+    );
     Ok(())
 }
 
@@ -433,6 +442,15 @@ fn rename_by_similarity_reports_limit_if_encountered() -> crate::Result {
         },
         "no similarity check was performed at all - all or nothing"
     );
+    assert_eq!(
+        out.degradation(),
+        Some(rewrites::Degradation {
+            renames: true,
+            copies: false,
+            required_limit: 2,
+        }),
+        "callers can learn that renames were only found by identity, and which limit would avoid that"
+    );
     Ok(())
 }
 
@@ -498,6 +516,7 @@ fn rename_by_50_percent_similarity() -> crate::Result {
         "the first attempt already yields the one pair, so it doesn't participate anymore\
          - we don't have best candidates yet, thus only one check"
     );
+    assert_eq!(out.degradation(), None, "without a limit, nothing is ever skipped");
     Ok(())
 }
 
@@ -3,6 +3,7 @@ use crate::{entry, Entry};
 use crate::entry::PathspecMatch;
 use crate::walk::{Context, Error, ForDeletionMode, Options};
 use bstr::{BStr, BString, ByteSlice};
+use std::borrow::Cow;
 use std::ffi::OsStr;
 use std::path::{Component, Path, PathBuf};
 
@@ -189,8 +190,16 @@ pub fn path(
         path.push(gix_discover::DOT_GIT_DIR);
         let mut is_nested_nonbare_repo = gix_discover::is_git(path).is_ok();
         if is_nested_nonbare_repo {
-            let git_dir_is_our_own = gix_path::realpath_opts(path, ctx.current_dir, gix_path::realpath::MAX_SYMLINKS)
-                .ok()
+            // Linked worktrees have a `.git` file which points to their private git directory.
+            let git_dir = if path.is_file() {
+                gix_discover::path::from_gitdir_file(path).ok().map(Cow::Owned)
+            } else {
+                Some(Cow::Borrowed(path.as_path()))
+            };
+            let git_dir_is_our_own = git_dir
+                .and_then(|git_dir| {
+                    gix_path::realpath_opts(&git_dir, ctx.current_dir, gix_path::realpath::MAX_SYMLINKS).ok()
+                })
                 .map_or(false, |realpath_candidate| realpath_candidate == ctx.git_dir_realpath);
             is_nested_nonbare_repo = !git_dir_is_our_own;
         }
@@ -324,7 +333,7 @@ fn resolve_file_type_with_index(
                 rela_path.pop();
 
                 let mut one_index_signalling_with_cone = None;
-                let mut all_excluded_from_worktree_non_cone = false;
+                let mut all_excluded_from_worktree = false;
                 let is_tracked = res.is_some();
                 let kind = res
                     .filter(|range| {
@@ -335,8 +344,9 @@ fn resolve_file_type_with_index(
                         let any_up_to_date = entries
                             .iter()
                             .any(|e| e.flags.contains(gix_index::entry::Flags::UPTODATE));
-                        if !any_up_to_date && one_index_signalling_with_cone.is_none() {
-                            all_excluded_from_worktree_non_cone = entries
+                        if !any_up_to_date {
+                            // This also holds for directories with just a single file, which aren't sparse directories.
+                            all_excluded_from_worktree = entries
                                 .iter()
                                 .all(|e| e.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE));
                         }
@@ -344,7 +354,7 @@ fn resolve_file_type_with_index(
                     })
                     .map(|_| entry::Kind::Directory);
 
-                if all_excluded_from_worktree_non_cone
+                if all_excluded_from_worktree
                     || one_index_signalling_with_cone
                         .filter(|_| kind.is_none())
                         .map_or(false, |idx| index.entries()[idx].mode.is_sparse())
@@ -71,12 +71,16 @@ pub fn walk(
         return Ok(out);
     }
 
+    let cache_dir = ctx
+        .untracked_cache
+        .and_then(|cache| cache.root_directory(buf.as_bstr(), &options, &ctx));
     let mut state = readdir::State::default();
     let _ = readdir::recursive(
         root == worktree_root,
         &mut current,
         &mut buf,
         root_info,
+        cache_dir,
         &mut ctx,
         options,
         delegate,
@@ -201,6 +201,35 @@ pub struct Context<'a> {
     pub excludes: Option<&'a mut gix_worktree::Stack>,
     /// Access to the object database for use with `excludes` - it's possible to access `.gitignore` files in the index if configured.
     pub objects: &'a dyn gix_object::Find,
+    /// If set, the untracked cache of `index` is used to avoid reading directories that didn't change since it was written.
+    ///
+    /// It's only used if the [options](Options) are compatible with the way it was created, see [`UntrackedCache`] for details.
+    pub untracked_cache: Option<UntrackedCache<'a>>,
+}
+
+/// The untracked cache extension of an index along with what's needed to validate it.
+///
+/// The cache remembers which untracked files and directories were present in each directory, along with the directories
+/// stat information and the id of its `.gitignore` file. If both are still the same, the directory doesn't have to be read again,
+/// which is what `git` does as well.
+///
+/// It's only used if the walk is configured like `git status` would for listing untracked files, i.e. if
+/// neither ignored, tracked, pruned or empty directories are emitted, if the traversal isn't for deletion,
+/// if repositories aren't recursed into and if there is no pathspec.
+/// Further, the [flags](gix_index::extension::UntrackedCache::dir_flags()) that the cache was written with must match
+/// [`Options::emit_untracked`].
+///
+/// ### Important
+///
+/// The caller must assure the cache is valid as a whole, which is when its [identifier](gix_index::extension::UntrackedCache::identifier())
+/// matches the worktree, and when the global exclude files didn't change since it was written.
+/// Also, [`Context::excludes`] must be set, as only untracked files are stored in the cache.
+#[derive(Clone, Copy)]
+pub struct UntrackedCache<'a> {
+    /// The untracked cache extension of the [index](Context::index).
+    pub extension: &'a gix_index::extension::UntrackedCache,
+    /// Options to control how the stat information of directories is compared.
+    pub stat: gix_index::entry::stat::Options,
 }
 
 /// Additional information collected as outcome of [`walk()`](function::walk()).
@@ -249,3 +278,4 @@ pub enum Error {
 mod classify;
 pub(crate) mod function;
 mod readdir;
+mod untracked_cache;
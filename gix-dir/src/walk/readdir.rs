@@ -1,11 +1,12 @@
 use bstr::{BStr, BString, ByteSlice};
+use gix_index::extension::untracked_cache::Directory;
 use std::borrow::Cow;
 use std::path::PathBuf;
 
 use crate::entry::{PathspecMatch, Status};
 use crate::walk::function::{can_recurse, emit_entry};
 use crate::walk::EmissionMode::CollapseDirectory;
-use crate::walk::{classify, Action, Context, Delegate, Error, Options, Outcome};
+use crate::walk::{classify, Action, Context, Delegate, Error, Options, Outcome, UntrackedCache};
 use crate::{entry, walk, Entry};
 
 /// ### Deviation
@@ -17,12 +18,31 @@ pub(super) fn recursive(
     current: &mut PathBuf,
     current_bstr: &mut BString,
     current_info: classify::Outcome,
+    cache_dir: Option<usize>,
     ctx: &mut Context<'_>,
     opts: Options,
     delegate: &mut dyn Delegate,
     out: &mut Outcome,
     state: &mut State,
 ) -> Result<(Action, bool), Error> {
+    if let Some((cache, dir)) = cache_dir.and_then(|idx| {
+        let cache = ctx.untracked_cache?;
+        cache.valid_directory(idx, current, ctx.index).map(|dir| (cache, dir))
+    }) {
+        return cached(
+            is_worktree_dir,
+            current,
+            current_bstr,
+            current_info,
+            cache,
+            dir,
+            ctx,
+            opts,
+            delegate,
+            out,
+            state,
+        );
+    }
     out.read_dir_calls += 1;
     let entries = gix_fs::read_dir(current, opts.precompose_unicode).map_err(|err| Error::ReadDir {
         path: current.to_owned(),
@@ -46,11 +66,10 @@ pub(super) fn recursive(
             current_bstr.push(b'/');
         }
         let file_name = entry.file_name();
-        current_bstr.extend_from_slice(
-            gix_path::try_os_str_into_bstr(Cow::Borrowed(file_name.as_ref()))
-                .expect("no illformed UTF-8")
-                .as_ref(),
-        );
+        let file_name_bstr =
+            gix_path::try_os_str_into_bstr(Cow::Borrowed(file_name.as_ref())).expect("no illformed UTF-8");
+        let file_name_len = file_name_bstr.len();
+        current_bstr.extend_from_slice(file_name_bstr.as_ref());
         current.push(file_name);
 
         let info = classify::path(
@@ -64,8 +83,22 @@ pub(super) fn recursive(
         )?;
 
         if can_recurse(current_bstr.as_bstr(), info, opts.for_deletion, delegate) {
-            let (action, subdir_prevent_collapse) =
-                recursive(false, current, current_bstr, info, ctx, opts, delegate, out, state)?;
+            let subdir_cache_dir = cache_dir.and_then(|idx| {
+                ctx.untracked_cache?
+                    .sub_directory(idx, current_bstr[current_bstr.len() - file_name_len..].as_bstr())
+            });
+            let (action, subdir_prevent_collapse) = recursive(
+                false,
+                current,
+                current_bstr,
+                info,
+                subdir_cache_dir,
+                ctx,
+                opts,
+                delegate,
+                out,
+                state,
+            )?;
             prevent_collapse = subdir_prevent_collapse;
             if action != Action::Continue {
                 break;
@@ -94,6 +127,192 @@ pub(super) fn recursive(
     Ok((res, prevent_collapse))
 }
 
+/// Like [`recursive()`], but obtain the untracked entries of the directory from its still valid entry `dir` in the untracked `cache`,
+/// and learn about tracked entries from the index, instead of reading the directory.
+#[allow(clippy::too_many_arguments)]
+fn cached(
+    is_worktree_dir: bool,
+    current: &mut PathBuf,
+    current_bstr: &mut BString,
+    current_info: classify::Outcome,
+    cache: UntrackedCache<'_>,
+    dir: &Directory,
+    ctx: &mut Context<'_>,
+    opts: Options,
+    delegate: &mut dyn Delegate,
+    out: &mut Outcome,
+    state: &mut State,
+) -> Result<(Action, bool), Error> {
+    let children = cached_children(current_bstr.as_bstr(), cache, dir, ctx.index);
+    let num_entries = children.len();
+    let mark = state.mark(is_worktree_dir);
+    let mut prevent_collapse = false;
+    for child in children {
+        let prev_len = current_bstr.len();
+        if prev_len != 0 {
+            current_bstr.push(b'/');
+        }
+        current_bstr.extend_from_slice(child.name);
+        current.push(gix_path::from_bstr(child.name));
+
+        let disk_kind = match child.kind {
+            Some(kind) => Some(kind),
+            // Untracked files may also be symlinks, which the cache doesn't know.
+            None => Some(
+                current
+                    .symlink_metadata()
+                    .map_err(|err| Error::SymlinkMetadata {
+                        path: current.to_owned(),
+                        source: err,
+                    })?
+                    .file_type()
+                    .into(),
+            ),
+        };
+        let info = classify::path(
+            current,
+            current_bstr,
+            if prev_len == 0 { 0 } else { prev_len + 1 },
+            disk_kind,
+            || None,
+            opts,
+            ctx,
+        )?;
+
+        let is_collapsed_by_cache = child.is_untracked_directory
+            && opts.emit_untracked == CollapseDirectory
+            && info.status == Status::Untracked
+            && info.disk_kind == Some(entry::Kind::Directory);
+        if !is_collapsed_by_cache && can_recurse(current_bstr.as_bstr(), info, opts.for_deletion, delegate) {
+            let (action, subdir_prevent_collapse) = recursive(
+                false,
+                current,
+                current_bstr,
+                info,
+                child.cache_dir,
+                ctx,
+                opts,
+                delegate,
+                out,
+                state,
+            )?;
+            prevent_collapse = subdir_prevent_collapse;
+            if action != Action::Continue {
+                break;
+            }
+        } else if !state.held_for_directory_collapse(current_bstr.as_bstr(), info, &opts) {
+            let action = emit_entry(Cow::Borrowed(current_bstr.as_bstr()), info, None, opts, out, delegate);
+            if action != Action::Continue {
+                return Ok((action, prevent_collapse));
+            }
+        }
+        current_bstr.truncate(prev_len);
+        current.pop();
+    }
+
+    let res = mark.reduce_held_entries(
+        num_entries,
+        state,
+        &mut prevent_collapse,
+        current_bstr.as_bstr(),
+        current_info,
+        opts,
+        out,
+        ctx,
+        delegate,
+    );
+    Ok((res, prevent_collapse))
+}
+
+/// An entry of a directory as known to the untracked cache or the index.
+struct CachedChild<'a> {
+    name: &'a BStr,
+    /// The kind of the entry on disk, or `None` if it's not known.
+    kind: Option<entry::Kind>,
+    /// `true` if the cache lists this directory as untracked.
+    is_untracked_directory: bool,
+    /// The directory in the untracked cache for this entry, if there is one.
+    cache_dir: Option<usize>,
+}
+
+/// Produce all entries of the directory at `rela_path` that is represented by `dir` in `cache`, sorted by name.
+///
+/// Tracked entries are taken from `index`, as the cache only knows untracked files and directories that contain them.
+fn cached_children<'a>(
+    rela_path: &BStr,
+    cache: UntrackedCache<'a>,
+    dir: &'a Directory,
+    index: &'a gix_index::State,
+) -> Vec<CachedChild<'a>> {
+    let mut children = Vec::new();
+    let mut prefix = BString::from(rela_path);
+    if !prefix.is_empty() {
+        prefix.push(b'/');
+    }
+    for entry in index
+        .prefixed_entries(prefix.as_bstr())
+        .unwrap_or_default()
+        .iter()
+        .filter(|entry| !entry.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE))
+    {
+        let path = entry.path(index);
+        let relative = &path[prefix.len()..];
+        let (name, kind) = match relative.find_byte(b'/') {
+            Some(pos) => (relative[..pos].as_bstr(), Some(entry::Kind::Directory)),
+            None if entry.mode.is_submodule() => (relative.as_bstr(), Some(entry::Kind::Repository)),
+            None if entry.mode.contains(gix_index::entry::Mode::SYMLINK) => {
+                (relative.as_bstr(), Some(entry::Kind::Symlink))
+            }
+            None => (relative.as_bstr(), Some(entry::Kind::File)),
+        };
+        if children
+            .last()
+            .map_or(false, |prev: &CachedChild<'_>| prev.name == name)
+        {
+            continue;
+        }
+        children.push(CachedChild {
+            name,
+            kind,
+            is_untracked_directory: false,
+            cache_dir: None,
+        });
+    }
+    for name in &dir.untracked_entries {
+        let (name, kind) = match name.strip_suffix(b"/") {
+            Some(name) => (name.as_bstr(), Some(entry::Kind::Directory)),
+            None => (name.as_bstr(), None),
+        };
+        children.push(CachedChild {
+            name,
+            kind,
+            is_untracked_directory: kind.is_some(),
+            cache_dir: None,
+        });
+    }
+    let directories = cache.extension.directories();
+    for idx in &dir.sub_directories {
+        children.push(CachedChild {
+            name: directories[*idx].name.as_bstr(),
+            kind: Some(entry::Kind::Directory),
+            is_untracked_directory: false,
+            cache_dir: Some(*idx),
+        });
+    }
+
+    children.sort_by(|a, b| a.name.cmp(b.name));
+    children.dedup_by(|removed, kept| {
+        if removed.name != kept.name {
+            return false;
+        }
+        kept.kind = kept.kind.or(removed.kind);
+        kept.is_untracked_directory |= removed.is_untracked_directory;
+        kept.cache_dir = kept.cache_dir.or(removed.cache_dir);
+        true
+    });
+    children
+}
+
 #[derive(Default)]
 pub(super) struct State {
     /// The entries to hold back until it's clear what to do with them.
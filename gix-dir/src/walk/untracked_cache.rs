@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use bstr::{BStr, ByteSlice};
+use gix_index::extension::untracked_cache::{DirFlags, Directory};
+
+use crate::walk::{Context, EmissionMode, Options, UntrackedCache};
+
+impl<'a> UntrackedCache<'a> {
+    /// Return the index of the cached directory that corresponds to `rela_path`, the root of the walk,
+    /// if the cache can be used with `opts` and `ctx` at all.
+    pub(super) fn root_directory(&self, rela_path: &BStr, opts: &Options, ctx: &Context<'_>) -> Option<usize> {
+        let mut expected_flags = DirFlags::HIDE_EMPTY_DIRECTORIES;
+        if opts.emit_untracked == EmissionMode::CollapseDirectory {
+            expected_flags |= DirFlags::SHOW_OTHER_DIRECTORIES;
+        }
+        let is_usable = self.extension.dir_flags() == expected_flags
+            && opts.emit_ignored.is_none()
+            && !opts.emit_tracked
+            && !opts.emit_pruned
+            && !opts.emit_empty_directories
+            && opts.for_deletion.is_none()
+            && !opts.recurse_repositories
+            && ctx.excludes.is_some()
+            && ctx.pathspec.patterns().len() == 0;
+        if !is_usable || self.extension.directories().is_empty() {
+            return None;
+        }
+        rela_path
+            .split_str("/")
+            .filter(|component| !component.is_empty())
+            .try_fold(0, |dir, name| self.sub_directory(dir, name.as_bstr()))
+    }
+
+    /// Return the index of the directory named `name` within the cached directory at `parent`.
+    pub(super) fn sub_directory(&self, parent: usize, name: &BStr) -> Option<usize> {
+        let directories = self.extension.directories();
+        directories[parent]
+            .sub_directories
+            .iter()
+            .copied()
+            .find(|idx| directories[*idx].name == name)
+    }
+
+    /// Return the cached directory at `idx` if the directory at `path` still matches it, so its content can be
+    /// taken from the cache instead of reading it.
+    pub(super) fn valid_directory(&self, idx: usize, path: &Path, index: &gix_index::State) -> Option<&'a Directory> {
+        let dir = &self.extension.directories()[idx];
+        if dir.check_only {
+            return None;
+        }
+        let cached_stat = dir.stat.as_ref()?;
+        let stat = gix_index::fs::Metadata::from_path_no_follow(path)
+            .ok()
+            .and_then(|meta| gix_index::entry::Stat::from_fs(&meta).ok())?;
+        if !cached_stat.matches(&stat, self.stat) || cached_stat.is_racy(index.timestamp(), self.stat) {
+            return None;
+        }
+
+        let exclude_file = path.join(gix_path::from_bstr(self.extension.exclude_filename_per_dir()));
+        let exclude_file_oid = match std::fs::read(exclude_file) {
+            // Git appends a newline to non-empty files before hashing them.
+            Ok(mut data) => Some({
+                if !data.is_empty() {
+                    data.push(b'\n');
+                }
+                gix_object::compute_hash(index.object_hash(), gix_object::Kind::Blob, &data)
+            }),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(_) => return None,
+        };
+        (exclude_file_oid == dir.exclude_file_oid).then_some(dir)
+    }
+}
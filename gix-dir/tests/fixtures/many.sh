@@ -149,6 +149,20 @@ git init partial-checkout-non-cone
   mkdir d && touch d/file-created-manually
 )
 
+git init partial-checkout-cone-mode-single-file-directory
+(cd partial-checkout-cone-mode-single-file-directory
+  touch a
+  mkdir c d
+  touch c/a d/a
+
+  git add .
+  git commit -m "init"
+
+  git sparse-checkout set c
+
+  mkdir d && touch d/file-created-manually
+)
+
 git init only-untracked
 (cd only-untracked
   >a
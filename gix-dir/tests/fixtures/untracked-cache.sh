@@ -0,0 +1,19 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config core.untrackedCache true
+
+mkdir -p tracked/sub mixed
+touch tracked/file tracked/sub/file mixed/tracked
+git add .
+git commit -q -m "init"
+
+mkdir -p untracked-dir/sub
+touch untracked-file mixed/untracked untracked-dir/file untracked-dir/sub/file tracked/sub/untracked
+echo "ignored*" > .gitignore
+touch ignored-file mixed/ignored-file
+
+# Assure directories aren't racy in relation to the index, which would invalidate their cache entries.
+find . -path ./.git -prune -o -type d -exec touch -t 202001010000 {} +
+git status >/dev/null
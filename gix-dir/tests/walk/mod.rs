@@ -2448,7 +2448,11 @@ fn root_cannot_pass_through_case_altered_capital_dot_git_if_case_insensitive() {
 
 #[test]
 fn partial_checkout_cone_and_non_one() -> crate::Result {
-    for fixture_name in ["partial-checkout-cone-mode", "partial-checkout-non-cone"] {
+    for fixture_name in [
+        "partial-checkout-cone-mode",
+        "partial-checkout-non-cone",
+        "partial-checkout-cone-mode-single-file-directory",
+    ] {
         let root = fixture(fixture_name);
         let not_in_cone_but_created_locally_by_hand = "d/file-created-manually";
         let (out, entries) = collect(&root, |keep, ctx| {
@@ -2734,3 +2738,98 @@ fn type_mismatch_ignore_case_clash_file_is_dir() {
          If there was no special handling for this, it would have found the file (`d` in the index, icase), which would have been wrong."
     );
 }
+
+#[test]
+fn untracked_cache_avoids_reading_unchanged_directories() -> crate::Result {
+    // The cache stores the inode and ctime of each directory, which change if a generated fixture is copied or restored.
+    let tmp = gix_testtools::scripted_fixture_writable_with_args(
+        "untracked-cache.sh",
+        None::<String>,
+        gix_testtools::Creation::ExecuteScript,
+    )?;
+    let root = tmp.path().to_owned();
+    for (root_rela_path, expected_read_dir_calls) in [("", 6), ("mixed", 1), ("tracked", 2)] {
+        let walk_root = root.join(root_rela_path);
+        let collect_with = |untracked_cache: bool| {
+            try_collect_filtered_opts(
+                &root,
+                |keep, ctx| {
+                    walk(
+                        &walk_root,
+                        &root,
+                        ctx,
+                        walk::Options {
+                            emit_untracked: CollapseDirectory,
+                            ..options()
+                        },
+                        keep,
+                    )
+                },
+                None::<&str>,
+                Options {
+                    untracked_cache,
+                    ..Default::default()
+                },
+            )
+        };
+        let (uncached_out, uncached_entries) = collect_with(false)?;
+        let (out, entries) = collect_with(true)?;
+        assert_eq!(
+            entries, uncached_entries,
+            "{root_rela_path:?}: the cache must not change the result"
+        );
+        assert_eq!(
+            uncached_out.read_dir_calls, expected_read_dir_calls,
+            "{root_rela_path:?}: all directories are read without cache"
+        );
+        assert_eq!(
+            out.read_dir_calls, 0,
+            "{root_rela_path:?}: all directories are valid in the cache, and untracked directories are known to be untracked"
+        );
+        assert_eq!(out.returned_entries, uncached_out.returned_entries);
+        assert!(
+            out.seen_entries <= uncached_out.seen_entries,
+            "ignored files and the content of untracked directories aren't seen with the cache"
+        );
+    }
+
+    let (_out, entries) = collect(&root, |keep, ctx| {
+        walk(
+            &root,
+            &root,
+            ctx,
+            walk::Options {
+                emit_untracked: CollapseDirectory,
+                ..options()
+            },
+            keep,
+        )
+    });
+    assert_eq!(
+        entries,
+        [
+            entry(".gitignore", Untracked, File),
+            entry("mixed/untracked", Untracked, File),
+            entry("tracked/sub/untracked", Untracked, File),
+            entry("untracked-dir", Untracked, Directory),
+            entry("untracked-file", Untracked, File),
+        ],
+        "this is what the cache has to reproduce"
+    );
+
+    let (uncached_out, _) = collect(&root, |keep, ctx| walk(&root, &root, ctx, options(), keep));
+    let (out, _) = try_collect_filtered_opts(
+        &root,
+        |keep, ctx| walk(&root, &root, ctx, options(), keep),
+        None::<&str>,
+        Options {
+            untracked_cache: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        out, uncached_out,
+        "the cache was created for collapsed untracked directories, so it can't be used for listing all files"
+    );
+    Ok(())
+}
@@ -176,15 +176,28 @@ pub fn try_collect_filtered_opts(
     worktree_root: &Path,
     cb: impl FnOnce(&mut dyn walk::Delegate, walk::Context) -> Result<walk::Outcome, walk::Error>,
     patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
-    Options { fresh_index, git_dir }: Options<'_>,
+    Options {
+        fresh_index,
+        git_dir,
+        untracked_cache,
+    }: Options<'_>,
 ) -> Result<(walk::Outcome, Entries), walk::Error> {
     let git_dir = worktree_root.join(git_dir.unwrap_or(".git"));
-    let mut index = std::fs::read(git_dir.join("index")).ok().map_or_else(
+    let index_path = git_dir.join("index");
+    let mut index = std::fs::read(&index_path).ok().map_or_else(
         || gix_index::State::new(gix_index::hash::Kind::Sha1),
         |bytes| {
             gix_index::State::from_bytes(
                 &bytes,
-                std::time::UNIX_EPOCH.into(),
+                if untracked_cache {
+                    // The untracked cache needs the actual timestamp to detect racy directories.
+                    std::fs::metadata(&index_path)
+                        .and_then(|m| m.modified())
+                        .expect("index exists")
+                        .into()
+                } else {
+                    std::time::UNIX_EPOCH.into()
+                },
                 gix_index::hash::Kind::Sha1,
                 Default::default(),
             )
@@ -242,6 +255,10 @@ pub fn try_collect_filtered_opts(
             pathspec_attributes: &mut |_, _, _, _| panic!("we do not use pathspecs that require attributes access."),
             excludes: Some(&mut stack),
             objects: &gix_object::find::Never,
+            untracked_cache: untracked_cache.then(|| walk::UntrackedCache {
+                extension: index.untracked().expect("fixture has an untracked cache"),
+                stat: Default::default(),
+            }),
         },
     )?;
 
@@ -251,6 +268,7 @@ pub fn try_collect_filtered_opts(
 pub struct Options<'a> {
     pub fresh_index: bool,
     pub git_dir: Option<&'a str>,
+    pub untracked_cache: bool,
 }
 
 impl<'a> Options<'a> {
@@ -267,6 +285,7 @@ impl<'a> Default for Options<'a> {
         Options {
             fresh_index: true,
             git_dir: None,
+            untracked_cache: false,
         }
     }
 }
@@ -14,6 +14,7 @@ pub mod pipe {
         /// The channel through which bytes are transferred. Useful for sending [`std::io::Error`]s instead.
         pub channel: std::sync::mpsc::SyncSender<io::Result<BytesMut>>,
         buf: BytesMut,
+        max_chunk_size: Option<usize>,
     }
 
     /// The read-end of the pipe, implementing the [`std::io::Read`] trait.
@@ -62,6 +63,10 @@ pub mod pipe {
 
     impl io::Write for Writer {
         fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let buf = match self.max_chunk_size {
+                Some(max) => &buf[..buf.len().min(max)],
+                None => buf,
+            };
             self.buf.put_slice(buf);
             self.channel
                 .send(Ok(self.buf.split()))
@@ -79,11 +84,27 @@ pub mod pipe {
     /// * `in_flight_writes` defines the amount of chunks of bytes to keep in memory until the `write` end will block when writing.
     ///    If `0`, the `write` end will always block until the `read` end consumes the transferred bytes.
     pub fn unidirectional(in_flight_writes: usize) -> (Writer, Reader) {
+        new(in_flight_writes, None)
+    }
+
+    /// Like [`unidirectional()`], but each write transfers at most `max_chunk_size` bytes so that no more than
+    /// `(in_flight_writes + 1) * max_chunk_size` bytes are held in memory at any time, no matter how large the
+    /// buffers passed to `write` are.
+    ///
+    /// Writes larger than `max_chunk_size` are partial, so [`write_all()`][std::io::Write::write_all()] should be used to
+    /// write entire buffers. `max_chunk_size` must not be `0`.
+    pub fn unidirectional_bounded(in_flight_writes: usize, max_chunk_size: usize) -> (Writer, Reader) {
+        assert!(max_chunk_size > 0, "non-zero chunk size is needed");
+        new(in_flight_writes, Some(max_chunk_size))
+    }
+
+    fn new(in_flight_writes: usize, max_chunk_size: Option<usize>) -> (Writer, Reader) {
         let (tx, rx) = std::sync::mpsc::sync_channel(in_flight_writes);
         (
             Writer {
                 channel: tx,
-                buf: BytesMut::with_capacity(4096),
+                buf: BytesMut::with_capacity(max_chunk_size.map_or(4096, |max| max.min(4096))),
+                max_chunk_size,
             },
             Reader {
                 channel: rx,
@@ -46,11 +46,44 @@ pub fn build_thread() -> std::thread::Builder {
 /// * `consume(Item, &mut State) -> Output` produces an output given an input obtained by `input` along with mutable state initially
 ///   created by `new_thread_state(…)`.
 /// * For `reducer`, see the [`Reduce`] trait
+///
+/// This is [`in_parallel_bounded()`] with a queue capacity of one item per thread.
 pub fn in_parallel<I, S, O, R>(
     input: impl Iterator<Item = I> + Send,
     thread_limit: Option<usize>,
     new_thread_state: impl FnOnce(usize) -> S + Send + Clone,
     consume: impl FnMut(I, &mut S) -> O + Send + Clone,
+    reducer: R,
+) -> Result<<R as Reduce>::Output, <R as Reduce>::Error>
+where
+    R: Reduce<Input = O>,
+    I: Send,
+    O: Send,
+{
+    in_parallel_bounded(
+        input,
+        thread_limit,
+        num_threads(thread_limit),
+        new_thread_state,
+        consume,
+        reducer,
+    )
+}
+
+/// Like [`in_parallel()`], but with explicit control over the amount of items that may be queued between stages.
+///
+/// * `capacity` is the amount of inputs waiting to be consumed, and the amount of outputs waiting to be reduced,
+///   before the producing side blocks. If `0`, each item is handed over directly, so a slow `reducer` stalls
+///   all threads instead of letting outputs pile up in memory.
+///
+/// This keeps memory usage stable if outputs are large or the `reducer` is slower than `consume(…)`,
+/// at the cost of less slack to compensate for uneven processing times.
+pub fn in_parallel_bounded<I, S, O, R>(
+    input: impl Iterator<Item = I> + Send,
+    thread_limit: Option<usize>,
+    capacity: usize,
+    new_thread_state: impl FnOnce(usize) -> S + Send + Clone,
+    consume: impl FnMut(I, &mut S) -> O + Send + Clone,
     mut reducer: R,
 ) -> Result<<R as Reduce>::Output, <R as Reduce>::Error>
 where
@@ -61,8 +94,8 @@ where
     let num_threads = num_threads(thread_limit);
     std::thread::scope(move |s| {
         let receive_result = {
-            let (send_input, receive_input) = crossbeam_channel::bounded::<I>(num_threads);
-            let (send_result, receive_result) = crossbeam_channel::bounded::<O>(num_threads);
+            let (send_input, receive_input) = crossbeam_channel::bounded::<I>(capacity);
+            let (send_result, receive_result) = crossbeam_channel::bounded::<O>(capacity);
             for thread_id in 0..num_threads {
                 std::thread::Builder::new()
                     .name(format!("gitoxide.in_parallel.produce.{thread_id}"))
@@ -32,16 +32,26 @@
 //! In order to assure that threads don't outlive the data they borrow because their handles are leaked, we enforce
 //! the `'static` lifetime for its inputs, making it less intuitive to use. It is, however, possible to produce
 //! suitable input iterators as long as they can hold something on the heap.
+//!
+//! ### Backpressure
+//!
+//! Both [`in_parallel_bounded()`] and [`Stepwise::new_bounded()`][reduce::Stepwise::new_bounded()] allow to control how many
+//! inputs and outputs may be queued before producers block, so slow consumers don't cause memory to grow. Outputs that
+//! own buffers can be recycled with a [`ScratchPool`] to avoid allocating them anew for each item.
 #[cfg(feature = "parallel")]
 mod in_parallel;
 #[cfg(feature = "parallel")]
 pub use in_parallel::{
-    build_thread, in_parallel, in_parallel_with_finalize, in_parallel_with_slice, join, threads, Scope,
+    build_thread, in_parallel, in_parallel_bounded, in_parallel_with_finalize, in_parallel_with_slice, join, threads,
+    Scope,
 };
 
 mod serial;
 #[cfg(not(feature = "parallel"))]
-pub use serial::{build_thread, in_parallel, in_parallel_with_finalize, in_parallel_with_slice, join, threads, Scope};
+pub use serial::{
+    build_thread, in_parallel, in_parallel_bounded, in_parallel_with_finalize, in_parallel_with_slice, join, threads,
+    Scope,
+};
 
 mod in_order;
 pub use in_order::{InOrderIter, SequenceId};
@@ -49,6 +59,9 @@ pub use in_order::{InOrderIter, SequenceId};
 mod eager_iter;
 pub use eager_iter::{EagerIter, EagerIterIf};
 
+mod scratch;
+pub use scratch::ScratchPool;
+
 /// A no-op returning the input _(`desired_chunk_size`, `Some(thread_limit)`, `thread_limit)_ used
 /// when the `parallel` feature toggle is not set.
 #[cfg(not(feature = "parallel"))]
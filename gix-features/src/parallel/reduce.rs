@@ -42,6 +42,29 @@ mod stepped {
             consume: ConsumeFn,
             reducer: Reduce,
         ) -> Self
+        where
+            InputIter: Iterator<Item = I> + Send + 'static,
+            ThreadStateFn: Fn(usize) -> S + Send + Clone + 'static,
+            ConsumeFn: Fn(I, &mut S) -> O + Send + Clone + 'static,
+            Reduce: super::Reduce<Input = O> + 'static,
+            I: Send + 'static,
+            O: Send + 'static,
+        {
+            let capacity = num_threads(thread_limit);
+            Self::new_bounded(input, thread_limit, capacity, new_thread_state, consume, reducer)
+        }
+
+        /// Instantiate a new iterator and start working in threads, with at most `capacity` inputs and outputs
+        /// being queued before the producing side blocks.
+        /// For a description of parameters, see [`in_parallel_bounded()`][crate::parallel::in_parallel_bounded()].
+        pub fn new_bounded<InputIter, ThreadStateFn, ConsumeFn, I, O, S>(
+            input: InputIter,
+            thread_limit: Option<usize>,
+            capacity: usize,
+            new_thread_state: ThreadStateFn,
+            consume: ConsumeFn,
+            reducer: Reduce,
+        ) -> Self
         where
             InputIter: Iterator<Item = I> + Send + 'static,
             ThreadStateFn: Fn(usize) -> S + Send + Clone + 'static,
@@ -53,8 +76,8 @@ mod stepped {
             let num_threads = num_threads(thread_limit);
             let mut threads = Vec::with_capacity(num_threads + 1);
             let receive_result = {
-                let (send_input, receive_input) = crossbeam_channel::bounded::<I>(num_threads);
-                let (send_result, receive_result) = std::sync::mpsc::sync_channel::<O>(num_threads);
+                let (send_input, receive_input) = crossbeam_channel::bounded::<I>(capacity);
+                let (send_result, receive_result) = std::sync::mpsc::sync_channel::<O>(capacity);
                 for thread_id in 0..num_threads {
                     let handle = std::thread::spawn({
                         let send_result = send_result.clone();
@@ -162,6 +185,22 @@ mod stepped {
             }
         }
 
+        /// Instantiate a new iterator.
+        /// For a description of parameters, see [`in_parallel_bounded()`][crate::parallel::in_parallel_bounded()].
+        pub fn new_bounded<ThreadStateFn>(
+            input: InputIter,
+            thread_limit: Option<usize>,
+            _capacity: usize,
+            new_thread_state: ThreadStateFn,
+            consume: ConsumeFn,
+            reducer: Reduce,
+        ) -> Self
+        where
+            ThreadStateFn: Fn(usize) -> S,
+        {
+            Self::new(input, thread_limit, new_thread_state, consume, reducer)
+        }
+
         /// Consume the iterator by finishing its iteration and calling [`Reduce::finalize()`][crate::parallel::Reduce::finalize()].
         pub fn finalize(mut self) -> Result<Reduce::Output, Reduce::Error> {
             for value in self.by_ref() {
@@ -0,0 +1,65 @@
+use crate::threading::{lock, Mutable, OwnShared};
+
+/// A pool of reusable scratch buffers to be shared between the threads of [`in_parallel()`][crate::parallel::in_parallel()]
+/// and its reducer.
+///
+/// Workers [`take()`][ScratchPool::take()] a buffer to produce their output into, and the reducer [`put()`][ScratchPool::put()]s
+/// it back once its done with it. That way, the amount of allocations stays proportional to the amount of items in flight
+/// instead of the amount of items processed, which keeps memory usage stable when processing many large items.
+///
+/// Use [`ScratchPool::new()`] for instantiation, and clone it to share it.
+pub struct ScratchPool<T> {
+    items: OwnShared<Mutable<Vec<T>>>,
+    capacity: usize,
+}
+
+impl<T> Clone for ScratchPool<T> {
+    fn clone(&self) -> Self {
+        ScratchPool {
+            items: self.items.clone(),
+            capacity: self.capacity,
+        }
+    }
+}
+
+impl<T> ScratchPool<T> {
+    /// Create a new empty pool which keeps at most `capacity` items for reuse, dropping all others that are returned to it.
+    pub fn new(capacity: usize) -> Self {
+        ScratchPool {
+            items: OwnShared::new(Mutable::new(Vec::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Return a previously [returned][ScratchPool::put()] item, or `None` if there is none.
+    pub fn try_take(&self) -> Option<T> {
+        lock(&self.items).pop()
+    }
+
+    /// Return `item` to the pool for later reuse, or drop it if the pool is at capacity already.
+    ///
+    /// Note that `item` is returned as is, so it's up to the caller to clear it if needed.
+    pub fn put(&self, item: T) {
+        let mut items = lock(&self.items);
+        if items.len() < self.capacity {
+            items.push(item);
+        }
+    }
+
+    /// Return the amount of items that are currently available for reuse.
+    pub fn len(&self) -> usize {
+        lock(&self.items).len()
+    }
+
+    /// Return `true` if there is no item available for reuse.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Default> ScratchPool<T> {
+    /// Return a previously [returned][ScratchPool::put()] item, or a new default instance if there is none.
+    pub fn take(&self) -> T {
+        self.try_take().unwrap_or_default()
+    }
+}
@@ -142,6 +142,26 @@ where
     reducer.finalize()
 }
 
+/// Read items from `input` and `consume` them in a single thread, producing an output to be collected by a `reducer`.
+///
+/// `capacity` has no effect as no item is ever queued, but is present to keep the signature similar to the parallel version.
+///
+/// **This serial version performing all calculations on the current thread.**
+#[cfg(not(feature = "parallel"))]
+pub fn in_parallel_bounded<I, S, O, R>(
+    input: impl Iterator<Item = I>,
+    thread_limit: Option<usize>,
+    _capacity: usize,
+    new_thread_state: impl FnOnce(usize) -> S,
+    consume: impl FnMut(I, &mut S) -> O,
+    reducer: R,
+) -> Result<<R as Reduce>::Output, <R as Reduce>::Error>
+where
+    R: Reduce<Input = O>,
+{
+    in_parallel(input, thread_limit, new_thread_state, consume, reducer)
+}
+
 /// Read items from `input` and `consume` them in multiple threads,
 /// whose output output is collected by a `reducer`. Its task is to
 /// aggregate these outputs into the final result returned by this function with the benefit of not having to be thread-safe.
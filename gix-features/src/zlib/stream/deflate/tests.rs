@@ -98,4 +98,22 @@ mod deflate_stream {
 
         assert_deflate_buffer(w.inner, bytes)
     }
+
+    #[test]
+    fn compression_levels_can_be_chosen() -> Result<(), Box<dyn std::error::Error>> {
+        let bytes: Vec<u8> = (0..10_000u32).flat_map(|n| (n % 97).to_string().into_bytes()).collect();
+        let mut sizes = Vec::new();
+        for level in [Some(0), None, Some(9)] {
+            let mut w = deflate::Write::with_compression_level(Vec::new(), level);
+            w.write_all(&bytes)?;
+            w.flush()?;
+            sizes.push(w.inner.len());
+            assert_deflate_buffer(w.inner, &bytes)?;
+        }
+        assert!(
+            sizes[0] > sizes[1] && sizes[1] >= sizes[2],
+            "no compression is largest, the best compression is smallest: {sizes:?}"
+        );
+        Ok(())
+    }
 }
@@ -37,6 +37,51 @@ fn in_parallel() {
     assert_eq!(res, 100);
 }
 
+#[test]
+fn in_parallel_bounded_without_queue() {
+    let res = parallel::in_parallel_bounded(
+        std::iter::from_fn(|| Some(1)).take(100),
+        None,
+        0,
+        |_n| (),
+        |input, _state| input,
+        Adder::default(),
+    )
+    .expect("successful computation");
+    assert_eq!(res, 100, "items are handed over one by one, without any being lost");
+}
+
+#[test]
+fn stepwise_bounded_without_queue() {
+    let res = parallel::reduce::Stepwise::new_bounded(
+        std::iter::from_fn(|| Some(1)).take(100),
+        None,
+        0,
+        |_n| (),
+        |input, _state: &mut ()| input,
+        Adder::default(),
+    )
+    .finalize()
+    .expect("successful computation");
+    assert_eq!(res, 100);
+}
+
+#[test]
+fn scratch_pool_reuses_returned_items_up_to_capacity() {
+    let pool = parallel::ScratchPool::<Vec<u8>>::new(1);
+    assert!(pool.is_empty());
+    let mut buf = pool.take();
+    buf.reserve(100);
+    let capacity = buf.capacity();
+    pool.put(buf);
+    pool.put(Vec::new());
+    assert_eq!(pool.len(), 1, "items beyond capacity are dropped");
+
+    let buf = pool.clone().take();
+    assert_eq!(buf.capacity(), capacity, "the previously returned item is handed out");
+    assert!(pool.try_take().is_none(), "the pool is shared among clones");
+}
+
 #[test]
 fn in_parallel_with_mut_slice_in_chunks() {
     let num_items = 33;
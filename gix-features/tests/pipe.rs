@@ -114,4 +114,25 @@ mod io {
         }
         assert_eq!(block_count * BLOCK_SIZE, bytes_read);
     }
+
+    #[test]
+    fn bounded_writes_are_partial_but_write_all_transfers_everything() {
+        let (mut writer, mut reader) = io::pipe::unidirectional_bounded(1, 4);
+        assert_eq!(
+            writer.write(b"hello").expect("space for one chunk"),
+            4,
+            "at most one chunk is transferred per write"
+        );
+        let mut buf = [0; 4];
+        reader.read_exact(&mut buf).expect("success");
+        assert_eq!(&buf, b"hell");
+
+        let message = vec![42u8; 1000];
+        let expected = message.clone();
+        let handle = std::thread::spawn(move || writer.write_all(&message));
+        let mut received = Vec::new();
+        reader.read_to_end(&mut received).unwrap();
+        handle.join().expect("no panic").expect("write_all succeeds");
+        assert_eq!(received, expected);
+    }
 }
@@ -16,6 +16,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if needs_failure {
         panic!("failure requested for {sub_command}");
     }
+    let needs_timeout = next_arg.as_deref().map_or(false, |file| file.ends_with("hang"));
+    if needs_timeout {
+        std::thread::sleep(Duration::from_secs(10));
+    }
 
     match sub_command.as_str() {
         "process" => {
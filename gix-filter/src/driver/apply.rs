@@ -1,4 +1,11 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
 use bstr::{BStr, BString};
 
@@ -44,6 +51,18 @@ pub enum Error {
         status: driver::process::Status,
         command: String,
     },
+    #[error("Driver process {command:?} was killed as it didn't finish within {timeout:?}")]
+    Timeout {
+        command: std::process::Command,
+        timeout: Duration,
+    },
+    #[error("In-process driver '{name}' failed")]
+    InProcess {
+        name: BString,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("In-process driver '{name}' produced more than {limit} bytes of output")]
+    OutputLimitExceeded { name: BString, limit: u64 },
 }
 
 /// Additional information for use in the [`State::apply()`] method.
@@ -75,6 +94,9 @@ impl State {
     /// Note that it's not an error if there is no filter process for `operation` or if a long-running process doesn't supported
     /// the desired capability.
     ///
+    /// If an [in-process driver](Self::register_in_process_driver()) is registered for `driver`, it's used instead of any program,
+    /// and the [limits](Self::limits) configured for `driver` are applied.
+    ///
     /// ### Deviation
     ///
     /// If a long running process returns the 'abort' status after receiving the data, it will be removed similar to how `git` does it.
@@ -109,12 +131,60 @@ impl State {
         delay: Delay,
         ctx: Context<'_, '_>,
     ) -> Result<Option<MaybeDelayed<'a>>, Error> {
+        let limits = self.limits.get(&driver.name).copied().unwrap_or_default();
+        if let Some(in_process) = self.in_process.get(&driver.name) {
+            self.buf.clear();
+            let mut out = LimitedWrite {
+                buf: &mut self.buf,
+                limit: limits.max_output_size,
+                exceeded: false,
+            };
+            let res = in_process(operation, ctx, src, &mut out);
+            if let Some(limit) = limits.max_output_size.filter(|_| out.exceeded) {
+                return Err(Error::OutputLimitExceeded {
+                    name: driver.name.clone(),
+                    limit,
+                });
+            }
+            let applied = res.map_err(|err| Error::InProcess {
+                name: driver.name.clone(),
+                source: err,
+            })?;
+            if !applied {
+                return Ok(None);
+            }
+            return Ok(Some(MaybeDelayed::Immediate(Box::new(self.buf.as_slice()))));
+        }
+
         match self.maybe_launch_process(driver, operation, ctx.rela_path)? {
             Some(Process::SingleFile { mut child, command }) => {
-                std::io::copy(src, &mut child.stdin.take().expect("configured"))?;
+                let mut stdin = child.stdin.take().expect("configured");
+                let stdout = child.stdout.take();
+                let child = Arc::new(Mutex::new(child));
+                let watchdog = limits.timeout.map(|timeout| Watchdog::spawn(child.clone(), timeout));
+                if let Err(err) = std::io::copy(src, &mut stdin) {
+                    return Err(match watchdog {
+                        Some(watchdog) if watchdog.timed_out() => Error::Timeout {
+                            command,
+                            timeout: watchdog.timeout,
+                        },
+                        _ => err.into(),
+                    });
+                }
+                drop(stdin);
+                let stdout = stdout.map(|stdout| match &watchdog {
+                    Some(watchdog) => Stdout::WithDeadline(DeadlineRead::spawn(stdout, watchdog.deadline)),
+                    None => Stdout::Direct(stdout),
+                });
                 Ok(Some(MaybeDelayed::Immediate(Box::new(ReadFilterOutput {
-                    inner: child.stdout.take(),
-                    child: driver.required.then_some((child, command)),
+                    inner: stdout,
+                    wait_for_exit: driver.required || watchdog.is_some(),
+                    child,
+                    command,
+                    required: driver.required,
+                    watchdog,
+                    max_output_size: limits.max_output_size,
+                    num_read: 0,
                 }))))
             }
             Some(Process::MultiFile { client, key }) => {
@@ -165,7 +235,15 @@ impl State {
                     self.running.insert(key.0.clone(), client);
                     let client = self.running.get_mut(&key.0).expect("just inserted");
 
-                    Ok(Some(MaybeDelayed::Immediate(Box::new(client.as_read()))))
+                    let read = client.as_read();
+                    Ok(Some(MaybeDelayed::Immediate(match limits.max_output_size {
+                        Some(limit) => Box::new(LimitedProcessOutput {
+                            inner: Some(read),
+                            limit,
+                            num_read: 0,
+                        }),
+                        None => Box::new(read),
+                    })))
                 } else {
                     let message = status.message().unwrap_or_default();
                     match message {
@@ -204,9 +282,161 @@ pub enum MaybeDelayed<'a> {
 
 /// A utility type to facilitate streaming the output of a filter process.
 struct ReadFilterOutput {
-    inner: Option<std::process::ChildStdout>,
-    /// The child is present if we need its exit code to be positive.
-    child: Option<(std::process::Child, std::process::Command)>,
+    inner: Option<Stdout>,
+    /// The child to kill if it exceeds its limits, and to wait for if `wait_for_exit` is set.
+    child: Arc<Mutex<std::process::Child>>,
+    command: std::process::Command,
+    /// If `true`, we wait for the child to exit once its output is exhausted.
+    wait_for_exit: bool,
+    /// If `true`, the exit code of the child needs to be positive.
+    required: bool,
+    watchdog: Option<Watchdog>,
+    max_output_size: Option<u64>,
+    num_read: u64,
+}
+
+/// The output buffer of in-process drivers, which refuses to grow beyond `limit`.
+struct LimitedWrite<'a> {
+    buf: &'a mut Vec<u8>,
+    limit: Option<u64>,
+    /// Set once a write was refused, so the limit is detected even if the driver ignores the error.
+    exceeded: bool,
+}
+
+impl std::io::Write for LimitedWrite<'_> {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        if let Some(limit) = self.limit.filter(|limit| (self.buf.len() + data.len()) as u64 > *limit) {
+            self.exceeded = true;
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("In-process driver produced more than {limit} bytes of output"),
+            ));
+        }
+        self.buf.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The output of a single-file filter process.
+enum Stdout {
+    Direct(std::process::ChildStdout),
+    /// Used if the process has a timeout, as killing it doesn't necessarily close its output, which may be held by
+    /// processes it spawned in turn.
+    WithDeadline(DeadlineRead),
+}
+
+/// A reader that fails with [`TimedOut`](std::io::ErrorKind::TimedOut) if the data isn't available before `deadline`.
+struct DeadlineRead {
+    chunks: mpsc::Receiver<std::io::Result<Vec<u8>>>,
+    chunk: Vec<u8>,
+    pos: usize,
+    deadline: Instant,
+}
+
+impl DeadlineRead {
+    /// Read `inner` in a thread and make its data available until `deadline`.
+    fn spawn(mut inner: impl std::io::Read + Send + 'static, deadline: Instant) -> Self {
+        let (send, chunks) = mpsc::sync_channel(1);
+        std::thread::spawn(move || loop {
+            let mut buf = vec![0; 64 * 1024];
+            let res = match inner.read(&mut buf) {
+                Ok(0) => break,
+                Ok(num_read) => {
+                    buf.truncate(num_read);
+                    Ok(buf)
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => Err(err),
+            };
+            let is_err = res.is_err();
+            if send.send(res).is_err() || is_err {
+                break;
+            }
+        });
+        DeadlineRead {
+            chunks,
+            chunk: Vec::new(),
+            pos: 0,
+            deadline,
+        }
+    }
+}
+
+impl std::io::Read for DeadlineRead {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.pos == self.chunk.len() {
+            match self
+                .chunks
+                .recv_timeout(self.deadline.saturating_duration_since(Instant::now()))
+            {
+                Ok(chunk) => {
+                    self.chunk = chunk?;
+                    self.pos = 0;
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(0),
+                Err(mpsc::RecvTimeoutError::Timeout) => return Err(std::io::ErrorKind::TimedOut.into()),
+            }
+        }
+        let num_read = buf.len().min(self.chunk.len() - self.pos);
+        buf[..num_read].copy_from_slice(&self.chunk[self.pos..][..num_read]);
+        self.pos += num_read;
+        Ok(num_read)
+    }
+}
+
+impl std::io::Read for Stdout {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Stdout::Direct(read) => read.read(buf),
+            Stdout::WithDeadline(read) => read.read(buf),
+        }
+    }
+}
+
+/// A thread that kills a child process once it ran for longer than `timeout`, unless it's dropped before.
+struct Watchdog {
+    timeout: Duration,
+    deadline: Instant,
+    timed_out: Arc<AtomicBool>,
+    /// Dropping this stops the thread.
+    _stop: mpsc::Sender<()>,
+}
+
+impl Watchdog {
+    fn spawn(child: Arc<Mutex<std::process::Child>>, timeout: Duration) -> Self {
+        let timed_out = Arc::new(AtomicBool::default());
+        let (stop, stopped) = mpsc::channel::<()>();
+        std::thread::spawn({
+            let timed_out = timed_out.clone();
+            move || {
+                if let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(timeout) {
+                    timed_out.store(true, Ordering::SeqCst);
+                    child.lock().expect("not poisoned").kill().ok();
+                }
+            }
+        });
+        Watchdog {
+            timeout,
+            deadline: Instant::now() + timeout,
+            timed_out,
+            _stop: stop,
+        }
+    }
+
+    fn timed_out(&self) -> bool {
+        self.timed_out.load(Ordering::SeqCst)
+    }
+}
+
+/// A utility to fail reading the output of a long-running process once it exceeds `limit`.
+struct LimitedProcessOutput<R> {
+    inner: Option<R>,
+    limit: u64,
+    num_read: u64,
 }
 
 pub(crate) fn handle_io_err(err: &std::io::Error, running: &mut HashMap<BString, process::Client>, process: &BStr) {
@@ -218,22 +448,105 @@ pub(crate) fn handle_io_err(err: &std::io::Error, running: &mut HashMap<BString,
     }
 }
 
+impl ReadFilterOutput {
+    fn wait_for_exit(&mut self) -> std::io::Result<()> {
+        let status = match &self.watchdog {
+            // Don't block the watchdog from killing the child while we are waiting for it.
+            Some(_) => loop {
+                if let Some(status) = self.child.lock().expect("not poisoned").try_wait()? {
+                    break status;
+                }
+                std::thread::sleep(Duration::from_millis(5));
+            },
+            None => self.child.lock().expect("not poisoned").wait()?,
+        };
+        if status.success() {
+            return Ok(());
+        }
+        if self.watchdog.as_ref().map_or(false, Watchdog::timed_out) {
+            Err(self.timeout_error())
+        } else if self.required {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("Driver process {:?} failed", self.command),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn timeout_error(&self) -> std::io::Error {
+        std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            format!(
+                "Driver process {:?} was killed as it didn't finish within {:?}",
+                self.command,
+                self.watchdog
+                    .as_ref()
+                    .map(|watchdog| watchdog.timeout)
+                    .unwrap_or_default()
+            ),
+        )
+    }
+}
+
 impl std::io::Read for ReadFilterOutput {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         match self.inner.as_mut() {
             Some(inner) => {
-                let num_read = inner.read(buf)?;
+                let num_read = match inner.read(buf) {
+                    Ok(num_read) => num_read,
+                    Err(err) if err.kind() == std::io::ErrorKind::TimedOut => {
+                        self.inner.take();
+                        self.child.lock().expect("not poisoned").kill().ok();
+                        return Err(self.timeout_error());
+                    }
+                    Err(err) => return Err(err),
+                };
                 if num_read == 0 {
                     self.inner.take();
-                    if let Some((mut child, cmd)) = self.child.take() {
-                        let status = child.wait()?;
-                        if !status.success() {
-                            return Err(std::io::Error::new(
-                                std::io::ErrorKind::Other,
-                                format!("Driver process {cmd:?} failed"),
-                            ));
-                        }
+                    if self.wait_for_exit {
+                        self.wait_for_exit()?;
                     }
+                    self.watchdog.take();
+                    return Ok(0);
+                }
+                self.num_read += num_read as u64;
+                if let Some(limit) = self.max_output_size.filter(|limit| self.num_read > *limit) {
+                    self.inner.take();
+                    self.watchdog.take();
+                    let mut child = self.child.lock().expect("not poisoned");
+                    child.kill().ok();
+                    child.wait()?;
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::Other,
+                        format!(
+                            "Driver process {:?} produced more than {limit} bytes of output",
+                            self.command
+                        ),
+                    ));
+                }
+                Ok(num_read)
+            }
+            None => Ok(0),
+        }
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for LimitedProcessOutput<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.inner.as_mut() {
+            Some(inner) => {
+                let num_read = inner.read(buf)?;
+                self.num_read += num_read as u64;
+                if self.num_read > self.limit {
+                    // Discard the remaining output to keep the process usable for the next entry.
+                    std::io::copy(inner, &mut std::io::sink())?;
+                    self.inner.take();
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::Other,
+                        format!("Driver process produced more than {} bytes of output", self.limit),
+                    ));
                 }
                 Ok(num_read)
             }
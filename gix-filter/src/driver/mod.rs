@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use bstr::{BStr, BString, ByteSlice, ByteVec};
 
@@ -54,6 +54,35 @@ impl Operation {
     }
 }
 
+/// Limits to apply when invoking a driver, configured per driver in [`State::limits`].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Limits {
+    /// The time after which a `clean` or `smudge` program is killed if it didn't finish yet, or `None` to wait for it indefinitely.
+    ///
+    /// Note that long-running `process` filters are shared by all invocations and thus aren't subject to this timeout.
+    pub timeout: Option<Duration>,
+    /// The maximum amount of bytes the driver may produce for a single entry, or `None` if there is no limit.
+    ///
+    /// Reading from the driver fails once the limit is exceeded.
+    pub max_output_size: Option<u64>,
+}
+
+/// A driver implemented in Rust that is called in place of programs to spawn, see [`State::register_in_process_driver()`].
+///
+/// It's called with the `operation` to perform along with the context of the entry, and is expected to read the entire input
+/// from the given reader and write the result into the given writer, which fails once the
+/// [output limit](Limits::max_output_size) would be exceeded.
+/// If it returns `Ok(false)`, the driver doesn't implement the operation and nothing is applied. In that case the input
+/// should not be consumed.
+pub type InProcessDriver = dyn Fn(
+        Operation,
+        apply::Context<'_, '_>,
+        &mut dyn std::io::Read,
+        &mut dyn std::io::Write,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>>
+    + Send
+    + Sync;
+
 /// State required to handle `process` filters, which are running until all their work is done.
 ///
 /// These can be significantly faster on some platforms as they are launched only once, while supporting asynchronous processing.
@@ -73,6 +102,16 @@ pub struct State {
 
     /// The context to pass to spawned filter programs.
     pub context: gix_command::Context,
+
+    /// The limits to apply to drivers, keyed by [driver name](crate::Driver::name).
+    ///
+    /// Drivers without an entry can run indefinitely and produce as much output as they want.
+    pub limits: HashMap<BString, Limits>,
+
+    /// Drivers implemented in-process, keyed by driver name. These are preferred over any program.
+    in_process: HashMap<BString, Arc<InProcessDriver>>,
+    /// A buffer to hold the output of in-process drivers.
+    buf: Vec<u8>,
 }
 
 /// Initialization
@@ -82,15 +121,51 @@ impl State {
         Self {
             running: Default::default(),
             context,
+            limits: Default::default(),
+            in_process: Default::default(),
+            buf: Vec::new(),
         }
     }
 }
 
+/// In-process drivers
+impl State {
+    /// Register `driver` to be used whenever the driver named `name` is applied, instead of spawning any of its programs,
+    /// and return the previously registered driver of the same name, if there was one.
+    ///
+    /// This allows to implement `clean` and `smudge` operations in Rust without spawning processes at all.
+    /// Note that the [`Driver`](crate::Driver) itself still has to be configured for the driver to be selected by attributes,
+    /// even if it has no programs set.
+    pub fn register_in_process_driver(
+        &mut self,
+        name: impl Into<BString>,
+        driver: impl Fn(
+                Operation,
+                apply::Context<'_, '_>,
+                &mut dyn std::io::Read,
+                &mut dyn std::io::Write,
+            ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Option<Arc<InProcessDriver>> {
+        self.in_process.insert(name.into(), Arc::new(driver))
+    }
+
+    /// Remove the in-process driver named `name` and return it, if it was registered.
+    pub fn unregister_in_process_driver(&mut self, name: &BStr) -> Option<Arc<InProcessDriver>> {
+        self.in_process.remove(name)
+    }
+}
+
 impl Clone for State {
     fn clone(&self) -> Self {
         State {
             running: Default::default(),
             context: self.context.clone(),
+            limits: self.limits.clone(),
+            in_process: self.in_process.clone(),
+            buf: Vec::new(),
         }
     }
 }
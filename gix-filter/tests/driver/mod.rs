@@ -58,7 +58,10 @@ mod shutdown {
 }
 
 pub(crate) mod apply {
-    use std::io::Read;
+    use std::{
+        io::Read,
+        time::{Duration, Instant},
+    };
 
     use bstr::ByteSlice;
     use gix_filter::{
@@ -303,6 +306,166 @@ pub(crate) mod apply {
         Ok(())
     }
 
+    #[serial]
+    #[test]
+    fn single_file_drivers_are_killed_after_timeout() -> crate::Result {
+        let mut state = gix_filter::driver::State::default();
+        let driver = driver_no_process();
+        let timeout = Duration::from_millis(500);
+        state.limits.insert(
+            driver.name.clone(),
+            driver::Limits {
+                timeout: Some(timeout),
+                max_output_size: None,
+            },
+        );
+
+        let start = Instant::now();
+        let mut filtered = state
+            .apply(
+                &driver,
+                &mut &b"hello\n"[..],
+                Operation::Smudge,
+                context_from_path("will/hang"),
+            )?
+            .expect("filter present");
+        let err = filtered.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut, "{err}");
+        assert!(start.elapsed() >= timeout);
+        drop(filtered);
+
+        let mut filtered = state
+            .apply(
+                &driver,
+                &mut &b"hello\n"[..],
+                Operation::Smudge,
+                context_from_path("fast"),
+            )?
+            .expect("filter present");
+        let mut buf = Vec::new();
+        filtered.read_to_end(&mut buf)?;
+        assert_eq!(buf.as_bstr(), "➡hello\n", "drivers finishing in time aren't affected");
+        Ok(())
+    }
+
+    #[serial]
+    #[test]
+    fn output_size_can_be_limited() -> crate::Result {
+        let mut state = gix_filter::driver::State::default();
+        let input = "hello\nthere\n";
+        for driver in [driver_no_process(), driver_with_process()] {
+            state.limits.insert(
+                driver.name.clone(),
+                driver::Limits {
+                    timeout: None,
+                    max_output_size: Some(input.len() as u64),
+                },
+            );
+            let mut filtered = state
+                .apply(
+                    &driver,
+                    &mut input.as_bytes(),
+                    Operation::Smudge,
+                    context_from_path("some/path.txt"),
+                )?
+                .expect("filter present");
+            let err = filtered.read_to_end(&mut Vec::new()).unwrap_err();
+            assert!(err.to_string().contains("more than 12 bytes of output"), "{err}");
+            drop(filtered);
+
+            let mut filtered = state
+                .apply(
+                    &driver,
+                    &mut "➡hello\n➡there\n".as_bytes(),
+                    Operation::Clean,
+                    context_from_path("some/path.txt"),
+                )?
+                .expect("filter present");
+            let mut buf = Vec::new();
+            filtered.read_to_end(&mut buf)?;
+            assert_eq!(
+                buf.as_bstr(),
+                input,
+                "output within the limit is fine, and processes remain usable"
+            );
+        }
+        state.shutdown(gix_filter::driver::shutdown::Mode::WaitForProcesses)?;
+        Ok(())
+    }
+
+    #[test]
+    fn in_process_drivers_are_preferred_over_programs() -> crate::Result {
+        let mut state = gix_filter::driver::State::default();
+        let driver = driver_with_process();
+        state.register_in_process_driver(driver.name.clone(), |operation, ctx, src, out| {
+            if ctx.rela_path == "fail" {
+                return Err("failure requested".into());
+            }
+            match operation {
+                Operation::Clean => {
+                    let mut buf = Vec::new();
+                    src.read_to_end(&mut buf)?;
+                    if ctx.rela_path == "endless" {
+                        std::io::copy(&mut std::io::repeat(b'x'), out)?;
+                    }
+                    buf.make_ascii_lowercase();
+                    out.write_all(&buf)?;
+                    Ok(true)
+                }
+                Operation::Smudge => Ok(false),
+            }
+        });
+
+        let mut filtered = state
+            .apply(&driver, &mut &b"HELLO"[..], Operation::Clean, context_from_path("any"))?
+            .expect("filter present");
+        let mut buf = Vec::new();
+        filtered.read_to_end(&mut buf)?;
+        drop(filtered);
+        assert_eq!(buf.as_bstr(), "hello");
+
+        assert!(
+            state
+                .apply(&driver, &mut &b"HELLO"[..], Operation::Smudge, context_from_path("any"))?
+                .is_none(),
+            "in-process drivers may decline an operation, which then isn't applied"
+        );
+
+        let err = match state.apply(&driver, &mut &b""[..], Operation::Clean, context_from_path("fail")) {
+            Ok(_) => panic!("failure was requested"),
+            Err(err) => err,
+        };
+        assert!(matches!(err, apply::Error::InProcess { .. }), "{err:?}");
+
+        state.limits.insert(
+            driver.name.clone(),
+            driver::Limits {
+                timeout: None,
+                max_output_size: Some(3),
+            },
+        );
+        let err = match state.apply(&driver, &mut &b"HELLO"[..], Operation::Clean, context_from_path("any")) {
+            Ok(_) => panic!("the output is too large"),
+            Err(err) => err,
+        };
+        assert!(
+            matches!(err, apply::Error::OutputLimitExceeded { limit: 3, .. }),
+            "{err:?}"
+        );
+
+        let err = match state.apply(&driver, &mut &b""[..], Operation::Clean, context_from_path("endless")) {
+            Ok(_) => panic!("the output is too large"),
+            Err(err) => err,
+        };
+        assert!(
+            matches!(err, apply::Error::OutputLimitExceeded { limit: 3, .. }),
+            "writing stops once the limit is reached, so unbounded output doesn't exhaust memory: {err:?}"
+        );
+
+        assert!(state.unregister_in_process_driver(driver.name.as_ref()).is_some());
+        Ok(())
+    }
+
     #[test]
     fn smudge_and_clean_delayed() -> crate::Result {
         let mut state = gix_filter::driver::State::default();
@@ -1,5 +1,9 @@
 // TODO: tests
-use std::path::Path;
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, Mutex},
+};
 
 use crate::Capabilities;
 
@@ -120,3 +124,73 @@ impl Capabilities {
         res
     }
 }
+
+/// A cache to remember the [probed capabilities](Capabilities::probe()) per filesystem, so that probing happens only
+/// once for each device or volume even if many repositories are created on it.
+///
+/// It's cheap to clone and can be shared across threads.
+/// Entries can be [invalidated](Cache::invalidate()) if a filesystem is known to have changed, like when a different
+/// filesystem was mounted at the same location.
+#[derive(Debug, Clone, Default)]
+pub struct Cache {
+    /// The probed capabilities by the id of the filesystem they were probed on.
+    by_filesystem: Arc<Mutex<HashMap<FilesystemId, Capabilities>>>,
+}
+
+impl Cache {
+    /// Return the capabilities of the filesystem `git_dir` is located on, [probing](Capabilities::probe()) them in
+    /// `git_dir` only if they aren't known yet.
+    ///
+    /// If the filesystem can't be identified, the capabilities are probed each time.
+    pub fn probe(&self, git_dir: &Path) -> Capabilities {
+        let Some(id) = filesystem_id(git_dir) else {
+            return Capabilities::probe(git_dir);
+        };
+        if let Some(caps) = self.by_filesystem.lock().expect("no panic while holding lock").get(&id) {
+            return *caps;
+        }
+        let caps = Capabilities::probe(git_dir);
+        self.by_filesystem
+            .lock()
+            .expect("no panic while holding lock")
+            .insert(id, caps);
+        caps
+    }
+
+    /// Forget the capabilities of the filesystem `dir` is located on, so they are probed again next time.
+    pub fn invalidate(&self, dir: &Path) {
+        if let Some(id) = filesystem_id(dir) {
+            self.by_filesystem
+                .lock()
+                .expect("no panic while holding lock")
+                .remove(&id);
+        }
+    }
+
+    /// Forget the capabilities of all filesystems.
+    pub fn clear(&self) {
+        self.by_filesystem.lock().expect("no panic while holding lock").clear();
+    }
+}
+
+#[cfg(unix)]
+type FilesystemId = u64;
+
+#[cfg(not(unix))]
+type FilesystemId = std::path::PathBuf;
+
+#[cfg(unix)]
+fn filesystem_id(path: &Path) -> Option<FilesystemId> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).ok().map(|m| m.dev())
+}
+
+/// Use the volume of the real path, like `\\?\C:`, as we can't obtain device ids with the standard library.
+#[cfg(not(unix))]
+fn filesystem_id(path: &Path) -> Option<FilesystemId> {
+    let path = std::fs::canonicalize(path).ok()?;
+    match path.components().next()? {
+        std::path::Component::Prefix(prefix) => Some(prefix.as_os_str().into()),
+        _ => None,
+    }
+}
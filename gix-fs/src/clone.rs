@@ -0,0 +1,123 @@
+//! Copy file contents by sharing the underlying storage if the filesystem supports it.
+//!
+//! On Linux, `FICLONE` is tried first, followed by `copy_file_range()` which is used by the standard library
+//! and may share extents as well. On MacOS, `clonefile()` is used by the standard library when copying to a
+//! destination that doesn't exist yet. Everywhere else, bytes are copied.
+use std::{
+    collections::HashSet,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+/// Describes how the content of a file ended up at its destination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// The destination shares all data with the source, as done by `FICLONE`.
+    Reflink,
+    /// The content was copied, which may still share data if the platform's copy routine supports it.
+    Copy,
+}
+
+/// A cache to remember per filesystem whether cloning file data is supported, to avoid retrying a failing
+/// operation for each file.
+///
+/// It's cheap to clone and can be shared across threads.
+#[derive(Debug, Clone, Default)]
+pub struct Probe {
+    /// The device ids of filesystems on which cloning failed as it's unsupported.
+    unsupported: Arc<Mutex<HashSet<u64>>>,
+}
+
+impl Probe {
+    /// Return `true` if the filesystem of `dir` is not yet known to be incapable of cloning.
+    pub fn may_reflink(&self, dir: &Path) -> bool {
+        match device_id(dir) {
+            Some(id) => !self
+                .unsupported
+                .lock()
+                .expect("no panic while holding lock")
+                .contains(&id),
+            None => false,
+        }
+    }
+
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn mark_unsupported(&self, dir: &Path) {
+        if let Some(id) = device_id(dir) {
+            self.unsupported.lock().expect("no panic while holding lock").insert(id);
+        }
+    }
+}
+
+/// Create a new file at `destination` which must not exist yet, with all content of the file at `source`,
+/// trying to share the underlying storage if `probe` indicates this could work on the filesystem of `destination`.
+///
+/// Return the file opened for writing along with the amount of bytes in it and how its data was transferred.
+/// Note that symbolic links are not followed when opening `destination`.
+pub fn file(source: &Path, destination: &Path, probe: &Probe) -> std::io::Result<(std::fs::File, u64, Method)> {
+    #[cfg(target_os = "linux")]
+    {
+        let mut src = std::fs::File::open(source)?;
+        let mut dst = create_new(destination)?;
+        let dir = destination.parent().unwrap_or(destination);
+        if probe.may_reflink(dir) {
+            match rustix::fs::ioctl_ficlone(&dst, &src) {
+                Ok(()) => {
+                    let len = dst.metadata()?.len();
+                    return Ok((dst, len, Method::Reflink));
+                }
+                Err(err) if is_unsupported(err) => probe.mark_unsupported(dir),
+                Err(err) => return Err(err.into()),
+            }
+        }
+        // This uses `copy_file_range()` on Linux if possible.
+        let len = std::io::copy(&mut src, &mut dst)?;
+        Ok((dst, len, Method::Copy))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = probe;
+        if destination.symlink_metadata().is_ok() {
+            return Err(std::io::ErrorKind::AlreadyExists.into());
+        }
+        // This uses `clonefile()` on MacOS if possible.
+        let len = std::fs::copy(source, destination)?;
+        let dst = gix_features::fs::open_options_no_follow()
+            .write(true)
+            .open(destination)?;
+        Ok((dst, len, Method::Copy))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn create_new(path: &Path) -> std::io::Result<std::fs::File> {
+    gix_features::fs::open_options_no_follow()
+        .create_new(true)
+        .write(true)
+        .open(path)
+}
+
+#[cfg(target_os = "linux")]
+fn is_unsupported(err: rustix::io::Errno) -> bool {
+    use rustix::io::Errno;
+    [
+        Errno::OPNOTSUPP,
+        Errno::XDEV,
+        Errno::INVAL,
+        Errno::NOSYS,
+        Errno::NOTTY,
+        Errno::PERM,
+    ]
+    .contains(&err)
+}
+
+#[cfg(unix)]
+fn device_id(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).ok().map(|m| m.dev())
+}
+
+#[cfg(not(unix))]
+fn device_id(_path: &Path) -> Option<u64> {
+    None
+}
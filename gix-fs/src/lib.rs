@@ -30,7 +30,8 @@ pub struct Capabilities {
     /// out as files which contain the link as text.
     pub symlink: bool,
 }
-mod capabilities;
+///
+pub mod capabilities;
 
 mod snapshot;
 pub use snapshot::{FileSnapshot, SharedFileSnapshot, SharedFileSnapshotMut};
@@ -45,6 +46,9 @@ pub use read_dir::function::read_dir;
 ///
 pub mod dir;
 
+///
+pub mod clone;
+
 /// Like [`std::env::current_dir()`], but it will `precompose_unicode` if that value is true, if the current directory
 /// is valid unicode and if there are decomposed unicode codepoints.
 ///
@@ -16,3 +16,28 @@ fn probe() {
         "there should be no left-over files after probing, found {entries:?}"
     );
 }
+
+#[test]
+fn cache_probes_once_per_filesystem() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let (first, second) = (dir.path().join("first"), dir.path().join("second"));
+    for dir in [&first, &second] {
+        std::fs::create_dir(dir)?;
+        std::fs::File::create(dir.join("config"))?;
+    }
+    let cache = gix_fs::capabilities::Cache::default();
+    let expected = gix_fs::Capabilities::probe(&first);
+    assert_eq!(cache.probe(&first), expected);
+
+    std::fs::remove_dir_all(&first)?;
+    assert_eq!(
+        cache.clone().probe(&second),
+        expected,
+        "directories on the same filesystem share the probed values"
+    );
+
+    cache.invalidate(&second);
+    assert_eq!(cache.probe(&second), expected, "probing again yields the same result");
+    cache.clear();
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use gix_fs::clone;
+
+#[test]
+fn file_copies_content_and_refuses_existing_destinations() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let source = dir.path().join("source");
+    std::fs::write(&source, b"content")?;
+    let destination = dir.path().join("destination");
+
+    let probe = clone::Probe::default();
+    let (file, len, _method) = clone::file(&source, &destination, &probe)?;
+    drop(file);
+    assert_eq!(len, 7);
+    assert_eq!(std::fs::read(&destination)?, b"content");
+
+    let err = clone::file(&source, &destination, &probe).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    Ok(())
+}
+
+#[test]
+fn probe_is_shared_between_clones() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let source = dir.path().join("source");
+    std::fs::write(&source, b"content")?;
+
+    let probe = clone::Probe::default();
+    let shared = probe.clone();
+    let (_file, _len, method) = clone::file(&source, &dir.path().join("a"), &probe)?;
+    if method == clone::Method::Copy && cfg!(target_os = "linux") {
+        assert!(
+            !shared.may_reflink(dir.path()),
+            "the filesystem was marked as incapable of cloning for all copies of the probe"
+        );
+    } else {
+        assert!(shared.may_reflink(dir.path()) || !cfg!(unix));
+    }
+    Ok(())
+}
@@ -1,6 +1,7 @@
 type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync + 'static>>;
 
 mod capabilities;
+mod clone;
 mod dir;
 mod read_dir;
 mod stack;
@@ -71,12 +71,16 @@ where
     /// Blobs are checked right away, trees are stored in `tree_ids` for the parent to iterate them, and only
     /// if they have not been `seen` yet.
     fn check_tree(&mut self, oid: &ObjectId, tree_ids: &mut VecDeque<ObjectId>) {
-        let Ok(tree) = self.db.find_tree(oid, &mut self.buf) else {
+        let Ok(tree) = self.db.find_tree_iter(oid, &mut self.buf) else {
             (self.missing_cb)(oid, Kind::Tree);
             return;
         };
 
-        for entry_ref in tree.entries.iter() {
+        for entry_ref in tree {
+            let Ok(entry_ref) = entry_ref else {
+                (self.missing_cb)(oid, Kind::Tree);
+                return;
+            };
             match entry_ref.mode.kind() {
                 EntryKind::Tree => {
                     let tree_id = entry_ref.oid.to_owned();
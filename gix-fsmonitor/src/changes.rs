@@ -0,0 +1,95 @@
+use bstr::{BStr, BString, ByteSlice};
+
+/// The prefix of all tokens handed out by [`Changes`], the same one that is used by `git fsmonitor--daemon`.
+const TOKEN_PREFIX: &str = "builtin:";
+
+/// A record of all paths that changed in a worktree, identified by tokens which represent a point in time.
+///
+/// Tokens are of the form `builtin:<id>:<sequence-number>`, where `id` identifies a continuous history of changes.
+/// Whenever this history is interrupted, for instance because the watcher lost events, a new `id` is chosen so that all tokens
+/// handed out previously are unknown, which makes clients assume that everything may have changed.
+#[derive(Debug, Clone)]
+pub struct Changes {
+    id: String,
+    num_resets: usize,
+    seq: u64,
+    /// The sequence number at which each path was changed, in order of their occurrence.
+    paths: Vec<(u64, BString)>,
+    max_paths: usize,
+}
+
+impl Changes {
+    /// Create a new instance which starts a new history of changes, and which [resets](Self::reset()) itself once more than
+    /// `max_paths` changes are recorded to bound its memory usage.
+    pub fn new(max_paths: usize) -> Self {
+        let nanos_since_1970 = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        Changes {
+            id: format!("{}.{}", std::process::id(), nanos_since_1970),
+            num_resets: 0,
+            seq: 0,
+            paths: Vec::new(),
+            max_paths,
+        }
+    }
+
+    /// Return the token that represents the current point in time.
+    pub fn token(&self) -> BString {
+        format!("{TOKEN_PREFIX}{}.{}:{}", self.id, self.num_resets, self.seq).into()
+    }
+
+    /// Record that all `paths`, relative to the worktree root and with slashes as separator, changed just now.
+    pub fn record<'a>(&mut self, paths: impl IntoIterator<Item = &'a BStr>) {
+        self.seq += 1;
+        let seq = self.seq;
+        self.paths.extend(paths.into_iter().map(|path| (seq, path.to_owned())));
+        if self.paths.len() > self.max_paths {
+            self.reset();
+        }
+    }
+
+    /// Forget all changes and start a new history, which makes all previously handed out tokens unknown.
+    ///
+    /// This must be called whenever changes may have been missed.
+    pub fn reset(&mut self) {
+        self.num_resets += 1;
+        self.seq = 0;
+        self.paths.clear();
+    }
+
+    /// Return the response to a query with `token`, in the format of version 2 of the fsmonitor hook protocol.
+    ///
+    /// It consists of the current token and all paths that changed since `token`, each terminated with a null byte.
+    /// If `token` is unknown, the only path is `/` to indicate that everything may have changed.
+    pub fn since(&self, token: &BStr) -> Vec<u8> {
+        let mut out: Vec<u8> = self.token().into();
+        out.push(0);
+        match self.parse_seq(token) {
+            Some(seq) => {
+                let mut paths: Vec<_> = self
+                    .paths
+                    .iter()
+                    .filter(|(path_seq, _)| *path_seq > seq)
+                    .map(|(_, path)| path.as_bstr())
+                    .collect();
+                paths.sort();
+                paths.dedup();
+                for path in paths {
+                    out.extend_from_slice(path);
+                    out.push(0);
+                }
+            }
+            None => out.extend_from_slice(b"/\0"),
+        }
+        out
+    }
+
+    /// Return the sequence number of `token` if it belongs to our current history.
+    fn parse_seq(&self, token: &BStr) -> Option<u64> {
+        let (id, seq) = token.strip_prefix(TOKEN_PREFIX.as_bytes())?.rsplit_once_str(":")?;
+        let current_id = format!("{}.{}", self.id, self.num_resets);
+        let seq: u64 = seq.to_str().ok()?.parse().ok()?;
+        (id == current_id.as_bytes() && seq <= self.seq).then_some(seq)
+    }
+}
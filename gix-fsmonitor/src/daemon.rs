@@ -0,0 +1,267 @@
+//! Run the filesystem monitor daemon.
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+/// The error returned by [`run()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A filesystem monitor daemon is already listening at '{}'", socket.display())]
+    AlreadyRunning { socket: PathBuf },
+    #[error("Could not watch the worktree for changes")]
+    Watch(#[from] notify::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("The filesystem monitor daemon is only supported on unix")]
+    Unsupported,
+}
+
+/// The options for use in [`run()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// The amount of changed paths to remember before forgetting all of them, which makes clients look at all paths once.
+    pub max_paths: usize,
+    /// The longest time to wait for the watcher to catch up with all changes that happened before a query was received.
+    pub sync_timeout: std::time::Duration,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            max_paths: 1_000_000,
+            sync_timeout: std::time::Duration::from_secs(1),
+        }
+    }
+}
+
+/// Watch `worktree` for changes and answer queries for them on the [socket](crate::ipc::socket_path()) of `git_dir`
+/// until a client sends `quit` or `should_interrupt` is set, using `options`.
+///
+/// Before answering a query, a cookie file is written into `git_dir` and the answer is delayed until the watcher observed it,
+/// to be sure all changes that happened before the query are included.
+#[cfg(unix)]
+pub fn run(
+    worktree: &std::path::Path,
+    git_dir: &std::path::Path,
+    should_interrupt: &AtomicBool,
+    options: Options,
+) -> Result<(), Error> {
+    unix::run(worktree, git_dir, should_interrupt, options)
+}
+
+/// Watch `worktree` for changes and answer queries for them on the [socket](crate::ipc::socket_path()) of `git_dir`.
+///
+/// It's not supported on this platform.
+#[cfg(not(unix))]
+pub fn run(
+    _worktree: &std::path::Path,
+    _git_dir: &std::path::Path,
+    _should_interrupt: &AtomicBool,
+    _options: Options,
+) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::{
+        borrow::Cow,
+        io::ErrorKind,
+        os::unix::net::{UnixListener, UnixStream},
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Condvar, Mutex,
+        },
+    };
+
+    use bstr::ByteSlice;
+    use notify::Watcher;
+
+    use super::{Error, Options};
+    use crate::{
+        ipc::{read_message, socket_path, write_message},
+        Changes,
+    };
+
+    struct State {
+        changes: Changes,
+        /// The names of cookie files that were created, but not yet seen by the watcher.
+        pending_cookies: Vec<std::ffi::OsString>,
+    }
+
+    struct Shared {
+        state: Mutex<State>,
+        cookie_seen: Condvar,
+    }
+
+    /// Removes the socket file when dropped.
+    struct Socket(PathBuf);
+
+    impl Drop for Socket {
+        fn drop(&mut self) {
+            std::fs::remove_file(&self.0).ok();
+        }
+    }
+
+    pub(super) fn run(
+        worktree: &Path,
+        git_dir: &Path,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<(), Error> {
+        let worktree = gix_path::realpath(worktree).map_err(|err| std::io::Error::new(ErrorKind::Other, err))?;
+        let git_dir = gix_path::realpath(git_dir).map_err(|err| std::io::Error::new(ErrorKind::Other, err))?;
+        let cookie_dir = git_dir.join("fsmonitor--daemon").join("cookies");
+        std::fs::create_dir_all(&cookie_dir)?;
+
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                changes: Changes::new(options.max_paths),
+                pending_cookies: Vec::new(),
+            }),
+            cookie_seen: Condvar::new(),
+        });
+        let mut watcher = notify::recommended_watcher({
+            let shared = Arc::clone(&shared);
+            let (worktree, git_dir, cookie_dir) = (worktree.clone(), git_dir.clone(), cookie_dir.clone());
+            move |event: notify::Result<notify::Event>| {
+                handle_event(event, &shared, &worktree, &git_dir, &cookie_dir);
+            }
+        })?;
+        watcher.watch(&worktree, notify::RecursiveMode::Recursive)?;
+        if !cookie_dir.starts_with(&worktree) {
+            watcher.watch(&cookie_dir, notify::RecursiveMode::NonRecursive)?;
+        }
+
+        let socket = socket_path(&git_dir);
+        if socket.exists() {
+            if UnixStream::connect(&socket).is_ok() {
+                return Err(Error::AlreadyRunning { socket });
+            }
+            std::fs::remove_file(&socket)?;
+        }
+        let listener = UnixListener::bind(&socket)?;
+        let _socket = Socket(socket);
+        listener.set_nonblocking(true)?;
+        gix_features::trace::debug!(worktree = ?worktree, "fsmonitor daemon is listening");
+
+        let mut num_cookies = 0;
+        while !should_interrupt.load(Ordering::Relaxed) {
+            let stream = match listener.accept() {
+                Ok((stream, _addr)) => stream,
+                Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                    std::thread::sleep(std::time::Duration::from_millis(50));
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
+            let mut sync_with_watcher = || {
+                num_cookies += 1;
+                sync_with_watcher(&shared, &cookie_dir, num_cookies, options.sync_timeout);
+            };
+            match handle_connection(stream, &shared, &mut sync_with_watcher) {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(_err) => {
+                    gix_features::trace::debug!("failed to serve fsmonitor client: {}", _err);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Answer the request on `stream`, and return `true` if we should stop serving.
+    fn handle_connection(
+        mut stream: UnixStream,
+        shared: &Shared,
+        sync_with_watcher: &mut dyn FnMut(),
+    ) -> std::io::Result<bool> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(std::time::Duration::from_secs(5)))?;
+        let request = read_message(&mut stream)?;
+        let response = match request.as_slice() {
+            b"quit" => {
+                write_message(&mut stream, b"")?;
+                return Ok(true);
+            }
+            b"flush" => {
+                sync_with_watcher();
+                let mut state = shared.state.lock().expect("no panic while holding the lock");
+                state.changes.reset();
+                state.changes.since("".into())
+            }
+            token => {
+                sync_with_watcher();
+                let state = shared.state.lock().expect("no panic while holding the lock");
+                state.changes.since(token.as_bstr())
+            }
+        };
+        write_message(&mut stream, &response)?;
+        Ok(false)
+    }
+
+    /// Create a cookie file and wait until the watcher saw it, which means it also saw all changes that happened before.
+    fn sync_with_watcher(shared: &Shared, cookie_dir: &Path, cookie_id: usize, timeout: std::time::Duration) {
+        let name: std::ffi::OsString = format!("{}-{cookie_id}", std::process::id()).into();
+        let cookie = cookie_dir.join(&name);
+        let state = shared.state.lock().expect("no panic while holding the lock");
+        let mut state = if std::fs::File::create(&cookie).is_ok() {
+            let mut state = state;
+            state.pending_cookies.push(name.clone());
+            shared
+                .cookie_seen
+                .wait_timeout_while(state, timeout, |state| state.pending_cookies.contains(&name))
+                .expect("no panic while holding the lock")
+                .0
+        } else {
+            state
+        };
+        state.pending_cookies.retain(|pending| *pending != name);
+        drop(state);
+        std::fs::remove_file(cookie).ok();
+    }
+
+    fn handle_event(
+        event: notify::Result<notify::Event>,
+        shared: &Shared,
+        worktree: &Path,
+        git_dir: &Path,
+        cookie_dir: &Path,
+    ) {
+        let mut state = shared.state.lock().expect("no panic while holding the lock");
+        let event = match event {
+            Ok(event) if !event.need_rescan() => event,
+            Ok(_) | Err(_) => {
+                gix_features::trace::debug!("fsmonitor watcher lost track of changes");
+                state.changes.reset();
+                return;
+            }
+        };
+        if event.kind.is_access() {
+            return;
+        }
+        let mut paths = Vec::new();
+        for path in &event.paths {
+            if path.parent() == Some(cookie_dir) {
+                if let Some(name) = path.file_name() {
+                    if state.pending_cookies.iter().any(|pending| pending == name) {
+                        state.pending_cookies.retain(|pending| pending != name);
+                        shared.cookie_seen.notify_all();
+                    }
+                }
+                continue;
+            }
+            if path.starts_with(git_dir) {
+                continue;
+            }
+            match path.strip_prefix(worktree) {
+                Ok(rela_path) if rela_path.as_os_str().is_empty() => {}
+                Ok(rela_path) => paths.push(gix_path::to_unix_separators_on_windows(gix_path::into_bstr(rela_path))),
+                Err(_) => {}
+            }
+        }
+        if !paths.is_empty() {
+            state.changes.record(paths.iter().map(Cow::as_ref));
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Communicate with a running [daemon](crate::daemon::run()) through the socket that is also used by `git fsmonitor--daemon`.
+//!
+//! Each connection carries a single request and its response, both of which are sent as packet lines terminated by a flush packet.
+//! A request is either a token to learn about all changes since, or one of the commands `flush` and `quit`.
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+/// The error returned by [`send()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not connect to the filesystem monitor daemon at '{}'", path.display())]
+    Connect { path: PathBuf, source: std::io::Error },
+    #[error("Could not exchange messages with the filesystem monitor daemon")]
+    Io(#[from] std::io::Error),
+    #[error("The filesystem monitor daemon is only supported on unix")]
+    Unsupported,
+}
+
+/// Return the path to the socket at which the daemon for the repository at `git_dir` listens.
+pub fn socket_path(git_dir: &Path) -> PathBuf {
+    git_dir.join("fsmonitor--daemon.ipc")
+}
+
+/// Send `request` to the daemon that serves the repository at `git_dir` and return its response.
+///
+/// If `request` is a token, like the one stored in the [index extension](https://git-scm.com/docs/index-format), the response
+/// lists all paths that changed since in the format of version 2 of the fsmonitor hook protocol.
+/// `flush` makes the daemon forget all changes, and `quit` makes it stop.
+#[cfg(unix)]
+pub fn send(git_dir: &Path, request: &[u8]) -> Result<Vec<u8>, Error> {
+    let path = socket_path(git_dir);
+    let mut stream =
+        std::os::unix::net::UnixStream::connect(&path).map_err(|err| Error::Connect { path, source: err })?;
+    write_message(&mut stream, request)?;
+    Ok(read_message(&mut stream)?)
+}
+
+/// Send `request` to the daemon that serves the repository at `git_dir` and return its response.
+///
+/// It's not supported on this platform.
+#[cfg(not(unix))]
+pub fn send(_git_dir: &Path, _request: &[u8]) -> Result<Vec<u8>, Error> {
+    Err(Error::Unsupported)
+}
+
+/// Read a message consisting of packet lines up to the next flush packet.
+#[cfg(unix)]
+pub(crate) fn read_message(read: impl Read) -> std::io::Result<Vec<u8>> {
+    let mut lines = gix_packetline::StreamingPeekableIter::new(read, &[gix_packetline::PacketLineRef::Flush], false);
+    let mut buf = Vec::new();
+    lines.as_read().read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+/// Write `data` as packet lines, followed by a flush packet.
+#[cfg(unix)]
+pub(crate) fn write_message(mut out: impl Write, data: &[u8]) -> std::io::Result<()> {
+    if !data.is_empty() {
+        gix_packetline::Writer::new(&mut out).write_all(data)?;
+    }
+    gix_packetline::encode::flush_to_write(&mut out)?;
+    out.flush()
+}
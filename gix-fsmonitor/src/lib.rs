@@ -0,0 +1,16 @@
+//! A filesystem monitor which watches a worktree and tells clients which paths changed since they last asked, so they don't have
+//! to look at all other files.
+//!
+//! The [daemon](daemon::run()) speaks the same IPC protocol as `git fsmonitor--daemon` and listens on the same socket, which makes
+//! it usable by `git` with `core.fsmonitor=true` as well. Clients [send](ipc::send()) the token they stored in the index
+//! to receive all paths that changed since in the format of version 2 of the fsmonitor hook protocol, along with a new token.
+//!
+//! Only unix platforms are supported for now.
+#![deny(missing_docs, rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+mod changes;
+pub use changes::Changes;
+
+pub mod daemon;
+pub mod ipc;
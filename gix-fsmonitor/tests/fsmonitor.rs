@@ -0,0 +1,131 @@
+use gix_fsmonitor::Changes;
+use gix_status::fs_monitor::Outcome;
+use gix_testtools::Result;
+
+fn outcome(response: &[u8]) -> Outcome {
+    Outcome::from_response(response).expect("always starts with a token")
+}
+
+mod changes {
+    use bstr::ByteSlice;
+
+    use super::outcome;
+    use crate::Changes;
+
+    #[test]
+    fn unknown_tokens_mean_everything_changed() {
+        let changes = Changes::new(10);
+        for token in ["", "token", "builtin:other:0", "builtin:"] {
+            let outcome = outcome(&changes.since(token.into()));
+            assert!(outcome.everything_possibly_changed(), "{token:?}");
+            assert_eq!(outcome.token, changes.token());
+        }
+    }
+
+    #[test]
+    fn paths_changed_since_token_are_reported() {
+        let mut changes = Changes::new(10);
+        let start = changes.token();
+        changes.record(["b", "a/file"].into_iter().map(|p| p.as_bytes().as_bstr()));
+        let middle = changes.token();
+        assert_ne!(start, middle, "tokens change with each recorded change");
+        changes.record(["c", "b"].into_iter().map(|p| p.as_bytes().as_bstr()));
+
+        assert_eq!(
+            changes.since(start.as_ref()).as_bstr(),
+            format!("{}\0a/file\0b\0c\0", changes.token()).as_bytes().as_bstr(),
+            "paths are sorted and unique"
+        );
+        assert_eq!(
+            changes.since(middle.as_ref()).as_bstr(),
+            format!("{}\0b\0c\0", changes.token()).as_bytes().as_bstr()
+        );
+        assert_eq!(
+            changes.since(changes.token().as_ref()).as_bstr(),
+            format!("{}\0", changes.token()).as_bytes().as_bstr(),
+            "nothing changed since the current token"
+        );
+    }
+
+    #[test]
+    fn reset_invalidates_all_tokens() {
+        let mut changes = Changes::new(10);
+        let token = changes.token();
+        changes.reset();
+        assert!(outcome(&changes.since(token.as_ref())).everything_possibly_changed());
+
+        let token = changes.token();
+        changes.record(std::iter::repeat("a".as_bytes().as_bstr()).take(11));
+        assert!(
+            outcome(&changes.since(token.as_ref())).everything_possibly_changed(),
+            "too many changes reset automatically"
+        );
+    }
+}
+
+#[cfg(unix)]
+mod daemon {
+    use std::{path::Path, sync::atomic::AtomicBool};
+
+    use bstr::ByteSlice;
+    use gix_fsmonitor::{daemon, ipc};
+
+    use super::{outcome, Result};
+
+    fn send_when_ready(git_dir: &Path, request: &[u8]) -> Result<Vec<u8>> {
+        for _ in 0..100 {
+            if let Ok(response) = ipc::send(git_dir, request) {
+                return Ok(response);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        Ok(ipc::send(git_dir, request)?)
+    }
+
+    #[test]
+    fn changes_in_the_worktree_are_reported_until_quit() -> Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let worktree = tmp.path();
+        let git_dir = worktree.join(".git");
+        std::fs::create_dir_all(&git_dir)?;
+        std::fs::create_dir(worktree.join("dir"))?;
+
+        let should_interrupt = AtomicBool::new(false);
+        std::thread::scope(|s| -> Result {
+            let daemon = s.spawn(|| daemon::run(worktree, &git_dir, &should_interrupt, Default::default()));
+
+            let initial = outcome(&send_when_ready(&git_dir, b"")?);
+            assert!(
+                initial.everything_possibly_changed(),
+                "without a known token, everything may have changed"
+            );
+
+            std::fs::write(worktree.join("dir").join("file"), b"content")?;
+            std::fs::write(git_dir.join("index"), b"ignored")?;
+            let changed = outcome(&ipc::send(&git_dir, &initial.token)?);
+            assert!(changed.is_possibly_changed("dir/file".into()));
+            assert!(!changed.is_possibly_changed("other".into()));
+            assert!(
+                !changed.is_possibly_changed(".git/index".into()),
+                "changes to the repository itself are ignored"
+            );
+
+            let unchanged = outcome(&ipc::send(&git_dir, &changed.token)?);
+            assert!(!unchanged.everything_possibly_changed());
+            assert!(!unchanged.is_possibly_changed("dir/file".into()));
+
+            assert!(
+                matches!(
+                    daemon::run(worktree, &git_dir, &AtomicBool::new(false), Default::default()),
+                    Err(daemon::Error::AlreadyRunning { .. })
+                ),
+                "only one daemon per repository"
+            );
+
+            assert_eq!(ipc::send(&git_dir, b"quit")?.as_bstr(), "");
+            daemon.join().expect("no panic")?;
+            assert!(!ipc::socket_path(&git_dir).exists(), "the socket is removed");
+            Ok(())
+        })
+    }
+}
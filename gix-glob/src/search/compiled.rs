@@ -0,0 +1,212 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::{
+    pattern::{Case, Mode},
+    search::{
+        pattern::{strip_base_handle_recompute_basename_pos, List},
+        Pattern,
+    },
+    wildmatch,
+};
+
+/// A compiled form of a [pattern list](List) to match a path against all of its patterns at once,
+/// which is faster than trying each pattern in turn if there are many of them.
+///
+/// Patterns are grouped so that a lookup only needs to verify patterns that can possibly match:
+///
+/// * literal basenames like `Cargo.lock` and literal paths like `/target` are looked up by hash,
+/// * basename suffixes like `*.rs` are found in a trie of reversed suffixes,
+/// * all other patterns are found in a trie of their literal prefix up to the first wildcard, separately for patterns
+///   matching the basename and those matching the whole path.
+///
+/// The index returned by [`pattern_idx_matching_relative_path()`](Self::pattern_idx_matching_relative_path()) is the
+/// same as the one produced by trying all patterns from last to first, so `git`'s precedence is kept.
+#[derive(Debug, Clone)]
+pub struct Compiled {
+    patterns: Vec<crate::Pattern>,
+    base: Option<BString>,
+    case: Case,
+    mode: wildmatch::Mode,
+    literal_basenames: HashMap<BString, Vec<usize>>,
+    literal_paths: HashMap<BString, Vec<usize>>,
+    basename_suffixes: Trie,
+    basename_prefixes: Trie,
+    path_prefixes: Trie,
+}
+
+/// Lifecycle
+impl Compiled {
+    /// Compile all patterns of `list` to match paths with `case`, with `*` not matching slashes, just like
+    /// [`Evaluator::new()`](crate::search::Evaluator::new()) does.
+    pub fn new<T: Pattern>(list: &List<T>, case: Case) -> Self {
+        let mut out = Compiled {
+            patterns: list.patterns.iter().map(|mapping| mapping.pattern.clone()).collect(),
+            base: list.base.clone(),
+            case,
+            mode: wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+            literal_basenames: Default::default(),
+            literal_paths: Default::default(),
+            basename_suffixes: Default::default(),
+            basename_prefixes: Default::default(),
+            path_prefixes: Default::default(),
+        };
+        for (idx, pattern) in out.patterns.iter().enumerate() {
+            let matches_basename = pattern.mode.contains(Mode::NO_SUB_DIR) && !pattern.mode.contains(Mode::ABSOLUTE);
+            match (pattern.first_wildcard_pos, matches_basename) {
+                (None, true) => out
+                    .literal_basenames
+                    .entry(fold(pattern.text.as_ref(), case).into_owned())
+                    .or_default()
+                    .push(idx),
+                (None, false) => out
+                    .literal_paths
+                    .entry(fold(pattern.text.as_ref(), case).into_owned())
+                    .or_default()
+                    .push(idx),
+                (Some(pos), true) if pattern.mode.contains(Mode::ENDS_WITH) => {
+                    out.basename_suffixes
+                        .insert(pattern.text[pos + 1..].iter().rev().copied(), case, idx);
+                }
+                (Some(pos), true) => out
+                    .basename_prefixes
+                    .insert(pattern.text[..pos].iter().copied(), case, idx),
+                (Some(pos), false) => out.path_prefixes.insert(pattern.text[..pos].iter().copied(), case, idx),
+            }
+        }
+        out
+    }
+}
+
+/// Access
+impl Compiled {
+    /// Return the index of the last pattern matching `relative_path`, or `None` if there was no match.
+    /// The index is the one of the pattern in the [list](List) this instance was compiled from.
+    ///
+    /// `basename_pos` is the position at which the basename of `relative_path` starts, if it is known already,
+    /// and `is_dir` is `true` if `relative_path` is a directory.
+    ///
+    /// Note that negated patterns are returned like any other and need to be interpreted by the caller.
+    pub fn pattern_idx_matching_relative_path(
+        &self,
+        relative_path: &BStr,
+        basename_pos: Option<usize>,
+        is_dir: Option<bool>,
+    ) -> Option<usize> {
+        let (relative_path, basename_pos) = match self.base.as_deref() {
+            Some(base) => {
+                strip_base_handle_recompute_basename_pos(base.as_bstr(), relative_path, basename_pos, self.case)?
+            }
+            None => (relative_path, basename_pos),
+        };
+        let basename_pos = basename_pos.or_else(|| relative_path.rfind_byte(b'/').map(|pos| pos + 1));
+        let basename = &relative_path[basename_pos.unwrap_or_default()..];
+
+        let mut best = None;
+        let mut consider = |idx: usize| {
+            if best.map_or(true, |best| idx > best)
+                && self.patterns[idx].matches_repo_relative_path(
+                    relative_path,
+                    basename_pos,
+                    is_dir,
+                    self.case,
+                    self.mode,
+                )
+            {
+                best = Some(idx);
+            }
+        };
+
+        self.literal_basenames
+            .get(fold(basename, self.case).as_ref())
+            .into_iter()
+            .chain(self.literal_paths.get(fold(relative_path, self.case).as_ref()))
+            .flatten()
+            .copied()
+            .for_each(&mut consider);
+        self.basename_suffixes
+            .for_each_prefix_of(basename.iter().rev().copied(), self.case, &mut consider);
+        self.basename_prefixes
+            .for_each_prefix_of(basename.iter().copied(), self.case, &mut consider);
+        self.path_prefixes
+            .for_each_prefix_of(relative_path.iter().copied(), self.case, &mut consider);
+        best
+    }
+
+    /// Return the number of compiled patterns.
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Return `true` if there are no compiled patterns, which can never match.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+}
+
+fn fold(text: &[u8], case: Case) -> Cow<'_, BStr> {
+    match case {
+        Case::Sensitive => Cow::Borrowed(text.as_bstr()),
+        Case::Fold => Cow::Owned(text.to_ascii_lowercase().into()),
+    }
+}
+
+fn fold_byte(byte: u8, case: Case) -> u8 {
+    match case {
+        Case::Sensitive => byte,
+        Case::Fold => byte.to_ascii_lowercase(),
+    }
+}
+
+/// A byte-wise trie which associates keys with the indices of the patterns they were taken from.
+#[derive(Debug, Clone, Default)]
+struct Trie {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// Child nodes by their byte, sorted by byte.
+    children: Vec<(u8, usize)>,
+    /// The pattern indices whose key ends at this node.
+    patterns: Vec<usize>,
+}
+
+impl Trie {
+    fn insert(&mut self, key: impl Iterator<Item = u8>, case: Case, idx: usize) {
+        if self.nodes.is_empty() {
+            self.nodes.push(Node::default());
+        }
+        let mut node = 0;
+        for byte in key.map(|byte| fold_byte(byte, case)) {
+            node = match self.nodes[node].children.binary_search_by_key(&byte, |(b, _)| *b) {
+                Ok(pos) => self.nodes[node].children[pos].1,
+                Err(pos) => {
+                    let child = self.nodes.len();
+                    self.nodes.push(Node::default());
+                    self.nodes[node].children.insert(pos, (byte, child));
+                    child
+                }
+            };
+        }
+        self.nodes[node].patterns.push(idx);
+    }
+
+    /// Call `cb` with all pattern indices whose key is a prefix of `value`, including the empty key.
+    fn for_each_prefix_of(&self, value: impl Iterator<Item = u8>, case: Case, cb: &mut dyn FnMut(usize)) {
+        let Some(mut node) = self.nodes.first() else {
+            return;
+        };
+        node.patterns.iter().copied().for_each(&mut *cb);
+        for byte in value.map(|byte| fold_byte(byte, case)) {
+            match node.children.binary_search_by_key(&byte, |(b, _)| *b) {
+                Ok(pos) => {
+                    node = &self.nodes[node.children[pos].1];
+                    node.patterns.iter().copied().for_each(&mut *cb);
+                }
+                Err(_) => break,
+            }
+        }
+    }
+}
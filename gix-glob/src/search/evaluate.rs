@@ -0,0 +1,154 @@
+use std::path::Path;
+
+use bstr::{BStr, ByteSlice};
+
+use crate::{
+    pattern::Case,
+    search::{pattern::List, Pattern},
+    wildmatch,
+};
+
+/// Describes the pattern that decided the outcome of an [evaluation](Evaluator).
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Match<'a, T> {
+    /// The glob pattern itself, like `/target/*` or `!*.rs`.
+    pub pattern: &'a crate::Pattern,
+    /// The value associated with the pattern.
+    pub value: &'a T,
+    /// The path to the source from which the pattern was loaded, or `None` if it was specified by other means.
+    pub source: Option<&'a Path>,
+    /// The line at which the pattern was found in its `source` file, or the occurrence in which it was provided.
+    pub sequence_number: usize,
+}
+
+impl<T> Match<'_, T> {
+    /// Return `true` if the matching pattern is negated, like `!*.rs`, which undoes the effect of earlier patterns.
+    pub fn is_negative(&self) -> bool {
+        self.pattern.is_negative()
+    }
+}
+
+/// Answer match queries against ordered pattern lists with the last-match-wins semantics of `.gitignore` files.
+///
+/// Lists are expected in order of increasing precedence, so later lists override earlier ones, just like later
+/// patterns within a list override earlier ones. Negated patterns are returned like any other match and
+/// [must be interpreted](Match::is_negative()) by the caller.
+///
+/// This is shared by everything that evaluates ordered patterns, like ignore patterns and non-cone sparse checkouts.
+#[derive(Debug)]
+pub struct Evaluator<'a, T: Pattern> {
+    /// The pattern lists to evaluate, with those of highest precedence last.
+    pub lists: &'a [List<T>],
+    /// Whether or not to fold the case of paths and patterns when matching.
+    pub case: Case,
+    /// The mode to use when the patterns need to be matched with [`wildmatch()`](crate::wildmatch()).
+    pub mode: wildmatch::Mode,
+}
+
+/// Lifecycle
+impl<'a, T: Pattern> Evaluator<'a, T> {
+    /// Create a new instance to evaluate `lists` with `case`, matching paths with `*` not matching slashes.
+    pub fn new(lists: &'a [List<T>], case: Case) -> Self {
+        Evaluator {
+            lists,
+            case,
+            mode: wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+        }
+    }
+}
+
+/// Evaluation
+impl<'a, T: Pattern> Evaluator<'a, T> {
+    /// Return the last pattern matching `relative_path`, which may be negative, or `None` if no pattern matched.
+    /// `is_dir` is `true` if `relative_path` is a directory.
+    ///
+    /// Note that only `relative_path` itself is considered, use
+    /// [`pattern_matching_relative_path_or_parent()`](Self::pattern_matching_relative_path_or_parent()) to also
+    /// respect matches of its parent directories.
+    pub fn pattern_matching_relative_path(
+        &self,
+        relative_path: &BStr,
+        is_dir: Option<bool>,
+    ) -> Option<Match<'a, T::Value>> {
+        let basename_pos = relative_path.rfind_byte(b'/').map(|pos| pos + 1);
+        self.lists
+            .iter()
+            .rev()
+            .find_map(|list| self.list_match(list, relative_path, basename_pos, is_dir))
+    }
+
+    /// Like [`pattern_matching_relative_path()`](Self::pattern_matching_relative_path()), but applies the rule that
+    /// paths can't be re-included if one of their parent directories is excluded.
+    ///
+    /// Thus, starting at the top-most parent directory, the first one whose last matching pattern is positive decides
+    /// for `relative_path`, and only if there is none, `relative_path` is matched directly.
+    pub fn pattern_matching_relative_path_or_parent(
+        &self,
+        relative_path: &BStr,
+        is_dir: Option<bool>,
+    ) -> Option<Match<'a, T::Value>> {
+        let mut start = 0;
+        while let Some(pos) = relative_path[start..].find_byte(b'/') {
+            let dir = relative_path[..start + pos].as_bstr();
+            if let Some(m) = self
+                .pattern_matching_relative_path(dir, Some(true))
+                .filter(|m| !m.is_negative())
+            {
+                return Some(m);
+            }
+            start += pos + 1;
+        }
+        self.pattern_matching_relative_path(relative_path, is_dir)
+    }
+
+    /// Like [`pattern_matching_relative_path()`](Self::pattern_matching_relative_path()), but if no pattern matches
+    /// `relative_path`, its parent directories are tried from the closest one upwards, so the closest decision applies.
+    ///
+    /// This is how non-cone sparse checkouts decide which files to include.
+    pub fn pattern_matching_relative_path_or_closest_parent(
+        &self,
+        relative_path: &BStr,
+        is_dir: Option<bool>,
+    ) -> Option<Match<'a, T::Value>> {
+        if let Some(m) = self.pattern_matching_relative_path(relative_path, is_dir) {
+            return Some(m);
+        }
+        let mut dir = relative_path;
+        while let Some(pos) = dir.rfind_byte(b'/') {
+            dir = dir[..pos].as_bstr();
+            if let Some(m) = self.pattern_matching_relative_path(dir, Some(true)) {
+                return Some(m);
+            }
+        }
+        None
+    }
+
+    /// Return `true` if `relative_path` is matched by a positive pattern, either directly or through an excluded parent
+    /// directory, as determined by [`pattern_matching_relative_path_or_parent()`](Self::pattern_matching_relative_path_or_parent()).
+    pub fn is_match(&self, relative_path: &BStr, is_dir: Option<bool>) -> bool {
+        self.pattern_matching_relative_path_or_parent(relative_path, is_dir)
+            .map_or(false, |m| !m.is_negative())
+    }
+
+    fn list_match(
+        &self,
+        list: &'a List<T>,
+        relative_path: &BStr,
+        basename_pos: Option<usize>,
+        is_dir: Option<bool>,
+    ) -> Option<Match<'a, T::Value>> {
+        let (relative_path, basename_pos) =
+            list.strip_base_handle_recompute_basename_pos(relative_path, basename_pos, self.case)?;
+        list.patterns.iter().rev().find_map(|mapping| {
+            mapping
+                .pattern
+                .matches_repo_relative_path(relative_path, basename_pos, is_dir, self.case, self.mode)
+                .then_some(Match {
+                    pattern: &mapping.pattern,
+                    value: &mapping.value,
+                    source: list.source.as_deref(),
+                    sequence_number: mapping.sequence_number,
+                })
+        })
+    }
+}
@@ -7,6 +7,14 @@ use std::path::{Path, PathBuf};
 ///
 pub mod pattern;
 
+///
+pub mod evaluate;
+pub use evaluate::Evaluator;
+
+///
+pub mod compiled;
+pub use compiled::Compiled;
+
 /// A trait to convert bytes into patterns and their associated value.
 ///
 /// This is used for `gitattributes` which have a value, and `gitignore` which don't.
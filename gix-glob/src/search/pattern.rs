@@ -11,7 +11,19 @@ use crate::{pattern::Case, search::Pattern};
 ///
 /// Knowing their base which is relative to a source directory, it will ignore all path to match against
 /// that don't also start with said base.
+///
+/// With the `serde` feature, compiled lists can be stored in any format supported by `serde`, like `bincode`,
+/// to load them without parsing their source files again. Note that loading them always copies the patterns into
+/// owned values, so they can't be used in place from memory-mapped bytes.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "T::Value: serde::Serialize",
+        deserialize = "T::Value: serde::Deserialize<'de>"
+    ))
+)]
 pub struct List<T: Pattern> {
     /// Patterns and their associated data in the order they were loaded in or specified,
     /// the line number in its source file or its sequence number (_`(pattern, value, line_number)`_).
@@ -30,6 +42,7 @@ pub struct List<T: Pattern> {
 
 /// An association of a pattern with its value, along with a sequence number providing a sort order in relation to its peers.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mapping<T> {
     /// The pattern itself, like `/target/*`
     pub pattern: crate::Pattern,
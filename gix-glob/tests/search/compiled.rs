@@ -0,0 +1,181 @@
+use std::path::Path;
+
+use bstr::{BStr, ByteSlice};
+use gix_glob::{
+    pattern::Case,
+    search::{pattern::List, Compiled},
+};
+
+use super::evaluate::{list, Lines};
+
+/// The reference implementation which tries all patterns from last to first.
+fn linear(list: &List<Lines>, path: &BStr, is_dir: Option<bool>, case: Case) -> Option<usize> {
+    let basename_pos = path.rfind_byte(b'/').map(|pos| pos + 1);
+    let (path, basename_pos) = list.strip_base_handle_recompute_basename_pos(path, basename_pos, case)?;
+    list.patterns.iter().enumerate().rev().find_map(|(idx, mapping)| {
+        mapping
+            .pattern
+            .matches_repo_relative_path(
+                path,
+                basename_pos,
+                is_dir,
+                case,
+                gix_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+            )
+            .then_some(idx)
+    })
+}
+
+fn assert_same_as_linear<'a>(list: &List<Lines>, paths: impl IntoIterator<Item = &'a BStr> + Clone) {
+    for case in [Case::Sensitive, Case::Fold] {
+        let compiled = Compiled::new(list, case);
+        assert_eq!(compiled.len(), list.patterns.len());
+        for path in paths.clone() {
+            for is_dir in [None, Some(false), Some(true)] {
+                assert_eq!(
+                    compiled.pattern_idx_matching_relative_path(path, None, is_dir),
+                    linear(list, path, is_dir, case),
+                    "{path:?} {is_dir:?} {case:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn last_matching_pattern_is_found_for_all_kinds_of_patterns() {
+    let patterns = "Cargo.lock
+/target
+*.rs
+!main.rs
+*.RS
+src/*.rs
+!src/lib.rs
+/doc/**/*.md
+doc/
+**/tmp
+foo*bar
+!foo?bar
+*
+!*.txt
+a/b/c
+\\*literal
+[ab]*.c
+dir/sub/
+";
+    let list = list(patterns, "ignore", None);
+    let paths = [
+        "Cargo.lock",
+        "cargo.lock",
+        "sub/Cargo.lock",
+        "target",
+        "sub/target",
+        "lib.rs",
+        "main.rs",
+        "sub/main.rs",
+        "MAIN.RS",
+        "src/lib.rs",
+        "src/mod.rs",
+        "src/deep/mod.rs",
+        "doc",
+        "doc/a.md",
+        "doc/a/b/c.md",
+        "DOC/a.md",
+        "tmp",
+        "a/b/tmp",
+        "fooxbar",
+        "foo-long-bar",
+        "readme.txt",
+        "a/b/c",
+        "a/b/c/d",
+        "*literal",
+        "xliteral",
+        "a.c",
+        "b/c.c",
+        "dir/sub",
+        "",
+    ]
+    .map(|p| p.as_bytes().as_bstr());
+    assert_same_as_linear(&list, paths);
+
+    let compiled = Compiled::new(&list, Case::Sensitive);
+    assert_eq!(
+        compiled.pattern_idx_matching_relative_path("src/lib.rs".into(), None, Some(false)),
+        Some(12),
+        "the last matching pattern wins, even if it's the catch-all `*`"
+    );
+}
+
+#[test]
+fn patterns_with_base_only_match_within() {
+    let list = list("*.o\n/build\n!keep.o\n", "repo/sub/.gitignore", Some(Path::new("repo")));
+    assert_same_as_linear(
+        &list,
+        [
+            "a.o",
+            "sub/a.o",
+            "sub/build",
+            "sub/x/build",
+            "sub/keep.o",
+            "SUB/a.o",
+            "sub",
+        ]
+        .map(|p| p.as_bytes().as_bstr()),
+    );
+
+    let compiled = Compiled::new(&list, Case::Sensitive);
+    assert_eq!(
+        compiled.pattern_idx_matching_relative_path("a.o".into(), None, None),
+        None
+    );
+    assert_eq!(
+        compiled.pattern_idx_matching_relative_path("sub/x/a.o".into(), Some(6), None),
+        Some(0),
+        "a known basename position is adjusted to the base"
+    );
+}
+
+#[test]
+fn empty_lists_never_match() {
+    let list = list("", "empty", None);
+    let compiled = Compiled::new(&list, Case::Sensitive);
+    assert!(compiled.is_empty());
+    assert_eq!(
+        compiled.pattern_idx_matching_relative_path("a".into(), None, None),
+        None
+    );
+}
+
+#[test]
+fn baseline_patterns_and_values_match_like_a_linear_search() {
+    let dir = gix_testtools::scripted_fixture_read_only("make_baseline.sh").unwrap();
+    let mut patterns = Vec::new();
+    let mut values = Vec::new();
+    for input_file in ["git-baseline.match", "git-baseline.nmatch", "git-baseline.match-icase"] {
+        let input = std::fs::read(dir.join(input_file)).unwrap();
+        for (idx, line) in input.lines().enumerate().filter(|(idx, _)| idx % 2 == 0) {
+            let mut tokens = line.splitn(2, |b| *b == b' ');
+            let pattern = tokens.next().expect("pattern");
+            let value = tokens.next().expect("value").trim_start();
+            assert!(!pattern.is_empty(), "line {idx} of {input_file} has a pattern");
+            patterns.push(pattern.as_bstr().to_owned());
+            values.push(value.as_bstr().to_owned());
+        }
+    }
+
+    let list = list(
+        patterns
+            .iter()
+            .map(|p| p.to_str_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join("\n")
+            .as_str(),
+        "baseline",
+        None,
+    );
+    assert!(
+        list.patterns.len() > 100,
+        "the baseline is large enough to be meaningful"
+    );
+    assert_same_as_linear(&list, values.iter().map(|v| v.as_bstr()));
+}
@@ -0,0 +1,126 @@
+use std::path::Path;
+
+use bstr::{BStr, ByteSlice};
+use gix_glob::{
+    pattern::Case,
+    search::{
+        pattern::{List, Mapping},
+        Evaluator, Pattern,
+    },
+};
+
+#[derive(Clone, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Default)]
+pub struct Lines;
+
+impl Pattern for Lines {
+    type Value = ();
+
+    fn bytes_to_patterns(bytes: &[u8], _source: &Path) -> Vec<Mapping<Self::Value>> {
+        bytes
+            .lines()
+            .enumerate()
+            .filter_map(|(idx, line)| {
+                gix_glob::parse(line).map(|pattern| Mapping {
+                    pattern,
+                    value: (),
+                    sequence_number: idx + 1,
+                })
+            })
+            .collect()
+    }
+}
+
+pub fn list(patterns: &str, source: &str, root: Option<&Path>) -> List<Lines> {
+    List::from_bytes(patterns.as_bytes(), source.into(), root)
+}
+
+fn path(path: &str) -> &BStr {
+    path.into()
+}
+
+#[test]
+fn last_match_wins_within_and_across_lists() {
+    let lists = [
+        list("*.log\n!keep.log\n", "global", None),
+        list("keep.log\n!debug.log\n", "repo/.gitignore", Some(Path::new("repo"))),
+        list("!*.log\n", "repo/sub/.gitignore", Some(Path::new("repo"))),
+    ];
+    let eval = Evaluator::new(&lists, Case::Sensitive);
+
+    let m = eval
+        .pattern_matching_relative_path(path("out.log"), Some(false))
+        .expect("matched");
+    assert!(!m.is_negative());
+    assert_eq!(m.source, Some(Path::new("global")));
+    assert_eq!(m.sequence_number, 1);
+
+    let m = eval
+        .pattern_matching_relative_path(path("keep.log"), Some(false))
+        .expect("matched");
+    assert!(!m.is_negative(), "the later list re-excludes the file");
+    assert_eq!(m.source, Some(Path::new("repo/.gitignore")));
+
+    assert!(eval
+        .pattern_matching_relative_path(path("debug.log"), Some(false))
+        .expect("matched")
+        .is_negative());
+    assert!(
+        eval.pattern_matching_relative_path(path("sub/a.log"), Some(false))
+            .expect("matched")
+            .is_negative(),
+        "lists with a base only apply to paths within it"
+    );
+    assert!(!eval.is_match(path("sub/a.log"), Some(false)));
+    assert!(eval.is_match(path("a.log"), Some(false)));
+    assert!(eval
+        .pattern_matching_relative_path(path("a.txt"), Some(false))
+        .is_none());
+}
+
+#[test]
+fn excluded_parent_directories_prevent_reinclusion() {
+    let lists = [list("build/\n!build/keep\nlogs/*\n!logs/keep\n", "a", None)];
+    let eval = Evaluator::new(&lists, Case::Sensitive);
+
+    assert!(
+        eval.pattern_matching_relative_path(path("build/keep"), Some(false))
+            .expect("matched")
+            .is_negative(),
+        "on its own, the file is matched by the negation…"
+    );
+    let m = eval
+        .pattern_matching_relative_path_or_parent(path("build/keep"), Some(false))
+        .expect("matched");
+    assert_eq!(m.pattern.text, "build", "…but the excluded parent directory decides");
+    assert!(eval.is_match(path("build/keep"), Some(false)));
+    assert!(eval.is_match(path("build/deeply/nested"), Some(false)));
+
+    assert!(
+        !eval.is_match(path("logs/keep"), Some(false)),
+        "the parent directory itself isn't excluded, only its contents, so re-inclusion works"
+    );
+    assert!(eval.is_match(path("logs/other"), Some(false)));
+}
+
+#[test]
+fn closest_parent_decides_if_the_path_does_not_match() {
+    let lists = [list("/*\n!/*/\n/a/\n!/a/b/\n", "sparse-checkout", None)];
+    let eval = Evaluator::new(&lists, Case::Sensitive);
+    let is_included = |p: &str| {
+        eval.pattern_matching_relative_path_or_closest_parent(path(p), Some(false))
+            .map_or(false, |m| !m.is_negative())
+    };
+
+    assert!(is_included("top-level-file"));
+    assert!(is_included("a/file"));
+    assert!(is_included("a/c/file"));
+    assert!(!is_included("a/b/file"), "the closest directory decides");
+    assert!(!is_included("other/file"));
+}
+
+#[test]
+fn case_folding() {
+    let lists = [list("*.LOG\n", "a", None)];
+    assert!(Evaluator::new(&lists, Case::Fold).is_match(path("a.log"), Some(false)));
+    assert!(!Evaluator::new(&lists, Case::Sensitive).is_match(path("a.log"), Some(false)));
+}
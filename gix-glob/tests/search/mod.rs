@@ -1 +1,3 @@
+mod compiled;
+mod evaluate;
 mod pattern;
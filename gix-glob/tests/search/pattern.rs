@@ -95,4 +95,25 @@ mod list {
             assert!(list.is_none(), "the file does not exist");
         }
     }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn binary_roundtrip() -> gix_testtools::Result {
+        let mut list = List::<Dummy>::from_bytes(&[], "a/b/source".into(), Some(Path::new("a")));
+        for (sequence_number, pattern) in ["*.rs", "/target/", "!keep"].into_iter().enumerate() {
+            list.patterns.push(Mapping {
+                pattern: gix_glob::parse(pattern).expect("valid"),
+                value: (),
+                sequence_number,
+            });
+        }
+
+        let bytes = bincode::serialize(&list)?;
+        let actual: List<Dummy> = bincode::deserialize(&bytes)?;
+        assert_eq!(
+            actual, list,
+            "compiled patterns can be stored and loaded without parsing them again"
+        );
+        Ok(())
+    }
 }
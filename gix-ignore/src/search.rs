@@ -3,7 +3,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use bstr::{BStr, ByteSlice};
+use bstr::BStr;
 use gix_glob::search::{pattern, Pattern};
 
 use crate::Search;
@@ -172,10 +172,13 @@ impl Search {
         is_dir: Option<bool>,
         case: gix_glob::pattern::Case,
     ) -> Option<Match<'_>> {
-        let basename_pos = relative_path.rfind(b"/").map(|p| p + 1);
-        self.patterns
-            .iter()
-            .rev()
-            .find_map(|pl| pattern_matching_relative_path(pl, relative_path, basename_pos, is_dir, case))
+        gix_glob::search::Evaluator::new(&self.patterns, case)
+            .pattern_matching_relative_path(relative_path, is_dir)
+            .map(|m| Match {
+                pattern: m.pattern,
+                source: m.source,
+                kind: *m.value,
+                sequence_number: m.sequence_number,
+            })
     }
 }
@@ -16,6 +16,14 @@ impl State {
         self.version
     }
 
+    /// Set the `version` to use when storing this state's information on disk.
+    ///
+    /// Note that only [`Version::V4`] is retained when writing, as V2 and V3 are automatically chosen depending
+    /// on which is required to store all entries.
+    pub fn set_version(&mut self, version: Version) {
+        self.version = version;
+    }
+
     /// Returns time at which the state was created, indicating its freshness compared to other files on disk.
     pub fn timestamp(&self) -> FileTime {
         self.timestamp
@@ -535,6 +543,46 @@ impl State {
     }
 }
 
+/// Racy-git handling
+impl State {
+    /// Return `true` if `entry` is racy, which is if it was modified at or after the [timestamp](Self::timestamp()) of this index,
+    /// so its stat information can't be trusted to indicate that it's unchanged.
+    ///
+    /// Submodules are never racy as their content is always checked, and neither are entries of an index without timestamp.
+    /// See racy-git.txt in the git documentation for more details.
+    pub fn is_racy(&self, entry: &Entry, options: entry::stat::Options) -> bool {
+        self.timestamp.unix_seconds() != 0 && !entry.mode.is_submodule() && entry.stat.is_racy(self.timestamp, options)
+    }
+
+    /// Set the size of all racy entries whose file content differs from what's in the index to `0`, so they are
+    /// checked thoroughly once the index is read again, and return the amount of smudged entries.
+    /// This should be done right before writing the index, as its new timestamp would otherwise make modified files
+    /// look unchanged if their modification happened in the same instant as the previous write.
+    ///
+    /// `is_modified(state, entry)` is called for each [racy](Self::is_racy()) entry that isn't smudged yet, and is expected
+    /// to return `true` if the file in the worktree still matches the entry's stat information, but has a different content.
+    /// Files that don't exist or whose stat information doesn't match are already known to be modified and don't need smudging.
+    pub fn smudge_racily_clean_entries<E>(
+        &mut self,
+        options: entry::stat::Options,
+        mut is_modified: impl FnMut(&State, &Entry) -> Result<bool, E>,
+    ) -> Result<usize, E> {
+        let mut smudged = Vec::new();
+        for (idx, entry) in self.entries.iter().enumerate() {
+            if entry.stat.size == 0 || entry.flags.contains(entry::Flags::REMOVE) || !self.is_racy(entry, options) {
+                continue;
+            }
+            if is_modified(self, entry)? {
+                smudged.push(idx);
+            }
+        }
+        for idx in &smudged {
+            self.entries[*idx].stat.size = 0;
+        }
+        Ok(smudged.len())
+    }
+}
+
 /// Extensions
 impl State {
     /// Access the `tree` extension.
@@ -557,6 +605,12 @@ impl State {
     pub fn fs_monitor(&self) -> Option<&extension::FsMonitor> {
         self.fs_monitor.as_ref()
     }
+    /// Set the fsmonitor extension to `fs_monitor`, or remove it if `None`.
+    ///
+    /// It's written along with the index if [all extensions](crate::write::Extensions::All) are written.
+    pub fn set_fs_monitor(&mut self, fs_monitor: Option<extension::FsMonitor>) {
+        self.fs_monitor = fs_monitor;
+    }
     /// Return `true` if the end-of-index extension was present when decoding this index.
     pub fn had_end_of_index_marker(&self) -> bool {
         self.end_of_index_at_decode_time
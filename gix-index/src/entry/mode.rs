@@ -48,7 +48,7 @@ impl Mode {
         executable_bit: bool,
     ) -> Option<Change> {
         match self {
-            Mode::FILE if !stat.is_file() => (),
+            Mode::FILE | Mode::FILE_EXECUTABLE if !stat.is_file() => (),
             Mode::SYMLINK if has_symlinks && !stat.is_symlink() => (),
             Mode::SYMLINK if !has_symlinks && !stat.is_file() => (),
             Mode::COMMIT | Mode::DIR if !stat.is_dir() => (),
@@ -56,15 +56,38 @@ impl Mode {
             Mode::FILE_EXECUTABLE if executable_bit && !stat.is_executable() => return Some(Change::ExecutableBit),
             _ => return None,
         };
-        let new_mode = if stat.is_dir() {
-            Mode::COMMIT
-        } else if executable_bit && stat.is_executable() {
-            Mode::FILE_EXECUTABLE
-        } else {
-            Mode::FILE
-        };
+        let new_mode = Mode::from_fs(stat, has_symlinks, executable_bit, None);
         Some(Change::Type { new_mode })
     }
+
+    /// Return the mode for an entry that tracks the file system object with `stat`, similar to what `git` does
+    /// when adding files to the index.
+    ///
+    /// * if `has_symlinks` is false, symlinks on disk are treated like files, and if `previous` is a symlink,
+    ///   a file on disk is assumed to represent that symlink.
+    /// * if `executable_bit` is false, the executable bit of files is taken from `previous` if it's a file, or
+    ///   assumed to be unset otherwise.
+    ///
+    /// Directories are assumed to be submodules.
+    pub fn from_fs(
+        stat: &crate::fs::Metadata,
+        has_symlinks: bool,
+        executable_bit: bool,
+        previous: Option<Mode>,
+    ) -> Mode {
+        if stat.is_dir() {
+            return Mode::COMMIT;
+        }
+        if has_symlinks && stat.is_symlink() {
+            return Mode::SYMLINK;
+        }
+        match previous {
+            Some(Mode::SYMLINK) if !has_symlinks => Mode::SYMLINK,
+            Some(mode @ (Mode::FILE | Mode::FILE_EXECUTABLE)) if !executable_bit => mode,
+            _ if executable_bit && stat.is_file() && stat.is_executable() => Mode::FILE_EXECUTABLE,
+            _ => Mode::FILE,
+        }
+    }
 }
 
 /// A change of a [`Mode`].
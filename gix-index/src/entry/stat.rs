@@ -56,11 +56,11 @@ impl Stat {
             return false;
         }
 
-        if trust_ctime {
+        if trust_ctime && check_stat {
             if self.ctime.secs != other.ctime.secs {
                 return false;
             }
-            if check_stat && use_nsec && self.ctime.nsecs != other.ctime.nsecs {
+            if use_nsec && self.ctime.nsecs != other.ctime.nsecs {
                 return false;
             }
         }
@@ -171,8 +171,10 @@ pub struct Options {
     /// Default `true`.
     pub trust_ctime: bool,
     /// If true, all stat fields will be used when checking for up-to-date'ness of the entry. Otherwise
-    /// nano-second parts of mtime and ctime,uid, gid, inode and device number _will not_ be used, leaving only
-    /// the whole-second part of ctime and mtime and the file size to be checked.
+    /// ctime, the nano-second part of mtime, uid, gid, inode and device number _will not_ be used, leaving only
+    /// the whole-second part of mtime and the file size to be checked.
+    /// This corresponds to `core.checkStat=minimal`, which is useful on filesystems with unstable inode numbers or
+    /// ownership information, like network shares or volumes mounted into containers.
     ///
     /// Default `true`.
     pub check_stat: bool,
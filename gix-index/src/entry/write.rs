@@ -1,10 +1,42 @@
 use std::convert::TryInto;
 
+use bstr::BStr;
+
 use crate::{entry, Entry, State};
 
 impl Entry {
     /// Serialize ourselves to `out` with path access via `state`, without padding.
     pub fn write_to(&self, mut out: impl std::io::Write, state: &State) -> std::io::Result<()> {
+        let path = self.path(state);
+        self.write_to_without_path(&mut out, path)?;
+        out.write_all(path)?;
+        out.write_all(b"\0")
+    }
+
+    /// Serialize ourselves to `out` with path access via `state` like [`write_to()`](Self::write_to()), but in the format
+    /// of index V4 where only the portion of the path that differs from `previous_path` is stored.
+    ///
+    /// `previous_path` is the path of the entry written just before this one, or empty if this is the first entry.
+    pub(crate) fn write_to_v4(
+        &self,
+        mut out: impl std::io::Write,
+        state: &State,
+        previous_path: &BStr,
+    ) -> std::io::Result<()> {
+        let path = self.path(state);
+        self.write_to_without_path(&mut out, path)?;
+        let common_prefix_len = previous_path
+            .iter()
+            .zip(path.iter())
+            .take_while(|(lhs, rhs)| lhs == rhs)
+            .count();
+        let mut buf = [0u8; 10];
+        out.write_all(leb64_encode((previous_path.len() - common_prefix_len) as u64, &mut buf))?;
+        out.write_all(&path[common_prefix_len..])?;
+        out.write_all(b"\0")
+    }
+
+    fn write_to_without_path(&self, mut out: impl std::io::Write, path: &BStr) -> std::io::Result<()> {
         let stat = self.stat;
         out.write_all(&stat.ctime.secs.to_be_bytes())?;
         out.write_all(&stat.ctime.nsecs.to_be_bytes())?;
@@ -17,7 +49,6 @@ impl Entry {
         out.write_all(&stat.gid.to_be_bytes())?;
         out.write_all(&stat.size.to_be_bytes())?;
         out.write_all(self.id.as_bytes())?;
-        let path = self.path(state);
         let path_len: u16 = if path.len() >= entry::Flags::PATH_LEN.bits() as usize {
             entry::Flags::PATH_LEN.bits() as u16
         } else {
@@ -33,7 +64,25 @@ impl Entry {
                     .to_be_bytes(),
             )?;
         }
-        out.write_all(path)?;
-        out.write_all(b"\0")
+        Ok(())
+    }
+}
+
+/// Encode `n` in the variable-length format that is also used for offsets in packs, the inverse of
+/// [`gix_features::decode::leb64()`].
+#[inline]
+fn leb64_encode(mut n: u64, buf: &mut [u8; 10]) -> &[u8] {
+    let mut bytes_written = 1;
+    buf[buf.len() - 1] = n as u8 & 0b0111_1111;
+    for out in buf.iter_mut().rev().skip(1) {
+        n >>= 7;
+        if n == 0 {
+            break;
+        }
+        n -= 1;
+        *out = 0b1000_0000 | (n as u8 & 0b0111_1111);
+        bytes_written += 1;
     }
+    debug_assert_eq!(n, 0, "BUG: buffer must be large enough to hold a 64 bit integer");
+    &buf[buf.len() - bytes_written..]
 }
@@ -5,14 +5,71 @@ use crate::{
     util::{read_u32, read_u64, split_at_byte_exclusive},
 };
 
-#[derive(Clone)]
+/// The token identifying the point in time at which the filesystem monitor was last queried.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Token {
-    V1 { nanos_since_1970: u64 },
-    V2 { token: BString },
+    /// The token of the first version of the hook protocol, a timestamp.
+    V1 {
+        /// The amount of nanoseconds since the unix epoch.
+        nanos_since_1970: u64,
+    },
+    /// The opaque token of the second version of the hook protocol, as provided by the filesystem monitor.
+    V2 {
+        /// The token itself.
+        token: BString,
+    },
 }
 
+/// The signature of the fsmonitor extension.
 pub const SIGNATURE: Signature = *b"FSMN";
 
+impl FsMonitor {
+    /// Create a new instance from the `token` provided by the filesystem monitor, and a bitmap with a bit set for each
+    /// entry that isn't known to be unchanged.
+    pub fn new(token: Token, entry_dirty: gix_bitmap::ewah::Vec) -> Self {
+        FsMonitor { token, entry_dirty }
+    }
+
+    /// Return the token that was provided by the filesystem monitor when it was last queried.
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    /// Return a bitmap with a bit set for each entry that wasn't known to be unchanged by the filesystem monitor,
+    /// indexed by the position of the entry in the index.
+    ///
+    /// Entries past the end of the bitmap are not dirty.
+    pub fn entry_dirty(&self) -> &gix_bitmap::ewah::Vec {
+        &self.entry_dirty
+    }
+
+    /// Serialize this instance to `out`.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> Result<(), std::io::Error> {
+        let mut data = Vec::new();
+        match &self.token {
+            Token::V1 { nanos_since_1970 } => {
+                data.extend_from_slice(&1_u32.to_be_bytes());
+                data.extend_from_slice(&nanos_since_1970.to_be_bytes());
+            }
+            Token::V2 { token } => {
+                data.extend_from_slice(&2_u32.to_be_bytes());
+                data.extend_from_slice(token);
+                data.push(0);
+            }
+        }
+        let mut bitmap = Vec::new();
+        self.entry_dirty.write_to(&mut bitmap)?;
+        data.extend_from_slice(&(u32::try_from(bitmap.len()).expect("less than 4GB bitmap")).to_be_bytes());
+        data.extend_from_slice(&bitmap);
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&(u32::try_from(data.len()).expect("less than 4GB fsmonitor extension")).to_be_bytes())?;
+        out.write_all(&data)?;
+        Ok(())
+    }
+}
+
+/// Decode the fsmonitor extension from `data`.
 pub fn decode(data: &[u8]) -> Option<FsMonitor> {
     let (version, data) = read_u32(data)?;
     let (token, data) = match version {
@@ -42,7 +42,6 @@ pub struct Link {
 }
 
 /// The extension for untracked files.
-#[allow(dead_code)]
 #[derive(Clone)]
 pub struct UntrackedCache {
     /// Something identifying the location and machine that this cache is for.
@@ -61,7 +60,6 @@ pub struct UntrackedCache {
 }
 
 /// The extension for keeping state on recent information provided by the filesystem monitor.
-#[allow(dead_code)]
 #[derive(Clone)]
 pub struct FsMonitor {
     token: fs_monitor::Token,
@@ -71,7 +69,8 @@ pub struct FsMonitor {
 
 mod iter;
 
-pub(crate) mod fs_monitor;
+///
+pub mod fs_monitor;
 
 ///
 pub mod decode;
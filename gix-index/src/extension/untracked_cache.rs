@@ -1,6 +1,6 @@
 use std::convert::TryInto;
 
-use bstr::BString;
+use bstr::{BStr, BString};
 use gix_hash::ObjectId;
 
 use crate::{
@@ -28,17 +28,63 @@ pub struct Directory {
     /// indices for sub-directories similar to this one.
     pub sub_directories: Vec<usize>,
 
-    /// The directories stat data, if available or valid // TODO: or is it the exclude file?
+    /// The directories stat data, if available or valid.
+    ///
+    /// If the stat of the directory on disk still matches, no entries were added or removed from it.
     pub stat: Option<entry::Stat>,
     /// The oid of a .gitignore file, if it exists
     pub exclude_file_oid: Option<ObjectId>,
-    /// TODO: figure out what this really does
+    /// If `true`, the directory was only checked for containing any untracked file, so `untracked_entries` is incomplete.
     pub check_only: bool,
 }
 
+bitflags::bitflags! {
+    /// The flags `git` used when collecting untracked files, which need to match for the cache to be usable.
+    #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    pub struct DirFlags: u32 {
+        /// Ignored files were collected as well.
+        const SHOW_IGNORED = 1 << 0;
+        /// Directories that are entirely untracked are listed instead of their content.
+        const SHOW_OTHER_DIRECTORIES = 1 << 1;
+        /// Directories without any file aren't listed.
+        const HIDE_EMPTY_DIRECTORIES = 1 << 2;
+    }
+}
+
 /// Only used as an indicator
 pub const SIGNATURE: Signature = *b"UNTR";
 
+impl UntrackedCache {
+    /// Return the string identifying the location and machine that this cache is for.
+    ///
+    /// `git` produces it as `Location <worktree>, system <sysname>`, and discards the cache if it doesn't match.
+    pub fn identifier(&self) -> &BStr {
+        self.identifier.as_ref()
+    }
+    /// Return the stat and object id of the `.git/info/exclude` file at the time the cache was written, if it existed.
+    pub fn info_exclude(&self) -> Option<&OidStat> {
+        self.info_exclude.as_ref()
+    }
+    /// Return the stat and object id of the file at `core.excludesFile` at the time the cache was written, if it existed.
+    pub fn excludes_file(&self) -> Option<&OidStat> {
+        self.excludes_file.as_ref()
+    }
+    /// Return the name of the per-directory exclude files, usually `.gitignore`.
+    pub fn exclude_filename_per_dir(&self) -> &BStr {
+        self.exclude_filename_per_dir.as_ref()
+    }
+    /// Return the flags that were used to collect untracked files.
+    pub fn dir_flags(&self) -> DirFlags {
+        DirFlags::from_bits_retain(self.dir_flags)
+    }
+    /// Return all directories of the cache, with the first one being the root of the worktree if there is any.
+    ///
+    /// Use [`Directory::sub_directories`] to learn about the index of the sub-directories of each directory.
+    pub fn directories(&self) -> &[Directory] {
+        &self.directories
+    }
+}
+
 // #[allow(unused)]
 /// Decode an untracked cache extension from `data`, assuming object hashes are of type `object_hash`.
 pub fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Option<UntrackedCache> {
@@ -49,9 +95,19 @@ pub fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Option<UntrackedCache
     let (identifier, data) = split_at_pos(data, identifier_len.try_into().ok()?)?;
 
     let hash_len = object_hash.len_in_bytes();
-    let (info_exclude, data) = decode_oid_stat(data, hash_len)?;
-    let (excludes_file, data) = decode_oid_stat(data, hash_len)?;
+    let (info_exclude_stat, data) = crate::decode::stat(data)?;
+    let (excludes_file_stat, data) = crate::decode::stat(data)?;
     let (dir_flags, data) = read_u32(data)?;
+    let (info_exclude_id, data) = split_at_pos(data, hash_len)?;
+    let (excludes_file_id, data) = split_at_pos(data, hash_len)?;
+    let info_exclude = OidStat {
+        stat: info_exclude_stat,
+        id: ObjectId::from_bytes_or_panic(info_exclude_id),
+    };
+    let excludes_file = OidStat {
+        stat: excludes_file_stat,
+        id: ObjectId::from_bytes_or_panic(excludes_file_id),
+    };
     let (exclude_filename_per_dir, data) = split_at_byte_exclusive(data, 0)?;
 
     let (num_directory_blocks, data) = var_int(data)?;
@@ -142,15 +198,3 @@ fn decode_directory_block<'a>(data: &'a [u8], directories: &mut Vec<Directory>)
 
     data.into()
 }
-
-fn decode_oid_stat(data: &[u8], hash_len: usize) -> Option<(OidStat, &[u8])> {
-    let (stat, data) = crate::decode::stat(data)?;
-    let (hash, data) = split_at_pos(data, hash_len)?;
-    Some((
-        OidStat {
-            stat,
-            id: ObjectId::from_bytes_or_panic(hash),
-        },
-        data,
-    ))
-}
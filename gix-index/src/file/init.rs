@@ -98,7 +98,12 @@ impl File {
         };
 
         let (state, checksum) = State::from_bytes(&data, mtime, object_hash, options)?;
-        let mut file = File { state, path, checksum };
+        let mut file = File {
+            state,
+            path,
+            checksum,
+            shared_index_checksum: None,
+        };
         if let Some(mut link) = file.link.take() {
             link.dissolve_into(&mut file, object_hash, skip_hash, options)?;
         }
@@ -114,6 +119,7 @@ impl File {
             state,
             path: path.into(),
             checksum: None,
+            shared_index_checksum: None,
         }
     }
 }
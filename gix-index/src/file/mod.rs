@@ -28,6 +28,7 @@ mod impl_ {
             f.debug_struct("File")
                 .field("path", &self.path.display())
                 .field("checksum", &self.checksum)
+                .field("shared_index_checksum", &self.shared_index_checksum)
                 .finish_non_exhaustive()
         }
     }
@@ -63,6 +64,12 @@ mod access {
         pub fn checksum(&self) -> Option<gix_hash::ObjectId> {
             self.checksum
         }
+
+        /// The checksum of the shared index that makes up most of our entries if this index was read from
+        /// or [written](File::write_split()) as split index, or `None` if it is a regular index.
+        pub fn shared_index_checksum(&self) -> Option<gix_hash::ObjectId> {
+            self.shared_index_checksum
+        }
     }
 }
 
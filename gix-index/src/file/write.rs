@@ -1,8 +1,16 @@
+use filetime::FileTime;
 use gix_features::hash;
 
-use crate::{write, File, Version};
+use crate::{
+    decode,
+    entry::Flags,
+    extension::{link, Link},
+    write,
+    write::WriteBack,
+    Entry, File, PathStorage, State, Version,
+};
 
-/// The error produced by [`File::write()`].
+/// The error produced by [`File::write()`] and [`File::write_split()`].
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
 pub enum Error {
@@ -14,6 +22,19 @@ pub enum Error {
     CommitLock(#[from] gix_lock::commit::Error<gix_lock::File>),
 }
 
+/// The outcome of [`File::write_back()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The index was written.
+    Written,
+    /// Writing was skipped as the policy was [`WriteBack::Never`].
+    Disabled,
+    /// Writing was skipped as another writer holds the lock of the index file.
+    Locked,
+    /// Writing was skipped as the index on disk changed since it was read or last written by us.
+    Changed,
+}
+
 impl File {
     /// Write the index to `out` with `options`, to be readable by [`File::at()`], returning the version that was actually written
     /// to retain all information of this index.
@@ -40,19 +61,247 @@ impl File {
     /// Write ourselves to the path we were read from after acquiring a lock, using `options`.
     ///
     /// Note that the hash produced will be stored which is why we need to be mutable.
+    /// The [timestamp](crate::State::timestamp()) is also updated to the modification time of the written file,
+    /// which is what decides which entries are racy from now on.
+    /// Call [`State::smudge_racily_clean_entries()`](crate::State::smudge_racily_clean_entries()) beforehand to assure
+    /// modifications that happened in the same instant as the previous write are still detected.
+    ///
+    /// Note that indices read from a split index are written as regular index, use [`File::write_split()`] to keep them split.
     pub fn write(&mut self, options: write::Options) -> Result<(), Error> {
         let _span = gix_features::trace::detail!("gix_index::File::write()", path = ?self.path);
-        let mut lock = std::io::BufWriter::with_capacity(
-            64 * 1024,
-            gix_lock::File::acquire_to_update_resource(&self.path, gix_lock::acquire::Fail::Immediately, None)?,
-        );
+        let lock = gix_lock::File::acquire_to_update_resource(&self.path, gix_lock::acquire::Fail::Immediately, None)?;
+        self.write_locked(lock, options)
+    }
+
+    /// Write ourselves back to the path we were read from if `policy` permits it, using `options`, after modifying the index
+    /// as a side-effect of an operation that doesn't depend on the write to succeed, like refreshing stat information during a status.
+    ///
+    /// With [`WriteBack::IfUnchanged`], writing is skipped instead of failing if another writer currently holds the lock,
+    /// and if the index on disk changed since it was read or written by us, to not overwrite changes made by explicit writers
+    /// in the meantime. The latter is determined by comparing checksums, or modification times if the index was written
+    /// without a checksum.
+    pub fn write_back(&mut self, options: write::Options, policy: WriteBack) -> Result<Outcome, Error> {
+        let _span = gix_features::trace::detail!("gix_index::File::write_back()", path = ?self.path, policy = ?policy);
+        match policy {
+            WriteBack::Never => return Ok(Outcome::Disabled),
+            WriteBack::Always => {
+                self.write(options)?;
+                return Ok(Outcome::Written);
+            }
+            WriteBack::IfUnchanged => {}
+        }
+        let lock =
+            match gix_lock::File::acquire_to_update_resource(&self.path, gix_lock::acquire::Fail::Immediately, None) {
+                Ok(lock) => lock,
+                Err(gix_lock::acquire::Error::PermanentlyLocked { .. }) => return Ok(Outcome::Locked),
+                Err(err) => return Err(err.into()),
+            };
+        if self.changed_on_disk()? {
+            return Ok(Outcome::Changed);
+        }
+        self.write_locked(lock, options)?;
+        Ok(Outcome::Written)
+    }
+
+    fn changed_on_disk(&self) -> std::io::Result<bool> {
+        use std::io::{Read, Seek};
+        let mut file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(self.checksum.is_some()),
+            Err(err) => return Err(err),
+        };
+        let expected = self.checksum.unwrap_or_else(|| self.state.object_hash.null());
+        let mut actual = expected.kind().null();
+        let meta = file.metadata()?;
+        if meta.len() < actual.as_slice().len() as u64 {
+            return Ok(true);
+        }
+        file.seek(std::io::SeekFrom::End(-(actual.as_slice().len() as i64)))?;
+        file.read_exact(actual.as_mut_slice())?;
+        Ok(actual != expected
+            || (expected.is_null() && FileTime::from_last_modification_time(&meta) != self.state.timestamp))
+    }
+
+    fn write_locked(&mut self, lock: gix_lock::File, options: write::Options) -> Result<(), Error> {
+        let mut lock = std::io::BufWriter::with_capacity(64 * 1024, lock);
         let (version, digest) = self.write_to(&mut lock, options)?;
         match lock.into_inner() {
             Ok(lock) => lock.commit()?,
             Err(err) => return Err(err.into_error().into()),
         };
         self.state.version = version;
+        self.state.timestamp = FileTime::from_last_modification_time(&std::fs::metadata(&self.path)?);
         self.checksum = Some(digest);
+        self.shared_index_checksum = None;
         Ok(())
     }
+
+    /// Like [`File::write()`], but write ourselves as split index which stores only the entries that changed compared
+    /// to a *shared index*, which is kept in a file named `sharedindex.<checksum>` next to our [path](File::path()).
+    ///
+    /// If we were read from or written as split index before, its shared index is reused unless more than `max_percent_change`
+    /// percent of all entries would have to be stored in the split index, similar to `splitIndex.maxPercentChange`
+    /// (which defaults to `20`). Otherwise, or if the shared index can't be read, a new shared index with all entries is written
+    /// and the split index merely refers to it. Use `0` to always write a new shared index, and `100` to never do so
+    /// if one already exists.
+    ///
+    /// Note that shared indices which aren't referred to anymore are not deleted.
+    pub fn write_split(&mut self, options: write::Options, max_percent_change: u8) -> Result<(), Error> {
+        let _span = gix_features::trace::detail!("gix_index::File::write_split()", path = ?self.path);
+        let index_dir = self.path.parent().expect("index file in .git folder").to_owned();
+        // The link is represented by `shared_index_checksum`, and is recreated below.
+        self.state.link = None;
+
+        let split = self.shared_index_checksum.and_then(|checksum| {
+            // A shared index that vanished or can't be read is simply replaced with a new one.
+            let shared_index = File::at(
+                index_dir.join(format!("sharedindex.{checksum}")),
+                self.state.object_hash,
+                false,
+                decode::Options {
+                    expected_checksum: Some(checksum),
+                    ..Default::default()
+                },
+            )
+            .ok()?;
+            split_against(&self.state, &shared_index.state, max_percent_change)
+                .map(|(entries, path_backing, bitmaps)| (entries, path_backing, checksum, bitmaps))
+        });
+        let (entries, path_backing, shared_index_checksum, bitmaps) = match split {
+            Some(split) => split,
+            None => {
+                let mut buf = Vec::new();
+                let (version, checksum) = self.write_to(
+                    &mut buf,
+                    write::Options {
+                        extensions: write::Extensions::None,
+                        skip_hash: false,
+                    },
+                )?;
+                let mut lock = gix_lock::File::acquire_to_update_resource(
+                    index_dir.join(format!("sharedindex.{checksum}")),
+                    gix_lock::acquire::Fail::Immediately,
+                    None,
+                )?;
+                std::io::Write::write_all(&mut lock, &buf)?;
+                lock.commit()?;
+                self.state.version = version;
+                let no_bits = || gix_bitmap::ewah::Vec::from_words(&[], 0);
+                let bitmaps = link::Bitmaps {
+                    delete: no_bits(),
+                    replace: no_bits(),
+                };
+                (Vec::new(), Vec::new(), checksum, bitmaps)
+            }
+        };
+
+        let state = &self.state;
+        let mut split_index = File {
+            state: State {
+                object_hash: state.object_hash,
+                timestamp: state.timestamp,
+                version: state.version,
+                entries,
+                path_backing,
+                is_sparse: state.is_sparse,
+                end_of_index_at_decode_time: false,
+                offset_table_at_decode_time: false,
+                tree: state.tree.clone(),
+                link: Some(Link {
+                    shared_index_checksum,
+                    bitmaps: Some(bitmaps),
+                }),
+                resolve_undo: state.resolve_undo.clone(),
+                untracked: state.untracked.clone(),
+                fs_monitor: state.fs_monitor.clone(),
+            },
+            path: self.path.clone(),
+            checksum: None,
+            shared_index_checksum: None,
+        };
+        split_index.write(options)?;
+
+        self.state.timestamp = split_index.state.timestamp;
+        self.checksum = split_index.checksum;
+        self.shared_index_checksum = Some(shared_index_checksum);
+        Ok(())
+    }
+}
+
+/// Compare the entries of `index` to the ones in `shared` and return the entries to store in the split index along with their
+/// path storage and the bitmaps to apply them, or `None` if more than `max_percent_change` percent of the entries changed.
+fn split_against(
+    index: &State,
+    shared: &State,
+    max_percent_change: u8,
+) -> Option<(Vec<Entry>, PathStorage, link::Bitmaps)> {
+    if max_percent_change == 0 {
+        return None;
+    }
+    let num_words = (shared.entries.len() + 63) / 64;
+    let mut delete = vec![0_u64; num_words];
+    let mut replace = vec![0_u64; num_words];
+    let set_bit = |words: &mut [u64], idx: usize| words[idx / 64] |= 1 << (idx % 64);
+
+    fn key<'a>(entry: &Entry, state: &'a State) -> (&'a bstr::BStr, u32) {
+        (entry.path(state), entry.stage())
+    }
+    let mut lhs = shared.entries.iter().enumerate().peekable();
+    let mut rhs = index
+        .entries
+        .iter()
+        .filter(|entry| !entry.flags.contains(Flags::REMOVE))
+        .peekable();
+    let (mut replaced, mut added, mut num_entries) = (Vec::new(), Vec::new(), 0);
+    loop {
+        match (lhs.peek(), rhs.peek()) {
+            (None, None) => break,
+            (Some((_, old)), new) if new.map_or(true, |new| key(old, shared) < key(new, index)) => {
+                let (idx, _) = lhs.next().expect("peeked");
+                set_bit(&mut delete, idx);
+            }
+            (old, Some(new)) if old.map_or(true, |(_, old)| key(old, shared) > key(new, index)) => {
+                added.push(rhs.next().expect("peeked"));
+                num_entries += 1;
+            }
+            (Some(_), Some(_)) => {
+                let ((idx, old), new) = (lhs.next().expect("peeked"), rhs.next().expect("peeked"));
+                let is_unchanged =
+                    old.stat == new.stat && old.id == new.id && old.mode == new.mode && old.flags == new.flags;
+                if !is_unchanged {
+                    set_bit(&mut replace, idx);
+                    replaced.push(new);
+                }
+                num_entries += 1;
+            }
+            (None, Some(_)) | (Some(_), None) => unreachable!("handled by guards above"),
+        }
+    }
+    if (replaced.len() + added.len()) * 100 > num_entries * usize::from(max_percent_change) {
+        return None;
+    }
+
+    // Replacements come first, in the order of the shared index and without a path, followed by all new entries.
+    let mut path_backing = Vec::new();
+    let entries = replaced
+        .into_iter()
+        .map(|entry| Entry {
+            path: 0..0,
+            ..entry.clone()
+        })
+        .chain(added.into_iter().map(|entry| {
+            let start = path_backing.len();
+            path_backing.extend_from_slice(entry.path(index));
+            Entry {
+                path: start..path_backing.len(),
+                ..entry.clone()
+            }
+        }))
+        .collect();
+    let num_bits = u32::try_from(shared.entries.len()).expect("definitely not 4billion entries");
+    let bitmaps = link::Bitmaps {
+        delete: gix_bitmap::ewah::Vec::from_words(&delete, num_bits),
+        replace: gix_bitmap::ewah::Vec::from_words(&replace, num_bits),
+    };
+    Some((entries, path_backing, bitmaps))
 }
@@ -34,6 +34,9 @@ pub mod verify;
 ///
 pub mod write;
 
+///
+pub mod write_tree;
+
 pub mod fs;
 
 /// All known versions of a git index file.
@@ -73,6 +76,8 @@ pub struct File {
     pub(crate) path: PathBuf,
     /// The checksum of all bytes prior to the checksum itself.
     pub(crate) checksum: Option<gix_hash::ObjectId>,
+    /// The checksum of the shared index that was merged into this one if it was read from or written as split index.
+    pub(crate) shared_index_checksum: Option<gix_hash::ObjectId>,
 }
 
 /// The type to use and store paths to all entries.
@@ -43,9 +43,28 @@ impl Extensions {
     }
 }
 
+/// Control if an index that was modified as a side-effect, like refreshed stat information during a status, is written back
+/// with [`File::write_back()`](crate::File::write_back()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WriteBack {
+    /// Never write the index, leaving the one on disk untouched.
+    Never,
+    /// Write the index only if its lock can be acquired right away and if it didn't change on disk since it was read,
+    /// and silently skip writing otherwise.
+    ///
+    /// This is what `git status` does to not interfere with explicit writers, like `git add`, which hold the lock
+    /// while they are making changes.
+    #[default]
+    IfUnchanged,
+    /// Write the index like [`File::write()`](crate::File::write()) does, failing if the lock is held by another writer
+    /// and overwriting changes made on disk since the index was read.
+    Always,
+}
+
 /// The options for use when [writing an index][State::write_to()].
 ///
-/// Note that default options write either index V2 or V3 depending on the content of the entries.
+/// Note that default options write either index V2 or V3 depending on the content of the entries,
+/// unless the [version of the index](State::version()) is V4, which is retained.
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Options {
     /// Configures which extensions to write.
@@ -86,7 +105,7 @@ impl State {
             .expect("definitely not too many entries");
 
         let offset_to_entries = header(&mut write, version, num_entries - removed_entries)?;
-        let offset_to_extensions = entries(&mut write, self, offset_to_entries)?;
+        let offset_to_extensions = entries(&mut write, self, version, offset_to_entries)?;
         let (extension_toc, out) = self.write_extensions(write, offset_to_extensions, extensions)?;
 
         if num_entries > 0
@@ -112,11 +131,23 @@ impl State {
     {
         type WriteExtFn<'a> = &'a dyn Fn(&mut dyn std::io::Write) -> Option<std::io::Result<extension::Signature>>;
         let extensions: &[WriteExtFn<'_>] = &[
+            &|write| {
+                self.link()
+                    .map(|link| link.write_to(write).map(|_| extension::link::SIGNATURE))
+            },
             &|write| {
                 extensions
                     .should_write(extension::tree::SIGNATURE)
                     .and_then(|signature| self.tree().map(|tree| tree.write_to(write).map(|_| signature)))
             },
+            &|write| {
+                extensions
+                    .should_write(extension::fs_monitor::SIGNATURE)
+                    .and_then(|signature| {
+                        self.fs_monitor()
+                            .map(|fs_monitor| fs_monitor.write_to(write).map(|_| signature))
+                    })
+            },
             &|write| {
                 self.is_sparse()
                     .then(|| extension::sparse::write_to(write).map(|_| extension::sparse::SIGNATURE))
@@ -139,6 +170,10 @@ impl State {
 
 impl State {
     fn detect_required_version(&self) -> Version {
+        if self.version == Version::V4 {
+            // V4 supports extended flags, and has to be retained as it was chosen deliberately.
+            return Version::V4;
+        }
         self.entries
             .iter()
             .find_map(|e| e.flags.contains(entry::Flags::EXTENDED).then_some(Version::V3))
@@ -164,7 +199,24 @@ fn header<T: std::io::Write>(
     Ok(out.count)
 }
 
-fn entries<T: std::io::Write>(out: &mut CountBytes<T>, state: &State, header_size: u32) -> Result<u32, std::io::Error> {
+fn entries<T: std::io::Write>(
+    out: &mut CountBytes<T>,
+    state: &State,
+    version: Version,
+    header_size: u32,
+) -> Result<u32, std::io::Error> {
+    if version == Version::V4 {
+        let mut previous_path = "".into();
+        for entry in state.entries() {
+            if entry.flags.contains(entry::Flags::REMOVE) {
+                continue;
+            }
+            entry.write_to_v4(&mut *out, state, previous_path)?;
+            previous_path = entry.path(state);
+        }
+        return Ok(out.count);
+    }
+
     for entry in state.entries() {
         if entry.flags.contains(entry::Flags::REMOVE) {
             continue;
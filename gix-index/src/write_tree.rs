@@ -0,0 +1,110 @@
+use bstr::{BStr, BString, ByteSlice};
+use gix_object::tree::EntryKind;
+
+use crate::{entry::Mode, Entry, State};
+
+/// The error returned by [`State::write_tree()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    #[error("Entry at '{path}' is unmerged at stage {stage} and can't be written into a tree")]
+    Unmerged { path: BString, stage: crate::entry::Stage },
+    #[error("Entry at '{path}' has a mode that can't be represented in a tree")]
+    InvalidMode { path: BString },
+    #[error(transparent)]
+    Write(E),
+}
+
+/// Conversion
+impl State {
+    /// Write all entries of this index as hierarchy of trees, calling `write` for each tree starting with the leaves,
+    /// and return the id of the root tree.
+    ///
+    /// `write` is expected to store the given tree in an object database and return its object id.
+    /// This is similar to what `git write-tree` does, and fails if there are entries that still have
+    /// conflicts, i.e. are at a stage other than 0.
+    /// Entries that are marked as *intent-to-add* or that are scheduled for removal are skipped.
+    ///
+    /// Note that the cache-tree extension isn't used to speed up the operation, and isn't updated either.
+    pub fn write_tree<E>(
+        &self,
+        mut write: impl FnMut(&gix_object::Tree) -> Result<gix_hash::ObjectId, E>,
+    ) -> Result<gix_hash::ObjectId, Error<E>>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let _span = gix_features::trace::coarse!("gix_index::State::write_tree()");
+        let mut entries = Vec::with_capacity(self.entries.len());
+        for entry in &self.entries {
+            let stage = entry.stage();
+            if stage != 0 {
+                return Err(Error::Unmerged {
+                    path: entry.path(self).to_owned(),
+                    stage,
+                });
+            }
+            if entry
+                .flags
+                .intersects(crate::entry::Flags::INTENT_TO_ADD | crate::entry::Flags::REMOVE)
+            {
+                continue;
+            }
+            entries.push(entry);
+        }
+        write_recursive(self, &entries, 0, &mut write)
+    }
+}
+
+fn write_recursive<E>(
+    state: &State,
+    entries: &[&Entry],
+    prefix_len: usize,
+    write: &mut dyn FnMut(&gix_object::Tree) -> Result<gix_hash::ObjectId, E>,
+) -> Result<gix_hash::ObjectId, Error<E>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut tree = gix_object::Tree::empty();
+    let mut cursor = 0;
+    while let Some(entry) = entries.get(cursor) {
+        let path = entry.path(state);
+        let rela_path: &BStr = path[prefix_len..].as_bstr();
+        match rela_path.find_byte(b'/') {
+            Some(slash_pos) => {
+                let dir = &rela_path[..slash_pos + 1];
+                let end = entries[cursor..]
+                    .iter()
+                    .position(|e| !e.path(state)[prefix_len..].starts_with(dir))
+                    .map_or(entries.len(), |pos| cursor + pos);
+                let oid = write_recursive(state, &entries[cursor..end], prefix_len + dir.len(), write)?;
+                tree.entries.push(gix_object::tree::Entry {
+                    mode: EntryKind::Tree.into(),
+                    filename: dir[..slash_pos].into(),
+                    oid,
+                });
+                cursor = end;
+            }
+            None => {
+                let mode = if entry.mode == Mode::DIR {
+                    EntryKind::Tree.into()
+                } else {
+                    entry
+                        .mode
+                        .to_tree_entry_mode()
+                        .ok_or_else(|| Error::InvalidMode { path: path.to_owned() })?
+                };
+                tree.entries.push(gix_object::tree::Entry {
+                    mode,
+                    filename: rela_path.to_owned(),
+                    oid: entry.id,
+                });
+                cursor += 1;
+            }
+        }
+    }
+    tree.entries.sort();
+    write(&tree).map_err(Error::Write)
+}
@@ -310,3 +310,58 @@ fn check_prefix(index: &gix_index::State, prefix: &str, expected: &[&str]) {
         "{prefix:?}"
     );
 }
+
+#[test]
+fn smudge_racily_clean_entries() -> crate::Result {
+    use gix_index::entry::{stat, Flags, Mode, Stat};
+
+    let mut state = gix_index::State::new(gix_hash::Kind::Sha1);
+    state.set_timestamp(filetime::FileTime::from_unix_time(100, 0));
+    let stat_at = |secs: u32, size: u32| Stat {
+        mtime: stat::Time { secs, nsecs: 0 },
+        size,
+        ..Default::default()
+    };
+    let id = gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha1);
+    for (path, stat, mode) in [
+        ("racy", stat_at(100, 3), Mode::FILE),
+        ("racy-but-unchanged", stat_at(101, 3), Mode::FILE),
+        ("older", stat_at(99, 3), Mode::FILE),
+        ("smudged-already", stat_at(100, 0), Mode::FILE),
+        ("submodule", stat_at(100, 3), Mode::COMMIT),
+    ] {
+        state.dangerously_push_entry(stat, id, Flags::empty(), mode, path.into());
+    }
+
+    let options = stat::Options::default();
+    assert!(state.is_racy(&state.entries()[0], options));
+    assert!(!state.is_racy(&state.entries()[2], options));
+    assert!(
+        !state.is_racy(&state.entries()[4], options),
+        "submodules are never racy"
+    );
+
+    let mut seen = Vec::new();
+    let smudged =
+        state.smudge_racily_clean_entries(options, |state, entry| -> Result<_, std::convert::Infallible> {
+            let path = entry.path(state).to_owned();
+            let is_modified = path == "racy";
+            seen.push(path);
+            Ok(is_modified)
+        })?;
+    assert_eq!(smudged, 1);
+    assert_eq!(seen, ["racy", "racy-but-unchanged"], "only racy entries are checked");
+    let sizes: Vec<_> = state.entries().iter().map(|e| e.stat.size).collect();
+    assert_eq!(
+        sizes,
+        [0, 3, 3, 0, 3],
+        "only racy entries with changed content are smudged"
+    );
+
+    state.set_timestamp(filetime::FileTime::zero());
+    assert!(
+        !state.is_racy(&state.entries()[1], options),
+        "without timestamp, nothing is racy as the index wasn't written yet"
+    );
+    Ok(())
+}
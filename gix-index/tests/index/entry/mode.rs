@@ -12,3 +12,69 @@ fn apply() {
         Mode::SYMLINK
     );
 }
+
+#[cfg(unix)]
+mod from_fs {
+    use std::os::unix::fs::PermissionsExt;
+
+    use gix_index::{entry::Mode, fs::Metadata};
+
+    fn metadata_of_executable_file_and_symlink(
+    ) -> gix_testtools::Result<(gix_testtools::tempfile::TempDir, Metadata, Metadata)> {
+        let dir = gix_testtools::tempfile::tempdir()?;
+        let file = dir.path().join("file");
+        std::fs::write(&file, b"content")?;
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o755))?;
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink("file", &link)?;
+        let file = Metadata::from_path_no_follow(&file)?;
+        let link = Metadata::from_path_no_follow(&link)?;
+        Ok((dir, file, link))
+    }
+
+    #[test]
+    fn executable_bit_and_symlinks_are_taken_from_the_previous_mode_if_unsupported() -> gix_testtools::Result {
+        let (_dir, file, link) = metadata_of_executable_file_and_symlink()?;
+        assert_eq!(Mode::from_fs(&file, true, true, None), Mode::FILE_EXECUTABLE);
+        assert_eq!(Mode::from_fs(&file, true, false, None), Mode::FILE);
+        assert_eq!(
+            Mode::from_fs(&file, true, false, Some(Mode::FILE_EXECUTABLE)),
+            Mode::FILE_EXECUTABLE
+        );
+        assert_eq!(Mode::from_fs(&file, false, true, Some(Mode::SYMLINK)), Mode::SYMLINK);
+        assert_eq!(
+            Mode::from_fs(&file, true, true, Some(Mode::SYMLINK)),
+            Mode::FILE_EXECUTABLE
+        );
+
+        assert_eq!(Mode::from_fs(&link, true, true, None), Mode::SYMLINK);
+        assert_eq!(
+            Mode::from_fs(&link, false, true, None),
+            Mode::FILE,
+            "symlinks are treated like files if unsupported, but their permissions are meaningless"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn type_changes_of_executables_are_detected_and_produce_fitting_modes() -> gix_testtools::Result {
+        let (_dir, file, link) = metadata_of_executable_file_and_symlink()?;
+        assert!(matches!(
+            Mode::FILE_EXECUTABLE.change_to_match_fs(&link, true, true),
+            Some(gix_index::entry::mode::Change::Type { new_mode }) if new_mode == Mode::SYMLINK
+        ));
+        assert!(matches!(
+            Mode::SYMLINK.change_to_match_fs(&file, true, true),
+            Some(gix_index::entry::mode::Change::Type { new_mode }) if new_mode == Mode::FILE_EXECUTABLE
+        ));
+        assert!(
+            Mode::SYMLINK.change_to_match_fs(&file, false, true).is_none(),
+            "without symlink support, files represent symlinks"
+        );
+        assert!(
+            Mode::FILE.change_to_match_fs(&file, true, false).is_none(),
+            "without executable bit support, the executable bit isn't compared"
+        );
+        Ok(())
+    }
+}
@@ -108,6 +108,17 @@ mod matches {
             ),
             "ctime nsecs are different so stat doesn't match (trust_ctime=true,use_nsec=false)"
         );
+        stat2.ctime.secs = 3;
+        assert!(
+            stat1.matches(
+                &stat2,
+                Options {
+                    check_stat: false,
+                    ..Default::default()
+                },
+            ),
+            "ctime isn't used at all with check_stat=false, just like core.checkStat=minimal in git"
+        );
     }
 
     #[test]
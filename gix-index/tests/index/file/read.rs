@@ -197,7 +197,14 @@ fn untr_extension_with_oids() {
     let file = loose_file("UNTR-with-oids");
     assert_eq!(file.version(), Version::V2);
 
-    assert!(file.untracked().is_some());
+    let untracked = file.untracked().expect("present");
+    assert_eq!(untracked.exclude_filename_per_dir(), ".gitignore");
+    assert_eq!(
+        untracked.dir_flags(),
+        gix_index::extension::untracked_cache::DirFlags::SHOW_OTHER_DIRECTORIES
+            | gix_index::extension::untracked_cache::DirFlags::HIDE_EMPTY_DIRECTORIES
+    );
+    assert_eq!(untracked.directories()[0].name, "", "the first directory is the root");
 }
 
 #[test]
@@ -20,6 +20,7 @@ fn roundtrips() -> crate::Result {
         (Generated("V2_empty"), only_tree_ext()),
         (Generated("v2_more_files"), only_tree_ext()),
         (Generated("v2_all_file_kinds"), only_tree_ext()),
+        (Generated("v4_more_files"), only_tree_ext()),
     ];
 
     for (fixture, options) in input {
@@ -78,6 +79,23 @@ fn skip_hash() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn writing_updates_the_timestamp_to_that_of_the_written_file() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join("index");
+    let mut file = Loose("conflicting-file").open();
+    file.set_path(&path);
+    file.set_timestamp(FileTime::zero());
+
+    file.write(Options::default())?;
+    assert_eq!(
+        file.timestamp(),
+        FileTime::from_last_modification_time(&std::fs::metadata(&path)?),
+        "entries modified after the write are racy, just like after reading the index"
+    );
+    Ok(())
+}
+
 #[test]
 fn roundtrips_sparse_index() -> crate::Result {
     // NOTE: I initially tried putting these fixtures into the main roundtrip test above,
@@ -133,15 +151,13 @@ fn state_comparisons_with_various_extension_configurations() {
         Generated("v2_more_files"),
         Generated("v2_all_file_kinds"),
         Generated("v2_split_index"),
-        // TODO: this fails because git allows to configure the index version while gitoxide doesn't
-        //       the fixture artificially sets the version to V4 and gitoxide writes it back out as the lowest required version, V2
-        // Generated("v4_more_files_IEOT"),
+        Generated("v4_more_files_IEOT"),
+        Generated("v4_more_files"),
         Generated("v3_skip_worktree"),
         Generated("v3_added_files"),
         Generated("v3_sparse_index_non_cone"),
         Generated("v3_sparse_index"),
-        // TODO: this fails because git writes the sdir extension in this case while gitoxide doesn't
-        // Generated("v2_sparse_index_no_dirs"),
+        Generated("v2_sparse_index_no_dirs"),
     ] {
         for options in [
             options_with(write::Extensions::None),
@@ -168,6 +184,48 @@ fn state_comparisons_with_various_extension_configurations() {
     }
 }
 
+#[test]
+fn fs_monitor_extension_is_written_with_all_extensions() -> crate::Result {
+    let mut expected = Loose("FSMN").open();
+    let mut out = Vec::<u8>::new();
+    expected.write_to(&mut out, options_with(write::Extensions::All))?;
+    let (actual, _) = State::from_bytes(&out, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    let (actual, expected_fsmn) = (
+        actual.fs_monitor().expect("written"),
+        expected.fs_monitor().expect("present"),
+    );
+    assert_eq!(actual.token(), expected_fsmn.token());
+    assert_eq!(actual.entry_dirty().num_bits(), expected_fsmn.entry_dirty().num_bits());
+
+    expected.set_fs_monitor(Some(extension::FsMonitor::new(
+        extension::fs_monitor::Token::V2 { token: "token".into() },
+        gix_bitmap::ewah::Vec::from_words(&[0b101], 3),
+    )));
+    out.clear();
+    expected.write_to(&mut out, options_with(write::Extensions::All))?;
+    let (actual, _) = State::from_bytes(&out, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    let actual = actual.fs_monitor().expect("written");
+    assert_eq!(
+        actual.token(),
+        &extension::fs_monitor::Token::V2 { token: "token".into() }
+    );
+    let mut set_bits = Vec::new();
+    actual.entry_dirty().for_each_set_bit(|idx| {
+        set_bits.push(idx);
+        Some(())
+    });
+    assert_eq!(set_bits, [0, 2]);
+
+    out.clear();
+    expected.write_to(&mut out, only_tree_ext())?;
+    let (actual, _) = State::from_bytes(&out, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    assert!(
+        actual.fs_monitor().is_none(),
+        "only written if all extensions are desired"
+    );
+    Ok(())
+}
+
 #[test]
 fn extended_flags_automatically_upgrade_the_version_to_avoid_data_loss() -> crate::Result {
     let mut expected = Generated("v2").open();
@@ -181,6 +239,26 @@ fn extended_flags_automatically_upgrade_the_version_to_avoid_data_loss() -> crat
     Ok(())
 }
 
+#[test]
+fn v4_is_retained_and_writes_prefix_compressed_paths() -> crate::Result {
+    let mut index = Generated("v2_more_files").open();
+    let mut v2_bytes = Vec::new();
+    index.write_to(&mut v2_bytes, only_tree_ext())?;
+
+    index.set_version(Version::V4);
+    let mut v4_bytes = Vec::new();
+    let (actual_version, _digest) = index.write_to(&mut v4_bytes, only_tree_ext())?;
+    assert_eq!(actual_version, Version::V4, "V4 is never downgraded");
+    assert!(
+        v4_bytes.len() < v2_bytes.len(),
+        "paths share prefixes and there is no padding, so V4 is smaller"
+    );
+
+    let (actual, _) = State::from_bytes(&v4_bytes, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    compare_states_against_baseline(&actual, actual_version, &index, only_tree_ext(), "v2_more_files as V4");
+    Ok(())
+}
+
 #[test]
 fn remove_flag_is_respected() -> crate::Result {
     let mut index = Generated("v4_more_files_IEOT").open();
@@ -210,6 +288,269 @@ fn remove_flag_is_respected() -> crate::Result {
     Ok(())
 }
 
+mod write_back {
+    use filetime::FileTime;
+    use gix_index::{
+        file::write::{Error, Outcome},
+        write::{Options, WriteBack},
+    };
+
+    use crate::index::Fixture::Loose;
+
+    fn written_to_tempdir() -> crate::Result<(gix_testtools::tempfile::TempDir, gix_index::File)> {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let mut file = Loose("conflicting-file").open();
+        file.set_path(tmp.path().join("index"));
+        file.write(Options::default())?;
+        Ok((tmp, file))
+    }
+
+    fn change(file: &mut gix_index::File) {
+        file.entries_mut()[0].stat.size += 1;
+    }
+
+    #[test]
+    fn if_unchanged_writes_and_remains_possible_after_own_writes() -> crate::Result {
+        let (_tmp, mut file) = written_to_tempdir()?;
+        change(&mut file);
+        assert_eq!(
+            file.write_back(Options::default(), WriteBack::IfUnchanged)?,
+            Outcome::Written
+        );
+        change(&mut file);
+        assert_eq!(
+            file.write_back(Options::default(), WriteBack::IfUnchanged)?,
+            Outcome::Written,
+            "our own write is the new baseline"
+        );
+
+        let actual = gix_index::File::at(file.path(), gix_hash::Kind::Sha1, false, Default::default())?;
+        assert_eq!(actual.entries()[0].stat, file.entries()[0].stat);
+        Ok(())
+    }
+
+    #[test]
+    fn never_does_nothing() -> crate::Result {
+        let (_tmp, mut file) = written_to_tempdir()?;
+        let checksum = file.checksum();
+        change(&mut file);
+        assert_eq!(
+            file.write_back(Options::default(), WriteBack::Never)?,
+            Outcome::Disabled
+        );
+        let actual = gix_index::File::at(file.path(), gix_hash::Kind::Sha1, false, Default::default())?;
+        assert_eq!(actual.checksum(), checksum, "the file on disk is unchanged");
+        Ok(())
+    }
+
+    #[test]
+    fn a_held_lock_skips_the_write_unless_it_is_required() -> crate::Result {
+        let (tmp, mut file) = written_to_tempdir()?;
+        let lock_path = tmp.path().join("index.lock");
+        std::fs::write(&lock_path, b"held by an explicit writer")?;
+
+        change(&mut file);
+        assert_eq!(
+            file.write_back(Options::default(), WriteBack::IfUnchanged)?,
+            Outcome::Locked
+        );
+        assert!(
+            matches!(
+                file.write_back(Options::default(), WriteBack::Always),
+                Err(Error::AcquireLock(_))
+            ),
+            "explicit writes fail as before"
+        );
+        assert_eq!(
+            std::fs::read(&lock_path)?,
+            b"held by an explicit writer",
+            "the lock of the other writer is untouched"
+        );
+
+        std::fs::remove_file(&lock_path)?;
+        assert_eq!(
+            file.write_back(Options::default(), WriteBack::IfUnchanged)?,
+            Outcome::Written
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn changes_by_other_writers_are_not_overwritten() -> crate::Result {
+        let (_tmp, mut file) = written_to_tempdir()?;
+        let mut other = gix_index::File::at(file.path(), gix_hash::Kind::Sha1, false, Default::default())?;
+        other.entries_mut()[0].stat.size += 42;
+        other.write(Options::default())?;
+
+        change(&mut file);
+        assert_eq!(
+            file.write_back(Options::default(), WriteBack::IfUnchanged)?,
+            Outcome::Changed
+        );
+        let actual = gix_index::File::at(file.path(), gix_hash::Kind::Sha1, false, Default::default())?;
+        assert_eq!(actual.checksum(), other.checksum(), "the other write was retained");
+
+        assert_eq!(
+            file.write_back(Options::default(), WriteBack::Always)?,
+            Outcome::Written,
+            "it's possible to enforce the write"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn without_checksum_the_modification_time_is_used() -> crate::Result {
+        let (_tmp, mut file) = written_to_tempdir()?;
+        let options = Options {
+            skip_hash: true,
+            ..Default::default()
+        };
+        file.write(options)?;
+        let mut file = gix_index::File::at(file.path(), gix_hash::Kind::Sha1, false, Default::default())?;
+        assert_eq!(file.checksum(), None);
+
+        change(&mut file);
+        assert_eq!(file.write_back(options, WriteBack::IfUnchanged)?, Outcome::Written);
+
+        filetime::set_file_mtime(file.path(), FileTime::from_unix_time(1, 0))?;
+        change(&mut file);
+        assert_eq!(
+            file.write_back(options, WriteBack::IfUnchanged)?,
+            Outcome::Changed,
+            "a touched file is assumed to be changed"
+        );
+        Ok(())
+    }
+}
+
+mod split {
+    use std::path::Path;
+
+    use filetime::FileTime;
+    use gix_index::{write::Options, State};
+
+    fn split_and_regular_index() -> crate::Result<(gix_testtools::tempfile::TempDir, std::path::PathBuf)> {
+        let base = gix_testtools::scripted_fixture_read_only_standalone(
+            Path::new("make_index").join("v2_split_vs_regular_index.sh"),
+        )?;
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        for entry in std::fs::read_dir(base.join("split/.git"))? {
+            let entry = entry?;
+            let name = entry.file_name();
+            if name == "index" || name.to_string_lossy().starts_with("sharedindex.") {
+                std::fs::copy(entry.path(), tmp.path().join(name))?;
+            }
+        }
+        Ok((tmp, base.join("regular/.git/index")))
+    }
+
+    fn open(path: &Path) -> crate::Result<gix_index::File> {
+        Ok(gix_index::File::at(
+            path,
+            gix_hash::Kind::Sha1,
+            false,
+            Default::default(),
+        )?)
+    }
+
+    fn raw_state(path: &Path) -> crate::Result<State> {
+        Ok(State::from_bytes(
+            &std::fs::read(path)?,
+            FileTime::now(),
+            gix_hash::Kind::Sha1,
+            Default::default(),
+        )?
+        .0)
+    }
+
+    fn num_shared_indices(dir: &Path) -> crate::Result<usize> {
+        Ok(std::fs::read_dir(dir)?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("sharedindex."))
+            .count())
+    }
+
+    fn assert_entries_eq(actual: &State, expected: &State) {
+        assert_eq!(actual.entries().len(), expected.entries().len());
+        for (a, e) in actual.entries().iter().zip(expected.entries()) {
+            assert_eq!(a.path(actual), e.path(expected));
+            assert_eq!((a.stat, a.id, a.flags, a.mode), (e.stat, e.id, e.flags, e.mode));
+        }
+    }
+
+    #[test]
+    fn changes_are_stored_in_the_split_index_and_the_shared_index_is_reused() -> crate::Result {
+        let (tmp, _regular) = split_and_regular_index()?;
+        let path = tmp.path().join("index");
+        let mut index = open(&path)?;
+        let shared_index_checksum = index.shared_index_checksum().expect("read from split index");
+
+        index.entries_mut()[0].stat.size += 1;
+        let last = index.entries().len() - 1;
+        index.entries_mut()[last].flags.insert(gix_index::entry::Flags::REMOVE);
+        index.write_split(Options::default(), 100)?;
+        assert_eq!(
+            index.shared_index_checksum(),
+            Some(shared_index_checksum),
+            "the shared index is reused"
+        );
+        assert_eq!(num_shared_indices(tmp.path())?, 1, "no new shared index was written");
+
+        let split = raw_state(&path)?;
+        let link = split.link().expect("written as split index");
+        assert_eq!(link.shared_index_checksum, shared_index_checksum);
+        assert!(
+            split.entries().len() < index.entries().len() - 1,
+            "only changed and new entries are stored"
+        );
+
+        index.remove_entries(|_, _, entry| entry.flags.contains(gix_index::entry::Flags::REMOVE));
+        let actual = open(&path)?;
+        assert_eq!(actual.shared_index_checksum(), Some(shared_index_checksum));
+        assert_entries_eq(&actual, &index);
+        Ok(())
+    }
+
+    #[test]
+    fn a_new_shared_index_is_written_if_there_is_none_or_too_much_changed() -> crate::Result {
+        let (tmp, regular) = split_and_regular_index()?;
+        let path = tmp.path().join("index");
+        let mut index = open(&regular)?;
+        assert_eq!(index.shared_index_checksum(), None, "a regular index");
+        index.set_path(&path);
+
+        index.write_split(Options::default(), 20)?;
+        let shared_index_checksum = index.shared_index_checksum().expect("written as split index");
+        assert!(tmp
+            .path()
+            .join(format!("sharedindex.{shared_index_checksum}"))
+            .is_file());
+        assert_eq!(num_shared_indices(tmp.path())?, 2);
+        let split = raw_state(&path)?;
+        assert_eq!(split.entries().len(), 0, "all entries are in the shared index");
+        assert_eq!(split.tree(), index.tree(), "extensions are stored in the split index");
+        assert_entries_eq(&*open(&path)?, &index);
+
+        for entry in index.entries_mut() {
+            entry.stat.size += 1;
+        }
+        index.write_split(Options::default(), 20)?;
+        assert_ne!(
+            index.shared_index_checksum(),
+            Some(shared_index_checksum),
+            "too many changes lead to a new shared index"
+        );
+        assert_eq!(num_shared_indices(tmp.path())?, 3);
+        assert_eq!(raw_state(&path)?.entries().len(), 0);
+        assert_entries_eq(&*open(&path)?, &index);
+
+        index.write(Options::default())?;
+        assert_eq!(index.shared_index_checksum(), None, "written as regular index");
+        assert!(raw_state(&path)?.link().is_none());
+        Ok(())
+    }
+}
+
 fn compare_states_against_baseline(
     actual: &State,
     actual_version: Version,
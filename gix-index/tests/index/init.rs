@@ -24,6 +24,44 @@ fn from_tree() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn write_tree_is_inverse_of_from_tree() -> crate::Result {
+    for fixture in ["make_index/v2_more_files.sh", "make_index/v2_all_file_kinds.sh"] {
+        let repo_dir = scripted_fixture_read_only_standalone(fixture)?;
+        let repo = gix::open(&repo_dir)?;
+
+        let tree_id = repo.head_commit()?.tree_id()?;
+        let state = State::from_tree(&tree_id, &repo.objects)?;
+        let actual = state.write_tree(|tree| {
+            use gix::objs::WriteTo;
+            let mut buf = Vec::new();
+            tree.write_to(&mut buf)?;
+            Ok::<_, std::io::Error>(gix::objs::compute_hash(repo.object_hash(), tree.kind(), &buf))
+        })?;
+        assert_eq!(
+            actual, tree_id,
+            "{fixture}: the tree written from an index matches the one it was created from"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn write_tree_fails_on_unmerged_entries() {
+    let mut state = State::new(gix_hash::Kind::Sha1);
+    state.dangerously_push_entry(
+        Default::default(),
+        gix_hash::Kind::Sha1.null(),
+        gix_index::entry::Flags::from_bits_retain(2 << 12),
+        gix_index::entry::Mode::FILE,
+        "conflicted".into(),
+    );
+    let err = state
+        .write_tree(|_| Ok::<_, std::io::Error>(gix_hash::Kind::Sha1.null()))
+        .unwrap_err();
+    assert!(matches!(err, gix_index::write_tree::Error::Unmerged { stage: 2, .. }));
+}
+
 #[test]
 fn new() {
     let state = State::new(gix_hash::Kind::Sha1);
@@ -0,0 +1,170 @@
+use std::path::{Path, PathBuf};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+use crate::blob::Resolution;
+
+/// A merge driver as defined by `merge.<name>.driver` in the git configuration, for use with files that have
+/// the `merge=<name>` attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Driver {
+    /// The name of the driver, as used in the `merge` attribute.
+    pub name: BString,
+    /// A human-readable name of the driver, as set by `merge.<name>.name`.
+    pub display_name: Option<BString>,
+    /// The command to run, as set by `merge.<name>.driver`.
+    ///
+    /// The placeholders `%O`, `%A` and `%B` are substituted with the paths to temporary files containing the content of
+    /// the common ancestor, *ours* and *theirs*, respectively, while `%L` is the size of conflict markers and `%P` is the
+    /// path of the merged file. The command is expected to write the merged content into the file at `%A`, and to exit
+    /// with a non-zero status if there are conflicts.
+    pub command: BString,
+    /// The name of the driver to use when merging common ancestors, as set by `merge.<name>.recursive`.
+    pub recursive: Option<BString>,
+}
+
+/// Information to pass along when [applying](Driver::apply()) a driver.
+#[derive(Debug, Clone)]
+pub struct Context<'a> {
+    /// The repository-relative path of the file that is merged, substituted for `%P`.
+    pub path: &'a BStr,
+    /// The amount of characters to use for conflict markers, substituted for `%L`.
+    pub marker_size: usize,
+    /// The directory to place the temporary files for each side of the merge into.
+    pub temp_dir: &'a Path,
+    /// Additional information to pass to the command, like the location of the repository.
+    pub command: gix_command::Context,
+}
+
+///
+pub mod apply {
+    /// The error returned by [Driver::apply()](super::Driver::apply()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not write the content of a side of the merge into a temporary file")]
+        WriteTempfile(#[source] std::io::Error),
+        #[error("Could not spawn the merge driver {command:?}")]
+        SpawnCommand {
+            source: std::io::Error,
+            command: std::process::Command,
+        },
+        #[error("The merge driver {command:?} was terminated by a signal")]
+        Terminated { command: std::process::Command },
+        #[error("Could not read the result of the merge driver from '{}'", path.display())]
+        ReadResult {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+
+impl Driver {
+    /// Run the command of this driver to merge `base`, `ours` and `theirs` of the file at `context.path`, and write
+    /// the merged content into `out`, which is cleared beforehand.
+    ///
+    /// The merge is considered [conflicting](Resolution::Conflict) if the command exits with a non-zero status, just like in `git`.
+    #[allow(clippy::result_large_err)]
+    pub fn apply(
+        &self,
+        out: &mut Vec<u8>,
+        base: &[u8],
+        ours: &[u8],
+        theirs: &[u8],
+        context: Context<'_>,
+    ) -> Result<Resolution, apply::Error> {
+        let base_file = write_tempfile(context.temp_dir, base)?;
+        let ours_file = write_tempfile(context.temp_dir, ours)?;
+        let theirs_file = write_tempfile(context.temp_dir, theirs)?;
+
+        let cmd = substitute(
+            self.command.as_ref(),
+            [&base_file.1, &ours_file.1, &theirs_file.1],
+            context.marker_size,
+            context.path,
+        );
+        let mut cmd: std::process::Command = gix_command::prepare(gix_path::from_bstring(cmd))
+            .with_shell()
+            .with_context(context.command)
+            .stdin(std::process::Stdio::null())
+            .into();
+        gix_trace::debug!(cmd = ?cmd, "launching merge driver");
+        let status = match cmd.status() {
+            Ok(status) => status,
+            Err(err) => {
+                return Err(apply::Error::SpawnCommand {
+                    source: err,
+                    command: cmd,
+                })
+            }
+        };
+        if status.code().is_none() {
+            return Err(apply::Error::Terminated { command: cmd });
+        }
+
+        out.clear();
+        out.extend(std::fs::read(&ours_file.1).map_err(|err| apply::Error::ReadResult {
+            path: ours_file.1.clone(),
+            source: err,
+        })?);
+        Ok(if status.success() {
+            Resolution::Complete
+        } else {
+            Resolution::Conflict
+        })
+    }
+}
+
+/// Write `data` into a new temporary file in `dir`, and return it along with its path.
+/// The file is removed once the handle is dropped.
+#[allow(clippy::result_large_err)]
+fn write_tempfile(
+    dir: &Path,
+    data: &[u8],
+) -> Result<(gix_tempfile::Handle<gix_tempfile::handle::Writable>, PathBuf), apply::Error> {
+    use std::io::Write;
+
+    let mut file = gix_tempfile::new(
+        dir,
+        gix_tempfile::ContainingDirectory::Exists,
+        gix_tempfile::AutoRemove::Tempfile,
+    )
+    .map_err(apply::Error::WriteTempfile)?;
+    file.write_all(data).map_err(apply::Error::WriteTempfile)?;
+    let path = file
+        .with_mut(|file| {
+            file.as_file().flush()?;
+            Ok::<_, std::io::Error>(file.path().to_owned())
+        })
+        .and_then(|res| res)
+        .map_err(apply::Error::WriteTempfile)?;
+    Ok((file, path))
+}
+
+/// Substitute all placeholders in `command`, with `paths` being the paths to the files of the common ancestor, *ours* and
+/// *theirs*.
+fn substitute(command: &BStr, paths: [&PathBuf; 3], marker_size: usize, path: &BStr) -> BString {
+    let mut out = BString::default();
+    let mut bytes = command.iter().copied();
+    while let Some(b) = bytes.next() {
+        if b != b'%' {
+            out.push(b);
+            continue;
+        }
+        match bytes.next() {
+            Some(b'O') => out.push_str(gix_quote::single(gix_path::into_bstr(paths[0].as_path()).as_ref())),
+            Some(b'A') => out.push_str(gix_quote::single(gix_path::into_bstr(paths[1].as_path()).as_ref())),
+            Some(b'B') => out.push_str(gix_quote::single(gix_path::into_bstr(paths[2].as_path()).as_ref())),
+            Some(b'L') => out.push_str(marker_size.to_string()),
+            Some(b'P') => out.push_str(gix_quote::single(path)),
+            Some(b'%') => out.push(b'%'),
+            Some(other) => {
+                out.push(b'%');
+                out.push(other);
+            }
+            None => out.push(b'%'),
+        }
+    }
+    debug_assert!(out.find_byte(0).is_none(), "commands can't contain null bytes");
+    out
+}
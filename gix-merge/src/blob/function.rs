@@ -0,0 +1,223 @@
+use std::ops::Range;
+
+use bstr::{BStr, ByteSlice};
+use imara_diff::intern::InternedInput;
+
+use crate::blob::{is_binary, Algorithm, ConflictStyle, Labels, Options, Resolution};
+
+/// Perform a three-way merge of the lines in `ours` and `theirs`, with `base` being their common ancestor,
+/// and write the result into `out` which is cleared beforehand.
+///
+/// Changes from both sides that don't touch each other are applied without conflict. Changes that overlap or are adjacent
+/// to each other are only merged if they are the same, and are marked as conflict with `labels` otherwise.
+///
+/// If any of the inputs is [binary](is_binary()), no merge is attempted and `ours` is written to `out`, along with a
+/// [conflicting resolution](Resolution::Conflict).
+pub fn blob(
+    out: &mut Vec<u8>,
+    base: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+    labels: Labels<'_>,
+    options: Options,
+) -> Resolution {
+    merge(out, base, ours, theirs, labels, options, false)
+}
+
+/// Like [`blob()`], but if `union` is `true`, overlapping changes are resolved by keeping the lines of both sides.
+pub(super) fn merge(
+    out: &mut Vec<u8>,
+    base: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+    labels: Labels<'_>,
+    options: Options,
+    union: bool,
+) -> Resolution {
+    out.clear();
+    if is_binary(base) || is_binary(ours) || is_binary(theirs) {
+        out.extend_from_slice(ours);
+        return Resolution::Conflict;
+    }
+
+    let mut hunks = Vec::new();
+    collect_hunks(options.algorithm, base, ours, Side::Ours, &mut hunks);
+    collect_hunks(options.algorithm, base, theirs, Side::Theirs, &mut hunks);
+    hunks.sort_by_key(|h| (h.before.start, h.before.end));
+
+    let base_lines: Vec<_> = base.lines_with_terminator().collect();
+    let ours_lines: Vec<_> = ours.lines_with_terminator().collect();
+    let theirs_lines: Vec<_> = theirs.lines_with_terminator().collect();
+
+    let mut resolution = Resolution::Complete;
+    let (mut ours_offset, mut theirs_offset) = (0_i64, 0_i64);
+    let mut base_pos = 0;
+    let mut cursor = 0;
+    while cursor < hunks.len() {
+        let start = hunks[cursor].before.start;
+        let mut end = hunks[cursor].before.end;
+        let mut cluster_end = cursor + 1;
+        // Changes that are adjacent or overlapping in the base are considered together, and are conflicting if
+        // they come from different sides.
+        while cluster_end < hunks.len() && hunks[cluster_end].before.start <= end {
+            end = end.max(hunks[cluster_end].before.end);
+            cluster_end += 1;
+        }
+        let cluster = &hunks[cursor..cluster_end];
+
+        extend_lines(out, &base_lines[base_pos..start as usize]);
+        let ours_delta = cluster
+            .iter()
+            .filter(|h| h.side == Side::Ours)
+            .map(Hunk::delta)
+            .sum::<i64>();
+        let theirs_delta = cluster
+            .iter()
+            .filter(|h| h.side == Side::Theirs)
+            .map(Hunk::delta)
+            .sum::<i64>();
+        let ours_range = shifted(start..end, ours_offset, ours_delta);
+        let theirs_range = shifted(start..end, theirs_offset, theirs_delta);
+
+        let has_ours = cluster.iter().any(|h| h.side == Side::Ours);
+        let has_theirs = cluster.iter().any(|h| h.side == Side::Theirs);
+        match (has_ours, has_theirs) {
+            (true, false) => extend_lines(out, &ours_lines[ours_range]),
+            (false, true) => extend_lines(out, &theirs_lines[theirs_range]),
+            _ => {
+                let (ours, theirs) = (&ours_lines[ours_range], &theirs_lines[theirs_range]);
+                if ours == theirs {
+                    extend_lines(out, ours);
+                } else if union {
+                    write_union(out, ours, theirs);
+                } else {
+                    resolution = Resolution::Conflict;
+                    write_conflict(
+                        out,
+                        ours,
+                        &base_lines[start as usize..end as usize],
+                        theirs,
+                        labels,
+                        options,
+                    );
+                }
+            }
+        }
+
+        ours_offset += ours_delta;
+        theirs_offset += theirs_delta;
+        base_pos = end as usize;
+        cursor = cluster_end;
+    }
+    extend_lines(out, &base_lines[base_pos..]);
+    resolution
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Side {
+    Ours,
+    Theirs,
+}
+
+struct Hunk {
+    before: Range<u32>,
+    after: Range<u32>,
+    side: Side,
+}
+
+impl Hunk {
+    fn delta(&self) -> i64 {
+        self.after.len() as i64 - self.before.len() as i64
+    }
+}
+
+fn collect_hunks(algorithm: Algorithm, base: &[u8], side_data: &[u8], side: Side, out: &mut Vec<Hunk>) {
+    let input = InternedInput::new(
+        imara_diff::sources::byte_lines_with_terminator(base),
+        imara_diff::sources::byte_lines_with_terminator(side_data),
+    );
+    imara_diff::diff(algorithm, &input, |before: Range<u32>, after: Range<u32>| {
+        out.push(Hunk { before, after, side })
+    });
+}
+
+fn shifted(base_range: Range<u32>, offset: i64, delta: i64) -> Range<usize> {
+    (base_range.start as i64 + offset) as usize..(base_range.end as i64 + offset + delta) as usize
+}
+
+fn extend_lines(out: &mut Vec<u8>, lines: &[&[u8]]) {
+    for line in lines {
+        out.extend_from_slice(line);
+    }
+}
+
+fn write_conflict(
+    out: &mut Vec<u8>,
+    mut ours: &[&[u8]],
+    base: &[&[u8]],
+    mut theirs: &[&[u8]],
+    labels: Labels<'_>,
+    options: Options,
+) {
+    let mut suffix: &[&[u8]] = &[];
+    if options.conflict_style == ConflictStyle::Merge {
+        let common_prefix = ours.iter().zip(theirs).take_while(|(a, b)| a == b).count();
+        extend_lines(out, &ours[..common_prefix]);
+        ours = &ours[common_prefix..];
+        theirs = &theirs[common_prefix..];
+
+        let common_suffix = ours
+            .iter()
+            .rev()
+            .zip(theirs.iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        suffix = &ours[ours.len() - common_suffix..];
+        ours = &ours[..ours.len() - common_suffix];
+        theirs = &theirs[..theirs.len() - common_suffix];
+    }
+
+    write_marker(out, b'<', labels.current, options.marker_size);
+    extend_lines(out, ours);
+    if options.conflict_style == ConflictStyle::Diff3 {
+        write_marker(out, b'|', labels.ancestor, options.marker_size);
+        extend_lines(out, base);
+    }
+    write_marker(out, b'=', None, options.marker_size);
+    extend_lines(out, theirs);
+    write_marker(out, b'>', labels.other, options.marker_size);
+    extend_lines(out, suffix);
+}
+
+/// Write the lines of `ours` followed by the lines of `theirs`, with lines they have in common at their beginning
+/// and end written only once.
+fn write_union(out: &mut Vec<u8>, ours: &[&[u8]], theirs: &[&[u8]]) {
+    let common_prefix = ours.iter().zip(theirs).take_while(|(a, b)| a == b).count();
+    extend_lines(out, &ours[..common_prefix]);
+    let (ours, theirs) = (&ours[common_prefix..], &theirs[common_prefix..]);
+    let common_suffix = ours
+        .iter()
+        .rev()
+        .zip(theirs.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (ours, suffix) = ours.split_at(ours.len() - common_suffix);
+    for line in ours.iter().chain(&theirs[..theirs.len() - common_suffix]).chain(suffix) {
+        if !out.is_empty() && !out.ends_with(b"\n") {
+            out.push(b'\n');
+        }
+        out.extend_from_slice(line);
+    }
+}
+
+fn write_marker(out: &mut Vec<u8>, marker: u8, label: Option<&BStr>, marker_size: usize) {
+    if !out.is_empty() && !out.ends_with(b"\n") {
+        out.push(b'\n');
+    }
+    out.extend(std::iter::repeat(marker).take(marker_size));
+    if let Some(label) = label {
+        out.push(b' ');
+        out.extend_from_slice(label);
+    }
+    out.push(b'\n');
+}
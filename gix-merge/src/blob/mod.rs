@@ -0,0 +1,119 @@
+use bstr::{BStr, ByteSlice};
+pub use imara_diff::Algorithm;
+
+///
+pub mod driver;
+pub use driver::Driver;
+
+/// Determine how conflicts are represented in the merged output.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ConflictStyle {
+    /// Only show the conflicting lines of *ours* and *theirs*, without the lines of their common ancestor.
+    ///
+    /// Lines that are the same on both sides at the beginning and the end of a conflict are moved out of the conflict markers.
+    #[default]
+    Merge,
+    /// Show the conflicting lines of *ours* and *theirs*, along with the lines of their common ancestor.
+    Diff3,
+}
+
+/// Options for use in [`blob()`](crate::blob()).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The algorithm to use when diffing the common ancestor with each side.
+    pub algorithm: Algorithm,
+    /// How to represent conflicts.
+    pub conflict_style: ConflictStyle,
+    /// The amount of characters to use for each conflict marker, like `<<<<<<<`.
+    pub marker_size: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            algorithm: Algorithm::Histogram,
+            conflict_style: Default::default(),
+            marker_size: 7,
+        }
+    }
+}
+
+/// The labels to write after conflict markers to identify where the conflicting lines are coming from.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Labels<'a> {
+    /// The label for the common ancestor, shown only with [`ConflictStyle::Diff3`].
+    pub ancestor: Option<&'a BStr>,
+    /// The label for *our* side of the merge, typically the current branch.
+    pub current: Option<&'a BStr>,
+    /// The label for *their* side of the merge, typically the commit that is merged.
+    pub other: Option<&'a BStr>,
+}
+
+/// The outcome of a [`blob()`](crate::blob()) merge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Resolution {
+    /// All changes could be merged without conflict.
+    Complete,
+    /// At least one conflict was encountered, which is marked in the output with conflict markers.
+    ///
+    /// Binary files are always considered conflicting if both sides changed them, with the output being the content of *ours*.
+    Conflict,
+}
+
+/// A built-in way of merging the content of blobs, as selected by the `merge` attribute.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BuiltinDriver {
+    /// Merge line by line and mark overlapping changes as conflict, which is what `merge` and `merge=text` select,
+    /// and what's used if the attribute isn't specified.
+    #[default]
+    Text,
+    /// Don't merge at all, but keep the content of *ours* and report a conflict, as selected by `-merge` and `merge=binary`.
+    Binary,
+    /// Merge line by line, but resolve overlapping changes by keeping the lines of *ours* followed by the ones of *theirs*,
+    /// as selected by `merge=union`.
+    ///
+    /// Note that this never conflicts unless the inputs are binary.
+    Union,
+}
+
+impl BuiltinDriver {
+    /// Return the driver that is selected by the value `name` of the `merge` attribute, or `None` if it isn't built-in.
+    pub fn from_name(name: &BStr) -> Option<Self> {
+        Some(match name.as_bytes() {
+            b"text" => BuiltinDriver::Text,
+            b"binary" => BuiltinDriver::Binary,
+            b"union" => BuiltinDriver::Union,
+            _ => return None,
+        })
+    }
+
+    /// Merge `base`, `ours` and `theirs` into `out` like this driver would, with `labels` and `options` being passed
+    /// to [`blob()`](crate::blob()).
+    pub fn merge(
+        self,
+        out: &mut Vec<u8>,
+        base: &[u8],
+        ours: &[u8],
+        theirs: &[u8],
+        labels: Labels<'_>,
+        options: Options,
+    ) -> Resolution {
+        match self {
+            BuiltinDriver::Text => function::merge(out, base, ours, theirs, labels, options, false),
+            BuiltinDriver::Binary => {
+                out.clear();
+                out.extend_from_slice(ours);
+                Resolution::Conflict
+            }
+            BuiltinDriver::Union => function::merge(out, base, ours, theirs, labels, options, true),
+        }
+    }
+}
+
+/// Return `true` if `data` is considered binary, which is the case if it contains a null-byte in the first 8000 bytes,
+/// just like git does it.
+pub fn is_binary(data: &[u8]) -> bool {
+    data[..data.len().min(8000)].contains(&0)
+}
+
+pub(super) mod function;
@@ -0,0 +1,15 @@
+//! Perform three-way merges of blobs and trees, the building block for higher-level operations like
+//! `cherry-pick`, `revert`, `rebase` and `merge`.
+//!
+//! Merges are performed in memory only, and it's up to the caller to persist the results, be it by writing the merged
+//! blobs and trees into the object database, or by updating the index and worktree with the outcome.
+#![deny(missing_docs, rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+///
+pub mod blob;
+pub use blob::function::blob;
+
+///
+pub mod tree;
+pub use tree::function::tree;
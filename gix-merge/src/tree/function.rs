@@ -0,0 +1,536 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_hash::{oid, ObjectId};
+use gix_index::entry::{Flags, Mode, Stat};
+use gix_object::FindExt;
+use imara_diff::intern::InternedInput;
+
+use crate::{
+    blob,
+    tree::{BlobInput, Conflict, ConflictKind, DirectoryRenames, Error, Options, Outcome, Renames, Strategy, Version},
+};
+
+/// All entries of a tree, sorted by path.
+type Entries = Vec<(BString, Version)>;
+
+/// Perform a three-way merge of the trees `ours` and `theirs`, with `base` being the tree of their common ancestor,
+/// and return the merged index along with all conflicts.
+///
+/// Use `objects` to read trees and blobs, and `write_blob` to store merged blobs, returning their ids.
+/// `merge_blob` is called to merge the content of files that were changed on both sides into the given buffer,
+/// which allows to select a merge driver by path. Use [`BlobInput::merge_builtin()`] to merge like `git` does by default.
+/// `labels` are used to annotate conflict markers in blobs that were changed on both sides.
+///
+/// Entries are merged by path, unless the [strategy](Options::strategy) says otherwise. If only one side changed an entry
+/// compared to `base`, that change is taken. If both sides changed a file, its content is merged with `merge_blob`,
+/// and a conflict is recorded if that fails. Deletions on one side paired with modifications on the other are always conflicting.
+///
+/// If [renames](Options::renames) are detected, a file that was renamed on one side is merged with the changes of the other
+/// side at its new location. Files that were renamed on both sides to different locations are kept at both locations.
+/// Otherwise, renames appear as deletion and addition.
+/// Files that were added on one side to a directory that was renamed on the other side are handled according to
+/// [`Renames::directories`].
+#[allow(clippy::too_many_arguments)]
+pub fn tree<E, M>(
+    base: &oid,
+    ours: &oid,
+    theirs: &oid,
+    objects: &impl gix_object::Find,
+    mut write_blob: impl FnMut(&[u8]) -> Result<ObjectId, E>,
+    mut merge_blob: impl FnMut(BlobInput<'_>, &mut Vec<u8>) -> Result<blob::Resolution, M>,
+    labels: blob::Labels<'_>,
+    options: Options,
+) -> Result<Outcome, Error>
+where
+    E: std::error::Error + Send + Sync + 'static,
+    M: std::error::Error + Send + Sync + 'static,
+{
+    let object_hash = base.kind();
+    match options.strategy {
+        Strategy::Ours => return Ok(unconflicted(flatten(ours, objects)?, object_hash)),
+        Strategy::Theirs => return Ok(unconflicted(flatten(theirs, objects)?, object_hash)),
+        Strategy::Ort | Strategy::Subtree { .. } => {}
+    }
+    let mut base = flatten(base, objects)?;
+    let mut ours = flatten(ours, objects)?;
+    let mut theirs = flatten(theirs, objects)?;
+    if let Strategy::Subtree { prefix } = &options.strategy {
+        if let Some(prefix) = prefix.clone().or_else(|| guess_subtree_prefix(&ours, &theirs)) {
+            base = shifted(base, prefix.as_ref());
+            theirs = shifted(theirs, prefix.as_ref());
+        }
+    }
+
+    let (mut buf_base, mut buf_ours, mut buf_theirs, mut buf_out) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    let mut conflicts = Vec::new();
+    if let Some(renames) = options.renames {
+        let ours_renames = detect_renames(&base, &ours, renames, objects, &mut buf_base, &mut buf_ours)?;
+        let theirs_renames = detect_renames(&base, &theirs, renames, objects, &mut buf_base, &mut buf_theirs)?;
+        apply_renames(&mut base, &mut theirs, &ours_renames, &theirs_renames);
+        apply_renames(&mut base, &mut ours, &theirs_renames, &ours_renames);
+
+        if renames.directories != DirectoryRenames::Ignore {
+            let ours_directory_renames = directory_renames(&ours_renames, &ours);
+            let theirs_directory_renames = directory_renames(&theirs_renames, &theirs);
+            let theirs_relocated = relocate_added_files(&base, &mut theirs, &ours, &ours_directory_renames);
+            let ours_relocated = relocate_added_files(&base, &mut ours, &theirs, &theirs_directory_renames);
+            if renames.directories == DirectoryRenames::Conflict {
+                location_conflicts(&mut ours, ours_relocated, true, &mut conflicts);
+                location_conflicts(&mut theirs, theirs_relocated, false, &mut conflicts);
+            }
+        }
+    }
+
+    let mut merged: Entries = Vec::new();
+    let (mut base_cursor, mut ours_cursor, mut theirs_cursor) = (0, 0, 0);
+    while let Some(path) = [
+        path_at(&base, base_cursor),
+        path_at(&ours, ours_cursor),
+        path_at(&theirs, theirs_cursor),
+    ]
+    .into_iter()
+    .flatten()
+    .min()
+    .map(ToOwned::to_owned)
+    {
+        let b = take_if_at(&base, &mut base_cursor, path.as_ref());
+        let o = take_if_at(&ours, &mut ours_cursor, path.as_ref());
+        let t = take_if_at(&theirs, &mut theirs_cursor, path.as_ref());
+
+        let resolved = if o == t {
+            Ok(o)
+        } else if b == o {
+            Ok(t)
+        } else if b == t {
+            Ok(o)
+        } else {
+            Err(())
+        };
+        match resolved {
+            Ok(Some(version)) => merged.push((path, version)),
+            Ok(None) => {}
+            Err(()) => {
+                let kind = match (b, o, t) {
+                    (None, _, _) => ConflictKind::BothAdded,
+                    (Some(_), None, _) => ConflictKind::DeletedByUs,
+                    (Some(_), _, None) => ConflictKind::DeletedByThem,
+                    (Some(_), Some(_), Some(_)) => ConflictKind::BothModified,
+                };
+                let (ours_version, theirs_version) = match (o, t) {
+                    (Some(o), Some(t)) if is_file(o.mode) && is_file(t.mode) => (o, t),
+                    _ => {
+                        conflicts.push(Conflict {
+                            path,
+                            kind,
+                            base: b,
+                            ours: o,
+                            theirs: t,
+                            merged_blob: None,
+                            original_path: None,
+                        });
+                        continue;
+                    }
+                };
+
+                let base_data: &[u8] = match b {
+                    Some(b) if is_file(b.mode) => objects.find_blob(&b.id, &mut buf_base)?.data,
+                    _ => &[],
+                };
+                let ours_data = objects.find_blob(&ours_version.id, &mut buf_ours)?.data;
+                let theirs_data = objects.find_blob(&theirs_version.id, &mut buf_theirs)?.data;
+                let input = BlobInput {
+                    path: path.as_ref(),
+                    base: base_data,
+                    ours: ours_data,
+                    theirs: theirs_data,
+                    labels,
+                    options: options.blob,
+                };
+                let resolution = merge_blob(input, &mut buf_out).map_err(|err| Error::MergeBlob {
+                    path: path.clone(),
+                    source: Box::new(err),
+                })?;
+                let id = write_blob(&buf_out).map_err(|err| Error::WriteBlob {
+                    path: path.clone(),
+                    source: Box::new(err),
+                })?;
+
+                let mode = if ours_version.mode == theirs_version.mode {
+                    Some(ours_version.mode)
+                } else if b.map(|b| b.mode) == Some(ours_version.mode) {
+                    Some(theirs_version.mode)
+                } else if b.map(|b| b.mode) == Some(theirs_version.mode) {
+                    Some(ours_version.mode)
+                } else {
+                    None
+                };
+                match (resolution, mode) {
+                    (blob::Resolution::Complete, Some(mode)) => merged.push((path, Version { mode, id })),
+                    _ => conflicts.push(Conflict {
+                        path,
+                        kind,
+                        base: b,
+                        ours: o,
+                        theirs: t,
+                        merged_blob: Some(id),
+                        original_path: None,
+                    }),
+                }
+            }
+        }
+    }
+
+    move_files_in_the_way_of_directories(&mut merged, &mut conflicts, &ours);
+
+    let mut index = gix_index::State::new(object_hash);
+    for (path, version) in &merged {
+        index.dangerously_push_entry(Stat::default(), version.id, Flags::empty(), version.mode, path.as_ref());
+    }
+    for conflict in &conflicts {
+        for (stage, version) in [(1, conflict.base), (2, conflict.ours), (3, conflict.theirs)] {
+            if let Some(version) = version {
+                index.dangerously_push_entry(
+                    Stat::default(),
+                    version.id,
+                    Flags::from_bits_retain(stage << 12),
+                    version.mode,
+                    conflict.path.as_ref(),
+                );
+            }
+        }
+    }
+    index.sort_entries();
+    conflicts.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(Outcome { index, conflicts })
+}
+
+fn flatten(tree: &oid, objects: &impl gix_object::Find) -> Result<Entries, Error> {
+    if tree == ObjectId::empty_tree(tree.kind()) {
+        return Ok(Vec::new());
+    }
+    let state = gix_index::State::from_tree(tree, objects).map_err(|err| Error::Traverse {
+        id: tree.to_owned(),
+        source: err,
+    })?;
+    Ok(state
+        .entries()
+        .iter()
+        .map(|e| (e.path(&state).to_owned(), Version { mode: e.mode, id: e.id }))
+        .collect())
+}
+
+/// Produce an outcome that has all `entries` merged, without conflicts.
+fn unconflicted(entries: Entries, object_hash: gix_hash::Kind) -> Outcome {
+    let mut index = gix_index::State::new(object_hash);
+    for (path, version) in &entries {
+        index.dangerously_push_entry(Stat::default(), version.id, Flags::empty(), version.mode, path.as_ref());
+    }
+    index.sort_entries();
+    Outcome {
+        index,
+        conflicts: Vec::new(),
+    }
+}
+
+fn path_at(entries: &Entries, idx: usize) -> Option<&BStr> {
+    entries.get(idx).map(|(path, _)| path.as_ref())
+}
+
+fn take_if_at(entries: &Entries, cursor: &mut usize, path: &BStr) -> Option<Version> {
+    let (_, version) = entries.get(*cursor).filter(|(p, _)| p == path)?;
+    *cursor += 1;
+    Some(*version)
+}
+
+fn find(entries: &Entries, path: &BStr) -> Result<usize, usize> {
+    entries.binary_search_by(|(p, _)| p.as_bstr().cmp(path))
+}
+
+fn is_file(mode: Mode) -> bool {
+    matches!(mode, Mode::FILE | Mode::FILE_EXECUTABLE)
+}
+
+/// Move all `entries` into the directory at `prefix`.
+fn shifted(entries: Entries, prefix: &BStr) -> Entries {
+    entries
+        .into_iter()
+        .map(|(path, version)| {
+            let mut shifted = prefix.to_owned();
+            shifted.push_byte(b'/');
+            shifted.push_str(path);
+            (shifted, version)
+        })
+        .collect()
+}
+
+/// Find the directory in `ours` that has the most paths in common with the root of `theirs`.
+fn guess_subtree_prefix(ours: &Entries, theirs: &Entries) -> Option<BString> {
+    let mut matches_by_prefix = BTreeMap::<&BStr, usize>::new();
+    for (path, _) in ours {
+        for pos in path.find_iter("/") {
+            if find(theirs, path[pos + 1..].as_bstr()).is_ok() {
+                *matches_by_prefix.entry(path[..pos].as_bstr()).or_default() += 1;
+            }
+        }
+    }
+    matches_by_prefix
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(prefix, _)| prefix.to_owned())
+}
+
+/// Return all files that were renamed in `side` compared to `base` as pairs of `(source, destination)`.
+fn detect_renames(
+    base: &Entries,
+    side: &Entries,
+    renames: Renames,
+    objects: &impl gix_object::Find,
+    buf_base: &mut Vec<u8>,
+    buf_side: &mut Vec<u8>,
+) -> Result<Vec<(BString, BString)>, Error> {
+    let mut deleted: Vec<_> = base
+        .iter()
+        .filter(|(path, version)| is_file(version.mode) && find(side, path.as_ref()).is_err())
+        .collect();
+    let mut added: Vec<_> = side
+        .iter()
+        .filter(|(path, version)| is_file(version.mode) && find(base, path.as_ref()).is_err())
+        .collect();
+
+    let mut out = Vec::new();
+    deleted.retain(|(source, source_version)| {
+        match added.iter().position(|(_, version)| version.id == source_version.id) {
+            Some(pos) => {
+                out.push((source.clone(), added.remove(pos).0.clone()));
+                false
+            }
+            None => true,
+        }
+    });
+
+    let percentage = match renames.percentage {
+        Some(percentage) if renames.limit == 0 || deleted.len() * added.len() <= renames.limit => percentage,
+        _ => return Ok(out),
+    };
+    for (source, source_version) in deleted {
+        let source_data = objects.find_blob(&source_version.id, buf_base)?.data;
+        if blob::is_binary(source_data) {
+            continue;
+        }
+        let mut best = None;
+        for (pos, (_, version)) in added.iter().enumerate() {
+            let data = objects.find_blob(&version.id, buf_side)?.data;
+            if blob::is_binary(data) {
+                continue;
+            }
+            let score = similarity(source_data, data);
+            if score >= percentage && best.map_or(true, |(_, best_score)| score > best_score) {
+                best = Some((pos, score));
+            }
+        }
+        if let Some((pos, _)) = best {
+            out.push((source.clone(), added.remove(pos).0.clone()));
+        }
+    }
+    Ok(out)
+}
+
+/// Return the fraction of lines that `a` and `b` have in common, relative to the larger of both.
+fn similarity(a: &[u8], b: &[u8]) -> f32 {
+    let input = InternedInput::new(
+        imara_diff::sources::byte_lines_with_terminator(a),
+        imara_diff::sources::byte_lines_with_terminator(b),
+    );
+    let total = input.before.len().max(input.after.len());
+    if total == 0 {
+        return 1.0;
+    }
+    let mut removed = 0;
+    imara_diff::diff(
+        imara_diff::Algorithm::Histogram,
+        &input,
+        |before: Range<u32>, _after: Range<u32>| removed += before.len(),
+    );
+    (input.before.len() - removed) as f32 / total as f32
+}
+
+/// Move the entries of `base` and `other` to the destination of each of the `renames` of a side, so changes in `other` are
+/// merged with the renamed entry. `other_renames` are the renames of `other`, which prevent a move if they have the same source.
+fn apply_renames(
+    base: &mut Entries,
+    other: &mut Entries,
+    renames: &[(BString, BString)],
+    other_renames: &[(BString, BString)],
+) {
+    for (source, destination) in renames {
+        match other_renames.iter().find(|(other_source, _)| other_source == source) {
+            Some((_, other_destination)) if other_destination == destination => {}
+            Some(_) => continue,
+            None => move_entry(other, source.as_ref(), destination.as_ref()),
+        }
+        move_entry(base, source.as_ref(), destination.as_ref());
+    }
+}
+
+/// Move the entry at `source` in `entries` to `destination`, unless `source` doesn't exist or `destination` does.
+fn move_entry(entries: &mut Entries, source: &BStr, destination: &BStr) {
+    let Ok(source_pos) = find(entries, source) else {
+        return;
+    };
+    if find(entries, destination).is_ok() {
+        return;
+    }
+    let (_, version) = entries.remove(source_pos);
+    let pos = find(entries, destination).unwrap_err();
+    entries.insert(pos, (destination.to_owned(), version));
+}
+
+/// Return the directories that were renamed as part of the file `renames` of `side`, as pairs of `(source, destination)`.
+///
+/// A directory is considered renamed if it doesn't exist anymore on `side`, and if most of its files were renamed into
+/// the same directory. If there is no such majority, it's not considered renamed.
+fn directory_renames(renames: &[(BString, BString)], side: &Entries) -> Vec<(BString, BString)> {
+    let mut counts = BTreeMap::<&BStr, BTreeMap<&BStr, usize>>::new();
+    for (source, destination) in renames {
+        if let Some((source, destination)) = renamed_directory(source.as_ref(), destination.as_ref()) {
+            *counts.entry(source).or_default().entry(destination).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .filter(|(source, _)| !has_directory(side, source))
+        .filter_map(|(source, destinations)| {
+            let max = destinations.values().copied().max()?;
+            let mut candidates = destinations.into_iter().filter(|(_, count)| *count == max);
+            let (destination, _) = candidates.next()?;
+            candidates
+                .next()
+                .is_none()
+                .then(|| (source.to_owned(), destination.to_owned()))
+        })
+        .collect()
+}
+
+/// Return the directories of `source` and `destination` without the trailing components they have in common,
+/// or `None` if the directory didn't change or one of them is at the root.
+fn renamed_directory<'a>(source: &'a BStr, destination: &'a BStr) -> Option<(&'a BStr, &'a BStr)> {
+    let mut source = source[..source.rfind_byte(b'/')?].as_bstr();
+    let mut destination = destination[..destination.rfind_byte(b'/')?].as_bstr();
+    while let (Some(source_pos), Some(destination_pos)) = (source.rfind_byte(b'/'), destination.rfind_byte(b'/')) {
+        if source[source_pos..] != destination[destination_pos..] {
+            break;
+        }
+        source = source[..source_pos].as_bstr();
+        destination = destination[..destination_pos].as_bstr();
+    }
+    (source != destination).then_some((source, destination))
+}
+
+/// Return `true` if there is at least one entry in the directory `dir` of `entries`.
+fn has_directory(entries: &Entries, dir: &BStr) -> bool {
+    let mut prefix = dir.to_owned();
+    prefix.push_byte(b'/');
+    let pos = entries.partition_point(|(path, _)| path.as_bstr() < prefix.as_bstr());
+    entries.get(pos).map_or(false, |(path, _)| path.starts_with(&prefix))
+}
+
+/// Move the files of `side` that were added compared to `base` into the directory they were renamed to according to
+/// `directory_renames` of the `other` side, and return their paths as pairs of `(source, destination)`.
+///
+/// Files are only moved if their destination isn't used on any side.
+fn relocate_added_files(
+    base: &Entries,
+    side: &mut Entries,
+    other: &Entries,
+    directory_renames: &[(BString, BString)],
+) -> Vec<(BString, BString)> {
+    if directory_renames.is_empty() {
+        return Vec::new();
+    }
+    let mut out = Vec::new();
+    for (path, _) in side.iter().filter(|(path, _)| find(base, path.as_ref()).is_err()) {
+        let Some((source, destination)) = directory_renames
+            .iter()
+            .filter(|(source, _)| path.get(source.len()) == Some(&b'/') && path.starts_with(source))
+            .max_by_key(|(source, _)| source.len())
+        else {
+            continue;
+        };
+        let mut new_path = destination.clone();
+        new_path.push_str(&path[source.len()..]);
+        if [base, &*side, other]
+            .into_iter()
+            .any(|entries| find(entries, new_path.as_ref()).is_ok())
+        {
+            continue;
+        }
+        out.push((path.clone(), new_path));
+    }
+    for (source, destination) in &out {
+        move_entry(side, source.as_ref(), destination.as_ref());
+    }
+    out
+}
+
+/// Take the `relocated` files out of `side` and turn them into [`ConflictKind::FileLocation`] conflicts at their new location.
+fn location_conflicts(
+    side: &mut Entries,
+    relocated: Vec<(BString, BString)>,
+    is_ours: bool,
+    conflicts: &mut Vec<Conflict>,
+) {
+    for (source, destination) in relocated {
+        let Ok(pos) = find(side, destination.as_ref()) else {
+            continue;
+        };
+        let (path, version) = side.remove(pos);
+        conflicts.push(Conflict {
+            path,
+            kind: ConflictKind::FileLocation,
+            base: None,
+            ours: is_ours.then_some(version),
+            theirs: (!is_ours).then_some(version),
+            merged_blob: None,
+            original_path: Some(source),
+        });
+    }
+}
+
+/// As trees are merged by path only, a file on one side may now be in the place of a directory on the other side.
+/// Such files are turned into conflicts to keep the merged entries representable as tree.
+fn move_files_in_the_way_of_directories(merged: &mut Entries, conflicts: &mut Vec<Conflict>, ours: &Entries) {
+    let mut all_paths: Vec<&BStr> = merged
+        .iter()
+        .map(|(path, _)| path.as_ref())
+        .chain(conflicts.iter().map(|c| c.path.as_ref()))
+        .collect();
+    all_paths.sort();
+    let mut in_the_way = Vec::new();
+    for (path, version) in merged.iter() {
+        let mut dir = path.clone();
+        dir.push_byte(b'/');
+        let pos = all_paths.partition_point(|p| *p < dir.as_bstr());
+        if all_paths.get(pos).map_or(false, |p| p.starts_with(&dir)) {
+            in_the_way.push((path.clone(), *version));
+        }
+    }
+    if in_the_way.is_empty() {
+        return;
+    }
+    merged.retain(|(path, _)| !in_the_way.iter().any(|(p, _)| p == path));
+    for (path, version) in in_the_way {
+        let is_ours = find(ours, path.as_ref()).map_or(false, |pos| ours[pos].1 == version);
+        conflicts.push(Conflict {
+            path,
+            kind: ConflictKind::FileDirectory,
+            base: None,
+            ours: is_ours.then_some(version),
+            theirs: (!is_ours).then_some(version),
+            merged_blob: None,
+            original_path: None,
+        });
+    }
+}
@@ -0,0 +1,233 @@
+use bstr::{BStr, BString, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::blob;
+
+/// Options for use in [`tree()`](crate::tree()).
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Options {
+    /// The options to use when merging the content of blobs that were changed on both sides.
+    pub blob: crate::blob::Options,
+    /// The way trees are merged, similar to `git merge --strategy`.
+    pub strategy: Strategy,
+    /// If set, files that were renamed on one side are detected so that changes to them on the other side are merged into
+    /// the renamed file. Otherwise, renames appear as deletion and addition.
+    pub renames: Option<Renames>,
+}
+
+/// Determine how trees are merged, similar to the strategies `git merge` can use.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// Merge both sides path by path, and detect renames if [configured](Options::renames), similar to the
+    /// `ort` and `recursive` strategies of `git`.
+    #[default]
+    Ort,
+    /// Keep the tree of *ours* and ignore all changes of *theirs*, which never conflicts.
+    Ours,
+    /// Keep the tree of *theirs* and ignore all changes of *ours*, which never conflicts.
+    Theirs,
+    /// Like [`Ort`](Strategy::Ort), but with the trees of *theirs* and the common ancestor moved into the directory
+    /// at `prefix` first, to merge a project that is a subtree of *ours*.
+    ///
+    /// If `prefix` is `None`, the directory of *ours* that has most paths in common with *theirs* is used.
+    /// If there is none, the trees are merged without moving them.
+    Subtree {
+        /// The directory to move the trees of *theirs* and the common ancestor into, without trailing slash.
+        prefix: Option<BString>,
+    },
+}
+
+impl Strategy {
+    /// Return the strategy that is selected by `name`, like `ours` or `subtree=lib/vendored`, or `None` if it's unknown.
+    ///
+    /// `recursive` and `octopus` are aliases for `ort`, as merges of multiple commits merge each of them with `ort`.
+    pub fn from_name(name: &BStr) -> Option<Self> {
+        Some(match name.as_bytes() {
+            b"ort" | b"recursive" | b"octopus" => Strategy::Ort,
+            b"ours" => Strategy::Ours,
+            b"theirs" => Strategy::Theirs,
+            b"subtree" => Strategy::Subtree { prefix: None },
+            other => {
+                let prefix = other.strip_prefix(b"subtree=")?.trim_with(|c| c == '/');
+                Strategy::Subtree {
+                    prefix: (!prefix.is_empty()).then(|| prefix.into()),
+                }
+            }
+        })
+    }
+}
+
+/// Control how renames are detected when [merging trees](crate::tree()).
+///
+/// Directories are considered renamed if all of their files were renamed into another directory on one side, which
+/// affects files that were added to them on the other side as configured by [`directories`](Self::directories).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Renames {
+    /// The percentage of similarity needed for files to be considered renamed, defaulting to `Some(0.5)`, similar to
+    /// `git merge -Xfind-renames=50%`.
+    ///
+    /// If `None`, only files with identical content are considered renamed.
+    pub percentage: Option<f32>,
+    /// The maximum amount of deleted files multiplied with the amount of added files on one side to compare their content
+    /// for finding inexact renames, defaulting to `1000²`. If there are more, only files with identical content are
+    /// considered renamed. `0` means there is no limit.
+    pub limit: usize,
+    /// How to handle files that were added on one side to a directory that was renamed on the other side,
+    /// similar to `merge.directoryRenames`.
+    pub directories: DirectoryRenames,
+}
+
+impl Default for Renames {
+    fn default() -> Self {
+        Renames {
+            percentage: Some(0.5),
+            limit: 1000 * 1000,
+            directories: Default::default(),
+        }
+    }
+}
+
+/// Determine what happens to files that were added on one side to a directory that was renamed on the other side,
+/// similar to the values of `merge.directoryRenames`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DirectoryRenames {
+    /// Don't detect renamed directories, so added files stay where they were added, like `false`.
+    Ignore,
+    /// Move added files into the renamed directory, like `true`.
+    Apply,
+    /// Move added files into the renamed directory, but record a [`ConflictKind::FileLocation`] conflict so the new location
+    /// is confirmed by the user, like `conflict`, which is also the default.
+    #[default]
+    Conflict,
+}
+
+impl DirectoryRenames {
+    /// Return the mode that is selected by the value of `merge.directoryRenames`, or `None` if it's unknown.
+    pub fn from_name(name: &BStr) -> Option<Self> {
+        Some(match name.as_bytes() {
+            b"false" | b"no" | b"off" | b"0" => DirectoryRenames::Ignore,
+            b"true" | b"yes" | b"on" | b"1" => DirectoryRenames::Apply,
+            b"conflict" => DirectoryRenames::Conflict,
+            _ => return None,
+        })
+    }
+}
+
+/// The outcome of a [`tree()`](crate::tree()) merge.
+#[derive(Clone)]
+pub struct Outcome {
+    /// The merged index, with all entries that could be merged at stage 0, and conflicting entries at
+    /// stage 1 (common ancestor), 2 (ours) and 3 (theirs) respectively, just like `git` would represent them.
+    ///
+    /// If there are no [conflicts](Self::conflicts), it can be [written as tree](gix_index::State::write_tree()).
+    pub index: gix_index::State,
+    /// All conflicts we encountered, in order of their path.
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Outcome {
+    /// Return `true` if there was at least one conflict.
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+}
+
+/// The content of a file that was changed on both sides, as passed to [`tree()`](crate::tree()) for merging it.
+#[derive(Debug, Copy, Clone)]
+pub struct BlobInput<'a> {
+    /// The repository-relative path of the file, useful to lookup its `merge` attribute.
+    pub path: &'a BStr,
+    /// The content of the file in the common ancestor, which is empty if it didn't exist there.
+    pub base: &'a [u8],
+    /// The content of the file on our side.
+    pub ours: &'a [u8],
+    /// The content of the file on their side.
+    pub theirs: &'a [u8],
+    /// The labels to use for conflict markers.
+    pub labels: blob::Labels<'a>,
+    /// The options for merging the content.
+    pub options: blob::Options,
+}
+
+impl BlobInput<'_> {
+    /// Merge the content with the built-in `driver` into `out`.
+    pub fn merge_builtin(&self, out: &mut Vec<u8>, driver: blob::BuiltinDriver) -> blob::Resolution {
+        driver.merge(out, self.base, self.ours, self.theirs, self.labels, self.options)
+    }
+}
+
+/// The version of an entry on one side of the merge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Version {
+    /// The mode of the entry.
+    pub mode: gix_index::entry::Mode,
+    /// The id of the object the entry points to.
+    pub id: ObjectId,
+}
+
+/// Classify a [`Conflict`], using the same terms as `git status`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ConflictKind {
+    /// Both sides modified the entry in different ways.
+    BothModified,
+    /// Both sides added an entry at the same path, with different content.
+    BothAdded,
+    /// We deleted the entry, while they modified it.
+    DeletedByUs,
+    /// They deleted the entry, while we modified it.
+    DeletedByThem,
+    /// A file on one side is in the way of a directory on the other side.
+    FileDirectory,
+    /// A file was added on one side to a directory that was renamed on the other side, and was moved into the renamed
+    /// directory. Its new location should be confirmed, as it may also belong where it was added.
+    ///
+    /// The path it was added at is available as [`Conflict::original_path`].
+    FileLocation,
+}
+
+/// A path that couldn't be merged automatically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// The repository-relative path of the conflicting entry.
+    pub path: BString,
+    /// The kind of conflict.
+    pub kind: ConflictKind,
+    /// The version of the entry in the common ancestor, if present.
+    pub base: Option<Version>,
+    /// The version of the entry on our side, if present.
+    pub ours: Option<Version>,
+    /// The version of the entry on their side, if present.
+    pub theirs: Option<Version>,
+    /// The id of a blob with the merged content, including conflict markers, which is suitable for placing into the worktree.
+    ///
+    /// It's `None` if no content-merge was attempted, for instance because the kinds of the entries are incompatible.
+    pub merged_blob: Option<ObjectId>,
+    /// The path the entry had on its side before it was moved to [`path`](Self::path), which is only set for
+    /// [`ConflictKind::FileLocation`] conflicts.
+    pub original_path: Option<BString>,
+}
+
+/// The error returned by [`tree()`](crate::tree()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not traverse tree {id} to merge it")]
+    Traverse {
+        id: ObjectId,
+        source: gix_traverse::tree::breadthfirst::Error,
+    },
+    #[error(transparent)]
+    FindBlob(#[from] gix_object::find::existing_object::Error),
+    #[error("Could not merge the content of '{path}'")]
+    MergeBlob {
+        path: BString,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("Could not write merged blob at '{path}'")]
+    WriteBlob {
+        path: BString,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+}
+
+pub(super) mod function;
@@ -0,0 +1,195 @@
+use gix_merge::blob::{ConflictStyle, Labels, Options, Resolution};
+
+fn merge(base: &str, ours: &str, theirs: &str, options: Options) -> (String, Resolution) {
+    let mut out = Vec::new();
+    let labels = Labels {
+        ancestor: Some("base".into()),
+        current: Some("ours".into()),
+        other: Some("theirs".into()),
+    };
+    let res = gix_merge::blob(
+        &mut out,
+        base.as_bytes(),
+        ours.as_bytes(),
+        theirs.as_bytes(),
+        labels,
+        options,
+    );
+    (String::from_utf8(out).expect("valid UTF8"), res)
+}
+
+#[test]
+fn non_overlapping_changes_are_combined() {
+    let (out, res) = merge(
+        "a\nb\nc\nd\ne\n",
+        "A\nb\nc\nd\ne\n",
+        "a\nb\nc\nd\nE\n",
+        Default::default(),
+    );
+    assert_eq!(res, Resolution::Complete);
+    assert_eq!(out, "A\nb\nc\nd\nE\n");
+}
+
+#[test]
+fn identical_changes_are_taken_once() {
+    let (out, res) = merge("a\nb\nc\n", "a\nB\nc\n", "a\nB\nc\n", Default::default());
+    assert_eq!(res, Resolution::Complete);
+    assert_eq!(out, "a\nB\nc\n");
+}
+
+#[test]
+fn insertions_at_the_end_without_trailing_newline() {
+    let (out, res) = merge("a\nb\nc", "a\nb\nc\nd", "A\nb\nc", Default::default());
+    assert_eq!(res, Resolution::Complete);
+    assert_eq!(out, "A\nb\nc\nd");
+}
+
+#[test]
+fn conflicts_are_marked_with_common_lines_moved_out() {
+    let (out, res) = merge("a\nb\nc\n", "a\nx\nsame\nc\n", "a\ny\nsame\nc\n", Default::default());
+    assert_eq!(res, Resolution::Conflict);
+    assert_eq!(
+        out, "a\n<<<<<<< ours\nx\n=======\ny\n>>>>>>> theirs\nsame\nc\n",
+        "the common suffix isn't part of the conflict"
+    );
+}
+
+#[test]
+fn adjacent_changes_are_conflicting() {
+    let (out, res) = merge("a\nb\nc\n", "A\nb\nc\n", "a\nB\nc\n", Default::default());
+    assert_eq!(res, Resolution::Conflict);
+    assert_eq!(out, "<<<<<<< ours\nA\nb\n=======\na\nB\n>>>>>>> theirs\nc\n");
+}
+
+#[test]
+fn diff3_style_shows_the_ancestor() {
+    let options = Options {
+        conflict_style: ConflictStyle::Diff3,
+        marker_size: 3,
+        ..Default::default()
+    };
+    let (out, res) = merge("a\nb\n", "a\nx", "a\ny\n", options);
+    assert_eq!(res, Resolution::Conflict);
+    assert_eq!(
+        out, "a\n<<< ours\nx\n||| base\nb\n===\ny\n>>> theirs\n",
+        "a missing newline on the last line is added to keep markers on their own line"
+    );
+}
+
+#[test]
+fn binary_files_are_always_conflicting() {
+    let (out, res) = merge("a\0", "b\0", "c\0", Default::default());
+    assert_eq!(res, Resolution::Conflict);
+    assert_eq!(out, "b\0", "our side is retained");
+}
+
+mod builtin_driver {
+    use gix_merge::blob::{BuiltinDriver, Resolution};
+
+    fn merge(driver: BuiltinDriver, base: &str, ours: &str, theirs: &str) -> (String, Resolution) {
+        let mut out = Vec::new();
+        let res = driver.merge(
+            &mut out,
+            base.as_bytes(),
+            ours.as_bytes(),
+            theirs.as_bytes(),
+            Default::default(),
+            Default::default(),
+        );
+        (String::from_utf8(out).expect("valid UTF8"), res)
+    }
+
+    #[test]
+    fn from_name() {
+        assert_eq!(BuiltinDriver::from_name("text".into()), Some(BuiltinDriver::Text));
+        assert_eq!(BuiltinDriver::from_name("binary".into()), Some(BuiltinDriver::Binary));
+        assert_eq!(BuiltinDriver::from_name("union".into()), Some(BuiltinDriver::Union));
+        assert_eq!(BuiltinDriver::from_name("custom".into()), None);
+    }
+
+    #[test]
+    fn union_keeps_the_lines_of_both_sides_instead_of_conflicting() {
+        let (out, res) = merge(BuiltinDriver::Union, "a\nb\nc\n", "a\nx\nsame\nc\n", "a\ny\nsame\nc\n");
+        assert_eq!(res, Resolution::Complete);
+        assert_eq!(out, "a\nx\ny\nsame\nc\n");
+
+        let (out, res) = merge(BuiltinDriver::Union, "a\n", "a\nb", "a\nc");
+        assert_eq!(res, Resolution::Complete);
+        assert_eq!(out, "a\nb\nc", "a newline is added between lines without one");
+    }
+
+    #[test]
+    fn union_merges_non_overlapping_changes_like_text() {
+        let (out, res) = merge(
+            BuiltinDriver::Union,
+            "a\nb\nc\nd\ne\n",
+            "A\nb\nc\nd\ne\n",
+            "a\nb\nc\nd\nE\n",
+        );
+        assert_eq!(res, Resolution::Complete);
+        assert_eq!(out, "A\nb\nc\nd\nE\n");
+    }
+
+    #[test]
+    fn binary_keeps_ours_and_conflicts() {
+        let (out, res) = merge(BuiltinDriver::Binary, "a\n", "a\nb\n", "a\nc\n");
+        assert_eq!(res, Resolution::Conflict);
+        assert_eq!(out, "a\nb\n");
+    }
+}
+
+mod driver {
+    use gix_merge::blob::{driver::Context, Driver, Resolution};
+
+    fn apply(command: &str) -> (String, Resolution) {
+        let driver = Driver {
+            name: "custom".into(),
+            display_name: None,
+            command: command.into(),
+            recursive: None,
+        };
+        let tmp = gix_testtools::tempfile::TempDir::new().expect("can create temp dir");
+        let mut out = Vec::new();
+        let res = driver
+            .apply(
+                &mut out,
+                b"base\n",
+                b"ours\n",
+                b"theirs\n",
+                Context {
+                    path: "dir/file name".into(),
+                    marker_size: 7,
+                    temp_dir: tmp.path(),
+                    command: Default::default(),
+                },
+            )
+            .expect("the command can be spawned");
+        assert_eq!(
+            std::fs::read_dir(tmp.path()).expect("readable").count(),
+            0,
+            "temporary files are removed"
+        );
+        (String::from_utf8(out).expect("valid UTF8"), res)
+    }
+
+    #[test]
+    fn the_result_is_read_from_the_file_of_ours() {
+        let (out, res) = apply("cat %O %B >>%A");
+        assert_eq!(res, Resolution::Complete);
+        assert_eq!(out, "ours\nbase\ntheirs\n");
+    }
+
+    #[test]
+    fn placeholders_are_substituted() {
+        let (out, res) = apply("echo %P %L 100%% %X >%A");
+        assert_eq!(res, Resolution::Complete);
+        assert_eq!(out, "dir/file name 7 100% %X\n");
+    }
+
+    #[test]
+    fn non_zero_exit_codes_are_conflicts() {
+        let (out, res) = apply("echo conflict >%A; exit 1");
+        assert_eq!(res, Resolution::Conflict);
+        assert_eq!(out, "conflict\n");
+    }
+}
@@ -109,6 +109,23 @@ pub fn window_size(transport_is_stateless: bool, window_size: Option<usize>) ->
     }
 }
 
+/// The maximum amount of `HAVE` lines we send in a single request of a stateless transport by default, including those we
+/// have to repeat as they are known to be in common with the remote.
+///
+/// With each `HAVE` line taking 50 bytes for SHA1, this keeps requests below 1MB, the default of `http.postBuffer` in `git`.
+pub const MAX_STATELESS_HAVES: usize = 20_000;
+
+/// Limit `window_size`, as previously obtained with [`window_size()`], for a request of a stateless transport that has to repeat
+/// `num_common` `HAVE` lines known to be in common with the remote so that it contains no more than `max_haves` `HAVE` lines in total.
+///
+/// Note that the initial window size is the smallest possible value to assure each round makes progress, even if there are more
+/// common commits than `max_haves`.
+pub fn stateless_window_size(window_size: usize, num_common: usize, max_haves: usize) -> usize {
+    window_size
+        .min(max_haves.saturating_sub(num_common))
+        .max(self::window_size(true, None))
+}
+
 impl Algorithm {
     /// Create an instance of a negotiator which implements this algorithm.
     pub fn into_negotiator(self) -> Box<dyn Negotiator> {
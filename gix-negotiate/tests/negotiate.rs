@@ -1,7 +1,7 @@
 use gix_testtools::Result;
 
 mod window_size {
-    use gix_negotiate::window_size;
+    use gix_negotiate::{stateless_window_size, window_size};
 
     #[test]
     fn initial_value_without_previous_window_size() {
@@ -18,6 +18,21 @@ mod window_size {
         }
     }
 
+    #[test]
+    fn stateless_requests_are_capped() {
+        assert_eq!(stateless_window_size(1024, 0, 20_000), 1024, "below the limit");
+        assert_eq!(
+            stateless_window_size(16384, 10_000, 20_000),
+            10_000,
+            "common haves are repeated and count towards the limit"
+        );
+        assert_eq!(
+            stateless_window_size(1024, 30_000, 20_000),
+            16,
+            "there is always room for at least a few new haves to make progress"
+        );
+    }
+
     #[test]
     fn transport_is_not_stateless() {
         let mut ws = window_size(false, None);
@@ -1,2 +1,6 @@
-#![deny(rust_2018_idioms)]
+//! A crate for dealing with [git notes](https://git-scm.com/docs/git-notes).
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
+
+///
+pub mod merge;
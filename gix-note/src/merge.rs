@@ -0,0 +1,109 @@
+use std::borrow::Cow;
+
+use bstr::{BStr, ByteSlice};
+
+/// The strategy to use for resolving notes of the same object that were changed on both sides of a notes merge,
+/// as configured by `notes.mergeStrategy` or passed to `git notes merge --strategy`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// Leave the conflict to be resolved by the user, which is the default.
+    ///
+    /// `git` writes the conflicting notes into `.git/NOTES_MERGE_WORKTREE` for editing, with conflict markers if needed.
+    #[default]
+    Manual,
+    /// Keep our version of the note.
+    Ours,
+    /// Use their version of the note.
+    Theirs,
+    /// Concatenate our note and their note, separated by an empty line.
+    Union,
+    /// Concatenate the lines of our note and their note, and sort them while removing duplicate and empty lines.
+    CatSortUniq,
+}
+
+impl Strategy {
+    /// Return the strategy with the given `name` as used by `git`, like `cat_sort_uniq`, or `None` if it is unknown.
+    pub fn from_name(name: &BStr) -> Option<Self> {
+        Some(match name.as_bytes() {
+            b"manual" => Strategy::Manual,
+            b"ours" => Strategy::Ours,
+            b"theirs" => Strategy::Theirs,
+            b"union" => Strategy::Union,
+            b"cat_sort_uniq" => Strategy::CatSortUniq,
+            _ => return None,
+        })
+    }
+
+    /// Return the name of the strategy as used by `git`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Strategy::Manual => "manual",
+            Strategy::Ours => "ours",
+            Strategy::Theirs => "theirs",
+            Strategy::Union => "union",
+            Strategy::CatSortUniq => "cat_sort_uniq",
+        }
+    }
+}
+
+/// The result of [resolving](resolve()) notes that were changed on both sides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution<'a> {
+    /// The note should have the given content.
+    Note(Cow<'a, BStr>),
+    /// The note should be removed.
+    Remove,
+    /// The notes conflict and have to be resolved [manually](Strategy::Manual).
+    Conflict,
+}
+
+/// Resolve the notes `ours` and `theirs` of the same object, which were both changed compared to their merge-base,
+/// according to `strategy`, just like `git notes merge` does it. `None` means the note was removed on the respective side.
+pub fn resolve<'a>(strategy: Strategy, ours: Option<&'a BStr>, theirs: Option<&'a BStr>) -> Resolution<'a> {
+    let note = |note: Option<&'a BStr>| note.map_or(Resolution::Remove, |note| Resolution::Note(note.into()));
+    match (strategy, ours, theirs) {
+        (Strategy::Manual, _, _) => Resolution::Conflict,
+        (Strategy::Ours, ours, _) => note(ours),
+        (Strategy::Theirs, _, theirs) => note(theirs),
+        (Strategy::Union, None, theirs) => note(theirs),
+        (Strategy::Union, Some(ours), None) => note(Some(ours)),
+        (Strategy::Union, Some(ours), Some(theirs)) => Resolution::Note(concatenate(ours, theirs)),
+        (Strategy::CatSortUniq, None, None) => Resolution::Remove,
+        (Strategy::CatSortUniq, ours, theirs) => Resolution::Note(cat_sort_uniq(ours, theirs).into()),
+    }
+}
+
+/// Concatenate `ours` and `theirs`, separated by an empty line, unless one of them is empty.
+///
+/// This is also how `git notes append` adds to an existing note.
+pub fn concatenate<'a>(ours: &'a BStr, theirs: &'a BStr) -> Cow<'a, BStr> {
+    if ours.is_empty() {
+        return theirs.into();
+    } else if theirs.is_empty() {
+        return ours.into();
+    }
+    let ours = ours.strip_suffix(b"\n").unwrap_or(ours);
+    let mut out = Vec::with_capacity(ours.len() + 2 + theirs.len());
+    out.extend_from_slice(ours);
+    out.extend_from_slice(b"\n\n");
+    out.extend_from_slice(theirs);
+    Cow::Owned(out.into())
+}
+
+/// Concatenate all non-empty lines of `ours` and `theirs`, sorted and without duplicates.
+fn cat_sort_uniq(ours: Option<&BStr>, theirs: Option<&BStr>) -> bstr::BString {
+    let mut lines: Vec<_> = ours
+        .into_iter()
+        .chain(theirs)
+        .flat_map(|note| note.split_str(b"\n"))
+        .filter(|line| !line.is_empty())
+        .collect();
+    lines.sort_unstable();
+    lines.dedup();
+    let mut out = Vec::new();
+    for line in lines {
+        out.extend_from_slice(line);
+        out.push(b'\n');
+    }
+    out.into()
+}
@@ -0,0 +1,19 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git commit -q --allow-empty -m base
+
+git notes add -m "base" HEAD
+git update-ref refs/notes/remote refs/notes/commits
+
+printf 'b\n\na\nshared\n' >ours
+printf 'c\nshared\n\n' >theirs
+git notes add -f -C "$(git hash-object -w ours)" HEAD
+git notes --ref remote add -f -C "$(git hash-object -w theirs)" HEAD
+
+for strategy in ours theirs union cat_sort_uniq; do
+  git update-ref refs/notes/$strategy refs/notes/commits
+  git notes --ref $strategy merge -q -s $strategy refs/notes/remote
+  git notes --ref $strategy show HEAD >$strategy.expected
+done
@@ -0,0 +1,62 @@
+use gix_testtools::Result;
+
+mod merge {
+    use gix_note::merge::{resolve, Resolution, Strategy};
+    use gix_testtools::bstr::{BStr, ByteSlice};
+
+    #[test]
+    fn strategies_resolve_conflicting_notes_like_git() -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_read_only("merge.sh")?;
+        let ours = std::fs::read(dir.join("ours"))?;
+        let theirs = std::fs::read(dir.join("theirs"))?;
+        for strategy in [Strategy::Ours, Strategy::Theirs, Strategy::Union, Strategy::CatSortUniq] {
+            let expected = std::fs::read(dir.join(format!("{}.expected", strategy.as_str())))?;
+            assert_eq!(
+                resolve(strategy, Some(ours.as_bstr()), Some(theirs.as_bstr())),
+                Resolution::Note(expected.as_bstr().into()),
+                "{strategy:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn manual_strategy_always_conflicts() {
+        assert_eq!(resolve(Strategy::Manual, Some("a".into()), None), Resolution::Conflict);
+        assert_eq!(Strategy::default(), Strategy::Manual);
+    }
+
+    #[test]
+    fn removed_notes() {
+        let note: Option<&BStr> = Some("a\n".into());
+        assert_eq!(resolve(Strategy::Ours, None, note), Resolution::Remove);
+        assert_eq!(resolve(Strategy::Theirs, note, None), Resolution::Remove);
+        for strategy in [Strategy::Union, Strategy::CatSortUniq] {
+            assert_eq!(
+                resolve(strategy, note, None),
+                Resolution::Note("a\n".as_bytes().as_bstr().into()),
+                "{strategy:?}: the remaining note is kept"
+            );
+            assert_eq!(
+                resolve(strategy, None, note),
+                Resolution::Note("a\n".as_bytes().as_bstr().into()),
+                "{strategy:?}"
+            );
+            assert_eq!(resolve(strategy, None, None), Resolution::Remove, "{strategy:?}");
+        }
+    }
+
+    #[test]
+    fn strategy_names_round_trip() {
+        for strategy in [
+            Strategy::Manual,
+            Strategy::Ours,
+            Strategy::Theirs,
+            Strategy::Union,
+            Strategy::CatSortUniq,
+        ] {
+            assert_eq!(Strategy::from_name(strategy.as_str().into()), Some(strategy));
+        }
+        assert_eq!(Strategy::from_name("resolve".into()), None);
+    }
+}
@@ -0,0 +1,188 @@
+use bstr::{BStr, BString, ByteSlice};
+use gix_hash::ObjectId;
+use smallvec::SmallVec;
+
+use crate::{commit::message, Commit, Encoded, Find, Kind};
+
+/// The error returned by [`CommitBuilder::build()`] and [`CommitBuilder::encode()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The {field} must not contain '<', '>' or newlines in its name or email")]
+    InvalidSignature { field: &'static str },
+    #[error("The encoding must not be empty or contain whitespace")]
+    InvalidEncoding,
+    #[error("The extra header '{name}' must not be empty, contain whitespace or be one of the standard headers")]
+    InvalidExtraHeader { name: BString },
+    #[error("Parent {id} was given more than once")]
+    DuplicateParent { id: ObjectId },
+    #[error("Object {id} uses a different hash than the tree, which is {expected}")]
+    HashKindMismatch { id: ObjectId, expected: gix_hash::Kind },
+    #[error(transparent)]
+    Find(#[from] crate::find::Error),
+    #[error("Object {id} could not be found")]
+    NotFound { id: ObjectId },
+    #[error("Expected object {id} to be a {expected}, but it is a {actual}")]
+    ObjectKind { id: ObjectId, expected: Kind, actual: Kind },
+}
+
+/// Build a [`Commit`] while assuring it's valid, with headers written in the order `git` expects them and a normalized message.
+///
+/// If [objects to verify with](CommitBuilder::verify_with()) are set, the tree and all parents also have to exist
+/// and be of the correct kind.
+#[derive(Clone)]
+pub struct CommitBuilder<'a> {
+    tree: ObjectId,
+    parents: SmallVec<[ObjectId; 1]>,
+    author: gix_actor::Signature,
+    committer: gix_actor::Signature,
+    encoding: Option<BString>,
+    message: BString,
+    extra_headers: Vec<(BString, BString)>,
+    normalize_message: bool,
+    objects: Option<&'a dyn Find>,
+}
+
+/// Lifecycle
+impl<'a> CommitBuilder<'a> {
+    /// Create a new instance for a commit of `tree`, written by `author` and committed by `committer`, without parents
+    /// and with an empty message.
+    pub fn new(tree: impl Into<ObjectId>, author: gix_actor::Signature, committer: gix_actor::Signature) -> Self {
+        CommitBuilder {
+            tree: tree.into(),
+            parents: Default::default(),
+            author,
+            committer,
+            encoding: None,
+            message: Default::default(),
+            extra_headers: Vec::new(),
+            normalize_message: true,
+            objects: None,
+        }
+    }
+
+    /// Add `id` as the next parent of the commit.
+    pub fn parent(mut self, id: impl Into<ObjectId>) -> Self {
+        self.parents.push(id.into());
+        self
+    }
+
+    /// Add all `ids` as the next parents of the commit, in order.
+    pub fn parents(mut self, ids: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
+        self.parents.extend(ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the name of the encoding of the message, which is UTF-8 if unset.
+    pub fn encoding(mut self, encoding: impl Into<BString>) -> Self {
+        self.encoding = Some(encoding.into());
+        self
+    }
+
+    /// Set the commit message.
+    pub fn message(mut self, message: impl Into<BString>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Add a header named `name` with `value`, which may span multiple lines, to be written after all standard headers.
+    pub fn extra_header(mut self, name: impl Into<BString>, value: impl Into<BString>) -> Self {
+        self.extra_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// If `toggle` is `true` (the default), [normalize](message::normalize()) the message, or keep it as is otherwise.
+    pub fn normalize_message(mut self, toggle: bool) -> Self {
+        self.normalize_message = toggle;
+        self
+    }
+
+    /// Use `objects` to assure that the tree exists and is a tree, and that all parents exist and are commits.
+    pub fn verify_with(mut self, objects: &'a dyn Find) -> Self {
+        self.objects = Some(objects);
+        self
+    }
+}
+
+/// Building
+impl CommitBuilder<'_> {
+    /// Validate all fields and return the commit.
+    pub fn build(self) -> Result<Commit, Error> {
+        let expected_hash = self.tree.kind();
+        let mut seen = SmallVec::<[ObjectId; 1]>::new();
+        for parent in &self.parents {
+            if parent.kind() != expected_hash {
+                return Err(Error::HashKindMismatch {
+                    id: *parent,
+                    expected: expected_hash,
+                });
+            }
+            if seen.contains(parent) {
+                return Err(Error::DuplicateParent { id: *parent });
+            }
+            seen.push(*parent);
+        }
+        for (field, signature) in [("author", &self.author), ("committer", &self.committer)] {
+            if !is_valid_signature(signature) {
+                return Err(Error::InvalidSignature { field });
+            }
+        }
+        if let Some(encoding) = &self.encoding {
+            if encoding.is_empty() || encoding.find_byteset(b" \t\r\n").is_some() {
+                return Err(Error::InvalidEncoding);
+            }
+        }
+        for (name, _) in &self.extra_headers {
+            let is_standard = ["tree", "parent", "author", "committer", "encoding"]
+                .iter()
+                .any(|standard| name == standard);
+            if is_standard || name.is_empty() || name.find_byteset(b" \t\r\n").is_some() {
+                return Err(Error::InvalidExtraHeader { name: name.clone() });
+            }
+        }
+        if let Some(objects) = self.objects {
+            let mut buf = Vec::new();
+            verify_kind(objects, self.tree, Kind::Tree, &mut buf)?;
+            for parent in &self.parents {
+                verify_kind(objects, *parent, Kind::Commit, &mut buf)?;
+            }
+        }
+
+        Ok(Commit {
+            tree: self.tree,
+            parents: self.parents,
+            author: self.author,
+            committer: self.committer,
+            encoding: self.encoding,
+            message: if self.normalize_message {
+                message::normalize(self.message.as_ref())
+            } else {
+                self.message
+            },
+            extra_headers: self.extra_headers,
+        })
+    }
+
+    /// Like [`build()`](Self::build()), but also serialize the commit and compute its id.
+    pub fn encode(self) -> Result<Encoded<Commit>, Error> {
+        let commit = self.build()?;
+        Ok(Encoded::new(commit.tree.kind(), commit))
+    }
+}
+
+pub(crate) fn is_valid_signature(signature: &gix_actor::Signature) -> bool {
+    let is_valid_token = |token: &BStr| token.find_byteset(b"<>\n").is_none();
+    is_valid_token(signature.name.as_ref()) && is_valid_token(signature.email.as_ref())
+}
+
+fn verify_kind(objects: &dyn Find, id: ObjectId, expected: Kind, buf: &mut Vec<u8>) -> Result<(), Error> {
+    match objects.try_find(&id, buf)? {
+        None => Err(Error::NotFound { id }),
+        Some(object) if object.kind != expected => Err(Error::ObjectKind {
+            id,
+            expected,
+            actual: object.kind,
+        }),
+        Some(_) => Ok(()),
+    }
+}
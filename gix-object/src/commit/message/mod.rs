@@ -51,6 +51,29 @@ impl<'a> MessageRef<'a> {
     }
 }
 
+/// Normalize `message` like `git commit --cleanup=whitespace` does, which is to remove trailing whitespace from all lines,
+/// collapse consecutive empty lines into one, remove leading and trailing empty lines, and end the last line with a newline.
+///
+/// The result is empty if `message` consists only of whitespace.
+pub fn normalize(message: &BStr) -> BString {
+    let mut out = BString::default();
+    let mut pending_empty_line = false;
+    for line in message.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            pending_empty_line = !out.is_empty();
+            continue;
+        }
+        if pending_empty_line {
+            out.push_byte(b'\n');
+            pending_empty_line = false;
+        }
+        out.push_str(line);
+        out.push_byte(b'\n');
+    }
+    out
+}
+
 pub(crate) fn summary(message: &BStr) -> Cow<'_, BStr> {
     let message = message.trim();
     match message.find_byte(b'\n') {
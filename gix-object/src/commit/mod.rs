@@ -5,6 +5,8 @@ use winnow::prelude::*;
 
 use crate::{Commit, CommitRef, TagRef};
 
+///
+pub mod builder;
 mod decode;
 ///
 pub mod message;
@@ -36,6 +36,9 @@ pub mod find;
 mod traits;
 pub use traits::{Exists, Find, FindExt, FindObjectOrHeader, Header as FindHeader, HeaderExt, WriteTo};
 
+pub use commit::builder::CommitBuilder;
+pub use tag::builder::TagBuilder;
+
 pub mod encode;
 pub(crate) mod parse;
 
@@ -178,6 +181,29 @@ pub struct Tag {
     pub pgp_signature: Option<BString>,
 }
 
+/// An object as produced by a [`CommitBuilder`] or [`TagBuilder`], along with its serialized form and its id.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Encoded<T> {
+    /// The validated object.
+    pub object: T,
+    /// The serialized object, without the loose object header.
+    pub data: Vec<u8>,
+    /// The id of the object as computed from `data`.
+    pub id: gix_hash::ObjectId,
+}
+
+impl<T: WriteTo> Encoded<T> {
+    /// Serialize `object` and compute its id with `hash_kind`.
+    pub(crate) fn new(hash_kind: gix_hash::Kind, object: T) -> Self {
+        let mut data = Vec::with_capacity(object.size() as usize);
+        object
+            .write_to(&mut data)
+            .expect("validated objects can be written to memory");
+        let id = compute_hash(hash_kind, object.kind(), &data);
+        Encoded { object, data, id }
+    }
+}
+
 /// Immutable objects are read-only structures referencing most data from [a byte slice][crate::ObjectRef::from_bytes()].
 ///
 /// Immutable objects are expected to be deserialized from bytes that acts as backing store, and they
@@ -250,6 +276,7 @@ impl Tree {
 
 /// A borrowed object using a slice as backing buffer, or in other words a bytes buffer that knows the kind of object it represents.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Data<'a> {
     /// kind of object
     pub kind: Kind,
@@ -259,6 +286,7 @@ pub struct Data<'a> {
 
 /// Information about an object, which includes its kind and the amount of bytes it would have when obtained.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Header {
     /// The kind of object.
     pub kind: Kind,
@@ -0,0 +1,128 @@
+use bstr::BString;
+use gix_hash::ObjectId;
+
+use crate::{commit, Encoded, Find, Kind, Tag};
+
+/// The error returned by [`TagBuilder::build()`] and [`TagBuilder::encode()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    InvalidName(#[from] crate::tag::write::Error),
+    #[error("The tagger must not contain '<', '>' or newlines in its name or email")]
+    InvalidTagger,
+    #[error(transparent)]
+    Find(#[from] crate::find::Error),
+    #[error("Target object {id} could not be found")]
+    NotFound { id: ObjectId },
+    #[error("Target object {id} was declared to be a {expected}, but it is a {actual}")]
+    ObjectKind { id: ObjectId, expected: Kind, actual: Kind },
+}
+
+/// Build a [`Tag`] while assuring it's valid, with headers written in the order `git` expects them and a normalized message.
+///
+/// If [objects to verify with](TagBuilder::verify_with()) are set, the target also has to exist and be of the declared kind.
+#[derive(Clone)]
+pub struct TagBuilder<'a> {
+    name: BString,
+    target: ObjectId,
+    target_kind: Kind,
+    tagger: Option<gix_actor::Signature>,
+    message: BString,
+    pgp_signature: Option<BString>,
+    normalize_message: bool,
+    objects: Option<&'a dyn Find>,
+}
+
+/// Lifecycle
+impl<'a> TagBuilder<'a> {
+    /// Create a new instance for a tag `name` pointing to `target` of kind `target_kind`, without tagger and with an empty message.
+    pub fn new(name: impl Into<BString>, target: impl Into<ObjectId>, target_kind: Kind) -> Self {
+        TagBuilder {
+            name: name.into(),
+            target: target.into(),
+            target_kind,
+            tagger: None,
+            message: Default::default(),
+            pgp_signature: None,
+            normalize_message: true,
+            objects: None,
+        }
+    }
+
+    /// Set the signature of the one who created the tag.
+    pub fn tagger(mut self, tagger: gix_actor::Signature) -> Self {
+        self.tagger = Some(tagger);
+        self
+    }
+
+    /// Set the tag message.
+    pub fn message(mut self, message: impl Into<BString>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Set the pgp `signature` over all bytes of the encoded tag, which is written after the message.
+    pub fn pgp_signature(mut self, signature: impl Into<BString>) -> Self {
+        self.pgp_signature = Some(signature.into());
+        self
+    }
+
+    /// If `toggle` is `true` (the default), [normalize](commit::message::normalize()) the message, or keep it as is otherwise.
+    pub fn normalize_message(mut self, toggle: bool) -> Self {
+        self.normalize_message = toggle;
+        self
+    }
+
+    /// Use `objects` to assure that the target exists and is of the declared kind.
+    pub fn verify_with(mut self, objects: &'a dyn Find) -> Self {
+        self.objects = Some(objects);
+        self
+    }
+}
+
+/// Building
+impl TagBuilder<'_> {
+    /// Validate all fields and return the tag.
+    pub fn build(self) -> Result<Tag, Error> {
+        crate::tag::write::validated_name(self.name.as_ref())?;
+        if let Some(tagger) = &self.tagger {
+            if !commit::builder::is_valid_signature(tagger) {
+                return Err(Error::InvalidTagger);
+            }
+        }
+        if let Some(objects) = self.objects {
+            let mut buf = Vec::new();
+            match objects.try_find(&self.target, &mut buf)? {
+                None => return Err(Error::NotFound { id: self.target }),
+                Some(object) if object.kind != self.target_kind => {
+                    return Err(Error::ObjectKind {
+                        id: self.target,
+                        expected: self.target_kind,
+                        actual: object.kind,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(Tag {
+            target: self.target,
+            target_kind: self.target_kind,
+            name: self.name,
+            tagger: self.tagger,
+            message: if self.normalize_message {
+                commit::message::normalize(self.message.as_ref())
+            } else {
+                self.message
+            },
+            pgp_signature: self.pgp_signature,
+        })
+    }
+
+    /// Like [`build()`](Self::build()), but also serialize the tag and compute its id.
+    pub fn encode(self) -> Result<Encoded<Tag>, Error> {
+        let tag = self.build()?;
+        Ok(Encoded::new(tag.target.kind(), tag))
+    }
+}
@@ -2,6 +2,8 @@ use winnow::prelude::*;
 
 use crate::TagRef;
 
+///
+pub mod builder;
 mod decode;
 
 ///
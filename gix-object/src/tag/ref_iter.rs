@@ -51,6 +51,33 @@ impl<'a> TagRefIter<'a> {
         })
         .ok_or_else(missing_field)?
     }
+
+    /// Parse `data` as tag and return its signature along with all data before it, which is the data that was signed,
+    /// or `None` if the tag isn't signed.
+    ///
+    /// Like `git`, the signature starts at the last line that begins an OpenPGP, X.509 or SSH signature block, and
+    /// extends to the end of the tag.
+    /// This allows the caller to validate the signature by passing the signed data along with the signature back to the program
+    /// that created it.
+    pub fn signature(data: &'a [u8]) -> Result<Option<(&'a BStr, &'a BStr)>, crate::decode::Error> {
+        const SIGNATURE_BEGIN: &[&[u8]] = &[
+            b"-----BEGIN PGP SIGNATURE-----",
+            b"-----BEGIN PGP MESSAGE-----",
+            b"-----BEGIN SIGNED MESSAGE-----",
+            b"-----BEGIN SSH SIGNATURE-----",
+        ];
+        crate::TagRef::from_bytes(data)?;
+
+        let mut signature_start = None;
+        let mut line_start = 0;
+        for line in data.lines_with_terminator() {
+            if SIGNATURE_BEGIN.iter().any(|begin| line.starts_with(begin)) {
+                signature_start = Some(line_start);
+            }
+            line_start += line.len();
+        }
+        Ok(signature_start.map(|start| (data[start..].as_bstr(), data[..start].as_bstr())))
+    }
 }
 
 fn missing_field() -> crate::decode::Error {
@@ -57,6 +57,31 @@ impl<'a> TreeRefIter<'a> {
     pub fn entries(self) -> Result<Vec<EntryRef<'a>>, crate::decode::Error> {
         self.collect()
     }
+
+    /// Find the entry named `name` by decoding one entry at a time, and stop as soon as it was found or the sort order
+    /// shows that it can't be present anymore. Thus, entries after it are never decoded.
+    ///
+    /// Returns `Ok(None)` if there is no such entry, or an error if an entry that had to be looked at couldn't be decoded.
+    pub fn lookup_entry(self, name: &BStr) -> Result<Option<EntryRef<'a>>, crate::decode::Error> {
+        static NULL_HASH: gix_hash::ObjectId = gix_hash::Kind::shortest().null();
+
+        // A tree named `name` sorts after a blob with the same name, so nothing after it can match.
+        let last_possible = EntryRef {
+            mode: tree::EntryKind::Tree.into(),
+            filename: name,
+            oid: &NULL_HASH,
+        };
+        for entry in self {
+            let entry = entry?;
+            if entry.filename == name {
+                return Ok(Some(entry));
+            }
+            if entry > last_possible {
+                break;
+            }
+        }
+        Ok(None)
+    }
 }
 
 impl<'a> Iterator for TreeRefIter<'a> {
@@ -0,0 +1,120 @@
+use gix_object::{commit::builder::Error, CommitBuilder, Kind, WriteTo};
+
+use crate::{hex_to_id, signature, Objects};
+
+fn sig() -> gix_actor::Signature {
+    signature(1592381636).to_owned()
+}
+
+const TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+#[test]
+fn encode_yields_data_and_id_of_a_commit_with_normalized_message() -> crate::Result {
+    let parent = hex_to_id("e2dd313d17b7bfcb4a2f9c8bde6aa25e6ae59d9a");
+    let encoded = CommitBuilder::new(hex_to_id(TREE), sig(), sig())
+        .parent(parent)
+        .extra_header("gpgsig", "signature\nover two lines")
+        .message("\n \ntitle \n\n\n  body\n\n")
+        .encode()?;
+
+    assert_eq!(encoded.object.message, "title\n\n  body\n", "the message is normalized");
+    assert_eq!(encoded.object.parents.as_slice(), &[parent]);
+    let mut data = Vec::new();
+    encoded.object.write_to(&mut data)?;
+    assert_eq!(encoded.data, data);
+    assert_eq!(
+        encoded.id,
+        gix_object::compute_hash(gix_hash::Kind::Sha1, Kind::Commit, &encoded.data)
+    );
+    let decoded = gix_object::CommitRef::from_bytes(&encoded.data)?;
+    assert_eq!(
+        decoded.extra_headers().find("gpgsig"),
+        Some(b"signature\nover two lines".as_ref().into())
+    );
+    Ok(())
+}
+
+#[test]
+fn message_normalization_can_be_disabled() -> crate::Result {
+    let commit = CommitBuilder::new(hex_to_id(TREE), sig(), sig())
+        .message("title \n\n")
+        .normalize_message(false)
+        .build()?;
+    assert_eq!(commit.message, "title \n\n");
+    Ok(())
+}
+
+#[test]
+fn invalid_fields_are_rejected() {
+    let parent = hex_to_id("e2dd313d17b7bfcb4a2f9c8bde6aa25e6ae59d9a");
+    let builder = || CommitBuilder::new(hex_to_id(TREE), sig(), sig());
+
+    assert!(matches!(
+        builder().parents([parent, parent]).build(),
+        Err(Error::DuplicateParent { id }) if id == parent
+    ));
+
+    let mut invalid = sig();
+    invalid.email = "a>b".into();
+    assert!(matches!(
+        CommitBuilder::new(hex_to_id(TREE), sig(), invalid).build(),
+        Err(Error::InvalidSignature { field: "committer" })
+    ));
+    assert!(matches!(
+        builder().encoding("utf 8").build(),
+        Err(Error::InvalidEncoding)
+    ));
+    for name in ["parent", "", "with space"] {
+        assert!(
+            matches!(
+                builder().extra_header(name, "value").build(),
+                Err(Error::InvalidExtraHeader { .. })
+            ),
+            "{name:?} is not a valid extra header name"
+        );
+    }
+}
+
+#[test]
+fn tree_and_parents_are_verified_if_objects_are_provided() -> crate::Result {
+    let mut objects = Objects::default();
+    let tree = objects.insert(Kind::Tree, b"");
+    let first = CommitBuilder::new(tree, sig(), sig()).message("first").encode()?;
+    let parent = objects.insert(Kind::Commit, &first.data);
+    assert_eq!(parent, first.id);
+
+    let commit = CommitBuilder::new(tree, sig(), sig())
+        .parent(parent)
+        .verify_with(&objects)
+        .build()?;
+    assert_eq!(commit.parents.as_slice(), &[parent]);
+
+    let missing = hex_to_id("e2dd313d17b7bfcb4a2f9c8bde6aa25e6ae59d9a");
+    assert!(matches!(
+        CommitBuilder::new(tree, sig(), sig())
+            .parent(missing)
+            .verify_with(&objects)
+            .build(),
+        Err(Error::NotFound { id }) if id == missing
+    ));
+    assert!(matches!(
+        CommitBuilder::new(tree, sig(), sig())
+            .parent(tree)
+            .verify_with(&objects)
+            .build(),
+        Err(Error::ObjectKind {
+            expected: Kind::Commit,
+            actual: Kind::Tree,
+            ..
+        })
+    ));
+    assert!(matches!(
+        CommitBuilder::new(parent, sig(), sig()).verify_with(&objects).build(),
+        Err(Error::ObjectKind {
+            expected: Kind::Tree,
+            actual: Kind::Commit,
+            ..
+        })
+    ));
+    Ok(())
+}
@@ -295,3 +295,22 @@ mod summary {
         assert_eq!(summary(input), Cow::Borrowed(b"hello world foo".as_bstr()));
     }
 }
+
+mod normalize {
+    use gix_object::{bstr::ByteSlice, commit::message::normalize};
+
+    #[test]
+    fn whitespace_is_cleaned_up_and_a_trailing_newline_is_added() {
+        assert_eq!(
+            normalize(b"\n \t\ntitle  \r\n\n\n\nbody\t\nmore \n\n \n".as_bstr()),
+            "title\n\nbody\nmore\n"
+        );
+        assert_eq!(normalize(b"title".as_bstr()), "title\n");
+    }
+
+    #[test]
+    fn messages_with_only_whitespace_become_empty() {
+        assert_eq!(normalize(b" \n\t\r\n\n".as_bstr()), "");
+        assert_eq!(normalize(b"".as_bstr()), "");
+    }
+}
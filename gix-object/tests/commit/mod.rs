@@ -184,6 +184,7 @@ fn invalid() {
     );
 }
 
+mod builder;
 mod from_bytes;
 mod iter;
 mod message;
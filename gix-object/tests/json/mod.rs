@@ -0,0 +1,107 @@
+use gix_object::{bstr::ByteSlice, CommitRef, Object, ObjectRef, TagRef, TreeRef};
+use serde_json::{json, Value};
+
+use crate::fixture_name;
+
+fn keys(value: &Value) -> Vec<&str> {
+    let mut keys: Vec<_> = value
+        .as_object()
+        .expect("an object")
+        .keys()
+        .map(String::as_str)
+        .collect();
+    keys.sort_unstable();
+    keys
+}
+
+fn roundtrip(object: ObjectRef<'_>) -> crate::Result<Value> {
+    let object = object.into_owned();
+    let value = serde_json::to_value(&object)?;
+    let back: Object = serde_json::from_value(value.clone())?;
+    assert_eq!(back, object, "owned objects survive a roundtrip through JSON");
+    Ok(value)
+}
+
+#[test]
+fn commit() -> crate::Result {
+    let data = fixture_name("commit", "signed-with-encoding.txt");
+    let commit = roundtrip(ObjectRef::Commit(CommitRef::from_bytes(&data)?))?;
+    let commit = &commit["Commit"];
+    assert_eq!(
+        keys(commit),
+        [
+            "author",
+            "committer",
+            "encoding",
+            "extra_headers",
+            "message",
+            "parents",
+            "tree"
+        ],
+        "field names are stable"
+    );
+    assert_eq!(keys(&commit["author"]), ["email", "name", "time"]);
+    assert_eq!(
+        commit["author"]["time"],
+        json!({"seconds": 1592448995, "offset": 28800, "sign": "Plus"})
+    );
+    assert_eq!(commit["encoding"], json!(b"ISO-8859-1".to_vec()));
+    assert_eq!(
+        commit["extra_headers"][0][0],
+        json!(b"gpgsig".to_vec()),
+        "extra headers are pairs of name and value"
+    );
+    Ok(())
+}
+
+#[test]
+fn tag() -> crate::Result {
+    let data = fixture_name("tag", "signed.txt");
+    let tag = roundtrip(ObjectRef::Tag(TagRef::from_bytes(&data)?))?;
+    let tag = &tag["Tag"];
+    assert_eq!(
+        keys(tag),
+        ["message", "name", "pgp_signature", "tagger", "target", "target_kind"]
+    );
+    assert_eq!(tag["target_kind"], json!("Commit"));
+    assert_eq!(keys(&tag["tagger"]), ["email", "name", "time"]);
+
+    let data = fixture_name("tag", "no-tagger.txt");
+    let tag = roundtrip(ObjectRef::Tag(TagRef::from_bytes(&data)?))?;
+    assert_eq!(tag["Tag"]["tagger"], Value::Null, "missing taggers are null");
+    Ok(())
+}
+
+#[test]
+fn tree() -> crate::Result {
+    let data = fixture_name("tree", "everything.tree");
+    let tree = roundtrip(ObjectRef::Tree(TreeRef::from_bytes(&data)?))?;
+    let entries = tree["Tree"]["entries"].as_array().expect("entries");
+    assert!(!entries.is_empty());
+    for entry in entries {
+        assert_eq!(keys(entry), ["filename", "mode", "oid"]);
+    }
+    Ok(())
+}
+
+#[test]
+fn borrowed_objects_serialize_like_their_owned_counterparts_where_types_match() -> crate::Result {
+    let data = fixture_name("commit", "unsigned.txt");
+    let commit = CommitRef::from_bytes(&data)?;
+    let borrowed = serde_json::to_value(ObjectRef::Commit(commit.clone()))?;
+    let owned = serde_json::to_value(ObjectRef::Commit(commit).into_owned())?;
+    assert_eq!(keys(&borrowed["Commit"]), keys(&owned["Commit"]));
+    for field in ["author", "committer", "message"] {
+        assert_eq!(borrowed["Commit"][field], owned["Commit"][field], "{field}");
+    }
+    assert_eq!(
+        borrowed["Commit"]["tree"],
+        json!(owned_tree_hex(&data)),
+        "borrowed commits keep the hexadecimal tree id"
+    );
+    Ok(())
+}
+
+fn owned_tree_hex(data: &[u8]) -> Vec<u8> {
+    data.lines().next().expect("tree line")[b"tree ".len()..].to_vec()
+}
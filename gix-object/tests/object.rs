@@ -4,6 +4,8 @@ use gix_hash::ObjectId;
 
 mod commit;
 mod encode;
+#[cfg(feature = "serde")]
+mod json;
 mod object_ref;
 mod tag;
 mod tree;
@@ -117,3 +119,32 @@ fn linus_signature(seconds: SecondsSinceUnixEpoch) -> gix_actor::SignatureRef<'s
         },
     }
 }
+
+/// An in-memory object database for use with [`gix_object::Find`].
+#[derive(Default)]
+struct Objects(std::collections::HashMap<ObjectId, (gix_object::Kind, Vec<u8>)>);
+
+impl Objects {
+    fn insert(&mut self, kind: gix_object::Kind, data: &[u8]) -> ObjectId {
+        let id = gix_object::compute_hash(gix_hash::Kind::Sha1, kind, data);
+        self.0.insert(id, (kind, data.to_owned()));
+        id
+    }
+}
+
+impl gix_object::Find for Objects {
+    fn try_find<'a>(
+        &self,
+        id: &gix_hash::oid,
+        buffer: &'a mut Vec<u8>,
+    ) -> std::result::Result<Option<gix_object::Data<'a>>, gix_object::find::Error> {
+        Ok(self.0.get(id).map(|(kind, data)| {
+            buffer.clear();
+            buffer.extend_from_slice(data);
+            gix_object::Data {
+                kind: *kind,
+                data: buffer.as_slice(),
+            }
+        }))
+    }
+}
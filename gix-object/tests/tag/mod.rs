@@ -268,3 +268,116 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
         }),
     }
 }
+
+mod signature {
+    use gix_object::{bstr::ByteSlice, TagRefIter};
+
+    use crate::fixture_name;
+
+    #[test]
+    fn pgp() -> crate::Result {
+        let data = fixture_name("tag", "signed.txt");
+        let (signature, signed_data) = TagRefIter::signature(&data)?.expect("signed");
+        assert!(signature.starts_with(b"-----BEGIN PGP SIGNATURE-----\nComment: GPGTools"));
+        assert!(signature.ends_with(b"-----END PGP SIGNATURE-----"));
+        assert!(signed_data.ends_with(b"\n\nfor the signature\n"));
+        assert_eq!(
+            [signed_data.as_bytes(), signature.as_bytes()].concat(),
+            data,
+            "the signature is appended to the signed data"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ssh_with_the_last_signature_block_winning() -> crate::Result {
+        let signed_data = b"object ffa700b4aca13b80cb6b98a078e7c96804f8e0ec
+type commit
+tag 1.0.0
+tagger Sebastian Thiel <byronimo@gmail.com> 1528473343 +0230
+
+message that quotes
+-----BEGIN PGP SIGNATURE-----
+";
+        let signature = b"-----BEGIN SSH SIGNATURE-----
+U1NIU0lHAAAAAQAAADMAAAALc3NoLWVkMjU1MTkAAAAg
+-----END SSH SIGNATURE-----
+";
+        let data = [&signed_data[..], signature].concat();
+        assert_eq!(
+            TagRefIter::signature(&data)?,
+            Some((signature.as_bstr(), signed_data.as_bstr()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unsigned() -> crate::Result {
+        assert_eq!(TagRefIter::signature(&fixture_name("tag", "whitespace.txt"))?, None);
+        Ok(())
+    }
+}
+
+mod builder {
+    use gix_object::{tag::builder::Error, Kind, TagBuilder, TagRef};
+
+    use crate::{hex_to_id, signature, Objects};
+
+    #[test]
+    fn encode_yields_data_and_id_of_a_valid_tag() -> crate::Result {
+        let target = hex_to_id("ffa700b4aca13b80cb6b98a078e7c96804f8e0ec");
+        let encoded = TagBuilder::new("v1.0.0", target, Kind::Commit)
+            .tagger(signature(1592381636).to_owned())
+            .message("release \n\n\n")
+            .encode()?;
+
+        assert_eq!(encoded.object.message, "release\n");
+        assert_eq!(
+            encoded.id,
+            gix_object::compute_hash(gix_hash::Kind::Sha1, Kind::Tag, &encoded.data)
+        );
+        let decoded = TagRef::from_bytes(&encoded.data)?;
+        assert_eq!(decoded.name, "v1.0.0");
+        assert_eq!(decoded.target(), target);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_fields_are_rejected() {
+        let target = hex_to_id("ffa700b4aca13b80cb6b98a078e7c96804f8e0ec");
+        assert!(matches!(
+            TagBuilder::new("-v1", target, Kind::Commit).build(),
+            Err(Error::InvalidName(_))
+        ));
+        let mut tagger = signature(0).to_owned();
+        tagger.name = "a\nb".into();
+        assert!(matches!(
+            TagBuilder::new("v1", target, Kind::Commit).tagger(tagger).build(),
+            Err(Error::InvalidTagger)
+        ));
+    }
+
+    #[test]
+    fn target_is_verified_if_objects_are_provided() -> crate::Result {
+        let mut objects = Objects::default();
+        let blob = objects.insert(Kind::Blob, b"content");
+
+        let tag = TagBuilder::new("v1", blob, Kind::Blob).verify_with(&objects).build()?;
+        assert_eq!(tag.target, blob);
+
+        assert!(matches!(
+            TagBuilder::new("v1", blob, Kind::Commit).verify_with(&objects).build(),
+            Err(Error::ObjectKind {
+                expected: Kind::Commit,
+                actual: Kind::Blob,
+                ..
+            })
+        ));
+        let missing = hex_to_id("ffa700b4aca13b80cb6b98a078e7c96804f8e0ec");
+        assert!(matches!(
+            TagBuilder::new("v1", missing, Kind::Commit).verify_with(&objects).build(),
+            Err(Error::NotFound { id }) if id == missing
+        ));
+        Ok(())
+    }
+}
@@ -53,6 +53,46 @@ mod iter {
         );
         Ok(())
     }
+
+    #[test]
+    fn lookup_entry() -> crate::Result {
+        let data = fixture_name("tree", "everything.tree");
+        for entry in TreeRefIter::from_bytes(&data) {
+            let entry = entry?;
+            assert_eq!(
+                TreeRefIter::from_bytes(&data).lookup_entry(entry.filename)?,
+                Some(entry),
+                "every entry can be found"
+            );
+        }
+        for name in ["a", "file.txt", "subdi", "subdir-other", "zzz"] {
+            assert_eq!(TreeRefIter::from_bytes(&data).lookup_entry(name.into())?, None);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn lookup_entry_stops_decoding_early() -> crate::Result {
+        let data = fixture_name("tree", "everything.tree");
+        let data = &data[..data.len() / 2];
+        assert_eq!(
+            TreeRefIter::from_bytes(data)
+                .lookup_entry("exe".into())?
+                .map(|e| e.filename),
+            Some("exe".into()),
+            "entries after the one we look for are never decoded"
+        );
+        assert_eq!(
+            TreeRefIter::from_bytes(data).lookup_entry("d".into())?,
+            None,
+            "the sort order shows early that there is no such entry"
+        );
+        assert!(
+            TreeRefIter::from_bytes(data).lookup_entry("zzz".into()).is_err(),
+            "but entries that need to be decoded still produce errors"
+        );
+        Ok(())
+    }
 }
 
 mod from_bytes {
@@ -426,7 +426,7 @@ impl super::Store {
         let mut indices_by_modification_time = Vec::with_capacity(initial_capacity.unwrap_or_default());
         for db_path in db_paths {
             let packs = db_path.join("pack");
-            let entries = match std::fs::read_dir(packs) {
+            let entries = match std::fs::read_dir(&packs) {
                 Ok(e) => e,
                 Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
                 Err(err) => return Err(err.into()),
@@ -443,44 +443,57 @@ impl super::Store {
                 .map(|(p, md)| md.modified().map_err(Error::from).map(|mtime| (p, mtime, md.len())))
                 .collect::<Result<Vec<_>, _>>()?;
 
-            let multi_index_info = multi_pack_index_object_hash
-                .and_then(|hash| {
-                    indices.iter().find_map(|(p, a, b)| {
-                        is_multipack_index(p)
-                            .then(|| {
-                                // we always open the multi-pack here to be able to remove indices
-                                gix_pack::multi_index::File::at(p)
-                                    .ok()
-                                    .filter(|midx| midx.object_hash() == hash)
-                                    .map(|midx| (midx, *a, *b))
-                            })
-                            .flatten()
-                            .map(|t| {
-                                if t.0.num_indices() > PackId::max_packs_in_multi_index() {
-                                    Err(Error::TooManyPacksInMultiIndex {
-                                        index_path: p.to_owned(),
-                                        actual: t.0.num_indices(),
-                                        limit: PackId::max_packs_in_multi_index(),
-                                    })
-                                } else {
-                                    Ok(t)
-                                }
-                            })
-                    })
-                })
-                .transpose()?;
-            if let Some((multi_index, mtime, flen)) = multi_index_info {
-                let index_names_in_multi_index: Vec<_> = multi_index.index_names().iter().map(AsRef::as_ref).collect();
-                let mut indices_not_in_multi_index: Vec<(Either, _, _)> = indices
-                    .into_iter()
-                    .filter_map(|(path, a, b)| {
-                        (path != multi_index.path()
-                            && !index_names_in_multi_index
-                                .contains(&Path::new(path.file_name().expect("file name present"))))
-                        .then_some((Either::IndexPath(path), a, b))
-                    })
-                    .collect();
-                indices_not_in_multi_index.insert(0, (Either::MultiIndexFile(Arc::new(multi_index)), mtime, flen));
+            let multi_indices = match multi_pack_index_object_hash {
+                Some(hash) => {
+                    // An incremental multi-pack index takes precedence, like in `git`.
+                    let mut multi_indices = incremental_multi_index_layers(&packs, hash);
+                    if multi_indices.is_empty() {
+                        multi_indices.extend(indices.iter().find_map(|(p, a, b)| {
+                            is_multipack_index(p)
+                                .then(|| {
+                                    // we always open the multi-pack here to be able to remove indices
+                                    gix_pack::multi_index::File::at(p)
+                                        .ok()
+                                        .filter(|midx| midx.object_hash() == hash)
+                                        .map(|midx| (midx, *a, *b))
+                                })
+                                .flatten()
+                        }));
+                    }
+                    multi_indices
+                }
+                None => Vec::new(),
+            };
+            for (multi_index, _, _) in &multi_indices {
+                if multi_index.num_indices() > PackId::max_packs_in_multi_index() {
+                    return Err(Error::TooManyPacksInMultiIndex {
+                        index_path: multi_index.path().to_owned(),
+                        actual: multi_index.num_indices(),
+                        limit: PackId::max_packs_in_multi_index(),
+                    });
+                }
+            }
+            if !multi_indices.is_empty() {
+                let indices_not_in_multi_index: Vec<(Either, _, _)> = {
+                    let index_names_in_multi_index: Vec<_> = multi_indices
+                        .iter()
+                        .flat_map(|(multi_index, _, _)| multi_index.index_names().iter().map(AsRef::as_ref))
+                        .collect();
+                    indices
+                        .into_iter()
+                        .filter_map(|(path, a, b)| {
+                            (!is_multipack_index(&path)
+                                && !index_names_in_multi_index
+                                    .contains(&Path::new(path.file_name().expect("file name present"))))
+                            .then_some((Either::IndexPath(path), a, b))
+                        })
+                        .collect()
+                };
+                indices_by_modification_time.extend(
+                    multi_indices
+                        .into_iter()
+                        .map(|(multi_index, mtime, flen)| (Either::MultiIndexFile(Arc::new(multi_index)), mtime, flen)),
+                );
                 indices_by_modification_time.extend(indices_not_in_multi_index);
             } else {
                 indices_by_modification_time.extend(
@@ -652,6 +665,35 @@ impl super::Store {
 }
 
 // Outside of this method we will never assign new slot indices.
+/// Load all layers of the incremental multi-pack index for the packs in `pack_dir`, from the base layer to the most recent one.
+///
+/// The result is empty if there is no such index, or if any of its layers couldn't be loaded, in which case the packs
+/// are used without it.
+fn incremental_multi_index_layers(
+    pack_dir: &Path,
+    object_hash: gix_hash::Kind,
+) -> Vec<(gix_pack::multi_index::File, SystemTime, u64)> {
+    use gix_pack::multi_index::chain;
+    let Some(checksums) = std::fs::read(chain::path(pack_dir))
+        .ok()
+        .and_then(|data| chain::decode(&data).ok())
+    else {
+        return Vec::new();
+    };
+    checksums
+        .iter()
+        .map(|checksum| {
+            let path = chain::layer_path(pack_dir, checksum);
+            let metadata = path.metadata().ok()?;
+            let layer = gix_pack::multi_index::File::at(&path)
+                .ok()
+                .filter(|layer| layer.object_hash() == object_hash && layer.checksum() == *checksum)?;
+            Some((layer, metadata.modified().ok()?, metadata.len()))
+        })
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default()
+}
+
 fn is_multipack_index(path: &Path) -> bool {
     path.file_name() == Some(OsStr::new("multi-pack-index"))
 }
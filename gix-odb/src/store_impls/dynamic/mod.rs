@@ -78,6 +78,9 @@ pub mod load_index;
 ///
 pub mod verify;
 
+///
+pub mod multi_pack_index;
+
 mod load_one;
 
 mod metrics;
@@ -0,0 +1,223 @@
+use std::{
+    io::BufWriter,
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use gix_features::progress::DynNestedProgress;
+use gix_pack::multi_index::{self, chain};
+
+/// Options for use in [`Store::write_multi_pack_index()`](crate::Store::write_multi_pack_index()).
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Options {
+    /// If `true`, add a layer with all packs that aren't yet contained in the incremental multi-pack index, instead of
+    /// writing a single multi-pack index over all packs.
+    ///
+    /// This is faster when only a few packs were added, but eventually the chain of layers should be collapsed by writing
+    /// a single multi-pack index again.
+    pub incremental: bool,
+}
+
+/// The outcome of [`Store::write_multi_pack_index()`](crate::Store::write_multi_pack_index()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The path to the newly written multi-pack index or layer, or `None` if there were no packs to write it for.
+    pub path: Option<PathBuf>,
+    /// The amount of packs in the newly written multi-pack index or layer.
+    pub num_packs: usize,
+}
+
+/// The error returned by [`Store::write_multi_pack_index()`](crate::Store::write_multi_pack_index()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Could not move the new multi-pack index into place")]
+    Persist(#[from] tempfile::PersistError),
+    #[error(transparent)]
+    Write(#[from] multi_index::write::Error),
+}
+
+const MULTI_PACK_INDEX_FILE_NAME: &str = "multi-pack-index";
+
+impl crate::Store {
+    /// Write a multi-pack index for all packs of this store, excluding those of alternate object databases, to speed up
+    /// object lookups, similar to `git multi-pack-index write [--incremental]`.
+    ///
+    /// Multi-pack index files that became stale in the process are removed, but only after the new file is in place
+    /// so readers always see a consistent state.
+    /// Layers of an incremental multi-pack index that refer to packs that don't exist anymore are removed along with all
+    /// layers on top of them, with their packs being added to the new layer instead.
+    ///
+    /// `progress` and `should_interrupt` are used to observe and abort the operation.
+    pub fn write_multi_pack_index(
+        &self,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let pack_dir = self.path().join("pack");
+        let index_paths = pack_index_paths(&pack_dir)?;
+        let write_options = multi_index::write::Options {
+            object_hash: self.object_hash(),
+        };
+        if options.incremental {
+            return write_layer(&pack_dir, index_paths, progress, should_interrupt, write_options);
+        }
+
+        let path = pack_dir.join(MULTI_PACK_INDEX_FILE_NAME);
+        let num_packs = index_paths.len();
+        if num_packs == 0 {
+            remove_if_present(&path)?;
+        } else {
+            let mut out = BufWriter::new(tempfile::NamedTempFile::new_in(&pack_dir)?);
+            multi_index::File::write_from_index_paths(
+                index_paths,
+                &mut out,
+                progress,
+                should_interrupt,
+                write_options,
+            )?;
+            out.into_inner()
+                .map_err(std::io::IntoInnerError::into_error)?
+                .persist(&path)?;
+        }
+        // An incremental multi-pack index would take precedence over the one we just wrote.
+        remove_if_present(&chain::path(&pack_dir))?;
+        remove_unused_layers(&pack_dir, &[])?;
+        Ok(Outcome {
+            path: (num_packs != 0).then_some(path),
+            num_packs,
+        })
+    }
+}
+
+fn write_layer(
+    pack_dir: &Path,
+    mut index_paths: Vec<PathBuf>,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    options: multi_index::write::Options,
+) -> Result<Outcome, Error> {
+    let chain_path = chain::path(pack_dir);
+    let previous_checksums = match std::fs::read(&chain_path) {
+        Ok(data) => chain::decode(&data).unwrap_or_default(),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err.into()),
+    };
+    // Keep all layers up to the first one that can't be used anymore, as all later ones depend on it.
+    let mut layers = Vec::new();
+    for checksum in &previous_checksums {
+        let layer = match multi_index::File::at(chain::layer_path(pack_dir, checksum)) {
+            Ok(layer) if layer.checksum() == *checksum && layer.object_hash() == options.object_hash => layer,
+            _ => break,
+        };
+        if !layer
+            .index_names()
+            .iter()
+            .all(|name| index_paths.contains(&pack_dir.join(name)))
+        {
+            break;
+        }
+        layers.push(layer);
+    }
+    index_paths.retain(|path| {
+        let name = Path::new(path.file_name().expect("file name present"));
+        layers
+            .iter()
+            .all(|layer| !layer.index_names().iter().any(|indexed| indexed == name))
+    });
+
+    let mut checksums: Vec<_> = layers.iter().map(multi_index::File::checksum).collect();
+    if index_paths.is_empty() && checksums == previous_checksums {
+        return Ok(Outcome {
+            path: None,
+            num_packs: 0,
+        });
+    }
+
+    let layer_dir = pack_dir.join(chain::DIRECTORY_NAME);
+    std::fs::create_dir_all(&layer_dir)?;
+    let num_packs = index_paths.len();
+    let mut path = None;
+    if num_packs != 0 {
+        let mut out = BufWriter::new(tempfile::NamedTempFile::new_in(&layer_dir)?);
+        let outcome = multi_index::File::write_layer_from_index_paths(
+            index_paths,
+            &layers,
+            &mut out,
+            progress,
+            should_interrupt,
+            options,
+        )?;
+        let layer_path = chain::layer_path(pack_dir, &outcome.multi_index_checksum);
+        out.into_inner()
+            .map_err(std::io::IntoInnerError::into_error)?
+            .persist(&layer_path)?;
+        checksums.push(outcome.multi_index_checksum);
+        path = Some(layer_path);
+    }
+
+    if checksums.is_empty() {
+        remove_if_present(&chain_path)?;
+    } else {
+        let mut out = tempfile::NamedTempFile::new_in(&layer_dir)?;
+        chain::write_to(&checksums, &mut out)?;
+        out.persist(&chain_path)?;
+    }
+    // Now that all packs are in the chain, which takes precedence, a single multi-pack index isn't needed anymore.
+    remove_if_present(&pack_dir.join(MULTI_PACK_INDEX_FILE_NAME))?;
+    remove_unused_layers(pack_dir, &checksums)?;
+    Ok(Outcome { path, num_packs })
+}
+
+/// Return the paths to all pack indices in `pack_dir` whose pack is present as well.
+fn pack_index_paths(pack_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let entries = match std::fs::read_dir(pack_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) == Some("idx") && path.with_extension("pack").is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Remove all layers of an incremental multi-pack index in `pack_dir` whose checksum isn't in `checksums`.
+fn remove_unused_layers(pack_dir: &Path, checksums: &[gix_hash::ObjectId]) -> std::io::Result<()> {
+    let layer_dir = pack_dir.join(chain::DIRECTORY_NAME);
+    let entries = match std::fs::read_dir(&layer_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    let used: Vec<_> = checksums
+        .iter()
+        .map(|checksum| chain::layer_path(pack_dir, checksum))
+        .collect();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) == Some("midx") && !used.contains(&path) {
+            remove_if_present(&path)?;
+        }
+    }
+    if checksums.is_empty() {
+        // Other processes might be writing into it, which is fine.
+        std::fs::remove_dir(&layer_dir).ok();
+    }
+    Ok(())
+}
+
+fn remove_if_present(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        res => res,
+    }
+}
@@ -1,6 +1,6 @@
 use std::{
     ops::Deref,
-    sync::atomic::{AtomicBool, Ordering},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
     time::Instant,
 };
 
@@ -207,7 +207,7 @@ impl super::Store {
                         );
                         let outcome = index.verify_integrity(&mut child_progress, should_interrupt, options.clone())?;
 
-                        let index_dir = bundle.multi_index.path().parent().expect("file in a directory");
+                        let index_dir = index.pack_directory();
                         statistics.push(IndexStatistics {
                             path: Default::default(),
                             statistics: SingleOrMultiStatistics::Multi(
@@ -267,3 +267,73 @@ impl super::Store {
         })
     }
 }
+
+/// Verifying multiple object databases at once
+impl super::Store {
+    /// Check the integrity of all objects in each of `stores` just like [`verify_integrity()`](Self::verify_integrity()),
+    /// but verify multiple stores concurrently to make audits of many repositories faster.
+    ///
+    /// The amount of stores verified at the same time is determined by `options.thread_limit`, and the threads are distributed
+    /// among the stores being verified concurrently. Each thread receives its own child of `progress` which is named after
+    /// the path of the store it currently verifies, and `progress` itself counts the amount of verified stores.
+    ///
+    /// Returns the outcome for each store in the order of `stores`, so failures of individual stores don't prevent
+    /// the others from being verified.
+    pub fn verify_integrity_of_many<C, F>(
+        stores: &[&Self],
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: integrity::Options<F>,
+    ) -> Vec<Result<integrity::Outcome, integrity::Error>>
+    where
+        C: pack::cache::DecodeEntry,
+        F: Fn() -> C + Send + Clone,
+    {
+        let _span = gix_features::trace::coarse!("gix_odb:Store::verify_integrity_of_many()");
+        let num_threads = gix_features::parallel::num_threads(options.thread_limit);
+        let concurrency = num_threads.min(stores.len()).max(1);
+        let options = integrity::Options {
+            thread_limit: Some((num_threads / concurrency).max(1)),
+            ..options
+        };
+
+        progress.init(Some(stores.len()), gix_features::progress::count("object databases"));
+        let verified_stores = progress.counter();
+        let next_store = AtomicUsize::new(0);
+        let mut outcomes: Vec<_> = std::iter::repeat_with(|| None).take(stores.len()).collect();
+        gix_features::parallel::threads(|scope| {
+            let threads: Vec<_> = (0..concurrency)
+                .map(|thread_id| {
+                    let mut progress = progress.add_child(format!("thread {thread_id}"));
+                    let (next_store, verified_stores, options) =
+                        (&next_store, verified_stores.clone(), options.clone());
+                    gix_features::parallel::build_thread()
+                        .name(format!("gix_odb::Store::verify_integrity_of_many thread {thread_id}"))
+                        .spawn_scoped(scope, move || {
+                            let mut out = Vec::new();
+                            loop {
+                                let idx = next_store.fetch_add(1, Ordering::SeqCst);
+                                let Some(store) = stores.get(idx) else {
+                                    break out;
+                                };
+                                progress.set_name(store.path().display().to_string());
+                                let res = store.verify_integrity(&mut progress, should_interrupt, options.clone());
+                                verified_stores.fetch_add(1, Ordering::Relaxed);
+                                out.push((idx, res));
+                            }
+                        })
+                        .expect("valid name")
+                })
+                .collect();
+            for thread in threads {
+                for (idx, res) in thread.join().expect("no panic") {
+                    outcomes[idx] = Some(res);
+                }
+            }
+        });
+        outcomes
+            .into_iter()
+            .map(|outcome| outcome.expect("each store was verified"))
+            .collect()
+    }
+}
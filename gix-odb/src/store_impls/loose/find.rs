@@ -264,3 +264,27 @@ impl Store {
         Ok(gix_object::Data { kind, data: buf })
     }
 }
+
+impl gix_object::Find for Store {
+    fn try_find<'a>(
+        &self,
+        id: &gix_hash::oid,
+        buffer: &'a mut Vec<u8>,
+    ) -> Result<Option<gix_object::Data<'a>>, gix_object::find::Error> {
+        Store::try_find(self, id, buffer).map_err(|err| Box::new(err) as _)
+    }
+}
+
+impl gix_object::FindHeader for Store {
+    fn try_header(&self, id: &gix_hash::oid) -> Result<Option<gix_object::Header>, gix_object::find::Error> {
+        Store::try_header(self, id)
+            .map(|maybe_header| maybe_header.map(|(size, kind)| gix_object::Header { kind, size }))
+            .map_err(|err| Box::new(err) as _)
+    }
+}
+
+impl gix_object::Exists for Store {
+    fn exists(&self, id: &gix_hash::oid) -> bool {
+        self.contains(id)
+    }
+}
@@ -955,7 +955,34 @@ mod verify {
     use gix_features::progress;
     use gix_testtools::fixture_path_standalone;
 
-    use crate::store::dynamic::db;
+    use crate::{odb::db_small_packs, store::dynamic::db};
+
+    #[test]
+    fn integrity_of_many() {
+        let (a, b) = (db(), db_small_packs());
+        let stores = [a.store_ref(), b.store_ref(), a.store_ref()];
+        let outcomes = gix_odb::Store::verify_integrity_of_many(
+            &stores,
+            &mut progress::Discard,
+            &AtomicBool::new(false),
+            gix_odb::store::verify::integrity::Options {
+                thread_limit: Some(2),
+                ..Default::default()
+            },
+        );
+        assert_eq!(outcomes.len(), stores.len(), "one outcome per store");
+        for (store, outcome) in stores.iter().zip(outcomes) {
+            let outcome = outcome.expect("all stores are valid");
+            let expected = store
+                .verify_integrity(&mut progress::Discard, &AtomicBool::new(false), Default::default())
+                .unwrap();
+            assert_eq!(
+                outcome.index_statistics, expected.index_statistics,
+                "outcomes are in the order of the input stores"
+            );
+            assert_eq!(outcome.loose_object_stores, expected.loose_object_stores);
+        }
+    }
 
     #[test]
     fn integrity() {
@@ -1003,3 +1030,103 @@ mod verify {
         );
     }
 }
+
+mod write_multi_pack_index {
+    use std::{path::Path, sync::atomic::AtomicBool};
+
+    use gix_odb::store::multi_pack_index::{Options, Outcome};
+    use gix_pack::multi_index::chain;
+    use gix_testtools::fixture_path_standalone;
+
+    const PACK: &str = "pack-11fdfa9e156ab73caae3b6da867192221f2089c2";
+
+    fn write(objects_dir: &Path, incremental: bool) -> crate::Result<Outcome> {
+        Ok(gix_odb::at(objects_dir)?.store_ref().write_multi_pack_index(
+            &mut gix_features::progress::Discard,
+            &AtomicBool::default(),
+            Options { incremental },
+        )?)
+    }
+
+    fn move_pack(from: &Path, to: &Path) -> std::io::Result<()> {
+        for ext in ["idx", "pack"] {
+            std::fs::rename(from.join(format!("{PACK}.{ext}")), to.join(format!("{PACK}.{ext}")))?;
+        }
+        Ok(())
+    }
+
+    fn chain_len(pack_dir: &Path) -> crate::Result<usize> {
+        Ok(chain::decode(&std::fs::read(chain::path(pack_dir))?)?.len())
+    }
+
+    fn assert_all_objects_are_found(objects_dir: &Path) -> crate::Result<usize> {
+        let expected = gix_odb::at(fixture_path_standalone("objects"))?.iter()?.count();
+        let handle = gix_odb::at(objects_dir)?;
+        let mut count = 0;
+        for id in handle.iter()? {
+            assert!(gix_object::Exists::exists(&handle, &id?));
+            count += 1;
+        }
+        assert_eq!(count, expected, "all objects are found, and none twice");
+        Ok(handle.store_ref().metrics().known_reachable_indices)
+    }
+
+    #[test]
+    fn single_and_incremental() -> crate::Result {
+        let objects_dir = gix_testtools::tempfile::tempdir()?;
+        gix_testtools::copy_recursively_into_existing_dir(fixture_path_standalone("objects"), &objects_dir)?;
+        let pack_dir = objects_dir.path().join("pack");
+        let aside = gix_testtools::tempfile::tempdir()?;
+        move_pack(&pack_dir, aside.path())?;
+
+        let outcome = write(objects_dir.path(), true)?;
+        assert_eq!(outcome.num_packs, 2);
+        let layer = outcome.path.expect("written");
+        assert_eq!(layer.parent(), Some(pack_dir.join(chain::DIRECTORY_NAME).as_path()));
+        assert_eq!(chain_len(&pack_dir)?, 1);
+
+        let outcome = write(objects_dir.path(), true)?;
+        assert_eq!(outcome.path, None, "there is nothing to do if there are no new packs");
+        assert_eq!(chain_len(&pack_dir)?, 1);
+
+        move_pack(aside.path(), &pack_dir)?;
+        let outcome = write(objects_dir.path(), true)?;
+        assert_eq!(outcome.num_packs, 1, "only the new pack is in the new layer");
+        assert_eq!(chain_len(&pack_dir)?, 2);
+        assert!(layer.is_file(), "the previous layer is still used");
+        assert_eq!(
+            assert_all_objects_are_found(objects_dir.path())?,
+            2,
+            "both layers are used as multi-pack index each"
+        );
+
+        let outcome = write(objects_dir.path(), false)?;
+        assert_eq!(outcome.num_packs, 3);
+        assert_eq!(outcome.path, Some(pack_dir.join("multi-pack-index")));
+        assert!(
+            !pack_dir.join(chain::DIRECTORY_NAME).exists(),
+            "the incremental multi-pack index would take precedence, and is removed"
+        );
+        assert_eq!(assert_all_objects_are_found(objects_dir.path())?, 1);
+
+        let outcome = write(objects_dir.path(), true)?;
+        assert_eq!(outcome.num_packs, 3, "all packs are in the first layer");
+        assert!(
+            !pack_dir.join("multi-pack-index").exists(),
+            "the single multi-pack index would be ignored, and is removed"
+        );
+        move_pack(&pack_dir, aside.path())?;
+        let outcome = write(objects_dir.path(), true)?;
+        assert_eq!(
+            outcome.num_packs, 2,
+            "the layer with the removed pack is invalid and replaced with one with the remaining packs"
+        );
+        assert_eq!(chain_len(&pack_dir)?, 1);
+        assert_eq!(
+            std::fs::read_dir(pack_dir.join(chain::DIRECTORY_NAME))?.count(),
+            2,
+            "the chain file and the only layer, the stale one is removed"
+        );
+        Ok(())
+    }
+}
@@ -425,6 +425,24 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
             }
             Ok(())
         }
+
+        #[test]
+        fn via_traits() -> crate::Result {
+            let db = ldb();
+            let mut buf = Vec::new();
+            for id in db.iter() {
+                let id = id?;
+                let expected = gix_object::Find::try_find(&db, &id, &mut buf)?.expect("exists");
+                let header = gix_object::FindHeader::try_header(&db, &id)?.expect("header exists");
+                assert_eq!(header.size, expected.data.len() as u64);
+                assert_eq!(header.kind, expected.kind);
+                assert!(gix_object::Exists::exists(&db, &id));
+            }
+            let missing = hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+            assert_eq!(gix_object::FindHeader::try_header(&db, &missing)?, None);
+            assert!(!gix_object::Exists::exists(&db, &missing));
+            Ok(())
+        }
     }
 }
 
@@ -14,4 +14,10 @@ pub enum Error {
     Persist(#[from] gix_tempfile::handle::persist::Error<Writable>),
     #[error(transparent)]
     IndexWrite(#[from] crate::index::write::Error),
+    #[cfg(feature = "generate")]
+    #[error("Could not open the newly written pack to compute its bitmaps")]
+    BundleInit(#[from] crate::bundle::init::Error),
+    #[cfg(feature = "generate")]
+    #[error(transparent)]
+    Bitmap(#[from] crate::data::output::bitmap::Error),
 }
@@ -141,6 +141,7 @@ impl crate::Bundle {
             data_path,
             index_path,
             keep_path,
+            bitmap_path,
         } = crate::Bundle::inner_write(
             directory,
             progress,
@@ -158,6 +159,7 @@ impl crate::Bundle {
             data_path,
             index_path,
             keep_path,
+            bitmap_path,
         })
     }
 
@@ -241,6 +243,7 @@ impl crate::Bundle {
             data_path,
             index_path,
             keep_path,
+            bitmap_path,
         } = crate::Bundle::inner_write(
             directory,
             progress,
@@ -258,6 +261,7 @@ impl crate::Bundle {
             data_path,
             index_path,
             keep_path,
+            bitmap_path,
         })
     }
 
@@ -269,6 +273,8 @@ impl crate::Bundle {
             iteration_mode: _,
             index_version: index_kind,
             object_hash,
+            #[cfg(feature = "generate")]
+            bitmap_commits,
         }: Options,
         data_file: SharedTempFile,
         mut pack_entries_iter: Box<dyn Iterator<Item = Result<data::input::Entry, data::input::Error>> + 'a>,
@@ -320,11 +326,28 @@ impl crate::Bundle {
                         ));
                         err
                     })?;
+                #[cfg(feature = "generate")]
+                let bitmap_path = if bitmap_commits.is_empty() {
+                    None
+                } else {
+                    let bitmap_path = data_path.with_extension("bitmap");
+                    write_bitmap(
+                        &index_path,
+                        &bitmap_path,
+                        object_hash,
+                        &bitmap_commits,
+                        should_interrupt,
+                    )?;
+                    Some(bitmap_path)
+                };
+                #[cfg(not(feature = "generate"))]
+                let bitmap_path = None;
                 WriteOutcome {
                     outcome,
                     data_path: Some(data_path),
                     index_path: Some(index_path),
                     keep_path: Some(keep_path),
+                    bitmap_path,
                 }
             }
             None => WriteOutcome {
@@ -342,11 +365,40 @@ impl crate::Bundle {
                 data_path: None,
                 index_path: None,
                 keep_path: None,
+                bitmap_path: None,
             },
         })
     }
 }
 
+/// Compute the bitmaps of `commits` in the bundle whose index is at `index_path`, and write them to `bitmap_path`.
+#[cfg(feature = "generate")]
+fn write_bitmap(
+    index_path: &Path,
+    bitmap_path: &Path,
+    object_hash: gix_hash::Kind,
+    commits: &[gix_hash::ObjectId],
+    should_interrupt: &AtomicBool,
+) -> Result<(), Error> {
+    let bundle = crate::Bundle::at(index_path, object_hash)?;
+    let mut builder = data::output::bitmap::Builder::new(&bundle);
+    for commit in commits {
+        builder.add_commit(commit, should_interrupt)?;
+    }
+    let directory = bitmap_path.parent().expect("bitmaps are written next to the index");
+    let mut bitmap_file = io::BufWriter::new(gix_tempfile::new(
+        directory,
+        ContainingDirectory::Exists,
+        AutoRemove::Tempfile,
+    )?);
+    builder.write_to(&mut bitmap_file)?;
+    bitmap_file
+        .into_inner()
+        .map_err(|err| Error::from(err.into_error()))?
+        .persist(bitmap_path)?;
+    Ok(())
+}
+
 fn resolve_entry(range: data::EntryRange, mapped_file: &memmap2::Mmap) -> Option<&[u8]> {
     mapped_file.get(range.start as usize..range.end as usize)
 }
@@ -368,4 +420,5 @@ struct WriteOutcome {
     data_path: Option<PathBuf>,
     index_path: Option<PathBuf>,
     keep_path: Option<PathBuf>,
+    bitmap_path: Option<PathBuf>,
 }
@@ -14,6 +14,11 @@ pub struct Options {
     pub index_version: crate::index::Version,
     /// The kind of hash to use when writing the bundle.
     pub object_hash: gix_hash::Kind,
+    /// If not empty, compute reachability bitmaps for these commits and write them into a `.bitmap` file next to the index.
+    ///
+    /// Note that this requires the pack to contain all objects reachable from these commits.
+    #[cfg(feature = "generate")]
+    pub bitmap_commits: Vec<gix_hash::ObjectId>,
 }
 
 impl Default for Options {
@@ -24,6 +29,8 @@ impl Default for Options {
             iteration_mode: crate::data::input::Mode::Verify,
             index_version: Default::default(),
             object_hash: Default::default(),
+            #[cfg(feature = "generate")]
+            bitmap_commits: Vec::new(),
         }
     }
 }
@@ -49,6 +56,8 @@ pub struct Outcome {
     /// The file is created right before moving the pack data and index data into place (i.e. `data_path` and `index_path`)
     /// and is expected to be removed by the caller when ready.
     pub keep_path: Option<PathBuf>,
+    /// The path to the `.bitmap` file with reachability bitmaps, if [bitmaps were requested](Options::bitmap_commits).
+    pub bitmap_path: Option<PathBuf>,
 }
 
 impl Outcome {
@@ -0,0 +1,222 @@
+use std::{
+    io,
+    io::Write,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_features::zlib;
+use gix_hash::{oid, ObjectId};
+
+use crate::{data, index};
+
+/// The error returned by [`Builder::add_commit()`] and [`Builder::write_to()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Object {id} is reachable from a bitmapped commit, but isn't contained in the pack")]
+    MissingObject { id: ObjectId },
+    #[error("Bitmaps can only be created for commits, but {id} is a {kind}")]
+    NotACommit { id: ObjectId, kind: gix_object::Kind },
+    #[error(transparent)]
+    DecodeEntry(#[from] data::decode::Error),
+    #[error(transparent)]
+    DecodeObject(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// The signature at the beginning of each bitmap file.
+const SIGNATURE: &[u8] = b"BITM";
+/// The only version of bitmap files we can write.
+const VERSION: u16 = 1;
+/// A flag that must be set as the bitmaps contain all reachable objects, not only those in the pack of the previous bitmap.
+const OPTION_FULL_DAG: u16 = 0x1;
+
+/// A utility to compute reachability bitmaps for commits in a pack, and to write them into a `.bitmap` file that is placed next
+/// to the pack index, similar to what `git repack -b` does.
+///
+/// Each bit in a bitmap represents an object in the pack, sorted by its offset within the pack, and is set if the object
+/// is reachable from the commit the bitmap belongs to.
+/// This allows servers to quickly count the objects to send, and to determine which objects the client already has.
+///
+/// Note that the pack has to contain all objects reachable from the commits to create bitmaps for.
+pub struct Builder<'a> {
+    bundle: &'a crate::Bundle,
+    /// The position of each object within the pack, sorted by pack offset, indexed by its position in the pack index.
+    pack_positions: Vec<u32>,
+    /// The position of each bitmapped commit in the pack index, along with its uncompressed bitmap, in order of addition.
+    bitmaps: Vec<(index::EntryIndex, Vec<u64>)>,
+    /// A mapping of the position of bitmapped commits in the pack index to their bitmap.
+    bitmap_by_index: std::collections::HashMap<index::EntryIndex, usize>,
+    buf: Vec<u8>,
+    inflate: zlib::Inflate,
+}
+
+/// Lifecycle
+impl<'a> Builder<'a> {
+    /// Create a new instance to create bitmaps for commits contained in `bundle`.
+    pub fn new(bundle: &'a crate::Bundle) -> Self {
+        let index = &bundle.index;
+        let mut index_positions_by_offset: Vec<index::EntryIndex> = (0..index.num_objects()).collect();
+        index_positions_by_offset.sort_by_key(|idx| index.pack_offset_at_index(*idx));
+        let mut pack_positions = vec![0; index_positions_by_offset.len()];
+        for (pack_position, idx) in index_positions_by_offset.into_iter().enumerate() {
+            pack_positions[idx as usize] = pack_position as u32;
+        }
+        Builder {
+            bundle,
+            pack_positions,
+            bitmaps: Vec::new(),
+            bitmap_by_index: Default::default(),
+            buf: Vec::new(),
+            inflate: Default::default(),
+        }
+    }
+}
+
+impl Builder<'_> {
+    /// Compute the bitmap of all objects reachable from `commit`, reusing the bitmaps of previously added commits
+    /// that are reachable from it.
+    ///
+    /// It's hence most efficient to add commits from the oldest to the newest.
+    /// Adding the same commit more than once has no effect.
+    /// `should_interrupt` is checked regularly to stop the operation early.
+    pub fn add_commit(&mut self, commit: &oid, should_interrupt: &AtomicBool) -> Result<(), Error> {
+        let Builder {
+            bundle,
+            pack_positions,
+            bitmaps,
+            bitmap_by_index,
+            buf,
+            inflate,
+        } = self;
+        let commit_index = lookup(bundle, commit)?;
+        if bitmap_by_index.contains_key(&commit_index) {
+            return Ok(());
+        }
+        let kind = kind_at_index(bundle, commit_index)?;
+        if kind != gix_object::Kind::Commit {
+            return Err(Error::NotACommit {
+                id: commit.to_owned(),
+                kind,
+            });
+        }
+
+        let mut bits = vec![0u64; words_for(pack_positions.len())];
+        let mut next = vec![commit.to_owned()];
+        while let Some(id) = next.pop() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let idx = lookup(bundle, &id)?;
+            let pack_position = pack_positions[idx as usize] as usize;
+            let (word, bit) = (pack_position / 64, 1 << (pack_position % 64));
+            if bits[word] & bit != 0 {
+                continue;
+            }
+            bits[word] |= bit;
+            if let Some(bitmap) = bitmap_by_index.get(&idx) {
+                for (word, reachable) in bits.iter_mut().zip(&bitmaps[*bitmap].1) {
+                    *word |= reachable;
+                }
+                continue;
+            }
+
+            let (object, _location) = bundle.get_object_by_index(idx, buf, inflate, &mut crate::cache::Never)?;
+            match object.kind {
+                gix_object::Kind::Commit => {
+                    let commit = gix_object::CommitRef::from_bytes(object.data)?;
+                    next.push(commit.tree());
+                    next.extend(commit.parents());
+                }
+                gix_object::Kind::Tree => {
+                    for entry in gix_object::TreeRefIter::from_bytes(object.data) {
+                        let entry = entry?;
+                        // Submodule commits are never part of the pack.
+                        if !entry.mode.is_commit() {
+                            next.push(entry.oid.to_owned());
+                        }
+                    }
+                }
+                gix_object::Kind::Tag => {
+                    next.push(gix_object::TagRefIter::from_bytes(object.data).target_id()?);
+                }
+                gix_object::Kind::Blob => {}
+            }
+        }
+        bitmap_by_index.insert(commit_index, bitmaps.len());
+        bitmaps.push((commit_index, bits));
+        Ok(())
+    }
+
+    /// Write all bitmaps in the format of a `.bitmap` file into `out`, along with bitmaps for the objects of each kind in the pack,
+    /// and return the checksum of the written data.
+    pub fn write_to(&self, out: &mut dyn io::Write) -> Result<ObjectId, Error> {
+        let object_hash = self.bundle.index.object_hash();
+        let mut out = gix_features::hash::Write::new(out, object_hash);
+
+        let mut by_kind = [(); 4].map(|_| vec![0u64; words_for(self.pack_positions.len())]);
+        for (idx, pack_position) in self.pack_positions.iter().enumerate() {
+            let bitmap = match kind_at_index(self.bundle, idx as index::EntryIndex)? {
+                gix_object::Kind::Commit => &mut by_kind[0],
+                gix_object::Kind::Tree => &mut by_kind[1],
+                gix_object::Kind::Blob => &mut by_kind[2],
+                gix_object::Kind::Tag => &mut by_kind[3],
+            };
+            let pack_position = *pack_position as usize;
+            bitmap[pack_position / 64] |= 1 << (pack_position % 64);
+        }
+
+        out.write_all(SIGNATURE)?;
+        out.write_all(&VERSION.to_be_bytes())?;
+        out.write_all(&OPTION_FULL_DAG.to_be_bytes())?;
+        out.write_all(&(self.bitmaps.len() as u32).to_be_bytes())?;
+        out.write_all(self.bundle.pack.checksum().as_slice())?;
+        for bitmap in &by_kind {
+            ewah(bitmap).write_to(&mut out)?;
+        }
+        for (commit_index, bitmap) in &self.bitmaps {
+            out.write_all(&commit_index.to_be_bytes())?;
+            // We don't yet store bitmaps as difference to one of the previous bitmaps, and have no flags to set.
+            let (xor_offset, flags) = (0u8, 0u8);
+            out.write_all(&[xor_offset, flags])?;
+            ewah(bitmap).write_to(&mut out)?;
+        }
+
+        let checksum = ObjectId::from(out.hash.digest());
+        out.inner.write_all(checksum.as_slice())?;
+        Ok(checksum)
+    }
+}
+
+fn ewah(words: &[u64]) -> gix_bitmap::ewah::Vec {
+    gix_bitmap::ewah::Vec::from_words(words, (words.len() * 64) as u32)
+}
+
+fn words_for(num_objects: usize) -> usize {
+    (num_objects + 63) / 64
+}
+
+fn lookup(bundle: &crate::Bundle, id: &oid) -> Result<index::EntryIndex, Error> {
+    bundle
+        .index
+        .lookup(id)
+        .ok_or_else(|| Error::MissingObject { id: id.to_owned() })
+}
+
+/// Return the kind of the object at `idx` in the index of `bundle`, following delta chains without decompressing any data.
+fn kind_at_index(bundle: &crate::Bundle, idx: index::EntryIndex) -> Result<gix_object::Kind, Error> {
+    let mut entry = bundle.pack.entry(bundle.index.pack_offset_at_index(idx));
+    loop {
+        entry = match entry.header {
+            data::entry::Header::OfsDelta { base_distance } => bundle.pack.entry(entry.base_pack_offset(base_distance)),
+            data::entry::Header::RefDelta { base_id } => {
+                let base_idx = lookup(bundle, &base_id)?;
+                bundle.pack.entry(bundle.index.pack_offset_at_index(base_idx))
+            }
+            _ => return Ok(entry.header.as_kind().expect("no delta")),
+        }
+    }
+}
@@ -56,27 +56,32 @@ where
     };
     let seen_objs = gix_hashtable::sync::ObjectIdMap::default();
     let objects = objects.counter();
+    // The reducer returns the counts of each chunk once it took them, so threads don't allocate them anew for each chunk.
+    let counts = parallel::ScratchPool::new(parallel::num_threads(thread_limit));
 
     parallel::in_parallel(
         chunks,
         thread_limit,
         {
+            let counts = counts.clone();
             move |_| {
                 (
                     Vec::new(), // object data buffer
                     Vec::new(), // object data buffer 2 to hold two objects at a time
                     objects.clone(),
+                    counts,
                 )
             }
         },
         {
             let seen_objs = &seen_objs;
-            move |oids: Vec<_>, (buf1, buf2, objects)| {
+            move |oids: Vec<_>, (buf1, buf2, objects, counts)| {
                 expand::this(
                     &db,
                     input_object_expansion,
                     seen_objs,
                     &mut oids.into_iter(),
+                    counts.take(),
                     buf1,
                     buf2,
                     objects,
@@ -85,7 +90,7 @@ where
                 )
             }
         },
-        reduce::Statistics::new(),
+        reduce::Statistics::new(Some(counts)),
     )
 }
 
@@ -105,6 +110,7 @@ pub fn objects_unthreaded(
         input_object_expansion,
         &seen_objs,
         object_ids,
+        Vec::new(),
         &mut buf1,
         &mut buf2,
         &objects.counter(),
@@ -138,6 +144,7 @@ mod expand {
         input_object_expansion: ObjectExpansion,
         seen_objs: &impl util::InsertImmutable,
         oids: &mut dyn Iterator<Item = Result<ObjectId, Box<dyn std::error::Error + Send + Sync + 'static>>>,
+        mut out: Vec<output::Count>,
         buf1: &mut Vec<u8>,
         #[allow(clippy::ptr_arg)] buf2: &mut Vec<u8>,
         objects: &gix_features::progress::AtomicStep,
@@ -146,7 +153,6 @@ mod expand {
     ) -> Result<(Vec<output::Count>, Outcome), Error> {
         use ObjectExpansion::*;
 
-        let mut out = Vec::new();
         let mut tree_traversal_state = gix_traverse::tree::breadthfirst::State::default();
         let mut tree_diff_state = gix_diff::tree::State::default();
         let mut parent_commit_ids = Vec::new();
@@ -8,14 +8,17 @@ use crate::data::output;
 pub struct Statistics<E> {
     total: Outcome,
     counts: Vec<output::Count>,
+    /// A place to return the emptied counts of each chunk to for reuse.
+    scratch: Option<parallel::ScratchPool<Vec<output::Count>>>,
     _err: PhantomData<E>,
 }
 
 impl<E> Statistics<E> {
-    pub fn new() -> Self {
+    pub fn new(scratch: Option<parallel::ScratchPool<Vec<output::Count>>>) -> Self {
         Statistics {
             total: Default::default(),
             counts: Default::default(),
+            scratch,
             _err: PhantomData,
         }
     }
@@ -28,9 +31,12 @@ impl<E> parallel::Reduce for Statistics<E> {
     type Error = E;
 
     fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
-        let (counts, stats) = item?;
+        let (mut counts, stats) = item?;
         self.total.aggregate(stats);
-        self.counts.extend(counts);
+        self.counts.append(&mut counts);
+        if let Some(scratch) = &self.scratch {
+            scratch.put(counts);
+        }
         Ok(())
     }
 
@@ -60,6 +60,8 @@ pub struct Options {
     /// The amount of threads to use at most when resolving the pack. If `None`, all logical cores are used.
     /// If more than one thread is used, the order of returned [counts][crate::data::output::Count] is not deterministic anymore
     /// especially when tree traversal is involved. Thus deterministic ordering requires `Some(1)` to be set.
+    /// Note that packs can be reproducible nonetheless when generated with
+    /// [`stable_order`](crate::data::output::entry::iter_from_counts::Options::stable_order).
     pub thread_limit: Option<usize>,
     /// The amount of objects per chunk or unit of work to be sent to threads for processing
     pub chunk_size: usize,
@@ -52,6 +52,9 @@ pub(crate) mod function {
             allow_thin_pack,
             thread_limit,
             chunk_size,
+            compression_level,
+            stable_order,
+            chunks_in_flight,
         }: Options,
     ) -> impl Iterator<Item = Result<(SequenceId, Vec<output::Entry>), Error>>
            + parallel::reduce::Finalize<Reduce = reduce::Statistics<Error>>
@@ -105,7 +108,13 @@ pub(crate) mod function {
 
                 use crate::data::output::count::PackLocation::*;
                 counts.sort_by(|lhs, rhs| match (&lhs.entry_pack_location, &rhs.entry_pack_location) {
-                    (LookedUp(None), LookedUp(None)) => Ordering::Equal,
+                    (LookedUp(None), LookedUp(None)) => {
+                        if stable_order {
+                            lhs.id.cmp(&rhs.id)
+                        } else {
+                            Ordering::Equal
+                        }
+                    }
                     (LookedUp(Some(_)), LookedUp(None)) => Ordering::Greater,
                     (LookedUp(None), LookedUp(Some(_))) => Ordering::Less,
                     (LookedUp(Some(lhs)), LookedUp(Some(rhs))) => lhs
@@ -139,9 +148,10 @@ pub(crate) mod function {
         let progress = Arc::new(parking_lot::Mutex::new(progress));
         let chunks = util::ChunkRanges::new(chunk_size, counts.len());
 
-        parallel::reduce::Stepwise::new(
+        parallel::reduce::Stepwise::new_bounded(
             chunks.enumerate(),
             thread_limit,
+            chunks_in_flight.unwrap_or_else(|| parallel::num_threads(thread_limit)),
             {
                 let progress = Arc::clone(&progress);
                 move |n| {
@@ -214,7 +224,11 @@ pub(crate) mod function {
                                     None => match db.try_find(&count.id, buf).map_err(Error::Find)? {
                                         Some((obj, _location)) => {
                                             stats.decoded_and_recompressed_objects += 1;
-                                            output::Entry::from_data(count, &obj)
+                                            output::Entry::from_data_with_compression_level(
+                                                count,
+                                                &obj,
+                                                compression_level,
+                                            )
                                         }
                                         None => {
                                             stats.missing_objects += 1;
@@ -226,7 +240,7 @@ pub(crate) mod function {
                             None => match db.try_find(&count.id, buf).map_err(Error::Find)? {
                                 Some((obj, _location)) => {
                                     stats.decoded_and_recompressed_objects += 1;
-                                    output::Entry::from_data(count, &obj)
+                                    output::Entry::from_data_with_compression_level(count, &obj, compression_level)
                                 }
                                 None => {
                                     stats.missing_objects += 1;
@@ -385,6 +399,22 @@ mod types {
         pub chunk_size: usize,
         /// The pack data version to produce for each entry
         pub version: crate::data::Version,
+        /// The zlib compression level from 0 to 9 to use for objects that aren't copied from an existing pack,
+        /// or `None` to use the fast default.
+        pub compression_level: Option<u8>,
+        /// If `true`, objects that aren't copied from an existing pack are ordered by their id, so that the pack doesn't
+        /// depend on the order of the input counts, which isn't stable if they were produced with multiple threads.
+        ///
+        /// Together with a fixed `compression_level`, this makes packs reproducible, i.e. the same counts and object
+        /// database always produce the same pack, byte for byte, as packs don't contain timestamps or other varying data.
+        /// Note that the compressed bytes may still differ between zlib backends.
+        pub stable_order: bool,
+        /// The amount of chunks that may be queued for threads to process, as well as the amount of processed chunks that may
+        /// be queued for consumption, before the producing side blocks. If `None`, it's the amount of threads.
+        ///
+        /// A small amount keeps memory usage stable if chunks are large or the consumer is slow, like when sending the
+        /// pack over the network, at the cost of threads idling if chunks take differently long to process.
+        pub chunks_in_flight: Option<usize>,
     }
 
     impl Default for Options {
@@ -395,6 +425,9 @@ mod types {
                 allow_thin_pack: false,
                 chunk_size: 10,
                 version: Default::default(),
+                compression_level: None,
+                stable_order: false,
+                chunks_in_flight: None,
             }
         }
     }
@@ -127,12 +127,22 @@ impl output::Entry {
 
     /// Create a new instance from the given `oid` and its corresponding git object data `obj`.
     pub fn from_data(count: &output::Count, obj: &gix_object::Data<'_>) -> Result<Self, Error> {
+        Self::from_data_with_compression_level(count, obj, None)
+    }
+
+    /// Like [`from_data()`](Self::from_data()), but compress `obj` with the given zlib compression `level` from 0 to 9,
+    /// or with the fast default if `None`.
+    pub fn from_data_with_compression_level(
+        count: &output::Count,
+        obj: &gix_object::Data<'_>,
+        level: Option<u8>,
+    ) -> Result<Self, Error> {
         Ok(output::Entry {
             id: count.id.to_owned(),
             kind: Kind::Base(obj.kind),
             decompressed_size: obj.data.len(),
             compressed_data: {
-                let mut out = gix_features::zlib::stream::deflate::Write::new(Vec::new());
+                let mut out = gix_features::zlib::stream::deflate::Write::with_compression_level(Vec::new(), level);
                 if let Err(err) = std::io::copy(&mut &*obj.data, &mut out) {
                     match err.kind() {
                         std::io::ErrorKind::Other => return Err(Error::ZlibDeflate(err)),
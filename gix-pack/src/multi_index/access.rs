@@ -34,6 +34,19 @@ impl File {
     pub fn path(&self) -> &Path {
         &self.path
     }
+    /// Returns the directory containing the packs whose indices are listed in this file.
+    ///
+    /// It's the directory containing this file, or its parent if this file is a layer of an [incremental multi-pack index](super::chain).
+    pub fn pack_directory(&self) -> &Path {
+        let parent = self.path.parent().expect("must be in a directory");
+        if parent.file_name().and_then(|name| name.to_str()) == Some(super::chain::DIRECTORY_NAME) {
+            parent
+                .parent()
+                .expect("the layer directory is within the pack directory")
+        } else {
+            parent
+        }
+    }
     /// Returns the amount of indices stored in this multi-index file. It's the same as [File::index_names().len()][File::index_names()],
     /// and returned as one past the highest known index.
     pub fn num_indices(&self) -> PackIndex {
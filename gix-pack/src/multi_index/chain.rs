@@ -0,0 +1,37 @@
+use std::path::{Path, PathBuf};
+
+use gix_hash::{oid, ObjectId};
+
+/// The name of the directory within the pack directory that contains the layers of an incremental multi-pack index,
+/// along with the file listing them.
+pub const DIRECTORY_NAME: &str = "multi-pack-index.d";
+/// The name of the file that lists the checksums of all layers of an incremental multi-pack index.
+pub const FILE_NAME: &str = "multi-pack-index-chain";
+
+/// Return the path to the chain file of the incremental multi-pack index for the packs in `pack_dir`.
+pub fn path(pack_dir: &Path) -> PathBuf {
+    pack_dir.join(DIRECTORY_NAME).join(FILE_NAME)
+}
+
+/// Return the path to the layer of the incremental multi-pack index for the packs in `pack_dir` whose checksum is `checksum`.
+pub fn layer_path(pack_dir: &Path, checksum: &oid) -> PathBuf {
+    pack_dir
+        .join(DIRECTORY_NAME)
+        .join(format!("multi-pack-index-{}.midx", checksum.to_hex()))
+}
+
+/// Parse the content of a chain file into the checksums of its layers, from the base layer to the most recent one.
+pub fn decode(data: &[u8]) -> Result<Vec<ObjectId>, gix_hash::decode::Error> {
+    data.split(|b| *b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(ObjectId::from_hex)
+        .collect()
+}
+
+/// Write the checksums of all `layers`, from the base layer to the most recent one, as chain file to `out`.
+pub fn write_to(layers: &[ObjectId], out: &mut dyn std::io::Write) -> std::io::Result<()> {
+    for checksum in layers {
+        writeln!(out, "{checksum}")?;
+    }
+    Ok(())
+}
@@ -50,3 +50,9 @@ pub mod chunk;
 
 ///
 pub mod init;
+
+/// Utilities for incremental multi-pack indices, whose layers are listed in a chain file.
+///
+/// Each layer is a multi-pack index on its own, but it only refers to packs and objects that aren't contained in any of the layers
+/// before it.
+pub mod chain;
@@ -78,7 +78,33 @@ impl multi_index::File {
     ///
     /// Progress is sent to `progress` and interruptions checked via `should_interrupt`.
     pub fn write_from_index_paths(
+        index_paths: Vec<PathBuf>,
+        out: &mut dyn std::io::Write,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        Self::write_from_index_paths_inner(index_paths, &[], out, progress, should_interrupt, options)
+    }
+
+    /// Like [`write_from_index_paths()`](Self::write_from_index_paths()), but write a layer of an
+    /// [incremental multi-pack index](multi_index::chain) on top of `base_layers`, from the base layer to the most recent one.
+    ///
+    /// Objects that are contained in any of the `base_layers` are left out, so each object is only listed in a single layer.
+    pub fn write_layer_from_index_paths(
+        index_paths: Vec<PathBuf>,
+        base_layers: &[multi_index::File],
+        out: &mut dyn std::io::Write,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        Self::write_from_index_paths_inner(index_paths, base_layers, out, progress, should_interrupt, options)
+    }
+
+    fn write_from_index_paths_inner(
         mut index_paths: Vec<PathBuf>,
+        base_layers: &[multi_index::File],
         out: &mut dyn std::io::Write,
         progress: &mut dyn DynNestedProgress,
         should_interrupt: &AtomicBool,
@@ -134,6 +160,9 @@ impl multi_index::File {
                     .then_with(|| l.pack_index.cmp(&r.pack_index))
             });
             entries.dedup_by_key(|e| e.id);
+            if !base_layers.is_empty() {
+                entries.retain(|e| base_layers.iter().all(|layer| layer.lookup(e.id).is_none()));
+            }
             progress.inc_by(entries.len());
             progress.show_throughput(start);
             if should_interrupt.load(Ordering::Relaxed) {
@@ -95,7 +95,7 @@ mod write_to_directory {
     use gix_testtools::tempfile::TempDir;
 
     use crate::{
-        fixture_path,
+        fixture_path, hex_to_id,
         pack::{SMALL_PACK, SMALL_PACK_INDEX},
     };
 
@@ -111,13 +111,14 @@ mod write_to_directory {
             index_path: None,
             data_path: None,
             keep_path: None,
+            bitmap_path: None,
             object_hash: gix_hash::Kind::Sha1,
         })
     }
 
     #[test]
     fn without_providing_one() -> Result<(), Box<dyn std::error::Error>> {
-        let res = write_pack(None::<&Path>, SMALL_PACK)?;
+        let res = write_pack(None::<&Path>, SMALL_PACK, Vec::new())?;
         assert_eq!(res, expected_outcome()?);
         assert_eq!(
             res.index.index_hash,
@@ -130,7 +131,7 @@ mod write_to_directory {
     #[test]
     fn given_a_directory() -> Result<(), Box<dyn std::error::Error>> {
         let dir = TempDir::new()?;
-        let mut res = write_pack(Some(&dir), SMALL_PACK)?;
+        let mut res = write_pack(Some(&dir), SMALL_PACK, Vec::new())?;
         let (index_path, data_path, keep_path) = (res.index_path.take(), res.data_path.take(), res.keep_path.take());
         assert_eq!(res, expected_outcome()?);
         let mut sorted_entries = fs::read_dir(&dir)?.filter_map(Result::ok).collect::<Vec<_>>();
@@ -154,6 +155,34 @@ mod write_to_directory {
         Ok(())
     }
 
+    #[test]
+    fn given_a_directory_and_commits_to_create_bitmaps_for() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let tip = hex_to_id("779c5451ba9fe210ffd1f55db202e55f51acecac");
+        let res = write_pack(Some(&dir), SMALL_PACK, vec![tip])?;
+        let pack_hash = res.index.data_hash.to_hex();
+        let bitmap_path = res.bitmap_path.expect("bitmap was requested");
+        assert_eq!(
+            bitmap_path,
+            dir.path().join(format!("pack-{pack_hash}.bitmap")),
+            "the bitmap is placed next to the index"
+        );
+        assert_eq!(fs::read_dir(&dir)?.count(), 4, "pack, index, keep file and bitmap");
+
+        let bitmap = fs::read(bitmap_path)?;
+        assert_eq!(&bitmap[..4], b"BITM");
+        assert_eq!(&bitmap[8..12], &1u32.to_be_bytes(), "a single bitmap was written");
+        Ok(())
+    }
+
+    #[test]
+    fn bitmaps_require_a_directory() -> Result<(), Box<dyn std::error::Error>> {
+        let tip = hex_to_id("779c5451ba9fe210ffd1f55db202e55f51acecac");
+        let res = write_pack(None::<&Path>, SMALL_PACK, vec![tip])?;
+        assert_eq!(res.bitmap_path, None, "nothing is written without a directory");
+        Ok(())
+    }
+
     fn file_name(entry: &fs::DirEntry) -> String {
         entry.path().file_name().unwrap().to_str().unwrap().to_owned()
     }
@@ -161,6 +190,7 @@ mod write_to_directory {
     fn write_pack(
         directory: Option<impl AsRef<Path>>,
         pack_file: &str,
+        bitmap_commits: Vec<gix_hash::ObjectId>,
     ) -> Result<pack::bundle::write::Outcome, Box<dyn std::error::Error>> {
         let pack_file = fs::File::open(fixture_path(pack_file))?;
         static SHOULD_INTERRUPT: AtomicBool = AtomicBool::new(false);
@@ -176,6 +206,7 @@ mod write_to_directory {
                 iteration_mode: pack::data::input::Mode::Verify,
                 index_version: pack::index::Version::V2,
                 object_hash: gix_hash::Kind::Sha1,
+                bitmap_commits,
             },
         )
         .map_err(Into::into)
@@ -0,0 +1,91 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_odb::pack;
+use gix_pack::data::output::bitmap;
+
+use crate::{fixture_path, hex_to_id, pack::SMALL_PACK_INDEX};
+
+const TIP: &str = "779c5451ba9fe210ffd1f55db202e55f51acecac";
+const ROOT: &str = "dab40c3349552f320f6427975719845e6d2fa770";
+
+fn set_bits(bitmap: &gix_bitmap::ewah::Vec) -> Vec<usize> {
+    let mut bits = Vec::new();
+    bitmap.for_each_set_bit(|idx| {
+        bits.push(idx);
+        Some(())
+    });
+    bits
+}
+
+#[test]
+fn commits_map_to_all_objects_reachable_from_them() -> crate::Result {
+    let bundle = pack::Bundle::at(fixture_path(SMALL_PACK_INDEX), gix_hash::Kind::Sha1)?;
+    let mut builder = bitmap::Builder::new(&bundle);
+    let should_interrupt = AtomicBool::default();
+    builder.add_commit(&hex_to_id(ROOT), &should_interrupt)?;
+    builder.add_commit(&hex_to_id(TIP), &should_interrupt)?;
+    builder.add_commit(&hex_to_id(ROOT), &should_interrupt)?;
+
+    let mut out = Vec::new();
+    let checksum = builder.write_to(&mut out)?;
+    assert_eq!(
+        out[out.len() - 20..],
+        *checksum.as_slice(),
+        "the checksum is the trailer"
+    );
+
+    assert_eq!(&out[..4], b"BITM");
+    assert_eq!(&out[4..6], &1u16.to_be_bytes(), "version");
+    assert_eq!(&out[6..8], &1u16.to_be_bytes(), "the full-DAG flag is always set");
+    assert_eq!(&out[8..12], &2u32.to_be_bytes(), "adding a commit twice has no effect");
+    assert_eq!(&out[12..32], bundle.pack.checksum().as_slice());
+
+    let mut data = &out[32..out.len() - 20];
+    let mut by_kind = Vec::new();
+    for _ in 0..4 {
+        let (bitmap, rest) = gix_bitmap::ewah::decode(data)?;
+        by_kind.push(set_bits(&bitmap));
+        data = rest;
+    }
+    assert_eq!(
+        by_kind.iter().map(Vec::len).collect::<Vec<_>>(),
+        [14, 14, 14, 0],
+        "commits, trees, blobs and tags"
+    );
+
+    let mut commits = Vec::new();
+    while !data.is_empty() {
+        let index_position = u32::from_be_bytes(data[..4].try_into()?);
+        assert_eq!(&data[4..6], &[0, 0], "neither xor-offset nor flags are used");
+        let (bitmap, rest) = gix_bitmap::ewah::decode(&data[6..])?;
+        commits.push((bundle.index.oid_at_index(index_position).to_owned(), set_bits(&bitmap)));
+        data = rest;
+    }
+    assert_eq!(commits.len(), 2);
+    assert_eq!(
+        commits[0].0,
+        hex_to_id(ROOT),
+        "bitmaps are written in order of addition"
+    );
+    assert_eq!(commits[0].1.len(), 3, "the root commit, its tree and a single blob");
+    assert!(commits[0].1.iter().all(|bit| commits[1].1.contains(bit)));
+    assert_eq!(commits[1].0, hex_to_id(TIP));
+    assert_eq!(
+        commits[1].1,
+        (0..bundle.index.num_objects() as usize).collect::<Vec<_>>(),
+        "all objects in the pack are reachable from the tip"
+    );
+    Ok(())
+}
+
+#[test]
+fn only_commits_can_have_bitmaps() -> crate::Result {
+    let bundle = pack::Bundle::at(fixture_path(SMALL_PACK_INDEX), gix_hash::Kind::Sha1)?;
+    let mut builder = bitmap::Builder::new(&bundle);
+    let tree = hex_to_id("e90926b07092bccb7bf7da445fae6ffdfacf3eae");
+    let err = builder
+        .add_commit(&tree, &AtomicBool::default())
+        .expect_err("trees can't be bitmapped");
+    assert!(matches!(err, bitmap::Error::NotACommit { id, kind: gix_object::Kind::Tree } if id == tree));
+    Ok(())
+}
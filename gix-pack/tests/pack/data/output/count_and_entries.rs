@@ -418,3 +418,67 @@ fn write_and_verify(
 
     Ok(())
 }
+
+#[test]
+fn stable_order_and_compression_level_make_packs_reproducible() -> crate::Result {
+    let db = db(DbKind::DeterministicGeneratedContent)?;
+    let (mut counts, _stats) = output::count::objects(
+        db.clone(),
+        Box::new(std::iter::once(Ok(hex_to_id(
+            "dfcb5e39ac6eb30179808bbab721e8a28ce1b52e",
+        )))),
+        &progress::Discard,
+        &AtomicBool::new(false),
+        count::objects::Options {
+            input_object_expansion: count::objects::ObjectExpansion::TreeContents,
+            ..Default::default()
+        },
+    )?;
+    // Pretend objects aren't packed to force them to be compressed, in the order in which they were counted.
+    for count in &mut counts {
+        count.entry_pack_location = count::PackLocation::LookedUp(None);
+    }
+    let mut reversed_counts = counts.clone();
+    reversed_counts.reverse();
+
+    let pack_from_counts = |counts: Vec<output::Count>, stable_order: bool| -> crate::Result<Vec<u8>> {
+        let entries: Vec<_> = InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            db.clone(),
+            Box::new(progress::Discard),
+            output::entry::iter_from_counts::Options {
+                compression_level: Some(9),
+                stable_order,
+                ..Default::default()
+            },
+        ))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+        let mut pack = Vec::new();
+        let num_entries = entries.len() as u32;
+        for res in output::bytes::FromEntriesIter::new(
+            std::iter::once(Ok::<_, entry::iter_from_counts::Error>(entries)),
+            &mut pack,
+            num_entries,
+            pack::data::Version::V2,
+            gix_hash::Kind::Sha1,
+        ) {
+            res?;
+        }
+        Ok(pack)
+    };
+
+    assert_ne!(
+        pack_from_counts(counts.clone(), false)?,
+        pack_from_counts(reversed_counts.clone(), false)?,
+        "by default, the order of objects follows the input order"
+    );
+    assert_eq!(
+        pack_from_counts(counts, true)?,
+        pack_from_counts(reversed_counts, true)?,
+        "with stable order, the same objects yield the same pack"
+    );
+    Ok(())
+}
@@ -41,4 +41,5 @@ fn db(kind: DbKind) -> crate::Result<gix_odb::HandleArc> {
         })
 }
 
+mod bitmap;
 mod count_and_entries;
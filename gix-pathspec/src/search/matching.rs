@@ -13,7 +13,8 @@ impl Search {
     /// `is_dir` is `true` if `relative_path` is a directory, or assumed `false` if `None`.
     /// `attributes` is called as `attributes(relative_path, case, is_dir, outcome) -> has_match` to obtain for attributes for `relative_path`, if
     /// the underlying pathspec defined an attribute filter, to be stored in `outcome`, returning true if there was a match.
-    /// All attributes of the pathspec have to be present in the defined value for the pathspec to match.
+    /// All attributes of the pathspec have to be present in the defined value for the pathspec to match, which includes
+    /// attributes that are required to be unspecified, so paths without any matching attribute can still match.
     ///
     /// Note that `relative_path` is expected to be starting at the same root as is assumed for this pattern, see [`Pattern::normalize()`].
     /// Further, empty searches match everything, as if `:` was provided.
@@ -87,10 +88,9 @@ impl Search {
             }
 
             if let Some(attrs) = mapping.value.attrs_match.as_mut() {
-                if !attributes(relative_path, Case::Sensitive, is_dir, attrs) {
-                    // we have attrs, but it didn't match any
-                    return None;
-                }
+                // Even without any attribute match we have to compare, as pathspecs may require attributes to be unspecified.
+                attrs.reset();
+                attributes(relative_path, Case::Sensitive, is_dir, attrs);
                 for (actual, expected) in attrs.iter_selected().zip(mapping.value.pattern.attributes.iter()) {
                     if actual.assignment != expected.as_ref() {
                         return None;
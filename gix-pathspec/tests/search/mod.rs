@@ -117,6 +117,21 @@ fn directory_matches_prefix_all_excluded() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn unspecified_attributes_match_paths_without_any_attribute() -> crate::Result {
+    let mut search = gix_pathspec::Search::from_specs(pathspecs(&[":(attr:!a)"]), None, Path::new(""))?;
+    let m = search
+        .pattern_matching_relative_path("hello".into(), None, &mut |_, _, _, _| false)
+        .expect("matches as `a` is unspecified");
+    assert!(!m.is_excluded());
+
+    let mut search = gix_pathspec::Search::from_specs(pathspecs(&[":(attr:a)"]), None, Path::new(""))?;
+    assert!(search
+        .pattern_matching_relative_path("hello".into(), None, &mut |_, _, _, _| false)
+        .is_none());
+    Ok(())
+}
+
 #[test]
 fn no_pathspecs_match_everything() -> crate::Result {
     let mut search = gix_pathspec::Search::from_specs([], None, Path::new(""))?;
@@ -0,0 +1,74 @@
+/// The error returned by [`bundle_uri()`][crate::bundle_uri()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Transport(#[from] gix_transport::client::Error),
+    #[error(transparent)]
+    DecodePacketline(#[from] gix_transport::packetline::decode::Error),
+    #[error(transparent)]
+    ParseList(#[from] gix_bundle::list::Error),
+}
+
+impl gix_transport::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Io(err) => err.is_spurious(),
+            Error::Transport(err) => err.is_spurious(),
+            _ => false,
+        }
+    }
+}
+
+pub use gix_bundle::list::{Bundle, List, Mode};
+
+pub(crate) mod function {
+    use bstr::BString;
+    use gix_transport::client::{Capabilities, Transport, TransportV2Ext};
+    use maybe_async::maybe_async;
+
+    use super::{Error, List};
+    use crate::{command::Feature, Command};
+
+    /// Invoke the `bundle-uri` V2 command on `transport` to obtain the list of bundles the server advertises,
+    /// which requires a prior handshake that yielded server `capabilities` which contain `bundle-uri`.
+    /// `features` are sent along with the command, typically just the `agent`.
+    /// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+    ///
+    /// Note that the server may return an empty list, which is represented as `None`.
+    #[maybe_async]
+    pub async fn bundle_uri(
+        mut transport: impl Transport,
+        capabilities: &Capabilities,
+        features: Vec<Feature>,
+        trace: bool,
+    ) -> Result<Option<List>, Error> {
+        let _span = gix_features::trace::detail!("gix_protocol::bundle_uri()", capabilities = ?capabilities);
+        let command = Command::BundleUri;
+        command.validate_argument_prefixes_or_panic(gix_transport::Protocol::V2, capabilities, &[], &features);
+        let mut reader = transport
+            .invoke(
+                command.as_str(),
+                features.into_iter(),
+                None::<std::iter::Empty<BString>>,
+                trace,
+            )
+            .await?;
+        let mut lines = Vec::<BString>::new();
+        while let Some(line) = reader
+            .readline()
+            .await
+            .transpose()?
+            .transpose()?
+            .and_then(|l| l.as_bstr())
+        {
+            lines.push(line.to_owned());
+        }
+        if lines.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(List::from_lines(lines.iter().map(AsRef::as_ref))?))
+    }
+}
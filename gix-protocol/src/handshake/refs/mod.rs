@@ -60,6 +60,38 @@ impl Ref {
             } => (full_ref_name.as_ref(), None, None),
         }
     }
+
+    /// Write this ref to `out` as a line in the format of a V2 `ls-refs` response, which can be read back
+    /// with [`from_v2_ref_line()`].
+    pub fn write_v2_line(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        match self {
+            Ref::Direct { full_ref_name, object } => writeln!(out, "{object} {full_ref_name}"),
+            Ref::Peeled {
+                full_ref_name,
+                tag,
+                object,
+            } => writeln!(out, "{tag} {full_ref_name} peeled:{object}"),
+            Ref::Symbolic {
+                full_ref_name,
+                target,
+                tag: Some(tag),
+                object,
+            } => writeln!(out, "{tag} {full_ref_name} symref-target:{target} peeled:{object}"),
+            Ref::Symbolic {
+                full_ref_name,
+                target,
+                tag: None,
+                object,
+            } => writeln!(out, "{object} {full_ref_name} symref-target:{target}"),
+            Ref::Unborn { full_ref_name, target } => writeln!(out, "unborn {full_ref_name} symref-target:{target}"),
+        }
+    }
+}
+
+/// Parse a single `line` in the format of a V2 `ls-refs` response, like `<hex-hash> <path>[ (peeled|symref-target):<value>]`.
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub fn from_v2_ref_line(line: &BStr) -> Result<Ref, parse::Error> {
+    shared::parse_v2(line)
 }
 
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
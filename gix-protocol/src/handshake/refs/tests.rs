@@ -118,6 +118,44 @@ dce0ea858eef7ff61ad345cc5cdac62203fb3c10 refs/tags/gix-commitgraph-v0.0.0
     )
 }
 
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+#[test]
+fn v2_ref_lines_round_trip() -> Result<(), refs::parse::Error> {
+    let refs = [
+        Ref::Direct {
+            full_ref_name: "refs/heads/main".into(),
+            object: oid("808e50d724f604f69ab93c6da2919c014667bedb"),
+        },
+        Ref::Peeled {
+            full_ref_name: "refs/tags/foo".into(),
+            tag: oid("7fe1b98b39423b71e14217aa299a03b7c937d656"),
+            object: oid("808e50d724f604f69ab93c6da2919c014667bedb"),
+        },
+        Ref::Symbolic {
+            full_ref_name: "HEAD".into(),
+            target: "refs/heads/main".into(),
+            tag: None,
+            object: oid("808e50d724f604f69ab93c6da2919c014667bedb"),
+        },
+        Ref::Symbolic {
+            full_ref_name: "refs/heads/symbolic".into(),
+            target: "refs/tags/v1.0".into(),
+            tag: Some(oid("978f927e6397113757dfec6332e7d9c7e356ac25")),
+            object: oid("4d979abcde5cea47b079c38850828956c9382a56"),
+        },
+        Ref::Unborn {
+            full_ref_name: "HEAD".into(),
+            target: "refs/heads/main".into(),
+        },
+    ];
+    for r in refs {
+        let mut line = Vec::new();
+        r.write_v2_line(&mut line)?;
+        assert_eq!(refs::from_v2_ref_line(line.as_slice().into())?, r);
+    }
+    Ok(())
+}
+
 #[test]
 fn extract_symbolic_references_from_capabilities() -> Result<(), client::Error> {
     let caps = client::Capabilities::from_bytes(
@@ -17,6 +17,8 @@ pub enum Command {
     LsRefs,
     /// Fetch a pack.
     Fetch,
+    /// Obtain a list of bundles to download before fetching.
+    BundleUri,
 }
 pub mod command;
 
@@ -41,7 +43,7 @@ mod fetch_fn;
 pub use fetch_fn::{fetch, FetchConnection};
 
 mod remote_progress;
-pub use remote_progress::RemoteProgress;
+pub use remote_progress::{RemoteMessage, RemoteProgress};
 
 #[cfg(all(feature = "blocking-client", feature = "async-client"))]
 compile_error!("Cannot set both 'blocking-client' and 'async-client' features as they are mutually exclusive");
@@ -58,6 +60,12 @@ pub mod ls_refs;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub use ls_refs::function::ls_refs;
 
+///
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub mod bundle_uri;
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub use bundle_uri::function::bundle_uri;
+
 mod util;
 pub use util::agent;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
@@ -23,6 +23,63 @@ pub struct RemoteProgress<'a> {
     pub max: Option<usize>,
 }
 
+/// A message as sent by a git server on the progress or error sideband channel, classified by its content.
+///
+/// Servers use these channels not only to report progress, but also to provide hints or to explain why an operation
+/// was rejected, for instance due to a server-side policy.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RemoteMessage<'a> {
+    /// Progress information, like `Receiving objects: 50% (5/10)`.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    Progress(RemoteProgress<'a>),
+    /// A message prefixed with `hint:`, with the prefix removed.
+    Hint(&'a bstr::BStr),
+    /// A message prefixed with `warning:`, with the prefix removed.
+    Warning(&'a bstr::BStr),
+    /// A message prefixed with `error:` or `fatal:`, with the prefix removed, or any message sent on the error channel.
+    Error(&'a bstr::BStr),
+    /// Any other message, typically providing additional information like the amount of objects sent in total.
+    Info(&'a bstr::BStr),
+}
+
+impl<'a> RemoteMessage<'a> {
+    /// Parse a single `line` as sent by the remote, which is interpreted as error if `is_error` is true as it was received on
+    /// the error channel, or return `None` if the line is empty.
+    ///
+    /// A `remote:` prefix as added by `git` when displaying these lines is removed, as are trailing line separators.
+    pub fn from_bytes(is_error: bool, line: &'a [u8]) -> Option<RemoteMessage<'a>> {
+        let mut line = line.trim_end_with(|c| c == '\r' || c == '\n');
+        if let Some(rest) = line.strip_prefix(b"remote:") {
+            line = rest.trim_start();
+        }
+        if line.is_empty() {
+            return None;
+        }
+        let strip = |prefix: &[u8]| line.strip_prefix(prefix).map(|rest| rest.trim_start().as_bstr());
+        Some(if let Some(message) = strip(b"hint:") {
+            RemoteMessage::Hint(message)
+        } else if let Some(message) = strip(b"warning:") {
+            RemoteMessage::Warning(message)
+        } else if let Some(message) = strip(b"error:").or_else(|| strip(b"fatal:")) {
+            RemoteMessage::Error(message)
+        } else if is_error {
+            RemoteMessage::Error(line.as_bstr())
+        } else {
+            match RemoteProgress::from_bytes(line) {
+                Some(progress) => RemoteMessage::Progress(progress),
+                None => RemoteMessage::Info(line.as_bstr()),
+            }
+        })
+    }
+
+    /// Return `true` if this is an [error](RemoteMessage::Error), which typically indicates that the remote rejected
+    /// the operation.
+    pub fn is_error(&self) -> bool {
+        matches!(self, RemoteMessage::Error(_))
+    }
+}
+
 impl<'a> RemoteProgress<'a> {
     /// Parse the progress from a typical git progress `line` as sent by the remote.
     pub fn from_bytes(mut line: &[u8]) -> Option<RemoteProgress<'_>> {
@@ -35,8 +92,10 @@ impl<'a> RemoteProgress<'a> {
         })
     }
 
-    /// Parse `text`, which is interpreted as error if `is_error` is true, as [`RemoteProgress`] and call the respective
+    /// Parse `text`, which is interpreted as error if `is_error` is true, as [`RemoteMessage`] and call the respective
     /// methods on the given `progress` instance.
+    ///
+    /// Errors are reported as failure, while hints and warnings are reported as information.
     pub fn translate_to_progress(is_error: bool, text: &[u8], progress: &mut impl gix_features::progress::Progress) {
         fn progress_name(current: Option<String>, action: &[u8]) -> String {
             match current {
@@ -48,27 +107,25 @@ impl<'a> RemoteProgress<'a> {
                 None => action.as_bstr().to_string(),
             }
         }
-        if is_error {
-            // ignore keep-alive packages sent with 'sideband-all'
-            if !text.is_empty() {
-                progress.fail(progress_name(None, text));
-            }
-        } else {
-            match RemoteProgress::from_bytes(text) {
-                Some(RemoteProgress {
-                    action,
-                    percent: _,
-                    step,
-                    max,
-                }) => {
-                    progress.set_name(progress_name(progress.name(), action));
-                    progress.init(max, gix_features::progress::count("objects"));
-                    if let Some(step) = step {
-                        progress.set(step);
-                    }
+        // keep-alive packages sent with 'sideband-all' are empty and ignored.
+        match RemoteMessage::from_bytes(is_error, text) {
+            Some(RemoteMessage::Progress(RemoteProgress {
+                action,
+                percent: _,
+                step,
+                max,
+            })) => {
+                progress.set_name(progress_name(progress.name(), action));
+                progress.init(max, gix_features::progress::count("objects"));
+                if let Some(step) = step {
+                    progress.set(step);
                 }
-                None => progress.set_name(progress_name(progress.name(), text)),
-            };
+            }
+            Some(RemoteMessage::Error(message)) => progress.fail(progress_name(None, message)),
+            Some(RemoteMessage::Hint(message)) => progress.info(format!("hint: {message}")),
+            Some(RemoteMessage::Warning(message)) => progress.info(format!("warning: {message}")),
+            Some(RemoteMessage::Info(message)) => progress.set_name(progress_name(progress.name(), message)),
+            None => {}
         }
     }
 }
@@ -1,6 +1,6 @@
 use bstr::ByteSlice;
 use gix_features::progress;
-use gix_protocol::{fetch, handshake, ls_refs, FetchConnection};
+use gix_protocol::{bundle_uri, fetch, handshake, ls_refs, FetchConnection};
 use gix_transport::Protocol;
 
 use crate::fetch::{helper_unused, oid, transport, CloneDelegate, CloneRefInWantDelegate, LsRemoteDelegate};
@@ -202,3 +202,60 @@ async fn ref_in_want() -> crate::Result {
 
     Ok(())
 }
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn bundle_uri_list() -> crate::Result {
+    let out = Vec::new();
+    let mut transport = transport(
+        out,
+        "v2/bundle-uri.response",
+        Protocol::V2,
+        gix_transport::client::git::ConnectMode::Daemon,
+    );
+    let handshake = gix_protocol::handshake(
+        &mut transport,
+        gix_transport::Service::UploadPack,
+        helper_unused,
+        Vec::new(),
+        &mut progress::Discard,
+    )
+    .await?;
+    assert!(handshake.capabilities.contains("bundle-uri"));
+
+    let list = gix_protocol::bundle_uri(
+        &mut transport,
+        &handshake.capabilities,
+        vec![("agent", Some(gix_protocol::agent("agent").into()))],
+        false,
+    )
+    .await?
+    .expect("the server sent a list");
+    assert_eq!(list.mode, bundle_uri::Mode::All);
+    assert!(list.uses_creation_token_heuristic());
+    assert_eq!(
+        list.bundles,
+        vec![
+            bundle_uri::Bundle {
+                id: "base".into(),
+                uri: "https://cdn.example.com/base.bundle".into(),
+                filter: None,
+                creation_token: Some(1),
+            },
+            bundle_uri::Bundle {
+                id: "incremental".into(),
+                uri: "incremental.bundle".into(),
+                filter: None,
+                creation_token: Some(2),
+            }
+        ],
+        "keys are case-insensitive and unknown keys are ignored"
+    );
+    assert_eq!(
+        transport.into_inner().1.as_bstr(),
+        "002fgit-upload-pack does/not/matter\0\0version=2\00017command=bundle-uri
+0014agent=git/agent
+0000",
+        "there are no arguments"
+    );
+    Ok(())
+}
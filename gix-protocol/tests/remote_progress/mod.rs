@@ -1,6 +1,6 @@
 mod parse {
     use bstr::ByteSlice;
-    use gix_protocol::RemoteProgress;
+    use gix_protocol::{RemoteMessage, RemoteProgress};
 
     #[test]
     fn a_message_we_dont_understand() {
@@ -35,4 +35,63 @@ mod parse {
             })
         )
     }
+
+    #[test]
+    fn messages_are_classified_by_their_prefix() {
+        assert_eq!(
+            RemoteMessage::from_bytes(false, b"remote: hint: try pulling first\n"),
+            Some(RemoteMessage::Hint(b"try pulling first".as_bstr()))
+        );
+        assert_eq!(
+            RemoteMessage::from_bytes(false, b"warning: large files detected"),
+            Some(RemoteMessage::Warning(b"large files detected".as_bstr()))
+        );
+        for line in [
+            &b"error: GH006: Protected branch update failed"[..],
+            b"fatal: GH006: Protected branch update failed",
+        ] {
+            let message = RemoteMessage::from_bytes(false, line).expect("not empty");
+            assert!(message.is_error());
+            assert_eq!(
+                message,
+                RemoteMessage::Error(b"GH006: Protected branch update failed".as_bstr())
+            );
+        }
+        assert_eq!(
+            RemoteMessage::from_bytes(false, b"Total 3 (delta 0), reused 0 (delta 0)"),
+            Some(RemoteMessage::Info(b"Total 3 (delta 0), reused 0 (delta 0)".as_bstr())),
+            "lines without a colon aren't progress"
+        );
+    }
+
+    #[test]
+    fn progress_messages() {
+        assert_eq!(
+            RemoteMessage::from_bytes(false, b"Receiving objects:  50% (5/10)\r"),
+            Some(RemoteMessage::Progress(RemoteProgress {
+                action: b"Receiving objects".as_bstr(),
+                percent: Some(50),
+                step: Some(5),
+                max: Some(10)
+            }))
+        );
+    }
+
+    #[test]
+    fn everything_on_the_error_channel_is_an_error() {
+        assert_eq!(
+            RemoteMessage::from_bytes(true, b"the hook declined\n"),
+            Some(RemoteMessage::Error(b"the hook declined".as_bstr()))
+        );
+        assert_eq!(
+            RemoteMessage::from_bytes(true, b"hint: but hints stay hints"),
+            Some(RemoteMessage::Hint(b"but hints stay hints".as_bstr()))
+        );
+    }
+
+    #[test]
+    fn empty_lines_like_keep_alive_packets_are_ignored() {
+        assert_eq!(RemoteMessage::from_bytes(true, b""), None);
+        assert_eq!(RemoteMessage::from_bytes(false, b"remote: \n"), None);
+    }
 }
@@ -0,0 +1,57 @@
+//! Encode and decode the `author-script` file, a shell script to set the environment variables for the author of a commit.
+use bstr::{BString, ByteSlice, ByteVec};
+
+/// The error returned by [`decode()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The author script is missing the '{variable}' variable")]
+    MissingVariable { variable: &'static str },
+    #[error("Line '{line}' is not a valid shell variable assignment")]
+    InvalidLine { line: BString },
+    #[error(transparent)]
+    Date(#[from] gix_date::parse::Error),
+}
+
+const NAME: &str = "GIT_AUTHOR_NAME";
+const EMAIL: &str = "GIT_AUTHOR_EMAIL";
+const DATE: &str = "GIT_AUTHOR_DATE";
+
+/// Encode `author` as shell script that sets `GIT_AUTHOR_NAME`, `GIT_AUTHOR_EMAIL` and `GIT_AUTHOR_DATE`, just like git would.
+pub fn encode(author: gix_actor::SignatureRef<'_>) -> BString {
+    let mut out = BString::default();
+    let mut date = BString::from("@");
+    date.push_str(author.time.to_bstring());
+    for (variable, value) in [(NAME, author.name), (EMAIL, author.email), (DATE, date.as_ref())] {
+        out.push_str(variable);
+        out.push_str("='");
+        out.push_str(value.replace("'", "'\\''"));
+        out.push_str("'\n");
+    }
+    out
+}
+
+/// Decode an author script previously written by [`encode()`] or by `git`.
+pub fn decode(data: &[u8]) -> Result<gix_actor::Signature, Error> {
+    let (mut name, mut email, mut date) = (None, None, None);
+    for line in data.lines().map(ByteSlice::trim).filter(|l| !l.is_empty()) {
+        let (variable, value) = line
+            .split_once_str("=")
+            .filter(|(_, value)| value.len() >= 2 && value.starts_with(b"'") && value.ends_with(b"'"))
+            .ok_or_else(|| Error::InvalidLine { line: line.into() })?;
+        let value: BString = value[1..value.len() - 1].replace("'\\''", "'").into();
+        match variable {
+            b"GIT_AUTHOR_NAME" => name = Some(value),
+            b"GIT_AUTHOR_EMAIL" => email = Some(value),
+            b"GIT_AUTHOR_DATE" => date = Some(value),
+            _ => {}
+        }
+    }
+    let date = date.ok_or(Error::MissingVariable { variable: DATE })?;
+    let date = date.strip_prefix(b"@").unwrap_or(date.as_slice());
+    Ok(gix_actor::Signature {
+        name: name.ok_or(Error::MissingVariable { variable: NAME })?,
+        email: email.ok_or(Error::MissingVariable { variable: EMAIL })?,
+        time: gix_date::parse(date.to_str_lossy().as_ref(), None)?,
+    })
+}
@@ -1,2 +1,41 @@
-#![deny(rust_2018_idioms)]
+//! Persist and restore the state of an ongoing rebase, in a format compatible with `git rebase`.
+//!
+//! The state lives in the `rebase-merge` directory of a git repository, which allows an interrupted rebase to be continued or
+//! aborted by later invocations, including those of `git` itself.
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
+
+use bstr::BString;
+use gix_hash::ObjectId;
+
+/// The name of the directory within the `.git` directory which holds the [`State`].
+pub const DIRECTORY_NAME: &str = "rebase-merge";
+
+/// The state of a rebase that is in progress, as stored in the `rebase-merge` directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    /// The name of the branch that is rebased, or `None` if `HEAD` was detached when the rebase started.
+    pub head_name: Option<gix_ref::FullName>,
+    /// The commit onto which all commits are replayed.
+    pub onto: ObjectId,
+    /// The commit that `HEAD` pointed to when the rebase started, useful to restore it when aborting.
+    pub orig_head: ObjectId,
+    /// The instructions that are yet to be executed.
+    pub todo: gix_sequencer::todo::List,
+    /// The instructions that were executed already, with the last one being the one we stopped at, if any.
+    pub done: gix_sequencer::todo::List,
+    /// If `true`, the rebase is interactive.
+    pub interactive: bool,
+    /// The commit whose changes couldn't be applied without conflict, and at which the rebase stopped.
+    pub stopped_at: Option<ObjectId>,
+    /// The message of the commit at which the rebase stopped, to be used when continuing.
+    pub message: Option<BString>,
+    /// The author of the commit at which the rebase stopped, to be used when continuing.
+    pub author: Option<gix_actor::Signature>,
+}
+
+///
+pub mod author_script;
+
+///
+pub mod state;
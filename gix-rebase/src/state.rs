@@ -0,0 +1,188 @@
+use std::path::Path;
+
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::State;
+
+///
+pub mod from_dir {
+    use bstr::BString;
+
+    /// The error returned by [`State::from_dir()`](crate::State::from_dir()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read '{name}' from the rebase state directory")]
+        Io { name: &'static str, source: std::io::Error },
+        #[error("The content of '{name}' was not a valid object id: {content:?}")]
+        ObjectId {
+            name: &'static str,
+            content: BString,
+            source: gix_hash::decode::Error,
+        },
+        #[error("The branch name in 'head-name' was invalid")]
+        HeadName(#[from] gix_validate::reference::name::Error),
+        #[error("Could not parse the instructions in '{name}'")]
+        Todo {
+            name: &'static str,
+            source: gix_sequencer::todo::decode::Error,
+        },
+        #[error(transparent)]
+        AuthorScript(#[from] crate::author_script::Error),
+    }
+}
+
+const HEAD_NAME: &str = "head-name";
+const ONTO: &str = "onto";
+const ORIG_HEAD: &str = "orig-head";
+const TODO: &str = "git-rebase-todo";
+const DONE: &str = "done";
+const MSGNUM: &str = "msgnum";
+const END: &str = "end";
+const INTERACTIVE: &str = "interactive";
+const STOPPED_SHA: &str = "stopped-sha";
+const MESSAGE: &str = "message";
+const AUTHOR_SCRIPT: &str = "author-script";
+
+const DETACHED: &[u8] = b"detached HEAD";
+
+/// Initialization
+impl State {
+    /// Read the rebase state from `dir`, typically `.git/rebase-merge`, or return `None` if `dir` doesn't exist and
+    /// thus no rebase is in progress.
+    ///
+    /// `comment_char` is used to skip comments in the instruction lists.
+    pub fn from_dir(dir: &Path, comment_char: u8) -> Result<Option<Self>, from_dir::Error> {
+        if !dir.is_dir() {
+            return Ok(None);
+        }
+        let head_name = read(dir, HEAD_NAME)?.map(|s| s.trim().to_owned());
+        let head_name = match head_name {
+            Some(name) if name != DETACHED => Some(gix_ref::FullName::try_from(BString::from(name))?),
+            _ => None,
+        };
+        let todo = parse_list(dir, TODO, comment_char)?;
+        let done = parse_list(dir, DONE, comment_char)?;
+        let author = read(dir, AUTHOR_SCRIPT)?
+            .map(|data| crate::author_script::decode(&data))
+            .transpose()?;
+
+        Ok(Some(State {
+            head_name,
+            onto: read_id(dir, ONTO)?,
+            orig_head: read_id(dir, ORIG_HEAD)?,
+            todo,
+            done,
+            interactive: dir.join(INTERACTIVE).is_file(),
+            stopped_at: read(dir, STOPPED_SHA)?
+                .map(|data| parse_id(STOPPED_SHA, &data))
+                .transpose()?,
+            message: read(dir, MESSAGE)?.map(Into::into),
+            author,
+        }))
+    }
+}
+
+/// Persistence
+impl State {
+    /// Write all state into `dir`, typically `.git/rebase-merge`, creating it if needed.
+    ///
+    /// Files of optional state that isn't set are removed, and the progress files `msgnum` and `end` are derived
+    /// from the amount of instructions that are done and yet to do.
+    pub fn write_to_dir(&self, dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let head_name = self
+            .head_name
+            .as_ref()
+            .map_or(DETACHED, |name| name.as_bstr().as_bytes());
+        write(dir, HEAD_NAME, head_name)?;
+        write(dir, ONTO, self.onto.to_hex().to_string().as_bytes())?;
+        write(dir, ORIG_HEAD, self.orig_head.to_hex().to_string().as_bytes())?;
+
+        let mut buf = Vec::new();
+        self.todo.write_to(&mut buf)?;
+        std::fs::write(dir.join(TODO), &buf)?;
+        buf.clear();
+        self.done.write_to(&mut buf)?;
+        std::fs::write(dir.join(DONE), &buf)?;
+
+        let done = self.done.instructions.len();
+        write(dir, MSGNUM, done.to_string().as_bytes())?;
+        write(dir, END, (done + self.todo.instructions.len()).to_string().as_bytes())?;
+
+        if self.interactive {
+            std::fs::write(dir.join(INTERACTIVE), b"")?;
+        } else {
+            remove_if_present(&dir.join(INTERACTIVE))?;
+        }
+        match self.stopped_at {
+            Some(id) => write(dir, STOPPED_SHA, id.to_hex().to_string().as_bytes())?,
+            None => remove_if_present(&dir.join(STOPPED_SHA))?,
+        }
+        match &self.message {
+            Some(message) => std::fs::write(dir.join(MESSAGE), message)?,
+            None => remove_if_present(&dir.join(MESSAGE))?,
+        }
+        match &self.author {
+            Some(author) => std::fs::write(dir.join(AUTHOR_SCRIPT), crate::author_script::encode(author.to_ref()))?,
+            None => remove_if_present(&dir.join(AUTHOR_SCRIPT))?,
+        }
+        Ok(())
+    }
+
+    /// Remove the state directory `dir` entirely, which concludes the rebase. It's not an error if it doesn't exist.
+    pub fn remove_dir(dir: &Path) -> std::io::Result<()> {
+        match std::fs::remove_dir_all(dir) {
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            res => res,
+        }
+    }
+}
+
+fn write(dir: &Path, name: &str, line: &[u8]) -> std::io::Result<()> {
+    let mut content = Vec::with_capacity(line.len() + 1);
+    content.extend_from_slice(line);
+    content.push(b'\n');
+    std::fs::write(dir.join(name), content)
+}
+
+fn remove_if_present(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        res => res,
+    }
+}
+
+fn read(dir: &Path, name: &'static str) -> Result<Option<Vec<u8>>, from_dir::Error> {
+    match std::fs::read(dir.join(name)) {
+        Ok(data) => Ok(Some(data)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(from_dir::Error::Io { name, source: err }),
+    }
+}
+
+fn read_id(dir: &Path, name: &'static str) -> Result<ObjectId, from_dir::Error> {
+    let data = read(dir, name)?.ok_or_else(|| from_dir::Error::Io {
+        name,
+        source: std::io::ErrorKind::NotFound.into(),
+    })?;
+    parse_id(name, &data)
+}
+
+fn parse_id(name: &'static str, data: &[u8]) -> Result<ObjectId, from_dir::Error> {
+    let content = data.trim();
+    ObjectId::from_hex(content).map_err(|err| from_dir::Error::ObjectId {
+        name,
+        content: content.into(),
+        source: err,
+    })
+}
+
+fn parse_list(dir: &Path, name: &'static str, comment_char: u8) -> Result<gix_sequencer::todo::List, from_dir::Error> {
+    Ok(match read(dir, name)? {
+        Some(data) => gix_sequencer::todo::List::from_bytes(&data, comment_char)
+            .map_err(|err| from_dir::Error::Todo { name, source: err })?,
+        None => Default::default(),
+    })
+}
@@ -0,0 +1,66 @@
+use gix_rebase::State;
+use gix_sequencer::todo::{Command, Instruction, List};
+
+pub use gix_testtools::Result;
+
+fn id(hex: &str) -> gix_hash::ObjectId {
+    gix_hash::ObjectId::from_hex(hex.as_bytes()).expect("valid hex")
+}
+
+#[test]
+fn state_roundtrip() -> Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let state_dir = dir.path().join(gix_rebase::DIRECTORY_NAME);
+    assert_eq!(State::from_dir(&state_dir, b'#')?, None, "no rebase in progress");
+
+    let mut state = State {
+        head_name: Some("refs/heads/main".try_into()?),
+        onto: id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+        orig_head: id("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"),
+        todo: List {
+            instructions: vec![Instruction::new_with_commit(
+                Command::Pick,
+                id("cccccccccccccccccccccccccccccccccccccccc"),
+                "subject".into(),
+            )],
+        },
+        done: List {
+            instructions: vec![Instruction::new_with_commit(
+                Command::Pick,
+                id("dddddddddddddddddddddddddddddddddddddddd"),
+                "".into(),
+            )],
+        },
+        interactive: false,
+        stopped_at: Some(id("dddddddddddddddddddddddddddddddddddddddd")),
+        message: Some("the message\n".into()),
+        author: Some(gix_actor::Signature {
+            name: "O'Neil".into(),
+            email: "o@example.com".into(),
+            time: gix_date::Time::new(1_700_000_000, 3600),
+        }),
+    };
+    state.write_to_dir(&state_dir)?;
+    assert_eq!(std::fs::read(state_dir.join("msgnum"))?, b"1\n");
+    assert_eq!(std::fs::read(state_dir.join("end"))?, b"2\n");
+    assert_eq!(
+        std::fs::read_to_string(state_dir.join("author-script"))?,
+        "GIT_AUTHOR_NAME='O'\\''Neil'\nGIT_AUTHOR_EMAIL='o@example.com'\nGIT_AUTHOR_DATE='@1700000000 +0100'\n",
+        "single quotes are escaped for use in the shell"
+    );
+    assert_eq!(State::from_dir(&state_dir, b'#')?.as_ref(), Some(&state));
+
+    state.head_name = None;
+    state.stopped_at = None;
+    state.message = None;
+    state.author = None;
+    state.write_to_dir(&state_dir)?;
+    assert_eq!(std::fs::read(state_dir.join("head-name"))?, b"detached HEAD\n");
+    assert!(!state_dir.join("stopped-sha").exists(), "unset state is removed");
+    assert_eq!(State::from_dir(&state_dir, b'#')?.as_ref(), Some(&state));
+
+    State::remove_dir(&state_dir)?;
+    assert!(!state_dir.exists());
+    State::remove_dir(&state_dir)?;
+    Ok(())
+}
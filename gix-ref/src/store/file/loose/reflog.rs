@@ -76,8 +76,9 @@ impl file::Store {
 }
 
 impl file::Store {
-    /// Implements the logic required to transform a fully qualified refname into its log name
-    pub(crate) fn reflog_path(&self, name: &FullNameRef) -> PathBuf {
+    /// Implements the logic required to transform a fully qualified refname into its log name, and return the path
+    /// at which the reflog of `name` is stored, whether it exists or not.
+    pub fn reflog_path(&self, name: &FullNameRef) -> PathBuf {
         let (base, rela_path) = self.reflog_base_and_relative_path(name);
         base.join(rela_path)
     }
@@ -27,7 +27,7 @@ pub trait ReferenceExt: Sealed {
     fn peel_to_id_in_place(
         &mut self,
         store: &file::Store,
-        objects: &dyn gix_object::Find,
+        objects: &dyn gix_object::FindObjectOrHeader,
     ) -> Result<ObjectId, peel::to_id::Error>;
 
     /// Like [`ReferenceExt::peel_to_id_in_place()`], but with support for a known stable packed buffer
@@ -35,7 +35,7 @@ pub trait ReferenceExt: Sealed {
     fn peel_to_id_in_place_packed(
         &mut self,
         store: &file::Store,
-        objects: &dyn gix_object::Find,
+        objects: &dyn gix_object::FindObjectOrHeader,
         packed: Option<&packed::Buffer>,
     ) -> Result<ObjectId, peel::to_id::Error>;
 
@@ -73,7 +73,7 @@ impl ReferenceExt for Reference {
     fn peel_to_id_in_place(
         &mut self,
         store: &file::Store,
-        objects: &dyn gix_object::Find,
+        objects: &dyn gix_object::FindObjectOrHeader,
     ) -> Result<ObjectId, peel::to_id::Error> {
         let packed = store.assure_packed_refs_uptodate().map_err(|err| {
             peel::to_id::Error::Follow(file::find::existing::Error::Find(file::find::Error::PackedOpen(err)))
@@ -84,7 +84,7 @@ impl ReferenceExt for Reference {
     fn peel_to_id_in_place_packed(
         &mut self,
         store: &file::Store,
-        objects: &dyn gix_object::Find,
+        objects: &dyn gix_object::FindObjectOrHeader,
         packed: Option<&packed::Buffer>,
     ) -> Result<ObjectId, peel::to_id::Error> {
         match self.peeled {
@@ -115,22 +115,19 @@ impl ReferenceExt for Reference {
                 };
                 let mut buf = Vec::new();
                 let mut oid = self.target.try_id().expect("peeled ref").to_owned();
+                let not_found = |oid| peel::to_id::Error::NotFound {
+                    oid,
+                    name: self.name.0.clone(),
+                };
                 let peeled_id = loop {
-                    let gix_object::Data { kind, data } =
-                        objects
-                            .try_find(&oid, &mut buf)?
-                            .ok_or_else(|| peel::to_id::Error::NotFound {
-                                oid,
-                                name: self.name.0.clone(),
-                            })?;
+                    // Only tags need to be decoded, so avoid decoding the final object by looking at its header first.
+                    let kind = objects.try_header(&oid)?.ok_or_else(|| not_found(oid))?.kind;
                     match kind {
                         gix_object::Kind::Tag => {
-                            oid = gix_object::TagRefIter::from_bytes(data).target_id().map_err(|_err| {
-                                peel::to_id::Error::NotFound {
-                                    oid,
-                                    name: self.name.0.clone(),
-                                }
-                            })?;
+                            let data = objects.try_find(&oid, &mut buf)?.ok_or_else(|| not_found(oid))?.data;
+                            oid = gix_object::TagRefIter::from_bytes(data)
+                                .target_id()
+                                .map_err(|_err| not_found(oid))?;
                         }
                         _ => break oid,
                     };
@@ -28,30 +28,6 @@ impl<'s, 'p> Transaction<'s, 'p> {
             "locks can only be acquired once and it's all or nothing"
         );
 
-        let existing_ref = store
-            .ref_contents(change.update.name.as_ref())
-            .map_err(Error::from)
-            .and_then(|maybe_loose| {
-                maybe_loose
-                    .map(|buf| {
-                        loose::Reference::try_from_path(change.update.name.clone(), &buf)
-                            .map(Reference::from)
-                            .map_err(Error::from)
-                    })
-                    .transpose()
-            })
-            .or_else(|err| match err {
-                Error::ReferenceDecode(_) => Ok(None),
-                other => Err(other),
-            })
-            .and_then(|maybe_loose| match (maybe_loose, packed) {
-                (None, Some(packed)) => packed
-                    .try_find(change.update.name.as_ref())
-                    .map(|opt| opt.map(Into::into))
-                    .map_err(Error::from),
-                (None, None) => Ok(None),
-                (maybe_loose, _) => Ok(maybe_loose),
-            });
         let lock = match &mut change.update.change {
             Change::Delete { expected, .. } => {
                 let (base, relative_path) = store.reference_path_with_base(change.update.name.as_ref());
@@ -70,7 +46,7 @@ impl<'s, 'p> Transaction<'s, 'p> {
                     .into()
                 };
 
-                let existing_ref = existing_ref?;
+                let existing_ref = existing_reference(store, change.update.name.as_ref(), packed)?;
                 match (&expected, &existing_ref) {
                     (PreviousValue::MustNotExist, _) => {
                         panic!("BUG: MustNotExist constraint makes no sense if references are to be deleted")
@@ -120,7 +96,7 @@ impl<'s, 'p> Transaction<'s, 'p> {
                 };
                 let mut lock = (!has_global_lock).then(obtain_lock).transpose()?;
 
-                let existing_ref = existing_ref?;
+                let existing_ref = existing_reference(store, change.update.name.as_ref(), packed)?;
                 match (&expected, &existing_ref) {
                     (PreviousValue::Any, _)
                     | (PreviousValue::MustExist, Some(_))
@@ -204,6 +180,12 @@ impl<'s, 'p> Transaction<'s, 'p> {
     /// If the operation succeeds, the transaction can be committed or dropped to cause a rollback automatically.
     /// Rollbacks happen automatically on failure and they tend to be perfect.
     /// This method is idempotent.
+    ///
+    /// The expected previous value of each edit is checked against the value in its loose file or in the packed references only
+    /// after its lock was acquired, so it can't change anymore until the transaction is committed or rolled back.
+    /// Use [`Fail::AfterDurationWithBackoff`](gix_lock::acquire::Fail::AfterDurationWithBackoff) as `ref_files_lock_fail_mode`
+    /// and `packed_refs_lock_fail_mode` to retry acquiring locks held by concurrent transactions for a bounded amount of time,
+    /// and [`Error::failed_precondition()`] to learn which expectation didn't hold.
     pub fn prepare(
         self,
         edits: impl IntoIterator<Item = RefEdit>,
@@ -422,6 +404,37 @@ impl<'s, 'p> Transaction<'s, 'p> {
     }
 }
 
+/// Read the current value of the reference `name` from its loose file, or from `packed` if there is none.
+///
+/// This is called only after the lock for the reference was acquired, so its value can't change anymore
+/// before the transaction is committed or rolled back.
+fn existing_reference(
+    store: &file::Store,
+    name: &FullNameRef,
+    packed: Option<&packed::Buffer>,
+) -> Result<Option<Reference>, Error> {
+    let maybe_loose = store
+        .ref_contents(name)
+        .map_err(Error::from)
+        .and_then(|maybe_loose| {
+            maybe_loose
+                .map(|buf| {
+                    loose::Reference::try_from_path(name.to_owned(), &buf)
+                        .map(Reference::from)
+                        .map_err(Error::from)
+                })
+                .transpose()
+        })
+        .or_else(|err| match err {
+            Error::ReferenceDecode(_) => Ok(None),
+            other => Err(other),
+        })?;
+    match (maybe_loose, packed) {
+        (None, Some(packed)) => Ok(packed.try_find(name)?.map(Into::into)),
+        (maybe_loose, _) => Ok(maybe_loose),
+    }
+}
+
 fn possibly_adjust_name_for_prefixes(name: &FullNameRef) -> Option<FullName> {
     match name.category_and_short_name() {
         Some((c, sn)) => {
@@ -442,7 +455,7 @@ fn possibly_adjust_name_for_prefixes(name: &FullNameRef) -> Option<FullName> {
 }
 
 mod error {
-    use gix_object::bstr::BString;
+    use gix_object::bstr::{BStr, BString};
 
     use crate::{
         store_impl::{file, packed},
@@ -489,6 +502,64 @@ mod error {
         #[error("Could not read reference")]
         ReferenceDecode(#[from] file::loose::reference::decode::Error),
     }
+
+    /// A precondition of an edit, as expressed by its [`PreviousValue`](crate::transaction::PreviousValue), that didn't hold
+    /// when the lock for the reference was held, as returned by [`Error::failed_precondition()`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum FailedPrecondition<'a> {
+        /// The reference was expected to exist, but it didn't.
+        Missing {
+            /// The name of the reference.
+            full_name: &'a BStr,
+        },
+        /// The reference was expected to not exist, but it did with the `actual` value.
+        Exists {
+            /// The name of the reference.
+            full_name: &'a BStr,
+            /// The value the reference had.
+            actual: &'a Target,
+        },
+        /// The reference was expected to have the `expected` value, but it had the `actual` one.
+        Mismatch {
+            /// The name of the reference.
+            full_name: &'a BStr,
+            /// The value the reference was expected to have.
+            expected: &'a Target,
+            /// The value the reference had.
+            actual: &'a Target,
+        },
+    }
+
+    impl Error {
+        /// Return the precondition that failed if this error was caused by a reference not having the value it was expected
+        /// to have, or `None` if it's any other error.
+        ///
+        /// This makes it possible to tell conflicting updates, which may be retried with an updated expectation,
+        /// apart from other failures.
+        pub fn failed_precondition(&self) -> Option<FailedPrecondition<'_>> {
+            Some(match self {
+                Error::DeleteReferenceMustExist { full_name } | Error::MustExist { full_name, .. } => {
+                    FailedPrecondition::Missing {
+                        full_name: full_name.as_ref(),
+                    }
+                }
+                Error::MustNotExist { full_name, actual, .. } => FailedPrecondition::Exists {
+                    full_name: full_name.as_ref(),
+                    actual,
+                },
+                Error::ReferenceOutOfDate {
+                    full_name,
+                    expected,
+                    actual,
+                } => FailedPrecondition::Mismatch {
+                    full_name: full_name.as_ref(),
+                    expected,
+                    actual,
+                },
+                _ => return None,
+            })
+        }
+    }
 }
 
-pub use error::Error;
+pub use error::{Error, FailedPrecondition};
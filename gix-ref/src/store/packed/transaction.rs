@@ -53,7 +53,7 @@ impl packed::Transaction {
     pub fn prepare(
         mut self,
         edits: &mut dyn Iterator<Item = RefEdit>,
-        objects: &dyn gix_object::Find,
+        objects: &dyn gix_object::FindObjectOrHeader,
     ) -> Result<Self, prepare::Error> {
         assert!(self.edits.is_none(), "BUG: cannot call prepare(…) more than once");
         let buffer = &self.buffer;
@@ -108,10 +108,13 @@ impl packed::Transaction {
             {
                 let mut next_id = new;
                 edit.peeled = loop {
-                    let kind = objects.try_find(&next_id, &mut buf)?.map(|d| d.kind);
+                    let kind = objects.try_header(&next_id)?.map(|header| header.kind);
                     match kind {
                         Some(gix_object::Kind::Tag) => {
-                            next_id = gix_object::TagRefIter::from_bytes(&buf).target_id().map_err(|_| {
+                            let data = objects.try_find(&next_id, &mut buf)?.map(|d| d.data).ok_or_else(|| {
+                                prepare::Error::Resolve(format!("Couldn't find object with id {next_id}").into())
+                            })?;
+                            next_id = gix_object::TagRefIter::from_bytes(data).target_id().map_err(|_| {
                                 prepare::Error::Resolve(
                                     format!("Couldn't get target object id from tag {next_id}").into(),
                                 )
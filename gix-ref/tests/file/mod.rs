@@ -51,6 +51,15 @@ impl gix_object::Find for EmptyCommit {
     }
 }
 
+impl gix_object::FindHeader for EmptyCommit {
+    fn try_header(&self, _id: &gix_hash::oid) -> Result<Option<gix_object::Header>, gix_object::find::Error> {
+        Ok(Some(gix_object::Header {
+            kind: gix_object::Kind::Commit,
+            size: 0,
+        }))
+    }
+}
+
 mod log;
 mod reference;
 mod store;
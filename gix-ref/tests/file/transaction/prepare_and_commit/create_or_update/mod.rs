@@ -815,3 +815,145 @@ fn packed_refs_creation_with_packed_refs_mode_leave_keeps_original_loose_refs()
     );
     Ok(())
 }
+
+#[test]
+fn failed_preconditions_are_reported_with_the_actual_value() -> crate::Result {
+    let (_keep, store) = store_writable("make_repo_for_reflog.sh")?;
+    let head = store.try_find_loose("HEAD")?.expect("head exists already");
+    let expected = Target::Peeled(hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242"));
+
+    let update = |name: &str, expected: PreviousValue| -> crate::Result<RefEdit> {
+        Ok(RefEdit {
+            change: Change::Update {
+                log: LogChange::default(),
+                new: Target::Peeled(gix_hash::Kind::Sha1.null()),
+                expected,
+            },
+            name: name.try_into()?,
+            deref: false,
+        })
+    };
+    let err = store
+        .transaction()
+        .prepare(
+            Some(update("HEAD", PreviousValue::MustExistAndMatch(expected.clone()))?),
+            Fail::Immediately,
+            Fail::Immediately,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.failed_precondition(),
+        Some(transaction::prepare::FailedPrecondition::Mismatch {
+            full_name: "HEAD".into(),
+            expected: &expected,
+            actual: &head.target,
+        })
+    );
+
+    let err = store
+        .transaction()
+        .prepare(
+            Some(update("HEAD", PreviousValue::MustNotExist)?),
+            Fail::Immediately,
+            Fail::Immediately,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.failed_precondition(),
+        Some(transaction::prepare::FailedPrecondition::Exists {
+            full_name: "HEAD".into(),
+            actual: &head.target,
+        })
+    );
+
+    let err = store
+        .transaction()
+        .prepare(
+            Some(update("refs/heads/missing", PreviousValue::MustExist)?),
+            Fail::Immediately,
+            Fail::Immediately,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.failed_precondition(),
+        Some(transaction::prepare::FailedPrecondition::Missing {
+            full_name: "refs/heads/missing".into(),
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn concurrent_conditional_updates_let_only_one_of_them_succeed() -> crate::Result {
+    let (dir, store) = empty_store()?;
+    let name = "refs/heads/contended";
+    let initial = Target::Peeled(hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242"));
+    store
+        .transaction()
+        .prepare(
+            Some(RefEdit {
+                change: Change::Update {
+                    log: LogChange::default(),
+                    new: initial.clone(),
+                    expected: PreviousValue::MustNotExist,
+                },
+                name: name.try_into()?,
+                deref: false,
+            }),
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+
+    let results: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (1..=8u8)
+            .map(|byte| {
+                let git_dir = dir.path().to_owned();
+                let initial = initial.clone();
+                scope.spawn(move || {
+                    let store = gix_ref::file::Store::at(git_dir, WriteReflog::Normal, gix_hash::Kind::Sha1, false);
+                    store
+                        .transaction()
+                        .prepare(
+                            Some(RefEdit {
+                                change: Change::Update {
+                                    log: LogChange::default(),
+                                    new: Target::Peeled(ObjectId::from([byte; 20])),
+                                    expected: PreviousValue::MustExistAndMatch(initial),
+                                },
+                                name: name.try_into().expect("valid"),
+                                deref: false,
+                            }),
+                            Fail::AfterDurationWithBackoff(std::time::Duration::from_secs(30)),
+                            Fail::AfterDurationWithBackoff(std::time::Duration::from_secs(30)),
+                        )
+                        .and_then(|transaction| {
+                            transaction
+                                .commit(committer().to_ref())
+                                .map_err(|err| panic!("commit failed unexpectedly: {err}"))
+                        })
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("no panic"))
+            .collect()
+    });
+
+    assert_eq!(
+        results.iter().filter(|res| res.is_ok()).count(),
+        1,
+        "only the first update sees the expected value"
+    );
+    for err in results.iter().filter_map(|res| res.as_ref().err()) {
+        assert!(
+            matches!(
+                err.failed_precondition(),
+                Some(transaction::prepare::FailedPrecondition::Mismatch { expected, .. }) if *expected == initial
+            ),
+            "all others fail as the value changed underneath them: {err:?}"
+        );
+    }
+    Ok(())
+}
@@ -1,2 +1,10 @@
-#![deny(rust_2018_idioms)]
+//! Handle sequences of operations which may need human aid to complete, and which can't necessarily be completed in one
+//! invocation.
+//!
+//! This crate provides the data structures that are persisted between invocations, like the list of instructions
+//! that `git rebase` and `git cherry-pick` keep in their `todo` files.
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
+
+///
+pub mod todo;
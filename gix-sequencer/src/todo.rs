@@ -0,0 +1,183 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// A command in a list of instructions, as used in `git-rebase-todo` or `sequencer/todo` files.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Command {
+    /// Apply the changes of a commit.
+    Pick,
+    /// Apply the reverse of the changes of a commit.
+    Revert,
+    /// Apply the changes of a commit, but stop to edit its message.
+    Reword,
+    /// Apply the changes of a commit, but stop to amend it.
+    Edit,
+    /// Meld the changes of a commit into the previous one, combining the messages.
+    Squash,
+    /// Meld the changes of a commit into the previous one, discarding its message.
+    Fixup,
+    /// Run a shell command.
+    Exec,
+    /// Stop, to be continued later.
+    Break,
+    /// Skip a commit.
+    Drop,
+    /// Label the current `HEAD` with a name.
+    Label,
+    /// Reset `HEAD` to a label.
+    Reset,
+    /// Create a merge commit.
+    Merge,
+    /// Update a reference to the current `HEAD`.
+    UpdateRef,
+    /// Do nothing.
+    Noop,
+}
+
+impl Command {
+    /// Return the name of the command as written into todo files.
+    pub fn as_str(&self) -> &'static str {
+        use Command::*;
+        match self {
+            Pick => "pick",
+            Revert => "revert",
+            Reword => "reword",
+            Edit => "edit",
+            Squash => "squash",
+            Fixup => "fixup",
+            Exec => "exec",
+            Break => "break",
+            Drop => "drop",
+            Label => "label",
+            Reset => "reset",
+            Merge => "merge",
+            UpdateRef => "update-ref",
+            Noop => "noop",
+        }
+    }
+
+    /// Parse `name` as the full or abbreviated name of a command, or return `None` if it's unknown.
+    pub fn from_name(name: &BStr) -> Option<Self> {
+        use Command::*;
+        Some(match name.as_bytes() {
+            b"pick" | b"p" => Pick,
+            b"revert" => Revert,
+            b"reword" | b"r" => Reword,
+            b"edit" | b"e" => Edit,
+            b"squash" | b"s" => Squash,
+            b"fixup" | b"f" => Fixup,
+            b"exec" | b"x" => Exec,
+            b"break" | b"b" => Break,
+            b"drop" | b"d" => Drop,
+            b"label" | b"l" => Label,
+            b"reset" | b"t" => Reset,
+            b"merge" | b"m" => Merge,
+            b"update-ref" | b"u" => UpdateRef,
+            b"noop" => Noop,
+            _ => return None,
+        })
+    }
+
+    /// Return `true` if this command operates on a commit that is given as its first argument.
+    pub fn takes_commit(&self) -> bool {
+        use Command::*;
+        matches!(self, Pick | Revert | Reword | Edit | Squash | Fixup | Drop)
+    }
+}
+
+/// A single line in a list of instructions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    /// The command to execute.
+    pub command: Command,
+    /// Everything after the command, with surrounding whitespace removed.
+    ///
+    /// For commands that [operate on commits](Command::takes_commit()) this is the possibly abbreviated commit
+    /// followed by its subject, for `exec` it's the shell command, and for `label` or `reset` it's the name of the label.
+    pub rest: BString,
+}
+
+impl Instruction {
+    /// Create a new instruction to execute `command` on the commit with `id`, followed by `subject` for informational purposes.
+    pub fn new_with_commit(command: Command, id: impl std::fmt::Display, subject: &BStr) -> Self {
+        let mut rest: BString = id.to_string().into();
+        if !subject.is_empty() {
+            rest.push_byte(b' ');
+            rest.push_str(subject);
+        }
+        Instruction { command, rest }
+    }
+
+    /// Return the possibly abbreviated hexadecimal commit this instruction refers to, if its command operates on a commit.
+    ///
+    /// Note that the `-C` and `-c` flags of `fixup` are skipped.
+    pub fn commit(&self) -> Option<&BStr> {
+        if !self.command.takes_commit() {
+            return None;
+        }
+        let mut tokens = self.rest.fields();
+        let mut token = tokens.next()?;
+        if self.command == Command::Fixup && (token == b"-C" || token == b"-c") {
+            token = tokens.next()?;
+        }
+        Some(token.as_bstr())
+    }
+}
+
+/// A list of instructions, as stored in `git-rebase-todo` or `sequencer/todo` files.
+///
+/// Comments and empty lines are not retained.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct List {
+    /// All instructions in order of execution.
+    pub instructions: Vec<Instruction>,
+}
+
+///
+pub mod decode {
+    use bstr::BString;
+
+    /// The error returned by [`List::from_bytes()`](super::List::from_bytes()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Unknown command '{name}' in line {line_number}")]
+        UnknownCommand { name: BString, line_number: usize },
+    }
+}
+
+/// Serialization
+impl List {
+    /// Parse all instructions from `data`, skipping comments which start with `comment_char`, and empty lines.
+    pub fn from_bytes(data: &[u8], comment_char: u8) -> Result<Self, decode::Error> {
+        let mut instructions = Vec::new();
+        for (line_number, line) in data.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line[0] == comment_char {
+                continue;
+            }
+            let (name, rest) = line.split_once_str(" ").unwrap_or((line, b""));
+            let command = Command::from_name(name.as_bstr()).ok_or_else(|| decode::Error::UnknownCommand {
+                name: name.into(),
+                line_number: line_number + 1,
+            })?;
+            instructions.push(Instruction {
+                command,
+                rest: rest.trim().into(),
+            });
+        }
+        Ok(List { instructions })
+    }
+
+    /// Write all instructions to `out`, one per line, using the full name of each command.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        for Instruction { command, rest } in &self.instructions {
+            out.write_all(command.as_str().as_bytes())?;
+            if !rest.is_empty() {
+                out.write_all(b" ")?;
+                out.write_all(rest)?;
+            }
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
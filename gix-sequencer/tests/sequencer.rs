@@ -0,0 +1,35 @@
+mod todo {
+    use gix_sequencer::todo::{Command, List};
+
+    #[test]
+    fn parse_and_write_roundtrip() {
+        let input = b"p 1234567 first\n# a comment\n\nfixup -C abcdef0 second\nexec make test\nbreak\n";
+        let list = List::from_bytes(input, b'#').expect("valid");
+        assert_eq!(
+            list.instructions.iter().map(|i| i.command).collect::<Vec<_>>(),
+            [Command::Pick, Command::Fixup, Command::Exec, Command::Break]
+        );
+        assert_eq!(list.instructions[0].commit().expect("present"), "1234567");
+        assert_eq!(
+            list.instructions[1].commit().expect("present"),
+            "abcdef0",
+            "flags are skipped"
+        );
+        assert_eq!(list.instructions[2].commit(), None, "exec has no commit");
+
+        let mut buf = Vec::new();
+        list.write_to(&mut buf).expect("in-memory write works");
+        assert_eq!(
+            buf.as_slice(),
+            b"pick 1234567 first\nfixup -C abcdef0 second\nexec make test\nbreak\n",
+            "abbreviated commands are written in full, comments are dropped"
+        );
+        assert_eq!(List::from_bytes(&buf, b'#').expect("valid"), list);
+    }
+
+    #[test]
+    fn unknown_commands_fail() {
+        let err = List::from_bytes(b"pick abc\nfrobnicate abc\n", b'#').unwrap_err();
+        assert_eq!(err.to_string(), "Unknown command 'frobnicate' in line 2");
+    }
+}
@@ -0,0 +1,173 @@
+//! Query a filesystem monitor to learn which paths may have changed since it was last asked, which allows to avoid
+//! looking at all other files in the worktree.
+use std::{path::Path, process::Stdio};
+
+use bstr::{BStr, BString, ByteSlice};
+
+/// The error returned by [`query()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not launch the fsmonitor hook '{command}'")]
+    Spawn { command: BString, source: std::io::Error },
+    #[error("The fsmonitor hook '{command}' failed with {status}")]
+    Failed {
+        command: BString,
+        status: std::process::ExitStatus,
+    },
+    #[error("The fsmonitor hook '{command}' didn't provide a token")]
+    MissingToken { command: BString },
+}
+
+/// The result of [`query()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The token to pass to the next query, and to store in the index to identify the point in time at which it
+    /// was last known to be fresh.
+    pub token: BString,
+    /// Sorted paths that may have changed, without trailing slashes, or `None` if all of them may have changed.
+    paths: Option<Vec<BString>>,
+}
+
+impl Outcome {
+    /// Return `true` if `rela_path`, relative to the worktree root, may have changed since the last query.
+    ///
+    /// This is the case if the path itself was reported, or any of its parent directories.
+    pub fn is_possibly_changed(&self, rela_path: &BStr) -> bool {
+        let Some(paths) = self.paths.as_ref() else {
+            return true;
+        };
+        let is_reported = |path: &[u8]| paths.binary_search_by(|p| p.as_slice().cmp(path)).is_ok();
+        is_reported(rela_path)
+            || rela_path
+                .find_iter("/")
+                .any(|slash_pos| is_reported(&rela_path[..slash_pos]))
+    }
+
+    /// Return `true` if the filesystem monitor couldn't tell what changed, so all paths may have changed.
+    pub fn everything_possibly_changed(&self) -> bool {
+        self.paths.is_none()
+    }
+
+    /// Parse the `response` of a filesystem monitor in the format of version 2 of the hook protocol,
+    /// `<token>\0<path>\0<path>\0…`, where a path of `/` means everything may have changed.
+    ///
+    /// Return `None` if there is no token.
+    pub fn from_response(response: &[u8]) -> Option<Self> {
+        let mut tokens = response.split_str(b"\0");
+        let token = tokens.next().filter(|token| !token.is_empty())?;
+        let mut paths = Vec::new();
+        for path in tokens.filter(|path| !path.is_empty()) {
+            if path == b"/" {
+                return Some(Outcome {
+                    token: token.into(),
+                    paths: None,
+                });
+            }
+            paths.push(path.strip_suffix(b"/").unwrap_or(path).into());
+        }
+        paths.sort();
+        paths.dedup();
+        Some(Outcome {
+            token: token.into(),
+            paths: Some(paths),
+        })
+    }
+}
+
+/// Ask the fsmonitor `hook`, usually the value of `core.fsmonitor`, for all paths in `worktree` that changed since the
+/// point in time identified by `token`, using version 2 of the hook protocol.
+///
+/// `token` is typically obtained from the [fsmonitor extension](gix_index::extension::FsMonitor::token()) of the index.
+/// The `hook` is executed through the shell with `worktree` as its working directory, just like `git` does.
+pub fn query(hook: &BStr, worktree: &Path, token: &BStr) -> Result<Outcome, Error> {
+    let mut cmd: std::process::Command = gix_command::prepare(gix_path::from_bstr(hook).into_owned())
+        .with_shell()
+        .arg("2")
+        .arg(gix_path::from_bstr(token).into_owned())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .into();
+    cmd.current_dir(worktree);
+    gix_features::trace::debug!(cmd = ?cmd, "querying fsmonitor");
+    let output = cmd.output().map_err(|err| Error::Spawn {
+        command: hook.to_owned(),
+        source: err,
+    })?;
+    if !output.status.success() {
+        return Err(Error::Failed {
+            command: hook.to_owned(),
+            status: output.status,
+        });
+    }
+    Outcome::from_response(&output.stdout).ok_or_else(|| Error::MissingToken {
+        command: hook.to_owned(),
+    })
+}
+
+/// Set the [`FSMONITOR_VALID`](gix_index::entry::Flags::FSMONITOR_VALID) flag on all entries of `index` which are known to
+/// be unchanged, or clear it otherwise, and return the amount of entries that are marked as valid.
+///
+/// Entries are unchanged if they are not dirty according to the [fsmonitor extension](gix_index::State::fs_monitor()) of
+/// `index`, and if they are not [possibly changed](Outcome::is_possibly_changed()) according to `outcome`, which was
+/// obtained by [querying](query()) with the token stored in the extension.
+/// Without the extension, nothing is known about the entries and none of them are marked.
+///
+/// [`index_as_worktree()`](crate::index_as_worktree()) skips entries marked like this, so they don't have to be
+/// checked on disk.
+pub fn mark_valid_entries(index: &mut gix_index::State, outcome: &Outcome) -> usize {
+    let mut is_dirty = vec![false; index.entries().len()];
+    let has_extension = match index.fs_monitor() {
+        Some(fs_monitor) => {
+            fs_monitor.entry_dirty().for_each_set_bit(|idx| {
+                if let Some(dirty) = is_dirty.get_mut(idx) {
+                    *dirty = true;
+                }
+                Some(())
+            });
+            true
+        }
+        None => false,
+    };
+
+    let mut num_valid = 0;
+    for ((entry, path), is_dirty) in index.entries_mut_with_paths().zip(is_dirty) {
+        let is_valid = has_extension && !is_dirty && !outcome.is_possibly_changed(path);
+        entry.flags.set(gix_index::entry::Flags::FSMONITOR_VALID, is_valid);
+        num_valid += usize::from(is_valid);
+    }
+    num_valid
+}
+
+/// Store the token of `outcome` in the [fsmonitor extension](gix_index::State::fs_monitor()) of `index` after all of its
+/// entries were compared to the worktree, with `dirty_entries` being the indices of all entries that didn't match.
+///
+/// All other entries are marked as [valid](gix_index::entry::Flags::FSMONITOR_VALID) as they are known to be unchanged
+/// at the time `outcome` was obtained, so the next [query()] with its token only needs to report what changed since.
+/// Write `index` with all extensions to persist this information.
+pub fn update_extension(
+    index: &mut gix_index::State,
+    outcome: &Outcome,
+    dirty_entries: impl IntoIterator<Item = usize>,
+) {
+    let num_entries = index.entries().len();
+    let mut dirty = vec![0_u64; (num_entries + 63) / 64];
+    let mut is_dirty = vec![false; num_entries];
+    for idx in dirty_entries.into_iter().filter(|idx| *idx < num_entries) {
+        dirty[idx / 64] |= 1 << (idx % 64);
+        is_dirty[idx] = true;
+    }
+    for (entry, is_dirty) in index.entries_mut().iter_mut().zip(is_dirty) {
+        entry.flags.set(gix_index::entry::Flags::FSMONITOR_VALID, !is_dirty);
+    }
+    index.set_fs_monitor(Some(gix_index::extension::FsMonitor::new(
+        gix_index::extension::fs_monitor::Token::V2 {
+            token: outcome.token.clone(),
+        },
+        gix_bitmap::ewah::Vec::from_words(
+            &dirty,
+            u32::try_from(num_entries).expect("definitely not 4billion entries"),
+        ),
+    )));
+}
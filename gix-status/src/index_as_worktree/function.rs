@@ -3,6 +3,7 @@ use std::{
     path::Path,
     slice::Chunks,
     sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    time::Instant,
 };
 
 use bstr::BStr;
@@ -16,7 +17,7 @@ use crate::{
         traits,
         traits::{read_data::Stream, CompareBlobs, SubmoduleStatus},
         types::{Error, Options},
-        Change, Conflict, EntryStatus, Outcome, VisitEntry,
+        Change, Conflict, Continuation, EntryStatus, Outcome, VisitEntry,
     },
     Pathspec, SymlinkCheck,
 };
@@ -32,7 +33,8 @@ use crate::{
 /// as [`EntryStatus`].
 /// The `pathspec` is used to determine which index entries to check for status in the first place.
 ///
-/// `should_interrupt` can be used to stop all processing.
+/// `should_interrupt` can be used to stop all processing. If set, the returned [`Outcome::continuation`] can be used to
+/// [resume](Options::resume_from) the operation later, just like when running out of [time](Options::time_budget).
 /// `filter` is used to convert worktree files back to their internal git representation. For this to be correct,
 /// [`Options::attributes`] must be configured as well.
 /// `objects` is used to access the version of an object in the object database for direct comparison.
@@ -80,9 +82,20 @@ where
         None,
     );
 
-    let range = index
+    let deadline = options.time_budget.map(|budget| Instant::now() + budget);
+    let mut range = index
         .prefixed_entries_range(pathspec.common_prefix())
         .unwrap_or(0..index.entries().len());
+    let entries_skipped_by_common_prefix = index.entries().len() - range.len();
+    if let Some(continuation) = options.resume_from {
+        if continuation.num_entries != index.entries().len() {
+            return Err(Error::ContinuationMismatch {
+                expected: continuation.num_entries,
+                actual: index.entries().len(),
+            });
+        }
+        range.start = continuation.next_entry.clamp(range.start, range.end);
+    }
 
     let stack = gix_worktree::Stack::from_state_and_ignore_case(
         worktree,
@@ -92,7 +105,6 @@ where
         index.path_backing(),
     );
     let (entries, path_backing) = (index.entries(), index.path_backing());
-    let mut num_entries = entries.len();
     let entry_index_offset = range.start;
     let entries = &entries[range];
 
@@ -101,11 +113,11 @@ where
                                              chunk_size = chunk_size,
                                              thread_limit = ?thread_limit);
 
-    let entries_skipped_by_common_prefix = num_entries - entries.len();
     let (skipped_by_pathspec, skipped_by_entry_flags, symlink_metadata_calls, entries_to_update) = Default::default();
     let (worktree_bytes, worktree_reads, odb_bytes, odb_reads, racy_clean) = Default::default();
 
-    num_entries = entries.len();
+    let num_entries = entries.len();
+    let stopped_at = AtomicUsize::new(usize::MAX);
     progress.init(entries.len().into(), gix_features::progress::count("files"));
     let count = progress.counter();
 
@@ -146,13 +158,14 @@ where
     };
     in_parallel_if(
         || true, // TODO: heuristic: when is parallelization not worth it? Git says 500 items per thread, but to 20 threads, we can be more fine-grained though.
-        gix_features::interrupt::Iter::new(
-            OffsetIter {
-                inner: entries.chunks(chunk_size),
-                offset: entry_index_offset,
-            },
+        OffsetIter {
+            inner: entries.chunks(chunk_size),
+            offset: entry_index_offset,
             should_interrupt,
-        ),
+            deadline,
+            made_progress: false,
+            stopped_at: &stopped_at,
+        },
         thread_limit,
         new_state,
         |(entry_offset, chunk_entries), (state, blobdiff, submdule, objects, pathspec)| {
@@ -215,6 +228,13 @@ where
         worktree_bytes: worktree_bytes.load(Ordering::Relaxed),
         odb_objects_read: odb_reads.load(Ordering::Relaxed),
         odb_bytes: odb_bytes.load(Ordering::Relaxed),
+        continuation: match stopped_at.load(Ordering::Relaxed) {
+            usize::MAX => None,
+            next_entry => Some(Continuation {
+                next_entry,
+                num_entries: index.entries().len(),
+            }),
+        },
     })
 }
 
@@ -263,12 +283,14 @@ impl<'index> State<'_, 'index> {
         E: std::error::Error + Send + Sync + 'static,
         Find: gix_object::Find,
     {
+        let is_sparse_excluded = entry.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE);
         if entry.flags.intersects(
             gix_index::entry::Flags::UPTODATE
                 | gix_index::entry::Flags::SKIP_WORKTREE
                 | gix_index::entry::Flags::ASSUME_VALID
                 | gix_index::entry::Flags::FSMONITOR_VALID,
-        ) {
+        ) && !(is_sparse_excluded && self.options.emit_sparse_excluded)
+        {
             self.skipped_by_entry_flags.fetch_add(1, Ordering::Relaxed);
             return None;
         }
@@ -277,7 +299,9 @@ impl<'index> State<'_, 'index> {
             self.skipped_by_pathspec.fetch_add(1, Ordering::Relaxed);
             return None;
         }
-        let status = if entry.stage() != 0 {
+        let status = if is_sparse_excluded {
+            Ok(Some(EntryStatus::SparseExcluded))
+        } else if entry.stage() != 0 {
             Ok(
                 Conflict::try_from_entry(entries, self.path_backing, entry_index, path).map(|(conflict, offset)| {
                     *outer_entry_index += offset; // let out loop skip over entries related to the conflict
@@ -561,18 +585,34 @@ where
     }
 }
 
-struct OffsetIter<'a, T> {
+/// Yield chunks along with the index of their first entry, and stop early if interrupted or out of time.
+/// In that case, `stopped_at` is set to the index of the first entry of the chunk that wasn't yielded.
+struct OffsetIter<'a, 'b, T> {
     inner: Chunks<'a, T>,
     offset: usize,
+    should_interrupt: &'b AtomicBool,
+    deadline: Option<Instant>,
+    /// The first chunk is always yielded, even if out of time, to assure each run makes progress.
+    made_progress: bool,
+    stopped_at: &'b AtomicUsize,
 }
 
-impl<'a, T> Iterator for OffsetIter<'a, T> {
+impl<'a, T> Iterator for OffsetIter<'a, '_, T> {
     type Item = (usize, &'a [T]);
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.stopped_at.load(Ordering::Relaxed) != usize::MAX {
+            return None;
+        }
         let block = self.inner.next()?;
+        let out_of_time = self.made_progress && self.deadline.map_or(false, |deadline| Instant::now() >= deadline);
+        if self.should_interrupt.load(Ordering::Relaxed) || out_of_time {
+            self.stopped_at.store(self.offset, Ordering::Relaxed);
+            return None;
+        }
         let offset = self.offset;
         self.offset += block.len();
+        self.made_progress = true;
         Some((offset, block))
     }
 }
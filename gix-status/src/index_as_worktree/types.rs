@@ -17,6 +17,8 @@ pub enum Error {
         rela_path: BString,
         source: Box<dyn std::error::Error + Send + Sync + 'static>,
     },
+    #[error("Cannot resume status of an index with {expected} entries as it now has {actual} entries")]
+    ContinuationMismatch { expected: usize, actual: usize },
 }
 
 /// Options that control how the index status with a worktree is computed.
@@ -35,6 +37,34 @@ pub struct Options {
     /// These are needed to potentially refresh the index with data read from the worktree, which needs to be converted back
     /// to the form stored in git.
     pub attributes: gix_worktree::stack::state::Attributes,
+    /// If `true`, entries that are excluded from the worktree by sparse checkout, i.e. those marked with
+    /// [`SKIP_WORKTREE`](gix_index::entry::Flags::SKIP_WORKTREE), are emitted as [`EntryStatus::SparseExcluded`].
+    ///
+    /// Otherwise, they are skipped like `git status` does, and never show up as [removed](Change::Removed)
+    /// even though they are not present in the worktree.
+    pub emit_sparse_excluded: bool,
+    /// If `Some(continuation)`, resume a previous run that [stopped early](Outcome::continuation) and only process the entries
+    /// that it didn't get to.
+    ///
+    /// The index must be the same as in the previous run, or the operation fails.
+    pub resume_from: Option<Continuation>,
+    /// If `Some(budget)`, stop processing entries once this much time has passed and provide a [continuation](Outcome::continuation)
+    /// to resume with.
+    ///
+    /// This allows showing partial results quickly on huge worktrees, and to refine them progressively.
+    /// Note that the first batch of entries is always processed to assure progress is made, and that batches that are
+    /// started are always completed, so the budget may be exceeded.
+    pub time_budget: Option<std::time::Duration>,
+}
+
+/// A token to resume an [`index_as_worktree()`](crate::index_as_worktree()) operation that stopped before processing all entries,
+/// via [`Options::resume_from`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Continuation {
+    /// The index of the first entry that wasn't processed yet.
+    pub next_entry: usize,
+    /// The amount of entries in the index at the time, to detect if the index changed in the meantime.
+    pub num_entries: usize,
 }
 
 /// Provide additional information collected during the runtime of [`index_as_worktree()`](crate::index_as_worktree()).
@@ -71,6 +101,12 @@ pub struct Outcome {
     pub odb_bytes: u64,
     /// The amount of objects read from the object database.
     pub odb_objects_read: usize,
+    /// If `Some(continuation)`, not all entries were processed as the operation was interrupted or ran out of its
+    /// [time budget](Options::time_budget).
+    ///
+    /// All changes that were observed are complete up to the [next entry](Continuation::next_entry), and passing the
+    /// continuation to [`Options::resume_from`] processes the remaining ones.
+    pub continuation: Option<Continuation>,
 }
 
 impl Outcome {
@@ -135,6 +171,10 @@ pub enum EntryStatus<T = (), U = ()> {
     /// Effectively this means nothing changed.
     /// This also means the file is still present, and that no detailed change checks were performed.
     IntentToAdd,
+    /// The entry is excluded from the worktree by sparse checkout, and no check with the worktree was performed.
+    ///
+    /// This is only emitted if [`Options::emit_sparse_excluded`] is `true`.
+    SparseExcluded,
 }
 
 impl<T, U> From<Change<T, U>> for EntryStatus<T, U> {
@@ -2,10 +2,11 @@
 //! of the repository state, like comparisons between…
 //!
 //! * index and working tree
-//! * index and tree
+//! * index and tree, with rename and copy tracking
 //! * find untracked files
 //!
-//! While also being able to check check if the working tree is dirty, quickly.
+//! While also being able to check check if the working tree is dirty, quickly, optionally with the help of a
+//! [filesystem monitor](fs_monitor).
 #![deny(missing_docs, rust_2018_idioms, unsafe_code)]
 
 use bstr::BStr;
@@ -13,6 +14,11 @@ use bstr::BStr;
 pub mod index_as_worktree;
 pub use index_as_worktree::function::index_as_worktree;
 
+pub mod tree_index;
+pub use tree_index::function::tree_index;
+
+pub mod fs_monitor;
+
 /// A trait to facilitate working working with pathspecs.
 pub trait Pathspec {
     /// Return the portion of the prefix among all of the pathspecs involved in this search, or an empty string if
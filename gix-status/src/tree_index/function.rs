@@ -0,0 +1,200 @@
+use bstr::BStr;
+use gix_diff::{
+    rewrites::{tracker::visit::SourceKind, Tracker},
+    tree::visit::Change as TrackedChange,
+};
+use gix_index::entry::Flags;
+use gix_object::tree::EntryMode;
+
+use crate::tree_index::{Action, Change, Error, Options, Outcome};
+
+/// Compare `tree`, the state of a tree as index, typically the one of `HEAD^{tree}`, with `index` and call `cb` with each
+/// [`Change`] needed to turn `tree` into `index`, in the order of their paths, similar to what `git status` lists
+/// under *changes to be committed*.
+///
+/// With [`Options::rewrites`] set, additions and deletions are paired up as renames or copies of similar content,
+/// which is why all changes that may be part of a rewrite are emitted last, starting with the rewrites themselves.
+/// `resource_cache` is used to compare blobs for similarity if the content isn't identical, and `objects` to obtain them.
+///
+/// Note that entries that are conflicting in `index`, or that are only intended to be added, are not considered.
+pub fn tree_index<E>(
+    tree: &gix_index::State,
+    index: &gix_index::State,
+    mut cb: impl FnMut(Change<'_>) -> Result<Action, E>,
+    resource_cache: &mut gix_diff::blob::Platform,
+    objects: &impl gix_object::FindObjectOrHeader,
+    options: Options,
+) -> Result<Outcome, Error>
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    let mut tracker = options.rewrites.map(Tracker::<TrackedChange>::new);
+    let mut err = None;
+    let mut emit = |change: Change<'_>, err: &mut Option<E>| match cb(change) {
+        Ok(action) => action,
+        Err(e) => {
+            *err = Some(e);
+            Action::Cancel
+        }
+    };
+
+    let mut lhs = tree.entries().iter().peekable();
+    let mut rhs = index
+        .entries()
+        .iter()
+        .filter(|entry| entry.stage() == 0 && !entry.flags.intersects(Flags::REMOVE | Flags::INTENT_TO_ADD))
+        .peekable();
+    let mut unchanged = Vec::new();
+    loop {
+        let (change, location) = match (lhs.peek(), rhs.peek()) {
+            (None, None) => break,
+            (Some(old), new) if new.map_or(true, |new| old.path(tree) < new.path(index)) => {
+                let old = lhs.next().expect("peeked");
+                let location = old.path(tree);
+                if index.entry_range(location).is_some() {
+                    // The path is conflicting in the index.
+                    continue;
+                }
+                let change = TrackedChange::Deletion {
+                    entry_mode: tree_entry_mode(old, location)?,
+                    oid: old.id,
+                };
+                (change, location)
+            }
+            (old, Some(new)) if old.map_or(true, |old| old.path(tree) > new.path(index)) => {
+                let new = rhs.next().expect("peeked");
+                let location = new.path(index);
+                let change = TrackedChange::Addition {
+                    entry_mode: tree_entry_mode(new, location)?,
+                    oid: new.id,
+                };
+                (change, location)
+            }
+            (Some(_), Some(_)) => {
+                let (old, new) = (lhs.next().expect("peeked"), rhs.next().expect("peeked"));
+                let location = new.path(index);
+                debug_assert_eq!(old.path(tree), location, "guards assure paths are equal");
+                if old.id == new.id && old.mode == new.mode {
+                    unchanged.push(old);
+                    continue;
+                }
+                let change = TrackedChange::Modification {
+                    previous_entry_mode: tree_entry_mode(old, location)?,
+                    previous_oid: old.id,
+                    entry_mode: tree_entry_mode(new, location)?,
+                    oid: new.id,
+                };
+                (change, location)
+            }
+            (None, Some(_)) | (Some(_), None) => unreachable!("handled by guards above"),
+        };
+        let change = match tracker.as_mut() {
+            Some(tracker) => match tracker.try_push_change(change, location) {
+                Some(change) => change,
+                None => continue,
+            },
+            None => change,
+        };
+        if emit(untracked_change(change, location), &mut err) == Action::Cancel {
+            return match err {
+                Some(err) => Err(Error::Callback(err.into())),
+                None => Ok(Outcome::default()),
+            };
+        }
+    }
+
+    let mut outcome = Outcome::default();
+    if let Some(mut tracker) = tracker {
+        let rewrites = tracker
+            .emit(
+                |dest, source| {
+                    let change = match source {
+                        Some(source) => {
+                            let (id, entry_mode) = (dest.change.oid().to_owned(), dest.change.entry_mode());
+                            let similarity = source.diff.map_or(1.0, |diff| diff.similarity);
+                            match source.kind {
+                                SourceKind::Rename => Change::Rename {
+                                    from: source.location,
+                                    to: dest.location,
+                                    source_entry_mode: source.entry_mode,
+                                    source_id: source.id,
+                                    entry_mode,
+                                    id,
+                                    similarity,
+                                },
+                                SourceKind::Copy => Change::Copy {
+                                    from: source.location,
+                                    to: dest.location,
+                                    source_entry_mode: source.entry_mode,
+                                    source_id: source.id,
+                                    entry_mode,
+                                    id,
+                                    similarity,
+                                },
+                            }
+                        }
+                        None => untracked_change(dest.change, dest.location),
+                    };
+                    emit(change, &mut err)
+                },
+                resource_cache,
+                objects,
+                |push| -> Result<(), std::convert::Infallible> {
+                    for entry in &unchanged {
+                        let Some(entry_mode) = entry.mode.to_tree_entry_mode() else {
+                            continue;
+                        };
+                        push(
+                            TrackedChange::Modification {
+                                previous_entry_mode: entry_mode,
+                                previous_oid: entry.id,
+                                entry_mode,
+                                oid: entry.id,
+                            },
+                            entry.path(tree),
+                        );
+                    }
+                    Ok(())
+                },
+            )
+            .map_err(|err| Error::RenameTracking(Box::new(err)))?;
+        outcome.rewrites = Some(rewrites);
+    }
+    match err {
+        Some(err) => Err(Error::Callback(err.into())),
+        None => Ok(outcome),
+    }
+}
+
+fn tree_entry_mode(entry: &gix_index::Entry, location: &BStr) -> Result<EntryMode, Error> {
+    entry.mode.to_tree_entry_mode().ok_or_else(|| Error::InvalidEntryMode {
+        rela_path: location.to_owned(),
+    })
+}
+
+fn untracked_change(change: TrackedChange, location: &BStr) -> Change<'_> {
+    match change {
+        TrackedChange::Addition { entry_mode, oid } => Change::Addition {
+            location,
+            entry_mode,
+            id: oid,
+        },
+        TrackedChange::Deletion { entry_mode, oid } => Change::Deletion {
+            location,
+            entry_mode,
+            id: oid,
+        },
+        TrackedChange::Modification {
+            previous_entry_mode,
+            previous_oid,
+            entry_mode,
+            oid,
+        } => Change::Modification {
+            location,
+            previous_entry_mode,
+            previous_id: previous_oid,
+            entry_mode,
+            id: oid,
+        },
+    }
+}
@@ -0,0 +1,119 @@
+//! Changes between a tree and an index, i.e. the changes that would be committed.
+use bstr::BStr;
+use gix_hash::ObjectId;
+use gix_object::tree::EntryMode;
+
+pub(crate) mod function;
+
+/// The error returned by [`tree_index()`](crate::tree_index()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The index entry at '{rela_path}' has a mode that can't be represented in a tree")]
+    InvalidEntryMode { rela_path: bstr::BString },
+    #[error(transparent)]
+    RenameTracking(Box<gix_diff::rewrites::tracker::emit::Error>),
+    #[error("The callback failed")]
+    Callback(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+/// Options for use in [`tree_index()`](crate::tree_index()).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Options {
+    /// If set, additions and deletions are paired up as renames, and possibly copies, according to these settings.
+    ///
+    /// `git` controls this with `status.renames` and `status.renameLimit`, which default to their `diff.*` counterparts.
+    pub rewrites: Option<gix_diff::Rewrites>,
+}
+
+/// Provide additional information collected during the runtime of [`tree_index()`](crate::tree_index()).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Outcome {
+    /// The outcome of the rename and copy tracking, if it was enabled.
+    pub rewrites: Option<gix_diff::rewrites::Outcome>,
+}
+
+/// What to do after a [`Change`] was received.
+pub use gix_diff::tree::visit::Action;
+
+/// A change needed to turn the tree into the index.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change<'a> {
+    /// An entry was added to the index.
+    Addition {
+        /// The repository-relative path of the entry.
+        location: &'a BStr,
+        /// The mode of the entry in the index.
+        entry_mode: EntryMode,
+        /// The object id of the entry in the index.
+        id: ObjectId,
+    },
+    /// An entry was removed from the index.
+    Deletion {
+        /// The repository-relative path of the entry.
+        location: &'a BStr,
+        /// The mode of the entry in the tree.
+        entry_mode: EntryMode,
+        /// The object id of the entry in the tree.
+        id: ObjectId,
+    },
+    /// An entry changed its content or its mode.
+    Modification {
+        /// The repository-relative path of the entry.
+        location: &'a BStr,
+        /// The mode of the entry in the tree.
+        previous_entry_mode: EntryMode,
+        /// The object id of the entry in the tree.
+        previous_id: ObjectId,
+        /// The mode of the entry in the index.
+        entry_mode: EntryMode,
+        /// The object id of the entry in the index.
+        id: ObjectId,
+    },
+    /// The entry at `from` was moved to `to`, possibly with changes, so it's not present at `from` anymore.
+    Rename {
+        /// The repository-relative path of the entry in the tree.
+        from: &'a BStr,
+        /// The repository-relative path of the entry in the index.
+        to: &'a BStr,
+        /// The mode of the entry in the tree.
+        source_entry_mode: EntryMode,
+        /// The object id of the entry in the tree.
+        source_id: ObjectId,
+        /// The mode of the entry in the index.
+        entry_mode: EntryMode,
+        /// The object id of the entry in the index.
+        id: ObjectId,
+        /// How similar the content at `from` is to the one at `to`, from 0.0 to 1.0, with 1.0 meaning they are equal.
+        similarity: f32,
+    },
+    /// The entry at `to` was added as a copy of the one at `from`, possibly with changes, which may itself also have changed.
+    Copy {
+        /// The repository-relative path of the entry in the tree that served as source.
+        from: &'a BStr,
+        /// The repository-relative path of the new entry in the index.
+        to: &'a BStr,
+        /// The mode of the entry in the tree.
+        source_entry_mode: EntryMode,
+        /// The object id of the entry in the tree.
+        source_id: ObjectId,
+        /// The mode of the entry in the index.
+        entry_mode: EntryMode,
+        /// The object id of the entry in the index.
+        id: ObjectId,
+        /// How similar the content at `from` is to the one at `to`, from 0.0 to 1.0, with 1.0 meaning they are equal.
+        similarity: f32,
+    },
+}
+
+impl Change<'_> {
+    /// Return the repository-relative path of the entry in the index, or in the tree if it was deleted.
+    pub fn location(&self) -> &BStr {
+        match self {
+            Change::Addition { location, .. }
+            | Change::Deletion { location, .. }
+            | Change::Modification { location, .. } => location,
+            Change::Rename { to, .. } | Change::Copy { to, .. } => to,
+        }
+    }
+}
@@ -0,0 +1,25 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+echo -n "a" > a
+echo -n "c" > c
+mkdir d e
+echo -n "b" > d/b
+echo -n "f" > e/f
+
+git add -A
+git commit -m "Commit"
+
+cat <<'HOOK' > .git/fsmonitor-hook
+#!/bin/sh
+printf 'token-2\0c\0d/\0'
+HOOK
+chmod +x .git/fsmonitor-hook
+git config core.fsmonitor .git/fsmonitor-hook
+git update-index --fsmonitor
+git status >/dev/null
+
+echo -n "changed and reported" > c
+echo -n "changed but not reported" > e/f
@@ -0,0 +1,94 @@
+use bstr::ByteSlice;
+use gix_index::extension::fs_monitor::Token;
+use gix_status::fs_monitor;
+
+use crate::fixture_path;
+
+#[test]
+fn query_reports_paths_and_their_children_as_possibly_changed() -> crate::Result {
+    let worktree = fixture_path("status_fs_monitor");
+    let index = gix_index::File::at(
+        worktree.join(".git").join("index"),
+        gix_hash::Kind::Sha1,
+        false,
+        Default::default(),
+    )?;
+    let token = match index.fs_monitor().expect("written by git").token() {
+        Token::V2 { token } => token.clone(),
+        Token::V1 { .. } => unreachable!("the fixture uses the second version of the hook protocol"),
+    };
+    assert_eq!(token, "token-2");
+
+    let outcome = fs_monitor::query(".git/fsmonitor-hook".into(), &worktree, token.as_ref())?;
+    assert_eq!(outcome.token, "token-2", "the hook always provides the same token");
+    assert!(!outcome.everything_possibly_changed());
+    for (path, expected) in [
+        ("c", true),
+        ("d", true),
+        ("d/b", true),
+        ("d/b/deeper", true),
+        ("a", false),
+        ("c-and-more", false),
+        ("e/f", false),
+        ("dd/b", false),
+    ] {
+        assert_eq!(
+            outcome.is_possibly_changed(path.as_bytes().as_bstr()),
+            expected,
+            "{path}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn query_fails_if_the_hook_fails() {
+    let worktree = fixture_path("status_fs_monitor");
+    let err = fs_monitor::query("exit 42".into(), &worktree, "token".into()).unwrap_err();
+    assert!(matches!(err, fs_monitor::Error::Failed { .. }));
+}
+
+#[test]
+fn update_extension_marks_all_but_dirty_entries_as_valid() -> crate::Result {
+    let worktree = fixture_path("status_fs_monitor");
+    let mut index = gix_index::File::at(
+        worktree.join(".git").join("index"),
+        gix_hash::Kind::Sha1,
+        false,
+        Default::default(),
+    )?;
+    let outcome = fs_monitor::Outcome::from_response(b"token-3\0/\0").expect("valid");
+    assert!(outcome.everything_possibly_changed());
+    let c_idx = index.entry_index_by_path("c".into()).expect("present");
+    fs_monitor::update_extension(&mut index, &outcome, Some(c_idx));
+
+    let mut buf = Vec::new();
+    index.write_to(&mut buf, Default::default())?;
+    let (mut index, _) = gix_index::State::from_bytes(
+        &buf,
+        filetime::FileTime::now(),
+        gix_hash::Kind::Sha1,
+        Default::default(),
+    )?;
+    assert_eq!(
+        index.fs_monitor().expect("written").token(),
+        &Token::V2 {
+            token: "token-3".into()
+        }
+    );
+
+    let outcome = fs_monitor::Outcome::from_response(b"token-4\0d/b\0").expect("valid");
+    assert_eq!(
+        fs_monitor::mark_valid_entries(&mut index, &outcome),
+        2,
+        "'c' is dirty and 'd/b' changed since"
+    );
+    let valid: Vec<_> = index
+        .entries()
+        .iter()
+        .filter(|e| e.flags.contains(gix_index::entry::Flags::FSMONITOR_VALID))
+        .map(|e| e.path(&index).to_owned())
+        .collect();
+    assert_eq!(valid, ["a", "e/f"]);
+    Ok(())
+}
@@ -41,23 +41,31 @@ fn fixture_with_index(
     prepare_index: impl FnMut(&mut gix_index::State),
     expected_status: &[Expectation<'_>],
 ) -> Outcome {
-    fixture_filtered_detailed(name, "", &[], expected_status, prepare_index, false)
+    fixture_filtered_detailed(name, "", &[], expected_status, prepare_index, false, false)
 }
 
 fn submodule_fixture(name: &str, expected_status: &[Expectation<'_>]) -> Outcome {
-    fixture_filtered_detailed("status_submodule", name, &[], expected_status, |_| {}, false)
+    fixture_filtered_detailed("status_submodule", name, &[], expected_status, |_| {}, false, false)
 }
 
 fn conflict_fixture(name: &str, expected_status: &[Expectation<'_>]) -> Outcome {
-    fixture_filtered_detailed("conflicts", name, &[], expected_status, |_| {}, false)
+    fixture_filtered_detailed("conflicts", name, &[], expected_status, |_| {}, false, false)
 }
 
 fn submodule_fixture_status(name: &str, expected_status: &[Expectation<'_>], submodule_dirty: bool) -> Outcome {
-    fixture_filtered_detailed("status_submodule", name, &[], expected_status, |_| {}, submodule_dirty)
+    fixture_filtered_detailed(
+        "status_submodule",
+        name,
+        &[],
+        expected_status,
+        |_| {},
+        submodule_dirty,
+        false,
+    )
 }
 
 fn fixture_filtered(name: &str, pathspecs: &[&str], expected_status: &[Expectation<'_>]) -> Outcome {
-    fixture_filtered_detailed(name, "", pathspecs, expected_status, |_| {}, false)
+    fixture_filtered_detailed(name, "", pathspecs, expected_status, |_| {}, false, false)
 }
 
 fn fixture_filtered_detailed(
@@ -67,6 +75,7 @@ fn fixture_filtered_detailed(
     expected_status: &[Expectation<'_>],
     mut prepare_index: impl FnMut(&mut gix_index::State),
     submodule_dirty: bool,
+    emit_sparse_excluded: bool,
 ) -> Outcome {
     // This can easily happen in some fixtures, which can cause flakiness. It's time-dependent after all.
     fn ignore_racyclean(mut out: Outcome) -> Outcome {
@@ -107,6 +116,7 @@ fn fixture_filtered_detailed(
         Options {
             fs: gix_fs::Capabilities::probe(&git_dir),
             stat: TEST_OPTIONS,
+            emit_sparse_excluded,
             ..Options::default()
         },
     )
@@ -144,6 +154,7 @@ fn deracify_status(status: EntryStatus) -> Option<EntryStatus> {
         .into(),
         EntryStatus::NeedsUpdate(_) => return None,
         EntryStatus::IntentToAdd => EntryStatus::IntentToAdd,
+        EntryStatus::SparseExcluded => EntryStatus::SparseExcluded,
     })
 }
 
@@ -213,6 +224,172 @@ fn removed() {
     );
 }
 
+#[test]
+fn interrupted_status_can_be_resumed() {
+    let worktree = fixture_path("status_removed");
+    let git_dir = worktree.join(".git");
+    let index = gix_index::File::at(git_dir.join("index"), gix_hash::Kind::Sha1, false, Default::default()).unwrap();
+    let run = |should_interrupt: bool,
+               resume_from: Option<index_as_worktree::Continuation>,
+               time_budget: Option<std::time::Duration>| {
+        let mut recorder = Recorder::default();
+        let search = gix_pathspec::Search::from_specs(None, None, std::path::Path::new("")).expect("empty is valid");
+        let outcome = index_as_worktree(
+            &index,
+            &worktree,
+            &mut recorder,
+            FastEq,
+            SubmoduleStatusMock { dirty: false },
+            gix_object::find::Never,
+            &mut gix_features::progress::Discard,
+            Pathspec(search),
+            Default::default(),
+            &AtomicBool::new(should_interrupt),
+            Options {
+                fs: gix_fs::Capabilities::probe(&git_dir),
+                stat: TEST_OPTIONS,
+                resume_from,
+                time_budget,
+                ..Options::default()
+            },
+        );
+        recorder.records.sort_unstable_by_key(|r| r.entry_index);
+        outcome.map(|outcome| (records_to_tuple(recorder.records), outcome))
+    };
+
+    let (records, outcome) = run(true, None, None).unwrap();
+    assert_eq!(records, [], "nothing was processed");
+    let continuation = outcome.continuation.expect("interrupted");
+    assert_eq!(
+        continuation,
+        index_as_worktree::Continuation {
+            next_entry: 0,
+            num_entries: 4
+        }
+    );
+    assert_eq!(outcome.entries_processed, 0);
+
+    let mut all_records = Vec::new();
+    let mut resume_from = Some(continuation);
+    while let Some(continuation) = resume_from {
+        let (records, outcome) = run(false, Some(continuation), Some(std::time::Duration::ZERO)).unwrap();
+        assert_ne!(
+            records.len(),
+            0,
+            "at least one batch is always processed, even if the time budget is exceeded"
+        );
+        all_records.extend(records);
+        resume_from = outcome.continuation;
+    }
+    assert_eq!(
+        all_records,
+        [
+            (BStr::new(b"dir/content"), 0, status_removed()),
+            (BStr::new(b"dir/sub-dir/symlink"), 1, status_removed()),
+            (BStr::new(b"empty"), 2, status_removed()),
+            (BStr::new(b"executable"), 3, status_removed()),
+        ],
+        "resuming until done yields the same result as a single run"
+    );
+
+    let (records, outcome) = run(
+        false,
+        Some(index_as_worktree::Continuation {
+            next_entry: 2,
+            num_entries: 4,
+        }),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        records,
+        [
+            (BStr::new(b"empty"), 2, status_removed()),
+            (BStr::new(b"executable"), 3, status_removed()),
+        ],
+        "only entries that weren't processed yet are processed when resuming"
+    );
+    assert_eq!(
+        outcome,
+        Outcome {
+            entries_to_process: 2,
+            entries_processed: 2,
+            symlink_metadata_calls: 2,
+            ..Default::default()
+        }
+    );
+
+    let err = run(
+        false,
+        Some(index_as_worktree::Continuation {
+            next_entry: 2,
+            num_entries: 5,
+        }),
+        None,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            index_as_worktree::Error::ContinuationMismatch { expected: 5, actual: 4 }
+        ),
+        "continuations can't be used if the index changed"
+    );
+}
+
+#[test]
+fn sparse_excluded_entries_are_not_removed() {
+    fn exclude_dir(index: &mut gix_index::State) {
+        for entry in &mut index.entries_mut()[..2] {
+            entry.flags.insert(gix_index::entry::Flags::SKIP_WORKTREE);
+        }
+    }
+    let out = fixture_with_index(
+        "status_removed",
+        exclude_dir,
+        &[
+            (BStr::new(b"empty"), 2, status_removed()),
+            (BStr::new(b"executable"), 3, status_removed()),
+        ],
+    );
+    assert_eq!(
+        out,
+        Outcome {
+            entries_to_process: 4,
+            entries_processed: 4,
+            entries_skipped_by_entry_flags: 2,
+            symlink_metadata_calls: 2,
+            ..Default::default()
+        },
+        "entries excluded by sparse checkout aren't in the worktree, but that's expected"
+    );
+
+    let out = fixture_filtered_detailed(
+        "status_removed",
+        "",
+        &[],
+        &[
+            (BStr::new(b"dir/content"), 0, EntryStatus::SparseExcluded),
+            (BStr::new(b"dir/sub-dir/symlink"), 1, EntryStatus::SparseExcluded),
+            (BStr::new(b"empty"), 2, status_removed()),
+            (BStr::new(b"executable"), 3, status_removed()),
+        ],
+        exclude_dir,
+        false,
+        true,
+    );
+    assert_eq!(
+        out,
+        Outcome {
+            entries_to_process: 4,
+            entries_processed: 4,
+            symlink_metadata_calls: 2,
+            ..Default::default()
+        },
+        "they can be listed separately, without looking at the worktree"
+    );
+}
+
 #[test]
 fn subomdule_nochange() {
     assert_eq!(
@@ -720,3 +897,45 @@ impl gix_status::Pathspec for Pathspec {
             .map_or(false, |m| !m.is_excluded())
     }
 }
+
+#[test]
+fn fs_monitor_valid_entries_are_skipped() {
+    let worktree = fixture_path("status_fs_monitor");
+    let outcome = fixture_with_index(
+        "status_fs_monitor",
+        |index| {
+            let token = match index.fs_monitor().expect("written by git").token() {
+                index::extension::fs_monitor::Token::V2 { token } => token.clone(),
+                index::extension::fs_monitor::Token::V1 { .. } => unreachable!("the fixture uses a V2 hook"),
+            };
+            let changes = gix_status::fs_monitor::query(".git/fsmonitor-hook".into(), &worktree, token.as_ref())
+                .expect("hook works");
+            assert_eq!(
+                gix_status::fs_monitor::mark_valid_entries(index, &changes),
+                2,
+                "'a' and 'e/f' weren't reported, so they are assumed to be unchanged"
+            );
+        },
+        &[(
+            BStr::new(b"c"),
+            1,
+            Change::Modification {
+                executable_bit_changed: false,
+                content_change: Some(()),
+                set_entry_stat_size_zero: false,
+            }
+            .into(),
+        )],
+    );
+    assert_eq!(
+        outcome,
+        Outcome {
+            entries_to_process: 4,
+            entries_processed: 4,
+            entries_skipped_by_entry_flags: 2,
+            symlink_metadata_calls: 2,
+            ..Default::default()
+        },
+        "the change to 'e/f' goes unnoticed as the fsmonitor claims it's unchanged"
+    );
+}
@@ -69,8 +69,8 @@ impl File {
     /// Return a platform which allows to check if a submodule name is active or inactive.
     /// Use `defaults` for parsing the pathspecs used to later match on names via `submodule.active` configuration retrieved from `config`.
     ///
-    /// All `submodule.active` pathspecs are considered to be top-level specs and match the name of submodules, which are active
-    /// on inclusive match.
+    /// All `submodule.active` pathspecs are considered to be top-level specs and match the path of submodules, which are active
+    /// on inclusive match. Submodules without a valid path are matched by name instead.
     /// The full algorithm is described as [hierarchy of rules](https://git-scm.com/docs/gitsubmodules#_active_submodules).
     pub fn is_active_platform(
         &self,
@@ -91,7 +91,11 @@ impl File {
                 )?)
             })
             .transpose()?;
-        Ok(IsActivePlatform { search })
+        let paths = self
+            .names()
+            .filter_map(|name| Some((name.to_owned(), self.path(name).ok()?.into_owned())))
+            .collect();
+        Ok(IsActivePlatform { search, paths })
     }
 
     /// Given the `relative_path` (as seen from the root of the worktree) of a submodule with possibly platform-specific
@@ -21,7 +21,7 @@ impl IsActivePlatform {
     /// A submodule's active state is determined in the following order
     ///
     /// * it's `submodule.<name>.active` is set in `config`
-    /// * it matches a `submodule.active` pathspec either positively or negatively via `:!<spec>`
+    /// * its path matches a `submodule.active` pathspec either positively or negatively via `:!<spec>`
     /// * it's active if it has any `url` set in `config`
     pub fn is_active(
         &mut self,
@@ -37,9 +37,10 @@ impl IsActivePlatform {
         if let Some(val) = config.boolean("submodule", Some(name), "active").transpose()? {
             return Ok(val);
         };
+        let path = self.paths.get(name).map_or(name, |path| path.as_ref());
         if let Some(val) = self.search.as_mut().map(|search| {
             search
-                .pattern_matching_relative_path(name, Some(true), attributes)
+                .pattern_matching_relative_path(path, Some(true), attributes)
                 .map_or(false, |m| !m.is_excluded())
         }) {
             return Ok(val);
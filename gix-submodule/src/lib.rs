@@ -4,7 +4,7 @@
 
 use std::{borrow::Cow, collections::BTreeMap};
 
-use bstr::BStr;
+use bstr::{BStr, BString};
 
 /// All relevant information about a git module, typically from `.gitmodules` files.
 ///
@@ -27,6 +27,8 @@ pub mod is_active_platform;
 /// A platform to keep the state necessary to perform repeated active checks, created by [File::is_active_platform()].
 pub struct IsActivePlatform {
     pub(crate) search: Option<gix_pathspec::Search>,
+    /// The paths of all submodules by name, as `submodule.active` pathspecs match paths.
+    pub(crate) paths: BTreeMap<BString, BString>,
 }
 
 /// Mutation
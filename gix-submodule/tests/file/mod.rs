@@ -116,13 +116,31 @@ mod is_active_platform {
                 )?,
                 Default::default()
             )?,
+            &[
+                ("submodule", false),
+                ("a/b", false),
+                (".a/..c", false),
+                ("a/d\\", false),
+                ("a\\e", false)
+            ],
+            "pathspecs match the path of submodules, like `a\\c` of `.a/..c`, and not their name"
+        );
+        assert_eq!(
+            assume_valid_active_state(
+                &module,
+                &gix_config::File::from_str(
+                    "[submodule.submodule]\n active = 0\n[submodule]\n active = *\n[submodule]\n active = :!a/*"
+                )?,
+                Default::default()
+            )?,
             &[
                 ("submodule", false),
                 ("a/b", false),
                 (".a/..c", true),
                 ("a/d\\", false),
                 ("a\\e", false)
-            ]
+            ],
+            "the path `a/e` of `a\\e` is excluded, while `a\\c` isn't inside of `a/`"
         );
         Ok(())
     }
@@ -9,7 +9,8 @@ pub(crate) mod function {
     ///
     /// This includes connections to
     /// [local repositories][crate::client::file::connect()],
-    /// [repositories over ssh][crate::client::ssh::connect()],
+    /// [repositories over ssh][crate::client::ssh::connect()], possibly [without an ssh program][crate::client::ssh::native::Connection]
+    /// if the `ssh-client-ssh2` feature is enabled,
     /// [git daemons][crate::client::git::connect()],
     /// and if compiled in connections to [git repositories over https][crate::client::http::connect()].
     ///
@@ -34,10 +35,23 @@ pub(crate) mod function {
                         .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
                 )
             }
-            gix_url::Scheme::Ssh => Box::new({
-                crate::client::blocking_io::ssh::connect(url, options.version, options.ssh, options.trace)
-                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?
-            }),
+            gix_url::Scheme::Ssh => {
+                let mut ssh = options.ssh;
+                if options.proxy.is_some() {
+                    ssh.proxy = options.proxy;
+                }
+                #[cfg(feature = "ssh-client-ssh2")]
+                if ssh.use_native_client() {
+                    return Ok(Box::new(
+                        crate::client::blocking_io::ssh::native::connect(url, options.version, options.trace)
+                            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
+                    ));
+                }
+                Box::new(
+                    crate::client::blocking_io::ssh::connect(url, options.version, ssh, options.trace)
+                        .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
+                )
+            }
             gix_url::Scheme::Git => {
                 if url.user().is_some() {
                     return Err(Error::UnsupportedUrlTokens {
@@ -52,6 +66,7 @@ pub(crate) mod function {
                         path,
                         options.version,
                         url.port,
+                        options.proxy.as_ref(),
                         options.trace,
                     )
                     .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?
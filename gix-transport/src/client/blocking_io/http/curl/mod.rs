@@ -62,6 +62,8 @@ pub struct Curl {
     res: Receiver<remote::Response>,
     handle: Option<thread::JoinHandle<Result<(), Error>>>,
     config: http::Options,
+    /// The identity to authenticate with if `curl` performs the authentication.
+    identity: Option<gix_sec::identity::Account>,
 }
 
 impl Curl {
@@ -99,6 +101,7 @@ impl Curl {
                 headers: list,
                 upload_body_kind,
                 config: self.config.clone(),
+                identity: self.identity.clone(),
             })
             .is_err()
         {
@@ -128,6 +131,7 @@ impl Default for Curl {
             req,
             res,
             config: http::Options::default(),
+            identity: None,
         }
     }
 }
@@ -166,4 +170,15 @@ impl http::Http for Curl {
         }
         Ok(())
     }
+
+    fn authenticate_with(&mut self, identity: Option<&gix_sec::identity::Account>) -> bool {
+        let curl_authenticates = match self.config.auth_method {
+            Some(method) => method != http::options::ProxyAuthMethod::Basic,
+            None => self.config.empty_auth && identity.is_none(),
+        };
+        if curl_authenticates {
+            self.identity = identity.cloned();
+        }
+        curl_authenticates
+    }
 }
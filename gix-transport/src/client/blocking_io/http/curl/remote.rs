@@ -113,6 +113,8 @@ pub struct Request {
     pub headers: curl::easy::List,
     pub upload_body_kind: Option<PostBodyDataKind>,
     pub config: http::Options,
+    /// The identity to authenticate with using `config.auth_method`, if curl should authenticate.
+    pub identity: Option<gix_sec::identity::Account>,
 }
 
 pub struct Response {
@@ -154,13 +156,22 @@ pub fn new() -> (
                     proxy_auth_method,
                     user_agent,
                     proxy_authenticate,
+                    auth_method,
+                    empty_auth,
                     verbose,
                     ssl_ca_info,
+                    ssl_cert,
+                    ssl_key,
+                    ssl_cert_password,
                     ssl_version,
                     ssl_verify,
                     http_version,
+                    http2_prior_knowledge,
+                    pool_idle_timeout,
+                    tcp_keepalive,
                     backend,
                 },
+            identity,
         } in req_recv
         {
             let effective_url = redirect::swap_tails(redirected_base_url.as_deref(), &base_url, url.clone());
@@ -178,6 +189,27 @@ pub fn new() -> (
                 handle.cainfo(ca_info)?;
             }
 
+            if let Some(cert) = ssl_cert {
+                handle.ssl_cert(cert)?;
+            }
+            if let Some(key) = ssl_key {
+                handle.ssl_key(key)?;
+            }
+            let mut ssl_cert_password_action = None;
+            if let Some((obtain_password_action, authenticate)) = ssl_cert_password {
+                let creds = authenticate.lock().expect("no panics in other threads")(obtain_password_action)?
+                    .expect("action to fetch credentials");
+                handle.key_password(&creds.identity.password)?;
+                ssl_cert_password_action = Some((creds.next, authenticate));
+            }
+
+            // An empty identity makes curl authenticate with the credentials of the current user, if the method supports it.
+            if let Some(identity) = identity.as_ref().or(empty_auth.then_some(&EMPTY_IDENTITY)) {
+                handle.http_auth(&to_curl_auth(auth_method.unwrap_or_default()))?;
+                handle.username(&identity.username)?;
+                handle.password(&identity.password)?;
+            }
+
             if let Some(ref mut curl_options) = backend.as_ref().and_then(|backend| backend.lock().ok()) {
                 if let Some(opts) = curl_options.downcast_mut::<super::Options>() {
                     if let Some(enabled) = opts.schannel_check_revoke {
@@ -201,6 +233,7 @@ pub fn new() -> (
             if let Some(http_version) = http_version {
                 let version = match http_version {
                     HttpVersion::V1_1 => curl::easy::HttpVersion::V11,
+                    HttpVersion::V2 if http2_prior_knowledge => curl::easy::HttpVersion::V2PriorKnowledge,
                     HttpVersion::V2 => curl::easy::HttpVersion::V2,
                 };
                 // Failing to set the version isn't critical, and may indeed fail depending on the version
@@ -210,9 +243,24 @@ pub fn new() -> (
                 handle.http_version(version).ok();
             }
 
+            // Connections are kept in the cache of our handle, which is reused for all requests of a transport.
+            if let Some(max_age) = pool_idle_timeout {
+                handle.forbid_reuse(max_age.is_zero())?;
+                handle.maxage_conn(max_age).ok();
+            }
+            handle.tcp_keepalive(tcp_keepalive.is_some())?;
+            if let Some(idle) = tcp_keepalive {
+                handle.tcp_keepidle(idle)?;
+            }
+
             let mut proxy_auth_action = None;
             if let Some(proxy) = proxy {
-                handle.proxy(&proxy)?;
+                if proxy_authenticate.is_some() {
+                    // Credentials in the url would take precedence over the ones we obtain, so only pass the host like `git`.
+                    handle.proxy(&without_user_info(&proxy))?;
+                } else {
+                    handle.proxy(&proxy)?;
+                }
                 let proxy_type = if proxy.starts_with("socks5h") {
                     curl::easy::ProxyType::Socks5Hostname
                 } else if proxy.starts_with("socks5") {
@@ -244,23 +292,7 @@ pub fn new() -> (
             if let Some(timeout) = connect_timeout {
                 handle.connect_timeout(timeout)?;
             }
-            {
-                let mut auth = Auth::new();
-                match proxy_auth_method {
-                    ProxyAuthMethod::AnyAuth => auth
-                        .basic(true)
-                        .digest(true)
-                        .digest_ie(true)
-                        .gssnegotiate(true)
-                        .ntlm(true)
-                        .aws_sigv4(true),
-                    ProxyAuthMethod::Basic => auth.basic(true),
-                    ProxyAuthMethod::Digest => auth.digest(true),
-                    ProxyAuthMethod::Negotiate => auth.digest_ie(true),
-                    ProxyAuthMethod::Ntlm => auth.ntlm(true),
-                };
-                handle.proxy_auth(&auth)?;
-            }
+            handle.proxy_auth(&to_curl_auth(proxy_auth_method))?;
             handle.tcp_keepalive(true)?;
 
             if low_speed_time_seconds > 0 && low_speed_limit_bytes_per_second > 0 {
@@ -312,7 +344,7 @@ pub fn new() -> (
                 let handler = handle.get_mut();
                 handler.reset();
 
-                if let Some((action, authenticate)) = proxy_auth_action {
+                for (action, authenticate) in proxy_auth_action.into_iter().chain(ssl_cert_password_action) {
                     authenticate.lock().expect("no panics in other threads")(action.erase()).ok();
                 }
                 let err = Err(io::Error::new(
@@ -348,6 +380,10 @@ pub fn new() -> (
                         action.erase()
                     })?;
                 }
+                // Having received a response at all means the TLS handshake with our certificate succeeded.
+                if let Some((action, authenticate)) = ssl_cert_password_action {
+                    authenticate.lock().expect("no panics in other threads")(action.store())?;
+                }
                 handler.reset();
                 handler.receive_body.take();
                 handler.send_header.take();
@@ -365,6 +401,44 @@ pub fn new() -> (
     (handle, req_send, res_recv)
 }
 
+const EMPTY_IDENTITY: gix_sec::identity::Account = gix_sec::identity::Account {
+    username: String::new(),
+    password: String::new(),
+};
+
+/// Return `proxy` without the `user[:password]@` part of its authority.
+fn without_user_info(proxy: &str) -> String {
+    let (scheme, rest) = proxy
+        .split_once("://")
+        .map_or(("", proxy), |(scheme, rest)| (scheme, rest));
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    let host_start = rest[..authority_end].rfind('@').map_or(0, |pos| pos + 1);
+    let rest = &rest[host_start..];
+    if scheme.is_empty() {
+        rest.to_owned()
+    } else {
+        format!("{scheme}://{rest}")
+    }
+}
+
+fn to_curl_auth(method: ProxyAuthMethod) -> Auth {
+    let mut auth = Auth::new();
+    match method {
+        ProxyAuthMethod::AnyAuth => auth
+            .basic(true)
+            .digest(true)
+            .digest_ie(true)
+            .gssnegotiate(true)
+            .ntlm(true)
+            .aws_sigv4(true),
+        ProxyAuthMethod::Basic => auth.basic(true),
+        ProxyAuthMethod::Digest => auth.digest(true),
+        ProxyAuthMethod::Negotiate => auth.gssnegotiate(true),
+        ProxyAuthMethod::Ntlm => auth.ntlm(true),
+    };
+    auth
+}
+
 fn to_curl_ssl_version(vers: SslVersion) -> curl::easy::SslVersion {
     use curl::easy::SslVersion::*;
     match vers {
@@ -143,6 +143,7 @@ pub struct Options {
     pub low_speed_time_seconds: u64,
     /// A curl-style proxy declaration of the form `[protocol://][user[:password]@]proxyhost[:port]`.
     ///
+    /// `protocol` may be `http`, `https`, `socks5` or `socks5h`, the latter resolving host names through the proxy.
     /// Note that an empty string means the proxy is disabled entirely.
     /// Refers to `http.proxy`.
     pub proxy: Option<String>,
@@ -150,6 +151,7 @@ pub struct Options {
     pub no_proxy: Option<String>,
     /// The way to authenticate against the proxy if the `proxy` field contains a username.
     ///
+    /// Note that the `reqwest` backend only supports basic authentication.
     /// Refers to `http.proxyAuthMethod`.
     pub proxy_auth_method: options::ProxyAuthMethod,
     /// If authentication is needed for the proxy as its URL contains a username, this method must be set to provide a password
@@ -158,6 +160,19 @@ pub struct Options {
         gix_credentials::helper::Action,
         Arc<std::sync::Mutex<options::AuthenticateFn>>,
     )>,
+    /// The way to authenticate against the server with the identity of the transport, or `None` to send it using HTTP basic
+    /// authentication with each request.
+    ///
+    /// Methods like NTLM or Negotiate (SPNEGO) need a handshake with the server and are only supported by backends that
+    /// implement [`Http::authenticate_with()`], falling back to basic authentication otherwise.
+    ///
+    /// Refers to `gitoxide.http.authMethod`.
+    pub auth_method: Option<options::ProxyAuthMethod>,
+    /// If `true`, authenticate against the server with an empty username and password if there is no identity, which allows
+    /// Negotiate to use the Kerberos ticket of the current user without asking for credentials.
+    ///
+    /// Refers to `http.emptyAuth`.
+    pub empty_auth: bool,
     /// The `HTTP` `USER_AGENT` string presented to an `HTTP` server, notably not the user agent present to the `git` server.
     ///
     /// If not overridden, it defaults to the user agent provided by `curl`, which is a deviation from how `git` handles this.
@@ -177,6 +192,22 @@ pub struct Options {
     pub verbose: bool,
     /// If set, use this path to point to a file with CA certificates to verify peers.
     pub ssl_ca_info: Option<PathBuf>,
+    /// If set, the path to the client certificate to present to the server for mutual TLS authentication.
+    ///
+    /// Refers to `http.sslCert`.
+    pub ssl_cert: Option<PathBuf>,
+    /// If set, the path to the private key of `ssl_cert`, if it isn't contained in the certificate file.
+    ///
+    /// Refers to `http.sslKey`.
+    pub ssl_key: Option<PathBuf>,
+    /// If the client certificate or its key is protected by a password, this method must be set to obtain the password
+    /// before making the request, and to store it if the connection succeeds.
+    ///
+    /// Is set if `http.sslCertPasswordProtected` is true.
+    pub ssl_cert_password: Option<(
+        gix_credentials::helper::Action,
+        Arc<std::sync::Mutex<options::AuthenticateFn>>,
+    )>,
     /// The SSL version or version range to use, or `None` to let the TLS backend determine which versions are acceptable.
     pub ssl_version: Option<SslVersionRangeInclusive>,
     /// Controls whether to perform SSL identity verification or not. Turning this off is not recommended and can lead to
@@ -184,7 +215,18 @@ pub struct Options {
     /// certificate and the user accepts the associated security risks.
     pub ssl_verify: bool,
     /// The HTTP version to enforce. If unset, it is implementation defined.
+    ///
+    /// Note that HTTP/2 is negotiated with the server using ALPN, which requires `https` URLs.
     pub http_version: Option<HttpVersion>,
+    /// If `true` and `http_version` is HTTP/2, speak HTTP/2 right away without negotiating it first, which also works with
+    /// plain `http` URLs but fails if the server doesn't support it.
+    pub http2_prior_knowledge: bool,
+    /// The amount of time an idle connection is kept open for reuse by subsequent requests, like the `fetch` following `ls-refs`,
+    /// or `None` to use the backend default. A zero duration disables connection reuse entirely.
+    pub pool_idle_timeout: Option<std::time::Duration>,
+    /// If set, enable TCP keep-alive probes on connections after they have been idle for the given duration, which helps
+    /// keeping pooled connections alive.
+    pub tcp_keepalive: Option<std::time::Duration>,
     /// Backend specific options, if available.
     pub backend: Option<Arc<Mutex<dyn Any + Send + Sync + 'static>>>,
 }
@@ -200,13 +242,21 @@ impl Default for Options {
             no_proxy: None,
             proxy_auth_method: Default::default(),
             proxy_authenticate: None,
+            auth_method: None,
+            empty_auth: false,
             user_agent: None,
             connect_timeout: None,
             verbose: false,
             ssl_ca_info: None,
+            ssl_cert: None,
+            ssl_key: None,
+            ssl_cert_password: None,
             ssl_version: None,
             ssl_verify: true,
             http_version: None,
+            http2_prior_knowledge: false,
+            pool_idle_timeout: None,
+            tcp_keepalive: None,
             backend: None,
         }
     }
@@ -295,7 +345,10 @@ impl<H: Http> Transport<H> {
     }
 
     #[allow(clippy::unnecessary_wraps, unknown_lints)]
-    fn add_basic_auth_if_present(&self, headers: &mut Vec<Cow<'_, str>>) -> Result<(), client::Error> {
+    fn add_basic_auth_if_present(&mut self, headers: &mut Vec<Cow<'_, str>>) -> Result<(), client::Error> {
+        if self.http.authenticate_with(self.identity.as_ref()) {
+            return Ok(());
+        }
         if let Some(gix_sec::identity::Account { username, password }) = &self.identity {
             #[cfg(not(debug_assertions))]
             if self.url.starts_with("http://") {
@@ -8,7 +8,13 @@ use std::{
 
 use gix_features::io::pipe;
 
-use crate::client::http::{self, options::FollowRedirects, redirect, reqwest::Remote, traits::PostBodyDataKind};
+use crate::client::http::{
+    self,
+    options::{FollowRedirects, HttpVersion},
+    redirect,
+    reqwest::Remote,
+    traits::PostBodyDataKind,
+};
 
 /// The error returned by the 'remote' helper, a purely internal construct to perform http requests.
 #[derive(Debug, thiserror::Error)]
@@ -22,6 +28,8 @@ pub enum Error {
     ConfigureRequest(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
     #[error(transparent)]
     Redirect(#[from] redirect::Error),
+    #[error(transparent)]
+    Authenticate(#[from] gix_credentials::protocol::Error),
 }
 
 impl crate::IsSpuriousError for Error {
@@ -46,37 +54,8 @@ impl Default for Remote {
 
             // We may error while configuring, which is expected as part of the internal protocol. The error will be
             // received and the sender of the request might restart us.
-            let client = reqwest::blocking::ClientBuilder::new()
-                .connect_timeout(std::time::Duration::from_secs(20))
-                .http1_title_case_headers()
-                .redirect(reqwest::redirect::Policy::custom({
-                    let allow_redirects = allow_redirects.clone();
-                    move |attempt| {
-                        if allow_redirects.load(atomic::Ordering::Relaxed) {
-                            let curr_url = attempt.url();
-                            let prev_urls = attempt.previous();
-
-                            match prev_urls.first() {
-                                Some(prev_url) if prev_url.host_str() != curr_url.host_str() => {
-                                    // git does not want to be redirected to a different host.
-                                    attempt.stop()
-                                }
-                                _ => {
-                                    // emulate default git behaviour which relies on curl default behaviour apparently.
-                                    const CURL_DEFAULT_REDIRS: usize = 50;
-                                    if prev_urls.len() >= CURL_DEFAULT_REDIRS {
-                                        attempt.error("too many redirects")
-                                    } else {
-                                        attempt.follow()
-                                    }
-                                }
-                            }
-                        } else {
-                            attempt.stop()
-                        }
-                    }
-                }))
-                .build()?;
+            let mut client_options = ClientOptions::default();
+            let mut client = new_client(&allow_redirects, &client_options)?;
 
             for Request {
                 url,
@@ -86,6 +65,22 @@ impl Default for Remote {
                 config,
             } in req_recv
             {
+                // The client holds the connection pool, so it's only recreated if its configuration changes to allow
+                // connections to be reused across requests.
+                let mut options = ClientOptions::from(&config);
+                let mut proxy_auth_action = None;
+                if let (Some(_), Some((obtain_creds_action, authenticate))) =
+                    (&options.proxy, &config.proxy_authenticate)
+                {
+                    let creds = authenticate.lock().expect("no panics in other threads")(obtain_creds_action.clone())?
+                        .expect("action to fetch credentials");
+                    options.proxy_identity = Some(creds.identity);
+                    proxy_auth_action = Some((creds.next, authenticate.clone()));
+                }
+                if options != client_options {
+                    client = new_client(&allow_redirects, &options)?;
+                    client_options = options;
+                }
                 let effective_url = redirect::swap_tails(redirected_base_url.as_deref(), &base_url, url.clone());
                 let mut req_builder = if upload_body_kind.is_some() {
                     client.post(&effective_url)
@@ -136,10 +131,17 @@ impl Default for Remote {
                     *follow = FollowRedirects::None;
                 }
 
-                let mut res = match client
+                let res = client
                     .execute(req)
-                    .and_then(reqwest::blocking::Response::error_for_status)
-                {
+                    .and_then(reqwest::blocking::Response::error_for_status);
+                if let Some((action, authenticate)) = proxy_auth_action {
+                    authenticate.lock().expect("no panics in other threads")(if res.is_ok() {
+                        action.store()
+                    } else {
+                        action.erase()
+                    })?;
+                }
+                let mut res = match res {
                     Ok(res) => res,
                     Err(err) => {
                         let (kind, err) = match err.status() {
@@ -308,6 +310,101 @@ impl http::Http for Remote {
     }
 }
 
+/// The parts of the http options that are needed to create a client.
+#[derive(Default, PartialEq, Eq)]
+struct ClientOptions {
+    proxy: Option<String>,
+    no_proxy: Option<String>,
+    /// The credentials to authenticate against the `proxy` with, as obtained from the credential helpers.
+    proxy_identity: Option<gix_sec::identity::Account>,
+    http_version: Option<HttpVersion>,
+    http2_prior_knowledge: bool,
+    pool_idle_timeout: Option<std::time::Duration>,
+    tcp_keepalive: Option<std::time::Duration>,
+}
+
+impl From<&http::Options> for ClientOptions {
+    fn from(opts: &http::Options) -> Self {
+        ClientOptions {
+            proxy: opts.proxy.clone(),
+            no_proxy: opts.proxy.as_ref().and(opts.no_proxy.clone()),
+            proxy_identity: None,
+            http_version: opts.http_version,
+            http2_prior_knowledge: opts.http2_prior_knowledge,
+            pool_idle_timeout: opts.pool_idle_timeout,
+            tcp_keepalive: opts.tcp_keepalive,
+        }
+    }
+}
+
+/// Create a new client that follows redirects only if `allow_redirects` is set, and which is configured with `opts`.
+///
+/// If `opts.proxy` is `None`, proxies are configured with environment variables, and an empty `proxy` disables them.
+fn new_client(
+    allow_redirects: &Arc<atomic::AtomicBool>,
+    opts: &ClientOptions,
+) -> reqwest::Result<reqwest::blocking::Client> {
+    let mut builder = reqwest::blocking::ClientBuilder::new()
+        .connect_timeout(std::time::Duration::from_secs(20))
+        .http1_title_case_headers()
+        .redirect(reqwest::redirect::Policy::custom({
+            let allow_redirects = allow_redirects.clone();
+            move |attempt| {
+                if allow_redirects.load(atomic::Ordering::Relaxed) {
+                    let curr_url = attempt.url();
+                    let prev_urls = attempt.previous();
+
+                    match prev_urls.first() {
+                        Some(prev_url) if prev_url.host_str() != curr_url.host_str() => {
+                            // git does not want to be redirected to a different host.
+                            attempt.stop()
+                        }
+                        _ => {
+                            // emulate default git behaviour which relies on curl default behaviour apparently.
+                            const CURL_DEFAULT_REDIRS: usize = 50;
+                            if prev_urls.len() >= CURL_DEFAULT_REDIRS {
+                                attempt.error("too many redirects")
+                            } else {
+                                attempt.follow()
+                            }
+                        }
+                    }
+                } else {
+                    attempt.stop()
+                }
+            }
+        }));
+    match opts.proxy.as_deref() {
+        Some(proxy) if proxy.trim().is_empty() => builder = builder.no_proxy(),
+        Some(proxy) => {
+            let mut proxy =
+                reqwest::Proxy::all(proxy)?.no_proxy(opts.no_proxy.as_deref().and_then(reqwest::NoProxy::from_string));
+            if let Some(identity) = &opts.proxy_identity {
+                proxy = proxy.basic_auth(&identity.username, &identity.password);
+            }
+            builder = builder.proxy(proxy)
+        }
+        None => {}
+    }
+    match opts.http_version {
+        Some(HttpVersion::V1_1) => builder = builder.http1_only(),
+        Some(HttpVersion::V2) if opts.http2_prior_knowledge => builder = builder.http2_prior_knowledge(),
+        // HTTP/2 is negotiated via ALPN if the server supports it.
+        Some(HttpVersion::V2) | None => {}
+    }
+    if let Some(timeout) = opts.pool_idle_timeout {
+        builder = if timeout.is_zero() {
+            builder.pool_max_idle_per_host(0)
+        } else {
+            builder.pool_idle_timeout(timeout)
+        };
+    }
+    if let Some(idle) = opts.tcp_keepalive {
+        builder = builder.tcp_keepalive(idle);
+    }
+    builder.build()
+}
+
 pub(crate) struct Request {
     pub url: String,
     pub base_url: String,
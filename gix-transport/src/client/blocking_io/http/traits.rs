@@ -130,4 +130,14 @@ pub trait Http {
         &mut self,
         config: &dyn std::any::Any,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>;
+
+    /// Authenticate subsequent requests with `identity`, or without one, using the
+    /// [authentication method](super::Options::auth_method) the implementation was configured with.
+    ///
+    /// Return `true` if the implementation authenticates by itself, or `false` to have the caller send `identity` using
+    /// HTTP basic authentication, which is the default.
+    fn authenticate_with(&mut self, identity: Option<&gix_sec::identity::Account>) -> bool {
+        let _ = identity;
+        false
+    }
 }
@@ -33,6 +33,10 @@ pub enum ProgramKind {
 
 mod program_kind;
 
+///
+#[cfg(feature = "ssh-client-ssh2")]
+pub mod native;
+
 ///
 pub mod invocation {
     use std::ffi::OsString;
@@ -73,6 +77,15 @@ pub mod connect {
         /// when invoking the program.
         /// If unset, the `program` basename determines the variant, or an invocation of the `command` itself.
         pub kind: Option<ProgramKind>,
+        /// If `true`, the ssh program is instructed to never prompt for passwords, passphrases or to confirm host keys,
+        /// and to fail instead. This is useful for non-interactive services which can't answer prompts.
+        ///
+        /// This passes `-o BatchMode=yes` to `ssh` and `-batch` to `plink` and `tortoiseplink`, whereas other variants
+        /// don't support it.
+        pub batch_mode: bool,
+        /// The proxy to connect through, which is passed as `ProxyCommand` to `ssh` and as `-proxycmd` to `plink`,
+        /// both of which run `nc` to perform the connection. Other variants don't support it.
+        pub proxy: Option<crate::client::Proxy>,
     }
 
     impl Options {
@@ -83,6 +96,12 @@ pub mod connect {
                 .or_else(|| self.kind.and_then(|kind| kind.exe()))
                 .unwrap_or_else(|| OsStr::new("ssh"))
         }
+
+        /// Return `true` if the embedded ssh client should be used instead of an ssh program, which is the case if it's
+        /// compiled in with the `ssh-client-ssh2` feature and neither `command`, `kind` nor `proxy` are set.
+        pub fn use_native_client(&self) -> bool {
+            cfg!(feature = "ssh-client-ssh2") && self.command.is_none() && self.kind.is_none() && self.proxy.is_none()
+        }
     }
 }
 
@@ -105,7 +124,9 @@ pub fn connect(
         return Err(Error::UnsupportedScheme(url));
     }
     let ssh_cmd = options.ssh_command();
-    let mut kind = options.kind.unwrap_or_else(|| ProgramKind::from(ssh_cmd));
+    let mut kind = options
+        .kind
+        .unwrap_or_else(|| ProgramKind::from_command(ssh_cmd, !options.disallow_shell));
     if options.kind.is_none() && kind == ProgramKind::Simple {
         let mut cmd = std::process::Command::from(
             gix_command::prepare(ssh_cmd)
@@ -133,6 +154,8 @@ pub fn connect(
         path,
         kind,
         options.disallow_shell,
+        options.batch_mode,
+        options.proxy.clone(),
         desired_version,
         trace,
     ))
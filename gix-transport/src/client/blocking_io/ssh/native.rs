@@ -0,0 +1,288 @@
+use std::{any::Any, borrow::Cow, io::Read, net::TcpStream, path::PathBuf};
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::{
+    client::{self, git, MessageKind, RequestWriter, SetServiceResponse, WriteMode},
+    Protocol, Service,
+};
+
+/// The error used when connecting with the embedded ssh client.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not connect to {host}:{port}")]
+    Connect {
+        source: std::io::Error,
+        host: String,
+        port: u16,
+    },
+    #[error(transparent)]
+    Ssh(#[from] ssh2::Error),
+    #[error("Host key verification failed: the host key of '{host}' with fingerprint {fingerprint} isn't in any known hosts file")]
+    UnknownHostKey { host: String, fingerprint: String },
+    #[error("Host key verification failed: the host key of '{host}' doesn't match the one in the known hosts file")]
+    HostKeyMismatch { host: String },
+    #[error("Host key verification failed: the host key of '{host}' could not be checked")]
+    HostKeyCheck { host: String },
+    #[error(
+        "Could not authenticate as '{user}' at '{host}' with the ssh agent, the default identity files or a password"
+    )]
+    Authentication { user: String, host: String },
+    #[error("Could not determine the user to log in as")]
+    MissingUser,
+}
+
+impl crate::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Connect { source, .. } => source.is_spurious(),
+            _ => false,
+        }
+    }
+}
+
+/// A transport to `ssh` servers which uses an embedded client instead of spawning the `ssh` program.
+///
+/// The connection is established on [handshake](client::Transport::handshake()), verifying the host key against the
+/// `known_hosts` files of the user and the system, and authenticating with the ssh agent, the default identity files
+/// in `~/.ssh` or the password of the [identity](client::TransportWithoutIO::set_identity()), in that order.
+///
+/// It can only be instantiated using [`connect()`].
+pub struct Connection {
+    url: gix_url::Url,
+    path: BString,
+    password: Option<String>,
+    desired_version: Protocol,
+    connection: Option<git::Connection<Box<dyn Read + Send>, ssh2::Stream>>,
+    trace: bool,
+}
+
+impl Connection {
+    fn open_session(&self) -> Result<ssh2::Session, Error> {
+        let host = self.url.host().expect("set in ssh urls");
+        let port = self.url.port.unwrap_or(22);
+        let stream = TcpStream::connect((host, port)).map_err(|source| Error::Connect {
+            source,
+            host: host.into(),
+            port,
+        })?;
+        let mut session = ssh2::Session::new()?;
+        session.set_tcp_stream(stream);
+        session.handshake()?;
+
+        let (key, _kind) = session
+            .host_key()
+            .ok_or_else(|| Error::HostKeyCheck { host: host.into() })?;
+        let mut known_hosts = session.known_hosts()?;
+        for file in known_hosts_files().into_iter().filter(|file| file.is_file()) {
+            // Entries libssh2 doesn't understand shouldn't prevent using the ones it does understand.
+            known_hosts.read_file(&file, ssh2::KnownHostFileKind::OpenSSH).ok();
+        }
+        verify_host_key(&known_hosts, host, port, key, || {
+            use base64::Engine;
+            session.host_key_hash(ssh2::HashType::Sha256).map_or_else(
+                || "<unknown>".into(),
+                |hash| {
+                    format!(
+                        "SHA256:{}",
+                        base64::engine::general_purpose::STANDARD_NO_PAD.encode(hash)
+                    )
+                },
+            )
+        })?;
+
+        let user = match self.url.user() {
+            Some(user) => user.to_owned(),
+            None => ["USER", "USERNAME"]
+                .iter()
+                .find_map(|name| std::env::var(name).ok())
+                .ok_or(Error::MissingUser)?,
+        };
+        authenticate(&session, &user, self.password.as_deref())
+            .then_some(session)
+            .ok_or_else(|| Error::Authentication {
+                user,
+                host: host.into(),
+            })
+    }
+}
+
+/// Return `Ok(())` if `key` of `host` at `port` is present in `known_hosts`, or an error that includes the
+/// `fingerprint()` of `key` if it's unknown.
+pub(crate) fn verify_host_key(
+    known_hosts: &ssh2::KnownHosts,
+    host: &str,
+    port: u16,
+    key: &[u8],
+    fingerprint: impl FnOnce() -> String,
+) -> Result<(), Error> {
+    match known_hosts.check_port(host, port, key) {
+        ssh2::CheckResult::Match => Ok(()),
+        ssh2::CheckResult::Mismatch => Err(Error::HostKeyMismatch { host: host.into() }),
+        ssh2::CheckResult::NotFound => Err(Error::UnknownHostKey {
+            host: host.into(),
+            fingerprint: fingerprint(),
+        }),
+        ssh2::CheckResult::Failure => Err(Error::HostKeyCheck { host: host.into() }),
+    }
+}
+
+/// Try all authentication methods we support and return `true` if one of them succeeded.
+fn authenticate(session: &ssh2::Session, user: &str, password: Option<&str>) -> bool {
+    if session.userauth_agent(user).is_ok() && session.authenticated() {
+        return true;
+    }
+    let ssh_dir = home_dir().map(|home| home.join(".ssh"));
+    for name in ["id_ed25519", "id_ecdsa", "id_rsa"] {
+        let Some(private_key) = ssh_dir.as_ref().map(|dir| dir.join(name)).filter(|path| path.is_file()) else {
+            continue;
+        };
+        if session.userauth_pubkey_file(user, None, &private_key, None).is_ok() && session.authenticated() {
+            return true;
+        }
+    }
+    password.map_or(false, |password| {
+        session.userauth_password(user, password).is_ok() && session.authenticated()
+    })
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+fn known_hosts_files() -> Vec<PathBuf> {
+    home_dir()
+        .map(|home| home.join(".ssh").join("known_hosts"))
+        .into_iter()
+        .chain(Some(PathBuf::from("/etc/ssh/ssh_known_hosts")))
+        .collect()
+}
+
+/// Read from `stdout` of the remote process, and turn what it wrote to `stderr` into an error if it ended prematurely.
+struct ReadStdoutFailOnError {
+    stdout: ssh2::Stream,
+    stderr: ssh2::Stream,
+}
+
+impl Read for ReadStdoutFailOnError {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.stdout.read(buf)?;
+        if n == 0 && !buf.is_empty() {
+            let mut message = Vec::new();
+            self.stderr.read_to_end(&mut message).ok();
+            let message = message.trim();
+            if !message.is_empty() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    message.to_str_lossy().into_owned(),
+                ));
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl client::TransportWithoutIO for Connection {
+    fn set_identity(&mut self, identity: gix_sec::identity::Account) -> Result<(), client::Error> {
+        self.url
+            .set_user((!identity.username.is_empty()).then_some(identity.username));
+        self.password = (!identity.password.is_empty()).then_some(identity.password);
+        Ok(())
+    }
+
+    fn request(
+        &mut self,
+        write_mode: WriteMode,
+        on_into_read: MessageKind,
+        trace: bool,
+    ) -> Result<RequestWriter<'_>, client::Error> {
+        self.connection
+            .as_mut()
+            .expect("handshake() to have been called first")
+            .request(write_mode, on_into_read, trace)
+    }
+
+    fn to_url(&self) -> Cow<'_, BStr> {
+        Cow::Owned(self.url.to_bstring())
+    }
+
+    fn connection_persists_across_multiple_requests(&self) -> bool {
+        true
+    }
+
+    fn configure(&mut self, _config: &dyn Any) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(())
+    }
+}
+
+impl client::Transport for Connection {
+    fn handshake<'a>(
+        &mut self,
+        service: Service,
+        extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<SetServiceResponse<'_>, client::Error> {
+        if self.path.trim().first() == Some(&b'-') {
+            return Err(client::Error::AmbiguousPath {
+                path: self.path.clone(),
+            });
+        }
+        let session = self.open_session().map_err(client::Error::NativeSsh)?;
+        let mut channel = session
+            .channel_session()
+            .map_err(|err| client::Error::NativeSsh(err.into()))?;
+        if self.desired_version != Protocol::V1 {
+            // Servers only accept environment variables they are configured to accept, and V1 is the fallback.
+            channel
+                .setenv("GIT_PROTOCOL", &format!("version={}", self.desired_version as usize))
+                .ok();
+        }
+        let command = format!(
+            "{} {}",
+            service.as_str(),
+            gix_quote::single(self.path.as_ref()).to_str_lossy()
+        );
+        gix_features::trace::debug!(command = ?command, "gix_transport::ssh::native");
+        channel
+            .exec(&command)
+            .map_err(|err| client::Error::NativeSsh(err.into()))?;
+
+        self.connection = Some(git::Connection::new_for_spawned_process(
+            Box::new(ReadStdoutFailOnError {
+                stdout: channel.stream(0),
+                stderr: channel.stderr(),
+            }),
+            channel.stream(0),
+            self.desired_version,
+            self.path.clone(),
+            self.trace,
+        ));
+        self.connection
+            .as_mut()
+            .expect("connection to be there right after setting it")
+            .handshake(service, extra_parameters)
+    }
+}
+
+/// Prepare a connection to `url` using the embedded ssh client to obtain data from the repository at the path of `url`.
+///
+/// The `desired_version` is the preferred protocol version when establishing the connection, but note that it can be
+/// downgraded by servers not supporting it.
+/// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+#[allow(clippy::result_large_err)]
+pub fn connect(url: gix_url::Url, desired_version: Protocol, trace: bool) -> Result<Connection, super::Error> {
+    if url.scheme != gix_url::Scheme::Ssh || url.host().is_none() {
+        return Err(super::Error::UnsupportedScheme(url));
+    }
+    let path = gix_url::expand_path::for_shell(url.path.clone());
+    Ok(Connection {
+        url,
+        path,
+        password: None,
+        desired_version,
+        connection: None,
+        trace,
+    })
+}
@@ -3,7 +3,8 @@ use std::{ffi::OsStr, io::ErrorKind};
 use bstr::{BString, ByteSlice, ByteVec};
 
 use crate::{
-    client::{ssh, ssh::ProgramKind},
+    client,
+    client::{proxy, ssh, ssh::ProgramKind},
     Protocol,
 };
 
@@ -19,6 +20,24 @@ impl ProgramKind {
         }))
     }
 
+    /// Determine the kind of ssh program invoked by `command`, which is split into arguments like a shell would
+    /// if `is_command_line` is `true`, so only the basename of the program it invokes is used.
+    /// Otherwise, `command` is taken as path to the program as a whole.
+    ///
+    /// This is similar to what `git` does for `core.sshCommand` and `GIT_SSH_COMMAND`, which are command lines,
+    /// and `GIT_SSH`, which is a path to the program.
+    pub fn from_command(command: &OsStr, is_command_line: bool) -> Self {
+        if !is_command_line {
+            return ProgramKind::from(command);
+        }
+        match command.to_str().and_then(|command| shell_words::split(command).ok()) {
+            Some(args) => args
+                .first()
+                .map_or(ProgramKind::Simple, |program| ProgramKind::from(OsStr::new(program))),
+            None => ProgramKind::from(command),
+        }
+    }
+
     /// Prepare all information needed to invoke the ssh command
     pub(crate) fn prepare_invocation(
         &self,
@@ -26,6 +45,8 @@ impl ProgramKind {
         url: &gix_url::Url,
         desired_version: Protocol,
         disallow_shell: bool,
+        batch_mode: bool,
+        proxy: Option<&client::Proxy>,
     ) -> Result<gix_command::Prepare, ssh::invocation::Error> {
         let mut prepare = gix_command::prepare(ssh_cmd).with_shell();
         if disallow_shell {
@@ -33,6 +54,9 @@ impl ProgramKind {
         }
         match self {
             ProgramKind::Ssh => {
+                if batch_mode {
+                    prepare = prepare.args(["-o", "BatchMode=yes"]);
+                }
                 if desired_version != Protocol::V1 {
                     prepare = prepare
                         .args(["-o", "SendEnv=GIT_PROTOCOL"])
@@ -41,17 +65,39 @@ impl ProgramKind {
                 if let Some(port) = url.port {
                     prepare = prepare.arg(format!("-p{port}"));
                 }
+                if let Some(proxy) = proxy {
+                    prepare = prepare.args([
+                        "-o".into(),
+                        format!("ProxyCommand={}", proxy_command(proxy, "%h", "%p")),
+                    ]);
+                }
             }
             ProgramKind::Plink | ProgramKind::Putty | ProgramKind::TortoisePlink => {
-                if *self == ProgramKind::TortoisePlink {
+                if *self == ProgramKind::TortoisePlink || (batch_mode && *self == ProgramKind::Plink) {
                     prepare = prepare.arg("-batch");
                 }
                 if let Some(port) = url.port {
                     prepare = prepare.arg("-P");
                     prepare = prepare.arg(port.to_string());
                 }
+                if let Some(proxy) = proxy {
+                    if *self != ProgramKind::Plink {
+                        return Err(ssh::invocation::Error::Unsupported {
+                            command: ssh_cmd.into(),
+                            function: "connecting through a proxy",
+                        });
+                    }
+                    prepare = prepare.arg("-proxycmd");
+                    prepare = prepare.arg(proxy_command(proxy, "%host", "%port"));
+                }
             }
             ProgramKind::Simple => {
+                if proxy.is_some() {
+                    return Err(ssh::invocation::Error::Unsupported {
+                        command: ssh_cmd.into(),
+                        function: "connecting through a proxy",
+                    });
+                }
                 if url.port.is_some() {
                     return Err(ssh::invocation::Error::Unsupported {
                         command: ssh_cmd.into(),
@@ -113,6 +159,31 @@ impl ProgramKind {
     }
 }
 
+/// Produce a command for use by `ssh` programs to connect to the host at the `host` and `port` placeholders through `proxy`,
+/// using the OpenBSD variant of `nc`, which is also the one shipped on MacOS.
+///
+/// Note that `nc` doesn't support passwords, and will prompt for them if a user is set for HTTP proxies.
+fn proxy_command(proxy: &client::Proxy, host: &str, port: &str) -> String {
+    let mut cmd = String::from("nc -X ");
+    match proxy.kind {
+        proxy::Kind::Http => {
+            cmd.push_str("connect");
+            if let Some(user) = &proxy.user {
+                cmd.push_str(" -P ");
+                cmd.push_str(&shell_words::quote(user));
+            }
+        }
+        proxy::Kind::Socks5 | proxy::Kind::Socks5Hostname => cmd.push('5'),
+    }
+    cmd.push_str(" -x ");
+    cmd.push_str(&shell_words::quote(&proxy.address()));
+    cmd.push(' ');
+    cmd.push_str(host);
+    cmd.push(' ');
+    cmd.push_str(port);
+    cmd
+}
+
 impl<'a> From<&'a OsStr> for ProgramKind {
     fn from(v: &'a OsStr) -> Self {
         let p = std::path::Path::new(v);
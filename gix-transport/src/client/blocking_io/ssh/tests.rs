@@ -40,6 +40,38 @@ mod options {
             );
         }
     }
+
+    mod use_native_client {
+        use crate::client::ssh::{connect::Options, ProgramKind};
+
+        #[test]
+        fn only_without_command_kind_or_proxy() {
+            assert_eq!(
+                Options::default().use_native_client(),
+                cfg!(feature = "ssh-client-ssh2"),
+                "the embedded client is used by default if compiled in"
+            );
+            for options in [
+                Options {
+                    command: Some("ssh".into()),
+                    ..Default::default()
+                },
+                Options {
+                    kind: Some(ProgramKind::Ssh),
+                    ..Default::default()
+                },
+                Options {
+                    proxy: crate::client::Proxy::from_declaration("socks5://proxy:1080").expect("valid"),
+                    ..Default::default()
+                },
+            ] {
+                assert!(
+                    !options.use_native_client(),
+                    "configured programs and proxies require the ssh program"
+                );
+            }
+        }
+    }
 }
 
 mod program_kind {
@@ -96,6 +128,50 @@ mod program_kind {
         }
     }
 
+    mod from_command {
+        use std::ffi::OsStr;
+
+        use crate::client::ssh::ProgramKind;
+
+        #[test]
+        fn command_lines_are_identified_by_their_program() {
+            for (command, expected) in [
+                ("ssh -VVV", ProgramKind::Ssh),
+                (
+                    "/usr/bin/ssh -i ~/.ssh/id_ed25519 -o IdentitiesOnly=yes",
+                    ProgramKind::Ssh,
+                ),
+                ("'/path with spaces/plink.exe' -ssh", ProgramKind::Plink),
+                (
+                    "\"C:/Program Files/TortoiseGit/bin/TortoisePlink.exe\" -batch",
+                    ProgramKind::TortoisePlink,
+                ),
+                ("putty", ProgramKind::Putty),
+                ("my-ssh-wrapper --verbose", ProgramKind::Simple),
+                ("", ProgramKind::Simple),
+            ] {
+                assert_eq!(
+                    ProgramKind::from_command(OsStr::new(command), true),
+                    expected,
+                    "{command:?}"
+                );
+            }
+        }
+
+        #[test]
+        fn paths_are_used_as_a_whole() {
+            assert_eq!(
+                ProgramKind::from_command(OsStr::new("/path with spaces/plink"), false),
+                ProgramKind::Plink
+            );
+            assert_eq!(
+                ProgramKind::from_command(OsStr::new("ssh -VVV"), false),
+                ProgramKind::Simple,
+                "like `GIT_SSH`, this is a path to a program and not a command line"
+            );
+        }
+    }
+
     mod prepare_invocation {
         use std::ffi::OsStr;
 
@@ -192,13 +268,25 @@ mod program_kind {
             let url = gix_url::parse("ssh://host/path".into()).expect("valid url");
 
             let disallow_shell = false;
-            let prepare =
-                ProgramKind::Ssh.prepare_invocation(OsStr::new("echo hi"), &url, Protocol::V1, disallow_shell)?;
+            let prepare = ProgramKind::Ssh.prepare_invocation(
+                OsStr::new("echo hi"),
+                &url,
+                Protocol::V1,
+                disallow_shell,
+                false,
+                None,
+            )?;
             assert!(prepare.use_shell, "shells are used when needed");
 
             let disallow_shell = true;
-            let prepare =
-                ProgramKind::Ssh.prepare_invocation(OsStr::new("echo hi"), &url, Protocol::V1, disallow_shell)?;
+            let prepare = ProgramKind::Ssh.prepare_invocation(
+                OsStr::new("echo hi"),
+                &url,
+                Protocol::V1,
+                disallow_shell,
+                false,
+                None,
+            )?;
             assert!(
                 !prepare.use_shell,
                 "but we can enforce it not to be used as well for historical reasons"
@@ -206,6 +294,89 @@ mod program_kind {
             Ok(())
         }
 
+        #[test]
+        fn batch_mode_is_passed_to_variants_supporting_it() -> Result {
+            let url = gix_url::parse("ssh://user@host/p".into()).expect("valid url");
+            for (kind, expected) in [
+                (
+                    ProgramKind::Ssh,
+                    &["ssh", "-o", "BatchMode=yes", "-o", "SendEnv=GIT_PROTOCOL", "user@host"][..],
+                ),
+                (ProgramKind::Plink, &["plink", "-batch", "user@host"]),
+                (
+                    ProgramKind::TortoisePlink,
+                    &["tortoiseplink.exe", "-batch", "user@host"],
+                ),
+                (ProgramKind::Putty, &["putty", "user@host"]),
+            ] {
+                let prepare =
+                    kind.prepare_invocation(kind.exe().expect("set"), &url, Protocol::V2, false, true, None)?;
+                assert_eq!(args_of(prepare), joined(expected), "{kind:?}");
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn proxy_is_passed_to_variants_supporting_it() -> Result {
+            let url = gix_url::parse("ssh://user@host/p".into()).expect("valid url");
+            for (proxy, expected) in [
+                (
+                    "socks5://proxy:1081",
+                    &["ssh", "-o", "ProxyCommand=nc -X 5 -x proxy:1081 %h %p", "user@host"][..],
+                ),
+                (
+                    "name@proxy",
+                    &[
+                        "ssh",
+                        "-o",
+                        "ProxyCommand=nc -X connect -P name -x proxy:1080 %h %p",
+                        "user@host",
+                    ][..],
+                ),
+            ] {
+                let proxy = crate::client::Proxy::from_declaration(proxy)
+                    .expect("valid")
+                    .expect("set");
+                let prepare = ProgramKind::Ssh.prepare_invocation(
+                    OsStr::new("ssh"),
+                    &url,
+                    Protocol::V1,
+                    false,
+                    false,
+                    Some(&proxy),
+                )?;
+                assert_eq!(prepare.args, expected[1..], "{proxy:?}");
+            }
+
+            let proxy = crate::client::Proxy::from_declaration("socks5h://proxy")
+                .expect("valid")
+                .expect("set");
+            let prepare = ProgramKind::Plink.prepare_invocation(
+                OsStr::new("plink"),
+                &url,
+                Protocol::V1,
+                false,
+                false,
+                Some(&proxy),
+            )?;
+            assert_eq!(
+                args_of(prepare),
+                joined(&["plink", "-proxycmd", "nc -X 5 -x proxy:1080 %host %port", "user@host"])
+            );
+
+            for kind in [ProgramKind::Putty, ProgramKind::TortoisePlink, ProgramKind::Simple] {
+                let ssh_cmd = kind.exe().unwrap_or_else(|| OsStr::new("simple"));
+                assert!(
+                    matches!(
+                        kind.prepare_invocation(ssh_cmd, &url, Protocol::V1, false, false, Some(&proxy)),
+                        Err(ssh::invocation::Error::Unsupported { .. })
+                    ),
+                    "{kind:?}"
+                );
+            }
+            Ok(())
+        }
+
         fn joined(input: &[&str]) -> String {
             input.to_vec().join(" ")
         }
@@ -216,13 +387,16 @@ mod program_kind {
         ) -> std::result::Result<gix_command::Prepare, ssh::invocation::Error> {
             let ssh_cmd = kind.exe().unwrap_or_else(|| OsStr::new("simple"));
             let url = gix_url::parse(url.into()).expect("valid url");
-            kind.prepare_invocation(ssh_cmd, &url, version, false)
+            kind.prepare_invocation(ssh_cmd, &url, version, false, false, None)
         }
         fn call(kind: ProgramKind, url: &str, version: Protocol) -> gix_command::Prepare {
             try_call(kind, url, version).expect("no error")
         }
         fn call_args(kind: ProgramKind, url: &str, version: Protocol) -> String {
-            let cmd = std::process::Command::from(call(kind, url, version));
+            args_of(call(kind, url, version))
+        }
+        fn args_of(prepare: gix_command::Prepare) -> String {
+            let cmd = std::process::Command::from(prepare);
             format!(
                 "{} {}",
                 cmd.get_program().to_string_lossy(),
@@ -306,3 +480,64 @@ mod program_kind {
         }
     }
 }
+
+#[cfg(feature = "ssh-client-ssh2")]
+mod native {
+    mod verify_host_key {
+        use base64::Engine;
+
+        use crate::client::ssh::native::{verify_host_key, Error};
+
+        const KEY: &str = "AAAAC3NzaC1lZDI1NTE5AAAAIIP1U/lq3sFcB71BzW2/s9uDHRvPIre/Jzz1RVvCpaNK";
+        const OTHER_KEY: &str = "AAAAC3NzaC1lZDI1NTE5AAAAIFQU/1Snnb/ygPSJmdCISeyPHzqI5mY6NBJmGwP6Dr+n";
+
+        fn raw(key: &str) -> Vec<u8> {
+            base64::engine::general_purpose::STANDARD.decode(key).expect("valid")
+        }
+
+        fn known_hosts(content: &str) -> ssh2::KnownHosts {
+            let session = ssh2::Session::new().expect("no IO needed");
+            let mut known_hosts = session.known_hosts().expect("no IO needed");
+            for line in content.lines() {
+                known_hosts
+                    .read_str(line, ssh2::KnownHostFileKind::OpenSSH)
+                    .expect("valid line");
+            }
+            known_hosts
+        }
+
+        #[test]
+        fn matching_key() {
+            let known_hosts = known_hosts(&format!(
+                "example.com ssh-ed25519 {KEY}\n[example.com]:2222 ssh-ed25519 {OTHER_KEY}"
+            ));
+            assert!(verify_host_key(&known_hosts, "example.com", 22, &raw(KEY), || unreachable!()).is_ok());
+            assert!(
+                verify_host_key(&known_hosts, "example.com", 2222, &raw(OTHER_KEY), || unreachable!()).is_ok(),
+                "non-standard ports have their own entries"
+            );
+        }
+
+        #[test]
+        fn mismatching_key() {
+            let known_hosts = known_hosts(&format!("example.com ssh-ed25519 {KEY}"));
+            assert!(matches!(
+                verify_host_key(&known_hosts, "example.com", 22, &raw(OTHER_KEY), || unreachable!()),
+                Err(Error::HostKeyMismatch { host }) if host == "example.com"
+            ));
+        }
+
+        #[test]
+        fn unknown_host() {
+            let known_hosts = known_hosts(&format!("example.com ssh-ed25519 {KEY}"));
+            let err = verify_host_key(&known_hosts, "other.example.com", 22, &raw(KEY), || {
+                "SHA256:fingerprint".into()
+            })
+            .expect_err("unknown");
+            assert_eq!(
+                err.to_string(),
+                "Host key verification failed: the host key of 'other.example.com' with fingerprint SHA256:fingerprint isn't in any known hosts file"
+            );
+        }
+    }
+}
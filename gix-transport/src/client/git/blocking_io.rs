@@ -137,7 +137,7 @@ pub mod connect {
 
     use bstr::BString;
 
-    use crate::client::git;
+    use crate::client::{self, git};
     /// The error used in [`connect()`].
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
@@ -172,21 +172,27 @@ pub mod connect {
     /// Connect to a git daemon running on `host` and optionally `port` and a repository at `path`.
     ///
     /// Use `desired_version` to specify a preferred protocol to use, knowing that it can be downgraded by a server not supporting it.
+    /// If `proxy` is set, the connection is tunneled through it.
     /// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
     pub fn connect(
         host: &str,
         path: BString,
         desired_version: crate::Protocol,
         port: Option<u16>,
+        proxy: Option<&client::Proxy>,
         trace: bool,
     ) -> Result<git::Connection<TcpStream, TcpStream>, Error> {
-        let read = TcpStream::connect_timeout(
-            &(host, port.unwrap_or(9418))
-                .to_socket_addrs()?
-                .next()
-                .expect("after successful resolution there is an IP address"),
-            std::time::Duration::from_secs(5),
-        )?;
+        let timeout = std::time::Duration::from_secs(5);
+        let read = match proxy {
+            Some(proxy) => proxy.connect(host, port.unwrap_or(9418), timeout)?,
+            None => TcpStream::connect_timeout(
+                &(host, port.unwrap_or(9418))
+                    .to_socket_addrs()?
+                    .next()
+                    .expect("after successful resolution there is an IP address"),
+                timeout,
+            )?,
+        };
         let write = read.try_clone()?;
         let vhost = std::env::var("GIT_OVERRIDE_VIRTUAL_HOST")
             .ok()
@@ -34,3 +34,9 @@ pub use non_io_types::{Error, MessageKind, WriteMode};
 ///
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub mod git;
+
+///
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub mod proxy;
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub use proxy::Proxy;
@@ -42,6 +42,12 @@ pub(crate) mod connect {
         pub ssh: crate::client::ssh::connect::Options,
         /// If `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
         pub trace: bool,
+        #[cfg(feature = "blocking-client")]
+        /// The proxy to tunnel connections through, which is used by the `git` protocol and by `ssh`, where it takes precedence
+        /// over the proxy in the [`ssh` options](Self::ssh).
+        ///
+        /// Note that `http` and `https` transports are configured [with their own options](crate::client::TransportWithoutIO::configure()).
+        pub proxy: Option<crate::client::Proxy>,
     }
 
     /// The error used in [`connect()`][crate::connect()].
@@ -107,6 +113,10 @@ mod error {
     type HttpError = std::convert::Infallible;
     #[cfg(not(feature = "blocking-client"))]
     type SshInvocationError = std::convert::Infallible;
+    #[cfg(feature = "ssh-client-ssh2")]
+    type NativeSshError = ssh::native::Error;
+    #[cfg(not(feature = "ssh-client-ssh2"))]
+    type NativeSshError = std::convert::Infallible;
 
     /// The error used in most methods of the [`client`][crate::client] module
     #[derive(thiserror::Error, Debug)]
@@ -140,6 +150,8 @@ mod error {
         Http(#[from] HttpError),
         #[error(transparent)]
         SshInvocation(SshInvocationError),
+        #[error(transparent)]
+        NativeSsh(NativeSshError),
         #[error("The repository path '{path}' could be mistaken for a command-line argument")]
         AmbiguousPath { path: BString },
     }
@@ -149,6 +161,8 @@ mod error {
             match self {
                 Error::Io(err) => err.is_spurious(),
                 Error::Http(err) => err.is_spurious(),
+                #[cfg(feature = "ssh-client-ssh2")]
+                Error::NativeSsh(err) => err.is_spurious(),
                 _ => false,
             }
         }
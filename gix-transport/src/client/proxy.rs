@@ -0,0 +1,337 @@
+/// The kind of proxy, as determined by the scheme of its declaration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Kind {
+    /// A HTTP proxy that is asked to tunnel connections using the `CONNECT` method, selected with `http://`
+    /// or if there is no scheme.
+    Http,
+    /// A SOCKS5 proxy that is passed the address of the host after resolving it locally, selected with `socks5://`.
+    Socks5,
+    /// A SOCKS5 proxy that is passed the name of the host to resolve it itself, selected with `socks5h://`.
+    Socks5Hostname,
+}
+
+/// A proxy to tunnel connections through.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Proxy {
+    /// The protocol to use when talking to the proxy.
+    pub kind: Kind,
+    /// The host name or IP address of the proxy.
+    pub host: String,
+    /// The port of the proxy, which defaults to 1080 like in `curl`.
+    pub port: u16,
+    /// The user to authenticate as, if set.
+    pub user: Option<String>,
+    /// The password to authenticate with, which is only used if `user` is set.
+    pub password: Option<String>,
+}
+
+///
+pub mod parse {
+    /// The error returned by [Proxy::from_declaration()](super::Proxy::from_declaration()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The proxy scheme '{scheme}' in '{proxy}' is not supported, use one of 'http', 'socks5' or 'socks5h'")]
+        UnsupportedScheme { scheme: String, proxy: String },
+        #[error("The proxy '{proxy}' doesn't contain a host")]
+        MissingHost { proxy: String },
+        #[error("The port of proxy '{proxy}' is invalid")]
+        InvalidPort { proxy: String },
+    }
+}
+
+impl Proxy {
+    /// Parse a `curl`-style proxy declaration of the form `[protocol://][user[:password]@]proxyhost[:port]`, as used by
+    /// `http.proxy` or the `ALL_PROXY` environment variable.
+    ///
+    /// Return `None` if `proxy` is empty, which means that the proxy is disabled.
+    pub fn from_declaration(proxy: &str) -> Result<Option<Self>, parse::Error> {
+        let declaration = proxy.trim();
+        if declaration.is_empty() {
+            return Ok(None);
+        }
+        let (kind, rest) = match declaration.split_once("://") {
+            Some((scheme, rest)) => {
+                let kind = match scheme.to_ascii_lowercase().as_str() {
+                    "http" => Kind::Http,
+                    "socks5" => Kind::Socks5,
+                    "socks5h" => Kind::Socks5Hostname,
+                    _ => {
+                        return Err(parse::Error::UnsupportedScheme {
+                            scheme: scheme.into(),
+                            proxy: proxy.into(),
+                        })
+                    }
+                };
+                (kind, rest)
+            }
+            None => (Kind::Http, declaration),
+        };
+        let rest = rest.split_once('/').map_or(rest, |(authority, _path)| authority);
+        let (user, password, host_and_port) = match rest.rsplit_once('@') {
+            Some((user_info, host_and_port)) => {
+                let (user, password) = match user_info.split_once(':') {
+                    Some((user, password)) => (user, Some(password.to_owned())),
+                    None => (user_info, None),
+                };
+                (Some(user.to_owned()), password, host_and_port)
+            }
+            None => (None, None, rest),
+        };
+        let (host, port) = if let Some(bracketed) = host_and_port.strip_prefix('[') {
+            let (host, port) = bracketed
+                .split_once(']')
+                .ok_or_else(|| parse::Error::MissingHost { proxy: proxy.into() })?;
+            (host, port.strip_prefix(':'))
+        } else {
+            match host_and_port.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (host_and_port, None),
+            }
+        };
+        if host.is_empty() {
+            return Err(parse::Error::MissingHost { proxy: proxy.into() });
+        }
+        let port = match port {
+            Some(port) => port
+                .parse()
+                .map_err(|_| parse::Error::InvalidPort { proxy: proxy.into() })?,
+            None => 1080,
+        };
+        Ok(Some(Proxy {
+            kind,
+            host: host.into(),
+            port,
+            user,
+            password,
+        }))
+    }
+
+    /// Return the address of the proxy as `host:port`, with IPv6 addresses enclosed in brackets.
+    pub fn address(&self) -> String {
+        host_and_port(&self.host, self.port)
+    }
+}
+
+/// Return `true` if connections to `host` should not use a proxy according to the `curl`-style `no_proxy` list,
+/// as set by the `NO_PROXY` environment variable.
+///
+/// The list is separated by commas, and `*` matches all hosts. Otherwise, entries match a host if they are equal
+/// to it, or if they are a domain the host is part of. A leading `.` in entries is ignored.
+/// Note that IP addresses are only matched exactly, and that ranges in CIDR notation aren't supported.
+pub fn is_excluded(host: &str, no_proxy: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']').trim_end_matches('.');
+    no_proxy
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(|entry| entry.trim_start_matches('.').trim_end_matches('.'))
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| {
+            if entry == "*" {
+                return true;
+            }
+            let entry = entry.trim_start_matches('[').trim_end_matches(']');
+            host.eq_ignore_ascii_case(entry)
+                || (host.len() > entry.len()
+                    && host.as_bytes()[host.len() - entry.len() - 1] == b'.'
+                    && host[host.len() - entry.len()..].eq_ignore_ascii_case(entry))
+        })
+}
+
+fn host_and_port(host: &str, port: u16) -> String {
+    if host.contains(':') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    }
+}
+
+#[cfg(feature = "blocking-client")]
+mod blocking_io {
+    use std::{
+        io::{Error, ErrorKind, Read, Write},
+        net::{IpAddr, TcpStream, ToSocketAddrs},
+        time::Duration,
+    };
+
+    use base64::Engine;
+
+    use super::{host_and_port, Kind, Proxy};
+
+    impl Proxy {
+        /// Connect to `host` at `port` through this proxy and return the established connection, using `timeout` when
+        /// connecting to the proxy itself.
+        ///
+        /// HTTP proxies are asked to tunnel the connection with `CONNECT`, while SOCKS5 proxies are asked to connect, both
+        /// of which authenticate with `user` and `password` if set.
+        pub fn connect(&self, host: &str, port: u16, timeout: Duration) -> std::io::Result<TcpStream> {
+            let proxy_addr = (self.host.as_str(), self.port)
+                .to_socket_addrs()?
+                .next()
+                .ok_or_else(|| Error::new(ErrorKind::NotFound, format!("Could not resolve proxy '{}'", self.host)))?;
+            let mut stream = TcpStream::connect_timeout(&proxy_addr, timeout)?;
+            match self.kind {
+                Kind::Http => self.http_connect(&mut stream, host, port)?,
+                Kind::Socks5 | Kind::Socks5Hostname => self.socks5_connect(&mut stream, host, port)?,
+            }
+            Ok(stream)
+        }
+
+        fn http_connect(&self, stream: &mut TcpStream, host: &str, port: u16) -> std::io::Result<()> {
+            let target = host_and_port(host, port);
+            let mut request = format!("CONNECT {target} HTTP/1.1\r\nHost: {target}\r\n");
+            if let Some(user) = &self.user {
+                let credentials = format!("{user}:{}", self.password.as_deref().unwrap_or_default());
+                request.push_str(&format!(
+                    "Proxy-Authorization: Basic {}\r\n",
+                    base64::engine::general_purpose::STANDARD.encode(credentials)
+                ));
+            }
+            request.push_str("\r\n");
+            stream.write_all(request.as_bytes())?;
+
+            // Read byte-wise to not consume any data that follows the response header.
+            let mut response = Vec::new();
+            let mut byte = [0u8; 1];
+            while !response.ends_with(b"\r\n\r\n") {
+                if response.len() > 16 * 1024 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "The proxy response header is too large",
+                    ));
+                }
+                stream.read_exact(&mut byte)?;
+                response.push(byte[0]);
+            }
+            let status_line = response.split(|b| *b == b'\n').next().unwrap_or_default();
+            let status_line = String::from_utf8_lossy(status_line);
+            let status = status_line
+                .split_whitespace()
+                .nth(1)
+                .and_then(|code| code.parse::<u16>().ok());
+            match status {
+                Some(status) if (200..300).contains(&status) => Ok(()),
+                Some(407) => Err(Error::new(
+                    ErrorKind::PermissionDenied,
+                    format!("Proxy authentication failed: {}", status_line.trim()),
+                )),
+                _ => Err(Error::new(
+                    ErrorKind::ConnectionRefused,
+                    format!("The proxy refused to connect to {target}: {}", status_line.trim()),
+                )),
+            }
+        }
+
+        fn socks5_connect(&self, stream: &mut TcpStream, host: &str, port: u16) -> std::io::Result<()> {
+            const NO_AUTHENTICATION: u8 = 0;
+            const USER_PASSWORD: u8 = 2;
+
+            let greeting: &[u8] = if self.user.is_some() {
+                &[5, 2, NO_AUTHENTICATION, USER_PASSWORD]
+            } else {
+                &[5, 1, NO_AUTHENTICATION]
+            };
+            stream.write_all(greeting)?;
+            let mut reply = [0u8; 2];
+            stream.read_exact(&mut reply)?;
+            if reply[0] != 5 {
+                return Err(Error::new(ErrorKind::InvalidData, "The proxy doesn't speak SOCKS5"));
+            }
+            match reply[1] {
+                NO_AUTHENTICATION => {}
+                USER_PASSWORD if self.user.is_some() => {
+                    let user = self.user.as_deref().unwrap_or_default().as_bytes();
+                    let password = self.password.as_deref().unwrap_or_default().as_bytes();
+                    let mut request = vec![1, length_byte(user)?];
+                    request.extend_from_slice(user);
+                    request.push(length_byte(password)?);
+                    request.extend_from_slice(password);
+                    stream.write_all(&request)?;
+                    stream.read_exact(&mut reply)?;
+                    if reply[1] != 0 {
+                        return Err(Error::new(ErrorKind::PermissionDenied, "Proxy authentication failed"));
+                    }
+                }
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::PermissionDenied,
+                        "The proxy doesn't accept any of the offered authentication methods",
+                    ))
+                }
+            }
+
+            let mut request = vec![5, 1 /* connect */, 0];
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            let ip = match self.kind {
+                Kind::Socks5Hostname => host.parse::<IpAddr>().ok(),
+                _ => Some(
+                    (host, port)
+                        .to_socket_addrs()?
+                        .next()
+                        .map(|addr| addr.ip())
+                        .ok_or_else(|| Error::new(ErrorKind::NotFound, format!("Could not resolve host '{host}'")))?,
+                ),
+            };
+            match ip {
+                Some(IpAddr::V4(ip)) => {
+                    request.push(1);
+                    request.extend_from_slice(&ip.octets());
+                }
+                Some(IpAddr::V6(ip)) => {
+                    request.push(4);
+                    request.extend_from_slice(&ip.octets());
+                }
+                None => {
+                    request.push(3);
+                    request.push(length_byte(host.as_bytes())?);
+                    request.extend_from_slice(host.as_bytes());
+                }
+            }
+            request.extend_from_slice(&port.to_be_bytes());
+            stream.write_all(&request)?;
+
+            let mut reply = [0u8; 4];
+            stream.read_exact(&mut reply)?;
+            if reply[1] != 0 {
+                let reason = match reply[1] {
+                    1 => "general failure",
+                    2 => "connection not allowed by ruleset",
+                    3 => "network unreachable",
+                    4 => "host unreachable",
+                    5 => "connection refused",
+                    6 => "TTL expired",
+                    7 => "command not supported",
+                    8 => "address type not supported",
+                    _ => "unknown error",
+                };
+                return Err(Error::new(
+                    ErrorKind::ConnectionRefused,
+                    format!("The proxy could not connect to {}: {reason}", host_and_port(host, port)),
+                ));
+            }
+            let bound_address_len = match reply[3] {
+                1 => 4,
+                4 => 16,
+                3 => {
+                    let mut len = [0u8; 1];
+                    stream.read_exact(&mut len)?;
+                    len[0] as usize
+                }
+                _ => return Err(Error::new(ErrorKind::InvalidData, "Invalid SOCKS5 address type")),
+            };
+            let mut bound_address_and_port = vec![0u8; bound_address_len + 2];
+            stream.read_exact(&mut bound_address_and_port)?;
+            Ok(())
+        }
+    }
+
+    fn length_byte(data: &[u8]) -> std::io::Result<u8> {
+        u8::try_from(data.len()).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "Values sent to SOCKS5 proxies must be shorter than 256 bytes",
+            )
+        })
+    }
+}
@@ -145,6 +145,137 @@ fn http_will_use_pipelining() {
     thread.join().unwrap();
 }
 
+#[test]
+fn http_connection_reuse_can_be_disabled() -> crate::Result {
+    let server = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let addr = server.local_addr()?;
+
+    let thread = std::thread::spawn(move || -> std::io::Result<()> {
+        let mut connections = Vec::new();
+        for _ in 0..2 {
+            // If the first connection was reused, we would wait forever, so fail instead.
+            server.set_nonblocking(true)?;
+            let start = std::time::Instant::now();
+            let conn = loop {
+                match server.accept() {
+                    Ok((conn, _)) => break conn,
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                        assert!(
+                            start.elapsed() < std::time::Duration::from_secs(10),
+                            "each request should use a new connection"
+                        );
+                        std::thread::sleep(std::time::Duration::from_millis(10));
+                    }
+                    Err(err) => return Err(err),
+                }
+            };
+            conn.set_nonblocking(false)?;
+            let mut conn = io::BufReader::new(conn);
+            let mut line = String::new();
+            while conn.read_line(&mut line)? > 2 {
+                line.clear();
+            }
+            conn.get_mut().write_all(
+                b"HTTP/1.1 401 Unauthorized\r\n\
+                  Content-Length: 0\r\n\
+                  \r\n",
+            )?;
+            // Keep the connection open so it could be reused.
+            connections.push(conn);
+        }
+        Ok(())
+    });
+
+    let url = format!("http://{}:{}/reponame", &addr.ip().to_string(), &addr.port());
+    let mut client = http::connect(url.try_into()?, Protocol::V2, false);
+    client
+        .configure(&http::Options {
+            pool_idle_timeout: Some(std::time::Duration::ZERO),
+            ..Default::default()
+        })
+        .expect("options are always accepted");
+    for _ in 0..2 {
+        match client.handshake(Service::UploadPack, &[]) {
+            Err(client::Error::Io(err)) if err.kind() == io::ErrorKind::PermissionDenied => {}
+            res => unreachable!("expecting permission denied to be detected: {:?}", res.err()),
+        }
+    }
+    thread.join().expect("no panic")?;
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "http-client-curl")]
+fn http_identity_is_only_transmitted_when_requested_by_server_with_configured_auth_method() -> crate::Result {
+    let (server, mut client) =
+        mock::serve_and_connect("v1/http-handshake.response", "path/not-important", Protocol::V1)?;
+    client
+        .configure(&http::Options {
+            auth_method: Some(http::options::ProxyAuthMethod::AnyAuth),
+            ..Default::default()
+        })
+        .expect("options are always accepted");
+    client.set_identity(gix_sec::identity::Account {
+        username: "user".into(),
+        password: "password".into(),
+    })?;
+    client.handshake(Service::UploadPack, &[])?;
+
+    let headers = server.received_as_string().to_lowercase();
+    assert!(
+        !headers.contains("authorization:"),
+        "curl waits for the server to ask for authentication to pick the method, instead of sending basic auth: {headers}"
+    );
+    Ok(())
+}
+
+#[test]
+fn http_proxy_is_used_with_credentials_from_helper() -> crate::Result {
+    let server = mock::serve_once("v1/http-handshake.response");
+    let actions = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut client = http::connect("http://example.com/repo".try_into()?, Protocol::V1, false);
+    client
+        .configure(&http::Options {
+            proxy: Some(format!("http://user@{}", server.addr)),
+            no_proxy: Some("example.org".into()),
+            proxy_auth_method: http::options::ProxyAuthMethod::Basic,
+            proxy_authenticate: Some((
+                gix_credentials::helper::Action::get_for_url(format!("http://user@{}", server.addr)),
+                std::sync::Arc::new(std::sync::Mutex::new({
+                    let actions = actions.clone();
+                    move |action: gix_credentials::helper::Action| {
+                        let is_get = matches!(action, gix_credentials::helper::Action::Get(_));
+                        actions.lock().expect("no panic").push(action);
+                        Ok(is_get.then(|| gix_credentials::protocol::Outcome {
+                            identity: gix_sec::identity::Account {
+                                username: "user".into(),
+                                password: "secret".into(),
+                            },
+                            next: gix_credentials::protocol::Context::default().into(),
+                        }))
+                    }
+                })),
+            )),
+            ..Default::default()
+        })
+        .expect("options are always accepted");
+    client.handshake(Service::UploadPack, &[])?;
+
+    let headers = server.received_as_string().to_lowercase();
+    assert!(
+        headers.starts_with("get http://example.com/repo/info/refs?service=git-upload-pack http/1.1"),
+        "the request is sent to the proxy with the full url: {headers}"
+    );
+    assert!(
+        headers.contains("proxy-authorization: basic dxnlcjpzzwnyzxq="),
+        "the password is obtained from the credential helper: {headers}"
+    );
+    let actions = actions.lock().expect("no panic");
+    assert_eq!(actions.len(), 2, "the credentials were obtained and then stored");
+    assert!(matches!(actions[1], gix_credentials::helper::Action::Store(_)));
+    Ok(())
+}
+
 #[test]
 fn http_authentication_error_can_be_differentiated_and_identity_is_transmitted() -> crate::Result {
     let (server, mut client) = assert_error_status(401, std::io::ErrorKind::PermissionDenied)?;
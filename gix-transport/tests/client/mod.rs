@@ -2,3 +2,4 @@
 mod blocking_io;
 mod capabilities;
 mod git;
+mod proxy;
@@ -1,5 +1,6 @@
 use std::{
     collections::BTreeSet,
+    path::Path,
     sync::atomic::{AtomicUsize, Ordering},
 };
 
@@ -26,6 +27,8 @@ mod reduce {
             let super::Outcome {
                 bytes_written,
                 files,
+                files_cloned,
+                files_skipped,
                 delayed_symlinks,
                 errors,
                 collisions,
@@ -34,6 +37,8 @@ mod reduce {
             } = item;
             self.aggregate.bytes_written += bytes_written;
             self.aggregate.files += files;
+            self.aggregate.files_cloned += files_cloned;
+            self.aggregate.files_skipped += files_skipped;
             self.aggregate.delayed_symlinks.extend(delayed_symlinks);
             self.aggregate.errors.extend(errors);
             self.aggregate.collisions.extend(collisions);
@@ -63,6 +68,10 @@ pub struct Outcome<'a> {
     pub bytes_written: u64,
     // the amount of files we processed
     pub files: usize,
+    // the amount of files whose content was cloned from another file
+    pub files_cloned: usize,
+    // the amount of files that were already checked out when resuming
+    pub files_skipped: usize,
     /// Relative paths that the process listed as 'delayed' even though we never passed them.
     pub delayed_paths_unknown: Vec<BString>,
     /// All paths that were left unprocessed, because they were never listed by the process even though we passed them.
@@ -76,6 +85,7 @@ pub struct Context<Find: Clone> {
     pub filters: gix_filter::Pipeline,
     pub buf: Vec<u8>,
     pub options: Options,
+    pub clone_sources: Option<checkout::CloneSources>,
 }
 
 #[derive(Clone, Copy)]
@@ -84,6 +94,8 @@ pub struct Options {
     pub destination_is_initially_empty: bool,
     pub overwrite_existing: bool,
     pub keep_going: bool,
+    pub resume: bool,
+    pub stat_options: gix_index::entry::stat::Options,
     pub filter_process_delay: gix_filter::driver::apply::Delay,
 }
 
@@ -91,9 +103,12 @@ impl From<&checkout::Options> for Options {
     fn from(opts: &checkout::Options) -> Self {
         Options {
             fs: opts.fs,
-            destination_is_initially_empty: opts.destination_is_initially_empty,
+            // When resuming, files of the previous run that we don't know about must be overwritten.
+            destination_is_initially_empty: opts.destination_is_initially_empty && !opts.resume,
             overwrite_existing: opts.overwrite_existing,
             keep_going: opts.keep_going,
+            resume: opts.resume,
+            stat_options: opts.stat_options,
             filter_process_delay: opts.filter_process_delay,
         }
     }
@@ -114,6 +129,8 @@ where
     let mut errors = Vec::new();
     let mut bytes_written = 0;
     let mut files_in_chunk = 0;
+    let mut files_cloned = 0;
+    let mut files_skipped = 0;
 
     for (entry, entry_path) in entries_with_paths {
         // TODO: write test for that
@@ -123,6 +140,12 @@ where
             continue;
         }
 
+        if ctx.options.resume && is_checked_out(entry, entry_path, ctx.path_cache.base(), ctx.options.stat_options) {
+            files.fetch_add(1, Ordering::Relaxed);
+            files_skipped += 1;
+            continue;
+        }
+
         // Symlinks always have to be delayed on windows as they have to point to something that exists on creation.
         // And even if not, there is a distinction between file and directory symlinks, hence we have to check what the target is
         // before creating it.
@@ -135,9 +158,10 @@ where
         }
 
         match checkout_entry_handle_result(entry, entry_path, &mut errors, &mut collisions, files, bytes, ctx)? {
-            entry::Outcome::Written { bytes } => {
+            entry::Outcome::Written { bytes, cloned } => {
                 bytes_written += bytes as u64;
-                files_in_chunk += 1
+                files_in_chunk += 1;
+                files_cloned += usize::from(cloned);
             }
             entry::Outcome::Delayed(delayed) => delayed_filter_results.push(delayed),
         }
@@ -146,6 +170,8 @@ where
     Ok(Outcome {
         bytes_written,
         files: files_in_chunk,
+        files_cloned,
+        files_skipped,
         errors,
         collisions,
         delayed_symlinks,
@@ -154,6 +180,23 @@ where
     })
 }
 
+/// Return `true` if the file of `entry` at `entry_path` within `root` was already written by a previous checkout,
+/// which is the case if its stat information matches the one recorded in `entry`.
+fn is_checked_out(
+    entry: &gix_index::Entry,
+    entry_path: &BStr,
+    root: &Path,
+    stat_options: gix_index::entry::stat::Options,
+) -> bool {
+    if entry.stat == gix_index::entry::Stat::default() {
+        return false;
+    }
+    gix_index::fs::Metadata::from_path_no_follow(&root.join(gix_path::from_bstr(entry_path)))
+        .ok()
+        .and_then(|md| gix_index::entry::Stat::from_fs(&md).ok())
+        .map_or(false, |stat| entry.stat.matches(&stat, stat_options))
+}
+
 pub fn process_delayed_filter_results<Find>(
     mut delayed_filter_results: Vec<DelayedFilteredStream<'_>>,
     files: &AtomicUsize,
@@ -292,6 +335,7 @@ pub fn checkout_entry_handle_result<'entry, Find>(
         filters,
         buf,
         options,
+        clone_sources,
     }: &mut Context<Find>,
 ) -> Result<entry::Outcome<'entry>, checkout::Error>
 where
@@ -305,6 +349,7 @@ where
             path_cache,
             filters,
             buf,
+            clone_sources: clone_sources.as_ref(),
         },
         *options,
     );
@@ -317,10 +362,17 @@ where
             Ok(out)
         }
         Err(checkout::Error::Io(err)) if is_collision(&err, entry_path, collisions, files) => {
-            Ok(entry::Outcome::Written { bytes: 0 })
+            Ok(entry::Outcome::Written {
+                bytes: 0,
+                cloned: false,
+            })
+        }
+        Err(err) => {
+            handle_error(err, entry_path, files, errors, options.keep_going).map(|()| entry::Outcome::Written {
+                bytes: 0,
+                cloned: false,
+            })
         }
-        Err(err) => handle_error(err, entry_path, files, errors, options.keep_going)
-            .map(|()| entry::Outcome::Written { bytes: 0 }),
     }
 }
 
@@ -16,6 +16,7 @@ pub struct Context<'a, Find> {
     pub path_cache: &'a mut Stack,
     pub filters: &'a mut gix_filter::Pipeline,
     pub buf: &'a mut Vec<u8>,
+    pub clone_sources: Option<&'a crate::checkout::CloneSources>,
 }
 
 /// A delayed result of a long-running filter process, which is made available as stream.
@@ -37,6 +38,8 @@ pub enum Outcome<'a> {
     Written {
         /// The amount of written bytes.
         bytes: usize,
+        /// If `true`, the content was cloned from another file.
+        cloned: bool,
     },
     /// The will be ready later.
     Delayed(DelayedFilteredStream<'a>),
@@ -46,7 +49,7 @@ impl Outcome<'_> {
     /// Return ourselves as (in-memory) bytes if possible.
     pub fn as_bytes(&self) -> Option<usize> {
         match self {
-            Outcome::Written { bytes } => Some(*bytes),
+            Outcome::Written { bytes, .. } => Some(*bytes),
             Outcome::Delayed { .. } => None,
         }
     }
@@ -61,6 +64,7 @@ pub fn checkout<'entry, Find>(
         filters,
         path_cache,
         buf,
+        clone_sources,
     }: Context<'_, Find>,
     crate::checkout::chunk::Options {
         fs: gix_fs::Capabilities {
@@ -84,6 +88,7 @@ where
     let path_cache = path_cache.at_path(dest_relative, is_dir, &*objects)?;
     let dest = path_cache.path();
 
+    let mut cloned = false;
     let object_size = match entry.mode {
         gix_index::entry::Mode::FILE | gix_index::entry::Mode::FILE_EXECUTABLE => {
             let obj = (*objects)
@@ -101,17 +106,40 @@ where
                 },
                 filter_process_delay,
             )?;
+            let content_is_blob = matches!(filtered, ToWorktreeOutcome::Unchanged(_));
             let (num_bytes, file, set_executable_after_creation) = match filtered {
                 ToWorktreeOutcome::Unchanged(buf) | ToWorktreeOutcome::Buffer(buf) => {
-                    let (mut file, flag) = open_file(
-                        dest,
-                        destination_is_initially_empty,
-                        overwrite_existing,
-                        executable_bit,
-                        entry.mode,
-                    )?;
-                    file.write_all(buf)?;
-                    (buf.len(), file, flag)
+                    if let Some((file, flag)) = clone_sources
+                        .and_then(|sources| {
+                            sources
+                                .find(&entry.id, entry_path, buf, content_is_blob)
+                                .map(|source| (sources, source))
+                        })
+                        .map(|(sources, source)| {
+                            clone_file(
+                                &source,
+                                dest,
+                                &sources.probe,
+                                destination_is_initially_empty,
+                                overwrite_existing,
+                                executable_bit && entry.mode == gix_index::entry::Mode::FILE_EXECUTABLE,
+                            )
+                        })
+                        .transpose()?
+                    {
+                        cloned = true;
+                        (buf.len(), file, flag)
+                    } else {
+                        let (mut file, flag) = open_file(
+                            dest,
+                            destination_is_initially_empty,
+                            overwrite_existing,
+                            executable_bit,
+                            entry.mode,
+                        )?;
+                        file.write_all(buf)?;
+                        (buf.len(), file, flag)
+                    }
                 }
                 ToWorktreeOutcome::Process(MaybeDelayed::Immediate(mut filtered)) => {
                     let (mut file, flag) = open_file(
@@ -180,7 +208,10 @@ where
         }
         _ => unreachable!(),
     };
-    Ok(Outcome::Written { bytes: object_size })
+    Ok(Outcome::Written {
+        bytes: object_size,
+        cloned,
+    })
 }
 
 /// Note that this works only because we assume to not race ourselves when symlinks are involved, and we do this by
@@ -215,6 +246,70 @@ fn try_unlink_path_recursively(path: &Path, path_meta: &std::fs::Metadata) -> st
     }
 }
 
+impl crate::checkout::CloneSources {
+    /// Return the path to a file with exactly `content`, the content of the blob with `id` after filtering,
+    /// to be checked out at `rela_path`. `content_is_blob` is `true` if no filter changed the content.
+    fn find(&self, id: &gix_hash::oid, rela_path: &BStr, content: &[u8], content_is_blob: bool) -> Option<PathBuf> {
+        let size = content.len() as u64;
+        if size < self.min_size.max(1) {
+            return None;
+        }
+        let is_candidate = |path: &Path| {
+            std::fs::symlink_metadata(path)
+                .ok()
+                .filter(|md| md.is_file() && md.len() == size && !gix_fs::is_executable(md))
+                .is_some()
+        };
+        if content_is_blob {
+            let hex = id.to_hex().to_string();
+            if let Some(path) = self
+                .object_dirs
+                .iter()
+                .map(|dir| dir.join(&hex))
+                .find(|path| is_candidate(path))
+            {
+                return Some(path);
+            }
+        }
+        let rela_path = gix_path::try_from_bstr(rela_path).ok()?;
+        self.worktrees
+            .iter()
+            .map(|root| root.join(&rela_path))
+            .find(|path| is_candidate(path) && std::fs::read(path).is_ok_and(|on_disk| on_disk == content))
+    }
+}
+
+/// Clone `source` to `dest`, replacing what's there unless the destination is supposed to be empty.
+fn clone_file(
+    source: &Path,
+    dest: &Path,
+    probe: &gix_fs::clone::Probe,
+    destination_is_initially_empty: bool,
+    overwrite_existing: bool,
+    needs_executable_bit: bool,
+) -> std::io::Result<(std::fs::File, bool)> {
+    if overwrite_existing || !destination_is_initially_empty {
+        debug_assert_dest_is_no_symlink(dest);
+    }
+    let file = match gix_fs::clone::file(source, dest, probe) {
+        Ok((file, _, _)) => file,
+        Err(err)
+            if err.kind() == std::io::ErrorKind::AlreadyExists
+                && (overwrite_existing || !destination_is_initially_empty) =>
+        {
+            let meta = std::fs::symlink_metadata(dest)?;
+            if meta.is_file() || overwrite_existing {
+                try_unlink_path_recursively(dest, &meta)?;
+            } else {
+                return Err(err);
+            }
+            gix_fs::clone::file(source, dest, probe)?.0
+        }
+        Err(err) => return Err(err),
+    };
+    Ok((file, needs_executable_bit))
+}
+
 #[cfg(not(debug_assertions))]
 fn debug_assert_dest_is_no_symlink(_path: &Path) {}
 
@@ -238,6 +333,7 @@ fn open_options(path: &Path, destination_is_initially_empty: bool, overwrite_exi
     options
         .create_new(destination_is_initially_empty && !overwrite_existing)
         .create(!destination_is_initially_empty || overwrite_existing)
+        .truncate(true)
         .write(true);
     options
 }
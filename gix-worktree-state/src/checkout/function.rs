@@ -1,6 +1,14 @@
-use std::sync::atomic::AtomicBool;
+use std::{
+    collections::{BTreeSet, HashSet},
+    sync::{atomic::AtomicBool, Mutex},
+};
 
-use gix_features::{interrupt, parallel::in_parallel_with_finalize};
+use bstr::BString;
+use gix_features::{
+    interrupt,
+    parallel::in_parallel_with_finalize,
+    progress::{Count, DynNestedProgress, Progress},
+};
 use gix_worktree::{stack, Stack};
 
 use crate::checkout::chunk;
@@ -25,11 +33,49 @@ pub fn checkout<Find>(
     should_interrupt: &AtomicBool,
     options: crate::checkout::Options,
 ) -> Result<crate::checkout::Outcome, crate::checkout::Error>
+where
+    Find: gix_object::Find + Send + Clone,
+{
+    checkout_with_progress(
+        index,
+        dir,
+        objects,
+        files,
+        bytes,
+        &mut gix_features::progress::Discard,
+        should_interrupt,
+        options,
+    )
+}
+
+/// Like [`checkout()`], but with `threads` to receive one child progress per thread that checks out chunks of entries,
+/// counting the entries it processed.
+#[allow(clippy::too_many_arguments)]
+pub fn checkout_with_progress<Find>(
+    index: &mut gix_index::State,
+    dir: impl Into<std::path::PathBuf>,
+    objects: Find,
+    files: &dyn gix_features::progress::Count,
+    bytes: &dyn gix_features::progress::Count,
+    threads: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    options: crate::checkout::Options,
+) -> Result<crate::checkout::Outcome, crate::checkout::Error>
 where
     Find: gix_object::Find + Send + Clone,
 {
     let paths = index.take_path_backing();
-    let res = checkout_inner(index, &paths, dir, objects, files, bytes, should_interrupt, options);
+    let res = checkout_inner(
+        index,
+        &paths,
+        dir,
+        objects,
+        files,
+        bytes,
+        threads,
+        should_interrupt,
+        options,
+    );
     index.return_path_backing(paths);
     res
 }
@@ -42,6 +88,7 @@ fn checkout_inner<Find>(
     objects: Find,
     files: &dyn gix_features::progress::Count,
     bytes: &dyn gix_features::progress::Count,
+    threads: &mut dyn DynNestedProgress,
     should_interrupt: &AtomicBool,
     mut options: crate::checkout::Options,
 ) -> Result<crate::checkout::Outcome, crate::checkout::Error>
@@ -51,6 +98,15 @@ where
     let num_files = files.counter();
     let num_bytes = bytes.counter();
     let dir = dir.into();
+    // Without exclusive creation of files, paths that are the same on a case-insensitive filesystem would silently
+    // overwrite each other, so we find them upfront.
+    let (colliding_entries, case_collisions) =
+        if options.fs.ignore_case && (!options.destination_is_initially_empty || options.resume) {
+            case_insensitive_collisions(index, paths)
+        } else {
+            Default::default()
+        };
+    num_files.fetch_add(case_collisions.len(), std::sync::atomic::Ordering::Relaxed);
     let (chunk_size, thread_limit, num_threads) = gix_features::parallel::optimize_chunk_size_and_thread_limit(
         100,
         index.entries().len().into(),
@@ -69,22 +125,42 @@ where
             paths,
         ),
         filters: options.filters,
+        clone_sources: options.clone_sources,
         objects,
     };
 
+    let entries_with_paths = interrupt::Iter::new(
+        index
+            .entries_mut_with_paths_in(paths)
+            .enumerate()
+            .filter(|(idx, _)| !colliding_entries.contains(idx))
+            .map(|(_, entry_and_path)| entry_and_path),
+        should_interrupt,
+    );
+    let threads = Mutex::new(threads);
+    let new_thread_progress = |thread_id: usize| {
+        let mut progress = threads
+            .lock()
+            .expect("no panic while holding the lock")
+            .add_child(format!("thread {thread_id}"));
+        progress.init(None, gix_features::progress::count("files"));
+        progress
+    };
     let chunk::Outcome {
         mut collisions,
         mut errors,
         mut bytes_written,
         files: files_updated,
+        files_cloned,
+        files_skipped,
         delayed_symlinks,
         delayed_paths_unknown,
         delayed_paths_unprocessed,
     } = if num_threads == 1 {
-        let entries_with_paths = interrupt::Iter::new(index.entries_mut_with_paths_in(paths), should_interrupt);
+        let progress = new_thread_progress(0);
         let mut delayed_filter_results = Vec::new();
         let mut out = chunk::process(
-            entries_with_paths,
+            entries_with_paths.inspect(|_| progress.inc()),
             &num_files,
             &num_bytes,
             &mut delayed_filter_results,
@@ -93,7 +169,6 @@ where
         chunk::process_delayed_filter_results(delayed_filter_results, &num_files, &num_bytes, &mut out, &mut ctx)?;
         out
     } else {
-        let entries_with_paths = interrupt::Iter::new(index.entries_mut_with_paths_in(paths), should_interrupt);
         in_parallel_with_finalize(
             gix_features::iter::Chunks {
                 inner: entries_with_paths,
@@ -102,12 +177,19 @@ where
             thread_limit,
             {
                 let ctx = ctx.clone();
-                move |_| (Vec::new(), ctx)
+                let new_thread_progress = &new_thread_progress;
+                move |thread_id| (Vec::new(), ctx, new_thread_progress(thread_id))
             },
-            |chunk, (delayed_filter_results, ctx)| {
-                chunk::process(chunk.into_iter(), &num_files, &num_bytes, delayed_filter_results, ctx)
+            |chunk, (delayed_filter_results, ctx, progress)| {
+                chunk::process(
+                    chunk.into_iter().inspect(|_| progress.inc()),
+                    &num_files,
+                    &num_bytes,
+                    delayed_filter_results,
+                    ctx,
+                )
             },
-            |(delayed_filter_results, mut ctx)| {
+            |(delayed_filter_results, mut ctx, _progress)| {
                 let mut out = chunk::Outcome::default();
                 chunk::process_delayed_filter_results(
                     delayed_filter_results,
@@ -139,12 +221,42 @@ where
             as u64;
     }
 
+    collisions.extend(case_collisions);
     Ok(crate::checkout::Outcome {
         files_updated,
         collisions,
         errors,
         bytes_written,
+        files_cloned,
+        files_skipped,
         delayed_paths_unknown,
         delayed_paths_unprocessed,
     })
 }
+
+/// Return the indices of all entries in `index` whose path is the same as the one of a previous entry when ignoring
+/// ASCII case, along with a collision for each of them.
+/// Entries that aren't checked out due to `SKIP_WORKTREE` are ignored.
+fn case_insensitive_collisions(
+    index: &gix_index::State,
+    paths: &gix_index::PathStorage,
+) -> (BTreeSet<usize>, Vec<crate::checkout::Collision>) {
+    let mut seen = HashSet::<BString>::new();
+    let mut indices = BTreeSet::new();
+    let mut collisions = Vec::new();
+    for (idx, entry) in index.entries().iter().enumerate() {
+        if entry.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE) {
+            continue;
+        }
+        let path = entry.path_in(paths);
+        if !seen.insert(path.to_ascii_lowercase().into()) {
+            gix_features::trace::error!("{path}: collided (case-insensitively)");
+            indices.insert(idx);
+            collisions.push(crate::checkout::Collision {
+                path: path.to_owned(),
+                error_kind: std::io::ErrorKind::AlreadyExists,
+            });
+        }
+    }
+    (indices, collisions)
+}
@@ -26,6 +26,11 @@ pub struct Outcome {
     pub files_updated: usize,
     /// The amount of bytes written to disk,
     pub bytes_written: u64,
+    /// The amount of files whose content was obtained from one of the [clone sources](Options::clone_sources)
+    /// instead of being written, a subset of `files_updated`.
+    pub files_cloned: usize,
+    /// The amount of files that were already checked out and thus skipped, if [`resume`](Options::resume) was enabled.
+    pub files_skipped: usize,
     /// The encountered collisions, which can happen on a case-insensitive filesystem.
     pub collisions: Vec<Collision>,
     /// Other errors that happened during checkout.
@@ -58,6 +63,12 @@ pub struct Options {
     /// due to a conflict.
     /// The checkout operation will never fail, but count the encountered errors instead along with their paths.
     pub keep_going: bool,
+    /// If true, default false, continue a checkout that was previously interrupted by skipping all entries whose files
+    /// on disk match the stat information in the index, which is where their stat information is kept after they were written.
+    /// Thus, the index as left by the interrupted checkout must be written and passed here.
+    ///
+    /// All other files are written as if the destination wasn't initially empty, and `stat_options` control how files are compared.
+    pub resume: bool,
     /// Control how stat comparisons are made when checking if a file is fresh.
     pub stat_options: stat::Options,
     /// A stack of attributes to use with the filesystem cache to use as driver for filters.
@@ -66,6 +77,40 @@ pub struct Options {
     pub filters: gix_filter::Pipeline,
     /// Control how long-running processes may use the 'delay' capability.
     pub filter_process_delay: gix_filter::driver::apply::Delay,
+    /// If set, files of sufficient size are cloned from existing files with identical content instead of being written,
+    /// which allows to share storage on filesystems that support it.
+    pub clone_sources: Option<CloneSources>,
+}
+
+/// Locations with files that may already have the content of files to check out, along with the configuration
+/// to control when to use them.
+///
+/// Files are cloned using [`gix_fs::clone::file()`], which shares the data if the filesystem supports it
+/// and falls back to copying otherwise. Capabilities are detected once per filesystem.
+#[derive(Clone, Debug)]
+pub struct CloneSources {
+    /// Directories with files named after the hexadecimal id of the blob they contain, like `<dir>/<hex-id>`.
+    ///
+    /// Their content is only used if no filter changed the blob content, and is trusted if its size matches.
+    pub object_dirs: Vec<std::path::PathBuf>,
+    /// The root directories of other worktrees, whose file at the same relative path is used if its content
+    /// matches exactly what would be written.
+    pub worktrees: Vec<std::path::PathBuf>,
+    /// Files with less than this amount of bytes are always written, as cloning them wouldn't be worth the overhead.
+    pub min_size: u64,
+    /// The cache of per-filesystem capabilities, shared among all threads.
+    pub probe: gix_fs::clone::Probe,
+}
+
+impl Default for CloneSources {
+    fn default() -> Self {
+        CloneSources {
+            object_dirs: Vec::new(),
+            worktrees: Vec::new(),
+            min_size: 1024 * 1024,
+            probe: Default::default(),
+        }
+    }
 }
 
 /// The error returned by the [checkout()][crate::checkout()] function.
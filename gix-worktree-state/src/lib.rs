@@ -3,4 +3,4 @@
 
 ///
 pub mod checkout;
-pub use checkout::function::checkout;
+pub use checkout::function::{checkout, checkout_with_progress};
@@ -245,6 +245,26 @@ fn overwriting_files_and_lone_directories_works() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn overwriting_longer_files_truncates_them() -> crate::Result {
+    let mut opts = opts_from_probe();
+    opts.overwrite_existing = true;
+    opts.destination_is_initially_empty = false;
+    let (_source, destination, _index, outcome) = checkout_index_in_tmp_dir_opts(
+        opts,
+        "make_mixed_without_submodules_and_symlinks",
+        |_| true,
+        |d| std::fs::write(d.join("executable"), b"longer than the actual content"),
+    )?;
+    assert!(outcome.collisions.is_empty());
+    assert_eq!(
+        std::fs::read(destination.path().join("executable"))?.as_bstr(),
+        "content",
+        "previous content past the new length doesn't remain in the file"
+    );
+    Ok(())
+}
+
 #[test]
 fn symlinks_become_files_if_disabled() -> crate::Result {
     let mut opts = opts_from_probe();
@@ -271,6 +291,26 @@ fn allow_or_disallow_symlinks() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn files_are_cloned_from_other_worktrees_if_their_content_matches() -> crate::Result {
+    let mut opts = opts_from_probe();
+    opts.clone_sources = Some(gix_worktree_state::checkout::CloneSources {
+        worktrees: vec![fixture_path("make_mixed_without_submodules")],
+        min_size: 1,
+        ..Default::default()
+    });
+    let (source_tree, destination, _index, outcome) =
+        checkout_index_in_tmp_dir(opts.clone(), "make_mixed_without_submodules")?;
+
+    assert_equality(&source_tree, &destination, opts.fs.symlink)?;
+    assert!(outcome.collisions.is_empty());
+    assert!(
+        outcome.files_cloned > 0 && outcome.files_cloned < outcome.files_updated,
+        "non-empty files are cloned, but empty ones and executables are always written"
+    );
+    Ok(())
+}
+
 #[test]
 fn keep_going_collects_results() {
     let mut opts = opts_from_probe();
@@ -431,6 +471,66 @@ fn collisions_are_detected_on_a_case_insensitive_filesystem_even_with_delayed_fi
     }
 }
 
+#[test]
+fn resuming_skips_files_that_were_already_checked_out() -> crate::Result {
+    let opts = opts_from_probe();
+    let (source_tree, destination, mut index, outcome) =
+        checkout_index_in_tmp_dir(opts.clone(), "make_mixed_without_submodules")?;
+    assert_eq!(outcome.files_skipped, 0, "nothing is skipped without resuming");
+    let num_entries = index.entries().len();
+
+    let mut opts = opts;
+    opts.resume = true;
+    let outcome = checkout_into(&mut index, &source_tree, &destination, opts.clone())?;
+    assert_eq!(outcome.files_updated, 0, "everything was checked out already");
+    assert_eq!(outcome.files_skipped, num_entries);
+
+    std::fs::write(destination.path().join("dir/content"), b"changed after checkout")?;
+    let (entry, _) = index
+        .entries_mut_with_paths()
+        .find(|(_, path)| *path == "empty")
+        .expect("present");
+    entry.stat = Default::default();
+    let outcome = checkout_into(&mut index, &source_tree, &destination, opts.clone())?;
+    assert_eq!(
+        outcome.files_updated, 2,
+        "the changed file and the one that wasn't recorded as checked out are written"
+    );
+    assert_eq!(outcome.files_skipped, num_entries - 2);
+    assert!(outcome.collisions.is_empty());
+    assert_equality(&source_tree, &destination, opts.fs.symlink)?;
+    Ok(())
+}
+
+#[test]
+fn case_insensitive_collisions_are_detected_upfront_if_the_destination_is_not_empty() -> crate::Result {
+    let mut opts = opts_from_probe();
+    if opts.fs.ignore_case {
+        eprintln!("Skipping as collisions on case-insensitive filesystems are tested elsewhere");
+        return Ok(());
+    }
+    opts.fs.ignore_case = true;
+    opts.destination_is_initially_empty = false;
+    let (_source_tree, destination, _index, outcome) = checkout_index_in_tmp_dir(opts, "make_ignorecase_collisions")?;
+
+    assert_eq!(
+        outcome.collisions,
+        ["FILE_x", "file_X", "file_x", "x"]
+            .into_iter()
+            .map(|path| Collision {
+                path: path.into(),
+                error_kind: AlreadyExists,
+            })
+            .collect::<Vec<_>>(),
+        "later entries with the same path when ignoring case aren't written, as they would overwrite earlier ones"
+    );
+    assert_eq!(
+        stripped_prefix(&destination, &dir_structure(&destination)),
+        paths(["D/B", "D/C", "FILE_X", "X", "d", "link-to-X"]),
+    );
+    Ok(())
+}
+
 fn multi_threaded() -> bool {
     gix_features::parallel::num_threads(None) > 1
 }
@@ -536,6 +636,27 @@ fn checkout_index_in_tmp_dir_opts(
     Ok((source_tree, destination, index, outcome))
 }
 
+fn checkout_into(
+    index: &mut gix_index::File,
+    source_tree: &Path,
+    destination: &TempDir,
+    opts: gix_worktree_state::checkout::Options,
+) -> crate::Result<gix_worktree_state::checkout::Outcome> {
+    let odb = gix_odb::at(source_tree.join(".git").join("objects"))?
+        .into_inner()
+        .into_arc()?;
+    Ok(gix_worktree_state::checkout_with_progress(
+        index,
+        destination.path(),
+        odb,
+        &progress::Discard,
+        &progress::Discard,
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        opts,
+    )?)
+}
+
 fn stripped_prefix(prefix: impl AsRef<Path>, source_files: &[PathBuf]) -> Vec<&Path> {
     source_files.iter().flat_map(|p| p.strip_prefix(&prefix)).collect()
 }
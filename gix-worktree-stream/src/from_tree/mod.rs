@@ -2,13 +2,14 @@ use std::io::Write;
 
 use gix_object::{bstr::BStr, FindExt};
 
-use crate::{entry, entry::Error, protocol, AdditionalEntry, SharedErrorSlot, Stream};
+use crate::{entry, entry::Error, protocol, utils, Additional, AdditionalEntry, SharedErrorSlot, Stream};
 
 /// Use `objects` to traverse `tree` and fetch the contained blobs to return as [`Stream`], which makes them queryable
 /// on demand with support for streaming each entry.
 ///
 /// `pipeline` is used to convert blobs to their worktree representation, and `attributes` is used to read
-/// the `export-ignore` attribute. If set on a directory or blob, it won't be added to the archive.
+/// the `export-ignore` and `export-subst` attributes. If `export-ignore` is set on a directory or blob, it won't be added to the archive.
+/// As there is no commit to obtain information from, `export-subst` has no effect - use [`from_commit()`] instead.
 ///
 /// ### Types of entries in stream
 ///
@@ -28,12 +29,53 @@ use crate::{entry, entry::Error, protocol, AdditionalEntry, SharedErrorSlot, Str
 ///
 /// This function spawns a thread that will access the tree data in the background, synchronized through
 /// `Stream` so that it will not be faster than the consumer, with at most one file in flight at any time.
+pub fn from_tree<Find, E>(
+    tree: gix_hash::ObjectId,
+    objects: Find,
+    pipeline: gix_filter::Pipeline,
+    attributes: impl FnMut(&BStr, gix_object::tree::EntryMode, &mut gix_attributes::search::Outcome) -> Result<(), E>
+        + Send
+        + 'static,
+) -> Stream
+where
+    Find: gix_object::Find + Clone + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    spawn(Root::Tree(tree), objects, pipeline, attributes)
+}
+
+/// Like [`from_tree()`], but use the tree of `commit` and expand `$Format:<placeholders>$` in blobs that have the
+/// `export-subst` attribute set with information from `commit`, similar to what `git archive` does.
 ///
 /// ### Limitations
 ///
-/// * `export-subst` is not support, as it requires the entire formatting engine of `git log`.
-pub fn from_tree<Find, E>(
-    tree: gix_hash::ObjectId,
+/// * Only a subset of the placeholders of `git log --pretty=format:` are supported, namely `%H`, `%h`, `%T`, `%t`, `%P`, `%p`,
+///   `%an`, `%ae`, `%ad`, `%aD`, `%at`, `%ai`, `%aI` along with their committer counterparts, as well as `%s`, `%b`, `%B`,
+///   `%n` and `%%`. All other placeholders are kept verbatim.
+/// * Abbreviated hashes always have 7 characters and aren't guaranteed to be unique.
+pub fn from_commit<Find, E>(
+    commit: gix_hash::ObjectId,
+    objects: Find,
+    pipeline: gix_filter::Pipeline,
+    attributes: impl FnMut(&BStr, gix_object::tree::EntryMode, &mut gix_attributes::search::Outcome) -> Result<(), E>
+        + Send
+        + 'static,
+) -> Stream
+where
+    Find: gix_object::Find + Clone + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    spawn(Root::Commit(commit), objects, pipeline, attributes)
+}
+
+/// The object to start the traversal from.
+enum Root {
+    Tree(gix_hash::ObjectId),
+    Commit(gix_hash::ObjectId),
+}
+
+fn spawn<Find, E>(
+    root: Root,
     objects: Find,
     pipeline: gix_filter::Pipeline,
     attributes: impl FnMut(&BStr, gix_object::tree::EntryMode, &mut gix_attributes::search::Outcome) -> Result<(), E>
@@ -49,7 +91,7 @@ where
         let slot = stream.err.clone();
         move || {
             if let Err(err) = run(
-                tree,
+                root,
                 objects,
                 pipeline,
                 attributes,
@@ -76,7 +118,7 @@ where
 }
 
 fn run<Find, E>(
-    tree: gix_hash::ObjectId,
+    root: Root,
     objects: Find,
     mut pipeline: gix_filter::Pipeline,
     mut attributes: impl FnMut(&BStr, gix_object::tree::EntryMode, &mut gix_attributes::search::Outcome) -> Result<(), E>
@@ -84,20 +126,27 @@ fn run<Find, E>(
         + 'static,
     out: &mut gix_features::io::pipe::Writer,
     err: SharedErrorSlot,
-    additional_entries: std::sync::mpsc::Receiver<AdditionalEntry>,
+    additional_entries: std::sync::mpsc::Receiver<Additional>,
 ) -> Result<(), Error>
 where
     Find: gix_object::Find + Clone,
     E: std::error::Error + Send + Sync + 'static,
 {
     let mut buf = Vec::new();
+    let (tree, commit) = match root {
+        Root::Tree(tree) => (tree, None),
+        Root::Commit(id) => {
+            let commit: gix_object::Commit = objects.find_commit(&id, &mut buf)?.into();
+            (commit.tree, Some(subst::Commit { id, commit }))
+        }
+    };
     let tree_iter = objects.find_tree_iter(tree.as_ref(), &mut buf)?;
     if pipeline.driver_context_mut().treeish.is_none() {
-        pipeline.driver_context_mut().treeish = Some(tree);
+        pipeline.driver_context_mut().treeish = Some(commit.as_ref().map_or(tree, |commit| commit.id));
     }
 
     let mut attrs = gix_attributes::search::Outcome::default();
-    attrs.initialize_with_selection(&Default::default(), Some("export-ignore"));
+    attrs.initialize_with_selection(&Default::default(), ["export-ignore", "export-subst"]);
     let mut dlg = traverse::Delegate {
         out,
         err,
@@ -113,6 +162,8 @@ where
         path_deque: Default::default(),
         path: Default::default(),
         buf: Vec::with_capacity(1024),
+        commit,
+        subst_buf: Vec::new(),
     };
     gix_traverse::tree::breadthfirst(
         tree_iter,
@@ -121,26 +172,46 @@ where
         &mut dlg,
     )?;
 
-    for entry in additional_entries {
-        protocol::write_entry_header_and_path(
-            entry.relative_path.as_ref(),
-            &entry.id,
-            entry.mode,
-            entry.source.len(),
-            out,
-        )?;
-        // pipe writer always writes all in one go.
-        #[allow(clippy::unused_io_amount)]
-        match entry.source {
-            entry::Source::Memory(buf) => out.write(&buf).map(|_| ()),
-            entry::Source::Null => out.write(&[]).map(|_| ()),
-            entry::Source::Path(path) => {
-                let file = std::fs::File::open(path)?;
-                protocol::write_stream(&mut buf, file, out)
+    for additional in additional_entries {
+        match additional {
+            Additional::Entry(entry) => write_additional_entry(entry, &mut buf, out)?,
+            Additional::Stream { prefix, read, err } => {
+                let mut stream = Stream::from_parts(utils::Read::Known(read), err);
+                let mut path = prefix;
+                let prefix_len = path.len();
+                while let Some(entry) = stream.next_entry()? {
+                    path.truncate(prefix_len);
+                    path.extend_from_slice(entry.relative_path());
+                    protocol::write_entry_header_and_path(path.as_ref(), &entry.id, entry.mode, None, out)?;
+                    protocol::write_stream(&mut buf, entry, out)?;
+                }
             }
-        }?
+        }
     }
     Ok(())
 }
 
+fn write_additional_entry(
+    entry: AdditionalEntry,
+    buf: &mut Vec<u8>,
+    out: &mut gix_features::io::pipe::Writer,
+) -> std::io::Result<()> {
+    protocol::write_entry_header_and_path(
+        entry.relative_path.as_ref(),
+        &entry.id,
+        entry.mode,
+        entry.source.len(),
+        out,
+    )?;
+    match entry.source {
+        entry::Source::Memory(buf) => out.write_all(&buf),
+        entry::Source::Null => Ok(()),
+        entry::Source::Path(path) => {
+            let file = std::fs::File::open(path)?;
+            protocol::write_stream(buf, file, out)
+        }
+    }
+}
+
+mod subst;
 mod traverse;
@@ -0,0 +1,122 @@
+use gix_date::time::format;
+use gix_object::bstr::{BStr, ByteSlice, ByteVec};
+
+/// The commit whose information is used to expand `$Format:…$` placeholders in files with the `export-subst` attribute.
+pub(crate) struct Commit {
+    pub(crate) id: gix_hash::ObjectId,
+    pub(crate) commit: gix_object::Commit,
+}
+
+/// The amount of hex characters to use for abbreviated hashes.
+///
+/// Note that `git` assures these are unique, which we don't do as it would require access to the object database.
+const ABBREV_LEN: usize = 7;
+
+/// Write `input` to `out` with all `$Format:<placeholders>$` replaced by the result of formatting `commit` according
+/// to `<placeholders>`, just like `git archive` does for files with the `export-subst` attribute.
+pub(crate) fn expand(input: &[u8], commit: &Commit, out: &mut Vec<u8>) {
+    const START: &[u8] = b"$Format:";
+    let mut rest = input;
+    while let Some(start) = rest.find(START) {
+        let Some(len) = rest[start + START.len()..].find_byte(b'$') else {
+            break;
+        };
+        out.extend_from_slice(&rest[..start]);
+        let format = &rest[start + START.len()..][..len];
+        format_commit(format.as_bstr(), commit, out);
+        rest = &rest[start + START.len() + len + 1..];
+    }
+    out.extend_from_slice(rest);
+}
+
+/// Format `commit` according to `format`, similar to `git log --pretty=format:<format>`, but only with support for a subset of
+/// all placeholders. Unknown placeholders are written verbatim.
+fn format_commit(format: &BStr, Commit { id, commit }: &Commit, out: &mut Vec<u8>) {
+    let message = gix_object::commit::MessageRef::from_bytes(&commit.message);
+    let mut rest: &[u8] = format;
+    while let Some(pos) = rest.find_byte(b'%') {
+        out.extend_from_slice(&rest[..pos]);
+        rest = &rest[pos + 1..];
+        let consumed = match rest {
+            [b'%', ..] => {
+                out.push(b'%');
+                1
+            }
+            [b'n', ..] => {
+                out.push(b'\n');
+                1
+            }
+            [b'H', ..] => {
+                out.push_str(id.to_hex().to_string());
+                1
+            }
+            [b'h', ..] => {
+                out.push_str(id.to_hex_with_len(ABBREV_LEN).to_string());
+                1
+            }
+            [b'T', ..] => {
+                out.push_str(commit.tree.to_hex().to_string());
+                1
+            }
+            [b't', ..] => {
+                out.push_str(commit.tree.to_hex_with_len(ABBREV_LEN).to_string());
+                1
+            }
+            [b'P' | b'p', ..] => {
+                let len = if rest[0] == b'P' {
+                    id.kind().len_in_hex()
+                } else {
+                    ABBREV_LEN
+                };
+                for (idx, parent) in commit.parents.iter().enumerate() {
+                    if idx != 0 {
+                        out.push(b' ');
+                    }
+                    out.push_str(parent.to_hex_with_len(len).to_string());
+                }
+                1
+            }
+            [b's', ..] => {
+                out.push_str(message.summary().as_ref());
+                1
+            }
+            [b'b', ..] => {
+                if let Some(body) = message.body {
+                    out.push_str(body);
+                }
+                1
+            }
+            [b'B', ..] => {
+                out.push_str(&commit.message);
+                1
+            }
+            [who @ (b'a' | b'c'), field, ..] => {
+                let signature = if *who == b'a' {
+                    &commit.author
+                } else {
+                    &commit.committer
+                };
+                match field {
+                    b'n' => out.push_str(&signature.name),
+                    b'e' => out.push_str(&signature.email),
+                    b'd' => out.push_str(signature.time.format(format::DEFAULT)),
+                    b'D' => out.push_str(signature.time.format(format::GIT_RFC2822)),
+                    b't' => out.push_str(signature.time.format(format::UNIX)),
+                    b'i' => out.push_str(signature.time.format(format::ISO8601)),
+                    b'I' => out.push_str(signature.time.format(format::ISO8601_STRICT)),
+                    _ => {
+                        out.push(b'%');
+                        continue;
+                    }
+                }
+                2
+            }
+            _ => {
+                out.push(b'%');
+                continue;
+            }
+        };
+        rest = &rest[consumed..];
+    }
+    out.extend_from_slice(rest);
+}
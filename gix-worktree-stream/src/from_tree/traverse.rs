@@ -1,4 +1,7 @@
-use std::{collections::VecDeque, io::Write};
+use std::{
+    collections::VecDeque,
+    io::{Read, Write},
+};
 
 use gix_filter::{driver::apply::MaybeDelayed, pipeline::convert::ToWorktreeOutcome};
 use gix_object::{
@@ -7,6 +10,7 @@ use gix_object::{
 };
 use gix_traverse::tree::{visit::Action, Visit};
 
+use super::subst;
 use crate::{entry::Error, protocol, SharedErrorSlot};
 
 pub struct Delegate<'a, AttributesFn, Find>
@@ -22,6 +26,10 @@ where
     pub(crate) fetch_attributes: AttributesFn,
     pub(crate) objects: Find,
     pub(crate) buf: Vec<u8>,
+    /// The commit to use for expanding placeholders in files with the `export-subst` attribute, if available.
+    pub(crate) commit: Option<subst::Commit>,
+    /// A buffer to hold the content of files after expanding their placeholders.
+    pub(crate) subst_buf: Vec<u8>,
 }
 
 impl<AttributesFn, Find> Delegate<'_, AttributesFn, Find>
@@ -49,7 +57,17 @@ where
         self.attrs
             .iter_selected()
             .next()
-            .expect("initialized with one attr")
+            .expect("initialized with two attrs")
+            .assignment
+            .state
+    }
+
+    /// Return the state of the `export-subst` attribute.
+    fn subst_state(&self) -> gix_attributes::StateRef<'_> {
+        self.attrs
+            .iter_selected()
+            .nth(1)
+            .expect("initialized with two attrs")
             .assignment
             .state
     }
@@ -62,6 +80,7 @@ where
         if self.ignore_state().is_set() {
             return Ok(Action::Continue);
         }
+        let expand_placeholders = self.commit.is_some() && !entry.mode.is_link() && self.subst_state().is_set();
         self.objects.find(entry.oid, &mut self.buf)?;
 
         self.pipeline.driver_context_mut().blob = Some(entry.oid.into());
@@ -74,8 +93,34 @@ where
             gix_filter::driver::apply::Delay::Forbid,
         )?;
 
-        // Our pipe writer always writes the whole amount.
-        #[allow(clippy::unused_io_amount)]
+        if expand_placeholders {
+            // Placeholders can only be expanded in memory, so streamed content has to be read entirely.
+            let commit = self.commit.as_ref().expect("checked before");
+            self.subst_buf.clear();
+            match converted {
+                ToWorktreeOutcome::Unchanged(buf) | ToWorktreeOutcome::Buffer(buf) => {
+                    subst::expand(buf, commit, &mut self.subst_buf);
+                }
+                ToWorktreeOutcome::Process(MaybeDelayed::Immediate(mut read)) => {
+                    let mut buf = Vec::new();
+                    read.read_to_end(&mut buf)?;
+                    subst::expand(&buf, commit, &mut self.subst_buf);
+                }
+                ToWorktreeOutcome::Process(MaybeDelayed::Delayed(_)) => {
+                    unreachable!("we forbade it")
+                }
+            }
+            protocol::write_entry_header_and_path(
+                self.path.as_ref(),
+                entry.oid,
+                entry.mode,
+                Some(self.subst_buf.len()),
+                self.out,
+            )?;
+            self.out.write_all(&self.subst_buf)?;
+            return Ok(Action::Continue);
+        }
+
         match converted {
             ToWorktreeOutcome::Unchanged(buf) | ToWorktreeOutcome::Buffer(buf) => {
                 protocol::write_entry_header_and_path(
@@ -85,7 +130,7 @@ where
                     Some(buf.len()),
                     self.out,
                 )?;
-                self.out.write(buf)?;
+                self.out.write_all(buf)?;
             }
             ToWorktreeOutcome::Process(MaybeDelayed::Immediate(read)) => {
                 protocol::write_entry_header_and_path(self.path.as_ref(), entry.oid, entry.mode, None, self.out)?;
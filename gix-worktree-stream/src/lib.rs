@@ -13,7 +13,7 @@ use gix_object::bstr::BString;
 pub struct Stream {
     read: utils::Read,
     err: SharedErrorSlot,
-    extra_entries: Option<std::sync::mpsc::Sender<AdditionalEntry>>,
+    extra_entries: Option<std::sync::mpsc::Sender<Additional>>,
     // additional_entries: Vec,
     /// `None` if currently held by an entry.
     path_buf: Option<BString>,
@@ -30,7 +30,7 @@ pub mod entry;
 pub(crate) mod protocol;
 
 mod from_tree;
-pub use from_tree::from_tree;
+pub use from_tree::{from_commit, from_tree};
 
 pub(crate) type SharedErrorSlot = Arc<parking_lot::Mutex<Option<entry::Error>>>;
 
@@ -70,6 +70,18 @@ pub struct AdditionalEntry {
     pub source: entry::Source,
 }
 
+/// Something to add to the stream after the entries of the git tree.
+pub(crate) enum Additional {
+    /// A single entry.
+    Entry(AdditionalEntry),
+    /// All entries of another stream, with `prefix` put in front of each of their paths.
+    Stream {
+        prefix: BString,
+        read: gix_features::io::pipe::Reader,
+        err: SharedErrorSlot,
+    },
+}
+
 /// Lifecycle
 impl Stream {
     /// Turn ourselves into the underlying byte stream which is a representation of the underlying git tree.
@@ -93,11 +105,15 @@ impl Stream {
     /// It must have been created from [`Self::into_read()`] to be compatible, and must
     /// not have been persisted.
     pub fn from_read(read: impl std::io::Read + 'static) -> Self {
+        Self::from_parts(utils::Read::Unknown(Box::new(read)), Default::default())
+    }
+
+    pub(crate) fn from_parts(read: utils::Read, err: SharedErrorSlot) -> Self {
         Self {
-            read: utils::Read::Unknown(Box::new(read)),
+            read,
             extra_entries: None,
             path_buf: Some(Vec::with_capacity(1024).into()),
-            err: Default::default(),
+            err,
             buf: std::iter::repeat(0).take(u16::MAX as usize).collect(),
             pos: 0,
             filled: 0,
@@ -116,7 +132,38 @@ impl Stream {
         self.extra_entries
             .as_ref()
             .expect("BUG: must not add entries after the start of entries traversal")
-            .send(entry)
+            .send(Additional::Entry(entry))
+            .expect("Failure is impossible as thread blocks on the receiving end");
+        self
+    }
+
+    /// Add all entries of `stream` to the list of entries to be returned in calls to [`Self::next_entry()`], with
+    /// `prefix` put in front of each of their paths verbatim. Use a trailing `/` if `prefix` is a directory.
+    ///
+    /// This is useful to include the worktree of submodules, and the entries will be returned after
+    /// the ones contained in the tree, in order of addition. Entries that were added to `stream` are returned as well.
+    /// Note that `stream` is consumed lazily, and errors that occur while doing so are returned by [`Self::next_entry()`].
+    ///
+    /// # Panics
+    /// If called after the first call to [`Self::next_entry()`], or if `stream` wasn't created
+    /// by [`from_tree()`] or [`from_commit()`], or if it was already read from.
+    pub fn add_stream(&mut self, prefix: impl Into<BString>, mut stream: Stream) -> &mut Self {
+        assert!(
+            stream.extra_entries.take().is_some(),
+            "BUG: streams must be created from a tree and must not be added after reading from them"
+        );
+        let read = match stream.read {
+            utils::Read::Known(read) => read,
+            utils::Read::Unknown(_) => unreachable!("streams created from a tree have a known reader"),
+        };
+        self.extra_entries
+            .as_ref()
+            .expect("BUG: must not add entries after the start of entries traversal")
+            .send(Additional::Stream {
+                prefix: prefix.into(),
+                read,
+                err: stream.err,
+            })
             .expect("Failure is impossible as thread blocks on the receiving end");
         self
     }
@@ -171,13 +218,14 @@ impl Stream {
     pub(crate) fn new() -> (
         Stream,
         gix_features::io::pipe::Writer,
-        std::sync::mpsc::Receiver<AdditionalEntry>,
+        std::sync::mpsc::Receiver<Additional>,
     ) {
         // 1 write for entry header and 1 for hash, 1 for entry path, + 1 for a buffer, then 32 of these.
         // Giving some buffer, at the expense of memory, is important to allow consumers to take off bytes more quickly,
         // otherwise, both threads effectively run in lock-step and nullify the benefit.
+        // Chunks are bounded in size so large blobs don't have to be copied into the pipe all at once.
         let in_flight_writes = (2 + 1) * 32;
-        let (write, read) = gix_features::io::pipe::unidirectional(in_flight_writes);
+        let (write, read) = gix_features::io::pipe::unidirectional_bounded(in_flight_writes, u16::MAX as usize);
         let (tx_entries, rx_entries) = std::sync::mpsc::channel();
         (
             Stream {
@@ -46,12 +46,8 @@ pub(crate) fn write_entry_header_and_path(
     bytes[1] = hash_to_byte(oid.kind());
     bytes[2..][..oid.kind().len_in_bytes()].copy_from_slice(oid.as_bytes());
 
-    // We know how `out` works in a pipe writer, it's always writing everything.
-    #[allow(clippy::unused_io_amount)]
-    {
-        out.write(&buf[..HEADER_LEN + oid.kind().len_in_bytes()])?;
-        out.write(path)?;
-    }
+    out.write_all(&buf[..HEADER_LEN + oid.kind().len_in_bytes()])?;
+    out.write_all(path)?;
     Ok(())
 }
 
@@ -65,18 +61,16 @@ pub(crate) fn write_stream(
     const BUF_LEN: usize = u16::MAX as usize;
     clear_and_set_len(buf, BUF_LEN)?;
 
-    // We know how `out` works in a pipe writer, it's always writing everything.
-    #[allow(clippy::unused_io_amount)]
     loop {
         match input.read(buf) {
             Ok(0) => {
                 // terminator
-                out.write(&0_u16.to_le_bytes())?;
+                out.write_all(&0_u16.to_le_bytes())?;
                 break;
             }
             Ok(n) => {
-                out.write(&(n as u16).to_le_bytes())?;
-                out.write(&buf[..n])?;
+                out.write_all(&(n as u16).to_le_bytes())?;
+                out.write_all(&buf[..n])?;
             }
             Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
             Err(e) => return Err(e),
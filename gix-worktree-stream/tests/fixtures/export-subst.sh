@@ -0,0 +1,22 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init
+
+cat <<'CONTENT' > subst
+hashes: $Format:%H %h %T %t %P %p$
+author: $Format:%an <%ae> %ad|%aD|%at|%ai|%aI$
+committer: $Format:%cn <%ce> %cd|%cD|%ct|%ci|%cI$
+message: $Format:%s|%b|%B$
+verbatim: $Format:%Q %% %n%$
+unterminated: $Format:%H
+CONTENT
+cp subst no-subst
+echo "/subst export-subst" > .gitattributes
+
+git add .
+git commit -m "first"
+git commit --allow-empty -m "second" -m "with body"
+
+git rev-parse @ > head.hex
+git archive HEAD subst no-subst | tar -x --to-stdout subst > subst.expected
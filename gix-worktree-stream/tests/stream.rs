@@ -235,8 +235,189 @@ mod from_tree {
         Ok(())
     }
 
+    #[test]
+    fn export_subst_expands_placeholders_like_git_if_created_from_commit() -> gix_testtools::Result {
+        let (dir, head_commit, odb, mut cache) = fixture("export-subst.sh")?;
+        let mut stream = gix_worktree_stream::from_commit(
+            head_commit,
+            odb.clone(),
+            gix_filter::Pipeline::default(),
+            move |rela_path, mode, attrs| {
+                cache
+                    .at_entry(rela_path, mode.is_tree().into(), &odb)
+                    .map(|entry| entry.matching_attributes(attrs))
+                    .map(|_| ())
+            },
+        );
+
+        let expected = std::fs::read(dir.join("subst.expected"))?;
+        let mut paths = Vec::new();
+        while let Some(mut entry) = stream.next_entry().expect("entry retrieval does not fail") {
+            let mut buf = Vec::new();
+            entry.read_to_end(&mut buf)?;
+            let path = entry.relative_path().to_owned();
+            if path == "subst" {
+                assert_eq!(
+                    buf.as_bstr(),
+                    expected.as_bstr(),
+                    "placeholders are expanded just like git does it"
+                );
+            } else if path == "no-subst" {
+                assert!(
+                    buf.find(b"$Format:%H %h").is_some(),
+                    "without the attribute, nothing is expanded"
+                );
+            }
+            paths.push(path);
+        }
+        assert_eq!(paths, [".gitattributes", "no-subst", "subst"]);
+        Ok(())
+    }
+
+    #[test]
+    fn export_subst_has_no_effect_without_commit() -> gix_testtools::Result {
+        let (_dir, head_commit, odb, mut cache) = fixture("export-subst.sh")?;
+        let mut buf = Vec::new();
+        let tree = gix_object::FindExt::find_commit(&odb, &head_commit, &mut buf)?.tree();
+        let mut stream = gix_worktree_stream::from_tree(
+            tree,
+            odb.clone(),
+            mutating_pipeline(false),
+            move |rela_path, mode, attrs| {
+                cache
+                    .at_entry(rela_path, mode.is_tree().into(), &odb)
+                    .map(|entry| entry.matching_attributes(attrs))
+                    .map(|_| ())
+            },
+        );
+
+        while let Some(mut entry) = stream.next_entry().expect("entry retrieval does not fail") {
+            buf.clear();
+            entry.read_to_end(&mut buf)?;
+            if entry.relative_path() == "subst" {
+                assert!(buf.find(b"$Format:%H %h").is_some(), "there is nothing to expand with");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn streams_can_be_added_with_prefix_along_with_their_additional_entries() -> gix_testtools::Result {
+        let (_dir, head_commit, odb, cache) = fixture("export-subst.sh")?;
+        let new_stream = |mut cache: gix_worktree::Stack| {
+            let odb = odb.clone();
+            gix_worktree_stream::from_commit(
+                head_commit,
+                odb.clone(),
+                gix_filter::Pipeline::default(),
+                move |rela_path, mode, attrs| {
+                    cache
+                        .at_entry(rela_path, mode.is_tree().into(), &odb)
+                        .map(|entry| entry.matching_attributes(attrs))
+                        .map(|_| ())
+                },
+            )
+        };
+        let mut stream = new_stream(cache.clone());
+        let mut nested = new_stream(cache);
+        nested.add_entry(gix_worktree_stream::AdditionalEntry {
+            id: gix_hash::Kind::Sha1.null(),
+            mode: EntryKind::Blob.into(),
+            relative_path: "virtual".into(),
+            source: gix_worktree_stream::entry::Source::Memory(b"content".to_vec()),
+        });
+        stream.add_stream("sub/", nested);
+
+        let mut entries = Vec::new();
+        let mut buf = Vec::new();
+        while let Some(mut entry) = stream.next_entry().expect("entry retrieval does not fail") {
+            buf.clear();
+            entry.read_to_end(&mut buf)?;
+            entries.push((entry.relative_path().to_owned(), buf.clone()));
+        }
+        assert_eq!(
+            entries.iter().map(|(path, _)| path.to_string()).collect::<Vec<_>>(),
+            [
+                ".gitattributes",
+                "no-subst",
+                "subst",
+                "sub/.gitattributes",
+                "sub/no-subst",
+                "sub/subst",
+                "sub/virtual"
+            ]
+        );
+        for (path, content) in &entries[..3] {
+            let nested_content = &entries
+                .iter()
+                .find(|(nested_path, _)| nested_path.strip_prefix(b"sub/") == Some(path.as_slice()))
+                .expect("present")
+                .1;
+            assert_eq!(content, nested_content, "nested entries are transferred losslessly");
+        }
+        assert_eq!(entries[6].1.as_bstr(), "content");
+        Ok(())
+    }
+
+    #[test]
+    fn large_entries_are_transferred_losslessly() -> gix_testtools::Result {
+        let (_dir, head_tree, odb, _cache) = basic()?;
+        let mut stream = gix_worktree_stream::from_tree(
+            head_tree,
+            odb,
+            mutating_pipeline(false),
+            |_, _, _| -> Result<_, Infallible> { Ok(()) },
+        );
+        let large: Vec<u8> = (0..1024 * 1024).map(|n| (n % 251) as u8).collect();
+        for (path, source) in [
+            ("large", gix_worktree_stream::entry::Source::Memory(large.clone())),
+            ("empty", gix_worktree_stream::entry::Source::Null),
+        ] {
+            stream.add_entry(gix_worktree_stream::AdditionalEntry {
+                id: gix_hash::Kind::Sha1.null(),
+                mode: EntryKind::Blob.into(),
+                relative_path: path.into(),
+                source,
+            });
+        }
+
+        let mut entries = Vec::new();
+        while let Some(mut entry) = stream.next_entry().expect("entry retrieval does not fail") {
+            let mut buf = Vec::new();
+            entry.read_to_end(&mut buf)?;
+            entries.push((entry.relative_path().to_owned(), buf));
+        }
+        let (_, content) = entries.iter().find(|(path, _)| path == "large").expect("present");
+        assert!(
+            content == &large,
+            "the entry is larger than what's sent through the pipe at once, but arrives in full"
+        );
+        let (_, content) = entries.iter().find(|(path, _)| path == "empty").expect("present");
+        assert!(content.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "BUG: streams must be created from a tree")]
+    fn streams_read_from_bytes_cannot_be_added() {
+        let (_dir, head_tree, odb, _cache) = basic().unwrap();
+        let mut stream = gix_worktree_stream::from_tree(
+            head_tree,
+            odb,
+            mutating_pipeline(false),
+            |_, _, _| -> Result<_, Infallible> { Ok(()) },
+        );
+        stream.add_stream("sub/", gix_worktree_stream::Stream::from_read(std::io::empty()));
+    }
+
     fn basic() -> gix_testtools::Result<(PathBuf, gix_hash::ObjectId, gix_odb::HandleArc, gix_worktree::Stack)> {
-        let dir = gix_testtools::scripted_fixture_read_only("basic.sh")?;
+        fixture("basic.sh")
+    }
+
+    fn fixture(
+        name: &str,
+    ) -> gix_testtools::Result<(PathBuf, gix_hash::ObjectId, gix_odb::HandleArc, gix_worktree::Stack)> {
+        let dir = gix_testtools::scripted_fixture_read_only(name)?;
 
         let head = {
             let hex = std::fs::read(dir.join("head.hex"))?;
@@ -0,0 +1,53 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const THREADS: usize = 4;
+
+/// Read the commits of this repository's history from multiple threads at once, either through a thread-local repository per
+/// thread or directly through the thread-safe repository.
+fn objects(c: &mut Criterion) {
+    let sync = gix::discover(env!("CARGO_MANIFEST_DIR")).unwrap().into_sync();
+    let ids = sync
+        .to_thread_local()
+        .head_id()
+        .unwrap()
+        .ancestors()
+        .all()
+        .unwrap()
+        .take(1000)
+        .map(|info| info.unwrap().id)
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("find commits in parallel");
+    group.throughput(criterion::Throughput::Elements((ids.len() * THREADS) as u64));
+    group.bench_function("thread-local repository", |b| {
+        b.iter(|| {
+            std::thread::scope(|s| {
+                for _ in 0..THREADS {
+                    s.spawn(|| {
+                        let repo = sync.to_thread_local();
+                        for id in &ids {
+                            repo.find_object(*id).unwrap();
+                        }
+                    });
+                }
+            })
+        })
+    });
+    group.bench_function("thread-safe repository", |b| {
+        b.iter(|| {
+            std::thread::scope(|s| {
+                for _ in 0..THREADS {
+                    s.spawn(|| {
+                        for id in &ids {
+                            sync.find_object(*id).unwrap();
+                        }
+                    });
+                }
+            })
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, objects);
+criterion_main!(benches);
@@ -0,0 +1,36 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/// Open a freshly initialized repository with a global configuration file that is large, as it's common for users
+/// to accumulate aliases and other settings over time.
+fn open(c: &mut Criterion) {
+    let tmp = gix_testtools::tempfile::tempdir().unwrap();
+    let global_config = tmp.path().join("global.config");
+    let mut config = String::from("[user]\n\tname = name\n\temail = name@example.com\n[alias]\n");
+    for idx in 0..500 {
+        config.push_str(&format!("\talias{idx} = log --oneline --graph --decorate -n {idx}\n"));
+    }
+    std::fs::write(&global_config, config).unwrap();
+    std::env::set_var("GIT_CONFIG_GLOBAL", &global_config);
+    std::env::set_var("GIT_CONFIG_NOSYSTEM", "1");
+    let repo_dir = gix::init(tmp.path().join("repo")).unwrap().git_dir().to_owned();
+
+    let mut group = c.benchmark_group("open");
+    group.bench_function("default", |b| {
+        b.iter(|| gix::open_opts(&repo_dir, gix::open::Options::default()).unwrap())
+    });
+    group.bench_function("lightweight", |b| {
+        b.iter(|| gix::open_opts(&repo_dir, gix::open::Options::lightweight()).unwrap())
+    });
+    group.bench_function("lightweight and read HEAD", |b| {
+        b.iter(|| {
+            gix::open_opts(&repo_dir, gix::open::Options::lightweight())
+                .unwrap()
+                .head_name()
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, open);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/// Decode all commits of a long history, either by looking up each commit returned by the walk, or by letting the walk
+/// prefetch them, with and without a commit-graph.
+fn revision_walk(c: &mut Criterion) {
+    let repo = gix::open_opts(
+        gix_testtools::scripted_fixture_read_only("make_long_history_repo.sh").unwrap(),
+        gix::open::Options::isolated(),
+    )
+    .unwrap();
+    let head = repo.head_id().unwrap().detach();
+    let num_commits = repo.rev_walk(Some(head)).all().unwrap().count();
+
+    let mut group = c.benchmark_group("decode all commits of a walk");
+    group.throughput(criterion::Throughput::Elements(num_commits as u64));
+    for use_commit_graph in [false, true] {
+        let walk = || {
+            repo.rev_walk(Some(head))
+                .use_commit_graph(use_commit_graph)
+                .all()
+                .unwrap()
+        };
+        group.bench_function(format!("find each commit (commit-graph: {use_commit_graph})"), |b| {
+            b.iter(|| {
+                for info in walk() {
+                    let commit = repo.find_object(info.unwrap().id).unwrap().into_commit();
+                    let commit: gix::objs::Commit = commit.decode().unwrap().into();
+                    criterion::black_box(commit);
+                }
+            })
+        });
+        group.bench_function(format!("prefetch (commit-graph: {use_commit_graph})"), |b| {
+            b.iter(|| {
+                for item in walk().prefetch(64, None) {
+                    criterion::black_box(item.unwrap());
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, revision_walk);
+criterion_main!(benches);
@@ -0,0 +1,152 @@
+//! Apply patches to the worktree, the index or trees, see [`Repository::apply_patch()`](crate::Repository::apply_patch()),
+//! and commit patches received as mail, see [`Repository::am()`](crate::Repository::am()).
+pub use gix_apply::*;
+
+use crate::bstr::BString;
+
+/// Determine where a patch is applied by [`Repository::apply_patch()`](crate::Repository::apply_patch()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Location {
+    /// Patch the files in the worktree, leaving the index untouched, which is the default like with `git apply`.
+    ///
+    /// Files are read and written as they are, without applying filters like line-ending conversions.
+    #[default]
+    Worktree,
+    /// Patch the entries in the index, leaving the worktree untouched, like `git apply --cached`.
+    Index,
+    /// Patch the entries in the index and bring the changes into the worktree, like `git apply --index`.
+    ///
+    /// The files touched by the patch must not have changes in the worktree compared to the index.
+    IndexAndWorktree,
+}
+
+/// Options for use in [`Repository::apply_patch()`](crate::Repository::apply_patch()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// Where to apply the patch.
+    pub location: Location,
+    /// Options to control how hunks are applied to each file.
+    pub blob: blob::Options,
+    /// If `true`, hunks that don't apply to a file are merged with it instead, using the version of the file the patch was
+    /// created for as base, like `git apply --3way`. That version has to be present in the object database.
+    ///
+    /// This requires the index, so [`Location::Worktree`] is treated like [`Location::IndexAndWorktree`].
+    pub three_way: bool,
+    /// Options to use when merging files if `three_way` is enabled.
+    pub merge: crate::merge::blob::Options,
+}
+
+/// The outcome of [`Repository::apply_patch()`](crate::Repository::apply_patch()).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The files that couldn't be merged without conflicts if [`Options::three_way`] is enabled.
+    ///
+    /// The index contains all versions of these files, and the worktree contains the merged content with conflict markers.
+    pub conflicts: Vec<crate::merge::tree::Conflict>,
+}
+
+/// The error returned by [`Repository::apply_patch()`](crate::Repository::apply_patch())
+/// and [`Repository::apply_patch_to_tree()`](crate::Repository::apply_patch_to_tree()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A worktree is required to apply patches to it")]
+    BareRepository,
+    #[error("Could not apply the patch to '{path}'")]
+    Apply { path: BString, source: blob::Error },
+    #[error("The file '{path}' to patch doesn't exist")]
+    Missing { path: BString },
+    #[error("The file '{path}' to create exists already")]
+    AlreadyExists { path: BString },
+    #[error("The file '{path}' has changes in the worktree compared to the index")]
+    Dirty { path: BString },
+    #[error("The blob {id} to merge '{path}' with isn't present in the object database")]
+    MissingBase { path: BString, id: gix_hash::Prefix },
+    #[error("The patch couldn't be applied without conflicts, the first of {} being at '{}'", .paths.len(), .paths[0])]
+    Conflicts { paths: Vec<BString> },
+    #[error("Could not access '{}' in the worktree", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Worktree(#[from] crate::merge::worktree::require_clean::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::merge::worktree::checkout::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error("Could not create an index from the tree at {id}")]
+    IndexFromTree {
+        id: gix_hash::ObjectId,
+        source: gix_traverse::tree::breadthfirst::Error,
+    },
+    #[error(transparent)]
+    LookupPrefix(#[from] gix_odb::store::prefix::lookup::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    WriteTree(#[from] gix_index::write_tree::Error<crate::object::write::Error>),
+}
+
+///
+pub mod options {
+    /// The error returned by [`Repository::apply_options()`](crate::Repository::apply_options()).
+    pub type Error = crate::config::key::GenericErrorWithValue;
+}
+
+///
+pub mod am {
+    use gix_hash::ObjectId;
+
+    /// The outcome of [`Repository::am()`](crate::Repository::am()).
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The newly created commit that `HEAD` now points to, or `None` if there were conflicts.
+        pub commit: Option<ObjectId>,
+        /// All conflicts that have to be resolved in the worktree and the index before the changes can be committed,
+        /// which can only happen if [`Options::three_way`](super::Options::three_way) is enabled.
+        pub conflicts: Vec<crate::merge::tree::Conflict>,
+    }
+
+    /// The error returned by [`Repository::am()`](crate::Repository::am()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot apply a mail while another operation is in progress: {state:?}")]
+        InProgress { state: crate::state::InProgress },
+        #[error("The changes of the mail are already present, there is nothing to commit")]
+        Empty,
+        #[error("Committer identity is not configured")]
+        CommitterMissing,
+        #[error(transparent)]
+        ParsePatch(#[from] crate::apply::patch::parse::Error),
+        #[error(transparent)]
+        Apply(#[from] super::Error),
+        #[error(transparent)]
+        RequireClean(#[from] crate::merge::worktree::require_clean::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToCommit(#[from] crate::object::try_into::Error),
+        #[error(transparent)]
+        CommitTree(#[from] crate::object::commit::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        WriteTree(#[from] gix_index::write_tree::Error<crate::object::write::Error>),
+        #[error(transparent)]
+        WriteObject(#[from] crate::object::write::Error),
+        #[error(transparent)]
+        ParseTime(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+    }
+}
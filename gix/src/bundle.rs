@@ -0,0 +1,248 @@
+pub use gix_bundle::{is_bundle, Capability, Header, Prerequisite, Ref, Version, SIGNATURE_V2, SIGNATURE_V3};
+
+///
+pub mod create {
+    use gix_hash::ObjectId;
+
+    /// Options for use in [`Repository::create_bundle()`](crate::Repository::create_bundle()).
+    #[derive(Debug, Clone, Default)]
+    pub struct Options {
+        /// The version of the bundle to write.
+        pub version: crate::bundle::Version,
+        /// Commits that the receiving repository is expected to have, similar to `^<commit>` in `git bundle create`.
+        ///
+        /// These commits, their ancestors and the trees and blobs of these commits won't be contained in the bundle,
+        /// which is how incremental bundles are created. If empty, the bundle contains all objects reachable from its references.
+        pub prerequisites: Vec<ObjectId>,
+    }
+
+    /// The outcome of [`Repository::create_bundle()`](crate::Repository::create_bundle()).
+    #[derive(Debug, Clone)]
+    pub struct Outcome {
+        /// The header that was written.
+        pub header: crate::bundle::Header,
+        /// The amount of objects in the pack.
+        pub num_objects: usize,
+    }
+
+    /// The error returned by [`Repository::create_bundle()`](crate::Repository::create_bundle()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        FollowReference(#[from] gix_ref::file::find::existing::Error),
+        #[error("The reference '{}' doesn't point to an object", name.as_bstr())]
+        UnbornReference { name: gix_ref::FullName },
+        #[error("The prerequisite {id} is not a commit")]
+        PrerequisiteNotACommit { id: ObjectId },
+        #[error(transparent)]
+        FindExistingObject(#[from] gix_object::find::existing::Error),
+        #[error(transparent)]
+        FindObject(#[from] gix_object::find::Error),
+        #[error(transparent)]
+        DecodeObject(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        CountObjects(#[from] gix_pack::data::output::count::objects::Error),
+        #[error(transparent)]
+        CreatePackEntries(#[from] gix_pack::data::output::entry::iter_from_counts::Error),
+        #[error(transparent)]
+        WritePackData(
+            #[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>,
+        ),
+        #[error(transparent)]
+        Lock(#[from] gix_lock::acquire::Error),
+        #[error(transparent)]
+        Commit(#[from] gix_lock::commit::Error<gix_lock::File>),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error("Interrupted")]
+        Interrupted,
+    }
+}
+
+///
+pub mod unbundle {
+    use std::path::PathBuf;
+
+    use gix_hash::ObjectId;
+
+    /// Options for use in [`Repository::unbundle()`](crate::Repository::unbundle()).
+    #[derive(Debug, Clone, Default)]
+    pub struct Options {
+        /// Fetch refspecs to map the references of the bundle to local references, like `+refs/heads/*:refs/remotes/origin/*`.
+        ///
+        /// References that don't match any refspec are not written, and if there are no refspecs, only the objects are stored.
+        pub refspecs: Vec<gix_refspec::RefSpec>,
+        /// The message to use in the reference log of all updated references, or `None` to use a message
+        /// naming the bundle.
+        pub reflog_message: Option<crate::bstr::BString>,
+    }
+
+    /// The outcome of [`Repository::unbundle()`](crate::Repository::unbundle()).
+    #[derive(Debug, Clone)]
+    pub struct Outcome {
+        /// The header of the bundle.
+        pub header: crate::bundle::Header,
+        /// Information about the pack that was stored in the object database.
+        pub pack: gix_pack::bundle::write::Outcome,
+        /// One update for each mapping of a reference of the bundle to a local reference, in the order of the bundle references.
+        pub updates: Vec<Update>,
+    }
+
+    /// Information about the update of a local reference from a reference of a bundle.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Update {
+        /// The name of the reference in the bundle.
+        pub source: crate::bstr::BString,
+        /// The name of the local reference.
+        pub name: gix_ref::FullName,
+        /// The id the local reference pointed to before, if it existed.
+        pub previous: Option<ObjectId>,
+        /// The id of the reference in the bundle.
+        pub new: ObjectId,
+        /// How the local reference was updated, or why it wasn't.
+        pub mode: Mode,
+    }
+
+    /// Describe how a local reference was updated from a reference of a bundle, similar to how references are updated when fetching.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Mode {
+        /// The local reference already pointed to the object of the bundle reference.
+        NoChangeNeeded,
+        /// The local reference was an ancestor of the bundle reference and was moved forward.
+        FastForward,
+        /// The local reference was set without considering its ancestry as the refspec allows it.
+        Forced,
+        /// The local reference was created.
+        New,
+        /// The local reference is a tag that would have changed, which is only possible if the refspec allows it.
+        RejectedTagUpdate,
+        /// The update wouldn't have been a fast-forward and the refspec doesn't allow forcing it.
+        RejectedNonFastForward,
+    }
+
+    impl Mode {
+        /// Return `true` if the local reference wasn't changed even though it should have been.
+        pub fn is_rejected(&self) -> bool {
+            matches!(self, Mode::RejectedTagUpdate | Mode::RejectedNonFastForward)
+        }
+    }
+
+    impl std::fmt::Display for Mode {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Mode::NoChangeNeeded => "up-to-date",
+                Mode::FastForward => "fast-forward",
+                Mode::Forced => "forced-update",
+                Mode::New => "new",
+                Mode::RejectedTagUpdate => "rejected (would overwrite existing tag)",
+                Mode::RejectedNonFastForward => "rejected (non-fast-forward)",
+            }
+            .fmt(f)
+        }
+    }
+
+    /// The error returned by [`Repository::unbundle()`](crate::Repository::unbundle()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open the bundle at '{}'", path.display())]
+        Open { path: PathBuf, source: std::io::Error },
+        #[error(transparent)]
+        DecodeHeader(#[from] gix_bundle::decode::Error),
+        #[error("The bundle uses {actual} objects, but the repository uses {expected}")]
+        ObjectHashMismatch {
+            expected: gix_hash::Kind,
+            actual: gix_hash::Kind,
+        },
+        #[error("The bundle requires the commit {id} which isn't present in the repository")]
+        MissingPrerequisite { id: ObjectId },
+        #[error("The bundle contains the invalid reference name '{name}'")]
+        InvalidReferenceName {
+            name: crate::bstr::BString,
+            source: gix_validate::reference::name::Error,
+        },
+        #[error(transparent)]
+        WritePackBundle(#[from] gix_pack::bundle::write::Error),
+        #[error(transparent)]
+        ValidateRefspecs(#[from] gix_refspec::match_group::validate::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        PeelReference(#[from] crate::reference::peel::Error),
+        #[error(transparent)]
+        EditReferences(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+
+    impl crate::error::ErrorCode for Error {
+        fn code(&self) -> crate::error::Code {
+            use crate::error::{self, Code};
+            match self {
+                Error::Open { source: err, .. } | Error::Io(err) => error::io(err),
+                Error::DecodeHeader(_) | Error::MissingPrerequisite { .. } => Code::ObjectCorrupt,
+                Error::ObjectHashMismatch { .. } | Error::InvalidReferenceName { .. } | Error::ValidateRefspecs(_) => {
+                    Code::InvalidInput
+                }
+                Error::WritePackBundle(err) => error::write_pack(err),
+                Error::FindReference(_) | Error::PeelReference(_) => Code::ObjectCorrupt,
+                Error::EditReferences(err) => error::reference_edit(err),
+            }
+        }
+    }
+}
+
+///
+pub mod list {
+    pub use gix_bundle::list::{Bundle, List, Mode};
+
+    /// The refspec `git` uses to make the branches of bundles from a bundle list available as `refs/bundles/*`.
+    pub const REFSPEC: &str = "+refs/heads/*:refs/bundles/*";
+
+    /// Options for use in [`Repository::unbundle_list()`](crate::Repository::unbundle_list()).
+    #[derive(Debug, Clone, Default)]
+    pub struct Options {
+        /// The refspecs to map the references of each bundle to local references, see [`REFSPEC`] for what `git` uses.
+        ///
+        /// If empty, only the objects are stored.
+        pub refspecs: Vec<gix_refspec::RefSpec>,
+        /// The filter the repository was cloned with, if it is a partial clone.
+        ///
+        /// Bundles that were created with a different filter are skipped.
+        pub filter: Option<crate::bstr::BString>,
+    }
+
+    /// The outcome of [`Repository::unbundle_list()`](crate::Repository::unbundle_list()).
+    #[derive(Debug, Clone, Default)]
+    pub struct Outcome {
+        /// The bundles that were stored in the repository, in the order in which that happened, along with the outcome of unbundling them.
+        pub unbundled: Vec<(Bundle, crate::bundle::unbundle::Outcome)>,
+        /// The bundles that were skipped as they couldn't be downloaded or applied, or as they weren't needed.
+        pub skipped: Vec<Bundle>,
+    }
+
+    /// The error returned by [`Repository::unbundle_list()`](crate::Repository::unbundle_list()).
+    ///
+    /// Note that bundles that can't be downloaded or applied are skipped instead of causing an error.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not create a temporary file to download a bundle into")]
+        CreateTempfile(#[from] std::io::Error),
+        #[error("Interrupted")]
+        Interrupted,
+    }
+
+    impl crate::error::ErrorCode for Error {
+        fn code(&self) -> crate::error::Code {
+            use crate::error::{self, Code};
+            match self {
+                Error::CreateTempfile(err) => error::io(err),
+                Error::Interrupted => Code::Interrupted,
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use gix_hash::ObjectId;
+use gix_ref::FullName;
+
+use crate::bstr::BString;
+
+/// Options for use in [`Repository::checkout_tree()`](crate::Repository::checkout_tree()) and [`Repository::switch()`](crate::Repository::switch()).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// If `true`, discard all changes in the index and the worktree, and overwrite untracked files that are in the way,
+    /// similar to `git checkout --force`.
+    ///
+    /// Otherwise, local changes to files that don't change between `HEAD` and the new tree are kept, and it's an error
+    /// if local changes or untracked files would be overwritten.
+    pub force: bool,
+}
+
+/// Options for use in [`Repository::restore()`](crate::Repository::restore()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoreOptions {
+    /// The tree-ish to restore files from, or `None` to restore from the index, or from `HEAD` if `staged` is `true`.
+    pub source: Option<ObjectId>,
+    /// If `true`, restore the matching entries of the index, similar to `git restore --staged`.
+    pub staged: bool,
+    /// If `true`, restore the matching files in the worktree, similar to `git restore --worktree`.
+    pub worktree: bool,
+}
+
+impl Default for RestoreOptions {
+    fn default() -> Self {
+        RestoreOptions {
+            source: None,
+            staged: false,
+            worktree: true,
+        }
+    }
+}
+
+/// The error returned by [`Repository::checkout_tree()`](crate::Repository::checkout_tree()) and related methods.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A worktree is required to perform this operation")]
+    BareRepository,
+    #[error("Local changes to {} file(s) would be overwritten, the first one being '{}'", .paths.len(), .paths[0])]
+    LocalChanges { paths: Vec<BString> },
+    #[error("Untracked file(s) would be overwritten, the first of {} being at '{}'", .paths.len(), .paths[0])]
+    UntrackedFiles { paths: Vec<BString> },
+    #[error("The index has conflicts that need to be resolved first, the first of {} being at '{}'", .paths.len(), .paths[0])]
+    UnresolvedConflicts { paths: Vec<BString> },
+    #[error("'{}' is not a local branch", name.as_bstr())]
+    NotABranch { name: FullName },
+    #[error("No path in the index or the source matched the given pathspecs")]
+    NoMatch,
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelHead(#[from] crate::head::peel::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    CommitterTime(#[from] crate::config::time::Error),
+    #[error("Could not append to the reflog of HEAD")]
+    Reflog(#[from] std::io::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToTree(#[from] crate::object::peel::to_kind::Error),
+    #[error("Could not create an index from the tree at {id}")]
+    IndexFromTree {
+        id: ObjectId,
+        source: gix_traverse::tree::breadthfirst::Error,
+    },
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    WorktreeChanges(#[from] crate::merge::worktree::require_clean::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::merge::worktree::checkout::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+}
+
+impl crate::error::ErrorCode for Error {
+    fn code(&self) -> crate::error::Code {
+        use crate::error::{self, Code};
+        match self {
+            Error::BareRepository => Code::WorktreeRequired,
+            Error::NotABranch { .. } | Error::NoMatch | Error::Pathspec(_) => Code::InvalidInput,
+            Error::LocalChanges { .. } | Error::UntrackedFiles { .. } => Code::WouldOverwriteLocalChanges,
+            Error::UnresolvedConflicts { .. } => Code::Other,
+            Error::FindReference(_)
+            | Error::PeelHead(_)
+            | Error::PeelReference(_)
+            | Error::FindObject(_)
+            | Error::PeelToTree(_)
+            | Error::IndexFromTree { .. } => Code::ObjectCorrupt,
+            Error::EditReference(err) => error::reference_edit(err),
+            Error::CommitterTime(_) => Code::Configuration,
+            Error::Reflog(err) => error::io(err),
+            Error::OpenIndex(err) => match err {
+                crate::worktree::open_index::Error::ConfigIndexThreads(_)
+                | crate::worktree::open_index::Error::ConfigSkipHash(_) => Code::Configuration,
+                crate::worktree::open_index::Error::IndexFile(gix_index::file::init::Error::Io(err)) => error::io(err),
+                crate::worktree::open_index::Error::IndexFile(_)
+                | crate::worktree::open_index::Error::IndexCorrupt(_) => Code::ObjectCorrupt,
+            },
+            Error::WorktreeChanges(err) => err.code(),
+            Error::Checkout(err) => err.code(),
+            Error::WriteIndex(err) => error::index_write(err),
+        }
+    }
+}
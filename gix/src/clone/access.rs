@@ -35,6 +35,16 @@ impl PrepareFetch {
         self
     }
 
+    /// Make this clone a partial one by fetching only objects that pass the given `filter`, like `blob:none` to not fetch
+    /// any blobs, or `blob:limit=1m` to not fetch blobs larger than a megabyte.
+    ///
+    /// The remote is configured as promisor remote, and missing objects will be fetched from it on demand, for example
+    /// when [finding objects](Repository::find_object()) or when checking out a worktree.
+    pub fn with_filter(mut self, filter: impl Into<BString>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
     /// Apply the given configuration `values` right before readying the actual fetch from the remote.
     /// The configuration is marked with [source API](gix_config::Source::Api), and will not be written back, it's
     /// retained only in memory.
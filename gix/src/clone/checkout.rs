@@ -31,6 +31,27 @@ pub mod main_worktree {
         FindHead(#[from] crate::reference::find::existing::Error),
         #[error("The HEAD reference could not be located")]
         PeelHeadToId(#[from] crate::head::peel::Error),
+        #[cfg(feature = "blocking-network-client")]
+        #[error("Could not fetch the objects to check out from the promisor remote")]
+        FetchMissingObjects(#[from] crate::promisor::Error),
+    }
+
+    impl crate::error::ErrorCode for Error {
+        fn code(&self) -> crate::error::Code {
+            use crate::error::{self, Code};
+            match self {
+                Error::BareRepository { .. } => Code::WorktreeRequired,
+                Error::NoHeadTree(_) | Error::IndexFromTree { .. } | Error::FindHead(_) | Error::PeelHeadToId(_) => {
+                    Code::ObjectCorrupt
+                }
+                Error::WriteIndex(err) => error::index_write(err),
+                Error::CheckoutOptions(_) => Code::Configuration,
+                Error::IndexCheckout(err) => error::worktree_checkout(err),
+                Error::OpenArcOdb(err) => error::io(err),
+                #[cfg(feature = "blocking-network-client")]
+                Error::FetchMissingObjects(_) => Code::Network,
+            }
+        }
     }
 
     /// The progress ids used in [`PrepareCheckout::main_worktree()`].
@@ -99,6 +120,20 @@ pub mod main_worktree {
                 source: err,
             })?;
             let mut index = gix_index::File::from_state(index, repo.index_path());
+            #[cfg(feature = "blocking-network-client")]
+            if repo.promisor_remote_name().is_some() {
+                // Obtain all blobs at once instead of fetching them one by one while checking them out.
+                repo.fetch_missing_objects_inner(
+                    index
+                        .entries()
+                        .iter()
+                        .filter(|entry| !entry.mode.is_submodule())
+                        .map(|entry| entry.id)
+                        .collect(),
+                    &mut *progress,
+                    should_interrupt,
+                )?;
+            }
 
             let mut opts = repo
                 .config
@@ -35,6 +35,30 @@ pub enum Error {
     },
     #[error("Failed to update HEAD with values from remote")]
     HeadUpdate(#[from] crate::reference::edit::Error),
+    #[cfg(feature = "bundle")]
+    #[error(transparent)]
+    Unbundle(#[from] crate::bundle::unbundle::Error),
+}
+
+impl crate::error::ErrorCode for Error {
+    fn code(&self) -> crate::error::Code {
+        use crate::error::{self, Code};
+        match self {
+            Error::Connect(err) => err.code(),
+            Error::PrepareFetch(err) => err.code(),
+            Error::Fetch(err) => err.code(),
+            Error::RemoteInit(_) | Error::RemoteName(_) => Code::InvalidInput,
+            Error::RemoteConfiguration(_) | Error::RemoteConnection(_) => Code::Other,
+            Error::ParseConfig(_) | Error::ApplyConfig(_) | Error::LoadConfig(_) | Error::SaveConfig(_) => {
+                Code::Configuration
+            }
+            Error::SaveConfigIo(err) => error::io(err),
+            Error::InvalidHeadRef { .. } => Code::Protocol,
+            Error::HeadUpdate(err) => error::reference_edit(err),
+            #[cfg(feature = "bundle")]
+            Error::Unbundle(err) => err.code(),
+        }
+    }
 }
 
 /// Modification
@@ -109,8 +133,17 @@ impl PrepareFetch {
         } else {
             clone_fetch_tags = remote::fetch::Tags::All.into();
         }
+        if let Some(filter) = self.filter.as_ref() {
+            remote = remote.with_partial_clone_filter(filter.clone());
+        }
 
-        let config = util::write_remote_to_local_config_file(&mut remote, remote_name.clone())?;
+        let mut config = util::write_remote_to_local_config_file(&mut remote, remote_name.clone())?;
+        if remote.partial_clone_filter().is_some() {
+            config.append(util::write_partial_clone_extension_to_local_config_file(
+                repo,
+                remote_name.as_ref(),
+            )?);
+        }
 
         // Now we are free to apply remote configuration we don't want to be written to disk.
         if let Some(fetch_tags) = clone_fetch_tags {
@@ -184,4 +217,125 @@ impl PrepareFetch {
     }
 }
 
+/// Cloning from bundles
+#[cfg(feature = "bundle")]
+impl PrepareFetch {
+    /// Like [`fetch_only()`](Self::fetch_only()), but read objects and references from the bundle file the url points to,
+    /// similar to `git clone <bundle>`.
+    ///
+    /// The remote is configured to point to the bundle, and tags are obtained from it unless the remote was configured
+    /// not to fetch them. `HEAD` is set to the branch the bundle's `HEAD` points to, or remains unborn if the bundle has no `HEAD`.
+    /// Note that all data we created will be removed once this instance drops if the operation wasn't successful.
+    pub fn unbundle_only(
+        &mut self,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+    ) -> Result<(crate::Repository, crate::bundle::unbundle::Outcome), Error> {
+        use crate::{bstr::ByteVec, remote};
+
+        let repo = self
+            .repo
+            .as_mut()
+            .expect("user error: multiple calls are allowed only until it succeeds");
+
+        if !self.config_overrides.is_empty() {
+            let mut snapshot = repo.config_snapshot_mut();
+            snapshot.append_config(&self.config_overrides, gix_config::Source::Api)?;
+            snapshot.commit()?;
+        }
+
+        let remote_name = match self.remote_name.as_ref() {
+            Some(name) => name.to_owned(),
+            None => repo
+                .config
+                .resolved
+                .string("clone", None, crate::config::tree::Clone::DEFAULT_REMOTE_NAME.name)
+                .map(|n| crate::config::tree::Clone::DEFAULT_REMOTE_NAME.try_into_symbolic_name(n))
+                .transpose()?
+                .unwrap_or_else(|| "origin".into()),
+        };
+
+        let mut remote = repo
+            .remote_at(self.url.clone())?
+            .with_refspecs(
+                Some(format!("+refs/heads/*:refs/remotes/{remote_name}/*").as_str()),
+                remote::Direction::Fetch,
+            )
+            .expect("valid static spec");
+        if let Some(f) = self.configure_remote.as_mut() {
+            remote = f(remote).map_err(Error::RemoteConfiguration)?;
+        }
+        let config = util::write_remote_to_local_config_file(&mut remote, remote_name.clone())?;
+        let mut refspecs = remote.refspecs(remote::Direction::Fetch).to_vec();
+        refspecs.extend(remote.fetch_tags().to_refspec().map(|spec| spec.to_owned()));
+
+        let reflog_message = {
+            let mut b = self.url.to_bstring();
+            b.insert_str(0, "clone: from ");
+            b
+        };
+        let outcome = repo.unbundle(
+            gix_path::from_bstr(self.url.path.as_ref()),
+            progress,
+            should_interrupt,
+            crate::bundle::unbundle::Options {
+                refspecs,
+                reflog_message: Some(reflog_message.clone()),
+            },
+        )?;
+
+        util::append_config_to_repo_config(repo, config);
+        util::update_head(
+            repo,
+            &bundle_head(&outcome.header),
+            reflog_message.as_ref(),
+            remote_name.as_ref(),
+        )?;
+
+        Ok((self.repo.take().expect("still present"), outcome))
+    }
+
+    /// Similar to [`unbundle_only()`](Self::unbundle_only()), but passes ownership to a utility type to configure a checkout operation.
+    #[cfg(feature = "worktree-mutation")]
+    pub fn unbundle_then_checkout(
+        &mut self,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+    ) -> Result<(crate::clone::PrepareCheckout, crate::bundle::unbundle::Outcome), Error> {
+        let (repo, outcome) = self.unbundle_only(progress, should_interrupt)?;
+        Ok((crate::clone::PrepareCheckout { repo: repo.into() }, outcome))
+    }
+}
+
+/// Express the `HEAD` of a bundle like a remote would advertise it, as symbolic reference to the first branch that points
+/// to the same commit, or as detached `HEAD` if there is no such branch.
+#[cfg(feature = "bundle")]
+fn bundle_head(header: &crate::bundle::Header) -> Vec<gix_protocol::handshake::Ref> {
+    use crate::bstr::ByteSlice;
+    header
+        .refs
+        .iter()
+        .find(|r| r.name == "HEAD")
+        .map(|head| {
+            match header
+                .refs
+                .iter()
+                .find(|r| r.id == head.id && r.name.starts_with_str("refs/heads/"))
+            {
+                Some(branch) => gix_protocol::handshake::Ref::Symbolic {
+                    full_ref_name: head.name.clone(),
+                    target: branch.name.clone(),
+                    tag: None,
+                    object: head.id,
+                },
+                None => gix_protocol::handshake::Ref::Direct {
+                    full_ref_name: head.name.clone(),
+                    object: head.id,
+                },
+            }
+        })
+        .into_iter()
+        .collect()
+}
+
 mod util;
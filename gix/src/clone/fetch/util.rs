@@ -28,6 +28,27 @@ pub fn write_remote_to_local_config_file(
     Ok(config)
 }
 
+/// Make `repo` a partial clone whose missing objects are provided by the remote named `remote_name`, similar to what
+/// `git clone --filter` does.
+#[allow(clippy::result_large_err)]
+pub fn write_partial_clone_extension_to_local_config_file(
+    repo: &Repository,
+    remote_name: &BStr,
+) -> Result<gix_config::File<'static>, Error> {
+    use crate::config::tree::{Core, Extensions};
+    let mut config = gix_config::File::new(local_config_meta(repo));
+    // Extensions are only honored by `git` if the repository format version indicates it.
+    config
+        .set_raw_value("core", None, Core::REPOSITORY_FORMAT_VERSION.name, "1")
+        .expect("works - statically known");
+    config
+        .set_raw_value("extensions", None, Extensions::PARTIAL_CLONE.name, remote_name)
+        .expect("works - remote names are validated");
+
+    write_to_local_config(&config, WriteMode::Append)?;
+    Ok(config)
+}
+
 fn local_config_meta(repo: &Repository) -> gix_config::file::Metadata {
     let meta = repo.config.resolved.meta().clone();
     assert_eq!(
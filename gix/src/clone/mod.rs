@@ -36,6 +36,9 @@ pub struct PrepareFetch {
     /// How to handle shallow clones
     #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
     shallow: remote::fetch::Shallow,
+    /// The object filter to use for a partial clone, if set.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    filter: Option<BString>,
 }
 
 /// The error returned by [`PrepareFetch::new()`].
@@ -53,6 +56,31 @@ pub enum Error {
     },
 }
 
+impl crate::error::ErrorCode for Error {
+    fn code(&self) -> crate::error::Code {
+        use crate::error::{self, Code};
+        match self {
+            Error::Init(err) => match err {
+                crate::init::Error::CurrentDir(err) => error::io(err),
+                crate::init::Error::Init(err) => match err {
+                    crate::create::Error::DirectoryExists { .. } | crate::create::Error::DirectoryNotEmpty { .. } => {
+                        Code::WouldOverwriteLocalChanges
+                    }
+                    crate::create::Error::CurrentDir(err)
+                    | crate::create::Error::IoOpen { source: err, .. }
+                    | crate::create::Error::IoWrite { source: err, .. }
+                    | crate::create::Error::CreateDirectory { source: err, .. } => error::io(err),
+                },
+                crate::init::Error::Open(_) | crate::init::Error::InvalidBranchName { .. } => Code::Configuration,
+                crate::init::Error::EditHeadForDefaultBranch(err) => error::reference_edit(err),
+                crate::init::Error::ReadConfig(_) | crate::init::Error::SetConfig(_) => Code::Configuration,
+                crate::init::Error::WriteConfig { source: err, .. } => error::io(err),
+            },
+            Error::UrlParse(_) | Error::CanonicalizeUrl { .. } => Code::InvalidInput,
+        }
+    }
+}
+
 /// Instantiation
 impl PrepareFetch {
     /// Create a new repository at `path` with `crate_opts` which is ready to clone from `url`, possibly after making additional adjustments to
@@ -134,6 +162,7 @@ impl PrepareFetch {
             #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
             configure_connection: None,
             shallow: remote::fetch::Shallow::NoChange,
+            filter: None,
         })
     }
 }
@@ -19,6 +19,26 @@ pub enum Error {
     WriteObject(#[from] crate::object::write::Error),
     #[error(transparent)]
     ReferenceEdit(#[from] crate::reference::edit::Error),
+    #[error("Could not sign the commit")]
+    Sign(#[source] crate::sign::Error),
+    #[cfg(feature = "hooks")]
+    #[error(transparent)]
+    Hook(#[from] crate::hooks::Error),
+}
+
+impl crate::error::ErrorCode for Error {
+    fn code(&self) -> crate::error::Code {
+        use crate::error::{self, Code};
+        match self {
+            Error::ParseTime(_) | Error::CommitterMissing | Error::AuthorMissing => Code::Configuration,
+            Error::ReferenceNameValidation(_) => Code::InvalidInput,
+            Error::WriteObject(_) => Code::Io,
+            Error::ReferenceEdit(err) => error::reference_edit(err),
+            Error::Sign(_) => Code::Other,
+            #[cfg(feature = "hooks")]
+            Error::Hook(err) => err.code(),
+        }
+    }
 }
 
 ///
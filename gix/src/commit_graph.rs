@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+
+/// Options for use in [`Repository::write_commit_graph()`](crate::Repository::write_commit_graph()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// If `true`, write a new layer of a split commit-graph with all commits that aren't yet contained in it, instead of
+    /// writing a single commit-graph file with all commits.
+    ///
+    /// Layers are merged into the new layer if it would contain at least a fraction of their commits as determined by
+    /// [`size_multiple`](Self::size_multiple), to keep the amount of layers low.
+    pub split: bool,
+    /// When writing a split commit-graph, merge the topmost layer into the new layer if the new layer multiplied by this
+    /// value has at least as many commits as the topmost layer, and repeat until the condition isn't met anymore.
+    pub size_multiple: u32,
+    /// If `true`, write bloom filters for the paths changed by each commit to speed up path-limited traversals.
+    pub changed_paths: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            split: false,
+            size_multiple: 2,
+            changed_paths: true,
+        }
+    }
+}
+
+/// The outcome of [`Repository::write_commit_graph()`](crate::Repository::write_commit_graph()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The path to the newly written commit-graph file or layer, or `None` if there was nothing to write.
+    pub path: Option<PathBuf>,
+    /// The amount of commits in the newly written file or layer.
+    pub num_commits: u32,
+    /// The amount of layers of the commit-graph after writing it, which is `1` if it isn't split.
+    pub num_layers: usize,
+}
+
+/// The error returned by [`Repository::write_commit_graph()`](crate::Repository::write_commit_graph()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Commit-graphs can't be written in shallow repositories")]
+    Shallow,
+    #[error(transparent)]
+    ReferenceIterInit(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    ReferenceIter(#[from] crate::reference::iter::Error),
+    #[error("Could not read a reference while collecting commits")]
+    Reference(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    FindCommit(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    FindTree(#[from] gix_object::find::existing_iter::Error),
+    #[error(transparent)]
+    DiffTrees(#[from] gix_diff::tree::changes::Error),
+    #[error(transparent)]
+    OpenLayers(#[from] gix_commitgraph::init::Error),
+    #[error(transparent)]
+    Write(#[from] gix_commitgraph::write::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    LockAcquire(#[from] gix_lock::acquire::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
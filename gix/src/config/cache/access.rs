@@ -38,6 +38,11 @@ impl Cache {
             .copied()
     }
 
+    #[cfg(feature = "status")]
+    pub(crate) fn status_renames(&self) -> Result<Option<crate::diff::Rewrites>, crate::diff::new_rewrites::Error> {
+        crate::diff::new_status_rewrites(&self.resolved, self.lenient_config)
+    }
+
     #[cfg(feature = "blob-diff")]
     pub(crate) fn diff_drivers(&self) -> Result<Vec<gix_diff::blob::Driver>, config::diff::drivers::Error> {
         use crate::config::cache::util::ApplyLeniencyDefault;
@@ -310,6 +315,8 @@ impl Cache {
             destination_is_initially_empty: false,
             overwrite_existing: false,
             keep_going: false,
+            resume: false,
+            clone_sources: None,
             stat_options: self.stat_options().map_err(|err| match err {
                 config::stat_options::Error::ConfigCheckStat(err) => {
                     config::checkout_options::Error::ConfigCheckStat(err)
@@ -442,6 +449,14 @@ impl Cache {
     pub(crate) fn home_dir(&self) -> Option<PathBuf> {
         gix_path::env::home_dir().and_then(|path| self.environment.home.check_opt(path))
     }
+
+    /// Return the path at which the configuration file of `source` is stored, as long as it's not inside of the repository,
+    /// while respecting permissions to access the environment.
+    pub(crate) fn storage_location(&self, source: gix_config::Source) -> Option<PathBuf> {
+        source
+            .storage_location(&mut Self::make_source_env(self.environment))
+            .map(Cow::into_owned)
+    }
 }
 
 fn boolean(
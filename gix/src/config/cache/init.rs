@@ -16,6 +16,88 @@ use crate::{
     repository::init::setup_objects,
 };
 
+thread_local! {
+    /// The global configuration that was loaded last on this thread, along with the state of the files it was loaded from,
+    /// for reuse for as long as none of these changes.
+    ///
+    /// It's thread-local as configuration files can't be shared across threads in all configurations.
+    static PREVIOUS_GLOBALS: std::cell::RefCell<Option<Globals>> = const { std::cell::RefCell::new(None) };
+}
+
+struct Globals {
+    files: Vec<GlobalFile>,
+    options: (bool, bool),
+    config: gix_config::File<'static>,
+}
+
+#[derive(PartialEq, Eq)]
+struct GlobalFile {
+    source: gix_config::Source,
+    path: std::path::PathBuf,
+    /// The modification time and size of the file, or `None` if it didn't exist.
+    state: Option<(std::time::SystemTime, u64)>,
+}
+
+fn load_globals(
+    metas: impl IntoIterator<Item = gix_config::file::Metadata>,
+    buf: &mut Vec<u8>,
+    options: gix_config::file::init::Options<'_>,
+) -> Result<gix_config::File<'static>, Error> {
+    let err_on_nonexisting_paths = false;
+    Ok(
+        gix_config::File::from_paths_metadata_buf(&mut metas.into_iter(), buf, err_on_nonexisting_paths, options)
+            .map_err(|err| match err {
+                gix_config::file::init::from_paths::Error::Init(err) => Error::from(err),
+                gix_config::file::init::from_paths::Error::Io { source, path } => Error::Io { source, path },
+            })?
+            .unwrap_or_default(),
+    )
+}
+
+/// Like [`load_globals()`], but return a copy of the previously loaded configuration if it was loaded from the
+/// same files with the same `options`, and none of these files changed since.
+fn load_globals_or_reuse_previous(
+    metas: Vec<gix_config::file::Metadata>,
+    buf: &mut Vec<u8>,
+    options: gix_config::file::init::Options<'_>,
+) -> Result<gix_config::File<'static>, Error> {
+    let files: Vec<_> = metas
+        .iter()
+        .filter_map(|meta| {
+            let path = meta.path.clone()?;
+            let state = std::fs::metadata(&path)
+                .ok()
+                .and_then(|md| Some((md.modified().ok()?, md.len())));
+            Some(GlobalFile {
+                source: meta.source,
+                path,
+                state,
+            })
+        })
+        .collect();
+    let options_key = (options.lossy, options.ignore_io_errors);
+    let previous = PREVIOUS_GLOBALS.with(|previous| {
+        previous
+            .borrow()
+            .as_ref()
+            .filter(|previous| previous.files == files && previous.options == options_key)
+            .map(|previous| previous.config.clone())
+    });
+    if let Some(config) = previous {
+        return Ok(config);
+    }
+
+    let config = load_globals(metas, buf, options)?;
+    PREVIOUS_GLOBALS.with(|previous| {
+        *previous.borrow_mut() = Some(Globals {
+            files,
+            options: options_key,
+            config: config.clone(),
+        });
+    });
+    Ok(config)
+}
+
 /// Initialization
 impl Cache {
     #[allow(clippy::too_many_arguments)]
@@ -53,6 +135,7 @@ impl Cache {
             includes: use_includes,
         }: open::permissions::Config,
         lenient_config: bool,
+        reuse_global_config: bool,
         api_config_overrides: &[BString],
         cli_config_overrides: &[BString],
     ) -> Result<Self, Error> {
@@ -73,7 +156,7 @@ impl Cache {
 
         let config = {
             let git_prefix = &git_prefix;
-            let mut metas = [
+            let metas = [
                 gix_config::source::Kind::GitInstallation,
                 gix_config::source::Kind::System,
                 gix_config::source::Kind::Global,
@@ -99,21 +182,15 @@ impl Cache {
                 trust: gix_sec::Trust::Full,
             });
 
-            let err_on_nonexisting_paths = false;
-            let mut globals = gix_config::File::from_paths_metadata_buf(
-                &mut metas,
-                &mut buf,
-                err_on_nonexisting_paths,
-                gix_config::file::init::Options {
-                    includes: gix_config::file::includes::Options::no_follow(),
-                    ..options
-                },
-            )
-            .map_err(|err| match err {
-                gix_config::file::init::from_paths::Error::Init(err) => Error::from(err),
-                gix_config::file::init::from_paths::Error::Io { source, path } => Error::Io { source, path },
-            })?
-            .unwrap_or_default();
+            let globals_options = gix_config::file::init::Options {
+                includes: gix_config::file::includes::Options::no_follow(),
+                ..options
+            };
+            let mut globals = if reuse_global_config {
+                load_globals_or_reuse_previous(metas.collect(), &mut buf, globals_options)?
+            } else {
+                load_globals(metas, &mut buf, globals_options)?
+            };
 
             let local_meta = git_dir_config.meta_owned();
             globals.append(git_dir_config);
@@ -341,6 +418,18 @@ fn apply_environment_overrides(
                     let key = &Http::SSL_VERSION;
                     (env(key), key.name)
                 },
+                {
+                    let key = &Http::SSL_CERT;
+                    (env(key), key.name)
+                },
+                {
+                    let key = &Http::SSL_KEY;
+                    (env(key), key.name)
+                },
+                {
+                    let key = &Http::SSL_CERT_PASSWORD_PROTECTED;
+                    (env(key), key.name)
+                },
             ][..],
         ),
         (
@@ -559,6 +648,15 @@ fn apply_environment_overrides(
                 (env(key), key.name)
             }],
         ),
+        (
+            "sequence",
+            None,
+            git_prefix,
+            &[{
+                let key = &config::tree::Sequence::EDITOR;
+                (env(key), key.name)
+            }],
+        ),
     ] {
         let mut section = env_override
             .new_section(section_name, subsection_name)
@@ -588,6 +686,14 @@ fn apply_environment_overrides(
                 let key = &Core::DELTA_BASE_CACHE_LIMIT;
                 (env(key), key.name, objects)
             },
+            {
+                let key = &Core::EDITOR;
+                (env(key), key.name, git_prefix)
+            },
+            {
+                let key = &Core::PAGER;
+                (env(key), key.name, git_prefix)
+            },
             {
                 let key = &Core::SSH_COMMAND;
                 (env(key), key.name, git_prefix)
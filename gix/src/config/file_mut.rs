@@ -0,0 +1,150 @@
+use std::path::{Path, PathBuf};
+
+use gix_macros::momo;
+
+use crate::{
+    bstr::{BStr, BString},
+    config::FileMut,
+};
+
+///
+pub mod open {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Repository::config_file_mut()`](crate::Repository::config_file_mut()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Configuration of source {kind:?} isn't stored in a file")]
+        NoLocation { kind: gix_config::Source },
+        #[error("Could not create the directory for the configuration file at \"{}\"", path.display())]
+        CreateDirectory { path: PathBuf, source: std::io::Error },
+        #[error(transparent)]
+        Lock(#[from] gix_lock::acquire::Error),
+        #[error("Could not read the configuration file at \"{}\"", path.display())]
+        Read { path: PathBuf, source: std::io::Error },
+        #[error(transparent)]
+        Parse(#[from] gix_config::file::init::Error),
+    }
+}
+
+///
+pub mod commit {
+    use std::path::PathBuf;
+
+    /// The error returned by [`FileMut::commit()`](crate::config::FileMut::commit()).
+    #[derive(Debug, thiserror::Error)]
+    #[error("Could not write the configuration file at \"{}\"", path.display())]
+    pub struct Error {
+        /// The path to the configuration file.
+        pub path: PathBuf,
+        /// The underlying error.
+        pub source: std::io::Error,
+    }
+}
+
+impl FileMut {
+    pub(crate) fn open(path: PathBuf, source: gix_config::Source) -> Result<Self, open::Error> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|err| open::Error::CreateDirectory {
+                path: dir.to_owned(),
+                source: err,
+            })?;
+        }
+        // Like `git`, we don't wait for other writers as edits are typically interactive.
+        let lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        let mut buf = match std::fs::read(&path) {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(open::Error::Read { path, source: err }),
+        };
+        let meta = gix_config::file::Metadata::from(source).at(path);
+        let config = gix_config::File::from_bytes_owned(
+            &mut buf,
+            meta,
+            gix_config::file::init::Options {
+                includes: gix_config::file::includes::Options::no_follow(),
+                ..Default::default()
+            },
+        )?;
+        Ok(FileMut { lock, config })
+    }
+}
+
+/// Access
+impl FileMut {
+    /// Return the path to the configuration file that is edited.
+    pub fn path(&self) -> &Path {
+        self.config.meta().path.as_deref().expect("always set")
+    }
+
+    /// Return the source the edited configuration file represents.
+    pub fn source(&self) -> gix_config::Source {
+        self.config.meta().source
+    }
+}
+
+/// Mutation
+impl FileMut {
+    /// Set the value at `key` to `new_value`, possibly creating the section if it doesn't exist yet, or overriding the most recent existing
+    /// value, which will be returned.
+    #[momo]
+    pub fn set_value<'b>(
+        &mut self,
+        key: &'static dyn crate::config::tree::Key,
+        new_value: impl Into<&'b BStr>,
+    ) -> Result<Option<BString>, crate::config::set_value::Error> {
+        crate::config::set_value::set(&mut self.config, key, new_value.into())
+    }
+
+    /// Set the value at `key` to `new_value` in the given `subsection`, possibly creating the section and sub-section if it doesn't exist yet,
+    /// or overriding the most recent existing value, which will be returned.
+    #[momo]
+    pub fn set_subsection_value<'a, 'b>(
+        &mut self,
+        key: &'static dyn crate::config::tree::Key,
+        subsection: impl Into<&'a BStr>,
+        new_value: impl Into<&'b BStr>,
+    ) -> Result<Option<BString>, crate::config::set_value::Error> {
+        crate::config::set_value::set_in_subsection(&mut self.config, key, subsection.into(), new_value.into())
+    }
+
+    /// Write all changes to disk, replacing the configuration file atomically, and return the written configuration.
+    pub fn commit(mut self) -> Result<gix_config::File<'static>, commit::Error> {
+        let path = self.path().to_owned();
+        let res = self
+            .config
+            .write_to(&mut self.lock)
+            .and_then(|_| self.lock.commit().map_err(|err| err.error));
+        match res {
+            Ok(_) => Ok(self.config),
+            Err(err) => Err(commit::Error { path, source: err }),
+        }
+    }
+}
+
+mod _impls {
+    use std::ops::{Deref, DerefMut};
+
+    use crate::config::FileMut;
+
+    impl std::fmt::Debug for FileMut {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(&self.config.to_string())
+        }
+    }
+
+    impl Deref for FileMut {
+        type Target = gix_config::File<'static>;
+
+        fn deref(&self) -> &Self::Target {
+            &self.config
+        }
+    }
+
+    impl DerefMut for FileMut {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.config
+        }
+    }
+}
@@ -27,14 +27,26 @@ pub struct Snapshot<'repo> {
 ///
 /// Note that these values won't update even if the underlying file(s) change.
 ///
-/// Use [`forget()`][Self::forget()] to not apply any of the changes.
-// TODO: make it possible to load snapshots with reloading via .config() and write mutated snapshots back to disk which should be the way
-//       to affect all instances of a repo, probably via `config_mut()` and `config_mut_at()`.
+/// Use [`forget()`][Self::forget()] to not apply any of the changes, and [`FileMut`] to persist changes on disk.
 pub struct SnapshotMut<'repo> {
     pub(crate) repo: Option<&'repo mut Repository>,
     pub(crate) config: gix_config::File<'static>,
 }
 
+/// A single configuration file of a particular [source](gix_config::Source), locked and loaded for editing without
+/// resolving includes, to write changes back to disk while keeping everything else in the file as is, byte for byte.
+///
+/// It's created with [`Repository::config_file_mut()`], and the changes are written with [`commit()`](Self::commit()),
+/// or discarded if it's dropped.
+/// Note that the configuration of repository instances that are already open won't change.
+pub struct FileMut {
+    pub(crate) lock: gix_lock::File,
+    pub(crate) config: gix_config::File<'static>,
+}
+
+///
+pub mod file_mut;
+
 /// A utility structure created by [`SnapshotMut::commit_auto_rollback()`] that restores the previous configuration on drop.
 pub struct CommitAutoRollback<'repo> {
     pub(crate) repo: Option<&'repo mut Repository>,
@@ -49,7 +61,10 @@ pub(crate) mod section {
 
 ///
 pub mod set_value {
+    use crate::bstr::{BStr, BString, ByteSlice};
+
     /// The error produced when calling [`SnapshotMut::set(_subsection)?_value()`][crate::config::SnapshotMut::set_value()]
+    /// or [`FileMut::set(_subsection)?_value()`][crate::config::FileMut::set_value()].
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
@@ -62,6 +77,40 @@ pub mod set_value {
         #[error("The key must not be used with a subsection")]
         SubSectionForbidden,
     }
+
+    pub(crate) fn set(
+        config: &mut gix_config::File<'static>,
+        key: &'static dyn crate::config::tree::Key,
+        value: &BStr,
+    ) -> Result<Option<BString>, Error> {
+        if let Some(crate::config::tree::SubSectionRequirement::Parameter(_)) = key.subsection_requirement() {
+            return Err(Error::SubSectionRequired);
+        }
+        key.validate(value)?;
+        let section = key.section();
+        let current = match section.parent() {
+            Some(parent) => config.set_raw_value(parent.name(), Some(section.name().into()), key.name(), value)?,
+            None => config.set_raw_value(section.name(), None, key.name(), value)?,
+        };
+        Ok(current.map(std::borrow::Cow::into_owned))
+    }
+
+    pub(crate) fn set_in_subsection(
+        config: &mut gix_config::File<'static>,
+        key: &'static dyn crate::config::tree::Key,
+        subsection: &BStr,
+        value: &BStr,
+    ) -> Result<Option<BString>, Error> {
+        if let Some(crate::config::tree::SubSectionRequirement::Never) = key.subsection_requirement() {
+            return Err(Error::SubSectionForbidden);
+        }
+        key.validate(value)?;
+
+        let name = key.full_name(Some(subsection)).expect("we know it needs a subsection");
+        let key = gix_config::parse::key((**name).as_bstr()).expect("statically known keys can always be parsed");
+        let current = config.set_raw_value(key.section_name, key.subsection_name, key.value_name.to_owned(), value)?;
+        Ok(current.map(std::borrow::Cow::into_owned))
+    }
 }
 
 /// The error returned when failing to initialize the repository configuration.
@@ -154,6 +203,23 @@ pub mod diff {
     }
 }
 
+///
+#[cfg(feature = "merge")]
+pub mod merge_tree_options {
+    /// The error produced when obtaining options to merge trees, as returned by
+    /// [Repository::merge_tree_options()](crate::Repository::merge_tree_options()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigRenames(#[from] super::key::GenericError),
+        #[error(transparent)]
+        ConfigRenameLimit(#[from] super::unsigned_integer::Error),
+        #[error(transparent)]
+        ConfigDirectoryRenames(#[from] super::key::GenericErrorWithValue),
+    }
+}
+
 ///
 pub mod stat_options {
     /// The error produced when collecting stat information, and returned by [Repository::stat_options()](crate::Repository::stat_options()).
@@ -258,8 +324,12 @@ pub mod ssh_connect_options {
     /// The error produced when obtaining ssh connection configuration.
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
-    #[error(transparent)]
-    pub struct Error(#[from] super::key::GenericErrorWithValue);
+    pub enum Error {
+        #[error(transparent)]
+        Variant(#[from] super::key::GenericErrorWithValue),
+        #[error(transparent)]
+        BatchMode(#[from] super::boolean::Error),
+    }
 }
 
 ///
@@ -534,6 +604,11 @@ pub mod transport {
                 source: crate::config::key::GenericErrorWithValue,
                 key: Cow<'static, BStr>,
             },
+            #[error("The authentication method at key `{key}` is invalid")]
+            InvalidAuthMethod {
+                source: crate::config::key::GenericErrorWithValue,
+                key: Cow<'static, BStr>,
+            },
             #[error("Could not configure the credential helpers for the authenticated proxy url")]
             #[cfg(feature = "credentials")]
             ConfigureProxyAuthenticate(#[from] crate::config::snapshot::credential_helpers::Error),
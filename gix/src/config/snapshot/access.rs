@@ -6,7 +6,7 @@ use gix_features::threading::OwnShared;
 use gix_macros::momo;
 
 use crate::{
-    bstr::{BStr, BString, ByteSlice},
+    bstr::{BStr, BString},
     config::{CommitAutoRollback, Snapshot, SnapshotMut},
 };
 
@@ -128,19 +128,7 @@ impl<'repo> SnapshotMut<'repo> {
         key: &'static dyn crate::config::tree::Key,
         new_value: impl Into<&'b BStr>,
     ) -> Result<Option<BString>, crate::config::set_value::Error> {
-        if let Some(crate::config::tree::SubSectionRequirement::Parameter(_)) = key.subsection_requirement() {
-            return Err(crate::config::set_value::Error::SubSectionRequired);
-        }
-        let value = new_value.into();
-        key.validate(value)?;
-        let section = key.section();
-        let current = match section.parent() {
-            Some(parent) => self
-                .config
-                .set_raw_value(parent.name(), Some(section.name().into()), key.name(), value)?,
-            None => self.config.set_raw_value(section.name(), None, key.name(), value)?,
-        };
-        Ok(current.map(std::borrow::Cow::into_owned))
+        crate::config::set_value::set(&mut self.config, key, new_value.into())
     }
 
     /// Set the value at `key` to `new_value` in the given `subsection`, possibly creating the section and sub-section if it doesn't exist yet,
@@ -152,20 +140,7 @@ impl<'repo> SnapshotMut<'repo> {
         subsection: impl Into<&'a BStr>,
         new_value: impl Into<&'b BStr>,
     ) -> Result<Option<BString>, crate::config::set_value::Error> {
-        if let Some(crate::config::tree::SubSectionRequirement::Never) = key.subsection_requirement() {
-            return Err(crate::config::set_value::Error::SubSectionForbidden);
-        }
-        let value = new_value.into();
-        key.validate(value)?;
-
-        let name = key
-            .full_name(Some(subsection.into()))
-            .expect("we know it needs a subsection");
-        let key = gix_config::parse::key((**name).as_bstr()).expect("statically known keys can always be parsed");
-        let current =
-            self.config
-                .set_raw_value(key.section_name, key.subsection_name, key.value_name.to_owned(), value)?;
-        Ok(current.map(std::borrow::Cow::into_owned))
+        crate::config::set_value::set_in_subsection(&mut self.config, key, subsection.into(), new_value.into())
     }
 
     pub(crate) fn commit_inner(
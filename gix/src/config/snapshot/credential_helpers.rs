@@ -156,6 +156,8 @@ impl Snapshot<'_> {
                 .unwrap_or_default(),
         }
         .apply_environment(allow_git_env, allow_ssh_env, false /* terminal prompt */);
+        // Depending on the features of `gix-credentials`, the cascade may have more fields, which keep their default.
+        #[allow(clippy::needless_update)]
         Ok((
             gix_credentials::helper::Cascade {
                 programs,
@@ -168,6 +170,7 @@ impl Snapshot<'_> {
                     .transpose()
                     .with_leniency(self.repo.options.lenient_config)?
                     .unwrap_or(true),
+                ..Default::default()
             },
             gix_credentials::helper::Action::get_for_url(url.to_bstring()),
             prompt_options,
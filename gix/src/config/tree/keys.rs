@@ -8,7 +8,7 @@ use std::{
 use crate::{
     bstr::BStr,
     config,
-    config::tree::{Key, Link, Note, Section, SubSectionRequirement},
+    config::tree::{Key, Link, Note, Section, SubSectionRequirement, ValueType},
 };
 
 /// Implements a value without any constraints, i.e. a any value.
@@ -144,6 +144,10 @@ impl<T: Validate> Key for Any<T> {
     fn note(&self) -> Option<&Note> {
         self.note.as_ref()
     }
+
+    fn value_type(&self) -> ValueType {
+        self.validate.value_type()
+    }
 }
 
 /// A key which represents a date.
@@ -478,6 +482,10 @@ mod remote_name {
 pub trait Validate {
     /// Validate `value` or return an error.
     fn validate(&self, value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>>;
+    /// Return the kind of value that [`validate()`][Self::validate()] accepts.
+    fn value_type(&self) -> ValueType {
+        ValueType::Any
+    }
 }
 
 /// various implementations of the `Validate` trait.
@@ -486,7 +494,7 @@ pub mod validate {
 
     use crate::{
         bstr::{BStr, ByteSlice},
-        config::tree::keys::Validate,
+        config::tree::{keys::Validate, ValueType},
         remote,
     };
 
@@ -509,6 +517,10 @@ pub mod validate {
             gix_date::parse(value.to_str()?, std::time::SystemTime::now().into())?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::Time
+        }
     }
 
     /// Assure that values that parse as unsigned integers are valid.
@@ -525,6 +537,10 @@ pub mod validate {
             .map_err(|_| "cannot use sign for unsigned integer")?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::UnsignedInteger
+        }
     }
 
     /// Assure that values that parse as git booleans are valid.
@@ -536,6 +552,10 @@ pub mod validate {
             gix_config::Boolean::try_from(value)?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::Boolean
+        }
     }
 
     /// Values that are git remotes, symbolic or urls
@@ -547,6 +567,10 @@ pub mod validate {
                 .map_err(|_| format!("Illformed UTF-8 in remote name: \"{}\"", value.to_str_lossy()))?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::RemoteName
+        }
     }
 
     /// Values that are programs - everything is allowed.
@@ -556,6 +580,10 @@ pub mod validate {
         fn validate(&self, _value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::Program
+        }
     }
 
     /// Values that are programs executables, everything is allowed.
@@ -565,6 +593,10 @@ pub mod validate {
         fn validate(&self, _value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::Executable
+        }
     }
 
     /// Values that parse as URLs.
@@ -575,6 +607,10 @@ pub mod validate {
             gix_url::parse(value)?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::Url
+        }
     }
 
     /// Values that parse as ref-specs for pushing.
@@ -585,6 +621,10 @@ pub mod validate {
             gix_refspec::parse(value, gix_refspec::parse::Operation::Push)?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::PushRefSpec
+        }
     }
 
     /// Values that parse as ref-specs for pushing.
@@ -595,6 +635,10 @@ pub mod validate {
             gix_refspec::parse(value, gix_refspec::parse::Operation::Fetch)?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::FetchRefSpec
+        }
     }
 
     /// Timeouts used for file locks.
@@ -607,6 +651,10 @@ pub mod validate {
             super::super::Core::FILES_REF_LOCK_TIMEOUT.try_into_lock_timeout(Ok(value?))?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::Integer
+        }
     }
 
     /// Durations in milliseconds.
@@ -619,6 +667,10 @@ pub mod validate {
             super::super::gitoxide::Http::CONNECT_TIMEOUT.try_into_duration(Ok(value?))?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::DurationInMilliseconds
+        }
     }
 
     /// A UTF-8 string.
@@ -628,6 +680,10 @@ pub mod validate {
             value.to_str()?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::String
+        }
     }
 
     /// Any path - everything is allowed.
@@ -636,5 +692,9 @@ pub mod validate {
         fn validate(&self, _value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::Path
+        }
     }
 }
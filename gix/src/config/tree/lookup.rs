@@ -0,0 +1,107 @@
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    config::{
+        tree::{find_key, validate_key, Diagnostic, Key, Section},
+        Tree,
+    },
+};
+
+/// Lookup and validation of arbitrary keys.
+impl Tree {
+    /// Find the statically known key of the given `name`, like `core.bare`, `remote.origin.url` or `gitoxide.core.useNsec`.
+    ///
+    /// Section and key names are matched case-insensitively, while sub-section names must match exactly, just like `git` does.
+    /// Sub-sections are only allowed if the key supports them, and they are required if the key is parameterized by them.
+    pub fn find_key(&self, name: &BStr) -> Result<&dyn Key, find_key::Error> {
+        let key = gix_config::parse::key(name).ok_or_else(|| find_key::Error::Malformed { name: name.to_owned() })?;
+        let section = self
+            .sections()
+            .iter()
+            .copied()
+            .find(|section| section.name().eq_ignore_ascii_case(key.section_name))
+            .ok_or_else(|| find_key::Error::UnknownSection {
+                name: key.section_name.into(),
+            })?;
+
+        if let Some(sub_section) = key.subsection_name.and_then(|subsection_name| {
+            section
+                .sub_sections()
+                .iter()
+                .copied()
+                .find(|sub_section| sub_section.name().as_bytes() == subsection_name.as_bytes())
+        }) {
+            return key_by_name(sub_section, key.value_name)
+                .ok_or_else(|| find_key::Error::UnknownKey { name: name.to_owned() });
+        }
+
+        let found = key_by_name(section, key.value_name)
+            .ok_or_else(|| find_key::Error::UnknownKey { name: name.to_owned() })?;
+        found
+            .full_name(key.subsection_name)
+            .map_err(|message| find_key::Error::SubSection { message })?;
+        Ok(found)
+    }
+
+    /// Find the key of the given `name` like [`find_key()`][Self::find_key()] does, and validate `value` with it.
+    ///
+    /// Return the key that `value` was validated with.
+    pub fn validate_key(&self, name: &BStr, value: &BStr) -> Result<&dyn Key, validate_key::Error> {
+        let key = self.find_key(name)?;
+        key.validate(value).map_err(|source| validate_key::Error::Value {
+            name: name.to_owned(),
+            value: value.to_owned(),
+            source,
+        })?;
+        Ok(key)
+    }
+
+    /// Validate all keys and values in `config`, and return one diagnostic for each key that isn't known to us,
+    /// or for each value that is invalid.
+    ///
+    /// Note that keys without `=` are validated as if they had an empty value, and that unknown keys aren't
+    /// necessarily wrong as `git` and other tools may know and use them.
+    pub fn diagnostics(&self, config: &gix_config::File<'_>) -> Vec<Diagnostic> {
+        let mut out = Vec::new();
+        for section in config.sections() {
+            let mut seen = Vec::new();
+            for value_name in section.body().keys() {
+                if seen.contains(&value_name) {
+                    continue;
+                }
+                seen.push(value_name);
+
+                let mut name: BString = section.header().name().to_owned();
+                if let Some(subsection_name) = section.header().subsection_name() {
+                    name.push(b'.');
+                    name.push_str(subsection_name);
+                }
+                name.push(b'.');
+                name.push_str(value_name.as_ref());
+
+                for value in section.body().values(value_name.as_ref()) {
+                    if let Err(error) = self.validate_key(name.as_ref(), value.as_ref()) {
+                        let is_unknown = matches!(error, validate_key::Error::Find(_));
+                        out.push(Diagnostic {
+                            name: name.clone(),
+                            value: value.into_owned(),
+                            meta: section.meta().clone(),
+                            error,
+                        });
+                        if is_unknown {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn key_by_name<'a>(section: &'a dyn Section, name: &str) -> Option<&'a dyn Key> {
+    section
+        .keys()
+        .iter()
+        .copied()
+        .find(|key| key.name().eq_ignore_ascii_case(name))
+}
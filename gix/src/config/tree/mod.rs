@@ -16,6 +16,9 @@ pub(crate) mod root {
     pub struct Tree;
 
     impl Tree {
+        /// The `apply` section.
+        #[cfg(feature = "apply")]
+        pub const APPLY: sections::Apply = sections::Apply;
         /// The `author` section.
         pub const AUTHOR: sections::Author = sections::Author;
         /// The `branch` section.
@@ -37,6 +40,8 @@ pub(crate) mod root {
         pub const EXTENSIONS: sections::Extensions = sections::Extensions;
         /// The `fetch` section.
         pub const FETCH: sections::Fetch = sections::Fetch;
+        /// The `gc` section.
+        pub const GC: sections::Gc = sections::Gc;
         /// The `gitoxide` section.
         pub const GITOXIDE: sections::Gitoxide = sections::Gitoxide;
         /// The `http` section.
@@ -47,6 +52,12 @@ pub(crate) mod root {
         pub const INIT: sections::Init = sections::Init;
         /// The `mailmap` section.
         pub const MAILMAP: sections::Mailmap = sections::Mailmap;
+        /// The `merge` section.
+        #[cfg(feature = "merge")]
+        pub const MERGE: sections::Merge = sections::Merge;
+        /// The `notes` section.
+        #[cfg(feature = "notes")]
+        pub const NOTES: sections::Notes = sections::Notes;
         /// The `pack` section.
         pub const PACK: sections::Pack = sections::Pack;
         /// The `protocol` section.
@@ -55,10 +66,23 @@ pub(crate) mod root {
         pub const PUSH: sections::Push = sections::Push;
         /// The `remote` section.
         pub const REMOTE: sections::Remote = sections::Remote;
+        /// The `rerere` section.
+        #[cfg(feature = "merge")]
+        pub const RERERE: sections::Rerere = sections::Rerere;
         /// The `safe` section.
         pub const SAFE: sections::Safe = sections::Safe;
+        /// The `sequence` section.
+        pub const SEQUENCE: sections::Sequence = sections::Sequence;
         /// The `ssh` section.
         pub const SSH: sections::Ssh = sections::Ssh;
+        /// The `status` section.
+        #[cfg(feature = "status")]
+        pub const STATUS: sections::Status = sections::Status;
+        /// The `submodule` section.
+        #[cfg(feature = "attributes")]
+        pub const SUBMODULE: sections::Submodule = sections::Submodule;
+        /// The `transfer` section.
+        pub const TRANSFER: sections::Transfer = sections::Transfer;
         /// The `user` section.
         pub const USER: sections::User = sections::User;
         /// The `url` section.
@@ -67,6 +91,8 @@ pub(crate) mod root {
         /// List all available sections.
         pub fn sections(&self) -> &[&dyn Section] {
             &[
+                #[cfg(feature = "apply")]
+                &Self::APPLY,
                 &Self::AUTHOR,
                 &Self::BRANCH,
                 &Self::CHECKOUT,
@@ -78,17 +104,30 @@ pub(crate) mod root {
                 &Self::DIFF,
                 &Self::EXTENSIONS,
                 &Self::FETCH,
+                &Self::GC,
                 &Self::GITOXIDE,
                 &Self::HTTP,
                 &Self::INDEX,
                 &Self::INIT,
                 &Self::MAILMAP,
+                #[cfg(feature = "merge")]
+                &Self::MERGE,
+                #[cfg(feature = "notes")]
+                &Self::NOTES,
                 &Self::PACK,
                 &Self::PROTOCOL,
                 &Self::PUSH,
                 &Self::REMOTE,
+                #[cfg(feature = "merge")]
+                &Self::RERERE,
                 &Self::SAFE,
+                &Self::SEQUENCE,
                 &Self::SSH,
+                #[cfg(feature = "status")]
+                &Self::STATUS,
+                #[cfg(feature = "attributes")]
+                &Self::SUBMODULE,
+                &Self::TRANSFER,
                 &Self::USER,
                 &Self::URL,
             ]
@@ -97,13 +136,23 @@ pub(crate) mod root {
 }
 
 mod sections;
+#[cfg(feature = "status")]
+pub use sections::Status;
+#[cfg(feature = "apply")]
+pub use sections::{apply, Apply};
 pub use sections::{
-    branch, checkout, core, credential, extensions, fetch, gitoxide, http, index, protocol, push, remote, ssh, Author,
-    Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gitoxide, Http, Index, Init, Mailmap,
-    Pack, Protocol, Push, Remote, Safe, Ssh, Url, User,
+    branch, checkout, core, credential, extensions, fetch, gc, gitoxide, http, index, protocol, push, remote, ssh,
+    Author, Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gc, Gitoxide, Http, Index, Init,
+    Mailmap, Pack, Protocol, Push, Remote, Safe, Sequence, Ssh, Transfer, Url, User,
 };
 #[cfg(feature = "blob-diff")]
 pub use sections::{diff, Diff};
+#[cfg(feature = "merge")]
+pub use sections::{merge, Merge, Rerere};
+#[cfg(feature = "notes")]
+pub use sections::{notes, Notes};
+#[cfg(feature = "attributes")]
+pub use sections::{submodule, Submodule};
 
 /// Generic value implementations for static instantiation.
 pub mod keys;
@@ -135,5 +184,57 @@ pub mod key {
     }
 }
 
+///
+pub mod find_key {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Tree::find_key()`][crate::config::Tree::find_key()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Key name \"{name}\" must be of the form 'section.key' or 'section.subsection.key'")]
+        Malformed { name: BString },
+        #[error("The section named \"{name}\" is unknown")]
+        UnknownSection { name: BString },
+        #[error("The key named \"{name}\" is unknown")]
+        UnknownKey { name: BString },
+        #[error("{message}")]
+        SubSection { message: String },
+    }
+}
+
+///
+pub mod validate_key {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Tree::validate_key()`][crate::config::Tree::validate_key()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Find(#[from] super::find_key::Error),
+        #[error("The value \"{value}\" of key \"{name}\" is invalid")]
+        Value {
+            name: BString,
+            value: BString,
+            source: super::key::validate::Error,
+        },
+    }
+}
+
+/// A problem with a key or its value as found by [`Tree::diagnostics()`][crate::config::Tree::diagnostics()].
+#[derive(Debug)]
+pub struct Diagnostic {
+    /// The full name of the key as written in the configuration, like `remote.origin.url`.
+    pub name: crate::bstr::BString,
+    /// The value of the key, which is empty if it was set without `=`.
+    pub value: crate::bstr::BString,
+    /// Information about the configuration file the key was found in.
+    pub meta: gix_config::file::Metadata,
+    /// What's wrong with the key or its value.
+    pub error: validate_key::Error,
+}
+
+mod lookup;
 mod traits;
-pub use traits::{Key, Link, Note, Section, SubSectionRequirement};
+pub use traits::{Key, Link, Note, Section, SubSectionRequirement, ValueType};
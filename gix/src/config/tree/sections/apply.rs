@@ -0,0 +1,94 @@
+use crate::{
+    config,
+    config::tree::{keys, Apply, Key, Section},
+};
+
+impl Apply {
+    /// The `apply.whitespace` key.
+    pub const WHITESPACE: Whitespace =
+        Whitespace::new_with_validate("whitespace", &config::Tree::APPLY, validate::Whitespace)
+            .with_note("'warn' is treated like 'nowarn' and 'error-all' like 'error' as no warnings are emitted");
+    /// The `apply.ignoreWhitespace` key.
+    pub const IGNORE_WHITESPACE: IgnoreWhitespace =
+        IgnoreWhitespace::new_with_validate("ignoreWhitespace", &config::Tree::APPLY, validate::IgnoreWhitespace);
+}
+
+impl Section for Apply {
+    fn name(&self) -> &str {
+        "apply"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::WHITESPACE, &Self::IGNORE_WHITESPACE]
+    }
+}
+
+/// The `apply.whitespace` key.
+pub type Whitespace = keys::Any<validate::Whitespace>;
+
+/// The `apply.ignoreWhitespace` key.
+pub type IgnoreWhitespace = keys::Any<validate::IgnoreWhitespace>;
+
+mod whitespace {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::apply::Whitespace};
+
+    impl Whitespace {
+        /// Convert `value` into the action to take for whitespace errors in added lines.
+        pub fn try_into_whitespace(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_apply::blob::Whitespace, config::key::GenericErrorWithValue> {
+            gix_apply::blob::Whitespace::from_name(value.as_ref())
+                .ok_or_else(|| config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+}
+
+mod ignore_whitespace {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::apply::IgnoreWhitespace,
+    };
+
+    impl IgnoreWhitespace {
+        /// Convert `value` into `true` if changes in the amount of whitespace should be ignored when matching lines.
+        pub fn try_into_ignore_whitespace(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<bool, config::key::GenericErrorWithValue> {
+            Ok(match value.as_ref().as_bytes() {
+                b"change" => true,
+                b"no" | b"none" | b"never" | b"false" => false,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            })
+        }
+    }
+}
+
+mod validate {
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, Apply},
+    };
+
+    pub struct Whitespace;
+    impl keys::Validate for Whitespace {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Apply::WHITESPACE.try_into_whitespace(value.into())?;
+            Ok(())
+        }
+    }
+
+    pub struct IgnoreWhitespace;
+    impl keys::Validate for IgnoreWhitespace {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Apply::IGNORE_WHITESPACE.try_into_ignore_whitespace(value.into())?;
+            Ok(())
+        }
+    }
+}
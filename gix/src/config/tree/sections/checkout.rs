@@ -43,7 +43,10 @@ mod workers {
 
 ///
 pub mod validate {
-    use crate::{bstr::BStr, config::tree::keys};
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, ValueType},
+    };
 
     pub struct Workers;
     impl keys::Validate for Workers {
@@ -54,5 +57,9 @@ pub mod validate {
             }))?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::Integer
+        }
     }
 }
@@ -23,7 +23,8 @@ impl Core {
     pub const DISAMBIGUATE: Disambiguate =
         Disambiguate::new_with_validate("disambiguate", &config::Tree::CORE, validate::Disambiguate);
     /// The `core.editor` key.
-    pub const EDITOR: keys::Program = keys::Program::new_program("editor", &config::Tree::CORE);
+    pub const EDITOR: keys::Program =
+        keys::Program::new_program("editor", &config::Tree::CORE).with_environment_override("GIT_EDITOR");
     /// The `core.fileMode` key.
     pub const FILE_MODE: keys::Boolean = keys::Boolean::new_boolean("fileMode", &config::Tree::CORE);
     /// The `core.ignoreCase` key.
@@ -39,6 +40,9 @@ impl Core {
     /// The `core.logAllRefUpdates` key.
     pub const LOG_ALL_REF_UPDATES: LogAllRefUpdates =
         LogAllRefUpdates::new_with_validate("logAllRefUpdates", &config::Tree::CORE, validate::LogAllRefUpdates);
+    /// The `core.pager` key.
+    pub const PAGER: keys::Program =
+        keys::Program::new_program("pager", &config::Tree::CORE).with_environment_override("GIT_PAGER");
     /// The `core.precomposeUnicode` key.
     ///
     /// Needs application to use [`env::args_os`][crate::env::args_os()] to conform all input paths before they are used.
@@ -65,6 +69,8 @@ impl Core {
     pub const ATTRIBUTES_FILE: keys::Path =
         keys::Path::new_path("attributesFile", &config::Tree::CORE)
             .with_deviation("for checkout - it's already queried but needs building of attributes group, and of course support during checkout");
+    /// The `core.hooksPath` key.
+    pub const HOOKS_PATH: keys::Path = keys::Path::new_path("hooksPath", &config::Tree::CORE);
     /// The `core.sshCommand` key.
     pub const SSH_COMMAND: keys::Executable = keys::Executable::new_executable("sshCommand", &config::Tree::CORE)
         .with_environment_override("GIT_SSH_COMMAND");
@@ -73,6 +79,14 @@ impl Core {
         .with_environment_override("GIT_NO_REPLACE_OBJECTS");
     /// The `core.commitGraph` key.
     pub const COMMIT_GRAPH: keys::Boolean = keys::Boolean::new_boolean("commitGraph", &config::Tree::CORE);
+    /// The `core.sparseCheckout` key.
+    pub const SPARSE_CHECKOUT: keys::Boolean = keys::Boolean::new_boolean("sparseCheckout", &config::Tree::CORE);
+    /// The `core.sparseCheckoutCone` key.
+    pub const SPARSE_CHECKOUT_CONE: keys::Boolean =
+        keys::Boolean::new_boolean("sparseCheckoutCone", &config::Tree::CORE);
+    /// The `core.notesRef` key.
+    #[cfg(feature = "notes")]
+    pub const NOTES_REF: keys::String = keys::String::new_string("notesRef", &config::Tree::CORE);
     /// The `core.safecrlf` key.
     #[cfg(feature = "attributes")]
     pub const SAFE_CRLF: SafeCrlf = SafeCrlf::new_with_validate("safecrlf", &config::Tree::CORE, validate::SafeCrlf);
@@ -111,6 +125,7 @@ impl Section for Core {
             &Self::PACKED_REFS_TIMEOUT,
             &Self::MULTIPACK_INDEX,
             &Self::LOG_ALL_REF_UPDATES,
+            &Self::PAGER,
             &Self::PRECOMPOSE_UNICODE,
             &Self::REPOSITORY_FORMAT_VERSION,
             &Self::SYMLINKS,
@@ -119,9 +134,14 @@ impl Section for Core {
             &Self::ASKPASS,
             &Self::EXCLUDES_FILE,
             &Self::ATTRIBUTES_FILE,
+            &Self::HOOKS_PATH,
             &Self::SSH_COMMAND,
             &Self::USE_REPLACE_REFS,
             &Self::COMMIT_GRAPH,
+            &Self::SPARSE_CHECKOUT,
+            &Self::SPARSE_CHECKOUT_CONE,
+            #[cfg(feature = "notes")]
+            &Self::NOTES_REF,
             #[cfg(feature = "attributes")]
             &Self::SAFE_CRLF,
             #[cfg(feature = "attributes")]
@@ -434,7 +454,10 @@ mod abbrev {
 }
 
 mod validate {
-    use crate::{bstr::BStr, config::tree::keys};
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, ValueType},
+    };
 
     pub struct LockTimeout;
     impl keys::Validate for LockTimeout {
@@ -445,6 +468,10 @@ mod validate {
             super::Core::FILES_REF_LOCK_TIMEOUT.try_into_lock_timeout(Ok(value?))?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::Integer
+        }
     }
 
     pub struct Disambiguate;
@@ -455,6 +482,10 @@ mod validate {
             super::Core::DISAMBIGUATE.try_into_object_kind_hint(value.into())?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["none", "commit", "committish", "tree", "treeish", "blob"])
+        }
     }
 
     pub struct LogAllRefUpdates;
@@ -464,6 +495,10 @@ mod validate {
                 .try_into_ref_updates(Some(gix_config::Boolean::try_from(value).map(|b| b.0)))?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::BooleanOrOneOf(&["always"])
+        }
     }
 
     pub struct CheckStat;
@@ -472,6 +507,10 @@ mod validate {
             super::Core::CHECK_STAT.try_into_checkstat(value.into())?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["default", "minimal"])
+        }
     }
 
     pub struct Abbrev;
@@ -491,6 +530,10 @@ mod validate {
             super::Core::SAFE_CRLF.try_into_safecrlf(value.into())?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::BooleanOrOneOf(&["warn"])
+        }
     }
 
     pub struct AutoCrlf;
@@ -501,6 +544,10 @@ mod validate {
             super::Core::AUTO_CRLF.try_into_autocrlf(value.into())?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::BooleanOrOneOf(&["input"])
+        }
     }
 
     pub struct Eol;
@@ -511,6 +558,10 @@ mod validate {
             super::Core::EOL.try_into_eol(value.into())?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["lf", "crlf", "native"])
+        }
     }
 
     pub struct CheckRoundTripEncoding;
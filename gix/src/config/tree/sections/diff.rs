@@ -70,7 +70,6 @@ mod algorithm {
 
     use crate::{
         bstr::BStr,
-        config,
         config::{diff::algorithm::Error, tree::sections::diff::Algorithm},
     };
 
@@ -84,9 +83,7 @@ mod algorithm {
             } else if name.eq_ignore_ascii_case(b"histogram") {
                 gix_diff::blob::Algorithm::Histogram
             } else if name.eq_ignore_ascii_case(b"patience") {
-                return Err(config::diff::algorithm::Error::Unimplemented {
-                    name: name.into_owned(),
-                });
+                gix_diff::blob::Algorithm::Patience
             } else {
                 return Err(Error::Unknown {
                     name: name.into_owned(),
@@ -168,7 +165,7 @@ mod renames {
 mod validate {
     use crate::{
         bstr::BStr,
-        config::tree::{keys, Diff},
+        config::tree::{keys, Diff, ValueType},
     };
 
     pub struct Algorithm;
@@ -177,6 +174,10 @@ mod validate {
             Diff::ALGORITHM.try_into_algorithm(value.into())?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["myers", "default", "minimal", "histogram", "patience"])
+        }
     }
 
     pub struct Renames;
@@ -186,6 +187,10 @@ mod validate {
             Diff::RENAMES.try_into_renames(boolean)?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::BooleanOrOneOf(&["copy", "copies"])
+        }
     }
 
     pub struct Binary;
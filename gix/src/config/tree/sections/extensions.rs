@@ -11,6 +11,9 @@ impl Extensions {
         ObjectFormat::new_with_validate("objectFormat", &config::Tree::EXTENSIONS, validate::ObjectFormat).with_note(
             "Support for SHA256 is prepared but not fully implemented yet. For now we abort when encountered",
         );
+    /// The `extensions.partialClone` key, naming the remote that promises to provide objects missing locally.
+    pub const PARTIAL_CLONE: keys::RemoteName =
+        keys::RemoteName::new_remote_name("partialClone", &config::Tree::EXTENSIONS);
 }
 
 /// The `core.checkStat` key.
@@ -41,12 +44,15 @@ impl Section for Extensions {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::OBJECT_FORMAT, &Self::WORKTREE_CONFIG]
+        &[&Self::OBJECT_FORMAT, &Self::PARTIAL_CLONE, &Self::WORKTREE_CONFIG]
     }
 }
 
 mod validate {
-    use crate::{bstr::BStr, config::tree::keys};
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, ValueType},
+    };
 
     pub struct ObjectFormat;
 
@@ -55,5 +61,9 @@ mod validate {
             super::Extensions::OBJECT_FORMAT.try_into_object_format(value.into())?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["sha1"])
+        }
     }
 }
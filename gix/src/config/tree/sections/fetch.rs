@@ -75,7 +75,10 @@ mod algorithm {
 }
 
 mod validate {
-    use crate::{bstr::BStr, config::tree::keys};
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, ValueType},
+    };
 
     pub struct NegotiationAlgorithm;
     impl keys::Validate for NegotiationAlgorithm {
@@ -85,6 +88,10 @@ mod validate {
             crate::config::tree::Fetch::NEGOTIATION_ALGORITHM.try_into_negotiation_algorithm(value.into())?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["noop", "consecutive", "default", "skipping"])
+        }
     }
 
     pub struct RecurseSubmodules;
@@ -98,5 +105,9 @@ mod validate {
             }
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::BooleanOrOneOf(&["on-demand"])
+        }
     }
 }
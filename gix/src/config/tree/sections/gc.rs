@@ -0,0 +1,100 @@
+use crate::{
+    config,
+    config::tree::{keys, Gc, Key, Section},
+};
+
+impl Gc {
+    /// The `gc.rerereResolved` key.
+    pub const RERERE_RESOLVED: Expiry =
+        Expiry::new_with_validate("rerereResolved", &config::Tree::GC, validate::Expiry);
+    /// The `gc.rerereUnresolved` key.
+    pub const RERERE_UNRESOLVED: Expiry =
+        Expiry::new_with_validate("rerereUnresolved", &config::Tree::GC, validate::Expiry);
+    /// The `gc.reflogExpire` key.
+    pub const REFLOG_EXPIRE: Expiry = Expiry::new_with_validate("reflogExpire", &config::Tree::GC, validate::Expiry);
+    /// The `gc.reflogExpireUnreachable` key.
+    pub const REFLOG_EXPIRE_UNREACHABLE: Expiry =
+        Expiry::new_with_validate("reflogExpireUnreachable", &config::Tree::GC, validate::Expiry);
+    /// The `gc.pruneExpire` key.
+    pub const PRUNE_EXPIRE: Expiry = Expiry::new_with_validate("pruneExpire", &config::Tree::GC, validate::Expiry);
+}
+
+impl Section for Gc {
+    fn name(&self) -> &str {
+        "gc"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::RERERE_RESOLVED,
+            &Self::RERERE_UNRESOLVED,
+            &Self::REFLOG_EXPIRE,
+            &Self::REFLOG_EXPIRE_UNREACHABLE,
+            &Self::PRUNE_EXPIRE,
+        ]
+    }
+}
+
+/// A key that denotes how long something is kept before it expires, like `gc.rerereResolved`.
+pub type Expiry = keys::Any<validate::Expiry>;
+
+mod expiry {
+    use std::{borrow::Cow, time::SystemTime};
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::gc::Expiry,
+    };
+
+    impl Expiry {
+        /// Convert `value` into the point in time before which items expire, with `now` being the current time,
+        /// or `None` if nothing ever expires.
+        ///
+        /// Integers are interpreted as days, `never` and `false` never expire, and `now` and `all` expire everything.
+        /// Otherwise the value is parsed as date, with `.` allowed as separator, like in `2.weeks.ago`.
+        pub fn try_into_expiry_date(
+            &'static self,
+            value: Cow<'_, BStr>,
+            now: SystemTime,
+        ) -> Result<Option<SystemTime>, config::key::GenericErrorWithValue> {
+            const DAY_IN_SECONDS: u64 = 24 * 60 * 60;
+            let err = || config::key::GenericErrorWithValue::from_value(self, value.clone().into_owned());
+            let value_str = value.to_str().map_err(|_| err())?;
+            Ok(match value_str {
+                "never" | "false" => None,
+                "now" | "all" => Some(now),
+                _ => {
+                    if let Ok(days) = value_str.parse::<u64>() {
+                        Some(
+                            now.checked_sub(std::time::Duration::from_secs(days.saturating_mul(DAY_IN_SECONDS)))
+                                .unwrap_or(SystemTime::UNIX_EPOCH),
+                        )
+                    } else {
+                        let time = gix_date::parse(&value_str.replace('.', " "), Some(now)).map_err(|_| err())?;
+                        Some(if time.seconds < 0 {
+                            SystemTime::UNIX_EPOCH
+                        } else {
+                            SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(time.seconds as u64)
+                        })
+                    }
+                }
+            })
+        }
+    }
+}
+
+mod validate {
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, Gc},
+    };
+
+    pub struct Expiry;
+    impl keys::Validate for Expiry {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Gc::RERERE_RESOLVED.try_into_expiry_date(value.into(), std::time::SystemTime::now())?;
+            Ok(())
+        }
+    }
+}
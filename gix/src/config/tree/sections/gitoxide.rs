@@ -202,6 +202,12 @@ mod subsections {
         pub const PROXY_AUTH_METHOD: http::ProxyAuthMethod =
             http::ProxyAuthMethod::new_proxy_auth_method("proxyAuthMethod", &Gitoxide::HTTP)
                 .with_environment_override("GIT_HTTP_PROXY_AUTHMETHOD");
+        /// The `gitoxide.http.authMethod` key.
+        pub const AUTH_METHOD: http::ProxyAuthMethod = http::ProxyAuthMethod::new_proxy_auth_method(
+            "authMethod",
+            &Gitoxide::HTTP,
+        )
+        .with_note("entirely new, to authenticate against the server using NTLM or Negotiate instead of HTTP basic authentication, with the same values as `http.proxyAuthMethod`");
     }
 
     impl Section for Http {
@@ -220,6 +226,7 @@ mod subsections {
                 &Self::SSL_VERSION_MAX,
                 &Self::SSL_NO_VERIFY,
                 &Self::PROXY_AUTH_METHOD,
+                &Self::AUTH_METHOD,
             ]
         }
 
@@ -345,6 +352,9 @@ mod subsections {
             keys::Executable::new_executable("commandWithoutShellFallback", &Gitoxide::SSH)
                 .with_environment_override("GIT_SSH")
                 .with_note("is always executed without shell and treated as fallback");
+        /// The `gitoxide.ssh.batchMode` key.
+        pub const BATCH_MODE: keys::Boolean = keys::Boolean::new_boolean("batchMode", &Gitoxide::SSH)
+            .with_note("prevents ssh from prompting for passwords or passphrases, useful for non-interactive services");
     }
 
     impl Section for Ssh {
@@ -353,7 +363,7 @@ mod subsections {
         }
 
         fn keys(&self) -> &[&dyn Key] {
-            &[&Self::COMMAND_WITHOUT_SHELL_FALLBACK]
+            &[&Self::COMMAND_WITHOUT_SHELL_FALLBACK, &Self::BATCH_MODE]
         }
 
         fn parent(&self) -> Option<&dyn Section> {
@@ -532,7 +542,10 @@ pub use subsections::{Allow, Author, Commit, Committer, Core, Credentials, Http,
 pub mod validate {
     use std::error::Error;
 
-    use crate::{bstr::BStr, config::tree::keys::Validate};
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys::Validate, ValueType},
+    };
 
     pub struct ProtocolFromUser;
     impl Validate for ProtocolFromUser {
@@ -542,6 +555,10 @@ pub mod validate {
             }
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["1"])
+        }
     }
 
     pub struct RefsNamespace;
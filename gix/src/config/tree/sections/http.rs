@@ -48,6 +48,20 @@ impl Http {
     /// The `http.sslCAInfo` key.
     pub const SSL_CA_INFO: keys::Path =
         keys::Path::new_path("sslCAInfo", &config::Tree::HTTP).with_environment_override("GIT_SSL_CAINFO");
+    /// The `http.sslCert` key.
+    pub const SSL_CERT: keys::Path =
+        keys::Path::new_path("sslCert", &config::Tree::HTTP).with_environment_override("GIT_SSL_CERT");
+    /// The `http.sslKey` key.
+    pub const SSL_KEY: keys::Path =
+        keys::Path::new_path("sslKey", &config::Tree::HTTP).with_environment_override("GIT_SSL_KEY");
+    /// The `http.sslCertPasswordProtected` key.
+    pub const SSL_CERT_PASSWORD_PROTECTED: keys::Boolean =
+        keys::Boolean::new_boolean("sslCertPasswordProtected", &config::Tree::HTTP)
+            .with_environment_override("GIT_SSL_CERT_PASSWORD_PROTECTED");
+    /// The `http.emptyAuth` key.
+    pub const EMPTY_AUTH: keys::Boolean = keys::Boolean::new_boolean("emptyAuth", &config::Tree::HTTP).with_deviation(
+        "doesn't support 'auto', which `git` uses by default to try empty credentials if the server offers Negotiate",
+    );
     /// The `http.schannelCheckRevoke` key.
     pub const SCHANNEL_CHECK_REVOKE: keys::Boolean =
         keys::Boolean::new_boolean("schannelCheckRevoke", &config::Tree::HTTP);
@@ -72,6 +86,10 @@ impl Section for Http {
             &Self::LOW_SPEED_LIMIT,
             &Self::SCHANNEL_USE_SSL_CA_INFO,
             &Self::SSL_CA_INFO,
+            &Self::SSL_CERT,
+            &Self::SSL_KEY,
+            &Self::SSL_CERT_PASSWORD_PROTECTED,
+            &Self::EMPTY_AUTH,
             &Self::SCHANNEL_CHECK_REVOKE,
         ]
     }
@@ -255,7 +273,7 @@ pub mod validate {
 
     use crate::{
         bstr::{BStr, ByteSlice},
-        config::tree::keys::Validate,
+        config::tree::{keys::Validate, ValueType},
     };
 
     pub struct SslVersion;
@@ -269,6 +287,12 @@ pub mod validate {
 
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&[
+                "default", "tlsv1", "sslv2", "sslv3", "tlsv1.0", "tlsv1.1", "tlsv1.2", "tlsv1.3",
+            ])
+        }
     }
 
     pub struct ProxyAuthMethod;
@@ -282,6 +306,10 @@ pub mod validate {
 
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["anyauth", "basic", "digest", "negotiate", "ntlm"])
+        }
     }
 
     pub struct Version;
@@ -295,6 +323,10 @@ pub mod validate {
 
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["HTTP/1.1", "HTTP/2"])
+        }
     }
 
     pub struct ExtraHeader;
@@ -303,6 +335,10 @@ pub mod validate {
             value.to_str()?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::String
+        }
     }
 
     pub struct FollowRedirects;
@@ -317,5 +353,9 @@ pub mod validate {
             })?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::BooleanOrOneOf(&["initial"])
+        }
     }
 }
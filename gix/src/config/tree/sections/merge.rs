@@ -0,0 +1,91 @@
+use crate::{
+    config,
+    config::tree::{diff, keys, Key, Merge, Section, SubSectionRequirement},
+};
+
+const DRIVER_PARAMETER: Option<SubSectionRequirement> = Some(SubSectionRequirement::Parameter("driver"));
+
+impl Merge {
+    /// The `merge.renames` key, which defaults to `diff.renames`.
+    pub const RENAMES: diff::Renames = diff::Renames::new_renames("renames", &config::Tree::MERGE);
+    /// The `merge.renameLimit` key, which defaults to `diff.renameLimit`.
+    pub const RENAME_LIMIT: keys::UnsignedInteger = keys::UnsignedInteger::new_unsigned_integer(
+        "renameLimit",
+        &config::Tree::MERGE,
+    )
+    .with_note(
+        "The limit is actually squared, so 1000 stands for up to 1 million diffs if fuzzy rename tracking is enabled",
+    );
+    /// The `merge.directoryRenames` key.
+    pub const DIRECTORY_RENAMES: DirectoryRenames =
+        DirectoryRenames::new_with_validate("directoryRenames", &config::Tree::MERGE, validate::DirectoryRenames);
+    /// The `merge.default` key, naming the merge driver to use for files without the `merge` attribute.
+    pub const DEFAULT: keys::String = keys::String::new_string("default", &config::Tree::MERGE);
+    /// The `merge.<driver>.name` key.
+    pub const DRIVER_NAME: keys::String =
+        keys::String::new_string("name", &config::Tree::MERGE).with_subsection_requirement(DRIVER_PARAMETER);
+    /// The `merge.<driver>.driver` key.
+    pub const DRIVER_DRIVER: keys::Program =
+        keys::Program::new_program("driver", &config::Tree::MERGE).with_subsection_requirement(DRIVER_PARAMETER);
+    /// The `merge.<driver>.recursive` key.
+    pub const DRIVER_RECURSIVE: keys::String = keys::String::new_string("recursive", &config::Tree::MERGE)
+        .with_subsection_requirement(DRIVER_PARAMETER)
+        .with_note("only recorded, as virtual merge bases aren't created");
+}
+
+impl Section for Merge {
+    fn name(&self) -> &str {
+        "merge"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::RENAMES,
+            &Self::RENAME_LIMIT,
+            &Self::DIRECTORY_RENAMES,
+            &Self::DEFAULT,
+            &Self::DRIVER_NAME,
+            &Self::DRIVER_DRIVER,
+            &Self::DRIVER_RECURSIVE,
+        ]
+    }
+}
+
+/// The `merge.directoryRenames` key.
+pub type DirectoryRenames = keys::Any<validate::DirectoryRenames>;
+
+mod directory_renames {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::merge::DirectoryRenames, merge::tree};
+
+    impl DirectoryRenames {
+        /// Convert `value` into the way files that were added to renamed directories are handled.
+        pub fn try_into_directory_renames(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<tree::DirectoryRenames, config::key::GenericErrorWithValue> {
+            tree::DirectoryRenames::from_name(value.as_ref())
+                .ok_or_else(|| config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+}
+
+mod validate {
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, Merge, ValueType},
+    };
+
+    pub struct DirectoryRenames;
+    impl keys::Validate for DirectoryRenames {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Merge::DIRECTORY_RENAMES.try_into_directory_renames(value.into())?;
+            Ok(())
+        }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::BooleanOrOneOf(&["conflict"])
+        }
+    }
+}
@@ -1,5 +1,12 @@
 #![allow(missing_docs)]
 
+/// The `apply` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "apply")]
+pub struct Apply;
+#[cfg(feature = "apply")]
+pub mod apply;
+
 /// The `author` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Author;
@@ -57,6 +64,11 @@ pub mod fetch;
 pub struct Gitoxide;
 pub mod gitoxide;
 
+/// The `gc` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gc;
+pub mod gc;
+
 /// The `http` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Http;
@@ -76,6 +88,20 @@ mod init;
 pub struct Mailmap;
 mod mailmap;
 
+/// The `merge` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "merge")]
+pub struct Merge;
+#[cfg(feature = "merge")]
+pub mod merge;
+
+/// The `notes` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "notes")]
+pub struct Notes;
+#[cfg(feature = "notes")]
+pub mod notes;
+
 /// The `pack` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Pack;
@@ -96,16 +122,47 @@ pub mod push;
 pub struct Remote;
 pub mod remote;
 
+/// The `rerere` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "merge")]
+pub struct Rerere;
+#[cfg(feature = "merge")]
+mod rerere;
+
 /// The `safe` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Safe;
 mod safe;
 
+/// The `sequence` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Sequence;
+mod sequence;
+
 /// The `ssh` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Ssh;
 pub mod ssh;
 
+/// The `status` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "status")]
+pub struct Status;
+#[cfg(feature = "status")]
+mod status;
+
+/// The `submodule` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "attributes")]
+pub struct Submodule;
+#[cfg(feature = "attributes")]
+pub mod submodule;
+
+/// The `transfer` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Transfer;
+mod transfer;
+
 /// The `user` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct User;
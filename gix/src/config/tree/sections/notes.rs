@@ -0,0 +1,59 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Notes, Section, SubSectionRequirement},
+};
+
+impl Notes {
+    /// The `notes.mergeStrategy` key.
+    pub const MERGE_STRATEGY: MergeStrategy =
+        MergeStrategy::new_with_validate("mergeStrategy", &config::Tree::NOTES, validate::MergeStrategy);
+    /// The `notes.<name>.mergeStrategy` key, with `<name>` being the notes reference without the `refs/notes/` prefix.
+    pub const REF_MERGE_STRATEGY: MergeStrategy =
+        MergeStrategy::new_with_validate("mergeStrategy", &config::Tree::NOTES, validate::MergeStrategy)
+            .with_subsection_requirement(Some(SubSectionRequirement::Parameter("name")));
+}
+
+impl Section for Notes {
+    fn name(&self) -> &str {
+        "notes"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::MERGE_STRATEGY, &Self::REF_MERGE_STRATEGY]
+    }
+}
+
+/// The `notes.mergeStrategy` key.
+pub type MergeStrategy = keys::Any<validate::MergeStrategy>;
+
+mod merge_strategy {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::notes::MergeStrategy, notes::merge::Strategy};
+
+    impl MergeStrategy {
+        /// Convert `value` into the strategy to resolve conflicting notes with.
+        pub fn try_into_strategy(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<Strategy, config::key::GenericErrorWithValue> {
+            Strategy::from_name(value.as_ref())
+                .ok_or_else(|| config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+}
+
+mod validate {
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, Notes},
+    };
+
+    pub struct MergeStrategy;
+    impl keys::Validate for MergeStrategy {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Notes::MERGE_STRATEGY.try_into_strategy(value.into())?;
+            Ok(())
+        }
+    }
+}
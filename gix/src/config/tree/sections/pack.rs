@@ -47,7 +47,10 @@ impl Section for Pack {
 }
 
 mod validate {
-    use crate::{bstr::BStr, config::tree::keys};
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, ValueType},
+    };
 
     pub struct IndexVersion;
     impl keys::Validate for IndexVersion {
@@ -60,5 +63,9 @@ mod validate {
             ))?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::Integer
+        }
     }
 }
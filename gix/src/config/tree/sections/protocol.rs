@@ -109,7 +109,10 @@ mod key_impls {
 }
 
 mod validate {
-    use crate::{bstr::BStr, config::tree::keys};
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, ValueType},
+    };
 
     pub struct Allow;
     impl keys::Validate for Allow {
@@ -118,6 +121,10 @@ mod validate {
             super::Protocol::ALLOW.try_into_allow(std::borrow::Cow::Borrowed(_value), None)?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["always", "never", "user"])
+        }
     }
 
     pub struct Version;
@@ -131,5 +138,9 @@ mod validate {
                 _ => Err(format!("protocol version {value} is unknown").into()),
             }
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["0", "1", "2"])
+        }
     }
 }
@@ -53,12 +53,19 @@ mod validate {
     pub struct Default;
     use std::{borrow::Cow, error::Error};
 
-    use crate::{bstr::BStr, config::tree::keys::Validate};
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys::Validate, ValueType},
+    };
 
     impl Validate for Default {
         fn validate(&self, value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
             super::Push::DEFAULT.try_into_default(Cow::Borrowed(value))?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["nothing", "current", "upstream", "tracking", "simple", "matching"])
+        }
     }
 }
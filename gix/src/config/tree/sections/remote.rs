@@ -31,6 +31,13 @@ impl Remote {
         http::ProxyAuthMethod::new_proxy_auth_method("proxyAuthMethod", &config::Tree::REMOTE)
             .with_subsection_requirement(NAME_PARAMETER)
             .with_deviation("implemented like git, but never actually tried");
+    /// The `remote.<name>.promisor` key.
+    pub const PROMISOR: keys::Boolean =
+        keys::Boolean::new_boolean("promisor", &config::Tree::REMOTE).with_subsection_requirement(NAME_PARAMETER);
+    /// The `remote.<name>.partialCloneFilter` key.
+    pub const PARTIAL_CLONE_FILTER: keys::String =
+        keys::String::new_string("partialCloneFilter", &config::Tree::REMOTE)
+            .with_subsection_requirement(NAME_PARAMETER);
 }
 
 impl Section for Remote {
@@ -48,6 +55,8 @@ impl Section for Remote {
             &Self::PUSH,
             &Self::PROXY,
             &Self::PROXY_AUTH_METHOD,
+            &Self::PROMISOR,
+            &Self::PARTIAL_CLONE_FILTER,
         ]
     }
 }
@@ -89,7 +98,10 @@ mod tag_opts {
 pub mod validate {
     use std::{borrow::Cow, error::Error};
 
-    use crate::{bstr::BStr, config::tree::keys::Validate};
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys::Validate, ValueType},
+    };
 
     pub struct TagOpt;
     impl Validate for TagOpt {
@@ -97,5 +109,9 @@ pub mod validate {
             super::Remote::TAG_OPT.try_into_tag_opt(Cow::Borrowed(value))?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["--tags", "--no-tags"])
+        }
     }
 }
@@ -0,0 +1,21 @@
+use crate::config::{
+    tree::{keys, Key, Rerere, Section},
+    Tree,
+};
+
+impl Rerere {
+    /// The `rerere.enabled` key.
+    pub const ENABLED: keys::Boolean = keys::Boolean::new_boolean("enabled", &Tree::RERERE);
+    /// The `rerere.autoUpdate` key.
+    pub const AUTO_UPDATE: keys::Boolean = keys::Boolean::new_boolean("autoUpdate", &Tree::RERERE);
+}
+
+impl Section for Rerere {
+    fn name(&self) -> &str {
+        "rerere"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::ENABLED, &Self::AUTO_UPDATE]
+    }
+}
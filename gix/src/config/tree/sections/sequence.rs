@@ -0,0 +1,20 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Section, Sequence},
+};
+
+impl Sequence {
+    /// The `sequence.editor` key.
+    pub const EDITOR: keys::Program =
+        keys::Program::new_program("editor", &config::Tree::SEQUENCE).with_environment_override("GIT_SEQUENCE_EDITOR");
+}
+
+impl Section for Sequence {
+    fn name(&self) -> &str {
+        "sequence"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::EDITOR]
+    }
+}
@@ -52,7 +52,10 @@ impl Section for Ssh {
 }
 
 mod validate {
-    use crate::{bstr::BStr, config::tree::keys};
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, ValueType},
+    };
 
     pub struct Variant;
     impl keys::Validate for Variant {
@@ -61,5 +64,9 @@ mod validate {
             super::Ssh::VARIANT.try_into_variant(_value.into())?;
             Ok(())
         }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["auto", "ssh", "plink", "putty", "tortoiseplink", "simple"])
+        }
     }
 }
@@ -0,0 +1,22 @@
+use crate::config::{
+    tree::{diff::Renames, keys, Key, Section, Status},
+    Tree,
+};
+
+impl Status {
+    /// The `status.renameLimit` key, which defaults to `diff.renameLimit`.
+    pub const RENAME_LIMIT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("renameLimit", &Tree::STATUS);
+    /// The `status.renames` key, which defaults to `diff.renames`.
+    pub const RENAMES: Renames = Renames::new_renames("renames", &Tree::STATUS);
+}
+
+impl Section for Status {
+    fn name(&self) -> &str {
+        "status"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::RENAME_LIMIT, &Self::RENAMES]
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{
+    config,
+    config::tree::{keys, traits::SubSectionRequirement, Key, Section, Submodule},
+};
+
+const NAME_PARAMETER: Option<SubSectionRequirement> = Some(SubSectionRequirement::Parameter("name"));
+
+impl Submodule {
+    /// The `submodule.active` key, with each value being a pathspec that is matched against the path of submodules.
+    pub const ACTIVE: keys::String = keys::String::new_string("active", &config::Tree::SUBMODULE)
+        .with_note("multi-value key, with all values contributing to the pathspec");
+    /// The `submodule.recurse` key.
+    pub const RECURSE: keys::Boolean = keys::Boolean::new_boolean("recurse", &config::Tree::SUBMODULE);
+    /// The `submodule.propagateBranches` key.
+    pub const PROPAGATE_BRANCHES: keys::Boolean =
+        keys::Boolean::new_boolean("propagateBranches", &config::Tree::SUBMODULE);
+    /// The `submodule.fetchJobs` key.
+    pub const FETCH_JOBS: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("fetchJobs", &config::Tree::SUBMODULE);
+    /// The `submodule.alternateLocation` key.
+    pub const ALTERNATE_LOCATION: keys::String =
+        keys::String::new_string("alternateLocation", &config::Tree::SUBMODULE);
+    /// The `submodule.alternateErrorStrategy` key.
+    pub const ALTERNATE_ERROR_STRATEGY: keys::String =
+        keys::String::new_string("alternateErrorStrategy", &config::Tree::SUBMODULE);
+
+    /// The `submodule.<name>.active` key.
+    pub const NAME_ACTIVE: keys::Boolean =
+        keys::Boolean::new_boolean("active", &config::Tree::SUBMODULE).with_subsection_requirement(NAME_PARAMETER);
+    /// The `submodule.<name>.url` key.
+    pub const URL: keys::Url =
+        keys::Url::new_url("url", &config::Tree::SUBMODULE).with_subsection_requirement(NAME_PARAMETER);
+    /// The `submodule.<name>.update` key.
+    pub const UPDATE: Update = Update::new_with_validate("update", &config::Tree::SUBMODULE, validate::Update)
+        .with_subsection_requirement(NAME_PARAMETER);
+    /// The `submodule.<name>.branch` key.
+    pub const BRANCH: keys::String =
+        keys::String::new_string("branch", &config::Tree::SUBMODULE).with_subsection_requirement(NAME_PARAMETER);
+    /// The `submodule.<name>.fetchRecurseSubmodules` key.
+    pub const FETCH_RECURSE_SUBMODULES: FetchRecurseSubmodules = FetchRecurseSubmodules::new_with_validate(
+        "fetchRecurseSubmodules",
+        &config::Tree::SUBMODULE,
+        validate::FetchRecurseSubmodules,
+    )
+    .with_subsection_requirement(NAME_PARAMETER);
+    /// The `submodule.<name>.ignore` key.
+    pub const IGNORE: Ignore = Ignore::new_with_validate("ignore", &config::Tree::SUBMODULE, validate::Ignore)
+        .with_subsection_requirement(NAME_PARAMETER);
+}
+
+impl Section for Submodule {
+    fn name(&self) -> &str {
+        "submodule"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::ACTIVE,
+            &Self::RECURSE,
+            &Self::PROPAGATE_BRANCHES,
+            &Self::FETCH_JOBS,
+            &Self::ALTERNATE_LOCATION,
+            &Self::ALTERNATE_ERROR_STRATEGY,
+            &Self::NAME_ACTIVE,
+            &Self::URL,
+            &Self::UPDATE,
+            &Self::BRANCH,
+            &Self::FETCH_RECURSE_SUBMODULES,
+            &Self::IGNORE,
+        ]
+    }
+}
+
+/// The `submodule.<name>.update` key.
+pub type Update = keys::Any<validate::Update>;
+
+/// The `submodule.<name>.fetchRecurseSubmodules` key.
+pub type FetchRecurseSubmodules = keys::Any<validate::FetchRecurseSubmodules>;
+
+/// The `submodule.<name>.ignore` key.
+pub type Ignore = keys::Any<validate::Ignore>;
+
+mod update {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::submodule::Update};
+
+    impl Update {
+        /// Convert `value` into the way a submodule should be updated.
+        ///
+        /// Unlike in `.gitmodules` files, commands like `!command` are allowed here.
+        pub fn try_into_update(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_submodule::config::Update, config::key::GenericErrorWithValue> {
+            gix_submodule::config::Update::try_from(value.as_ref())
+                .map_err(|()| config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+}
+
+mod fetch_recurse_submodules {
+    use crate::{config, config::tree::submodule::FetchRecurseSubmodules};
+
+    impl FetchRecurseSubmodules {
+        /// Convert `value`, which is either a boolean or `on-demand`, into the way the submodule should be fetched.
+        pub fn try_into_fetch_recurse(
+            &'static self,
+            value: Result<bool, gix_config::value::Error>,
+        ) -> Result<gix_submodule::config::FetchRecurse, config::key::GenericErrorWithValue> {
+            gix_submodule::config::FetchRecurse::new(value)
+                .map_err(|value| config::key::GenericErrorWithValue::from_value(self, value))
+        }
+    }
+}
+
+mod ignore {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::submodule::Ignore};
+
+    impl Ignore {
+        /// Convert `value` into the changes of a submodule to ignore in status queries.
+        pub fn try_into_ignore(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_submodule::config::Ignore, config::key::GenericErrorWithValue> {
+            gix_submodule::config::Ignore::try_from(value.as_ref())
+                .map_err(|()| config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+}
+
+mod validate {
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, Submodule, ValueType},
+    };
+
+    pub struct Update;
+    impl keys::Validate for Update {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Submodule::UPDATE.try_into_update(value.into())?;
+            Ok(())
+        }
+    }
+
+    pub struct FetchRecurseSubmodules;
+    impl keys::Validate for FetchRecurseSubmodules {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            let boolean = gix_config::Boolean::try_from(value).map(|b| b.0);
+            Submodule::FETCH_RECURSE_SUBMODULES.try_into_fetch_recurse(boolean)?;
+            Ok(())
+        }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::BooleanOrOneOf(&["on-demand"])
+        }
+    }
+
+    pub struct Ignore;
+    impl keys::Validate for Ignore {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Submodule::IGNORE.try_into_ignore(value.into())?;
+            Ok(())
+        }
+
+        fn value_type(&self) -> ValueType {
+            ValueType::OneOf(&["all", "dirty", "untracked", "none"])
+        }
+    }
+}
@@ -46,6 +46,46 @@ pub enum Note {
     Deviation(&'static str),
 }
 
+/// The kind of value a key expects, as far as it is known statically.
+///
+/// It's meant for tools that present or check configuration without converting values, and is informative only
+/// as [`Key::validate()`] remains authoritative.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ValueType {
+    /// Any value is accepted, or nothing more specific is known about it.
+    Any,
+    /// A UTF-8 encoded string.
+    String,
+    /// A git boolean, like `true`, `no` or `1`.
+    Boolean,
+    /// A signed integer, possibly with a `k`, `m` or `g` suffix.
+    Integer,
+    /// An unsigned integer, possibly with a `k`, `m` or `g` suffix.
+    UnsignedInteger,
+    /// An integer denoting a duration in milliseconds.
+    DurationInMilliseconds,
+    /// A date as understood by git, like `2 weeks ago`.
+    Time,
+    /// A path, which may be subject to interpolation.
+    Path,
+    /// A program along with its arguments, to be executed by a shell.
+    Program,
+    /// The name of or path to an executable.
+    Executable,
+    /// A URL as understood by git.
+    Url,
+    /// A remote, either by its symbolic name or as URL.
+    RemoteName,
+    /// A ref-spec for fetching.
+    FetchRefSpec,
+    /// A ref-spec for pushing.
+    PushRefSpec,
+    /// Exactly one of the given values.
+    OneOf(&'static [&'static str]),
+    /// A git boolean, or exactly one of the given values.
+    BooleanOrOneOf(&'static [&'static str]),
+}
+
 /// A leaf-level entry in the git configuration, like `url` in `remote.origin.url`.
 pub trait Key: std::fmt::Debug {
     /// The key's name, like `url` in `remote.origin.url`.
@@ -68,6 +108,10 @@ pub trait Key: std::fmt::Debug {
     fn note(&self) -> Option<&Note> {
         None
     }
+    /// Return the kind of value this key expects, along with the allowed values if these are known.
+    fn value_type(&self) -> ValueType {
+        ValueType::Any
+    }
 
     /// Return the name of an environment variable that would override this value (after following links until one is found).
     fn environment_override(&self) -> Option<&str> {
@@ -116,10 +116,19 @@ pub struct Options {
     /// By default repos with worktree can be initialized into a non-empty repository as long as there is no `.git` directory.
     pub destination_must_be_empty: bool,
     /// If set, use these filesystem capabilities to populate the respective git-config fields.
-    /// If `None`, the directory will be probed.
+    /// If `None`, the directory will be probed, or the [cached](fs_capabilities_cache()) values of its filesystem are used.
     pub fs_capabilities: Option<gix_fs::Capabilities>,
 }
 
+/// Return the cache of filesystem capabilities used by [`into()`] when probing the filesystem of new repositories.
+///
+/// Probing is performed only once per filesystem within a process, so entries may have to be
+/// [invalidated](gix_fs::capabilities::Cache::invalidate()) if the filesystem behind a location changes.
+pub fn fs_capabilities_cache() -> &'static gix_fs::capabilities::Cache {
+    static CACHE: once_cell::sync::Lazy<gix_fs::capabilities::Cache> = once_cell::sync::Lazy::new(Default::default);
+    &CACHE
+}
+
 /// Create a new `.git` repository of `kind` within the possibly non-existing `directory`
 /// and return its path.
 /// Note that this is a simple template-based initialization routine which should be accompanied with additional corrections
@@ -208,7 +217,7 @@ pub fn into(
     let caps = {
         let mut config = gix_config::File::default();
         let caps = {
-            let caps = fs_capabilities.unwrap_or_else(|| gix_fs::Capabilities::probe(&dot_git));
+            let caps = fs_capabilities.unwrap_or_else(|| fs_capabilities_cache().probe(&dot_git));
             let mut core = config.new_section("core", None).expect("valid section name");
 
             core.push(key("repositoryformatversion"), Some("0".into()));
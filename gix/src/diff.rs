@@ -70,10 +70,47 @@ mod utils {
         config: &gix_config::File<'static>,
         lenient: bool,
     ) -> Result<Option<Rewrites>, new_rewrites::Error> {
-        let key = "diff.renames";
+        new_rewrites_inner(config, lenient, &Diff::RENAMES, &Diff::RENAME_LIMIT, false)
+    }
+
+    /// Like [`new_rewrites()`], but read `status.renames` and `status.renameLimit`, which default to
+    /// `diff.renames` and `diff.renameLimit` respectively.
+    /// Returns `Ok(None)` only if rename tracking is disabled, as like in `git`, it's enabled if nothing is configured.
+    #[cfg(feature = "status")]
+    #[allow(clippy::result_large_err)]
+    pub fn new_status_rewrites(
+        config: &gix_config::File<'static>,
+        lenient: bool,
+    ) -> Result<Option<Rewrites>, new_rewrites::Error> {
+        use crate::config::tree::{Key, Status};
+        let renames = if config.boolean_by_key(Status::RENAMES.logical_name().as_str()).is_some() {
+            &Status::RENAMES
+        } else {
+            &Diff::RENAMES
+        };
+        let limit = if config
+            .integer_by_key(Status::RENAME_LIMIT.logical_name().as_str())
+            .is_some()
+        {
+            &Status::RENAME_LIMIT
+        } else {
+            &Diff::RENAME_LIMIT
+        };
+        new_rewrites_inner(config, lenient, renames, limit, true)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn new_rewrites_inner(
+        config: &gix_config::File<'static>,
+        lenient: bool,
+        renames: &'static crate::config::tree::diff::Renames,
+        rename_limit: &'static crate::config::tree::keys::UnsignedInteger,
+        enabled_by_default: bool,
+    ) -> Result<Option<Rewrites>, new_rewrites::Error> {
+        use crate::config::tree::Key;
         let copies = match config
-            .boolean_by_key(key)
-            .map(|value| Diff::RENAMES.try_into_renames(value))
+            .boolean_by_key(renames.logical_name().as_str())
+            .map(|value| renames.try_into_renames(value))
             .transpose()
             .with_leniency(lenient)?
         {
@@ -82,6 +119,7 @@ mod utils {
                 Tracking::Renames => None,
                 Tracking::RenamesAndCopies => Some(Copies::default()),
             },
+            None if enabled_by_default => None,
             None => return Ok(None),
         };
 
@@ -89,8 +127,8 @@ mod utils {
         Ok(Rewrites {
             copies,
             limit: config
-                .integer_by_key("diff.renameLimit")
-                .map(|value| Diff::RENAME_LIMIT.try_into_usize(value))
+                .integer_by_key(rename_limit.logical_name().as_str())
+                .map(|value| rename_limit.try_into_usize(value))
                 .transpose()
                 .with_leniency(lenient)?
                 .unwrap_or(default.limit),
@@ -140,5 +178,7 @@ mod utils {
         Ok(diff_cache)
     }
 }
+#[cfg(feature = "status")]
+pub use utils::new_status_rewrites;
 #[cfg(feature = "blob-diff")]
 pub use utils::{new_rewrites, resource_cache};
@@ -0,0 +1,177 @@
+//! Stable codes to classify the errors of high-level operations.
+//!
+//! Errors of operations like cloning, fetching, checking out and committing implement [`ErrorCode`] so applications can
+//! decide how to react to them, for example by asking for credentials again, without inspecting error messages.
+
+/// A coarse classification of an error, which stays the same even if the error it was obtained from changes its structure.
+///
+/// New codes may be added over time, which is why applications should also handle codes they don't know.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Code {
+    /// Credentials were missing, couldn't be obtained or were rejected by the remote.
+    AuthenticationFailed,
+    /// The remote couldn't be reached or the connection broke, which might be resolved by trying again.
+    Network,
+    /// The remote sent something unexpected or lacks a feature that is required.
+    Protocol,
+    /// Objects or other repository data are missing or corrupt.
+    ObjectCorrupt,
+    /// The operation refused to overwrite existing files or local changes.
+    WouldOverwriteLocalChanges,
+    /// A reference didn't have the value it was expected to have, probably as it was changed concurrently.
+    Conflict,
+    /// A lock could not be obtained as it is held by another process.
+    Locked,
+    /// Configuration is missing, or its values are invalid.
+    Configuration,
+    /// An input, like a URL or reference name, is invalid.
+    InvalidInput,
+    /// The operation needs a worktree, but the repository is bare.
+    WorktreeRequired,
+    /// A hook rejected the operation.
+    HookRejected,
+    /// The operation was interrupted.
+    Interrupted,
+    /// Reading from or writing to the filesystem failed.
+    Io,
+    /// None of the other codes applies.
+    Other,
+}
+
+/// Obtain the [`Code`] of an error.
+pub trait ErrorCode {
+    /// Return the code that classifies this error.
+    fn code(&self) -> Code;
+}
+
+pub(crate) fn io(err: &std::io::Error) -> Code {
+    match err.kind() {
+        std::io::ErrorKind::Interrupted => Code::Interrupted,
+        _ => Code::Io,
+    }
+}
+
+pub(crate) fn lock_acquire(err: &gix_lock::acquire::Error) -> Code {
+    match err {
+        gix_lock::acquire::Error::PermanentlyLocked { .. } => Code::Locked,
+        gix_lock::acquire::Error::Io(err) => io(err),
+    }
+}
+
+pub(crate) fn reference_edit(err: &crate::reference::edit::Error) -> Code {
+    use crate::reference::edit::Error;
+    use gix_ref::file::transaction::{commit, prepare};
+    match err {
+        Error::FileTransactionPrepare(err) => match err {
+            prepare::Error::PackedTransactionAcquire(err) | prepare::Error::LockAcquire { source: err, .. } => {
+                lock_acquire(err)
+            }
+            prepare::Error::MustNotExist { .. }
+            | prepare::Error::MustExist { .. }
+            | prepare::Error::ReferenceOutOfDate { .. }
+            | prepare::Error::DeleteReferenceMustExist { .. } => Code::Conflict,
+            prepare::Error::PreprocessingFailed(err) | prepare::Error::Io(err) => io(err),
+            prepare::Error::Packed(_)
+            | prepare::Error::PackedTransactionPrepare(_)
+            | prepare::Error::PackedFind(_)
+            | prepare::Error::ReferenceDecode(_) => Code::ObjectCorrupt,
+        },
+        Error::FileTransactionCommit(err) => match err {
+            commit::Error::PreprocessingFailed { source: err }
+            | commit::Error::LockCommit { source: err, .. }
+            | commit::Error::DeleteReference { err, .. }
+            | commit::Error::DeleteReflog { source: err, .. } => io(err),
+            commit::Error::PackedTransactionCommit(_) | commit::Error::CreateOrUpdateRefLog(_) => Code::Io,
+        },
+        Error::NameValidation(_) => Code::InvalidInput,
+        Error::LockTimeoutConfiguration(_) | Error::ParseCommitterTime(_) => Code::Configuration,
+    }
+}
+
+#[cfg(feature = "index")]
+pub(crate) fn index_write(err: &gix_index::file::write::Error) -> Code {
+    use gix_index::file::write::Error;
+    match err {
+        Error::Io(err) => io(err),
+        Error::AcquireLock(err) => lock_acquire(err),
+        Error::CommitLock(_) => Code::Io,
+    }
+}
+
+#[cfg(feature = "worktree-mutation")]
+pub(crate) fn worktree_checkout(err: &gix_worktree_state::checkout::Error) -> Code {
+    use gix_worktree_state::checkout::Error;
+    match err {
+        Error::Io(err) => io(err),
+        Error::Time(_) => Code::Io,
+        Error::Find { .. } => Code::ObjectCorrupt,
+        Error::IllformedUtf8 { .. } => Code::InvalidInput,
+        Error::Filter(_)
+        | Error::FilterListDelayed(_)
+        | Error::FilterFetchDelayed(_)
+        | Error::FilterPathUnknown { .. }
+        | Error::FilterPathsUnprocessed { .. } => Code::Other,
+    }
+}
+
+#[cfg(any(
+    feature = "async-network-client",
+    feature = "blocking-network-client",
+    feature = "bundle"
+))]
+pub(crate) fn write_pack(err: &gix_pack::bundle::write::Error) -> Code {
+    use gix_pack::bundle::write::Error;
+    match err {
+        Error::Io(err) => io(err),
+        Error::Persist(err) => io(&err.error),
+        _ => Code::ObjectCorrupt,
+    }
+}
+
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+pub(crate) use network::{handshake, transport};
+
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+mod network {
+    use gix_protocol::transport::client;
+
+    use super::Code;
+
+    pub(crate) fn transport(err: &client::Error) -> Code {
+        match err {
+            client::Error::Io(err) => match err.kind() {
+                std::io::ErrorKind::PermissionDenied => Code::AuthenticationFailed,
+                std::io::ErrorKind::Interrupted => Code::Interrupted,
+                _ => Code::Network,
+            },
+            client::Error::AuthenticationUnsupported | client::Error::AuthenticationRefused(_) => {
+                Code::AuthenticationFailed
+            }
+            client::Error::Capabilities { .. }
+            | client::Error::LineDecode { .. }
+            | client::Error::ExpectedLine(_)
+            | client::Error::ExpectedDataLine
+            | client::Error::UnsupportedProtocolVersion(_) => Code::Protocol,
+            client::Error::InvokeProgram { .. } => Code::Configuration,
+            client::Error::AmbiguousPath { .. } => Code::InvalidInput,
+            client::Error::Http(_) | client::Error::SshInvocation(_) => Code::Network,
+            #[cfg(feature = "blocking-ssh-transport-ssh2")]
+            client::Error::NativeSsh(gix_protocol::transport::client::ssh::native::Error::Authentication {
+                ..
+            }) => Code::AuthenticationFailed,
+            client::Error::NativeSsh(_) => Code::Network,
+        }
+    }
+
+    pub(crate) fn handshake(err: &gix_protocol::handshake::Error) -> Code {
+        use gix_protocol::handshake::Error;
+        match err {
+            Error::Credentials(_) | Error::EmptyCredentials | Error::InvalidCredentials { .. } => {
+                Code::AuthenticationFailed
+            }
+            Error::Transport(err) => transport(err),
+            Error::TransportProtocolPolicyViolation { .. } | Error::ParseRefs(_) => Code::Protocol,
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use gix_hash::ObjectId;
+use gix_object::Kind;
+
+/// Options for use in [`Repository::fsck()`](crate::Repository::fsck()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// If `true`, recompute the hash of each object and compare it to its id.
+    pub verify_hashes: bool,
+    /// If `true`, check that all objects referred to by other objects and by references exist and are of the expected kind,
+    /// and find objects that can't be reached from references, their reference logs, `HEAD` or the index.
+    pub check_connectivity: bool,
+    /// If `true`, report all unreachable objects as [`Finding::Unreachable`], instead of only those that aren't referred to
+    /// by any other object, which are reported as [`Finding::Dangling`], similar to `git fsck --unreachable`.
+    ///
+    /// This only has an effect if [`check_connectivity`](Self::check_connectivity) is set.
+    pub report_unreachable: bool,
+}
+
+impl Default for Options {
+    /// Verify hashes and connectivity, and report dangling objects, similar to `git fsck`.
+    fn default() -> Self {
+        Options {
+            verify_hashes: true,
+            check_connectivity: true,
+            report_unreachable: false,
+        }
+    }
+}
+
+/// The outcome of [`Repository::fsck()`](crate::Repository::fsck()).
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// The amount of distinct objects that were checked.
+    pub num_objects: usize,
+    /// Everything that was found to be wrong or noteworthy, in no particular order.
+    pub findings: Vec<Finding>,
+}
+
+impl Outcome {
+    /// Return `true` if the repository is intact, which is when all findings are only about
+    /// [dangling](Finding::Dangling) or [unreachable](Finding::Unreachable) objects.
+    pub fn is_intact(&self) -> bool {
+        self.findings.iter().all(|finding| !finding.is_error())
+    }
+}
+
+/// Something that [`Repository::fsck()`](crate::Repository::fsck()) found about an object or a reference.
+#[derive(Debug, thiserror::Error)]
+pub enum Finding {
+    /// The object could not be read from the object database.
+    #[error("Object {id} could not be read")]
+    Unreadable {
+        /// The id of the object.
+        id: ObjectId,
+        /// The reason for the object not being readable.
+        source: gix_object::find::Error,
+    },
+    /// The hash of the object doesn't match its id.
+    #[error("{kind} {id} has hash {actual}")]
+    HashMismatch {
+        /// The id of the object.
+        id: ObjectId,
+        /// The kind of the object.
+        kind: Kind,
+        /// The hash computed from the object data.
+        actual: ObjectId,
+    },
+    /// The object could not be parsed.
+    #[error("{kind} {id} could not be parsed")]
+    Malformed {
+        /// The id of the object.
+        id: ObjectId,
+        /// The kind of the object.
+        kind: Kind,
+        /// The reason for the object being malformed.
+        source: gix_object::decode::Error,
+    },
+    /// An object referred to by another object doesn't exist.
+    #[error("{kind} {id} is referred to by {referenced_by}, but it is missing")]
+    Missing {
+        /// The id of the missing object.
+        id: ObjectId,
+        /// The kind the missing object is expected to have.
+        kind: Kind,
+        /// The id of the object referring to the missing object.
+        referenced_by: ObjectId,
+    },
+    /// An object referred to by another object isn't of the kind it's expected to be.
+    #[error("Object {id} is referred to by {referenced_by} as {expected}, but it is a {actual}")]
+    WrongKind {
+        /// The id of the object.
+        id: ObjectId,
+        /// The kind the object is expected to have.
+        expected: Kind,
+        /// The actual kind of the object.
+        actual: Kind,
+        /// The id of the object referring to it.
+        referenced_by: ObjectId,
+    },
+    /// A reference points to an object or another reference that doesn't exist.
+    #[error("Reference {name} points to {target}, which doesn't exist")]
+    BrokenReference {
+        /// The name of the broken reference.
+        name: gix_ref::FullName,
+        /// The object id or name of the reference that doesn't exist.
+        target: gix_ref::Target,
+    },
+    /// The object can't be reached, and no other object refers to it.
+    #[error("Dangling {kind} {id}")]
+    Dangling {
+        /// The id of the object.
+        id: ObjectId,
+        /// The kind of the object.
+        kind: Kind,
+    },
+    /// The object can't be reached, but other unreachable objects may refer to it.
+    #[error("Unreachable {kind} {id}")]
+    Unreachable {
+        /// The id of the object.
+        id: ObjectId,
+        /// The kind of the object.
+        kind: Kind,
+    },
+}
+
+impl Finding {
+    /// Return `true` if this finding indicates a corrupt or incomplete repository, or `false` if it's merely informational.
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Finding::Dangling { .. } | Finding::Unreachable { .. })
+    }
+}
+
+/// The error returned by [`Repository::fsck()`](crate::Repository::fsck()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    LoadIndex(#[from] gix_odb::store::load_index::Error),
+    #[error(transparent)]
+    IterObjects(#[from] gix_odb::loose::iter::Error),
+    #[error(transparent)]
+    ShallowCommits(#[from] crate::shallow::open::Error),
+    #[error(transparent)]
+    ReferenceIterInit(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    ReferenceIter(#[from] crate::reference::iter::Error),
+    #[error("Could not read a reference")]
+    Reference(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    DecodeReflog(#[from] gix_ref::file::log::iter::decode::Error),
+    #[error(transparent)]
+    OpenWorktree(#[from] crate::open::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
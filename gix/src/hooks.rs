@@ -0,0 +1,170 @@
+//! Find and run the hooks of a repository, similar to `git hook run`.
+//!
+//! Hooks are executables in the directory configured with `core.hooksPath`, or in the `hooks` directory of the repository.
+//! Use [`Repository::hook()`](crate::Repository::hook()) to obtain a hook, adjust its arguments, environment and input,
+//! and [`run()`](Prepare::run()) it. Hooks are never run in repositories that aren't fully trusted, as they could run any program.
+//!
+//! ### Hooks run by `gix`
+//!
+//! If [`open::Options::run_hooks()`](crate::open::Options::run_hooks()) is enabled, the following hooks are run just like `git` would:
+//!
+//! * `pre-commit`, or `pre-merge-commit` for commits with more than one parent, and then `prepare-commit-msg` and `commit-msg`
+//!    before a commit is created. All of them can prevent the commit, and the message hooks can change the commit message.
+//!    As the tree to commit is already given, changes made to the index by these hooks won't be committed.
+//! * `post-commit` after a commit was created. Its failure is ignored.
+//! * `post-checkout` after [`switch()`](crate::Repository::switch()) and [`checkout_detached()`](crate::Repository::checkout_detached()).
+//!    Its failure is ignored as the checkout was performed already.
+//! * `post-merge` after [`merge_into_head()`](crate::Repository::merge_into_head()) committed the merge. Its failure is ignored.
+//!    The merge commit itself runs the commit hooks, with `pre-merge-commit` instead of `pre-commit`.
+//! * `reference-transaction` with the `committed` state after a fetch updated references. Its failure is ignored.
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
+};
+
+/// The error returned by [`Repository::hook()`](crate::Repository::hook()) and [`Prepare::run()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not interpolate the value of core.hooksPath")]
+    HooksPath(#[from] gix_config::path::interpolate::Error),
+    #[error("Could not run hook at '{}'", path.display())]
+    Spawn { path: PathBuf, source: std::io::Error },
+    #[error("Could not write or read the commit message at '{}'", path.display())]
+    CommitMessage { path: PathBuf, source: std::io::Error },
+    #[error("The {name} hook failed with {status}")]
+    Failed { name: String, status: ExitStatus },
+}
+
+impl crate::error::ErrorCode for Error {
+    fn code(&self) -> crate::error::Code {
+        use crate::error::{self, Code};
+        match self {
+            Error::HooksPath(_) => Code::Configuration,
+            Error::Spawn { source, .. } | Error::CommitMessage { source, .. } => error::io(source),
+            Error::Failed { .. } => Code::HookRejected,
+        }
+    }
+}
+
+/// A hook that is ready to be run, as obtained by [`Repository::hook()`](crate::Repository::hook()).
+///
+/// It runs in the root of the worktree, or in the git directory of bare repositories, with `GIT_DIR` set.
+/// Its standard output is redirected to standard error, and its standard input is empty unless [set](Self::stdin()).
+#[derive(Debug, Clone)]
+pub struct Prepare {
+    pub(crate) name: String,
+    pub(crate) path: PathBuf,
+    pub(crate) current_dir: PathBuf,
+    pub(crate) args: Vec<OsString>,
+    pub(crate) env: Vec<(OsString, OsString)>,
+    pub(crate) stdin: Option<Vec<u8>>,
+}
+
+/// Access
+impl Prepare {
+    /// The name of the hook, like `pre-commit`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The path to the executable of the hook.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Builder
+impl Prepare {
+    /// Add `arg` to the arguments passed to the hook.
+    pub fn arg(mut self, arg: impl Into<OsString>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Add all `args` to the arguments passed to the hook.
+    pub fn args(mut self, args: impl IntoIterator<Item = impl Into<OsString>>) -> Self {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the environment variable `key` to `value` for the hook.
+    pub fn env(mut self, key: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Pass `input` to the hook via its standard input.
+    pub fn stdin(mut self, input: impl Into<Vec<u8>>) -> Self {
+        self.stdin = Some(input.into());
+        self
+    }
+}
+
+/// Execution
+impl Prepare {
+    /// Run the hook and wait for it to finish, and fail if it didn't succeed.
+    pub fn run(self) -> Result<(), Error> {
+        let name = self.name.clone();
+        let status = self.status()?;
+        if !status.success() {
+            return Err(Error::Failed { name, status });
+        }
+        Ok(())
+    }
+
+    /// Run the hook and wait for it to finish, returning its exit status.
+    pub fn status(self) -> Result<ExitStatus, Error> {
+        let _span = gix_trace::detail!("gix::hooks::Prepare::status()", name = self.name);
+        let Prepare {
+            name: _,
+            path,
+            current_dir,
+            args,
+            env,
+            stdin,
+        } = self;
+        let mut cmd = std::process::Command::from(
+            gix_command::prepare(&path)
+                .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+                .stdout(stdout_to_stderr())
+                .args(args),
+        );
+        cmd.envs(env).current_dir(current_dir);
+        let spawn_err = |source| Error::Spawn {
+            path: path.clone(),
+            source,
+        };
+        let mut child = cmd.spawn().map_err(spawn_err)?;
+        if let Some(input) = stdin {
+            let mut child_stdin = child.stdin.take().expect("configured");
+            match std::io::Write::write_all(&mut child_stdin, &input) {
+                // Hooks don't have to read their input.
+                Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => {}
+                res => res.map_err(spawn_err)?,
+            }
+        }
+        child.wait().map_err(spawn_err)
+    }
+}
+
+/// Hooks write to standard error like `git` makes them do, so their output doesn't interfere with the output of the program
+/// running them.
+fn stdout_to_stderr() -> Stdio {
+    #[cfg(unix)]
+    {
+        use std::os::unix::io::AsFd;
+        if let Ok(fd) = std::io::stderr().as_fd().try_clone_to_owned() {
+            return fd.into();
+        }
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::io::AsHandle;
+        if let Ok(handle) = std::io::stderr().as_handle().try_clone_to_owned() {
+            return handle.into();
+        }
+    }
+    Stdio::inherit()
+}
@@ -1,5 +1,9 @@
 #![allow(clippy::result_large_err)]
-use std::{borrow::Cow, convert::TryInto, path::Path};
+use std::{
+    borrow::Cow,
+    convert::TryInto,
+    path::{Path, PathBuf},
+};
 
 use gix_macros::momo;
 use gix_ref::{
@@ -8,7 +12,11 @@ use gix_ref::{
     FullName, Target,
 };
 
-use crate::{bstr::BString, config::tree::Init, ThreadSafeRepository};
+use crate::{
+    bstr::{BString, ByteSlice},
+    config::tree::{Core, Init, Key},
+    ThreadSafeRepository,
+};
 
 /// The name of the branch to use if non is configured via git configuration.
 ///
@@ -34,6 +42,12 @@ pub enum Error {
     },
     #[error("Could not edit HEAD reference with new default name")]
     EditHeadForDefaultBranch(#[from] crate::reference::edit::Error),
+    #[error("Could not read the configuration of the new repository to apply configured filesystem capabilities")]
+    ReadConfig(#[from] gix_config::file::init::from_paths::Error),
+    #[error("Could not set configured filesystem capabilities in the configuration of the new repository")]
+    SetConfig(#[from] gix_config::file::set_raw_value::Error),
+    #[error("Could not write the configuration of the new repository at '{}'", .path.display())]
+    WriteConfig { source: std::io::Error, path: PathBuf },
 }
 
 impl ThreadSafeRepository {
@@ -54,6 +68,11 @@ impl ThreadSafeRepository {
 
     /// Similar to [`init`][Self::init()], but allows to determine how exactly to open the newly created repository.
     ///
+    /// If the filesystem capabilities aren't set in `create_options`, they are probed, but values for `core.fileMode`,
+    /// `core.symlinks`, `core.ignoreCase` and `core.precomposeUnicode` that are configured outside of the new repository,
+    /// for instance in the global configuration or via `open_options`, take precedence. This helps with filesystems
+    /// that are probed incorrectly, like some network filesystems.
+    ///
     /// # Deviation
     ///
     /// Instead of naming the default branch `master`, we name it `main` unless configured explicitly using the `init.defaultBranch`
@@ -70,7 +89,11 @@ impl ThreadSafeRepository {
         open_options.git_dir_trust = Some(gix_sec::Trust::Full);
         // The repo will use `core.precomposeUnicode` to adjust the value as needed.
         open_options.current_dir = gix_fs::current_dir(false)?.into();
-        let repo = ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, open_options)?;
+        let mut repo =
+            ThreadSafeRepository::open_from_paths(git_dir.clone(), worktree_dir.clone(), open_options.clone())?;
+        if create_options.fs_capabilities.is_none() && write_configured_fs_capabilities(&repo)? {
+            repo = ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, open_options)?;
+        }
 
         let branch_name = repo
             .config
@@ -103,3 +126,36 @@ impl ThreadSafeRepository {
         Ok(repo)
     }
 }
+
+/// Write the filesystem capabilities that are configured outside of the new `repo` into its local configuration,
+/// overriding the probed values, and return `true` if there was any.
+fn write_configured_fs_capabilities(repo: &ThreadSafeRepository) -> Result<bool, Error> {
+    let mut configured = Vec::new();
+    for key in [
+        &Core::FILE_MODE as &dyn Key,
+        &Core::SYMLINKS,
+        &Core::IGNORE_CASE,
+        &Core::PRECOMPOSE_UNICODE,
+    ] {
+        if let Ok(value) = repo
+            .config
+            .resolved
+            .raw_value_filter("core", None, key.name(), &mut |meta| {
+                !matches!(meta.source, gix_config::Source::Local | gix_config::Source::Worktree)
+            })
+        {
+            configured.push((key.name(), value.into_owned()));
+        }
+    }
+    if configured.is_empty() {
+        return Ok(false);
+    }
+
+    let path = repo.git_dir().join("config");
+    let mut config = gix_config::File::from_path_no_includes(path.clone(), gix_config::Source::Local)?;
+    for (name, value) in &configured {
+        config.set_raw_value("core", None, *name, value.as_bstr())?;
+    }
+    std::fs::write(&path, config.to_bstring()).map_err(|source| Error::WriteConfig { source, path })?;
+    Ok(true)
+}
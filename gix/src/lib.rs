@@ -30,6 +30,8 @@
 //! By default, the [`Repository`] isn't `Sync` and thus can't be used in certain contexts which require the `Sync` trait.
 //!
 //! To help with this, convert it with [`.into_sync()`][Repository::into_sync()] into a [`ThreadSafeRepository`].
+//! It can read objects and references directly from any thread, which is convenient for parallel analyses, but everything else
+//! requires turning it back into a [`Repository`] with [`.to_thread_local()`][ThreadSafeRepository::to_thread_local()].
 //!
 //! ### Object-Access Performance
 //!
@@ -95,6 +97,8 @@
 pub use gix_actor as actor;
 #[cfg(feature = "attributes")]
 pub use gix_attributes as attrs;
+#[cfg(feature = "blame")]
+pub use gix_blame as blame;
 #[cfg(feature = "command")]
 pub use gix_command as command;
 pub use gix_commitgraph as commitgraph;
@@ -144,6 +148,7 @@ pub use gix_url::Url;
 pub use gix_utils as utils;
 pub use hash::{oid, ObjectId};
 
+pub mod error;
 pub mod interrupt;
 
 mod ext;
@@ -167,8 +172,8 @@ mod types;
 #[cfg(any(feature = "excludes", feature = "attributes"))]
 pub use types::AttributeStack;
 pub use types::{
-    Blob, Commit, Head, Id, Object, ObjectDetached, Reference, Remote, Repository, Tag, ThreadSafeRepository, Tree,
-    Worktree,
+    Blob, Commit, Head, Id, Object, ObjectDetached, Reference, Remote, Repository, SyncRepository, Tag,
+    ThreadSafeRepository, Tree, Worktree,
 };
 #[cfg(feature = "attributes")]
 pub use types::{Pathspec, PathspecDetached, Submodule};
@@ -186,6 +191,7 @@ pub mod object;
 pub mod pathspec;
 pub mod reference;
 pub mod repository;
+pub mod sign;
 #[cfg(feature = "attributes")]
 pub mod submodule;
 pub mod tag;
@@ -293,13 +299,57 @@ pub mod create;
 ///
 pub mod open;
 
+///
+#[cfg(feature = "apply")]
+pub mod apply;
+
+///
+#[cfg(feature = "checkout")]
+pub mod checkout;
+
 ///
 pub mod config;
 
+///
+#[cfg(feature = "hooks")]
+pub mod hooks;
+
+///
+#[cfg(feature = "attributes")]
+pub mod linguist;
+
 ///
 #[cfg(feature = "mailmap")]
 pub mod mailmap;
 
+///
+#[cfg(feature = "merge")]
+pub mod merge;
+
+///
+#[cfg(feature = "notes")]
+pub mod notes;
+
+///
+#[cfg(feature = "rebase")]
+pub mod rebase;
+
+///
+#[cfg(feature = "merge")]
+pub mod rerere;
+
+///
+#[cfg(feature = "shortlog")]
+pub mod shortlog;
+
+///
+#[cfg(feature = "sparse-checkout")]
+pub mod sparse_checkout;
+
+///
+#[cfg(feature = "stash")]
+pub mod stash;
+
 ///
 pub mod worktree;
 
@@ -320,6 +370,25 @@ pub mod state;
 ///
 pub mod shallow;
 
+///
+pub mod commit_graph;
+
+///
+#[cfg(feature = "maintenance")]
+pub mod maintenance;
+
+///
+#[cfg(feature = "fsck")]
+pub mod fsck;
+
+///
+#[cfg(feature = "bundle")]
+pub mod bundle;
+
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod promisor;
+
 ///
 pub mod discover;
 
@@ -0,0 +1,244 @@
+//! Classify paths as vendored, generated, documentation or binary like [linguist](https://github.com/github-linguist/linguist) does,
+//! which is typically used to exclude them from language statistics or to collapse them in diffs.
+//!
+//! Use [`Repository::linguist_classifier()`](crate::Repository::linguist_classifier()) to obtain a [`Classifier`], which
+//! uses the `linguist-vendored`, `linguist-generated`, `linguist-documentation`, `linguist-detectable` and `linguist-language`
+//! attributes as well as the `text` and `diff` attributes, which are unset by the `binary` macro, and falls back to
+//! built-in heuristics for paths that don't have them set.
+//!
+//! The built-in heuristics are also available as [`is_vendored()`], [`is_generated()`], [`is_documentation()`] and [`is_binary()`].
+//! They cover common cases only and are not as exhaustive as the ones of linguist.
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    AttributeStack,
+};
+
+/// The error returned by [`Repository::linguist_classifier()`](crate::Repository::linguist_classifier()) and [`Classifier::classify()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    AttributesFromHead(#[from] crate::repository::attributes_from_head::Error),
+    #[error(transparent)]
+    AttributeStack(#[from] crate::config::attribute_stack::Error),
+    #[error("Could not lookup the attributes of a path")]
+    Attributes(#[from] std::io::Error),
+}
+
+/// The classification of a path as obtained by [`Classifier::classify()`].
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Classification {
+    /// The path is third-party code, like a vendored dependency.
+    pub vendored: bool,
+    /// The path is generated, like a lock file or minified source code.
+    pub generated: bool,
+    /// The path is documentation, like a `README` or anything in a `docs` directory.
+    pub documentation: bool,
+    /// The path holds binary data.
+    pub binary: bool,
+    /// The path should be counted in language statistics, which is the case if it isn't any of the above unless
+    /// overridden with the `linguist-detectable` attribute.
+    pub detectable: bool,
+    /// The language set with the `linguist-language` attribute, if any.
+    pub language: Option<BString>,
+}
+
+/// The attributes we look up, in the order in which [`Classifier::classify()`] expects them.
+const ATTRIBUTES: [&str; 7] = [
+    "linguist-vendored",
+    "linguist-generated",
+    "linguist-documentation",
+    "linguist-detectable",
+    "linguist-language",
+    "text",
+    "diff",
+];
+
+/// Classify paths according to their attributes and built-in heuristics, see the [module documentation](self).
+pub struct Classifier<'repo> {
+    attributes: AttributeStack<'repo>,
+    outcome: gix_attributes::search::Outcome,
+}
+
+impl<'repo> Classifier<'repo> {
+    /// Create a new instance that uses `attributes` for lookups.
+    pub fn new(attributes: AttributeStack<'repo>) -> Self {
+        let outcome = attributes.selected_attribute_matches(ATTRIBUTES);
+        Classifier { attributes, outcome }
+    }
+
+    /// Classify the file at `rela_path`, with `data` being a prefix of its content, if available, to allow heuristics
+    /// for detecting binary and generated files to work.
+    pub fn classify(&mut self, rela_path: &BStr, data: Option<&[u8]>) -> Result<Classification, Error> {
+        let platform = self.attributes.at_entry(rela_path, Some(false))?;
+        platform.matching_attributes(&mut self.outcome);
+
+        let mut states = self.outcome.iter_selected().map(|m| m.assignment.state);
+        let mut next = || states.next().unwrap_or(gix_attributes::StateRef::Unspecified);
+        let (vendored, generated, documentation, detectable, language, text, diff) =
+            (next(), next(), next(), next(), next(), next(), next());
+
+        let binary = match (text, diff) {
+            (gix_attributes::StateRef::Unset, _) | (_, gix_attributes::StateRef::Unset) => true,
+            (gix_attributes::StateRef::Set, _) => false,
+            _ => data.map_or(false, is_binary),
+        };
+        let vendored = to_bool(vendored).unwrap_or_else(|| is_vendored(rela_path));
+        let generated = to_bool(generated).unwrap_or_else(|| is_generated(rela_path, data));
+        let documentation = to_bool(documentation).unwrap_or_else(|| is_documentation(rela_path));
+        let detectable = to_bool(detectable).unwrap_or(!(vendored || generated || documentation || binary));
+        let language = match language {
+            gix_attributes::StateRef::Value(value) => Some(value.as_bstr().to_owned()),
+            _ => None,
+        };
+        Ok(Classification {
+            vendored,
+            generated,
+            documentation,
+            binary,
+            detectable,
+            language,
+        })
+    }
+}
+
+/// Convert `state` into a boolean like linguist does, or return `None` if it is unspecified.
+fn to_bool(state: gix_attributes::StateRef<'_>) -> Option<bool> {
+    match state {
+        gix_attributes::StateRef::Set => Some(true),
+        gix_attributes::StateRef::Unset => Some(false),
+        gix_attributes::StateRef::Value(value) => match value.as_bstr().as_bytes() {
+            b"true" => Some(true),
+            b"false" => Some(false),
+            _ => None,
+        },
+        gix_attributes::StateRef::Unspecified => None,
+    }
+}
+
+/// Directories that hold third-party code.
+const VENDORED_DIRECTORIES: &[&str] = &[
+    "vendor",
+    "vendors",
+    "node_modules",
+    "bower_components",
+    "third_party",
+    "third-party",
+    "3rdparty",
+    "external",
+    "Godeps",
+    "Pods",
+    "Carthage",
+    ".yarn",
+];
+
+/// Return `true` if `rela_path` looks like third-party code by being in a directory like `vendor` or `node_modules`.
+pub fn is_vendored(rela_path: &BStr) -> bool {
+    directories(rela_path).any(|component| VENDORED_DIRECTORIES.iter().any(|dir| component == dir.as_bytes()))
+}
+
+/// File names of lock files and similar files that are generated by tools.
+const GENERATED_FILE_NAMES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "Gemfile.lock",
+    "poetry.lock",
+    "Pipfile.lock",
+    "composer.lock",
+    "go.sum",
+    "flake.lock",
+];
+
+/// File name suffixes of minified or generated files.
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".min.js",
+    ".min.css",
+    ".js.map",
+    ".css.map",
+    ".pb.go",
+    "_pb2.py",
+    ".designer.cs",
+];
+
+/// Markers in the first lines of a file that indicate it was generated.
+const GENERATED_MARKERS: &[&str] = &["DO NOT EDIT", "@generated", "auto-generated", "autogenerated"];
+
+/// Return `true` if the file at `rela_path` looks generated by its name, like `Cargo.lock` or `*.min.js`, or
+/// if `data` is available and has a marker like `@generated` or `DO NOT EDIT` in its first lines.
+pub fn is_generated(rela_path: &BStr, data: Option<&[u8]>) -> bool {
+    let file_name = file_name(rela_path);
+    if GENERATED_FILE_NAMES.iter().any(|name| file_name == name.as_bytes())
+        || GENERATED_SUFFIXES
+            .iter()
+            .any(|suffix| file_name.ends_with(suffix.as_bytes()))
+    {
+        return true;
+    }
+    data.map_or(false, |data| {
+        data.lines()
+            .take(5)
+            .any(|line| GENERATED_MARKERS.iter().any(|marker| line.contains_str(marker)))
+    })
+}
+
+/// Directories that hold documentation.
+const DOCUMENTATION_DIRECTORIES: &[&str] = &[
+    "doc",
+    "docs",
+    "Documentation",
+    "documentation",
+    "man",
+    "examples",
+    "example",
+];
+
+/// Prefixes of file names that are documentation, compared case-insensitively.
+const DOCUMENTATION_FILE_PREFIXES: &[&str] = &[
+    "readme",
+    "changelog",
+    "changes",
+    "contributing",
+    "license",
+    "licence",
+    "copying",
+    "install",
+    "authors",
+];
+
+/// Return `true` if `rela_path` looks like documentation by being in a directory like `docs` or by being a file like `README.md`.
+pub fn is_documentation(rela_path: &BStr) -> bool {
+    let file_name = file_name(rela_path);
+    directories(rela_path).any(|component| DOCUMENTATION_DIRECTORIES.iter().any(|dir| component == dir.as_bytes()))
+        || DOCUMENTATION_FILE_PREFIXES.iter().any(|prefix| {
+            file_name
+                .get(..prefix.len())
+                .map_or(false, |start| start.eq_ignore_ascii_case(prefix.as_bytes()))
+                && file_name
+                    .get(prefix.len())
+                    .map_or(true, |b| matches!(b, b'.' | b'-' | b'_'))
+        })
+}
+
+/// Return `true` if `data` looks binary, which is the case if its first 8000 bytes contain a null byte, just like in `git`.
+pub fn is_binary(data: &[u8]) -> bool {
+    data[..data.len().min(8000)].contains(&0)
+}
+
+fn file_name(rela_path: &BStr) -> &BStr {
+    rela_path
+        .rfind_byte(b'/')
+        .map_or(rela_path, |pos| rela_path[pos + 1..].as_bstr())
+}
+
+fn directories(rela_path: &BStr) -> impl Iterator<Item = &[u8]> {
+    rela_path
+        .rfind_byte(b'/')
+        .map(|pos| rela_path[..pos].split_str("/"))
+        .into_iter()
+        .flatten()
+}
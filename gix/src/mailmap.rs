@@ -14,5 +14,10 @@ pub mod load {
         PathInterpolate(#[from] gix_config::path::interpolate::Error),
         #[error("Could not find object configured in `mailmap.blob`")]
         FindExisting(#[from] crate::object::find::existing::Error),
+        #[error("The object {id} configured in `mailmap.blob` is a {actual}, but must be a blob")]
+        NotABlob {
+            id: gix_hash::ObjectId,
+            actual: gix_object::Kind,
+        },
     }
 }
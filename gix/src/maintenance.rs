@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+
+/// Options for use in [`Repository::maintenance()`](crate::Repository::maintenance()), with each field enabling one task.
+///
+/// Tasks run in the order of the fields.
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// If `true`, remove expired entries from the reference logs of `HEAD` and all references, according to the policy
+    /// that is [configured for each of them](crate::Repository::reflog_expire_policy()), similar to `git reflog expire --all`.
+    pub expire_reflogs: bool,
+    /// If `true`, remove unreachable loose objects that are older than [configured](crate::Repository::prune_options()),
+    /// similar to `git prune`.
+    pub prune_unreachable_objects: bool,
+    /// If `true`, write all objects of this repository that are in loose objects or in packs into a single new pack,
+    /// and remove the packs it replaces, similar to `git repack -a -d -l`.
+    ///
+    /// Packs that are marked with a `.keep` or `.promisor` file are left alone, and objects of alternate object databases aren't copied.
+    pub consolidate_packs: bool,
+    /// If `true`, remove loose objects that are also contained in a pack, similar to `git prune-packed`.
+    pub prune_loose_objects: bool,
+    /// If set, write the commit-graph with the given options, unless the repository is shallow, similar to `git commit-graph write`.
+    pub commit_graph: Option<crate::commit_graph::Options>,
+    /// If set, write the multi-pack index with the given options, similar to `git multi-pack-index write`.
+    ///
+    /// Note that if packs were consolidated, an existing multi-pack index is always rewritten.
+    pub multi_pack_index: Option<gix_odb::store::multi_pack_index::Options>,
+}
+
+impl Default for Options {
+    /// Run all tasks, similar to `git gc`.
+    fn default() -> Self {
+        Options {
+            expire_reflogs: true,
+            prune_unreachable_objects: true,
+            consolidate_packs: true,
+            prune_loose_objects: true,
+            commit_graph: Some(Default::default()),
+            multi_pack_index: None,
+        }
+    }
+}
+
+/// The outcome of [`Repository::maintenance()`](crate::Repository::maintenance()).
+#[derive(Debug, Clone, Default)]
+pub struct Outcome {
+    /// The amount of reference log entries that expired and were removed.
+    pub num_expired_reflog_entries: usize,
+    /// The outcome of pruning unreachable objects, if it was done.
+    pub pruned: Option<prune::Outcome>,
+    /// Information about the consolidated pack, or `None` if packs weren't consolidated as there was nothing to do.
+    pub consolidated_pack: Option<ConsolidatedPack>,
+    /// The amount of loose objects that were removed as they were contained in a pack.
+    pub num_pruned_loose_objects: usize,
+    /// The outcome of writing the commit-graph, if it was written.
+    pub commit_graph: Option<crate::commit_graph::Outcome>,
+    /// The outcome of writing the multi-pack index, if it was written.
+    pub multi_pack_index: Option<gix_odb::store::multi_pack_index::Outcome>,
+}
+
+/// Information about a pack that replaced other packs and loose objects.
+#[derive(Debug, Clone)]
+pub struct ConsolidatedPack {
+    /// The path to the index of the newly written pack.
+    pub index_path: PathBuf,
+    /// The amount of objects in the new pack.
+    pub num_objects: usize,
+    /// The amount of packs that were replaced by the new pack and removed.
+    pub num_removed_packs: usize,
+}
+
+///
+pub mod prune {
+    use std::time::SystemTime;
+
+    use gix_hash::ObjectId;
+
+    use crate::maintenance::ConsolidatedPack;
+
+    /// Options for use in [`Repository::prune_objects()`](crate::Repository::prune_objects()).
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Options {
+        /// Remove unreachable loose objects that were last modified before this time, or keep them all if `None`.
+        ///
+        /// This grace period protects objects that were just written, but aren't referenced yet.
+        pub expire: Option<SystemTime>,
+        /// If `true`, rewrite packs that contain unreachable objects to drop them, similar to `git repack -a -d`.
+        ///
+        /// Packs that are marked with a `.keep` or `.promisor` file are left alone.
+        pub repack: bool,
+        /// If `true`, don't change anything, but report what would be removed.
+        pub dry_run: bool,
+    }
+
+    /// The outcome of [`Repository::prune_objects()`](crate::Repository::prune_objects()).
+    #[derive(Default, Debug, Clone)]
+    pub struct Outcome {
+        /// The unreachable loose objects that were removed, or that would be removed in a dry-run.
+        pub loose_objects: Vec<ObjectId>,
+        /// The unreachable objects that were dropped from packs, or that would be dropped in a dry-run.
+        pub packed_objects: Vec<ObjectId>,
+        /// Information about the pack that replaced the packs with unreachable objects, or `None` if no pack was rewritten.
+        pub pack: Option<ConsolidatedPack>,
+    }
+}
+
+/// The error returned by [`Repository::maintenance()`](crate::Repository::maintenance()) and
+/// [`Repository::prune_objects()`](crate::Repository::prune_objects()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    ReferenceIterInit(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    ReferenceIter(#[from] crate::reference::iter::Error),
+    #[error("Could not read a reference while expiring reference logs")]
+    Reference(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    ExpireReflog(#[from] crate::reference::log::expire::Error),
+    #[error(transparent)]
+    ConfigExpiry(#[from] crate::config::key::GenericErrorWithValue),
+    #[error(transparent)]
+    DecodeReflog(#[from] gix_ref::file::log::iter::decode::Error),
+    #[error(transparent)]
+    OpenWorktree(#[from] crate::open::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    HeadId(#[from] crate::reference::head_id::Error),
+    #[error(transparent)]
+    FindObject(#[from] gix_object::find::Error),
+    #[error(transparent)]
+    DecodeObject(#[from] gix_object::decode::Error),
+    #[error("Could not read the pack index at '{}'", path.display())]
+    OpenPackIndex {
+        path: PathBuf,
+        source: gix_pack::index::init::Error,
+    },
+    #[error(transparent)]
+    CountObjects(#[from] gix_pack::data::output::count::objects::Error),
+    #[error(transparent)]
+    CreatePackEntries(#[from] gix_pack::data::output::entry::iter_from_counts::Error),
+    #[error(transparent)]
+    WritePackData(#[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>),
+    #[error(transparent)]
+    WritePackBundle(#[from] gix_pack::bundle::write::Error),
+    #[error(transparent)]
+    CommitGraph(#[from] crate::commit_graph::Error),
+    #[error(transparent)]
+    MultiPackIndex(#[from] gix_odb::store::multi_pack_index::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
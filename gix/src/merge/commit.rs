@@ -0,0 +1,66 @@
+//! Types for cherry-picking and reverting commits, see [`Repository::cherry_pick()`](crate::Repository::cherry_pick())
+//! and [`Repository::revert()`](crate::Repository::revert()).
+use gix_hash::ObjectId;
+
+/// The outcome of [`Repository::cherry_pick()`](crate::Repository::cherry_pick()) and [`Repository::revert()`](crate::Repository::revert()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The newly created commit that `HEAD` now points to, or `None` if there were conflicts.
+    pub commit: Option<ObjectId>,
+    /// All conflicts that have to be resolved in the worktree and the index before the changes can be committed.
+    ///
+    /// In that case, `CHERRY_PICK_HEAD` or `REVERT_HEAD` is written along with `MERGE_MSG`, so `git` can be used
+    /// to conclude or abort the operation.
+    pub conflicts: Vec<crate::merge::tree::Conflict>,
+}
+
+/// The error returned by [`Repository::cherry_pick()`](crate::Repository::cherry_pick()) and [`Repository::revert()`](crate::Repository::revert()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot {operation} while another operation is in progress: {state:?}")]
+    InProgress {
+        operation: &'static str,
+        state: crate::state::InProgress,
+    },
+    #[error("Commit {id} is a merge commit and can't be picked or reverted")]
+    MergeCommit { id: ObjectId },
+    #[error("The changes of commit {id} are already present, there is nothing to commit")]
+    Empty { id: ObjectId },
+    #[error("Committer identity is not configured")]
+    CommitterMissing,
+    #[error("Author identity is not configured")]
+    AuthorMissing,
+    #[error("Could not write the state for use by git")]
+    WriteState(#[from] std::io::Error),
+    #[error(transparent)]
+    RequireClean(#[from] crate::merge::worktree::require_clean::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::merge::worktree::checkout::Error),
+    #[error(transparent)]
+    Merge(#[from] crate::merge::tree::Error),
+    #[error(transparent)]
+    MergeOptions(#[from] crate::config::merge_tree_options::Error),
+    #[error(transparent)]
+    Rerere(#[from] crate::rerere::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    HeadId(#[from] crate::reference::head_id::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::try_into::Error),
+    #[error(transparent)]
+    CommitTree(#[from] crate::object::commit::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    WriteTree(#[from] gix_index::write_tree::Error<crate::object::write::Error>),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    ParseTime(#[from] crate::config::time::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+}
@@ -0,0 +1,45 @@
+//! Types for merging commits, see [`Repository::merge_commits()`](crate::Repository::merge_commits()).
+use gix_hash::ObjectId;
+
+/// The outcome of [`Repository::merge_commits()`](crate::Repository::merge_commits()).
+#[derive(Clone)]
+pub struct Outcome {
+    /// The result of merging the trees of all commits, with conflicts that may have occurred when merging the last of them.
+    ///
+    /// Without conflicts, the [index](crate::merge::tree::Outcome::index) can be written as tree of a merge commit.
+    pub tree: crate::merge::tree::Outcome,
+    /// The commits that were merged in order, without those that were already contained in *ours* or in previously merged
+    /// commits. Along with *ours*, they are the parents of a merge commit.
+    pub merged: Vec<ObjectId>,
+    /// The merge base that was used for each of the [merged](Self::merged) commits, or `None` if they have no common ancestor.
+    pub merge_bases: Vec<Option<ObjectId>>,
+}
+
+/// The error returned by [`Repository::merge_commits()`](crate::Repository::merge_commits()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("At least one commit to merge is required")]
+    NothingToMerge,
+    #[error("The {strategy:?} strategy can only merge a single commit, got {count}")]
+    TooManyCommits {
+        strategy: crate::merge::tree::Strategy,
+        count: usize,
+    },
+    #[error("Merging commit {id} failed with conflicts, which is only allowed for the last of multiple commits")]
+    OctopusConflict { id: ObjectId },
+    #[error(transparent)]
+    Merge(#[from] crate::merge::tree::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::try_into::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    WriteTree(#[from] gix_index::write_tree::Error<crate::object::write::Error>),
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    Traverse(#[from] gix_traverse::commit::ancestors::Error),
+}
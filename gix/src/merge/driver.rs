@@ -0,0 +1,126 @@
+use std::borrow::Cow;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::tree::{Merge, Section},
+    merge::blob::{BuiltinDriver, Driver},
+    AttributeStack, Repository,
+};
+
+/// The error returned when selecting and running merge drivers as part of
+/// [Repository::merge_trees_with_drivers()](crate::Repository::merge_trees_with_drivers()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] gix_traverse::tree::breadthfirst::Error),
+    #[error(transparent)]
+    AttributeStack(#[from] crate::config::attribute_stack::Error),
+    #[error("Could not lookup the merge attribute")]
+    Attributes(#[from] std::io::Error),
+    #[error(transparent)]
+    CommandContext(#[from] crate::config::command_context::Error),
+    #[error("The merge driver '{name}' could not be intercepted")]
+    Intercept {
+        name: BString,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("The merge driver '{name}' failed")]
+    Apply {
+        name: BString,
+        source: crate::merge::blob::driver::apply::Error,
+    },
+}
+
+/// The merge driver selected for a path by means of its `merge` attribute.
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum Selected<'a> {
+    Builtin(BuiltinDriver),
+    Custom(&'a Driver),
+}
+
+/// Select merge drivers for paths according to their `merge` attribute and the configuration.
+pub(crate) struct Drivers<'repo> {
+    attributes: AttributeStack<'repo>,
+    outcome: gix_attributes::search::Outcome,
+    default: Option<BString>,
+    drivers: Vec<Driver>,
+    pub(crate) command_context: gix_command::Context,
+}
+
+impl<'repo> Drivers<'repo> {
+    /// Prepare attributes and drivers in `repo`, with `tree_if_bare` being the tree to read attributes from if there is no worktree.
+    pub(crate) fn new(repo: &'repo Repository, tree_if_bare: &gix_hash::oid) -> Result<Self, Error> {
+        let attributes = if repo.is_bare() {
+            let index = repo.index_from_tree(tree_if_bare)?;
+            repo.attributes_only(&index, gix_worktree::stack::state::attributes::Source::IdMapping)?
+        } else {
+            let index = repo.index_or_empty()?;
+            repo.attributes_only(
+                &index,
+                gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            )?
+        };
+        let mut outcome = gix_attributes::search::Outcome::default();
+        outcome.initialize_with_selection(&Default::default(), Some("merge"));
+        Ok(Drivers {
+            attributes,
+            outcome,
+            default: repo
+                .config
+                .resolved
+                .string(Merge.name(), None, Merge::DEFAULT.name)
+                .map(Cow::into_owned),
+            drivers: extract_drivers(repo),
+            command_context: repo.command_context()?,
+        })
+    }
+
+    /// Return the driver to use for merging the content of the file at `rela_path`.
+    ///
+    /// Like in `git`, unknown drivers fall back to merging text.
+    pub(crate) fn select(&mut self, rela_path: &BStr) -> Result<Selected<'_>, Error> {
+        let platform = self.attributes.at_entry(rela_path, Some(false))?;
+        platform.matching_attributes(&mut self.outcome);
+        let name: Cow<'_, BStr> = match self.outcome.iter_selected().next().map(|m| m.assignment.state) {
+            Some(gix_attributes::StateRef::Set) => return Ok(Selected::Builtin(BuiltinDriver::Text)),
+            Some(gix_attributes::StateRef::Unset) => return Ok(Selected::Builtin(BuiltinDriver::Binary)),
+            Some(gix_attributes::StateRef::Value(value)) => value.as_bstr().to_owned().into(),
+            Some(gix_attributes::StateRef::Unspecified) | None => match &self.default {
+                Some(name) => name.as_bstr().into(),
+                None => return Ok(Selected::Builtin(BuiltinDriver::Text)),
+            },
+        };
+        if let Some(builtin) = BuiltinDriver::from_name(name.as_ref()) {
+            return Ok(Selected::Builtin(builtin));
+        }
+        Ok(self
+            .drivers
+            .iter()
+            .rev()
+            .find(|driver| driver.name == *name)
+            .map_or(Selected::Builtin(BuiltinDriver::Text), Selected::Custom))
+    }
+}
+
+/// Obtain all configured drivers that have a command, but ignore those in sections that we don't trust enough.
+fn extract_drivers(repo: &Repository) -> Vec<Driver> {
+    repo.config
+        .resolved
+        .sections_by_name(Merge.name())
+        .into_iter()
+        .flatten()
+        .filter(|s| repo.filter_config_section()(s.meta()))
+        .filter_map(|s| {
+            let name = s.header().subsection_name()?;
+            Some(Driver {
+                name: name.to_owned(),
+                display_name: s.value(Merge::DRIVER_NAME.name).map(Cow::into_owned),
+                command: s.value(Merge::DRIVER_DRIVER.name)?.into_owned(),
+                recursive: s.value(Merge::DRIVER_RECURSIVE.name).map(Cow::into_owned),
+            })
+        })
+        .collect()
+}
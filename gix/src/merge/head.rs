@@ -0,0 +1,43 @@
+//! Types for merging commits into `HEAD`, see [`Repository::merge_into_head()`](crate::Repository::merge_into_head()).
+use gix_hash::ObjectId;
+
+/// The outcome of [`Repository::merge_into_head()`](crate::Repository::merge_into_head()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The newly created merge commit that `HEAD` now points to, or `None` if there were conflicts or nothing to merge.
+    pub commit: Option<ObjectId>,
+    /// The commits that were merged, or an empty list if all of them are contained in `HEAD` already.
+    pub merged: Vec<ObjectId>,
+    /// All conflicts that have to be resolved in the worktree and the index before the merge can be committed.
+    ///
+    /// In that case, `MERGE_HEAD` is written along with `MERGE_MSG`, so `git` can be used to conclude or abort the merge.
+    pub conflicts: Vec<crate::merge::tree::Conflict>,
+}
+
+/// The error returned by [`Repository::merge_into_head()`](crate::Repository::merge_into_head()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot merge while another operation is in progress: {state:?}")]
+    InProgress { state: crate::state::InProgress },
+    #[error("Could not write the state for use by git")]
+    WriteState(#[from] std::io::Error),
+    #[error(transparent)]
+    RequireClean(#[from] crate::merge::worktree::require_clean::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::merge::worktree::checkout::Error),
+    #[error(transparent)]
+    MergeCommits(#[from] crate::merge::commits::Error),
+    #[error(transparent)]
+    MergeOptions(#[from] crate::config::merge_tree_options::Error),
+    #[error(transparent)]
+    Rerere(#[from] crate::rerere::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    HeadId(#[from] crate::reference::head_id::Error),
+    #[error(transparent)]
+    WriteTree(#[from] gix_index::write_tree::Error<crate::object::write::Error>),
+    #[error(transparent)]
+    Commit(#[from] crate::commit::Error),
+}
@@ -0,0 +1,9 @@
+pub use gix_merge::*;
+
+pub mod commit;
+pub mod commits;
+///
+pub mod driver;
+pub mod head;
+/// Utilities to bring the outcome of merges into the index and the worktree.
+pub mod worktree;
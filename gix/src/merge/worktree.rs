@@ -0,0 +1,353 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix_index::entry::{Flags, Mode, Stat};
+
+use crate::{
+    bstr::{BStr, BString},
+    merge::tree::{Conflict, ConflictKind},
+    Repository,
+};
+
+///
+pub mod require_clean {
+    use crate::bstr::BString;
+
+    /// The error returned by operations that need the index and the worktree to be free of changes.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A worktree is required to perform this operation")]
+        BareRepository,
+        #[error("There are uncommitted changes in the index or the worktree, the first of {} being at '{}'", .paths.len(), .paths[0])]
+        Dirty { paths: Vec<BString> },
+        #[error("Could not create an index from the tree at {id}")]
+        IndexFromTree {
+            id: gix_hash::ObjectId,
+            source: gix_traverse::tree::breadthfirst::Error,
+        },
+        #[error(transparent)]
+        Pathspec(#[from] crate::pathspec::init::Error),
+        #[error(transparent)]
+        Attributes(#[from] crate::config::attribute_stack::Error),
+        #[error(transparent)]
+        FilesystemOptions(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        StatOptions(#[from] crate::config::stat_options::Error),
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+        #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+        OpenArcOdb(#[from] std::io::Error),
+        #[error(transparent)]
+        Status(#[from] gix_status::index_as_worktree::Error),
+    }
+
+    impl crate::error::ErrorCode for Error {
+        fn code(&self) -> crate::error::Code {
+            use crate::error::{self, Code};
+            match self {
+                Error::BareRepository => Code::WorktreeRequired,
+                Error::Pathspec(_) => Code::InvalidInput,
+                Error::Dirty { .. } => Code::WouldOverwriteLocalChanges,
+                Error::IndexFromTree { .. } => Code::ObjectCorrupt,
+                Error::Attributes(_)
+                | Error::FilesystemOptions(_)
+                | Error::StatOptions(_)
+                | Error::FilterPipeline(_) => Code::Configuration,
+                Error::OpenArcOdb(err) => error::io(err),
+                Error::Status(err) => match err {
+                    gix_status::index_as_worktree::Error::Io(err) => error::io(err),
+                    gix_status::index_as_worktree::Error::Find(_) => Code::ObjectCorrupt,
+                    _ => Code::Other,
+                },
+            }
+        }
+    }
+}
+
+///
+pub mod checkout {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// The error returned when changing the index and the worktree to match the outcome of a merge.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A worktree is required to perform this operation")]
+        BareRepository,
+        #[error("Could not remove '{}' from the worktree", path.display())]
+        RemoveFile { path: PathBuf, source: std::io::Error },
+        #[error(transparent)]
+        CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error(transparent)]
+        Checkout(#[from] gix_worktree_state::checkout::Error),
+        #[error("Could not write {} file(s) into the worktree, the first one being '{}'", .paths.len(), .paths[0])]
+        Incomplete { paths: Vec<BString> },
+        #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+        OpenArcOdb(#[from] std::io::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+    }
+
+    impl crate::error::ErrorCode for Error {
+        fn code(&self) -> crate::error::Code {
+            use crate::error::{self, Code};
+            match self {
+                Error::BareRepository => Code::WorktreeRequired,
+                Error::RemoveFile { source: err, .. } | Error::OpenArcOdb(err) => error::io(err),
+                Error::CheckoutOptions(_) => Code::Configuration,
+                Error::Checkout(err) => error::worktree_checkout(err),
+                Error::Incomplete { .. } => Code::Io,
+                Error::WriteIndex(err) => error::index_write(err),
+            }
+        }
+    }
+}
+
+/// Fail if the worktree has changes compared to `index`, or if `index` isn't the same as `tree`, if set.
+///
+/// Files that aren't tracked are ignored.
+pub(crate) fn require_clean(
+    repo: &Repository,
+    index: &gix_index::State,
+    tree: Option<&gix_hash::oid>,
+) -> Result<(), require_clean::Error> {
+    use gix_status::index_as_worktree::EntryStatus;
+    use require_clean::Error;
+
+    let mut paths = Vec::new();
+    if let Some(tree) = tree {
+        let tree_index = gix_index::State::from_tree(tree, &repo.objects).map_err(|err| Error::IndexFromTree {
+            id: tree.to_owned(),
+            source: err,
+        })?;
+        paths.extend(changed_paths(index, &tree_index));
+    }
+
+    paths.extend(
+        worktree_changes(repo, index)?
+            .into_iter()
+            .filter(|(_, status)| !matches!(status, EntryStatus::NeedsUpdate(_)))
+            .map(|(path, _)| path),
+    );
+
+    if paths.is_empty() {
+        Ok(())
+    } else {
+        paths.sort();
+        paths.dedup();
+        Err(Error::Dirty { paths })
+    }
+}
+
+/// Compare `index` with the worktree and return the paths of all entries that differ, along with the kind of difference.
+///
+/// Files that aren't tracked and submodules are ignored.
+pub(crate) fn worktree_changes(
+    repo: &Repository,
+    index: &gix_index::State,
+) -> Result<Vec<(BString, gix_status::index_as_worktree::EntryStatus)>, require_clean::Error> {
+    use gix_status::index_as_worktree::{traits::FastEq, Recorder};
+    use require_clean::Error;
+
+    let workdir = repo.work_dir().ok_or(Error::BareRepository)?;
+    let attributes_source = gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping;
+    let pathspec = repo.pathspec(None::<&BStr>, true, index, attributes_source)?;
+    let options = gix_status::index_as_worktree::Options {
+        fs: repo.filesystem_options()?,
+        thread_limit: None,
+        stat: repo.stat_options()?,
+        attributes: match repo.attributes_only(index, attributes_source)?.detach().state_mut() {
+            gix_worktree::stack::State::AttributesStack(attrs) => std::mem::take(attrs),
+            _ => unreachable!("state must be attributes stack only"),
+        },
+        emit_sparse_excluded: false,
+        resume_from: None,
+        time_budget: None,
+    };
+    let mut recorder = Recorder::default();
+    gix_status::index_as_worktree(
+        index,
+        workdir,
+        &mut recorder,
+        FastEq,
+        IgnoreSubmodules,
+        repo.objects.clone().into_arc()?,
+        &mut gix_features::progress::Discard,
+        pathspec.detach()?,
+        repo.filter_pipeline(None)?.0.into_parts().0,
+        &AtomicBool::default(),
+        options,
+    )?;
+    Ok(recorder
+        .records
+        .into_iter()
+        .map(|record| (record.relative_path.to_owned(), record.status))
+        .collect())
+}
+
+/// Change the worktree, which is assumed to match `current`, so that it matches `new`, and write `new` as index of `repo`.
+///
+/// Only files that differ between both indices are touched, unless `force` is `true` in which case all files in `new`
+/// are written. The paths in `conflicts` are written with their merged content, or with the version of *ours* if there is none.
+/// Return the index as it was written.
+pub(crate) fn checkout(
+    repo: &Repository,
+    current: &gix_index::State,
+    mut new: gix_index::State,
+    conflicts: &[Conflict],
+    force: bool,
+) -> Result<gix_index::File, checkout::Error> {
+    use checkout::Error;
+
+    let workdir = repo.work_dir().ok_or(Error::BareRepository)?;
+    for entry in current.entries() {
+        let path = entry.path(current);
+        if entry.mode == Mode::COMMIT || new.entry_range(path).is_some() {
+            continue;
+        }
+        remove_file(workdir, path)?;
+    }
+
+    let mut to_write = gix_index::State::new(repo.object_hash());
+    let is_unchanged = |path: &BStr, id: &gix_hash::oid, mode: Mode| {
+        !force
+            && current
+                .entry_by_path_and_stage(path, 0)
+                .map_or(false, |e| e.id == id && e.mode == mode)
+    };
+    for entry in new.entries().iter().filter(|e| e.stage() == 0) {
+        let path = entry.path(&new);
+        if !is_unchanged(path, &entry.id, entry.mode) {
+            to_write.dangerously_push_entry(Stat::default(), entry.id, Flags::empty(), entry.mode, path);
+        }
+    }
+    for conflict in conflicts.iter().filter(|c| c.kind != ConflictKind::FileDirectory) {
+        let version = conflict.ours.or(conflict.theirs);
+        let (id, mode) = match (conflict.merged_blob, version) {
+            (Some(id), version) => (id, version.map_or(Mode::FILE, |v| v.mode)),
+            (None, Some(version)) => (version.id, version.mode),
+            (None, None) => continue,
+        };
+        if !is_unchanged(conflict.path.as_ref(), &id, mode) {
+            to_write.dangerously_push_entry(Stat::default(), id, Flags::empty(), mode, conflict.path.as_ref());
+        }
+    }
+    to_write.sort_entries();
+
+    let to_write = write_files(repo, to_write, true)?;
+
+    for (entry, path) in new.entries_mut_with_paths() {
+        if entry.stage() != 0 {
+            continue;
+        }
+        entry.stat = to_write
+            .entry_by_path_and_stage(path, 0)
+            .or_else(|| current.entry_by_path_and_stage(path, 0))
+            .map(|e| e.stat)
+            .unwrap_or_default();
+    }
+    let mut index = gix_index::File::from_state(new, repo.index_path());
+    index.write(Default::default())?;
+    Ok(index)
+}
+
+/// Write all entries of `to_write` into the worktree of `repo`, and return it with the stat information of the written files.
+///
+/// If `overwrite` is `false`, it's an error if files exist already.
+pub(crate) fn write_files(
+    repo: &Repository,
+    to_write: gix_index::State,
+    overwrite: bool,
+) -> Result<gix_index::State, checkout::Error> {
+    use checkout::Error;
+
+    if to_write.entries().is_empty() {
+        return Ok(to_write);
+    }
+    let workdir = repo.work_dir().ok_or(Error::BareRepository)?;
+    let mut to_write_file = gix_index::File::from_state(to_write, repo.index_path());
+    let mut opts = repo.config.checkout_options(
+        repo,
+        gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+    )?;
+    opts.overwrite_existing = overwrite;
+    let outcome = gix_worktree_state::checkout(
+        &mut to_write_file,
+        workdir,
+        repo.objects.clone().into_arc()?,
+        &gix_features::progress::Discard,
+        &gix_features::progress::Discard,
+        &AtomicBool::default(),
+        opts,
+    )?;
+    let failed: Vec<BString> = outcome
+        .collisions
+        .into_iter()
+        .map(|c| c.path)
+        .chain(outcome.errors.into_iter().map(|e| e.path))
+        .collect();
+    if !failed.is_empty() {
+        return Err(Error::Incomplete { paths: failed });
+    }
+    Ok(to_write_file.into_parts().0)
+}
+
+/// Remove the file at `rela_path` from `workdir` along with all parent directories that are empty afterwards.
+/// It's not an error if the file doesn't exist.
+pub(crate) fn remove_file(workdir: &Path, rela_path: &BStr) -> Result<(), checkout::Error> {
+    let path = workdir.join(gix_path::from_bstr(rela_path));
+    match std::fs::remove_file(&path) {
+        Ok(()) => remove_empty_parents(&path, workdir),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(checkout::Error::RemoveFile { path, source: err }),
+    }
+    Ok(())
+}
+
+/// Return the paths of all entries that differ between the indices `a` and `b`.
+fn changed_paths(a: &gix_index::State, b: &gix_index::State) -> Vec<BString> {
+    let is_different = |entry: &gix_index::Entry, path: &BStr, other: &gix_index::State| {
+        entry.stage() != 0
+            || entry.flags.contains(Flags::INTENT_TO_ADD)
+            || other
+                .entry_by_path_and_stage(path, 0)
+                .map_or(true, |e| e.id != entry.id || e.mode != entry.mode)
+    };
+    let mut paths: Vec<BString> = a
+        .entries()
+        .iter()
+        .filter(|e| is_different(e, e.path(a), b))
+        .map(|e| e.path(a).to_owned())
+        .collect();
+    paths.extend(
+        b.entries()
+            .iter()
+            .filter(|e| a.entry_by_path(e.path(b)).is_none())
+            .map(|e| e.path(b).to_owned()),
+    );
+    paths
+}
+
+fn remove_empty_parents(path: &Path, workdir: &Path) {
+    let mut dir = path.parent();
+    while let Some(parent) = dir.filter(|d| *d != workdir) {
+        if std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+}
+
+#[derive(Clone)]
+struct IgnoreSubmodules;
+
+impl gix_status::index_as_worktree::traits::SubmoduleStatus for IgnoreSubmodules {
+    type Output = ();
+    type Error = std::convert::Infallible;
+
+    fn status(&mut self, _entry: &gix_index::Entry, _rela_path: &BStr) -> Result<Option<Self::Output>, Self::Error> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,156 @@
+//! Types for merging notes, see [`Platform::merge()`](crate::notes::Platform::merge()).
+use std::collections::BTreeSet;
+
+use gix_hash::ObjectId;
+use gix_note::merge::Resolution;
+
+use crate::{
+    bstr::ByteSlice,
+    config::{
+        cache::util::ApplyLeniency,
+        tree::{Key, Notes},
+    },
+    notes::{Error, Platform},
+};
+
+pub use gix_note::merge::Strategy;
+
+/// The outcome of [`Platform::merge()`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The commit the notes reference points to after the merge, or `None` if nothing changed, which is also the case if
+    /// there were conflicts that weren't resolved.
+    pub commit: Option<ObjectId>,
+    /// If `true`, the notes reference was fast-forwarded to their notes, without creating a merge commit.
+    pub fast_forward: bool,
+    /// The objects whose notes were changed differently on both sides.
+    ///
+    /// They were resolved according to the [`Strategy`], unless it's [`Strategy::Manual`] in which case nothing was merged.
+    pub conflicts: Vec<ObjectId>,
+}
+
+/// Merging
+impl Platform<'_> {
+    /// Return the strategy to resolve conflicts when merging notes into this notes reference, as configured by
+    /// `notes.<name>.mergeStrategy` or `notes.mergeStrategy`, or [`Strategy::Manual`] if unset.
+    pub fn merge_strategy(&self) -> Result<Strategy, Error> {
+        let config = &self.repo.config;
+        let value = self
+            .name
+            .as_bstr()
+            .strip_prefix(b"refs/notes/")
+            .and_then(|name| {
+                config
+                    .resolved
+                    .string("notes", Some(name.as_bstr()), Notes::REF_MERGE_STRATEGY.name())
+            })
+            .or_else(|| config.resolved.string_by_key("notes.mergeStrategy"));
+        Ok(value
+            .map(|value| Notes::MERGE_STRATEGY.try_into_strategy(value))
+            .transpose()
+            .with_leniency(config.lenient_config)?
+            .unwrap_or_default())
+    }
+
+    /// Merge the committed notes of `other` into our committed notes with `strategy` to resolve conflicts, similar to
+    /// `git notes merge`, and point our notes reference to the result.
+    ///
+    /// Our notes reference is fast-forwarded if possible, or a merge commit is created. Files that aren't notes are taken
+    /// from our side.
+    pub fn merge(&mut self, other: &Platform<'_>, strategy: Strategy) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::notes::Platform::merge()");
+        if self.changed {
+            return Err(Error::PendingChanges);
+        }
+        let Some(theirs) = other.commit else {
+            return Ok(Outcome::default());
+        };
+        let message = format!(
+            "Merged notes from {} into {}",
+            other.name.as_bstr(),
+            self.name.as_bstr()
+        );
+        let fast_forward = |this: &mut Self| -> Result<Outcome, Error> {
+            this.set_reference(theirs, &message)?;
+            this.notes = other.notes.clone();
+            this.non_notes = other.non_notes.clone();
+            Ok(Outcome {
+                commit: Some(theirs),
+                fast_forward: true,
+                conflicts: Vec::new(),
+            })
+        };
+        let Some(ours) = self.commit else {
+            return fast_forward(self);
+        };
+        if ours == theirs {
+            return Ok(Outcome::default());
+        }
+        let base = self.repo.merge_base::<Error>(ours, &[theirs])?;
+        if base == Some(theirs) {
+            return Ok(Outcome::default());
+        }
+        if base == Some(ours) {
+            return fast_forward(self);
+        }
+
+        let base_notes = match base {
+            Some(base) => super::read_commit(self.repo, base)?.0,
+            None => Default::default(),
+        };
+        let objects: BTreeSet<ObjectId> = self
+            .notes
+            .keys()
+            .chain(other.notes.keys())
+            .chain(base_notes.keys())
+            .copied()
+            .collect();
+        let mut merged = self.notes.clone();
+        let mut conflicts = Vec::new();
+        for object in objects {
+            let (base, ours, theirs) = (
+                base_notes.get(&object).copied(),
+                self.notes.get(&object).copied(),
+                other.notes.get(&object).copied(),
+            );
+            let note = if ours == theirs || theirs == base {
+                continue;
+            } else if ours == base {
+                theirs
+            } else {
+                conflicts.push(object);
+                let ours_data = ours.map(|id| self.repo.find_object(id)).transpose()?;
+                let theirs_data = theirs.map(|id| self.repo.find_object(id)).transpose()?;
+                match gix_note::merge::resolve(
+                    strategy,
+                    ours_data.as_ref().map(|note| note.data.as_bstr()),
+                    theirs_data.as_ref().map(|note| note.data.as_bstr()),
+                ) {
+                    Resolution::Conflict => continue,
+                    Resolution::Remove => None,
+                    Resolution::Note(note) => Some(self.repo.write_blob(note.as_ref())?.detach()),
+                }
+            };
+            match note {
+                Some(note) => merged.insert(object, note),
+                None => merged.remove(&object),
+            };
+        }
+        if strategy == Strategy::Manual && !conflicts.is_empty() {
+            return Ok(Outcome {
+                commit: None,
+                fast_forward: false,
+                conflicts,
+            });
+        }
+
+        self.notes = merged;
+        let tree = self.write_tree()?;
+        let commit = self.write_commit(tree, vec![ours, theirs], &message)?;
+        Ok(Outcome {
+            commit: Some(commit),
+            fast_forward: false,
+            conflicts,
+        })
+    }
+}
@@ -0,0 +1,378 @@
+//! Read and write notes attached to objects, see [`Repository::notes()`](crate::Repository::notes()).
+//!
+//! Notes are stored in commits that `refs/notes/*` references point to, with each note being a blob in the tree of the commit
+//! that is named after the object it annotates, just like `git notes` does it, so both can be used interchangeably.
+use std::collections::BTreeMap;
+
+use gix_hash::ObjectId;
+use gix_index::entry::{Flags, Mode, Stat};
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, FullNameRef, Target,
+};
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    ext::ObjectIdExt,
+    Id, Repository,
+};
+
+///
+pub mod merge;
+
+/// The notes reference that is used if `core.notesRef` isn't set.
+pub const DEFAULT_REF: &str = "refs/notes/commits";
+
+/// The error returned by [`Repository::notes()`](crate::Repository::notes()) and methods of the notes [`Platform`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Object {id} already has a note")]
+    NoteExists { id: ObjectId },
+    #[error("Object {id} has no note")]
+    NoNote { id: ObjectId },
+    #[error("Notes have to be committed before they can be merged")]
+    PendingChanges,
+    #[error("Committer identity is not configured")]
+    CommitterMissing,
+    #[error(transparent)]
+    ValidateName(#[from] gix_validate::reference::name::Error),
+    #[error(transparent)]
+    ConfigMergeStrategy(#[from] crate::config::key::GenericErrorWithValue),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::try_into::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    TraverseTree(#[from] gix_traverse::tree::breadthfirst::Error),
+    #[error(transparent)]
+    WriteTree(#[from] gix_index::write_tree::Error<crate::object::write::Error>),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    ParseTime(#[from] crate::config::time::Error),
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    Traverse(#[from] gix_traverse::commit::ancestors::Error),
+}
+
+/// A note as returned by [`Platform::iter()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Entry {
+    /// The object that is annotated by the note.
+    pub object: ObjectId,
+    /// The blob that holds the message of the note.
+    pub note: ObjectId,
+}
+
+/// A platform to read and change the notes of a notes reference like `refs/notes/commits`, similar to `git notes`,
+/// as obtained by [`Repository::notes()`].
+///
+/// All changes are kept in memory until they are [committed](Self::commit()), which creates a new commit for the notes
+/// reference just like `git notes` would.
+///
+/// Notes may be stored in subdirectories named after the first characters of the hexadecimal hash of the object they annotate,
+/// a *fan-out* to keep trees small. When writing, notes are spread across one more level of subdirectories for each 256
+/// notes in a directory, and all files that aren't notes are kept.
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) name: FullName,
+    pub(crate) commit: Option<ObjectId>,
+    pub(crate) notes: BTreeMap<ObjectId, ObjectId>,
+    pub(crate) non_notes: Vec<(BString, Mode, ObjectId)>,
+    pub(crate) changed: bool,
+}
+
+/// Access
+impl<'repo> Platform<'repo> {
+    /// Return the name of the notes reference, like `refs/notes/commits`.
+    pub fn name(&self) -> &FullNameRef {
+        self.name.as_ref()
+    }
+
+    /// Return the commit of the notes reference that the notes were read from or last committed as, or `None` if the
+    /// reference doesn't exist yet.
+    pub fn commit_id(&self) -> Option<Id<'repo>> {
+        self.commit.map(|id| id.attach(self.repo))
+    }
+
+    /// Return `true` if there are changes that weren't [committed](Self::commit()) yet.
+    pub fn has_changes(&self) -> bool {
+        self.changed
+    }
+
+    /// Return the amount of notes.
+    pub fn len(&self) -> usize {
+        self.notes.len()
+    }
+
+    /// Return `true` if there are no notes.
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+    }
+
+    /// Return all notes, ordered by the object they annotate.
+    pub fn iter(&self) -> impl Iterator<Item = Entry> + '_ {
+        self.notes.iter().map(|(object, note)| Entry {
+            object: *object,
+            note: *note,
+        })
+    }
+
+    /// Return the id of the blob holding the note of `object`, or `None` if it has no note.
+    pub fn find(&self, object: impl AsRef<gix_hash::oid>) -> Option<Id<'repo>> {
+        self.notes.get(object.as_ref()).map(|id| id.attach(self.repo))
+    }
+
+    /// Return the message of the note of `object`, or `None` if it has no note.
+    pub fn message(&self, object: impl AsRef<gix_hash::oid>) -> Result<Option<Vec<u8>>, Error> {
+        match self.notes.get(object.as_ref()) {
+            Some(note) => Ok(Some(self.repo.find_object(*note)?.detach().data)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Mutation
+impl Platform<'_> {
+    /// Add a note with `message` to `object`, and return the id of the blob that holds it.
+    ///
+    /// If `object` has a note already, it's replaced if `overwrite` is `true`, or an error is returned otherwise.
+    /// `message` is stored as is, without adding a trailing newline.
+    pub fn add(
+        &mut self,
+        object: impl Into<ObjectId>,
+        message: impl AsRef<[u8]>,
+        overwrite: bool,
+    ) -> Result<ObjectId, Error> {
+        let object = object.into();
+        if !overwrite && self.notes.contains_key(&object) {
+            return Err(Error::NoteExists { id: object });
+        }
+        let note = self.repo.write_blob(message)?.detach();
+        self.set(object, Some(note));
+        Ok(note)
+    }
+
+    /// Append `message` to the note of `object`, separated by an empty line, or add it as new note if there is none.
+    /// Return the id of the blob that holds the note.
+    pub fn append(&mut self, object: impl Into<ObjectId>, message: impl AsRef<[u8]>) -> Result<ObjectId, Error> {
+        let object = object.into();
+        let message = match self.message(object)? {
+            Some(existing) => gix_note::merge::concatenate(existing.as_bstr(), message.as_ref().as_bstr()).into_owned(),
+            None => message.as_ref().into(),
+        };
+        self.add(object, message, true)
+    }
+
+    /// Remove the note of `object`, and return the id of its blob, or `None` if it had no note.
+    pub fn remove(&mut self, object: impl AsRef<gix_hash::oid>) -> Option<ObjectId> {
+        let note = self.notes.get(object.as_ref()).copied()?;
+        self.set(object.as_ref().to_owned(), None);
+        Some(note)
+    }
+
+    /// Copy the note of `from` to `to` and return the id of its blob, similar to `git notes copy`.
+    ///
+    /// If `to` has a note already, it's replaced if `overwrite` is `true`, or an error is returned otherwise.
+    pub fn copy(
+        &mut self,
+        from: impl AsRef<gix_hash::oid>,
+        to: impl Into<ObjectId>,
+        overwrite: bool,
+    ) -> Result<ObjectId, Error> {
+        let from = from.as_ref();
+        let note = *self
+            .notes
+            .get(from)
+            .ok_or_else(|| Error::NoNote { id: from.to_owned() })?;
+        let to = to.into();
+        if !overwrite && self.notes.contains_key(&to) {
+            return Err(Error::NoteExists { id: to });
+        }
+        self.set(to, Some(note));
+        Ok(note)
+    }
+
+    /// Write all changes as new commit with `message` and point the notes reference to it, with the previous commit
+    /// as its parent. Return the id of the new commit, or `None` if there was nothing to commit.
+    ///
+    /// The operation fails if the notes reference was changed by someone else since the notes were read.
+    pub fn commit(&mut self, message: impl AsRef<str>) -> Result<Option<ObjectId>, Error> {
+        if !self.changed {
+            return Ok(None);
+        }
+        let tree = self.write_tree()?;
+        let parents = self.commit.into_iter().collect();
+        let id = self.write_commit(tree, parents, message.as_ref())?;
+        Ok(Some(id))
+    }
+}
+
+/// Utilities
+impl<'repo> Platform<'repo> {
+    /// Read the notes of the notes reference `name` in `repo`.
+    pub(crate) fn from_reference(repo: &'repo Repository, name: FullName) -> Result<Self, Error> {
+        let commit = repo
+            .try_find_reference(name.as_ref())?
+            .map(|mut reference| reference.peel_to_id_in_place().map(Id::detach))
+            .transpose()?;
+        let mut platform = Platform {
+            repo,
+            name,
+            commit,
+            notes: Default::default(),
+            non_notes: Vec::new(),
+            changed: false,
+        };
+        if let Some(commit) = commit {
+            (platform.notes, platform.non_notes) = read_commit(repo, commit)?;
+        }
+        Ok(platform)
+    }
+
+    fn set(&mut self, object: ObjectId, note: Option<ObjectId>) {
+        match note {
+            Some(note) => {
+                self.notes.insert(object, note);
+            }
+            None => {
+                self.notes.remove(&object);
+            }
+        }
+        self.changed = true;
+    }
+
+    fn write_tree(&self) -> Result<ObjectId, Error> {
+        let hex_len = self.repo.object_hash().len_in_hex();
+        let mut fanout = 0;
+        let mut count = self.notes.len();
+        while count > 256 && fanout < hex_len / 2 - 1 {
+            count /= 256;
+            fanout += 1;
+        }
+
+        let mut state = gix_index::State::new(self.repo.object_hash());
+        let mut path = BString::default();
+        for (object, note) in &self.notes {
+            let hex = object.to_hex().to_string();
+            path.clear();
+            for level in 0..fanout {
+                path.extend_from_slice(&hex.as_bytes()[level * 2..][..2]);
+                path.push(b'/');
+            }
+            path.extend_from_slice(&hex.as_bytes()[fanout * 2..]);
+            state.dangerously_push_entry(Stat::default(), *note, Flags::empty(), Mode::FILE, path.as_ref());
+        }
+        for (path, mode, id) in &self.non_notes {
+            state.dangerously_push_entry(Stat::default(), *id, Flags::empty(), *mode, path.as_ref());
+        }
+        state.sort_entries();
+        Ok(state.write_tree(|tree| self.repo.write_object(tree).map(Id::detach))?)
+    }
+
+    /// Write a commit for `tree` with `parents` and `message`, and point the notes reference to it.
+    pub(crate) fn write_commit(
+        &mut self,
+        tree: ObjectId,
+        parents: Vec<ObjectId>,
+        message: &str,
+    ) -> Result<ObjectId, Error> {
+        let committer = self.repo.committer().ok_or(Error::CommitterMissing)??.to_owned();
+        let author = match self.repo.author() {
+            Some(author) => author?.to_owned(),
+            None => committer.clone(),
+        };
+        let mut message = message.to_owned();
+        if !message.ends_with('\n') {
+            message.push('\n');
+        }
+        let commit = gix_object::Commit {
+            tree,
+            parents: parents.into(),
+            author,
+            committer,
+            encoding: None,
+            message: message.as_str().into(),
+            extra_headers: Default::default(),
+        };
+        let id = self.repo.write_object(&commit)?.detach();
+        self.set_reference(id, &message)?;
+        Ok(id)
+    }
+
+    /// Point the notes reference to `commit`, which is expected to contain the notes of this instance, with a reflog
+    /// entry derived from `message`.
+    pub(crate) fn set_reference(&mut self, commit: ObjectId, message: &str) -> Result<(), Error> {
+        self.repo.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: format!(
+                        "notes: {}",
+                        message.as_bytes().lines().next().unwrap_or_default().as_bstr()
+                    )
+                    .into(),
+                },
+                expected: match self.commit {
+                    Some(previous) => PreviousValue::MustExistAndMatch(Target::Peeled(previous)),
+                    None => PreviousValue::MustNotExist,
+                },
+                new: Target::Peeled(commit),
+            },
+            name: self.name.clone(),
+            deref: false,
+        })?;
+        self.commit = Some(commit);
+        self.changed = false;
+        Ok(())
+    }
+}
+
+/// The notes of the notes commit `commit` by the object they annotate, along with all files that aren't notes.
+type NotesAndNonNotes = (BTreeMap<ObjectId, ObjectId>, Vec<(BString, Mode, ObjectId)>);
+
+/// Read all notes from the tree of `commit`, which may use any fan-out.
+pub(crate) fn read_commit(repo: &Repository, commit: ObjectId) -> Result<NotesAndNonNotes, Error> {
+    let tree = repo.find_object(commit)?.try_into_commit()?.tree_id()?.detach();
+    let state = gix_index::State::from_tree(&tree, &repo.objects)?;
+    let hex_len = repo.object_hash().len_in_hex();
+    let mut notes = BTreeMap::new();
+    let mut non_notes = Vec::new();
+    let mut hex = Vec::with_capacity(hex_len);
+    for entry in state.entries() {
+        let path = entry.path(&state);
+        hex.clear();
+        let mut components = path.split(|b| *b == b'/').peekable();
+        let mut is_note = entry.mode == Mode::FILE;
+        while let Some(component) = components.next() {
+            let is_fanout = components.peek().is_some();
+            if (is_fanout && component.len() != 2) || !component.iter().all(u8::is_ascii_hexdigit) {
+                is_note = false;
+                break;
+            }
+            hex.extend_from_slice(component);
+        }
+        match is_note
+            .then(|| ObjectId::from_hex(&hex).ok())
+            .flatten()
+            .filter(|_| hex.len() == hex_len)
+        {
+            Some(object) => {
+                notes.insert(object, entry.id);
+            }
+            None => non_notes.push((path.to_owned(), entry.mode, entry.id)),
+        }
+    }
+    Ok((notes, non_notes))
+}
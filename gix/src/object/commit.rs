@@ -158,13 +158,22 @@ impl<'repo> Commit<'repo> {
     }
 
     /// Extracts the PGP signature and the data that was used to create the signature, or `None` if it wasn't signed.
-    // TODO: make it possible to verify the signature, probably by wrapping `SignedData`. It's quite some work to do it properly.
+    ///
+    /// Use [`verify_signature()`](Self::verify_signature()) to check the signature right away.
     pub fn signature(
         &self,
     ) -> Result<Option<(std::borrow::Cow<'_, BStr>, gix_object::commit::SignedData<'_>)>, gix_object::decode::Error>
     {
         gix_object::CommitRefIter::signature(&self.data)
     }
+
+    /// Check the signature of this commit with `verifier`, and fail if it isn't signed or if the signature is invalid.
+    pub fn verify_signature(&self, verifier: &dyn crate::sign::Verify) -> Result<(), crate::sign::verify::Error> {
+        let (signature, signed_data) = self.signature()?.ok_or(crate::sign::verify::Error::Unsigned)?;
+        verifier
+            .verify(signature.as_ref(), &signed_data.to_bstring())
+            .map_err(crate::sign::verify::Error::Verify)
+    }
 }
 
 impl<'r> std::fmt::Debug for Commit<'r> {
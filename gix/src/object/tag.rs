@@ -1,4 +1,4 @@
-use crate::{ext::ObjectIdExt, ObjectDetached, Tag};
+use crate::{bstr::BStr, ext::ObjectIdExt, ObjectDetached, Tag};
 
 impl<'repo> Tag<'repo> {
     /// Decode the entire tag object and return it for accessing all tag information.
@@ -23,6 +23,21 @@ impl<'repo> Tag<'repo> {
     pub fn tagger(&self) -> Result<Option<gix_actor::SignatureRef<'_>>, gix_object::decode::Error> {
         gix_object::TagRefIter::from_bytes(&self.data).tagger()
     }
+
+    /// Extracts the signature and the data that was used to create the signature, or `None` if it wasn't signed.
+    ///
+    /// Use [`verify_signature()`](Self::verify_signature()) to check the signature right away.
+    pub fn signature(&self) -> Result<Option<(&BStr, &BStr)>, gix_object::decode::Error> {
+        gix_object::TagRefIter::signature(&self.data)
+    }
+
+    /// Check the signature of this tag with `verifier`, and fail if it isn't signed or if the signature is invalid.
+    pub fn verify_signature(&self, verifier: &dyn crate::sign::Verify) -> Result<(), crate::sign::verify::Error> {
+        let (signature, signed_data) = self.signature()?.ok_or(crate::sign::verify::Error::Unsigned)?;
+        verifier
+            .verify(signature, signed_data)
+            .map_err(crate::sign::verify::Error::Verify)
+    }
 }
 
 /// Remove Lifetime
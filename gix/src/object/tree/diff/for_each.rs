@@ -86,6 +86,7 @@ impl<'a, 'old> Platform<'a, 'old> {
             visit: for_each,
             location: self.tracking,
             tracked: self.rewrites.map(rewrites::Tracker::new),
+            pathspec: self.pathspec.as_mut(),
             err: None,
         };
         match gix_diff::tree::Changes::from(TreeRefIter::from_bytes(&self.lhs.data)).needed_to_obtain(
@@ -120,6 +121,7 @@ struct Delegate<'a, 'old, 'new, VisitFn, E> {
     visit: VisitFn,
     tracked: Option<rewrites::Tracker<gix_diff::tree::visit::Change>>,
     location: Option<gix_diff::tree::recorder::Location>,
+    pathspec: Option<&'a mut crate::PathspecDetached>,
     err: Option<E>,
 }
 
@@ -259,6 +261,12 @@ where
     }
 
     fn visit(&mut self, change: gix_diff::tree::visit::Change) -> gix_diff::tree::visit::Action {
+        if let Some(pathspec) = self.pathspec.as_mut() {
+            let is_dir = change.oid_and_entry_mode().1.is_tree();
+            if !pathspec.is_included(self.recorder.path(), Some(is_dir)) {
+                return gix_diff::tree::visit::Action::Continue;
+            }
+        }
         match self.tracked.as_mut() {
             Some(tracked) => tracked.try_push_change(change, self.recorder.path()).map_or(
                 gix_diff::tree::visit::Action::Continue,
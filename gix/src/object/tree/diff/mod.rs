@@ -45,6 +45,7 @@ impl<'repo> Tree<'repo> {
             lhs: self,
             tracking: None,
             rewrites: self.repo.config.diff_renames()?.unwrap_or_default().into(),
+            pathspec: None,
         })
     }
 }
@@ -56,6 +57,7 @@ pub struct Platform<'a, 'repo> {
     lhs: &'a Tree<'repo>,
     tracking: Option<Location>,
     rewrites: Option<Rewrites>,
+    pathspec: Option<crate::PathspecDetached>,
 }
 
 /// Configuration
@@ -83,6 +85,22 @@ impl<'a, 'repo> Platform<'a, 'repo> {
         self.rewrites = renames;
         self
     }
+
+    /// Provide `None` to see all changes, or `Some(pathspec)` to only see changes to paths that are included by `pathspec`,
+    /// which may also match on attributes like `:(attr:binary)`.
+    ///
+    /// As pathspecs match on the entire path of a change, this also turns on [path tracking](Self::track_path()).
+    /// Note that rewrite tracking only sees the changes that passed the `pathspec`, just like `git diff` without `--follow`.
+    ///
+    /// Create a `pathspec` with [`Repository::pathspec()`](crate::Repository::pathspec()) and
+    /// [detach it](crate::Pathspec::detach()).
+    pub fn pathspec(&mut self, pathspec: Option<crate::PathspecDetached>) -> &mut Self {
+        if pathspec.is_some() {
+            self.tracking = Some(Location::Path);
+        }
+        self.pathspec = pathspec;
+        self
+    }
 }
 
 ///
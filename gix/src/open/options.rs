@@ -14,6 +14,9 @@ impl Default for Options {
             lenient_config: true,
             bail_if_untrusted: false,
             open_path_as_is: false,
+            reuse_global_config: false,
+            #[cfg(feature = "hooks")]
+            run_hooks: false,
             api_config_overrides: Vec::new(),
             cli_config_overrides: Vec::new(),
             current_dir: None,
@@ -28,6 +31,16 @@ impl Options {
     pub fn isolated() -> Self {
         Options::default().permissions(Permissions::isolated())
     }
+
+    /// Options for integrations like shell prompts or status bars, which open many repositories in quick succession
+    /// only to learn about their `HEAD` and whether they are dirty.
+    ///
+    /// Configuration is read [without comments and whitespace](Self::lossy_config()) even in debug builds, and the
+    /// global configuration is [reused](Self::reuse_global_config()) across all repositories that are opened by the same thread.
+    /// Everything else, including the trust level of each repository, is determined as usual.
+    pub fn lightweight() -> Self {
+        Options::default().lossy_config(true).reuse_global_config(true)
+    }
 }
 
 /// Generic modification
@@ -142,6 +155,27 @@ impl Options {
         self
     }
 
+    /// If `true`, default `false`, run the hooks of the repository like `git` would when committing, checking out and fetching.
+    ///
+    /// See the [`hooks`](crate::hooks) module for details on which hooks are run and when.
+    #[cfg(feature = "hooks")]
+    pub fn run_hooks(mut self, toggle: bool) -> Self {
+        self.run_hooks = toggle;
+        self
+    }
+
+    /// If `true`, default `false`, the configuration files of the git installation, the system and the current user are
+    /// parsed only once per thread and reused for all repositories opened with this option, for as long as none of these
+    /// files changes its size or modification time.
+    ///
+    /// This makes opening repositories considerably faster if these files are large, and is most useful for applications
+    /// that open many repositories in quick succession.
+    /// Configuration that depends on the repository, like conditional includes, is still resolved for each repository.
+    pub fn reuse_global_config(mut self, toggle: bool) -> Self {
+        self.reuse_global_config = toggle;
+        self
+    }
+
     /// Open a repository at `path` with the options set so far.
     #[allow(clippy::result_large_err)]
     pub fn open(self, path: impl Into<PathBuf>) -> Result<ThreadSafeRepository, Error> {
@@ -167,6 +201,9 @@ impl gix_sec::trust::DefaultForLevel for Options {
                 bail_if_untrusted: false,
                 lenient_config: true,
                 open_path_as_is: false,
+                reuse_global_config: false,
+                #[cfg(feature = "hooks")]
+                run_hooks: false,
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
                 current_dir: None,
@@ -179,6 +216,9 @@ impl gix_sec::trust::DefaultForLevel for Options {
                 bail_if_untrusted: false,
                 lenient_config: true,
                 open_path_as_is: false,
+                reuse_global_config: false,
+                #[cfg(feature = "hooks")]
+                run_hooks: false,
                 lossy_config: None,
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
@@ -165,6 +165,9 @@ impl ThreadSafeRepository {
             lenient_config,
             bail_if_untrusted,
             open_path_as_is: _,
+            reuse_global_config,
+            #[cfg(feature = "hooks")]
+                run_hooks: _,
             permissions:
                 Permissions {
                     ref env,
@@ -243,6 +246,7 @@ impl ThreadSafeRepository {
             attributes,
             config,
             lenient_config,
+            reuse_global_config,
             api_config_overrides,
             cli_config_overrides,
         )?;
@@ -0,0 +1,41 @@
+/// The error returned by [`Repository::fetch_missing_objects()`](crate::Repository::fetch_missing_objects()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The repository isn't a partial clone as 'extensions.partialClone' isn't set")]
+    NotAPartialClone,
+    #[error(transparent)]
+    FindRemote(#[from] crate::remote::find::existing::Error),
+    #[error(transparent)]
+    Refspec(#[from] gix_refspec::parse::Error),
+    #[error(transparent)]
+    SetConfig(#[from] crate::config::set_value::Error),
+    #[error(transparent)]
+    ApplyConfig(#[from] crate::config::Error),
+    #[error(transparent)]
+    Connect(#[from] crate::remote::connect::Error),
+    #[error(transparent)]
+    PrepareFetch(#[from] crate::remote::fetch::prepare::Error),
+    #[error(transparent)]
+    Fetch(#[from] crate::remote::fetch::Error),
+    #[error("Failed to remove .keep file at \"{}\"", path.display())]
+    RemovePackKeepFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+///
+pub mod backfill {
+    /// The error returned by [`Repository::backfill_missing_blobs()`](crate::Repository::backfill_missing_blobs()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        TraverseTree(#[from] gix_traverse::tree::breadthfirst::Error),
+        #[error(transparent)]
+        Pathspec(#[from] crate::pathspec::init::Error),
+        #[error(transparent)]
+        Fetch(#[from] super::Error),
+    }
+}
@@ -0,0 +1,118 @@
+pub use gix_rebase::*;
+
+use gix_hash::ObjectId;
+
+/// Options for use in [`Repository::rebase()`](crate::Repository::rebase()).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The commit onto which all commits should be replayed, or `None` to use `upstream`.
+    pub onto: Option<ObjectId>,
+    /// If `true`, replay commits even if their changes are already present in `upstream`.
+    ///
+    /// Otherwise, and by default, these are skipped, which typically happens if they were cherry-picked or applied as patch already.
+    pub reapply_cherry_picks: bool,
+}
+
+/// A commit that was replayed successfully.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Replayed {
+    /// The commit whose changes were replayed.
+    pub original: ObjectId,
+    /// The newly created commit with the same changes.
+    pub rewritten: ObjectId,
+}
+
+/// Information about the commit at which the rebase stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stopped {
+    /// The commit whose changes couldn't be applied without conflicts.
+    pub commit: ObjectId,
+    /// All conflicts that have to be resolved before the rebase can be [continued](crate::Repository::rebase_continue()).
+    pub conflicts: Vec<crate::merge::tree::Conflict>,
+}
+
+/// The outcome of [`Repository::rebase()`](crate::Repository::rebase()) and [`Repository::rebase_continue()`](crate::Repository::rebase_continue()).
+///
+/// Note that it only contains information about the commits processed during the respective call.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// All commits that were replayed, in order.
+    pub replayed: Vec<Replayed>,
+    /// Commits that were not replayed as their changes are already present, in order.
+    pub skipped: Vec<ObjectId>,
+    /// If set, the rebase stopped due to conflicts and is still in progress.
+    ///
+    /// Otherwise, the rebase is complete.
+    pub stopped: Option<Stopped>,
+}
+
+/// The error returned by [`Repository::rebase()`](crate::Repository::rebase()) and related methods.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot rebase while another operation is in progress: {0:?}")]
+    InProgress(crate::state::InProgress),
+    #[error("No rebase is in progress")]
+    NotInProgress,
+    #[error("The rebase todo-list contains the unsupported command '{command}'")]
+    UnsupportedCommand { command: &'static str },
+    #[error("Commit {id} is a merge commit and can't be replayed")]
+    MergeCommit { id: ObjectId },
+    #[error("The index still has conflicts that need to be resolved, the first of {} being at '{}'", .paths.len(), .paths[0])]
+    UnresolvedConflicts { paths: Vec<crate::bstr::BString> },
+    #[error("Committer identity is not configured")]
+    CommitterMissing,
+    #[error(transparent)]
+    ReadState(#[from] gix_rebase::state::from_dir::Error),
+    #[error("Could not write the rebase state")]
+    WriteState(#[from] std::io::Error),
+    #[error(transparent)]
+    RequireClean(#[from] crate::merge::worktree::require_clean::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::merge::worktree::checkout::Error),
+    #[error(transparent)]
+    Merge(#[from] crate::merge::tree::Error),
+    #[error(transparent)]
+    MergeOptions(#[from] crate::config::merge_tree_options::Error),
+    #[error(transparent)]
+    Rerere(#[from] crate::rerere::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    HeadId(#[from] crate::reference::head_id::Error),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::try_into::Error),
+    #[error(transparent)]
+    CommitTree(#[from] crate::object::commit::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    ParseRevision(#[from] crate::revision::spec::parse::single::Error),
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    Traverse(#[from] gix_traverse::commit::ancestors::Error),
+    #[error(transparent)]
+    PatchId(#[from] crate::object::tree::diff::for_each::Error),
+    #[error(transparent)]
+    DiffResourceCache(#[from] crate::repository::diff::resource_cache::Error),
+    #[error(transparent)]
+    DiffRewrites(#[from] crate::diff::new_rewrites::Error),
+    #[error("Could not create an index from the tree at {id}")]
+    IndexFromTree {
+        id: ObjectId,
+        source: gix_traverse::tree::breadthfirst::Error,
+    },
+    #[error(transparent)]
+    WriteTree(#[from] gix_index::write_tree::Error<crate::object::write::Error>),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    ParseTime(#[from] crate::config::time::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+}
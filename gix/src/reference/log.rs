@@ -39,3 +39,49 @@ pub(crate) fn commit_type_by_parents(count: usize) -> Option<&'static str> {
         _two_or_more => "merge",
     })
 }
+
+///
+pub mod expire {
+    use std::time::SystemTime;
+
+    /// Determine which entries of a reference log to remove in [`Repository::reflog_expire()`](crate::Repository::reflog_expire()).
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Policy {
+        /// Remove all entries that were created before this time, or keep them all if `None`.
+        pub expire: Option<SystemTime>,
+        /// Remove all entries that were created before this time and whose new value isn't reachable from the current
+        /// value of the reference, or keep them all if `None`.
+        pub expire_unreachable: Option<SystemTime>,
+    }
+
+    /// The outcome of [`Repository::reflog_expire()`](crate::Repository::reflog_expire()).
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The amount of entries that were removed.
+        pub num_removed: usize,
+        /// The amount of entries that remain in the reference log.
+        pub num_kept: usize,
+    }
+
+    /// The error returned by [`Repository::reflog_expire()`](crate::Repository::reflog_expire()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigExpiry(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
+        LockAcquire(#[from] gix_lock::acquire::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        Decode(#[from] gix_ref::file::log::iter::decode::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        PeelReference(#[from] crate::reference::peel::Error),
+        #[error(transparent)]
+        FindCommit(#[from] gix_object::find::existing_iter::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+    }
+}
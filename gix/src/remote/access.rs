@@ -27,6 +27,14 @@ impl<'repo> Remote<'repo> {
         self.fetch_tags
     }
 
+    /// Return the object filter to use when fetching, like `blob:none`, as configured in `remote.<name>.partialCloneFilter`.
+    ///
+    /// If set, objects that don't pass the filter aren't fetched, and this remote is expected to provide them later
+    /// on demand.
+    pub fn partial_clone_filter(&self) -> Option<&BStr> {
+        self.partial_clone_filter.as_ref().map(AsRef::as_ref)
+    }
+
     /// Return the url used for the given `direction` with rewrites from `url.<base>.insteadOf|pushInsteadOf`, unless the instance
     /// was created with one of the `_without_url_rewrite()` methods.
     /// For pushing, this is the `remote.<name>.pushUrl` or the `remote.<name>.url` used for fetching, and for fetching it's
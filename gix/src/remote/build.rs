@@ -1,6 +1,9 @@
 use std::convert::TryInto;
 
-use crate::{bstr::BStr, remote, Remote};
+use crate::{
+    bstr::{BStr, BString},
+    remote, Remote,
+};
 
 /// Builder methods
 impl Remote<'_> {
@@ -35,6 +38,15 @@ impl Remote<'_> {
         self
     }
 
+    /// Set the object `filter` to use when fetching, like `blob:none` to only fetch commits and trees, turning a clone into
+    /// a partial clone.
+    ///
+    /// Objects filtered this way will be fetched from this remote on demand.
+    pub fn with_partial_clone_filter(mut self, filter: impl Into<BString>) -> Self {
+        self.partial_clone_filter = Some(filter.into());
+        self
+    }
+
     fn push_url_inner(
         mut self,
         push_url: gix_url::Url,
@@ -34,6 +34,13 @@ mod error {
             source: Box<gix_discover::is_git::Error>,
             url: gix_url::Url,
         },
+        #[cfg(feature = "blocking-network-client")]
+        #[error("The proxy {value:?} configured in {key:?} could not be parsed")]
+        Proxy {
+            key: BString,
+            value: BString,
+            source: gix_protocol::transport::client::proxy::parse::Error,
+        },
     }
 
     impl gix_protocol::transport::IsSpuriousError for Error {
@@ -45,6 +52,31 @@ mod error {
             }
         }
     }
+
+    impl crate::error::ErrorCode for Error {
+        fn code(&self) -> crate::error::Code {
+            use gix_protocol::transport::client::{self, connect};
+
+            use crate::error::{self, Code};
+            match self {
+                Error::SshOptions(_)
+                | Error::SchemePermission(_)
+                | Error::ProtocolDenied { .. }
+                | Error::UnknownProtocol { .. } => Code::Configuration,
+                #[cfg(feature = "blocking-network-client")]
+                Error::Proxy { .. } => Code::Configuration,
+                Error::CurrentDir(err) => error::io(err),
+                Error::InvalidRemoteRepositoryPath { .. } | Error::MissingUrl { .. } | Error::FileUrl { .. } => {
+                    Code::InvalidInput
+                }
+                Error::Connect(connect::Error::Connection(err)) => match err.downcast_ref::<client::Error>() {
+                    Some(err) => error::transport(err),
+                    None => Code::Network,
+                },
+                Error::Connect(_) => Code::InvalidInput,
+            }
+        }
+    }
 }
 pub use error::Error;
 
@@ -85,6 +117,8 @@ impl<'repo> Remote<'repo> {
         let (url, version) = self.sanitized_url_and_version(direction)?;
         #[cfg(feature = "blocking-network-client")]
         let scheme_is_ssh = url.scheme == gix_url::Scheme::Ssh;
+        #[cfg(feature = "blocking-network-client")]
+        let proxy = self.proxy(&url)?;
         let transport = gix_protocol::transport::connect(
             url,
             gix_protocol::transport::client::connect::Options {
@@ -94,6 +128,8 @@ impl<'repo> Remote<'repo> {
                     .then(|| self.repo.ssh_connect_options())
                     .transpose()?
                     .unwrap_or_default(),
+                #[cfg(feature = "blocking-network-client")]
+                proxy,
                 trace: self.repo.config.trace_packet(),
             },
         )
@@ -101,6 +137,57 @@ impl<'repo> Remote<'repo> {
         Ok(self.to_connection_with_transport(transport))
     }
 
+    /// Return the proxy to use when connecting to `url` via `git://` or `ssh://`, as configured in `remote.<name>.proxy`,
+    /// `http.proxy` or the `ALL_PROXY` environment variable, unless its host is excluded by `NO_PROXY`.
+    ///
+    /// HTTP transports configure their proxy with [`transport_options()`](crate::Repository::transport_options()) instead.
+    #[cfg(feature = "blocking-network-client")]
+    fn proxy(&self, url: &gix_url::Url) -> Result<Option<gix_protocol::transport::client::Proxy>, Error> {
+        use gix_protocol::transport::client::{proxy, Proxy};
+
+        use crate::{
+            bstr::{BString, ByteSlice},
+            config::tree::{gitoxide, Http, Key, Remote},
+        };
+
+        let host = match (&url.scheme, url.host()) {
+            (gix_url::Scheme::Git | gix_url::Scheme::Ssh, Some(host)) => host,
+            _ => return Ok(None),
+        };
+        let config = &self.repo.config.resolved;
+        let mut trusted_only = self.repo.filter_config_section();
+        let Some((key, value)) = self
+            .name()
+            .and_then(|name| {
+                config
+                    .string_filter("remote", Some(name.as_bstr()), Remote::PROXY.name, &mut trusted_only)
+                    .map(|v| (BString::from(format!("remote.{}.proxy", name.as_bstr())), v))
+            })
+            .or_else(|| {
+                [Http::PROXY.logical_name(), gitoxide::Http::ALL_PROXY.logical_name()]
+                    .into_iter()
+                    .find_map(|key| {
+                        config
+                            .string_filter_by_key(key.as_str(), &mut trusted_only)
+                            .map(|v| (key.into(), v))
+                    })
+            })
+        else {
+            return Ok(None);
+        };
+        let no_proxy = config.string_filter_by_key(gitoxide::Http::NO_PROXY.logical_name().as_str(), &mut trusted_only);
+        if no_proxy.map_or(false, |no_proxy| {
+            proxy::is_excluded(host, no_proxy.to_str_lossy().as_ref())
+        }) {
+            return Ok(None);
+        }
+        Proxy::from_declaration(value.to_str_lossy().as_ref()).map_err(|err| Error::Proxy {
+            key,
+            value: value.into_owned(),
+            source: err,
+        })
+    }
+
     /// Produce the sanitized URL and protocol version to use as obtained by querying the repository configuration.
     ///
     /// This can be useful when using custom transports to allow additional configuration.
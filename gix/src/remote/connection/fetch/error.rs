@@ -28,6 +28,11 @@ pub enum Error {
         path: std::path::PathBuf,
         source: std::io::Error,
     },
+    #[error("Failed to write .promisor file at \"{}\"", path.display())]
+    WritePromisorFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
     #[error(transparent)]
     ShallowOpen(#[from] crate::shallow::open::Error),
     #[error("Server lack feature {feature:?}: {description}")]
@@ -45,6 +50,15 @@ pub enum Error {
     RejectShallowRemote,
     #[error(transparent)]
     NegotiationAlgorithmConfig(#[from] config::key::GenericErrorWithValue),
+    #[cfg(feature = "bundle")]
+    #[error("Could not obtain configuration to learn if bundle URIs should be used")]
+    BundleUriConfig(#[source] config::boolean::Error),
+    #[cfg(feature = "bundle")]
+    #[error(transparent)]
+    BundleUri(#[from] gix_protocol::bundle_uri::Error),
+    #[cfg(feature = "bundle")]
+    #[error(transparent)]
+    UnbundleList(#[from] crate::bundle::list::Error),
 }
 
 impl gix_protocol::transport::IsSpuriousError for Error {
@@ -52,7 +66,59 @@ impl gix_protocol::transport::IsSpuriousError for Error {
         match self {
             Error::FetchResponse(err) => err.is_spurious(),
             Error::Client(err) => err.is_spurious(),
+            #[cfg(feature = "bundle")]
+            Error::BundleUri(err) => err.is_spurious(),
             _ => false,
         }
     }
 }
+
+impl crate::error::ErrorCode for Error {
+    fn code(&self) -> crate::error::Code {
+        use gix_protocol::fetch::response;
+
+        use crate::error::{self, Code};
+        match self {
+            Error::PackThreads(_)
+            | Error::PackIndexVersion(_)
+            | Error::RejectShallowRemoteConfig(_)
+            | Error::RejectShallowRemote
+            | Error::NegotiationAlgorithmConfig(_) => Code::Configuration,
+            Error::FetchResponse(err) => match err {
+                response::Error::Io(_) | response::Error::UploadPack(_) => Code::Network,
+                response::Error::Transport(err) => error::transport(err),
+                response::Error::MissingServerCapability { .. }
+                | response::Error::UnknownLineType { .. }
+                | response::Error::UnknownSectionHeader { .. } => Code::Protocol,
+            },
+            Error::IncompatibleObjectHash { .. } | Error::MissingServerFeature { .. } => Code::Protocol,
+            Error::Negotiate(err) => match err {
+                super::negotiate::Error::NegotiationFailed { .. } => Code::Protocol,
+                _ => Code::ObjectCorrupt,
+            },
+            Error::Client(err) => error::transport(err),
+            Error::WritePack(err) => error::write_pack(err),
+            Error::UpdateRefs(err) => match err {
+                super::refs::update::Error::EditReferences(err) => error::reference_edit(err),
+                super::refs::update::Error::InvalidRefName(_) => Code::Protocol,
+                super::refs::update::Error::WorktreeListing(err) => error::io(err),
+                _ => Code::ObjectCorrupt,
+            },
+            Error::RemovePackKeepFile { source, .. } | Error::WritePromisorFile { source, .. } => error::io(source),
+            Error::ShallowOpen(_) => Code::ObjectCorrupt,
+            Error::WriteShallowFile(_) => Code::Io,
+            Error::LockShallowFile(err) => error::lock_acquire(err),
+            #[cfg(feature = "bundle")]
+            Error::BundleUriConfig(_) => Code::Configuration,
+            #[cfg(feature = "bundle")]
+            Error::BundleUri(err) => match err {
+                gix_protocol::bundle_uri::Error::Io(_) => Code::Network,
+                gix_protocol::bundle_uri::Error::Transport(err) => error::transport(err),
+                gix_protocol::bundle_uri::Error::DecodePacketline(_)
+                | gix_protocol::bundle_uri::Error::ParseList(_) => Code::Protocol,
+            },
+            #[cfg(feature = "bundle")]
+            Error::UnbundleList(err) => error::ErrorCode::code(err),
+        }
+    }
+}
@@ -74,6 +74,10 @@ pub struct Outcome {
     pub ref_map: RefMap,
     /// The status of the operation to indicate what happened.
     pub status: Status,
+    /// The bundles that were downloaded and unbundled ahead of negotiation, if the server advertised a bundle list
+    /// via `bundle-uri` and `transfer.bundleURI` is enabled.
+    #[cfg(feature = "bundle")]
+    pub bundle_uri: Option<crate::bundle::list::Outcome>,
 }
 
 /// Additional types related to the outcome of a fetch operation.
@@ -108,6 +112,10 @@ pub mod outcome {
             /// If the value is lower than `haves_sent` (the `HAVE` lines actually sent), the negotiation algorithm has run out of options
             /// which typically indicates the end of the negotiation phase.
             pub haves_to_send: usize,
+            /// The amount of `HAVE` lines known to be in common with the remote which were repeated in this round.
+            ///
+            /// It's only non-zero for stateless transports, which don't remember what was sent in previous rounds.
+            pub common_haves_repeated: usize,
             /// If `true`, the server reported, as response to our previous `HAVE`s, that at least one of them is in common by acknowledging it.
             ///
             /// This may also lead to the server responding with a pack.
@@ -157,6 +165,15 @@ pub mod prepare {
             }
         }
     }
+
+    impl crate::error::ErrorCode for Error {
+        fn code(&self) -> crate::error::Code {
+            match self {
+                Error::MissingRefSpecs => crate::error::Code::Configuration,
+                Error::RefMap(err) => err.code(),
+            }
+        }
+    }
 }
 
 impl<'remote, 'repo, T> Connection<'remote, 'repo, T>
@@ -185,7 +202,7 @@ where
         if self.remote.refspecs(remote::Direction::Fetch).is_empty() {
             return Err(prepare::Error::MissingRefSpecs);
         }
-        let ref_map = self.ref_map_inner(progress, options).await?;
+        let (ref_map, _) = self.ref_map_inner(progress, options, ref_map::ReadCache::No).await?;
         Ok(Prepare {
             con: Some(self),
             ref_map,
@@ -193,6 +210,8 @@ where
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
             shallow: Default::default(),
+            refetch: false,
+            max_stateless_haves: gix_negotiate::MAX_STATELESS_HAVES,
         })
     }
 }
@@ -224,6 +243,8 @@ where
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
     shallow: remote::fetch::Shallow,
+    refetch: bool,
+    max_stateless_haves: usize,
 }
 
 /// Builder
@@ -265,6 +286,26 @@ where
         self.shallow = shallow;
         self
     }
+
+    /// If enabled, fetch all objects of the wanted refs as if this was a fresh clone, instead of negotiating with the remote
+    /// to only receive objects we don't have yet, similar to `git fetch --refetch`.
+    ///
+    /// This is useful to replace corrupted objects, or to apply a changed [partial clone filter](crate::Remote::partial_clone_filter())
+    /// so objects that were previously filtered out are received.
+    pub fn with_refetch(mut self, enabled: bool) -> Self {
+        self.refetch = enabled;
+        self
+    }
+
+    /// Limit the amount of `HAVE` lines sent in each request of stateless transports, like HTTP, to `max_haves`.
+    ///
+    /// As stateless transports have to repeat all `HAVE`s known to be in common with the remote in each request, this also
+    /// limits the amount of new `HAVE`s, with negotiation taking more rounds instead of sending oversized requests.
+    /// It defaults to [`MAX_STATELESS_HAVES`](gix_negotiate::MAX_STATELESS_HAVES).
+    pub fn with_max_stateless_haves(mut self, max_haves: usize) -> Self {
+        self.max_stateless_haves = max_haves;
+        self
+    }
 }
 
 impl<'remote, 'repo, T> Drop for Prepare<'remote, 'repo, T>
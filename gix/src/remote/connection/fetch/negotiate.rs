@@ -43,6 +43,23 @@ pub(crate) enum Action {
     },
 }
 
+/// Like [`mark_complete_and_common_ref()`], but pretend we don't have any object so all objects of the wanted refs are sent again,
+/// which is what `git fetch --refetch` does.
+pub(crate) fn refetch_all(ref_map: &fetch::RefMap, mapping_is_ignored: impl Fn(&fetch::Mapping) -> bool) -> Action {
+    if ref_map
+        .mappings
+        .iter()
+        .filter(|m| !mapping_is_ignored(m))
+        .all(|m| m.remote.as_id().is_none())
+    {
+        // Only unborn refs, there is nothing to want.
+        return Action::SkipToRefUpdate;
+    }
+    Action::MustNegotiate {
+        remote_ref_target_known: vec![false; ref_map.mappings.len()],
+    }
+}
+
 /// This function is modeled after the similarly named one in the git codebase to do the following:
 ///
 /// * figure out all advertised refs on the remote *that we already have* and keep track of the oldest one as cutoff date.
@@ -216,6 +233,7 @@ pub(crate) fn add_wants(
     ref_map: &fetch::RefMap,
     mapping_known: &[bool],
     shallow: &fetch::Shallow,
+    refetch: bool,
     mapping_is_ignored: impl Fn(&fetch::Mapping) -> bool,
 ) {
     // When using shallow, we can't exclude `wants` as the remote won't send anything then. Thus we have to resend everything
@@ -241,6 +259,7 @@ pub(crate) fn add_wants(
             )
         }
         let id_is_annotated_tag_we_have = id_on_remote
+            .filter(|_| !refetch)
             .and_then(|id| repo.objects.header(id).ok().map(|h| (id, h)))
             .filter(|(_, h)| h.kind() == gix_object::Kind::Tag)
             .map(|(id, _)| id);
@@ -326,15 +345,17 @@ fn mark_alternate_complete(
 /// Negotiate the nth `round` with `negotiator` sending `haves_to_send` after possibly making the known common commits
 /// as sent by the remote known to `negotiator` using `previous_response` if this isn't the first round.
 /// All `haves` are added to `arguments` accordingly.
-/// Returns the amount of haves actually sent.
+/// If `common` is set along with the maximum amount of haves per request, `haves_to_send` is reduced so that together
+/// with the repeated common commits, the request doesn't get larger than that.
+/// Returns the amount of haves actually sent, the amount of repeated common commits, and whether the remote acknowledged one of our haves.
 pub(crate) fn one_round(
     negotiator: &mut dyn gix_negotiate::Negotiator,
     graph: &mut gix_negotiate::Graph<'_>,
-    haves_to_send: usize,
+    haves_to_send: &mut usize,
     arguments: &mut gix_protocol::fetch::Arguments,
     previous_response: Option<&gix_protocol::fetch::Response>,
-    mut common: Option<&mut Vec<gix_hash::ObjectId>>,
-) -> Result<(usize, bool), Error> {
+    mut common: Option<(&mut Vec<gix_hash::ObjectId>, usize)>,
+) -> Result<(usize, usize, bool), Error> {
     let mut seen_ack = false;
     if let Some(response) = previous_response {
         use gix_protocol::fetch::response::Acknowledgement;
@@ -342,8 +363,9 @@ pub(crate) fn one_round(
             match ack {
                 Acknowledgement::Common(id) => {
                     seen_ack = true;
-                    negotiator.in_common_with_remote(*id, graph)?;
-                    if let Some(ref mut common) = common {
+                    let known_to_be_common = negotiator.in_common_with_remote(*id, graph)?;
+                    // The remote acknowledges commits again when we repeat them, but we only have to remember them once.
+                    if let Some((common, _)) = common.as_mut().filter(|_| !known_to_be_common) {
                         common.push(*id);
                     }
                 }
@@ -358,19 +380,22 @@ pub(crate) fn one_round(
 
     // `common` is set only if this is a stateless transport, and we repeat previously confirmed common commits as HAVE, because
     // we are not going to repeat them otherwise.
-    if let Some(common) = common {
-        for have_id in common {
+    let mut common_haves_repeated = 0;
+    if let Some((common, max_haves)) = common {
+        for have_id in common.iter() {
             arguments.have(have_id);
         }
+        common_haves_repeated = common.len();
+        *haves_to_send = gix_negotiate::stateless_window_size(*haves_to_send, common_haves_repeated, max_haves);
     }
 
     let mut haves_sent = 0;
-    for have_id in (0..haves_to_send).map_while(|_| negotiator.next_have(graph)) {
+    for have_id in (0..*haves_to_send).map_while(|_| negotiator.next_have(graph)) {
         arguments.have(have_id?);
         haves_sent += 1;
     }
     // Note that we are differing from the git implementation, which does an extra-round of with no new haves sent at all.
     // For us it seems better to just say we are done when we know we are done, as potentially additional acks won't affect the
     // queue of any of our implementation at all (so the negotiator won't come up with more haves next time either).
-    Ok((haves_sent, seen_ack))
+    Ok((haves_sent, common_haves_repeated, seen_ack))
 }
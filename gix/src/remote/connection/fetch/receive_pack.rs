@@ -116,6 +116,15 @@ where
             }
             arguments.use_include_tag();
         }
+        if let Some(filter) = con.remote.partial_clone_filter.as_ref() {
+            if !arguments.can_use_filter() {
+                return Err(Error::MissingServerFeature {
+                    feature: "filter",
+                    description: "Partial clones need the server to omit objects that don't pass the filter",
+                });
+            }
+            arguments.filter(crate::bstr::ByteSlice::to_str_lossy(filter.as_slice()).as_ref());
+        }
         let (shallow_commits, mut shallow_lock) = add_shallow_args(&mut arguments, &self.shallow, repo)?;
 
         if self.ref_map.object_hash != repo.object_hash() {
@@ -125,19 +134,29 @@ where
             });
         }
 
+        #[cfg(feature = "bundle")]
+        let bundle_uri = if matches!(self.dry_run, fetch::DryRun::No) {
+            unbundle_advertised_list(&mut con, handshake, progress, should_interrupt).await?
+        } else {
+            None
+        };
+
         let negotiate_span = gix_trace::detail!(
             "negotiate",
             protocol_version = self.ref_map.handshake.server_protocol_version as usize
         );
-        let mut negotiator = repo
-            .config
-            .resolved
-            .string_by_key(Fetch::NEGOTIATION_ALGORITHM.logical_name().as_str())
-            .map(|n| Fetch::NEGOTIATION_ALGORITHM.try_into_negotiation_algorithm(n))
-            .transpose()
-            .with_leniency(repo.config.lenient_config)?
-            .unwrap_or(Algorithm::Consecutive)
-            .into_negotiator();
+        let mut negotiator = if self.refetch {
+            Algorithm::Noop
+        } else {
+            repo.config
+                .resolved
+                .string_by_key(Fetch::NEGOTIATION_ALGORITHM.logical_name().as_str())
+                .map(|n| Fetch::NEGOTIATION_ALGORITHM.try_into_negotiation_algorithm(n))
+                .transpose()
+                .with_leniency(repo.config.lenient_config)?
+                .unwrap_or(Algorithm::Consecutive)
+        }
+        .into_negotiator();
         let graph_repo = {
             let mut r = repo.clone();
             // assure that checking for unknown server refs doesn't trigger ODB refreshes.
@@ -147,14 +166,21 @@ where
             r
         };
         let mut graph = graph_repo.revision_graph();
-        let action = negotiate::mark_complete_and_common_ref(
-            &graph_repo,
-            negotiator.deref_mut(),
-            &mut graph,
-            &self.ref_map,
-            &self.shallow,
-            negotiate::make_refmapping_ignore_predicate(con.remote.fetch_tags, &self.ref_map),
-        )?;
+        let action = if self.refetch {
+            negotiate::refetch_all(
+                &self.ref_map,
+                negotiate::make_refmapping_ignore_predicate(con.remote.fetch_tags, &self.ref_map),
+            )
+        } else {
+            negotiate::mark_complete_and_common_ref(
+                &graph_repo,
+                negotiator.deref_mut(),
+                &mut graph,
+                &self.ref_map,
+                &self.shallow,
+                negotiate::make_refmapping_ignore_predicate(con.remote.fetch_tags, &self.ref_map),
+            )?
+        };
         let mut previous_response = None::<gix_protocol::fetch::Response>;
         let (mut write_pack_bundle, negotiate) = match &action {
             negotiate::Action::NoChange | negotiate::Action::SkipToRefUpdate => {
@@ -172,6 +198,7 @@ where
                     &self.ref_map,
                     remote_ref_target_known,
                     &self.shallow,
+                    self.refetch,
                     negotiate::make_refmapping_ignore_predicate(con.remote.fetch_tags, &self.ref_map),
                 );
                 let mut rounds = Vec::new();
@@ -189,12 +216,12 @@ where
                     let is_done = match negotiate::one_round(
                         negotiator.deref_mut(),
                         &mut graph,
-                        haves_to_send,
+                        &mut haves_to_send,
                         &mut arguments,
                         previous_response.as_ref(),
-                        common.as_mut(),
+                        common.as_mut().map(|common| (common, self.max_stateless_haves)),
                     ) {
-                        Ok((haves_sent, ack_seen)) => {
+                        Ok((haves_sent, common_haves_repeated, ack_seen)) => {
                             if ack_seen {
                                 in_vain = 0;
                             }
@@ -204,6 +231,7 @@ where
                                 haves_sent,
                                 in_vain,
                                 haves_to_send,
+                                common_haves_repeated,
                                 previous_response_had_at_least_one_in_common: ack_seen,
                             });
                             let is_done = haves_sent != haves_to_send || (seen_ack && in_vain >= 256);
@@ -263,6 +291,7 @@ where
                     index_version: config::pack_index_version(repo)?,
                     iteration_mode: gix_pack::data::input::Mode::Verify,
                     object_hash: con.remote.repo.object_hash(),
+                    ..Default::default()
                 };
 
                 let write_pack_bundle = if matches!(self.dry_run, fetch::DryRun::No) {
@@ -298,6 +327,13 @@ where
                     {
                         reader = rd;
                     }
+                    if con.remote.partial_clone_filter.is_some() {
+                        // Mark the pack as coming from a promisor remote, so objects it refers to but doesn't contain
+                        // are known to be available from there.
+                        if let Some(path) = res.data_path.as_ref().map(|path| path.with_extension("promisor")) {
+                            std::fs::write(&path, b"").map_err(|err| Error::WritePromisorFile { path, source: err })?;
+                        }
+                    }
                     Some(res)
                 } else {
                     None
@@ -312,7 +348,12 @@ where
 
                 if let Some(shallow_lock) = shallow_lock {
                     if !previous_response.shallow_updates().is_empty() {
-                        crate::shallow::write(shallow_lock, shallow_commits, previous_response.shallow_updates())?;
+                        crate::shallow::write(
+                            shallow_lock,
+                            shallow_commits,
+                            previous_response.shallow_updates(),
+                            &repo.objects,
+                        )?;
                     }
                 }
                 (write_pack_bundle, Some(outcome::Negotiate { graph, rounds }))
@@ -354,11 +395,81 @@ where
                     update_refs,
                 },
             },
+            #[cfg(feature = "bundle")]
+            bundle_uri,
         };
         Ok(out)
     }
 }
 
+/// If enabled by `transfer.bundleURI` and supported by the server, obtain its bundle list and unbundle what's needed
+/// into the repository, so the following negotiation can take advantage of the newly received objects.
+#[cfg(feature = "bundle")]
+#[gix_protocol::maybe_async::maybe_async]
+async fn unbundle_advertised_list<T: Transport>(
+    con: &mut remote::Connection<'_, '_, T>,
+    handshake: &gix_protocol::handshake::Outcome,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<Option<crate::bundle::list::Outcome>, Error> {
+    use crate::{bundle::list, config::tree::Transfer};
+
+    let repo = con.remote.repo;
+    let use_bundle_uri = repo
+        .config
+        .resolved
+        .boolean_filter_by_key(
+            Transfer::BUNDLE_URI.logical_name().as_str(),
+            &mut repo.filter_config_section(),
+        )
+        .map(|val| Transfer::BUNDLE_URI.enrich_error(val))
+        .transpose()
+        .map_err(Error::BundleUriConfig)?
+        .unwrap_or(false);
+    if !use_bundle_uri
+        || handshake.server_protocol_version != gix_protocol::transport::Protocol::V2
+        || !handshake.capabilities.contains("bundle-uri")
+    {
+        return Ok(None);
+    }
+    let Some(base_url) = con.remote.url(remote::Direction::Fetch).cloned() else {
+        return Ok(None);
+    };
+
+    let bundle_list = match gix_protocol::bundle_uri(
+        &mut con.transport,
+        &handshake.capabilities,
+        vec![repo.config.user_agent_tuple()],
+        con.trace,
+    )
+    .await
+    {
+        Ok(Some(bundle_list)) => bundle_list,
+        Ok(None) => return Ok(None),
+        Err(gix_protocol::bundle_uri::Error::ParseList(err)) => {
+            // The list was read entirely, so the connection remains usable and we can fetch as if there was none.
+            progress.fail(format!("Ignoring the bundle list advertised by the remote: {err}"));
+            return Ok(None);
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let options = list::Options {
+        refspecs: vec![
+            gix_refspec::parse(list::REFSPEC.into(), gix_refspec::parse::Operation::Fetch)
+                .expect("valid static refspec")
+                .to_owned(),
+        ],
+        filter: con.remote.partial_clone_filter.clone(),
+    };
+    Ok(Some(repo.unbundle_list(
+        &bundle_list,
+        &base_url,
+        progress,
+        should_interrupt,
+        options,
+    )?))
+}
+
 fn acquire_shallow_lock(repo: &Repository) -> Result<gix_lock::File, Error> {
     gix_lock::File::acquire_to_update_resource(repo.shallow_file(), gix_lock::acquire::Fail::Immediately, None)
         .map_err(Into::into)
@@ -22,6 +22,8 @@ pub struct Connection<'a, 'repo, T> {
 
 mod access;
 
+mod advertisement_cache;
+
 ///
 pub mod ref_map;
 
@@ -6,7 +6,12 @@ use gix_protocol::transport::client::Transport;
 use crate::{
     bstr,
     bstr::{BString, ByteVec},
-    remote::{connection::HandshakeWithRefs, fetch, fetch::SpecIndex, Connection, Direction},
+    remote::{
+        connection::{advertisement_cache, HandshakeWithRefs},
+        fetch,
+        fetch::SpecIndex,
+        Connection, Direction,
+    },
 };
 
 /// The error returned by [`Connection::ref_map()`].
@@ -45,6 +50,26 @@ impl gix_protocol::transport::IsSpuriousError for Error {
     }
 }
 
+impl crate::error::ErrorCode for Error {
+    fn code(&self) -> crate::error::Code {
+        use crate::error::{self, Code};
+        match self {
+            Error::GatherTransportConfig { .. } | Error::ConfigureTransport(_) | Error::ConfigureCredentials(_) => {
+                Code::Configuration
+            }
+            Error::Handshake(err) => error::handshake(err),
+            Error::UnknownObjectFormat { .. } => Code::Protocol,
+            Error::ListRefs(err) => match err {
+                gix_protocol::ls_refs::Error::Io(_) => Code::Network,
+                gix_protocol::ls_refs::Error::Transport(err) => error::transport(err),
+                gix_protocol::ls_refs::Error::Parse(_) => Code::Protocol,
+            },
+            Error::Transport(err) => error::transport(err),
+            Error::MappingValidation(_) => Code::InvalidInput,
+        }
+    }
+}
+
 /// For use in [`Connection::ref_map()`].
 #[derive(Debug, Clone)]
 pub struct Options {
@@ -59,6 +84,14 @@ pub struct Options {
     ///
     /// This is useful for handling `remote.<name>.tagOpt` for example.
     pub extra_refspecs: Vec<gix_refspec::RefSpec>,
+    /// If set, the refs and capabilities advertised by the remote are cached on disk, and [`Connection::ref_map()`] will
+    /// use cached advertisements that are younger than the given duration instead of contacting the remote.
+    ///
+    /// This is useful to avoid overloading remotes with requests from short-lived processes that are repeatedly
+    /// querying the state of the same remote, like prompt integrations. Note that cached advertisements can't reflect
+    /// changes on the remote, which is why [`Connection::prepare_fetch()`] always contacts the remote, but updates
+    /// the cache if set. Defaults to `None`.
+    pub cache_ttl: Option<std::time::Duration>,
 }
 
 impl Default for Options {
@@ -67,6 +100,7 @@ impl Default for Options {
             prefix_from_spec_as_filter_on_remote: true,
             handshake_parameters: Vec::new(),
             extra_refspecs: Vec::new(),
+            cache_ttl: None,
         }
     }
 }
@@ -94,11 +128,13 @@ where
     #[allow(clippy::result_large_err)]
     #[gix_protocol::maybe_async::maybe_async]
     pub async fn ref_map(mut self, progress: impl Progress, options: Options) -> Result<fetch::RefMap, Error> {
-        let res = self.ref_map_inner(progress, options).await;
-        gix_protocol::indicate_end_of_interaction(&mut self.transport, self.trace)
-            .await
-            .ok();
-        res
+        let res = self.ref_map_inner(progress, options, ReadCache::Yes).await;
+        if !matches!(res, Ok((_, Source::Cache))) {
+            gix_protocol::indicate_end_of_interaction(&mut self.transport, self.trace)
+                .await
+                .ok();
+        }
+        res.map(|(ref_map, _)| ref_map)
     }
 
     #[allow(clippy::result_large_err)]
@@ -110,8 +146,10 @@ where
             prefix_from_spec_as_filter_on_remote,
             handshake_parameters,
             mut extra_refspecs,
+            cache_ttl,
         }: Options,
-    ) -> Result<fetch::RefMap, Error> {
+        read_cache: ReadCache,
+    ) -> Result<(fetch::RefMap, Source), Error> {
         let _span = gix_trace::coarse!("remote::Connection::ref_map()");
         let null = gix_hash::ObjectId::null(gix_hash::Kind::Sha1); // OK to hardcode Sha1, it's not supposed to match, ever.
 
@@ -125,14 +163,36 @@ where
             s.extend(extra_refspecs.clone());
             s
         };
-        let remote = self
-            .fetch_refs(
-                prefix_from_spec_as_filter_on_remote,
-                handshake_parameters,
-                &specs,
-                progress,
-            )
-            .await?;
+        let prefixes = prefix_from_spec_as_filter_on_remote.then(|| ref_prefixes(&specs));
+        let cache = cache_ttl.map(|ttl| {
+            let repo = self.remote.repo;
+            let desired_version = crate::config::tree::Protocol::VERSION
+                .try_into_protocol_version(repo.config.resolved.integer("protocol", None, "version"))
+                .unwrap_or_default();
+            let entry = advertisement_cache::Entry::new(
+                repo,
+                self.transport.to_url().as_ref(),
+                desired_version,
+                prefixes.as_deref(),
+                &handshake_parameters,
+            );
+            (entry, ttl)
+        });
+        let (remote, source) = match cache
+            .as_ref()
+            .filter(|_| read_cache == ReadCache::Yes)
+            .and_then(|(entry, ttl)| entry.read(*ttl))
+        {
+            Some(remote) => (remote, Source::Cache),
+            None => {
+                let remote = self.fetch_refs(prefixes, handshake_parameters, progress).await?;
+                if let Some((entry, _)) = &cache {
+                    // The cache is an optimization, so failing to write it must not fail the operation.
+                    entry.write(&remote).ok();
+                }
+                (remote, Source::Remote)
+            }
+        };
         let num_explicit_specs = self.remote.fetch_specs.len();
         let group = gix_refspec::MatchGroup::from_fetch_specs(specs.iter().map(gix_refspec::RefSpec::to_ref));
         let (res, fixes) = group
@@ -168,23 +228,25 @@ where
             .collect();
 
         let object_hash = extract_object_format(self.remote.repo, &remote.outcome)?;
-        Ok(fetch::RefMap {
-            mappings,
-            extra_refspecs,
-            fixes,
-            remote_refs: remote.refs,
-            handshake: remote.outcome,
-            object_hash,
-        })
+        Ok((
+            fetch::RefMap {
+                mappings,
+                extra_refspecs,
+                fixes,
+                remote_refs: remote.refs,
+                handshake: remote.outcome,
+                object_hash,
+            },
+            source,
+        ))
     }
 
     #[allow(clippy::result_large_err)]
     #[gix_protocol::maybe_async::maybe_async]
     async fn fetch_refs(
         &mut self,
-        filter_by_prefix: bool,
+        prefixes: Option<Vec<BString>>,
         extra_parameters: Vec<(String, Option<String>)>,
-        refspecs: &[gix_refspec::RefSpec],
         mut progress: impl Progress,
     ) -> Result<HandshakeWithRefs, Error> {
         let _span = gix_trace::coarse!("remote::Connection::fetch_refs()");
@@ -226,19 +288,10 @@ where
                     &outcome.capabilities,
                     move |_capabilities, arguments, features| {
                         features.push(agent_feature);
-                        if filter_by_prefix {
-                            let mut seen = HashSet::new();
-                            for spec in refspecs {
-                                let spec = spec.to_ref();
-                                if seen.insert(spec.instruction()) {
-                                    let mut prefixes = Vec::with_capacity(1);
-                                    spec.expand_prefixes(&mut prefixes);
-                                    for mut prefix in prefixes {
-                                        prefix.insert_str(0, "ref-prefix ");
-                                        arguments.push(prefix);
-                                    }
-                                }
-                            }
+                        for prefix in prefixes.into_iter().flatten() {
+                            let mut argument = BString::from("ref-prefix ");
+                            argument.push_str(prefix);
+                            arguments.push(argument);
                         }
                         Ok(gix_protocol::ls_refs::Action::Continue)
                     },
@@ -252,6 +305,37 @@ where
     }
 }
 
+/// Whether or not to use cached advertisements of the remote in [`Connection::ref_map_inner()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum ReadCache {
+    /// Use a cached advertisement if it's available and young enough.
+    Yes,
+    /// Always contact the remote, but update the cache with the advertisement if caching is enabled.
+    No,
+}
+
+/// Where the refs of the remote were obtained from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Source {
+    /// The remote was contacted.
+    Remote,
+    /// A cached advertisement was used, without contacting the remote.
+    Cache,
+}
+
+/// Return the prefixes of all refs that can be matched by `refspecs`, to let the server pre-filter the refs it sends.
+fn ref_prefixes(refspecs: &[gix_refspec::RefSpec]) -> Vec<BString> {
+    let mut seen = HashSet::new();
+    let mut prefixes = Vec::new();
+    for spec in refspecs {
+        let spec = spec.to_ref();
+        if seen.insert(spec.instruction()) {
+            spec.expand_prefixes(&mut prefixes);
+        }
+    }
+    prefixes
+}
+
 /// Assume sha1 if server says nothing, otherwise configure anything beyond sha1 in the local repo configuration
 #[allow(clippy::result_large_err)]
 fn extract_object_format(
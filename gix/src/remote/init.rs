@@ -36,6 +36,7 @@ impl<'repo> Remote<'repo> {
         push_specs: Vec<RefSpec>,
         should_rewrite_urls: bool,
         fetch_tags: remote::fetch::Tags,
+        partial_clone_filter: Option<BString>,
         repo: &'repo Repository,
     ) -> Result<Self, Error> {
         debug_assert!(
@@ -54,6 +55,7 @@ impl<'repo> Remote<'repo> {
             fetch_specs,
             push_specs,
             fetch_tags,
+            partial_clone_filter,
             repo,
         })
     }
@@ -91,6 +93,7 @@ impl<'repo> Remote<'repo> {
             fetch_specs: Vec::new(),
             push_specs: Vec::new(),
             fetch_tags: Default::default(),
+            partial_clone_filter: None,
             repo,
         })
     }
@@ -58,6 +58,8 @@ impl Remote<'_> {
                 config::tree::Remote::FETCH.name,
                 config::tree::Remote::PUSH.name,
                 config::tree::Remote::TAG_OPT.name,
+                config::tree::Remote::PROMISOR.name,
+                config::tree::Remote::PARTIAL_CLONE_FILTER.name,
             ];
             for id in section_ids {
                 let mut section = config.section_mut_by_id(id).expect("just queried");
@@ -96,6 +98,13 @@ impl Remote<'_> {
                 .into(),
             );
         }
+        if let Some(filter) = self.partial_clone_filter.as_ref() {
+            section.push(as_key(config::tree::Remote::PROMISOR.name), Some("true".into()));
+            section.push(
+                as_key(config::tree::Remote::PARTIAL_CLONE_FILTER.name),
+                Some(filter.as_ref()),
+            );
+        }
         for (key, spec) in self
             .fetch_specs
             .iter()
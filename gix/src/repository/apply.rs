@@ -0,0 +1,447 @@
+use std::{collections::BTreeMap, path::Path};
+
+use gix_hash::ObjectId;
+use gix_index::entry::{Flags, Mode, Stat};
+use gix_object::tree::EntryKind;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+
+use crate::{
+    apply::{am, options, Error, Location, Mail, Options, Outcome, Patch},
+    bstr::{BStr, BString, ByteSlice},
+    merge,
+    merge::tree::{Conflict, ConflictKind, Version},
+    Id, Repository,
+};
+
+/// The content and mode of a file as seen while applying a patch.
+type Blob = (Vec<u8>, Mode);
+
+/// The new state of each path changed by a patch, which is `None` if the file was removed.
+type Files = BTreeMap<BString, Option<Blob>>;
+
+/// A file that was merged with conflicts when applying a patch with the three-way fallback.
+struct Merged {
+    path: BString,
+    base: Version,
+    ours: Blob,
+    theirs: Blob,
+}
+
+/// Applying patches
+impl Repository {
+    /// Apply all changes of `patch` to the location configured in `options`, similar to `git apply`.
+    ///
+    /// Either all files are changed, or none of them if the patch doesn't apply, unless [`Options::three_way`] is enabled,
+    /// in which case hunks that don't apply are merged and conflicts are returned in the [`Outcome`].
+    /// Use [`apply_options()`](Self::apply_options()) to obtain options as configured in `apply.*`.
+    pub fn apply_patch(&self, patch: &Patch, options: Options) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::apply_patch()");
+        let location = match options.location {
+            Location::Worktree if options.three_way => Location::IndexAndWorktree,
+            location => location,
+        };
+        if location == Location::Worktree {
+            let workdir = self.work_dir().ok_or(Error::BareRepository)?;
+            let (files, _) = self.apply_to_files(patch, &options, false, |path| read_worktree_file(workdir, path))?;
+            for (path, blob) in files {
+                write_worktree_file(workdir, path.as_ref(), blob)?;
+            }
+            return Ok(Outcome::default());
+        }
+
+        let index = self.open_index()?;
+        if location == Location::IndexAndWorktree {
+            let workdir = self.work_dir().ok_or(Error::BareRepository)?;
+            let dirty: Vec<_> = merge::worktree::worktree_changes(self, &index)?
+                .into_iter()
+                .filter(|(_, status)| !matches!(status, gix_status::index_as_worktree::EntryStatus::NeedsUpdate(_)))
+                .map(|(path, _)| path)
+                .collect();
+            for file in &patch.files {
+                if let Some(path) = file.old_path.iter().chain(&file.new_path).find(|p| dirty.contains(p)) {
+                    return Err(Error::Dirty { path: path.clone() });
+                }
+                if let Some(path) = file
+                    .new_path
+                    .as_ref()
+                    .filter(|_| file.is_addition() || file.is_rename_or_copy())
+                {
+                    if workdir
+                        .join(gix_path::from_bstr(path.as_bstr()))
+                        .symlink_metadata()
+                        .is_ok()
+                    {
+                        return Err(Error::AlreadyExists { path: path.clone() });
+                    }
+                }
+            }
+        }
+
+        let (state, conflicts) = self.apply_to_state(&index, patch, &options)?;
+        if location == Location::Index {
+            gix_index::File::from_state(state, self.index_path()).write(Default::default())?;
+        } else {
+            merge::worktree::checkout(self, &index, state, &conflicts, false)?;
+        }
+        Ok(Outcome { conflicts })
+    }
+
+    /// Apply all changes of `patch` to `tree`, write the resulting tree into the object database and return its id.
+    ///
+    /// [`Options::location`] is ignored, and it's an error if [`Options::three_way`] is enabled and there are conflicts.
+    pub fn apply_patch_to_tree(
+        &self,
+        tree: impl Into<ObjectId>,
+        patch: &Patch,
+        options: Options,
+    ) -> Result<ObjectId, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::apply_patch_to_tree()");
+        let tree = tree.into();
+        let index = gix_index::State::from_tree(&tree, &self.objects)
+            .map_err(|err| Error::IndexFromTree { id: tree, source: err })?;
+        let (state, conflicts) = self.apply_to_state(&index, patch, &options)?;
+        if !conflicts.is_empty() {
+            return Err(Error::Conflicts {
+                paths: conflicts.into_iter().map(|c| c.path).collect(),
+            });
+        }
+        Ok(state.write_tree(|tree| self.write_object(tree).map(Id::detach))?)
+    }
+
+    /// Return the options to apply patches with as configured in `apply.whitespace` and `apply.ignoreWhitespace`.
+    pub fn apply_options(&self) -> Result<Options, options::Error> {
+        use crate::config::{cache::util::ApplyLeniency, tree::Apply};
+
+        let config = &self.config.resolved;
+        let lenient = self.config.lenient_config;
+        let whitespace = config
+            .string_by_key("apply.whitespace")
+            .map(|value| Apply::WHITESPACE.try_into_whitespace(value))
+            .transpose()
+            .with_leniency(lenient)?
+            .unwrap_or_default();
+        let ignore_whitespace_changes = config
+            .string_by_key("apply.ignoreWhitespace")
+            .map(|value| Apply::IGNORE_WHITESPACE.try_into_ignore_whitespace(value))
+            .transpose()
+            .with_leniency(lenient)?
+            .unwrap_or_default();
+        Ok(Options {
+            blob: crate::apply::blob::Options {
+                whitespace,
+                ignore_whitespace_changes,
+            },
+            ..Default::default()
+        })
+    }
+
+    /// Apply the patch in `mail` to the index and the worktree and commit the result on top of `HEAD` with the author,
+    /// date and message of `mail`, similar to `git am` for a single mail.
+    ///
+    /// The index and the worktree must not have changes. [`Options::location`] is ignored, and if [`Options::three_way`]
+    /// is enabled and there are conflicts, they are left in the index and the worktree without creating a commit.
+    /// Use [`split_mbox()`](crate::apply::mail::split_mbox()) and [`Mail::from_bytes()`] to obtain mails.
+    pub fn am(&self, mail: &Mail, options: Options) -> Result<am::Outcome, am::Error> {
+        let _span = gix_trace::coarse!("gix::Repository::am()");
+        if let Some(state) = self.state() {
+            return Err(am::Error::InProgress { state });
+        }
+        let patch = Patch::from_bytes(mail.patch.as_ref())?;
+        let head_id = self.head_id()?.detach();
+        let head_tree = self.find_object(head_id)?.try_into_commit()?.tree_id()?.detach();
+        let index = self.open_index()?;
+        merge::worktree::require_clean(self, &index, Some(&head_tree))?;
+        drop(index);
+
+        let outcome = self.apply_patch(
+            &patch,
+            Options {
+                location: Location::IndexAndWorktree,
+                ..options
+            },
+        )?;
+        if !outcome.conflicts.is_empty() {
+            return Ok(am::Outcome {
+                commit: None,
+                conflicts: outcome.conflicts,
+            });
+        }
+
+        let tree = self
+            .open_index()?
+            .write_tree(|tree| self.write_object(tree).map(Id::detach))?;
+        if tree == head_tree {
+            return Err(am::Error::Empty);
+        }
+        let author = gix_actor::Signature {
+            name: mail.author_name.clone(),
+            email: mail.author_email.clone(),
+            time: mail.date.unwrap_or_else(gix_date::Time::now_local_or_utc),
+        };
+        let committer = self.committer().ok_or(am::Error::CommitterMissing)??.to_owned();
+        let commit = gix_object::Commit {
+            tree,
+            parents: [head_id].into(),
+            author,
+            committer,
+            encoding: None,
+            message: mail.message.clone(),
+            extra_headers: Default::default(),
+        };
+        let new_id = self.write_object(&commit)?.detach();
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: crate::reference::log::message("am", commit.message.as_ref(), 1),
+                },
+                expected: PreviousValue::MustExistAndMatch(Target::Peeled(head_id)),
+                new: Target::Peeled(new_id),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: true,
+        })?;
+        Ok(am::Outcome {
+            commit: Some(new_id),
+            conflicts: Vec::new(),
+        })
+    }
+}
+
+/// Utilities
+impl Repository {
+    /// Apply `patch` to the stage-0 entries of `index` and return the changed index along with all conflicts.
+    fn apply_to_state(
+        &self,
+        index: &gix_index::State,
+        patch: &Patch,
+        options: &Options,
+    ) -> Result<(gix_index::State, Vec<Conflict>), Error> {
+        let (files, merged) = self.apply_to_files(patch, options, options.three_way, |path| {
+            Ok(match index.entry_by_path_and_stage(path, 0) {
+                Some(entry) => Some((self.find_object(entry.id)?.detach().data, entry.mode)),
+                None => None,
+            })
+        })?;
+
+        let mut state = gix_index::State::new(self.object_hash());
+        for entry in index.entries() {
+            let path = entry.path(index);
+            if !files.contains_key(path) {
+                state.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, path);
+            }
+        }
+        let mut conflicts = Vec::new();
+        for (path, blob) in files {
+            let Some((data, mode)) = blob else { continue };
+            let id = self.write_blob(data)?.detach();
+            match merged.iter().find(|m| m.path == path) {
+                Some(merged) => {
+                    let ours = Version {
+                        mode: merged.ours.1,
+                        id: self.write_blob(&merged.ours.0)?.detach(),
+                    };
+                    let theirs = Version {
+                        mode: merged.theirs.1,
+                        id: self.write_blob(&merged.theirs.0)?.detach(),
+                    };
+                    for (stage, version) in [(1, merged.base), (2, ours), (3, theirs)] {
+                        state.dangerously_push_entry(
+                            Stat::default(),
+                            version.id,
+                            Flags::from_bits_retain(stage << 12),
+                            version.mode,
+                            path.as_ref(),
+                        );
+                    }
+                    conflicts.push(Conflict {
+                        path,
+                        kind: ConflictKind::BothModified,
+                        base: Some(merged.base),
+                        ours: Some(ours),
+                        theirs: Some(theirs),
+                        merged_blob: Some(id),
+                        original_path: None,
+                    });
+                }
+                None => state.dangerously_push_entry(Stat::default(), id, Flags::empty(), mode, path.as_ref()),
+            }
+        }
+        state.sort_entries();
+        Ok((state, conflicts))
+    }
+
+    /// Apply all files of `patch` to the files obtained by `read`, and return the new state of each changed path along with
+    /// the files that were merged with conflicts if `three_way` is set.
+    fn apply_to_files(
+        &self,
+        patch: &Patch,
+        options: &Options,
+        three_way: bool,
+        mut read: impl FnMut(&BStr) -> Result<Option<Blob>, Error>,
+    ) -> Result<(Files, Vec<Merged>), Error> {
+        fn current(
+            files: &Files,
+            read: &mut impl FnMut(&BStr) -> Result<Option<Blob>, Error>,
+            path: &BStr,
+        ) -> Result<Option<Blob>, Error> {
+            match files.get(path) {
+                Some(blob) => Ok(blob.clone()),
+                None => read(path),
+            }
+        }
+
+        let mut files = BTreeMap::new();
+        let mut merged = Vec::new();
+        for file in &patch.files {
+            let path = file.path().to_owned();
+            let (preimage, mode) = match &file.old_path {
+                Some(old_path) => current(&files, &mut read, old_path.as_ref())?
+                    .ok_or_else(|| Error::Missing { path: old_path.clone() })?,
+                None => (Vec::new(), Mode::FILE),
+            };
+            if (file.is_addition() || file.is_rename_or_copy()) && current(&files, &mut read, path.as_ref())?.is_some()
+            {
+                return Err(Error::AlreadyExists { path });
+            }
+
+            let new_mode = file.new_mode.map_or(mode, index_mode);
+            let mut out = Vec::new();
+            match crate::apply::blob(&mut out, &preimage, file, options.blob) {
+                Ok(()) => {}
+                Err(crate::apply::blob::Error::Hunk { .. })
+                    if three_way && !file.is_deletion() && file.old_id.is_some() =>
+                {
+                    let prefix = file.old_id.expect("checked");
+                    let base = match self.objects.lookup_prefix(prefix, None)? {
+                        Some(Ok(id)) => id,
+                        Some(Err(())) | None => return Err(Error::MissingBase { path, id: prefix }),
+                    };
+                    let base_data = self.find_object(base)?.detach().data;
+                    let mut theirs = Vec::new();
+                    crate::apply::blob(&mut theirs, &base_data, file, options.blob).map_err(|err| Error::Apply {
+                        path: path.clone(),
+                        source: err,
+                    })?;
+                    let resolution = merge::blob(
+                        &mut out,
+                        &base_data,
+                        &preimage,
+                        &theirs,
+                        merge::blob::Labels {
+                            ancestor: None,
+                            current: Some("ours".into()),
+                            other: Some("theirs".into()),
+                        },
+                        options.merge,
+                    );
+                    if resolution == merge::blob::Resolution::Conflict {
+                        merged.push(Merged {
+                            path: path.clone(),
+                            base: Version { mode, id: base },
+                            ours: (preimage, mode),
+                            theirs: (theirs, new_mode),
+                        });
+                    }
+                }
+                Err(err) => return Err(Error::Apply { path, source: err }),
+            }
+
+            match (&file.old_path, &file.new_path) {
+                (Some(old_path), Some(_)) if file.is_rename_or_copy() && !file.is_copy => {
+                    files.insert(old_path.clone(), None);
+                }
+                _ => {}
+            }
+            files.insert(path, (!file.is_deletion()).then_some((out, new_mode)));
+        }
+        Ok((files, merged))
+    }
+}
+
+fn index_mode(mode: gix_object::tree::EntryMode) -> Mode {
+    match mode.kind() {
+        EntryKind::BlobExecutable => Mode::FILE_EXECUTABLE,
+        EntryKind::Link => Mode::SYMLINK,
+        EntryKind::Commit => Mode::COMMIT,
+        EntryKind::Blob | EntryKind::Tree => Mode::FILE,
+    }
+}
+
+fn read_worktree_file(workdir: &Path, rela_path: &BStr) -> Result<Option<Blob>, Error> {
+    let path = workdir.join(gix_path::from_bstr(rela_path));
+    let io_err = |err| Error::Io {
+        path: path.clone(),
+        source: err,
+    };
+    let metadata = match path.symlink_metadata() {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(io_err(err)),
+    };
+    Ok(if metadata.is_symlink() {
+        let target = std::fs::read_link(&path).map_err(io_err)?;
+        Some((gix_path::into_bstr(target).into_owned().into(), Mode::SYMLINK))
+    } else if metadata.is_dir() {
+        None
+    } else {
+        let mode = if gix_fs::is_executable(&metadata) {
+            Mode::FILE_EXECUTABLE
+        } else {
+            Mode::FILE
+        };
+        Some((std::fs::read(&path).map_err(io_err)?, mode))
+    })
+}
+
+fn write_worktree_file(workdir: &Path, rela_path: &BStr, blob: Option<Blob>) -> Result<(), Error> {
+    let Some((data, mode)) = blob else {
+        merge::worktree::remove_file(workdir, rela_path)?;
+        return Ok(());
+    };
+    let path = workdir.join(gix_path::from_bstr(rela_path));
+    let io_err = |err| Error::Io {
+        path: path.clone(),
+        source: err,
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    let is_symlink = path.symlink_metadata().map_or(false, |m| m.is_symlink());
+    if is_symlink || (mode == Mode::SYMLINK && path.exists()) {
+        std::fs::remove_file(&path).map_err(io_err)?;
+    }
+    if mode == Mode::SYMLINK {
+        gix_fs::symlink::create(&gix_path::from_bstr(data.as_bstr()), &path).map_err(io_err)
+    } else {
+        std::fs::write(&path, data).map_err(io_err)?;
+        set_executable(&path, mode == Mode::FILE_EXECUTABLE).map_err(io_err)
+    }
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path, executable: bool) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = std::fs::metadata(path)?.permissions();
+    let mode = permissions.mode();
+    let new_mode = if executable {
+        mode | ((mode & 0o444) >> 2)
+    } else {
+        mode & !0o111
+    };
+    if new_mode != mode {
+        permissions.set_mode(new_mode);
+        std::fs::set_permissions(path, permissions)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path, _executable: bool) -> std::io::Result<()> {
+    Ok(())
+}
@@ -60,6 +60,16 @@ impl Repository {
         ))
     }
 
+    /// Like [attributes_only()][Self::attributes_only()], but read `.gitattributes` files only from the tree of the commit
+    /// `HEAD` points to, as `git` does in bare repositories, or use no in-tree attributes at all if `HEAD` is unborn.
+    ///
+    /// Global attributes, like `$GIT_DIR/info/attributes` and `core.attributesFile`, are used as well.
+    #[cfg(feature = "attributes")]
+    pub fn attributes_from_head(&self) -> Result<AttributeStack<'_>, crate::repository::attributes_from_head::Error> {
+        let index = self.index_from_head()?;
+        Ok(self.attributes_only(&index, gix_worktree::stack::state::attributes::Source::IdMapping)?)
+    }
+
     /// Like [attributes()][Self::attributes()], but without access to exclude/ignore information.
     #[cfg(feature = "attributes")]
     pub fn attributes_only(
@@ -0,0 +1,55 @@
+use std::ops::Range;
+
+use gix_hash::ObjectId;
+
+use crate::bstr::BStr;
+
+impl crate::Repository {
+    /// Return default options for use with [`blame_file()`](Self::blame_file()) and related methods, which use the
+    /// algorithm configured in `diff.algorithm`.
+    pub fn blame_options(&self) -> Result<gix_blame::file::Options, crate::config::diff::algorithm::Error> {
+        Ok(gix_blame::file::Options {
+            algorithm: self.config.diff_algorithm()?,
+            ..Default::default()
+        })
+    }
+
+    /// Find the commit that introduced each line of the file at `file_path` as it is in the commit `suspect`,
+    /// see [`gix_blame::file()`] for details.
+    ///
+    /// Note that `file_path` is relative to the root of the tree of `suspect` and uses slashes as separators.
+    pub fn blame_file(
+        &self,
+        file_path: &BStr,
+        suspect: impl Into<ObjectId>,
+        options: gix_blame::file::Options,
+    ) -> Result<gix_blame::Outcome, gix_blame::file::Error> {
+        gix_blame::file(&self.objects, suspect.into(), file_path, options)
+    }
+
+    /// Like [`blame_file()`](Self::blame_file()), but pass each entry to `delegate` as soon as it is final,
+    /// see [`gix_blame::incremental()`] for details.
+    pub fn blame_file_incremental(
+        &self,
+        file_path: &BStr,
+        suspect: impl Into<ObjectId>,
+        options: gix_blame::file::Options,
+        delegate: &mut dyn gix_blame::incremental::Delegate,
+    ) -> Result<gix_blame::incremental::Outcome, gix_blame::file::Error> {
+        gix_blame::incremental(&self.objects, suspect.into(), file_path, options, delegate)
+    }
+
+    /// Follow the zero-based, end-exclusive line `ranges` of the file at `file_path` as it is in `commit` back through history,
+    /// and return all commits that changed them, see [`gix_blame::history()`] for details.
+    ///
+    /// Note that `file_path` is relative to the root of the tree of `commit` and uses slashes as separators.
+    pub fn line_history(
+        &self,
+        file_path: &BStr,
+        ranges: &[Range<u32>],
+        commit: impl Into<ObjectId>,
+        options: gix_blame::history::Options,
+    ) -> Result<gix_blame::history::Outcome, gix_blame::history::Error> {
+        gix_blame::history(&self.objects, commit.into(), file_path, ranges, options)
+    }
+}
@@ -0,0 +1,574 @@
+use std::{
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_features::progress::{Count, DynNestedProgress, Progress};
+use gix_hash::ObjectId;
+use gix_object::{CommitRefIter, Exists, Find, FindExt, Kind, TagRefIter, TreeRefIter};
+use gix_pack::data::output;
+use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    bundle::{create, list, unbundle, Header},
+    ext::ObjectIdExt,
+    Repository,
+};
+
+impl Repository {
+    /// Write a bundle to `path` which contains the references named by `refs` along with all objects reachable from them,
+    /// similar to `git bundle create`.
+    ///
+    /// Objects reachable from [`create::Options::prerequisites`] are assumed to be present in the receiving repository and are
+    /// left out, and the prerequisites are recorded in the bundle header.
+    /// Symbolic references like `HEAD` keep their name, but are recorded with the object they ultimately point to.
+    /// `progress` and `should_interrupt` are used to observe and abort the operation.
+    pub fn create_bundle<'a, Name, E>(
+        &self,
+        path: impl AsRef<Path>,
+        refs: impl IntoIterator<Item = Name>,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: create::Options,
+    ) -> Result<create::Outcome, create::Error>
+    where
+        Name: TryInto<&'a gix_ref::PartialNameRef, Error = E>,
+        gix_ref::file::find::Error: From<E>,
+    {
+        let _span = gix_trace::coarse!("gix::Repository::create_bundle()");
+        let object_hash = self.object_hash();
+        let mut header = Header::new(options.version, object_hash);
+        let mut buf = Vec::new();
+        for id in &options.prerequisites {
+            let commit = self.objects.find(id, &mut buf)?;
+            if commit.kind != Kind::Commit {
+                return Err(create::Error::PrerequisiteNotACommit { id: *id });
+            }
+            let summary = gix_object::CommitRef::from_bytes(commit.data)?
+                .message_summary()
+                .into_owned();
+            header.prerequisites.push(gix_bundle::Prerequisite {
+                id: *id,
+                comment: Some(summary).filter(|summary| !summary.is_empty()),
+            });
+        }
+        for name in refs {
+            let mut reference = self.find_reference(name)?;
+            let name = reference.name().to_owned();
+            while let Some(target) = reference.follow() {
+                reference = target?;
+            }
+            let id = reference
+                .try_id()
+                .ok_or(create::Error::UnbornReference { name: name.clone() })?;
+            header.refs.push(gix_bundle::Ref {
+                id: id.detach(),
+                name: name.into_inner(),
+            });
+        }
+
+        let ids = {
+            let mut progress = progress.add_child("finding objects".into());
+            progress.init(None, gix_features::progress::count("objects"));
+            let tips: Vec<_> = header.refs.iter().map(|r| r.id).collect();
+            self.bundle_objects(&tips, &options.prerequisites, &progress, should_interrupt)?
+        };
+
+        let mut objects = self.objects.clone().into_arc()?;
+        objects.prevent_pack_unload();
+        let counts = {
+            let mut progress = progress.add_child("counting".into());
+            progress.init(None, gix_features::progress::count("objects"));
+            let (counts, _) = output::count::objects_unthreaded(
+                &objects,
+                &mut ids.into_iter().map(Ok),
+                &progress,
+                should_interrupt,
+                output::count::objects::ObjectExpansion::AsIs,
+            )?;
+            counts
+        };
+        let num_objects = counts.len();
+        let mut entries = gix_features::parallel::InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            objects,
+            Box::new(progress.add_child("creating entries".into())),
+            output::entry::iter_from_counts::Options {
+                thread_limit: None,
+                mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                allow_thin_pack: false,
+                chunk_size: 1000,
+                version: Default::default(),
+                compression_level: None,
+                stable_order: false,
+                chunks_in_flight: None,
+            },
+        ));
+
+        let mut file = gix_lock::File::acquire_to_update_resource(path, gix_lock::acquire::Fail::Immediately, None)?;
+        header.write_to(&mut file)?;
+        {
+            let mut write = output::bytes::FromEntriesIter::new(
+                entries.by_ref(),
+                &mut file,
+                num_objects as u32,
+                gix_pack::data::Version::default(),
+                object_hash,
+            );
+            for res in write.by_ref() {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(create::Error::Interrupted);
+                }
+                res?;
+            }
+        }
+        file.commit()?;
+        Ok(create::Outcome { header, num_objects })
+    }
+
+    /// Read the bundle at `path`, store its pack in the object database and update local references from its references
+    /// as mapped by [`unbundle::Options::refspecs`], similar to `git fetch <bundle>`.
+    ///
+    /// Fails if the prerequisites of the bundle aren't present in this repository. References that would be updated without being
+    /// fast-forwarded are only changed if their refspec allows forcing the update, and are otherwise reported as rejected.
+    /// `progress` and `should_interrupt` are used to observe and abort the operation.
+    pub fn unbundle(
+        &self,
+        path: impl AsRef<Path>,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: unbundle::Options,
+    ) -> Result<unbundle::Outcome, unbundle::Error> {
+        let _span = gix_trace::coarse!("gix::Repository::unbundle()");
+        let path = path.as_ref();
+        let mut read = std::io::BufReader::new(std::fs::File::open(path).map_err(|source| unbundle::Error::Open {
+            path: path.to_owned(),
+            source,
+        })?);
+        let header = Header::from_read(&mut read)?;
+        if header.object_hash != self.object_hash() {
+            return Err(unbundle::Error::ObjectHashMismatch {
+                expected: self.object_hash(),
+                actual: header.object_hash,
+            });
+        }
+        if let Some(missing) = header
+            .prerequisites
+            .iter()
+            .find(|prerequisite| !self.objects.exists(&prerequisite.id))
+        {
+            return Err(unbundle::Error::MissingPrerequisite { id: missing.id });
+        }
+
+        let pack = gix_pack::Bundle::write_to_directory(
+            &mut read,
+            Some(&self.objects.store_ref().path().join("pack")),
+            progress,
+            should_interrupt,
+            Some(self.objects.clone()),
+            gix_pack::bundle::write::Options {
+                object_hash: header.object_hash,
+                ..Default::default()
+            },
+        )?;
+        let message = options.reflog_message.clone().unwrap_or_else(|| {
+            let mut message = BString::from("bundle: from ");
+            message.push_str(gix_path::into_bstr(path).as_ref());
+            message
+        });
+        let updates = self.update_refs_from_bundle(&header, &options.refspecs, message)?;
+        if let Some(keep_path) = &pack.keep_path {
+            std::fs::remove_file(keep_path)?;
+        }
+        Ok(unbundle::Outcome { header, pack, updates })
+    }
+
+    /// Download the bundles of `list` and store them in the repository like [`unbundle()`](Self::unbundle()) does, mapping their
+    /// references with `options.refspecs`, similar to what `git clone --bundle-uri` and `git fetch` do before talking to the remote.
+    ///
+    /// Relative bundle URIs are resolved against `base_url`, which typically is the URL of the remote that advertised `list`.
+    /// Local paths and `file://` URIs are always supported, while `http://` and `https://` URIs need one of the
+    /// `blocking-http-transport-*` features.
+    /// If the list uses the `creationToken` heuristic, bundles are applied in the order of their creation.
+    ///
+    /// Applying bundles is an optimization, which is why bundles that can't be downloaded or whose prerequisites
+    /// are missing are reported through `progress` and skipped.
+    /// For lists in [any](list::Mode::Any) mode, only the first bundle that could be applied is used.
+    pub fn unbundle_list(
+        &self,
+        list: &list::List,
+        base_url: &gix_url::Url,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: list::Options,
+    ) -> Result<list::Outcome, list::Error> {
+        let _span = gix_trace::coarse!("gix::Repository::unbundle_list()", bundles = list.bundles.len());
+        let mut out = list::Outcome::default();
+        let mut pending = Vec::new();
+        let mut bundles = list.bundles.clone();
+        if list.uses_creation_token_heuristic() {
+            bundles.sort_by_key(|bundle| bundle.creation_token.unwrap_or(u64::MAX));
+        }
+        for bundle in bundles {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(list::Error::Interrupted);
+            }
+            if list.mode == list::Mode::Any && !out.unbundled.is_empty() || bundle.filter != options.filter {
+                out.skipped.push(bundle);
+                continue;
+            }
+            let uri = resolve_bundle_uri(bundle.uri.as_ref(), base_url);
+            progress.set_name(format!("download bundle '{}'", bundle.id));
+            match self.download_bundle(uri.as_ref())? {
+                Ok(downloaded) => pending.push((bundle, downloaded)),
+                Err(err) => {
+                    progress.fail(format!("Skipping bundle at '{uri}': {err}"));
+                    out.skipped.push(bundle);
+                    continue;
+                }
+            }
+            if list.mode == list::Mode::Any {
+                self.apply_pending_bundles(&mut pending, &mut out, progress, should_interrupt, &options)?;
+            }
+        }
+        self.apply_pending_bundles(&mut pending, &mut out, progress, should_interrupt, &options)?;
+        for (bundle, _) in pending {
+            progress.fail(format!(
+                "Skipping bundle '{}' as its prerequisites are missing",
+                bundle.id
+            ));
+            out.skipped.push(bundle);
+        }
+        Ok(out)
+    }
+
+    /// Apply all `pending` bundles, retrying those with missing prerequisites as long as other bundles could be applied,
+    /// and leave those that couldn't be applied due to missing prerequisites in `pending`.
+    fn apply_pending_bundles(
+        &self,
+        pending: &mut Vec<(list::Bundle, DownloadedBundle)>,
+        out: &mut list::Outcome,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: &list::Options,
+    ) -> Result<(), list::Error> {
+        loop {
+            let num_pending = pending.len();
+            for (bundle, mut downloaded) in std::mem::take(pending) {
+                progress.set_name(format!("unbundle '{}'", bundle.id));
+                let res = self.unbundle(
+                    downloaded.path(),
+                    progress,
+                    should_interrupt,
+                    unbundle::Options {
+                        refspecs: options.refspecs.clone(),
+                        reflog_message: Some(format!("bundle-uri: {}", bundle.uri).into()),
+                    },
+                );
+                match res {
+                    Ok(outcome) => {
+                        // Assure the object database sees the new pack, even if the next user doesn't refresh it on a miss.
+                        for reference in &outcome.header.refs {
+                            self.objects.exists(&reference.id);
+                        }
+                        out.unbundled.push((bundle, outcome))
+                    }
+                    Err(unbundle::Error::MissingPrerequisite { .. }) => pending.push((bundle, downloaded)),
+                    Err(_) if should_interrupt.load(Ordering::Relaxed) => return Err(list::Error::Interrupted),
+                    Err(err) => {
+                        progress.fail(format!("Skipping bundle '{}': {err}", bundle.id));
+                        out.skipped.push(bundle);
+                    }
+                }
+            }
+            if pending.is_empty() || pending.len() == num_pending {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Obtain the bundle at `uri` as local file, downloading it if necessary, or return why that isn't possible.
+    fn download_bundle(
+        &self,
+        uri: &BStr,
+    ) -> Result<Result<DownloadedBundle, Box<dyn std::error::Error + Send + Sync>>, list::Error> {
+        if let Some(path) = uri
+            .strip_prefix(b"file://")
+            .map(BStr::new)
+            .or_else(|| uri.find(b"://").is_none().then_some(uri))
+        {
+            return Ok(Ok(DownloadedBundle::Local(gix_path::from_bstr(path).into_owned())));
+        }
+        if !(uri.starts_with(b"http://") || uri.starts_with(b"https://")) {
+            return Ok(Err("only local paths and HTTP URLs are supported".into()));
+        }
+        #[cfg(not(any(
+            feature = "blocking-http-transport-reqwest",
+            feature = "blocking-http-transport-curl"
+        )))]
+        {
+            Ok(Err("HTTP support wasn't compiled in".into()))
+        }
+        #[cfg(any(
+            feature = "blocking-http-transport-reqwest",
+            feature = "blocking-http-transport-curl"
+        ))]
+        {
+            use gix_protocol::transport::client::http::{self, Http};
+            let mut file = gix_tempfile::new(
+                self.objects.store_ref().path().join("pack"),
+                gix_tempfile::ContainingDirectory::CreateAllRaceProof(Default::default()),
+                gix_tempfile::AutoRemove::Tempfile,
+            )?;
+            let download =
+                |file: &mut gix_tempfile::Handle<_>| -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+                    let url = uri.to_str()?;
+                    let mut client = http::Impl::default();
+                    if let Some(options) = self.transport_options(uri, None)? {
+                        client.configure(options.as_ref())?;
+                    }
+                    let mut response = client.get(url, url, std::iter::empty::<&str>())?;
+                    std::io::copy(&mut response.body, file)?;
+                    Ok(())
+                };
+            Ok(download(&mut file).map(|()| DownloadedBundle::Temporary(file)))
+        }
+    }
+
+    /// Return the ids of all objects reachable from `tips` except for the ancestry of `prerequisites` and the trees and blobs of
+    /// `prerequisites` themselves.
+    /// Objects that are missing, like those beyond the boundary of a shallow repository, are ignored.
+    fn bundle_objects(
+        &self,
+        tips: &[ObjectId],
+        prerequisites: &[ObjectId],
+        progress: &dyn Count,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Vec<ObjectId>, create::Error> {
+        let mut buf = Vec::new();
+        let mut seen = gix_hashtable::HashSet::default();
+        let mut next = prerequisites.to_vec();
+        let mut trees = Vec::new();
+        while let Some(id) = next.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(create::Error::Interrupted);
+            }
+            let Some(commit) = self.objects.try_find(&id, &mut buf)? else {
+                continue;
+            };
+            let mut commit = CommitRefIter::from_bytes(commit.data);
+            if prerequisites.contains(&id) {
+                trees.push(commit.tree_id()?);
+            }
+            next.extend(commit.parent_ids());
+        }
+        while let Some(id) = trees.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            let Some(tree) = self.objects.try_find(&id, &mut buf)? else {
+                continue;
+            };
+            for entry in TreeRefIter::from_bytes(tree.data) {
+                let entry = entry?;
+                if entry.mode.is_tree() {
+                    trees.push(entry.oid.to_owned());
+                } else if !entry.mode.is_commit() {
+                    seen.insert(entry.oid.to_owned());
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        next.extend_from_slice(tips);
+        while let Some(id) = next.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(create::Error::Interrupted);
+            }
+            let Some(object) = self.objects.try_find(&id, &mut buf)? else {
+                continue;
+            };
+            out.push(id);
+            progress.inc();
+            match object.kind {
+                Kind::Commit => {
+                    let mut commit = CommitRefIter::from_bytes(object.data);
+                    next.push(commit.tree_id()?);
+                    next.extend(commit.parent_ids());
+                }
+                Kind::Tag => next.push(TagRefIter::from_bytes(object.data).target_id()?),
+                Kind::Tree => {
+                    for entry in TreeRefIter::from_bytes(object.data) {
+                        let entry = entry?;
+                        if entry.mode.is_tree() {
+                            next.push(entry.oid.to_owned());
+                        } else if !entry.mode.is_commit() && seen.insert(entry.oid.to_owned()) {
+                            out.push(entry.oid.to_owned());
+                            progress.inc();
+                        }
+                    }
+                }
+                Kind::Blob => {}
+            }
+        }
+        Ok(out)
+    }
+
+    /// Update local references from the references in `header` as mapped by `refspecs`, using `message` for the reference log.
+    fn update_refs_from_bundle(
+        &self,
+        header: &Header,
+        refspecs: &[gix_refspec::RefSpec],
+        message: BString,
+    ) -> Result<Vec<unbundle::Update>, unbundle::Error> {
+        let group = gix_refspec::MatchGroup::from_fetch_specs(refspecs.iter().map(gix_refspec::RefSpec::to_ref));
+        let (matches, _fixes) = group
+            .match_remotes(header.refs.iter().map(|r| gix_refspec::match_group::Item {
+                full_ref_name: r.name.as_ref(),
+                target: &r.id,
+                object: None,
+            }))
+            .validated()?;
+
+        let mut updates = Vec::new();
+        let mut edits = Vec::new();
+        for mapping in matches.mappings {
+            let (Some(index), Some(local)) = (mapping.item_index, mapping.rhs) else {
+                continue;
+            };
+            let source = &header.refs[index];
+            let name: gix_ref::FullName =
+                local
+                    .as_ref()
+                    .try_into()
+                    .map_err(|err| unbundle::Error::InvalidReferenceName {
+                        name: local.clone().into_owned(),
+                        source: err,
+                    })?;
+            let force = refspecs[mapping.spec_index].allow_non_fast_forward();
+            let (mode, previous, expected) = match self.try_find_reference(name.as_ref())? {
+                None => (unbundle::Mode::New, None, PreviousValue::MustNotExist),
+                Some(existing) => {
+                    let previous = existing
+                        .try_id()
+                        .map_or_else(|| existing.clone().peel_to_id_in_place(), Ok)?
+                        .detach();
+                    let mode = if previous == source.id {
+                        unbundle::Mode::NoChangeNeeded
+                    } else if existing.name().category() == Some(gix_ref::Category::Tag) {
+                        if force {
+                            unbundle::Mode::Forced
+                        } else {
+                            unbundle::Mode::RejectedTagUpdate
+                        }
+                    } else if source
+                        .id
+                        .ancestors(&self.objects)
+                        .any(|info| info.map_or(false, |info| info.id == previous))
+                    {
+                        unbundle::Mode::FastForward
+                    } else if force {
+                        unbundle::Mode::Forced
+                    } else {
+                        unbundle::Mode::RejectedNonFastForward
+                    };
+                    (
+                        mode,
+                        Some(previous),
+                        PreviousValue::MustExistAndMatch(existing.target().into_owned()),
+                    )
+                }
+            };
+            if matches!(
+                mode,
+                unbundle::Mode::New | unbundle::Mode::FastForward | unbundle::Mode::Forced
+            ) {
+                edits.push(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            message: message.clone(),
+                        },
+                        expected,
+                        new: gix_ref::Target::Peeled(source.id),
+                    },
+                    name: name.clone(),
+                    deref: false,
+                });
+            }
+            updates.push(unbundle::Update {
+                source: source.name.clone(),
+                name,
+                previous,
+                new: source.id,
+                mode,
+            });
+        }
+        if !edits.is_empty() {
+            self.edit_references(edits)?;
+        }
+        Ok(updates)
+    }
+}
+
+/// A bundle that is available locally.
+enum DownloadedBundle {
+    /// The bundle is a local file.
+    Local(std::path::PathBuf),
+    /// The bundle was downloaded into a temporary file that is removed when dropped.
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    Temporary(gix_tempfile::Handle<gix_tempfile::handle::Writable>),
+}
+
+impl DownloadedBundle {
+    fn path(&mut self) -> std::path::PathBuf {
+        match self {
+            DownloadedBundle::Local(path) => path.clone(),
+            #[cfg(any(
+                feature = "blocking-http-transport-reqwest",
+                feature = "blocking-http-transport-curl"
+            ))]
+            DownloadedBundle::Temporary(file) => file
+                .with_mut(|f| f.path().to_owned())
+                .expect("tempfile is still present"),
+        }
+    }
+}
+
+/// Resolve `uri` of a bundle in a bundle list against the `base_url` the list was obtained from, treating the latter as directory.
+fn resolve_bundle_uri(uri: &BStr, base_url: &gix_url::Url) -> BString {
+    if uri.find(b"://").is_some() {
+        return uri.to_owned();
+    }
+    if base_url.scheme == gix_url::Scheme::File {
+        if gix_path::from_bstr(uri).is_absolute() {
+            return uri.to_owned();
+        }
+        let mut path = base_url.path.clone();
+        if !path.ends_with(b"/") {
+            path.push(b'/');
+        }
+        path.extend_from_slice(uri);
+        return path;
+    }
+    let mut base = base_url.to_bstring();
+    if uri.starts_with(b"/") {
+        let len = base.len() - base_url.path.len();
+        base.truncate(len);
+    } else if !base.ends_with(b"/") {
+        base.push(b'/');
+    }
+    base.extend_from_slice(uri);
+    base
+}
@@ -0,0 +1,377 @@
+use std::{collections::BTreeSet, path::Path};
+
+use gix_hash::ObjectId;
+use gix_index::entry::{Flags, Mode, Stat};
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Category, PartialNameRef, Target,
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    checkout::{Error, Options, RestoreOptions},
+    merge, Repository,
+};
+
+/// Checking out trees, switching branches and restoring files
+impl Repository {
+    /// Change the index and the worktree to match `tree`, or the tree that `tree` peels to, without changing `HEAD`.
+    /// This is what `git switch` and `git checkout <commit>` do before pointing `HEAD` to the new commit.
+    ///
+    /// Unless `options.force` is `true`, local changes are carried over as long as they don't touch files that differ between the
+    /// tree of `HEAD` and `tree`, and it's an error if local changes or untracked files would be overwritten.
+    pub fn checkout_tree(&self, tree: impl Into<ObjectId>, options: Options) -> Result<(), Error> {
+        let _span = gix_trace::coarse!("gix::Repository::checkout_tree()");
+        let tree = self.find_object(tree.into())?.peel_to_tree()?.id;
+        self.checkout_tree_inner(tree, options)
+    }
+
+    /// Check out the local branch with the given `name`, like `main` or `refs/heads/main`, and point `HEAD` to it,
+    /// similar to `git switch`.
+    ///
+    /// The index and the worktree are changed just like [`checkout_tree()`](Self::checkout_tree()) does it, using `options`.
+    pub fn switch<'a, Name, E>(&self, name: Name, options: Options) -> Result<(), Error>
+    where
+        Name: TryInto<&'a PartialNameRef, Error = E>,
+        gix_ref::file::find::Error: From<E>,
+    {
+        let _span = gix_trace::coarse!("gix::Repository::switch()");
+        let mut branch = self.find_reference(name)?;
+        if !matches!(branch.name().category(), Some(Category::LocalBranch)) {
+            return Err(Error::NotABranch {
+                name: branch.name().to_owned(),
+            });
+        }
+        let commit = branch.peel_to_id_in_place()?.detach();
+        let tree = self.find_object(commit)?.peel_to_tree()?.id;
+
+        let (previous_id, previous) = self.head_id_and_description()?;
+        self.checkout_tree_inner(tree, options)?;
+
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: Default::default(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Symbolic(branch.name().to_owned()),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: false,
+        })?;
+
+        // Updates of symbolic references don't write the reflog as no object id is involved, so append to it like `git` does.
+        if self.refs.write_reflog != gix_ref::store::WriteReflog::Disable {
+            if let Some(committer) = self.committer().transpose()? {
+                let line = gix_ref::log::Line {
+                    previous_oid: previous_id.unwrap_or_else(|| ObjectId::null(self.object_hash())),
+                    new_oid: commit,
+                    signature: committer.into(),
+                    message: format!("checkout: moving from {previous} to {}", branch.name().shorten()).into(),
+                };
+                let mut log = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(self.refs.reflog_path("HEAD".try_into().expect("valid")))?;
+                line.write_to(&mut log)?;
+            }
+        }
+        #[cfg(feature = "hooks")]
+        if self.hooks_enabled() {
+            self.run_post_checkout_hook(previous_id, commit);
+        }
+        Ok(())
+    }
+
+    /// Check out the commit that `commit` peels to and detach `HEAD` so it points to it directly, similar to `git switch --detach`
+    /// or `git checkout <commit>`.
+    ///
+    /// The index and the worktree are changed just like [`checkout_tree()`](Self::checkout_tree()) does it, using `options`.
+    pub fn checkout_detached(&self, commit: impl Into<ObjectId>, options: Options) -> Result<(), Error> {
+        let _span = gix_trace::coarse!("gix::Repository::checkout_detached()");
+        let commit = self
+            .find_object(commit.into())?
+            .peel_to_kind(gix_object::Kind::Commit)?
+            .id;
+        let tree = self.find_object(commit)?.peel_to_tree()?.id;
+
+        #[cfg_attr(not(feature = "hooks"), allow(unused_variables))]
+        let (previous_id, previous) = self.head_id_and_description()?;
+        self.checkout_tree_inner(tree, options)?;
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: format!("checkout: moving from {previous} to {commit}").into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Peeled(commit),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: false,
+        })?;
+        #[cfg(feature = "hooks")]
+        if self.hooks_enabled() {
+            self.run_post_checkout_hook(previous_id, commit);
+        }
+        Ok(())
+    }
+
+    /// Restore all files and index entries matching the pathspecs in `patterns` from the index or a tree, similar to `git restore`.
+    ///
+    /// By default, the files in the worktree are restored from the index. Set `options.staged` to restore index entries from
+    /// `HEAD`, or `options.source` to restore from another tree. Entries that don't exist in the source are removed.
+    /// It's an error if no path in the index or the source matches `patterns`.
+    pub fn restore(
+        &self,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        options: RestoreOptions,
+    ) -> Result<(), Error> {
+        let _span = gix_trace::coarse!("gix::Repository::restore()");
+        let workdir = if options.worktree {
+            Some(self.work_dir().ok_or(Error::BareRepository)?)
+        } else {
+            None
+        };
+        let index = self.index_or_empty()?;
+        let source_tree = match (options.source, options.staged) {
+            (Some(source), _) => Some(self.find_object(source)?.peel_to_tree()?.id),
+            (None, true) => Some(self.head_tree_or_empty()?),
+            (None, false) => None,
+        };
+        let source_index = source_tree.map(|tree| self.tree_as_index(tree)).transpose()?;
+        let source: &gix_index::State = match &source_index {
+            Some(source_index) => source_index,
+            None => &index,
+        };
+
+        let attributes_source = gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping;
+        let mut pathspec = self.pathspec(patterns, true, &index, attributes_source)?;
+        let matched: BTreeSet<BString> = source
+            .entries()
+            .iter()
+            .map(|e| e.path(source))
+            .chain(index.entries().iter().map(|e| e.path(&index)))
+            .filter(|path| pathspec.is_included(*path, Some(false)))
+            .map(ToOwned::to_owned)
+            .collect();
+        if matched.is_empty() {
+            return Err(Error::NoMatch);
+        }
+        let unmerged = unmerged_paths(source, |path| matched.contains(path));
+        if !unmerged.is_empty() {
+            return Err(Error::UnresolvedConflicts { paths: unmerged });
+        }
+
+        let mut new_index = gix_index::State::new(self.object_hash());
+        for entry in index.entries() {
+            let path = entry.path(&index);
+            if !(options.staged && matched.contains(path)) {
+                new_index.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, path);
+            }
+        }
+        if options.staged {
+            for path in &matched {
+                if let Some(entry) = source.entry_by_path_and_stage(path.as_ref(), 0) {
+                    new_index.dangerously_push_entry(
+                        Stat::default(),
+                        entry.id,
+                        Flags::empty(),
+                        entry.mode,
+                        path.as_ref(),
+                    );
+                }
+            }
+        }
+        new_index.sort_entries();
+
+        if let Some(workdir) = workdir {
+            let target = if options.staged { &new_index } else { source };
+            let mut to_write = gix_index::State::new(self.object_hash());
+            for path in &matched {
+                match target.entry_by_path_and_stage(path.as_ref(), 0) {
+                    Some(entry) if entry.mode == Mode::COMMIT => {}
+                    Some(entry) => {
+                        to_write.dangerously_push_entry(
+                            Stat::default(),
+                            entry.id,
+                            Flags::empty(),
+                            entry.mode,
+                            path.as_ref(),
+                        );
+                    }
+                    None => merge::worktree::remove_file(workdir, path.as_ref())?,
+                }
+            }
+            let written = merge::worktree::write_files(self, to_write, true)?;
+            for (entry, path) in new_index.entries_mut_with_paths() {
+                if let Some(written) = written
+                    .entry_by_path_and_stage(path, 0)
+                    .filter(|written| written.id == entry.id && written.mode == entry.mode)
+                {
+                    entry.stat = written.stat;
+                }
+            }
+        }
+        gix_index::File::from_state(new_index, self.index_path()).write(Default::default())?;
+        Ok(())
+    }
+}
+
+/// Utilities
+impl Repository {
+    fn checkout_tree_inner(&self, tree: ObjectId, options: Options) -> Result<(), Error> {
+        let workdir = self.work_dir().ok_or(Error::BareRepository)?;
+        let index = self.index_or_empty()?;
+        let new = self.tree_as_index(tree)?;
+        if options.force {
+            merge::worktree::checkout(self, &index, new, &[], true)?;
+            return Ok(());
+        }
+
+        let unmerged = unmerged_paths(&index, |_| true);
+        if !unmerged.is_empty() {
+            return Err(Error::UnresolvedConflicts { paths: unmerged });
+        }
+        let head = self.tree_as_index(self.head_tree_or_empty()?)?;
+        let changed_in_worktree: BTreeSet<BString> = merge::worktree::worktree_changes(self, &index)?
+            .into_iter()
+            .filter(|(_, status)| !matches!(status, gix_status::index_as_worktree::EntryStatus::NeedsUpdate(_)))
+            .map(|(path, _)| path)
+            .collect();
+        let paths: BTreeSet<&BStr> = index
+            .entries()
+            .iter()
+            .map(|e| e.path(&index))
+            .chain(head.entries().iter().map(|e| e.path(&head)))
+            .chain(new.entries().iter().map(|e| e.path(&new)))
+            .collect();
+
+        let same = |a: Option<&gix_index::Entry>, b: Option<&gix_index::Entry>| match (a, b) {
+            (Some(a), Some(b)) => a.id == b.id && a.mode == b.mode,
+            (None, None) => true,
+            _ => false,
+        };
+        let mut result = gix_index::State::new(self.object_hash());
+        let (mut local_changes, mut untracked) = (Vec::new(), Vec::new());
+        for path in paths {
+            let (in_head, in_new, in_index) = (
+                head.entry_by_path(path),
+                new.entry_by_path(path),
+                index.entry_by_path(path),
+            );
+            let entry = if same(in_head, in_new) || same(in_index, in_new) {
+                in_index
+            } else if !same(in_index, in_head) || changed_in_worktree.contains(path) {
+                local_changes.push(path.to_owned());
+                continue;
+            } else if let Some(in_the_way) = untracked_in_the_way(workdir, path, &index) {
+                untracked.push(in_the_way);
+                continue;
+            } else {
+                in_new
+            };
+            if let Some(entry) = entry {
+                result.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, path);
+            }
+        }
+        if !local_changes.is_empty() {
+            return Err(Error::LocalChanges { paths: local_changes });
+        }
+        if !untracked.is_empty() {
+            untracked.sort();
+            untracked.dedup();
+            return Err(Error::UntrackedFiles { paths: untracked });
+        }
+        merge::worktree::checkout(self, &index, result, &[], false)?;
+        Ok(())
+    }
+
+    /// Return the id `HEAD` points to, if it's born, along with a description of it for use in reflog messages.
+    fn head_id_and_description(&self) -> Result<(Option<ObjectId>, BString), Error> {
+        let head = self.head()?;
+        let previous: BString = match (head.referent_name(), head.id()) {
+            (Some(name), _) => name.shorten().to_owned(),
+            (None, Some(id)) => id.to_string().into(),
+            (None, None) => "HEAD".into(),
+        };
+        Ok((head.id().map(crate::Id::detach), previous))
+    }
+
+    fn head_tree_or_empty(&self) -> Result<ObjectId, Error> {
+        Ok(match self.head()?.try_peel_to_id_in_place()? {
+            Some(id) => id.object()?.peel_to_tree()?.id,
+            None => ObjectId::empty_tree(self.object_hash()),
+        })
+    }
+
+    fn tree_as_index(&self, tree: ObjectId) -> Result<gix_index::State, Error> {
+        if tree.is_empty_tree() {
+            return Ok(gix_index::State::new(self.object_hash()));
+        }
+        gix_index::State::from_tree(&tree, &self.objects).map_err(|err| Error::IndexFromTree { id: tree, source: err })
+    }
+}
+
+/// Return the path of an untracked file in `workdir` that would be overwritten when writing a new file at `path`,
+/// which can be `path` itself, a file in the place of one of its parent directories, or a file within a directory at `path`.
+/// Files that are tracked in `index` are not considered to be in the way as they are removed or updated as part of the checkout.
+fn untracked_in_the_way(workdir: &Path, path: &BStr, index: &gix_index::State) -> Option<BString> {
+    let mut components = path.split(|b| *b == b'/').peekable();
+    let mut current = BString::default();
+    while let Some(component) = components.next() {
+        if !current.is_empty() {
+            current.push(b'/');
+        }
+        current.extend_from_slice(component);
+        let Ok(metadata) = workdir.join(gix_path::from_bstr(current.as_bstr())).symlink_metadata() else {
+            return None;
+        };
+        let is_last = components.peek().is_none();
+        if metadata.is_dir() {
+            if is_last {
+                return first_untracked_file(workdir, current, index);
+            }
+        } else {
+            return index.entry_by_path(current.as_bstr()).is_none().then_some(current);
+        }
+    }
+    None
+}
+
+/// Return the first file below the directory `rela_dir` in `workdir` that isn't tracked in `index`.
+fn first_untracked_file(workdir: &Path, rela_dir: BString, index: &gix_index::State) -> Option<BString> {
+    let mut dirs = vec![rela_dir];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(workdir.join(gix_path::from_bstr(dir.as_bstr()))) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let name = entry.file_name();
+            let mut path = dir.clone();
+            path.push(b'/');
+            path.extend_from_slice(gix_path::into_bstr(Path::new(&name)).as_ref());
+            if entry.file_type().map_or(false, |t| t.is_dir()) {
+                dirs.push(path);
+            } else if index.entry_by_path(path.as_bstr()).is_none() {
+                return Some(path);
+            }
+        }
+    }
+    None
+}
+
+/// Return the paths of all conflicting entries in `index` for which `filter` returns `true`.
+fn unmerged_paths(index: &gix_index::State, mut filter: impl FnMut(&BStr) -> bool) -> Vec<BString> {
+    let mut paths: Vec<BString> = index
+        .entries()
+        .iter()
+        .filter(|e| e.stage() != 0 && filter(e.path(index)))
+        .map(|e| e.path(index).to_owned())
+        .collect();
+    paths.dedup();
+    paths
+}
@@ -0,0 +1,198 @@
+use gix_hash::ObjectId;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+
+use crate::{
+    bstr::{BString, ByteSlice, ByteVec},
+    merge,
+    merge::commit::{Error, Outcome},
+    Commit, Id, Repository,
+};
+
+const CHERRY_PICK_HEAD: &str = "CHERRY_PICK_HEAD";
+const REVERT_HEAD: &str = "REVERT_HEAD";
+const MERGE_MSG: &str = "MERGE_MSG";
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Kind {
+    CherryPick,
+    Revert,
+}
+
+impl Kind {
+    fn operation(&self) -> &'static str {
+        match self {
+            Kind::CherryPick => "cherry-pick",
+            Kind::Revert => "revert",
+        }
+    }
+
+    fn head_file(&self) -> &'static str {
+        match self {
+            Kind::CherryPick => CHERRY_PICK_HEAD,
+            Kind::Revert => REVERT_HEAD,
+        }
+    }
+}
+
+/// Cherry-picking and reverting
+impl Repository {
+    /// Apply the changes introduced by `commit` compared to its parent on top of `HEAD`, and commit them with the
+    /// author and message of `commit`, similar to `git cherry-pick`.
+    ///
+    /// The index and the worktree must not have changes. If the changes can't be applied without conflicts, the
+    /// index and the worktree are left with the conflicts to resolve, and `CHERRY_PICK_HEAD` and `MERGE_MSG` are written
+    /// so `git cherry-pick --continue` or `git cherry-pick --abort` can take over.
+    pub fn cherry_pick(&self, commit: impl Into<ObjectId>) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::cherry_pick()");
+        self.pick_or_revert(commit.into(), Kind::CherryPick)
+    }
+
+    /// Undo the changes introduced by `commit` compared to its parent on top of `HEAD`, and commit the result with a
+    /// message referring to `commit`, similar to `git revert`.
+    ///
+    /// The index and the worktree must not have changes. If the changes can't be undone without conflicts, the
+    /// index and the worktree are left with the conflicts to resolve, and `REVERT_HEAD` and `MERGE_MSG` are written
+    /// so `git revert --continue` or `git revert --abort` can take over.
+    pub fn revert(&self, commit: impl Into<ObjectId>) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::revert()");
+        self.pick_or_revert(commit.into(), Kind::Revert)
+    }
+}
+
+/// Utilities
+impl Repository {
+    fn pick_or_revert(&self, id: ObjectId, kind: Kind) -> Result<Outcome, Error> {
+        if let Some(state) = self.state() {
+            return Err(Error::InProgress {
+                operation: kind.operation(),
+                state,
+            });
+        }
+        let head_id = self.head_id()?.detach();
+        let head_tree = find_commit(self, head_id)?.tree_id()?.detach();
+        let index = self.open_index()?;
+        merge::worktree::require_clean(self, &index, Some(&head_tree))?;
+
+        let commit = find_commit(self, id)?;
+        let mut parents = commit.parent_ids();
+        let parent_tree = match (parents.next(), parents.next()) {
+            (None, _) => ObjectId::empty_tree(self.object_hash()),
+            (Some(parent), None) => find_commit(self, parent)?.tree_id()?.detach(),
+            (Some(_), Some(_)) => return Err(Error::MergeCommit { id }),
+        };
+        let commit_tree = commit.tree_id()?.detach();
+        let commit_label: BString = format!(
+            "{} ({})",
+            commit.id().shorten_or_id(),
+            commit.message()?.summary().to_str_lossy()
+        )
+        .into();
+        let parent_label: BString = format!("parent of {commit_label}").into();
+
+        let (base, theirs, base_label, their_label, message, author) = match kind {
+            Kind::CherryPick => (
+                parent_tree,
+                commit_tree,
+                parent_label,
+                commit_label,
+                commit.message_raw()?.to_owned(),
+                commit.author()?.to_owned(),
+            ),
+            Kind::Revert => {
+                let author = self.author().ok_or(Error::AuthorMissing)??.to_owned();
+                (
+                    commit_tree,
+                    parent_tree,
+                    commit_label,
+                    parent_label,
+                    revert_message(&commit)?,
+                    author,
+                )
+            }
+        };
+        let merged = self.merge_trees(
+            base,
+            head_tree,
+            theirs,
+            merge::blob::Labels {
+                ancestor: Some(base_label.as_ref()),
+                current: Some("HEAD".into()),
+                other: Some(their_label.as_ref()),
+            },
+            self.merge_tree_options()?,
+        )?;
+
+        if merged.has_conflicts() {
+            merge::worktree::checkout(self, &index, merged.index, &merged.conflicts, false)?;
+            self.rerere()?;
+            let mut merge_msg = message;
+            merge_msg.push_str("\n# Conflicts:\n");
+            for conflict in &merged.conflicts {
+                merge_msg.push_str("#\t");
+                merge_msg.push_str(&conflict.path);
+                merge_msg.push_byte(b'\n');
+            }
+            std::fs::write(self.path().join(MERGE_MSG), merge_msg)?;
+            std::fs::write(self.path().join(kind.head_file()), format!("{id}\n"))?;
+            return Ok(Outcome {
+                commit: None,
+                conflicts: merged.conflicts,
+            });
+        }
+
+        let tree = merged
+            .index
+            .write_tree(|tree| self.write_object(tree).map(Id::detach))?;
+        if tree == head_tree {
+            return Err(Error::Empty { id });
+        }
+        merge::worktree::checkout(self, &index, merged.index, &[], false)?;
+
+        let committer = self.committer().ok_or(Error::CommitterMissing)??.to_owned();
+        let new_commit = gix_object::Commit {
+            tree,
+            parents: [head_id].into(),
+            author,
+            committer,
+            encoding: None,
+            message,
+            extra_headers: Default::default(),
+        };
+        let new_id = self.write_object(&new_commit)?.detach();
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: crate::reference::log::message(kind.operation(), new_commit.message.as_ref(), 1),
+                },
+                expected: PreviousValue::MustExistAndMatch(Target::Peeled(head_id)),
+                new: Target::Peeled(new_id),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: true,
+        })?;
+        Ok(Outcome {
+            commit: Some(new_id),
+            conflicts: Vec::new(),
+        })
+    }
+}
+
+/// Produce the message git would use to revert `commit`.
+fn revert_message(commit: &Commit<'_>) -> Result<BString, Error> {
+    let summary = commit.message()?.summary();
+    Ok(format!(
+        "Revert \"{}\"\n\nThis reverts commit {}.\n",
+        summary.to_str_lossy(),
+        commit.id
+    )
+    .into())
+}
+
+fn find_commit(repo: &Repository, id: impl Into<ObjectId>) -> Result<Commit<'_>, Error> {
+    Ok(repo.find_object(id)?.try_into_commit()?)
+}
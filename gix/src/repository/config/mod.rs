@@ -22,6 +22,27 @@ impl crate::Repository {
         }
     }
 
+    /// Lock and load the configuration file of `source` for editing, like `git config --local` or `git config --global` would,
+    /// to write changes to it with [`FileMut::commit()`](config::FileMut::commit()).
+    ///
+    /// The file will be created along with its directory if it doesn't exist yet.
+    /// Note that `source` must be stored in a file, which excludes sources for overrides like [`Cli`](gix_config::Source::Cli),
+    /// and that [`Worktree`](gix_config::Source::Worktree) configuration is only used if `extensions.worktreeConfig` is enabled.
+    pub fn config_file_mut(
+        &self,
+        source: gix_config::Source,
+    ) -> Result<config::FileMut, config::file_mut::open::Error> {
+        let path = match source {
+            gix_config::Source::Local => self.common_dir().join("config"),
+            gix_config::Source::Worktree => self.git_dir().join("config.worktree"),
+            _ => self
+                .config
+                .storage_location(source)
+                .ok_or(config::file_mut::open::Error::NoLocation { kind: source })?,
+        };
+        config::FileMut::open(path, source)
+    }
+
     /// Return filesystem options as retrieved from the repository configuration.
     ///
     /// Note that these values have not been [probed](gix_fs::Capabilities::probe()).
@@ -75,6 +96,18 @@ impl crate::Repository {
                 .and_then(|variant| Ssh::VARIANT.try_into_variant(variant).transpose())
                 .transpose()
                 .with_leniency(self.options.lenient_config)?,
+            batch_mode: config
+                .boolean_filter(
+                    "gitoxide",
+                    Some("ssh".into()),
+                    gitoxide::Ssh::BATCH_MODE.name,
+                    &mut trusted,
+                )
+                .map(|value| gitoxide::Ssh::BATCH_MODE.enrich_error(value))
+                .transpose()
+                .with_leniency(self.options.lenient_config)?
+                .unwrap_or_default(),
+            proxy: None,
         };
         Ok(opts)
     }
@@ -286,6 +286,32 @@ impl crate::Repository {
                             ))
                         })
                         .transpose()?;
+                    {
+                        let key = "gitoxide.http.authMethod";
+                        debug_assert_eq!(key, gitoxide::Http::AUTH_METHOD.logical_name());
+                        opts.auth_method = config
+                            .string_filter_by_key(key, &mut trusted_only)
+                            .map(|v| {
+                                gitoxide::Http::AUTH_METHOD
+                                    .try_into_proxy_auth_method(v)
+                                    .map_err(|err| config::transport::http::Error::InvalidAuthMethod {
+                                        source: err,
+                                        key: Cow::Borrowed(key.into()),
+                                    })
+                            })
+                            .transpose()?;
+                    }
+                    {
+                        let key = "http.emptyAuth";
+                        debug_assert_eq!(key, config::tree::Http::EMPTY_AUTH.logical_name());
+                        opts.empty_auth = config
+                            .boolean_filter_by_key(key, &mut trusted_only)
+                            .map(|value| config::tree::Http::EMPTY_AUTH.enrich_error(value))
+                            .transpose()
+                            .with_leniency(lenient)
+                            .map_err(config::transport::http::Error::from)?
+                            .unwrap_or_default();
+                    }
                     opts.connect_timeout = {
                         let key = "gitoxide.http.connectTimeout";
                         config
@@ -369,6 +395,53 @@ impl crate::Repository {
                             .map_err(|err| config::transport::Error::InterpolatePath { source: err, key })?;
                     }
 
+                    for (key, ssl_path) in [("http.sslCert", &mut opts.ssl_cert), ("http.sslKey", &mut opts.ssl_key)] {
+                        *ssl_path = config
+                            .path_filter_by_key(key, &mut trusted_only)
+                            .map(|p| {
+                                use crate::config::cache::interpolate_context;
+                                p.interpolate(interpolate_context(
+                                    self.install_dir().ok().as_deref(),
+                                    self.config.home_dir().as_deref(),
+                                ))
+                                .map(std::borrow::Cow::into_owned)
+                            })
+                            .transpose()
+                            .with_leniency(lenient)
+                            .map_err(|err| config::transport::Error::InterpolatePath { source: err, key })?;
+                    }
+
+                    {
+                        let key = "http.sslCertPasswordProtected";
+                        debug_assert_eq!(key, config::tree::Http::SSL_CERT_PASSWORD_PROTECTED.logical_name());
+                        let password_protected = config
+                            .boolean_filter_by_key(key, &mut trusted_only)
+                            .map(|value| config::tree::Http::SSL_CERT_PASSWORD_PROTECTED.enrich_error(value))
+                            .transpose()
+                            .with_leniency(lenient)
+                            .map_err(config::transport::http::Error::from)?
+                            .unwrap_or_default();
+                        // Like `git`, ask for the password of the certificate using the credential helpers.
+                        opts.ssl_cert_password = opts
+                            .ssl_cert
+                            .as_deref()
+                            .filter(|_| password_protected)
+                            .map(|cert| -> Result<_, config::transport::Error> {
+                                let mut url = gix_url::parse("cert:///".into())?;
+                                url.path = gix_path::into_bstr(cert).into_owned();
+                                let (mut cascade, action_with_normalized_url, prompt_opts) = self
+                                    .config_snapshot()
+                                    .credential_helpers(url)
+                                    .map_err(config::transport::http::Error::from)?;
+                                Ok((
+                                    action_with_normalized_url,
+                                    Arc::new(Mutex::new(move |action| cascade.invoke(action, prompt_opts.clone())))
+                                        as Arc<Mutex<http::options::AuthenticateFn>>,
+                                ))
+                            })
+                            .transpose()?;
+                    }
+
                     {
                         opts.ssl_version = ssl_version(
                             config,
@@ -13,6 +13,27 @@ pub mod resource_cache {
     }
 }
 
+///
+pub mod head_to_tree {
+    /// The error returned by [Repository::diff_head_to_tree()](super::Repository::diff_head_to_tree()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        HeadTree(#[from] crate::reference::head_tree::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToTree(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        NewRewrites(#[from] crate::diff::new_rewrites::Error),
+        #[error(transparent)]
+        Diff(#[from] crate::object::tree::diff::for_each::Error),
+    }
+}
+
 /// Diff-utilities
 impl Repository {
     /// Create a resource cache for diffable objects, and configured with everything it needs to know to perform diffs
@@ -42,4 +63,26 @@ impl Repository {
             worktree_roots,
         )?)
     }
+
+    /// Call `for_each` with all changes needed to turn the tree of the commit `HEAD` points to into the tree that `other`
+    /// peels to, which may be a commit, tag or tree. If `HEAD` is unborn, it is diffed as empty tree.
+    ///
+    /// As no index or worktree is involved, this works in bare repositories as well. Rename tracking is configured
+    /// like in [`Tree::changes()`](crate::Tree::changes()).
+    pub fn diff_head_to_tree<E>(
+        &self,
+        other: impl Into<gix_hash::ObjectId>,
+        for_each: impl FnMut(crate::object::tree::diff::Change<'_, '_, '_>) -> Result<crate::object::tree::diff::Action, E>,
+    ) -> Result<crate::object::tree::diff::for_each::Outcome, head_to_tree::Error>
+    where
+        E: Into<Box<dyn std::error::Error + Sync + Send + 'static>>,
+    {
+        let head = if self.head()?.is_unborn() {
+            self.empty_tree()
+        } else {
+            self.head_tree()?
+        };
+        let other = self.find_object(other)?.peel_to_tree()?;
+        Ok(head.changes()?.for_each_to_obtain_tree(&other, for_each)?)
+    }
 }
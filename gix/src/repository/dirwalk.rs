@@ -20,6 +20,18 @@ pub enum Error {
     FilesystemOptions(#[from] config::boolean::Error),
 }
 
+impl crate::error::ErrorCode for Error {
+    fn code(&self) -> crate::error::Code {
+        use crate::error::Code;
+        match self {
+            Error::MissinWorkDir => Code::WorktreeRequired,
+            Error::Walk(_) | Error::Prefix(_) => Code::Io,
+            Error::Excludes(_) | Error::FilesystemOptions(_) => Code::Configuration,
+            Error::Pathspec(_) => Code::InvalidInput,
+        }
+    }
+}
+
 impl Repository {
     /// Return default options suitable for performing a directory walk on this repository.
     ///
@@ -85,6 +97,7 @@ impl Repository {
                 },
                 excludes: Some(&mut excludes),
                 objects: &self.objects,
+                untracked_cache: None,
             },
             options.into(),
             delegate,
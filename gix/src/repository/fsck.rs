@@ -0,0 +1,249 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_features::progress::{Count, DynNestedProgress, Progress};
+use gix_hash::ObjectId;
+use gix_object::{Exists, Find, Kind, ObjectRef};
+
+use crate::{
+    fsck::{Error, Finding, Options, Outcome},
+    Repository,
+};
+
+impl Repository {
+    /// Check all objects of this repository and its alternates for integrity, and, depending on `options`, check that all objects
+    /// and references are connected, similar to `git fsck`.
+    ///
+    /// Each object is read and parsed strictly, and problems with objects or references are reported as [findings](Finding)
+    /// instead of aborting the check.
+    /// Parents of commits at the boundary of a shallow repository are expected to be missing and are not reported.
+    /// `progress` and `should_interrupt` are used to observe and abort the operation.
+    pub fn fsck(
+        &self,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::fsck()");
+        let mut outcome = Outcome::default();
+        let shallow_commits = self.shallow_commits()?;
+        let is_shallow_boundary =
+            |id: &ObjectId| shallow_commits.as_ref().map_or(false, |commits| commits.contains(id));
+
+        let mut kinds = gix_hashtable::HashMap::<ObjectId, Kind>::default();
+        let mut edges = gix_hashtable::HashMap::<ObjectId, Vec<(ObjectId, Kind)>>::default();
+        {
+            let mut progress = progress.add_child("checking objects".into());
+            progress.init(None, gix_features::progress::count("objects"));
+            let mut buf = Vec::new();
+            for id in self.objects.store_ref().iter()? {
+                let id = id?;
+                if kinds.contains_key(&id) {
+                    continue;
+                }
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                progress.inc();
+                let object = match self.objects.try_find(&id, &mut buf) {
+                    Ok(Some(object)) => object,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        outcome.findings.push(Finding::Unreadable { id, source: err });
+                        continue;
+                    }
+                };
+                kinds.insert(id, object.kind);
+                if options.verify_hashes {
+                    let actual = gix_object::compute_hash(id.kind(), object.kind, object.data);
+                    if actual != id {
+                        outcome.findings.push(Finding::HashMismatch {
+                            id,
+                            kind: object.kind,
+                            actual,
+                        });
+                    }
+                }
+                let object = match ObjectRef::from_bytes(object.kind, object.data) {
+                    Ok(object) => object,
+                    Err(err) => {
+                        outcome.findings.push(Finding::Malformed {
+                            id,
+                            kind: object.kind,
+                            source: err,
+                        });
+                        continue;
+                    }
+                };
+                if !options.check_connectivity {
+                    continue;
+                }
+                let mut targets = Vec::new();
+                match object {
+                    ObjectRef::Commit(commit) => {
+                        targets.push((commit.tree(), Kind::Tree));
+                        if !is_shallow_boundary(&id) {
+                            targets.extend(commit.parents().map(|parent| (parent, Kind::Commit)));
+                        }
+                    }
+                    ObjectRef::Tag(tag) => targets.push((tag.target(), tag.target_kind)),
+                    ObjectRef::Tree(tree) => targets.extend(
+                        tree.entries
+                            .iter()
+                            .filter(|entry| !entry.mode.is_commit())
+                            .map(|entry| {
+                                let kind = if entry.mode.is_tree() { Kind::Tree } else { Kind::Blob };
+                                (entry.oid.to_owned(), kind)
+                            }),
+                    ),
+                    ObjectRef::Blob(_) => {}
+                }
+                if !targets.is_empty() {
+                    edges.insert(id, targets);
+                }
+            }
+        }
+        outcome.num_objects = kinds.len();
+        if !options.check_connectivity {
+            return Ok(outcome);
+        }
+
+        let mut referenced = gix_hashtable::HashSet::default();
+        for (referenced_by, targets) in &edges {
+            for (id, expected) in targets {
+                referenced.insert(*id);
+                match kinds.get(id) {
+                    Some(actual) if actual != expected => outcome.findings.push(Finding::WrongKind {
+                        id: *id,
+                        expected: *expected,
+                        actual: *actual,
+                        referenced_by: *referenced_by,
+                    }),
+                    Some(_) => {}
+                    None if self.objects.exists(id) => {}
+                    None => outcome.findings.push(Finding::Missing {
+                        id: *id,
+                        kind: *expected,
+                        referenced_by: *referenced_by,
+                    }),
+                }
+            }
+        }
+
+        let mut next = Vec::new();
+        let mut reflog_names = vec![gix_ref::FullName::try_from("HEAD").expect("valid")];
+        for reference in self.references()?.all()? {
+            let reference = reference.map_err(Error::Reference)?;
+            self.check_reference(&reference.inner, &kinds, &mut outcome.findings)?;
+            next.extend(reference.target().try_id().map(ToOwned::to_owned));
+            reflog_names.push(reference.inner.name);
+        }
+        for name in &reflog_names {
+            add_reflog_ids(self, name.as_ref(), &mut next)?;
+        }
+        self.add_worktree_roots(&kinds, &mut next, &mut outcome.findings)?;
+        for proxy in self.worktrees()? {
+            let repo = proxy.into_repo_with_possibly_inaccessible_worktree()?;
+            add_reflog_ids(&repo, "HEAD".try_into().expect("valid"), &mut next)?;
+            repo.add_worktree_roots(&kinds, &mut next, &mut outcome.findings)?;
+        }
+
+        let mut reachable = gix_hashtable::HashSet::default();
+        while let Some(id) = next.pop() {
+            if !reachable.insert(id) {
+                continue;
+            }
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            if let Some(targets) = edges.get(&id) {
+                next.extend(targets.iter().map(|(id, _)| *id));
+            }
+        }
+
+        let mut unreachable: Vec<_> = kinds.into_iter().filter(|(id, _)| !reachable.contains(id)).collect();
+        unreachable.sort_by_key(|(id, _)| *id);
+        for (id, kind) in unreachable {
+            if !referenced.contains(&id) {
+                outcome.findings.push(Finding::Dangling { id, kind });
+            } else if options.report_unreachable {
+                outcome.findings.push(Finding::Unreachable { id, kind });
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Check that `reference` points to an object or reference that exists, or record a finding.
+    fn check_reference(
+        &self,
+        reference: &gix_ref::Reference,
+        kinds: &gix_hashtable::HashMap<ObjectId, Kind>,
+        findings: &mut Vec<Finding>,
+    ) -> Result<(), Error> {
+        let exists = match &reference.target {
+            gix_ref::Target::Peeled(id) => kinds.contains_key(id) || self.objects.exists(id),
+            gix_ref::Target::Symbolic(name) => self.try_find_reference(name.as_ref())?.is_some(),
+        };
+        if !exists {
+            findings.push(Finding::BrokenReference {
+                name: reference.name.clone(),
+                target: reference.target.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check the `HEAD` of the worktree of this instance, which may be unborn, and add it along with all ids in the index to `out`.
+    fn add_worktree_roots(
+        &self,
+        kinds: &gix_hashtable::HashMap<ObjectId, Kind>,
+        out: &mut Vec<ObjectId>,
+        findings: &mut Vec<Finding>,
+    ) -> Result<(), Error> {
+        fn add_trees(tree: &gix_index::extension::Tree, out: &mut Vec<ObjectId>) {
+            if tree.num_entries.is_some() {
+                out.push(tree.id);
+            }
+            for child in &tree.children {
+                add_trees(child, out);
+            }
+        }
+
+        if let Some(head) = self.try_find_reference("HEAD")? {
+            if let gix_ref::Target::Peeled(_) = head.inner.target {
+                self.check_reference(&head.inner, kinds, findings)?;
+            }
+        }
+        out.extend(self.head_id().ok().map(crate::Id::detach));
+        if let Some(index) = self.try_index()? {
+            out.extend(
+                index
+                    .entries()
+                    .iter()
+                    .filter(|entry| entry.mode != gix_index::entry::Mode::COMMIT)
+                    .map(|entry| entry.id),
+            );
+            if let Some(tree) = index.tree() {
+                add_trees(tree, out);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Add the ids in the reference log of `name` to `out`.
+fn add_reflog_ids(repo: &Repository, name: &gix_ref::FullNameRef, out: &mut Vec<ObjectId>) -> Result<(), Error> {
+    let data = match std::fs::read(repo.refs.reflog_path(name)) {
+        Ok(data) => data,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+    for line in gix_ref::file::log::iter::forward(&data) {
+        let line = line?;
+        out.extend(
+            [line.previous_oid(), line.new_oid()]
+                .into_iter()
+                .filter(|id| !id.is_null()),
+        );
+    }
+    Ok(())
+}
@@ -32,3 +32,320 @@ impl crate::Repository {
         gix_commitgraph::at(self.objects.store_ref().path().join("info"))
     }
 }
+
+mod write {
+    use std::{
+        collections::HashSet,
+        io::Write,
+        path::Path,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    use gix_commitgraph::write;
+    use gix_features::progress::{Count, DynNestedProgress, Progress};
+    use gix_hash::ObjectId;
+    use gix_object::FindExt;
+
+    use crate::{
+        commit_graph::{Error, Options, Outcome},
+        Repository,
+    };
+
+    /// The name of the file listing all layers of a split commit-graph.
+    const CHAIN_FILE_NAME: &str = "commit-graph-chain";
+
+    impl Repository {
+        /// Write a commit-graph with all commits reachable from references, or add a new layer with all commits that aren't
+        /// contained yet if [`Options::split`] is set, similar to `git commit-graph write --reachable [--split] [--changed-paths]`.
+        ///
+        /// Besides the topological levels of commits, their corrected commit dates, also known as generation numbers v2, are
+        /// written as well, along with bloom filters for their changed paths if [`Options::changed_paths`] is set.
+        /// Files and layers that aren't used anymore are removed once the new commit-graph is in place.
+        ///
+        /// `progress` and `should_interrupt` are used to observe and abort the operation.
+        pub fn write_commit_graph(
+            &self,
+            progress: &mut dyn DynNestedProgress,
+            should_interrupt: &AtomicBool,
+            options: Options,
+        ) -> Result<Outcome, Error> {
+            if self.is_shallow() {
+                return Err(Error::Shallow);
+            }
+            let info_dir = self.objects.store_ref().path().join("info");
+            let chain_dir = info_dir.join("commit-graphs");
+            let mut commits = self.reachable_commits(progress, should_interrupt)?;
+
+            let mut layers = if options.split {
+                read_layers(&chain_dir)
+            } else {
+                Vec::new()
+            };
+            let mut num_merged_layers = 0;
+            loop {
+                let num_new_commits = commits
+                    .iter()
+                    .filter(|c| layers.iter().all(|layer| layer.lookup(c.id).is_none()))
+                    .count();
+                match layers.last() {
+                    Some(top)
+                        if num_new_commits as u64 * u64::from(options.size_multiple.max(1))
+                            >= u64::from(top.num_commits()) =>
+                    {
+                        let top = layers.pop().expect("present");
+                        // Unreachable commits of merged layers are kept, just like `git` does.
+                        let known: HashSet<_> = commits.iter().map(|c| c.id).collect();
+                        let mut buf = Vec::new();
+                        for id in top.iter_ids().filter(|id| !known.contains(*id)) {
+                            commits.push(commit_to_write(self, id.to_owned(), &mut buf)?);
+                        }
+                        num_merged_layers += 1;
+                    }
+                    _ => break,
+                }
+            }
+            commits.retain(|c| layers.iter().all(|layer| layer.lookup(c.id).is_none()));
+            if commits.is_empty() && num_merged_layers == 0 {
+                return Ok(Outcome {
+                    path: None,
+                    num_commits: 0,
+                    num_layers: layers.len(),
+                });
+            }
+
+            if options.changed_paths {
+                self.add_changed_paths(&mut commits, progress, should_interrupt)?;
+            }
+            let write_options = write::Options {
+                object_hash: self.object_hash(),
+                generation_data: true,
+                changed_paths: options.changed_paths.then(Default::default),
+            };
+            let base = (!layers.is_empty())
+                .then(|| gix_commitgraph::Graph::new(layers))
+                .transpose()?;
+
+            let single_file_path = info_dir.join("commit-graph");
+            let chain_path = chain_dir.join(CHAIN_FILE_NAME);
+            if !options.split {
+                let mut file = gix_lock::File::acquire_to_update_resource(
+                    &single_file_path,
+                    gix_lock::acquire::Fail::Immediately,
+                    None,
+                )?;
+                let outcome = {
+                    let mut out = std::io::BufWriter::new(&mut file);
+                    let outcome = write::write_to(commits, None, &mut out, write_options)?;
+                    out.flush()?;
+                    outcome
+                };
+                file.commit().map_err(|err| err.error)?;
+                remove_if_present(&chain_path)?;
+                remove_unused_layers(&chain_dir, &[])?;
+                return Ok(Outcome {
+                    path: Some(single_file_path),
+                    num_commits: outcome.num_commits,
+                    num_layers: 1,
+                });
+            }
+
+            std::fs::create_dir_all(&chain_dir)?;
+            let mut file = gix_tempfile::new(
+                &chain_dir,
+                gix_tempfile::ContainingDirectory::Exists,
+                gix_tempfile::AutoRemove::Tempfile,
+            )?;
+            let outcome = {
+                let mut out = std::io::BufWriter::new(&mut file);
+                let outcome = write::write_to(commits, base.as_ref(), &mut out, write_options)?;
+                out.flush()?;
+                outcome
+            };
+            let layer_path = chain_dir.join(layer_file_name(&outcome.checksum));
+            file.persist(&layer_path).map_err(|err| err.error)?;
+
+            let mut checksums: Vec<_> = base
+                .as_ref()
+                .map(|base| base.files().iter().map(|f| f.checksum().to_owned()).collect())
+                .unwrap_or_default();
+            checksums.push(outcome.checksum);
+            let mut chain =
+                gix_lock::File::acquire_to_update_resource(&chain_path, gix_lock::acquire::Fail::Immediately, None)?;
+            for checksum in &checksums {
+                writeln!(chain, "{checksum}")?;
+            }
+            chain.commit().map_err(|err| err.error)?;
+            // A single commit-graph file would take precedence over the chain.
+            remove_if_present(&single_file_path)?;
+            remove_unused_layers(&chain_dir, &checksums)?;
+            Ok(Outcome {
+                path: Some(layer_path),
+                num_commits: outcome.num_commits,
+                num_layers: checksums.len(),
+            })
+        }
+
+        /// Collect all commits reachable from references and `HEAD`.
+        fn reachable_commits(
+            &self,
+            progress: &mut dyn DynNestedProgress,
+            should_interrupt: &AtomicBool,
+        ) -> Result<Vec<write::Commit>, Error> {
+            let mut progress = progress.add_child("collecting commits".into());
+            progress.init(None, gix_features::progress::count("commits"));
+
+            let mut tips = Vec::new();
+            tips.extend(self.head_id().ok().map(crate::Id::detach));
+            for reference in self.references()?.all()? {
+                let mut reference = reference.map_err(Error::Reference)?;
+                tips.push(reference.peel_to_id_in_place()?.detach());
+            }
+
+            let mut buf = Vec::new();
+            let mut seen = gix_hashtable::HashSet::default();
+            let mut commits = Vec::new();
+            let mut next = Vec::new();
+            for tip in tips {
+                if !seen.insert(tip) {
+                    continue;
+                }
+                match commit_to_write(self, tip, &mut buf) {
+                    Ok(commit) => next.push(commit),
+                    // References may point to trees or blobs.
+                    Err(Error::FindCommit(gix_object::find::existing_object::Error::ObjectKind { .. })) => continue,
+                    Err(err) => return Err(err),
+                }
+                while let Some(commit) = next.pop() {
+                    if should_interrupt.load(Ordering::Relaxed) {
+                        return Err(Error::Interrupted);
+                    }
+                    for parent in &commit.parents {
+                        if seen.insert(*parent) {
+                            next.push(commit_to_write(self, *parent, &mut buf)?);
+                        }
+                    }
+                    commits.push(commit);
+                    progress.inc();
+                }
+            }
+            Ok(commits)
+        }
+
+        /// Compute the paths that each of `commits` changed compared to its first parent.
+        fn add_changed_paths(
+            &self,
+            commits: &mut [write::Commit],
+            progress: &mut dyn DynNestedProgress,
+            should_interrupt: &AtomicBool,
+        ) -> Result<(), Error> {
+            let mut progress = progress.add_child("computing changed paths".into());
+            progress.init(Some(commits.len()), gix_features::progress::count("commits"));
+
+            let trees: gix_hashtable::HashMap<_, _> = commits.iter().map(|c| (c.id, c.tree)).collect();
+            let (mut buf, mut parent_buf, mut tree_buf) = (Vec::new(), Vec::new(), Vec::new());
+            let mut state = gix_diff::tree::State::default();
+            for commit in commits.iter_mut() {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                let parent_tree = match commit.parents.first() {
+                    Some(parent) => Some(match trees.get(parent) {
+                        Some(tree) => *tree,
+                        None => self.objects.find_commit(parent, &mut buf)?.tree(),
+                    }),
+                    None => None,
+                };
+                let parent_tree = parent_tree
+                    .map(|tree| self.objects.find_tree_iter(&tree, &mut parent_buf))
+                    .transpose()?;
+                let tree = self.objects.find_tree_iter(&commit.tree, &mut tree_buf)?;
+
+                let mut recorder = gix_diff::tree::Recorder::default();
+                gix_diff::tree::Changes::from(parent_tree).needed_to_obtain(
+                    tree,
+                    &mut state,
+                    &self.objects,
+                    &mut recorder,
+                )?;
+                commit.changed_paths = Some(
+                    recorder
+                        .records
+                        .into_iter()
+                        .filter_map(|change| {
+                            use gix_diff::tree::recorder::Change::*;
+                            let (mode, path) = match change {
+                                Addition { entry_mode, path, .. }
+                                | Deletion { entry_mode, path, .. }
+                                | Modification { entry_mode, path, .. } => (entry_mode, path),
+                            };
+                            // Leading directories are added automatically.
+                            (!mode.is_tree()).then_some(path)
+                        })
+                        .collect(),
+                );
+                progress.inc();
+            }
+            Ok(())
+        }
+    }
+
+    /// Read the commit `id` in `repo` for writing it into a commit-graph, using `buf` as buffer.
+    fn commit_to_write(repo: &Repository, id: ObjectId, buf: &mut Vec<u8>) -> Result<write::Commit, Error> {
+        let commit = repo.objects.find_commit(&id, buf)?;
+        Ok(write::Commit {
+            id,
+            tree: commit.tree(),
+            parents: commit.parents().collect(),
+            committer_timestamp: commit.committer.time.seconds.max(0) as u64,
+            changed_paths: None,
+        })
+    }
+
+    /// Return all layers of the split commit-graph in `chain_dir`, or no layer if there is none or if any of them can't be read.
+    fn read_layers(chain_dir: &Path) -> Vec<gix_commitgraph::File> {
+        gix_commitgraph::Graph::from_commit_graphs_dir(chain_dir)
+            .ok()
+            .map(|graph| {
+                graph
+                    .files()
+                    .iter()
+                    .filter_map(|file| gix_commitgraph::File::at(file.path()).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn layer_file_name(checksum: &gix_hash::oid) -> String {
+        format!("graph-{checksum}.graph")
+    }
+
+    /// Remove all layers of a split commit-graph in `chain_dir` whose checksum isn't in `checksums`.
+    fn remove_unused_layers(chain_dir: &Path, checksums: &[ObjectId]) -> std::io::Result<()> {
+        let entries = match std::fs::read_dir(chain_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        let used: Vec<_> = checksums.iter().map(|checksum| layer_file_name(checksum)).collect();
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else { continue };
+            if name.starts_with("graph-") && name.ends_with(".graph") && !used.iter().any(|used| used == name) {
+                remove_if_present(&entry.path())?;
+            }
+        }
+        if checksums.is_empty() {
+            // Other processes might be writing into it, which is fine.
+            std::fs::remove_dir(chain_dir).ok();
+        }
+        Ok(())
+    }
+
+    fn remove_if_present(path: &Path) -> std::io::Result<()> {
+        match std::fs::remove_file(path) {
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            res => res,
+        }
+    }
+}
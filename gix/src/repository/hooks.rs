@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+
+use crate::{bstr::BString, config::tree::Core, hooks, Repository};
+
+/// Hooks
+impl Repository {
+    /// Return the directory in which hooks are looked up, which is `core.hooksPath` if set, or the `hooks` directory
+    /// of the repository.
+    ///
+    /// A relative `core.hooksPath` is relative to the root of the worktree, or to the git directory of bare repositories,
+    /// just like in `git`.
+    pub fn hooks_dir(&self) -> Result<PathBuf, hooks::Error> {
+        Ok(
+            match self
+                .config
+                .trusted_file_path("core", None, Core::HOOKS_PATH.name)
+                .transpose()?
+            {
+                Some(path) => self.hooks_current_dir().join(path),
+                None => self.common_dir().join("hooks"),
+            },
+        )
+    }
+
+    /// Return the hook with the given `name`, like `pre-commit`, ready to be run, or `None` if there is no such hook,
+    /// if it isn't executable or if the repository [isn't fully trusted](Self::git_dir_trust()).
+    pub fn hook(&self, name: &str) -> Result<Option<hooks::Prepare>, hooks::Error> {
+        if self.git_dir_trust() != gix_sec::Trust::Full {
+            gix_trace::debug!("Not running {name} hook as the repository isn't fully trusted");
+            return Ok(None);
+        }
+        let path = self.hooks_dir()?.join(name);
+        let is_executable = match path.metadata() {
+            Ok(metadata) => metadata.is_file() && (cfg!(windows) || gix_fs::is_executable(&metadata)),
+            Err(_) => false,
+        };
+        if !is_executable {
+            return Ok(None);
+        }
+        let git_dir = gix_path::realpath(self.git_dir()).unwrap_or_else(|_| self.git_dir().to_owned());
+        Ok(Some(hooks::Prepare {
+            name: name.to_owned(),
+            path,
+            current_dir: self.hooks_current_dir().to_owned(),
+            args: Vec::new(),
+            env: vec![("GIT_DIR".into(), git_dir.into())],
+            stdin: None,
+        }))
+    }
+
+    /// Run the hook with the given `name` and `args` if it exists, and fail if it didn't succeed.
+    ///
+    /// Return `true` if the hook existed and was run.
+    pub fn run_hook(
+        &self,
+        name: &str,
+        args: impl IntoIterator<Item = impl Into<std::ffi::OsString>>,
+    ) -> Result<bool, hooks::Error> {
+        match self.hook(name)? {
+            Some(hook) => hook.args(args).run().map(|()| true),
+            None => Ok(false),
+        }
+    }
+
+    fn hooks_current_dir(&self) -> &std::path::Path {
+        self.work_dir().unwrap_or(self.git_dir())
+    }
+}
+
+/// Hooks that are run as part of other operations, if enabled.
+impl Repository {
+    pub(crate) fn hooks_enabled(&self) -> bool {
+        self.options.run_hooks
+    }
+
+    /// Run the hooks that can prevent a commit with `message` and `num_parents` from being created, and return the
+    /// message as possibly changed by them.
+    pub(crate) fn run_pre_commit_hooks(&self, message: BString, num_parents: usize) -> Result<BString, hooks::Error> {
+        let is_merge = num_parents > 1;
+        let with_commit_env =
+            |hook: hooks::Prepare| hook.env("GIT_INDEX_FILE", self.index_path()).env("GIT_EDITOR", ":");
+        if let Some(hook) = self.hook(if is_merge { "pre-merge-commit" } else { "pre-commit" })? {
+            with_commit_env(hook).run()?;
+        }
+
+        let prepare_message = self.hook("prepare-commit-msg")?;
+        let check_message = self.hook("commit-msg")?;
+        if prepare_message.is_none() && check_message.is_none() {
+            return Ok(message);
+        }
+        let message_path = self.git_dir().join("COMMIT_EDITMSG");
+        let io_err = |source| hooks::Error::CommitMessage {
+            path: message_path.clone(),
+            source,
+        };
+        std::fs::write(&message_path, &message).map_err(io_err)?;
+        if let Some(hook) = prepare_message {
+            with_commit_env(hook)
+                .arg(&message_path)
+                .arg(if is_merge { "merge" } else { "message" })
+                .run()?;
+        }
+        if let Some(hook) = check_message {
+            with_commit_env(hook).arg(&message_path).run()?;
+        }
+        Ok(std::fs::read(&message_path).map_err(io_err)?.into())
+    }
+
+    /// Run the `post-commit` hook, ignoring its failure.
+    pub(crate) fn run_post_commit_hook(&self) {
+        let res = self.hook("post-commit").and_then(|hook| {
+            hook.map(|hook| hook.env("GIT_INDEX_FILE", self.index_path()).run())
+                .transpose()
+        });
+        if let Err(_err) = res {
+            gix_trace::warn!("Ignoring failure of post-commit hook: {_err}");
+        }
+    }
+
+    /// Run the `post-checkout` hook after `HEAD` was changed from `previous` to `new`, ignoring its failure as the
+    /// checkout was performed already.
+    #[cfg(feature = "checkout")]
+    pub(crate) fn run_post_checkout_hook(&self, previous: Option<gix_hash::ObjectId>, new: gix_hash::ObjectId) {
+        let previous = previous.unwrap_or_else(|| gix_hash::ObjectId::null(self.object_hash()));
+        let res = self.hook("post-checkout").and_then(|hook| {
+            hook.map(|hook| hook.args([previous.to_string(), new.to_string(), "1".into()]).run())
+                .transpose()
+        });
+        if let Err(_err) = res {
+            gix_trace::warn!("Ignoring failure of post-checkout hook: {_err}");
+        }
+    }
+
+    /// Run the `post-merge` hook after a merge was committed, ignoring its failure.
+    #[cfg(feature = "merge")]
+    pub(crate) fn run_post_merge_hook(&self) {
+        let res = self
+            .hook("post-merge")
+            .and_then(|hook| hook.map(|hook| hook.arg("0").run()).transpose());
+        if let Err(_err) = res {
+            gix_trace::warn!("Ignoring failure of post-merge hook: {_err}");
+        }
+    }
+
+    /// Run the `reference-transaction` hook for `edits` that were committed, ignoring its failure.
+    #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+    pub(crate) fn run_reference_transaction_committed_hook(&self, edits: &[gix_ref::transaction::RefEdit]) {
+        use crate::bstr::ByteVec;
+
+        if edits.is_empty() {
+            return;
+        }
+        let res = self.hook("reference-transaction").and_then(|hook| {
+            let Some(hook) = hook else { return Ok(()) };
+            let null = gix_hash::ObjectId::null(self.object_hash());
+            let format = |target: Option<gix_ref::TargetRef<'_>>| -> BString {
+                match target {
+                    Some(gix_ref::TargetRef::Peeled(id)) => id.to_string().into(),
+                    Some(gix_ref::TargetRef::Symbolic(name)) => {
+                        let mut out = BString::from("ref:");
+                        out.push_str(name.as_bstr());
+                        out
+                    }
+                    None => null.to_string().into(),
+                }
+            };
+            let mut input = BString::default();
+            for edit in edits {
+                input.push_str(format(edit.change.previous_value()));
+                input.push_byte(b' ');
+                input.push_str(format(edit.change.new_value()));
+                input.push_byte(b' ');
+                input.push_str(edit.name.as_bstr());
+                input.push_byte(b'\n');
+            }
+            hook.arg("committed").stdin(input).status().map(|_ignored| ())
+        });
+        if let Err(_err) = res {
+            gix_trace::warn!("Ignoring failure of reference-transaction hook: {_err}");
+        }
+    }
+}
@@ -1,25 +1,82 @@
 use std::time::SystemTime;
 
 use crate::{
-    bstr::BString,
+    bstr::{BString, ByteSlice},
     config,
     config::tree::{gitoxide, keys, Author, Committer, Key, User},
 };
 
+/// Where a component of an identity was obtained from, as reported by [`Repository::committer_ident()`](crate::Repository::committer_ident()).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Source {
+    /// The `GIT_(COMMITTER|AUTHOR)_(NAME|EMAIL|DATE)` environment variables.
+    Environment,
+    /// The `EMAIL` environment variable, which is only used for email addresses.
+    EmailEnvironment,
+    /// Git configuration, like `committer.name`, `author.email` or `user.name`.
+    Configuration,
+    /// The fallbacks in the `gitoxide` section, like `gitoxide.committer.nameFallback`, typically set by the application.
+    Fallback,
+    /// The system, which provides the full name of the current user, along with the login name and host name
+    /// to form an email address.
+    System,
+    /// The current time, which is only used for dates.
+    Now,
+}
+
+/// A fully resolved identity along with information about where each of its components came from.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Outcome {
+    /// The signature to use.
+    pub signature: gix_actor::Signature,
+    /// Where the name of the `signature` came from.
+    pub name: Source,
+    /// Where the email of the `signature` came from.
+    pub email: Source,
+    /// Where the time of the `signature` came from.
+    pub time: Source,
+}
+
+/// The error returned by [`Repository::committer_ident()`](crate::Repository::committer_ident()) and
+/// [`Repository::author_ident()`](crate::Repository::author_ident()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("no email was given and auto-detection is disabled")]
+    EmailAutoDetectionDisabled,
+    #[error("unable to auto-detect email address (got '{email}')")]
+    BogusEmail { email: BString },
+    #[error("no name was given and auto-detection is disabled")]
+    NameAutoDetectionDisabled,
+    #[error("unable to auto-detect name (got '{name}')")]
+    BogusName { name: BString },
+    #[error("empty ident name (for <{email}>) not allowed")]
+    EmptyName { email: BString },
+    #[error(transparent)]
+    UseConfigOnly(#[from] config::boolean::Error),
+    #[error(transparent)]
+    Time(#[from] config::time::Error),
+}
+
 /// Identity handling.
 ///
 /// # Deviation
 ///
-/// There is no notion of a default user like in git, and instead failing to provide a user
-/// is fatal. That way, we enforce correctness and force application developers to take care
-/// of this issue which can be done in various ways, for instance by setting
+/// [`committer()`](Self::committer()) and [`author()`](Self::author()) have no notion of a default user like in git,
+/// and instead failing to provide a user is fatal. That way, we enforce correctness and force application developers
+/// to take care of this issue which can be done in various ways, for instance by setting
 /// `gitoxide.committer.nameFallback` and similar.
+///
+/// Use [`committer_ident()`](Self::committer_ident()) and [`author_ident()`](Self::author_ident()) to resolve
+/// identities like `git var GIT_COMMITTER_IDENT` would, including auto-detection from the system.
 impl crate::Repository {
     /// Return the committer as configured by this repository, which is determined by…
     ///
-    /// * …the git configuration `committer.name|email`…
     /// * …the `GIT_COMMITTER_(NAME|EMAIL|DATE)` environment variables…
-    /// * …the configuration for `user.name|email` as fallback…
+    /// * …the git configuration `committer.name|email`…
+    /// * …the configuration for `user.name|email`…
+    /// * …the `EMAIL` environment variable for the email…
+    /// * …the `gitoxide.committer.nameFallback|emailFallback` configuration as fallback…
     ///
     /// …and in that order, or `None` if no committer name or email was configured, or `Some(Err(…))`
     /// if the committer date could not be parsed.
@@ -31,14 +88,12 @@ impl crate::Repository {
         let p = self.config.personas();
 
         Ok(gix_actor::SignatureRef {
-            name: p.committer.name.as_ref().or(p.user.name.as_ref()).map(AsRef::as_ref)?,
-            email: p
-                .committer
-                .email
-                .as_ref()
-                .or(p.user.email.as_ref())
-                .map(AsRef::as_ref)?,
-            time: match extract_time_or_default(p.committer.time.as_ref(), &gitoxide::Commit::COMMITTER_DATE) {
+            name: p.committer.name.as_ref().map(|(name, _)| name.as_ref())?,
+            email: p.committer.email.as_ref().map(|(email, _)| email.as_ref())?,
+            time: match extract_time_or_default(
+                p.committer.time.as_ref().map(|(time, _)| time),
+                &gitoxide::Commit::COMMITTER_DATE,
+            ) {
                 Ok(t) => t,
                 Err(err) => return Some(Err(err)),
             },
@@ -48,9 +103,11 @@ impl crate::Repository {
 
     /// Return the author as configured by this repository, which is determined by…
     ///
-    /// * …the git configuration `author.name|email`…
     /// * …the `GIT_AUTHOR_(NAME|EMAIL|DATE)` environment variables…
-    /// * …the configuration for `user.name|email` as fallback…
+    /// * …the git configuration `author.name|email`…
+    /// * …the configuration for `user.name|email`…
+    /// * …the `EMAIL` environment variable for the email…
+    /// * …the `gitoxide.author.nameFallback|emailFallback` configuration as fallback…
     ///
     /// …and in that order, or `None` if there was nothing configured.
     ///
@@ -61,15 +118,98 @@ impl crate::Repository {
         let p = self.config.personas();
 
         Ok(gix_actor::SignatureRef {
-            name: p.author.name.as_ref().or(p.user.name.as_ref()).map(AsRef::as_ref)?,
-            email: p.author.email.as_ref().or(p.user.email.as_ref()).map(AsRef::as_ref)?,
-            time: match extract_time_or_default(p.author.time.as_ref(), &gitoxide::Commit::AUTHOR_DATE) {
+            name: p.author.name.as_ref().map(|(name, _)| name.as_ref())?,
+            email: p.author.email.as_ref().map(|(email, _)| email.as_ref())?,
+            time: match extract_time_or_default(
+                p.author.time.as_ref().map(|(time, _)| time),
+                &gitoxide::Commit::AUTHOR_DATE,
+            ) {
                 Ok(t) => t,
                 Err(err) => return Some(Err(err)),
             },
         })
         .into()
     }
+
+    /// Resolve the committer like [`committer()`](Self::committer()), but auto-detect missing values from the system
+    /// like `git var GIT_COMMITTER_IDENT` does, and report where each component came from.
+    ///
+    /// The name defaults to the full name of the current user, and the email to `<login>@<hostname>`.
+    /// If `user.useConfigOnly` is set, auto-detection and the `EMAIL` environment variable are disabled, and an error
+    /// is returned instead.
+    ///
+    /// # Deviation
+    ///
+    /// Host names without a domain aren't resolved to their canonical name, but are suffixed with `.(none)`
+    /// which makes them fail as bogus.
+    pub fn committer_ident(&self) -> Result<Outcome, Error> {
+        let p = self.config.personas();
+        self.resolve_ident(&p.committer, &gitoxide::Commit::COMMITTER_DATE)
+    }
+
+    /// Resolve the author like [`author()`](Self::author()), but auto-detect missing values from the system
+    /// like `git var GIT_AUTHOR_IDENT` does, and report where each component came from.
+    ///
+    /// See [`committer_ident()`](Self::committer_ident()) for details.
+    pub fn author_ident(&self) -> Result<Outcome, Error> {
+        let p = self.config.personas();
+        self.resolve_ident(&p.author, &gitoxide::Commit::AUTHOR_DATE)
+    }
+
+    fn resolve_ident(&self, entity: &Entity, date_key: &'static keys::Time) -> Result<Outcome, Error> {
+        let use_config_only = self
+            .config
+            .apply_leniency(
+                self.config
+                    .resolved
+                    .boolean_by_key("user.useConfigOnly")
+                    .map(|v| User::USE_CONFIG_ONLY.enrich_error(v)),
+            )?
+            .unwrap_or(false);
+        let mut account = None;
+
+        let (email, email_source) = match &entity.email {
+            Some((_, Source::EmailEnvironment)) if use_config_only => return Err(Error::EmailAutoDetectionDisabled),
+            Some((email, source)) => (email.clone(), *source),
+            None if use_config_only => return Err(Error::EmailAutoDetectionDisabled),
+            None => {
+                let login = account
+                    .get_or_insert_with(system::account)
+                    .as_ref()
+                    .map_or_else(|| "unknown".into(), |a| a.login.clone());
+                let (email, is_bogus) = system::email(login);
+                if is_bogus {
+                    return Err(Error::BogusEmail { email });
+                }
+                (email, Source::System)
+            }
+        };
+        let (name, name_source) = match &entity.name {
+            Some((name, source)) => (name.clone(), *source),
+            None if use_config_only => return Err(Error::NameAutoDetectionDisabled),
+            None => match account.get_or_insert_with(system::account) {
+                Some(account) => (account.full_name(), Source::System),
+                None => return Err(Error::BogusName { name: "Unknown".into() }),
+            },
+        };
+        if name.trim().is_empty() {
+            return Err(Error::EmptyName { email });
+        }
+        let (time, time_source) = match &entity.time {
+            Some((time, source)) => (extract_time_or_default(Some(time), date_key)?, *source),
+            None => (gix_date::Time::now_local_or_utc(), Source::Now),
+        };
+        Ok(Outcome {
+            signature: gix_actor::Signature {
+                name: name.trim().into(),
+                email,
+                time,
+            },
+            name: name_source,
+            email: email_source,
+            time: time_source,
+        })
+    }
 }
 
 fn extract_time_or_default(
@@ -83,92 +223,210 @@ fn extract_time_or_default(
     }
 }
 
+/// Information about the user and host as provided by the system.
+mod system {
+    use crate::bstr::{BString, ByteSlice, ByteVec};
+
+    pub struct Account {
+        pub login: BString,
+        gecos: Option<BString>,
+    }
+
+    impl Account {
+        /// Return the full name of the user like git does, which is the first field of the gecos entry with
+        /// `&` substituted by the capitalized login name.
+        pub fn full_name(&self) -> BString {
+            let Some(gecos) = self.gecos.as_ref() else {
+                return self.login.clone();
+            };
+            let mut out = BString::default();
+            for &b in gecos.split_str(",").next().unwrap_or_default() {
+                if b == b'&' {
+                    let mut login = self.login.iter();
+                    out.extend(login.next().map(u8::to_ascii_uppercase));
+                    out.extend(login);
+                } else {
+                    out.push(b);
+                }
+            }
+            out
+        }
+    }
+
+    /// Return the account of the current user, or `None` if it couldn't be determined.
+    pub fn account() -> Option<Account> {
+        let login = std::env::var_os("USER")
+            .or_else(|| std::env::var_os("LOGNAME"))
+            .or_else(|| std::env::var_os("USERNAME"))
+            .and_then(|login| gix_path::os_string_into_bstring(login).ok())
+            .filter(|login| !login.is_empty());
+        let passwd = std::fs::read("/etc/passwd").unwrap_or_default();
+        let mut entries = passwd.lines().filter_map(|line| {
+            let mut fields = line.splitn_str(6, ":");
+            let login = fields.next()?;
+            let uid = fields.nth(1)?;
+            let gecos = fields.nth(1)?;
+            Some((login, uid, gecos))
+        });
+        let entry = match login.as_ref() {
+            Some(login) => entries.find(|(name, _, _)| *name == login.as_slice()),
+            None => {
+                let uid = current_uid()?;
+                entries.find(|(_, entry_uid, _)| entry_uid.to_str().ok() == Some(uid.as_str()))
+            }
+        };
+        match entry {
+            Some((login, _, gecos)) => Some(Account {
+                login: login.into(),
+                gecos: Some(gecos.into()),
+            }),
+            None => Some(Account {
+                login: login?,
+                gecos: None,
+            }),
+        }
+    }
+
+    #[cfg(unix)]
+    fn current_uid() -> Option<String> {
+        use std::os::unix::fs::MetadataExt;
+        std::fs::metadata("/proc/self").ok().map(|m| m.uid().to_string())
+    }
+
+    #[cfg(not(unix))]
+    fn current_uid() -> Option<String> {
+        None
+    }
+
+    /// Return `<login>@<hostname>` along with `true` if the email is bogus as the host name has no domain.
+    pub fn email(mut login: BString) -> (BString, bool) {
+        login.push_byte(b'@');
+        match hostname() {
+            Some(host) => {
+                login.push_str(&host);
+                let is_bogus = !host.contains('.');
+                if is_bogus {
+                    login.push_str(".(none)");
+                }
+                (login, is_bogus)
+            }
+            None => {
+                login.push_str("(none)");
+                (login, true)
+            }
+        }
+    }
+
+    fn hostname() -> Option<String> {
+        ["/proc/sys/kernel/hostname", "/etc/hostname"]
+            .iter()
+            .find_map(|path| std::fs::read_to_string(path).ok())
+            .or_else(|| std::env::var("HOSTNAME").ok())
+            .or_else(|| std::env::var("COMPUTERNAME").ok())
+            .map(|host| host.trim().to_owned())
+            .filter(|host| !host.is_empty())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Entity {
-    pub name: Option<BString>,
-    pub email: Option<BString>,
-    /// A time parsed from an environment variable, handling potential errors is delayed.
-    pub time: Option<Result<gix_date::Time, gix_date::parse::Error>>,
+    pub name: Option<(BString, Source)>,
+    pub email: Option<(BString, Source)>,
+    /// A time parsed from an environment variable or configuration, handling potential errors is delayed.
+    pub time: Option<(Result<gix_date::Time, gix_date::parse::Error>, Source)>,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct Personas {
-    user: Entity,
     committer: Entity,
     author: Entity,
 }
 
 impl Personas {
     pub fn from_config_and_env(config: &gix_config::File<'_>) -> Self {
-        fn entity_in_section(
+        fn is_env(meta: &gix_config::file::Metadata) -> bool {
+            meta.source == gix_config::Source::EnvOverride
+        }
+        fn value(
+            config: &gix_config::File<'_>,
+            key: &dyn Key,
+            filter: fn(&gix_config::file::Metadata) -> bool,
+        ) -> Option<BString> {
+            let section = key.section();
+            let (section, subsection) = match section.parent() {
+                Some(parent) => (parent.name(), Some(section.name().into())),
+                None => (section.name(), None),
+            };
+            config
+                .string_filter(section, subsection, key.name(), &mut move |meta| filter(meta))
+                .map(std::borrow::Cow::into_owned)
+        }
+        fn component(
             config: &gix_config::File<'_>,
-            name_key: &keys::Any,
-            email_key: &keys::Any,
-            fallback: Option<(&keys::Any, &keys::Any)>,
-        ) -> (Option<BString>, Option<BString>) {
-            let fallback = fallback.and_then(|(name_key, email_key)| {
-                debug_assert_eq!(name_key.section.name(), email_key.section.name());
-                config
-                    .section("gitoxide", Some(name_key.section.name().into()))
-                    .ok()
-                    .map(|section| (section, name_key, email_key))
-            });
-            (
-                config
-                    .string(name_key.section.name(), None, name_key.name)
-                    .or_else(|| fallback.as_ref().and_then(|(s, name_key, _)| s.value(name_key.name)))
-                    .map(std::borrow::Cow::into_owned),
-                config
-                    .string(email_key.section.name(), None, email_key.name)
-                    .or_else(|| fallback.as_ref().and_then(|(s, _, email_key)| s.value(email_key.name)))
-                    .map(std::borrow::Cow::into_owned),
-            )
+            key: &keys::Any,
+            user_key: &keys::Any,
+            fallback_key: &keys::Any,
+            email_fallback_key: Option<&keys::Any>,
+        ) -> Option<(BString, Source)> {
+            value(config, fallback_key, is_env)
+                .map(|v| (v, Source::Environment))
+                .or_else(|| value(config, key, |_| true).map(|v| (v, Source::Configuration)))
+                .or_else(|| value(config, user_key, |_| true).map(|v| (v, Source::Configuration)))
+                .or_else(|| {
+                    email_fallback_key
+                        .and_then(|key| value(config, key, is_env))
+                        .map(|v| (v, Source::EmailEnvironment))
+                })
+                .or_else(|| value(config, fallback_key, |meta| !is_env(meta)).map(|v| (v, Source::Fallback)))
+                .or_else(|| {
+                    email_fallback_key
+                        .and_then(|key| value(config, key, |meta| !is_env(meta)))
+                        .map(|v| (v, Source::Fallback))
+                })
         }
         let now = SystemTime::now();
-        let parse_date = |key: &str, date: &keys::Time| -> Option<Result<gix_date::Time, gix_date::parse::Error>> {
-            debug_assert_eq!(
-                key,
-                date.logical_name(),
-                "BUG: drift of expected name and actual name of the key (we hardcode it to save an allocation)"
-            );
-            config
-                .string_by_key(key)
-                .map(|time| date.try_into_time(time, now.into()))
+        let parse_date = |key: &keys::Time| -> Option<(Result<gix_date::Time, gix_date::parse::Error>, Source)> {
+            value(config, key, is_env)
+                .map(|time| (time, Source::Environment))
+                .or_else(|| value(config, key, |meta| !is_env(meta)).map(|time| (time, Source::Fallback)))
+                .map(|(time, source)| (key.try_into_time(time.into(), now.into()), source))
         };
+        let email_fallback = Some(&gitoxide::User::EMAIL_FALLBACK);
 
-        let fallback = (
-            &gitoxide::Committer::NAME_FALLBACK,
-            &gitoxide::Committer::EMAIL_FALLBACK,
-        );
-        let (committer_name, committer_email) =
-            entity_in_section(config, &Committer::NAME, &Committer::EMAIL, Some(fallback));
-        let fallback = (&gitoxide::Author::NAME_FALLBACK, &gitoxide::Author::EMAIL_FALLBACK);
-        let (author_name, author_email) = entity_in_section(config, &Author::NAME, &Author::EMAIL, Some(fallback));
-        let (user_name, mut user_email) = entity_in_section(config, &User::NAME, &User::EMAIL, None);
-
-        let committer_date = parse_date("gitoxide.commit.committerDate", &gitoxide::Commit::COMMITTER_DATE);
-        let author_date = parse_date("gitoxide.commit.authorDate", &gitoxide::Commit::AUTHOR_DATE);
-
-        user_email = user_email.or_else(|| {
-            config
-                .string_by_key(gitoxide::User::EMAIL_FALLBACK.logical_name().as_str())
-                .map(std::borrow::Cow::into_owned)
-        });
         Personas {
-            user: Entity {
-                name: user_name,
-                email: user_email,
-                time: None,
-            },
             committer: Entity {
-                name: committer_name,
-                email: committer_email,
-                time: committer_date,
+                name: component(
+                    config,
+                    &Committer::NAME,
+                    &User::NAME,
+                    &gitoxide::Committer::NAME_FALLBACK,
+                    None,
+                ),
+                email: component(
+                    config,
+                    &Committer::EMAIL,
+                    &User::EMAIL,
+                    &gitoxide::Committer::EMAIL_FALLBACK,
+                    email_fallback,
+                ),
+                time: parse_date(&gitoxide::Commit::COMMITTER_DATE),
             },
             author: Entity {
-                name: author_name,
-                email: author_email,
-                time: author_date,
+                name: component(
+                    config,
+                    &Author::NAME,
+                    &User::NAME,
+                    &gitoxide::Author::NAME_FALLBACK,
+                    None,
+                ),
+                email: component(
+                    config,
+                    &Author::EMAIL,
+                    &User::EMAIL,
+                    &gitoxide::Author::EMAIL_FALLBACK,
+                    email_fallback,
+                ),
+                time: parse_date(&gitoxide::Commit::AUTHOR_DATE),
             },
         }
     }
@@ -103,6 +103,100 @@ impl crate::Repository {
         })
     }
 
+    /// Set the size of all racily clean entries in `index` to `0` so they will be checked thoroughly the next time they
+    /// are compared to the worktree, and return the amount of smudged entries.
+    ///
+    /// Racily clean entries are those that were modified at or after the last time `index` was written, and whose worktree file
+    /// still has matching stat information even though its content changed, as determined by re-hashing it after applying filters.
+    /// Call this right before writing `index` to prevent changes from being missed just after staging them, as `git` does.
+    ///
+    /// Nothing is done in bare repositories as they don't have a worktree to compare to.
+    #[cfg(feature = "attributes")]
+    pub fn smudge_racily_clean_entries(
+        &self,
+        index: &mut gix_index::State,
+    ) -> Result<usize, crate::repository::smudge_racily_clean_entries::Error> {
+        use crate::repository::smudge_racily_clean_entries::Error;
+
+        let Some(workdir) = self.work_dir() else {
+            return Ok(0);
+        };
+        let stat_options = self.stat_options()?;
+        let (mut pipeline, _) = self.filter_pipeline(None)?;
+        let mut buf = Vec::new();
+        index.smudge_racily_clean_entries(stat_options, |state, entry| -> Result<bool, Error> {
+            let rela_path = gix_path::from_bstr(entry.path(state));
+            let path = workdir.join(&rela_path);
+            let metadata = match gix_index::fs::Metadata::from_path_no_follow(&path) {
+                Ok(metadata) => metadata,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+                Err(err) => return Err(Error::Io { source: err, path }),
+            };
+            let stat = gix_index::entry::Stat::from_fs(&metadata)?;
+            if !stat.matches(&entry.stat, stat_options) || metadata.is_dir() {
+                return Ok(false);
+            }
+            buf.clear();
+            if entry.mode == gix_index::entry::Mode::SYMLINK && metadata.is_symlink() {
+                let target = std::fs::read_link(&path).map_err(|source| Error::Io {
+                    source,
+                    path: path.clone(),
+                })?;
+                buf.extend_from_slice(&gix_path::into_bstr(target));
+            } else {
+                let file = std::fs::File::open(&path).map_err(|source| Error::Io {
+                    source,
+                    path: path.clone(),
+                })?;
+                std::io::copy(&mut pipeline.convert_to_git(file, &rela_path, state)?, &mut buf)
+                    .map_err(|source| Error::Io { source, path })?;
+            }
+            Ok(gix_object::compute_hash(state.object_hash(), gix_object::Kind::Blob, &buf) != entry.id)
+        })
+    }
+
+    /// Create an index in memory from the tree of the commit `HEAD` points to, or an empty one if `HEAD` is unborn.
+    ///
+    /// Unlike [`index_or_load_from_head()`](Self::index_or_load_from_head()), this ignores the index on disk, which
+    /// makes it suitable for bare repositories and for operations that shouldn't be affected by staged changes.
+    pub fn index_from_head(&self) -> Result<gix_index::State, crate::repository::index_from_head::Error> {
+        if self.head()?.is_unborn() {
+            return Ok(gix_index::State::new(self.object_hash()));
+        }
+        Ok(gix_index::State::from_tree(&self.head_tree_id()?, &self.objects)?)
+    }
+
+    /// Write all entries of `index` as trees into the object database and return the id of the root tree,
+    /// similar to `git write-tree`.
+    ///
+    /// As `index` may be created in memory, this allows to produce trees without a worktree.
+    pub fn write_tree_from_index(
+        &self,
+        index: &gix_index::State,
+    ) -> Result<crate::Id<'_>, gix_index::write_tree::Error<crate::object::write::Error>> {
+        index
+            .write_tree(|tree| self.write_object(tree).map(crate::Id::detach))
+            .map(|id| crate::Id::from_id(id, self))
+    }
+
+    /// Like [`commit(…)`](Self::commit()), but write the tree of the commit from `index` first, which may be
+    /// created in memory, for instance with [`index_from_head()`](Self::index_from_head()), so commits can be
+    /// created in bare repositories as well.
+    pub fn commit_index<Name, E>(
+        &self,
+        reference: Name,
+        message: impl AsRef<str>,
+        index: &gix_index::State,
+        parents: impl IntoIterator<Item = impl Into<gix_hash::ObjectId>>,
+    ) -> Result<crate::Id<'_>, crate::repository::commit_index::Error>
+    where
+        Name: TryInto<gix_ref::FullName, Error = E>,
+        crate::commit::Error: From<E>,
+    {
+        let tree = self.write_tree_from_index(index)?;
+        Ok(self.commit(reference, message, tree, parents)?)
+    }
+
     /// Create new index-file, which would live at the correct location, in memory from the given `tree`.
     ///
     /// Note that this is an expensive operation as it requires recursively traversing the entire tree to unpack it into the index.
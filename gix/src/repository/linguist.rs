@@ -0,0 +1,18 @@
+use crate::{linguist, Repository};
+
+impl Repository {
+    /// Return a [classifier](linguist::Classifier) for paths that uses attributes from the worktree and the index, or from
+    /// `HEAD^{tree}` in bare repositories.
+    pub fn linguist_classifier(&self) -> Result<linguist::Classifier<'_>, linguist::Error> {
+        let attributes = if self.is_bare() {
+            self.attributes_from_head()?
+        } else {
+            let index = self.index_or_empty()?;
+            self.attributes_only(
+                &index,
+                gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            )?
+        };
+        Ok(linguist::Classifier::new(attributes))
+    }
+}
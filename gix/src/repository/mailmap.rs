@@ -2,7 +2,6 @@ use crate::config::tree::{Key, Mailmap};
 use crate::Id;
 
 impl crate::Repository {
-    // TODO: tests
     /// Similar to [`open_mailmap_into()`][crate::Repository::open_mailmap_into()], but ignores all errors and returns at worst
     /// an empty mailmap, e.g. if there is no mailmap or if there were errors loading them.
     ///
@@ -14,7 +13,6 @@ impl crate::Repository {
         out
     }
 
-    // TODO: tests
     /// Try to merge mailmaps from the following locations into `target`:
     ///
     /// - read the `.mailmap` file without following symlinks from the working tree, if present
@@ -22,6 +20,7 @@ impl crate::Repository {
     /// - read the mailmap as configured in `mailmap.blob`, if set.
     /// - read the file as configured by `mailmap.file`, following symlinks, if set.
     ///
+    /// Mappings read later take precedence over earlier ones, just like in `git`, and sources that don't exist are ignored.
     /// Only the first error will be reported, and as many source mailmaps will be merged into `target` as possible.
     /// Parsing errors will be ignored.
     pub fn open_mailmap_into(&self, target: &mut gix_mailmap::Snapshot) -> Result<(), crate::mailmap::load::Error> {
@@ -43,7 +42,9 @@ impl crate::Repository {
                     self.head().ok().and_then(|mut head| {
                         let commit = head.peel_to_commit_in_place().ok()?;
                         let tree = commit.tree().ok()?;
-                        tree.find_entry(".mailmap").map(|e| e.object_id())
+                        tree.find_entry(".mailmap")
+                            .filter(|e| e.mode().is_blob())
+                            .map(|e| e.object_id())
                     })
                 });
             }
@@ -66,8 +67,16 @@ impl crate::Repository {
             }
         }
 
-        if let Some(blob) = blob_id.and_then(|id| self.find_object(id).map_err(|e| err.get_or_insert(e.into())).ok()) {
-            target.merge(gix_mailmap::parse_ignore_errors(&blob.data));
+        if let Some(object) = blob_id.and_then(|id| self.find_object(id).map_err(|e| err.get_or_insert(e.into())).ok())
+        {
+            if object.kind == gix_object::Kind::Blob {
+                target.merge(gix_mailmap::parse_ignore_errors(&object.data));
+            } else {
+                err.get_or_insert(crate::mailmap::load::Error::NotABlob {
+                    id: object.id,
+                    actual: object.kind,
+                });
+            }
         }
 
         let configured_path = self
@@ -75,9 +84,15 @@ impl crate::Repository {
             .trusted_path(Mailmap::FILE.logical_name().as_str())
             .and_then(|res| res.map_err(|e| err.get_or_insert(e.into())).ok());
 
-        if let Some(mut file) =
-            configured_path.and_then(|path| std::fs::File::open(path).map_err(|e| err.get_or_insert(e.into())).ok())
-        {
+        if let Some(mut file) = configured_path.and_then(|path| {
+            std::fs::File::open(path)
+                .map_err(|e| {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        err.get_or_insert(e.into());
+                    }
+                })
+                .ok()
+        }) {
             buf.clear();
             std::io::copy(&mut file, &mut buf)
                 .map_err(|e| err.get_or_insert(e.into()))
@@ -0,0 +1,504 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_odb::store::multi_pack_index::{Error, Options, Outcome};
+
+impl crate::Repository {
+    /// Write a multi-pack index over all packs of this repository to speed up object lookups, or add a layer to its
+    /// incremental multi-pack index if [`Options::incremental`] is set, similar to `git multi-pack-index write`.
+    ///
+    /// `progress` and `should_interrupt` are used to observe and abort the operation.
+    /// Note that the objects of this instance will only pick up the new multi-pack index once they refresh their view on packs.
+    pub fn write_multi_pack_index(
+        &self,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        self.objects
+            .store_ref()
+            .write_multi_pack_index(progress, should_interrupt, options)
+    }
+}
+
+#[cfg(feature = "maintenance")]
+mod tasks {
+    use std::{
+        io::{Seek, SeekFrom},
+        path::{Path, PathBuf},
+        sync::atomic::{AtomicBool, Ordering},
+        time::{Duration, SystemTime},
+    };
+
+    use gix_features::progress::{Count, DynNestedProgress, Progress};
+    use gix_hash::ObjectId;
+    use gix_pack::data::output;
+
+    use crate::{
+        maintenance::{prune, ConsolidatedPack, Error, Options, Outcome},
+        Repository,
+    };
+
+    impl Repository {
+        /// Run the maintenance tasks enabled in `options` to keep this repository fast and small, similar to `git maintenance run`
+        /// or `git gc`, and return information about what was done.
+        ///
+        /// Tasks run one after another, and each of them leaves the repository in a consistent state.
+        /// `progress` and `should_interrupt` are used to observe and abort the operation.
+        /// Note that the objects of this instance will only pick up the changed packs once they refresh their view on packs.
+        pub fn maintenance(
+            &self,
+            progress: &mut dyn DynNestedProgress,
+            should_interrupt: &AtomicBool,
+            options: Options,
+        ) -> Result<Outcome, Error> {
+            let _span = gix_trace::coarse!("gix::Repository::maintenance()");
+            let pack_dir = self.objects.store_ref().path().join("pack");
+            let had_multi_pack_index =
+                pack_dir.join("multi-pack-index").is_file() || gix_pack::multi_index::chain::path(&pack_dir).is_file();
+
+            let mut outcome = Outcome::default();
+            if options.expire_reflogs {
+                outcome.num_expired_reflog_entries = self.expire_all_reflogs(should_interrupt)?;
+            }
+            if options.prune_unreachable_objects {
+                outcome.pruned = Some(self.prune_objects(progress, should_interrupt, self.prune_options()?)?);
+            }
+            if options.consolidate_packs {
+                outcome.consolidated_pack = self.consolidate_packs(&pack_dir, progress, should_interrupt)?;
+            }
+            if options.prune_loose_objects {
+                outcome.num_pruned_loose_objects = self.prune_packed_loose_objects(&pack_dir, should_interrupt)?;
+            }
+            if let Some(commit_graph) = options.commit_graph.filter(|_| !self.is_shallow()) {
+                outcome.commit_graph = Some(self.write_commit_graph(progress, should_interrupt, commit_graph)?);
+            }
+            let multi_pack_index = options
+                .multi_pack_index
+                .or_else(|| (had_multi_pack_index && outcome.consolidated_pack.is_some()).then(Default::default));
+            if let Some(multi_pack_index) = multi_pack_index {
+                outcome.multi_pack_index =
+                    Some(self.write_multi_pack_index(progress, should_interrupt, multi_pack_index)?);
+            }
+            Ok(outcome)
+        }
+
+        /// Return the options for [pruning objects](Self::prune_objects()) as configured by `gc.pruneExpire`, which
+        /// protects unreachable objects for two weeks by default.
+        pub fn prune_options(&self) -> Result<prune::Options, Error> {
+            use crate::config::{cache::util::ApplyLeniency, tree::Gc};
+            const TWO_WEEKS_IN_SECONDS: u64 = 14 * 24 * 60 * 60;
+
+            let now = SystemTime::now();
+            let expire = self
+                .config
+                .resolved
+                .string_by_key("gc.pruneExpire")
+                .map(|value| Gc::PRUNE_EXPIRE.try_into_expiry_date(value, now))
+                .transpose()
+                .with_leniency(self.config.lenient_config)?
+                .unwrap_or_else(|| now.checked_sub(Duration::from_secs(TWO_WEEKS_IN_SECONDS)));
+            Ok(prune::Options {
+                expire,
+                repack: false,
+                dry_run: false,
+            })
+        }
+
+        /// Remove objects that can't be reached from references, their reference logs, `HEAD` or the index of this repository
+        /// and all of its linked worktrees, similar to `git prune` and, if [`prune::Options::repack`] is set, `git repack -a -d`.
+        ///
+        /// Unreachable loose objects are only removed if they are older than [`prune::Options::expire`], and with
+        /// [`prune::Options::dry_run`] set, nothing is removed at all, but the outcome informs about what would be removed.
+        /// Objects of alternate object databases are never removed.
+        /// `progress` and `should_interrupt` are used to observe and abort the operation.
+        pub fn prune_objects(
+            &self,
+            progress: &mut dyn DynNestedProgress,
+            should_interrupt: &AtomicBool,
+            options: prune::Options,
+        ) -> Result<prune::Outcome, Error> {
+            let _span = gix_trace::coarse!("gix::Repository::prune_objects()");
+            let reachable = self.reachable_objects(progress, should_interrupt)?;
+            let loose = gix_odb::loose::Store::at(self.objects.store_ref().path(), self.object_hash());
+            let mut outcome = prune::Outcome::default();
+            let mut reachable_loose_ids = Vec::new();
+            for id in self.loose_object_ids()? {
+                if reachable.contains(&id) {
+                    reachable_loose_ids.push(id);
+                    continue;
+                }
+                let Some(expire) = options.expire else {
+                    continue;
+                };
+                let path = loose.object_path(&id);
+                let modified = match std::fs::metadata(&path).and_then(|m| m.modified()) {
+                    Ok(modified) => modified,
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                    Err(err) => return Err(err.into()),
+                };
+                if modified >= expire {
+                    continue;
+                }
+                if !options.dry_run {
+                    remove_if_present(&path)?;
+                    if let Some(fan_out_dir) = path.parent() {
+                        // It's fine if other objects are still in there.
+                        std::fs::remove_dir(fan_out_dir).ok();
+                    }
+                }
+                outcome.loose_objects.push(id);
+            }
+
+            if options.repack {
+                let pack_dir = self.objects.store_ref().path().join("pack");
+                let (packs, packed_ids) = self.replaceable_packs(&pack_dir)?;
+                outcome.packed_objects = packed_ids
+                    .iter()
+                    .filter(|id| !reachable.contains(*id))
+                    .copied()
+                    .collect();
+                outcome.packed_objects.sort();
+                outcome.packed_objects.dedup();
+                if !outcome.packed_objects.is_empty() && !options.dry_run {
+                    let had_multi_pack_index = has_multi_pack_index(&pack_dir);
+                    let ids = packed_ids
+                        .into_iter()
+                        .filter(|id| reachable.contains(id))
+                        .chain(reachable_loose_ids)
+                        .collect();
+                    outcome.pack = Some(self.replace_packs(&pack_dir, &packs, ids, progress, should_interrupt)?);
+                    if had_multi_pack_index {
+                        self.write_multi_pack_index(progress, should_interrupt, Default::default())?;
+                    }
+                }
+            }
+            Ok(outcome)
+        }
+
+        /// Return the ids of all objects that are reachable from references, their reference logs, and the `HEAD` and index
+        /// of this repository and all of its linked worktrees.
+        /// Objects that are missing, like those beyond the boundary of a shallow repository, are ignored.
+        fn reachable_objects(
+            &self,
+            progress: &mut dyn DynNestedProgress,
+            should_interrupt: &AtomicBool,
+        ) -> Result<gix_hashtable::HashSet, Error> {
+            use gix_object::{CommitRefIter, Find, Kind, TagRefIter, TreeRefIter};
+
+            let mut progress = progress.add_child("finding reachable objects".into());
+            progress.init(None, gix_features::progress::count("objects"));
+
+            let mut seen = gix_hashtable::HashSet::default();
+            let mut next = Vec::new();
+            let mut reflog_names = Vec::new();
+            for reference in self.references()?.all()? {
+                let reference = reference.map_err(Error::Reference)?;
+                next.extend(reference.target().try_id().map(ToOwned::to_owned));
+                reflog_names.push(reference.inner.name);
+            }
+            for name in &reflog_names {
+                add_reflog_ids(self, name.as_ref(), &mut next)?;
+            }
+            add_worktree_ids(self, &mut next, &mut seen)?;
+            for proxy in self.worktrees()? {
+                let repo = proxy.into_repo_with_possibly_inaccessible_worktree()?;
+                add_worktree_ids(&repo, &mut next, &mut seen)?;
+            }
+
+            let mut buf = Vec::new();
+            while let Some(id) = next.pop() {
+                if id.is_null() || !seen.insert(id) {
+                    continue;
+                }
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                progress.inc();
+                let Some(object) = self.objects.try_find(&id, &mut buf)? else {
+                    continue;
+                };
+                match object.kind {
+                    Kind::Commit => {
+                        let mut commit = CommitRefIter::from_bytes(object.data);
+                        next.push(commit.tree_id()?);
+                        next.extend(commit.parent_ids());
+                    }
+                    Kind::Tag => next.push(TagRefIter::from_bytes(object.data).target_id()?),
+                    Kind::Tree => {
+                        for entry in TreeRefIter::from_bytes(object.data) {
+                            let entry = entry?;
+                            if entry.mode.is_tree() {
+                                next.push(entry.oid.to_owned());
+                            } else if !entry.mode.is_commit() && seen.insert(entry.oid.to_owned()) {
+                                progress.inc();
+                            }
+                        }
+                    }
+                    Kind::Blob => {}
+                }
+            }
+            Ok(seen)
+        }
+
+        fn expire_all_reflogs(&self, should_interrupt: &AtomicBool) -> Result<usize, Error> {
+            let mut names = vec![gix_ref::FullName::try_from("HEAD").expect("valid")];
+            for reference in self.references()?.all()? {
+                names.push(reference.map_err(Error::Reference)?.inner.name);
+            }
+            let mut num_removed = 0;
+            for name in names {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                let policy = self.reflog_expire_policy(name.as_ref())?;
+                num_removed += self.reflog_expire(name.as_ref(), policy)?.num_removed;
+            }
+            Ok(num_removed)
+        }
+
+        /// Write all loose objects and all objects in packs that may be replaced into a new pack, and remove the replaced packs.
+        /// Return `None` if there is nothing to consolidate.
+        fn consolidate_packs(
+            &self,
+            pack_dir: &Path,
+            progress: &mut dyn DynNestedProgress,
+            should_interrupt: &AtomicBool,
+        ) -> Result<Option<ConsolidatedPack>, Error> {
+            let (packs, mut ids) = self.replaceable_packs(pack_dir)?;
+            let num_packed_ids = ids.len();
+            ids.extend(self.loose_object_ids()?);
+            if ids.len() == num_packed_ids && packs.len() < 2 {
+                return Ok(None);
+            }
+            self.replace_packs(pack_dir, &packs, ids, progress, should_interrupt)
+                .map(Some)
+        }
+
+        /// Return the paths to the indices of all packs that may be rewritten, which excludes packs with a `.keep` or
+        /// `.promisor` file, along with the ids of all objects they contain.
+        fn replaceable_packs(&self, pack_dir: &Path) -> Result<(Vec<PathBuf>, Vec<ObjectId>), Error> {
+            let packs: Vec<_> = pack_index_paths(pack_dir)?
+                .into_iter()
+                .filter(|path| !path.with_extension("keep").exists() && !path.with_extension("promisor").exists())
+                .collect();
+            let mut ids = Vec::new();
+            for path in &packs {
+                ids.extend(open_index(path, self.object_hash())?.iter().map(|entry| entry.oid));
+            }
+            Ok((packs, ids))
+        }
+
+        fn loose_object_ids(&self) -> Result<Vec<ObjectId>, Error> {
+            gix_odb::loose::Store::at(self.objects.store_ref().path(), self.object_hash())
+                .iter()
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| Error::Io(err.into()))
+        }
+
+        /// Write the objects with `ids` into a new pack in `pack_dir`, and remove the `packs` that it replaces.
+        fn replace_packs(
+            &self,
+            pack_dir: &Path,
+            packs: &[PathBuf],
+            ids: Vec<ObjectId>,
+            progress: &mut dyn DynNestedProgress,
+            should_interrupt: &AtomicBool,
+        ) -> Result<ConsolidatedPack, Error> {
+            let object_hash = self.object_hash();
+            let mut objects = self.objects.clone().into_arc()?;
+            objects.prevent_pack_unload();
+            objects.ignore_replacements = true;
+            let counts = {
+                let mut progress = progress.add_child("counting".into());
+                progress.init(None, gix_features::progress::count("objects"));
+                let (counts, _) = output::count::objects_unthreaded(
+                    &objects,
+                    &mut ids.into_iter().map(Ok),
+                    &progress,
+                    should_interrupt,
+                    output::count::objects::ObjectExpansion::AsIs,
+                )?;
+                counts
+            };
+            let num_objects = counts.len();
+            let mut entries = gix_features::parallel::InOrderIter::from(output::entry::iter_from_counts(
+                counts,
+                objects,
+                Box::new(progress.add_child("creating entries".into())),
+                output::entry::iter_from_counts::Options {
+                    thread_limit: None,
+                    mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                    allow_thin_pack: false,
+                    chunk_size: 1000,
+                    version: Default::default(),
+                    compression_level: None,
+                    stable_order: false,
+                    chunks_in_flight: None,
+                },
+            ));
+
+            let mut pack_file = gix_tempfile::new(
+                pack_dir,
+                gix_tempfile::ContainingDirectory::Exists,
+                gix_tempfile::AutoRemove::Tempfile,
+            )?;
+            {
+                let mut write = output::bytes::FromEntriesIter::new(
+                    entries.by_ref(),
+                    &mut pack_file,
+                    num_objects as u32,
+                    gix_pack::data::Version::default(),
+                    object_hash,
+                );
+                for res in write.by_ref() {
+                    if should_interrupt.load(Ordering::Relaxed) {
+                        return Err(Error::Interrupted);
+                    }
+                    res?;
+                }
+            }
+            pack_file.seek(SeekFrom::Start(0))?;
+            let bundle = gix_pack::Bundle::write_to_directory(
+                &mut std::io::BufReader::new(pack_file),
+                Some(pack_dir),
+                progress,
+                should_interrupt,
+                None::<gix_object::find::Never>,
+                gix_pack::bundle::write::Options {
+                    object_hash,
+                    ..Default::default()
+                },
+            )?;
+            if let Some(keep_path) = &bundle.keep_path {
+                std::fs::remove_file(keep_path)?;
+            }
+            let index_path = bundle.index_path.expect("a directory was provided");
+
+            let mut num_removed_packs = 0;
+            for path in packs.iter().filter(|path| **path != index_path) {
+                for extension in ["pack", "rev", "bitmap", "mtimes", "idx"] {
+                    remove_if_present(&path.with_extension(extension))?;
+                }
+                num_removed_packs += 1;
+            }
+            Ok(ConsolidatedPack {
+                index_path,
+                num_objects,
+                num_removed_packs,
+            })
+        }
+
+        /// Remove all loose objects that are contained in one of our packs, and return the amount of removed objects.
+        fn prune_packed_loose_objects(&self, pack_dir: &Path, should_interrupt: &AtomicBool) -> Result<usize, Error> {
+            let object_hash = self.object_hash();
+            let indices = pack_index_paths(pack_dir)?
+                .iter()
+                .map(|path| open_index(path, object_hash))
+                .collect::<Result<Vec<_>, _>>()?;
+            if indices.is_empty() {
+                return Ok(0);
+            }
+            let loose = gix_odb::loose::Store::at(self.objects.store_ref().path(), object_hash);
+            let mut num_removed = 0;
+            for id in self.loose_object_ids()? {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                if indices.iter().all(|index| index.lookup(id).is_none()) {
+                    continue;
+                }
+                let path = loose.object_path(&id);
+                remove_if_present(&path)?;
+                if let Some(fan_out_dir) = path.parent() {
+                    // It's fine if other objects are still in there.
+                    std::fs::remove_dir(fan_out_dir).ok();
+                }
+                num_removed += 1;
+            }
+            Ok(num_removed)
+        }
+    }
+
+    /// Add the ids in the reference log of `name` to `out`.
+    fn add_reflog_ids(repo: &Repository, name: &gix_ref::FullNameRef, out: &mut Vec<ObjectId>) -> Result<(), Error> {
+        let data = match std::fs::read(repo.refs.reflog_path(name)) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        for line in gix_ref::file::log::iter::forward(&data) {
+            let line = line?;
+            out.extend([line.previous_oid(), line.new_oid()]);
+        }
+        Ok(())
+    }
+
+    /// Add the ids that `HEAD`, its reference log and the index of the worktree of `repo` refer to, to `out`, or to `seen`
+    /// for blobs.
+    fn add_worktree_ids(
+        repo: &Repository,
+        out: &mut Vec<ObjectId>,
+        seen: &mut gix_hashtable::HashSet,
+    ) -> Result<(), Error> {
+        fn add_trees(tree: &gix_index::extension::Tree, out: &mut Vec<ObjectId>) {
+            if tree.num_entries.is_some() {
+                out.push(tree.id);
+            }
+            for child in &tree.children {
+                add_trees(child, out);
+            }
+        }
+
+        out.extend(repo.head_id().ok().map(crate::Id::detach));
+        add_reflog_ids(repo, "HEAD".try_into().expect("valid"), out)?;
+        if let Some(index) = repo.try_index()? {
+            seen.extend(
+                index
+                    .entries()
+                    .iter()
+                    .filter(|entry| entry.mode != gix_index::entry::Mode::COMMIT)
+                    .map(|entry| entry.id),
+            );
+            if let Some(tree) = index.tree() {
+                add_trees(tree, out);
+            }
+        }
+        Ok(())
+    }
+
+    fn has_multi_pack_index(pack_dir: &Path) -> bool {
+        pack_dir.join("multi-pack-index").is_file() || gix_pack::multi_index::chain::path(pack_dir).is_file()
+    }
+
+    fn open_index(path: &Path, object_hash: gix_hash::Kind) -> Result<gix_pack::index::File, Error> {
+        gix_pack::index::File::at(path, object_hash).map_err(|err| Error::OpenPackIndex {
+            path: path.to_owned(),
+            source: err,
+        })
+    }
+
+    /// Return the paths to all pack indices in `pack_dir` whose pack is present as well.
+    fn pack_index_paths(pack_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+        let entries = match std::fs::read_dir(pack_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut paths = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) == Some("idx") && path.with_extension("pack").is_file() {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn remove_if_present(path: &Path) -> std::io::Result<()> {
+        match std::fs::remove_file(path) {
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            res => res,
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use gix_hash::ObjectId;
+
+use crate::{
+    bstr::BString,
+    config,
+    config::{
+        cache::util::ApplyLeniency,
+        tree::{Diff, Key, Merge},
+    },
+    diff::rename::Tracking,
+    merge,
+    merge::driver::{Drivers, Selected},
+    Id, Repository,
+};
+
+const MERGE_HEAD: &str = "MERGE_HEAD";
+const MERGE_MSG: &str = "MERGE_MSG";
+
+/// Merge-utilities
+impl Repository {
+    /// Merge the trees `ours` and `theirs` with `base` being the tree of their common ancestor, and write blobs with merged
+    /// content into the object database. `labels` are used to annotate conflict markers.
+    ///
+    /// The content of files changed on both sides is merged with the driver selected by their `merge` attribute, which
+    /// runs the commands of user-defined drivers configured with `merge.<driver>.driver`.
+    /// Use [`merge_trees_with_drivers()`](Self::merge_trees_with_drivers()) to control how these are invoked.
+    ///
+    /// Note that neither the index nor the worktree are changed. Use the [`index`](merge::tree::Outcome::index) of the returned
+    /// outcome to learn about the merged state, including conflicts.
+    pub fn merge_trees(
+        &self,
+        base: impl AsRef<gix_hash::oid>,
+        ours: impl AsRef<gix_hash::oid>,
+        theirs: impl AsRef<gix_hash::oid>,
+        labels: merge::blob::Labels<'_>,
+        options: merge::tree::Options,
+    ) -> Result<merge::tree::Outcome, merge::tree::Error> {
+        self.merge_trees_with_drivers(base, ours, theirs, labels, options, |_driver, _input, _out| Ok(None))
+    }
+
+    /// Like [`merge_trees()`](Self::merge_trees()), but call `intercept` with each user-defined merge driver that was selected
+    /// for a file by its `merge` attribute, along with the content to merge and the buffer to write the merged content into.
+    ///
+    /// If `intercept` returns `Some(resolution)`, the content it wrote into the buffer is used as merge result.
+    /// Otherwise, the command of the driver is run in its place.
+    /// Files that use built-in drivers, or drivers that aren't configured, are never passed to `intercept`.
+    pub fn merge_trees_with_drivers(
+        &self,
+        base: impl AsRef<gix_hash::oid>,
+        ours: impl AsRef<gix_hash::oid>,
+        theirs: impl AsRef<gix_hash::oid>,
+        labels: merge::blob::Labels<'_>,
+        options: merge::tree::Options,
+        mut intercept: impl FnMut(
+            &merge::blob::Driver,
+            merge::tree::BlobInput<'_>,
+            &mut Vec<u8>,
+        ) -> Result<
+            Option<merge::blob::Resolution>,
+            Box<dyn std::error::Error + Send + Sync + 'static>,
+        >,
+    ) -> Result<merge::tree::Outcome, merge::tree::Error> {
+        let _span = gix_trace::coarse!("gix::Repository::merge_trees()");
+        let mut drivers = None;
+        let temp_dir = std::env::temp_dir();
+        gix_merge::tree(
+            base.as_ref(),
+            ours.as_ref(),
+            theirs.as_ref(),
+            &self.objects,
+            |data| self.write_blob(data).map(Id::detach),
+            |input, out| -> Result<_, merge::driver::Error> {
+                let drivers = match &mut drivers {
+                    Some(drivers) => drivers,
+                    None => drivers.insert(Drivers::new(self, ours.as_ref())?),
+                };
+                let command_context = drivers.command_context.clone();
+                let driver = match drivers.select(input.path)? {
+                    Selected::Builtin(builtin) => return Ok(input.merge_builtin(out, builtin)),
+                    Selected::Custom(driver) => driver,
+                };
+                if let Some(resolution) =
+                    intercept(driver, input, out).map_err(|err| merge::driver::Error::Intercept {
+                        name: driver.name.clone(),
+                        source: err,
+                    })?
+                {
+                    return Ok(resolution);
+                }
+                driver
+                    .apply(
+                        out,
+                        input.base,
+                        input.ours,
+                        input.theirs,
+                        merge::blob::driver::Context {
+                            path: input.path,
+                            marker_size: input.options.marker_size,
+                            temp_dir: &temp_dir,
+                            command: command_context,
+                        },
+                    )
+                    .map_err(|err| merge::driver::Error::Apply {
+                        name: driver.name.clone(),
+                        source: err,
+                    })
+            },
+            labels,
+            options,
+        )
+    }
+
+    /// Return the options to merge trees with as configured, similar to the ones `git merge` uses.
+    ///
+    /// Renames are detected unless disabled by `merge.renames` or `diff.renames`, within the limits of `merge.renameLimit` or
+    /// `diff.renameLimit`, and files that were added to renamed directories are handled as configured by `merge.directoryRenames`.
+    pub fn merge_tree_options(&self) -> Result<merge::tree::Options, config::merge_tree_options::Error> {
+        let (config, lenient) = (&self.config.resolved, self.config.lenient_config);
+        let renames_key = if config.boolean_by_key(Merge::RENAMES.logical_name().as_str()).is_some() {
+            &Merge::RENAMES
+        } else {
+            &Diff::RENAMES
+        };
+        let tracking = config
+            .boolean_by_key(renames_key.logical_name().as_str())
+            .map(|value| renames_key.try_into_renames(value))
+            .transpose()
+            .with_leniency(lenient)?;
+        if tracking == Some(Tracking::Disabled) {
+            return Ok(Default::default());
+        }
+
+        let limit_key = if config
+            .integer_by_key(Merge::RENAME_LIMIT.logical_name().as_str())
+            .is_some()
+        {
+            &Merge::RENAME_LIMIT
+        } else {
+            &Diff::RENAME_LIMIT
+        };
+        let default = merge::tree::Renames::default();
+        let limit = config
+            .integer_by_key(limit_key.logical_name().as_str())
+            .map(|value| limit_key.try_into_usize(value))
+            .transpose()
+            .with_leniency(lenient)?
+            .map_or(default.limit, |limit| limit.saturating_mul(limit));
+        let directories = config
+            .string_by_key(Merge::DIRECTORY_RENAMES.logical_name().as_str())
+            .map(|value| Merge::DIRECTORY_RENAMES.try_into_directory_renames(value))
+            .transpose()
+            .with_leniency(lenient)?
+            .unwrap_or_default();
+        Ok(merge::tree::Options {
+            renames: Some(merge::tree::Renames {
+                limit,
+                directories,
+                ..default
+            }),
+            ..Default::default()
+        })
+    }
+
+    /// Merge the commits `theirs` into the commit `ours` with `options`, and return the merged trees along with the commits
+    /// that were merged. Neither the index, the worktree nor any reference are changed.
+    ///
+    /// Each commit is merged in turn with the best common ancestor of it and the commits merged so far as merge base, using
+    /// the most recent one if there are multiple. Commits that are already contained in *ours* or in previously merged commits
+    /// are skipped. Merging more than one commit is an octopus merge, which fails if any but the last commit conflicts, just
+    /// like in `git`.
+    ///
+    /// Note that the [`Theirs`](merge::tree::Strategy::Theirs) and [`Subtree`](merge::tree::Strategy::Subtree) strategies
+    /// can only merge a single commit.
+    pub fn merge_commits(
+        &self,
+        ours: impl Into<ObjectId>,
+        theirs: impl IntoIterator<Item = impl Into<ObjectId>>,
+        options: merge::tree::Options,
+    ) -> Result<merge::commits::Outcome, merge::commits::Error> {
+        use merge::commits::Error;
+
+        let _span = gix_trace::coarse!("gix::Repository::merge_commits()");
+        let ours = ours.into();
+        let theirs: Vec<ObjectId> = theirs.into_iter().map(Into::into).collect();
+        match theirs.len() {
+            0 => return Err(Error::NothingToMerge),
+            1 => {}
+            count => {
+                if matches!(
+                    options.strategy,
+                    merge::tree::Strategy::Theirs | merge::tree::Strategy::Subtree { .. }
+                ) {
+                    return Err(Error::TooManyCommits {
+                        strategy: options.strategy,
+                        count,
+                    });
+                }
+            }
+        }
+
+        let mut tree = self.commit_tree(ours)?;
+        let ours_label = short_id(self, ours);
+        let mut reference_commits = vec![ours];
+        let mut outcome = None;
+        let (mut merged, mut merge_bases) = (Vec::new(), Vec::new());
+        for (idx, id) in theirs.iter().copied().enumerate() {
+            let base = self.merge_base::<merge::commits::Error>(id, &reference_commits)?;
+            if base == Some(id) {
+                continue;
+            }
+            let base_tree = match base {
+                Some(base) => self.commit_tree(base)?,
+                None => ObjectId::empty_tree(self.object_hash()),
+            };
+            let base_label = base.map_or_else(|| "empty tree".into(), |base| short_id(self, base));
+            let their_label = short_id(self, id);
+            let result = self.merge_trees(
+                base_tree,
+                tree,
+                self.commit_tree(id)?,
+                merge::blob::Labels {
+                    ancestor: Some(base_label.as_ref()),
+                    current: Some(ours_label.as_ref()),
+                    other: Some(their_label.as_ref()),
+                },
+                options.clone(),
+            )?;
+            let is_last = idx + 1 == theirs.len();
+            if result.has_conflicts() && !is_last {
+                return Err(Error::OctopusConflict { id });
+            }
+            if !is_last {
+                tree = result
+                    .index
+                    .write_tree(|tree| self.write_object(tree).map(Id::detach))?;
+            }
+            reference_commits.push(id);
+            merged.push(id);
+            merge_bases.push(base);
+            outcome = Some(result);
+        }
+
+        let tree = match outcome {
+            Some(outcome) => outcome,
+            // Everything is merged already, so produce the tree of `ours`.
+            None => self.merge_trees(
+                tree,
+                tree,
+                tree,
+                Default::default(),
+                merge::tree::Options {
+                    strategy: merge::tree::Strategy::Ours,
+                    ..Default::default()
+                },
+            )?,
+        };
+        Ok(merge::commits::Outcome {
+            tree,
+            merged,
+            merge_bases,
+        })
+    }
+
+    /// Merge the commits `theirs` into `HEAD` like [`merge_commits()`](Self::merge_commits()) does, and commit the result
+    /// with `message`, similar to `git merge --no-ff -m <message>`.
+    ///
+    /// The index and the worktree must not have changes. If there are conflicts, the index and the worktree are left with
+    /// the conflicts to resolve, and `MERGE_HEAD` and `MERGE_MSG` are written so `git merge --continue` or `git merge --abort`
+    /// can take over. The same is true if the merge commit, created with [`commit()`](Self::commit()), is prevented by the
+    /// `pre-merge-commit` or `commit-msg` hooks. Once committed, the `post-merge` hook is run.
+    pub fn merge_into_head(
+        &self,
+        theirs: impl IntoIterator<Item = impl Into<ObjectId>>,
+        message: impl AsRef<str>,
+    ) -> Result<merge::head::Outcome, merge::head::Error> {
+        use crate::bstr::ByteVec;
+        use merge::head::{Error, Outcome};
+
+        let _span = gix_trace::coarse!("gix::Repository::merge_into_head()");
+        if let Some(state) = self.state() {
+            return Err(Error::InProgress { state });
+        }
+        let head_id = self.head_id()?.detach();
+        let index = self.open_index()?;
+        merge::worktree::require_clean(self, &index, Some(&self.commit_tree(head_id)?))?;
+
+        let merge::commits::Outcome { tree, merged, .. } =
+            self.merge_commits(head_id, theirs, self.merge_tree_options()?)?;
+        if merged.is_empty() {
+            return Ok(Outcome {
+                commit: None,
+                merged,
+                conflicts: Vec::new(),
+            });
+        }
+        let merge_head: String = merged.iter().map(|id| format!("{id}\n")).collect();
+        let (merge_head_path, merge_msg_path) = (self.path().join(MERGE_HEAD), self.path().join(MERGE_MSG));
+        if tree.has_conflicts() {
+            merge::worktree::checkout(self, &index, tree.index, &tree.conflicts, false)?;
+            self.rerere()?;
+            let mut merge_msg = BString::from(message.as_ref());
+            merge_msg.push_str("\n# Conflicts:\n");
+            for conflict in &tree.conflicts {
+                merge_msg.push_str("#\t");
+                merge_msg.push_str(&conflict.path);
+                merge_msg.push_byte(b'\n');
+            }
+            std::fs::write(merge_msg_path, merge_msg)?;
+            std::fs::write(merge_head_path, merge_head)?;
+            return Ok(Outcome {
+                commit: None,
+                merged,
+                conflicts: tree.conflicts,
+            });
+        }
+
+        let tree_id = tree.index.write_tree(|tree| self.write_object(tree).map(Id::detach))?;
+        merge::worktree::checkout(self, &index, tree.index, &[], false)?;
+        std::fs::write(&merge_msg_path, message.as_ref())?;
+        std::fs::write(&merge_head_path, merge_head)?;
+        let commit = self
+            .commit(
+                "HEAD",
+                message,
+                tree_id,
+                std::iter::once(head_id).chain(merged.iter().copied()),
+            )?
+            .detach();
+        for path in [merge_head_path, merge_msg_path] {
+            std::fs::remove_file(path)?;
+        }
+        #[cfg(feature = "hooks")]
+        if self.hooks_enabled() {
+            self.run_post_merge_hook();
+        }
+        Ok(Outcome {
+            commit: Some(commit),
+            merged,
+            conflicts: Vec::new(),
+        })
+    }
+}
+
+/// Utilities
+impl Repository {
+    fn commit_tree(&self, id: ObjectId) -> Result<ObjectId, merge::commits::Error> {
+        Ok(self.find_object(id)?.try_into_commit()?.tree_id()?.detach())
+    }
+}
+
+fn short_id(repo: &Repository, id: ObjectId) -> BString {
+    use crate::ext::ObjectIdExt;
+    id.attach(repo).shorten_or_id().to_string().into()
+}
@@ -35,9 +35,19 @@ impl crate::Repository {
     }
 }
 
+#[cfg(feature = "apply")]
+mod apply;
 #[cfg(any(feature = "attributes", feature = "excludes"))]
 pub mod attributes;
+#[cfg(feature = "blame")]
+mod blame;
+#[cfg(feature = "bundle")]
+mod bundle;
 mod cache;
+#[cfg(feature = "checkout")]
+mod checkout;
+#[cfg(feature = "merge")]
+mod cherry_pick;
 mod config;
 ///
 #[cfg(feature = "blob-diff")]
@@ -48,28 +58,55 @@ pub mod dirwalk;
 ///
 #[cfg(feature = "attributes")]
 pub mod filter;
+#[cfg(feature = "fsck")]
+mod fsck;
 mod graph;
-pub(crate) mod identity;
+#[cfg(feature = "hooks")]
+mod hooks;
+///
+pub mod identity;
 mod impls;
 #[cfg(feature = "index")]
 mod index;
 pub(crate) mod init;
 mod kind;
+#[cfg(feature = "attributes")]
+mod linguist;
 mod location;
 #[cfg(feature = "mailmap")]
 mod mailmap;
+mod maintenance;
+#[cfg(feature = "merge")]
+mod merge;
+#[cfg(feature = "notes")]
+mod notes;
 mod object;
 #[cfg(feature = "attributes")]
 mod pathspec;
+mod promisor;
+#[cfg(feature = "rebase")]
+mod rebase;
 mod reference;
 mod remote;
+#[cfg(feature = "merge")]
+mod rerere;
 #[cfg(feature = "revision")]
 mod revision;
 mod shallow;
+#[cfg(feature = "shortlog")]
+mod shortlog;
+#[cfg(feature = "sparse-checkout")]
+mod sparse_checkout;
+#[cfg(feature = "stash")]
+mod stash;
 mod state;
+#[cfg(feature = "status")]
+mod status;
 #[cfg(feature = "attributes")]
 mod submodule;
 mod thread_safe;
+///
+pub mod var;
 mod worktree;
 
 ///
@@ -129,6 +166,47 @@ pub mod pathspec_defaults_ignore_case {
     }
 }
 
+///
+#[cfg(feature = "attributes")]
+pub mod smudge_racily_clean_entries {
+    /// The error returned by [`Repository::smudge_racily_clean_entries()`][crate::Repository::smudge_racily_clean_entries()].
+    #[derive(thiserror::Error, Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        StatOptions(#[from] crate::config::stat_options::Error),
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+        #[error(transparent)]
+        ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+        #[error("Could not obtain the modification time of a worktree file")]
+        Time(#[from] std::time::SystemTimeError),
+        #[error("Could not read '{}' to see if it changed", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+    }
+}
+
+///
+#[cfg(feature = "status")]
+pub mod tree_index_status {
+    /// The error returned by [`Repository::tree_index_status()`][crate::Repository::tree_index_status()].
+    #[derive(thiserror::Error, Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        RenamesConfiguration(#[from] crate::diff::new_rewrites::Error),
+        #[error(transparent)]
+        TraverseTree(#[from] gix_traverse::tree::breadthfirst::Error),
+        #[error(transparent)]
+        ResourceCache(#[from] crate::repository::diff::resource_cache::Error),
+        #[error(transparent)]
+        TreeIndex(#[from] crate::status::tree_index::Error),
+    }
+}
+
 ///
 #[cfg(feature = "index")]
 pub mod index_or_load_from_head {
@@ -147,10 +225,55 @@ pub mod index_or_load_from_head {
     }
 }
 
+///
+#[cfg(feature = "index")]
+pub mod index_from_head {
+    /// The error returned by [`Repository::index_from_head()`][crate::Repository::index_from_head()].
+    #[derive(thiserror::Error, Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        HeadTreeId(#[from] crate::reference::head_tree_id::Error),
+        #[error(transparent)]
+        TraverseTree(#[from] gix_traverse::tree::breadthfirst::Error),
+    }
+}
+
+///
+#[cfg(feature = "attributes")]
+pub mod attributes_from_head {
+    /// The error returned by [`Repository::attributes_from_head()`][crate::Repository::attributes_from_head()].
+    #[derive(thiserror::Error, Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        IndexFromHead(#[from] super::index_from_head::Error),
+        #[error(transparent)]
+        AttributeStack(#[from] crate::config::attribute_stack::Error),
+    }
+}
+
+///
+#[cfg(feature = "index")]
+pub mod commit_index {
+    /// The error returned by [`Repository::commit_index()`][crate::Repository::commit_index()].
+    #[derive(thiserror::Error, Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        WriteTree(#[from] gix_index::write_tree::Error<crate::object::write::Error>),
+        #[error(transparent)]
+        Commit(#[from] crate::commit::Error),
+    }
+}
+
 ///
 #[cfg(feature = "worktree-stream")]
 pub mod worktree_stream {
-    /// The error returned by [`Repository::worktree_stream()`][crate::Repository::worktree_stream()].
+    /// The error returned by [`Repository::worktree_stream()`][crate::Repository::worktree_stream()] and
+    /// [`Repository::worktree_stream_with_submodules()`][crate::Repository::worktree_stream_with_submodules()].
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
@@ -164,11 +287,24 @@ pub mod worktree_stream {
         FilterPipeline(#[from] crate::filter::pipeline::options::Error),
         #[error(transparent)]
         CommandContext(#[from] crate::config::command_context::Error),
-        #[error("Needed {id} to be a tree to turn into a workspace stream, got {actual}")]
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error("Needed {id} to be a tree or commit to turn into a workspace stream, got {actual}")]
         NotATree {
             id: gix_hash::ObjectId,
             actual: gix_object::Kind,
         },
+        #[error(transparent)]
+        Submodules(#[from] crate::submodule::modules::Error),
+        #[error(transparent)]
+        SubmodulePath(#[from] gix_submodule::config::path::Error),
+        #[error(transparent)]
+        OpenSubmodule(#[from] crate::submodule::open::Error),
+        #[error("Could not stream the worktree of the submodule at '{path}'")]
+        Submodule {
+            path: crate::bstr::BString,
+            source: Box<Error>,
+        },
     }
 }
 
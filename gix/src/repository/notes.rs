@@ -0,0 +1,39 @@
+use gix_ref::FullName;
+
+use crate::{
+    bstr::{BStr, BString},
+    config::tree::{Core, Key},
+    notes, Repository,
+};
+
+/// Notes
+impl Repository {
+    /// Return a platform to read and change the notes in the notes reference `name`, similar to `git notes --ref <name>`.
+    ///
+    /// Like `git`, `name` is expanded to `refs/notes/<name>` unless it starts with `refs/notes/`, or with `notes/` in which
+    /// case only `refs/` is prepended.
+    pub fn notes(&self, name: impl AsRef<BStr>) -> Result<notes::Platform<'_>, notes::Error> {
+        let name = name.as_ref();
+        let name: BString = if name.starts_with(b"refs/notes/") {
+            name.to_owned()
+        } else if name.starts_with(b"notes/") {
+            format!("refs/{name}").into()
+        } else {
+            format!("refs/notes/{name}").into()
+        };
+        notes::Platform::from_reference(self, FullName::try_from(name)?)
+    }
+
+    /// Return a platform to read and change the notes in the default notes reference, which is configured by
+    /// `core.notesRef` or is [`refs/notes/commits`](notes::DEFAULT_REF).
+    pub fn default_notes(&self) -> Result<notes::Platform<'_>, notes::Error> {
+        match self
+            .config
+            .resolved
+            .string_by_key(Core::NOTES_REF.logical_name().as_str())
+        {
+            Some(name) => self.notes(name.as_ref()),
+            None => self.notes(notes::DEFAULT_REF),
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::{convert::TryInto, ops::DerefMut};
 
 use gix_hash::ObjectId;
 use gix_macros::momo;
-use gix_object::{Exists, Find, FindExt};
+use gix_object::{Exists, Find, FindExt, WriteTo};
 use gix_odb::{Header, HeaderExt, Write};
 use gix_ref::{
     transaction::{LogChange, PreviousValue, RefLog},
@@ -11,7 +11,7 @@ use gix_ref::{
 };
 use smallvec::SmallVec;
 
-use crate::{commit, ext::ObjectIdExt, object, tag, Blob, Id, Object, Reference, Tree};
+use crate::{bstr::BString, commit, ext::ObjectIdExt, object, tag, Blob, Id, Object, Reference, Tree};
 
 /// Methods related to object creation.
 impl crate::Repository {
@@ -20,6 +20,11 @@ impl crate::Repository {
     /// There are various legitimate reasons for an object to not be present, which is why
     /// [`try_find_object(…)`][crate::Repository::try_find_object()] might be preferable instead.
     ///
+    /// # Partial Clones
+    ///
+    /// If this repository is a [partial clone](crate::Repository::promisor_remote_name()), objects that aren't present
+    /// are fetched from the promisor remote on demand, which blocks until the object was received.
+    ///
     /// # Performance Note
     ///
     /// In order to get the kind of the object, is must be fully decoded from storage if it is packed with deltas.
@@ -36,7 +41,15 @@ impl crate::Repository {
             });
         }
         let mut buf = self.free_buf();
-        let kind = self.objects.find(&id, &mut buf)?.kind;
+        let kind = match self.objects.find(&id, &mut buf) {
+            Ok(obj) => obj.kind,
+            #[cfg(feature = "blocking-network-client")]
+            Err(gix_object::find::existing::Error::NotFound { .. }) if self.promisor_remote_name().is_some() => {
+                self.fetch_missing_object_on_demand(id)?;
+                self.objects.find(&id, &mut buf)?.kind
+            }
+            Err(err) => return Err(err),
+        };
         Ok(Object::from_data(id, kind, buf, self))
     }
 
@@ -210,16 +223,80 @@ impl crate::Repository {
         tagger: Option<gix_actor::SignatureRef<'_>>,
         message: impl AsRef<str>,
         constraint: PreviousValue,
+    ) -> Result<Reference<'_>, tag::Error> {
+        self.tag_inner(
+            name.as_ref(),
+            target.as_ref(),
+            target_kind,
+            tagger,
+            message.as_ref(),
+            constraint,
+            None,
+        )
+    }
+
+    /// Like [`tag(…)`](Self::tag()), but sign the tag object with `signer` before writing it.
+    ///
+    /// The signature is appended to the message, just like `git tag -s` would do it.
+    #[allow(clippy::too_many_arguments)]
+    #[momo]
+    pub fn tag_signed(
+        &self,
+        name: impl AsRef<str>,
+        target: impl AsRef<gix_hash::oid>,
+        target_kind: gix_object::Kind,
+        tagger: Option<gix_actor::SignatureRef<'_>>,
+        message: impl AsRef<str>,
+        constraint: PreviousValue,
+        signer: &dyn crate::sign::Sign,
+    ) -> Result<Reference<'_>, tag::Error> {
+        self.tag_inner(
+            name.as_ref(),
+            target.as_ref(),
+            target_kind,
+            tagger,
+            message.as_ref(),
+            constraint,
+            Some(signer),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn tag_inner(
+        &self,
+        name: &str,
+        target: &gix_hash::oid,
+        target_kind: gix_object::Kind,
+        tagger: Option<gix_actor::SignatureRef<'_>>,
+        message: &str,
+        constraint: PreviousValue,
+        signer: Option<&dyn crate::sign::Sign>,
     ) -> Result<Reference<'_>, tag::Error> {
         let tag = gix_object::Tag {
-            target: target.as_ref().into(),
+            target: target.into(),
             target_kind,
-            name: name.as_ref().into(),
+            name: name.into(),
             tagger: tagger.map(|t| t.to_owned()),
-            message: message.as_ref().into(),
+            message: message.into(),
             pgp_signature: None,
         };
-        let tag_id = self.write_object(&tag)?;
+        let tag_id = match signer {
+            Some(signer) => {
+                // The signature is appended to the object as is, after the message which always ends with a newline.
+                let mut buf = Vec::new();
+                tag.write_to(&mut buf).expect("write to memory works");
+                if !buf.ends_with(b"\n") {
+                    buf.push(b'\n');
+                }
+                let signature = signer.sign(&buf).map_err(tag::Error::Sign)?;
+                buf.extend_from_slice(&signature);
+                if !buf.ends_with(b"\n") {
+                    buf.push(b'\n');
+                }
+                self.write_object_inner(&buf, gix_object::Kind::Tag)?
+            }
+            None => self.write_object(&tag)?,
+        };
         self.tag_reference(name, tag_id, constraint).map_err(Into::into)
     }
 
@@ -246,9 +323,40 @@ impl crate::Repository {
             message.as_ref(),
             tree.into(),
             parents.into_iter().map(Into::into).collect(),
+            None,
+        )
+    }
+
+    /// Like [`commit_as(…)`](Self::commit_as()), but sign the commit with `signer` before writing it.
+    ///
+    /// The signature is stored in the `gpgsig` header, just like `git commit -S` would do it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn commit_as_signed<'a, 'c, Name, E>(
+        &self,
+        committer: impl Into<gix_actor::SignatureRef<'c>>,
+        author: impl Into<gix_actor::SignatureRef<'a>>,
+        reference: Name,
+        message: impl AsRef<str>,
+        tree: impl Into<ObjectId>,
+        parents: impl IntoIterator<Item = impl Into<ObjectId>>,
+        signer: &dyn crate::sign::Sign,
+    ) -> Result<Id<'_>, commit::Error>
+    where
+        Name: TryInto<FullName, Error = E>,
+        commit::Error: From<E>,
+    {
+        self.commit_as_inner(
+            committer.into(),
+            author.into(),
+            reference.try_into()?,
+            message.as_ref(),
+            tree.into(),
+            parents.into_iter().map(Into::into).collect(),
+            Some(signer),
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn commit_as_inner(
         &self,
         committer: gix_actor::SignatureRef<'_>,
@@ -257,16 +365,24 @@ impl crate::Repository {
         message: &str,
         tree: ObjectId,
         parents: SmallVec<[ObjectId; 1]>,
+        signer: Option<&dyn crate::sign::Sign>,
     ) -> Result<Id<'_>, commit::Error> {
         use gix_ref::{
             transaction::{Change, RefEdit},
             Target,
         };
 
+        #[cfg_attr(not(feature = "hooks"), allow(unused_mut))]
+        let mut message: BString = message.into();
+        #[cfg(feature = "hooks")]
+        if self.hooks_enabled() {
+            message = self.run_pre_commit_hooks(message, parents.len())?;
+        }
+
         // TODO: possibly use CommitRef to save a few allocations (but will have to allocate for object ids anyway.
         //       This can be made vastly more efficient though if we wanted to, so we lie in the API
-        let commit = gix_object::Commit {
-            message: message.into(),
+        let mut commit = gix_object::Commit {
+            message,
             tree,
             author: author.into(),
             committer: committer.into(),
@@ -274,6 +390,16 @@ impl crate::Repository {
             parents,
             extra_headers: Default::default(),
         };
+        if let Some(signer) = signer {
+            let mut buf = Vec::new();
+            commit.write_to(&mut buf).expect("write to memory works");
+            let mut signature = signer.sign(&buf).map_err(commit::Error::Sign)?;
+            // The header is terminated by a newline already.
+            if signature.ends_with(b"\n") {
+                signature.pop();
+            }
+            commit.extra_headers.push(("gpgsig".into(), signature));
+        }
 
         let commit_id = self.write_object(&commit)?;
         self.edit_reference(RefEdit {
@@ -298,6 +424,10 @@ impl crate::Repository {
             name: reference,
             deref: true,
         })?;
+        #[cfg(feature = "hooks")]
+        if self.hooks_enabled() {
+            self.run_post_commit_hook();
+        }
         Ok(commit_id)
     }
 
@@ -330,6 +460,24 @@ impl crate::Repository {
         self.commit_as(committer, author, reference, message, tree, parents)
     }
 
+    /// Like [`commit(…)`](Self::commit()), but sign the commit with `signer` before writing it.
+    pub fn commit_signed<Name, E>(
+        &self,
+        reference: Name,
+        message: impl AsRef<str>,
+        tree: impl Into<ObjectId>,
+        parents: impl IntoIterator<Item = impl Into<ObjectId>>,
+        signer: &dyn crate::sign::Sign,
+    ) -> Result<Id<'_>, commit::Error>
+    where
+        Name: TryInto<FullName, Error = E>,
+        commit::Error: From<E>,
+    {
+        let author = self.author().ok_or(commit::Error::AuthorMissing)??;
+        let committer = self.committer().ok_or(commit::Error::CommitterMissing)??;
+        self.commit_as_signed(committer, author, reference, message, tree, parents, signer)
+    }
+
     /// Return an empty tree object, suitable for [getting changes](Tree::changes()).
     ///
     /// Note that the returned object is special and doesn't necessarily physically exist in the object database.
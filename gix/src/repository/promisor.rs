@@ -0,0 +1,148 @@
+use std::borrow::Cow;
+
+use crate::{bstr::BStr, config::tree::Extensions, Repository};
+
+impl Repository {
+    /// Return the name of the remote that promises to provide the objects missing in this partial clone, as configured
+    /// in `extensions.partialClone`, or `None` if this repository isn't a partial clone.
+    pub fn promisor_remote_name(&self) -> Option<Cow<'_, BStr>> {
+        self.config
+            .resolved
+            .string("extensions", None, Extensions::PARTIAL_CLONE.name)
+            .filter(|name| !name.is_empty())
+    }
+}
+
+#[cfg(feature = "blocking-network-client")]
+mod fetch {
+    use std::sync::atomic::AtomicBool;
+
+    use gix_hash::ObjectId;
+    use gix_object::Exists;
+
+    use crate::{
+        bstr::BStr,
+        config::tree::Fetch,
+        promisor::{backfill, Error},
+        remote,
+        remote::fetch::{Status, Tags},
+        Repository,
+    };
+
+    impl Repository {
+        /// Fetch all objects with `ids` that are missing locally from the [promisor remote](Self::promisor_remote_name()),
+        /// providing `progress` and checking `should_interrupt` to stop the operation.
+        ///
+        /// Objects that exist already are skipped, and nothing is done if all of them exist.
+        ///
+        /// Like `git`, we don't negotiate with the remote as objects reachable from our commits might be missing, and only
+        /// the objects that are explicitly asked for are sent by the remote, even if they don't pass the
+        /// [partial clone filter](crate::Remote::partial_clone_filter()).
+        pub fn fetch_missing_objects<P>(
+            &self,
+            ids: impl IntoIterator<Item = impl Into<ObjectId>>,
+            mut progress: P,
+            should_interrupt: &AtomicBool,
+        ) -> Result<(), Error>
+        where
+            P: crate::NestedProgress,
+            P::SubProgress: 'static,
+        {
+            self.fetch_missing_objects_inner(
+                ids.into_iter().map(Into::into).collect(),
+                &mut progress,
+                should_interrupt,
+            )
+        }
+
+        /// Fetch all blobs that are missing locally and are reachable from the tree `tree_id` in a single batch from the
+        /// [promisor remote](Self::promisor_remote_name()), similar to `git backfill`, providing `progress` and checking
+        /// `should_interrupt` to stop the operation.
+        ///
+        /// Only blobs whose path matches `patterns` are considered, or all of them if `patterns` is empty.
+        /// Note that trees are expected to be present, as each missing tree is fetched on demand.
+        ///
+        /// Return the amount of blobs that were fetched.
+        pub fn backfill_missing_blobs<P>(
+            &self,
+            tree_id: impl Into<ObjectId>,
+            patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+            mut progress: P,
+            should_interrupt: &AtomicBool,
+        ) -> Result<usize, backfill::Error>
+        where
+            P: crate::NestedProgress,
+            P::SubProgress: 'static,
+        {
+            if self.promisor_remote_name().is_none() {
+                return Err(Error::NotAPartialClone.into());
+            }
+            let index = gix_index::State::from_tree(&tree_id.into(), &self.objects)?;
+            let mut pathspec = self.pathspec(
+                patterns,
+                false,
+                &index,
+                gix_worktree::stack::state::attributes::Source::IdMapping,
+            )?;
+            let mut ids: Vec<_> = index
+                .entries()
+                .iter()
+                .filter(|entry| !entry.mode.is_submodule() && !self.objects.exists(&entry.id))
+                .filter(|entry| pathspec.is_included(entry.path(&index), Some(false)))
+                .map(|entry| entry.id)
+                .collect();
+            ids.sort();
+            ids.dedup();
+
+            let num_blobs = ids.len();
+            self.fetch_missing_objects_inner(ids, &mut progress, should_interrupt)?;
+            Ok(num_blobs)
+        }
+
+        pub(crate) fn fetch_missing_objects_inner(
+            &self,
+            mut ids: Vec<ObjectId>,
+            progress: &mut dyn crate::DynNestedProgress,
+            should_interrupt: &AtomicBool,
+        ) -> Result<(), Error> {
+            let remote_name = self.promisor_remote_name().ok_or(Error::NotAPartialClone)?;
+            ids.retain(|id| !self.objects.exists(id));
+            if ids.is_empty() {
+                return Ok(());
+            }
+
+            let mut repo = self.clone();
+            {
+                let mut config = repo.config_snapshot_mut();
+                config.set_value(&Fetch::NEGOTIATION_ALGORITHM, "noop")?;
+                config.commit()?;
+            }
+            let mut remote = repo.find_remote(remote_name.as_ref())?.with_fetch_tags(Tags::None);
+            remote.replace_refspecs(
+                ids.iter().map(|id| crate::bstr::BString::from(id.to_hex().to_string())),
+                remote::Direction::Fetch,
+            )?;
+            let outcome = remote
+                .connect(remote::Direction::Fetch)?
+                .prepare_fetch(&mut *progress, Default::default())?
+                .receive_inner(progress, should_interrupt)?;
+
+            // No ref points to the objects we received, so they are bound to the repository only by the objects referring to them.
+            if let Status::Change { write_pack_bundle, .. } = outcome.status {
+                if let Some(path) = write_pack_bundle.keep_path {
+                    std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
+                }
+            }
+            Ok(())
+        }
+
+        /// Fetch the object `id` from the promisor remote in response to it not being found.
+        pub(crate) fn fetch_missing_object_on_demand(
+            &self,
+            id: ObjectId,
+        ) -> Result<(), gix_object::find::existing::Error> {
+            self.fetch_missing_objects_inner(vec![id], &mut gix_features::progress::Discard, &AtomicBool::default())
+                .map_err(|err| gix_object::find::existing::Error::Find(Box::new(err)))
+        }
+    }
+}
@@ -0,0 +1,547 @@
+use std::{collections::VecDeque, path::PathBuf};
+
+use gix_hash::ObjectId;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+use gix_sequencer::todo::{Command, Instruction, List};
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    merge,
+    rebase::{Error, Options, Outcome, Replayed, Stopped},
+    Commit, Id, Repository,
+};
+
+/// The file that holds the commit at which a rebase stopped, for use by tooling.
+const REBASE_HEAD: &str = "REBASE_HEAD";
+/// The character to denote comments in todo-lists.
+const COMMENT_CHAR: u8 = b'#';
+
+/// Rebasing
+impl Repository {
+    /// Replay all commits that are reachable from `HEAD` but not from `upstream` onto `upstream`, or onto
+    /// [`options.onto`](Options::onto) if set, and update the current branch to point to the last of the replayed commits.
+    ///
+    /// Commits are replayed oldest first, and merge-commits are skipped, just like `git rebase` would do by default.
+    /// Commits whose changes are already present in `upstream` are skipped as well, unless
+    /// [`options.reapply_cherry_picks`](Options::reapply_cherry_picks) is set. If the commits to replay are based on
+    /// `onto` already, nothing is done.
+    ///
+    /// The index and the worktree must not have changes. `HEAD` is detached while the rebase is in progress, and if
+    /// a commit can't be replayed without conflicts, the rebase stops to let the conflicts be resolved in the worktree
+    /// and the index. The state of the rebase is persisted in the `rebase-merge` directory so it can be
+    /// [continued](Self::rebase_continue()) or [aborted](Self::rebase_abort()) later, even by `git` itself.
+    pub fn rebase(&self, upstream: impl Into<ObjectId>, options: Options) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::rebase()");
+        if let Some(state) = self.state() {
+            return Err(Error::InProgress(state));
+        }
+        let upstream = upstream.into();
+        let onto = options.onto.unwrap_or(upstream);
+        let head_name = self.head_name()?;
+        let head_id = self.head_id()?.detach();
+        let index = self.open_index()?;
+        let head_tree = find_commit(self, head_id)?.tree_id()?.detach();
+        merge::worktree::require_clean(self, &index, Some(&head_tree))?;
+
+        let (to_replay, skipped) = self.commits_to_replay(head_id, upstream, options.reapply_cherry_picks)?;
+        let outcome = Outcome {
+            skipped,
+            ..Default::default()
+        };
+        let is_up_to_date = match to_replay.first() {
+            Some(first) => find_commit(self, *first)?
+                .parent_ids()
+                .next()
+                .map_or(false, |parent| parent == onto),
+            None => onto == head_id,
+        };
+        if is_up_to_date {
+            return Ok(outcome);
+        }
+
+        let mut todo = List::default();
+        for id in to_replay {
+            let commit = find_commit(self, id)?;
+            todo.instructions.push(Instruction::new_with_commit(
+                Command::Pick,
+                id,
+                commit.message()?.summary().as_ref(),
+            ));
+        }
+        let state = gix_rebase::State {
+            head_name,
+            onto,
+            orig_head: head_id,
+            todo,
+            done: Default::default(),
+            interactive: false,
+            stopped_at: None,
+            message: None,
+            author: None,
+        };
+        state.write_to_dir(&self.rebase_state_dir())?;
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: "rebase (start)".into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Peeled(head_id),
+            },
+            name: "ORIG_HEAD".try_into().expect("valid"),
+            deref: false,
+        })?;
+
+        let onto_tree = find_commit(self, onto)?.tree_id()?.detach();
+        let index = merge::worktree::checkout(self, &index, self.index_from_tree_id(onto_tree)?, &[], false)?;
+        self.detach_head(onto, format!("rebase (start): checkout {onto}").into())?;
+        self.rebase_replay(state, index, outcome)
+    }
+
+    /// Continue a rebase that [stopped](Outcome::stopped) previously.
+    ///
+    /// All conflicts in the index must have been resolved, and the worktree must not have changes compared to the index.
+    /// The commit at which the rebase stopped is then committed with the content of the index, or skipped if it
+    /// ends up without changes.
+    pub fn rebase_continue(&self) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::rebase_continue()");
+        let mut state = self.rebase_state()?.ok_or(Error::NotInProgress)?;
+        self.rerere()?;
+        let index = self.open_index()?;
+        let mut outcome = Outcome::default();
+        if let Some(stopped_at) = state.stopped_at.take() {
+            let unresolved: Vec<BString> = index
+                .entries()
+                .iter()
+                .filter(|e| e.stage() != 0)
+                .map(|e| e.path(&index).to_owned())
+                .collect();
+            if !unresolved.is_empty() {
+                return Err(Error::UnresolvedConflicts { paths: unresolved });
+            }
+            merge::worktree::require_clean(self, &index, None)?;
+
+            let commit = find_commit(self, stopped_at)?;
+            let message = match state.message.take() {
+                Some(message) => message,
+                None => commit.message_raw()?.to_owned(),
+            };
+            let author = match state.author.take() {
+                Some(author) => author,
+                None => commit.author()?.to_owned(),
+            };
+            let tree = index.write_tree(|tree| self.write_object(tree).map(Id::detach))?;
+            self.commit_replayed(&mut outcome, stopped_at, tree, message, author)?;
+            state.write_to_dir(&self.rebase_state_dir())?;
+            remove_if_present(self.path().join(REBASE_HEAD))?;
+        }
+        self.rebase_replay(state, index, outcome)
+    }
+
+    /// Abort the rebase that is in progress, and restore `HEAD`, the index and the worktree to the state before it started.
+    ///
+    /// Note that all changes in the index and the worktree are discarded.
+    pub fn rebase_abort(&self) -> Result<(), Error> {
+        let _span = gix_trace::coarse!("gix::Repository::rebase_abort()");
+        let state = self.rebase_state()?.ok_or(Error::NotInProgress)?;
+        let index = self.open_index()?;
+        let orig_tree = find_commit(self, state.orig_head)?.tree_id()?.detach();
+        merge::worktree::checkout(self, &index, self.index_from_tree_id(orig_tree)?, &[], true)?;
+        self.rerere_clear()?;
+        match state.head_name {
+            Some(name) => {
+                let message = format!("rebase (abort): returning to {}", name.as_bstr());
+                self.attach_head(name, message.into())?;
+            }
+            None => self.detach_head(state.orig_head, "rebase (abort)".into())?,
+        }
+        self.remove_rebase_state()
+    }
+
+    /// Return the state of the rebase that is in progress, or `None` if there is none.
+    ///
+    /// Note that the state may have been written by `git` as well.
+    pub fn rebase_state(&self) -> Result<Option<gix_rebase::State>, gix_rebase::state::from_dir::Error> {
+        gix_rebase::State::from_dir(&self.rebase_state_dir(), COMMENT_CHAR)
+    }
+}
+
+/// Utilities
+impl Repository {
+    fn rebase_state_dir(&self) -> PathBuf {
+        self.path().join(gix_rebase::DIRECTORY_NAME)
+    }
+
+    fn remove_rebase_state(&self) -> Result<(), Error> {
+        remove_if_present(self.path().join(REBASE_HEAD))?;
+        gix_rebase::State::remove_dir(&self.rebase_state_dir())?;
+        Ok(())
+    }
+
+    fn index_from_tree_id(&self, tree: ObjectId) -> Result<gix_index::State, Error> {
+        gix_index::State::from_tree(&tree, &self.objects).map_err(|err| Error::IndexFromTree { id: tree, source: err })
+    }
+
+    /// Execute all instructions in `state`, with `index` being the index that matches `HEAD` and the worktree.
+    fn rebase_replay(
+        &self,
+        mut state: gix_rebase::State,
+        mut index: gix_index::File,
+        mut outcome: Outcome,
+    ) -> Result<Outcome, Error> {
+        if let Some(command) = state
+            .todo
+            .instructions
+            .iter()
+            .map(|i| i.command)
+            .find(|c| !matches!(c, Command::Pick | Command::Drop | Command::Noop))
+        {
+            return Err(Error::UnsupportedCommand {
+                command: command.as_str(),
+            });
+        }
+
+        let state_dir = self.rebase_state_dir();
+        let merge_options = self.merge_tree_options()?;
+        let mut todo = VecDeque::from(std::mem::take(&mut state.todo.instructions));
+        while let Some(instruction) = todo.pop_front() {
+            state.done.instructions.push(instruction.clone());
+            let id = match (instruction.command, instruction.commit()) {
+                (Command::Pick, Some(id)) => self.rev_parse_single(id)?.detach(),
+                _ => continue,
+            };
+            let commit = find_commit(self, id)?;
+            let mut parents = commit.parent_ids();
+            let base_tree = match (parents.next(), parents.next()) {
+                (None, _) => ObjectId::empty_tree(self.object_hash()),
+                (Some(parent), None) => find_commit(self, parent)?.tree_id()?.detach(),
+                (Some(_), Some(_)) => return Err(Error::MergeCommit { id }),
+            };
+            let head_tree = self.head_commit_tree()?;
+            let message = commit.message_raw()?.to_owned();
+            let other_label: BString = format!(
+                "{} ({})",
+                commit.id().shorten_or_id(),
+                commit.message()?.summary().to_str_lossy()
+            )
+            .into();
+            let ancestor_label: BString = format!("parent of {other_label}").into();
+            let merged = self.merge_trees(
+                base_tree,
+                head_tree,
+                commit.tree_id()?,
+                merge::blob::Labels {
+                    ancestor: Some(ancestor_label.as_ref()),
+                    current: Some("HEAD".into()),
+                    other: Some(other_label.as_ref()),
+                },
+                merge_options.clone(),
+            )?;
+
+            if merged.has_conflicts() {
+                merge::worktree::checkout(self, &index, merged.index, &merged.conflicts, false)?;
+                self.rerere()?;
+                state.stopped_at = Some(id);
+                state.message = Some(message);
+                state.author = Some(commit.author()?.to_owned());
+                write_state(&mut state, &todo, &state_dir)?;
+                std::fs::write(self.path().join(REBASE_HEAD), format!("{id}\n"))?;
+                outcome.stopped = Some(Stopped {
+                    commit: id,
+                    conflicts: merged.conflicts,
+                });
+                return Ok(outcome);
+            }
+
+            let tree = merged
+                .index
+                .write_tree(|tree| self.write_object(tree).map(Id::detach))?;
+            index = merge::worktree::checkout(self, &index, merged.index, &[], false)?;
+            self.commit_replayed(&mut outcome, id, tree, message, commit.author()?.to_owned())?;
+            write_state(&mut state, &todo, &state_dir)?;
+        }
+
+        if let Some(name) = &state.head_name {
+            let head_id = self.head_id()?.detach();
+            self.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: format!("rebase (finish): {} onto {}", name.as_bstr(), state.onto).into(),
+                    },
+                    expected: PreviousValue::MustExistAndMatch(Target::Peeled(state.orig_head)),
+                    new: Target::Peeled(head_id),
+                },
+                name: name.clone(),
+                deref: false,
+            })?;
+            let message = format!("rebase (finish): returning to {}", name.as_bstr());
+            self.attach_head(name.clone(), message.into())?;
+        }
+        self.remove_rebase_state()?;
+        Ok(outcome)
+    }
+
+    fn head_commit_tree(&self) -> Result<ObjectId, Error> {
+        let head_id = self.head_id()?.detach();
+        Ok(find_commit(self, head_id)?.tree_id()?.detach())
+    }
+
+    /// Commit `tree` on top of `HEAD` with `message` and `author` as a replay of `original`, or skip it if it has no changes.
+    fn commit_replayed(
+        &self,
+        outcome: &mut Outcome,
+        original: ObjectId,
+        tree: ObjectId,
+        message: BString,
+        author: gix_actor::Signature,
+    ) -> Result<(), Error> {
+        let head_id = self.head_id()?.detach();
+        if find_commit(self, head_id)?.tree_id()? == tree {
+            outcome.skipped.push(original);
+            return Ok(());
+        }
+        let committer = self.committer().ok_or(Error::CommitterMissing)??.to_owned();
+        let commit = gix_object::Commit {
+            tree,
+            parents: [head_id].into(),
+            author,
+            committer,
+            encoding: None,
+            message,
+            extra_headers: Default::default(),
+        };
+        let rewritten = self.write_object(&commit)?.detach();
+        let log_message = crate::reference::log::message("rebase (pick)", commit.message.as_ref(), 1);
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: log_message,
+                },
+                expected: PreviousValue::MustExistAndMatch(Target::Peeled(head_id)),
+                new: Target::Peeled(rewritten),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: false,
+        })?;
+        outcome.replayed.push(Replayed { original, rewritten });
+        Ok(())
+    }
+
+    fn detach_head(&self, id: ObjectId, message: BString) -> Result<(), Error> {
+        self.set_head(Target::Peeled(id), message)
+    }
+
+    fn attach_head(&self, name: FullName, message: BString) -> Result<(), Error> {
+        self.set_head(Target::Symbolic(name), message)
+    }
+
+    fn set_head(&self, new: Target, message: BString) -> Result<(), Error> {
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message,
+                },
+                expected: PreviousValue::Any,
+                new,
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: false,
+        })?;
+        Ok(())
+    }
+
+    /// Return all commits reachable from `head` but not from `upstream` to replay, oldest first, along with those that
+    /// should be skipped as their changes are present in `upstream` already.
+    fn commits_to_replay(
+        &self,
+        head: ObjectId,
+        upstream: ObjectId,
+        reapply_cherry_picks: bool,
+    ) -> Result<(Vec<ObjectId>, Vec<ObjectId>), Error> {
+        let exclusive_to_head = self.exclusive_commits(head, upstream)?;
+        if exclusive_to_head.is_empty() || reapply_cherry_picks {
+            return Ok((exclusive_to_head, Vec::new()));
+        }
+        let exclusive_to_upstream = self.exclusive_commits(upstream, head)?;
+        if exclusive_to_upstream.is_empty() {
+            return Ok((exclusive_to_head, Vec::new()));
+        }
+
+        let mut resource_cache = self.diff_resource_cache(
+            gix_diff::blob::pipeline::Mode::ToGit,
+            gix_diff::blob::pipeline::WorktreeRoots::default(),
+        )?;
+        let mut upstream_patch_ids = gix_hashtable::HashSet::default();
+        for id in exclusive_to_upstream {
+            if let Some(patch_id) = self.patch_id(id, &mut resource_cache)? {
+                upstream_patch_ids.insert(patch_id);
+            }
+        }
+        let (mut to_replay, mut skipped) = (Vec::new(), Vec::new());
+        for id in exclusive_to_head {
+            match self.patch_id(id, &mut resource_cache)? {
+                Some(patch_id) if upstream_patch_ids.contains(&patch_id) => skipped.push(id),
+                _ => to_replay.push(id),
+            }
+        }
+        Ok((to_replay, skipped))
+    }
+
+    /// Return all non-merge commits reachable from `tip` but not from `hidden`, oldest first.
+    ///
+    /// Like `git rev-list hidden..tip`, commits are visited newest first while remembering from which side they are reachable,
+    /// and the walk stops once only commits reachable from `hidden` are left, so the shared history isn't traversed.
+    fn exclusive_commits(&self, tip: ObjectId, hidden: ObjectId) -> Result<Vec<ObjectId>, Error> {
+        const TIP: u8 = 1;
+        const HIDDEN: u8 = 1 << 1;
+        const SLOP: usize = 5;
+        let mut flags = gix_hashtable::HashMap::<ObjectId, u8>::default();
+        let mut queue = gix_revwalk::PriorityQueue::new();
+        // Commits with the same time are visited in the order they were queued in, to visit children before their parents.
+        let mut num_queued = 0;
+        let mut enqueue = |queue: &mut gix_revwalk::PriorityQueue<_, _>, id: ObjectId| -> Result<(), Error> {
+            let commit = find_commit(self, id)?;
+            let parents: Vec<_> = commit.parent_ids().map(Id::detach).collect();
+            queue.insert((commit.time()?.seconds, std::cmp::Reverse(num_queued)), (id, parents));
+            num_queued += 1;
+            Ok(())
+        };
+        for (id, flag) in [(tip, TIP), (hidden, HIDDEN)] {
+            let is_queued = flags.contains_key(&id);
+            *flags.entry(id).or_default() |= flag;
+            if !is_queued {
+                enqueue(&mut queue, id)?;
+            }
+        }
+
+        let mut visited_parents = gix_hashtable::HashMap::<ObjectId, Vec<ObjectId>>::default();
+        let mut candidates = Vec::new();
+        // Like `git`, keep going for a few more commits once only hidden ones are queued, as commit times can't be trusted
+        // to order all commits before their parents. This allows hidden commits to reach visited ancestors in more cases.
+        let mut slop = SLOP;
+        loop {
+            if queue.iter_unordered().all(|(id, _)| flags[id] & HIDDEN != 0) {
+                if slop == 0 {
+                    break;
+                }
+                slop -= 1;
+            } else {
+                slop = SLOP;
+            }
+            let Some((_, (id, parents))) = queue.pop() else {
+                break;
+            };
+            let commit_flags = flags[&id];
+            if commit_flags & HIDDEN == 0 {
+                candidates.push(id);
+            }
+            for parent in &parents {
+                match flags.get_mut(parent) {
+                    Some(parent_flags) => {
+                        let becomes_hidden = commit_flags & HIDDEN != 0 && *parent_flags & HIDDEN == 0;
+                        *parent_flags |= commit_flags;
+                        if becomes_hidden {
+                            // Ancestors that were visited already are hidden as well, while queued ones pass it on when visited.
+                            let mut stack = visited_parents.get(parent).cloned().unwrap_or_default();
+                            while let Some(id) = stack.pop() {
+                                let flags = flags.get_mut(&id).expect("parents of visited commits are known");
+                                if *flags & HIDDEN == 0 {
+                                    *flags |= HIDDEN;
+                                    stack.extend(visited_parents.get(&id).into_iter().flatten());
+                                }
+                            }
+                        }
+                    }
+                    None => {
+                        flags.insert(*parent, commit_flags);
+                        enqueue(&mut queue, *parent)?;
+                    }
+                }
+            }
+            visited_parents.insert(id, parents);
+        }
+
+        let mut commits: Vec<_> = candidates
+            .into_iter()
+            .filter(|id| flags[id] & HIDDEN == 0 && visited_parents[id].len() < 2)
+            .collect();
+        commits.reverse();
+        Ok(commits)
+    }
+
+    /// Compute an id for the changes introduced by the commit with `id` compared to its first parent, which is independent
+    /// of line numbers and whitespace, similar to `git patch-id`. Return `None` if the commit has no changes.
+    fn patch_id(&self, id: ObjectId, resource_cache: &mut gix_diff::blob::Platform) -> Result<Option<ObjectId>, Error> {
+        let commit = find_commit(self, id)?;
+        let tree = commit.tree()?;
+        let parent_tree = match commit.parent_ids().next() {
+            Some(parent) => find_commit(self, parent)?.tree()?,
+            None => self.empty_tree(),
+        };
+        let mut hasher = gix_features::hash::hasher(self.object_hash());
+        let mut has_changes = false;
+        parent_tree
+            .changes()?
+            .track_path()
+            .track_rewrites(None)
+            .for_each_to_obtain_tree(&tree, |change| {
+                has_changes = true;
+                hasher.update(change.location);
+                hasher.update(&[0]);
+                change.diff(resource_cache)?.lines(|hunk| {
+                    use crate::object::blob::diff::lines::Change::*;
+                    let (before, after) = match hunk {
+                        Addition { lines } => (&[][..], lines),
+                        Deletion { lines } => (lines, &[][..]),
+                        Modification {
+                            lines_before,
+                            lines_after,
+                        } => (lines_before, lines_after),
+                    };
+                    for (sign, line) in before.iter().map(|l| (b'-', l)).chain(after.iter().map(|l| (b'+', l))) {
+                        hasher.update(&[sign]);
+                        for token in line.fields() {
+                            hasher.update(token);
+                        }
+                    }
+                    Ok::<_, std::convert::Infallible>(())
+                })?;
+                resource_cache.clear_resource_cache();
+                Ok::<_, Box<dyn std::error::Error + Send + Sync>>(crate::object::tree::diff::Action::Continue)
+            })?;
+        Ok(has_changes.then(|| ObjectId::from(hasher.digest())))
+    }
+}
+
+/// Write `state` to `dir` with `todo` as the instructions that are left to execute.
+fn write_state(
+    state: &mut gix_rebase::State,
+    todo: &VecDeque<Instruction>,
+    dir: &std::path::Path,
+) -> std::io::Result<()> {
+    state.todo.instructions.clear();
+    state.todo.instructions.extend(todo.iter().cloned());
+    state.write_to_dir(dir)
+}
+
+fn find_commit(repo: &Repository, id: impl Into<ObjectId>) -> Result<Commit<'_>, Error> {
+    Ok(repo.find_object(id)?.try_into_commit()?)
+}
+
+fn remove_if_present(path: PathBuf) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        res => res,
+    }
+}
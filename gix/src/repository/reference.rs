@@ -219,6 +219,16 @@ impl crate::Repository {
         Ok(self.head_commit()?.tree_id()?)
     }
 
+    /// Return the tree the `HEAD` reference currently points to after peeling it fully,
+    /// following symbolic references and tags until a commit is found.
+    ///
+    /// Note that this may fail for various reasons, most notably because the repository
+    /// is freshly initialized and doesn't have any commits yet. It could also fail if the
+    /// head does not point to a commit.
+    pub fn head_tree(&self) -> Result<crate::Tree<'_>, reference::head_tree::Error> {
+        Ok(self.head_commit()?.tree()?)
+    }
+
     /// Find the reference with the given partial or full `name`, like `main`, `HEAD`, `heads/branch` or `origin/other`,
     /// or return an error if it wasn't found.
     ///
@@ -263,3 +273,148 @@ impl crate::Repository {
         }
     }
 }
+
+/// Reference logs
+impl crate::Repository {
+    /// Return the policy to use when expiring the reference log of the reference `name` with
+    /// [`reflog_expire()`](Self::reflog_expire()), as configured by `gc.reflogExpire` and `gc.reflogExpireUnreachable`.
+    ///
+    /// By default, entries expire after 90 days, or after 30 days if they aren't reachable from the reference anymore.
+    /// Like in `git`, the reference log of `refs/stash` never expires as it holds all stash entries.
+    pub fn reflog_expire_policy(
+        &self,
+        name: &gix_ref::FullNameRef,
+    ) -> Result<reference::log::expire::Policy, reference::log::expire::Error> {
+        use crate::config::{cache::util::ApplyLeniency, tree::Gc};
+        const DAY_IN_SECONDS: u64 = 24 * 60 * 60;
+
+        if name.as_bstr() == "refs/stash" {
+            return Ok(Default::default());
+        }
+        let now = std::time::SystemTime::now();
+        let expiry = |name: &str,
+                      key: &'static crate::config::tree::gc::Expiry,
+                      default_days: u64|
+         -> Result<_, reference::log::expire::Error> {
+            Ok(self
+                .config
+                .resolved
+                .string_by_key(name)
+                .map(|value| key.try_into_expiry_date(value, now))
+                .transpose()
+                .with_leniency(self.config.lenient_config)?
+                .unwrap_or_else(|| now.checked_sub(std::time::Duration::from_secs(default_days * DAY_IN_SECONDS))))
+        };
+        Ok(reference::log::expire::Policy {
+            expire: expiry("gc.reflogExpire", &Gc::REFLOG_EXPIRE, 90)?,
+            expire_unreachable: expiry("gc.reflogExpireUnreachable", &Gc::REFLOG_EXPIRE_UNREACHABLE, 30)?,
+        })
+    }
+
+    /// Remove the entries of the reference log of the reference `name` that expired according to `policy`, similar to
+    /// `git reflog expire`.
+    ///
+    /// Entries expire if they are older than [`Policy::expire`](reference::log::expire::Policy::expire), or if they are older
+    /// than [`Policy::expire_unreachable`](reference::log::expire::Policy::expire_unreachable) and the commit they recorded
+    /// isn't reachable from the current value of the reference.
+    /// It's not an error if the reference log or the reference don't exist, in the latter case all entries are unreachable.
+    /// Use [`reflog_expire_policy()`](Self::reflog_expire_policy()) to obtain the configured policy.
+    pub fn reflog_expire(
+        &self,
+        name: &gix_ref::FullNameRef,
+        policy: reference::log::expire::Policy,
+    ) -> Result<reference::log::expire::Outcome, reference::log::expire::Error> {
+        use std::time::{Duration, SystemTime};
+
+        use reference::log::expire::Outcome;
+
+        let _span = gix_trace::coarse!("gix::Repository::reflog_expire()", name = ?name);
+        if policy.expire.is_none() && policy.expire_unreachable.is_none() {
+            return Ok(Outcome::default());
+        }
+        let path = self.refs.reflog_path(name);
+        let mut file = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Outcome::default()),
+            Err(err) => return Err(err.into()),
+        };
+        let lines = gix_ref::file::log::iter::forward(&data)
+            .map(|line| line.map(|line| line.to_owned()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let is_older_than = |time: &gix_date::Time, cutoff: Option<SystemTime>| {
+            cutoff.map_or(false, |cutoff| {
+                let time = SystemTime::UNIX_EPOCH + Duration::from_secs(time.seconds.max(0) as u64);
+                time < cutoff
+            })
+        };
+        let num_lines = lines.len();
+        let mut reachable = None;
+        let mut kept = Vec::with_capacity(num_lines);
+        for line in lines {
+            let expired = is_older_than(&line.signature.time, policy.expire)
+                || (is_older_than(&line.signature.time, policy.expire_unreachable) && {
+                    if reachable.is_none() {
+                        reachable = Some(self.commits_reachable_from_reference(name)?);
+                    }
+                    !reachable.as_ref().expect("just set").contains(&line.new_oid)
+                });
+            if !expired {
+                kept.push(line);
+            }
+        }
+
+        let outcome = Outcome {
+            num_removed: num_lines - kept.len(),
+            num_kept: kept.len(),
+        };
+        if outcome.num_removed == 0 {
+            return Ok(outcome);
+        }
+        for line in &kept {
+            line.write_to(&mut file)?;
+        }
+        file.commit().map_err(|err| err.error)?;
+        Ok(outcome)
+    }
+
+    /// Return all commits that are reachable from the peeled reference `name`, or nothing if it doesn't exist or doesn't
+    /// point to a commit.
+    fn commits_reachable_from_reference(
+        &self,
+        name: &gix_ref::FullNameRef,
+    ) -> Result<gix_hashtable::HashSet, reference::log::expire::Error> {
+        use gix_object::FindExt;
+
+        let mut seen = gix_hashtable::HashSet::default();
+        let Some(mut reference) = self.try_find_reference(name.as_partial_name())? else {
+            return Ok(seen);
+        };
+        if let Some(target) = reference.target().try_name() {
+            if self.try_find_reference(target.as_partial_name())?.is_none() {
+                return Ok(seen);
+            }
+        }
+        let tip = reference.peel_to_id_in_place()?.detach();
+        let mut buf = Vec::new();
+        let mut next = vec![tip];
+        seen.insert(tip);
+        while let Some(id) = next.pop() {
+            let commit = match self.objects.find_commit_iter(&id, &mut buf) {
+                Ok(commit) => commit,
+                Err(gix_object::find::existing_iter::Error::ObjectKind { .. }) if id == tip => {
+                    seen.clear();
+                    break;
+                }
+                Err(err) => return Err(err.into()),
+            };
+            for parent in commit.parent_ids() {
+                if seen.insert(parent) {
+                    next.push(parent);
+                }
+            }
+        }
+        Ok(seen)
+    }
+}
@@ -184,6 +184,14 @@ impl crate::Repository {
             Some(Err(err)) => return Some(Err(err)),
             None => Default::default(),
         };
+        let partial_clone_filter = config
+            .string_filter(
+                "remote",
+                Some(name_or_url),
+                config::tree::Remote::PARTIAL_CLONE_FILTER.name,
+                &mut filter,
+            )
+            .map(std::borrow::Cow::into_owned);
 
         match (url, fetch_specs, push_url, push_specs) {
             (None, None, None, None) => None,
@@ -219,6 +227,7 @@ impl crate::Repository {
                         push_specs,
                         rewrite_urls,
                         fetch_tags,
+                        partial_clone_filter,
                         self,
                     )
                     .map_err(Into::into),
@@ -0,0 +1,393 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use gix_index::entry::{Flags, Stat};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::tree::{Gc, Rerere},
+    merge,
+    rerere::{
+        normalize, read_merge_rr, write_merge_rr, Error, MergeRrEntry, Outcome, CACHE_DIR, MERGE_RR, POSTIMAGE,
+        PREIMAGE,
+    },
+    Repository,
+};
+
+/// Reusing recorded resolutions
+impl Repository {
+    /// Return `true` if conflicts and their resolutions should be recorded and replayed, as configured with `rerere.enabled`.
+    ///
+    /// If it isn't configured, it's enabled if the `rr-cache` directory exists, just like in `git`.
+    pub fn rerere_enabled(&self) -> Result<bool, crate::config::boolean::Error> {
+        match self.config.resolved.boolean_by_key("rerere.enabled") {
+            Some(value) => Rerere::ENABLED.enrich_error(value),
+            None => Ok(self.rerere_cache_dir().is_dir()),
+        }
+    }
+
+    /// Record the conflicts in the worktree files that are conflicting in the index, and resolve them with previously recorded
+    /// resolutions if these conflicts were seen before, similar to what `git rerere` does. Nothing is done if
+    /// [reusing recorded resolutions](Self::rerere_enabled()) is disabled.
+    ///
+    /// Paths with conflicts are tracked in the `MERGE_RR` file, and once they are resolved in the worktree, the resolution
+    /// is recorded to be replayed the next time the same conflict is seen. Conflicts are identified by their content, so the
+    /// same resolution applies to different paths and to conflicts with swapped sides.
+    /// If `rerere.autoUpdate` is enabled, paths that were resolved by replaying a resolution are also resolved in the index.
+    ///
+    /// This is called automatically when merges conflict during [rebases](Self::rebase()) or [cherry-picks](Self::cherry_pick()),
+    /// and when [continuing rebases](Self::rebase_continue()).
+    ///
+    /// ### Deviation
+    ///
+    /// Only a single resolution is kept per conflict, and conflicts in the `MERGE_RR` file that `git` recorded as variant
+    /// of another conflict are left untouched. Conflict markers are expected to be 7 characters long.
+    pub fn rerere(&self) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::rerere()");
+        if !self.rerere_enabled()? {
+            return Ok(Outcome::default());
+        }
+        let workdir = self.work_dir().ok_or(Error::BareRepository)?;
+        let merge_rr_path = self.path().join(MERGE_RR);
+        let mut entries = read_merge_rr(&merge_rr_path)?;
+        let mut index = self.open_index()?;
+        let mut outcome = Outcome::default();
+
+        for path in conflicted_paths(&index) {
+            if entries.iter().any(|entry| entry.path == path) {
+                continue;
+            }
+            let Some(content) = read_if_present(&workdir.join(gix_path::from_bstr(path.as_ref())))? else {
+                continue;
+            };
+            let Some(normalized) = normalize(&content, MARKER_SIZE, self.object_hash()) else {
+                continue;
+            };
+            let dir = self.rerere_conflict_dir(&normalized.id);
+            if !dir.join(POSTIMAGE).is_file() {
+                write_file(&dir.join(PREIMAGE), &normalized.content)?;
+                outcome.recorded_preimages.push(path.clone());
+            }
+            entries.push(MergeRrEntry {
+                id: normalized.id.to_string().into(),
+                path,
+            });
+        }
+
+        let mut remaining = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let Some(id) = entry.conflict_id() else {
+                remaining.push(entry);
+                continue;
+            };
+            let dir = self.rerere_conflict_dir(&id);
+            let file = workdir.join(gix_path::from_bstr(entry.path.as_ref()));
+            let Some(current) = read_if_present(&file)? else {
+                remaining.push(entry);
+                continue;
+            };
+            let normalized = normalize(&current, MARKER_SIZE, self.object_hash());
+            if let Some(merged) = replay(&dir, normalized.as_ref().map_or(&current, |n| &n.content))? {
+                if merged != current {
+                    write_file(&file, &merged)?;
+                }
+                outcome.resolved.push(entry.path);
+                continue;
+            }
+            if normalized.is_some() {
+                remaining.push(entry);
+                continue;
+            }
+            write_file(&dir.join(POSTIMAGE), &current)?;
+            outcome.recorded_resolutions.push(entry.path);
+        }
+        write_merge_rr(&merge_rr_path, &remaining)?;
+
+        if !outcome.resolved.is_empty()
+            && self
+                .config
+                .resolved
+                .boolean_by_key("rerere.autoUpdate")
+                .map(|value| Rerere::AUTO_UPDATE.enrich_error(value))
+                .transpose()?
+                .unwrap_or_default()
+        {
+            self.stage_resolved(workdir, &mut index, &outcome.resolved)?;
+        }
+        Ok(outcome)
+    }
+
+    /// Forget the recorded resolutions of the conflicts at `paths`, which must still be conflicting in the index, and record
+    /// their conflicts anew so their next resolution is recorded instead, similar to `git rerere forget`.
+    /// Each path matches itself and all paths in the directory it names.
+    ///
+    /// Return the paths whose conflicts had a resolution that was forgotten.
+    pub fn rerere_forget(&self, paths: impl IntoIterator<Item = impl AsRef<BStr>>) -> Result<Vec<BString>, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::rerere_forget()");
+        let patterns: Vec<BString> = paths.into_iter().map(|p| p.as_ref().to_owned()).collect();
+        let is_match = |path: &BStr| {
+            patterns.iter().any(|pattern| {
+                let pattern = pattern.trim_end_with(|c| c == '/');
+                pattern.is_empty()
+                    || path
+                        .strip_prefix(pattern)
+                        .map_or(false, |rest| rest.is_empty() || rest[0] == b'/')
+            })
+        };
+        let merge_rr_path = self.path().join(MERGE_RR);
+        let mut entries = read_merge_rr(&merge_rr_path)?;
+        let index = self.open_index()?;
+        let mut forgotten = Vec::new();
+        let mut buf = Vec::new();
+        for path in conflicted_paths(&index).into_iter().filter(|p| is_match(p.as_ref())) {
+            let stage = |stage: u32| -> Result<Vec<u8>, Error> {
+                Ok(match index.entry_by_path_and_stage(path.as_ref(), stage) {
+                    Some(entry) => self.find_object(entry.id)?.detach().data,
+                    None => Vec::new(),
+                })
+            };
+            merge::blob::BuiltinDriver::Text.merge(
+                &mut buf,
+                &stage(1)?,
+                &stage(2)?,
+                &stage(3)?,
+                Default::default(),
+                Default::default(),
+            );
+            let Some(normalized) = normalize(&buf, MARKER_SIZE, self.object_hash()) else {
+                continue;
+            };
+            let dir = self.rerere_conflict_dir(&normalized.id);
+            let postimage = dir.join(POSTIMAGE);
+            if postimage.is_file() {
+                remove_file(&postimage)?;
+                forgotten.push(path.clone());
+            }
+            write_file(&dir.join(PREIMAGE), &normalized.content)?;
+            entries.retain(|entry| entry.path != path);
+            entries.push(MergeRrEntry {
+                id: normalized.id.to_string().into(),
+                path,
+            });
+        }
+        write_merge_rr(&merge_rr_path, &entries)?;
+        Ok(forgotten)
+    }
+
+    /// Forget about the conflicts that are currently tracked in `MERGE_RR` and that weren't resolved yet, similar to
+    /// `git rerere clear`. This is done automatically when [aborting a rebase](Self::rebase_abort()).
+    pub fn rerere_clear(&self) -> Result<(), Error> {
+        let merge_rr_path = self.path().join(MERGE_RR);
+        for id in read_merge_rr(&merge_rr_path)?
+            .iter()
+            .filter_map(MergeRrEntry::conflict_id)
+        {
+            let dir = self.rerere_conflict_dir(&id);
+            if !dir.join(POSTIMAGE).is_file() {
+                remove_dir(&dir)?;
+            }
+        }
+        write_merge_rr(&merge_rr_path, &[])
+    }
+
+    /// Remove recorded conflicts whose resolution was last used before `gc.rerereResolved`, 60 days ago by default,
+    /// and conflicts without resolution that were recorded before `gc.rerereUnresolved`, 15 days ago by default,
+    /// similar to `git rerere gc`. Return the amount of removed conflicts.
+    pub fn rerere_gc(&self) -> Result<usize, Error> {
+        use crate::config::cache::util::ApplyLeniency;
+        const DAY_IN_SECONDS: u64 = 24 * 60 * 60;
+
+        let _span = gix_trace::coarse!("gix::Repository::rerere_gc()");
+        let now = SystemTime::now();
+        let expiry =
+            |name: &str, key: &'static crate::config::tree::gc::Expiry, default_days: u64| -> Result<_, Error> {
+                Ok(self
+                    .config
+                    .resolved
+                    .string_by_key(name)
+                    .map(|value| key.try_into_expiry_date(value, now))
+                    .transpose()
+                    .with_leniency(self.config.lenient_config)?
+                    .unwrap_or_else(|| now.checked_sub(std::time::Duration::from_secs(default_days * DAY_IN_SECONDS))))
+            };
+        let resolved_cutoff = expiry("gc.rerereResolved", &Gc::RERERE_RESOLVED, 60)?;
+        let unresolved_cutoff = expiry("gc.rerereUnresolved", &Gc::RERERE_UNRESOLVED, 15)?;
+
+        let cache_dir = self.rerere_cache_dir();
+        let dir_entries = match std::fs::read_dir(&cache_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => {
+                return Err(Error::Read {
+                    path: cache_dir,
+                    source: err,
+                })
+            }
+        };
+        let mut removed = 0;
+        for entry in dir_entries {
+            let entry = entry.map_err(|err| Error::Read {
+                path: cache_dir.clone(),
+                source: err,
+            })?;
+            let is_conflict_dir = entry
+                .file_name()
+                .to_str()
+                .map_or(false, |name| gix_hash::ObjectId::from_hex(name.as_bytes()).is_ok());
+            if !is_conflict_dir {
+                continue;
+            }
+            let dir = entry.path();
+            let (file, cutoff) = if dir.join(POSTIMAGE).is_file() {
+                (dir.join(POSTIMAGE), resolved_cutoff)
+            } else {
+                (dir.join(PREIMAGE), unresolved_cutoff)
+            };
+            let Some(cutoff) = cutoff else {
+                continue;
+            };
+            let modified = match file.metadata().and_then(|m| m.modified()) {
+                Ok(modified) => modified,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => SystemTime::UNIX_EPOCH,
+                Err(err) => {
+                    return Err(Error::Read {
+                        path: file,
+                        source: err,
+                    })
+                }
+            };
+            if modified < cutoff {
+                remove_dir(&dir)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn rerere_cache_dir(&self) -> PathBuf {
+        self.common_dir().join(CACHE_DIR)
+    }
+
+    fn rerere_conflict_dir(&self, id: &gix_hash::oid) -> PathBuf {
+        self.rerere_cache_dir().join(id.to_string())
+    }
+
+    /// Replace the conflicting entries at `paths` in `index` with the content of their worktree files, and write `index`.
+    fn stage_resolved(&self, workdir: &Path, index: &mut gix_index::File, paths: &[BString]) -> Result<(), Error> {
+        let mut pipeline = self.filter_pipeline(None)?.0;
+        let mut buf = Vec::new();
+        for path in paths {
+            let Some(mode) = index
+                .entry_by_path_and_stage(path.as_ref(), 2)
+                .or_else(|| index.entry_by_path_and_stage(path.as_ref(), 3))
+                .map(|entry| entry.mode)
+            else {
+                continue;
+            };
+            let file_path = workdir.join(gix_path::from_bstr(path.as_ref()));
+            let file = std::fs::File::open(&file_path).map_err(|err| Error::Read {
+                path: file_path.clone(),
+                source: err,
+            })?;
+            buf.clear();
+            std::io::copy(
+                &mut pipeline.convert_to_git(file, gix_path::from_bstr(path.as_ref()).as_ref(), index)?,
+                &mut buf,
+            )
+            .map_err(|err| Error::Read {
+                path: file_path,
+                source: err,
+            })?;
+            let id = self.write_blob(&buf)?.detach();
+            index.remove_entries(|_, entry_path, _| entry_path == path);
+            index.dangerously_push_entry(Stat::default(), id, Flags::empty(), mode, path.as_ref());
+        }
+        index.sort_entries();
+        index.write(Default::default())?;
+        Ok(())
+    }
+}
+
+/// The size of conflict markers.
+const MARKER_SIZE: usize = 7;
+
+/// Return the paths of all entries in `index` which were changed on both sides, in order.
+fn conflicted_paths(index: &gix_index::State) -> Vec<BString> {
+    let mut paths: Vec<BString> = index
+        .entries()
+        .iter()
+        .filter(|entry| entry.stage() == 2)
+        .map(|entry| entry.path(index))
+        .filter(|path| index.entry_by_path_and_stage(path, 3).is_some())
+        .map(ToOwned::to_owned)
+        .collect();
+    paths.dedup();
+    paths
+}
+
+/// Merge the recorded resolution in the conflict directory `dir` into `current` and return the result,
+/// or `None` if there is no resolution or if it doesn't apply cleanly.
+fn replay(dir: &Path, current: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+    let Some(postimage) = read_if_present(&dir.join(POSTIMAGE))? else {
+        return Ok(None);
+    };
+    let Some(preimage) = read_if_present(&dir.join(PREIMAGE))? else {
+        return Ok(None);
+    };
+    let mut merged = Vec::new();
+    let resolution = merge::blob::BuiltinDriver::Text.merge(
+        &mut merged,
+        &preimage,
+        current,
+        &postimage,
+        Default::default(),
+        Default::default(),
+    );
+    if resolution != merge::blob::Resolution::Complete {
+        return Ok(None);
+    }
+    // Rewrite the resolution to mark it as used, which keeps it from being garbage-collected.
+    write_file(&dir.join(POSTIMAGE), &postimage)?;
+    Ok(Some(merged))
+}
+
+fn read_if_present(path: &Path) -> Result<Option<Vec<u8>>, Error> {
+    match std::fs::read(path) {
+        Ok(data) => Ok(Some(data)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(Error::Read {
+            path: path.to_owned(),
+            source: err,
+        }),
+    }
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<(), Error> {
+    let write_error = |err| Error::Write {
+        path: path.to_owned(),
+        source: err,
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(write_error)?;
+    }
+    std::fs::write(path, data).map_err(write_error)
+}
+
+fn remove_file(path: &Path) -> Result<(), Error> {
+    std::fs::remove_file(path).map_err(|err| Error::Remove {
+        path: path.to_owned(),
+        source: err,
+    })
+}
+
+fn remove_dir(path: &Path) -> Result<(), Error> {
+    match std::fs::remove_dir_all(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(Error::Remove {
+            path: path.to_owned(),
+            source: err,
+        }),
+        _ => Ok(()),
+    }
+}
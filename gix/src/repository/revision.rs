@@ -46,3 +46,38 @@ impl crate::Repository {
         revision::walk::Platform::new(tips, self)
     }
 }
+
+/// Utilities
+impl crate::Repository {
+    /// Return the best common ancestor of `commit` and any of `others`, which is the most recent common ancestor that isn't
+    /// an ancestor of other common ancestors.
+    pub(crate) fn merge_base<E>(
+        &self,
+        commit: gix_hash::ObjectId,
+        others: &[gix_hash::ObjectId],
+    ) -> Result<Option<gix_hash::ObjectId>, E>
+    where
+        E: From<revision::walk::Error> + From<gix_traverse::commit::ancestors::Error>,
+    {
+        let ancestors = self
+            .rev_walk([commit])
+            .all()?
+            .map(|info| info.map(|info| info.id))
+            .collect::<Result<gix_hashtable::HashSet<_>, _>>()?;
+        let mut common = Vec::new();
+        let mut not_best = gix_hashtable::HashSet::default();
+        for info in self
+            .rev_walk(others.iter().copied())
+            .sorting(gix_traverse::commit::Sorting::ByCommitTimeNewestFirst)
+            .all()?
+        {
+            let info = info?;
+            if ancestors.contains(&info.id) {
+                // As all ancestors of common ancestors are common ancestors, the ones that aren't best are their parents.
+                not_best.extend(info.parent_ids.iter().copied());
+                common.push(info.id);
+            }
+        }
+        Ok(common.into_iter().find(|id| !not_best.contains(id)))
+    }
+}
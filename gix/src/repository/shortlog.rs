@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+
+use gix_hash::ObjectId;
+use gix_revision::Spec;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    ext::ObjectIdExt,
+    shortlog::{Error, Group, Options, Outcome},
+    Repository,
+};
+
+impl Repository {
+    /// Group all commits selected by `spec` by their author, or by their committer, with the mailmap applied, similar to
+    /// `git shortlog`.
+    ///
+    /// `spec` is typically obtained with [`rev_parse()`](Self::rev_parse()), like `v1.0..HEAD` to select all commits
+    /// made since `v1.0`.
+    pub fn shortlog(&self, spec: Spec, options: Options) -> Result<Outcome, Error> {
+        let (tips, hidden) = match spec {
+            Spec::Include(id) => (vec![peel_to_commit_id(self, id)?], Default::default()),
+            Spec::Exclude(_) => (Vec::new(), Default::default()),
+            Spec::Range { from, to } => (
+                vec![peel_to_commit_id(self, to)?],
+                ancestors(self, vec![peel_to_commit_id(self, from)?])?,
+            ),
+            Spec::Merge { theirs, ours } => {
+                let (theirs, ours) = (peel_to_commit_id(self, theirs)?, peel_to_commit_id(self, ours)?);
+                let ours_ancestors = ancestors(self, vec![ours])?;
+                let common = ancestors(self, vec![theirs])?
+                    .into_iter()
+                    .filter(|id| ours_ancestors.contains(id))
+                    .collect();
+                (vec![theirs, ours], common)
+            }
+            Spec::IncludeOnlyParents(id) => (parent_ids_of(self, id)?, Default::default()),
+            Spec::ExcludeParents(id) => (
+                vec![peel_to_commit_id(self, id)?],
+                ancestors(self, parent_ids_of(self, id)?)?,
+            ),
+        };
+
+        let mailmap = self.open_mailmap();
+        let mut groups = BTreeMap::<(BString, Option<BString>), Group>::new();
+        let mut commits = 0;
+        if !tips.is_empty() {
+            for info in self
+                .rev_walk(tips)
+                .sorting(gix_traverse::commit::Sorting::ByCommitTimeNewestFirst)
+                .selected(move |id| !hidden.contains(id))?
+            {
+                let commit = info?.object()?;
+                let commit = commit.decode()?;
+                let signature = mailmap.resolve(if options.committer {
+                    commit.committer
+                } else {
+                    commit.author
+                });
+                let email = options.email.then_some(signature.email);
+                let group = groups
+                    .entry((signature.name.clone(), email.clone()))
+                    .or_insert_with(|| Group {
+                        name: signature.name,
+                        email,
+                        count: 0,
+                        summaries: Vec::new(),
+                    });
+                group.count += 1;
+                if options.summaries {
+                    group.summaries.push(summary(commit.message));
+                }
+                commits += 1;
+            }
+        }
+
+        let mut groups: Vec<_> = groups
+            .into_values()
+            .map(|mut group| {
+                group.summaries.reverse();
+                group
+            })
+            .collect();
+        if options.sort_by_count {
+            groups.sort_by_key(|group| std::cmp::Reverse(group.count));
+        }
+        Ok(Outcome { groups, commits })
+    }
+}
+
+fn peel_to_commit_id(repo: &Repository, id: ObjectId) -> Result<ObjectId, Error> {
+    Ok(id.attach(repo).object()?.peel_to_kind(gix_object::Kind::Commit)?.id)
+}
+
+fn parent_ids_of(repo: &Repository, id: ObjectId) -> Result<Vec<ObjectId>, Error> {
+    let commit = repo.find_object(peel_to_commit_id(repo, id)?)?.into_commit();
+    Ok(commit.parent_ids().map(crate::Id::detach).collect())
+}
+
+/// Return the ids of `tips` and all of their ancestors.
+fn ancestors(repo: &Repository, tips: Vec<ObjectId>) -> Result<gix_hashtable::HashSet<ObjectId>, Error> {
+    if tips.is_empty() {
+        return Ok(Default::default());
+    }
+    Ok(repo
+        .rev_walk(tips)
+        .all()?
+        .map(|info| info.map(|info| info.id))
+        .collect::<Result<_, _>>()?)
+}
+
+/// Return the summary of `message` the way `git shortlog` shows it, without a leading `[PATCH]`, or `<none>` if it's empty.
+fn summary(message: &BStr) -> BString {
+    let summary = gix_object::commit::MessageRef::from_bytes(message.trim_start()).summary();
+    let mut summary = summary.as_bytes();
+    if summary.starts_with(b"[PATCH") {
+        if let Some(end) = summary.find_byte(b']') {
+            summary = summary[end + 1..].trim_start();
+        }
+    }
+    if summary.is_empty() {
+        "<none>".into()
+    } else {
+        summary.into()
+    }
+}
@@ -0,0 +1,9 @@
+use crate::{sparse_checkout, Repository};
+
+/// Sparse checkout
+impl Repository {
+    /// Return a platform to configure which files of the index are present in the worktree, similar to `git sparse-checkout`.
+    pub fn sparse_checkout(&mut self) -> sparse_checkout::Platform<'_> {
+        sparse_checkout::Platform { repo: self }
+    }
+}
@@ -0,0 +1,487 @@
+use std::path::Path;
+
+use gix_hash::ObjectId;
+use gix_index::entry::{Flags, Mode, Stat};
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+use gix_status::index_as_worktree::{Change as WorktreeChange, EntryStatus};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    merge,
+    stash::{ApplyOptions, Entry, Error, Outcome, SaveOptions, REFERENCE_NAME},
+    Commit, Id, Repository,
+};
+
+/// Stashing
+impl Repository {
+    /// Save all changes of the index and the worktree compared to `HEAD` as new stash entry, and reset the index and
+    /// the worktree to `HEAD`. Return the id of the new entry, or `None` if there was nothing to stash.
+    ///
+    /// The entry is structured like the ones created by `git stash`, so both can be used interchangeably. If
+    /// [`options.include_untracked`](SaveOptions::include_untracked) is set, untracked files that aren't ignored are
+    /// stashed and removed as well.
+    pub fn stash_save(&self, options: SaveOptions) -> Result<Option<ObjectId>, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::stash_save()");
+        let workdir = self.work_dir().ok_or(Error::BareRepository)?;
+        let index = self.open_index()?;
+        let unresolved = unresolved_paths(&index);
+        if !unresolved.is_empty() {
+            return Err(Error::UnresolvedConflicts { paths: unresolved });
+        }
+        let head = find_commit(self, self.head_id()?)?;
+        let head_tree = head.tree_id()?.detach();
+        let index_tree = index.write_tree(|tree| self.write_object(tree).map(Id::detach))?;
+        let worktree_state = self.worktree_state(workdir, &index)?;
+        let worktree_tree = worktree_state.write_tree(|tree| self.write_object(tree).map(Id::detach))?;
+        let untracked = if options.include_untracked {
+            self.untracked_files(workdir, &index)?
+        } else {
+            Vec::new()
+        };
+        if head_tree == index_tree && index_tree == worktree_tree && untracked.is_empty() {
+            return Ok(None);
+        }
+
+        let branch = match self.head_name()? {
+            Some(name) => name.shorten().to_owned(),
+            None => "(no branch)".into(),
+        };
+        let head_description = format!(
+            "{}: {} {}",
+            branch,
+            head.id().shorten_or_id(),
+            head.message()?.summary().to_str_lossy()
+        );
+        let head_id = head.id;
+        let index_commit = self.commit_stash_part(
+            index_tree,
+            vec![head_id],
+            format!("index on {head_description}\n").into(),
+        )?;
+        let mut parents = vec![head_id, index_commit];
+        if !untracked.is_empty() {
+            let mut untracked_state = gix_index::State::new(self.object_hash());
+            for (path, id, mode) in &untracked {
+                untracked_state.dangerously_push_entry(Stat::default(), *id, Flags::empty(), *mode, path.as_ref());
+            }
+            untracked_state.sort_entries();
+            let untracked_tree = untracked_state.write_tree(|tree| self.write_object(tree).map(Id::detach))?;
+            parents.push(self.commit_stash_part(
+                untracked_tree,
+                Vec::new(),
+                format!("untracked files on {head_description}\n").into(),
+            )?);
+        }
+        let message = match &options.message {
+            Some(message) => format!("On {}: {}", branch, message.to_str_lossy()),
+            None => format!("WIP on {head_description}"),
+        };
+        let stash_id = self.commit_stash_part(worktree_tree, parents, format!("{message}\n").into())?;
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: true,
+                    message: message.into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Peeled(stash_id),
+            },
+            name: REFERENCE_NAME.try_into().expect("valid"),
+            deref: false,
+        })?;
+
+        merge::worktree::checkout(
+            self,
+            &worktree_state,
+            self.index_state_from_tree(head_tree)?,
+            &[],
+            false,
+        )?;
+        for (path, _, _) in &untracked {
+            merge::worktree::remove_file(workdir, path.as_ref())?;
+        }
+        Ok(Some(stash_id))
+    }
+
+    /// Return all stash entries, with the most recent one first. The position of an entry in the list is its index,
+    /// as used in `stash@{<index>}`.
+    pub fn stash_list(&self) -> Result<Vec<Entry>, Error> {
+        let Some(data) = self.read_stash_reflog()? else {
+            return Ok(Vec::new());
+        };
+        let mut entries = Vec::new();
+        for line in gix_ref::file::log::iter::forward(&data) {
+            let line = line?;
+            entries.push(Entry {
+                id: line.new_oid(),
+                message: line.message.to_owned(),
+            });
+        }
+        entries.reverse();
+        Ok(entries)
+    }
+
+    /// Apply the changes of the stash entry at `index` to the index and the worktree, which must not have changes
+    /// compared to `HEAD`.
+    ///
+    /// Changes are merged with `HEAD` using the [configured merge options](Self::merge_tree_options()), and if they can't
+    /// be applied without conflicts, these are left in the worktree and the index to be resolved. Unless
+    /// [`options.reinstate_index`](ApplyOptions::reinstate_index) is set, only files that were added in the stashed index
+    /// are added to the index, just like `git stash apply` would do.
+    /// Stashed untracked files are restored as well, but it's an error if any of them exists already.
+    pub fn stash_apply(&self, index: usize, options: ApplyOptions) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::stash_apply()");
+        let workdir = self.work_dir().ok_or(Error::BareRepository)?;
+        let entry = self
+            .stash_list()?
+            .into_iter()
+            .nth(index)
+            .ok_or(Error::NoSuchEntry { index })?;
+        let stash = find_commit(self, entry.id)?;
+        let parents: Vec<ObjectId> = stash.parent_ids().map(Id::detach).collect();
+        let (base, stashed_index, untracked) = match parents.as_slice() {
+            [base, index] => (*base, *index, None),
+            [base, index, untracked] => (*base, *index, Some(*untracked)),
+            _ => return Err(Error::InvalidEntry { id: entry.id }),
+        };
+        let base_tree = find_commit(self, base)?.tree_id()?.detach();
+        let index_tree = find_commit(self, stashed_index)?.tree_id()?.detach();
+        let stash_tree = stash.tree_id()?.detach();
+
+        let head_tree = find_commit(self, self.head_id()?)?.tree_id()?.detach();
+        let current_index = self.open_index()?;
+        merge::worktree::require_clean(self, &current_index, Some(&head_tree))?;
+
+        let untracked = match untracked {
+            Some(id) => {
+                let state = self.index_state_from_tree(find_commit(self, id)?.tree_id()?.detach())?;
+                if let Some(existing) = state
+                    .entries()
+                    .iter()
+                    .map(|e| e.path(&state))
+                    .find(|path| workdir.join(gix_path::from_bstr(*path)).symlink_metadata().is_ok())
+                {
+                    return Err(Error::UntrackedFileExists {
+                        path: existing.to_owned(),
+                    });
+                }
+                Some(state)
+            }
+            None => None,
+        };
+
+        let merge_options = self.merge_tree_options()?;
+        let reinstated_index = if options.reinstate_index && index_tree != base_tree {
+            let merged = self.merge_trees(
+                base_tree,
+                head_tree,
+                index_tree,
+                Default::default(),
+                merge_options.clone(),
+            )?;
+            if merged.has_conflicts() {
+                return Err(Error::IndexConflicts);
+            }
+            Some(merged.index)
+        } else {
+            None
+        };
+
+        let merged = self.merge_trees(
+            base_tree,
+            head_tree,
+            stash_tree,
+            merge::blob::Labels {
+                ancestor: Some("Stash base".into()),
+                current: Some("Updated upstream".into()),
+                other: Some("Stashed changes".into()),
+            },
+            merge_options,
+        )?;
+        let has_conflicts = merged.has_conflicts();
+        // Like `git`, only files that are new compared to `HEAD` are added to the index, all other changes are only in the worktree.
+        let head_state = (!has_conflicts && reinstated_index.is_none())
+            .then(|| self.index_state_from_tree(head_tree))
+            .transpose()?;
+        let written = merge::worktree::checkout(self, &current_index, merged.index, &merged.conflicts, false)?;
+        if !has_conflicts {
+            let new_index = match (reinstated_index, head_state) {
+                (Some(reinstated), _) => reinstated,
+                (None, Some(mut head_state)) => {
+                    let added: Vec<_> = written
+                        .entries()
+                        .iter()
+                        .filter(|e| e.stage() == 0 && head_state.entry_by_path(e.path(&written)).is_none())
+                        .collect();
+                    for entry in added {
+                        let path = entry.path(&written);
+                        head_state.dangerously_push_entry(Stat::default(), entry.id, Flags::empty(), entry.mode, path);
+                    }
+                    head_state.sort_entries();
+                    head_state
+                }
+                (None, None) => unreachable!("one of both is always set without conflicts"),
+            };
+            self.write_index_with_stat_of(new_index, &written)?;
+        }
+        if let Some(untracked) = untracked {
+            merge::worktree::write_files(self, untracked, false)?;
+        }
+        Ok(Outcome {
+            conflicts: merged.conflicts,
+        })
+    }
+
+    /// Like [`stash_apply()`](Self::stash_apply()), but [drop](Self::stash_drop()) the entry at `index` afterwards if it
+    /// could be applied without conflicts.
+    pub fn stash_pop(&self, index: usize, options: ApplyOptions) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::stash_pop()");
+        let outcome = self.stash_apply(index, options)?;
+        if outcome.conflicts.is_empty() {
+            self.stash_drop(index)?;
+        }
+        Ok(outcome)
+    }
+
+    /// Remove the stash entry at `index` and return its id. If it's the last one, the stash reference is removed as well.
+    pub fn stash_drop(&self, index: usize) -> Result<ObjectId, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::stash_drop()");
+        let name: &gix_ref::FullNameRef = REFERENCE_NAME.try_into().expect("valid");
+        let data = self.read_stash_reflog()?.ok_or(Error::NoSuchEntry { index })?;
+        let mut lines = gix_ref::file::log::iter::forward(&data)
+            .map(|line| line.map(|line| line.to_owned()))
+            .collect::<Result<Vec<_>, _>>()?;
+        if index >= lines.len() {
+            return Err(Error::NoSuchEntry { index });
+        }
+        let position = lines.len() - 1 - index;
+        let dropped = lines.remove(position);
+        let previous_oid = match position.checked_sub(1) {
+            Some(previous) => lines[previous].new_oid,
+            None => ObjectId::null(self.object_hash()),
+        };
+        if let Some(next) = lines.get_mut(position) {
+            next.previous_oid = previous_oid;
+        }
+
+        let Some(new_top) = lines.last().map(|line| line.new_oid) else {
+            self.edit_reference(RefEdit {
+                change: Change::Delete {
+                    expected: PreviousValue::MustExistAndMatch(Target::Peeled(dropped.new_oid)),
+                    log: RefLog::AndReference,
+                },
+                name: name.to_owned(),
+                deref: false,
+            })?;
+            return Ok(dropped.new_oid);
+        };
+        if index == 0 {
+            self.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: Default::default(),
+                    },
+                    expected: PreviousValue::MustExistAndMatch(Target::Peeled(dropped.new_oid)),
+                    new: Target::Peeled(new_top),
+                },
+                name: name.to_owned(),
+                deref: false,
+            })?;
+        }
+        let mut file = gix_lock::File::acquire_to_update_resource(
+            self.refs.reflog_path(name),
+            gix_lock::acquire::Fail::Immediately,
+            None,
+        )?;
+        for line in &lines {
+            line.write_to(&mut file)?;
+        }
+        file.commit().map_err(|err| err.error)?;
+        Ok(dropped.new_oid)
+    }
+}
+
+/// Utilities
+impl Repository {
+    fn read_stash_reflog(&self) -> Result<Option<Vec<u8>>, Error> {
+        let name: &gix_ref::FullNameRef = REFERENCE_NAME.try_into().expect("valid");
+        match std::fs::read(self.refs.reflog_path(name)) {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn index_state_from_tree(&self, tree: ObjectId) -> Result<gix_index::State, Error> {
+        gix_index::State::from_tree(&tree, &self.objects).map_err(|err| Error::IndexFromTree { id: tree, source: err })
+    }
+
+    fn commit_stash_part(&self, tree: ObjectId, parents: Vec<ObjectId>, message: BString) -> Result<ObjectId, Error> {
+        let committer = self.committer().ok_or(Error::CommitterMissing)??.to_owned();
+        let author = match self.author() {
+            Some(author) => author?.to_owned(),
+            None => committer.clone(),
+        };
+        let commit = gix_object::Commit {
+            tree,
+            parents: parents.into(),
+            author,
+            committer,
+            encoding: None,
+            message,
+            extra_headers: Default::default(),
+        };
+        Ok(self.write_object(&commit)?.detach())
+    }
+
+    /// Return a copy of `index` with all entries changed to match the worktree, with blobs for changed files written
+    /// to the object database. Entries whose files are missing are removed.
+    fn worktree_state(&self, workdir: &Path, index: &gix_index::State) -> Result<gix_index::State, Error> {
+        let changes: std::collections::HashMap<BString, EntryStatus> = merge::worktree::worktree_changes(self, index)?
+            .into_iter()
+            .filter(|(_, status)| !matches!(status, EntryStatus::NeedsUpdate(_)))
+            .collect();
+        let fs_caps = self.filesystem_options()?;
+        let mut pipeline = None;
+        let mut state = gix_index::State::new(self.object_hash());
+        for entry in index.entries() {
+            let path = entry.path(index);
+            let (id, mode) = match changes.get(path) {
+                None => (entry.id, entry.mode),
+                Some(EntryStatus::Change(WorktreeChange::Removed)) => continue,
+                Some(_) => {
+                    let pipeline = match &mut pipeline {
+                        Some(pipeline) => pipeline,
+                        None => pipeline.insert(self.filter_pipeline(None)?.0),
+                    };
+                    match self.write_worktree_blob(workdir, path, Some(entry.mode), fs_caps, pipeline, index)? {
+                        Some(id_and_mode) => id_and_mode,
+                        None => continue,
+                    }
+                }
+            };
+            state.dangerously_push_entry(entry.stat, id, Flags::empty(), mode, path);
+        }
+        Ok(state)
+    }
+
+    /// Return the path, blob id and mode of all files that are untracked and not ignored, with their blobs written
+    /// to the object database.
+    fn untracked_files(
+        &self,
+        workdir: &Path,
+        index: &gix_index::State,
+    ) -> Result<Vec<(BString, ObjectId, Mode)>, Error> {
+        let mut collect = gix_dir::walk::delegate::Collect::default();
+        self.dirwalk(
+            index,
+            None::<&BStr>,
+            self.dirwalk_options()?
+                .emit_untracked(gix_dir::walk::EmissionMode::Matching),
+            &mut collect,
+        )?;
+        let fs_caps = self.filesystem_options()?;
+        let mut pipeline = self.filter_pipeline(None)?.0;
+        let mut out = Vec::new();
+        for (entry, _) in collect.into_entries_by_path() {
+            if entry.status != gix_dir::entry::Status::Untracked
+                || !matches!(
+                    entry.disk_kind,
+                    Some(gix_dir::entry::Kind::File | gix_dir::entry::Kind::Symlink)
+                )
+            {
+                continue;
+            }
+            if let Some((id, mode)) =
+                self.write_worktree_blob(workdir, entry.rela_path.as_ref(), None, fs_caps, &mut pipeline, index)?
+            {
+                out.push((entry.rela_path, id, mode));
+            }
+        }
+        Ok(out)
+    }
+
+    /// Write the worktree file at `rela_path` as blob and return its id along with its mode, or `None` if it's not a file.
+    /// `index_mode` is used to fill in information if the filesystem can't represent it.
+    fn write_worktree_blob(
+        &self,
+        workdir: &Path,
+        rela_path: &BStr,
+        index_mode: Option<Mode>,
+        fs_caps: gix_fs::Capabilities,
+        pipeline: &mut crate::filter::Pipeline<'_>,
+        index: &gix_index::State,
+    ) -> Result<Option<(ObjectId, Mode)>, Error> {
+        let path = workdir.join(gix_path::from_bstr(rela_path));
+        let read_error = |err| Error::ReadFile {
+            path: path.clone(),
+            source: err,
+        };
+        let metadata = match path.symlink_metadata() {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(read_error(err)),
+        };
+        if metadata.is_symlink() {
+            let target = std::fs::read_link(&path).map_err(read_error)?;
+            let target = gix_path::into_bstr(target);
+            let id = self.write_blob(target.as_ref())?.detach();
+            return Ok(Some((id, Mode::SYMLINK)));
+        }
+        if !metadata.is_file() {
+            return Ok(None);
+        }
+        let mode = if fs_caps.executable_bit {
+            if gix_fs::is_executable(&metadata) {
+                Mode::FILE_EXECUTABLE
+            } else {
+                Mode::FILE
+            }
+        } else {
+            match index_mode {
+                Some(mode) if mode == Mode::FILE_EXECUTABLE || (mode == Mode::SYMLINK && !fs_caps.symlink) => mode,
+                _ => Mode::FILE,
+            }
+        };
+        let file = std::fs::File::open(&path).map_err(read_error)?;
+        let mut data = Vec::new();
+        let mut converted = pipeline.convert_to_git(file, gix_path::from_bstr(rela_path).as_ref(), index)?;
+        std::io::copy(&mut converted, &mut data).map_err(read_error)?;
+        let id = self.write_blob(&data)?.detach();
+        Ok(Some((id, mode)))
+    }
+
+    /// Write `new` as index, using the stat information from `written` for all entries that are unchanged.
+    fn write_index_with_stat_of(&self, mut new: gix_index::State, written: &gix_index::State) -> Result<(), Error> {
+        for (entry, path) in new.entries_mut_with_paths() {
+            entry.stat = written
+                .entry_by_path_and_stage(path, 0)
+                .filter(|e| e.id == entry.id && e.mode == entry.mode)
+                .map(|e| e.stat)
+                .unwrap_or_default();
+        }
+        gix_index::File::from_state(new, self.index_path()).write(Default::default())?;
+        Ok(())
+    }
+}
+
+fn unresolved_paths(index: &gix_index::State) -> Vec<BString> {
+    let mut paths: Vec<BString> = index
+        .entries()
+        .iter()
+        .filter(|e| e.stage() != 0)
+        .map(|e| e.path(index).to_owned())
+        .collect();
+    paths.dedup();
+    paths
+}
+
+fn find_commit(repo: &Repository, id: impl Into<ObjectId>) -> Result<Commit<'_>, Error> {
+    Ok(repo.find_object(id)?.try_into_commit()?)
+}
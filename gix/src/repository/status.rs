@@ -0,0 +1,47 @@
+use crate::{repository::tree_index_status, status::tree_index, Repository};
+
+/// Status
+impl Repository {
+    /// Compare the tree with `tree_id`, typically the one of `HEAD` or the empty tree if it is unborn, with `index` and call `cb` with each change that would
+    /// be committed, similar to what `git status` shows as *changes to be committed*.
+    ///
+    /// If `options` is `None`, renames and copies will be tracked as configured by `status.renames` and `status.renameLimit`,
+    /// which fall back to `diff.renames` and `diff.renameLimit`, with rename tracking being enabled if nothing is configured.
+    ///
+    /// Note that if a clone with `--filter=blob=none` was created, rename tracking may fail as it might
+    /// try to access blobs to compute a similarity metric.
+    pub fn tree_index_status<E>(
+        &self,
+        tree_id: &gix_hash::oid,
+        index: &gix_index::State,
+        options: Option<tree_index::Options>,
+        cb: impl FnMut(tree_index::Change<'_>) -> Result<tree_index::Action, E>,
+    ) -> Result<tree_index::Outcome, tree_index_status::Error>
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    {
+        let options = match options {
+            Some(options) => options,
+            None => tree_index::Options {
+                rewrites: self.config.status_renames()?,
+            },
+        };
+        let tree = if tree_id == gix_hash::ObjectId::empty_tree(self.object_hash()) {
+            gix_index::State::new(self.object_hash())
+        } else {
+            gix_index::State::from_tree(tree_id, &self.objects)?
+        };
+        let mut resource_cache = self.diff_resource_cache(
+            gix_diff::blob::pipeline::Mode::ToGit,
+            gix_diff::blob::pipeline::WorktreeRoots::default(),
+        )?;
+        Ok(crate::status::tree_index(
+            &tree,
+            index,
+            cb,
+            &mut resource_cache,
+            &self.objects,
+            options,
+        )?)
+    }
+}
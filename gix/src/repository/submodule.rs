@@ -73,6 +73,9 @@ impl Repository {
     }
 
     /// Return the list of available submodules, or `None` if there is no submodule configuration.
+    ///
+    /// Note that this includes submodules that aren't active, use [`active_submodules()`](Self::active_submodules())
+    /// to only obtain those that git would consider for recursive operations.
     #[doc(alias = "git2")]
     pub fn submodules(&self) -> Result<Option<impl Iterator<Item = crate::Submodule<'_>>>, submodule::modules::Error> {
         let modules = match self.modules()? {
@@ -93,4 +96,22 @@ impl Repository {
                 }),
         ))
     }
+
+    /// Return the list of submodules that are [active](crate::Submodule::is_active()), or `None` if there is no submodule configuration.
+    ///
+    /// Like git, a submodule is active if `submodule.<name>.active` is true, or if its path matches the `submodule.active` pathspecs,
+    /// or, if neither is configured, if `submodule.<name>.url` is set.
+    pub fn active_submodules(&self) -> Result<Option<Vec<crate::Submodule<'_>>>, submodule::active_submodules::Error> {
+        let submodules = match self.submodules()? {
+            None => return Ok(None),
+            Some(submodules) => submodules,
+        };
+        let mut out = Vec::new();
+        for submodule in submodules {
+            if submodule.is_active()? {
+                out.push(submodule);
+            }
+        }
+        Ok(Some(out))
+    }
 }
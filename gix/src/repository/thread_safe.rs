@@ -7,6 +7,126 @@ mod access {
     }
 }
 
+mod object {
+    use gix_hash::ObjectId;
+    use gix_object::{Exists, Find};
+    use gix_odb::{Header, HeaderExt};
+
+    use crate::{object, ObjectDetached};
+
+    /// Object access without a thread-local [`Repository`](crate::Repository).
+    ///
+    /// Each call obtains a new handle to the object database, which picks up the latest snapshot of all loaded packs without locking.
+    /// There are no object or pack caches involved, so for reading many objects from a single thread, a [thread-local
+    /// repository](crate::ThreadSafeRepository::to_thread_local()) is still faster.
+    /// Objects missing in partial clones are not fetched from promisor remotes.
+    impl crate::ThreadSafeRepository {
+        /// The kind of hash used for all objects in this repository.
+        pub fn object_hash(&self) -> gix_hash::Kind {
+            self.config.object_hash
+        }
+
+        /// Find the object with `id` in the object database or return an error if it could not be found.
+        pub fn find_object(&self, id: impl Into<ObjectId>) -> Result<ObjectDetached, object::find::existing::Error> {
+            let id = id.into();
+            self.try_find_object(id)
+                .map_err(|err| object::find::existing::Error::Find(err.0))?
+                .ok_or(object::find::existing::Error::NotFound { oid: id })
+        }
+
+        /// Try to find the object with `id` or return `None` if it wasn't found.
+        pub fn try_find_object(&self, id: impl Into<ObjectId>) -> Result<Option<ObjectDetached>, object::find::Error> {
+            let id = id.into();
+            if id == ObjectId::empty_tree(self.object_hash()) {
+                return Ok(Some(ObjectDetached {
+                    id,
+                    kind: gix_object::Kind::Tree,
+                    data: Vec::new(),
+                }));
+            }
+            let mut data = Vec::new();
+            let kind = match self.objects.to_handle().try_find(&id, &mut data)? {
+                Some(obj) => obj.kind,
+                None => return Ok(None),
+            };
+            Ok(Some(ObjectDetached { id, kind, data }))
+        }
+
+        /// Obtain information about an object without fully decoding it, or fail if the object doesn't exist.
+        pub fn find_header(
+            &self,
+            id: impl Into<ObjectId>,
+        ) -> Result<gix_odb::find::Header, object::find::existing::Error> {
+            let id = id.into();
+            if id == ObjectId::empty_tree(self.object_hash()) {
+                return Ok(gix_odb::find::Header::Loose {
+                    kind: gix_object::Kind::Tree,
+                    size: 0,
+                });
+            }
+            self.objects.to_handle().header(id)
+        }
+
+        /// Obtain information about an object without fully decoding it, or `None` if the object doesn't exist.
+        pub fn try_find_header(
+            &self,
+            id: impl Into<ObjectId>,
+        ) -> Result<Option<gix_odb::find::Header>, object::find::Error> {
+            let id = id.into();
+            if id == ObjectId::empty_tree(self.object_hash()) {
+                return Ok(Some(gix_odb::find::Header::Loose {
+                    kind: gix_object::Kind::Tree,
+                    size: 0,
+                }));
+            }
+            self.objects.to_handle().try_header(&id).map_err(Into::into)
+        }
+
+        /// Return `true` if `id` exists in the object database.
+        pub fn has_object(&self, id: impl AsRef<gix_hash::oid>) -> bool {
+            let id = id.as_ref();
+            id == ObjectId::empty_tree(self.object_hash()) || self.objects.to_handle().exists(id)
+        }
+    }
+}
+
+mod reference {
+    use gix_ref::PartialNameRef;
+
+    use crate::reference;
+
+    /// Reference access without a thread-local [`Repository`](crate::Repository).
+    impl crate::ThreadSafeRepository {
+        /// Find the reference named `name`, like `main`, `heads/branch`, `HEAD` or `origin/other`, and return it,
+        /// or fail if it doesn't exist.
+        pub fn find_reference<'a, Name, E>(
+            &self,
+            name: Name,
+        ) -> Result<gix_ref::Reference, reference::find::existing::Error>
+        where
+            Name: TryInto<&'a PartialNameRef, Error = E>,
+            gix_ref::file::find::Error: From<E>,
+        {
+            self.try_find_reference(name)?
+                .ok_or(reference::find::existing::Error::NotFound)
+        }
+
+        /// Try to find the reference named `name`, like `main`, `heads/branch`, `HEAD` or `origin/other`, and return it.
+        ///
+        /// Otherwise return `None` if the reference wasn't found.
+        pub fn try_find_reference<'a, Name, E>(
+            &self,
+            name: Name,
+        ) -> Result<Option<gix_ref::Reference>, reference::find::Error>
+        where
+            Name: TryInto<&'a PartialNameRef, Error = E>,
+            gix_ref::file::find::Error: From<E>,
+        {
+            Ok(self.refs.try_find(name)?)
+        }
+    }
+}
+
 mod location {
 
     impl crate::ThreadSafeRepository {
@@ -0,0 +1,150 @@
+use std::{borrow::Cow, ffi::OsString};
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    config::tree::{Core, Init, Key, Sequence},
+    repository::identity,
+};
+
+/// A logical variable as known to `git var`, see [`Repository::var()`](crate::Repository::var()).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Var {
+    /// `GIT_AUTHOR_IDENT`, the author identity along with the current time.
+    AuthorIdent,
+    /// `GIT_COMMITTER_IDENT`, the committer identity along with the current time.
+    CommitterIdent,
+    /// `GIT_EDITOR`, the program to use for editing text like commit messages.
+    Editor,
+    /// `GIT_SEQUENCE_EDITOR`, the program to use for editing the todo list of an interactive rebase.
+    SequenceEditor,
+    /// `GIT_PAGER`, the program to use for paging output, which is `cat` if paging is disabled.
+    Pager,
+    /// `GIT_DEFAULT_BRANCH`, the name of the branch to create in new repositories, which is `main` unless configured otherwise.
+    DefaultBranch,
+}
+
+impl Var {
+    /// All variables, in the order in which `git var -l` lists them.
+    pub const ALL: &'static [Var] = &[
+        Var::AuthorIdent,
+        Var::CommitterIdent,
+        Var::Editor,
+        Var::SequenceEditor,
+        Var::Pager,
+        Var::DefaultBranch,
+    ];
+
+    /// Return the name of the variable, like `GIT_EDITOR`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Var::AuthorIdent => "GIT_AUTHOR_IDENT",
+            Var::CommitterIdent => "GIT_COMMITTER_IDENT",
+            Var::Editor => "GIT_EDITOR",
+            Var::SequenceEditor => "GIT_SEQUENCE_EDITOR",
+            Var::Pager => "GIT_PAGER",
+            Var::DefaultBranch => "GIT_DEFAULT_BRANCH",
+        }
+    }
+
+    /// Return the variable with the given `name`, like `GIT_EDITOR`, or `None` if it is unknown.
+    pub fn from_name(name: &str) -> Option<Var> {
+        Var::ALL.iter().copied().find(|var| var.name() == name)
+    }
+}
+
+/// The error returned by [`Repository::var()`](crate::Repository::var()) and related methods.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Identity(#[from] identity::Error),
+    #[error("Terminal is dumb, but EDITOR unset")]
+    DumbTerminal,
+}
+
+/// Resolution of logical variables, similar to `git var`.
+impl crate::Repository {
+    /// Resolve `var` like `git var` would, and return its value.
+    pub fn var(&self, var: Var) -> Result<BString, Error> {
+        Ok(match var {
+            Var::AuthorIdent => ident(self.author_ident()?),
+            Var::CommitterIdent => ident(self.committer_ident()?),
+            Var::Editor => self.editor()?,
+            Var::SequenceEditor => self.sequence_editor()?,
+            Var::Pager => self.pager().unwrap_or_else(|| "cat".into()),
+            Var::DefaultBranch => self
+                .config
+                .resolved
+                .string("init", None, Init::DEFAULT_BRANCH.name)
+                .map_or_else(|| crate::init::DEFAULT_BRANCH_NAME.into(), Cow::into_owned),
+        })
+    }
+
+    /// Return the program to use for editing text, which is `GIT_EDITOR`, `core.editor`, `VISUAL` unless the terminal is dumb,
+    /// `EDITOR`, or `vi`, in that order.
+    ///
+    /// It's an error if the terminal is dumb, or if `TERM` is unset, and no editor is configured.
+    /// Note that `VISUAL`, `EDITOR` and `TERM` are only read if [the `git_prefix` permission](crate::open::permissions::Environment::git_prefix)
+    /// allows it.
+    pub fn editor(&self) -> Result<BString, Error> {
+        if let Some(editor) = self.program(&Core::EDITOR) {
+            return Ok(editor);
+        }
+        let term = self.env_var("TERM");
+        let is_dumb = term.as_ref().map_or(true, |term| term == "dumb");
+        if !is_dumb {
+            if let Some(editor) = self.env_var("VISUAL") {
+                return Ok(editor);
+            }
+        }
+        match self.env_var("EDITOR") {
+            Some(editor) => Ok(editor),
+            None if is_dumb => Err(Error::DumbTerminal),
+            None => Ok("vi".into()),
+        }
+    }
+
+    /// Return the program to use for editing the todo list of an interactive rebase, which is `GIT_SEQUENCE_EDITOR`,
+    /// `sequence.editor`, or the [editor](Self::editor()) in that order.
+    pub fn sequence_editor(&self) -> Result<BString, Error> {
+        match self.program(&Sequence::EDITOR) {
+            Some(editor) => Ok(editor),
+            None => self.editor(),
+        }
+    }
+
+    /// Return the program to use for paging output, which is `GIT_PAGER`, `core.pager`, `PAGER` or `less`, in that order,
+    /// or `None` if paging is disabled as the pager is empty or `cat`.
+    ///
+    /// Note that `PAGER` is only read if [the `git_prefix` permission](crate::open::permissions::Environment::git_prefix)
+    /// allows it.
+    pub fn pager(&self) -> Option<BString> {
+        let pager = self
+            .program(&Core::PAGER)
+            .or_else(|| self.env_var("PAGER"))
+            .unwrap_or_else(|| "less".into());
+        (!pager.trim().is_empty() && pager != "cat").then_some(pager)
+    }
+
+    fn program(&self, key: &'static dyn Key) -> Option<BString> {
+        self.config_snapshot()
+            .trusted_program(key.logical_name().as_str())
+            .and_then(|program| gix_path::os_str_into_bstr(&program).ok().map(ToOwned::to_owned))
+    }
+
+    fn env_var(&self, name: &str) -> Option<BString> {
+        self.options
+            .permissions
+            .env
+            .git_prefix
+            .check_opt(name)
+            .and_then(std::env::var_os)
+            .and_then(|value: OsString| gix_path::os_string_into_bstring(value).ok())
+    }
+}
+
+fn ident(outcome: identity::Outcome) -> BString {
+    let mut buf = Vec::new();
+    outcome.signature.write_to(&mut buf).expect("write to memory works");
+    buf.into()
+}
@@ -1,4 +1,4 @@
-use crate::{worktree, Worktree};
+use crate::{bstr::BString, worktree, Worktree};
 
 /// Interact with individual worktrees and their information.
 impl crate::Repository {
@@ -25,6 +25,49 @@ impl crate::Repository {
         res.sort_by(|a, b| a.git_dir.cmp(&b.git_dir));
         Ok(res)
     }
+
+    /// Remove the private files of all linked worktrees whose checkout doesn't exist anymore, unless they are locked, similar to
+    /// `git worktree prune`. Return the ids of all worktrees that were pruned, or that would be pruned if `dry_run` is `true`.
+    pub fn worktree_prune(&self, dry_run: bool) -> std::io::Result<Vec<BString>> {
+        let mut pruned = Vec::new();
+        let iter = match std::fs::read_dir(self.common_dir().join("worktrees")) {
+            Ok(iter) => iter,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(pruned),
+            Err(err) => return Err(err),
+        };
+        for entry in iter {
+            let git_dir = entry?.path();
+            let is_dir = git_dir.is_dir();
+            if is_dir && git_dir.join("locked").is_file() {
+                continue;
+            }
+            let is_stale = !is_dir
+                || match gix_discover::path::from_plain_file(&git_dir.join("gitdir")) {
+                    Some(Ok(dot_git)) => dot_git.as_os_str().is_empty() || !dot_git.exists(),
+                    Some(Err(_)) | None => true,
+                };
+            if !is_stale {
+                continue;
+            }
+            if !dry_run {
+                if is_dir {
+                    std::fs::remove_dir_all(&git_dir)?;
+                } else {
+                    std::fs::remove_file(&git_dir)?;
+                }
+            }
+            pruned.push(
+                gix_path::into_bstr(std::path::Path::new(git_dir.file_name().expect("read from directory")))
+                    .into_owned(),
+            );
+        }
+        if !dry_run {
+            worktree::remove_worktrees_dir_if_empty(self);
+        }
+        pruned.sort();
+        Ok(pruned)
+    }
+
     /// Return the repository owning the main worktree, typically from a linked worktree.
     ///
     /// Note that it might be the one that is currently open if this repository doesn't point to a linked worktree.
@@ -51,11 +94,12 @@ impl crate::Repository {
         self.config.is_bare && self.work_dir().is_none()
     }
 
-    /// If `id` points to a tree, produce a stream that yields one worktree entry after the other. The index of the tree at `id`
-    /// is returned as well as it is an intermediate byproduct that might be useful to callers.
+    /// If `id` points to a tree or commit, produce a stream that yields one worktree entry after the other. The index of the tree
+    /// at `id`, or of the tree of the commit at `id`, is returned as well as it is an intermediate byproduct that might be useful to callers.
     ///
     /// The entries will look exactly like they would if one would check them out, with filters applied.
     /// The `export-ignore` attribute is used to skip blobs or directories to which it applies.
+    /// If `id` is a commit, `$Format:…$` placeholders in blobs with the `export-subst` attribute are expanded with its information.
     #[cfg(feature = "worktree-stream")]
     #[gix_macros::momo]
     pub fn worktree_stream(
@@ -65,32 +109,86 @@ impl crate::Repository {
         use gix_odb::HeaderExt;
         let id = id.into();
         let header = self.objects.header(id)?;
-        if !header.kind().is_tree() {
-            return Err(crate::repository::worktree_stream::Error::NotATree {
-                id,
-                actual: header.kind(),
-            });
-        }
+        let (tree, commit) = match header.kind() {
+            gix_object::Kind::Tree => (id, None),
+            gix_object::Kind::Commit => (self.find_object(id)?.into_commit().tree_id()?.detach(), Some(id)),
+            actual => return Err(crate::repository::worktree_stream::Error::NotATree { id, actual }),
+        };
 
         // TODO(perf): potential performance improvements could be to use the index at `HEAD` if possible (`index_from_head_tree…()`)
         // TODO(perf): when loading a non-HEAD tree, we effectively traverse the tree twice. This is usually fast though, and sharing
         //             an object cache between the copies of the ODB handles isn't trivial and needs a lock.
-        let index = self.index_from_tree(&id)?;
+        let index = self.index_from_tree(&tree)?;
         let mut cache = self
             .attributes_only(&index, gix_worktree::stack::state::attributes::Source::IdMapping)?
             .detach();
         let pipeline = gix_filter::Pipeline::new(self.command_context()?, crate::filter::Pipeline::options(self)?);
         let objects = self.objects.clone().into_arc().expect("TBD error handling");
-        let stream = gix_worktree_stream::from_tree(
-            id,
-            objects.clone(),
-            pipeline,
-            move |path, mode, attrs| -> std::io::Result<()> {
+        let attributes = {
+            let objects = objects.clone();
+            move |path: &crate::bstr::BStr,
+                  mode: gix_object::tree::EntryMode,
+                  attrs: &mut gix_attributes::search::Outcome|
+                  -> std::io::Result<()> {
                 let entry = cache.at_entry(path, Some(mode.is_tree()), &objects)?;
                 entry.matching_attributes(attrs);
                 Ok(())
-            },
-        );
+            }
+        };
+        let stream = match commit {
+            Some(commit) => gix_worktree_stream::from_commit(commit, objects, pipeline, attributes),
+            None => gix_worktree_stream::from_tree(tree, objects, pipeline, attributes),
+        };
+        Ok((stream, index))
+    }
+
+    /// Like [`worktree_stream()`](Self::worktree_stream()), but also add the worktree of each submodule at the commit recorded
+    /// in the tree at `id`, placed at the path of the submodule, recursively. This is similar to what
+    /// `git archive --recurse-submodules` does.
+    ///
+    /// Submodules that aren't known in `.gitmodules` or whose repository wasn't cloned yet are skipped.
+    /// Note that the returned index is the one of the tree at `id`, and doesn't contain the entries of submodules.
+    #[cfg(feature = "worktree-stream")]
+    #[gix_macros::momo]
+    pub fn worktree_stream_with_submodules(
+        &self,
+        id: impl Into<gix_hash::ObjectId>,
+    ) -> Result<(gix_worktree_stream::Stream, gix_index::File), crate::repository::worktree_stream::Error> {
+        let (mut stream, index) = self.worktree_stream(id)?;
+        let mut gitlinks = index
+            .entries()
+            .iter()
+            .filter(|entry| entry.mode == gix_index::entry::Mode::COMMIT)
+            .map(|entry| (entry.path(&index), entry.id))
+            .peekable();
+        if gitlinks.peek().is_none() {
+            return Ok((stream, index));
+        }
+        let Some(submodules) = self.submodules()? else {
+            return Ok((stream, index));
+        };
+        let submodules: Vec<_> = submodules.collect();
+        for (path, id) in gitlinks {
+            let mut submodule = None;
+            for candidate in &submodules {
+                if candidate.path()? == path {
+                    submodule = Some(candidate);
+                    break;
+                }
+            }
+            let Some(repo) = submodule.map(crate::Submodule::open).transpose()?.flatten() else {
+                continue;
+            };
+            let (submodule_stream, _index) = repo.worktree_stream_with_submodules(id).map_err(|err| {
+                crate::repository::worktree_stream::Error::Submodule {
+                    path: path.to_owned(),
+                    source: Box::new(err),
+                }
+            })?;
+            let mut prefix = path.to_owned();
+            prefix.push(b'/');
+            stream.add_stream(prefix, submodule_stream);
+        }
         Ok((stream, index))
     }
 
@@ -0,0 +1,218 @@
+//! Reuse recorded resolutions of conflicts, see [`Repository::rerere()`](crate::Repository::rerere()).
+//!
+//! The recorded conflicts and resolutions are stored in the `rr-cache` directory like `git rerere` does it,
+//! so both can be used interchangeably.
+use std::path::{Path, PathBuf};
+
+use gix_hash::ObjectId;
+
+use crate::bstr::{BString, ByteSlice};
+
+/// The name of the directory in the git directory in which conflicts and their resolutions are recorded.
+pub const CACHE_DIR: &str = "rr-cache";
+/// The name of the file in the git directory which associates paths with conflicts to the id of their conflict.
+pub const MERGE_RR: &str = "MERGE_RR";
+/// The name of the file in the directory of a conflict that holds the normalized conflict.
+pub(crate) const PREIMAGE: &str = "preimage";
+/// The name of the file in the directory of a conflict that holds its resolution.
+pub(crate) const POSTIMAGE: &str = "postimage";
+
+/// A conflict as seen in a file with conflict markers, in a form that doesn't depend on the side the changes came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Normalized {
+    /// The id of the conflict, which is the same for all files with the same conflicting changes.
+    pub id: ObjectId,
+    /// The content of the file with all conflicts normalized, which is recorded as *preimage*.
+    ///
+    /// Labels are removed from conflict markers, the lines of the common ancestor are dropped,
+    /// and the sides of each conflict are sorted.
+    pub content: Vec<u8>,
+}
+
+/// Normalize the conflicts in `content` whose conflict markers are `marker_size` characters long, and hash them with `hash_kind`
+/// to obtain their id, just like `git rerere` does it.
+///
+/// Return `None` if there are no conflicts or if the conflict markers are malformed, for instance if conflicts are nested.
+pub fn normalize(content: &[u8], marker_size: usize, hash_kind: gix_hash::Kind) -> Option<Normalized> {
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    enum State {
+        Outside,
+        Ours,
+        Base,
+        Theirs,
+    }
+    let is_marker = |line: &[u8], marker: u8| {
+        line.len() >= marker_size
+            && line[..marker_size].iter().all(|b| *b == marker)
+            && match line.get(marker_size) {
+                None | Some(b'\n' | b'\r') => true,
+                Some(b' ') => marker != b'=',
+                Some(_) => false,
+            }
+    };
+    let write_marker = |out: &mut Vec<u8>, marker: u8| {
+        out.extend(std::iter::repeat(marker).take(marker_size));
+        out.push(b'\n');
+    };
+
+    let mut out = Vec::with_capacity(content.len());
+    let mut hasher = gix_features::hash::hasher(hash_kind);
+    let mut state = State::Outside;
+    let (mut ours, mut theirs) = (Vec::new(), Vec::new());
+    let mut has_conflicts = false;
+    for line in content.lines_with_terminator() {
+        let marker = [b'<', b'|', b'=', b'>'].into_iter().find(|m| is_marker(line, *m));
+        state = match (state, marker) {
+            (State::Outside, Some(b'<')) => {
+                ours.clear();
+                theirs.clear();
+                State::Ours
+            }
+            (State::Outside, _) => {
+                out.extend_from_slice(line);
+                State::Outside
+            }
+            (State::Ours, Some(b'|')) => State::Base,
+            (State::Ours | State::Base, Some(b'=')) => State::Theirs,
+            (State::Ours, None) => {
+                ours.extend_from_slice(line);
+                State::Ours
+            }
+            (State::Base, None) => State::Base,
+            (State::Theirs, Some(b'>')) => {
+                let (one, two) = if ours <= theirs {
+                    (&ours, &theirs)
+                } else {
+                    (&theirs, &ours)
+                };
+                write_marker(&mut out, b'<');
+                out.extend_from_slice(one);
+                write_marker(&mut out, b'=');
+                out.extend_from_slice(two);
+                write_marker(&mut out, b'>');
+                hasher.update(one);
+                hasher.update(&[0]);
+                hasher.update(two);
+                hasher.update(&[0]);
+                has_conflicts = true;
+                State::Outside
+            }
+            (State::Theirs, None) => {
+                theirs.extend_from_slice(line);
+                State::Theirs
+            }
+            (State::Ours | State::Base | State::Theirs, Some(_)) => return None,
+        };
+    }
+    (state == State::Outside && has_conflicts).then(|| Normalized {
+        id: ObjectId::from(hasher.digest()),
+        content: out,
+    })
+}
+
+/// The outcome of [`Repository::rerere()`](crate::Repository::rerere()).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The paths with conflicts that were seen for the first time, and whose conflicts were recorded.
+    pub recorded_preimages: Vec<BString>,
+    /// The paths whose conflicts were resolved in the worktree since they were recorded, and whose resolution was recorded.
+    pub recorded_resolutions: Vec<BString>,
+    /// The paths whose conflicts were resolved in the worktree by replaying a previously recorded resolution.
+    ///
+    /// If `rerere.autoUpdate` is enabled, these are also resolved in the index.
+    pub resolved: Vec<BString>,
+}
+
+/// The error returned by [`Repository::rerere()`](crate::Repository::rerere()) and related methods.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A worktree is required to reuse recorded resolutions")]
+    BareRepository,
+    #[error("Could not read '{}'", path.display())]
+    Read { path: PathBuf, source: std::io::Error },
+    #[error("Could not write '{}'", path.display())]
+    Write { path: PathBuf, source: std::io::Error },
+    #[error("Could not remove '{}'", path.display())]
+    Remove { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    ConfigExpiry(#[from] crate::config::key::GenericErrorWithValue),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+}
+
+/// An entry of the `MERGE_RR` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MergeRrEntry {
+    /// The id of the conflict as hexadecimal hash, possibly with a `.<variant>` suffix as written by `git`.
+    pub id: BString,
+    /// The path of the file with the conflict.
+    pub path: BString,
+}
+
+impl MergeRrEntry {
+    /// Return the id of the conflict if it refers to the directory named after it, which isn't the case for variants.
+    pub fn conflict_id(&self) -> Option<ObjectId> {
+        ObjectId::from_hex(&self.id).ok()
+    }
+}
+
+/// Read all entries of the `MERGE_RR` file at `path`, or none if it doesn't exist. Malformed entries are skipped.
+pub(crate) fn read_merge_rr(path: &Path) -> Result<Vec<MergeRrEntry>, Error> {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(Error::Read {
+                path: path.to_owned(),
+                source: err,
+            })
+        }
+    };
+    Ok(data
+        .split(|b| *b == 0)
+        .filter_map(|record| {
+            let (id, path) = record.split_once_str(b"\t")?;
+            Some(MergeRrEntry {
+                id: id.into(),
+                path: path.into(),
+            })
+        })
+        .collect())
+}
+
+/// Write `entries` into the `MERGE_RR` file at `path`, or remove it if there are none.
+pub(crate) fn write_merge_rr(path: &Path, entries: &[MergeRrEntry]) -> Result<(), Error> {
+    if entries.is_empty() {
+        return match std::fs::remove_file(path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(Error::Remove {
+                path: path.to_owned(),
+                source: err,
+            }),
+            _ => Ok(()),
+        };
+    }
+    let mut data = Vec::new();
+    for entry in entries {
+        data.extend_from_slice(&entry.id);
+        data.push(b'\t');
+        data.extend_from_slice(&entry.path);
+        data.push(0);
+    }
+    std::fs::write(path, data).map_err(|err| Error::Write {
+        path: path.to_owned(),
+        source: err,
+    })
+}
@@ -143,14 +143,14 @@ impl<'repo> Delegate<'repo> {
     }
     fn disambiguate_objects_by_fallback_hint(&mut self, hint: Option<ObjectKindHint>) {
         fn require_object_kind(repo: &Repository, obj: &gix_hash::oid, kind: gix_object::Kind) -> Result<(), Error> {
-            let obj = repo.find_object(obj)?;
-            if obj.kind == kind {
+            let actual = repo.find_header(obj)?.kind();
+            if actual == kind {
                 Ok(())
             } else {
                 Err(Error::ObjectKind {
-                    actual: obj.kind,
+                    actual,
                     expected: kind,
-                    oid: obj.id.attach(repo).shorten_or_id(),
+                    oid: obj.to_owned().attach(repo).shorten_or_id(),
                 })
             }
         }
@@ -242,10 +242,9 @@ impl<'repo> delegate::Navigate for Delegate<'repo> {
                                     .peeled()
                                     .filter_map(Result::ok)
                                     .filter(|r| {
-                                        r.id()
-                                            .object()
-                                            .ok()
-                                            .map_or(false, |obj| obj.kind == gix_object::Kind::Commit)
+                                        self.repo
+                                            .find_header(r.id())
+                                            .map_or(false, |header| header.kind() == gix_object::Kind::Commit)
                                     })
                                     .filter_map(|r| r.detach().peeled),
                             )
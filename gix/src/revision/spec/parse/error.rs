@@ -59,17 +59,17 @@ impl Error {
             let mut c: Vec<_> = candidates
                 .into_iter()
                 .map(|oid| {
-                    let obj = repo.find_object(oid);
-                    let order = match &obj {
+                    let kind = repo.find_header(oid).map(|header| header.kind());
+                    let order = match &kind {
                         Err(_) => Order::Invalid,
-                        Ok(obj) => match obj.kind {
+                        Ok(kind) => match kind {
                             gix_object::Kind::Tag => Order::Tag,
                             gix_object::Kind::Commit => Order::Commit,
                             gix_object::Kind::Tree => Order::Tree,
                             gix_object::Kind::Blob => Order::Blob,
                         },
                     };
-                    (oid, obj, order)
+                    (oid, kind, order)
                 })
                 .collect();
             c.sort_by(|lhs, rhs| lhs.2.cmp(&rhs.2).then_with(|| lhs.0.cmp(&rhs.0)));
@@ -80,14 +80,17 @@ impl Error {
             info: candidates
                 .into_iter()
                 .map(|(oid, find_result, _)| {
+                    // Only tags and commits need to be decoded, for everything else the kind is all we show.
                     let info = match find_result {
-                        Ok(obj) => match obj.kind {
-                            gix_object::Kind::Tree | gix_object::Kind::Blob => CandidateInfo::Object { kind: obj.kind },
-                            gix_object::Kind::Tag => {
-                                let tag = obj.to_tag_ref();
-                                CandidateInfo::Tag { name: tag.name.into() }
-                            }
-                            gix_object::Kind::Commit => {
+                        Ok(kind @ (gix_object::Kind::Tree | gix_object::Kind::Blob)) => CandidateInfo::Object { kind },
+                        Ok(gix_object::Kind::Tag) => match repo.find_object(oid) {
+                            Ok(obj) => CandidateInfo::Tag {
+                                name: obj.to_tag_ref().name.into(),
+                            },
+                            Err(err) => CandidateInfo::FindError { source: err },
+                        },
+                        Ok(gix_object::Kind::Commit) => match repo.find_object(oid) {
+                            Ok(obj) => {
                                 use bstr::ByteSlice;
                                 let commit = obj.to_commit_ref();
                                 CandidateInfo::Commit {
@@ -95,6 +98,7 @@ impl Error {
                                     title: commit.message().title.trim().into(),
                                 }
                             }
+                            Err(err) => CandidateInfo::FindError { source: err },
                         },
                         Err(err) => CandidateInfo::FindError { source: err },
                     };
@@ -3,6 +3,10 @@ use gix_object::FindExt;
 
 use crate::{ext::ObjectIdExt, revision, Repository};
 
+///
+pub mod prefetch;
+pub use prefetch::Prefetch;
+
 /// The error returned by [`Platform::all()`].
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
@@ -163,13 +167,18 @@ impl<'repo> Platform<'repo> {
             use_commit_graph,
             commit_graph,
         } = self;
+        let recorded = std::rc::Rc::new(prefetch::Recorded::default());
         Ok(revision::Walk {
             repo,
+            recorded: recorded.clone(),
             inner: Box::new(
                 gix_traverse::commit::Ancestors::filtered(
                     tips,
                     gix_traverse::commit::ancestors::State::default(),
-                    &repo.objects,
+                    prefetch::Objects {
+                        objects: &repo.objects,
+                        recorded: recorded.clone(),
+                    },
                     {
                         // Note that specific shallow handling for commit-graphs isn't needed as these contain
                         // all information there is, and exclude shallow parents to be structurally consistent.
@@ -226,6 +235,8 @@ pub(crate) mod iter {
     /// The iterator returned by [`crate::revision::walk::Platform::all()`].
     pub struct Walk<'repo> {
         pub(crate) repo: &'repo crate::Repository,
+        /// The commits read by `inner`, to be reused when prefetching.
+        pub(crate) recorded: std::rc::Rc<super::prefetch::Recorded>,
         pub(crate) inner: Box<
             dyn Iterator<Item = Result<gix_traverse::commit::Info, gix_traverse::commit::ancestors::Error>> + 'repo,
         >,
@@ -0,0 +1,321 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::Rc,
+};
+
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+
+use crate::revision::{walk::Info, Walk};
+
+/// The error returned by the [`Prefetch`] iterator.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Walk(#[from] gix_traverse::commit::ancestors::Error),
+    #[error(transparent)]
+    FindCommit(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    Decode(#[from] gix_object::decode::Error),
+}
+
+/// A commit produced by the [`Prefetch`] iterator, along with the information obtained during the walk.
+#[derive(Debug, Clone)]
+pub struct Item<'repo> {
+    /// Information about the commit as obtained by the walk.
+    pub info: Info<'repo>,
+    /// The fully decoded commit.
+    pub commit: gix_object::Commit,
+}
+
+/// An iterator returned by [`Walk::prefetch()`] which decodes upcoming commits of a [`Walk`] while the current one is being
+/// processed, to speed up pipelines that do work per commit.
+///
+/// Commits whose data was read by the walk already are decoded from that data, while the ones the walk didn't have to read,
+/// for instance as it used the commit-graph, are looked up and decoded on worker threads.
+///
+/// Commits are returned in the order of the walk. Without the `parallel` feature, commits are looked up on the current thread.
+pub struct Prefetch<'repo> {
+    walk: Walk<'repo>,
+    lookahead: usize,
+    in_flight: VecDeque<(Info<'repo>, Data)>,
+    walk_error: Option<gix_traverse::commit::ancestors::Error>,
+    walk_done: bool,
+    /// The amount of threads to spawn once the first commit needs to be looked up, or `0` if there should be no threads.
+    #[cfg(feature = "parallel")]
+    num_threads: usize,
+    #[cfg(feature = "parallel")]
+    workers: Option<threaded::Workers>,
+    buf: Vec<u8>,
+}
+
+/// The data of a commit in flight.
+enum Data {
+    /// The data as read by the walk, which only has to be decoded.
+    Recorded(Vec<u8>),
+    /// The commit is looked up and decoded by a worker thread.
+    #[cfg(feature = "parallel")]
+    Sent,
+    /// The commit has to be looked up as the walk didn't read it.
+    Missing,
+}
+
+impl<'repo> Walk<'repo> {
+    /// Turn this walk into an iterator which additionally returns each decoded commit, while looking up and decoding at most
+    /// `lookahead` commits ahead of the current one using up to `thread_limit` threads, or as many as there are logical cores
+    /// if `None`.
+    ///
+    /// `lookahead` bounds the amount of commits held in memory, and should be large enough to keep all threads busy.
+    /// Note that threads are only used for commits that the walk doesn't read itself, which is the case if it uses the commit-graph.
+    pub fn prefetch(self, lookahead: usize, thread_limit: Option<usize>) -> Prefetch<'repo> {
+        let lookahead = lookahead.max(1);
+        self.recorded.enable();
+        #[cfg(not(feature = "parallel"))]
+        let _ = thread_limit;
+        Prefetch {
+            walk: self,
+            lookahead,
+            in_flight: VecDeque::with_capacity(lookahead),
+            walk_error: None,
+            walk_done: false,
+            #[cfg(feature = "parallel")]
+            num_threads: Some(gix_features::parallel::num_threads(thread_limit).min(lookahead))
+                .filter(|num_threads| *num_threads > 1)
+                .unwrap_or_default(),
+            #[cfg(feature = "parallel")]
+            workers: None,
+            buf: Vec::new(),
+        }
+    }
+}
+
+impl<'repo> Prefetch<'repo> {
+    fn fill(&mut self) {
+        while !self.walk_done && self.in_flight.len() < self.lookahead {
+            match self.walk.next() {
+                Some(Ok(info)) => {
+                    let data = match self.walk.recorded.take(&info.id) {
+                        Some(data) => Data::Recorded(data),
+                        None => self.send(info.id),
+                    };
+                    self.in_flight.push_back((info, data));
+                }
+                Some(Err(err)) => {
+                    self.walk_error = Some(err);
+                    self.walk_done = true;
+                }
+                None => self.walk_done = true,
+            }
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    fn send(&mut self, id: ObjectId) -> Data {
+        if self.workers.is_none() && self.num_threads > 1 {
+            self.workers = self
+                .walk
+                .repo
+                .objects
+                .clone()
+                .into_arc()
+                .ok()
+                .map(|objects| threaded::Workers::spawn(objects, self.num_threads));
+            if self.workers.is_none() {
+                self.num_threads = 0;
+            }
+        }
+        match self.workers.as_mut() {
+            Some(workers) => {
+                workers.send(id);
+                Data::Sent
+            }
+            None => Data::Missing,
+        }
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn send(&mut self, _id: ObjectId) -> Data {
+        Data::Missing
+    }
+
+    fn decode(&mut self, id: ObjectId, data: Data) -> Result<gix_object::Commit, Error> {
+        match data {
+            Data::Recorded(data) => Ok(gix_object::CommitRef::from_bytes(&data)?.into()),
+            #[cfg(feature = "parallel")]
+            Data::Sent => match self.workers.as_mut().and_then(threaded::Workers::receive) {
+                Some(res) => res,
+                None => {
+                    self.workers = None;
+                    self.num_threads = 0;
+                    self.find(id)
+                }
+            },
+            Data::Missing => self.find(id),
+        }
+    }
+
+    fn find(&mut self, id: ObjectId) -> Result<gix_object::Commit, Error> {
+        Ok(self.walk.repo.objects.find_commit(&id, &mut self.buf)?.into())
+    }
+}
+
+impl<'repo> Iterator for Prefetch<'repo> {
+    type Item = Result<Item<'repo>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.fill();
+        let Some((info, data)) = self.in_flight.pop_front() else {
+            return self.walk_error.take().map(|err| Err(err.into()));
+        };
+        Some(self.decode(info.id, data).map(|commit| Item { info, commit }))
+    }
+}
+
+/// Commits read by a walk, which are only recorded once the walk is used for prefetching.
+#[derive(Default)]
+pub(crate) struct Recorded {
+    enabled: Cell<bool>,
+    commits: RefCell<Vec<(ObjectId, Vec<u8>)>>,
+}
+
+impl Recorded {
+    fn enable(&self) {
+        self.enabled.set(true);
+    }
+
+    /// Return the data of the commit with `id` if it was read since the last call, and forget all other commits.
+    fn take(&self, id: &ObjectId) -> Option<Vec<u8>> {
+        let mut commits = self.commits.borrow_mut();
+        let data = commits
+            .iter()
+            .position(|(recorded_id, _)| recorded_id == id)
+            .map(|pos| commits.swap_remove(pos).1);
+        commits.clear();
+        data
+    }
+}
+
+/// The objects used by a walk, which record the commits they read if enabled.
+pub(crate) struct Objects<'repo> {
+    pub(crate) objects: &'repo crate::OdbHandle,
+    pub(crate) recorded: Rc<Recorded>,
+}
+
+impl gix_object::Find for Objects<'_> {
+    fn try_find<'a>(
+        &self,
+        id: &gix_hash::oid,
+        buffer: &'a mut Vec<u8>,
+    ) -> Result<Option<gix_object::Data<'a>>, gix_object::find::Error> {
+        let data = self.objects.try_find(id, buffer)?;
+        if let Some(data) = data.as_ref().filter(|data| data.kind == gix_object::Kind::Commit) {
+            if self.recorded.enabled.get() {
+                self.recorded
+                    .commits
+                    .borrow_mut()
+                    .push((id.to_owned(), data.data.to_vec()));
+            }
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(feature = "parallel")]
+mod threaded {
+    use std::{
+        collections::BTreeMap,
+        sync::mpsc::{channel, Receiver, Sender},
+    };
+
+    use gix_hash::ObjectId;
+    use gix_object::FindExt;
+
+    use super::Error;
+
+    type Result = std::result::Result<gix_object::Commit, Error>;
+
+    /// Threads which decode commits in the order they were sent, with results being returned in the same order.
+    pub(super) struct Workers {
+        /// The input of each thread, which receives its work in a round-robin fashion.
+        send: Vec<Sender<(usize, ObjectId)>>,
+        receive: Receiver<(usize, Result)>,
+        /// Results that arrived out of order.
+        received: BTreeMap<usize, Result>,
+        next_to_send: usize,
+        next_to_receive: usize,
+        threads: Vec<std::thread::JoinHandle<()>>,
+    }
+
+    impl Workers {
+        pub fn spawn(objects: gix_odb::HandleArc, num_threads: usize) -> Self {
+            let (send_result, receive) = channel();
+            let mut send = Vec::with_capacity(num_threads);
+            let mut threads = Vec::with_capacity(num_threads);
+            for thread_id in 0..num_threads {
+                let (send_input, receive_input) = channel::<(usize, ObjectId)>();
+                let send_result = send_result.clone();
+                let objects = objects.clone();
+                let handle = std::thread::Builder::new()
+                    .name(format!("gitoxide.revision.walk.prefetch.{thread_id}"))
+                    .spawn(move || {
+                        let mut buf = Vec::new();
+                        for (seq, id) in receive_input {
+                            let res = objects.find_commit(&id, &mut buf).map(Into::into).map_err(Error::from);
+                            if send_result.send((seq, res)).is_err() {
+                                break;
+                            }
+                        }
+                    })
+                    .expect("valid name");
+                send.push(send_input);
+                threads.push(handle);
+            }
+            Workers {
+                send,
+                receive,
+                received: BTreeMap::new(),
+                next_to_send: 0,
+                next_to_receive: 0,
+                threads,
+            }
+        }
+
+        pub fn send(&mut self, id: ObjectId) {
+            let seq = self.next_to_send;
+            self.next_to_send += 1;
+            self.send[seq % self.send.len()].send((seq, id)).ok();
+        }
+
+        /// Return the next result in order, or `None` if the threads stopped unexpectedly.
+        pub fn receive(&mut self) -> Option<Result> {
+            let seq = self.next_to_receive;
+            let res = match self.received.remove(&seq) {
+                Some(res) => res,
+                None => loop {
+                    let (received_seq, res) = self.receive.recv().ok()?;
+                    if received_seq == seq {
+                        break res;
+                    }
+                    self.received.insert(received_seq, res);
+                },
+            };
+            self.next_to_receive += 1;
+            Some(res)
+        }
+    }
+
+    impl Drop for Workers {
+        fn drop(&mut self) {
+            self.send.clear();
+            for handle in std::mem::take(&mut self.threads) {
+                if let Err(err) = handle.join() {
+                    if !std::thread::panicking() {
+                        std::panic::resume_unwind(err);
+                    }
+                }
+            }
+        }
+    }
+}
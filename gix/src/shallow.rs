@@ -17,15 +17,14 @@ pub mod write {
         /// Write the previously obtained (possibly non-existing) `shallow_commits` to the shallow `file`
         /// after applying all `updates`.
         ///
+        /// Like `git`, shallow commits that don't exist in `objects` are pruned, as they can't be part of the
+        /// shallow boundary anymore, and duplicates are removed.
         /// If this leaves the list of shallow commits empty, the file is removed.
-        ///
-        /// ### Deviation
-        ///
-        /// Git also prunes the set of shallow commits while writing, we don't until we support some sort of pruning.
         pub fn write(
             mut file: gix_lock::File,
             shallow_commits: Option<Commits>,
             updates: &[ShallowUpdate],
+            objects: &dyn gix_object::Exists,
         ) -> Result<(), Error> {
             let mut shallow_commits = shallow_commits.map(|sc| (**sc).to_owned()).unwrap_or_default();
             for update in updates {
@@ -36,11 +35,9 @@ pub mod write {
                     ShallowUpdate::Unshallow(id) => shallow_commits.retain(|oid| oid != id),
                 }
             }
-            if shallow_commits.is_empty() {
-                std::fs::remove_file(file.resource_path())?;
-                drop(file);
-                return Ok(());
-            }
+            shallow_commits.retain(|id| objects.exists(id));
+            shallow_commits.sort();
+            shallow_commits.dedup();
 
             if shallow_commits.is_empty() {
                 if let Err(err) = std::fs::remove_file(file.resource_path()) {
@@ -48,16 +45,17 @@ pub mod write {
                         return Err(err.into());
                     }
                 }
-            } else {
-                shallow_commits.sort();
-                let mut buf = Vec::<u8>::new();
-                for commit in shallow_commits {
-                    commit.write_hex_to(&mut buf).map_err(Error::Io)?;
-                    buf.push(b'\n');
-                }
-                file.write_all(&buf).map_err(Error::Io)?;
-                file.flush()?;
+                drop(file);
+                return Ok(());
+            }
+
+            let mut buf = Vec::<u8>::new();
+            for commit in shallow_commits {
+                commit.write_hex_to(&mut buf).map_err(Error::Io)?;
+                buf.push(b'\n');
             }
+            file.write_all(&buf).map_err(Error::Io)?;
+            file.flush()?;
             file.commit()?;
             Ok(())
         }
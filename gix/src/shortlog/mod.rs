@@ -0,0 +1,55 @@
+use crate::bstr::BString;
+
+/// Options for use in [`Repository::shortlog()`](crate::Repository::shortlog()).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// If `true`, group commits by their committer instead of their author.
+    pub committer: bool,
+    /// If `true`, group commits by name and email, instead of by name only.
+    pub email: bool,
+    /// If `true`, collect the summary of each commit in its group.
+    pub summaries: bool,
+    /// If `true`, sort groups by the amount of their commits, from the most to the fewest, instead of by name alone.
+    pub sort_by_count: bool,
+}
+
+/// All commits of a single author or committer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    /// The name of the author or committer, after applying the mailmap.
+    pub name: BString,
+    /// The email of the author or committer after applying the mailmap, or `None` if
+    /// [grouping by email](Options::email) is disabled.
+    pub email: Option<BString>,
+    /// The amount of commits in this group.
+    pub count: usize,
+    /// The summary of each commit in this group, from the oldest to the newest, if [`Options::summaries`] is enabled.
+    ///
+    /// Like `git`, a leading `[PATCH]` is removed, and commits without summary are listed as `<none>`.
+    pub summaries: Vec<BString>,
+}
+
+/// The outcome of [`Repository::shortlog()`](crate::Repository::shortlog()).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// One group per author or committer, sorted by name or by the amount of their commits as configured in [`Options`].
+    pub groups: Vec<Group>,
+    /// The amount of commits across all groups.
+    pub commits: usize,
+}
+
+/// The error returned by [`Repository::shortlog()`](crate::Repository::shortlog()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    Traverse(#[from] gix_traverse::commit::ancestors::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    Decode(#[from] gix_object::decode::Error),
+}
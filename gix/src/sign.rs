@@ -0,0 +1,85 @@
+//! Cryptographically sign commits and tags at the time they are created, and verify their signatures.
+//!
+//! The signing itself is left to implementations of [`Sign`], which typically invoke `gpg` or `ssh-keygen -Y sign`.
+//! Similarly, signatures are checked by implementations of [`Verify`], which typically invoke `gpg --verify` or
+//! `ssh-keygen -Y verify`.
+use crate::bstr::{BStr, BString};
+
+/// The error returned by implementations of [`Sign`] and [`Verify`].
+pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A way to produce a signature for objects as they are created.
+pub trait Sign {
+    /// Return the signature over `data`, which is the serialized object as it will be stored, but without signature.
+    ///
+    /// The signature is expected to be ASCII-armored, as in `-----BEGIN PGP SIGNATURE-----…`, and will be embedded into
+    /// commits as `gpgsig` header and appended to the message of tags.
+    fn sign(&self, data: &[u8]) -> Result<BString, Error>;
+}
+
+impl<F> Sign for F
+where
+    F: Fn(&[u8]) -> Result<BString, Error>,
+{
+    fn sign(&self, data: &[u8]) -> Result<BString, Error> {
+        self(data)
+    }
+}
+
+/// The kind of a signature, as determined by the first line of its ASCII-armored block.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// An OpenPGP signature as created by `gpg`.
+    OpenPgp,
+    /// An X.509 signature as created by `gpgsm`.
+    X509,
+    /// An SSH signature as created by `ssh-keygen -Y sign`.
+    Ssh,
+}
+
+impl Format {
+    /// Determine the format of `signature` by its first line, or return `None` if it's not known.
+    pub fn from_signature(signature: &[u8]) -> Option<Self> {
+        [
+            (&b"-----BEGIN PGP SIGNATURE-----"[..], Format::OpenPgp),
+            (b"-----BEGIN PGP MESSAGE-----", Format::OpenPgp),
+            (b"-----BEGIN SIGNED MESSAGE-----", Format::X509),
+            (b"-----BEGIN SSH SIGNATURE-----", Format::Ssh),
+        ]
+        .into_iter()
+        .find_map(|(begin, format)| signature.starts_with(begin).then_some(format))
+    }
+}
+
+/// A way to check the signature of existing objects.
+pub trait Verify {
+    /// Succeed if `signature` is a valid signature over `signed_data`, and fail otherwise.
+    ///
+    /// [`Format::from_signature()`] can be used to learn which program should perform the verification.
+    fn verify(&self, signature: &BStr, signed_data: &[u8]) -> Result<(), Error>;
+}
+
+impl<F> Verify for F
+where
+    F: Fn(&BStr, &[u8]) -> Result<(), Error>,
+{
+    fn verify(&self, signature: &BStr, signed_data: &[u8]) -> Result<(), Error> {
+        self(signature, signed_data)
+    }
+}
+
+///
+pub mod verify {
+    /// The error returned by [`Commit::verify_signature()`](crate::Commit::verify_signature()) and
+    /// [`Tag::verify_signature()`](crate::Tag::verify_signature()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The object isn't signed")]
+        Unsigned,
+        #[error(transparent)]
+        Decode(#[from] gix_object::decode::Error),
+        #[error("The signature is invalid")]
+        Verify(#[source] super::Error),
+    }
+}
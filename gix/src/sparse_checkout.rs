@@ -0,0 +1,427 @@
+use std::path::PathBuf;
+
+use gix_index::entry::{Flags, Mode, Stat};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::{
+        cache::util::ApplyLeniencyDefaultValue,
+        tree::{Core, Key},
+    },
+    merge::worktree::{remove_file, worktree_changes, write_files},
+    Repository,
+};
+
+/// The patterns that are written into a new sparse-checkout file, which only include the files in the root of the worktree.
+const INITIAL_PATTERNS: &str = "/*\n!/*/\n";
+
+/// The error returned by methods of the sparse-checkout [`Platform`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A worktree is required to perform this operation")]
+    BareRepository,
+    #[error("Sparse checkout isn't enabled, initialize it first")]
+    NotEnabled,
+    #[error("Sparse indices with directory entries can't be changed yet")]
+    SparseIndex,
+    #[error("The sparse-checkout file contains '{line}', which isn't a valid pattern in cone mode")]
+    InvalidConePattern { line: BString },
+    #[error("Files that would be removed from the worktree have uncommitted changes, the first of {} being at '{}'", .paths.len(), .paths[0])]
+    Dirty { paths: Vec<BString> },
+    #[error("Could not read the sparse-checkout file at '{}'", path.display())]
+    ReadPatterns { path: PathBuf, source: std::io::Error },
+    #[error("Could not write the sparse-checkout file at '{}'", path.display())]
+    WritePatterns { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error("Could not load the repository-local configuration")]
+    LoadLocalConfig(#[from] gix_config::file::init::from_paths::Error),
+    #[error("Could not write the repository-local configuration")]
+    WriteLocalConfig(#[from] std::io::Error),
+    #[error(transparent)]
+    LockLocalConfig(#[from] gix_lock::acquire::Error),
+    #[error(transparent)]
+    SetConfig(#[from] crate::config::set_value::Error),
+    #[error(transparent)]
+    UpdateConfig(#[from] crate::config::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    WorktreeChanges(#[from] crate::merge::worktree::require_clean::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::merge::worktree::checkout::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+}
+
+/// The outcome of changing the sparse-checkout of the worktree.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The paths of all files that were written into the worktree as they are now included.
+    pub added: Vec<BString>,
+    /// The paths of all files that were removed from the worktree as they are now excluded.
+    pub removed: Vec<BString>,
+}
+
+/// A platform to manage which files are present in the worktree, similar to `git sparse-checkout`, as
+/// obtained by [`Repository::sparse_checkout()`].
+///
+/// Patterns are kept in `.git/info/sparse-checkout`, and all files in the index that they don't include are marked with
+/// [`SKIP_WORKTREE`](Flags::SKIP_WORKTREE) and removed from the worktree.
+/// In _cone mode_, the patterns are directories whose files are included recursively, along with all files in the root and
+/// in their parent directories. Otherwise, they are matched like `.gitignore` patterns, but to include paths.
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo mut Repository,
+}
+
+/// Access
+impl Platform<'_> {
+    /// Return the path to the file containing the sparse-checkout patterns.
+    pub fn path(&self) -> PathBuf {
+        self.repo.git_dir().join("info").join("sparse-checkout")
+    }
+
+    /// Return `true` if sparse checkout is enabled by `core.sparseCheckout`.
+    pub fn is_enabled(&self) -> Result<bool, Error> {
+        self.boolean(&Core::SPARSE_CHECKOUT, false)
+    }
+
+    /// Return `true` if patterns are interpreted as directories in _cone mode_, as configured by `core.sparseCheckoutCone`.
+    ///
+    /// Cone mode is assumed if it isn't configured.
+    pub fn is_cone(&self) -> Result<bool, Error> {
+        self.boolean(&Core::SPARSE_CHECKOUT_CONE, true)
+    }
+
+    /// Return the patterns of the sparse-checkout file, or the included directories in _cone mode_.
+    pub fn list(&self) -> Result<Vec<BString>, Error> {
+        let patterns = self.read_patterns()?.unwrap_or_default();
+        if self.is_cone()? {
+            cone::directories(patterns.as_ref())
+        } else {
+            Ok(lines(patterns.as_ref()).map(ToOwned::to_owned).collect())
+        }
+    }
+}
+
+/// Mutation
+impl Platform<'_> {
+    /// Enable sparse checkout, with `cone` controlling if patterns are directories, and update the worktree
+    /// to only contain included files.
+    ///
+    /// If there is no sparse-checkout file yet, one is created which only includes the files in the root of the worktree.
+    pub fn init(&mut self, cone: bool) -> Result<Outcome, Error> {
+        if self.read_patterns()?.is_none() {
+            self.write_patterns(INITIAL_PATTERNS.into())?;
+        }
+        self.configure(true, Some(cone))?;
+        self.update_worktree()
+    }
+
+    /// Replace all patterns with `patterns` and update the worktree accordingly, enabling sparse checkout if needed.
+    ///
+    /// In _cone mode_, `patterns` are the directories to include.
+    pub fn set<'a>(&mut self, patterns: impl IntoIterator<Item = &'a BStr>) -> Result<Outcome, Error> {
+        let cone = self.is_cone()?;
+        let patterns = if cone {
+            cone::patterns(patterns)
+        } else {
+            non_cone::patterns(patterns)
+        };
+        self.write_patterns(patterns)?;
+        self.configure(true, Some(cone))?;
+        self.update_worktree()
+    }
+
+    /// Add `patterns` to the existing ones and update the worktree accordingly.
+    ///
+    /// In _cone mode_, `patterns` are directories to include in addition to the ones that are already included.
+    pub fn add<'a>(&mut self, patterns: impl IntoIterator<Item = &'a BStr>) -> Result<Outcome, Error> {
+        if !self.is_enabled()? {
+            return Err(Error::NotEnabled);
+        }
+        let existing = self.read_patterns()?.unwrap_or_default();
+        let patterns = if self.is_cone()? {
+            let directories = cone::directories(existing.as_ref())?;
+            let mut all: Vec<&BStr> = directories.iter().map(AsRef::as_ref).collect();
+            all.extend(patterns.into_iter().map(|pattern| pattern.as_bstr()));
+            cone::patterns(all)
+        } else {
+            let mut all: Vec<&BStr> = lines(existing.as_ref()).collect();
+            all.extend(patterns.into_iter().map(|pattern| pattern.as_bstr()));
+            non_cone::patterns(all)
+        };
+        self.write_patterns(patterns)?;
+        self.update_worktree()
+    }
+
+    /// Disable sparse checkout and write all files that were excluded into the worktree.
+    ///
+    /// The sparse-checkout file is kept, so the same patterns are in effect when it is [initialized](Self::init()) again.
+    pub fn disable(&mut self) -> Result<Outcome, Error> {
+        self.configure(false, None)?;
+        self.update_worktree()
+    }
+}
+
+/// Utilities
+impl Platform<'_> {
+    fn boolean(&self, key: &'static crate::config::tree::keys::Boolean, default: bool) -> Result<bool, Error> {
+        let config = &self.repo.config;
+        Ok(config
+            .resolved
+            .boolean_by_key(key.logical_name().as_str())
+            .map_or(Ok(default), |res| {
+                key.enrich_error(res)
+                    .with_lenient_default_value(config.lenient_config, default)
+            })?)
+    }
+
+    fn read_patterns(&self) -> Result<Option<BString>, Error> {
+        let path = self.path();
+        match std::fs::read(&path) {
+            Ok(patterns) => Ok(Some(patterns.into())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(Error::ReadPatterns { path, source: err }),
+        }
+    }
+
+    fn write_patterns(&self, patterns: BString) -> Result<(), Error> {
+        let path = self.path();
+        std::fs::create_dir_all(path.parent().expect("info directory"))
+            .and_then(|_| std::fs::write(&path, patterns))
+            .map_err(|err| Error::WritePatterns { path, source: err })
+    }
+
+    /// Persist `enabled` and `cone` in the repository-local configuration file, and apply them to the configuration
+    /// of the repository as well.
+    fn configure(&mut self, enabled: bool, cone: Option<bool>) -> Result<(), Error> {
+        let values = std::iter::once((&Core::SPARSE_CHECKOUT, enabled))
+            .chain(cone.map(|cone| (&Core::SPARSE_CHECKOUT_CONE, cone)))
+            .collect::<Vec<_>>();
+
+        let path = self.repo.common_dir().join("config");
+        let mut local = gix_config::File::from_path_no_includes(path.clone(), gix_config::Source::Local)?;
+        for (key, value) in &values {
+            local
+                .set_raw_value(
+                    key.section().name(),
+                    None,
+                    key.name(),
+                    if *value { "true" } else { "false" },
+                )
+                .expect("statically known and valid");
+        }
+        let mut file = gix_lock::File::acquire_to_update_resource(path, gix_lock::acquire::Fail::Immediately, None)?;
+        local.write_to(&mut file)?;
+        file.commit().map_err(|err| err.error)?;
+
+        let mut config = self.repo.config_snapshot_mut();
+        for (key, value) in values {
+            config.set_value(key, if value { "true" } else { "false" })?;
+        }
+        config.commit()?;
+        Ok(())
+    }
+
+    /// Mark all files in the index which are excluded by the current patterns with `SKIP_WORKTREE` and remove them
+    /// from the worktree, and write all files which are included now but weren't before.
+    fn update_worktree(&mut self) -> Result<Outcome, Error> {
+        let repo = &*self.repo;
+        let workdir = repo.work_dir().ok_or(Error::BareRepository)?;
+        let matcher = if self.is_enabled()? {
+            let patterns = self.read_patterns()?.unwrap_or_default();
+            Some(if self.is_cone()? {
+                Matcher::Cone(cone::directories(patterns.as_ref())?)
+            } else {
+                Matcher::Patterns(gix_ignore::Search::from_overrides(
+                    lines(patterns.as_ref()).map(|line| gix_path::from_bstr(line).into_owned()),
+                ))
+            })
+        } else {
+            None
+        };
+        let case = if repo.filesystem_options()?.ignore_case {
+            gix_glob::pattern::Case::Fold
+        } else {
+            gix_glob::pattern::Case::Sensitive
+        };
+
+        let mut index = repo.open_index()?;
+        if index.is_sparse() {
+            return Err(Error::SparseIndex);
+        }
+        let mut to_add = gix_index::State::new(repo.object_hash());
+        let mut to_remove = Vec::new();
+        for entry in index.entries() {
+            let path = entry.path(&index);
+            if entry.stage() != 0 || entry.mode == Mode::COMMIT || entry.flags.contains(Flags::INTENT_TO_ADD) {
+                continue;
+            }
+            let is_included = matcher.as_ref().map_or(true, |m| m.is_included(path, case));
+            match (entry.flags.contains(Flags::SKIP_WORKTREE), is_included) {
+                (true, true) => {
+                    to_add.dangerously_push_entry(Stat::default(), entry.id, Flags::empty(), entry.mode, path);
+                }
+                (false, false) => to_remove.push(path.to_owned()),
+                _ => {}
+            }
+        }
+
+        if !to_remove.is_empty() {
+            let mut dirty: Vec<_> = worktree_changes(repo, &index)?
+                .into_iter()
+                .filter(|(path, status)| {
+                    !matches!(status, gix_status::index_as_worktree::EntryStatus::NeedsUpdate(_))
+                        && to_remove.binary_search(path).is_ok()
+                })
+                .map(|(path, _)| path)
+                .collect();
+            if !dirty.is_empty() {
+                dirty.sort();
+                dirty.dedup();
+                return Err(Error::Dirty { paths: dirty });
+            }
+        }
+
+        let added = write_files(repo, to_add, false)?;
+        for (entry, path) in index.entries_mut_with_paths() {
+            if let Some(written) = added.entry_by_path_and_stage(path, 0) {
+                entry.stat = written.stat;
+                entry.flags.remove(Flags::SKIP_WORKTREE | Flags::EXTENDED);
+            } else if to_remove.binary_search_by(|p| p.as_bstr().cmp(path)).is_ok() {
+                entry.flags.insert(Flags::SKIP_WORKTREE | Flags::EXTENDED);
+            }
+        }
+        for path in &to_remove {
+            remove_file(workdir, path.as_ref())?;
+        }
+        index.write(Default::default())?;
+
+        Ok(Outcome {
+            added: added.entries().iter().map(|e| e.path(&added).to_owned()).collect(),
+            removed: to_remove,
+        })
+    }
+}
+
+enum Matcher {
+    /// Recursively included directories in cone mode.
+    Cone(Vec<BString>),
+    /// Patterns to match paths against, with the last matching one deciding.
+    Patterns(gix_ignore::Search),
+}
+
+impl Matcher {
+    fn is_included(&self, path: &BStr, case: gix_glob::pattern::Case) -> bool {
+        match self {
+            Matcher::Cone(directories) => {
+                let Some(parent) = path.rfind_byte(b'/').map(|pos| &path[..pos]) else {
+                    return true;
+                };
+                directories.iter().any(|dir| {
+                    is_in_directory(path, dir.as_ref())
+                        || dir.as_bstr() == parent
+                        || is_in_directory(dir.as_ref(), parent)
+                })
+            }
+            Matcher::Patterns(search) => gix_glob::search::Evaluator::new(&search.patterns, case)
+                .pattern_matching_relative_path_or_closest_parent(path, Some(false))
+                .map_or(false, |m| !m.is_negative()),
+        }
+    }
+}
+
+/// Return `true` if `path` is contained in directory `dir`, at any depth.
+fn is_in_directory(path: &BStr, dir: &BStr) -> bool {
+    path.len() > dir.len() && path.starts_with(dir) && path[dir.len()] == b'/'
+}
+
+/// Return all non-empty lines that aren't comments.
+fn lines(patterns: &BStr) -> impl Iterator<Item = &BStr> {
+    patterns
+        .lines()
+        .map(|line| line.trim_end().as_bstr())
+        .filter(|line| !line.is_empty() && !line.starts_with(b"#"))
+}
+
+mod cone {
+    use super::{is_in_directory, lines, Error, INITIAL_PATTERNS};
+    use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+    /// Return the directories that are included recursively by the cone-mode `patterns`.
+    pub(super) fn directories(patterns: &BStr) -> Result<Vec<BString>, Error> {
+        let mut included = Vec::new();
+        let mut parents = Vec::new();
+        for line in lines(patterns) {
+            if line == "/*" || line == "!/*/" {
+                continue;
+            }
+            if let Some(dir) = line
+                .strip_prefix(b"!/")
+                .and_then(|line| line.strip_suffix(b"/*/"))
+                .filter(|dir| !dir.is_empty())
+            {
+                parents.push(dir.as_bstr());
+            } else if let Some(dir) = line
+                .strip_prefix(b"/")
+                .and_then(|line| line.strip_suffix(b"/"))
+                .filter(|dir| !dir.is_empty() && !dir.contains(&b'*'))
+            {
+                included.push(dir.as_bstr());
+            } else {
+                return Err(Error::InvalidConePattern { line: line.to_owned() });
+            }
+        }
+        Ok(included
+            .into_iter()
+            .filter(|dir| !parents.contains(dir))
+            .map(ToOwned::to_owned)
+            .collect())
+    }
+
+    /// Turn `directories` into cone-mode patterns which include them recursively, along with all files in their parent
+    /// directories and in the root.
+    pub(super) fn patterns<'a>(directories: impl IntoIterator<Item = &'a BStr>) -> BString {
+        let mut directories: Vec<_> = directories
+            .into_iter()
+            .map(|dir| dir.trim_with(|c| c == '/').as_bstr())
+            .filter(|dir| !dir.is_empty())
+            .collect();
+        directories.sort();
+        directories.dedup();
+        let directories: Vec<_> = directories
+            .iter()
+            .filter(|dir| !directories.iter().any(|other| is_in_directory(dir, other)))
+            .collect();
+
+        let mut out = BString::from(INITIAL_PATTERNS);
+        let mut written_parents = Vec::<&BStr>::new();
+        for dir in directories {
+            for pos in dir.find_iter("/") {
+                let parent = dir[..pos].as_bstr();
+                if !written_parents.contains(&parent) {
+                    written_parents.push(parent);
+                    out.push_str(format!("/{parent}/\n!/{parent}/*/\n"));
+                }
+            }
+            out.push_str(format!("/{dir}/\n"));
+        }
+        out
+    }
+}
+
+mod non_cone {
+    use crate::bstr::{BStr, BString, ByteVec};
+
+    /// Write each of `patterns` on its own line.
+    pub(super) fn patterns<'a>(patterns: impl IntoIterator<Item = &'a BStr>) -> BString {
+        let mut out = BString::default();
+        for pattern in patterns {
+            out.push_str(pattern);
+            out.push(b'\n');
+        }
+        out
+    }
+}
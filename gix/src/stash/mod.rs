@@ -0,0 +1,118 @@
+use gix_hash::ObjectId;
+
+use crate::bstr::BString;
+
+/// The name of the reference whose reflog holds all stash entries, with the most recent one being the current value of the reference.
+pub const REFERENCE_NAME: &str = "refs/stash";
+
+/// Options for use in [`Repository::stash_save()`](crate::Repository::stash_save()).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SaveOptions {
+    /// A message to describe the stash entry with, or `None` to use the summary of the `HEAD` commit.
+    pub message: Option<BString>,
+    /// If `true`, files that aren't tracked and aren't ignored are stashed as well, and removed from the worktree.
+    pub include_untracked: bool,
+}
+
+/// Options for use in [`Repository::stash_apply()`](crate::Repository::stash_apply()) and [`Repository::stash_pop()`](crate::Repository::stash_pop()).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ApplyOptions {
+    /// If `true`, restore the changes of the index as well, instead of only restoring them in the worktree.
+    pub reinstate_index: bool,
+}
+
+/// An entry of the stash as returned by [`Repository::stash_list()`](crate::Repository::stash_list()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The id of the commit that holds the stashed changes of the worktree.
+    ///
+    /// Its first parent is the commit that was checked out when the stash was created, its second parent holds the
+    /// changes of the index, and its third parent, if present, holds the untracked files.
+    pub id: ObjectId,
+    /// The message that describes the entry.
+    pub message: BString,
+}
+
+/// The outcome of [`Repository::stash_apply()`](crate::Repository::stash_apply()) and [`Repository::stash_pop()`](crate::Repository::stash_pop()).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// All conflicts that have to be resolved in the worktree and the index, which also means that the entry wasn't dropped
+    /// when popping it.
+    pub conflicts: Vec<crate::merge::tree::Conflict>,
+}
+
+/// The error returned by [`Repository::stash_save()`](crate::Repository::stash_save()) and related methods.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("There is no stash entry at index {index}")]
+    NoSuchEntry { index: usize },
+    #[error("Commit {id} is not a stash entry as it doesn't have the expected parents")]
+    InvalidEntry { id: ObjectId },
+    #[error("The index has conflicts that need to be resolved first, the first of {} being at '{}'", .paths.len(), .paths[0])]
+    UnresolvedConflicts { paths: Vec<BString> },
+    #[error("The changes of the stashed index conflict with the current index")]
+    IndexConflicts,
+    #[error("Untracked file '{path}' would be overwritten when restoring stashed untracked files")]
+    UntrackedFileExists { path: BString },
+    #[error("A worktree is required to perform this operation")]
+    BareRepository,
+    #[error("Committer identity is not configured")]
+    CommitterMissing,
+    #[error("Could not read the worktree file '{}'", path.display())]
+    ReadFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not update the stash reflog")]
+    Reflog(#[from] std::io::Error),
+    #[error("Could not decode the stash reflog")]
+    DecodeReflog(#[from] gix_ref::file::log::iter::decode::Error),
+    #[error(transparent)]
+    LockReflog(#[from] gix_lock::acquire::Error),
+    #[error(transparent)]
+    RequireClean(#[from] crate::merge::worktree::require_clean::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::merge::worktree::checkout::Error),
+    #[error(transparent)]
+    Merge(#[from] crate::merge::tree::Error),
+    #[error(transparent)]
+    MergeOptions(#[from] crate::config::merge_tree_options::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error(transparent)]
+    HeadId(#[from] crate::reference::head_id::Error),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::try_into::Error),
+    #[error(transparent)]
+    CommitTree(#[from] crate::object::commit::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error("Could not create an index from the tree at {id}")]
+    IndexFromTree {
+        id: ObjectId,
+        source: gix_traverse::tree::breadthfirst::Error,
+    },
+    #[error(transparent)]
+    WriteTree(#[from] gix_index::write_tree::Error<crate::object::write::Error>),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+    #[error(transparent)]
+    FilesystemOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    Dirwalk(#[from] crate::repository::dirwalk::Error),
+    #[error(transparent)]
+    ParseTime(#[from] crate::config::time::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+}
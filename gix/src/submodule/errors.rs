@@ -49,6 +49,19 @@ pub mod is_active {
     }
 }
 
+///
+pub mod active_submodules {
+    /// The error returned by [Repository::active_submodules()](crate::Repository::active_submodules()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Modules(#[from] super::modules::Error),
+        #[error(transparent)]
+        IsActive(#[from] super::is_active::Error),
+    }
+}
+
 ///
 pub mod fetch_recurse {
     /// The error returned by [Submodule::fetch_recurse()](crate::Submodule::fetch_recurse()).
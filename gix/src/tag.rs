@@ -11,6 +11,8 @@ mod error {
         WriteObject(#[from] crate::object::write::Error),
         #[error(transparent)]
         ReferenceEdit(#[from] crate::reference::edit::Error),
+        #[error("Could not sign the tag")]
+        Sign(#[source] crate::sign::Error),
     }
 }
 pub use error::Error;
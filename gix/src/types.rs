@@ -170,7 +170,8 @@ pub struct Repository {
 ///
 /// Use this type to reference it in a threaded context for creation the creation of a thread-local [`Repositories`][Repository].
 ///
-/// Note that this type purposefully isn't very useful until it is converted into a thread-local repository with `to_thread_local()`,
+/// Besides [reading objects](ThreadSafeRepository::find_object()) and [references](ThreadSafeRepository::find_reference()),
+/// this type purposefully isn't very useful until it is converted into a thread-local repository with `to_thread_local()`,
 /// it's merely meant to be able to exist in a `Sync` context.
 ///
 /// Note that it can also cheaply be cloned, and it will retain references to all contained resources.
@@ -195,6 +196,9 @@ pub struct ThreadSafeRepository {
     pub(crate) shallow_commits: crate::shallow::CommitsStorage,
 }
 
+/// Another name for a [`ThreadSafeRepository`], as obtained by [`Repository::into_sync()`].
+pub type SyncRepository = ThreadSafeRepository;
+
 /// A remote which represents a way to interact with hosts for remote clones of the parent repository.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Remote<'repo> {
@@ -215,6 +219,8 @@ pub struct Remote<'repo> {
     pub(crate) push_specs: Vec<gix_refspec::RefSpec>,
     /// Tell us what to do with tags when fetched.
     pub(crate) fetch_tags: remote::fetch::Tags,
+    /// The object filter to use when fetching, like `blob:none`, which also makes this remote a promisor remote.
+    pub(crate) partial_clone_filter: Option<crate::bstr::BString>,
     // /// Delete local tracking branches that don't exist on the remote anymore.
     // pub(crate) prune: bool,
     // /// Delete tags that don't exist on the remote anymore, equivalent to pruning the refspec `refs/tags/*:refs/tags/*`.
@@ -0,0 +1,207 @@
+use std::path::{Path, PathBuf};
+
+use gix_hash::ObjectId;
+use gix_ref::{transaction::PreviousValue, FullName};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    Repository,
+};
+
+/// What to check out in a new worktree created with [`Repository::worktree_add()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// Check out the existing branch with the given name, which must not be checked out in another worktree.
+    Branch(FullName),
+    /// Create a new branch with the given `name` which points to `start`, and check it out.
+    NewBranch {
+        /// The name of the branch to create, which must not exist yet.
+        name: FullName,
+        /// The commit the new branch should point to.
+        start: ObjectId,
+    },
+    /// Check out the given commit with a detached `HEAD`.
+    Detached(ObjectId),
+}
+
+/// Options for use in [`Repository::worktree_add()`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The name of the directory within `.git/worktrees` to keep the private files of the worktree, or `None` to
+    /// use the name of the worktree directory.
+    ///
+    /// A number is appended to it if a worktree with the same name already exists.
+    pub id: Option<BString>,
+    /// If set, the new worktree is [locked](crate::worktree::Proxy::lock()) with the given reason, which may be empty.
+    pub lock_reason: Option<BString>,
+    /// If `true`, the files of the worktree are not written and no index is created, similar to `git worktree add --no-checkout`.
+    pub no_checkout: bool,
+}
+
+/// The error returned by [`Repository::worktree_add()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("'{}' already exists and isn't an empty directory", path.display())]
+    PathExists { path: PathBuf },
+    #[error("The worktree id '{id}' must not be empty or contain path separators")]
+    InvalidId { id: BString },
+    #[error("Branch '{}' is already checked out in another worktree", name.as_bstr())]
+    BranchCheckedOut { name: FullName },
+    #[error(transparent)]
+    FindBranch(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelBranch(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    CreateBranch(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToTree(#[from] crate::object::peel::to_kind::Error),
+    #[error("Could not create an index from the tree at {id}")]
+    IndexFromTree {
+        id: ObjectId,
+        source: gix_traverse::tree::breadthfirst::Error,
+    },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Open(#[from] crate::open::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::merge::worktree::checkout::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+}
+
+/// Creating linked worktrees
+impl Repository {
+    /// Create a new linked worktree at `path` which checks out `target`, and return it as repository, similar to `git worktree add`.
+    ///
+    /// `path` must not exist or be an empty directory. The private files of the worktree are kept in `.git/worktrees/<id>`, and
+    /// are written just like `git` does, so `git` can use and manage the worktree as well.
+    pub fn worktree_add(&self, path: impl AsRef<Path>, target: Target, options: Options) -> Result<Repository, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::worktree_add()");
+        let path = path.as_ref();
+        let path_existed = path.exists();
+        if path_existed && (!path.is_dir() || std::fs::read_dir(path)?.next().is_some()) {
+            return Err(Error::PathExists { path: path.to_owned() });
+        }
+        let id = match options.id {
+            Some(id) => id,
+            None => gix_path::into_bstr(path.file_name().map_or(path, Path::new)).into_owned(),
+        };
+        if id.is_empty() || id == "." || id == ".." || id.contains(&b'/') || id.contains(&b'\\') {
+            return Err(Error::InvalidId { id });
+        }
+
+        let (head, commit) = match target {
+            Target::Branch(name) => {
+                if self.is_checked_out(name.as_bstr())? {
+                    return Err(Error::BranchCheckedOut { name });
+                }
+                let commit = self.find_reference(name.as_ref())?.peel_to_id_in_place()?.detach();
+                (format!("ref: {}\n", name.as_bstr()), commit)
+            }
+            Target::NewBranch { name, start } => {
+                self.reference(
+                    name.clone(),
+                    start,
+                    PreviousValue::MustNotExist,
+                    format!("branch: Created from {start}"),
+                )?;
+                (format!("ref: {}\n", name.as_bstr()), start)
+            }
+            Target::Detached(commit) => (format!("{commit}\n"), commit),
+        };
+        let tree = self.find_object(commit)?.peel_to_tree()?.id;
+
+        std::fs::create_dir_all(path)?;
+        let path = gix_path::realpath(path).map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+        let worktrees_dir = gix_path::realpath(self.common_dir())
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?
+            .join("worktrees");
+        std::fs::create_dir_all(&worktrees_dir)?;
+        let git_dir = create_unique_dir(&worktrees_dir, id.as_ref())?;
+
+        let res = (|| -> Result<Repository, Error> {
+            let locked = git_dir.join("locked");
+            std::fs::write(&locked, "initializing\n")?;
+            std::fs::write(
+                git_dir.join("gitdir"),
+                super::path_file_contents(&path.join(gix_discover::DOT_GIT_DIR)),
+            )?;
+            std::fs::write(git_dir.join("commondir"), "../..\n")?;
+            std::fs::write(git_dir.join("HEAD"), head)?;
+            std::fs::write(
+                path.join(gix_discover::DOT_GIT_DIR),
+                [b"gitdir: ".as_slice(), &super::path_file_contents(&git_dir)].concat(),
+            )?;
+
+            let repo: Repository = crate::ThreadSafeRepository::open_opts(&path, self.options.clone())?.into();
+            if !options.no_checkout {
+                let index = gix_index::State::from_tree(&tree, &repo.objects)
+                    .map_err(|err| Error::IndexFromTree { id: tree, source: err })?;
+                let index = crate::merge::worktree::write_files(&repo, index, false)?;
+                gix_index::File::from_state(index, repo.index_path()).write(Default::default())?;
+            }
+
+            match options.lock_reason {
+                Some(reason) => std::fs::write(&locked, super::lock_file_contents(reason.as_ref()))?,
+                None => std::fs::remove_file(&locked)?,
+            }
+            Ok(repo)
+        })();
+        if res.is_err() {
+            std::fs::remove_dir_all(&git_dir).ok();
+            if path_existed {
+                if let Ok(entries) = std::fs::read_dir(&path) {
+                    for entry in entries.flatten() {
+                        let path = entry.path();
+                        if path.is_dir() {
+                            std::fs::remove_dir_all(path).ok();
+                        } else {
+                            std::fs::remove_file(path).ok();
+                        }
+                    }
+                }
+            } else {
+                std::fs::remove_dir_all(&path).ok();
+            }
+        }
+        res
+    }
+
+    /// Return `true` if the branch with `name` is checked out in the main worktree, unless it's bare, or any linked worktree.
+    fn is_checked_out(&self, name: &BStr) -> std::io::Result<bool> {
+        let mut git_dirs = Vec::new();
+        if !self.config.is_bare {
+            git_dirs.push(self.common_dir().to_owned());
+        }
+        git_dirs.extend(self.worktrees()?.into_iter().map(|proxy| proxy.git_dir));
+        Ok(git_dirs.iter().any(|git_dir| {
+            std::fs::read(git_dir.join("HEAD")).map_or(false, |head| {
+                head.trim_end()
+                    .strip_prefix(b"ref: ")
+                    .map_or(false, |head| head.trim() == name)
+            })
+        }))
+    }
+}
+
+/// Create a directory named `id` in `dir`, or `id` with a number appended if it already exists, and return its path.
+fn create_unique_dir(dir: &Path, id: &BStr) -> std::io::Result<PathBuf> {
+    let mut counter = 0;
+    loop {
+        let name = if counter == 0 {
+            id.to_owned()
+        } else {
+            format!("{id}{counter}").into()
+        };
+        let candidate = dir.join(gix_path::from_bstr(name.as_bstr()));
+        match std::fs::create_dir(&candidate) {
+            Ok(()) => return Ok(candidate),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => counter += 1,
+            Err(err) => return Err(err),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{
+    bstr::{BStr, BString},
+    worktree::Proxy,
+};
+
+/// The error returned by [`Proxy::lock()`] and [`Proxy::unlock()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The worktree '{id}' is already locked")]
+    AlreadyLocked { id: BString },
+    #[error("The worktree '{id}' isn't locked")]
+    NotLocked { id: BString },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Locking
+impl Proxy<'_> {
+    /// Lock the worktree with an optional `reason` so it won't be [pruned](crate::Repository::worktree_prune()), moved or
+    /// removed, similar to `git worktree lock`.
+    ///
+    /// This is useful if the worktree is located on a storage device that isn't always available.
+    pub fn lock(&self, reason: Option<&BStr>) -> Result<(), Error> {
+        if self.is_locked() {
+            return Err(Error::AlreadyLocked {
+                id: self.id().to_owned(),
+            });
+        }
+        std::fs::write(
+            self.git_dir.join("locked"),
+            super::lock_file_contents(reason.unwrap_or_default()),
+        )?;
+        Ok(())
+    }
+
+    /// Unlock a previously [locked](Self::lock()) worktree, similar to `git worktree unlock`.
+    pub fn unlock(&self) -> Result<(), Error> {
+        if !self.is_locked() {
+            return Err(Error::NotLocked {
+                id: self.id().to_owned(),
+            });
+        }
+        std::fs::remove_file(self.git_dir.join("locked"))?;
+        Ok(())
+    }
+}
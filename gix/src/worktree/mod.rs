@@ -82,9 +82,41 @@ pub(crate) fn id(git_dir: &std::path::Path, has_common_dir: bool) -> Option<&BSt
     (maybe_worktrees.file_name()?.to_str()? == "worktrees").then_some(candidate)
 }
 
+/// Return `path` as it is written into the files linking worktrees and their private git directories.
+pub(crate) fn path_file_contents(path: &std::path::Path) -> Vec<u8> {
+    let mut out = gix_path::to_unix_separators_on_windows(gix_path::into_bstr(path)).into_owned();
+    out.push(b'\n');
+    out.into()
+}
+
+/// Remove the directory containing the private files of all linked worktrees of `repo` if there are none left.
+pub(crate) fn remove_worktrees_dir_if_empty(repo: &Repository) {
+    std::fs::remove_dir(repo.common_dir().join("worktrees")).ok();
+}
+
+/// Return `reason` as it is written into the `locked` file, which is empty if there is no reason.
+pub(crate) fn lock_file_contents(reason: &BStr) -> Vec<u8> {
+    let mut out = reason.to_vec();
+    if !out.is_empty() {
+        out.push(b'\n');
+    }
+    out
+}
+
 ///
 pub mod proxy;
 
+///
+#[cfg(feature = "worktree-management")]
+pub mod add;
+///
+pub mod lock;
+///
+#[cfg(feature = "worktree-management")]
+pub mod remove;
+///
+pub mod repair;
+
 ///
 #[cfg(feature = "index")]
 pub mod open_index {
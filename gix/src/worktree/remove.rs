@@ -0,0 +1,74 @@
+use crate::{
+    bstr::{BStr, BString},
+    worktree::Proxy,
+};
+
+/// The error returned by [`Proxy::remove()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The worktree '{id}' is locked and can't be removed without force")]
+    Locked { id: BString },
+    #[error("The worktree contains untracked files, the first of {} being at '{}'", .paths.len(), .paths[0])]
+    Untracked { paths: Vec<BString> },
+    #[error(transparent)]
+    RequireClean(#[from] crate::merge::worktree::require_clean::Error),
+    #[error(transparent)]
+    IntoRepo(#[from] crate::worktree::proxy::into_repo::Error),
+    #[error(transparent)]
+    HeadTree(#[from] crate::reference::head_tree_id::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    DirwalkOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    Dirwalk(#[from] crate::repository::dirwalk::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Removal
+impl Proxy<'_> {
+    /// Delete the checkout of this worktree along with its private files in the git directory of the parent repository,
+    /// similar to `git worktree remove`.
+    ///
+    /// Unless `force` is `true`, it's an error if the worktree is [locked](Self::is_locked()), if the index or files in the worktree
+    /// have changes, or if there are untracked files that aren't ignored.
+    pub fn remove(self, force: bool) -> Result<(), Error> {
+        if !force && self.is_locked() {
+            return Err(Error::Locked {
+                id: self.id().to_owned(),
+            });
+        }
+        let base = self.base().ok().filter(|base| base.is_dir());
+        if let Some(base) = &base {
+            if !force {
+                let repo = self.clone().into_repo()?;
+                let index = repo.index_or_empty()?;
+                crate::merge::worktree::require_clean(&repo, &index, Some(&repo.head_tree_id()?))?;
+
+                let mut collect = gix_dir::walk::delegate::Collect::default();
+                repo.dirwalk(
+                    &index,
+                    None::<&BStr>,
+                    repo.dirwalk_options()?
+                        .emit_untracked(gix_dir::walk::EmissionMode::CollapseDirectory),
+                    &mut collect,
+                )?;
+                let untracked: Vec<_> = collect
+                    .into_entries_by_path()
+                    .into_iter()
+                    .filter(|(entry, _)| entry.status == gix_dir::entry::Status::Untracked)
+                    .map(|(entry, _)| entry.rela_path)
+                    .collect();
+                if !untracked.is_empty() {
+                    return Err(Error::Untracked { paths: untracked });
+                }
+            }
+            std::fs::remove_dir_all(base)?;
+        }
+        std::fs::remove_dir_all(&self.git_dir)?;
+        super::remove_worktrees_dir_if_empty(self.parent);
+        Ok(())
+    }
+}
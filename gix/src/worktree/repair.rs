@@ -0,0 +1,104 @@
+use std::path::{Path, PathBuf};
+
+use crate::Repository;
+
+/// A file rewritten by [`Repository::worktree_repair()`] to restore the link between a linked worktree and its private files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// The `.git` file in the worktree at `path` now points to the private git directory of the worktree again.
+    DotGitFile {
+        /// The path to the `.git` file that was rewritten.
+        path: PathBuf,
+    },
+    /// The `gitdir` file at `path` within the private git directory of a worktree now points to the worktree again.
+    GitDirFile {
+        /// The path to the `gitdir` file that was rewritten.
+        path: PathBuf,
+    },
+}
+
+/// Repairing linked worktrees
+impl Repository {
+    /// Restore the links between linked worktrees and their private git directories in case the worktrees or the repository
+    /// were moved, similar to `git worktree repair`, and return all files that were rewritten.
+    ///
+    /// `paths` are the new locations of worktrees that were moved, or the worktree of this repository is used if it is a linked worktree.
+    /// All worktrees whose `.git` file doesn't point to their private git directory, for example because the repository was moved, are
+    /// repaired as well.
+    pub fn worktree_repair(&self, paths: impl IntoIterator<Item = impl AsRef<Path>>) -> std::io::Result<Vec<Change>> {
+        let worktrees_dir = realpath(self.common_dir())?.join("worktrees");
+        let mut changes = Vec::new();
+        let mut paths: Vec<_> = paths.into_iter().map(|path| path.as_ref().to_owned()).collect();
+        if paths.is_empty() && self.common_dir.is_some() {
+            paths.extend(self.work_dir().map(ToOwned::to_owned));
+        }
+        for path in paths {
+            let dot_git = realpath(&path)?.join(gix_discover::DOT_GIT_DIR);
+            let Ok(git_dir) = gix_discover::path::from_gitdir_file(&dot_git) else {
+                continue;
+            };
+            let git_dir = match realpath(&git_dir) {
+                Ok(git_dir) if git_dir.parent() == Some(worktrees_dir.as_path()) => git_dir,
+                _ => {
+                    let Some(candidate) = git_dir.file_name().map(|id| worktrees_dir.join(id)) else {
+                        continue;
+                    };
+                    if !candidate.is_dir() {
+                        continue;
+                    }
+                    write_link(&dot_git, &candidate, b"gitdir: ", &mut changes, |path| {
+                        Change::DotGitFile { path }
+                    })?;
+                    candidate
+                }
+            };
+            let gitdir_file = git_dir.join("gitdir");
+            if gix_discover::path::from_plain_file(&gitdir_file).and_then(Result::ok) != Some(dot_git.clone()) {
+                write_link(&gitdir_file, &dot_git, b"", &mut changes, |path| Change::GitDirFile {
+                    path,
+                })?;
+            }
+        }
+
+        let iter = match std::fs::read_dir(&worktrees_dir) {
+            Ok(iter) => iter,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(changes),
+            Err(err) => return Err(err),
+        };
+        for entry in iter {
+            let git_dir = entry?.path();
+            let Some(Ok(dot_git)) = gix_discover::path::from_plain_file(&git_dir.join("gitdir")) else {
+                continue;
+            };
+            if !dot_git.is_file() {
+                continue;
+            }
+            let points_to_git_dir = gix_discover::path::from_gitdir_file(&dot_git)
+                .ok()
+                .and_then(|path| realpath(&path).ok())
+                .map_or(false, |path| path == git_dir);
+            if !points_to_git_dir {
+                write_link(&dot_git, &git_dir, b"gitdir: ", &mut changes, |path| {
+                    Change::DotGitFile { path }
+                })?;
+            }
+        }
+        Ok(changes)
+    }
+}
+
+fn write_link(
+    path: &Path,
+    target: &Path,
+    prefix: &[u8],
+    changes: &mut Vec<Change>,
+    change: impl FnOnce(PathBuf) -> Change,
+) -> std::io::Result<()> {
+    std::fs::write(path, [prefix, &super::path_file_contents(target)].concat())?;
+    changes.push(change(path.to_owned()));
+    Ok(())
+}
+
+fn realpath(path: &Path) -> std::io::Result<PathBuf> {
+    gix_path::realpath(path).map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))
+}
@@ -102,6 +102,7 @@ mod blocking_io {
             ),
             "we can avoid fetching from remotes with this setting"
         );
+        assert_eq!(gix::error::ErrorCode::code(&err), gix::error::Code::Configuration);
         Ok(())
     }
 
@@ -116,8 +117,8 @@ mod blocking_io {
             vec![
                 hex_to_id("2d9d136fb0765f2e24c44a0f91984318d580d03b"),
                 hex_to_id("dfd0954dabef3b64f458321ef15571cc1a46d552"),
-                hex_to_id("dfd0954dabef3b64f458321ef15571cc1a46d552"),
-            ]
+            ],
+            "shallow commits the remote sends more than once are only written once"
         );
         assert_eq!(
             repo.config_snapshot().boolean("my.marker"),
@@ -559,6 +560,176 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    fn fetch_only_partial_then_fetch_missing_objects_on_demand() -> crate::Result {
+        for version in [
+            gix::protocol::transport::Protocol::V1,
+            gix::protocol::transport::Protocol::V2,
+        ] {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let (repo, _out) = gix::clone::PrepareFetch::new(
+                gix_testtools::scripted_fixture_read_only("make_promisor_remote.sh")?.join("server"),
+                tmp.path(),
+                gix::create::Kind::Bare,
+                Default::default(),
+                restricted().config_overrides(Some(format!("protocol.version={}", version as u8))),
+            )?
+            .with_filter("blob:none")
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+            for repo in [&gix::open_opts(repo.git_dir(), restricted())?, &repo] {
+                assert_eq!(
+                    repo.promisor_remote_name().expect("set").as_ref(),
+                    "origin",
+                    "the configuration is persisted"
+                );
+                assert_eq!(
+                    repo.find_remote("origin")?.partial_clone_filter().expect("set"),
+                    "blob:none"
+                );
+            }
+            let promisor_files = std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().extension() == Some("promisor".as_ref()))
+                .count();
+            assert_eq!(
+                promisor_files, 1,
+                "the received pack is marked as coming from a promisor"
+            );
+
+            let head = repo.head_commit()?;
+            let blob_id = head.tree()?.find_entry("a").expect("present").object_id();
+            let previous_blob_id = head
+                .parent_ids()
+                .next()
+                .expect("has parent")
+                .object()?
+                .into_commit()
+                .tree()?
+                .find_entry("a")
+                .expect("present")
+                .object_id();
+            assert!(!repo.has_object(blob_id), "blobs were filtered");
+            assert!(!repo.has_object(previous_blob_id));
+
+            assert_eq!(
+                repo.find_object(blob_id)?.data.as_bstr(),
+                "a changed\n",
+                "missing objects are fetched on demand"
+            );
+            assert!(repo.has_object(blob_id));
+            assert!(
+                !repo.has_object(previous_blob_id),
+                "only the objects that are asked for are fetched"
+            );
+            assert!(
+                repo.try_find_object(previous_blob_id)?.is_none(),
+                "lookups that expect objects to be missing don't fetch"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_only_partial_then_backfill_and_refetch() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let server = gix_testtools::scripted_fixture_read_only("make_promisor_remote.sh")?.join("server");
+        let (repo, _out) = gix::clone::PrepareFetch::new(
+            server.clone(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )?
+        .with_filter("blob:none")
+        .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+
+        let head = repo.head_commit()?;
+        let tree = head.tree()?;
+        let blob_a = tree.find_entry("a").expect("present").object_id();
+        let blob_b = tree
+            .lookup_entry_by_path("dir/b", &mut Vec::new())?
+            .expect("present")
+            .object_id();
+        let previous_blob_a = head
+            .parent_ids()
+            .next()
+            .expect("has parent")
+            .object()?
+            .into_commit()
+            .tree()?
+            .find_entry("a")
+            .expect("present")
+            .object_id();
+
+        assert_eq!(
+            repo.backfill_missing_blobs(tree.id, Some("dir"), gix::progress::Discard, &AtomicBool::default())?,
+            1
+        );
+        assert!(repo.has_object(blob_b));
+        assert!(!repo.has_object(blob_a), "only blobs matching the pathspec are fetched");
+
+        for expected_count in [1, 0] {
+            assert_eq!(
+                repo.backfill_missing_blobs(tree.id, None::<&str>, gix::progress::Discard, &AtomicBool::default())?,
+                expected_count,
+                "all missing blobs are fetched, and nothing is done if none is missing"
+            );
+        }
+        assert!(repo.has_object(blob_a));
+        assert!(!repo.has_object(previous_blob_a), "blobs of other trees aren't fetched");
+
+        let remote = || -> crate::Result<gix::Remote<'_>> {
+            Ok(repo
+                .remote_at(server.as_path())?
+                .with_refspecs(Some("+refs/heads/*:refs/remotes/origin/*"), Direction::Fetch)?)
+        };
+        let outcome = remote()?
+            .connect(Direction::Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        assert!(
+            matches!(outcome.status, gix::remote::fetch::Status::NoPackReceived { .. }),
+            "without changes on the remote, there is nothing to fetch"
+        );
+
+        let outcome = remote()?
+            .connect(Direction::Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .with_refetch(true)
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        assert!(
+            matches!(outcome.status, gix::remote::fetch::Status::Change { .. }),
+            "a pack is received even though all refs are up-to-date"
+        );
+        assert!(
+            repo.has_object(previous_blob_a),
+            "as the remote has no filter, all previously filtered objects are received"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_and_checkout_partial() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let mut prepare = gix::clone::PrepareFetch::new(
+            gix_testtools::scripted_fixture_read_only("make_promisor_remote.sh")?.join("server"),
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted(),
+        )?
+        .with_filter("blob:none");
+        let (mut checkout, _out) =
+            prepare.fetch_then_checkout(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        let (repo, _) = checkout.main_worktree(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let work_dir = repo.work_dir().expect("non-bare");
+        assert_eq!(std::fs::read(work_dir.join("a"))?.as_bstr(), "a changed\n");
+        assert_eq!(std::fs::read(work_dir.join("dir").join("b"))?.as_bstr(), "b\n");
+        Ok(())
+    }
+
     #[test]
     fn fetch_only_without_configuration() -> crate::Result {
         let tmp = gix_testtools::tempfile::TempDir::new()?;
@@ -611,9 +782,15 @@ fn clone_and_destination_must_be_empty() -> crate::Result {
         restricted(),
     ) {
         Ok(_) => unreachable!("this should fail as the directory isn't empty"),
-        Err(err) => assert!(err
-            .to_string()
-            .starts_with("Refusing to initialize the non-empty directory as ")),
+        Err(err) => {
+            assert!(err
+                .to_string()
+                .starts_with("Refusing to initialize the non-empty directory as "));
+            assert_eq!(
+                gix::error::ErrorCode::code(&err),
+                gix::error::Code::WouldOverwriteLocalChanges
+            );
+        }
     }
     Ok(())
 }
@@ -238,6 +238,130 @@ mod fetch {
     }
 }
 
+#[cfg(feature = "apply")]
+mod apply {
+    use gix::{
+        apply::blob::Whitespace,
+        config::tree::{Apply, Key},
+    };
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn whitespace() -> crate::Result {
+        for (value, expected) in [
+            ("nowarn", Whitespace::Ignore),
+            ("warn", Whitespace::Ignore),
+            ("fix", Whitespace::Fix),
+            ("strip", Whitespace::Fix),
+            ("error", Whitespace::Error),
+            ("error-all", Whitespace::Error),
+        ] {
+            assert_eq!(Apply::WHITESPACE.try_into_whitespace(bcow(value))?, expected);
+            assert!(Apply::WHITESPACE.validate(value.into()).is_ok());
+        }
+        assert_eq!(
+            Apply::WHITESPACE
+                .try_into_whitespace(bcow("foo"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"apply.whitespace=foo\" was invalid"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ignore_whitespace() -> crate::Result {
+        assert!(Apply::IGNORE_WHITESPACE.try_into_ignore_whitespace(bcow("change"))?);
+        for value in ["no", "none", "never", "false"] {
+            assert!(!Apply::IGNORE_WHITESPACE.try_into_ignore_whitespace(bcow(value))?);
+        }
+        assert!(Apply::IGNORE_WHITESPACE.validate("true".into()).is_err());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "attributes")]
+mod submodule {
+    use gix::config::tree::{Key, Submodule};
+    use gix::submodule::config::{FetchRecurse, Ignore, Update};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn update() -> crate::Result {
+        for (value, expected) in [
+            ("checkout", Update::Checkout),
+            ("rebase", Update::Rebase),
+            ("merge", Update::Merge),
+            ("none", Update::None),
+            ("!git pull", Update::Command("git pull".into())),
+        ] {
+            assert_eq!(Submodule::UPDATE.try_into_update(bcow(value))?, expected);
+            assert!(Submodule::UPDATE.validate(value.into()).is_ok());
+        }
+        assert_eq!(
+            Submodule::UPDATE.try_into_update(bcow("foo")).unwrap_err().to_string(),
+            "The key \"submodule.<name>.update=foo\" was invalid"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_recurse_submodules() -> crate::Result {
+        assert_eq!(
+            Submodule::FETCH_RECURSE_SUBMODULES.try_into_fetch_recurse(Ok(true))?,
+            FetchRecurse::Always
+        );
+        assert_eq!(
+            Submodule::FETCH_RECURSE_SUBMODULES.try_into_fetch_recurse(Ok(false))?,
+            FetchRecurse::Never
+        );
+        for value in ["on-demand", "yes", "false"] {
+            assert!(Submodule::FETCH_RECURSE_SUBMODULES.validate(value.into()).is_ok());
+        }
+        assert!(Submodule::FETCH_RECURSE_SUBMODULES.validate("foo".into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn ignore() -> crate::Result {
+        for (value, expected) in [
+            ("all", Ignore::All),
+            ("dirty", Ignore::Dirty),
+            ("untracked", Ignore::Untracked),
+            ("none", Ignore::None),
+        ] {
+            assert_eq!(Submodule::IGNORE.try_into_ignore(bcow(value))?, expected);
+            assert!(Submodule::IGNORE.validate(value.into()).is_ok());
+        }
+        assert!(Submodule::IGNORE.validate("foo".into()).is_err());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "merge")]
+mod merge {
+    use gix::config::tree::{Key, Merge};
+
+    #[test]
+    fn driver_keys_require_a_driver_name() {
+        assert_eq!(Merge::DEFAULT.logical_name(), "merge.default");
+        for (key, expected) in [
+            (&Merge::DRIVER_NAME as &dyn Key, "merge.<driver>.name"),
+            (&Merge::DRIVER_DRIVER, "merge.<driver>.driver"),
+            (&Merge::DRIVER_RECURSIVE, "merge.<driver>.recursive"),
+        ] {
+            assert_eq!(key.logical_name(), expected);
+            assert!(key.full_name(None).is_err());
+        }
+        assert_eq!(
+            Merge::DRIVER_DRIVER.full_name(Some("union".into())).expect("valid"),
+            "merge.union.driver"
+        );
+    }
+}
+
 #[cfg(feature = "blob-diff")]
 mod diff {
     use gix::{
@@ -315,17 +439,12 @@ mod diff {
             ("Default", Algorithm::Myers),
             ("minimal", Algorithm::MyersMinimal),
             ("histogram", Algorithm::Histogram),
+            ("patience", Algorithm::Patience),
+            ("Patience", Algorithm::Patience),
         ] {
             assert_eq!(Diff::ALGORITHM.try_into_algorithm(bcow(actual))?, expected);
             assert!(Diff::ALGORITHM.validate(actual.into()).is_ok());
         }
-        assert_eq!(
-            Diff::ALGORITHM
-                .try_into_algorithm(bcow("patience"))
-                .unwrap_err()
-                .to_string(),
-            "The 'patience' algorithm is not yet implemented"
-        );
         assert_eq!(
             Diff::ALGORITHM.try_into_algorithm(bcow("foo")).unwrap_err().to_string(),
             "Unknown diff algorithm named 'foo'"
@@ -1080,3 +1199,162 @@ mod remote {
         );
     }
 }
+
+mod lookup {
+    use gix::config::{
+        tree::{find_key, gitoxide, validate_key, Core, Key, Remote, Section, ValueType},
+        Tree,
+    };
+
+    fn all_keys(section: &dyn Section, out: &mut Vec<String>) {
+        for key in section.keys() {
+            out.push(key.logical_name());
+            for value in match key.value_type() {
+                ValueType::OneOf(values) => values.to_vec(),
+                ValueType::BooleanOrOneOf(values) => values.iter().copied().chain(Some("true")).collect(),
+                _ => Vec::new(),
+            } {
+                assert!(
+                    key.validate(value.into()).is_ok(),
+                    "{key:?}: advertised value {value:?} must be valid"
+                );
+            }
+        }
+        for sub_section in section.sub_sections() {
+            all_keys(*sub_section, out);
+        }
+    }
+
+    #[test]
+    fn advertised_values_are_valid() {
+        let mut names = Vec::new();
+        for section in Tree.sections() {
+            all_keys(*section, &mut names);
+        }
+        assert!(names.len() > 100, "there are many keys to enumerate");
+    }
+
+    #[test]
+    fn value_type() {
+        assert_eq!(Core::BARE.value_type(), ValueType::Boolean);
+        assert_eq!(Remote::URL.value_type(), ValueType::Url);
+        assert_eq!(Core::EOL.value_type(), ValueType::OneOf(&["lf", "crlf", "native"]));
+        assert_eq!(Core::AUTO_CRLF.value_type(), ValueType::BooleanOrOneOf(&["input"]));
+        assert_eq!(Core::EDITOR.value_type(), ValueType::Program);
+        assert_eq!(Core::HOOKS_PATH.value_type(), ValueType::Path);
+    }
+
+    #[test]
+    fn find_key() -> crate::Result {
+        assert_eq!(Tree.find_key("core.bare".into())?.logical_name(), "core.bare");
+        assert_eq!(
+            Tree.find_key("CORE.BaRe".into())?.logical_name(),
+            "core.bare",
+            "section and key names are case-insensitive"
+        );
+        assert_eq!(
+            Tree.find_key("remote.origin.url".into())?.logical_name(),
+            "remote.<name>.url"
+        );
+        assert_eq!(
+            Tree.find_key("remote.a.b.url".into())?.logical_name(),
+            "remote.<name>.url",
+            "sub-sections may contain dots"
+        );
+        assert_eq!(
+            Tree.find_key("gitoxide.core.useNsec".into())?.logical_name(),
+            gitoxide::Core::USE_NSEC.logical_name(),
+            "static sub-sections are found as well"
+        );
+
+        for (name, expected) in [
+            (
+                "core",
+                "Key name \"core\" must be of the form 'section.key' or 'section.subsection.key'",
+            ),
+            ("unknown.key", "The section named \"unknown\" is unknown"),
+            ("core.unknown", "The key named \"core.unknown\" is unknown"),
+            (
+                "gitoxide.core.unknown",
+                "The key named \"gitoxide.core.unknown\" is unknown",
+            ),
+            (
+                "core.sub.bare",
+                "The key named 'core.bare' cannot be used with non-static subsections.",
+            ),
+            (
+                "remote.url",
+                "The key named 'remote.<name>.url' cannot be used without subsections.",
+            ),
+        ] {
+            assert_eq!(Tree.find_key(name.into()).unwrap_err().to_string(), expected);
+        }
+        assert!(matches!(
+            Tree.find_key("core.unknown".into()),
+            Err(find_key::Error::UnknownKey { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn validate_key() -> crate::Result {
+        assert_eq!(
+            Tree.validate_key("core.bare".into(), "yes".into())?.logical_name(),
+            "core.bare"
+        );
+        let err = Tree.validate_key("core.bare".into(), "maybe".into()).unwrap_err();
+        assert_eq!(err.to_string(), "The value \"maybe\" of key \"core.bare\" is invalid");
+        assert!(matches!(err, validate_key::Error::Value { .. }));
+        assert!(matches!(
+            Tree.validate_key("core.unknown".into(), "maybe".into()),
+            Err(validate_key::Error::Find(find_key::Error::UnknownKey { .. }))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn diagnostics() -> crate::Result {
+        let config = gix_config::File::try_from(
+            "[core]\n\tbare = maybe\n\tbare = true\n\tunknown = 1\n\tunknown = 2\n\tnovalue\n\teditor = vim\n\
+             [remote \"origin\"]\n\turl = https://example.com/repo\n\ttagOpt = sometimes\n\
+             [foo]\n\tbar = baz\n",
+        )?;
+        let actual: Vec<_> = Tree
+            .diagnostics(&config)
+            .into_iter()
+            .map(|d| (d.name.to_string(), d.value.to_string(), d.error.to_string()))
+            .collect();
+        assert_eq!(
+            actual,
+            [
+                (
+                    "core.bare".into(),
+                    "maybe".into(),
+                    "The value \"maybe\" of key \"core.bare\" is invalid".into()
+                ),
+                (
+                    "core.unknown".into(),
+                    "1".into(),
+                    "The key named \"core.unknown\" is unknown".into()
+                ),
+                (
+                    "core.novalue".into(),
+                    "".into(),
+                    "The key named \"core.novalue\" is unknown".into()
+                ),
+                (
+                    "remote.origin.tagOpt".into(),
+                    "sometimes".into(),
+                    "The value \"sometimes\" of key \"remote.origin.tagOpt\" is invalid".into()
+                ),
+                (
+                    "foo.bar".into(),
+                    "baz".into(),
+                    "The section named \"foo\" is unknown".into()
+                ),
+            ],
+            "each unknown key is reported once, while every invalid value is reported"
+        );
+        Ok(())
+    }
+}
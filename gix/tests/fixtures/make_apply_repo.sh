@@ -0,0 +1,39 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q repo
+cd repo
+git config apply.whitespace fix
+git config apply.ignoreWhitespace change
+
+seq 1 10 >a
+echo b >b
+git add . && git commit -q -m base
+
+sed -i 's/^3$/three/' a
+git mv b b-renamed
+chmod +x b-renamed
+echo c >c
+git add a b-renamed c
+git diff --cached >../change.patch
+git reset -q --hard
+
+sed -i 's/^7$/seven/' a
+git diff >../three-way.patch
+git checkout -q a
+
+sed -i 's/^9$/neun/' a
+git diff >../conflict.patch
+git checkout -q a
+
+sed -i 's/^9$/nine/' a
+git commit -q -am "nine on main"
+
+git checkout -q -b feature
+sed -i 's/^5$/five/' a
+echo d >d
+git add a d
+GIT_AUTHOR_NAME="Patch Author" GIT_AUTHOR_EMAIL="author@example.com" GIT_AUTHOR_DATE="2023-01-02 03:04:05 +0200" \
+  git commit -q -m "five and d" -m "A body with details."
+git format-patch -1 --stdout >../mail
+git checkout -q main
@@ -0,0 +1,20 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git checkout -q -b main
+  echo a > a && git add a && git commit -q -m first
+  echo b > b && git add b && git commit -q -m second
+  git tag -a -m "annotated" v1
+)
+
+# A clone that lacks the last commit, to apply incremental bundles to.
+git clone -q repo older
+
+(cd repo
+  echo c > c && git add c && git commit -q -m third
+
+  git bundle create -q ../all.bundle --all
+  git bundle create -q ../incremental.bundle main~1..main
+)
@@ -0,0 +1,17 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+mkdir dir
+echo a > a && echo b > b && echo c > dir/c
+git add . && git commit -q -m "main"
+git tag tagged
+
+git checkout -q -b other
+echo "b on other" > b && echo d > d
+git rm -q dir/c
+git add . && git commit -q -m "other"
+
+git checkout -q main
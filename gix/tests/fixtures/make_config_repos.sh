@@ -33,6 +33,15 @@ git clone --shared http-config http-remote-override
   git config remote.origin.proxyAuthMethod negotiate
 )
 
+git init http-client-auth
+(cd http-client-auth
+  git config http.sslCert ./client.pem
+  git config http.sslKey ./client.key
+  git config http.sslCertPasswordProtected true
+  git config http.emptyAuth true
+  git config gitoxide.http.authMethod ntlm
+)
+
 git init http-no-proxy
 (cd http-no-proxy
   git config gitoxide.http.noProxy "no validation done here"
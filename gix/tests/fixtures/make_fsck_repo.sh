@@ -0,0 +1,16 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo a > a && git add a && git commit -q -m first
+echo b > b && git add b && git commit -q -m second
+
+# A blob that nothing refers to.
+echo dangling | git hash-object -w --stdin >/dev/null
+
+# A commit that nothing refers to, along with its tree and blob which are only referred to by the commit.
+blob=$(echo unreachable | git hash-object -w --stdin)
+tree=$(printf "100644 blob %s\tunreachable\n" "$blob" | git mktree)
+git commit-tree "$tree" -p HEAD -m unreachable >/dev/null
@@ -0,0 +1,17 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+# A linear history that is long enough to make the per-commit cost of a walk measurable.
+for idx in $(seq 1 5000); do
+  message="commit $idx"
+  echo "commit refs/heads/main"
+  echo "author author <author@example.com> $((1000000000 + idx)) +0000"
+  echo "committer committer <committer@example.com> $((1000000000 + idx)) +0000"
+  echo "data ${#message}"
+  echo "$message"
+done | git fast-import --quiet
+
+git commit-graph write --no-progress --reachable
@@ -0,0 +1,25 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  cat >.mailmap <<MAILMAP
+From HEAD <a@example.com>
+From HEAD <b@example.com>
+MAILMAP
+  mkdir dir
+  echo "Not A Mailmap <d@example.com>" > dir/.mailmap
+  git add . && git commit -q -m "first"
+
+  cat >.mailmap <<MAILMAP
+From Worktree <a@example.com>
+From Worktree <b@example.com>
+From Worktree <c@example.com>
+MAILMAP
+
+  git tag mailmap-blob "$(printf 'From Blob <b@example.com>\nFrom Blob <c@example.com>\n' | git hash-object -w --stdin)"
+  git tag mailmap-tree "$(git rev-parse HEAD:dir)"
+  git config mailmap.blob mailmap-blob
+)
+
+git clone -q --bare repo bare.git
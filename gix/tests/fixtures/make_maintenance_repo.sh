@@ -0,0 +1,15 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo a > a && git add a && git commit -q -m first
+git repack -q -d
+echo b > b && git add b && git commit -q -m second
+git repack -q -d
+echo c > c && git add c && git commit -q -m third
+
+# The third commit is still reachable from `other`, but not from `main` anymore.
+git branch other
+git reset -q --hard HEAD~1
@@ -0,0 +1,17 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+mkdir dir
+echo a >dir/a && echo b >dir/b
+git add . && git commit -q -m base
+
+git checkout -q -b added main
+echo new >dir/new
+git add dir/new && git commit -q -m "add file to dir"
+
+git checkout -q main
+git mv dir renamed
+git commit -q -m "rename dir"
@@ -0,0 +1,25 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+cat <<EOF >.gitattributes
+*.union merge=union
+*.custom merge=custom
+*.unknown merge=unknown
+*.bin -merge
+EOF
+files="a.union a.custom a.unknown a.bin a.txt"
+for f in $files; do echo base >$f; done
+git add . && git commit -q -m base
+
+git checkout -q -b theirs
+for f in $files; do echo theirs >$f; done
+git commit -q -am theirs
+
+git checkout -q -
+for f in $files; do echo ours >$f; done
+git commit -q -am ours
+
+git config merge.custom.name "take theirs"
+git config merge.custom.driver "cat %B >%A"
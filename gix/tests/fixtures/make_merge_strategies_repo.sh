@@ -0,0 +1,43 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+printf '1\n2\n3\n4\n5\n' >a.txt
+printf 'one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n' >long.txt
+git add . && git commit -q -m base
+
+for name in one two three; do
+  git checkout -q -b $name main
+  echo $name >$name.txt
+  git add $name.txt && git commit -q -m $name
+done
+
+git checkout -q -b conflicting main
+printf 'conflicting\n2\n3\n4\n5\n' >a.txt
+git commit -q -am conflicting
+
+git checkout -q -b renamed main
+git mv long.txt moved.txt
+printf 'one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\neleven\n' >moved.txt
+git commit -q -am renamed
+
+git checkout -q --orphan sub
+git rm -q -rf .
+printf 'fn lib() {}\n' >lib.rs
+git add lib.rs && git commit -q -m "sub v1"
+
+git checkout -q main
+printf 'main\n2\n3\n4\n5\n' >a.txt
+printf 'ONE\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n' >long.txt
+git commit -q -am main
+git merge -q -s ours --no-commit --allow-unrelated-histories sub
+git read-tree --prefix=vendor/sub -u sub
+git commit -q -m "add sub as subtree"
+
+git checkout -q sub
+printf 'fn lib() {}\nfn more() {}\n' >lib.rs
+git commit -q -am "sub v2"
+
+git checkout -q main
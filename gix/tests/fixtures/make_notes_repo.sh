@@ -0,0 +1,21 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo a > a && git add a && git commit -q -m first
+echo b > b && git add b && git commit -q -m second
+
+git notes add -m "note on first" HEAD~1
+git notes add -m "note on second" HEAD
+
+# A notes reference with fan-out, as it's written once there are many notes, along with a file that isn't a note.
+first=$(git rev-parse HEAD~1)
+note=$(echo "fanned out" | git hash-object -w --stdin)
+readme=$(echo "not a note" | git hash-object -w --stdin)
+printf "100644 %s\t%s/%s\n100644 %s\tREADME\n" "$note" "${first:0:2}" "${first:2}" "$readme" \
+  | GIT_INDEX_FILE=.git/fanout-index git update-index --add --index-info
+tree=$(GIT_INDEX_FILE=.git/fanout-index git write-tree)
+rm .git/fanout-index
+git update-ref refs/notes/fanout "$(git commit-tree -m fanout "$tree")"
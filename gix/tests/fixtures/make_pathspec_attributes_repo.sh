@@ -0,0 +1,26 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+cat <<ATTRS > .gitattributes
+[attr]mybin -diff -merge -text
+[attr]nested mybin custom=value
+*.bin mybin
+*.dat nested
+*.txt text
+ATTRS
+
+mkdir sub
+cat <<ATTRS > sub/.gitattributes
+[attr]ignored-macro -text
+*.sub ignored-macro
+ATTRS
+
+echo a > a.bin
+echo b > b.dat
+echo c > c.txt
+echo d > sub/d.sub
+echo e > sub/e.bin
+git add .
+git commit -q -m init
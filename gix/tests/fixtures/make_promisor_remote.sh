@@ -0,0 +1,15 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q server
+(cd server
+  git config uploadpack.allowFilter true
+  git config uploadpack.allowAnySHA1InWant true
+
+  mkdir dir
+  echo a > a
+  echo b > dir/b
+  git add . && git commit -q -m "first"
+  echo "a changed" > a
+  git commit -q -am "second"
+)
@@ -0,0 +1,54 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function setup() {
+  git init -q
+  git checkout -q -b main
+
+  printf '1\n2\n3\n' > a
+  echo 1 > b
+  git add . && git commit -q -m "base"
+
+  git branch feature
+  git branch conflicting
+
+  echo main >> a
+  git commit -q -am "main changes a"
+
+  git checkout -q feature
+  echo 2 > b
+  git commit -q -am "feature changes b"
+  echo main >> a
+  git commit -q -am "feature applies the change to a of main"
+  echo c > c
+  git add c && git commit -q -m "feature adds c"
+
+  git checkout -q conflicting
+  echo conflicting >> a
+  git commit -q -am "conflicting changes a"
+  echo 2 > b
+  git commit -q -am "conflicting changes b"
+}
+
+(mkdir feature && cd feature && setup && git checkout -q feature)
+(mkdir conflicting && cd conflicting && setup)
+
+(mkdir merged && cd merged && setup
+  git checkout -q feature
+  git merge -q --no-edit main
+  echo d > d
+  git add d && git commit -q -m "feature adds d after merging main"
+  git checkout -q main
+  echo e > e
+  git add e && git commit -q -m "main adds e"
+  git checkout -q feature
+)
+
+(mkdir upstream-ahead && cd upstream-ahead && setup
+  git checkout -q main
+  for n in 1 2 3; do
+    echo $n > main-$n
+    git add main-$n && git commit -q -m "main adds main-$n"
+  done
+  git checkout -q conflicting
+)
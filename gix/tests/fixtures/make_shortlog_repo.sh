@@ -0,0 +1,25 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+function commit() {
+  local name="$1" email="$2" time="$3" message="$4"
+  GIT_AUTHOR_NAME="$name" GIT_AUTHOR_EMAIL="$email" GIT_AUTHOR_DATE="$time +0000" GIT_COMMITTER_DATE="$time +0000" \
+    git commit -q --allow-empty -m "$message"
+}
+
+commit "A U Thor" author@example.com 1000000000 first
+git tag v1
+commit Other other@example.com 1000000001 "[PATCH] second"
+commit "a u thor" old@example.com 1000000002 "third
+continued
+
+body"
+commit Other other@example.com 1000000003 fourth
+commit Other other@example.com 1000000004 fifth
+
+cat >.mailmap <<MAILMAP
+A U Thor <author@example.com> a u thor <old@example.com>
+MAILMAP
@@ -0,0 +1,19 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function setup() {
+  git init -q
+  git checkout -q -b main
+
+  mkdir -p a/b/c d e
+  for file in root a/f a/b/f a/b/c/f d/f e/f e/f.txt; do
+    echo "$file" > "$file"
+  done
+  git add . && git commit -q -m "base"
+}
+
+(mkdir full && cd full && setup)
+
+(mkdir cone-by-git && cd cone-by-git && setup
+  git sparse-checkout set a/b d
+)
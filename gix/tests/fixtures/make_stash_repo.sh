@@ -0,0 +1,37 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function setup() {
+  git init -q
+  git checkout -q -b main
+
+  printf '1\n2\n3\n' > a
+  echo 1 > b
+  mkdir dir && echo c > dir/c
+  echo '*.ignored' > .gitignore
+  git add . && git commit -q -m "base"
+}
+
+(mkdir clean && cd clean && setup)
+
+(mkdir stashed && cd stashed && setup
+  echo first >> a
+  git stash push -q -m "first"
+  echo second >> b
+  echo untracked > untracked
+  git stash push -q -u
+)
+
+(mkdir conflicting && cd conflicting && setup
+  echo stashed >> b
+  git stash push -q
+  echo conflicting >> b
+  git commit -q -am "conflicting"
+)
+
+(mkdir renamed && cd renamed && setup
+  echo stashed >> dir/c
+  git stash push -q
+  git mv dir renamed
+  git commit -q -m "rename dir"
+)
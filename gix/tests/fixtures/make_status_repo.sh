@@ -0,0 +1,20 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+printf 'a\nb\nc\nd\ne\nf\n' > renamed-source
+echo unchanged > unchanged
+echo removed > removed
+echo modified > modified
+git add .
+git commit -q -m init
+
+git mv renamed-source renamed
+echo g >> renamed
+git rm -q removed
+echo changed >> modified
+cp unchanged copy
+echo added > added
+git add .
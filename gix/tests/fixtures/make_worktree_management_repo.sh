@@ -0,0 +1,13 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q main
+(cd main
+  git checkout -q -b main
+  mkdir dir
+  echo one > file && echo two > dir/file
+  git add . && git commit -q -m "first"
+  git branch other
+  echo three > dir/other
+  git add . && git commit -q -m "second"
+)
@@ -74,6 +74,32 @@ mod non_bare {
         );
         Ok(())
     }
+
+    #[test]
+    fn init_with_configured_filesystem_capabilities_overrides_probed_ones() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo: gix::Repository = gix::ThreadSafeRepository::init_opts(
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            gix::create::Options::default(),
+            gix::open::Options::isolated().config_overrides(["core.symlinks=false", "core.fileMode=false"]),
+        )?
+        .into();
+        let local =
+            gix::config::File::from_path_no_includes(repo.git_dir().join("config"), gix::config::Source::Local)?;
+        assert_eq!(local.boolean_by_key("core.symlinks"), Some(Ok(false)));
+        assert_eq!(local.boolean_by_key("core.filemode"), Some(Ok(false)));
+        assert_eq!(
+            local.boolean_by_key("core.ignorecase"),
+            Some(Ok(gix::create::fs_capabilities_cache()
+                .probe(repo.git_dir())
+                .ignore_case)),
+            "values that aren't configured are still probed"
+        );
+        let caps = repo.filesystem_options()?;
+        assert!(!caps.symlink && !caps.executable_bit);
+        Ok(())
+    }
     #[test]
     fn init_into_empty_directory_creates_a_dot_git_dir() -> crate::Result {
         let tmp = tempfile::tempdir()?;
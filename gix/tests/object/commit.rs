@@ -15,6 +15,20 @@ fn short_id() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn verify_signature_of_unsigned_commit() -> crate::Result {
+    let repo = basic_repo()?;
+    let commit = repo.head_commit()?;
+    assert!(commit.signature()?.is_none());
+    assert!(matches!(
+        commit.verify_signature(&|_: &gix::bstr::BStr, _: &[u8]| -> Result<(), gix::sign::Error> {
+            unreachable!("unsigned commits aren't passed to the verifier")
+        }),
+        Err(gix::sign::verify::Error::Unsigned)
+    ));
+    Ok(())
+}
+
 #[test]
 fn tree() -> crate::Result {
     let repo = basic_repo()?;
@@ -91,6 +91,44 @@ fn changes_against_tree_with_filename_tracking() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn changes_against_tree_with_pathspec() -> crate::Result {
+    let repo = named_repo("make_pathspec_attributes_repo.sh")?;
+    let from = repo.empty_tree();
+    let to = tree_named(&repo, "HEAD");
+    let index = repo.index()?;
+
+    for (spec, expected) in [
+        (":(attr:mybin)", vec!["a.bin", "b.dat", "sub/e.bin"]),
+        (
+            ":(attr:!text)",
+            vec![".gitattributes", "sub/.gitattributes", "sub/d.sub"],
+        ),
+        ("sub/*.bin", vec!["sub/e.bin"]),
+        (":!sub", vec![".gitattributes", "a.bin", "b.dat", "c.txt"]),
+    ] {
+        let pathspec = repo
+            .pathspec(
+                Some(spec),
+                false,
+                &index,
+                gix_worktree::stack::state::attributes::Source::IdMapping,
+            )?
+            .detach()?;
+        let mut actual = Vec::new();
+        from.changes()?
+            .pathspec(Some(pathspec))
+            .for_each_to_obtain_tree(&to, |change| -> Result<_, Infallible> {
+                if !change.event.entry_mode().is_tree() {
+                    actual.push(change.location.to_owned());
+                }
+                Ok(Default::default())
+            })?;
+        assert_eq!(actual, expected, "{spec}");
+    }
+    Ok(())
+}
+
 fn tree_named(repo: &gix::Repository, rev_spec: impl AsRef<str>) -> gix::Tree {
     repo.rev_parse_single(rev_spec.as_ref())
         .unwrap()
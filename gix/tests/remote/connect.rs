@@ -43,3 +43,59 @@ mod blocking_io {
         }
     }
 }
+
+#[cfg(feature = "blocking-network-client")]
+mod proxy {
+    use std::io::{Read, Write};
+
+    use gix::remote::Direction::Fetch;
+
+    use crate::remote;
+
+    fn repo_with_remote(proxy: &str) -> crate::Result<gix::Repository> {
+        let mut repo = remote::repo("base");
+        {
+            let mut config = repo.config_snapshot_mut();
+            config.set_raw_value("remote", Some("proxied".into()), "url", "git://example.com/repo")?;
+            config.set_raw_value("remote", Some("proxied".into()), "proxy", proxy)?;
+        }
+        Ok(repo)
+    }
+
+    #[test]
+    fn git_connections_are_tunneled_through_the_remote_proxy() -> crate::Result {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+        let server = std::thread::spawn(move || -> std::io::Result<Vec<u8>> {
+            let mut stream = listener.accept()?.0;
+            let mut greeting = [0; 3];
+            stream.read_exact(&mut greeting)?;
+            stream.write_all(&[5, 0])?;
+            let mut request = vec![0; 4 + 1 + "example.com".len() + 2];
+            stream.read_exact(&mut request)?;
+            stream.write_all(&[5, 0, 0, 1, 127, 0, 0, 1, 0, 0])?;
+            Ok(request)
+        });
+
+        let repo = repo_with_remote(&format!("socks5h://127.0.0.1:{port}"))?;
+        let remote = repo.find_remote("proxied")?;
+        drop(remote.connect(Fetch)?);
+        assert_eq!(
+            server.join().expect("no panic")?,
+            b"\x05\x01\x00\x03\x0bexample.com\x24\xca",
+            "the proxy is asked to connect to the host of the remote"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_proxies_are_configuration_errors() -> crate::Result {
+        let repo = repo_with_remote("ftp://proxy")?;
+        let remote = repo.find_remote("proxied")?;
+        assert!(matches!(
+            remote.connect(Fetch).err(),
+            Some(gix::remote::connect::Error::Proxy { key, .. }) if key == "remote.proxied.proxy"
+        ));
+        Ok(())
+    }
+}
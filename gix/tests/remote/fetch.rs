@@ -109,6 +109,38 @@ mod blocking_and_async_io {
         Ok(())
     }
 
+    #[test]
+    #[cfg(all(feature = "blocking-network-client", feature = "hooks", unix))]
+    fn fetch_runs_reference_transaction_hook_if_enabled() -> crate::Result {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_repo, tmp) = repo_rw("two-origins");
+        let repo = gix::open_opts(tmp.path().join("two-origins"), crate::restricted().run_hooks(true))?;
+        let hook = repo.hooks_dir()?.join("reference-transaction");
+        std::fs::create_dir_all(repo.hooks_dir()?)?;
+        std::fs::write(
+            &hook,
+            "#!/bin/sh\necho \"$1\" >> \"$GIT_DIR/transactions\"\ncat >> \"$GIT_DIR/transactions\"\n",
+        )?;
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755))?;
+
+        repo.find_remote("changes-on-top-of-origin")?
+            .with_fetch_tags(fetch::Tags::None)
+            .connect(Fetch)?
+            .prepare_fetch(progress::Discard, Default::default())?
+            .receive(progress::Discard, &AtomicBool::default())?;
+
+        let transactions = std::fs::read_to_string(repo.git_dir().join("transactions"))?;
+        let mut lines = transactions.lines();
+        assert_eq!(lines.next(), Some("committed"));
+        let updated_main = repo.find_reference("refs/remotes/changes-on-top-of-origin/main")?.id();
+        assert!(
+            lines.any(|line| line.ends_with(&format!(" {updated_main} refs/remotes/changes-on-top-of-origin/main"))),
+            "{transactions}"
+        );
+        Ok(())
+    }
+
     #[test]
     #[cfg(feature = "blocking-network-client")]
     fn fetch_with_alternates_adds_tips_from_alternates() -> crate::Result<()> {
@@ -225,6 +257,20 @@ mod blocking_and_async_io {
                             expected_negotiation_rounds,
                             "we need multiple rounds"
                         );
+                        let common_haves_repeated: Vec<_> =
+                            negotiate.rounds.iter().map(|r| r.common_haves_repeated).collect();
+                        let expected_common_haves_repeated: &[usize] = match (version, algorithm) {
+                            (gix::protocol::transport::Protocol::V2, gix::negotiate::Algorithm::Consecutive) => {
+                                &[0, 1, 2, 2]
+                            }
+                            (gix::protocol::transport::Protocol::V2, _) => &[0, 1],
+                            (_, gix::negotiate::Algorithm::Consecutive) => &[0, 0, 0, 0],
+                            _ => &[0, 0],
+                        };
+                        assert_eq!(
+                            common_haves_repeated, expected_common_haves_repeated,
+                            "only stateless transports repeat common commits, and each one only once even if acknowledged again"
+                        );
                         // the server only has our `b1` and an extra commit or two.
                         assert_eq!(
                             write_pack_bundle.index.num_objects, 7,
@@ -238,6 +284,54 @@ mod blocking_and_async_io {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_with_multi_round_negotiation_limits_the_size_of_stateless_requests() -> crate::Result {
+        let tmp = TempDir::new()?;
+        let mut client_repo = gix::prepare_clone_bare(remote::repo("multi_round/client").git_dir(), tmp.path())?
+            .fetch_only(gix::progress::Discard, &AtomicBool::default())?
+            .0;
+        client_repo
+            .config_snapshot_mut()
+            .set_value(&gix::config::tree::Protocol::VERSION, "2")?;
+
+        let server_repo = remote::repo("multi_round/server");
+        let max_haves = 20;
+        let changes = client_repo
+            .remote_at(server_repo.work_dir().expect("non-bare"))?
+            .with_refspecs(Some("refs/heads/*:refs/remotes/origin/*"), Fetch)?
+            .connect(Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .with_max_stateless_haves(max_haves)
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+
+        match changes.status {
+            Status::Change {
+                write_pack_bundle,
+                negotiate,
+                ..
+            } => {
+                assert!(
+                    negotiate.rounds.len() > 4,
+                    "it takes more rounds than without limit: {:?}",
+                    negotiate.rounds
+                );
+                for round in &negotiate.rounds {
+                    assert!(
+                        round.haves_sent + round.common_haves_repeated <= max_haves,
+                        "no request exceeds the limit: {round:?}"
+                    );
+                }
+                assert_eq!(
+                    write_pack_bundle.index.num_objects, 7,
+                    "the outcome is the same as without limit"
+                );
+            }
+            _ => unreachable!("We expect a pack for sure"),
+        }
+        Ok(())
+    }
+
     #[maybe_async::test(
         feature = "blocking-network-client",
         async(feature = "async-network-client-async-std", async_std::test)
@@ -333,6 +427,57 @@ mod blocking_and_async_io {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_shallow_deepen_and_unshallow() -> crate::Result {
+        let (_clone, tmp) = try_repo_rw_args("two-origins", ["--depth=2"], Mode::CloneWithShallowSupport)?;
+        let repo = gix::open_opts(tmp.path().join("base"), crate::restricted())?;
+        let fetch = |shallow: fetch::Shallow| -> crate::Result<fetch::Outcome> {
+            Ok(repo
+                .find_remote("origin")?
+                .with_refspecs(Some("+refs/heads/*:refs/heads/*"), Fetch)?
+                .connect(Fetch)?
+                .prepare_fetch(progress::Discard, Default::default())?
+                .with_shallow(shallow)
+                .receive(progress::Discard, &AtomicBool::default())?)
+        };
+
+        let previous_shallow_commits = repo.shallow_commits()?.expect("shallow clone").to_vec();
+        let missing_commit = hex_to_id("ffffffffffffffffffffffffffffffffffffffff");
+        let mut shallow_file = std::fs::read(repo.shallow_file())?;
+        shallow_file.extend_from_slice(format!("{missing_commit}\n").as_bytes());
+        std::fs::write(repo.shallow_file(), shallow_file)?;
+        let prev_commits = repo.head_id()?.ancestors().all()?.count();
+
+        fetch(fetch::Shallow::Deepen(1))?;
+        let shallow_commits = repo.shallow_commits()?.expect("still shallow").to_vec();
+        assert_ne!(
+            shallow_commits, previous_shallow_commits,
+            "the shallow boundary was moved further into the past"
+        );
+        assert!(
+            shallow_commits.iter().all(|id| repo.find_object(*id).is_ok()),
+            "shallow commits that don't exist are pruned"
+        );
+        assert!(
+            shallow_commits.windows(2).all(|ids| ids[0] < ids[1]),
+            "the shallow file is sorted and free of duplicates"
+        );
+        let deepened_commits = repo.head_id()?.ancestors().all()?.count();
+        assert!(deepened_commits > prev_commits, "more history is available");
+
+        fetch(fetch::Shallow::undo())?;
+        assert!(!repo.is_shallow(), "unshallowing removes the shallow file");
+        assert!(repo.shallow_commits()?.is_none());
+        let remote = gix::open_opts(base_repo_path(), crate::restricted())?;
+        assert_eq!(
+            repo.head_id()?.ancestors().all()?.count(),
+            remote.head_id()?.ancestors().all()?.count(),
+            "all history is available now"
+        );
+        Ok(())
+    }
+
     #[maybe_async::test(
         feature = "blocking-network-client",
         async(feature = "async-network-client-async-std", async_std::test)
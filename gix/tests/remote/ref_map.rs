@@ -89,4 +89,72 @@ mod blocking_and_async_io {
         }
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn cached_advertisements_are_used_until_they_expire() -> crate::Result {
+        use std::time::Duration;
+
+        let tmp = gix_testtools::scripted_fixture_writable("make_remote_repos.sh")?;
+        let repo = gix::open_opts(tmp.path().join("clone"), gix::open::Options::isolated())?;
+        let remote_path = tmp.path().join("base");
+        let ref_map = |ttl: Option<Duration>| -> crate::Result<gix::remote::fetch::RefMap> {
+            Ok(repo
+                .remote_at(remote_path.to_str().expect("valid UTF-8"))?
+                .with_refspecs(Some("+refs/heads/*:refs/remotes/origin/*"), Fetch)?
+                .connect(Fetch)?
+                .ref_map(
+                    progress::Discard,
+                    gix::remote::ref_map::Options {
+                        cache_ttl: ttl,
+                        ..Default::default()
+                    },
+                )?)
+        };
+
+        let hour = Some(Duration::from_secs(60 * 60));
+        let map = ref_map(hour)?;
+        assert_eq!(
+            map.remote_refs.len(),
+            17,
+            "the cache is filled by contacting the remote"
+        );
+        assert_eq!(
+            std::fs::read_dir(repo.git_dir().join("gitoxide").join("ref-advertisements"))?.count(),
+            1,
+            "there is one entry per remote"
+        );
+
+        let remote_repo = gix::open_opts(&remote_path, gix::open::Options::isolated())?;
+        std::fs::write(
+            remote_repo.git_dir().join("refs").join("heads").join("new"),
+            format!("{}\n", remote_repo.head_id()?),
+        )?;
+        let cached_map = ref_map(hour)?;
+        assert_eq!(
+            cached_map.remote_refs, map.remote_refs,
+            "the remote isn't asked for its refs anymore"
+        );
+        assert_eq!(cached_map.mappings.len(), map.mappings.len());
+        assert_eq!(
+            cached_map.handshake.server_protocol_version,
+            map.handshake.server_protocol_version
+        );
+        assert_eq!(
+            cached_map.handshake.capabilities.iter().count(),
+            map.handshake.capabilities.iter().count()
+        );
+
+        assert_eq!(
+            ref_map(Some(Duration::ZERO))?.remote_refs.len(),
+            18,
+            "expired entries are ignored, so the remote is asked again"
+        );
+        assert_eq!(
+            ref_map(None)?.remote_refs.len(),
+            18,
+            "without TTL, the cache isn't used"
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,203 @@
+use gix::{
+    apply::{Location, Mail, Options, Patch},
+    bstr::ByteSlice,
+};
+
+fn patch(dir: &gix_testtools::tempfile::TempDir, name: &str) -> crate::Result<Patch> {
+    Ok(Patch::from_bytes(&std::fs::read(dir.path().join(name))?)?)
+}
+
+fn read(repo: &gix::Repository, path: &str) -> Option<String> {
+    std::fs::read(repo.work_dir().expect("non-bare").join(path))
+        .ok()
+        .map(|data| data.to_str().expect("valid UTF-8").to_owned())
+}
+
+fn index_paths(repo: &gix::Repository) -> crate::Result<Vec<String>> {
+    let index = repo.open_index()?;
+    Ok(index
+        .entries()
+        .iter()
+        .map(|e| format!("{}:{}", e.path(&index), e.stage()))
+        .collect())
+}
+
+fn options(location: Location) -> Options {
+    Options {
+        location,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn apply_patch_to_worktree_leaves_index_untouched() -> crate::Result {
+    let (repo, tmp) = crate::named_subrepo_rw("make_apply_repo.sh", "repo")?;
+    let outcome = repo.apply_patch(&patch(&tmp, "change.patch")?, options(Location::Worktree))?;
+    assert!(outcome.conflicts.is_empty());
+
+    assert_eq!(
+        read(&repo, "a").expect("present"),
+        "1\n2\nthree\n4\n5\n6\n7\n8\nnine\n10\n"
+    );
+    assert_eq!(read(&repo, "b"), None, "renamed");
+    assert_eq!(read(&repo, "b-renamed").as_deref(), Some("b\n"));
+    assert_eq!(read(&repo, "c").as_deref(), Some("c\n"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(repo.work_dir().expect("non-bare").join("b-renamed"))?
+            .permissions()
+            .mode();
+        assert_ne!(mode & 0o111, 0, "the mode change is applied");
+    }
+    assert_eq!(index_paths(&repo)?, ["a:0", "b:0"]);
+    Ok(())
+}
+
+#[test]
+fn apply_patch_to_index_leaves_worktree_untouched() -> crate::Result {
+    let (repo, tmp) = crate::named_subrepo_rw("make_apply_repo.sh", "repo")?;
+    repo.apply_patch(&patch(&tmp, "change.patch")?, options(Location::Index))?;
+
+    assert_eq!(index_paths(&repo)?, ["a:0", "b-renamed:0", "c:0"]);
+    let index = repo.open_index()?;
+    let renamed = index.entry_by_path("b-renamed".into()).expect("present");
+    assert_eq!(renamed.mode, gix::index::entry::Mode::FILE_EXECUTABLE);
+    assert_eq!(read(&repo, "b").as_deref(), Some("b\n"));
+    assert_eq!(read(&repo, "c"), None);
+    assert!(read(&repo, "a").expect("present").contains("\n3\n"));
+    Ok(())
+}
+
+#[test]
+fn apply_patch_to_index_and_worktree() -> crate::Result {
+    let (repo, tmp) = crate::named_subrepo_rw("make_apply_repo.sh", "repo")?;
+    repo.apply_patch(&patch(&tmp, "change.patch")?, options(Location::IndexAndWorktree))?;
+
+    assert_eq!(index_paths(&repo)?, ["a:0", "b-renamed:0", "c:0"]);
+    assert_eq!(read(&repo, "b"), None);
+    assert_eq!(read(&repo, "c").as_deref(), Some("c\n"));
+    assert!(read(&repo, "a").expect("present").contains("\nthree\n"));
+    Ok(())
+}
+
+#[test]
+fn apply_patch_changes_nothing_if_one_file_fails() -> crate::Result {
+    let (repo, tmp) = crate::named_subrepo_rw("make_apply_repo.sh", "repo")?;
+    let change = patch(&tmp, "change.patch")?;
+    repo.apply_patch(&change, options(Location::Worktree))?;
+    let err = repo
+        .apply_patch(&change, options(Location::Worktree))
+        .expect_err("the changes are already applied");
+    assert!(matches!(err, gix::apply::Error::Apply { path, .. } if path == "a"));
+    assert!(read(&repo, "a").expect("present").contains("\nthree\n"), "unchanged");
+
+    let (repo, tmp) = crate::named_subrepo_rw("make_apply_repo.sh", "repo")?;
+    std::fs::write(repo.work_dir().expect("non-bare").join("c"), "untracked\n")?;
+    let err = repo
+        .apply_patch(&change, options(Location::Worktree))
+        .expect_err("the file to add exists");
+    assert!(matches!(err, gix::apply::Error::AlreadyExists { path } if path == "c"));
+    assert!(read(&repo, "a").expect("present").contains("\n3\n"), "unchanged");
+    assert_eq!(read(&repo, "b").as_deref(), Some("b\n"), "unchanged");
+
+    let err = repo
+        .apply_patch(&patch(&tmp, "conflict.patch")?, Default::default())
+        .expect_err("the hunk doesn't apply");
+    assert!(matches!(err, gix::apply::Error::Apply { path, .. } if path == "a"));
+    Ok(())
+}
+
+#[test]
+fn apply_patch_to_index_and_worktree_fails_for_changed_files() -> crate::Result {
+    let (repo, tmp) = crate::named_subrepo_rw("make_apply_repo.sh", "repo")?;
+    std::fs::write(repo.work_dir().expect("non-bare").join("b"), "changed\n")?;
+    let err = repo
+        .apply_patch(&patch(&tmp, "change.patch")?, options(Location::IndexAndWorktree))
+        .expect_err("changes in the worktree would be lost");
+    assert!(matches!(err, gix::apply::Error::Dirty { path } if path == "b"));
+    assert_eq!(index_paths(&repo)?, ["a:0", "b:0"]);
+    Ok(())
+}
+
+#[test]
+fn apply_patch_with_three_way_fallback() -> crate::Result {
+    let (repo, tmp) = crate::named_subrepo_rw("make_apply_repo.sh", "repo")?;
+    let three_way = Options {
+        three_way: true,
+        ..Default::default()
+    };
+    let outcome = repo.apply_patch(&patch(&tmp, "three-way.patch")?, three_way)?;
+    assert!(outcome.conflicts.is_empty(), "the changes are merged cleanly");
+    assert_eq!(
+        read(&repo, "a").expect("present"),
+        "1\n2\n3\n4\n5\n6\nseven\n8\nnine\n10\n"
+    );
+    assert_eq!(index_paths(&repo)?, ["a:0", "b:0"]);
+
+    let (repo, tmp) = crate::named_subrepo_rw("make_apply_repo.sh", "repo")?;
+    let outcome = repo.apply_patch(&patch(&tmp, "conflict.patch")?, three_way)?;
+    assert_eq!(outcome.conflicts.len(), 1);
+    assert_eq!(outcome.conflicts[0].path, "a");
+    assert_eq!(index_paths(&repo)?, ["a:1", "a:2", "a:3", "b:0"]);
+    let a = read(&repo, "a").expect("present");
+    assert!(a.contains("<<<<<<< ours\nnine\n=======\nneun\n>>>>>>> theirs\n"), "{a}");
+    Ok(())
+}
+
+#[test]
+fn apply_patch_to_tree() -> crate::Result {
+    let (repo, tmp) = crate::named_subrepo_rw("make_apply_repo.sh", "repo")?;
+    let head_tree = repo.head_tree_id()?.detach();
+    let tree = repo.apply_patch_to_tree(head_tree, &patch(&tmp, "change.patch")?, Default::default())?;
+    let tree = repo.find_object(tree)?.into_tree();
+    let names: Vec<_> = tree.decode()?.entries.iter().map(|e| e.filename.to_string()).collect();
+    assert_eq!(names, ["a", "b-renamed", "c"]);
+    assert_eq!(index_paths(&repo)?, ["a:0", "b:0"], "the index isn't touched");
+
+    let err = repo
+        .apply_patch_to_tree(
+            head_tree,
+            &patch(&tmp, "conflict.patch")?,
+            Options {
+                three_way: true,
+                ..Default::default()
+            },
+        )
+        .expect_err("conflicts can't be represented in trees");
+    assert!(matches!(err, gix::apply::Error::Conflicts { paths } if paths == ["a"]));
+    Ok(())
+}
+
+#[test]
+fn apply_options_from_configuration() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_apply_repo.sh", "repo")?;
+    let options = repo.apply_options()?;
+    assert_eq!(options.blob.whitespace, gix::apply::blob::Whitespace::Fix);
+    assert!(options.blob.ignore_whitespace_changes);
+    assert_eq!(options.location, Location::Worktree);
+    Ok(())
+}
+
+#[test]
+fn am_commits_the_patch_of_a_mail() -> crate::Result {
+    let (repo, tmp) = crate::named_subrepo_rw("make_apply_repo.sh", "repo")?;
+    let head = repo.head_id()?.detach();
+    let mail = Mail::from_bytes(&std::fs::read(tmp.path().join("mail"))?)?;
+    let outcome = repo.am(&mail, Default::default())?;
+    assert!(outcome.conflicts.is_empty());
+
+    let new_id = outcome.commit.expect("committed");
+    assert_eq!(repo.head_id()?, new_id);
+    let commit = repo.find_object(new_id)?.into_commit();
+    let original = repo.rev_parse_single("feature")?.object()?.into_commit();
+    assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [head]);
+    assert_eq!(commit.tree_id()?, original.tree_id()?);
+    assert_eq!(commit.message_raw()?, original.message_raw()?);
+    assert_eq!(commit.author()?, original.author()?);
+    assert_eq!(read(&repo, "d").as_deref(), Some("d\n"));
+
+    let err = repo.am(&mail, Default::default()).expect_err("it doesn't apply twice");
+    assert!(matches!(err, gix::apply::am::Error::Apply(_)));
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use gix::{
+    bstr::ByteSlice,
+    error::ErrorCode,
+    object::tree::diff::{change::Event, Action},
+};
+
+fn bare_repo() -> crate::Result<(gix_testtools::tempfile::TempDir, gix::Repository)> {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let repo = gix::ThreadSafeRepository::init_opts(
+        tmp.path(),
+        gix::create::Kind::Bare,
+        Default::default(),
+        crate::restricted(),
+    )?
+    .to_thread_local();
+    Ok((tmp, repo))
+}
+
+fn add_blob(repo: &gix::Repository, index: &mut gix_index::State, path: &str, data: &str) -> crate::Result {
+    let id = repo.write_blob(data)?.detach();
+    index.dangerously_push_entry(
+        Default::default(),
+        id,
+        gix_index::entry::Flags::empty(),
+        gix_index::entry::Mode::FILE,
+        path.into(),
+    );
+    index.sort_entries();
+    Ok(())
+}
+
+#[test]
+fn commits_attributes_and_diffs_without_worktree() -> crate::Result {
+    let (_tmp, repo) = bare_repo()?;
+    let _env = crate::util::freeze_time();
+
+    let mut index = repo.index_from_head()?;
+    assert_eq!(index.entries().len(), 0, "an unborn HEAD yields an empty index");
+    assert!(repo.head_tree().is_err(), "there is no tree yet");
+
+    add_blob(&repo, &mut index, ".gitattributes", "*.dat binary\n")?;
+    add_blob(&repo, &mut index, "dir/a.dat", "a")?;
+    let first = repo.commit_index("HEAD", "first", &index, gix::commit::NO_PARENT_IDS)?;
+    assert_eq!(repo.head_id()?, first);
+
+    let index = repo.index_from_head()?;
+    assert_eq!(
+        index
+            .entries()
+            .iter()
+            .map(|e| e.path(&index).to_str_lossy().into_owned())
+            .collect::<Vec<_>>(),
+        [".gitattributes", "dir/a.dat"],
+        "the index is created from HEAD^{{tree}}"
+    );
+    assert_eq!(repo.write_tree_from_index(&index)?, repo.head_tree()?.id);
+
+    let mut attributes = repo.attributes_from_head()?;
+    let mut outcome = attributes.selected_attribute_matches(["diff"]);
+    attributes
+        .at_entry("dir/a.dat", Some(false))?
+        .matching_attributes(&mut outcome);
+    assert_eq!(
+        outcome.iter_selected().next().map(|m| m.assignment.state),
+        Some(gix_attributes::StateRef::Unset),
+        "attributes are read from HEAD:.gitattributes"
+    );
+
+    let mut index = index;
+    add_blob(&repo, &mut index, "b", "b")?;
+    let second = repo.commit_index("refs/heads/other", "second", &index, [first])?;
+
+    let mut changes = Vec::new();
+    repo.diff_head_to_tree(second, |change| {
+        changes.push(match change.event {
+            Event::Addition { id, .. } => id.detach(),
+            _ => unreachable!("only an addition is expected"),
+        });
+        Ok::<_, std::convert::Infallible>(Action::Continue)
+    })?;
+    assert_eq!(
+        changes,
+        [repo.rev_parse_single("other:b")?.detach()],
+        "commits are peeled to their tree"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "dirwalk")]
+fn operations_needing_a_worktree_fail_with_a_typed_error() -> crate::Result {
+    let (_tmp, repo) = bare_repo()?;
+    let err = repo
+        .dirwalk(
+            &repo.index_from_head()?,
+            None::<&str>,
+            repo.dirwalk_options()?,
+            &mut gix::dir::walk::delegate::Collect::default(),
+        )
+        .unwrap_err();
+    assert_eq!(err.code(), gix::error::Code::WorktreeRequired);
+    Ok(())
+}
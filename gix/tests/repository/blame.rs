@@ -0,0 +1,72 @@
+use std::ops::Range;
+
+use gix::bstr::ByteSlice;
+
+/// Put a single `range` into a `Vec`, which `clippy` would otherwise suspect to be meant as `(start..end).collect()`.
+fn lines(range: Range<u32>) -> Vec<Range<u32>> {
+    vec![range]
+}
+
+#[test]
+fn blame_file_attributes_lines_to_the_commits_that_introduced_them() -> crate::Result {
+    let repo = crate::named_subrepo_opts("make_rebase_repo.sh", "feature", crate::restricted())?;
+    let outcome = repo.blame_file("a".into(), repo.head_id()?, Default::default())?;
+
+    let actual: Vec<_> = outcome
+        .entries_with_lines()
+        .map(|(entry, lines)| -> crate::Result<_> {
+            let commit = repo.find_object(entry.commit_id)?.try_into_commit()?;
+            Ok((
+                commit.message()?.summary().to_string(),
+                lines.iter().flat_map(|line| line.iter().copied()).collect::<Vec<u8>>(),
+            ))
+        })
+        .collect::<Result<_, _>>()?;
+    assert_eq!(
+        actual,
+        [
+            ("base".to_string(), b"1\n2\n3\n".to_vec()),
+            ("feature applies the change to a of main".into(), b"main\n".to_vec())
+        ]
+    );
+    assert_eq!(outcome.blob.as_bstr(), "1\n2\n3\nmain\n");
+    Ok(())
+}
+
+#[test]
+fn line_history_lists_the_commits_that_changed_the_lines() -> crate::Result {
+    let repo = crate::named_subrepo_opts("make_rebase_repo.sh", "feature", crate::restricted())?;
+    let outcome = repo.line_history("a".into(), &lines(2..4), repo.head_id()?, Default::default())?;
+
+    let actual: Vec<_> = outcome
+        .entries
+        .iter()
+        .map(|entry| -> crate::Result<_> {
+            let commit = repo.find_object(entry.commit_id)?.try_into_commit()?;
+            Ok((commit.message()?.summary().to_string(), entry.ranges.clone()))
+        })
+        .collect::<Result<_, _>>()?;
+    assert_eq!(
+        actual,
+        [
+            ("feature applies the change to a of main".to_string(), lines(2..4)),
+            ("base".into(), lines(2..3))
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn blame_options_use_the_configured_diff_algorithm() -> crate::Result {
+    let mut repo = crate::named_subrepo_opts("make_rebase_repo.sh", "feature", crate::restricted())?;
+    assert_eq!(repo.blame_options()?.algorithm, gix::diff::blob::Algorithm::Myers);
+
+    repo.config_snapshot_mut()
+        .set_raw_value("diff", None, "algorithm", "patience")?;
+    let options = repo.blame_options()?;
+    assert_eq!(options.algorithm, gix::diff::blob::Algorithm::Patience);
+
+    let outcome = repo.blame_file("a".into(), repo.head_id()?, options)?;
+    assert_eq!(outcome.entries.len(), 2, "the result is the same with all algorithms");
+    Ok(())
+}
@@ -0,0 +1,291 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::bundle::{create, unbundle};
+
+fn fixture() -> crate::Result<std::path::PathBuf> {
+    gix_testtools::scripted_fixture_read_only("make_bundle_repo.sh")
+}
+
+fn open(path: impl Into<std::path::PathBuf>) -> crate::Result<gix::Repository> {
+    Ok(gix::ThreadSafeRepository::open_opts(path, crate::restricted())?.to_thread_local())
+}
+
+fn refspec(spec: &str) -> gix_refspec::RefSpec {
+    gix_refspec::parse(spec.into(), gix_refspec::parse::Operation::Fetch)
+        .expect("valid")
+        .to_owned()
+}
+
+fn unbundle_options(specs: &[&str]) -> unbundle::Options {
+    unbundle::Options {
+        refspecs: specs.iter().copied().map(refspec).collect(),
+        reflog_message: None,
+    }
+}
+
+fn id_of(repo: &gix::Repository, name: &str) -> crate::Result<gix::ObjectId> {
+    Ok(repo.find_reference(name)?.target().try_id().expect("peeled").to_owned())
+}
+
+#[test]
+fn create_and_unbundle_roundtrip() -> crate::Result {
+    let source = open(fixture()?.join("repo"))?;
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let bundle_path = tmp.path().join("all.bundle");
+    let outcome = source.create_bundle(
+        &bundle_path,
+        ["HEAD", "main", "v1"],
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        create::Options::default(),
+    )?;
+    assert_eq!(
+        outcome.num_objects, 10,
+        "three commits and trees, three blobs and a tag"
+    );
+    let names: Vec<_> = outcome.header.refs.iter().map(|r| r.name.to_string()).collect();
+    assert_eq!(names, ["HEAD", "refs/heads/main", "refs/tags/v1"]);
+    assert!(gix::bundle::is_bundle(&std::fs::read(&bundle_path)?));
+
+    let repo = gix::init_bare(tmp.path().join("target"))?;
+    let outcome = repo.unbundle(
+        &bundle_path,
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        unbundle_options(&["+refs/heads/*:refs/remotes/origin/*", "refs/tags/*:refs/tags/*"]),
+    )?;
+    assert_eq!(outcome.pack.index.num_objects, 10);
+    let modes: Vec<_> = outcome.updates.iter().map(|u| (u.name.to_string(), u.mode)).collect();
+    assert_eq!(
+        modes,
+        [
+            ("refs/remotes/origin/main".to_string(), unbundle::Mode::New),
+            ("refs/tags/v1".to_string(), unbundle::Mode::New)
+        ],
+        "HEAD doesn't match any refspec"
+    );
+    assert_eq!(id_of(&repo, "refs/remotes/origin/main")?, id_of(&source, "main")?);
+    assert_eq!(
+        id_of(&repo, "refs/tags/v1")?,
+        id_of(&source, "v1")?,
+        "tags aren't peeled"
+    );
+    assert!(
+        repo.fsck(&mut gix::progress::Discard, &AtomicBool::default(), Default::default())?
+            .is_intact(),
+        "all objects are present"
+    );
+    Ok(())
+}
+
+#[test]
+fn incremental_bundles_require_their_prerequisites() -> crate::Result {
+    let dir = fixture()?;
+    let source = open(dir.join("repo"))?;
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let bundle_path = tmp.path().join("incremental.bundle");
+    let parent = source.rev_parse_single("main~1")?.detach();
+    let outcome = source.create_bundle(
+        &bundle_path,
+        ["main"],
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        create::Options {
+            version: gix::bundle::Version::V3,
+            prerequisites: vec![parent],
+        },
+    )?;
+    assert_eq!(
+        outcome.num_objects, 3,
+        "only the last commit, its tree and its new blob"
+    );
+    assert_eq!(outcome.header.prerequisites[0].comment.as_ref().expect("set"), "second");
+
+    let empty = gix::init_bare(tmp.path().join("empty"))?;
+    let err = empty
+        .unbundle(
+            &bundle_path,
+            &mut gix::progress::Discard,
+            &AtomicBool::default(),
+            unbundle_options(&[]),
+        )
+        .unwrap_err();
+    assert!(matches!(err, unbundle::Error::MissingPrerequisite { id } if id == parent));
+
+    let writable = gix_testtools::scripted_fixture_writable("make_bundle_repo.sh")?;
+    let older = open(writable.path().join("older"))?;
+    let outcome = older.unbundle(
+        &bundle_path,
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        unbundle_options(&["refs/heads/main:refs/remotes/origin/main"]),
+    )?;
+    assert_eq!(outcome.updates.len(), 1);
+    assert_eq!(outcome.updates[0].mode, unbundle::Mode::FastForward);
+    assert_eq!(outcome.updates[0].previous, Some(parent));
+    assert_eq!(id_of(&older, "refs/remotes/origin/main")?, id_of(&source, "main")?);
+    Ok(())
+}
+
+#[test]
+fn non_fast_forward_updates_need_force() -> crate::Result {
+    let dir = fixture()?;
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let repo = gix::init_bare(tmp.path().join("target"))?;
+    repo.unbundle(
+        dir.join("all.bundle"),
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        unbundle_options(&["refs/heads/main:refs/heads/main"]),
+    )?;
+    let main = id_of(&repo, "main")?;
+    let tag = id_of(&repo, "refs/tags/v1").ok();
+    assert_eq!(tag, None, "only what matches the refspecs is written");
+
+    let outcome = repo.unbundle(
+        dir.join("all.bundle"),
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        unbundle_options(&["refs/tags/v1:refs/heads/main"]),
+    )?;
+    assert_eq!(outcome.updates[0].mode, unbundle::Mode::RejectedNonFastForward);
+    assert!(outcome.updates[0].mode.is_rejected());
+    assert_eq!(id_of(&repo, "main")?, main, "the reference wasn't changed");
+
+    let outcome = repo.unbundle(
+        dir.join("all.bundle"),
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        unbundle_options(&["+refs/tags/v1:refs/heads/main"]),
+    )?;
+    assert_eq!(outcome.updates[0].mode, unbundle::Mode::Forced);
+    assert_ne!(id_of(&repo, "main")?, main);
+    Ok(())
+}
+
+#[cfg(all(feature = "blocking-network-client", feature = "worktree-mutation"))]
+#[test]
+fn clone_from_bundle() -> crate::Result {
+    let dir = fixture()?;
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let (mut checkout, outcome) = gix::prepare_clone(dir.join("all.bundle"), tmp.path().join("clone"))?
+        .unbundle_then_checkout(&mut gix::progress::Discard, &AtomicBool::default())?;
+    let (repo, _) = checkout.main_worktree(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(outcome.updates.len(), 2, "main and the tag");
+
+    assert_eq!(
+        repo.head_name()?.expect("not detached").as_bstr(),
+        "refs/heads/main",
+        "HEAD points to the branch the bundle's HEAD points to"
+    );
+    let main = id_of(&repo, "refs/remotes/origin/main")?;
+    assert_eq!(repo.head_id()?, main);
+    assert!(repo.find_reference("refs/tags/v1").is_ok());
+    assert_eq!(
+        repo.find_remote("origin")?
+            .url(gix::remote::Direction::Fetch)
+            .expect("set")
+            .path,
+        gix::path::into_bstr(std::env::current_dir()?.join(dir.join("all.bundle"))).as_ref(),
+        "the remote points to the absolute path of the bundle"
+    );
+    assert!(repo.work_dir().expect("non-bare").join("c").is_file());
+    Ok(())
+}
+
+mod unbundle_list {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::{
+        bstr::BStr,
+        bundle::list::{self, List},
+    };
+
+    use super::{fixture, id_of, open, refspec};
+
+    fn unbundle_list(
+        lines: &[&str],
+    ) -> crate::Result<(gix::Repository, list::Outcome, gix_testtools::tempfile::TempDir)> {
+        let fixture = fixture()?;
+        let base_url = gix::url::parse(gix::path::into_bstr(fixture.as_path()).as_ref())?;
+        let list = List::from_lines(lines.iter().map(BStr::new))?;
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let repo = gix::init_bare(tmp.path())?;
+        let outcome = repo.unbundle_list(
+            &list,
+            &base_url,
+            &mut gix::progress::Discard,
+            &AtomicBool::default(),
+            list::Options {
+                refspecs: vec![refspec(list::REFSPEC)],
+                filter: None,
+            },
+        )?;
+        Ok((repo, outcome, tmp))
+    }
+
+    fn ids<'a>(bundles: impl IntoIterator<Item = &'a list::Bundle>) -> Vec<String> {
+        bundles.into_iter().map(|b| b.id.to_string()).collect()
+    }
+
+    #[test]
+    fn bundles_are_applied_in_order_of_creation() -> crate::Result {
+        let (repo, outcome, _tmp) = unbundle_list(&[
+            "bundle.version=1",
+            "bundle.mode=all",
+            "bundle.heuristic=creationToken",
+            "bundle.incremental.uri=incremental.bundle",
+            "bundle.incremental.creationToken=2",
+            "bundle.base.uri=all.bundle",
+            "bundle.base.creationToken=1",
+        ])?;
+        assert_eq!(ids(outcome.unbundled.iter().map(|(b, _)| b)), ["base", "incremental"]);
+        assert!(outcome.skipped.is_empty());
+        assert_eq!(
+            id_of(&repo, "refs/bundles/main")?,
+            id_of(&open(fixture()?.join("repo"))?, "main")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bundles_with_missing_prerequisites_are_retried_and_skipped() -> crate::Result {
+        let (_repo, outcome, _tmp) = unbundle_list(&[
+            "bundle.version=1",
+            "bundle.mode=all",
+            "bundle.incremental.uri=incremental.bundle",
+            "bundle.base.uri=all.bundle",
+        ])?;
+        assert_eq!(
+            ids(outcome.unbundled.iter().map(|(b, _)| b)),
+            ["base", "incremental"],
+            "the incremental bundle is retried once its prerequisites are present"
+        );
+
+        let (repo, outcome, _tmp) = unbundle_list(&[
+            "bundle.version=1",
+            "bundle.mode=all",
+            "bundle.incremental.uri=incremental.bundle",
+        ])?;
+        assert!(outcome.unbundled.is_empty());
+        assert_eq!(ids(&outcome.skipped), ["incremental"]);
+        assert!(repo.try_find_reference("refs/bundles/main")?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn any_mode_uses_the_first_bundle_that_can_be_applied() -> crate::Result {
+        let (repo, outcome, _tmp) = unbundle_list(&[
+            "bundle.version=1",
+            "bundle.mode=any",
+            "bundle.ssh.uri=ssh://example.com/all.bundle",
+            "bundle.missing.uri=missing.bundle",
+            "bundle.local.uri=all.bundle",
+            "bundle.other.uri=all.bundle",
+        ])?;
+        assert_eq!(ids(outcome.unbundled.iter().map(|(b, _)| b)), ["local"]);
+        assert_eq!(ids(&outcome.skipped), ["ssh", "missing", "other"]);
+        assert!(repo.try_find_reference("refs/bundles/main")?.is_some());
+        Ok(())
+    }
+}
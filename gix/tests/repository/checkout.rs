@@ -0,0 +1,201 @@
+use std::process::Command;
+
+use gix::{
+    checkout::{Error, Options, RestoreOptions},
+    error::ErrorCode,
+};
+
+fn git(repo: &gix::Repository, args: &[&str]) -> crate::Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo.work_dir().expect("non-bare"))
+        .env_remove("GIT_DIR")
+        .output()?;
+    assert!(
+        output.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+fn status(repo: &gix::Repository) -> crate::Result<String> {
+    git(repo, &["status", "--porcelain"])
+}
+
+fn read(repo: &gix::Repository, path: &str) -> std::io::Result<String> {
+    std::fs::read_to_string(repo.work_dir().expect("non-bare").join(path))
+}
+
+fn write(repo: &gix::Repository, path: &str, content: &str) -> std::io::Result<()> {
+    std::fs::write(repo.work_dir().expect("non-bare").join(path), content)
+}
+
+#[test]
+fn switch_carries_local_changes_over() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_checkout_repo.sh")?;
+    write(&repo, "a", "changed")?;
+    repo.switch("other", Options::default())?;
+
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/other");
+    assert_eq!(read(&repo, "b")?, "b on other\n");
+    assert_eq!(read(&repo, "d")?, "d\n");
+    assert!(!repo.work_dir().expect("non-bare").join("dir").exists());
+    assert_eq!(read(&repo, "a")?, "changed", "local changes are kept");
+    assert_eq!(status(&repo)?, " M a\n");
+
+    let head = repo.find_reference("HEAD")?;
+    let mut log = head.log_iter();
+    let last = log.rev()?.expect("reflog exists").next().expect("one entry")?;
+    assert_eq!(last.message, "checkout: moving from main to other");
+    assert_eq!(last.previous_oid, repo.rev_parse_single("main")?);
+    assert_eq!(last.new_oid, repo.rev_parse_single("other")?);
+    Ok(())
+}
+
+#[test]
+fn switch_refuses_to_overwrite_local_changes_unless_forced() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_checkout_repo.sh")?;
+    write(&repo, "b", "changed")?;
+    write(&repo, "d", "untracked")?;
+
+    let err = repo.switch("other", Options::default()).unwrap_err();
+    assert!(
+        matches!(&err, Error::LocalChanges { paths } if paths == &["b"]),
+        "{err:?}"
+    );
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/main");
+    assert_eq!(read(&repo, "b")?, "changed", "nothing was changed");
+
+    write(&repo, "b", "b\n")?;
+    let err = repo.switch("other", Options::default()).unwrap_err();
+    assert!(
+        matches!(&err, Error::UntrackedFiles { paths } if paths == &["d"]),
+        "{err:?}"
+    );
+
+    write(&repo, "b", "changed")?;
+    repo.switch("other", Options { force: true })?;
+    assert_eq!(read(&repo, "b")?, "b on other\n");
+    assert_eq!(read(&repo, "d")?, "d\n");
+    assert_eq!(status(&repo)?, "");
+    Ok(())
+}
+
+#[test]
+fn switch_refuses_to_overwrite_untracked_files_in_directories() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_checkout_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare");
+    std::fs::create_dir_all(workdir.join("d/nested"))?;
+    write(&repo, "d/nested/untracked", "content")?;
+
+    let err = repo.switch("other", Options::default()).unwrap_err();
+    assert!(
+        matches!(&err, Error::UntrackedFiles { paths } if paths == &["d/nested/untracked"]),
+        "{err:?}"
+    );
+    assert_eq!(err.code(), gix::error::Code::WouldOverwriteLocalChanges);
+    assert_eq!(read(&repo, "d/nested/untracked")?, "content", "nothing was changed");
+
+    std::fs::remove_dir_all(workdir.join("d/nested"))?;
+    repo.switch("other", Options::default())?;
+    assert_eq!(read(&repo, "d")?, "d\n", "empty directories are not in the way");
+
+    write(&repo, "dir", "untracked file in place of a directory")?;
+    let err = repo.switch("main", Options::default()).unwrap_err();
+    assert!(
+        matches!(&err, Error::UntrackedFiles { paths } if paths == &["dir"]),
+        "{err:?}"
+    );
+    repo.switch("main", Options { force: true })?;
+    assert_eq!(read(&repo, "dir/c")?, "c\n");
+    Ok(())
+}
+
+#[test]
+fn switch_only_accepts_local_branches() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_checkout_repo.sh")?;
+    let err = repo.switch("tagged", Options::default()).unwrap_err();
+    assert!(matches!(err, Error::NotABranch { .. }), "{err:?}");
+    Ok(())
+}
+
+#[test]
+fn checkout_tree_keeps_head() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_checkout_repo.sh")?;
+    let other = repo.rev_parse_single("other")?.detach();
+    repo.checkout_tree(other, Options::default())?;
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/main");
+    assert_eq!(read(&repo, "d")?, "d\n");
+    assert_eq!(status(&repo)?, "M  b\nA  d\nD  dir/c\n", "the index matches the tree");
+    Ok(())
+}
+
+#[test]
+fn checkout_detached_points_head_to_the_commit() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_checkout_repo.sh")?;
+    let other = repo.rev_parse_single("other")?.detach();
+    repo.checkout_detached(other, Options::default())?;
+
+    let head = repo.head()?;
+    assert!(head.is_detached());
+    assert_eq!(head.id().expect("born"), other);
+    assert_eq!(read(&repo, "d")?, "d\n");
+    assert_eq!(status(&repo)?, "");
+
+    let head = repo.find_reference("HEAD")?;
+    let mut log = head.log_iter();
+    let last = log.rev()?.expect("reflog exists").next().expect("one entry")?;
+    assert_eq!(last.message, format!("checkout: moving from main to {other}"));
+    Ok(())
+}
+
+#[test]
+fn restore_from_index_head_and_other_sources() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_checkout_repo.sh")?;
+    write(&repo, "a", "changed")?;
+    write(&repo, "b", "staged")?;
+    git(&repo, &["add", "b"])?;
+    assert_eq!(status(&repo)?, " M a\nM  b\n");
+
+    repo.restore(["a"], RestoreOptions::default())?;
+    assert_eq!(read(&repo, "a")?, "a\n");
+    assert_eq!(status(&repo)?, "M  b\n");
+
+    repo.restore(
+        ["b"],
+        RestoreOptions {
+            staged: true,
+            worktree: false,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(status(&repo)?, " M b\n", "only the index was restored");
+
+    repo.restore(
+        ["b", "dir"],
+        RestoreOptions {
+            source: Some(repo.rev_parse_single("other")?.detach()),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(read(&repo, "b")?, "b on other\n");
+    assert_eq!(
+        status(&repo)?,
+        " M b\n D dir/c\n",
+        "files not in the source are removed"
+    );
+
+    repo.restore(
+        ["*"],
+        RestoreOptions {
+            staged: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(status(&repo)?, "");
+
+    let err = repo.restore(["missing"], RestoreOptions::default()).unwrap_err();
+    assert!(matches!(err, Error::NoMatch), "{err:?}");
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use gix::bstr::ByteSlice;
+
+fn read(repo: &gix::Repository, path: &str) -> crate::Result<String> {
+    Ok(std::fs::read(repo.work_dir().expect("non-bare").join(path))?
+        .to_str()?
+        .to_owned())
+}
+
+#[test]
+fn cherry_pick_commits_changes_on_top_of_head() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_rebase_repo.sh", "conflicting")?;
+    let orig_head = repo.head_id()?.detach();
+    let picked = repo.rev_parse_single("feature")?.detach();
+
+    let outcome = repo.cherry_pick(picked)?;
+    assert!(outcome.conflicts.is_empty());
+    let new_id = outcome.commit.expect("committed");
+    assert_eq!(repo.head_id()?, new_id);
+    assert_eq!(
+        repo.head_name()?.expect("still on branch").as_bstr(),
+        "refs/heads/conflicting",
+        "the branch was moved"
+    );
+
+    let commit = repo.find_object(new_id)?.into_commit();
+    let original = repo.find_object(picked)?.into_commit();
+    assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [orig_head]);
+    assert_eq!(commit.message_raw()?, original.message_raw()?);
+    assert_eq!(commit.author()?, original.author()?);
+    assert_eq!(read(&repo, "c")?, "c\n");
+    assert_eq!(repo.index()?.entries().len(), 3);
+    assert_eq!(repo.state(), None);
+    Ok(())
+}
+
+#[test]
+fn cherry_pick_with_conflicts_leaves_state_for_git() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_rebase_repo.sh", "conflicting")?;
+    let orig_head = repo.head_id()?.detach();
+    let picked = repo.rev_parse_single("main")?.detach();
+
+    let outcome = repo.cherry_pick(picked)?;
+    assert_eq!(outcome.commit, None);
+    assert_eq!(outcome.conflicts.len(), 1);
+    assert_eq!(outcome.conflicts[0].path, "a");
+    assert_eq!(repo.head_id()?, orig_head, "nothing was committed");
+    assert_eq!(repo.state(), Some(gix::state::InProgress::CherryPick));
+    assert_eq!(
+        std::fs::read_to_string(repo.path().join("CHERRY_PICK_HEAD"))?,
+        format!("{picked}\n")
+    );
+    assert!(std::fs::read_to_string(repo.path().join("MERGE_MSG"))?.starts_with("main changes a\n"));
+    assert!(read(&repo, "a")?.contains("<<<<<<< HEAD\n"));
+    assert!(
+        repo.index()?.entries().iter().any(|e| e.stage() != 0),
+        "the conflict is recorded in the index"
+    );
+
+    assert!(
+        matches!(
+            repo.cherry_pick(picked),
+            Err(gix::merge::commit::Error::InProgress { .. })
+        ),
+        "another operation can't be started"
+    );
+    Ok(())
+}
+
+#[test]
+fn cherry_pick_of_changes_that_are_present_fails() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_rebase_repo.sh", "feature")?;
+    let picked = repo.rev_parse_single("main")?.detach();
+    assert!(matches!(
+        repo.cherry_pick(picked),
+        Err(gix::merge::commit::Error::Empty { id }) if id == picked
+    ));
+    assert_eq!(repo.state(), None);
+    Ok(())
+}
+
+#[test]
+fn revert_undoes_changes_of_a_commit() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_rebase_repo.sh", "conflicting")?;
+    let orig_head = repo.head_id()?.detach();
+    let reverted = repo.rev_parse_single("HEAD~1")?.detach();
+
+    let outcome = repo.revert(reverted)?;
+    let new_id = outcome.commit.expect("no conflicts");
+    let commit = repo.find_object(new_id)?.into_commit();
+    assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [orig_head]);
+    assert_eq!(
+        commit.message_raw()?,
+        format!("Revert \"conflicting changes a\"\n\nThis reverts commit {reverted}.\n")
+    );
+    assert_eq!(read(&repo, "a")?, "1\n2\n3\n");
+    assert_eq!(read(&repo, "b")?, "2\n", "later changes are kept");
+    assert_eq!(repo.state(), None);
+    Ok(())
+}
+
+#[test]
+fn dirty_worktree_is_rejected() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_rebase_repo.sh", "conflicting")?;
+    std::fs::write(repo.work_dir().expect("non-bare").join("b"), "dirty\n")?;
+    let reverted = repo.head_id()?.detach();
+    assert!(matches!(
+        repo.revert(reverted),
+        Err(gix::merge::commit::Error::RequireClean(_))
+    ));
+    Ok(())
+}
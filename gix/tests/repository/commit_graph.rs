@@ -0,0 +1,142 @@
+use std::{process::Command, sync::atomic::AtomicBool};
+
+use gix::commit_graph::Options;
+
+fn git(repo: &gix::Repository, args: &[&str]) -> crate::Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo.work_dir().expect("non-bare"))
+        .env_remove("GIT_DIR")
+        .envs([
+            ("GIT_AUTHOR_NAME", "author"),
+            ("GIT_AUTHOR_EMAIL", "author@example.com"),
+            ("GIT_COMMITTER_NAME", "committer"),
+            ("GIT_COMMITTER_EMAIL", "committer@example.com"),
+        ])
+        .output()?;
+    assert!(
+        output.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+fn write(repo: &gix::Repository, options: Options) -> crate::Result<gix::commit_graph::Outcome> {
+    Ok(repo.write_commit_graph(&mut gix::progress::Discard, &AtomicBool::default(), options)?)
+}
+
+#[test]
+fn single_file_with_generation_data_and_changed_paths() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_repo_with_fork_and_dates.sh")?;
+    let outcome = write(&repo, Options::default())?;
+    assert_eq!(
+        outcome.num_commits, 4,
+        "all commits are contained, including the one not yet in the old graph"
+    );
+    assert_eq!(outcome.num_layers, 1);
+    assert_eq!(
+        outcome.path.as_deref(),
+        Some(
+            repo.objects
+                .store_ref()
+                .path()
+                .join("info")
+                .join("commit-graph")
+                .as_path()
+        )
+    );
+    git(&repo, &["commit-graph", "verify"])?;
+
+    let graph = repo.commit_graph()?;
+    assert_eq!(graph.num_commits(), 4);
+    let c1 = graph.commit_by_id(repo.rev_parse_single("main~1^1")?).expect("present");
+    let c2 = graph.commit_by_id(repo.rev_parse_single("main^1")?).expect("present");
+    assert_eq!(
+        c1.committer_timestamp(),
+        c2.committer_timestamp(),
+        "both were committed at the same time"
+    );
+    assert_eq!(
+        c2.corrected_commit_date(),
+        Some(c1.committer_timestamp() + 1),
+        "corrected commit dates are always larger than the ones of parents"
+    );
+    Ok(())
+}
+
+#[test]
+fn split_layers_are_added_and_merged() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_repo_with_fork_and_dates.sh")?;
+    let split = Options {
+        split: true,
+        ..Default::default()
+    };
+    let outcome = write(&repo, split)?;
+    assert_eq!(outcome.num_commits, 4);
+    assert_eq!(outcome.num_layers, 1);
+    let info_dir = repo.objects.store_ref().path().join("info");
+    assert!(
+        !info_dir.join("commit-graph").exists(),
+        "the single file is removed as it would take precedence"
+    );
+    assert!(info_dir.join("commit-graphs").join("commit-graph-chain").is_file());
+
+    let outcome = write(&repo, split)?;
+    assert_eq!(outcome.path, None, "nothing changed, so nothing is written");
+
+    std::fs::write(repo.work_dir().expect("non-bare").join("file"), "content")?;
+    git(&repo, &["add", "file"])?;
+    git(&repo, &["commit", "-q", "-m", "add file"])?;
+    let outcome = write(&repo, split)?;
+    assert_eq!(outcome.num_commits, 1, "only the new commit is in the new layer");
+    assert_eq!(outcome.num_layers, 2, "the layer below is much larger, so it's kept");
+    git(&repo, &["commit-graph", "verify"])?;
+    assert_eq!(repo.commit_graph()?.num_commits(), 5);
+
+    let outcome = write(
+        &repo,
+        Options {
+            size_multiple: 4,
+            ..split
+        },
+    )?;
+    assert_eq!(outcome.num_commits, 0, "nothing is new");
+    assert_eq!(outcome.path, None);
+
+    git(&repo, &["commit", "-q", "--allow-empty", "-m", "empty"])?;
+    let outcome = write(
+        &repo,
+        Options {
+            size_multiple: 4,
+            ..split
+        },
+    )?;
+    assert_eq!(outcome.num_commits, 6, "both layers were merged into the new one");
+    assert_eq!(outcome.num_layers, 1);
+    git(&repo, &["commit-graph", "verify"])?;
+    assert_eq!(
+        std::fs::read_dir(info_dir.join("commit-graphs"))?.count(),
+        2,
+        "the chain file and a single layer, as unused layers are removed"
+    );
+
+    let outcome = write(&repo, Options::default())?;
+    assert_eq!(outcome.num_commits, 6);
+    assert!(
+        !info_dir.join("commit-graphs").exists(),
+        "the split commit-graph is removed entirely"
+    );
+    git(&repo, &["commit-graph", "verify"])?;
+    Ok(())
+}
+
+#[test]
+fn shallow_repositories_are_rejected() -> crate::Result {
+    let repo = crate::named_subrepo_opts("make_shallow_repo.sh", "shallow.git", crate::restricted())?;
+    assert!(matches!(
+        repo.write_commit_graph(&mut gix::progress::Discard, &AtomicBool::default(), Options::default()),
+        Err(gix::commit_graph::Error::Shallow)
+    ));
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use gix::config::tree::{Branch, Core};
+
+fn repo_with_local_config(config: &str) -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let repo = gix::init_bare(tmp.path())?;
+    std::fs::write(repo.common_dir().join("config"), config)?;
+    Ok((gix::open_opts(tmp.path(), gix::open::Options::isolated())?, tmp))
+}
+
+#[test]
+fn edits_are_written_without_affecting_anything_else() -> crate::Result {
+    let (repo, _tmp) = repo_with_local_config(
+        "# a comment that stays\n[core]\n\tbare = true   ; trailing comment\n\tabbrev = 12\n\n[remote \"origin\"]\n\turl = https://example.com\n[user]\n  name = me # custom indentation\n[remote \"origin\"]\n\tfetch = +refs/heads/*:refs/remotes/origin/*\n",
+    )?;
+    let mut file = repo.config_file_mut(gix::config::Source::Local)?;
+    assert_eq!(file.path(), repo.common_dir().join("config"));
+    assert_eq!(file.source(), gix::config::Source::Local);
+
+    let previous = file.set_value(&Core::ABBREV, "7")?;
+    assert_eq!(previous.expect("set"), "12");
+    file.set_subsection_value(&Branch::MERGE, "main", "refs/heads/main")?;
+    assert_eq!(file.remove_sections("remote", Some("origin".into())).len(), 2);
+    assert!(
+        file.set_value(&Core::ABBREV, "invalid").is_err(),
+        "values are validated"
+    );
+    file.commit()?;
+
+    assert_eq!(
+        std::fs::read_to_string(repo.common_dir().join("config"))?,
+        "# a comment that stays\n[core]\n\tbare = true   ; trailing comment\n\tabbrev = 7\n\n[user]\n  name = me # custom indentation\n[branch \"main\"]\n\tmerge = refs/heads/main\n"
+    );
+    assert_eq!(
+        repo.config_snapshot().integer("core.abbrev"),
+        Some(12),
+        "open repositories don't see the change"
+    );
+    let repo = gix::open_opts(repo.git_dir(), gix::open::Options::isolated())?;
+    assert_eq!(repo.config_snapshot().integer("core.abbrev"), Some(7));
+    assert_eq!(
+        repo.config_snapshot().string("branch.main.merge").as_deref(),
+        Some("refs/heads/main".into())
+    );
+    assert!(repo.find_remote("origin").is_err(), "the remote is gone");
+    Ok(())
+}
+
+#[test]
+fn dropping_discards_changes_and_files_are_created_as_needed() -> crate::Result {
+    let (repo, _tmp) = repo_with_local_config("[core]\n\tbare = true\n")?;
+    let mut file = repo.config_file_mut(gix::config::Source::Local)?;
+    file.set_value(&Core::ABBREV, "7")?;
+    assert!(
+        matches!(
+            repo.config_file_mut(gix::config::Source::Local),
+            Err(gix::config::file_mut::open::Error::Lock(_))
+        ),
+        "only one writer at a time"
+    );
+    drop(file);
+    assert_eq!(
+        std::fs::read_to_string(repo.common_dir().join("config"))?,
+        "[core]\n\tbare = true\n"
+    );
+
+    let worktree_config = repo.git_dir().join("config.worktree");
+    assert!(!worktree_config.exists());
+    let mut file = repo.config_file_mut(gix::config::Source::Worktree)?;
+    file.set_value(&Core::SPARSE_CHECKOUT, "true")?;
+    file.commit()?;
+    assert_eq!(
+        std::fs::read_to_string(worktree_config)?,
+        "[core]\n\tsparseCheckout = true\n"
+    );
+
+    assert!(matches!(
+        repo.config_file_mut(gix::config::Source::Cli),
+        Err(gix::config::file_mut::open::Error::NoLocation { .. })
+    ));
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use std::path::Path;
 
+use gix::repository::identity::{Error, Source};
 use gix_sec::Permission;
 use gix_testtools::Env;
 use serial_test::serial;
@@ -20,8 +21,8 @@ fn author_and_committer_and_fallback() -> crate::Result {
             .set("GIT_AUTHOR_NAME", "author")
             .set("GIT_AUTHOR_EMAIL", "author@email")
             .set("GIT_AUTHOR_DATE", "Thu, 1 Aug 2022 12:45:06 +0800")
-            .set("GIT_COMMITTER_NAME", "committer-overrider-unused")
-            .set("GIT_COMMITTER_EMAIL", "committer-override-unused@email")
+            .set("GIT_COMMITTER_NAME", "committer-override")
+            .set("GIT_COMMITTER_EMAIL", "committer-override@email")
             .set("GIT_COMMITTER_DATE", "Thu, 1 Aug 2022 12:45:06 -0200")
             .set("EMAIL", "general@email-unused")
             .set("GIT_CONFIG_COUNT", "1")
@@ -58,14 +59,15 @@ fn author_and_committer_and_fallback() -> crate::Result {
         assert_eq!(
             repo.committer().expect("present")?,
             gix_actor::SignatureRef {
-                name: "committer".into(),
-                email: "committer@email".into(),
+                name: "committer-override".into(),
+                email: "committer-override@email".into(),
                 time: gix_date::Time {
                     seconds: 1659365106,
                     offset: -7200,
                     sign: gix_date::time::Sign::Minus
                 }
-            }
+            },
+            "like in git, the environment takes precedence over `committer.name|email`"
         );
         let config = repo.config_snapshot();
 
@@ -191,3 +193,131 @@ fn author_from_different_config_sections() -> crate::Result {
     );
     Ok(())
 }
+
+#[test]
+#[serial]
+fn ident_reports_where_each_component_came_from() -> crate::Result {
+    let repo = named_repo("make_signatures_repo.sh")?;
+    let work_dir = repo.work_dir().unwrap().canonicalize()?;
+
+    let _env = Env::new()
+        .set("GIT_CONFIG_GLOBAL", work_dir.join("global.config").to_str().unwrap())
+        .set("GIT_CONFIG_SYSTEM", work_dir.join("system.config").to_str().unwrap())
+        .set("GIT_AUTHOR_NAME", "env author")
+        .set("GIT_COMMITTER_DATE", "1980-02-26 18:30:00 +0000")
+        .set("EMAIL", "general@email-unused");
+    let repo = gix::open_opts(
+        repo.git_dir(),
+        repo.open_options()
+            .clone()
+            .config_overrides(None::<&str>)
+            .with(gix_sec::Trust::Full)
+            .permissions(gix::open::Permissions {
+                env: gix::open::permissions::Environment {
+                    xdg_config_home: Permission::Deny,
+                    home: Permission::Deny,
+                    ..gix::open::permissions::Environment::all()
+                },
+                ..Default::default()
+            }),
+    )?;
+
+    let author = repo.author_ident()?;
+    assert_eq!(author.signature.name, "env author");
+    assert_eq!(author.signature.email, "local@example.com");
+    assert_eq!(
+        (author.name, author.email, author.time),
+        (Source::Environment, Source::Configuration, Source::Now)
+    );
+
+    let committer = repo.committer_ident()?;
+    assert_eq!(committer.signature.name, "local committer");
+    assert_eq!(committer.signature.email, "global-committer@example.com");
+    assert_eq!(committer.signature.time.seconds, 320437800);
+    assert_eq!(
+        (committer.name, committer.email, committer.time),
+        (Source::Configuration, Source::Configuration, Source::Environment)
+    );
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn ident_with_email_from_environment_or_system() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let repo = gix::init(tmp.path())?;
+    let _env = Env::new()
+        .set("GIT_COMMITTER_NAME", "committer")
+        .set("EMAIL", "general@email");
+
+    let repo = open_isolated(repo.git_dir(), &[])?;
+    let committer = repo.committer_ident()?;
+    assert_eq!(committer.signature.email, "general@email");
+    assert_eq!(
+        (committer.name, committer.email),
+        (Source::Environment, Source::EmailEnvironment)
+    );
+
+    let _env = _env.unset("EMAIL");
+    let repo = open_isolated(repo.git_dir(), &[])?;
+    assert!(repo.committer().is_none(), "there is no auto-detection here");
+    match repo.committer_ident() {
+        Ok(committer) => {
+            assert_eq!(committer.email, Source::System);
+            assert!(committer.signature.email.contains(&b'@'));
+        }
+        Err(Error::BogusEmail { email }) => {
+            assert!(
+                email.ends_with(b".(none)"),
+                "host names without domain are marked like in git: {email}"
+            );
+        }
+        Err(err) => return Err(err.into()),
+    }
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn ident_with_use_config_only() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let repo = gix::init(tmp.path())?;
+    let _env = Env::new()
+        .set("GIT_COMMITTER_NAME", "committer")
+        .set("EMAIL", "general@email");
+
+    let repo = open_isolated(repo.git_dir(), &["user.useConfigOnly=true"])?;
+    assert!(
+        matches!(repo.committer_ident(), Err(Error::EmailAutoDetectionDisabled)),
+        "the `EMAIL` variable is ignored"
+    );
+    assert_eq!(
+        repo.committer().expect("present")?.email,
+        "general@email",
+        "the non-strict version isn't affected"
+    );
+
+    let repo = open_isolated(repo.git_dir(), &["user.useConfigOnly=true", "user.email=config@email"])?;
+    assert_eq!(repo.committer_ident()?.signature.email, "config@email");
+    assert!(matches!(repo.author_ident(), Err(Error::NameAutoDetectionDisabled)));
+    Ok(())
+}
+
+/// Open the repository at `git_dir` with only its local configuration and `config_overrides`, but with identity information
+/// from the environment.
+fn open_isolated(git_dir: &Path, config_overrides: &[&str]) -> crate::Result<gix::Repository> {
+    Ok(gix::open_opts(
+        git_dir,
+        gix::open::Options::isolated()
+            .config_overrides(config_overrides.iter().copied())
+            .permissions(gix::open::Permissions {
+                env: gix::open::permissions::Environment {
+                    xdg_config_home: Permission::Deny,
+                    home: Permission::Deny,
+                    ..gix::open::permissions::Environment::all()
+                },
+                config: gix::open::permissions::Config::isolated(),
+                ..Default::default()
+            }),
+    )?)
+}
@@ -1,6 +1,8 @@
 mod config_snapshot;
+mod file_mut;
 mod identity;
 mod remote;
+mod var;
 
 #[cfg(feature = "blocking-network-client")]
 mod ssh_options {
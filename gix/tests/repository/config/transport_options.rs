@@ -50,13 +50,21 @@ mod http {
             no_proxy,
             proxy_auth_method,
             proxy_authenticate,
+            auth_method,
+            empty_auth,
             user_agent,
             connect_timeout,
             verbose,
             ssl_ca_info,
+            ssl_cert,
+            ssl_key,
+            ssl_cert_password,
             ssl_version,
             ssl_verify,
             http_version,
+            http2_prior_knowledge,
+            pool_idle_timeout,
+            tcp_keepalive,
             backend,
         } = http_options(&repo, None, "https://example.com/does/not/matter");
         assert_eq!(
@@ -73,11 +81,19 @@ mod http {
             "no username means no authentication required"
         );
         assert_eq!(proxy_auth_method, ProxyAuthMethod::Basic);
+        assert_eq!(auth_method, None, "basic authentication is used by default");
+        assert!(!empty_auth);
         assert_eq!(user_agent.as_deref(), Some("agentJustForHttp"));
         assert_eq!(connect_timeout, Some(std::time::Duration::from_millis(60 * 1024)));
         assert_eq!(no_proxy, None);
         assert!(!verbose, "verbose is disabled by default");
+        assert!(!http2_prior_knowledge, "there is no configuration for it");
+        assert_eq!(pool_idle_timeout, None, "the backend default is used");
+        assert_eq!(tcp_keepalive, None);
         assert_eq!(ssl_ca_info.as_deref(), Some(std::path::Path::new("./CA.pem")));
+        assert_eq!(ssl_cert, None);
+        assert_eq!(ssl_key, None);
+        assert!(ssl_cert_password.is_none());
         #[cfg(feature = "blocking-http-transport-reqwest")]
         {
             assert!(
@@ -112,6 +128,27 @@ mod http {
         assert_eq!(http_version, Some(HttpVersion::V1_1));
     }
 
+    #[test]
+    fn client_certificate_and_server_authentication() {
+        let repo = repo("http-client-auth");
+        let opts = http_options(&repo, None, "https://example.com/does/not/matter");
+        assert_eq!(opts.ssl_cert.as_deref(), Some(std::path::Path::new("./client.pem")));
+        assert_eq!(opts.ssl_key.as_deref(), Some(std::path::Path::new("./client.key")));
+        let (action, _authenticate) = opts
+            .ssl_cert_password
+            .expect("the password is obtained as http.sslCertPasswordProtected is set");
+        assert_eq!(
+            action
+                .context()
+                .and_then(|ctx| ctx.url.as_ref())
+                .map(ToString::to_string),
+            Some("cert://./client.pem".into()),
+            "credential helpers are asked for the password of the certificate like in `git`"
+        );
+        assert_eq!(opts.auth_method, Some(ProxyAuthMethod::Ntlm));
+        assert!(opts.empty_auth);
+    }
+
     #[test]
     fn http_ssl_cainfo_suppressed_by_() {
         let repo = repo("http-disabled-cainfo");
@@ -0,0 +1,110 @@
+use gix::repository::var::{Error, Var};
+use gix_testtools::Env;
+use serial_test::serial;
+
+fn open(git_dir: &std::path::Path, config_overrides: &[&str]) -> crate::Result<gix::Repository> {
+    let mut opts = gix::open::Options::isolated().config_overrides(config_overrides.iter().copied());
+    opts.permissions.env.git_prefix = gix_sec::Permission::Allow;
+    opts.permissions.env.identity = gix_sec::Permission::Allow;
+    Ok(gix::open_opts(git_dir, opts)?)
+}
+
+#[test]
+#[serial]
+fn editor_and_pager_follow_git_precedence() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let git_dir = gix::init(tmp.path())?.git_dir().to_owned();
+    let env = Env::new()
+        .unset("GIT_EDITOR")
+        .unset("GIT_SEQUENCE_EDITOR")
+        .unset("GIT_PAGER")
+        .unset("VISUAL")
+        .unset("EDITOR")
+        .unset("PAGER")
+        .set("TERM", "xterm");
+
+    let repo = open(&git_dir, &[])?;
+    assert_eq!(repo.editor()?, "vi");
+    assert_eq!(
+        repo.sequence_editor()?,
+        "vi",
+        "the sequence editor falls back to the editor"
+    );
+    assert_eq!(repo.pager().expect("set"), "less");
+
+    let env = env
+        .set("EDITOR", "editor")
+        .set("VISUAL", "visual")
+        .set("PAGER", "pager");
+    let repo = open(&git_dir, &[])?;
+    assert_eq!(repo.editor()?, "visual");
+    assert_eq!(repo.pager().expect("set"), "pager");
+
+    let env = env.set("TERM", "dumb");
+    let repo = open(&git_dir, &[])?;
+    assert_eq!(repo.editor()?, "editor", "VISUAL is ignored in dumb terminals");
+
+    let env = env.unset("EDITOR");
+    let repo = open(&git_dir, &[])?;
+    assert!(matches!(repo.editor(), Err(Error::DumbTerminal)));
+
+    let repo = open(
+        &git_dir,
+        &[
+            "core.editor=core-editor",
+            "sequence.editor=seq-editor",
+            "core.pager=cat",
+        ],
+    )?;
+    assert_eq!(repo.editor()?, "core-editor");
+    assert_eq!(repo.sequence_editor()?, "seq-editor");
+    assert_eq!(repo.pager(), None, "`cat` disables paging");
+    assert_eq!(repo.var(Var::Pager)?, "cat");
+
+    let _env = env
+        .set("GIT_EDITOR", "git-editor")
+        .set("GIT_SEQUENCE_EDITOR", "git-seq-editor")
+        .set("GIT_PAGER", "git-pager");
+    let repo = open(
+        &git_dir,
+        &[
+            "core.editor=core-editor",
+            "sequence.editor=seq-editor",
+            "core.pager=cat",
+        ],
+    )?;
+    assert_eq!(
+        repo.editor()?,
+        "git-editor",
+        "environment variables override configuration"
+    );
+    assert_eq!(repo.sequence_editor()?, "git-seq-editor");
+    assert_eq!(repo.var(Var::Pager)?, "git-pager");
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn var_by_name() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let git_dir = gix::init(tmp.path())?.git_dir().to_owned();
+    let _env = Env::new()
+        .set("GIT_COMMITTER_NAME", "committer")
+        .set("GIT_COMMITTER_EMAIL", "committer@example.com")
+        .set("GIT_COMMITTER_DATE", "@1700000000 +0100");
+
+    let repo = open(&git_dir, &[])?;
+    assert_eq!(
+        repo.var(Var::CommitterIdent)?,
+        "committer <committer@example.com> 1700000000 +0100"
+    );
+    assert_eq!(repo.var(Var::DefaultBranch)?, "main");
+    let repo = open(&git_dir, &["init.defaultBranch=trunk"])?;
+    assert_eq!(repo.var(Var::DefaultBranch)?, "trunk");
+
+    for var in Var::ALL {
+        assert_eq!(Var::from_name(var.name()), Some(*var));
+    }
+    assert_eq!(Var::from_name("GIT_UNKNOWN"), None);
+    Ok(())
+}
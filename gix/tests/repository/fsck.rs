@@ -0,0 +1,95 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::{
+    fsck::{Finding, Options},
+    objs::Kind,
+};
+
+fn loose_path(repo: &gix::Repository, id: &gix::oid) -> std::path::PathBuf {
+    let hex = id.to_string();
+    repo.objects.store_ref().path().join(&hex[..2]).join(&hex[2..])
+}
+
+#[test]
+fn intact_repository_has_only_dangling_objects() -> crate::Result {
+    let repo = crate::named_repo("make_fsck_repo.sh")?;
+    let outcome = repo.fsck(&mut gix::progress::Discard, &AtomicBool::default(), Options::default())?;
+    assert!(outcome.is_intact());
+    assert_eq!(
+        outcome.num_objects, 10,
+        "three commits with their trees, and four blobs"
+    );
+    let mut dangling: Vec<_> = outcome
+        .findings
+        .iter()
+        .map(|finding| match finding {
+            Finding::Dangling { kind, .. } => *kind,
+            other => unreachable!("unexpected finding: {other:?}"),
+        })
+        .collect();
+    dangling.sort();
+    assert_eq!(
+        dangling,
+        [Kind::Blob, Kind::Commit],
+        "the unreachable tree and blob are referenced by the commit"
+    );
+
+    let outcome = repo.fsck(
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        Options {
+            report_unreachable: true,
+            ..Default::default()
+        },
+    )?;
+    assert!(outcome.is_intact());
+    let mut unreachable: Vec<_> = outcome
+        .findings
+        .iter()
+        .filter_map(|finding| match finding {
+            Finding::Unreachable { kind, .. } => Some(*kind),
+            _ => None,
+        })
+        .collect();
+    unreachable.sort();
+    assert_eq!(unreachable, [Kind::Tree, Kind::Blob]);
+    Ok(())
+}
+
+#[test]
+fn corrupt_and_missing_objects_and_broken_references_are_reported() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_fsck_repo.sh")?;
+    let tree = repo.head_commit()?.tree()?;
+    let blob = tree.find_entry("b").expect("present").object_id();
+    let fake = gix::ObjectId::from_hex(b"1111111111111111111111111111111111111111")?;
+    std::fs::create_dir_all(loose_path(&repo, &fake).parent().expect("directory"))?;
+    std::fs::rename(loose_path(&repo, &blob), loose_path(&repo, &fake))?;
+    std::fs::write(
+        repo.git_dir().join("refs/heads/broken"),
+        format!("{}\n", repo.object_hash().null()),
+    )?;
+    std::fs::write(repo.git_dir().join("refs/heads/symbolic"), "ref: refs/heads/missing\n")?;
+
+    let outcome = repo.fsck(&mut gix::progress::Discard, &AtomicBool::default(), Options::default())?;
+    assert!(!outcome.is_intact());
+    let mut findings: Vec<_> = outcome.findings.iter().filter(|finding| finding.is_error()).collect();
+    findings.sort_by_key(ToString::to_string);
+    assert_eq!(findings.len(), 4, "{findings:#?}");
+    assert!(matches!(
+        findings[0],
+        Finding::BrokenReference { name, .. } if name.as_bstr() == "refs/heads/broken"
+    ));
+    assert!(matches!(
+        findings[1],
+        Finding::BrokenReference { name, .. } if name.as_bstr() == "refs/heads/symbolic"
+    ));
+    assert!(matches!(
+        findings[2],
+        Finding::HashMismatch { id, kind: Kind::Blob, actual } if *id == fake && *actual == blob
+    ));
+    assert!(matches!(
+        findings[3],
+        Finding::Missing { id, kind: Kind::Blob, referenced_by } if *id == blob && *referenced_by == tree.id
+    ));
+    Ok(())
+}
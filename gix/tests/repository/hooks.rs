@@ -0,0 +1,243 @@
+use std::path::Path;
+
+use gix::error::ErrorCode;
+use gix_testtools::tempfile;
+
+fn init(run_hooks: bool) -> crate::Result<(gix::Repository, tempfile::TempDir)> {
+    let tmp = tempfile::tempdir()?;
+    let repo = gix::ThreadSafeRepository::init_opts(
+        tmp.path(),
+        gix::create::Kind::WithWorktree,
+        Default::default(),
+        crate::restricted().run_hooks(run_hooks),
+    )?
+    .to_thread_local();
+    Ok((repo, tmp))
+}
+
+fn write_hook(dir: &Path, name: &str, script: &str) -> crate::Result {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{script}\n"))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn hooks_are_found_in_the_hooks_directory_or_in_core_hooks_path() -> crate::Result {
+    let (repo, tmp) = init(false)?;
+    assert_eq!(repo.hooks_dir()?, repo.git_dir().join("hooks"));
+    assert!(repo.hook("does-not-exist")?.is_none());
+
+    let hooks_dir = repo.hooks_dir()?;
+    std::fs::write(hooks_dir.join("not-executable"), "#!/bin/sh\n")?;
+    assert!(
+        repo.hook("not-executable")?.is_none(),
+        "hooks must be executable to be considered"
+    );
+    write_hook(&hooks_dir, "pre-commit", "exit 0")?;
+    let hook = repo.hook("pre-commit")?.expect("present");
+    assert_eq!(hook.name(), "pre-commit");
+    assert_eq!(hook.path(), hooks_dir.join("pre-commit"));
+
+    let mut config = std::fs::OpenOptions::new()
+        .append(true)
+        .open(repo.git_dir().join("config"))?;
+    std::io::Write::write_all(&mut config, b"[core]\n\thooksPath = my-hooks\n")?;
+    let repo = gix::open_opts(tmp.path(), crate::restricted())?;
+    assert_eq!(
+        repo.hooks_dir()?,
+        repo.work_dir().expect("non-bare").join("my-hooks"),
+        "relative paths are relative to the worktree"
+    );
+    assert!(repo.hook("pre-commit")?.is_none());
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn hooks_are_not_run_in_repositories_that_are_not_fully_trusted() -> crate::Result {
+    let (repo, tmp) = init(true)?;
+    write_hook(&repo.hooks_dir()?, "pre-commit", "exit 1")?;
+    assert!(repo.hook("pre-commit")?.is_some());
+
+    let repo = gix::open_opts(
+        tmp.path(),
+        crate::restricted().run_hooks(true).with(gix::sec::Trust::Reduced),
+    )?;
+    assert!(repo.hook("pre-commit")?.is_none(), "hooks could run any program");
+    assert!(!repo.run_hook("pre-commit", None::<&str>)?);
+    repo.commit("HEAD", "initial", repo.empty_tree().id, gix::commit::NO_PARENT_IDS)?;
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn run_passes_arguments_and_input_and_fails_if_the_hook_fails() -> crate::Result {
+    let (repo, _tmp) = init(false)?;
+    let hooks_dir = repo.hooks_dir()?;
+    write_hook(&hooks_dir, "record", r#"echo "$@" > args; cat > input; pwd > cwd"#)?;
+    write_hook(&hooks_dir, "fail", "exit 3")?;
+
+    repo.hook("record")?
+        .expect("present")
+        .args(["a", "b"])
+        .stdin("input")
+        .run()?;
+    let workdir = repo.work_dir().expect("non-bare");
+    assert_eq!(std::fs::read_to_string(workdir.join("args"))?, "a b\n");
+    assert_eq!(std::fs::read_to_string(workdir.join("input"))?, "input");
+    assert_eq!(
+        Path::new(std::fs::read_to_string(workdir.join("cwd"))?.trim_end()),
+        gix::path::realpath(workdir)?,
+        "hooks run in the root of the worktree"
+    );
+
+    let err = repo.hook("fail")?.expect("present").run().unwrap_err();
+    assert_eq!(err.to_string(), "The fail hook failed with exit status: 3");
+    assert_eq!(err.code(), gix::error::Code::HookRejected);
+    assert!(!repo.run_hook("does-not-exist", ["ignored"])?);
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn commit_runs_hooks_only_if_enabled() -> crate::Result {
+    for run_hooks in [false, true] {
+        let (repo, _tmp) = init(run_hooks)?;
+        let hooks_dir = repo.hooks_dir()?;
+        write_hook(&hooks_dir, "pre-commit", r#"echo "$GIT_INDEX_FILE" > pre-commit-ran"#)?;
+        write_hook(&hooks_dir, "prepare-commit-msg", r#"echo "$2" > source"#)?;
+        write_hook(&hooks_dir, "commit-msg", r#"echo "changed by hook" >> "$1""#)?;
+        write_hook(&hooks_dir, "post-commit", "touch post-commit-ran")?;
+
+        let tree = repo.empty_tree().id;
+        let id = repo.commit("HEAD", "initial\n", tree, gix::commit::NO_PARENT_IDS)?;
+        let workdir = repo.work_dir().expect("non-bare");
+        let message = id.object()?.into_commit().message_raw()?.to_owned();
+        if run_hooks {
+            assert_eq!(message, "initial\nchanged by hook\n");
+            assert_eq!(
+                std::fs::read_to_string(workdir.join("pre-commit-ran"))?,
+                format!("{}\n", repo.index_path().display())
+            );
+            assert_eq!(std::fs::read_to_string(workdir.join("source"))?, "message\n");
+            assert!(workdir.join("post-commit-ran").is_file());
+        } else {
+            assert_eq!(message, "initial\n", "hooks aren't run by default");
+            assert!(!workdir.join("pre-commit-ran").exists());
+            assert!(!workdir.join("post-commit-ran").exists());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn failing_pre_commit_hooks_prevent_the_commit() -> crate::Result {
+    let (repo, _tmp) = init(true)?;
+    let hooks_dir = repo.hooks_dir()?;
+    let tree = repo.empty_tree().id;
+    let first = repo.commit("HEAD", "initial", tree, gix::commit::NO_PARENT_IDS)?;
+
+    write_hook(&hooks_dir, "pre-commit", "exit 1")?;
+    let err = repo.commit("HEAD", "second", tree, [first]).unwrap_err();
+    assert_eq!(err.to_string(), "The pre-commit hook failed with exit status: 1");
+    assert_eq!(err.code(), gix::error::Code::HookRejected);
+    assert_eq!(repo.head_id()?, first, "HEAD is unchanged");
+
+    std::fs::remove_file(hooks_dir.join("pre-commit"))?;
+    let second = repo.commit("refs/heads/other", "second", tree, [first])?;
+    write_hook(&hooks_dir, "pre-merge-commit", "exit 1")?;
+    let err = repo.commit("HEAD", "merge", tree, [first, second]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The pre-merge-commit hook failed with exit status: 1",
+        "merge commits run a different hook"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(all(unix, feature = "checkout"))]
+fn post_checkout_runs_after_switching() -> crate::Result {
+    let (repo, _tmp) = crate::util::repo_rw_opts("make_checkout_repo.sh", crate::restricted().run_hooks(true))?;
+    write_hook(
+        &repo.hooks_dir()?,
+        "post-checkout",
+        r#"echo "$@" > "$GIT_DIR/post-checkout-args""#,
+    )?;
+    let previous = repo.head_id()?.detach();
+    let other = repo.rev_parse_single("other")?.detach();
+
+    repo.switch("other", Default::default())?;
+    let args = repo.git_dir().join("post-checkout-args");
+    assert_eq!(std::fs::read_to_string(&args)?, format!("{previous} {other} 1\n"));
+
+    repo.checkout_detached(previous, Default::default())?;
+    assert_eq!(std::fs::read_to_string(&args)?, format!("{other} {previous} 1\n"));
+
+    write_hook(&repo.hooks_dir()?, "post-checkout", "exit 1")?;
+    repo.switch("other", Default::default())?;
+    assert_eq!(
+        repo.head_id()?,
+        other,
+        "failures are ignored as the checkout was performed already"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(all(unix, feature = "merge"))]
+fn merge_into_head_runs_merge_hooks() -> crate::Result {
+    let (repo, _tmp) = crate::util::repo_rw_opts("make_merge_strategies_repo.sh", crate::restricted().run_hooks(true))?;
+    let hooks_dir = repo.hooks_dir()?;
+    write_hook(&hooks_dir, "pre-commit", "exit 1")?;
+    write_hook(&hooks_dir, "pre-merge-commit", "git ls-files > pre-merge-commit-index")?;
+    write_hook(&hooks_dir, "prepare-commit-msg", r#"echo "$2" > source"#)?;
+    write_hook(&hooks_dir, "post-merge", r#"echo "$@" > post-merge-args; exit 1"#)?;
+
+    let previous = repo.head_id()?.detach();
+    let one = repo.rev_parse_single("one")?.detach();
+    let outcome = repo.merge_into_head([one], "merge one")?;
+    let commit = outcome.commit.expect("committed");
+    assert_eq!(repo.head_id()?, commit);
+    assert_eq!(
+        repo.find_object(commit)?.into_commit().parent_ids().collect::<Vec<_>>(),
+        [previous, one]
+    );
+    let workdir = repo.work_dir().expect("non-bare");
+    assert!(
+        std::fs::read_to_string(workdir.join("pre-merge-commit-index"))?.contains("one.txt"),
+        "the merge is staged when the hook runs"
+    );
+    assert_eq!(std::fs::read_to_string(workdir.join("source"))?, "merge\n");
+    assert_eq!(
+        std::fs::read_to_string(workdir.join("post-merge-args"))?,
+        "0\n",
+        "it's not a squash merge, and its failure is ignored"
+    );
+    assert!(repo.state().is_none(), "the merge is concluded");
+
+    for name in ["pre-merge-commit-index", "source", "post-merge-args"] {
+        std::fs::remove_file(workdir.join(name))?;
+    }
+    write_hook(&hooks_dir, "pre-merge-commit", "exit 1")?;
+    let err = repo
+        .merge_into_head([repo.rev_parse_single("two")?], "merge two")
+        .unwrap_err();
+    assert_eq!(err.to_string(), "The pre-merge-commit hook failed with exit status: 1");
+    assert_eq!(repo.head_id()?, commit, "HEAD is unchanged");
+    assert_eq!(
+        repo.state(),
+        Some(gix::state::InProgress::Merge),
+        "the merge can be concluded with git"
+    );
+    assert!(!workdir.join("post-merge-args").exists());
+    Ok(())
+}
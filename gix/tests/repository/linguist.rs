@@ -0,0 +1,124 @@
+use gix::linguist::Classification;
+
+#[test]
+fn attributes_override_heuristics() -> crate::Result {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let repo = gix::ThreadSafeRepository::init_opts(
+        tmp.path(),
+        gix::create::Kind::WithWorktree,
+        Default::default(),
+        crate::restricted(),
+    )?
+    .to_thread_local();
+    std::fs::write(
+        tmp.path().join(".gitattributes"),
+        "vendor/ours/** -linguist-vendored\n\
+         *.gen linguist-generated\n\
+         docs/api.rs linguist-documentation=false linguist-language=Rust\n\
+         *.dat binary\n\
+         *.txt text\n\
+         README.md linguist-detectable\n",
+    )?;
+    let mut classifier = repo.linguist_classifier()?;
+    let mut classify = |path: &str, data: Option<&[u8]>| classifier.classify(path.into(), data);
+
+    let source = Classification {
+        detectable: true,
+        ..Default::default()
+    };
+    assert_eq!(classify("src/lib.rs", Some(b"fn main() {}\n"))?, source);
+    assert_eq!(
+        classify("vendor/theirs/lib.rs", None)?,
+        Classification {
+            vendored: true,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        classify("vendor/ours/lib.rs", None)?,
+        source,
+        "attributes can turn off heuristics"
+    );
+    assert_eq!(
+        classify("a/Cargo.lock", None)?,
+        Classification {
+            generated: true,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        classify("file.gen", None)?,
+        Classification {
+            generated: true,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        classify("src/bindings.rs", Some(b"// @generated by a tool\nfn f() {}\n"))?,
+        Classification {
+            generated: true,
+            ..Default::default()
+        },
+        "the first lines of the content are checked for markers"
+    );
+    assert_eq!(
+        classify("docs/guide.md", None)?,
+        Classification {
+            documentation: true,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        classify("docs/api.rs", None)?,
+        Classification {
+            detectable: true,
+            language: Some("Rust".into()),
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        classify("README.md", None)?,
+        Classification {
+            documentation: true,
+            detectable: true,
+            ..Default::default()
+        },
+        "detectability can be forced"
+    );
+    assert_eq!(
+        classify("blob.dat", None)?,
+        Classification {
+            binary: true,
+            ..Default::default()
+        },
+        "the binary macro unsets `text` and `diff`"
+    );
+    assert_eq!(
+        classify("image.png", Some(b"\x89PNG\0\0"))?,
+        Classification {
+            binary: true,
+            ..Default::default()
+        },
+        "content is checked for null bytes"
+    );
+    assert_eq!(
+        classify("notes.txt", Some(b"a\0b"))?,
+        source,
+        "set `text` attributes take precedence over the content"
+    );
+    Ok(())
+}
+
+#[test]
+fn heuristics() {
+    use gix::linguist::{is_binary, is_documentation, is_generated, is_vendored};
+    assert!(is_vendored("node_modules/x/index.js".into()));
+    assert!(!is_vendored("vendor".into()), "only directories count");
+    assert!(is_generated("dist/app.min.js".into(), None));
+    assert!(!is_generated("src/main.rs".into(), Some(b"fn main() {}")));
+    assert!(is_documentation("CHANGELOG.md".into()));
+    assert!(is_documentation("LICENSE".into()));
+    assert!(!is_documentation("licenses.rs".into()));
+    assert!(is_binary(b"\0"));
+    assert!(!is_binary(b"text"));
+}
@@ -0,0 +1,85 @@
+use gix::config::tree::Mailmap;
+
+fn names(mailmap: &gix::mailmap::Snapshot) -> Vec<String> {
+    ["a", "b", "c", "d"]
+        .into_iter()
+        .map(|name| {
+            let email = format!("{name}@example.com");
+            mailmap
+                .try_resolve(gix::actor::SignatureRef {
+                    name: "Original".into(),
+                    email: email.as_str().into(),
+                    time: Default::default(),
+                })
+                .map_or_else(|| "Original".into(), |signature| signature.name.to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn later_sources_take_precedence() -> crate::Result {
+    let (mut repo, tmp) = crate::named_subrepo_rw("make_mailmap_repo.sh", "repo")?;
+    assert_eq!(
+        names(&repo.open_mailmap()),
+        ["From Worktree", "From Blob", "From Blob", "Original"],
+        "`mailmap.blob` overrides the `.mailmap` file in the worktree, while `HEAD:.mailmap` isn't used"
+    );
+
+    let path = tmp.path().join("configured.mailmap");
+    std::fs::write(&path, "From File <c@example.com>\n")?;
+    repo.config_snapshot_mut()
+        .set_value(&Mailmap::FILE, gix::path::into_bstr(path.as_path()).as_ref())?;
+    let mut mailmap = Default::default();
+    repo.open_mailmap_into(&mut mailmap)?;
+    assert_eq!(
+        names(&mailmap),
+        ["From Worktree", "From Blob", "From File", "Original"],
+        "`mailmap.file` has the highest precedence"
+    );
+
+    std::fs::remove_file(path)?;
+    repo.open_mailmap_into(&mut Default::default())
+        .expect("missing files are ignored");
+    Ok(())
+}
+
+#[test]
+fn bare_repositories_use_the_mailmap_at_head_unless_a_blob_is_configured() -> crate::Result {
+    let (mut repo, _tmp) = crate::named_subrepo_rw("make_mailmap_repo.sh", "bare.git")?;
+    assert_eq!(
+        names(&repo.open_mailmap()),
+        ["From HEAD", "From HEAD", "Original", "Original"]
+    );
+
+    repo.config_snapshot_mut().set_value(&Mailmap::BLOB, "mailmap-blob")?;
+    assert_eq!(
+        names(&repo.open_mailmap()),
+        ["Original", "From Blob", "From Blob", "Original"],
+        "`HEAD:.mailmap` is only the default for `mailmap.blob`"
+    );
+    Ok(())
+}
+
+#[test]
+fn configured_objects_must_be_blobs() -> crate::Result {
+    let (mut repo, _tmp) = crate::named_subrepo_rw("make_mailmap_repo.sh", "repo")?;
+    repo.config_snapshot_mut().set_value(&Mailmap::BLOB, "mailmap-tree")?;
+    let mut mailmap = Default::default();
+    let err = repo.open_mailmap_into(&mut mailmap).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            gix::mailmap::load::Error::NotABlob {
+                actual: gix::object::Kind::Tree,
+                ..
+            }
+        ),
+        "{err:?}"
+    );
+    assert_eq!(
+        names(&mailmap),
+        ["From Worktree", "From Worktree", "From Worktree", "Original"],
+        "other sources are still used"
+    );
+    Ok(())
+}
@@ -0,0 +1,225 @@
+use std::{process::Command, sync::atomic::AtomicBool};
+
+use gix::maintenance::Options;
+
+fn files_in(dir: &std::path::Path, extension: &str) -> crate::Result<usize> {
+    Ok(std::fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().map_or(false, |ext| ext == extension))
+        .count())
+}
+
+fn nothing() -> Options {
+    Options {
+        expire_reflogs: false,
+        prune_unreachable_objects: false,
+        consolidate_packs: false,
+        prune_loose_objects: false,
+        commit_graph: None,
+        multi_pack_index: None,
+    }
+}
+
+#[test]
+fn all_tasks_consolidate_objects_into_one_pack() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_maintenance_repo.sh")?;
+    let objects_dir = repo.objects.store_ref().path().to_owned();
+    let pack_dir = objects_dir.join("pack");
+    assert_eq!(files_in(&pack_dir, "pack")?, 2);
+    let num_loose = gix::odb::loose::Store::at(&objects_dir, repo.object_hash())
+        .iter()
+        .count();
+    assert_ne!(num_loose, 0, "the last commit is loose");
+
+    let outcome = repo.maintenance(
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        Options {
+            multi_pack_index: Some(Default::default()),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        outcome.num_expired_reflog_entries, 9,
+        "all entries of the fixture are too old, four in HEAD and main each, and one in other"
+    );
+    let pruned = outcome.pruned.expect("pruning is enabled by default");
+    assert!(
+        pruned.loose_objects.is_empty() && pruned.packed_objects.is_empty(),
+        "all objects are still reachable from `other`"
+    );
+    let pack = outcome.consolidated_pack.expect("packs were consolidated");
+    assert_eq!(pack.num_removed_packs, 2);
+    assert_eq!(pack.num_objects, 9, "three commits with their trees, and three blobs");
+    assert_eq!(outcome.num_pruned_loose_objects, num_loose);
+    assert_eq!(outcome.commit_graph.expect("written").num_commits, 3);
+    assert_eq!(outcome.multi_pack_index.expect("written").num_packs, 1);
+
+    assert_eq!(files_in(&pack_dir, "pack")?, 1);
+    assert_eq!(files_in(&pack_dir, "keep")?, 0);
+    assert_eq!(
+        gix::odb::loose::Store::at(&objects_dir, repo.object_hash())
+            .iter()
+            .count(),
+        0
+    );
+
+    let fsck = Command::new("git")
+        .args(["fsck", "--no-dangling"])
+        .current_dir(repo.path())
+        .output()?;
+    assert!(fsck.status.success(), "{}", String::from_utf8_lossy(&fsck.stderr));
+
+    let repo = gix::open_opts(repo.path(), crate::restricted())?;
+    for name in ["main", "other"] {
+        let commit = repo.rev_parse_single(name)?.object()?.into_commit();
+        for tree_entry in commit.tree()?.iter() {
+            repo.find_object(tree_entry?.oid())?;
+        }
+    }
+
+    let outcome = repo.maintenance(&mut gix::progress::Discard, &AtomicBool::default(), Default::default())?;
+    assert!(outcome.consolidated_pack.is_none(), "there is nothing to do");
+    assert_eq!(outcome.num_pruned_loose_objects, 0);
+    assert!(
+        outcome.multi_pack_index.is_none(),
+        "it's only rewritten if packs change"
+    );
+    Ok(())
+}
+
+#[test]
+fn nothing_is_done_if_no_task_is_enabled() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_maintenance_repo.sh")?;
+    let outcome = repo.maintenance(&mut gix::progress::Discard, &AtomicBool::default(), nothing())?;
+    assert_eq!(outcome.num_expired_reflog_entries, 0);
+    assert!(outcome.consolidated_pack.is_none());
+    assert!(outcome.commit_graph.is_none());
+    assert_eq!(files_in(&repo.objects.store_ref().path().join("pack"), "pack")?, 2);
+    Ok(())
+}
+
+mod prune_objects {
+    use std::{
+        sync::atomic::AtomicBool,
+        time::{Duration, SystemTime},
+    };
+
+    use gix::maintenance::{prune, Options};
+
+    use super::{files_in, nothing};
+
+    fn prune(repo: &gix::Repository, options: prune::Options) -> crate::Result<prune::Outcome> {
+        Ok(repo.prune_objects(&mut gix::progress::Discard, &AtomicBool::default(), options)?)
+    }
+
+    #[test]
+    fn unreachable_loose_objects_are_removed_after_their_grace_period() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_maintenance_repo.sh")?;
+        let unreachable = repo.write_blob("unreachable")?.detach();
+        let in_a_minute = SystemTime::now() + Duration::from_secs(60);
+
+        let outcome = prune(
+            &repo,
+            prune::Options {
+                expire: Some(in_a_minute),
+                repack: true,
+                dry_run: true,
+            },
+        )?;
+        assert_eq!(outcome.loose_objects, [unreachable]);
+        assert!(
+            outcome.packed_objects.is_empty(),
+            "the third commit is reachable from `other` and reference logs"
+        );
+        assert!(outcome.pack.is_none(), "nothing changes in a dry-run");
+        assert!(repo.has_object(unreachable));
+
+        assert!(
+            prune(&repo, repo.prune_options()?)?.loose_objects.is_empty(),
+            "by default, objects are protected for two weeks"
+        );
+        assert!(prune(&repo, prune::Options::default())?.loose_objects.is_empty());
+
+        let outcome = prune(
+            &repo,
+            prune::Options {
+                expire: Some(in_a_minute),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(outcome.loose_objects, [unreachable]);
+        let repo = gix::open_opts(repo.path(), crate::restricted())?;
+        assert!(!repo.has_object(unreachable));
+
+        let repo: gix::Repository = gix::open_opts(
+            repo.path(),
+            crate::restricted().config_overrides(["gc.pruneExpire=now"]),
+        )?;
+        assert!(repo.prune_options()?.expire.expect("set") <= SystemTime::now());
+        let repo: gix::Repository = gix::open_opts(
+            repo.path(),
+            crate::restricted().config_overrides(["gc.pruneExpire=never"]),
+        )?;
+        assert_eq!(repo.prune_options()?.expire, None);
+        Ok(())
+    }
+
+    #[test]
+    fn packs_are_rewritten_to_drop_unreachable_objects() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_maintenance_repo.sh")?;
+        repo.maintenance(
+            &mut gix::progress::Discard,
+            &AtomicBool::default(),
+            Options {
+                consolidate_packs: true,
+                prune_loose_objects: true,
+                ..nothing()
+            },
+        )?;
+        let third = repo.rev_parse_single("other")?.detach();
+        repo.find_reference("other")?.delete()?;
+        repo.maintenance(
+            &mut gix::progress::Discard,
+            &AtomicBool::default(),
+            Options {
+                expire_reflogs: true,
+                ..nothing()
+            },
+        )?;
+
+        let repo = gix::open_opts(repo.path(), crate::restricted())?;
+        let options = prune::Options {
+            expire: Some(SystemTime::now()),
+            repack: true,
+            dry_run: true,
+        };
+        let outcome = prune(&repo, options)?;
+        assert!(outcome.loose_objects.is_empty(), "all objects are packed");
+        assert_eq!(
+            outcome.packed_objects.len(),
+            3,
+            "the third commit, its tree and the blob it added aren't reachable anymore"
+        );
+        assert!(outcome.packed_objects.contains(&third));
+        assert!(outcome.pack.is_none());
+
+        let outcome = prune(
+            &repo,
+            prune::Options {
+                dry_run: false,
+                ..options
+            },
+        )?;
+        assert_eq!(outcome.packed_objects.len(), 3);
+        let pack = outcome.pack.expect("rewritten");
+        assert_eq!(pack.num_objects, 6);
+        assert_eq!(pack.num_removed_packs, 1);
+        assert_eq!(files_in(&repo.objects.store_ref().path().join("pack"), "pack")?, 1);
+
+        let repo = gix::open_opts(repo.path(), crate::restricted())?;
+        assert!(!repo.has_object(third));
+        assert_eq!(repo.head_commit()?.message_raw()?, "second\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,489 @@
+use gix::{
+    bstr::ByteSlice,
+    merge::{blob::Resolution, tree::Outcome},
+};
+
+fn merge(
+    repo: &gix::Repository,
+    intercept: impl FnMut(
+        &gix::merge::blob::Driver,
+        gix::merge::tree::BlobInput<'_>,
+        &mut Vec<u8>,
+    ) -> Result<Option<Resolution>, Box<dyn std::error::Error + Send + Sync + 'static>>,
+) -> Result<Outcome, gix::merge::tree::Error> {
+    repo.merge_trees_with_drivers(
+        repo.rev_parse_single("HEAD~1^{tree}").expect("present"),
+        repo.rev_parse_single("HEAD^{tree}").expect("present"),
+        repo.rev_parse_single("theirs^{tree}").expect("present"),
+        Default::default(),
+        Default::default(),
+        intercept,
+    )
+}
+
+/// Return the merged content at `path`, and whether it's conflicting.
+fn content(repo: &gix::Repository, outcome: &Outcome, path: &str) -> crate::Result<(String, bool)> {
+    let (id, is_conflict) = match outcome.conflicts.iter().find(|c| c.path == path) {
+        Some(conflict) => (conflict.merged_blob.expect("content was merged"), true),
+        None => (
+            outcome
+                .index
+                .entry_by_path(path.into())
+                .expect("merged entry is present")
+                .id,
+            false,
+        ),
+    };
+    Ok((repo.find_object(id)?.data.to_str()?.to_owned(), is_conflict))
+}
+
+#[test]
+fn merge_drivers_are_selected_by_attribute() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_merge_drivers_repo.sh")?;
+    let outcome = repo.merge_trees(
+        repo.rev_parse_single("HEAD~1^{tree}")?,
+        repo.rev_parse_single("HEAD^{tree}")?,
+        repo.rev_parse_single("theirs^{tree}")?,
+        Default::default(),
+        Default::default(),
+    )?;
+
+    assert_eq!(
+        content(&repo, &outcome, "a.union")?,
+        ("ours\ntheirs\n".into(), false),
+        "union keeps both sides"
+    );
+    assert_eq!(
+        content(&repo, &outcome, "a.custom")?,
+        ("theirs\n".into(), false),
+        "the configured command ran"
+    );
+    assert_eq!(
+        content(&repo, &outcome, "a.bin")?,
+        ("ours\n".into(), true),
+        "-merge conflicts and keeps ours"
+    );
+    for path in ["a.txt", "a.unknown"] {
+        let (merged, is_conflict) = content(&repo, &outcome, path)?;
+        assert!(is_conflict, "{path}: unknown drivers fall back to text");
+        assert!(merged.starts_with("<<<<<<<"), "{path}: {merged}");
+    }
+    Ok(())
+}
+
+#[test]
+fn merge_drivers_can_be_intercepted() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_merge_drivers_repo.sh")?;
+    let mut seen = Vec::new();
+    let outcome = merge(&repo, |driver, input, out| {
+        seen.push((driver.name.clone(), driver.display_name.clone(), input.path.to_owned()));
+        out.clear();
+        out.extend_from_slice(b"intercepted\n");
+        Ok(Some(Resolution::Complete))
+    })?;
+    assert_eq!(
+        seen,
+        [("custom".into(), Some("take theirs".into()), "a.custom".into())],
+        "only user-defined drivers are passed"
+    );
+    assert_eq!(content(&repo, &outcome, "a.custom")?, ("intercepted\n".into(), false));
+
+    let outcome = merge(&repo, |_driver, _input, _out| Ok(None))?;
+    assert_eq!(
+        content(&repo, &outcome, "a.custom")?,
+        ("theirs\n".into(), false),
+        "the command runs if the driver isn't intercepted"
+    );
+
+    let err = match merge(&repo, |_driver, _input, _out| Err("failure".into())) {
+        Ok(_) => unreachable!("interception fails"),
+        Err(err) => err,
+    };
+    assert!(
+        matches!(&err, gix::merge::tree::Error::MergeBlob { path, .. } if path == "a.custom"),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn merge_default_applies_to_files_without_merge_attribute() -> crate::Result {
+    let (mut repo, _tmp) = crate::repo_rw("make_merge_drivers_repo.sh")?;
+    repo.config_snapshot_mut()
+        .set_raw_value("merge", None, "default", "union")?;
+    let outcome = merge(&repo, |_driver, _input, _out| Ok(None))?;
+    assert_eq!(content(&repo, &outcome, "a.txt")?, ("ours\ntheirs\n".into(), false));
+    assert!(
+        content(&repo, &outcome, "a.unknown")?.1,
+        "explicit drivers aren't affected"
+    );
+    Ok(())
+}
+
+mod commits {
+    use gix::merge::{
+        commits::Error,
+        tree::{Options, Renames, Strategy},
+    };
+    use gix_hash::ObjectId;
+
+    use super::content;
+
+    fn id(repo: &gix::Repository, spec: &str) -> ObjectId {
+        repo.rev_parse_single(spec).expect("present").detach()
+    }
+
+    fn merge(repo: &gix::Repository, theirs: &[&str], options: Options) -> Result<gix::merge::commits::Outcome, Error> {
+        repo.merge_commits(id(repo, "main"), theirs.iter().map(|spec| id(repo, spec)), options)
+    }
+
+    fn paths(outcome: &gix::merge::commits::Outcome) -> Vec<String> {
+        let index = &outcome.tree.index;
+        index.entries().iter().map(|e| e.path(index).to_string()).collect()
+    }
+
+    #[test]
+    fn octopus_merges_all_commits() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_merge_strategies_repo.sh")?;
+        let outcome = merge(&repo, &["one", "two", "main~1", "three"], Default::default())?;
+        assert!(!outcome.tree.has_conflicts());
+        assert_eq!(
+            outcome.merged,
+            [id(&repo, "one"), id(&repo, "two"), id(&repo, "three")],
+            "commits that are already merged are skipped"
+        );
+        assert_eq!(outcome.merge_bases, vec![Some(id(&repo, "main~2")); 3]);
+        assert_eq!(
+            paths(&outcome),
+            [
+                "a.txt",
+                "long.txt",
+                "one.txt",
+                "three.txt",
+                "two.txt",
+                "vendor/sub/lib.rs"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn merge_into_head_commits_the_merge_or_leaves_conflicts() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_merge_strategies_repo.sh")?;
+        let previous = id(&repo, "main");
+        let outcome = repo.merge_into_head([id(&repo, "one")], "merge one")?;
+        let commit = outcome.commit.expect("no conflicts");
+        assert_eq!(outcome.merged, [id(&repo, "one")]);
+        assert_eq!(repo.head_id()?, commit);
+        assert_eq!(
+            repo.find_object(commit)?.into_commit().parent_ids().collect::<Vec<_>>(),
+            [previous, id(&repo, "one")]
+        );
+        let workdir = repo.work_dir().expect("non-bare");
+        assert_eq!(std::fs::read_to_string(workdir.join("one.txt"))?, "one\n");
+        assert!(repo.state().is_none());
+
+        let outcome = repo.merge_into_head([id(&repo, "one")], "nothing to do")?;
+        assert_eq!(outcome.commit, None);
+        assert!(outcome.merged.is_empty(), "it's merged already");
+        assert_eq!(repo.head_id()?, commit);
+
+        let outcome = repo.merge_into_head([id(&repo, "conflicting")], "merge conflicting")?;
+        assert_eq!(outcome.commit, None);
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert_eq!(repo.head_id()?, commit, "nothing was committed");
+        assert!(std::fs::read_to_string(workdir.join("a.txt"))?.starts_with("<<<<<<<"));
+        assert_eq!(repo.state(), Some(gix::state::InProgress::Merge));
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("MERGE_HEAD"))?,
+            format!("{}\n", id(&repo, "conflicting"))
+        );
+        assert!(std::fs::read_to_string(repo.path().join("MERGE_MSG"))?
+            .starts_with("merge conflicting\n# Conflicts:\n#\ta.txt"));
+        Ok(())
+    }
+
+    #[test]
+    fn octopus_only_allows_conflicts_in_the_last_commit() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_merge_strategies_repo.sh")?;
+        let err = match merge(&repo, &["conflicting", "one"], Default::default()) {
+            Ok(_) => unreachable!("the first commit conflicts"),
+            Err(err) => err,
+        };
+        assert!(matches!(err, Error::OctopusConflict { id: conflicting } if conflicting == id(&repo, "conflicting")));
+
+        let outcome = merge(&repo, &["one", "conflicting"], Default::default())?;
+        assert_eq!(outcome.tree.conflicts.len(), 1);
+        let (merged, is_conflict) = content(&repo, &outcome.tree, "a.txt")?;
+        assert!(is_conflict);
+        assert!(merged.starts_with("<<<<<<<"), "{merged}");
+        assert!(paths(&outcome).contains(&"one.txt".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn ours_and_theirs_keep_one_side() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_merge_strategies_repo.sh")?;
+        for (strategy, expected) in [(Strategy::Ours, "main\n"), (Strategy::Theirs, "conflicting\n")] {
+            let outcome = merge(
+                &repo,
+                &["conflicting"],
+                Options {
+                    strategy: strategy.clone(),
+                    ..Default::default()
+                },
+            )?;
+            assert!(!outcome.tree.has_conflicts(), "{strategy:?}");
+            assert!(content(&repo, &outcome.tree, "a.txt")?.0.starts_with(expected));
+        }
+
+        let outcome = merge(
+            &repo,
+            &["one", "two"],
+            Options {
+                strategy: Strategy::Ours,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(outcome.merged.len(), 2, "ours can merge any amount of commits");
+        assert!(!paths(&outcome).contains(&"one.txt".to_string()));
+
+        assert!(matches!(
+            merge(
+                &repo,
+                &["one", "two"],
+                Options {
+                    strategy: Strategy::Theirs,
+                    ..Default::default()
+                },
+            ),
+            Err(Error::TooManyCommits { count: 2, .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn renames_are_detected_if_enabled() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_merge_strategies_repo.sh")?;
+        let outcome = merge(&repo, &["renamed"], Default::default())?;
+        assert_eq!(
+            outcome.tree.conflicts[0].kind,
+            gix::merge::tree::ConflictKind::DeletedByThem,
+            "without rename detection, the rename is a deletion"
+        );
+
+        for percentage in [None, Some(0.5)] {
+            let outcome = merge(
+                &repo,
+                &["renamed"],
+                Options {
+                    renames: Some(Renames {
+                        percentage,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )?;
+            if percentage.is_none() {
+                assert!(
+                    outcome.tree.has_conflicts(),
+                    "the renamed file was changed, so it's not an exact rename"
+                );
+                continue;
+            }
+            assert!(!outcome.tree.has_conflicts());
+            assert!(!paths(&outcome).contains(&"long.txt".to_string()));
+            assert_eq!(
+                content(&repo, &outcome.tree, "moved.txt")?.0,
+                "ONE\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\neleven\n",
+                "our change was merged into the renamed file"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn subtree_merges_into_the_directory_of_the_subproject() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_merge_strategies_repo.sh")?;
+        for prefix in [None, Some("vendor/sub".into())] {
+            let outcome = merge(
+                &repo,
+                &["sub"],
+                Options {
+                    strategy: Strategy::Subtree { prefix: prefix.clone() },
+                    ..Default::default()
+                },
+            )?;
+            assert!(!outcome.tree.has_conflicts(), "{prefix:?}");
+            assert_eq!(outcome.merge_bases, [Some(id(&repo, "sub~1"))]);
+            assert_eq!(paths(&outcome), ["a.txt", "long.txt", "vendor/sub/lib.rs"]);
+            assert_eq!(
+                content(&repo, &outcome.tree, "vendor/sub/lib.rs")?.0,
+                "fn lib() {}\nfn more() {}\n"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn strategy_from_name() {
+        for (name, expected) in [
+            ("ort", Some(Strategy::Ort)),
+            ("recursive", Some(Strategy::Ort)),
+            ("ours", Some(Strategy::Ours)),
+            ("theirs", Some(Strategy::Theirs)),
+            ("subtree", Some(Strategy::Subtree { prefix: None })),
+            (
+                "subtree=vendor/sub/",
+                Some(Strategy::Subtree {
+                    prefix: Some("vendor/sub".into()),
+                }),
+            ),
+            ("octopus", Some(Strategy::Ort)),
+            ("resolve", None),
+        ] {
+            assert_eq!(Strategy::from_name(name.into()), expected, "{name}");
+        }
+    }
+}
+
+mod directory_renames {
+    use gix::merge::tree::{Conflict, ConflictKind, DirectoryRenames, Options, Renames};
+
+    fn options(config: &[&str]) -> gix::open::Options {
+        crate::restricted()
+            .strict_config(true)
+            .cli_overrides(config.iter().copied())
+    }
+
+    fn merge(
+        repo: &gix::Repository,
+        ours: &str,
+        theirs: &str,
+        directories: DirectoryRenames,
+    ) -> crate::Result<gix::merge::tree::Outcome> {
+        let id = |spec: &str| repo.rev_parse_single(spec).map(gix::Id::detach);
+        Ok(repo
+            .merge_commits(
+                id(ours)?,
+                Some(id(theirs)?),
+                Options {
+                    renames: Some(Renames {
+                        directories,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )?
+            .tree)
+    }
+
+    fn paths(outcome: &gix::merge::tree::Outcome) -> Vec<String> {
+        let index = &outcome.index;
+        index.entries().iter().map(|e| e.path(index).to_string()).collect()
+    }
+
+    #[test]
+    fn files_added_to_renamed_directories_are_moved_as_configured() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw_opts("make_merge_directory_renames_repo.sh", options(&[]))?;
+        let outcome = merge(&repo, "main", "added", DirectoryRenames::Ignore)?;
+        assert!(!outcome.has_conflicts());
+        assert_eq!(paths(&outcome), ["dir/new", "renamed/a", "renamed/b"]);
+
+        for (ours, theirs) in [("main", "added"), ("added", "main")] {
+            let outcome = merge(&repo, ours, theirs, DirectoryRenames::Apply)?;
+            assert!(!outcome.has_conflicts());
+            assert_eq!(paths(&outcome), ["renamed/a", "renamed/b", "renamed/new"]);
+
+            let outcome = merge(&repo, ours, theirs, DirectoryRenames::Conflict)?;
+            let version = outcome.conflicts[0].ours.or(outcome.conflicts[0].theirs);
+            assert_eq!(
+                outcome.conflicts,
+                [Conflict {
+                    path: "renamed/new".into(),
+                    kind: ConflictKind::FileLocation,
+                    base: None,
+                    ours: (ours == "added").then_some(version.expect("set")),
+                    theirs: (theirs == "added").then_some(version.expect("set")),
+                    merged_blob: None,
+                    original_path: Some("dir/new".into()),
+                }],
+                "the new location is suggested, and needs to be confirmed"
+            );
+            let entry = outcome
+                .index
+                .entry_by_path_and_stage("renamed/new".into(), if ours == "added" { 2 } else { 3 })
+                .expect("the file is present at its new location as conflict");
+            assert_eq!(entry.id, version.expect("set").id);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn options_are_configurable() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw_opts("make_merge_directory_renames_repo.sh", options(&[]))?;
+        let renames = repo.merge_tree_options()?.renames.expect("enabled by default");
+        assert_eq!(renames.directories, DirectoryRenames::Conflict);
+        assert_eq!(renames.limit, Renames::default().limit);
+
+        let (repo, _tmp) = crate::repo_rw_opts(
+            "make_merge_directory_renames_repo.sh",
+            options(&["merge.directoryRenames=true", "diff.renameLimit=10"]),
+        )?;
+        let renames = repo.merge_tree_options()?.renames.expect("enabled");
+        assert_eq!(renames.directories, DirectoryRenames::Apply);
+        assert_eq!(renames.limit, 100, "the limit is squared");
+
+        let (repo, _tmp) = crate::repo_rw_opts(
+            "make_merge_directory_renames_repo.sh",
+            options(&["diff.renames=false", "merge.renameLimit=5"]),
+        )?;
+        assert_eq!(repo.merge_tree_options()?.renames, None);
+        let (repo, _tmp) = crate::repo_rw_opts(
+            "make_merge_directory_renames_repo.sh",
+            options(&["diff.renames=false", "merge.renames=true", "merge.renameLimit=5"]),
+        )?;
+        assert_eq!(
+            repo.merge_tree_options()?.renames.expect("merge.renames wins").limit,
+            25
+        );
+
+        let (repo, _tmp) = crate::repo_rw_opts(
+            "make_merge_directory_renames_repo.sh",
+            options(&["merge.directoryRenames=maybe"]),
+        )?;
+        assert!(repo.merge_tree_options().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn cherry_pick_uses_configured_directory_renames() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw_opts("make_merge_directory_renames_repo.sh", options(&[]))?;
+        let workdir = repo.work_dir().expect("non-bare").to_owned();
+        let outcome = repo.cherry_pick(repo.rev_parse_single("added")?.detach())?;
+        assert_eq!(outcome.commit, None);
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert_eq!(outcome.conflicts[0].kind, ConflictKind::FileLocation);
+        assert_eq!(
+            std::fs::read_to_string(workdir.join("renamed/new"))?,
+            "new\n",
+            "the file is placed at the suggested location"
+        );
+        assert!(!workdir.join("dir").exists());
+
+        let (repo, _tmp) = crate::repo_rw_opts(
+            "make_merge_directory_renames_repo.sh",
+            options(&["merge.directoryRenames=true"]),
+        )?;
+        let workdir = repo.work_dir().expect("non-bare").to_owned();
+        let outcome = repo.cherry_pick(repo.rev_parse_single("added")?.detach())?;
+        assert!(outcome.conflicts.is_empty());
+        let tree = repo
+            .find_object(outcome.commit.expect("committed"))?
+            .into_commit()
+            .tree()?;
+        assert!(tree.lookup_entry_by_path("renamed/new", &mut Vec::new())?.is_some());
+        assert_eq!(std::fs::read_to_string(workdir.join("renamed/new"))?, "new\n");
+        Ok(())
+    }
+}
@@ -1,18 +1,58 @@
 use gix::Repository;
 
+#[cfg(feature = "apply")]
+mod apply;
+#[cfg(feature = "blob-diff")]
+mod bare;
+#[cfg(feature = "blame")]
+mod blame;
+#[cfg(feature = "bundle")]
+mod bundle;
+#[cfg(feature = "checkout")]
+mod checkout;
+#[cfg(feature = "merge")]
+mod cherry_pick;
+mod commit_graph;
 mod config;
 #[cfg(feature = "attributes")]
 mod filter;
+#[cfg(feature = "fsck")]
+mod fsck;
+#[cfg(feature = "hooks")]
+mod hooks;
+#[cfg(feature = "attributes")]
+mod linguist;
+#[cfg(feature = "mailmap")]
+mod mailmap;
+#[cfg(feature = "maintenance")]
+mod maintenance;
+#[cfg(feature = "merge")]
+mod merge;
+#[cfg(feature = "notes")]
+mod notes;
 mod object;
 mod open;
 #[cfg(feature = "attributes")]
 mod pathspec;
+#[cfg(feature = "rebase")]
+mod rebase;
 mod reference;
 mod remote;
+#[cfg(feature = "merge")]
+mod rerere;
 mod shallow;
+#[cfg(feature = "shortlog")]
+mod shortlog;
+#[cfg(feature = "sparse-checkout")]
+mod sparse_checkout;
+#[cfg(feature = "stash")]
+mod stash;
 mod state;
+#[cfg(feature = "status")]
+mod status;
 #[cfg(feature = "attributes")]
 mod submodule;
+mod thread_safe;
 mod worktree;
 
 #[cfg(feature = "dirwalk")]
@@ -45,6 +85,34 @@ mod dirwalk {
     }
 }
 
+#[cfg(feature = "attributes")]
+mod smudge_racily_clean_entries {
+    use gix::index::entry::Stat;
+
+    #[test]
+    fn entries_whose_content_changed_despite_matching_stat_information_are_smudged() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_basic_repo.sh")?;
+        let path = repo.work_dir().expect("non-bare").join("this");
+        let mut index = repo.open_index()?;
+
+        for (content, expected_smudged) in [(&b"hello\n"[..], 0), (b"HELLO\n", 1)] {
+            std::fs::write(&path, content)?;
+            let stat = Stat::from_fs(&gix::index::fs::Metadata::from_path_no_follow(&path)?)?;
+            let entry = index.entry_mut_by_path_and_stage("this".into(), 0).expect("present");
+            entry.stat = stat;
+            index.set_timestamp(filetime::FileTime::from_unix_time(
+                stat.mtime.secs.into(),
+                stat.mtime.nsecs,
+            ));
+
+            assert_eq!(repo.smudge_racily_clean_entries(&mut index)?, expected_smudged);
+            let size = index.entry_by_path("this".into()).expect("present").stat.size;
+            assert_eq!(size, if expected_smudged == 0 { 6 } else { 0 });
+        }
+        Ok(())
+    }
+}
+
 #[test]
 fn size_in_memory() {
     let actual_size = std::mem::size_of::<Repository>();
@@ -0,0 +1,247 @@
+use gix::{bstr::ByteSlice, notes::merge::Strategy};
+use gix_ref::transaction::PreviousValue;
+
+fn message(notes: &gix::notes::Platform<'_>, object: gix::ObjectId) -> crate::Result<Option<String>> {
+    Ok(notes.message(object)?.map(|data| data.to_str_lossy().into_owned()))
+}
+
+#[test]
+fn read() -> crate::Result {
+    let repo = crate::named_repo("make_notes_repo.sh")?;
+    let (first, second) = (
+        repo.rev_parse_single("HEAD~1")?.detach(),
+        repo.rev_parse_single("HEAD")?.detach(),
+    );
+    let notes = repo.default_notes()?;
+    assert_eq!(notes.name().as_bstr(), "refs/notes/commits");
+    assert_eq!(notes.len(), 2);
+    assert!(notes.commit_id().is_some());
+    assert_eq!(message(&notes, first)?.as_deref(), Some("note on first\n"));
+    assert_eq!(message(&notes, second)?.as_deref(), Some("note on second\n"));
+    let blob = repo.rev_parse_single("HEAD:a")?;
+    assert_eq!(notes.find(blob), None, "objects without note");
+    assert_eq!(message(&notes, blob.detach())?, None);
+
+    let notes = repo.notes("fanout")?;
+    assert_eq!(
+        notes.len(),
+        1,
+        "notes in fan-out directories are found, files that aren't notes are ignored"
+    );
+    assert_eq!(message(&notes, first)?.as_deref(), Some("fanned out\n"));
+
+    let notes = repo.notes("refs/notes/missing")?;
+    assert!(notes.is_empty());
+    assert_eq!(notes.commit_id(), None, "the reference doesn't exist");
+    Ok(())
+}
+
+#[test]
+fn names_are_expanded_like_git_does() -> crate::Result {
+    let repo = crate::named_repo("make_notes_repo.sh")?;
+    for (name, expected) in [
+        ("commits", "refs/notes/commits"),
+        ("notes/commits", "refs/notes/commits"),
+        ("refs/notes/commits", "refs/notes/commits"),
+        ("refs/heads/main", "refs/notes/refs/heads/main"),
+    ] {
+        assert_eq!(repo.notes(name)?.name().as_bstr(), expected);
+    }
+
+    let repo: gix::Repository = gix::open_opts(
+        repo.path(),
+        crate::restricted().config_overrides(["core.notesRef=fanout"]),
+    )?;
+    assert_eq!(
+        repo.default_notes()?.name().as_bstr(),
+        "refs/notes/fanout",
+        "the default can be configured"
+    );
+    Ok(())
+}
+
+#[test]
+fn add_append_copy_remove_and_commit() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_notes_repo.sh")?;
+    let (first, second) = (
+        repo.rev_parse_single("HEAD~1")?.detach(),
+        repo.rev_parse_single("HEAD")?.detach(),
+    );
+    let blob = repo.rev_parse_single("HEAD:a")?.detach();
+    let mut notes = repo.default_notes()?;
+    let previous = notes.commit_id().expect("present").detach();
+    assert_eq!(
+        notes.commit("nothing to do")?,
+        None,
+        "nothing to commit without changes"
+    );
+
+    assert!(matches!(
+        notes.add(first, "replaced\n", false),
+        Err(gix::notes::Error::NoteExists { id }) if id == first
+    ));
+    notes.add(first, "replaced\n", true)?;
+    notes.append(first, "appended\n")?;
+    assert_eq!(message(&notes, first)?.as_deref(), Some("replaced\n\nappended\n"));
+    notes.append(blob, "new\n")?;
+    assert_eq!(
+        message(&notes, blob)?.as_deref(),
+        Some("new\n"),
+        "appending to nothing adds a note"
+    );
+
+    assert!(
+        notes.copy(second, first, false).is_err(),
+        "won't overwrite without permission"
+    );
+    let note = notes.copy(second, first, true)?;
+    assert_eq!(notes.find(first).expect("copied"), note);
+    assert_eq!(notes.remove(second), Some(note));
+    assert_eq!(notes.remove(second), None, "it's gone");
+    assert!(matches!(
+        notes.copy(second, blob, true),
+        Err(gix::notes::Error::NoNote { id }) if id == second
+    ));
+    assert!(notes.has_changes());
+
+    let commit = notes.commit("Notes added by 'git notes add'")?.expect("changes");
+    assert!(!notes.has_changes());
+    let commit = repo.find_object(commit)?.into_commit();
+    assert_eq!(commit.parent_ids().map(gix::Id::detach).collect::<Vec<_>>(), [previous]);
+    assert_eq!(commit.message_raw()?, "Notes added by 'git notes add'\n");
+    assert_eq!(repo.find_reference("refs/notes/commits")?.id(), commit.id);
+
+    let notes = repo.default_notes()?;
+    assert_eq!(notes.len(), 2);
+    assert_eq!(message(&notes, first)?.as_deref(), Some("note on second\n"));
+    assert_eq!(message(&notes, blob)?.as_deref(), Some("new\n"));
+    assert_eq!(notes.find(second), None);
+    Ok(())
+}
+
+#[test]
+fn fan_out_is_used_for_many_notes_and_other_files_are_kept() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_notes_repo.sh")?;
+    let mut notes = repo.notes("fanout")?;
+    let mut objects = Vec::new();
+    for index in 0..300 {
+        let object = repo.write_blob(format!("object {index}"))?.detach();
+        notes.add(object, format!("note {index}\n"), false)?;
+        objects.push(object);
+    }
+    let commit = notes.commit("many notes")?.expect("changes");
+
+    let tree = repo.find_object(commit)?.into_commit().tree()?;
+    assert!(tree.find_entry("README").is_some(), "files that aren't notes are kept");
+    let hex = objects[0].to_hex().to_string();
+    assert!(
+        tree.lookup_entry_by_path(format!("{}/{}", &hex[..2], &hex[2..]), &mut Vec::new())?
+            .is_some(),
+        "notes are spread across directories"
+    );
+
+    let notes = repo.notes("fanout")?;
+    assert_eq!(notes.len(), 301);
+    assert_eq!(message(&notes, objects[299])?.as_deref(), Some("note 299\n"));
+    Ok(())
+}
+
+#[test]
+fn merge() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_notes_repo.sh")?;
+    let (first, second) = (
+        repo.rev_parse_single("HEAD~1")?.detach(),
+        repo.rev_parse_single("HEAD")?.detach(),
+    );
+    let base = repo.find_reference("refs/notes/commits")?.id().detach();
+    repo.reference("refs/notes/other", base, PreviousValue::MustNotExist, "copy")?;
+
+    let mut theirs = repo.notes("other")?;
+    theirs.add(first, "theirs\n", true)?;
+    theirs.add(second, "theirs on second\n", true)?;
+    theirs.commit("change notes")?;
+
+    let outcome = repo.notes("refs/notes/new")?.merge(&theirs, Strategy::Manual)?;
+    assert!(outcome.fast_forward, "there is nothing to merge with");
+    assert_eq!(outcome.commit, theirs.commit_id().map(gix::Id::detach));
+    assert_eq!(
+        repo.notes("new")?.merge(&theirs, Strategy::Manual)?,
+        Default::default(),
+        "nothing to do if they are merged already"
+    );
+
+    let mut ours = repo.default_notes()?;
+    ours.add(first, "ours\n", true)?;
+    let ours_commit = ours.commit("change notes")?.expect("changed");
+    assert!(matches!(
+        {
+            ours.add(first, "uncommitted", true)?;
+            ours.merge(&theirs, Strategy::Ours)
+        },
+        Err(gix::notes::Error::PendingChanges)
+    ));
+
+    let mut ours = repo.default_notes()?;
+    let outcome = ours.merge(&theirs, Strategy::Manual)?;
+    assert_eq!(outcome.conflicts, [first]);
+    assert_eq!(outcome.commit, None, "nothing is merged if there are conflicts");
+    assert_eq!(repo.find_reference("refs/notes/commits")?.id(), ours_commit);
+
+    assert_eq!(ours.merge_strategy()?, Strategy::Manual, "the default");
+    let outcome = ours.merge(&theirs, Strategy::Union)?;
+    assert_eq!(outcome.conflicts, [first]);
+    assert!(!outcome.fast_forward);
+    let merge_commit = repo.find_object(outcome.commit.expect("merged"))?.into_commit();
+    assert_eq!(
+        merge_commit.parent_ids().map(gix::Id::detach).collect::<Vec<_>>(),
+        [ours_commit, theirs.commit_id().expect("present").detach()]
+    );
+    assert_eq!(
+        merge_commit.message_raw()?,
+        "Merged notes from refs/notes/other into refs/notes/commits\n"
+    );
+    assert_eq!(message(&ours, first)?.as_deref(), Some("ours\n\ntheirs\n"));
+    assert_eq!(
+        message(&ours, second)?.as_deref(),
+        Some("theirs on second\n"),
+        "changes on one side are taken"
+    );
+    assert_eq!(repo.default_notes()?.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn merge_strategies() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_notes_repo.sh")?;
+    let first = repo.rev_parse_single("HEAD~1")?.detach();
+    let base = repo.find_reference("refs/notes/commits")?.id().detach();
+    for (strategy, expected) in [
+        (Strategy::Ours, "b\na\n"),
+        (Strategy::Theirs, "c\na\n"),
+        (Strategy::Union, "b\na\n\nc\na\n"),
+        (Strategy::CatSortUniq, "a\nb\nc\n"),
+    ] {
+        let ours_name = format!("refs/notes/ours-{strategy:?}");
+        let theirs_name = format!("refs/notes/theirs-{strategy:?}");
+        repo.reference(ours_name.as_str(), base, PreviousValue::MustNotExist, "copy")?;
+        repo.reference(theirs_name.as_str(), base, PreviousValue::MustNotExist, "copy")?;
+        let mut ours = repo.notes(ours_name.as_str())?;
+        ours.add(first, "b\na\n", true)?;
+        ours.commit("ours")?;
+        let mut theirs = repo.notes(theirs_name.as_str())?;
+        theirs.add(first, "c\na\n", true)?;
+        theirs.commit("theirs")?;
+
+        let outcome = ours.merge(&theirs, strategy)?;
+        assert_eq!(outcome.conflicts, [first]);
+        assert_eq!(message(&ours, first)?.as_deref(), Some(expected), "{strategy:?}");
+    }
+
+    let repo: gix::Repository = gix::open_opts(
+        repo.path(),
+        crate::restricted().config_overrides(["notes.mergeStrategy=union", "notes.commits.mergeStrategy=theirs"]),
+    )?;
+    assert_eq!(repo.default_notes()?.merge_strategy()?, Strategy::Theirs);
+    assert_eq!(repo.notes("other")?.merge_strategy()?, Strategy::Union);
+    Ok(())
+}
@@ -193,6 +193,8 @@ mod find {
 }
 
 mod tag {
+    use gix::bstr::{BStr, ByteSlice};
+
     #[test]
     fn simple() -> crate::Result {
         let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
@@ -220,9 +222,67 @@ mod tag {
         assert_eq!(tag.message, message);
         Ok(())
     }
+
+    #[test]
+    fn signed() -> crate::Result {
+        let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+        let current_head_id = repo.head_id()?;
+        let payload = std::cell::RefCell::new(Vec::new());
+        let signer = |data: &[u8]| -> Result<gix::bstr::BString, gix::sign::Error> {
+            *payload.borrow_mut() = data.to_owned();
+            Ok(super::SIGNATURE.into())
+        };
+        let tag_ref = repo.tag_signed(
+            "v1.0.0",
+            current_head_id,
+            gix_object::Kind::Commit,
+            Some(repo.committer().expect("present")?),
+            "message",
+            gix_ref::transaction::PreviousValue::MustNotExist,
+            &signer,
+        )?;
+        let tag = tag_ref.id().object()?;
+        let payload = payload.into_inner();
+        assert_eq!(
+            tag.data,
+            [payload.as_slice(), super::SIGNATURE.as_bytes()].concat(),
+            "the signature is appended to the signed data"
+        );
+        assert!(payload.ends_with(b"\n\nmessage\n"));
+
+        let tag_ref = tag.try_to_tag_ref()?;
+        assert_eq!(tag_ref.message, "message");
+        assert_eq!(tag_ref.pgp_signature.expect("signed"), super::SIGNATURE);
+
+        let tag = tag.into_tag();
+        assert_eq!(
+            tag.signature()?,
+            Some((super::SIGNATURE.into(), payload.as_bstr())),
+            "the signature can be split from the signed data"
+        );
+        tag.verify_signature(
+            &|signature: &BStr, signed_data: &[u8]| -> Result<(), gix::sign::Error> {
+                assert_eq!(signature, super::SIGNATURE);
+                assert_eq!(
+                    gix::sign::Format::from_signature(signature),
+                    Some(gix::sign::Format::OpenPgp)
+                );
+                assert_eq!(signed_data, payload, "the verifier sees what the signer saw");
+                Ok(())
+            },
+        )?;
+        assert!(matches!(
+            tag.verify_signature(&|_: &BStr, _: &[u8]| -> Result<(), gix::sign::Error> { Err("bad signature".into()) }),
+            Err(gix::sign::verify::Error::Verify(err)) if err.to_string() == "bad signature"
+        ));
+        Ok(())
+    }
 }
 
+const SIGNATURE: &str = "-----BEGIN PGP SIGNATURE-----\n\niQEzBAABCAAdFiEE\n-----END PGP SIGNATURE-----\n";
+
 mod commit_as {
+    use gix::bstr::BStr;
     use gix_testtools::tempfile;
 
     use crate::util::restricted_and_git;
@@ -263,6 +323,73 @@ mod commit_as {
         assert_eq!(commit.author()?, author.to_ref());
         Ok(())
     }
+
+    #[test]
+    fn signed() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo = gix::ThreadSafeRepository::init_opts(
+            &tmp,
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted_and_git(),
+        )?
+        .to_thread_local();
+        let signature = gix::actor::Signature {
+            name: "c".into(),
+            email: "c@example.com".into(),
+            time: gix::date::Time::new(1, 1800),
+        };
+        let payload = std::cell::RefCell::new(Vec::new());
+        let signer = |data: &[u8]| -> Result<gix::bstr::BString, gix::sign::Error> {
+            *payload.borrow_mut() = data.to_owned();
+            Ok(super::SIGNATURE.into())
+        };
+
+        let commit_id = repo.commit_as_signed(
+            &signature,
+            &signature,
+            "HEAD",
+            "initial",
+            repo.empty_tree().id,
+            gix::commit::NO_PARENT_IDS,
+            &signer,
+        )?;
+        let commit = commit_id.object()?;
+        let payload = payload.into_inner();
+        let (pgp_signature, signed_data) =
+            gix::objs::CommitRefIter::signature(&commit.data)?.expect("the commit is signed");
+        assert_eq!(
+            signed_data.to_bstring(),
+            payload,
+            "the signer sees exactly what will be verified later"
+        );
+        assert_eq!(
+            pgp_signature.as_ref(),
+            super::SIGNATURE.trim_end(),
+            "the signature is stored without its trailing newline, which terminates the header instead"
+        );
+        assert!(
+            commit.data.ends_with(
+                b"gpgsig -----BEGIN PGP SIGNATURE-----\n \n iQEzBAABCAAdFiEE\n -----END PGP SIGNATURE-----\n\ninitial"
+            ),
+            "all lines of the signature are indented, including empty ones, just like git does it"
+        );
+
+        let commit = commit.into_commit();
+        assert_eq!(commit.message_raw()?, "initial");
+        assert_eq!(
+            commit.decode()?.extra_headers().pgp_signature().expect("present"),
+            super::SIGNATURE.trim_end()
+        );
+        commit.verify_signature(
+            &|signature: &BStr, signed_data: &[u8]| -> Result<(), gix::sign::Error> {
+                assert_eq!(signature, super::SIGNATURE.trim_end());
+                assert_eq!(signed_data, payload, "the verifier sees what the signer saw");
+                Ok(())
+            },
+        )?;
+        Ok(())
+    }
 }
 
 mod commit {
@@ -289,6 +416,11 @@ mod commit {
             "Reference \"refs/heads/main\" was supposed to exist with value 4b825dc642cb6eb9a060e54bf8d69288fbee4904, but didn't.",
             "cannot provide parent id in initial commit"
         );
+        assert_eq!(
+            gix::error::ErrorCode::code(&err),
+            gix::error::Code::Conflict,
+            "the reference didn't have the expected value"
+        );
         Ok(())
     }
 
@@ -326,6 +458,44 @@ mod commit {
         Ok(())
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn commits_and_tags_use_dates_from_the_environment_in_raw_format() -> crate::Result {
+        let _env = freeze_time()
+            .set("GIT_AUTHOR_DATE", "@1660874655 +0800")
+            .set("GIT_COMMITTER_DATE", "1660874700 -0130");
+        let (repo, _keep) = crate::repo_rw_opts("make_basic_repo.sh", restricted_and_git())?;
+        let parent = repo.head_id()?;
+        let tree_id = parent.object()?.into_commit().tree_id()?;
+        let commit_id = repo.commit("HEAD", "raw dates", tree_id, Some(parent))?;
+
+        let commit = commit_id.object()?.into_commit();
+        let author = commit.author()?;
+        assert_eq!((author.time.seconds, author.time.offset), (1660874655, 8 * 3600));
+        let committer = commit.committer()?;
+        assert_eq!(
+            (committer.time.seconds, committer.time.offset),
+            (1660874700, -(3600 + 30 * 60))
+        );
+
+        let tagger = repo.committer().expect("configured")?;
+        let tag = repo.tag(
+            "raw-dates",
+            commit_id,
+            gix_object::Kind::Commit,
+            Some(tagger),
+            "a message",
+            gix_ref::transaction::PreviousValue::MustNotExist,
+        )?;
+        let tag = tag.id().object()?.into_tag();
+        assert_eq!(
+            tag.tagger()?.expect("present").time,
+            committer.time,
+            "taggers are committers"
+        );
+        Ok(())
+    }
+
     #[test]
     #[serial_test::serial]
     fn multi_line_commit_message_uses_first_line_in_ref_log_ref_nonexisting() -> crate::Result {
@@ -383,3 +383,42 @@ mod worktree {
         Ok(())
     }
 }
+
+mod reuse_global_config {
+    use gix_testtools::Env;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn global_configuration_is_reloaded_once_it_changes() -> crate::Result {
+        let tmp = gix_testtools::tempfile::tempdir()?;
+        let global = tmp.path().join("global.config");
+        std::fs::write(&global, "[user]\n\tname = first\n")?;
+        let _env = Env::new()
+            .set("GIT_CONFIG_GLOBAL", global.to_str().expect("valid UTF-8"))
+            .set("GIT_CONFIG_NOSYSTEM", "1");
+        let repo_dir = gix::init(tmp.path().join("repo"))?
+            .work_dir()
+            .expect("non-bare")
+            .to_owned();
+
+        let user_name = || -> crate::Result<Option<String>> {
+            let repo = gix::open_opts(&repo_dir, gix::open::Options::lightweight())?;
+            let name = repo.config_snapshot().string("user.name").map(|name| name.to_string());
+            Ok(name)
+        };
+        assert_eq!(user_name()?.as_deref(), Some("first"));
+        assert_eq!(user_name()?.as_deref(), Some("first"), "the same when reused");
+
+        std::fs::write(&global, "[user]\n\tname = second\n\temail = changed@example.com\n")?;
+        assert_eq!(
+            user_name()?.as_deref(),
+            Some("second"),
+            "changes to the file are picked up"
+        );
+
+        std::fs::remove_file(&global)?;
+        assert_eq!(user_name()?, None, "files that vanish are noticed as well");
+        Ok(())
+    }
+}
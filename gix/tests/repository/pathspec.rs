@@ -37,3 +37,45 @@ fn defaults_are_taken_from_repo_config() -> crate::Result {
     );
     Ok(())
 }
+
+#[test]
+fn attributes_resolve_user_defined_macros() -> crate::Result {
+    let repo = named_repo("make_pathspec_attributes_repo.sh")?;
+    let index = repo.index()?;
+    let matches = |spec: &str| -> crate::Result<Vec<&str>> {
+        let mut pathspec = repo.pathspec(
+            Some(spec),
+            false,
+            &index,
+            Source::WorktreeThenIdMapping.adjust_for_bare(repo.is_bare()),
+        )?;
+        Ok(["a.bin", "b.dat", "c.txt", "sub/d.sub", "sub/e.bin"]
+            .into_iter()
+            .filter(|path| pathspec.is_included(*path, Some(false)))
+            .collect())
+    };
+
+    assert_eq!(
+        matches(":(attr:mybin)")?,
+        ["a.bin", "b.dat", "sub/e.bin"],
+        "macros are attributes themselves, and can be set by other macros"
+    );
+    assert_eq!(
+        matches(":(attr:-diff -text)")?,
+        ["a.bin", "b.dat", "sub/e.bin"],
+        "macros expand into their attributes"
+    );
+    assert_eq!(
+        matches(":(attr:custom=value)")?,
+        ["b.dat"],
+        "nested macros are expanded"
+    );
+    assert_eq!(matches(":(attr:text)")?, ["c.txt"]);
+    assert_eq!(
+        matches(":(attr:!text)")?,
+        ["sub/d.sub"],
+        "macros defined outside the top-level attributes file are ignored"
+    );
+    assert_eq!(matches(":(attr:-text)")?, ["a.bin", "b.dat", "sub/e.bin"]);
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use gix::{bstr::ByteSlice, rebase::Options};
+
+fn subjects(repo: &gix::Repository) -> crate::Result<Vec<String>> {
+    let mut out = Vec::new();
+    for info in repo.rev_walk(Some(repo.head_id()?)).all()? {
+        let commit = info?.object()?;
+        out.push(commit.message()?.summary().to_string());
+    }
+    Ok(out)
+}
+
+fn read(repo: &gix::Repository, path: &str) -> crate::Result<String> {
+    Ok(std::fs::read(repo.work_dir().expect("non-bare").join(path))?
+        .to_str()?
+        .to_owned())
+}
+
+#[test]
+fn replays_commits_onto_upstream_and_skips_those_that_are_applied_already() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_rebase_repo.sh", "feature")?;
+    let upstream = repo.rev_parse_single("main")?.detach();
+    let orig_head = repo.head_id()?.detach();
+    let already_applied = repo.rev_parse_single("feature~1")?.detach();
+
+    let outcome = repo.rebase(upstream, Options::default())?;
+    assert!(outcome.stopped.is_none());
+    assert_eq!(outcome.replayed.len(), 2);
+    assert_eq!(
+        outcome.skipped,
+        [already_applied],
+        "the change to 'a' is present in upstream already"
+    );
+
+    assert_eq!(
+        repo.head_name()?.expect("branch is checked out").as_bstr(),
+        "refs/heads/feature"
+    );
+    assert_eq!(repo.head_id()?, outcome.replayed[1].rewritten);
+    assert_eq!(
+        subjects(&repo)?,
+        ["feature adds c", "feature changes b", "main changes a", "base"]
+    );
+    assert_eq!(read(&repo, "a")?, "1\n2\n3\nmain\n");
+    assert_eq!(read(&repo, "b")?, "2\n");
+    assert_eq!(read(&repo, "c")?, "c\n");
+    assert_eq!(repo.state(), None, "the rebase is complete");
+    assert_eq!(repo.find_reference("ORIG_HEAD")?.id(), orig_head);
+    assert_eq!(
+        repo.index()?.entries().len(),
+        3,
+        "the index matches the new HEAD, as does the worktree"
+    );
+    assert!(
+        repo.rebase(upstream, Options::default())?.replayed.is_empty(),
+        "nothing to do once rebased"
+    );
+    Ok(())
+}
+
+#[test]
+fn reapplying_cherry_picks_drops_commits_that_become_empty() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_rebase_repo.sh", "feature")?;
+    let upstream = repo.rev_parse_single("main")?.detach();
+    let outcome = repo.rebase(
+        upstream,
+        Options {
+            reapply_cherry_picks: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.replayed.len(), 2);
+    assert_eq!(outcome.skipped.len(), 1, "the commit became empty and was dropped");
+    Ok(())
+}
+
+#[test]
+fn refuses_to_start_with_changes_in_the_worktree() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_rebase_repo.sh", "feature")?;
+    std::fs::write(repo.work_dir().expect("non-bare").join("b"), "changed\n")?;
+    let err = repo
+        .rebase(repo.rev_parse_single("main")?, Options::default())
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        gix::rebase::Error::RequireClean(gix::merge::worktree::require_clean::Error::Dirty { ref paths }) if paths == &["b"]
+    ));
+    assert_eq!(repo.state(), None);
+    Ok(())
+}
+
+#[test]
+fn stops_on_conflict_and_continues_once_resolved() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_rebase_repo.sh", "conflicting")?;
+    let upstream = repo.rev_parse_single("main")?.detach();
+    let outcome = repo.rebase(upstream, Options::default())?;
+    let stopped = outcome.stopped.expect("conflict in 'a'");
+    assert_eq!(stopped.commit, repo.rev_parse_single("conflicting~1")?);
+    assert_eq!(stopped.conflicts.len(), 1);
+    assert_eq!(stopped.conflicts[0].path, "a");
+    assert_eq!(repo.state(), Some(gix::state::InProgress::Rebase));
+    assert_eq!(repo.head_name()?, None, "HEAD is detached during the rebase");
+    assert_eq!(repo.head_id()?, upstream);
+    assert!(read(&repo, "a")?.contains("<<<<<<< HEAD\nmain\n=======\nconflicting\n>>>>>>> "));
+
+    let state = repo.rebase_state()?.expect("rebase in progress");
+    assert_eq!(
+        state.head_name.expect("branch").as_bstr(),
+        "refs/heads/conflicting",
+        "the state can be read back, as git would"
+    );
+    assert_eq!(state.todo.instructions.len(), 1);
+    assert_eq!(state.done.instructions.len(), 1);
+    assert_eq!(state.stopped_at, Some(stopped.commit));
+
+    assert!(
+        matches!(
+            repo.rebase_continue(),
+            Err(gix::rebase::Error::UnresolvedConflicts { .. })
+        ),
+        "conflicts have to be resolved first"
+    );
+
+    let resolved = "1\n2\n3\nmain\nconflicting\n";
+    let path = repo.work_dir().expect("non-bare").join("a");
+    std::fs::write(&path, resolved)?;
+    let mut index = repo.open_index()?;
+    index.remove_entries(|_, path, _| path == "a");
+    index.dangerously_push_entry(
+        gix::index::entry::Stat::from_fs(&gix::index::fs::Metadata::from_path_no_follow(&path)?)?,
+        repo.write_blob(resolved)?.detach(),
+        gix::index::entry::Flags::empty(),
+        gix::index::entry::Mode::FILE,
+        "a".into(),
+    );
+    index.sort_entries();
+    index.write(Default::default())?;
+
+    let outcome = repo.rebase_continue()?;
+    assert!(outcome.stopped.is_none());
+    assert_eq!(outcome.replayed.len(), 2, "the resolved commit and the one after it");
+    assert_eq!(
+        repo.head_name()?.expect("branch is checked out").as_bstr(),
+        "refs/heads/conflicting"
+    );
+    assert_eq!(
+        subjects(&repo)?,
+        [
+            "conflicting changes b",
+            "conflicting changes a",
+            "main changes a",
+            "base"
+        ]
+    );
+    assert_eq!(read(&repo, "a")?, resolved);
+    assert_eq!(repo.state(), None);
+    Ok(())
+}
+
+#[test]
+fn abort_restores_the_original_state() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_rebase_repo.sh", "conflicting")?;
+    let orig_head = repo.head_id()?.detach();
+    let outcome = repo.rebase(repo.rev_parse_single("main")?, Options::default())?;
+    assert!(outcome.stopped.is_some());
+
+    repo.rebase_abort()?;
+    assert_eq!(repo.state(), None);
+    assert_eq!(repo.head_id()?, orig_head);
+    assert_eq!(
+        repo.head_name()?.expect("branch is checked out").as_bstr(),
+        "refs/heads/conflicting"
+    );
+    assert_eq!(read(&repo, "a")?, "1\n2\n3\nconflicting\n");
+    assert!(repo.index()?.entries().iter().all(|e| e.stage() == 0));
+    assert!(matches!(repo.rebase_abort(), Err(gix::rebase::Error::NotInProgress)));
+    Ok(())
+}
+
+#[test]
+fn commits_merged_from_upstream_are_not_replayed() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_rebase_repo.sh", "merged")?;
+    let merged_upstream_commit = repo.rev_parse_single("main~1")?.detach();
+    let outcome = repo.rebase(repo.rev_parse_single("main")?, Options::default())?;
+    assert!(outcome.stopped.is_none());
+    assert!(
+        outcome
+            .replayed
+            .iter()
+            .map(|replayed| replayed.original)
+            .chain(outcome.skipped.iter().copied())
+            .all(|id| id != merged_upstream_commit),
+        "commits reachable from upstream are excluded even if the branch merged them"
+    );
+    assert_eq!(
+        subjects(&repo)?,
+        [
+            "feature adds d after merging main",
+            "feature adds c",
+            "feature changes b",
+            "main adds e",
+            "main changes a",
+            "base"
+        ],
+        "the merge is dropped, and the change to 'a' is empty after the rebase"
+    );
+    assert_eq!(read(&repo, "e")?, "e\n");
+    Ok(())
+}
+
+#[test]
+fn only_commits_of_the_branch_are_replayed_if_upstream_has_more_commits() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_rebase_repo.sh", "upstream-ahead")?;
+    let outcome = repo.rebase(repo.rev_parse_single("main")?, Options::default())?;
+    let stopped = outcome.stopped.expect("'a' was changed on both sides");
+    assert_eq!(stopped.commit, repo.rev_parse_single("conflicting~1")?.detach());
+    let todo = repo.rebase_state()?.expect("in progress").todo;
+    assert_eq!(
+        todo.instructions.len(),
+        1,
+        "only the commits of the branch are replayed, even though all commit times are the same"
+    );
+    Ok(())
+}
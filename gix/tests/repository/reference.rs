@@ -224,3 +224,117 @@ mod head {
         Ok(())
     }
 }
+
+mod reflog_expire {
+    use std::time::{Duration, SystemTime};
+
+    use gix::reference::log::expire::{Outcome, Policy};
+
+    /// The time at which all reference log entries of the fixture were written.
+    fn fixture_time() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(946_771_200)
+    }
+
+    fn num_entries(repo: &gix::Repository, name: &str) -> crate::Result<usize> {
+        let reference = repo.find_reference(name)?;
+        let mut platform = reference.log_iter();
+        Ok(platform.all()?.expect("present").count())
+    }
+
+    #[test]
+    fn entries_expire_by_age_and_reachability() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_maintenance_repo.sh")?;
+        let name = "refs/heads/main".try_into()?;
+        assert_eq!(num_entries(&repo, "main")?, 4);
+
+        let outcome = repo.reflog_expire(
+            name,
+            Policy {
+                expire: Some(fixture_time()),
+                expire_unreachable: None,
+            },
+        )?;
+        assert_eq!(
+            outcome,
+            Outcome {
+                num_removed: 0,
+                num_kept: 4
+            },
+            "entries that were written at the cutoff are kept"
+        );
+
+        let outcome = repo.reflog_expire(
+            name,
+            Policy {
+                expire: None,
+                expire_unreachable: Some(fixture_time() + Duration::from_secs(1)),
+            },
+        )?;
+        assert_eq!(
+            outcome,
+            Outcome {
+                num_removed: 1,
+                num_kept: 3
+            },
+            "the third commit was reset away and isn't reachable from `main` anymore"
+        );
+        let third = repo.rev_parse_single("other")?.detach();
+        let main = repo.find_reference("main")?;
+        let mut platform = main.log_iter();
+        assert!(platform
+            .all()?
+            .expect("present")
+            .all(|line| line.map_or(false, |line| line.new_oid() != third)));
+
+        let outcome = repo.reflog_expire(
+            name,
+            Policy {
+                expire: Some(SystemTime::now()),
+                expire_unreachable: None,
+            },
+        )?;
+        assert_eq!(
+            outcome,
+            Outcome {
+                num_removed: 3,
+                num_kept: 0
+            }
+        );
+        assert_eq!(num_entries(&repo, "main")?, 0, "the log is kept, but empty");
+
+        assert_eq!(
+            repo.reflog_expire("refs/heads/missing".try_into()?, Default::default())?,
+            Outcome::default(),
+            "it's not an error if there is no log"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn policy_from_configuration() -> crate::Result {
+        let repo = crate::named_repo("make_maintenance_repo.sh")?;
+        let policy = repo.reflog_expire_policy("refs/heads/main".try_into()?)?;
+        let days_ago = |days: u64| SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60);
+        assert!(policy.expire.expect("set") <= days_ago(90), "the default is 90 days");
+        assert!(policy.expire.expect("set") > days_ago(91));
+        assert!(policy.expire_unreachable.expect("set") <= days_ago(30));
+        assert!(policy.expire_unreachable.expect("set") > days_ago(31));
+        assert_eq!(
+            repo.reflog_expire_policy("refs/stash".try_into()?)?,
+            Policy::default(),
+            "the stash never expires"
+        );
+
+        let repo: gix::Repository = gix::open_opts(
+            repo.path(),
+            crate::restricted().config_overrides(["gc.reflogExpire=never", "gc.reflogExpireUnreachable=2000-01-01"]),
+        )?;
+        let policy = repo.reflog_expire_policy("refs/heads/main".try_into()?)?;
+        assert_eq!(policy.expire, None);
+        assert_eq!(
+            policy.expire_unreachable,
+            Some(fixture_time() - Duration::from_secs(24 * 60 * 60))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use gix::bstr::ByteSlice;
+
+fn options(config: &[&str]) -> gix::open::Options {
+    crate::restricted().cli_overrides(config.iter().copied())
+}
+
+fn read(repo: &gix::Repository, path: &str) -> crate::Result<String> {
+    Ok(std::fs::read(repo.work_dir().expect("non-bare").join(path))?
+        .to_str()?
+        .to_owned())
+}
+
+fn conflict_dirs(repo: &gix::Repository) -> crate::Result<Vec<std::path::PathBuf>> {
+    let mut dirs = std::fs::read_dir(repo.common_dir().join(gix::rerere::CACHE_DIR))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    dirs.sort();
+    Ok(dirs)
+}
+
+const RESOLVED: &str = "1\n2\n3\nconflicting\nmain\n";
+
+/// Cherry-pick `main` onto `conflicting` and record the resolution of the conflict in `a`.
+fn record_resolution(repo: &gix::Repository) -> crate::Result {
+    let main = repo.rev_parse_single("main")?.detach();
+    let outcome = repo.cherry_pick(main)?;
+    assert_eq!(outcome.conflicts.len(), 1);
+    assert!(
+        repo.path().join(gix::rerere::MERGE_RR).is_file(),
+        "the conflict is tracked until it's resolved"
+    );
+    let dirs = conflict_dirs(repo)?;
+    assert_eq!(dirs.len(), 1, "one conflict was recorded");
+    assert!(dirs[0].join("preimage").is_file());
+    assert!(!dirs[0].join("postimage").exists(), "it's not yet resolved");
+
+    std::fs::write(repo.work_dir().expect("non-bare").join("a"), RESOLVED)?;
+    let outcome = repo.rerere()?;
+    assert_eq!(outcome.recorded_resolutions, ["a"]);
+    assert!(outcome.recorded_preimages.is_empty());
+    assert!(dirs[0].join("postimage").is_file());
+    assert!(
+        !repo.path().join(gix::rerere::MERGE_RR).exists(),
+        "there is nothing left to track"
+    );
+    Ok(())
+}
+
+fn copy_cache(from: &gix::Repository, to: &gix::Repository) -> crate::Result {
+    let dst = to.common_dir().join(gix::rerere::CACHE_DIR);
+    for dir in conflict_dirs(from)? {
+        let dst = dst.join(dir.file_name().expect("named"));
+        std::fs::create_dir_all(&dst)?;
+        for file in std::fs::read_dir(&dir)? {
+            let file = file?;
+            std::fs::copy(file.path(), dst.join(file.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn normalize() {
+    let hash_kind = gix::hash::Kind::Sha1;
+    let ours_first = gix::rerere::normalize(
+        b"1\n<<<<<<< HEAD\nours\n||||||| base\nbase\n=======\ntheirs\n>>>>>>> other\n2\n",
+        7,
+        hash_kind,
+    )
+    .expect("conflict");
+    assert_eq!(
+        ours_first.content.as_bstr(),
+        "1\n<<<<<<<\nours\n=======\ntheirs\n>>>>>>>\n2\n",
+        "labels and the ancestor are removed"
+    );
+    let theirs_first = gix::rerere::normalize(
+        b"1\n<<<<<<< other\ntheirs\n=======\nours\n>>>>>>> HEAD\n2\n",
+        7,
+        hash_kind,
+    )
+    .expect("conflict");
+    assert_eq!(
+        ours_first, theirs_first,
+        "the sides are sorted, so the conflict is the same no matter where the changes came from"
+    );
+
+    assert_eq!(gix::rerere::normalize(b"1\n2\n", 7, hash_kind), None, "no conflict");
+    assert_eq!(
+        gix::rerere::normalize(b"<<<<<<< HEAD\nours\n=======\ntheirs\n", 7, hash_kind),
+        None,
+        "unterminated conflict"
+    );
+    assert_eq!(
+        gix::rerere::normalize(
+            b"<<<<<<< HEAD\n<<<<<<< HEAD\na\n=======\nb\n>>>>>>> x\n=======\nc\n>>>>>>> y\n",
+            7,
+            hash_kind
+        ),
+        None,
+        "nested conflicts"
+    );
+}
+
+#[test]
+fn nothing_is_recorded_unless_enabled() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw_opts("make_rebase_repo.sh", "conflicting", options(&[]))?;
+    assert!(!repo.rerere_enabled()?);
+    repo.cherry_pick(repo.rev_parse_single("main")?.detach())?;
+    assert!(!repo.common_dir().join(gix::rerere::CACHE_DIR).exists());
+    assert_eq!(repo.rerere()?, Default::default());
+    Ok(())
+}
+
+#[test]
+fn resolutions_are_recorded_and_replayed_in_the_worktree() -> crate::Result {
+    let (repo, _tmp) =
+        crate::named_subrepo_rw_opts("make_rebase_repo.sh", "conflicting", options(&["rerere.enabled=true"]))?;
+    record_resolution(&repo)?;
+
+    let (other, _tmp) = crate::named_subrepo_rw_opts("make_rebase_repo.sh", "conflicting", options(&[]))?;
+    std::fs::create_dir(other.common_dir().join(gix::rerere::CACHE_DIR))?;
+    assert!(other.rerere_enabled()?, "an existing cache directory enables rerere");
+    copy_cache(&repo, &other)?;
+
+    let outcome = other.cherry_pick(other.rev_parse_single("main")?.detach())?;
+    assert_eq!(outcome.conflicts.len(), 1, "the conflict is still reported");
+    assert_eq!(read(&other, "a")?, RESOLVED, "but the worktree is resolved");
+    assert!(
+        other.open_index()?.entries().iter().any(|e| e.stage() != 0),
+        "without auto-update, the index remains conflicted"
+    );
+    assert!(
+        !other.path().join(gix::rerere::MERGE_RR).exists(),
+        "the conflict doesn't need tracking once it's resolved"
+    );
+
+    let forgotten = other.rerere_forget(["a"])?;
+    assert_eq!(forgotten, ["a"]);
+    let dirs = conflict_dirs(&other)?;
+    assert!(!dirs[0].join("postimage").exists(), "the resolution was forgotten");
+    assert!(dirs[0].join("preimage").is_file(), "but the conflict is still known");
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "rebase")]
+fn resolutions_are_replayed_during_rebase_and_staged_with_auto_update() -> crate::Result {
+    let (repo, _tmp) =
+        crate::named_subrepo_rw_opts("make_rebase_repo.sh", "conflicting", options(&["rerere.enabled=true"]))?;
+    record_resolution(&repo)?;
+
+    let (other, _tmp) = crate::named_subrepo_rw_opts(
+        "make_rebase_repo.sh",
+        "conflicting",
+        options(&["rerere.enabled=true", "rerere.autoUpdate=true"]),
+    )?;
+    copy_cache(&repo, &other)?;
+
+    let outcome = other.rebase(other.rev_parse_single("main")?.detach(), Default::default())?;
+    let stopped = outcome.stopped.expect("rebase stops at the conflict");
+    assert_eq!(stopped.conflicts.len(), 1);
+    assert_eq!(
+        read(&other, "a")?,
+        RESOLVED,
+        "the sides are swapped compared to the cherry-pick, but the conflict is the same"
+    );
+    assert!(
+        other.open_index()?.entries().iter().all(|e| e.stage() == 0),
+        "the resolution was staged"
+    );
+
+    let outcome = other.rebase_continue()?;
+    assert!(outcome.stopped.is_none());
+    assert_eq!(read(&other, "a")?, RESOLVED);
+    Ok(())
+}
+
+#[test]
+fn gc_removes_expired_entries() -> crate::Result {
+    let (repo, _tmp) =
+        crate::named_subrepo_rw_opts("make_rebase_repo.sh", "conflicting", options(&["rerere.enabled=true"]))?;
+    record_resolution(&repo)?;
+    assert_eq!(repo.rerere_gc()?, 0, "nothing expires by default");
+
+    let (other, _tmp) = crate::named_subrepo_rw_opts(
+        "make_rebase_repo.sh",
+        "conflicting",
+        options(&["rerere.enabled=true", "gc.rerereResolved=never"]),
+    )?;
+    copy_cache(&repo, &other)?;
+    assert_eq!(other.rerere_gc()?, 0);
+
+    let (other, _tmp) = crate::named_subrepo_rw_opts(
+        "make_rebase_repo.sh",
+        "conflicting",
+        options(&["rerere.enabled=true", "gc.rerereResolved=now"]),
+    )?;
+    copy_cache(&repo, &other)?;
+    assert_eq!(other.rerere_gc()?, 1);
+    assert!(conflict_dirs(&other)?.is_empty());
+    Ok(())
+}
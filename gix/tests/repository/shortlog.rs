@@ -0,0 +1,68 @@
+use gix::shortlog::{Group, Options};
+
+fn group(name: &str, email: Option<&str>, count: usize, summaries: &[&str]) -> Group {
+    Group {
+        name: name.into(),
+        email: email.map(Into::into),
+        count,
+        summaries: summaries.iter().copied().map(Into::into).collect(),
+    }
+}
+
+#[test]
+fn authors_are_grouped_by_name_with_the_mailmap_applied() -> crate::Result {
+    let repo = crate::named_repo("make_shortlog_repo.sh")?;
+    let outcome = repo.shortlog(
+        repo.rev_parse("HEAD")?.detach(),
+        Options {
+            summaries: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.commits, 5);
+    assert_eq!(
+        outcome.groups,
+        [
+            group("A U Thor", None, 2, &["first", "third continued"]),
+            group("Other", None, 3, &["second", "fourth", "fifth"]),
+        ],
+        "summaries are listed from oldest to newest, with `[PATCH]` removed and the title folded into one line"
+    );
+    Ok(())
+}
+
+#[test]
+fn ranges_can_be_sorted_by_count_and_grouped_by_email() -> crate::Result {
+    let repo = crate::named_repo("make_shortlog_repo.sh")?;
+    let outcome = repo.shortlog(
+        repo.rev_parse("v1..HEAD")?.detach(),
+        Options {
+            email: true,
+            sort_by_count: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.commits, 4, "the first commit is excluded");
+    assert_eq!(
+        outcome.groups,
+        [
+            group("Other", Some("other@example.com"), 3, &[]),
+            group("A U Thor", Some("author@example.com"), 1, &[]),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn committers_can_be_grouped_instead() -> crate::Result {
+    let repo = crate::named_repo("make_shortlog_repo.sh")?;
+    let outcome = repo.shortlog(
+        repo.rev_parse("v1")?.detach(),
+        Options {
+            committer: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.groups, [group("committer", None, 1, &[])]);
+    Ok(())
+}
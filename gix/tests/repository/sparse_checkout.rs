@@ -0,0 +1,117 @@
+use gix::bstr::{BStr, ByteSlice};
+
+fn files(repo: &gix::Repository) -> Vec<String> {
+    let workdir = repo.work_dir().expect("non-bare");
+    let mut files: Vec<_> = ["root", "a/f", "a/b/f", "a/b/c/f", "d/f", "e/f", "e/f.txt"]
+        .into_iter()
+        .filter(|path| workdir.join(path).is_file())
+        .map(ToOwned::to_owned)
+        .collect();
+    files.sort();
+    files
+}
+
+fn skipped(repo: &gix::Repository) -> crate::Result<Vec<String>> {
+    let index = repo.open_index()?;
+    Ok(index
+        .entries()
+        .iter()
+        .filter(|e| e.flags.contains(gix::index::entry::Flags::SKIP_WORKTREE))
+        .map(|e| e.path(&index).to_string())
+        .collect())
+}
+
+fn bstrs<'a>(patterns: &'a [&str]) -> impl Iterator<Item = &'a BStr> {
+    patterns.iter().map(|p| p.as_bytes().as_bstr())
+}
+
+#[test]
+fn cone_mode_init_set_add_and_disable() -> crate::Result {
+    let (mut repo, _tmp) = crate::named_subrepo_rw("make_sparse_checkout_repo.sh", "full")?;
+    let outcome = repo.sparse_checkout().init(true)?;
+    assert_eq!(outcome.added.len(), 0);
+    assert_eq!(files(&repo), ["root"], "only files in the root are included initially");
+    assert_eq!(skipped(&repo)?.len(), 6);
+    assert!(repo.sparse_checkout().is_enabled()?);
+    assert!(repo.sparse_checkout().is_cone()?);
+
+    let outcome = repo.sparse_checkout().set(bstrs(&["a/b/", "/d"]))?;
+    assert_eq!(outcome.added, ["a/b/c/f", "a/b/f", "a/f", "d/f"]);
+    assert_eq!(files(&repo), ["a/b/c/f", "a/b/f", "a/f", "d/f", "root"]);
+    assert_eq!(skipped(&repo)?, ["e/f", "e/f.txt"]);
+    assert_eq!(repo.sparse_checkout().list()?, ["a/b", "d"]);
+
+    let outcome = repo.sparse_checkout().add(bstrs(&["e", "a/b/c"]))?;
+    assert_eq!(outcome.added, ["e/f", "e/f.txt"]);
+    assert_eq!(
+        repo.sparse_checkout().list()?,
+        ["a/b", "d", "e"],
+        "nested directories are merged"
+    );
+
+    let outcome = repo.sparse_checkout().set(bstrs(&["e"]))?;
+    assert_eq!(outcome.removed, ["a/b/c/f", "a/b/f", "a/f", "d/f"]);
+    assert_eq!(files(&repo), ["e/f", "e/f.txt", "root"]);
+
+    let outcome = repo.sparse_checkout().disable()?;
+    assert_eq!(outcome.added.len(), 4);
+    assert!(skipped(&repo)?.is_empty());
+    assert_eq!(files(&repo).len(), 7, "all files are present again");
+    assert!(!repo.sparse_checkout().is_enabled()?);
+
+    let repo = gix::open_opts(repo.git_dir(), crate::restricted())?;
+    assert_eq!(
+        repo.config_snapshot().boolean("core.sparseCheckout"),
+        Some(false),
+        "the configuration is persisted"
+    );
+    Ok(())
+}
+
+#[test]
+fn cone_patterns_are_compatible_with_git() -> crate::Result {
+    let (mut repo, _tmp) = crate::named_subrepo_rw("make_sparse_checkout_repo.sh", "cone-by-git")?;
+    let git_patterns = std::fs::read(repo.sparse_checkout().path())?;
+    assert_eq!(repo.sparse_checkout().list()?, ["a/b", "d"]);
+    assert_eq!(files(&repo), ["a/b/c/f", "a/b/f", "a/f", "d/f", "root"]);
+
+    let (mut full, _full_tmp) = crate::named_subrepo_rw("make_sparse_checkout_repo.sh", "full")?;
+    full.sparse_checkout().set(bstrs(&["d", "a/b"]))?;
+    assert_eq!(
+        std::fs::read(full.sparse_checkout().path())?.as_bstr(),
+        git_patterns.as_bstr(),
+        "patterns are written exactly like git does"
+    );
+    assert_eq!(files(&full), files(&repo));
+    Ok(())
+}
+
+#[test]
+fn non_cone_mode_uses_ignore_style_patterns() -> crate::Result {
+    let (mut repo, _tmp) = crate::named_subrepo_rw("make_sparse_checkout_repo.sh", "full")?;
+    repo.sparse_checkout().init(false)?;
+    assert!(!repo.sparse_checkout().is_cone()?);
+
+    repo.sparse_checkout().set(bstrs(&["*.txt", "/a/", "!/a/b/c/"]))?;
+    assert_eq!(files(&repo), ["a/b/f", "a/f", "e/f.txt"]);
+    assert_eq!(repo.sparse_checkout().list()?, ["*.txt", "/a/", "!/a/b/c/"]);
+
+    repo.sparse_checkout().add(bstrs(&["root"]))?;
+    assert_eq!(files(&repo), ["a/b/f", "a/f", "e/f.txt", "root"]);
+    Ok(())
+}
+
+#[test]
+fn files_with_changes_are_not_removed() -> crate::Result {
+    let (mut repo, _tmp) = crate::named_subrepo_rw("make_sparse_checkout_repo.sh", "full")?;
+    let path = repo.work_dir().expect("non-bare").join("e/f");
+    std::fs::write(&path, "changed")?;
+    let err = repo.sparse_checkout().init(true).unwrap_err();
+    assert!(
+        matches!(&err, gix::sparse_checkout::Error::Dirty { paths } if paths == &["e/f"]),
+        "{err:?}"
+    );
+    assert_eq!(std::fs::read(path)?, b"changed");
+    assert!(skipped(&repo)?.is_empty(), "the index is unchanged");
+    Ok(())
+}
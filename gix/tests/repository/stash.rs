@@ -0,0 +1,204 @@
+use gix::{
+    bstr::ByteSlice,
+    stash::{ApplyOptions, SaveOptions},
+};
+
+fn path(repo: &gix::Repository, path: &str) -> std::path::PathBuf {
+    repo.work_dir().expect("non-bare").join(path)
+}
+
+fn read(repo: &gix::Repository, rela_path: &str) -> crate::Result<String> {
+    Ok(std::fs::read(path(repo, rela_path))?.to_str()?.to_owned())
+}
+
+fn index_paths(repo: &gix::Repository) -> crate::Result<Vec<String>> {
+    let index = repo.open_index()?;
+    Ok(index.entries().iter().map(|e| e.path(&index).to_string()).collect())
+}
+
+#[test]
+fn save_and_pop_round_trip_including_untracked_files() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_stash_repo.sh", "clean")?;
+    let head = repo.head_id()?.detach();
+    std::fs::write(path(&repo, "a"), "1\n2\n3\nchanged\n")?;
+    std::fs::remove_file(path(&repo, "b"))?;
+    std::fs::write(path(&repo, "untracked"), "untracked\n")?;
+    std::fs::write(path(&repo, "file.ignored"), "ignored\n")?;
+    std::fs::write(path(&repo, "added"), "added\n")?;
+    let mut index = repo.open_index()?;
+    let added_id = repo.write_blob(b"added\n")?.detach();
+    index.dangerously_push_entry(
+        Default::default(),
+        added_id,
+        gix::index::entry::Flags::empty(),
+        gix::index::entry::Mode::FILE,
+        "added".into(),
+    );
+    index.sort_entries();
+    index.write(Default::default())?;
+
+    let id = repo
+        .stash_save(SaveOptions {
+            message: None,
+            include_untracked: true,
+        })?
+        .expect("there are changes");
+    let stash = repo.find_object(id)?.into_commit();
+    let parents: Vec<_> = stash.parent_ids().collect();
+    assert_eq!(parents.len(), 3, "head, index and untracked files");
+    assert_eq!(parents[0], head);
+    assert_eq!(
+        stash.message_raw()?,
+        format!("WIP on main: {} base\n", repo.head_id()?.shorten_or_id())
+    );
+    let untracked_tree = repo.find_object(parents[2])?.into_commit().tree()?;
+    assert_eq!(
+        untracked_tree
+            .iter()
+            .map(|e| e.map(|e| e.filename().to_string()))
+            .collect::<Result<Vec<_>, _>>()?,
+        ["untracked"],
+        "ignored files aren't stashed"
+    );
+
+    assert_eq!(read(&repo, "a")?, "1\n2\n3\n", "the worktree was reset");
+    assert_eq!(read(&repo, "b")?, "1\n");
+    assert!(!path(&repo, "added").exists());
+    assert!(!path(&repo, "untracked").exists());
+    assert!(path(&repo, "file.ignored").exists());
+    assert_eq!(index_paths(&repo)?, [".gitignore", "a", "b", "dir/c"]);
+
+    let list = repo.stash_list()?;
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].id, id);
+    assert_eq!(
+        list[0].message,
+        format!("WIP on main: {} base", repo.head_id()?.shorten_or_id())
+    );
+
+    let outcome = repo.stash_pop(0, ApplyOptions::default())?;
+    assert!(outcome.conflicts.is_empty());
+    assert_eq!(read(&repo, "a")?, "1\n2\n3\nchanged\n");
+    assert!(!path(&repo, "b").exists());
+    assert_eq!(read(&repo, "added")?, "added\n");
+    assert_eq!(read(&repo, "untracked")?, "untracked\n");
+    assert_eq!(
+        index_paths(&repo)?,
+        [".gitignore", "a", "added", "b", "dir/c"],
+        "only new files are added to the index, modifications and deletions stay in the worktree"
+    );
+    assert!(repo.stash_list()?.is_empty());
+    assert!(repo.try_find_reference("refs/stash")?.is_none());
+    Ok(())
+}
+
+#[test]
+fn save_without_changes_does_nothing() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_stash_repo.sh", "clean")?;
+    std::fs::write(path(&repo, "untracked"), "untracked\n")?;
+    assert_eq!(
+        repo.stash_save(SaveOptions::default())?,
+        None,
+        "untracked files are ignored by default"
+    );
+    assert!(repo.stash_list()?.is_empty());
+    assert!(path(&repo, "untracked").exists());
+    Ok(())
+}
+
+#[test]
+fn entries_created_by_git_can_be_listed_applied_and_dropped() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_stash_repo.sh", "stashed")?;
+    let list = repo.stash_list()?;
+    assert_eq!(list.len(), 2);
+    assert_eq!(
+        list[0].message,
+        format!("WIP on main: {} base", repo.head_id()?.shorten_or_id())
+    );
+    assert_eq!(list[1].message, "On main: first");
+
+    let outcome = repo.stash_apply(1, ApplyOptions { reinstate_index: true })?;
+    assert!(outcome.conflicts.is_empty());
+    assert_eq!(read(&repo, "a")?, "1\n2\n3\nfirst\n");
+    assert_eq!(repo.stash_list()?.len(), 2, "applying keeps the entry");
+
+    assert_eq!(repo.stash_drop(1)?, list[1].id);
+    let remaining = repo.stash_list()?;
+    assert_eq!(remaining, [list[0].clone()]);
+    assert_eq!(repo.find_reference("refs/stash")?.id(), list[0].id);
+
+    assert!(
+        matches!(
+            repo.stash_apply(0, ApplyOptions::default()),
+            Err(gix::stash::Error::RequireClean(_))
+        ),
+        "the worktree must be clean"
+    );
+    assert!(matches!(
+        repo.stash_drop(1),
+        Err(gix::stash::Error::NoSuchEntry { index: 1 })
+    ));
+    Ok(())
+}
+
+#[test]
+fn apply_refuses_to_overwrite_changes_and_untracked_files() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_stash_repo.sh", "stashed")?;
+    std::fs::write(path(&repo, "b"), "changed\n")?;
+    std::fs::write(path(&repo, "untracked"), "in the way\n")?;
+    assert!(
+        matches!(
+            repo.stash_pop(0, ApplyOptions::default()),
+            Err(gix::stash::Error::RequireClean(_))
+        ),
+        "changes are not allowed"
+    );
+    std::fs::write(path(&repo, "b"), "1\n")?;
+    assert!(
+        matches!(
+            repo.stash_pop(0, ApplyOptions::default()),
+            Err(gix::stash::Error::UntrackedFileExists { path }) if path == "untracked"
+        ),
+        "untracked files are never overwritten"
+    );
+    assert_eq!(read(&repo, "untracked")?, "in the way\n");
+    assert_eq!(repo.stash_list()?.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn apply_with_conflicts_keeps_the_entry() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_stash_repo.sh", "conflicting")?;
+    let outcome = repo.stash_pop(0, ApplyOptions::default())?;
+    assert_eq!(outcome.conflicts.len(), 1);
+    assert_eq!(outcome.conflicts[0].path, "b");
+    assert!(read(&repo, "b")?.contains("<<<<<<< Updated upstream\n"));
+    assert!(
+        repo.open_index()?.entries().iter().any(|e| e.stage() != 0),
+        "the conflict is recorded in the index"
+    );
+    assert_eq!(repo.stash_list()?.len(), 1, "the entry is kept if there are conflicts");
+    Ok(())
+}
+
+#[test]
+fn apply_follows_renames_as_configured_for_merges() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_stash_repo.sh", "renamed")?;
+    let outcome = repo.stash_apply(0, ApplyOptions::default())?;
+    assert!(outcome.conflicts.is_empty(), "renames are detected by default");
+    assert_eq!(read(&repo, "renamed/c")?, "c\nstashed\n");
+
+    let (repo, _tmp) = crate::named_subrepo_rw_opts(
+        "make_stash_repo.sh",
+        "renamed",
+        crate::restricted().cli_overrides(["merge.renames=false"]),
+    )?;
+    let outcome = repo.stash_apply(0, ApplyOptions::default())?;
+    assert_eq!(
+        outcome.conflicts.len(),
+        1,
+        "without renames, the stashed change conflicts with the deletion"
+    );
+    assert_eq!(outcome.conflicts[0].path, "dir/c");
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use gix::status::tree_index::{Action, Change, Options};
+
+fn staged_changes(repo: &gix::Repository, options: Option<Options>) -> crate::Result<Vec<String>> {
+    let index = repo.index()?;
+    let mut changes = Vec::new();
+    repo.tree_index_status(
+        &repo.head_tree_id()?,
+        &index,
+        options,
+        |change| -> Result<_, std::convert::Infallible> {
+            changes.push(match change {
+                Change::Addition { location, .. } => format!("A {location}"),
+                Change::Deletion { location, .. } => format!("D {location}"),
+                Change::Modification { location, .. } => format!("M {location}"),
+                Change::Rename {
+                    from, to, similarity, ..
+                } => format!("R{:.0} {from} -> {to}", similarity * 100.0),
+                Change::Copy {
+                    from, to, similarity, ..
+                } => format!("C{:.0} {from} -> {to}", similarity * 100.0),
+            });
+            Ok(Action::Continue)
+        },
+    )?;
+    Ok(changes)
+}
+
+#[test]
+fn renames_are_tracked_by_default() -> crate::Result {
+    let repo = crate::named_repo("make_status_repo.sh")?;
+    assert_eq!(
+        staged_changes(&repo, None)?,
+        ["M modified", "R86 renamed-source -> renamed", "A added", "A copy", "D removed"],
+        "rewrites are emitted after modifications, followed by the remaining additions and deletions, and unchanged files aren't considered as source of copies by default"
+    );
+    Ok(())
+}
+
+#[test]
+fn renames_can_be_disabled_and_copies_can_be_found_among_all_sources() -> crate::Result {
+    let repo = crate::named_repo("make_status_repo.sh")?;
+    assert_eq!(
+        staged_changes(&repo, Some(Options { rewrites: None }))?,
+        [
+            "A added",
+            "A copy",
+            "M modified",
+            "D removed",
+            "A renamed",
+            "D renamed-source"
+        ]
+    );
+
+    assert_eq!(
+        staged_changes(
+            &repo,
+            Some(Options {
+                rewrites: Some(gix::diff::Rewrites {
+                    copies: Some(gix::diff::rewrites::Copies {
+                        source: gix::diff::rewrites::CopySource::FromSetOfModifiedFilesAndAllSources,
+                        percentage: None,
+                    }),
+                    ..Default::default()
+                }),
+            })
+        )?,
+        [
+            "R86 renamed-source -> renamed",
+            "C100 unchanged -> copy",
+            "A added",
+            "M modified",
+            "D removed"
+        ],
+        "exact copies are found among unchanged files as well, and with copy tracking all changes are emitted by the tracker"
+    );
+    Ok(())
+}
+
+#[test]
+fn status_renames_configuration_is_respected() -> crate::Result {
+    let mut repo = crate::named_repo("make_status_repo.sh")?;
+    repo.config_snapshot_mut()
+        .set_value(&gix::config::tree::Diff::RENAMES, "true")?;
+    repo.config_snapshot_mut()
+        .set_value(&gix::config::tree::Status::RENAMES, "false")?;
+    assert_eq!(
+        staged_changes(&repo, None)?,
+        [
+            "A added",
+            "A copy",
+            "M modified",
+            "D removed",
+            "A renamed",
+            "D renamed-source"
+        ],
+        "`status.renames` overrides `diff.renames`"
+    );
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use gix::prelude::ObjectIdExt;
+
+#[test]
+fn objects_and_references_can_be_read_without_thread_local_repository() -> crate::Result {
+    let sync = crate::util::repo("make_basic_repo.sh")?;
+    let repo = sync.to_thread_local();
+    let head_id = repo.head_id()?.detach();
+
+    let head = sync.find_reference("HEAD")?;
+    assert_eq!(
+        head.target.try_name().map(|name| name.as_bstr().to_string()),
+        Some("refs/heads/main".into())
+    );
+    assert!(sync.try_find_reference("does-not-exist")?.is_none());
+    assert!(sync.find_reference("does-not-exist").is_err());
+
+    let commit = sync.find_object(head_id)?;
+    let expected = head_id.attach(&repo).object()?;
+    assert_eq!(commit.kind, expected.kind);
+    assert_eq!(commit.data, expected.data);
+    assert_eq!(sync.find_header(head_id)?.kind(), gix::object::Kind::Commit);
+    assert!(sync.has_object(head_id));
+
+    let empty_tree = gix::ObjectId::empty_tree(sync.object_hash());
+    assert_eq!(
+        sync.find_object(empty_tree)?.kind,
+        gix::object::Kind::Tree,
+        "the empty tree is always present"
+    );
+    let missing = gix::ObjectId::from_hex(b"ffffffffffffffffffffffffffffffffffffffff")?;
+    assert!(!sync.has_object(missing));
+    assert!(sync.try_find_object(missing)?.is_none());
+    assert!(sync.try_find_header(missing)?.is_none());
+    assert!(sync.find_object(missing).is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "parallel")]
+fn objects_can_be_read_from_multiple_threads_at_once() -> crate::Result {
+    let sync = crate::util::repo("make_basic_repo.sh")?;
+    let repo = sync.to_thread_local();
+    let ids = repo
+        .head_id()?
+        .ancestors()
+        .all()?
+        .map(|info| info.map(|info| info.id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    std::thread::scope(|s| {
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                s.spawn(|| {
+                    ids.iter()
+                        .map(|id| sync.find_object(*id).map(|obj| obj.data.len()))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+            })
+            .collect();
+        let expected = ids
+            .iter()
+            .map(|id| repo.find_object(*id).map(|obj| obj.data.len()))
+            .collect::<Result<Vec<_>, _>>()?;
+        for handle in handles {
+            assert_eq!(handle.join().expect("no panic")?, expected);
+        }
+        Ok(())
+    })
+}
@@ -13,6 +13,44 @@ fn stream() -> crate::Result {
     Ok(())
 }
 
+#[test]
+#[cfg(feature = "worktree-stream")]
+fn stream_with_submodules() -> crate::Result {
+    use std::io::Read;
+    let repo = crate::submodule::repo("with-submodules")?;
+    let head = repo.head_commit()?.id;
+    let (mut stream, index) = repo.worktree_stream_with_submodules(head)?;
+    assert_eq!(index.entries().len(), 3, "the index is the one of the superproject");
+
+    let mut paths = Vec::new();
+    let mut buf = Vec::new();
+    while let Some(mut entry) = stream.next_entry()? {
+        buf.clear();
+        entry.read_to_end(&mut buf)?;
+        paths.push(entry.relative_path().to_string());
+        if entry.relative_path() == "m1/this" {
+            assert_eq!(
+                buf, b"hello\n",
+                "the submodule is at the commit recorded in the superproject"
+            );
+        }
+    }
+    assert_eq!(
+        paths,
+        [".gitmodules", "dir/file", "m1/this", "m1/subdir/that"],
+        "submodules that aren't committed aren't added, and submodules are traversed as part of the same stream"
+    );
+
+    let (mut stream, _index) = repo.worktree_stream(head)?;
+    let mut paths = Vec::new();
+    while let Some(mut entry) = stream.next_entry()? {
+        std::io::copy(&mut entry, &mut std::io::sink())?;
+        paths.push(entry.relative_path().to_string());
+    }
+    assert_eq!(paths, [".gitmodules", "dir/file"], "by default, submodules are ignored");
+    Ok(())
+}
+
 #[test]
 #[cfg(feature = "worktree-archive")]
 fn archive() -> crate::Result {
@@ -336,3 +374,191 @@ fn run_assertions(main_repo: gix::Repository, should_be_bare: bool) {
         );
     }
 }
+
+#[cfg(feature = "worktree-management")]
+mod management {
+    use std::{path::Path, process::Command};
+
+    use gix::{
+        bstr::ByteSlice,
+        worktree::add::{Options, Target},
+    };
+
+    fn git(dir: &Path, args: &[&str]) -> crate::Result<String> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env_remove("GIT_DIR")
+            .output()?;
+        assert!(
+            output.status.success(),
+            "git {args:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    fn branch(name: &str) -> gix::refs::FullName {
+        format!("refs/heads/{name}").try_into().expect("valid")
+    }
+
+    #[test]
+    fn add_writes_files_like_git() -> crate::Result {
+        let (repo, tmp) = crate::named_subrepo_rw("make_worktree_management_repo.sh", "main")?;
+        let path = tmp.path().join("wt");
+        let wt = repo.worktree_add(&path, Target::Branch(branch("other")), Options::default())?;
+        assert_eq!(wt.head_name()?, Some(branch("other")));
+        assert!(path.join("file").is_file());
+        assert!(
+            !path.join("dir/other").exists(),
+            "the worktree contains the files of the branch"
+        );
+
+        let git_dir = gix_path::realpath(repo.git_dir())?.join("worktrees").join("wt");
+        assert_eq!(gix_path::realpath(wt.git_dir())?, git_dir);
+        assert_eq!(std::fs::read(git_dir.join("commondir"))?.as_bstr(), "../..\n");
+        assert!(
+            !git_dir.join("locked").exists(),
+            "the lock is removed after initialization"
+        );
+        assert_eq!(git(&path, &["status", "--porcelain"])?, "", "git sees a clean worktree");
+        assert!(git(repo.git_dir(), &["worktree", "list", "--porcelain"])?.contains("branch refs/heads/other"));
+
+        let err = repo
+            .worktree_add(
+                tmp.path().join("wt2"),
+                Target::Branch(branch("other")),
+                Options::default(),
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, gix::worktree::add::Error::BranchCheckedOut { .. }),
+            "{err:?}"
+        );
+        let err = repo
+            .worktree_add(&path, Target::Detached(repo.head_id()?.detach()), Options::default())
+            .unwrap_err();
+        assert!(matches!(err, gix::worktree::add::Error::PathExists { .. }), "{err:?}");
+
+        let wt = repo.worktree_add(
+            tmp.path().join("sub").join("wt"),
+            Target::NewBranch {
+                name: branch("new"),
+                start: repo.head_id()?.detach(),
+            },
+            Options::default(),
+        )?;
+        assert_eq!(wt.head_name()?, Some(branch("new")));
+        assert!(
+            wt.git_dir().ends_with("worktrees/wt1"),
+            "a number is appended to keep ids unique"
+        );
+        assert_eq!(repo.worktrees()?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn lock_unlock_and_remove() -> crate::Result {
+        let (repo, tmp) = crate::named_subrepo_rw("make_worktree_management_repo.sh", "main")?;
+        let path = tmp.path().join("wt");
+        repo.worktree_add(
+            &path,
+            Target::Detached(repo.head_id()?.detach()),
+            Options {
+                lock_reason: Some("on usb stick".into()),
+                ..Default::default()
+            },
+        )?;
+        let proxy = repo.worktrees()?.pop().expect("one worktree");
+        assert_eq!(proxy.lock_reason(), Some("on usb stick".into()));
+        assert!(matches!(
+            proxy.lock(None),
+            Err(gix::worktree::lock::Error::AlreadyLocked { .. })
+        ));
+        assert!(git(&path, &["worktree", "list", "--porcelain"])?.contains("locked on usb stick"));
+
+        let err = repo.worktrees()?.pop().expect("one").remove(false).unwrap_err();
+        assert!(matches!(err, gix::worktree::remove::Error::Locked { .. }), "{err:?}");
+        proxy.unlock()?;
+        assert!(proxy.lock_reason().is_none());
+
+        std::fs::write(path.join("file"), "changed")?;
+        let err = repo.worktrees()?.pop().expect("one").remove(false).unwrap_err();
+        assert!(matches!(err, gix::worktree::remove::Error::RequireClean(_)), "{err:?}");
+        git(&path, &["checkout", "file"])?;
+        std::fs::write(path.join("untracked"), "new")?;
+        let err = repo.worktrees()?.pop().expect("one").remove(false).unwrap_err();
+        assert!(
+            matches!(&err, gix::worktree::remove::Error::Untracked { paths } if paths == &["untracked"]),
+            "{err:?}"
+        );
+
+        repo.worktrees()?.pop().expect("one").remove(true)?;
+        assert!(!path.exists());
+        assert!(repo.worktrees()?.is_empty());
+        assert!(
+            !repo.git_dir().join("worktrees").exists(),
+            "the worktrees directory is removed once empty"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn prune_removes_missing_worktrees_unless_locked() -> crate::Result {
+        let (repo, tmp) = crate::named_subrepo_rw("make_worktree_management_repo.sh", "main")?;
+        let head = repo.head_id()?.detach();
+        for (name, lock_reason) in [("gone", None), ("kept", None), ("locked", Some("".into()))] {
+            repo.worktree_add(
+                tmp.path().join(name),
+                Target::Detached(head),
+                Options {
+                    lock_reason,
+                    ..Default::default()
+                },
+            )?;
+        }
+        std::fs::remove_dir_all(tmp.path().join("gone"))?;
+        std::fs::remove_dir_all(tmp.path().join("locked"))?;
+
+        assert_eq!(repo.worktree_prune(true)?, ["gone"]);
+        assert_eq!(repo.worktrees()?.len(), 3, "dry-runs don't change anything");
+        assert_eq!(repo.worktree_prune(false)?, ["gone"]);
+        let ids: Vec<_> = repo.worktrees()?.iter().map(|proxy| proxy.id().to_owned()).collect();
+        assert_eq!(ids, ["kept", "locked"]);
+        Ok(())
+    }
+
+    #[test]
+    fn repair_after_moving_worktree_and_repository() -> crate::Result {
+        let (repo, tmp) = crate::named_subrepo_rw("make_worktree_management_repo.sh", "main")?;
+        let head = repo.head_id()?.detach();
+        repo.worktree_add(tmp.path().join("wt"), Target::Detached(head), Options::default())?;
+        std::fs::rename(tmp.path().join("wt"), tmp.path().join("moved"))?;
+
+        let changes = repo.worktree_repair([tmp.path().join("moved")])?;
+        assert_eq!(
+            changes,
+            [gix::worktree::repair::Change::GitDirFile {
+                path: gix_path::realpath(repo.git_dir())?.join("worktrees/wt/gitdir")
+            }]
+        );
+        assert_eq!(git(&tmp.path().join("moved"), &["status", "--porcelain"])?, "");
+        assert!(
+            repo.worktree_repair(None::<&Path>)?.is_empty(),
+            "nothing to do once repaired"
+        );
+
+        std::fs::rename(tmp.path().join("main"), tmp.path().join("main-moved"))?;
+        let repo = gix::open_opts(tmp.path().join("main-moved"), crate::restricted())?;
+        let changes = repo.worktree_repair(None::<&Path>)?;
+        assert_eq!(
+            changes,
+            [gix::worktree::repair::Change::DotGitFile {
+                path: gix_path::realpath(tmp.path())?.join("moved/.git")
+            }]
+        );
+        assert_eq!(git(&tmp.path().join("moved"), &["status", "--porcelain"])?, "");
+        assert_eq!(repo.worktrees()?[0].id(), "wt");
+        Ok(())
+    }
+}
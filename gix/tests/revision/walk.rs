@@ -0,0 +1,47 @@
+mod prefetch {
+    use crate::named_repo;
+
+    #[test]
+    fn yields_decoded_commits_in_walk_order() -> crate::Result {
+        let repo = named_repo("make_repo_with_fork_and_dates.sh")?;
+        let head = repo.head_id()?;
+        let expected = repo
+            .rev_walk(Some(head))
+            .all()?
+            .map(|info| info.map(|info| info.id))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert!(expected.len() > 2, "need enough commits for a meaningful test");
+
+        for use_commit_graph in [false, true] {
+            for (lookahead, thread_limit) in [(0, Some(1)), (1, None), (2, Some(2)), (100, None)] {
+                let mut actual = Vec::new();
+                for item in repo
+                    .rev_walk(Some(head))
+                    .use_commit_graph(use_commit_graph)
+                    .all()?
+                    .prefetch(lookahead, thread_limit)
+                {
+                    let item = item?;
+                    let commit = repo.find_object(item.info.id)?.into_commit();
+                    assert_eq!(item.commit.tree, commit.tree_id()?, "the commit matches the info");
+                    assert_eq!(item.commit.message, commit.message_raw()?);
+                    actual.push(item.info.id);
+                }
+                assert_eq!(
+                    actual, expected,
+                    "lookahead {lookahead}, thread-limit {thread_limit:?}, commit-graph {use_commit_graph}"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn can_be_dropped_early() -> crate::Result {
+        let repo = named_repo("make_repo_with_fork_and_dates.sh")?;
+        let mut iter = repo.rev_walk(Some(repo.head_id()?)).all()?.prefetch(10, None);
+        assert!(iter.next().transpose()?.is_some());
+        drop(iter);
+        Ok(())
+    }
+}
@@ -141,3 +141,68 @@ mod open {
         Ok(())
     }
 }
+
+mod active_submodules {
+    fn active_names(repo: &gix::Repository) -> crate::Result<Vec<String>> {
+        Ok(repo
+            .active_submodules()?
+            .expect("modules present")
+            .iter()
+            .map(|sm| sm.name().to_string())
+            .collect())
+    }
+
+    /// Open `with-submodules` without the `submodule.<name>.active` values that `git submodule add` writes.
+    fn repo_without_name_active(
+        overrides: &[&str],
+    ) -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+        let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+        let repo_dir = tmp.path().join("with-submodules");
+        let config_path = repo_dir.join(".git").join("config");
+        let config = std::fs::read_to_string(&config_path)?;
+        std::fs::write(&config_path, config.replace("\tactive = true\n", ""))?;
+        let repo = gix::open_opts(
+            repo_dir,
+            gix::open::Options::isolated().config_overrides(overrides.iter().copied()),
+        )?;
+        Ok((repo, tmp))
+    }
+
+    #[test]
+    fn initialized_submodules_are_active() -> crate::Result {
+        let repo = crate::submodule::repo("with-submodules")?;
+        assert_eq!(active_names(&repo)?, ["m1", "dir/m1"]);
+
+        let (repo, _tmp) = repo_without_name_active(&[])?;
+        assert_eq!(active_names(&repo)?, ["m1", "dir/m1"], "they have a url configured");
+        Ok(())
+    }
+
+    #[test]
+    fn submodule_active_pathspecs_match_paths_and_override_the_url() -> crate::Result {
+        let (repo, _tmp) = repo_without_name_active(&["submodule.active=dir/*"])?;
+        assert_eq!(active_names(&repo)?, ["dir/m1"]);
+
+        let (repo, _tmp) = repo_without_name_active(&["submodule.active=:!dir/*", "submodule.active=*"])?;
+        assert_eq!(active_names(&repo)?, ["m1"]);
+
+        let (repo, _tmp) = repo_without_name_active(&["submodule.active=dir/*", "submodule.m1.active=true"])?;
+        assert_eq!(
+            active_names(&repo)?,
+            ["m1", "dir/m1"],
+            "submodule.<name>.active has the highest priority"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn name_active_can_deactivate() -> crate::Result {
+        let repo = crate::util::named_subrepo_opts(
+            "make_submodules.sh",
+            "with-submodules",
+            gix::open::Options::isolated().config_overrides(["submodule.dir/m1.active=false"]),
+        )?;
+        assert_eq!(active_names(&repo)?, ["m1"]);
+        Ok(())
+    }
+}
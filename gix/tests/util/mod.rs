@@ -73,6 +73,22 @@ pub fn repo_rw_opts(name: &str, opts: gix::open::Options) -> Result<(Repository,
     ))
 }
 
+pub fn named_subrepo_rw(fixture: &str, name: &str) -> Result<(Repository, tempfile::TempDir)> {
+    named_subrepo_rw_opts(fixture, name, restricted())
+}
+
+pub fn named_subrepo_rw_opts(
+    fixture: &str,
+    name: &str,
+    opts: open::Options,
+) -> Result<(Repository, tempfile::TempDir)> {
+    let repo_path = gix_testtools::scripted_fixture_writable(fixture)?;
+    Ok((
+        ThreadSafeRepository::open_opts(repo_path.path().join(name), opts)?.to_thread_local(),
+        repo_path,
+    ))
+}
+
 pub fn basic_repo() -> Result<Repository> {
     repo("make_basic_repo.sh").map(|r| r.to_thread_local())
 }
@@ -17,7 +17,7 @@ use gix::bstr::{io::BufReadExt, BString};
 use crate::plumbing::{
     options::{
         attributes, commit, commitgraph, config, credential, exclude, free, fsck, index, mailmap, odb, revision, tree,
-        Args, Subcommands,
+        var, Args, Subcommands,
     },
     show_progress,
 };
@@ -54,6 +54,14 @@ pub mod async_util {
 
 pub fn main() -> Result<()> {
     let args: Args = Args::parse_from(gix::env::args_os());
+    if args.schema {
+        if let Some(err) = schema_conflict(&args) {
+            err.exit()
+        }
+        serde_json::to_writer_pretty(std::io::stdout().lock(), &gitoxide::shared::schema(Args::command()))?;
+        println!();
+        return Ok(());
+    }
     #[allow(unsafe_code)]
     unsafe {
         // SAFETY: we don't manipulate the environment from any thread
@@ -62,7 +70,14 @@ pub fn main() -> Result<()> {
     let thread_limit = args.threads;
     let verbose = args.verbose;
     let format = args.format;
-    let cmd = args.cmd;
+    let Some(cmd) = args.cmd else {
+        Args::command()
+            .error(
+                clap::error::ErrorKind::MissingSubcommand,
+                "'gix' requires a subcommand but one was not provided",
+            )
+            .exit()
+    };
     #[cfg_attr(not(feature = "tracing"), allow(unused_mut))]
     #[cfg_attr(feature = "tracing", allow(unused_assignments))]
     let mut trace = false;
@@ -184,10 +199,217 @@ pub fn main() -> Result<()> {
                 )
             },
         ),
+        Subcommands::Blame(crate::plumbing::options::blame::Platform {
+            porcelain,
+            line_porcelain,
+            incremental,
+            statistics,
+            ignore_whitespace,
+            detect_moves,
+            detect_copies,
+            file,
+        }) => prepare_and_run(
+            "blame",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, err| {
+                use core::repository::blame::Format;
+                core::repository::blame::blame_file(
+                    repository(Mode::Lenient)?,
+                    file.as_ref(),
+                    out,
+                    err,
+                    core::repository::blame::Options {
+                        format,
+                        blame_format: if porcelain {
+                            Format::Porcelain
+                        } else if line_porcelain {
+                            Format::LinePorcelain
+                        } else if incremental {
+                            Format::Incremental
+                        } else {
+                            Format::Human
+                        },
+                        statistics,
+                        ignore_whitespace,
+                        detect_moves,
+                        detect_copies,
+                    },
+                )
+            },
+        ),
+        Subcommands::Switch(crate::plumbing::options::switch::Platform {
+            create,
+            detach,
+            force,
+            target,
+        }) => prepare_and_run(
+            "switch",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |progress, _out, err| {
+                core::repository::checkout(
+                    repository(Mode::Lenient)?,
+                    progress,
+                    err,
+                    core::repository::checkout::Options {
+                        revision: target,
+                        create_branch: create,
+                        detach,
+                        implicit_detach: false,
+                        force,
+                        paths: Vec::new(),
+                    },
+                )
+            },
+        ),
+        Subcommands::Checkout(crate::plumbing::options::checkout::Platform {
+            create,
+            detach,
+            force,
+            revision,
+            paths,
+        }) => prepare_and_run(
+            "checkout",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |progress, _out, err| {
+                core::repository::checkout(
+                    repository(Mode::Lenient)?,
+                    progress,
+                    err,
+                    core::repository::checkout::Options {
+                        revision,
+                        create_branch: create,
+                        detach,
+                        implicit_detach: true,
+                        force,
+                        paths,
+                    },
+                )
+            },
+        ),
+        Subcommands::Branch(crate::plumbing::options::branch::Platform { cmd }) => {
+            use crate::plumbing::options::branch::Subcommands;
+            use core::repository::branch::{Action, Kind};
+            let action = match cmd {
+                None => Action::List {
+                    kind: Kind::Local,
+                    verbose: 0,
+                },
+                Some(Subcommands::List { remotes, all, verbose }) => Action::List {
+                    kind: if all {
+                        Kind::All
+                    } else if remotes {
+                        Kind::Remote
+                    } else {
+                        Kind::Local
+                    },
+                    verbose,
+                },
+                Some(Subcommands::Create {
+                    force,
+                    name,
+                    start_point,
+                }) => Action::Create {
+                    name,
+                    start_point,
+                    force,
+                },
+                Some(Subcommands::Delete { force, names }) => Action::Delete { names, force },
+            };
+            prepare_and_run(
+                "branch",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, err| core::repository::branch(repository(Mode::Lenient)?, out, err, action),
+            )
+        }
+        Subcommands::Tag(crate::plumbing::options::tag::Platform { cmd }) => {
+            use crate::plumbing::options::tag::Subcommands;
+            use core::repository::tag::Action;
+            let action = match cmd {
+                None => Action::List { pattern: None },
+                Some(Subcommands::List { pattern }) => Action::List { pattern },
+                Some(Subcommands::Create {
+                    message,
+                    force,
+                    name,
+                    target,
+                }) => Action::Create {
+                    name,
+                    target,
+                    message,
+                    force,
+                },
+                Some(Subcommands::Delete { names }) => Action::Delete { names },
+            };
+            prepare_and_run(
+                "tag",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, err| core::repository::tag(repository(Mode::Lenient)?, out, err, action),
+            )
+        }
+        Subcommands::Stash(crate::plumbing::options::stash::Platform { cmd }) => {
+            use crate::plumbing::options::stash::Subcommands;
+            use core::repository::stash::Action;
+            let action = match cmd {
+                None => Action::Push {
+                    message: None,
+                    include_untracked: false,
+                },
+                Some(Subcommands::Push {
+                    message,
+                    include_untracked,
+                }) => Action::Push {
+                    message,
+                    include_untracked,
+                },
+                Some(Subcommands::List) => Action::List,
+                Some(Subcommands::Apply { index, entry }) => Action::Apply {
+                    index: entry,
+                    reinstate_index: index,
+                },
+                Some(Subcommands::Pop { index, entry }) => Action::Pop {
+                    index: entry,
+                    reinstate_index: index,
+                },
+                Some(Subcommands::Drop { entry }) => Action::Drop { index: entry },
+            };
+            prepare_and_run(
+                "stash",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, err| core::repository::stash(repository(Mode::Lenient)?, out, err, action),
+            )
+        }
         Subcommands::Status(crate::plumbing::options::status::Platform {
             statistics,
             submodules,
             no_write,
+            find_renames,
+            find_copies,
+            no_renames,
+            sparse,
             pathspec,
         }) => prepare_and_run(
             "status",
@@ -208,7 +430,32 @@ pub fn main() -> Result<()> {
                         format,
                         statistics,
                         thread_limit: thread_limit.or(cfg!(target_os = "macos").then_some(3)), // TODO: make this a configurable when in `gix`, this seems to be optimal on MacOS, linux scales though! MacOS also scales if reading a lot of files for refresh index
-                        allow_write: !no_write,
+                        index_write_back: if no_write {
+                            gix::index::write::WriteBack::Never
+                        } else {
+                            gix::index::write::WriteBack::IfUnchanged
+                        },
+                        sparse,
+                        renames: if no_renames {
+                            core::repository::status::Renames::Disabled
+                        } else if let Some(percent) = find_copies {
+                            let percentage = Some(f32::from(percent) / 100.0);
+                            core::repository::status::Renames::Enabled(gix::diff::Rewrites {
+                                copies: Some(gix::diff::rewrites::Copies {
+                                    percentage,
+                                    ..Default::default()
+                                }),
+                                percentage: find_renames.map_or(percentage, |percent| Some(f32::from(percent) / 100.0)),
+                                ..Default::default()
+                            })
+                        } else if let Some(percent) = find_renames {
+                            core::repository::status::Renames::Enabled(gix::diff::Rewrites {
+                                percentage: Some(f32::from(percent) / 100.0),
+                                ..Default::default()
+                            })
+                        } else {
+                            core::repository::status::Renames::FromConfiguration
+                        },
                         submodules: match submodules {
                             Submodules::All => core::repository::status::Submodules::All,
                             Submodules::RefChange => core::repository::status::Submodules::RefChange,
@@ -238,7 +485,9 @@ pub fn main() -> Result<()> {
             prefix,
             compression_level,
             add_path,
+            add_file,
             add_virtual_file,
+            recurse_submodules,
             output_file,
             treeish,
         }) => prepare_and_run(
@@ -249,12 +498,15 @@ pub fn main() -> Result<()> {
             progress_keep_open,
             None,
             move |progress, _out, _err| {
-                if add_virtual_file.len() % 2 != 0 {
-                    anyhow::bail!(
-                        "Virtual files must be specified in pairs of two: slash/separated/path content, got {}",
-                        add_virtual_file.join(", ")
-                    )
-                }
+                let files = add_virtual_file
+                    .into_iter()
+                    .map(|file| match file.split_once(':') {
+                        Some((path, content)) => Ok((path.to_owned(), content.to_owned())),
+                        None => Err(anyhow::anyhow!(
+                            "Virtual files must be specified as slash/separated/path:content, got '{file}'"
+                        )),
+                    })
+                    .collect::<Result<_, _>>()?;
                 core::repository::archive::stream(
                     repository(Mode::Lenient)?,
                     &output_file,
@@ -262,11 +514,10 @@ pub fn main() -> Result<()> {
                     progress,
                     core::repository::archive::Options {
                         add_paths: add_path,
+                        add_files: add_file,
                         prefix,
-                        files: add_virtual_file
-                            .chunks_exact(2)
-                            .map(|c| (c[0].clone(), c[1].clone()))
-                            .collect(),
+                        files,
+                        recurse_submodules,
                         format: format.map(|f| match f {
                             crate::plumbing::options::archive::Format::Internal => {
                                 gix::worktree::archive::Format::InternalTransientNonPersistable
@@ -275,6 +526,12 @@ pub fn main() -> Result<()> {
                             crate::plumbing::options::archive::Format::TarGz => {
                                 gix::worktree::archive::Format::TarGz { compression_level }
                             }
+                            crate::plumbing::options::archive::Format::TarXz => {
+                                gix::worktree::archive::Format::TarXz { compression_level }
+                            }
+                            crate::plumbing::options::archive::Format::TarZst => {
+                                gix::worktree::archive::Format::TarZst { compression_level }
+                            }
                             crate::plumbing::options::archive::Format::Zip => {
                                 gix::worktree::archive::Format::Zip { compression_level }
                             }
@@ -357,6 +614,7 @@ pub fn main() -> Result<()> {
             no_tags,
             remote,
             shallow,
+            filter,
             directory,
         }) => {
             let opts = core::repository::clone::Options {
@@ -365,6 +623,7 @@ pub fn main() -> Result<()> {
                 handshake_info,
                 no_tags,
                 shallow: shallow.into(),
+                filter,
             };
             prepare_and_run(
                 "clone",
@@ -377,6 +636,26 @@ pub fn main() -> Result<()> {
             )
         }
         #[cfg(feature = "gitoxide-core-blocking-client")]
+        Subcommands::Backfill(crate::plumbing::options::backfill::Platform { treeish, pathspec }) => prepare_and_run(
+            "backfill",
+            trace,
+            auto_verbose,
+            progress,
+            progress_keep_open,
+            core::repository::backfill::PROGRESS_RANGE,
+            move |progress, out, _err| {
+                core::repository::backfill(
+                    repository(Mode::LenientWithGitInstallConfig)?,
+                    progress,
+                    out,
+                    core::repository::backfill::Options {
+                        treeish: Some(treeish),
+                        pathspecs: pathspec,
+                    },
+                )
+            },
+        ),
+        #[cfg(feature = "gitoxide-core-blocking-client")]
         Subcommands::Fetch(crate::plumbing::options::fetch::Platform {
             dry_run,
             handshake_info,
@@ -384,6 +663,7 @@ pub fn main() -> Result<()> {
             open_negotiation_graph,
             remote,
             shallow,
+            refetch,
             ref_spec,
         }) => {
             let opts = core::repository::fetch::Options {
@@ -394,6 +674,7 @@ pub fn main() -> Result<()> {
                 negotiation_info,
                 open_negotiation_graph,
                 shallow: shallow.into(),
+                refetch,
                 ref_specs: ref_spec,
             };
             prepare_and_run(
@@ -408,20 +689,184 @@ pub fn main() -> Result<()> {
                 },
             )
         }
-        Subcommands::ConfigTree => show_progress(),
-        Subcommands::Credential(cmd) => core::repository::credential(
-            repository(Mode::StrictWithGitInstallConfig)?,
-            match cmd {
-                credential::Subcommands::Fill => gix::credentials::program::main::Action::Get,
-                credential::Subcommands::Approve => gix::credentials::program::main::Action::Store,
-                credential::Subcommands::Reject => gix::credentials::program::main::Action::Erase,
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        Subcommands::ReceivePack(crate::plumbing::options::receive_pack::Platform {
+            advertise_refs,
+            stateless_rpc,
+            directory,
+        }) => prepare_and_run(
+            "receive-pack",
+            trace,
+            false,
+            false,
+            false,
+            None,
+            move |_progress, out, err| {
+                core::repository::receive_pack(
+                    gix::open(directory)?,
+                    std::io::stdin().lock(),
+                    out,
+                    err,
+                    core::repository::receive_pack::Options {
+                        advertise_refs,
+                        stateless_rpc,
+                    },
+                )
+            },
+        ),
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        Subcommands::UploadPack(crate::plumbing::options::upload_pack::Platform {
+            advertise_refs,
+            stateless_rpc,
+            directory,
+        }) => prepare_and_run(
+            "upload-pack",
+            trace,
+            false,
+            false,
+            false,
+            None,
+            move |_progress, out, _err| {
+                core::repository::upload_pack(
+                    gix::open(directory)?,
+                    std::io::stdin().lock(),
+                    out,
+                    core::repository::upload_pack::Options {
+                        advertise_refs,
+                        stateless_rpc,
+                    },
+                )
             },
         ),
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        Subcommands::Serve(crate::plumbing::options::serve::Platform {
+            daemon,
+            listen,
+            port,
+            max_connections,
+            base_path,
+            export_all,
+            enable_receive_pack,
+            directories,
+        }) => prepare_and_run(
+            "serve",
+            trace,
+            verbose,
+            false,
+            false,
+            None,
+            move |_progress, out, _err| {
+                let opts = core::repository::serve::Options {
+                    base_path,
+                    export_all,
+                    enable_receive_pack,
+                    directories,
+                    max_connections: (max_connections != 0).then_some(max_connections),
+                };
+                if daemon {
+                    core::repository::daemon(
+                        (listen.as_str(), port),
+                        opts,
+                        std::io::stderr(),
+                        &gix::interrupt::IS_INTERRUPTED,
+                    )
+                } else {
+                    core::repository::serve(std::io::stdin().lock(), out, &opts)
+                }
+            },
+        ),
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        Subcommands::Push(crate::plumbing::options::push::Platform {
+            dry_run,
+            verbose,
+            force,
+            force_with_lease,
+            atomic,
+            push_options,
+            remote,
+            ref_spec,
+        }) => {
+            let opts = core::repository::push::Options {
+                remote,
+                ref_specs: ref_spec,
+                force,
+                force_with_lease,
+                atomic,
+                push_options,
+                dry_run,
+                verbose,
+            };
+            prepare_and_run(
+                "push",
+                trace,
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                core::repository::push::PROGRESS_RANGE,
+                move |progress, _out, err| {
+                    core::repository::push(repository(Mode::LenientWithGitInstallConfig)?, progress, err, opts)
+                },
+            )
+        }
+        Subcommands::ConfigTree => show_progress(),
+        #[cfg(unix)]
+        Subcommands::Credential(credential::Platform {
+            cmd:
+                credential::Subcommands::Cache {
+                    timeout,
+                    socket,
+                    action,
+                },
+            ..
+        }) => {
+            let socket = match socket {
+                Some(socket) => socket,
+                None => gix::credentials::cache::socket_path()
+                    .ok_or_else(|| anyhow!("Could not determine the socket path as the home directory is unknown"))?,
+            };
+            let mut options = gix::credentials::cache::Options::new(socket);
+            options.timeout = std::time::Duration::from_secs(timeout);
+            options.daemon = vec![
+                std::env::current_exe()?.into(),
+                "credential".into(),
+                "cache-daemon".into(),
+            ];
+            core::repository::credential::cache(&action, &options)
+        }
+        #[cfg(unix)]
+        Subcommands::Credential(credential::Platform {
+            cmd: credential::Subcommands::CacheDaemon { socket },
+            ..
+        }) => core::repository::credential::cache_daemon(&socket),
+        Subcommands::Credential(credential::Platform { helpers, cmd }) => {
+            use gix::credentials::program::main::Action;
+            let (action, as_helper) = match cmd {
+                credential::Subcommands::Fill => (Action::Get, false),
+                credential::Subcommands::Approve => (Action::Store, false),
+                credential::Subcommands::Reject => (Action::Erase, false),
+                credential::Subcommands::Get => (Action::Get, true),
+                credential::Subcommands::Store => (Action::Store, true),
+                credential::Subcommands::Erase => (Action::Erase, true),
+                #[cfg(unix)]
+                credential::Subcommands::Cache { .. } | credential::Subcommands::CacheDaemon { .. } => {
+                    unreachable!("handled above")
+                }
+            };
+            core::repository::credential(
+                repository(Mode::StrictWithGitInstallConfig)?,
+                action,
+                core::repository::credential::Options {
+                    helpers: helpers.into_iter().map(Into::into).collect(),
+                    as_helper,
+                },
+            )
+        }
         #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
         Subcommands::Remote(crate::plumbing::options::remote::Platform {
             name,
             cmd,
             handshake_info,
+            cache_ttl,
         }) => {
             use crate::plumbing::options::remote;
             match cmd {
@@ -440,6 +885,7 @@ pub fn main() -> Result<()> {
                         name_or_url: name,
                         format,
                         handshake_info,
+                        cache_ttl: cache_ttl.map(std::time::Duration::from_secs),
                     };
                     #[cfg(feature = "gitoxide-core-blocking-client")]
                     {
@@ -482,7 +928,38 @@ pub fn main() -> Result<()> {
                 }
             }
         }
-        Subcommands::Config(config::Platform { filter }) => prepare_and_run(
+        Subcommands::Config(config::Platform {
+            set,
+            unset,
+            remove_section,
+            scope,
+            ..
+        }) if !(set.is_empty() && unset.is_empty() && remove_section.is_empty()) => prepare_and_run(
+            "config-edit",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::repository::config::edit(
+                    repository(Mode::Lenient)?,
+                    scope.into(),
+                    core::repository::config::Edits {
+                        set,
+                        unset,
+                        remove_sections: remove_section,
+                    },
+                    out,
+                )
+            },
+        ),
+        Subcommands::Config(config::Platform {
+            filter,
+            show_origin,
+            show_scope,
+            ..
+        }) => prepare_and_run(
             "config-list",
             trace,
             verbose,
@@ -495,11 +972,26 @@ pub fn main() -> Result<()> {
                     filter,
                     config,
                     format,
+                    core::repository::config::Options {
+                        show_origin,
+                        show_scope,
+                    },
                     out,
                 )
             },
         )
         .map(|_| ()),
+        Subcommands::Var(var::Platform { list: _, name }) => prepare_and_run(
+            "var",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::repository::var(repository(Mode::LenientWithGitInstallConfig)?, name.as_deref(), out)
+            },
+        ),
         Subcommands::Free(subcommands) => match subcommands {
             free::Subcommands::Discover => prepare_and_run(
                 "discover",
@@ -897,6 +1389,7 @@ pub fn main() -> Result<()> {
                     decode,
                     re_encode,
                 },
+            also,
         } => prepare_and_run(
             "verify",
             trace,
@@ -905,18 +1398,21 @@ pub fn main() -> Result<()> {
             progress_keep_open,
             core::repository::verify::PROGRESS_RANGE,
             move |progress, out, _err| {
-                core::repository::verify::integrity(
-                    repository(Mode::Strict)?,
-                    out,
-                    progress,
-                    &should_interrupt,
-                    core::repository::verify::Context {
-                        output_statistics: statistics.then_some(format),
-                        algorithm,
-                        verify_mode: verify_mode(decode, re_encode),
-                        thread_limit,
-                    },
-                )
+                let context = core::repository::verify::Context {
+                    output_statistics: statistics.then_some(format),
+                    algorithm,
+                    verify_mode: verify_mode(decode, re_encode),
+                    thread_limit,
+                };
+                let repo = repository(Mode::Strict)?;
+                if also.is_empty() {
+                    core::repository::verify::integrity(repo, out, progress, &should_interrupt, context)
+                } else {
+                    let repos = std::iter::once(Ok(repo))
+                        .chain(also.into_iter().map(gix::open))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    core::repository::verify::integrity_of_many(repos, out, progress, &should_interrupt, context)
+                }
             },
         ),
         Subcommands::Revision(cmd) => match cmd {
@@ -1012,8 +1508,41 @@ pub fn main() -> Result<()> {
                 },
             ),
         },
-        Subcommands::Commit(cmd) => match cmd {
-            commit::Subcommands::Verify { rev_spec } => prepare_and_run(
+        Subcommands::Commit(commit::Platform {
+            message,
+            file,
+            amend,
+            allow_empty,
+            signoff,
+            no_verify,
+            no_edit,
+            cmd,
+        }) => match cmd {
+            None => prepare_and_run(
+                "commit",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |progress, _out, err| {
+                    core::repository::commit::create(
+                        repository(Mode::Strict)?,
+                        progress,
+                        err,
+                        core::repository::commit::create::Options {
+                            messages: message,
+                            file,
+                            amend,
+                            allow_empty,
+                            signoff,
+                            no_verify,
+                            no_edit,
+                        },
+                    )
+                },
+            ),
+            Some(commit::Subcommands::Verify { rev_spec }) => prepare_and_run(
                 "commit-verify",
                 trace,
                 auto_verbose,
@@ -1024,7 +1553,7 @@ pub fn main() -> Result<()> {
                     core::repository::commit::verify(repository(Mode::Lenient)?, rev_spec.as_deref())
                 },
             ),
-            commit::Subcommands::Describe {
+            Some(commit::Subcommands::Describe {
                 annotated_tags,
                 all_refs,
                 first_parent,
@@ -1033,7 +1562,7 @@ pub fn main() -> Result<()> {
                 statistics,
                 max_candidates,
                 rev_spec,
-            } => prepare_and_run(
+            }) => prepare_and_run(
                 "commit-describe",
                 trace,
                 verbose,
@@ -1138,14 +1667,112 @@ pub fn main() -> Result<()> {
                 move |_progress, out, err| core::repository::odb::info(repository(Mode::Strict)?, format, out, err),
             ),
         },
-        Subcommands::Fsck(fsck::Platform { spec }) => prepare_and_run(
+        #[cfg(feature = "gitoxide-core-tools-fsmonitor")]
+        Subcommands::Fsmonitor(cmd) => match cmd {
+            crate::plumbing::options::fsmonitor::Subcommands::Run => prepare_and_run(
+                "fsmonitor-run",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, err| {
+                    core::repository::fsmonitor::run(repository(Mode::Lenient)?, err, &should_interrupt)
+                },
+            ),
+            crate::plumbing::options::fsmonitor::Subcommands::Query { token } => prepare_and_run(
+                "fsmonitor-query",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::fsmonitor::query(repository(Mode::Lenient)?, token, out, format)
+                },
+            ),
+            crate::plumbing::options::fsmonitor::Subcommands::Stop => prepare_and_run(
+                "fsmonitor-stop",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, _err| core::repository::fsmonitor::stop(repository(Mode::Lenient)?),
+            ),
+        },
+        Subcommands::Fsck(fsck::Platform {
+            unreachable,
+            skip_hash_verification,
+            spec,
+        }) => prepare_and_run(
             "fsck",
             trace,
             auto_verbose,
             progress,
             progress_keep_open,
+            core::repository::fsck::PROGRESS_RANGE,
+            move |progress, out, _err| {
+                core::repository::fsck::function(
+                    repository(Mode::Strict)?,
+                    out,
+                    progress,
+                    &should_interrupt,
+                    core::repository::fsck::Options {
+                        spec,
+                        unreachable,
+                        skip_hash_verification,
+                    },
+                )
+            },
+        ),
+        Subcommands::Log(crate::plumbing::options::log::Platform {
+            line_ranges,
+            statistics,
+        }) => prepare_and_run(
+            "log",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, err| {
+                core::repository::log::line_ranges(
+                    repository(Mode::Lenient)?,
+                    &line_ranges,
+                    out,
+                    err,
+                    core::repository::log::Options { format, statistics },
+                )
+            },
+        ),
+        Subcommands::Shortlog(crate::plumbing::options::shortlog::Platform {
+            numbered,
+            summary,
+            email,
+            committer,
+            spec,
+        }) => prepare_and_run(
+            "shortlog",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
             None,
-            move |_progress, out, _err| core::repository::fsck(repository(Mode::Strict)?, spec, out),
+            move |_progress, out, _err| {
+                core::repository::shortlog::shortlog(
+                    repository(Mode::Lenient)?,
+                    spec.as_ref().map(AsRef::as_ref),
+                    out,
+                    core::repository::shortlog::Options {
+                        format,
+                        summary,
+                        numbered,
+                        email,
+                        committer,
+                    },
+                )
+            },
         ),
         Subcommands::Mailmap(cmd) => match cmd {
             mailmap::Subcommands::Entries => prepare_and_run(
@@ -1348,6 +1975,16 @@ fn verify_mode(decode: bool, re_encode: bool) -> verify::Mode {
     }
 }
 
+/// `exclusive = true` makes `clap` reject other arguments along with `--schema`, but not subcommands, which we check here.
+fn schema_conflict(args: &Args) -> Option<clap::Error> {
+    (args.schema && args.cmd.is_some()).then(|| {
+        Args::command().error(
+            clap::error::ErrorKind::ArgumentConflict,
+            "'--schema' cannot be used with a subcommand",
+        )
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1357,4 +1994,25 @@ mod tests {
         use clap::CommandFactory;
         Args::command().debug_assert();
     }
+
+    #[test]
+    fn schema_conflicts_with_arguments_and_subcommands() {
+        use clap::Parser;
+        let args = Args::try_parse_from(["gix", "--schema"]).expect("valid");
+        assert!(args.schema);
+        assert!(schema_conflict(&args).is_none());
+
+        assert_eq!(
+            Args::try_parse_from(["gix", "--schema", "--verbose"])
+                .expect_err("clap rejects other arguments")
+                .kind(),
+            clap::error::ErrorKind::ArgumentConflict
+        );
+
+        let args = Args::try_parse_from(["gix", "--schema", "status"]).expect("clap doesn't consider subcommands");
+        assert_eq!(
+            schema_conflict(&args).expect("subcommands are rejected").kind(),
+            clap::error::ErrorKind::ArgumentConflict
+        );
+    }
 }
@@ -6,7 +6,6 @@ use gix::bstr::BString;
 
 #[derive(Debug, clap::Parser)]
 #[clap(name = "gix", about = "The git underworld", version = option_env!("GIX_VERSION"))]
-#[clap(subcommand_required = true)]
 #[clap(arg_required_else_help = true)]
 pub struct Args {
     /// The repository to access.
@@ -72,8 +71,13 @@ pub struct Args {
     #[clap(long, default_value_t = gix::hash::Kind::default(), value_parser = gitoxide::shared::AsHashKind)]
     pub object_hash: gix::hash::Kind,
 
+    /// Print a description of all subcommands and their arguments as JSON, for use by tools that wrap `gix`.
+    #[clap(long, exclusive = true)]
+    pub schema: bool,
+
+    /// The subcommand to run, which is required unless `--schema` is given.
     #[clap(subcommand)]
-    pub cmd: Subcommands,
+    pub cmd: Option<Subcommands>,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -83,6 +87,8 @@ pub enum Subcommands {
     Archive(archive::Platform),
     #[cfg(feature = "gitoxide-core-tools-clean")]
     Clean(clean::Command),
+    /// Show which commit introduced each line of a file, similar to `git blame`.
+    Blame(blame::Platform),
     /// Subcommands for interacting with commit-graphs
     #[clap(subcommand)]
     CommitGraph(commitgraph::Subcommands),
@@ -91,31 +97,55 @@ pub enum Subcommands {
     Odb(odb::Subcommands),
     /// Check for missing objects.
     Fsck(fsck::Platform),
+    /// Run or query a filesystem monitor daemon, similar to `git fsmonitor--daemon`.
+    #[cfg(feature = "gitoxide-core-tools-fsmonitor")]
+    #[clap(subcommand)]
+    Fsmonitor(fsmonitor::Subcommands),
     /// Interact with tree objects.
     #[clap(subcommand)]
     Tree(tree::Subcommands),
-    /// Interact with commit objects.
-    #[clap(subcommand)]
-    Commit(commit::Subcommands),
+    /// Record the index as a new commit like `git commit`, or interact with commit objects.
+    Commit(commit::Platform),
     /// Verify the integrity of the entire repository
     Verify {
         #[clap(flatten)]
         args: free::pack::VerifyOptions,
+        /// Paths to additional repositories to verify concurrently along with the current one.
+        #[clap(long = "also", value_name = "REPOSITORY")]
+        also: Vec<std::path::PathBuf>,
     },
     /// Query and obtain information about revisions.
     #[clap(subcommand)]
     Revision(revision::Subcommands),
-    /// A program just like `git credential`.
-    #[clap(subcommand)]
-    Credential(credential::Subcommands),
+    /// A program just like `git credential`, which can also act as credential helper.
+    Credential(credential::Platform),
     /// Fetch data from remotes and store it in the repository
     #[cfg(feature = "gitoxide-core-blocking-client")]
     Fetch(fetch::Platform),
     #[cfg(feature = "gitoxide-core-blocking-client")]
     Clone(clone::Platform),
+    /// Fetch the blobs missing in a partial clone in a single batch, similar to `git backfill`.
+    #[cfg(feature = "gitoxide-core-blocking-client")]
+    Backfill(backfill::Platform),
+    /// Update references on a remote along with the objects they need, similar to `git push`.
+    #[cfg(feature = "gitoxide-core-blocking-client")]
+    Push(push::Platform),
+    /// Receive references and objects pushed by a client via stdin and stdout, similar to `git receive-pack`.
+    #[cfg(feature = "gitoxide-core-blocking-client")]
+    ReceivePack(receive_pack::Platform),
+    /// Send objects to a client fetching them via stdin and stdout, similar to `git upload-pack`.
+    #[cfg(feature = "gitoxide-core-blocking-client")]
+    UploadPack(upload_pack::Platform),
+    /// Serve repositories via the `git://` protocol, similar to `git daemon`.
+    #[cfg(feature = "gitoxide-core-blocking-client")]
+    Serve(serve::Platform),
+    /// Show the history of ranges of lines, similar to `git log -L`.
+    Log(log::Platform),
     /// Interact with the mailmap.
     #[clap(subcommand)]
     Mailmap(mailmap::Subcommands),
+    /// Summarize commits by author or committer, similar to `git shortlog`.
+    Shortlog(shortlog::Platform),
     /// Interact with the remote hosts.
     #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
     Remote(remote::Platform),
@@ -133,7 +163,18 @@ pub enum Subcommands {
     /// Show which git configuration values are used or planned.
     ConfigTree,
     Status(status::Platform),
+    /// Switch to a branch, optionally creating it first, similar to `git switch`.
+    Switch(switch::Platform),
+    /// Check out a branch or detach `HEAD` at a revision, or restore paths from it, similar to `git checkout`.
+    Checkout(checkout::Platform),
+    /// List, create or delete branches, similar to `git branch`.
+    Branch(branch::Platform),
+    /// List, create or delete tags, similar to `git tag`.
+    Tag(tag::Platform),
+    /// Stash away local changes and restore them later, similar to `git stash`.
+    Stash(stash::Platform),
     Config(config::Platform),
+    Var(var::Platform),
     #[cfg(feature = "gitoxide-core-tools-corpus")]
     Corpus(corpus::Platform),
     /// Subcommands that need no git repository to run.
@@ -165,6 +206,10 @@ pub mod archive {
         Tar,
         /// Use the `.tar.gz` file format, compressed with `gzip`.
         TarGz,
+        /// Use the `.tar.xz` file format, compressed with `xz`.
+        TarXz,
+        /// Use the `.tar.zst` file format, compressed with `zstd`.
+        TarZst,
         /// Use the `.zip` container format.
         Zip,
     }
@@ -177,15 +222,21 @@ pub mod archive {
         /// Apply the prefix verbatim to any path we add to the archive. Use a trailing `/` if prefix is a directory.
         #[clap(long)]
         pub prefix: Option<String>,
-        /// The compression strength to use for `.zip` and `.tar.gz` archives, valid from 0-9.
+        /// The compression strength to use for `.zip`, `.tar.gz` and `.tar.xz` archives, valid from 0-9, or from 1-22 for `.tar.zst` archives.
         #[clap(long, short = 'l', requires = "format")]
         pub compression_level: Option<u8>,
         /// Add the given path to the archive. Directories will always be empty.
         #[clap(long, short = 'p')]
         pub add_path: Vec<PathBuf>,
-        /// Add the new file from a slash-separated path, which must happen in pairs of two, first the path, then the content.
-        #[clap(long, short = 'v')]
+        /// Add the given file to the root of the archive under its file name, even if it's not in the worktree.
+        #[clap(long, value_name = "FILE")]
+        pub add_file: Vec<PathBuf>,
+        /// Add a new file with the given content at the slash-separated path, specified as `<path>:<content>`.
+        #[clap(long, short = 'v', value_name = "PATH:CONTENT")]
         pub add_virtual_file: Vec<String>,
+        /// Add the worktree of each submodule at the commit recorded in the tree, recursively.
+        #[clap(long)]
+        pub recurse_submodules: bool,
         /// The file to write the archive to.
         ///
         /// It's extension determines the archive format, unless `--format` is set.
@@ -198,6 +249,174 @@ pub mod archive {
     }
 }
 
+pub mod switch {
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Create a new branch with the given name at the start-point and switch to it.
+        #[clap(short = 'c', long, visible_short_alias = 'b', value_name = "NEW_BRANCH")]
+        pub create: Option<String>,
+        /// Detach `HEAD` at the given revision instead of switching to a branch.
+        #[clap(long, conflicts_with = "create")]
+        pub detach: bool,
+        /// Discard local changes and overwrite untracked files that are in the way.
+        #[clap(long, short = 'f', visible_alias = "discard-changes")]
+        pub force: bool,
+        /// The branch to switch to, or the revision to detach at or to start a new branch from, `HEAD` if unset.
+        pub target: Option<String>,
+    }
+}
+
+pub mod checkout {
+    use gix::bstr::BString;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Create a new branch with the given name at the start-point and switch to it.
+        #[clap(short = 'b', value_name = "NEW_BRANCH")]
+        pub create: Option<String>,
+        /// Detach `HEAD` at the given revision even if it names a local branch.
+        #[clap(long, conflicts_with = "create")]
+        pub detach: bool,
+        /// Discard local changes and overwrite untracked files that are in the way.
+        #[clap(long, short = 'f')]
+        pub force: bool,
+        /// The branch to check out or the revision to detach `HEAD` at, or the source to restore `paths` from.
+        pub revision: Option<String>,
+        /// Restore these paths from `revision`, or from the index if unset, without changing `HEAD`.
+        #[clap(last = true, value_parser = gitoxide::shared::AsBString)]
+        pub paths: Vec<BString>,
+    }
+}
+
+pub mod branch {
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// The action to perform, listing local branches if unset.
+        #[clap(subcommand)]
+        pub cmd: Option<Subcommands>,
+    }
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// List local branches, marking the one that is checked out.
+        List {
+            /// List remote tracking branches instead of local ones.
+            #[clap(long, short = 'r', conflicts_with = "all")]
+            remotes: bool,
+            /// List both local and remote tracking branches.
+            #[clap(long, short = 'a')]
+            all: bool,
+            /// Show the tip of each branch, and with `-vv` also the upstream it is ahead or behind of.
+            #[clap(long, short = 'v', action = clap::ArgAction::Count)]
+            verbose: u8,
+        },
+        /// Create a new branch without checking it out.
+        Create {
+            /// Overwrite the branch if it already exists.
+            #[clap(long, short = 'f')]
+            force: bool,
+            /// The name of the branch to create.
+            name: String,
+            /// The revision to point the branch to, `HEAD` if unset.
+            start_point: Option<String>,
+        },
+        /// Delete branches, as long as they are merged into their upstream or `HEAD`.
+        Delete {
+            /// Delete the branches even if they are not merged.
+            #[clap(long, short = 'f')]
+            force: bool,
+            /// The names of the branches to delete.
+            #[clap(required = true)]
+            names: Vec<String>,
+        },
+    }
+}
+
+pub mod tag {
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// The action to perform, listing all tags if unset.
+        #[clap(subcommand)]
+        pub cmd: Option<Subcommands>,
+    }
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// List tags in lexicographical order.
+        List {
+            /// Only list tags matching this glob pattern, like `v1.*`.
+            pattern: Option<String>,
+        },
+        /// Create a lightweight tag, or an annotated one if a message is given.
+        Create {
+            /// The message of the annotated tag to create.
+            #[clap(long, short = 'm')]
+            message: Option<String>,
+            /// Overwrite the tag if it already exists.
+            #[clap(long, short = 'f')]
+            force: bool,
+            /// The name of the tag to create.
+            name: String,
+            /// The revision to tag, `HEAD` if unset.
+            target: Option<String>,
+        },
+        /// Delete tags.
+        Delete {
+            /// The names of the tags to delete.
+            #[clap(required = true)]
+            names: Vec<String>,
+        },
+    }
+}
+
+pub mod stash {
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// The action to perform, stashing all local changes if unset.
+        #[clap(subcommand)]
+        pub cmd: Option<Subcommands>,
+    }
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Save local changes as a new stash entry and reset the worktree to `HEAD`.
+        Push {
+            /// Describe the stash entry with this message.
+            #[clap(long, short = 'm')]
+            message: Option<String>,
+            /// Also stash untracked files and remove them from the worktree.
+            #[clap(long, short = 'u')]
+            include_untracked: bool,
+        },
+        /// List all stash entries, the most recent one first.
+        List,
+        /// Apply a stash entry to the worktree, keeping it in the stash.
+        Apply {
+            /// Also restore the stashed index.
+            #[clap(long)]
+            index: bool,
+            /// The position of the entry to apply, with 0 being the most recent one.
+            #[clap(default_value_t = 0)]
+            entry: usize,
+        },
+        /// Apply a stash entry to the worktree and drop it if there were no conflicts.
+        Pop {
+            /// Also restore the stashed index.
+            #[clap(long)]
+            index: bool,
+            /// The position of the entry to apply, with 0 being the most recent one.
+            #[clap(default_value_t = 0)]
+            entry: usize,
+        },
+        /// Remove a stash entry.
+        Drop {
+            /// The position of the entry to drop, with 0 being the most recent one.
+            #[clap(default_value_t = 0)]
+            entry: usize,
+        },
+    }
+}
+
 pub mod status {
     use gitoxide::shared::CheckPathSpec;
     use gix::bstr::BString;
@@ -223,14 +442,62 @@ pub mod status {
         #[clap(long, short = 's')]
         pub statistics: bool,
         /// Don't write back a changed index, which forces this operation to always be idempotent.
+        ///
+        /// Otherwise, the index is only written if no other process holds its lock and if it didn't change in the meantime.
         #[clap(long)]
         pub no_write: bool,
+        /// Track renames of staged files, optionally with the minimal similarity in percent, similar to `git status -M`.
+        #[clap(long, short = 'M', value_name = "PERCENT", num_args = 0..=1, require_equals = true, default_missing_value = "50", value_parser = clap::value_parser!(u8).range(0..=100))]
+        pub find_renames: Option<u8>,
+        /// Track copies and renames of staged files, optionally with the minimal similarity in percent.
+        #[clap(long, short = 'C', value_name = "PERCENT", num_args = 0..=1, require_equals = true, default_missing_value = "50", value_parser = clap::value_parser!(u8).range(0..=100))]
+        pub find_copies: Option<u8>,
+        /// Don't track renames of staged files, even if configured, similar to `git status --no-renames`.
+        #[clap(long, conflicts_with_all = ["find_renames", "find_copies"])]
+        pub no_renames: bool,
+        /// List files that are excluded from the worktree by sparse checkout with status `S`, similar to `git ls-files -t`.
+        #[clap(long)]
+        pub sparse: bool,
         /// The git path specifications to list attributes for, or unset to read from stdin one per line.
         #[clap(value_parser = CheckPathSpec)]
         pub pathspec: Vec<BString>,
     }
 }
 
+pub mod blame {
+    use gix::bstr::BString;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Show information about each commit only the first time it's mentioned, similar to `git blame --porcelain`.
+        #[clap(long, conflicts_with_all = ["line_porcelain", "incremental"])]
+        pub porcelain: bool,
+        /// Show information about the commit of each line, similar to `git blame --line-porcelain`.
+        #[clap(long, conflicts_with = "incremental")]
+        pub line_porcelain: bool,
+        /// Show one record per range of lines in the order in which they were found, similar to `git blame --incremental`.
+        #[clap(long)]
+        pub incremental: bool,
+        /// Print additional statistics to help understanding performance.
+        #[clap(long, short = 's')]
+        pub statistics: bool,
+        /// Ignore whitespace when comparing lines, so lines that only changed their indentation keep their attribution.
+        #[clap(short = 'w')]
+        pub ignore_whitespace: bool,
+        /// Detect lines that were moved within the file, optionally with the minimum amount of alphanumeric characters
+        /// of a block of moved lines as in `-M=<score>`.
+        #[clap(short = 'M', num_args = 0..=1, default_missing_value = "20", require_equals = true)]
+        pub detect_moves: Option<u32>,
+        /// Detect lines that were moved or copied from other files changed in the same commit, optionally with the minimum
+        /// amount of alphanumeric characters of a block of copied lines as in `-C=<score>`.
+        #[clap(short = 'C', num_args = 0..=1, default_missing_value = "40", require_equals = true)]
+        pub detect_copies: Option<u32>,
+        /// The path to the file to blame, relative to the root of the repository.
+        #[clap(value_parser = gitoxide::shared::AsBString)]
+        pub file: BString,
+    }
+}
+
 #[cfg(feature = "gitoxide-core-tools-corpus")]
 pub mod corpus {
     use std::path::PathBuf;
@@ -287,6 +554,61 @@ pub mod config {
         /// and comparisons are case-insensitive.
         #[clap(value_parser = gitoxide::shared::AsBString)]
         pub filter: Vec<BString>,
+        /// Print each value on its own line, prefixed with the file or other origin it was read from.
+        #[clap(long)]
+        pub show_origin: bool,
+        /// Print each value on its own line, prefixed with the scope it was read from, like `global` or `local`.
+        #[clap(long)]
+        pub show_scope: bool,
+
+        /// Set a value given as `section.subsection.key=value` in the configuration file of `--scope`, instead of listing values.
+        #[clap(long, value_name = "KEY=VALUE", value_parser = gitoxide::shared::AsBString, conflicts_with_all = ["filter", "show_origin", "show_scope"])]
+        pub set: Vec<BString>,
+        /// Remove all values of a key given as `section.subsection.key` from the configuration file of `--scope`.
+        #[clap(long, value_name = "KEY", value_parser = gitoxide::shared::AsBString, conflicts_with_all = ["filter", "show_origin", "show_scope"])]
+        pub unset: Vec<BString>,
+        /// Remove all sections given as `section.subsection` from the configuration file of `--scope`.
+        #[clap(long, value_name = "SECTION", value_parser = gitoxide::shared::AsBString, conflicts_with_all = ["filter", "show_origin", "show_scope"])]
+        pub remove_section: Vec<BString>,
+        /// The configuration file to edit, with all comments and formatting preserved.
+        #[clap(long, value_enum, default_value = "local")]
+        pub scope: Scope,
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+    pub enum Scope {
+        /// The configuration file of the repository.
+        Local,
+        /// The configuration file of the current worktree, used if `extensions.worktreeConfig` is enabled.
+        Worktree,
+        /// The configuration file of the current user, `~/.gitconfig`.
+        Global,
+        /// The configuration file shared by all users of the system.
+        System,
+    }
+
+    impl From<Scope> for gix::config::Source {
+        fn from(scope: Scope) -> Self {
+            match scope {
+                Scope::Local => gix::config::Source::Local,
+                Scope::Worktree => gix::config::Source::Worktree,
+                Scope::Global => gix::config::Source::User,
+                Scope::System => gix::config::Source::System,
+            }
+        }
+    }
+}
+
+pub mod var {
+    /// Print the value of a logical variable like `GIT_EDITOR`, similar to `git var`.
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// List all variables and their values as `NAME=value`.
+        #[clap(long, short = 'l', conflicts_with = "name")]
+        pub list: bool,
+        /// The name of the variable to print, like `GIT_AUTHOR_IDENT`, `GIT_EDITOR` or `GIT_PAGER`.
+        #[clap(required_unless_present = "list")]
+        pub name: Option<String>,
     }
 }
 
@@ -317,6 +639,12 @@ pub mod fetch {
         #[clap(flatten)]
         pub shallow: ShallowOptions,
 
+        /// Fetch all objects of the wanted refs as if this was a fresh clone, instead of only the ones we don't have yet.
+        ///
+        /// Use it to replace corrupted objects, or to receive objects that were filtered out by a previous partial clone filter.
+        #[clap(long)]
+        pub refetch: bool,
+
         /// The name of the remote to connect to, or the url of the remote to connect to directly.
         ///
         /// If unset, the current branch will determine the remote.
@@ -374,10 +702,150 @@ pub mod fetch {
 }
 
 #[cfg(feature = "gitoxide-core-blocking-client")]
+pub mod push {
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Connect to the remote and decide what to update, but don't send anything.
+        #[clap(long, short = 'n')]
+        pub dry_run: bool,
+
+        /// Also list references that are already up to date on the remote.
+        #[clap(long, short = 'v')]
+        pub verbose: bool,
+
+        /// Allow updates that aren't fast-forwards, which may lose commits on the remote.
+        #[clap(long, short = 'f')]
+        pub force: bool,
+
+        /// Allow updates that aren't fast-forwards only if the remote reference still has the expected value.
+        ///
+        /// Without `EXPECT`, the value of the remote tracking branch is expected, and without `REFNAME` the lease
+        /// applies to all pushed references. An empty `EXPECT` means the reference must not exist yet.
+        #[clap(
+            long,
+            value_name = "REFNAME[:EXPECT]",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = ""
+        )]
+        pub force_with_lease: Vec<String>,
+
+        /// Update either all references on the remote or none of them.
+        #[clap(long)]
+        pub atomic: bool,
+
+        /// Transmit the given string to the remote, which passes it to its hooks.
+        #[clap(long = "push-option", short = 'o', value_name = "OPTION")]
+        pub push_options: Vec<String>,
+
+        /// The name of the remote to connect to, or the url of the remote to connect to directly.
+        ///
+        /// If unset, the current branch will determine the remote.
+        #[clap(long, short = 'r')]
+        pub remote: Option<String>,
+
+        /// Override the configured ref-specs and those implied by `push.default` with one or more of the given ones.
+        #[clap(value_parser = gitoxide::shared::AsBString)]
+        pub ref_spec: Vec<gix::bstr::BString>,
+    }
+}
+
+#[cfg(feature = "gitoxide-core-blocking-client")]
+pub mod receive_pack {
+    use std::path::PathBuf;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Only advertise the references of the repository, for use by smart HTTP servers.
+        #[clap(long)]
+        pub advertise_refs: bool,
+
+        /// Don't advertise references but read commands right away, for use by smart HTTP servers.
+        #[clap(long)]
+        pub stateless_rpc: bool,
+
+        /// The repository to receive objects and references in.
+        pub directory: PathBuf,
+    }
+}
+
+#[cfg(feature = "gitoxide-core-blocking-client")]
+pub mod upload_pack {
+    use std::path::PathBuf;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Only advertise the references of the repository, for use by smart HTTP servers.
+        #[clap(long)]
+        pub advertise_refs: bool,
+
+        /// Don't advertise references but read wants right away, and stop after one round of negotiation,
+        /// for use by smart HTTP servers.
+        #[clap(long)]
+        pub stateless_rpc: bool,
+
+        /// The repository to send objects from.
+        pub directory: PathBuf,
+    }
+}
+
+#[cfg(feature = "gitoxide-core-blocking-client")]
+pub mod serve {
+    use std::path::PathBuf;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Listen for connections on the `git://` port instead of serving a single connection via stdin and stdout.
+        #[clap(long)]
+        pub daemon: bool,
+
+        /// The address to listen on when running as daemon.
+        #[clap(long, default_value = "0.0.0.0", requires = "daemon")]
+        pub listen: String,
+
+        /// The port to listen on when running as daemon.
+        #[clap(long, default_value_t = 9418, requires = "daemon")]
+        pub port: u16,
+
+        /// The maximum amount of connections to serve at the same time when running as daemon, or 0 for no limit.
+        #[clap(long, default_value_t = 32, requires = "daemon")]
+        pub max_connections: usize,
+
+        /// Resolve the paths of all requested repositories relative to this directory.
+        #[clap(long)]
+        pub base_path: Option<PathBuf>,
+
+        /// Serve all repositories, even those without the `git-daemon-export-ok` file.
+        #[clap(long)]
+        pub export_all: bool,
+
+        /// Allow pushing via `receive-pack`, which can be overridden with `daemon.receivePack` in each repository.
+        #[clap(long)]
+        pub enable_receive_pack: bool,
+
+        /// Only serve repositories in these directories.
+        pub directories: Vec<PathBuf>,
+    }
+}
+
+#[cfg(feature = "gitoxide-core-blocking-client")]
+pub mod backfill {
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// The tree-ish whose missing blobs should be fetched, like a commit or a tree.
+        #[clap(long, short = 't', value_name = "TREEISH", default_value = "HEAD")]
+        pub treeish: String,
+
+        /// Only fetch blobs whose paths match the given pathspecs, or all missing blobs if none are given.
+        #[clap(value_parser = gitoxide::shared::AsBString)]
+        pub pathspec: Vec<gix::bstr::BString>,
+    }
+}
+
 pub mod clone {
     use std::{ffi::OsString, num::NonZeroU32, path::PathBuf};
 
-    use gix::remote::fetch::Shallow;
+    use gix::{bstr::BString, remote::fetch::Shallow};
 
     #[derive(Debug, clap::Parser)]
     pub struct Platform {
@@ -396,6 +864,12 @@ pub mod clone {
         #[clap(flatten)]
         pub shallow: ShallowOptions,
 
+        /// Create a partial clone which only contains objects passing the given filter, like `blob:none`.
+        ///
+        /// Missing objects are fetched from the remote on demand.
+        #[clap(long, value_name = "FILTER_SPEC", value_parser = gitoxide::shared::AsBString)]
+        pub filter: Option<BString>,
+
         /// The url of the remote to connect to, like `https://github.com/byron/gitoxide`.
         pub remote: OsString,
 
@@ -450,6 +924,11 @@ pub mod remote {
         #[clap(long, short = 'H')]
         pub handshake_info: bool,
 
+        /// Cache the refs advertised by the remote, and use cached refs instead of contacting the remote
+        /// if they are younger than the given amount of seconds.
+        #[clap(long, value_name = "SECONDS")]
+        pub cache_ttl: Option<u64>,
+
         /// Subcommands
         #[clap(subcommand)]
         pub cmd: Subcommands,
@@ -472,6 +951,65 @@ pub mod remote {
     }
 }
 
+pub mod log {
+    use std::{ffi::OsStr, str::FromStr};
+
+    use clap::{
+        builder::{NonEmptyStringValueParser, TypedValueParser},
+        Arg, Command, Error,
+    };
+    use gitoxide_core::repository::log::LineRange;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Follow the lines `<start>,<end>` of `<file>` back through history and show all commits that changed them.
+        ///
+        /// Line numbers start at 1 and are inclusive. `<end>` can also be `+<count>`, and `<start>` and `<end>`
+        /// default to the first and last line of the file if omitted. Can be specified multiple times for the same file.
+        #[clap(short = 'L', value_name = "START,END:FILE", required = true, value_parser = AsLineRange)]
+        pub line_ranges: Vec<LineRange>,
+        /// Print additional statistics to help understanding performance.
+        #[clap(long, short = 's')]
+        pub statistics: bool,
+    }
+
+    #[derive(Clone)]
+    pub struct AsLineRange;
+
+    impl TypedValueParser for AsLineRange {
+        type Value = LineRange;
+
+        fn parse_ref(&self, cmd: &Command, arg: Option<&Arg>, value: &OsStr) -> Result<Self::Value, Error> {
+            NonEmptyStringValueParser::new()
+                .try_map(|arg| LineRange::from_str(&arg))
+                .parse_ref(cmd, arg, value)
+        }
+    }
+}
+
+pub mod shortlog {
+    use gix::bstr::BString;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Sort the output by the amount of commits per author instead of alphabetically.
+        #[clap(long, short = 'n')]
+        pub numbered: bool,
+        /// Only show the amount of commits per author, without their summaries.
+        #[clap(long, short = 's')]
+        pub summary: bool,
+        /// Show the email address of each author, and consider authors with different addresses as different people.
+        #[clap(long, short = 'e')]
+        pub email: bool,
+        /// Group commits by their committer instead of their author.
+        #[clap(long, short = 'c')]
+        pub committer: bool,
+        /// The revision specification to select commits with, like `v1.0..HEAD`, defaulting to `HEAD`.
+        #[clap(value_parser = gitoxide::shared::AsBString)]
+        pub spec: Option<BString>,
+    }
+}
+
 pub mod mailmap {
     #[derive(Debug, clap::Subcommand)]
     pub enum Subcommands {
@@ -480,6 +1018,24 @@ pub mod mailmap {
     }
 }
 
+#[cfg(feature = "gitoxide-core-tools-fsmonitor")]
+pub mod fsmonitor {
+    use gix::bstr::BString;
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Watch the worktree and answer queries for changes until interrupted or stopped.
+        Run,
+        /// Print the token and all paths that changed since `token` according to the running daemon.
+        Query {
+            /// The token to ask for changes since, or the one stored in the index if unset.
+            token: Option<BString>,
+        },
+        /// Stop the running daemon.
+        Stop,
+    }
+}
+
 #[cfg(feature = "gitoxide-core-tools-clean")]
 pub mod clean {
     use gitoxide::shared::CheckPathSpec;
@@ -549,7 +1105,15 @@ pub mod odb {
 pub mod fsck {
     #[derive(Debug, clap::Parser)]
     pub struct Platform {
-        /// A revspec to start the connectivity check from.
+        /// Report all unreachable objects, not only the dangling ones that no other object refers to.
+        #[clap(long)]
+        pub unreachable: bool,
+        /// Don't recompute the hash of each object to compare it to its id, which is faster.
+        #[clap(long)]
+        pub skip_hash_verification: bool,
+        /// A revspec to start the connectivity check from, which only checks that all reachable objects exist.
+        ///
+        /// Otherwise, all objects are checked for integrity, along with the connectivity of all objects and references.
         pub spec: Option<String>,
     }
 }
@@ -582,6 +1146,36 @@ pub mod tree {
 }
 
 pub mod commit {
+    use std::path::PathBuf;
+
+    #[derive(Debug, clap::Parser)]
+    #[clap(args_conflicts_with_subcommands = true)]
+    pub struct Platform {
+        /// Use the given message as commit message, with multiple ones being separate paragraphs.
+        #[clap(long, short = 'm', value_name = "MSG")]
+        pub message: Vec<String>,
+        /// Read the commit message from the given file, or from stdin if it is `-`.
+        #[clap(long, short = 'F', conflicts_with = "message")]
+        pub file: Option<PathBuf>,
+        /// Replace the commit at `HEAD` with a new one, reusing its message unless another one is given.
+        #[clap(long)]
+        pub amend: bool,
+        /// Allow creating a commit with the same tree as its parent.
+        #[clap(long)]
+        pub allow_empty: bool,
+        /// Add a `Signed-off-by` trailer for the committer to the end of the message.
+        #[clap(long, short = 's')]
+        pub signoff: bool,
+        /// Bypass the `pre-commit` and `commit-msg` hooks.
+        #[clap(long, short = 'n')]
+        pub no_verify: bool,
+        /// When amending, reuse the message of the amended commit without launching an editor.
+        #[clap(long, requires = "amend")]
+        pub no_edit: bool,
+        #[clap(subcommand)]
+        pub cmd: Option<Subcommands>,
+    }
+
     #[derive(Debug, clap::Subcommand)]
     pub enum Subcommands {
         /// Verify the signature of a commit.
@@ -626,17 +1220,56 @@ pub mod commit {
 }
 
 pub mod credential {
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Use the credential helper `DEFINITION` instead of the ones configured with `credential.helper`.
+        ///
+        /// Can be specified multiple times to run the helpers in order, and definitions are interpreted like the values
+        /// of `credential.helper`. Just like in the configuration, an empty definition removes all previous ones.
+        #[clap(long = "helper", value_name = "DEFINITION")]
+        pub helpers: Vec<String>,
+        #[clap(subcommand)]
+        pub cmd: Subcommands,
+    }
+
     #[derive(Debug, clap::Subcommand)]
     pub enum Subcommands {
-        /// Get the credentials fed for `url=<url>` via STDIN.
-        #[clap(visible_alias = "get")]
+        /// Get the credentials fed for `url=<url>` via STDIN, and prompt for missing ones.
         Fill,
         /// Approve the information piped via STDIN as obtained with last call to `fill`
-        #[clap(visible_alias = "store")]
         Approve,
-        /// Try to resolve the given revspec and print the object names.
-        #[clap(visible_alias = "erase")]
+        /// Reject the information piped via STDIN as obtained with last call to `fill`
         Reject,
+        /// Act as credential helper by passing the credentials fed via STDIN on to the helpers, and print what they provided.
+        ///
+        /// Unlike `fill`, this never prompts and prints nothing if no credentials could be obtained. Configure `gix` as
+        /// credential helper with `credential.helper=!gix credential`.
+        Get,
+        /// Act as credential helper by passing the credentials fed via STDIN on to the helpers for storage.
+        Store,
+        /// Act as credential helper by passing the credentials fed via STDIN on to the helpers for removal.
+        Erase,
+        /// Act as credential helper that keeps credentials in memory of a daemon for a limited time, compatible with `git credential-cache`.
+        ///
+        /// Configure it with `credential.helper='!gix credential cache'`, and ignore `--helper` as well as `credential.helper`.
+        #[cfg(unix)]
+        Cache {
+            /// The amount of seconds for which credentials are kept.
+            #[clap(long, default_value_t = 900)]
+            timeout: u64,
+            /// The socket of the daemon, which is the same as for `git credential-cache` by default.
+            #[clap(long)]
+            socket: Option<std::path::PathBuf>,
+            /// The action to perform, one of `get`, `store`, `erase` or `exit`, with the latter stopping the daemon.
+            action: String,
+        },
+        /// Run the daemon for `gix credential cache` listening on `socket`, which is started automatically as needed.
+        #[clap(hide = true)]
+        #[cfg(unix)]
+        CacheDaemon {
+            /// The path to the socket to listen on.
+            socket: std::path::PathBuf,
+        },
     }
 }
 
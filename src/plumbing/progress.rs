@@ -142,14 +142,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "core.protectNTFS",
         usage: NotPlanned { reason: "lack of demand"},
     },
-    Record {
-        config: "core.sparseCheckout",
-        usage: Planned { note: Some("we want to support huge repos and be the fastest in doing so") },
-    },
-    Record {
-        config: "core.sparseCheckoutCone",
-        usage: Planned { note: Some("this is a nice improvement over spareCheckout alone and should one day be available too") },
-    },
     Record {
         config: "core.gitProxy",
         usage: NotPlanned { reason: "the transport mechanism works differently enough to not support it for now, but of course it's possible to add support if there is demand" },
@@ -330,14 +322,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "advice.updateSparsePath",
         usage: NotApplicable { reason: "gitoxide does not yet have an 'advice' system" },
     },
-    Record {
-        config: "core.sparseCheckout",
-        usage: Planned { note: Some("together with 'index.sparse' and 'core.sparseCheckoutCone', configures if the index should be written sparse or not") },
-    },
-    Record {
-        config: "core.sparseCheckoutCone",
-        usage: Planned { note: Some("non-cone mode is deprecated but should still fail gracefully if encountered") },
-    },
     Record {
         config: "core.splitIndex",
         usage: NotPlanned { reason: "we don't want to be able to create split indices, but we will read them. It's (somewhat) superseded by sparse indices" },
@@ -270,6 +270,71 @@ pub fn setup_line_renderer_range(
     )
 }
 
+/// Describe `cmd` along with all of its arguments and subcommands as JSON, to allow tools that wrap our binaries to learn
+/// about their interface without parsing `--help` output.
+pub fn schema(mut cmd: ::clap::Command) -> serde_json::Value {
+    // Building is needed to fill in everything that was derived, like the number of values each argument takes.
+    cmd.build();
+    command_schema(&cmd)
+}
+
+fn command_schema(cmd: &::clap::Command) -> serde_json::Value {
+    use serde_json::json;
+
+    fn styled(text: Option<&::clap::builder::StyledStr>) -> Option<String> {
+        text.map(ToString::to_string)
+    }
+
+    let args: Vec<_> = cmd
+        .get_arguments()
+        .filter(|arg| !arg.is_hide_set())
+        .map(|arg| {
+            let takes_value = arg.get_num_args().map_or(false, |num_args| num_args.takes_values());
+            json!({
+                "id": arg.get_id().as_str(),
+                "long": arg.get_long(),
+                "short": arg.get_short(),
+                "aliases": arg.get_visible_aliases().unwrap_or_default(),
+                "help": styled(arg.get_help()),
+                "required": arg.is_required_set(),
+                "global": arg.is_global_set(),
+                "positional": arg.is_positional(),
+                "takes_value": takes_value,
+                "multiple": takes_value && matches!(arg.get_action(), ::clap::ArgAction::Append),
+                "value_names": arg
+                    .get_value_names()
+                    .map(|names| names.iter().map(::clap::builder::Str::as_str).collect::<Vec<_>>()),
+                "possible_values": arg
+                    .get_possible_values()
+                    .iter()
+                    .filter(|value| !value.is_hide_set())
+                    .map(::clap::builder::PossibleValue::get_name)
+                    .collect::<Vec<_>>(),
+                "default_values": arg
+                    .get_default_values()
+                    .iter()
+                    .map(|value| value.to_string_lossy())
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    let subcommands: Vec<_> = cmd
+        .get_subcommands()
+        .filter(|cmd| !cmd.is_hide_set())
+        .map(command_schema)
+        .collect();
+    json!({
+        "name": cmd.get_name(),
+        "aliases": cmd.get_visible_aliases().collect::<Vec<_>>(),
+        "about": styled(cmd.get_about()),
+        "long_about": styled(cmd.get_long_about()),
+        "version": cmd.get_version(),
+        "subcommand_required": cmd.is_subcommand_required_set(),
+        "args": args,
+        "subcommands": subcommands,
+    })
+}
+
 mod clap {
     use std::{ffi::OsStr, str::FromStr};
 
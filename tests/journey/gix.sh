@@ -167,6 +167,16 @@ title "gix attributes"
   )
 )
 
+title "gix --schema"
+(with "the '--schema' flag"
+  it "prints a description of the command-line interface" && {
+    expect_run $SUCCESSFULLY "$exe_plumbing" --schema
+  }
+  it "fails when combined with a subcommand" && {
+    expect_run $WITH_CLAP_FAILURE "$exe_plumbing" --schema status
+  }
+)
+
 title "gix commit-graph"
 (when "running 'commit-graph'"
   snapshot="$snapshot/commit-graph"